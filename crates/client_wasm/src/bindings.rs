@@ -0,0 +1,146 @@
+//! `SensorConsole`, the JavaScript face of the console
+
+use crate::console::{Console, ConsoleUpdate};
+use crate::transport::{EventTransport, GrpcWebTransport};
+use crate::view::StatusView;
+use futures::StreamExt;
+use js_sys::{Function, Promise};
+use serde::Serialize;
+use spectre_protocol::proto::StreamRequest;
+use spectremesh_core::FearBucketThresholds;
+use std::cell::RefCell;
+use std::rc::Rc;
+use tonic::Status;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen_futures::future_to_promise;
+
+#[derive(Default)]
+struct Callbacks {
+    score: Option<Function>,
+    bucket_change: Option<Function>,
+    fault: Option<Function>,
+}
+
+/// A browser console's connection to one sensor daemon
+///
+/// ```js
+/// const sensor = new SensorConsole("http://127.0.0.1:50051");
+/// sensor.onScore(score => gauge.set(score.normalizedFear));
+/// sensor.onBucketChange(change => banner.show(change.to));
+/// await sensor.run(true);
+/// ```
+#[wasm_bindgen]
+pub struct SensorConsole {
+    transport: Rc<dyn EventTransport>,
+    console: Rc<RefCell<Console>>,
+    callbacks: Rc<RefCell<Callbacks>>,
+}
+
+#[wasm_bindgen]
+impl SensorConsole {
+    /// Connect over grpc-web to the daemon at `url`
+    #[wasm_bindgen(constructor)]
+    pub fn new(url: &str) -> SensorConsole {
+        Self::with_transport(GrpcWebTransport::new(url))
+    }
+
+    /// Call `callback(score)` for every score, delta-encoded on the wire or not
+    #[wasm_bindgen(js_name = onScore)]
+    pub fn on_score(&self, callback: Function) {
+        self.callbacks.borrow_mut().score = Some(callback);
+    }
+
+    /// Call `callback({ from, to, normalizedFear, timestampMs })` when fear changes bucket
+    #[wasm_bindgen(js_name = onBucketChange)]
+    pub fn on_bucket_change(&self, callback: Function) {
+        self.callbacks.borrow_mut().bucket_change = Some(callback);
+    }
+
+    /// Call `callback(fault)` for every fault the daemon reports
+    #[wasm_bindgen(js_name = onFault)]
+    pub fn on_fault(&self, callback: Function) {
+        self.callbacks.borrow_mut().fault = Some(callback);
+    }
+
+    /// Bucket boundaries, taking effect from the next score
+    #[wasm_bindgen(js_name = setThresholds)]
+    pub fn set_thresholds(&self, medium: f32, high: f32) -> Result<(), JsError> {
+        self.console
+            .borrow_mut()
+            .set_thresholds(FearBucketThresholds { medium, high })
+            .map_err(|e| JsError::new(&e))
+    }
+
+    /// `"low"`, `"medium"` or `"high"`; `undefined` before the first score with fear
+    #[wasm_bindgen(getter)]
+    pub fn bucket(&self) -> JsValue {
+        to_js(&self.console.borrow().bucket()).unwrap_or(JsValue::UNDEFINED)
+    }
+
+    /// Stream events until the daemon ends the stream, calling the callbacks
+    ///
+    /// `delta` asks for delta-encoded scores, which are rebuilt before any
+    /// callback sees them. Rejects on a transport error or when a callback
+    /// throws.
+    pub fn run(&self, delta: bool) -> Promise {
+        let transport = self.transport.clone();
+        let console = self.console.clone();
+        let callbacks = self.callbacks.clone();
+        future_to_promise(async move {
+            let request = StreamRequest { delta, ..Default::default() };
+            let mut events = transport.stream_events(request).await.map_err(status_error)?;
+            while let Some(event) = events.next().await {
+                let updates = console.borrow_mut().handle(event.map_err(status_error)?);
+                for update in updates {
+                    dispatch(&callbacks, update)?;
+                }
+            }
+            Ok(JsValue::UNDEFINED)
+        })
+    }
+
+    /// Sensor status as a plain object
+    pub fn status(&self) -> Promise {
+        let transport = self.transport.clone();
+        future_to_promise(async move {
+            let status = transport.get_status().await.map_err(status_error)?;
+            to_js(&StatusView::from(&status))
+        })
+    }
+}
+
+impl SensorConsole {
+    /// A console over any transport
+    pub fn with_transport(transport: impl EventTransport + 'static) -> Self {
+        Self {
+            transport: Rc::new(transport),
+            console: Rc::new(RefCell::new(Console::new())),
+            callbacks: Rc::new(RefCell::new(Callbacks::default())),
+        }
+    }
+}
+
+/// Hand `update` to its callback, if one is registered
+fn dispatch(callbacks: &RefCell<Callbacks>, update: ConsoleUpdate) -> Result<(), JsValue> {
+    // Cloned out so a callback may replace callbacks while it runs
+    let (callback, value) = {
+        let callbacks = callbacks.borrow();
+        match &update {
+            ConsoleUpdate::Score(score) => (callbacks.score.clone(), to_js(score)),
+            ConsoleUpdate::BucketChanged(change) => (callbacks.bucket_change.clone(), to_js(change)),
+            ConsoleUpdate::Fault(fault) => (callbacks.fault.clone(), to_js(fault)),
+        }
+    };
+    if let Some(callback) = callback {
+        callback.call1(&JsValue::NULL, &value?)?;
+    }
+    Ok(())
+}
+
+fn to_js<T: Serialize>(value: &T) -> Result<JsValue, JsValue> {
+    serde_wasm_bindgen::to_value(value).map_err(Into::into)
+}
+
+fn status_error(status: Status) -> JsValue {
+    JsError::new(&format!("{}: {}", status.code(), status.message())).into()
+}
@@ -0,0 +1,214 @@
+//! What a console makes of the daemon's event stream
+
+use crate::view::{BucketChangeView, FaultView, ScoreView};
+use spectre_protocol::delta::FearStreamConsumer;
+use spectre_protocol::proto::{sensor_event, SensorEvent};
+use spectremesh_core::{types::SensorCapability, FearBucket, FearBucketThresholds};
+
+/// Something a console callback is told about
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConsoleUpdate {
+    Score(ScoreView),
+    BucketChanged(BucketChangeView),
+    Fault(FaultView),
+}
+
+/// Turns received events into console updates
+///
+/// Rebuilds delta-encoded scores and tracks the fear bucket, reporting a
+/// change once, right after the score that crossed a threshold. Scores
+/// without fear (emotion inference offline) leave the bucket where it was.
+#[derive(Debug, Clone, Default)]
+pub struct Console {
+    stream: FearStreamConsumer,
+    thresholds: FearBucketThresholds,
+    bucket: Option<FearBucket>,
+}
+
+impl Console {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Classify fear with `thresholds` rather than the default ones
+    pub fn with_thresholds(mut self, thresholds: FearBucketThresholds) -> Self {
+        self.thresholds = thresholds;
+        self
+    }
+
+    /// Change the bucket boundaries, taking effect from the next score
+    pub fn set_thresholds(&mut self, thresholds: FearBucketThresholds) -> Result<(), String> {
+        let FearBucketThresholds { medium, high } = thresholds;
+        if !(0.0..=1.0).contains(&medium) || !(0.0..=1.0).contains(&high) || medium > high {
+            return Err(format!(
+                "Fear thresholds must satisfy 0 <= medium <= high <= 1, got {} and {}",
+                medium, high
+            ));
+        }
+        self.thresholds = thresholds;
+        Ok(())
+    }
+
+    pub fn thresholds(&self) -> FearBucketThresholds {
+        self.thresholds
+    }
+
+    /// Bucket of the last score with fear
+    pub fn bucket(&self) -> Option<FearBucket> {
+        self.bucket
+    }
+
+    /// Sequence gaps and dropped deltas so far
+    pub fn stream(&self) -> &FearStreamConsumer {
+        &self.stream
+    }
+
+    /// Updates for one received event, in the order callbacks should see them
+    ///
+    /// Markers, calibration progress and the like produce none, as does a
+    /// delta whose keyframe was lost.
+    pub fn handle(&mut self, event: SensorEvent) -> Vec<ConsoleUpdate> {
+        let Some(event) = self.stream.consume(event) else {
+            return Vec::new();
+        };
+        match &event.event {
+            Some(sensor_event::Event::Score(score)) => {
+                let fear_available = SensorCapability::from(score.capability()).fear_available();
+                let bucket = fear_available.then(|| self.thresholds.classify(score.normalized_fear));
+                let mut updates = vec![ConsoleUpdate::Score(ScoreView::new(&event, score, bucket))];
+                if let Some(to) = bucket.filter(|to| self.bucket != Some(*to)) {
+                    updates.push(ConsoleUpdate::BucketChanged(BucketChangeView {
+                        from: self.bucket.replace(to),
+                        to,
+                        normalized_fear: score.normalized_fear,
+                        timestamp_ms: event.timestamp_us as f64 / 1000.0,
+                    }));
+                }
+                updates
+            }
+            Some(sensor_event::Event::SensorFault(fault)) => vec![ConsoleUpdate::Fault(FaultView::from(fault))],
+            _ => Vec::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use spectre_protocol::delta::{DeltaConfig, StreamEncoder};
+    use spectre_protocol::proto::{self, FaultSeverity, Score};
+
+    fn score_event(fear: f32, capability: proto::SensorCapability) -> SensorEvent {
+        SensorEvent {
+            timestamp_us: 1_700_000_000_000_000,
+            sequence: 0,
+            event: Some(sensor_event::Event::Score(Score {
+                normalized_fear: fear,
+                raw_fear_logit: fear * 4.0,
+                confidence: 0.9,
+                calibrated: true,
+                emotion_logits: vec![0.1, 0.1, fear * 4.0, 0.1, 0.1, 0.1, 0.1],
+                capability: capability as i32,
+                ..Default::default()
+            })),
+        }
+    }
+
+    fn changes(updates: &[ConsoleUpdate]) -> Vec<(Option<FearBucket>, FearBucket)> {
+        updates
+            .iter()
+            .filter_map(|update| match update {
+                ConsoleUpdate::BucketChanged(change) => Some((change.from, change.to)),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_delta_stream_reports_scores_and_bucket_changes() {
+        let mut encoder = StreamEncoder::new(Some(DeltaConfig::default()));
+        let mut console = Console::new();
+        let mut updates = Vec::new();
+        for fear in [0.125, 0.25, 0.5, 0.5625, 0.875, 0.25] {
+            let event = encoder.encode(score_event(fear, proto::SensorCapability::Full));
+            updates.extend(console.handle(event));
+        }
+
+        let fears: Vec<f32> = updates
+            .iter()
+            .filter_map(|update| match update {
+                ConsoleUpdate::Score(score) => Some(score.normalized_fear),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(fears, [0.125, 0.25, 0.5, 0.5625, 0.875, 0.25]);
+        assert_eq!(
+            changes(&updates),
+            [
+                (None, FearBucket::Low),
+                (Some(FearBucket::Low), FearBucket::Medium),
+                (Some(FearBucket::Medium), FearBucket::High),
+                (Some(FearBucket::High), FearBucket::Low),
+            ]
+        );
+        // The change follows the score that caused it
+        assert!(matches!(&updates[3], ConsoleUpdate::Score(score) if score.normalized_fear == 0.5));
+        assert!(matches!(&updates[4], ConsoleUpdate::BucketChanged(change) if change.to == FearBucket::Medium));
+        assert_eq!(console.stream().dropped_deltas(), 0);
+    }
+
+    #[test]
+    fn test_offline_scores_keep_the_bucket() {
+        let mut console = Console::new();
+        console.handle(score_event(0.9, proto::SensorCapability::Full));
+
+        let updates = console.handle(score_event(0.1, proto::SensorCapability::EmotionOffline));
+        assert_eq!(updates.len(), 1);
+        assert!(matches!(&updates[0], ConsoleUpdate::Score(score) if score.bucket.is_none()));
+        assert_eq!(console.bucket(), Some(FearBucket::High));
+
+        // Older daemons leave capability unspecified, meaning full
+        let updates = console.handle(score_event(0.1, proto::SensorCapability::Unspecified));
+        assert_eq!(changes(&updates), [(Some(FearBucket::High), FearBucket::Low)]);
+    }
+
+    #[test]
+    fn test_thresholds() {
+        let mut console = Console::new().with_thresholds(FearBucketThresholds { medium: 0.1, high: 0.2 });
+        console.handle(score_event(0.15, proto::SensorCapability::Full));
+        assert_eq!(console.bucket(), Some(FearBucket::Medium));
+
+        assert!(console.set_thresholds(FearBucketThresholds { medium: 0.7, high: 0.6 }).is_err());
+        assert!(console.set_thresholds(FearBucketThresholds { medium: 0.5, high: 1.5 }).is_err());
+        console.set_thresholds(FearBucketThresholds { medium: 0.5, high: 0.8 }).unwrap();
+        let updates = console.handle(score_event(0.15, proto::SensorCapability::Full));
+        assert_eq!(changes(&updates), [(Some(FearBucket::Medium), FearBucket::Low)]);
+    }
+
+    #[test]
+    fn test_faults_and_other_events() {
+        let mut console = Console::new();
+        let fault = SensorEvent {
+            timestamp_us: 0,
+            sequence: 0,
+            event: Some(sensor_event::Event::SensorFault(proto::SensorFault {
+                severity: FaultSeverity::Critical as i32,
+                message: "Sensor stopped".to_string(),
+                error_code: "SENSOR_STOPPED".to_string(),
+                recoverable: false,
+            })),
+        };
+        let updates = console.handle(fault);
+        assert!(matches!(
+            &updates[..],
+            [ConsoleUpdate::Fault(fault)] if fault.severity == "critical" && fault.error_code == "SENSOR_STOPPED"
+        ));
+
+        let marker = SensorEvent {
+            timestamp_us: 0,
+            sequence: 0,
+            event: Some(sensor_event::Event::Marker(proto::Marker { label: "level_2".to_string() })),
+        };
+        assert!(console.handle(marker).is_empty());
+    }
+}
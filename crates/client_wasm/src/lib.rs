@@ -0,0 +1,30 @@
+//! Browser client for the sensor daemon
+//!
+//! Operator consoles running in a browser reach the daemon over grpc-web,
+//! which it serves once `SensorConfig::grpc_web_origins` lists the console's
+//! origin. `SensorConsole` streams the daemon's events, rebuilding
+//! delta-encoded scores with [`FearStreamConsumer`], and hands scores, fear
+//! bucket changes and faults to JavaScript callbacks as plain objects.
+//!
+//! Only the wasm-bindgen layer and the grpc-web transport are specific to
+//! `wasm32`; the console logic builds and is tested natively too. Core types
+//! stamped with `Instant::now()`, such as `FearFrame`, are not used here:
+//! browsers have no monotonic clock `std` can read.
+//!
+//! [`FearStreamConsumer`]: spectre_protocol::delta::FearStreamConsumer
+
+pub mod console;
+pub mod transport;
+pub mod view;
+
+#[cfg(target_arch = "wasm32")]
+mod bindings;
+
+pub use console::{Console, ConsoleUpdate};
+pub use transport::{EventStream, EventTransport};
+pub use view::{BucketChangeView, FaultView, RectView, ScoreView, StatusView};
+
+#[cfg(target_arch = "wasm32")]
+pub use bindings::SensorConsole;
+#[cfg(target_arch = "wasm32")]
+pub use transport::GrpcWebTransport;
@@ -0,0 +1,66 @@
+//! Where a console's events come from
+//!
+//! [`EventTransport`] is the two daemon calls a console makes. In the
+//! browser it is `GrpcWebTransport`; tests substitute a scripted one.
+
+use futures::future::LocalBoxFuture;
+use futures::stream::LocalBoxStream;
+use spectre_protocol::proto::{SensorEvent, StatusResponse, StreamRequest};
+use tonic::Status;
+
+/// Events as they arrive, ending when the daemon closes the stream
+pub type EventStream = LocalBoxStream<'static, Result<SensorEvent, Status>>;
+
+/// The daemon calls a console needs
+///
+/// Futures own what they use and need not be `Send`: browsers run one
+/// thread, and the console stays usable while a stream is open.
+pub trait EventTransport {
+    /// Open the event stream
+    fn stream_events(&self, request: StreamRequest) -> LocalBoxFuture<'static, Result<EventStream, Status>>;
+
+    /// Current sensor status
+    fn get_status(&self) -> LocalBoxFuture<'static, Result<StatusResponse, Status>>;
+}
+
+#[cfg(target_arch = "wasm32")]
+pub use grpc_web::GrpcWebTransport;
+
+#[cfg(target_arch = "wasm32")]
+mod grpc_web {
+    use super::*;
+    use futures::{FutureExt, StreamExt};
+    use spectre_protocol::proto::{sensor_service_client::SensorServiceClient, StatusRequest};
+    use tonic_web_wasm_client::Client;
+
+    /// grpc-web through the browser's `fetch`
+    #[derive(Clone)]
+    pub struct GrpcWebTransport {
+        client: SensorServiceClient<Client>,
+    }
+
+    impl GrpcWebTransport {
+        /// The daemon at `base_url` as the browser reaches it, e.g. `http://127.0.0.1:50051`
+        pub fn new(base_url: impl Into<String>) -> Self {
+            Self {
+                client: SensorServiceClient::new(Client::new(base_url.into())),
+            }
+        }
+    }
+
+    impl EventTransport for GrpcWebTransport {
+        fn stream_events(&self, request: StreamRequest) -> LocalBoxFuture<'static, Result<EventStream, Status>> {
+            let mut client = self.client.clone();
+            async move {
+                let events = client.stream_events(request).await?.into_inner();
+                Ok(events.boxed_local())
+            }
+            .boxed_local()
+        }
+
+        fn get_status(&self) -> LocalBoxFuture<'static, Result<StatusResponse, Status>> {
+            let mut client = self.client.clone();
+            async move { Ok(client.get_status(StatusRequest {}).await?.into_inner()) }.boxed_local()
+        }
+    }
+}
@@ -0,0 +1,154 @@
+//! Plain data handed to JavaScript
+//!
+//! Field names are camelCase and 64-bit counters become `f64`, so every view
+//! serializes to an ordinary object of numbers, strings and arrays rather
+//! than one holding `BigInt`s. Absent options are left `undefined`.
+
+use serde::Serialize;
+use spectre_protocol::proto::{self, FaultSeverity};
+use spectremesh_core::{types::SensorCapability, FearBucket, FEAR_INDEX};
+
+/// A score from the daemon, rebuilt in full when the stream is delta-encoded
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScoreView {
+    /// Position in the stream (0 from daemons that do not number events)
+    pub sequence: f64,
+    /// Milliseconds since the Unix epoch, ready for `new Date()`
+    pub timestamp_ms: f64,
+    pub normalized_fear: f32,
+    pub raw_fear_logit: f32,
+    pub confidence: f32,
+    pub calibrated: bool,
+    pub startle: f32,
+    /// `"full"`, `"hold_last_value"` or `"emotion_offline"`
+    pub capability: SensorCapability,
+    /// Bucket of `normalizedFear`; absent while emotion inference is offline
+    pub bucket: Option<FearBucket>,
+    /// In the model's order, fear at `fearIndex`
+    pub emotion_logits: Vec<f32>,
+    pub fear_index: u32,
+    pub inference_latency_ms: f64,
+    /// The scored face, normalized to the frame
+    pub face_bbox: Option<RectView>,
+    /// False while tracking is lost; `normalizedFear` then repeats the last
+    /// measured value
+    pub face_present: bool,
+}
+
+impl ScoreView {
+    pub fn new(event: &proto::SensorEvent, score: &proto::Score, bucket: Option<FearBucket>) -> Self {
+        Self {
+            sequence: event.sequence as f64,
+            timestamp_ms: event.timestamp_us as f64 / 1000.0,
+            normalized_fear: score.normalized_fear,
+            raw_fear_logit: score.raw_fear_logit,
+            confidence: score.confidence,
+            calibrated: score.calibrated,
+            startle: score.startle,
+            capability: score.capability().into(),
+            bucket,
+            emotion_logits: score.emotion_logits.clone(),
+            fear_index: score.fear_index.unwrap_or(FEAR_INDEX as u32),
+            inference_latency_ms: score.inference_latency_us as f64 / 1000.0,
+            face_bbox: score.face_bbox.as_ref().map(RectView::from),
+            face_present: score.face_present.unwrap_or(true),
+        }
+    }
+}
+
+/// Box in frame coordinates normalized to [0.0, 1.0], origin top-left
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct RectView {
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+}
+
+impl From<&proto::NormalizedRect> for RectView {
+    fn from(rect: &proto::NormalizedRect) -> Self {
+        Self {
+            x: rect.x,
+            y: rect.y,
+            width: rect.width,
+            height: rect.height,
+        }
+    }
+}
+
+/// Fear moved into another bucket
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BucketChangeView {
+    /// Absent for the first score with fear
+    pub from: Option<FearBucket>,
+    pub to: FearBucket,
+    /// The score that crossed the threshold
+    pub normalized_fear: f32,
+    pub timestamp_ms: f64,
+}
+
+/// A fault reported by the daemon
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FaultView {
+    /// `"info"`, `"warning"`, `"error"` or `"critical"`
+    pub severity: &'static str,
+    pub message: String,
+    /// `"SENSOR_STOPPED"` means the daemon shut down; do not reconnect
+    pub error_code: String,
+    pub recoverable: bool,
+}
+
+impl From<&proto::SensorFault> for FaultView {
+    fn from(fault: &proto::SensorFault) -> Self {
+        let severity = match fault.severity() {
+            FaultSeverity::Unspecified | FaultSeverity::Info => "info",
+            FaultSeverity::Warning => "warning",
+            FaultSeverity::Error => "error",
+            FaultSeverity::Critical => "critical",
+        };
+        Self {
+            severity,
+            message: fault.message.clone(),
+            error_code: fault.error_code.clone(),
+            recoverable: fault.recoverable,
+        }
+    }
+}
+
+/// What `GetStatus` reports, trimmed to what a console shows
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StatusView {
+    pub running: bool,
+    pub capability: SensorCapability,
+    pub calibrated: bool,
+    /// [0.0, 1.0]
+    pub calibration_progress: f32,
+    pub current_fps: f32,
+    pub p95_inference_latency_ms: f64,
+    pub dropped_frames: f64,
+    pub last_error: Option<FaultView>,
+    /// Why the sensor last stopped; absent while running
+    pub stopped_reason: Option<String>,
+}
+
+impl From<&proto::StatusResponse> for StatusView {
+    fn from(status: &proto::StatusResponse) -> Self {
+        let calibration = status.calibration.clone().unwrap_or_default();
+        let metrics = status.metrics.unwrap_or_default();
+        Self {
+            running: status.running,
+            capability: status.capability().into(),
+            calibrated: calibration.completed,
+            calibration_progress: calibration.progress,
+            current_fps: metrics.current_fps,
+            p95_inference_latency_ms: metrics.p95_inference_latency_us as f64 / 1000.0,
+            dropped_frames: metrics.dropped_frames as f64,
+            last_error: status.last_error.as_ref().map(FaultView::from),
+            stopped_reason: (!status.stopped_reason.is_empty()).then(|| status.stopped_reason.clone()),
+        }
+    }
+}
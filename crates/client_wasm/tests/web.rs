@@ -0,0 +1,146 @@
+//! `SensorConsole` over a scripted transport: delta-encoded scores and fear
+//! bucket changes reach JavaScript callbacks as plain objects
+//!
+//! Run with `wasm-pack test --node crates/client_wasm`.
+
+#![cfg(target_arch = "wasm32")]
+
+use futures::future::{self, FutureExt, LocalBoxFuture};
+use futures::stream::{self, StreamExt};
+use js_sys::{Function, Reflect};
+use spectre_client_wasm::{EventStream, EventTransport, SensorConsole};
+use spectre_protocol::delta::{DeltaConfig, StreamEncoder};
+use spectre_protocol::proto::{sensor_event, CalibrationProgress, Score, SensorCapability, SensorEvent, StatusResponse, StreamRequest};
+use std::cell::RefCell;
+use std::rc::Rc;
+use tonic::Status;
+use wasm_bindgen::{closure::Closure, JsCast, JsValue};
+use wasm_bindgen_futures::JsFuture;
+use wasm_bindgen_test::wasm_bindgen_test;
+
+const FEARS: [f32; 6] = [0.125, 0.25, 0.5, 0.5625, 0.875, 0.25];
+
+/// Replays events encoded the way the daemon would send them
+struct ScriptedTransport {
+    events: Vec<SensorEvent>,
+}
+
+impl ScriptedTransport {
+    fn delta_stream() -> Self {
+        let mut encoder = StreamEncoder::new(Some(DeltaConfig::default()));
+        let events: Vec<SensorEvent> = FEARS
+            .iter()
+            .map(|&fear| {
+                encoder.encode(SensorEvent {
+                    timestamp_us: 1_700_000_000_000_000,
+                    sequence: 0,
+                    event: Some(sensor_event::Event::Score(Score {
+                        normalized_fear: fear,
+                        raw_fear_logit: fear * 4.0,
+                        confidence: 0.9,
+                        calibrated: true,
+                        emotion_logits: vec![0.1, 0.1, fear * 4.0, 0.1, 0.1, 0.1, 0.1],
+                        capability: SensorCapability::Full as i32,
+                        ..Default::default()
+                    })),
+                })
+            })
+            .collect();
+        let deltas = events
+            .iter()
+            .filter(|event| matches!(event.event, Some(sensor_event::Event::ScoreDelta(_))))
+            .count();
+        assert_eq!(deltas, 5);
+        Self { events }
+    }
+}
+
+impl EventTransport for ScriptedTransport {
+    fn stream_events(&self, request: StreamRequest) -> LocalBoxFuture<'static, Result<EventStream, Status>> {
+        assert!(request.delta);
+        let events = stream::iter(self.events.clone().into_iter().map(Ok)).boxed_local();
+        future::ready(Ok(events)).boxed_local()
+    }
+
+    fn get_status(&self) -> LocalBoxFuture<'static, Result<StatusResponse, Status>> {
+        future::ready(Ok(StatusResponse {
+            running: true,
+            calibration: Some(CalibrationProgress { progress: 1.0, completed: true, ..Default::default() }),
+            capability: SensorCapability::HoldLastValue as i32,
+            ..Default::default()
+        }))
+        .boxed_local()
+    }
+}
+
+/// Arguments a recorder was called with
+type Calls = Rc<RefCell<Vec<JsValue>>>;
+
+/// A JavaScript function recording what it is called with
+fn recorder() -> (Calls, Closure<dyn FnMut(JsValue)>) {
+    let calls = Rc::new(RefCell::new(Vec::new()));
+    let sink = calls.clone();
+    let closure = Closure::<dyn FnMut(JsValue)>::new(move |value| sink.borrow_mut().push(value));
+    (calls, closure)
+}
+
+fn function(closure: &Closure<dyn FnMut(JsValue)>) -> Function {
+    closure.as_ref().unchecked_ref::<Function>().clone()
+}
+
+fn field(object: &JsValue, name: &str) -> JsValue {
+    Reflect::get(object, &JsValue::from_str(name)).unwrap()
+}
+
+#[wasm_bindgen_test]
+async fn test_scores_and_bucket_changes_reach_js_callbacks() {
+    let console = SensorConsole::with_transport(ScriptedTransport::delta_stream());
+    let (scores, on_score) = recorder();
+    let (changes, on_bucket_change) = recorder();
+    console.on_score(function(&on_score));
+    console.on_bucket_change(function(&on_bucket_change));
+
+    JsFuture::from(console.run(true)).await.unwrap();
+
+    let fears: Vec<f64> = scores.borrow().iter().map(|score| field(score, "normalizedFear").as_f64().unwrap()).collect();
+    assert_eq!(fears, FEARS.map(f64::from));
+    let first = &scores.borrow()[0];
+    assert_eq!(field(first, "capability").as_string().as_deref(), Some("full"));
+    assert_eq!(field(first, "bucket").as_string().as_deref(), Some("low"));
+    assert_eq!(field(first, "timestampMs").as_f64(), Some(1_700_000_000_000.0));
+    assert!(js_sys::Array::is_array(&field(first, "emotionLogits")));
+
+    let buckets: Vec<(Option<String>, Option<String>)> = changes
+        .borrow()
+        .iter()
+        .map(|change| (field(change, "from").as_string(), field(change, "to").as_string()))
+        .collect();
+    let bucket = |name: &str| Some(name.to_string());
+    assert_eq!(
+        buckets,
+        [
+            (None, bucket("low")),
+            (bucket("low"), bucket("medium")),
+            (bucket("medium"), bucket("high")),
+            (bucket("high"), bucket("low")),
+        ]
+    );
+    assert_eq!(console.bucket().as_string().as_deref(), Some("low"));
+}
+
+#[wasm_bindgen_test]
+async fn test_status_is_a_plain_object() {
+    let console = SensorConsole::with_transport(ScriptedTransport::delta_stream());
+    let status = JsFuture::from(console.status()).await.unwrap();
+    assert_eq!(field(&status, "running").as_bool(), Some(true));
+    assert_eq!(field(&status, "calibrated").as_bool(), Some(true));
+    assert_eq!(field(&status, "capability").as_string().as_deref(), Some("hold_last_value"));
+    assert!(field(&status, "lastError").is_undefined());
+}
+
+#[wasm_bindgen_test]
+async fn test_throwing_callback_rejects_run() {
+    let console = SensorConsole::with_transport(ScriptedTransport::delta_stream());
+    console.on_score(Function::new_with_args("score", "throw new Error('console closed')"));
+    assert!(JsFuture::from(console.run(true)).await.is_err());
+}
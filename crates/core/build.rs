@@ -0,0 +1,31 @@
+//! Generates the sigmoid lookup table used by `scoring::sigmoid_q16`
+//!
+//! The table covers z in [-8, 8] at a step of 1/16 and stores sigmoid(z) as
+//! unsigned Q0.16, so targets without an FPU never evaluate `exp`.
+
+use std::env;
+use std::fmt::Write as _;
+use std::fs;
+use std::path::Path;
+
+const Z_RANGE: f64 = 8.0;
+const STEPS_PER_UNIT: usize = 16;
+
+fn main() {
+    let entries = 2 * Z_RANGE as usize * STEPS_PER_UNIT + 1;
+
+    let mut table = String::new();
+    writeln!(table, "/// sigmoid(z) as Q0.16 for z = -8 + i/16").unwrap();
+    writeln!(table, "pub(crate) const SIGMOID_LUT: [u16; {}] = [", entries).unwrap();
+    for i in 0..entries {
+        let z = -Z_RANGE + i as f64 / STEPS_PER_UNIT as f64;
+        let value = 1.0 / (1.0 + (-z).exp());
+        writeln!(table, "    {},", (value * 65535.0).round() as u16).unwrap();
+    }
+    writeln!(table, "];").unwrap();
+
+    let out_dir = env::var("OUT_DIR").expect("OUT_DIR not set");
+    fs::write(Path::new(&out_dir).join("sigmoid_lut.rs"), table).expect("failed to write sigmoid table");
+
+    println!("cargo:rerun-if-changed=build.rs");
+}
@@ -2,7 +2,7 @@
 
 use std::time::Duration;
 use serde::{Deserialize, Serialize};
-use crate::{CameraConfig, ConfigError};
+use crate::{CameraConfig, ConfigError, FearBucket, FearBucketThresholds};
 
 /// Main configuration for fear detection
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -11,11 +11,13 @@ pub struct FearConfig {
     pub model_path: String,
     /// Camera configuration
     pub camera: CameraConfig,
-    /// Calibration duration in seconds
+    /// Calibration duration, e.g. "30s"
+    #[serde(with = "crate::duration")]
     pub calibration_duration: Duration,
     /// Whether to enable debug logging
     pub debug: bool,
-    /// Maximum inference timeout
+    /// Maximum inference timeout, e.g. "100ms"
+    #[serde(with = "crate::duration")]
     pub inference_timeout: Duration,
 }
 
@@ -61,6 +63,13 @@ impl FearConfig {
         self
     }
 
+    /// Samples needed to calibrate: `calibration_duration` worth of frames at
+    /// `camera.fps`, at least one
+    pub fn calibration_samples(&self) -> usize {
+        let samples = self.calibration_duration.as_millis() * u128::from(self.camera.fps) / 1000;
+        (samples as usize).max(1)
+    }
+
     /// Validate the configuration
     pub fn validate(&self) -> Result<(), ConfigError> {
         if self.model_path.is_empty() {
@@ -69,14 +78,14 @@ impl FearConfig {
             });
         }
 
-        if self.calibration_duration.as_secs() == 0 {
+        if self.calibration_duration.is_zero() {
             return Err(ConfigError::InvalidValue {
                 field: "calibration_duration".to_string(),
                 message: "must be greater than 0".to_string(),
             });
         }
 
-        if self.inference_timeout.as_millis() == 0 {
+        if self.inference_timeout.is_zero() {
             return Err(ConfigError::InvalidValue {
                 field: "inference_timeout".to_string(),
                 message: "must be greater than 0".to_string(),
@@ -98,7 +107,7 @@ impl FearConfig {
         Ok(config)
     }
 
-    /// Save configuration to TOML file
+    /// Save configuration to TOML file, durations written as e.g. "30s"
     pub fn to_file(&self, path: &str) -> Result<(), ConfigError> {
         self.validate()?;
         let content = toml::to_string_pretty(self)?;
@@ -137,6 +146,130 @@ impl Default for TerrainConfig {
     }
 }
 
+/// Shape of the continuous fear-to-distortion curve
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "mode", rename_all = "snake_case")]
+pub enum DistortionCurve {
+    /// Intensity in proportion to fear
+    Linear,
+    /// Eases in at low fear and out at high fear
+    Smoothstep,
+    /// `fear^exponent`: above 1 holds back until fear is high, below 1
+    /// rises early
+    Gamma { exponent: f32 },
+}
+
+impl DistortionCurve {
+    /// Position of `fear` along the curve [0.0, 1.0]
+    pub fn apply(&self, fear: f32) -> f32 {
+        let fear = if fear.is_nan() { 0.0 } else { fear.clamp(0.0, 1.0) };
+        match *self {
+            DistortionCurve::Linear => fear,
+            DistortionCurve::Smoothstep => fear * fear * (3.0 - 2.0 * fear),
+            DistortionCurve::Gamma { exponent } => fear.powf(exponent),
+        }
+    }
+}
+
+/// How fear scores map to buckets and distortion intensities
+///
+/// The defaults reproduce the fixed 0.33/0.66 buckets and their 0.1, 0.5
+/// and 1.0 intensities. Without a `curve` the shader intensity blends
+/// between the bucket intensities; with one it follows the curve from the
+/// Low to the High intensity instead.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FearMapping {
+    /// Scores at which the Medium and High buckets start
+    pub thresholds: FearBucketThresholds,
+    /// Distortion intensities of the Low, Medium and High buckets
+    pub intensities: [f32; 3],
+    /// Continuous curve for the shader intensity, e.g. `{ mode = "smoothstep" }`
+    pub curve: Option<DistortionCurve>,
+}
+
+impl Default for FearMapping {
+    fn default() -> Self {
+        Self {
+            thresholds: FearBucketThresholds::DEFAULT,
+            intensities: FearBucket::DISTORTION_INTENSITIES,
+            curve: None,
+        }
+    }
+}
+
+impl FearMapping {
+    /// Set the bucket boundaries
+    pub fn with_thresholds(mut self, thresholds: FearBucketThresholds) -> Self {
+        self.thresholds = thresholds;
+        self
+    }
+
+    /// Set the Low, Medium and High distortion intensities
+    pub fn with_intensities(mut self, intensities: [f32; 3]) -> Self {
+        self.intensities = intensities;
+        self
+    }
+
+    /// Set the continuous distortion curve
+    pub fn with_curve(mut self, curve: DistortionCurve) -> Self {
+        self.curve = Some(curve);
+        self
+    }
+
+    /// Distortion intensity of a bucket, used for terrain meshes
+    pub fn intensity(&self, bucket: FearBucket) -> f32 {
+        self.intensities[bucket as usize]
+    }
+
+    /// Distortion intensity for `score`, used for shader uniforms
+    pub fn distortion_intensity(&self, score: f32) -> f32 {
+        match self.curve {
+            None => self.thresholds.blend_intensities(score, self.intensities),
+            Some(curve) => {
+                let [low, _, high] = self.intensities;
+                low + (high - low) * curve.apply(score)
+            }
+        }
+    }
+
+    /// Validate the mapping
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        let FearBucketThresholds { medium, high } = self.thresholds;
+        if !(0.0..=1.0).contains(&medium) || !(0.0..=1.0).contains(&high) || medium > high {
+            return Err(ConfigError::InvalidValue {
+                field: "thresholds".to_string(),
+                message: format!("must satisfy 0 <= medium <= high <= 1, got {} and {}", medium, high),
+            });
+        }
+
+        if self.intensities.iter().any(|intensity| !intensity.is_finite() || *intensity < 0.0) {
+            return Err(ConfigError::InvalidValue {
+                field: "intensities".to_string(),
+                message: format!("must be finite and not negative, got {:?}", self.intensities),
+            });
+        }
+
+        if let Some(DistortionCurve::Gamma { exponent }) = self.curve {
+            if !exponent.is_finite() || exponent <= 0.0 {
+                return Err(ConfigError::InvalidValue {
+                    field: "curve.exponent".to_string(),
+                    message: format!("must be greater than 0, got {}", exponent),
+                });
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl FearBucket {
+    /// Classify a fear score into a bucket with `mapping`'s boundaries
+    pub fn from_score_with(score: f32, mapping: &FearMapping) -> Self {
+        mapping.thresholds.classify(score)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -165,6 +298,21 @@ mod tests {
         assert!(config.debug);
     }
 
+    #[test]
+    fn test_calibration_samples() {
+        assert_eq!(FearConfig::default().calibration_samples(), 900);
+
+        let mut config = FearConfig::default().with_calibration_duration(Duration::from_millis(500));
+        config.camera.fps = 20;
+        assert_eq!(config.calibration_samples(), 10);
+
+        // Never zero, however short the window
+        config.calibration_duration = Duration::from_millis(10);
+        assert_eq!(config.calibration_samples(), 1);
+        config.camera.fps = 0;
+        assert_eq!(config.calibration_samples(), 1);
+    }
+
     #[test]
     fn test_fear_config_validation() {
         let mut config = FearConfig::default();
@@ -176,6 +324,166 @@ mod tests {
         config.model_path = "test.onnx".to_string();
         config.calibration_duration = Duration::from_secs(0);
         assert!(config.validate().is_err());
+
+        // Sub-second durations are fine, zero ones are not
+        config.calibration_duration = Duration::from_millis(500);
+        assert!(config.validate().is_ok());
+        config.inference_timeout = Duration::ZERO;
+        assert!(matches!(
+            config.validate(),
+            Err(ConfigError::InvalidValue { field, .. }) if field == "inference_timeout"
+        ));
+    }
+
+    fn temp_path(name: &str) -> String {
+        std::env::temp_dir()
+            .join(format!("spectremesh_{}_{}.toml", name, std::process::id()))
+            .to_string_lossy()
+            .into_owned()
+    }
+
+    #[test]
+    fn test_fear_config_file_round_trip() {
+        let path = temp_path("round_trip");
+        let config = FearConfig::new()
+            .with_model_path("custom/model.onnx")
+            .with_calibration_duration(Duration::from_millis(1500));
+        config.to_file(&path).unwrap();
+
+        let content = std::fs::read_to_string(&path).unwrap();
+        assert!(content.contains("calibration_duration = \"1s 500ms\""), "{}", content);
+        assert!(content.contains("inference_timeout = \"100ms\""), "{}", content);
+
+        let loaded = FearConfig::from_file(&path).unwrap();
+        assert_eq!(loaded.calibration_duration, Duration::from_millis(1500));
+        assert_eq!(loaded.inference_timeout, Duration::from_millis(100));
+        assert_eq!(loaded.model_path, "custom/model.onnx");
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_fear_config_legacy_durations() {
+        let camera = "[camera]\ndevice_id = 0\nfps = 30\nwidth = 640\nheight = 480\n";
+
+        // Seconds as numbers, and serde's old { secs, nanos } tables
+        let config: FearConfig = toml::from_str(&format!(
+            "model_path = \"m.onnx\"\ncalibration_duration = 0.1\ndebug = false\ninference_timeout = 2\n{}",
+            camera
+        ))
+        .unwrap();
+        assert_eq!(config.calibration_duration, Duration::from_millis(100));
+        assert_eq!(config.inference_timeout, Duration::from_secs(2));
+        assert!(config.validate().is_ok());
+
+        let config: FearConfig = toml::from_str(&format!(
+            "model_path = \"m.onnx\"\ndebug = false\n\
+             calibration_duration = {{ secs = 30, nanos = 0 }}\n\
+             inference_timeout = {{ secs = 0, nanos = 100000000 }}\n{}",
+            camera
+        ))
+        .unwrap();
+        assert_eq!(config.calibration_duration, Duration::from_secs(30));
+        assert_eq!(config.inference_timeout, Duration::from_millis(100));
+
+        let path = temp_path("garbage");
+        std::fs::write(
+            &path,
+            format!(
+                "model_path = \"m.onnx\"\ncalibration_duration = \"30 parsecs\"\ndebug = false\ninference_timeout = \"100ms\"\n{}",
+                camera
+            ),
+        )
+        .unwrap();
+        let message = FearConfig::from_file(&path).unwrap_err().to_string();
+        assert!(message.contains("calibration_duration"), "{}", message);
+        assert!(message.contains("Invalid duration \"30 parsecs\": unknown time unit \"parsecs\""), "{}", message);
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_default_fear_mapping_matches_fixed_buckets() {
+        let mapping = FearMapping::default();
+        assert!(mapping.validate().is_ok());
+        for i in 0..=1000 {
+            let score = i as f32 / 1000.0;
+            assert_eq!(FearBucket::from_score_with(score, &mapping), FearBucket::from_score(score));
+            assert_eq!(mapping.distortion_intensity(score), FearBucketThresholds::DEFAULT.distortion_intensity(score));
+        }
+        for bucket in [FearBucket::Low, FearBucket::Medium, FearBucket::High] {
+            assert_eq!(mapping.intensity(bucket), bucket.distortion_intensity());
+        }
+        assert_eq!(mapping.distortion_intensity(f32::NAN), 0.1);
+    }
+
+    #[test]
+    fn test_fear_mapping_validation() {
+        let thresholds = |medium, high| FearMapping::default().with_thresholds(FearBucketThresholds { medium, high });
+        assert!(thresholds(0.2, 0.8).validate().is_ok());
+        // A collapsed Medium bucket is allowed, crossed boundaries are not
+        assert!(thresholds(0.5, 0.5).validate().is_ok());
+        assert!(matches!(
+            thresholds(0.7, 0.6).validate(),
+            Err(ConfigError::InvalidValue { field, .. }) if field == "thresholds"
+        ));
+        assert!(thresholds(-0.1, 0.6).validate().is_err());
+        assert!(thresholds(0.5, 1.5).validate().is_err());
+        assert!(thresholds(f32::NAN, 0.6).validate().is_err());
+
+        assert!(FearMapping::default().with_intensities([0.0, 0.2, 2.0]).validate().is_ok());
+        assert!(FearMapping::default().with_intensities([-0.1, 0.5, 1.0]).validate().is_err());
+        assert!(FearMapping::default().with_intensities([0.1, f32::INFINITY, 1.0]).validate().is_err());
+
+        let gamma = |exponent| FearMapping::default().with_curve(DistortionCurve::Gamma { exponent });
+        assert!(gamma(2.2).validate().is_ok());
+        assert!(gamma(0.0).validate().is_err());
+        assert!(gamma(f32::NAN).validate().is_err());
+    }
+
+    #[test]
+    fn test_fear_mapping_toml_round_trip() {
+        let mapping = FearMapping::default()
+            .with_thresholds(FearBucketThresholds { medium: 0.25, high: 0.75 })
+            .with_intensities([0.0, 0.4, 1.2])
+            .with_curve(DistortionCurve::Gamma { exponent: 2.0 });
+        let content = toml::to_string_pretty(&mapping).unwrap();
+        assert!(content.contains("mode = \"gamma\""), "{}", content);
+        assert_eq!(toml::from_str::<FearMapping>(&content).unwrap(), mapping);
+
+        for curve in [DistortionCurve::Linear, DistortionCurve::Smoothstep] {
+            let mapping = FearMapping::default().with_curve(curve);
+            let content = toml::to_string_pretty(&mapping).unwrap();
+            assert_eq!(toml::from_str::<FearMapping>(&content).unwrap(), mapping);
+        }
+
+        // Missing fields keep their defaults
+        let mapping: FearMapping = toml::from_str("[thresholds]\nmedium = 0.2\nhigh = 0.6\n").unwrap();
+        assert_eq!(mapping.thresholds, FearBucketThresholds { medium: 0.2, high: 0.6 });
+        assert_eq!(mapping.intensities, FearBucket::DISTORTION_INTENSITIES);
+        assert_eq!(mapping.curve, None);
+    }
+
+    #[test]
+    fn test_distortion_curves() {
+        let curved = |curve| FearMapping::default().with_intensities([0.2, 0.5, 1.0]).with_curve(curve);
+
+        let linear = curved(DistortionCurve::Linear);
+        assert_eq!(linear.distortion_intensity(0.0), 0.2);
+        assert!((linear.distortion_intensity(0.5) - 0.6).abs() < 1e-6);
+        assert_eq!(linear.distortion_intensity(1.0), 1.0);
+        assert_eq!(linear.distortion_intensity(f32::NAN), 0.2);
+
+        let smoothstep = curved(DistortionCurve::Smoothstep);
+        assert!((smoothstep.distortion_intensity(0.5) - 0.6).abs() < 1e-6);
+        assert!(smoothstep.distortion_intensity(0.1) < linear.distortion_intensity(0.1));
+        assert!(smoothstep.distortion_intensity(0.9) > linear.distortion_intensity(0.9));
+
+        let gamma = curved(DistortionCurve::Gamma { exponent: 2.0 });
+        assert!((gamma.distortion_intensity(0.5) - 0.4).abs() < 1e-6);
+        assert_eq!(gamma.distortion_intensity(1.5), 1.0);
+
+        // Curves ignore the Medium intensity but not the buckets
+        assert_eq!(FearBucket::from_score_with(0.5, &gamma), FearBucket::Medium);
+        assert_eq!(gamma.intensity(FearBucket::Medium), 0.5);
     }
 
     #[test]
@@ -0,0 +1,169 @@
+//! Human-readable durations in configuration files
+//!
+//! Config fields holding a [`Duration`] use
+//! `#[serde(with = "spectremesh_core::duration")]`: they are written as
+//! humantime strings such as `"30s"`, `"500ms"` or `"1h 30m"`, and read from
+//! any string humantime parses. Files written before durations were strings
+//! still load: a bare number is taken as seconds (`0.1`), and serde's
+//! default `{ secs, nanos }` table is accepted too.
+
+use crate::DurationError;
+use serde::de::{self, Deserializer, MapAccess, Visitor};
+use serde::Serializer;
+use std::fmt;
+use std::time::Duration;
+
+/// Parse a humantime duration such as `"30s"` or `"1m 30s"`
+pub fn parse(input: &str) -> Result<Duration, DurationError> {
+    humantime::parse_duration(input.trim()).map_err(|e| DurationError::Invalid {
+        input: input.to_string(),
+        message: e.to_string(),
+    })
+}
+
+/// A duration in whole or fractional seconds, as older config files hold them
+pub fn from_secs(seconds: f64) -> Result<Duration, DurationError> {
+    Duration::try_from_secs_f64(seconds).map_err(|_| DurationError::Seconds { seconds })
+}
+
+/// The humantime form of `duration`, e.g. `"1s 500ms"`
+pub fn format(duration: Duration) -> String {
+    humantime::format_duration(duration).to_string()
+}
+
+/// Serialize a duration as a humantime string
+pub fn serialize<S: Serializer>(duration: &Duration, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(&format(*duration))
+}
+
+/// Deserialize a duration from a humantime string, a number of seconds or a
+/// `{ secs, nanos }` table
+pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Duration, D::Error> {
+    deserializer.deserialize_any(DurationVisitor)
+}
+
+struct DurationVisitor;
+
+impl<'de> Visitor<'de> for DurationVisitor {
+    type Value = Duration;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("a duration such as \"30s\" or \"500ms\", or a number of seconds")
+    }
+
+    fn visit_str<E: de::Error>(self, value: &str) -> Result<Duration, E> {
+        parse(value).map_err(E::custom)
+    }
+
+    fn visit_u64<E: de::Error>(self, value: u64) -> Result<Duration, E> {
+        Ok(Duration::from_secs(value))
+    }
+
+    fn visit_i64<E: de::Error>(self, value: i64) -> Result<Duration, E> {
+        from_secs(value as f64).map_err(E::custom)
+    }
+
+    fn visit_f64<E: de::Error>(self, value: f64) -> Result<Duration, E> {
+        from_secs(value).map_err(E::custom)
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Duration, A::Error> {
+        let (mut secs, mut nanos) = (None, 0u32);
+        while let Some(key) = map.next_key::<String>()? {
+            match key.as_str() {
+                "secs" => secs = Some(map.next_value::<u64>()?),
+                "nanos" => nanos = map.next_value()?,
+                other => return Err(de::Error::unknown_field(other, &["secs", "nanos"])),
+            }
+        }
+        let secs = secs.ok_or_else(|| de::Error::missing_field("secs"))?;
+        Duration::from_secs(secs)
+            .checked_add(Duration::from_nanos(nanos.into()))
+            .ok_or_else(|| de::Error::custom("duration overflows"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Timeout {
+        #[serde(with = "crate::duration")]
+        timeout: Duration,
+    }
+
+    fn toml_timeout(value: &str) -> Result<Duration, toml::de::Error> {
+        toml::from_str::<Timeout>(&format!("timeout = {}", value)).map(|t| t.timeout)
+    }
+
+    #[test]
+    fn test_parse_humantime_forms() {
+        assert_eq!(toml_timeout("\"30s\"").unwrap(), Duration::from_secs(30));
+        assert_eq!(toml_timeout("\"500ms\"").unwrap(), Duration::from_millis(500));
+        assert_eq!(toml_timeout("\"1h 30m\"").unwrap(), Duration::from_secs(90 * 60));
+        assert_eq!(toml_timeout("\"2min\"").unwrap(), Duration::from_secs(120));
+        assert_eq!(toml_timeout("\"1s 250ms\"").unwrap(), Duration::from_millis(1250));
+        assert_eq!(toml_timeout("\" 100us \"").unwrap(), Duration::from_micros(100));
+        assert_eq!(toml_timeout("\"0s\"").unwrap(), Duration::ZERO);
+
+        let json: Timeout = serde_json::from_str(r#"{"timeout": "45s"}"#).unwrap();
+        assert_eq!(json.timeout, Duration::from_secs(45));
+    }
+
+    #[test]
+    fn test_numeric_seconds_still_accepted() {
+        assert_eq!(toml_timeout("30").unwrap(), Duration::from_secs(30));
+        assert_eq!(toml_timeout("0.1").unwrap(), Duration::from_millis(100));
+        assert_eq!(toml_timeout("{ secs = 2, nanos = 500000000 }").unwrap(), Duration::from_millis(2500));
+        assert_eq!(toml_timeout("{ secs = 3 }").unwrap(), Duration::from_secs(3));
+
+        let json: Timeout = serde_json::from_str(r#"{"timeout": 1.5}"#).unwrap();
+        assert_eq!(json.timeout, Duration::from_millis(1500));
+        let json: Timeout = serde_json::from_str(r#"{"timeout": {"secs": 1, "nanos": 0}}"#).unwrap();
+        assert_eq!(json.timeout, Duration::from_secs(1));
+    }
+
+    #[test]
+    fn test_garbage_is_rejected_clearly() {
+        let error = parse("30 parsecs").unwrap_err();
+        assert!(matches!(&error, DurationError::Invalid { input, .. } if input == "30 parsecs"));
+        let message = error.to_string();
+        assert!(message.starts_with("Invalid duration \"30 parsecs\": unknown time unit \"parsecs\""), "{}", message);
+        assert!(message.ends_with("(expected e.g. \"30s\", \"500ms\" or \"1h 30m\")"), "{}", message);
+
+        // Parse errors point at the offending field
+        let message = toml_timeout("\"30 parsecs\"").unwrap_err().to_string();
+        assert!(message.contains("timeout"), "{}", message);
+        assert!(message.contains("Invalid duration \"30 parsecs\""), "{}", message);
+
+        assert!(toml_timeout("\"\"").is_err());
+        assert!(toml_timeout("\"fast\"").is_err());
+        assert!(toml_timeout("true").is_err());
+        assert!(toml_timeout("{ minutes = 3 }").is_err());
+        assert_eq!(
+            toml_timeout("-1").unwrap_err().message(),
+            "Invalid duration -1 seconds: must be finite and not negative"
+        );
+        assert!(toml_timeout("nan").is_err());
+        assert!(toml_timeout("inf").is_err());
+    }
+
+    #[test]
+    fn test_round_trip() {
+        for duration in [
+            Duration::ZERO,
+            Duration::from_millis(500),
+            Duration::from_millis(1500),
+            Duration::from_secs(30),
+            Duration::from_secs(90 * 60 + 1),
+            Duration::from_nanos(1_000_000_007),
+        ] {
+            let text = toml::to_string(&Timeout { timeout: duration }).unwrap();
+            assert!(text.starts_with("timeout = \""), "{}", text);
+            assert_eq!(toml::from_str::<Timeout>(&text).unwrap().timeout, duration);
+        }
+        assert_eq!(format(Duration::from_millis(1500)), "1s 500ms");
+    }
+}
@@ -0,0 +1,402 @@
+//! Emotion logits sized by the model that produced them
+//!
+//! The bundled model emits seven classes with fear at index 2
+//! ([`EmotionLayout::STANDARD`]), but other models emit more or fewer.
+//! [`EmotionLogits`] holds however many a layout calls for and checks the
+//! length when it is built, so a mismatched model is an error rather than
+//! silently truncated or padded output.
+//!
+//! [`EmotionVector`] names the seven standard channels for gameplay code
+//! that reacts to more than fear.
+
+use crate::scoring::FEAR_INDEX;
+use crate::LogitsError;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::ops::{Deref, DerefMut};
+use std::str::FromStr;
+
+/// Channels in the standard emotion layout
+pub const STANDARD_CHANNELS: usize = 7;
+
+/// How many emotion classes a model emits and which one is fear
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct EmotionLayout {
+    /// Number of logits per inference
+    pub channels: usize,
+    /// Index of the fear logit
+    pub fear_index: usize,
+}
+
+impl EmotionLayout {
+    /// [angry, disgust, fear, happy, sad, surprise, neutral]
+    pub const STANDARD: Self = Self { channels: STANDARD_CHANNELS, fear_index: FEAR_INDEX };
+
+    /// A layout of `channels` logits with fear at `fear_index`
+    pub fn new(channels: usize, fear_index: usize) -> Result<Self, LogitsError> {
+        let layout = Self { channels, fear_index };
+        layout.validate()?;
+        Ok(layout)
+    }
+
+    /// Check that fear is one of the channels
+    pub fn validate(&self) -> Result<(), LogitsError> {
+        if self.fear_index >= self.channels {
+            return Err(LogitsError::Layout {
+                channels: self.channels,
+                fear_index: self.fear_index,
+            });
+        }
+        Ok(())
+    }
+
+    /// Whether this is the seven-class layout
+    pub fn is_standard(&self) -> bool {
+        *self == Self::STANDARD
+    }
+}
+
+impl Default for EmotionLayout {
+    fn default() -> Self {
+        Self::STANDARD
+    }
+}
+
+/// One inference's emotion logits, exactly as many as their layout has channels
+///
+/// Dereferences to a slice, whose length cannot change, so the invariant
+/// holds for as long as the value lives.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EmotionLogits {
+    values: Vec<f32>,
+    layout: EmotionLayout,
+}
+
+impl EmotionLogits {
+    /// Logits for `layout`, rejecting any other length
+    pub fn new(values: Vec<f32>, layout: EmotionLayout) -> Result<Self, LogitsError> {
+        layout.validate()?;
+        if values.len() != layout.channels {
+            return Err(LogitsError::Length {
+                expected: layout.channels,
+                got: values.len(),
+            });
+        }
+        Ok(Self { values, layout })
+    }
+
+    /// `layout.channels` zeros
+    pub fn zeros(layout: EmotionLayout) -> Self {
+        Self {
+            values: vec![0.0; layout.channels],
+            layout,
+        }
+    }
+
+    /// Seven logits in the standard layout
+    pub fn standard(values: [f32; STANDARD_CHANNELS]) -> Self {
+        Self {
+            values: values.to_vec(),
+            layout: EmotionLayout::STANDARD,
+        }
+    }
+
+    pub fn layout(&self) -> EmotionLayout {
+        self.layout
+    }
+
+    /// The fear logit, wherever the layout puts it
+    pub fn fear(&self) -> f32 {
+        self.values[self.layout.fear_index]
+    }
+
+    pub fn as_slice(&self) -> &[f32] {
+        &self.values
+    }
+
+    /// Softmax probabilities, one per channel ([`crate::math::softmax`])
+    pub fn probabilities(&self) -> Vec<f32> {
+        crate::math::softmax(&self.values)
+    }
+
+    pub fn into_vec(self) -> Vec<f32> {
+        self.values
+    }
+
+    /// The seven standard logits, for code that predates other layouts
+    ///
+    /// Fails with [`LogitsError::NotStandard`] for any other layout rather
+    /// than truncating or padding: a channel index means nothing across
+    /// layouts.
+    pub fn to_standard(&self) -> Result<[f32; STANDARD_CHANNELS], LogitsError> {
+        if !self.layout.is_standard() {
+            return Err(LogitsError::NotStandard { layout: self.layout });
+        }
+        let mut standard = [0.0; STANDARD_CHANNELS];
+        standard.copy_from_slice(&self.values);
+        Ok(standard)
+    }
+}
+
+impl Default for EmotionLogits {
+    /// Seven zeros
+    fn default() -> Self {
+        Self::zeros(EmotionLayout::STANDARD)
+    }
+}
+
+impl From<[f32; STANDARD_CHANNELS]> for EmotionLogits {
+    fn from(values: [f32; STANDARD_CHANNELS]) -> Self {
+        Self::standard(values)
+    }
+}
+
+impl AsRef<[f32]> for EmotionLogits {
+    fn as_ref(&self) -> &[f32] {
+        &self.values
+    }
+}
+
+impl Deref for EmotionLogits {
+    type Target = [f32];
+
+    fn deref(&self) -> &[f32] {
+        &self.values
+    }
+}
+
+impl DerefMut for EmotionLogits {
+    fn deref_mut(&mut self) -> &mut [f32] {
+        &mut self.values
+    }
+}
+
+impl<const N: usize> PartialEq<[f32; N]> for EmotionLogits {
+    fn eq(&self, other: &[f32; N]) -> bool {
+        self.values[..] == other[..]
+    }
+}
+
+/// The emotions of the standard layout, in channel order
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Emotion {
+    Angry,
+    Disgust,
+    Fear,
+    Happy,
+    Sad,
+    Surprise,
+    Neutral,
+}
+
+impl Emotion {
+    /// Every emotion, in channel order
+    pub const ALL: [Self; STANDARD_CHANNELS] = [
+        Self::Angry,
+        Self::Disgust,
+        Self::Fear,
+        Self::Happy,
+        Self::Sad,
+        Self::Surprise,
+        Self::Neutral,
+    ];
+
+    /// Channel of this emotion in the standard layout
+    pub fn index(self) -> usize {
+        self as usize
+    }
+
+    /// Lowercase name, as used in configuration
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Angry => "angry",
+            Self::Disgust => "disgust",
+            Self::Fear => "fear",
+            Self::Happy => "happy",
+            Self::Sad => "sad",
+            Self::Surprise => "surprise",
+            Self::Neutral => "neutral",
+        }
+    }
+}
+
+impl fmt::Display for Emotion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for Emotion {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let name = s.trim().to_ascii_lowercase();
+        Self::ALL
+            .into_iter()
+            .find(|emotion| emotion.name() == name)
+            .ok_or_else(|| format!("Unknown emotion {:?}, expected one of angry, disgust, fear, happy, sad, surprise, neutral", s))
+    }
+}
+
+/// Softmax probabilities of the seven standard emotions, summing to 1
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct EmotionVector {
+    probabilities: [f32; STANDARD_CHANNELS],
+}
+
+impl EmotionVector {
+    /// Probabilities of standard-layout `logits`
+    ///
+    /// Fails with [`LogitsError::NotStandard`] for any other layout, whose
+    /// channels have no names.
+    pub fn from_logits(logits: &EmotionLogits) -> Result<Self, LogitsError> {
+        let mut probabilities = [0.0; STANDARD_CHANNELS];
+        crate::math::softmax_into(&logits.to_standard()?, &mut probabilities);
+        Ok(Self { probabilities })
+    }
+
+    /// Probability of `emotion`
+    pub fn get(&self, emotion: Emotion) -> f32 {
+        self.probabilities[emotion.index()]
+    }
+
+    pub fn angry(&self) -> f32 {
+        self.get(Emotion::Angry)
+    }
+
+    pub fn disgust(&self) -> f32 {
+        self.get(Emotion::Disgust)
+    }
+
+    pub fn fear(&self) -> f32 {
+        self.get(Emotion::Fear)
+    }
+
+    pub fn happy(&self) -> f32 {
+        self.get(Emotion::Happy)
+    }
+
+    pub fn sad(&self) -> f32 {
+        self.get(Emotion::Sad)
+    }
+
+    pub fn surprise(&self) -> f32 {
+        self.get(Emotion::Surprise)
+    }
+
+    pub fn neutral(&self) -> f32 {
+        self.get(Emotion::Neutral)
+    }
+
+    /// The most probable emotion
+    ///
+    /// A tie involving neutral goes to neutral, so a face showing nothing
+    /// in particular reads as neutral; other ties go to the earlier channel.
+    pub fn dominant(&self) -> Emotion {
+        let ties = crate::math::argmax_ties(&self.probabilities);
+        if ties.contains(&Emotion::Neutral.index()) {
+            return Emotion::Neutral;
+        }
+        ties.first().map_or(Emotion::Neutral, |&index| Emotion::ALL[index])
+    }
+
+    /// Every emotion with its probability, in channel order
+    pub fn iter(&self) -> impl Iterator<Item = (Emotion, f32)> + '_ {
+        Emotion::ALL.into_iter().zip(self.probabilities)
+    }
+
+    pub fn as_array(&self) -> [f32; STANDARD_CHANNELS] {
+        self.probabilities
+    }
+}
+
+impl Default for EmotionVector {
+    /// Entirely neutral
+    fn default() -> Self {
+        let mut probabilities = [0.0; STANDARD_CHANNELS];
+        probabilities[Emotion::Neutral.index()] = 1.0;
+        Self { probabilities }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_length_checked_at_construction() {
+        let layout = EmotionLayout::new(10, 4).unwrap();
+        let mut values = vec![0.0; 10];
+        values[4] = 2.5;
+        let logits = EmotionLogits::new(values, layout).unwrap();
+        assert_eq!(logits.len(), 10);
+        assert_eq!(logits.fear(), 2.5);
+        assert_eq!(logits.layout(), layout);
+
+        assert_eq!(
+            EmotionLogits::new(vec![0.0; 10], EmotionLayout::STANDARD).unwrap_err(),
+            LogitsError::Length { expected: 7, got: 10 }
+        );
+        assert_eq!(
+            EmotionLogits::new(vec![0.0; 7], layout).unwrap_err().to_string(),
+            "expected 10 emotion channels, got 7"
+        );
+        assert_eq!(
+            EmotionLayout::new(3, 3).unwrap_err(),
+            LogitsError::Layout { channels: 3, fear_index: 3 }
+        );
+    }
+
+    #[test]
+    fn test_standard_shim() {
+        let logits = EmotionLogits::from([0.1, 0.1, 0.8, 0.1, 0.1, 0.1, 0.1]);
+        assert_eq!(logits.fear(), 0.8);
+        assert_eq!(logits, [0.1, 0.1, 0.8, 0.1, 0.1, 0.1, 0.1]);
+        assert_eq!(logits.to_standard().unwrap()[2], 0.8);
+        assert_eq!(EmotionLogits::default(), [0.0; 7]);
+
+        let wide = EmotionLogits::zeros(EmotionLayout::new(10, 2).unwrap());
+        assert!(matches!(wide.to_standard(), Err(LogitsError::NotStandard { .. })));
+    }
+
+    #[test]
+    fn test_emotion_vector_is_normalized() {
+        let logits = EmotionLogits::from([1.0, -2.0, 3.5, 0.5, 80.0, -60.0, 2.0]);
+        let emotions = EmotionVector::from_logits(&logits).unwrap();
+        let total: f32 = emotions.iter().map(|(_, probability)| probability).sum();
+        assert!((total - 1.0).abs() < 1e-6);
+        assert!(emotions.iter().all(|(_, probability)| (0.0..=1.0).contains(&probability)));
+        assert!(emotions.sad() > 0.99);
+        assert!(emotions.fear() > emotions.angry());
+        assert_eq!(emotions.get(Emotion::Surprise), emotions.surprise());
+
+        let wide = EmotionLogits::zeros(EmotionLayout::new(10, 2).unwrap());
+        assert!(matches!(EmotionVector::from_logits(&wide), Err(LogitsError::NotStandard { .. })));
+        assert_eq!(EmotionVector::default().dominant(), Emotion::Neutral);
+        assert_eq!(EmotionVector::default().neutral(), 1.0);
+    }
+
+    #[test]
+    fn test_dominant_emotion_ties() {
+        let dominant = |logits: [f32; 7]| EmotionVector::from_logits(&logits.into()).unwrap().dominant();
+        assert_eq!(dominant([0.0, 0.0, 4.0, 0.0, 0.0, 1.0, 0.0]), Emotion::Fear);
+        // Ties go to the earlier channel, unless neutral is among them
+        assert_eq!(dominant([0.0, 0.0, 3.0, 0.0, 0.0, 3.0, 0.0]), Emotion::Fear);
+        assert_eq!(dominant([0.0, 0.0, 0.0, 2.0, 0.0, 2.0, 2.0]), Emotion::Neutral);
+        assert_eq!(dominant([0.0; 7]), Emotion::Neutral);
+        // A NaN logit carries no preference
+        assert_eq!(dominant([f32::NAN, 0.0, 5.0, 0.0, 0.0, 0.0, 0.0]), Emotion::Neutral);
+    }
+
+    #[test]
+    fn test_emotion_names() {
+        for emotion in Emotion::ALL {
+            assert_eq!(emotion.name().parse::<Emotion>(), Ok(emotion));
+            assert_eq!(Emotion::ALL[emotion.index()], emotion);
+        }
+        assert_eq!(Emotion::Fear.index(), FEAR_INDEX);
+        assert_eq!(" Surprise ".parse::<Emotion>(), Ok(Emotion::Surprise));
+        assert!("joy".parse::<Emotion>().is_err());
+    }
+}
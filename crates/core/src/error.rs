@@ -88,8 +88,8 @@ pub enum TerrainError {
     #[error("Mesh generation failed: {message}")]
     MeshGeneration { message: String },
 
-    #[error("Invalid chunk coordinates: x={x}, z={z}")]
-    InvalidChunkCoordinates { x: i32, z: i32 },
+    #[error("Invalid chunk coordinates: x={x}, y={y}, z={z}")]
+    InvalidChunkCoordinates { x: i32, y: i32, z: i32 },
 }
 
 /// Configuration error types
@@ -111,6 +111,35 @@ pub enum ConfigError {
     Deserialization(#[from] toml::de::Error),
 }
 
+/// Duration parsing error types
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum DurationError {
+    #[error("Invalid duration \"{input}\": {message} (expected e.g. \"30s\", \"500ms\" or \"1h 30m\")")]
+    Invalid { input: String, message: String },
+
+    #[error("Invalid duration {seconds} seconds: must be finite and not negative")]
+    Seconds { seconds: f64 },
+}
+
+/// Emotion logits that do not fit their layout
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum LogitsError {
+    #[error("expected {expected} emotion channels, got {got}")]
+    Length { expected: usize, got: usize },
+
+    #[error("fear index {fear_index} is outside {channels} emotion channels")]
+    Layout { channels: usize, fear_index: usize },
+
+    #[error("{} emotion channels with fear at {} are not the standard seven", .layout.channels, .layout.fear_index)]
+    NotStandard { layout: crate::emotion::EmotionLayout },
+}
+
+impl From<LogitsError> for FearError {
+    fn from(error: LogitsError) -> Self {
+        Self::invalid_logits(error.to_string())
+    }
+}
+
 impl FearError {
     /// Create a new ONNX Runtime error
     pub fn onnx_runtime(message: impl Into<String>) -> Self {
@@ -1,10 +1,36 @@
 //! Core types and utilities for SpectreMesh
+//!
+//! The `scoring` module is `no_std` and allocation-free so the bucket,
+//! normalization and fear-index weighting math can run on embedded targets.
+//! Build with `--no-default-features` to drop the std-only modules.
 
+#![cfg_attr(not(feature = "std"), no_std)]
+
+pub mod scoring;
+#[cfg(feature = "std")]
 pub mod types;
+#[cfg(feature = "std")]
 pub mod error;
+#[cfg(feature = "std")]
 pub mod config;
+#[cfg(feature = "std")]
+pub mod duration;
+#[cfg(feature = "std")]
+pub mod emotion;
+#[cfg(feature = "std")]
+pub mod math;
+#[cfg(feature = "std")]
+pub mod normalization;
 
 // Re-export main types
+pub use scoring::*;
+#[cfg(feature = "std")]
 pub use types::*;
+#[cfg(feature = "std")]
 pub use error::*;
+#[cfg(feature = "std")]
 pub use config::*;
+#[cfg(feature = "std")]
+pub use emotion::{Emotion, EmotionLayout, EmotionLogits, EmotionVector};
+#[cfg(feature = "std")]
+pub use normalization::InputNormalization;
@@ -0,0 +1,357 @@
+//! Numerically stable probability math over logits
+//!
+//! Emotion models emit logits far outside the range a naive softmax
+//! survives: `exp(100.0)` is already infinite in `f32`, and one infinite
+//! term turns every probability into NaN. Everything here subtracts the
+//! largest logit before exponentiating, so every term is at most 1 and the
+//! normalizing sum at least 1. Functions take slices and work the same for
+//! the fixed seven-channel arrays and [`crate::EmotionLogits`] of any
+//! layout. Sums run in index order, so a result depends only on its input.
+//!
+//! Edge cases, for every function taking logits:
+//!
+//! - Empty input gives empty output (entropy 0, no argmax).
+//! - A single logit has probability 1 (log-probability 0).
+//! - Equal logits, however large, give the uniform distribution.
+//! - A NaN logit carries no preference: the result is uniform.
+//! - `+inf` logits share the probability evenly; `-inf` logits get none
+//!   (log-probability `-inf`). All `-inf` is uniform.
+
+/// Logits shifted so the largest is 0, then divided by `temperature`;
+/// `None` when they show no preference and the distribution is uniform
+fn shifted(logits: &[f32], temperature: f32) -> Option<Vec<f32>> {
+    if temperature.is_nan() || temperature == f32::INFINITY || logits.iter().any(|logit| logit.is_nan()) {
+        return None;
+    }
+    let max = logits.iter().copied().fold(f32::NEG_INFINITY, f32::max);
+    if max == f32::NEG_INFINITY {
+        return None;
+    }
+    Some(
+        logits
+            .iter()
+            .map(|&logit| {
+                if logit == max {
+                    // Exact for ties and infinite maxima alike
+                    0.0
+                } else if temperature <= 0.0 {
+                    f32::NEG_INFINITY
+                } else {
+                    // Never positive, so dividing cannot overflow towards +inf
+                    (logit - max) / temperature
+                }
+            })
+            .collect(),
+    )
+}
+
+/// `ln Σ exp(s)` of shifted logits; the largest term is `exp(0) = 1`
+fn log_normalizer(shifted: &[f32]) -> f32 {
+    let sum: f64 = shifted.iter().map(|&s| f64::from(s.exp())).sum();
+    sum.ln() as f32
+}
+
+/// Probabilities of `logits`, summing to 1
+pub fn softmax(logits: &[f32]) -> Vec<f32> {
+    softmax_with_temperature(logits, 1.0)
+}
+
+/// [`softmax`] written into `probabilities`
+///
+/// # Panics
+///
+/// If the slices differ in length.
+pub fn softmax_into(logits: &[f32], probabilities: &mut [f32]) {
+    assert_eq!(logits.len(), probabilities.len(), "one probability per logit");
+    probabilities.copy_from_slice(&softmax(logits));
+}
+
+/// Natural log of each [`softmax`] probability, computed without forming
+/// the probabilities, so tiny ones keep their precision
+pub fn log_softmax(logits: &[f32]) -> Vec<f32> {
+    log_softmax_with_temperature(logits, 1.0)
+}
+
+/// Softmax of `logits / temperature`
+///
+/// Temperatures above 1 flatten the distribution and below 1 sharpen it.
+/// The limits are exact: `0` or below puts all probability on the largest
+/// logits (shared evenly between ties), `+inf` is uniform. A NaN
+/// temperature is uniform too.
+pub fn softmax_with_temperature(logits: &[f32], temperature: f32) -> Vec<f32> {
+    let Some(shifted) = shifted(logits, temperature) else {
+        return vec![1.0 / logits.len() as f32; logits.len()];
+    };
+    let exp: Vec<f64> = shifted.iter().map(|&s| f64::from(s.exp())).collect();
+    let sum: f64 = exp.iter().sum();
+    exp.iter().map(|&e| (e / sum) as f32).collect()
+}
+
+/// [`log_softmax`] of `logits / temperature`, with the limits of
+/// [`softmax_with_temperature`]
+pub fn log_softmax_with_temperature(logits: &[f32], temperature: f32) -> Vec<f32> {
+    let Some(shifted) = shifted(logits, temperature) else {
+        return vec![-(logits.len() as f32).ln(); logits.len()];
+    };
+    let normalizer = log_normalizer(&shifted);
+    shifted.iter().map(|&s| s - normalizer).collect()
+}
+
+/// Shannon entropy in nats of a probability distribution
+///
+/// Zero, negative and NaN probabilities contribute nothing. The result lies
+/// in `[0, ln n]`: 0 for certainty (and empty input), `ln n` for uniform.
+pub fn entropy(probabilities: &[f32]) -> f32 {
+    let uniform = (probabilities.len().max(1) as f32).ln();
+    probabilities
+        .iter()
+        .filter(|&&p| p > 0.0)
+        .map(|&p| -f64::from(p) * f64::from(p).ln())
+        .sum::<f64>()
+        .clamp(0.0, f64::from(uniform)) as f32
+}
+
+/// Entropy in nats of the [`softmax`] of `logits`
+pub fn softmax_entropy(logits: &[f32]) -> f32 {
+    entropy(&softmax(logits))
+}
+
+/// Confidence [0.0, 1.0] of a prediction from the face detector's
+/// confidence and the emotion `logits`
+///
+/// The model's certainty is one minus the softmax entropy over its maximum
+/// `ln n`: 1 for a one-hot prediction, 0 for uniform logits. The result is
+/// the geometric mean of the face confidence (clamped, NaN as 0) and that
+/// certainty, so either being 0 makes it 0. Fewer than two logits carry no
+/// uncertainty and leave the face confidence.
+pub fn compute_confidence(face_confidence: f32, logits: &[f32]) -> f32 {
+    let face_confidence = if face_confidence.is_nan() { 0.0 } else { face_confidence.clamp(0.0, 1.0) };
+    if logits.len() < 2 {
+        return face_confidence;
+    }
+    let certainty = (1.0 - softmax_entropy(logits) / (logits.len() as f32).ln()).clamp(0.0, 1.0);
+    (face_confidence * certainty).sqrt()
+}
+
+/// Index of the largest value, the first one on ties
+///
+/// NaN values are skipped; `None` for empty input or all NaN.
+pub fn argmax(values: &[f32]) -> Option<usize> {
+    values
+        .iter()
+        .enumerate()
+        .filter(|(_, value)| !value.is_nan())
+        .fold(None, |best: Option<(usize, f32)>, (index, &value)| match best {
+            Some((_, max)) if max >= value => best,
+            _ => Some((index, value)),
+        })
+        .map(|(index, _)| index)
+}
+
+/// Indices of every value equal to the largest, in order
+///
+/// NaN values are skipped; empty for empty input or all NaN.
+pub fn argmax_ties(values: &[f32]) -> Vec<usize> {
+    let Some(max) = argmax(values).map(|index| values[index]) else {
+        return Vec::new();
+    };
+    values
+        .iter()
+        .enumerate()
+        .filter(|(_, &value)| value == max)
+        .map(|(index, _)| index)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    const TRIALS: usize = 2000;
+
+    fn close(actual: &[f32], expected: &[f32], tolerance: f32) -> bool {
+        actual.len() == expected.len()
+            && actual.iter().zip(expected).all(|(a, e)| (a - e).abs() <= tolerance * e.abs().max(1.0))
+    }
+
+    /// Logits of random length and spread, reaching ±1e4
+    fn random_logits(rng: &mut StdRng) -> Vec<f32> {
+        let len = rng.gen_range(1..=32);
+        let spread = [1.0, 10.0, 100.0, 1e4][rng.gen_range(0..4)];
+        (0..len)
+            .map(|_| match rng.gen_range(0..10) {
+                0 => spread,
+                1 => -spread,
+                _ => rng.gen_range(-spread..=spread),
+            })
+            .collect()
+    }
+
+    /// Softmax and log-softmax worked in f64 from the textbook formula
+    fn reference(logits: &[f32], temperature: f64) -> (Vec<f64>, Vec<f64>) {
+        let scaled: Vec<f64> = logits.iter().map(|&logit| f64::from(logit) / temperature).collect();
+        let max = scaled.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+        let sum: f64 = scaled.iter().map(|x| (x - max).exp()).sum();
+        let log: Vec<f64> = scaled.iter().map(|x| x - max - sum.ln()).collect();
+        (log.iter().map(|l| l.exp()).collect(), log)
+    }
+
+    #[test]
+    fn test_edge_cases() {
+        assert!(softmax(&[]).is_empty());
+        assert!(log_softmax(&[]).is_empty());
+        assert_eq!(entropy(&[]), 0.0);
+        assert_eq!(softmax_entropy(&[]), 0.0);
+        assert_eq!(argmax(&[]), None);
+
+        assert_eq!(softmax(&[-3.0e4]), [1.0]);
+        assert_eq!(log_softmax(&[7.0]), [0.0]);
+        assert_eq!(softmax_entropy(&[7.0]), 0.0);
+        assert_eq!(argmax(&[7.0]), Some(0));
+
+        for value in [0.0, 1e4, -1e4, f32::MAX] {
+            assert_eq!(softmax(&[value; 4]), [0.25; 4]);
+            assert!((softmax_entropy(&[value; 7]) - 7f32.ln()).abs() < 1e-6);
+            assert_eq!(argmax(&[value; 4]), Some(0));
+            assert_eq!(argmax_ties(&[value; 4]), [0, 1, 2, 3]);
+        }
+    }
+
+    #[test]
+    fn test_non_finite_logits() {
+        assert_eq!(softmax(&[f32::NAN, 1.0, 2.0, 3.0]), [0.25; 4]);
+        assert_eq!(log_softmax(&[f32::NAN, 1.0]), [-(2f32.ln()); 2]);
+        assert_eq!(softmax_entropy(&[f32::NAN; 7]), 7f32.ln());
+
+        assert_eq!(softmax(&[f32::INFINITY, 1e4, f32::INFINITY, 0.0]), [0.5, 0.0, 0.5, 0.0]);
+        assert_eq!(softmax(&[f32::NEG_INFINITY, 0.0, 0.0]), [0.0, 0.5, 0.5]);
+        assert_eq!(log_softmax(&[f32::NEG_INFINITY, 0.0]), [f32::NEG_INFINITY, 0.0]);
+        assert_eq!(softmax(&[f32::NEG_INFINITY; 2]), [0.5; 2]);
+        assert_eq!(softmax(&[f32::MAX, -f32::MAX]), [1.0, 0.0]);
+
+        assert_eq!(argmax(&[f32::NAN, 1.0, f32::NAN, 3.0]), Some(3));
+        assert_eq!(argmax(&[f32::NAN; 3]), None);
+        assert!(argmax_ties(&[f32::NAN; 3]).is_empty());
+        assert_eq!(argmax_ties(&[f32::NEG_INFINITY, f32::INFINITY, 2.0, f32::INFINITY]), [1, 3]);
+    }
+
+    #[test]
+    fn test_temperature() {
+        let logits = [1.0, 3.0, 3.0, -2.0];
+        assert_eq!(softmax_with_temperature(&logits, 0.0), [0.0, 0.5, 0.5, 0.0]);
+        assert_eq!(softmax_with_temperature(&logits, -1.0), [0.0, 0.5, 0.5, 0.0]);
+        assert_eq!(softmax_with_temperature(&logits, f32::INFINITY), [0.25; 4]);
+        assert_eq!(softmax_with_temperature(&logits, f32::NAN), [0.25; 4]);
+        // A tiny temperature is the zero limit, not an overflow
+        assert_eq!(softmax_with_temperature(&[1.0, 0.999], 1e-30), [1.0, 0.0]);
+
+        let sharp = entropy(&softmax_with_temperature(&logits, 0.5));
+        let flat = entropy(&softmax_with_temperature(&logits, 4.0));
+        assert!(sharp < softmax_entropy(&logits) && softmax_entropy(&logits) < flat);
+        assert_eq!(log_softmax_with_temperature(&logits, 0.0)[0], f32::NEG_INFINITY);
+    }
+
+    #[test]
+    fn test_argmax_ties_go_to_the_first() {
+        assert_eq!(argmax(&[0.1, 0.7, 0.2, 0.7]), Some(1));
+        assert_eq!(argmax_ties(&[0.1, 0.7, 0.2, 0.7]), [1, 3]);
+        assert_eq!(argmax(&[-1.0, -0.5, -0.9]), Some(1));
+        assert_eq!(argmax_ties(&[0.0, -0.0]), [0, 1]);
+    }
+
+    #[test]
+    fn test_softmax_into() {
+        let mut probabilities = [0.0; 3];
+        softmax_into(&[0.0, 0.0, 0.0], &mut probabilities);
+        assert_eq!(probabilities, [1.0 / 3.0; 3]);
+    }
+
+    #[test]
+    #[should_panic(expected = "one probability per logit")]
+    fn test_softmax_into_rejects_mismatched_lengths() {
+        softmax_into(&[0.0; 3], &mut [0.0; 2]);
+    }
+
+    #[test]
+    fn test_property_probabilities_sum_to_one_and_stay_finite() {
+        let mut rng = StdRng::seed_from_u64(465);
+        for _ in 0..TRIALS {
+            let logits = random_logits(&mut rng);
+            let temperature = rng.gen_range(0.05..=10.0);
+            for probabilities in [softmax(&logits), softmax_with_temperature(&logits, temperature)] {
+                assert!(probabilities.iter().all(|p| p.is_finite() && (0.0..=1.0).contains(p)), "{:?}", logits);
+                let sum: f32 = probabilities.iter().sum();
+                assert!((sum - 1.0).abs() < 1e-5, "sum {} for {:?}", sum, logits);
+            }
+            for log in [log_softmax(&logits), log_softmax_with_temperature(&logits, temperature)] {
+                assert!(log.iter().all(|l| l.is_finite() && *l <= 0.0), "{:?}", logits);
+            }
+            let entropy = softmax_entropy(&logits);
+            assert!(entropy.is_finite() && (0.0..=(logits.len() as f32).ln() + 1e-6).contains(&entropy));
+        }
+    }
+
+    #[test]
+    fn test_property_invariant_to_shifting_every_logit() {
+        let mut rng = StdRng::seed_from_u64(466);
+        for _ in 0..TRIALS {
+            // Multiples of 1/64 within ±1.1e4 stay exact when shifted by an
+            // integer, so the shifted logits differ by exactly the same amounts
+            let logits: Vec<f32> = random_logits(&mut rng).iter().map(|logit| (logit * 64.0).round() / 64.0).collect();
+            let shift = rng.gen_range(-1000..=1000) as f32;
+            let moved: Vec<f32> = logits.iter().map(|logit| logit + shift).collect();
+            assert_eq!(softmax(&logits), softmax(&moved));
+            assert_eq!(log_softmax(&logits), log_softmax(&moved));
+            assert_eq!(softmax_with_temperature(&logits, 3.0), softmax_with_temperature(&moved, 3.0));
+            assert_eq!(argmax(&logits), argmax(&moved));
+        }
+    }
+
+    #[test]
+    fn test_property_matches_f64_reference() {
+        let mut rng = StdRng::seed_from_u64(467);
+        for _ in 0..TRIALS {
+            let logits = random_logits(&mut rng);
+            let temperature = [1.0, 0.5, 2.0, 7.5][rng.gen_range(0..4)];
+            let (probabilities, log) = reference(&logits, f64::from(temperature));
+            let probabilities: Vec<f32> = probabilities.iter().map(|&p| p as f32).collect();
+            let log: Vec<f32> = log.iter().map(|&l| l as f32).collect();
+
+            assert!(close(&softmax_with_temperature(&logits, temperature), &probabilities, 1e-6), "{:?}", logits);
+            assert!(close(&log_softmax_with_temperature(&logits, temperature), &log, 1e-5), "{:?}", logits);
+            let expected_entropy: f64 = reference(&logits, 1.0).0.iter().filter(|&&p| p > 0.0).map(|p| -p * p.ln()).sum();
+            assert!((f64::from(softmax_entropy(&logits)) - expected_entropy).abs() < 1e-5, "{:?}", logits);
+        }
+    }
+
+    #[test]
+    fn test_compute_confidence() {
+        // Uniform logits carry no certainty, however confident the face
+        assert_eq!(compute_confidence(1.0, &[0.3; 7]), 0.0);
+        assert!(compute_confidence(0.9, &[0.1, 0.1, 0.12, 0.1, 0.1, 0.1, 0.1]) < 0.1);
+
+        // A peaked distribution is nearly as confident as the face
+        let peaked = [-4.0, -4.0, 6.0, -4.0, -4.0, -4.0, -4.0];
+        assert!(compute_confidence(1.0, &peaked) > 0.95);
+        assert!(compute_confidence(0.9, &peaked) > 0.85);
+        assert!(compute_confidence(0.9, &peaked) < compute_confidence(1.0, &peaked));
+
+        // Two-way uncertainty sits between the two
+        let split = [-4.0, -4.0, 3.0, -4.0, -4.0, -4.0, 3.0];
+        let confidence = compute_confidence(1.0, &split);
+        assert!(confidence > 0.5 && confidence < compute_confidence(1.0, &peaked), "{}", confidence);
+
+        assert_eq!(compute_confidence(0.0, &peaked), 0.0);
+        assert_eq!(compute_confidence(f32::NAN, &peaked), 0.0);
+        assert_eq!(compute_confidence(1.5, &[2.0]), 1.0);
+        assert_eq!(compute_confidence(0.8, &[]), 0.8);
+
+        let mut rng = StdRng::seed_from_u64(11);
+        for _ in 0..TRIALS {
+            let logits = random_logits(&mut rng);
+            let confidence = compute_confidence(rng.gen_range(0.0..=1.0), &logits);
+            assert!((0.0..=1.0).contains(&confidence), "{} for {:?}", confidence, logits);
+        }
+    }
+}
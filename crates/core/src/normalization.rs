@@ -0,0 +1,135 @@
+//! Emotion model input normalization conventions
+//!
+//! Face crops are converted to grayscale pixels in [0, 1]; models trained
+//! with another convention expect those values shifted or scaled first. The
+//! convention a frame was scored with travels on its [`FearFrame`], so it
+//! lives here; choosing one for a model (from its metadata or a self-check)
+//! is up to the sensor.
+//!
+//! [`FearFrame`]: crate::types::FearFrame
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+/// ImageNet mean and standard deviation averaged over RGB, for grayscale input
+pub const IMAGENET_GRAY: InputNormalization = InputNormalization::MeanStd { mean: 0.449, std: 0.226 };
+
+/// How pixels in [0, 1] are transformed before emotion inference
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum InputNormalization {
+    /// Pixels as they are, in [0, 1]
+    #[default]
+    ZeroToOne,
+    /// Pixels scaled to [-1, 1]
+    MinusOneToOne,
+    /// `(pixel - mean) / std`, with mean and std on the [0, 1] scale
+    MeanStd { mean: f32, std: f32 },
+    /// Read the model metadata, else run the self-check
+    Auto,
+}
+
+impl InputNormalization {
+    /// Transform pixels in [0, 1] in place
+    ///
+    /// `Auto` must be resolved first and leaves the pixels unchanged.
+    pub fn apply(&self, pixels: &mut [f32]) {
+        match *self {
+            InputNormalization::ZeroToOne | InputNormalization::Auto => {}
+            InputNormalization::MinusOneToOne => pixels.iter_mut().for_each(|p| *p = *p * 2.0 - 1.0),
+            InputNormalization::MeanStd { mean, std } => {
+                let std = if std.abs() > f32::EPSILON { std } else { 1.0 };
+                pixels.iter_mut().for_each(|p| *p = (*p - mean) / std);
+            }
+        }
+    }
+}
+
+impl fmt::Display for InputNormalization {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InputNormalization::ZeroToOne => write!(f, "zero_to_one"),
+            InputNormalization::MinusOneToOne => write!(f, "minus_one_to_one"),
+            InputNormalization::MeanStd { mean, std } => write!(f, "mean_std:{},{}", mean, std),
+            InputNormalization::Auto => write!(f, "auto"),
+        }
+    }
+}
+
+impl FromStr for InputNormalization {
+    type Err = String;
+
+    /// `zero_to_one`, `minus_one_to_one`, `mean_std:<mean>,<std>` or `auto`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim().to_ascii_lowercase();
+        match s.as_str() {
+            "zero_to_one" | "0..1" => return Ok(Self::ZeroToOne),
+            "minus_one_to_one" | "-1..1" => return Ok(Self::MinusOneToOne),
+            "auto" => return Ok(Self::Auto),
+            _ => {}
+        }
+
+        let invalid = || format!("Unknown input normalization: {}", s);
+        let params = s.strip_prefix("mean_std:").ok_or_else(invalid)?;
+        let (mean, std) = params.split_once(',').ok_or_else(invalid)?;
+        let mean = mean.trim().parse().map_err(|_| invalid())?;
+        let std: f32 = std.trim().parse().map_err(|_| invalid())?;
+        if std <= 0.0 {
+            return Err(format!("Input normalization std must be positive: {}", s));
+        }
+        Ok(Self::MeanStd { mean, std })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_convention_math() {
+        let raw = [0.0, 0.25, 0.5, 1.0];
+
+        let mut pixels = raw;
+        InputNormalization::ZeroToOne.apply(&mut pixels);
+        assert_eq!(pixels, raw);
+
+        let mut pixels = raw;
+        InputNormalization::MinusOneToOne.apply(&mut pixels);
+        assert_eq!(pixels, [-1.0, -0.5, 0.0, 1.0]);
+
+        let mut pixels = raw;
+        InputNormalization::MeanStd { mean: 0.5, std: 0.25 }.apply(&mut pixels);
+        assert_eq!(pixels, [-2.0, -1.0, 0.0, 2.0]);
+
+        // Mean 0.5 and std 0.5 is the same as [-1, 1]
+        let mut pixels = raw;
+        InputNormalization::MeanStd { mean: 0.5, std: 0.5 }.apply(&mut pixels);
+        assert_eq!(pixels, [-1.0, -0.5, 0.0, 1.0]);
+
+        let mut pixels = raw;
+        IMAGENET_GRAY.apply(&mut pixels);
+        assert!((pixels[0] + 0.449 / 0.226).abs() < 1e-5);
+        assert!((pixels[3] - 0.551 / 0.226).abs() < 1e-5);
+    }
+
+    #[test]
+    fn test_parse_and_display_round_trip() {
+        for normalization in [
+            InputNormalization::ZeroToOne,
+            InputNormalization::MinusOneToOne,
+            InputNormalization::MeanStd { mean: 0.449, std: 0.226 },
+            InputNormalization::Auto,
+        ] {
+            assert_eq!(normalization.to_string().parse::<InputNormalization>(), Ok(normalization));
+        }
+        assert_eq!("-1..1".parse(), Ok(InputNormalization::MinusOneToOne));
+        assert!("mean_std:0.5".parse::<InputNormalization>().is_err());
+        assert!("mean_std:0.5,0".parse::<InputNormalization>().is_err());
+        assert!("zscore".parse::<InputNormalization>().is_err());
+
+        let config: InputNormalization = serde_json::from_str(r#"{"mean_std": {"mean": 0.5, "std": 0.2}}"#).unwrap();
+        assert_eq!(config, InputNormalization::MeanStd { mean: 0.5, std: 0.2 });
+        assert_eq!(serde_json::to_string(&InputNormalization::Auto).unwrap(), r#""auto""#);
+    }
+}
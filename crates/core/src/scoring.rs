@@ -0,0 +1,528 @@
+//! Allocation-free scoring math shared by the sensor and embedded targets
+//!
+//! Everything here builds under `#![no_std]`. The sigmoid is evaluated from
+//! a table generated at build time, with a Q16.16 entry point for targets
+//! that lack an FPU.
+
+include!(concat!(env!("OUT_DIR"), "/sigmoid_lut.rs"));
+
+/// Index of the fear class in the emotion logits
+/// Assumes: [angry, disgust, fear, happy, sad, surprise, neutral]
+pub const FEAR_INDEX: usize = 2;
+
+/// Smallest standard deviation used for z-score normalization
+pub const MIN_STD_DEV: f32 = 0.1;
+
+/// Q16.16 bounds of the sigmoid table
+const LUT_MIN_Q16: i32 = -8 << 16;
+const LUT_MAX_Q16: i32 = 8 << 16;
+/// Fractional bits between two table entries (1/16 step in Q16.16)
+const LUT_STEP_SHIFT: u32 = 12;
+
+/// Fear bucket classification for terrain updates
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "snake_case"))]
+pub enum FearBucket {
+    Low,    // [0.0, 0.33)
+    Medium, // [0.33, 0.66)
+    High,   // [0.66, 1.0]
+}
+
+impl FearBucket {
+    /// Distortion intensities of the Low, Medium and High buckets
+    pub const DISTORTION_INTENSITIES: [f32; 3] = [0.1, 0.5, 1.0];
+
+    /// Classify a fear score into a bucket with the default thresholds
+    pub fn from_score(score: f32) -> Self {
+        FearBucketThresholds::DEFAULT.classify(score)
+    }
+
+    /// The bucket above, if any
+    pub fn next(&self) -> Option<Self> {
+        match self {
+            FearBucket::Low => Some(FearBucket::Medium),
+            FearBucket::Medium => Some(FearBucket::High),
+            FearBucket::High => None,
+        }
+    }
+
+    /// The bucket below, if any
+    pub fn previous(&self) -> Option<Self> {
+        match self {
+            FearBucket::Low => None,
+            FearBucket::Medium => Some(FearBucket::Low),
+            FearBucket::High => Some(FearBucket::Medium),
+        }
+    }
+
+    /// Position of `score` within this bucket's range [0, 1]
+    ///
+    /// Scores outside the range (a transition not yet committed to this
+    /// bucket) clamp to its edges.
+    pub fn progress(&self, score: f32, thresholds: &FearBucketThresholds) -> f32 {
+        if score.is_nan() {
+            return 0.0;
+        }
+        let (lower, upper) = thresholds.range(*self);
+        let width = upper - lower;
+        if width <= f32::EPSILON {
+            return if score >= upper { 1.0 } else { 0.0 };
+        }
+        ((score - lower) / width).clamp(0.0, 1.0)
+    }
+
+    /// How far `score` is from tipping into the next bucket, in widths of this bucket
+    ///
+    /// `None` for the top bucket.
+    pub fn distance_to_next(&self, score: f32, thresholds: &FearBucketThresholds) -> Option<f32> {
+        self.next().map(|_| 1.0 - self.progress(score, thresholds))
+    }
+
+    /// How far `score` is from falling into the previous bucket, in widths of this bucket
+    ///
+    /// `None` for the bottom bucket.
+    pub fn distance_to_previous(&self, score: f32, thresholds: &FearBucketThresholds) -> Option<f32> {
+        self.previous().map(|_| self.progress(score, thresholds))
+    }
+
+    /// Get the distortion intensity for shader uniforms
+    pub fn distortion_intensity(&self) -> f32 {
+        Self::DISTORTION_INTENSITIES[*self as usize]
+    }
+}
+
+/// Scores at which the Medium and High buckets start
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
+pub struct FearBucketThresholds {
+    /// Lowest Medium score
+    pub medium: f32,
+    /// Lowest High score
+    pub high: f32,
+}
+
+impl FearBucketThresholds {
+    /// Low below 0.33, Medium below 0.66, High above
+    pub const DEFAULT: Self = Self { medium: 0.33, high: 0.66 };
+
+    /// Classify a fear score into a bucket
+    pub fn classify(&self, score: f32) -> FearBucket {
+        if score < self.medium {
+            FearBucket::Low
+        } else if score < self.high {
+            FearBucket::Medium
+        } else {
+            FearBucket::High
+        }
+    }
+
+    /// Scores covered by `bucket`, from its lower to its upper boundary
+    pub fn range(&self, bucket: FearBucket) -> (f32, f32) {
+        match bucket {
+            FearBucket::Low => (0.0, self.medium),
+            FearBucket::Medium => (self.medium, self.high),
+            FearBucket::High => (self.high, 1.0),
+        }
+    }
+
+    /// Distortion intensity for `score`, blended between the buckets'
+    /// intensities at their midpoints so it changes smoothly with fear
+    ///
+    /// Flat at the Low intensity below the Low midpoint and at the High
+    /// intensity above the High midpoint.
+    pub fn distortion_intensity(&self, score: f32) -> f32 {
+        self.blend_intensities(score, FearBucket::DISTORTION_INTENSITIES)
+    }
+
+    /// [`distortion_intensity`](Self::distortion_intensity) with the Low,
+    /// Medium and High intensities given in `intensities`
+    pub fn blend_intensities(&self, score: f32, intensities: [f32; 3]) -> f32 {
+        let anchors = [FearBucket::Low, FearBucket::Medium, FearBucket::High].map(|bucket| {
+            let (lower, upper) = self.range(bucket);
+            ((lower + upper) / 2.0, intensities[bucket as usize])
+        });
+        if score.is_nan() || score <= anchors[0].0 {
+            return anchors[0].1;
+        }
+        for pair in anchors.windows(2) {
+            let ((x0, y0), (x1, y1)) = (pair[0], pair[1]);
+            if score < x1 {
+                let t = if x1 - x0 > f32::EPSILON { (score - x0) / (x1 - x0) } else { 1.0 };
+                return y0 + (y1 - y0) * t.clamp(0.0, 1.0);
+            }
+        }
+        anchors[2].1
+    }
+}
+
+impl Default for FearBucketThresholds {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+/// Hysteresis and minimum dwell for bucket changes
+///
+/// A score oscillating around a threshold would otherwise flip the bucket
+/// (and rebuild the terrain) every frame. The bucket only follows once the
+/// score is `margin` past the threshold, and only after `dwell_frames`
+/// consecutive scores agree on the new bucket. Dwell is counted in scores
+/// rather than time so the result depends on the scores alone.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
+pub struct FearBucketSmoother {
+    /// How far past a threshold a score must be to count toward a change
+    pub margin: f32,
+    /// Consecutive scores needed before the bucket changes (0 acts as 1)
+    pub dwell_frames: u32,
+    /// Bucket the latest scores point to and how many in a row have
+    #[cfg_attr(feature = "std", serde(default))]
+    pending: Option<(FearBucket, u32)>,
+}
+
+impl FearBucketSmoother {
+    /// 0.05 past a threshold for three scores in a row (100 ms at 30 FPS)
+    pub const DEFAULT: Self = Self::new(0.05, 3);
+
+    /// Follow every score at once, like [`FearBucketThresholds::classify`]
+    pub const IMMEDIATE: Self = Self::new(0.0, 1);
+
+    pub const fn new(margin: f32, dwell_frames: u32) -> Self {
+        Self { margin, dwell_frames, pending: None }
+    }
+
+    /// The bucket to commit for `score`, given the committed `current` one
+    pub fn next(&mut self, current: FearBucket, score: f32, thresholds: &FearBucketThresholds) -> FearBucket {
+        let target = self.target(current, score, thresholds);
+        if target == current {
+            self.pending = None;
+            return current;
+        }
+
+        let seen = match self.pending {
+            Some((bucket, seen)) if bucket == target => seen + 1,
+            _ => 1,
+        };
+        if seen >= self.dwell_frames.max(1) {
+            self.pending = None;
+            target
+        } else {
+            self.pending = Some((target, seen));
+            current
+        }
+    }
+
+    /// Bucket a change is building toward and the scores seen for it so far
+    pub fn pending(&self) -> Option<(FearBucket, u32)> {
+        self.pending
+    }
+
+    /// Forget a change in progress
+    pub fn reset(&mut self) {
+        self.pending = None;
+    }
+
+    /// Where `score` points from `current`, counting only thresholds it is
+    /// `margin` past
+    fn target(&self, current: FearBucket, score: f32, thresholds: &FearBucketThresholds) -> FearBucket {
+        if score.is_nan() {
+            return current;
+        }
+        let margin = self.margin.max(0.0);
+        let up = thresholds.classify(score - margin);
+        let down = thresholds.classify(score + margin);
+        if up > current {
+            up
+        } else if down < current {
+            down
+        } else {
+            current
+        }
+    }
+}
+
+impl Default for FearBucketSmoother {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+/// Extract the fear logit from the seven emotion logits
+pub fn fear_logit(emotion_logits: &[f32; 7]) -> f32 {
+    emotion_logits[FEAR_INDEX]
+}
+
+/// Z-score of `raw` against a baseline, with the deviation floored at `MIN_STD_DEV`
+pub fn z_score(raw: f32, mean: f32, std_dev: f32) -> f32 {
+    (raw - mean) / std_dev.max(MIN_STD_DEV)
+}
+
+/// Sigmoid of a Q16.16 value, returned as Q0.16
+///
+/// Inputs outside [-8, 8] saturate to the table ends.
+pub fn sigmoid_q16(z_q16: i32) -> u16 {
+    let offset = (z_q16.clamp(LUT_MIN_Q16, LUT_MAX_Q16) - LUT_MIN_Q16) as u32;
+    let index = (offset >> LUT_STEP_SHIFT) as usize;
+    if index >= SIGMOID_LUT.len() - 1 {
+        return SIGMOID_LUT[SIGMOID_LUT.len() - 1];
+    }
+
+    let frac = offset & ((1 << LUT_STEP_SHIFT) - 1);
+    let low = SIGMOID_LUT[index] as u32;
+    let high = SIGMOID_LUT[index + 1] as u32;
+    (low + (((high - low) * frac) >> LUT_STEP_SHIFT)) as u16
+}
+
+/// Sigmoid evaluated from the lookup table (absolute error below 5e-4)
+pub fn sigmoid(z: f32) -> f32 {
+    if z.is_nan() {
+        return 0.5;
+    }
+    let z_q16 = (z.clamp(-8.0, 8.0) * 65536.0) as i32;
+    sigmoid_q16(z_q16) as f32 / 65535.0
+}
+
+/// Normalize a raw fear logit to [0, 1] via z-score and sigmoid
+pub fn normalize_z_sigmoid(raw: f32, mean: f32, std_dev: f32) -> f32 {
+    sigmoid(z_score(raw, mean, std_dev))
+}
+
+/// Fixed-point `normalize_z_sigmoid`: Q16.16 inputs, Q0.16 output
+pub fn normalize_z_sigmoid_q16(raw_q16: i32, mean_q16: i32, std_dev_q16: i32) -> u16 {
+    let min_std_q16 = (MIN_STD_DEV * 65536.0) as i64;
+    let std_dev = (std_dev_q16 as i64).max(min_std_q16);
+    let z = ((raw_q16 as i64 - mean_q16 as i64) << 16) / std_dev;
+    sigmoid_q16(z.clamp(i32::MIN as i64, i32::MAX as i64) as i32)
+}
+
+/// Weighted composite of emotion channels already normalized to [0, 1]
+///
+/// Positive weights raise the index as their channel rises; negative
+/// weights lower it (e.g. happiness counteracting fear). Channels without a
+/// weight count for nothing. `None` when every weight is zero.
+pub fn weighted_fear_index(normalized: impl IntoIterator<Item = f32>, weights: &[f32]) -> Option<f32> {
+    let mut weighted = 0.0;
+    let mut total = 0.0;
+    for (value, &weight) in normalized.into_iter().zip(weights) {
+        let contribution = if weight >= 0.0 { value } else { 1.0 - value };
+        weighted += weight.abs() * contribution;
+        total += weight.abs();
+    }
+    (total > 0.0).then(|| weighted / total)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn reference_sigmoid(z: f32) -> f32 {
+        1.0 / (1.0 + (-z).exp())
+    }
+
+    #[test]
+    fn test_fear_bucket_classification() {
+        assert_eq!(FearBucket::from_score(0.0), FearBucket::Low);
+        assert_eq!(FearBucket::from_score(0.32), FearBucket::Low);
+        assert_eq!(FearBucket::from_score(0.33), FearBucket::Medium);
+        assert_eq!(FearBucket::from_score(0.65), FearBucket::Medium);
+        assert_eq!(FearBucket::from_score(0.66), FearBucket::High);
+        assert_eq!(FearBucket::from_score(1.0), FearBucket::High);
+    }
+
+    fn close(actual: Option<f32>, expected: f32) -> bool {
+        actual.is_some_and(|actual| (actual - expected).abs() < 1e-5)
+    }
+
+    #[test]
+    fn test_bucket_progress_at_boundaries() {
+        let thresholds = FearBucketThresholds::default();
+
+        assert_eq!(FearBucket::Low.progress(0.0, &thresholds), 0.0);
+        assert!(close(Some(FearBucket::Low.progress(0.165, &thresholds)), 0.5));
+        assert_eq!(FearBucket::Medium.progress(0.33, &thresholds), 0.0);
+        assert!(close(Some(FearBucket::Medium.progress(0.6, &thresholds)), 0.27 / 0.33));
+        assert_eq!(FearBucket::High.progress(0.66, &thresholds), 0.0);
+        assert_eq!(FearBucket::High.progress(1.0, &thresholds), 1.0);
+
+        // Just below the next threshold is almost no distance away
+        assert!(FearBucket::Low.distance_to_next(0.3299, &thresholds).unwrap() < 1e-3);
+        assert!(close(FearBucket::Medium.distance_to_next(0.33, &thresholds), 1.0));
+        assert!(close(FearBucket::Medium.distance_to_previous(0.33, &thresholds), 0.0));
+        assert!(close(FearBucket::Medium.distance_to_previous(0.495, &thresholds), 0.5));
+        assert!(close(FearBucket::High.distance_to_previous(0.83, &thresholds), 0.5));
+
+        // The top bucket has no next, the bottom one no previous
+        assert_eq!(FearBucket::High.distance_to_next(0.9, &thresholds), None);
+        assert_eq!(FearBucket::Low.distance_to_previous(0.1, &thresholds), None);
+        assert_eq!(FearBucket::Low.progress(f32::NAN, &thresholds), 0.0);
+    }
+
+    #[test]
+    fn test_bucket_progress_with_custom_thresholds() {
+        let thresholds = FearBucketThresholds { medium: 0.2, high: 0.8 };
+        assert_eq!(thresholds.classify(0.19), FearBucket::Low);
+        assert_eq!(thresholds.classify(0.5), FearBucket::Medium);
+        assert_eq!(thresholds.classify(0.8), FearBucket::High);
+
+        assert!(close(Some(FearBucket::Low.progress(0.1, &thresholds)), 0.5));
+        assert!(close(Some(FearBucket::Medium.progress(0.5, &thresholds)), 0.5));
+        assert!(close(FearBucket::Medium.distance_to_next(0.65, &thresholds), 0.25));
+        assert!(close(FearBucket::High.distance_to_previous(0.9, &thresholds), 0.5));
+
+        // A collapsed Medium bucket is entered and left at once
+        let collapsed = FearBucketThresholds { medium: 0.5, high: 0.5 };
+        assert_eq!(FearBucket::Medium.progress(0.49, &collapsed), 0.0);
+        assert_eq!(FearBucket::Medium.progress(0.5, &collapsed), 1.0);
+    }
+
+    #[test]
+    fn test_progress_outside_the_bucket_clamps() {
+        // Score already past a boundary the bucket has not committed to yet
+        let thresholds = FearBucketThresholds::default();
+        assert_eq!(FearBucket::Low.progress(0.45, &thresholds), 1.0);
+        assert!(close(FearBucket::Low.distance_to_next(0.45, &thresholds), 0.0));
+        assert_eq!(FearBucket::High.progress(0.5, &thresholds), 0.0);
+        assert!(close(FearBucket::High.distance_to_previous(0.5, &thresholds), 0.0));
+    }
+
+    #[test]
+    fn test_continuous_distortion_intensity() {
+        let thresholds = FearBucketThresholds::default();
+        // Each bucket's intensity at its midpoint, flat beyond the outer ones
+        assert_eq!(thresholds.distortion_intensity(0.0), 0.1);
+        assert!(close(Some(thresholds.distortion_intensity(0.165)), 0.1));
+        assert!(close(Some(thresholds.distortion_intensity(0.495)), 0.5));
+        assert!(close(Some(thresholds.distortion_intensity(0.83)), 1.0));
+        assert_eq!(thresholds.distortion_intensity(1.0), 1.0);
+        assert_eq!(thresholds.distortion_intensity(f32::NAN), 0.1);
+
+        // No step at a threshold, and never decreasing
+        let below = thresholds.distortion_intensity(0.3299);
+        let above = thresholds.distortion_intensity(0.33);
+        assert!((above - below).abs() < 1e-3);
+        let mut previous = 0.0;
+        for i in 0..=1000 {
+            let intensity = thresholds.distortion_intensity(i as f32 / 1000.0);
+            assert!(intensity >= previous && (0.1..=1.0).contains(&intensity));
+            previous = intensity;
+        }
+    }
+
+    /// Buckets committed for `scores`, starting from `start`
+    fn smoothed(smoother: &mut FearBucketSmoother, start: FearBucket, scores: &[f32]) -> Vec<FearBucket> {
+        let thresholds = FearBucketThresholds::default();
+        let mut bucket = start;
+        scores
+            .iter()
+            .map(|&score| {
+                bucket = smoother.next(bucket, score, &thresholds);
+                bucket
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_smoother_ignores_oscillation_around_a_threshold() {
+        let scores: Vec<f32> = (0..100).map(|i| if i % 2 == 0 { 0.32 } else { 0.34 }).collect();
+        let mut smoother = FearBucketSmoother::default();
+        assert!(smoothed(&mut smoother, FearBucket::Low, &scores).iter().all(|&b| b == FearBucket::Low));
+        assert_eq!(smoother.pending(), None);
+
+        // The same from the other side of the threshold
+        assert!(smoothed(&mut smoother, FearBucket::Medium, &scores).iter().all(|&b| b == FearBucket::Medium));
+    }
+
+    #[test]
+    fn test_smoother_follows_a_sustained_jump_after_the_dwell() {
+        let mut smoother = FearBucketSmoother::default();
+        let buckets = smoothed(&mut smoother, FearBucket::Low, &[0.8; 5]);
+        assert_eq!(buckets, [FearBucket::Low, FearBucket::Low, FearBucket::High, FearBucket::High, FearBucket::High]);
+
+        // A dip back for one score restarts the count
+        let buckets = smoothed(&mut smoother, FearBucket::High, &[0.1, 0.1, 0.8, 0.1, 0.1, 0.1]);
+        assert_eq!(buckets[..5], [FearBucket::High; 5]);
+        assert_eq!(buckets[5], FearBucket::Low);
+    }
+
+    #[test]
+    fn test_smoother_needs_the_margin() {
+        let mut smoother = FearBucketSmoother::default();
+        // 0.04 past the High threshold only reaches Medium
+        assert_eq!(smoothed(&mut smoother, FearBucket::Low, &[0.70; 3])[2], FearBucket::Medium);
+        assert_eq!(smoothed(&mut smoother, FearBucket::Medium, &[0.70; 10])[9], FearBucket::Medium);
+        assert_eq!(smoothed(&mut smoother, FearBucket::Medium, &[0.72; 3])[2], FearBucket::High);
+        assert_eq!(smoothed(&mut smoother, FearBucket::High, &[f32::NAN; 5])[4], FearBucket::High);
+
+        // Immediate follows the plain thresholds
+        let mut immediate = FearBucketSmoother::IMMEDIATE;
+        let buckets = smoothed(&mut immediate, FearBucket::Low, &[0.34, 0.32, 0.66]);
+        assert_eq!(buckets, [FearBucket::Medium, FearBucket::Low, FearBucket::High]);
+    }
+
+    #[test]
+    fn test_sigmoid_matches_reference() {
+        for i in -1000..=1000 {
+            let z = i as f32 / 100.0;
+            let error = (sigmoid(z) - reference_sigmoid(z)).abs();
+            assert!(error < 5e-4, "z={} error={}", z, error);
+        }
+        assert_eq!(sigmoid(f32::NAN), 0.5);
+    }
+
+    #[test]
+    fn test_sigmoid_q16_is_monotonic_and_saturates() {
+        assert_eq!(sigmoid_q16(0), 32768);
+        assert_eq!(sigmoid_q16(i32::MIN), SIGMOID_LUT[0]);
+        assert_eq!(sigmoid_q16(i32::MAX), SIGMOID_LUT[SIGMOID_LUT.len() - 1]);
+
+        let mut previous = 0;
+        for z in (LUT_MIN_Q16..=LUT_MAX_Q16).step_by(997) {
+            let value = sigmoid_q16(z);
+            assert!(value >= previous);
+            previous = value;
+        }
+    }
+
+    #[test]
+    fn test_normalize_z_sigmoid() {
+        assert!((normalize_z_sigmoid(2.0, 2.0, 1.0) - 0.5).abs() < 1e-4);
+        let expected = reference_sigmoid(1.5);
+        assert!((normalize_z_sigmoid(4.0, 1.0, 2.0) - expected).abs() < 5e-4);
+
+        // Degenerate deviation is floored instead of dividing by zero
+        let floored = reference_sigmoid(0.05 / MIN_STD_DEV);
+        assert!((normalize_z_sigmoid(1.05, 1.0, 0.0) - floored).abs() < 5e-4);
+    }
+
+    #[test]
+    fn test_normalize_z_sigmoid_q16_matches_float() {
+        let to_q16 = |value: f32| (value * 65536.0) as i32;
+        for &(raw, mean, std_dev) in &[(4.0, 1.0, 2.0), (-3.0, 0.5, 1.5), (0.2, 0.2, 0.4)] {
+            let fixed = normalize_z_sigmoid_q16(to_q16(raw), to_q16(mean), to_q16(std_dev)) as f32 / 65535.0;
+            assert!((fixed - normalize_z_sigmoid(raw, mean, std_dev)).abs() < 1e-3);
+        }
+    }
+
+    #[test]
+    fn test_fear_logit() {
+        assert_eq!(fear_logit(&[0.1, 0.1, 0.8, 0.1, 0.1, 0.1, 0.1]), 0.8);
+    }
+
+    #[test]
+    fn test_weighted_fear_index() {
+        let normalized = [0.5, 0.5, 0.9, 0.2, 0.5, 0.5, 0.5];
+        // Fear alone passes straight through
+        let fear_only = [0.0, 0.0, 1.0];
+        assert_eq!(weighted_fear_index(normalized, &fear_only), Some(0.9));
+
+        // Happiness counts against fear, in proportion to its weight
+        let calming = [0.0, 0.0, 3.0, -1.0];
+        let index = weighted_fear_index(normalized, &calming).unwrap();
+        assert!((index - (3.0 * 0.9 + 0.8) / 4.0).abs() < 1e-6);
+
+        // No weight, no index
+        assert_eq!(weighted_fear_index(normalized, &[0.0; 7]), None);
+        assert_eq!(weighted_fear_index(normalized, &[]), None);
+    }
+}
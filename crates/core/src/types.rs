@@ -3,49 +3,116 @@
 use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 use serde::{Deserialize, Serialize};
 
+pub use crate::emotion::{Emotion, EmotionLayout, EmotionLogits, EmotionVector};
+pub use crate::normalization::InputNormalization;
+pub use crate::scoring::{FearBucket, FearBucketSmoother, FearBucketThresholds};
+
+/// Fear level uncalibrated scores carry in place of a measured one
+///
+/// Sensors report it while their baseline is still being collected, so an
+/// uncalibrated stream reads as mildly uneasy rather than calm or terrified.
+pub const PROVISIONAL_FEAR: f32 = 0.3;
+
 /// A fear score measurement with metadata
 #[derive(Debug, Clone, PartialEq)]
 pub struct FearScore {
-    /// Normalized fear level [0.0, 1.0]
-    pub value: f32,
+    /// Normalized fear level [0.0, 1.0]; [`PROVISIONAL_FEAR`] when uncalibrated
+    value: f32,
     /// Raw emotion logits from the model
-    pub emotion_logits: [f32; 7],
+    pub emotion_logits: EmotionLogits,
     /// Model confidence [0.0, 1.0]
     pub confidence: f32,
     /// Whether this score has been calibrated
     pub calibrated: bool,
+    /// Whether a face was in frame; without one `value` repeats the last
+    /// measured fear
+    pub face_present: bool,
     /// When this measurement was taken
     pub timestamp: Instant,
+    /// Wall-clock time the camera frame was captured
+    pub captured_at: SystemTime,
+    /// Position in the sensor's frame count, starting at 1 (0 when not numbered)
+    pub sequence: u64,
 }
 
 impl FearScore {
     /// Create a new calibrated fear score
-    pub fn new_calibrated(value: f32, emotion_logits: [f32; 7], confidence: f32) -> Self {
+    pub fn new_calibrated(value: f32, emotion_logits: impl Into<EmotionLogits>, confidence: f32) -> Self {
         Self {
             value,
-            emotion_logits,
+            emotion_logits: emotion_logits.into(),
             confidence,
             calibrated: true,
+            face_present: true,
             timestamp: Instant::now(),
+            captured_at: SystemTime::now(),
+            sequence: 0,
         }
     }
 
     /// Create a new uncalibrated fear score
-    pub fn new_uncalibrated(value: f32, emotion_logits: [f32; 7], confidence: f32) -> Self {
+    ///
+    /// Without a baseline there is no normalized fear to report, so the
+    /// score carries [`PROVISIONAL_FEAR`], read through
+    /// [`provisional_value`](Self::provisional_value).
+    pub fn new_uncalibrated(emotion_logits: impl Into<EmotionLogits>, confidence: f32) -> Self {
         Self {
-            value,
-            emotion_logits,
+            value: PROVISIONAL_FEAR,
+            emotion_logits: emotion_logits.into(),
             confidence,
             calibrated: false,
+            face_present: true,
             timestamp: Instant::now(),
+            captured_at: SystemTime::now(),
+            sequence: 0,
         }
     }
 
-    /// Extract the fear logit from emotion logits
-    /// Assumes: [angry, disgust, fear, happy, sad, surprise, neutral]
+    /// Normalized fear level [0.0, 1.0]; `None` until calibrated
+    pub fn value(&self) -> Option<f32> {
+        self.calibrated.then_some(self.value)
+    }
+
+    /// Normalized fear level when calibrated, otherwise [`PROVISIONAL_FEAR`]
+    pub fn provisional_value(&self) -> f32 {
+        self.value
+    }
+
+    /// Record whether a face was in frame
+    pub fn with_face_present(mut self, face_present: bool) -> Self {
+        self.face_present = face_present;
+        self
+    }
+
+    /// Set the wall-clock time the frame was captured
+    pub fn with_capture_time(mut self, captured_at: SystemTime) -> Self {
+        self.captured_at = captured_at;
+        self
+    }
+
+    /// Set the frame's position in the sensor's frame count
+    pub fn with_sequence(mut self, sequence: u64) -> Self {
+        self.sequence = sequence;
+        self
+    }
+
+    /// Capture time as microseconds since Unix epoch
+    pub fn timestamp_us(&self) -> u64 {
+        self.captured_at
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_micros() as u64
+    }
+
+    /// Extract the fear logit from emotion logits, at the index their layout gives
     pub fn extract_fear_logit(&self) -> f32 {
-        const FEAR_INDEX: usize = 2;
-        self.emotion_logits[FEAR_INDEX]
+        self.emotion_logits.fear()
+    }
+
+    /// Probabilities of the seven standard emotions; `None` for logits in
+    /// another layout
+    pub fn emotions(&self) -> Option<EmotionVector> {
+        EmotionVector::from_logits(&self.emotion_logits).ok()
     }
 }
 
@@ -54,80 +121,253 @@ impl FearScore {
 pub struct FearFrame {
     /// When this measurement was taken
     pub timestamp: Instant,
+    /// Wall-clock time the camera frame was captured
+    pub captured_at: SystemTime,
+    /// Position in the sensor's frame count, starting at 1; a jump means
+    /// frames were dropped (0 when the frame is not numbered)
+    pub sequence: u64,
     /// The fear score measurement
     pub fear_score: f32,
-    /// Raw emotion logits from the model
-    pub emotion_logits: [f32; 7],
+    /// Raw emotion logits from the model, in its layout
+    pub emotion_logits: EmotionLogits,
     /// Model confidence [0.0, 1.0]
     pub confidence: f32,
     /// Whether this score has been calibrated
     pub calibrated: bool,
     /// Inference latency for this frame
     pub inference_latency: Duration,
+    /// Time the frame waited between capture and inference
+    pub queue_delay: Duration,
+    /// Rate-of-change (jump scare) signal [0.0, 1.0]
+    pub startle: f32,
+    /// Sensor capability when the frame was produced
+    pub capability: SensorCapability,
+    /// Pixel convention the emotion logits were computed with
+    pub input_normalization: InputNormalization,
+    /// Conditioning steps applied to the logits before calibration
+    pub conditioning: Conditioning,
+    /// Whether the frame was flipped horizontally after capture
+    pub mirrored: bool,
+    /// The scored face, normalized to the captured (and mirrored) frame;
+    /// `None` without a face
+    pub face_bbox: Option<NormalizedRect>,
+    /// Center of `face_bbox`
+    pub face_center: Option<(f32, f32)>,
+    /// Whether a face was in frame; without one nothing was measured and
+    /// the fear score repeats the last measured value
+    pub face_present: bool,
+    /// Track of the scored face, stable while the same person is followed
+    pub track_id: Option<u32>,
+    /// Faces detected in the frame, scored or not
+    pub faces_seen: u32,
 }
 
 impl FearFrame {
     /// Create a new fear frame
     pub fn new(
         fear_score: f32,
-        emotion_logits: [f32; 7],
+        emotion_logits: impl Into<EmotionLogits>,
         confidence: f32,
         calibrated: bool,
         inference_latency: Duration,
     ) -> Self {
         Self {
             timestamp: Instant::now(),
+            captured_at: SystemTime::now(),
+            sequence: 0,
             fear_score,
-            emotion_logits,
+            emotion_logits: emotion_logits.into(),
             confidence,
             calibrated,
             inference_latency,
+            queue_delay: Duration::ZERO,
+            startle: 0.0,
+            capability: SensorCapability::Full,
+            input_normalization: InputNormalization::default(),
+            conditioning: Conditioning::default(),
+            mirrored: false,
+            face_bbox: None,
+            face_center: None,
+            face_present: true,
+            track_id: None,
+            faces_seen: 0,
         }
     }
 
-    /// Get timestamp as microseconds since Unix epoch
+    /// Set the startle signal
+    pub fn with_startle(mut self, startle: f32) -> Self {
+        self.startle = startle.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Set the sensor capability
+    pub fn with_capability(mut self, capability: SensorCapability) -> Self {
+        self.capability = capability;
+        self
+    }
+
+    /// Set the input convention the logits were computed with
+    pub fn with_input_normalization(mut self, normalization: InputNormalization) -> Self {
+        self.input_normalization = normalization;
+        self
+    }
+
+    /// Set the conditioning steps applied to the logits
+    pub fn with_conditioning(mut self, conditioning: Conditioning) -> Self {
+        self.conditioning = conditioning;
+        self
+    }
+
+    /// Record whether the frame was flipped horizontally after capture
+    pub fn with_mirrored(mut self, mirrored: bool) -> Self {
+        self.mirrored = mirrored;
+        self
+    }
+
+    /// Set the face box, and the face center with it
+    pub fn with_face_bbox(mut self, face_bbox: Option<NormalizedRect>) -> Self {
+        self.face_bbox = face_bbox;
+        self.face_center = face_bbox.map(|bbox| bbox.center());
+        self
+    }
+
+    /// Set the time the frame waited for inference after capture
+    pub fn with_queue_delay(mut self, queue_delay: Duration) -> Self {
+        self.queue_delay = queue_delay;
+        self
+    }
+
+    /// Record whether a face was in frame
+    pub fn with_face_present(mut self, face_present: bool) -> Self {
+        self.face_present = face_present;
+        self
+    }
+
+    /// Set the wall-clock time the frame was captured
+    pub fn with_capture_time(mut self, captured_at: SystemTime) -> Self {
+        self.captured_at = captured_at;
+        self
+    }
+
+    /// Set the frame's position in the sensor's frame count
+    pub fn with_sequence(mut self, sequence: u64) -> Self {
+        self.sequence = sequence;
+        self
+    }
+
+    /// Record the scored face's track and how many faces were in view
+    pub fn with_tracking(mut self, track_id: Option<u32>, faces_seen: u32) -> Self {
+        self.track_id = track_id;
+        self.faces_seen = faces_seen;
+        self
+    }
+
+    /// Whether the fear score can be used
+    pub fn fear_available(&self) -> bool {
+        self.capability.fear_available()
+    }
+
+    /// Capture time as microseconds since Unix epoch
     pub fn timestamp_us(&self) -> u64 {
-        SystemTime::now()
+        self.captured_at
             .duration_since(UNIX_EPOCH)
             .unwrap_or_default()
             .as_micros() as u64
     }
 
-    /// Extract the fear logit from emotion logits
-    /// Assumes: [angry, disgust, fear, happy, sad, surprise, neutral]
+    /// Extract the fear logit from emotion logits, at the index their layout gives
     pub fn extract_fear_logit(&self) -> f32 {
-        const FEAR_INDEX: usize = 2;
-        self.emotion_logits[FEAR_INDEX]
+        self.emotion_logits.fear()
+    }
+
+    /// Probabilities of the seven standard emotions; `None` for logits in
+    /// another layout
+    pub fn emotions(&self) -> Option<EmotionVector> {
+        EmotionVector::from_logits(&self.emotion_logits).ok()
     }
 }
 
-/// Fear bucket classification for terrain updates
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
-pub enum FearBucket {
-    Low,    // [0.0, 0.33)
-    Medium, // [0.33, 0.66)
-    High,   // [0.66, 1.0]
+/// A box in frame coordinates normalized to [0.0, 1.0]
+///
+/// The origin is the top-left corner of the frame as captured, after any
+/// mirroring, so (0.5, 0.5) is the middle of the picture whatever its
+/// resolution.
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub struct NormalizedRect {
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
 }
 
-impl FearBucket {
-    /// Classify a fear score into a bucket
-    pub fn from_score(score: f32) -> Self {
-        if score < 0.33 {
-            FearBucket::Low
-        } else if score < 0.66 {
-            FearBucket::Medium
-        } else {
-            FearBucket::High
+impl NormalizedRect {
+    /// Normalize a pixel box against a `frame_width` × `frame_height` capture
+    ///
+    /// The box is clipped to the frame; `None` when nothing of it is inside.
+    pub fn from_pixels(x: i32, y: i32, width: i32, height: i32, frame_width: i32, frame_height: i32) -> Option<Self> {
+        if frame_width <= 0 || frame_height <= 0 {
+            return None;
+        }
+        let (left, top) = (x.max(0), y.max(0));
+        let right = x.saturating_add(width).min(frame_width);
+        let bottom = y.saturating_add(height).min(frame_height);
+        if right <= left || bottom <= top {
+            return None;
         }
+
+        let (frame_width, frame_height) = (frame_width as f32, frame_height as f32);
+        Some(Self {
+            x: left as f32 / frame_width,
+            y: top as f32 / frame_height,
+            width: (right - left) as f32 / frame_width,
+            height: (bottom - top) as f32 / frame_height,
+        })
     }
 
-    /// Get the distortion intensity for shader uniforms
-    pub fn distortion_intensity(&self) -> f32 {
-        match self {
-            FearBucket::Low => 0.1,
-            FearBucket::Medium => 0.5,
-            FearBucket::High => 1.0,
-        }
+    /// Center of the box
+    pub fn center(&self) -> (f32, f32) {
+        (self.x + self.width / 2.0, self.y + self.height / 2.0)
+    }
+}
+
+/// What the sensor can currently measure
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SensorCapability {
+    /// Emotion inference is healthy
+    #[default]
+    Full,
+    /// Emotion inference is failing; fear is held at the last good value
+    /// with falling confidence
+    HoldLastValue,
+    /// Emotion inference is offline; frames report face presence only and
+    /// fear must not be used
+    EmotionOffline,
+}
+
+impl SensorCapability {
+    /// Whether fear values from the sensor can be used
+    pub fn fear_available(&self) -> bool {
+        !matches!(self, SensorCapability::EmotionOffline)
+    }
+}
+
+/// Conditioning steps that changed a frame's logits
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Conditioning {
+    /// At least one raw logit exceeded the clamp bound
+    pub clamped: bool,
+    /// Logits were divided by a temperature other than 1
+    pub temperature_scaled: bool,
+    /// The calibration sample was capped at the baseline bounds
+    pub winsorized: bool,
+}
+
+impl Conditioning {
+    /// Whether any step changed the frame
+    pub fn any(&self) -> bool {
+        self.clamped || self.temperature_scaled || self.winsorized
     }
 }
 
@@ -140,12 +380,21 @@ pub struct CameraDevice {
     pub name: String,
     /// Supported resolution (width, height)
     pub resolution: (u32, u32),
+    /// Every resolution the device lists, largest first; empty where the
+    /// platform cannot say
+    pub supported_resolutions: Vec<(u32, u32)>,
 }
 
 impl CameraDevice {
     /// Create a new camera device
     pub fn new(id: u32, name: String, resolution: (u32, u32)) -> Self {
-        Self { id, name, resolution }
+        Self { id, name, resolution, supported_resolutions: Vec::new() }
+    }
+
+    /// Set the resolutions the device lists
+    pub fn with_supported_resolutions(mut self, resolutions: Vec<(u32, u32)>) -> Self {
+        self.supported_resolutions = resolutions;
+        self
     }
 }
 
@@ -178,13 +427,34 @@ mod tests {
     use super::*;
 
     #[test]
-    fn test_fear_bucket_classification() {
-        assert_eq!(FearBucket::from_score(0.0), FearBucket::Low);
-        assert_eq!(FearBucket::from_score(0.32), FearBucket::Low);
-        assert_eq!(FearBucket::from_score(0.33), FearBucket::Medium);
-        assert_eq!(FearBucket::from_score(0.65), FearBucket::Medium);
-        assert_eq!(FearBucket::from_score(0.66), FearBucket::High);
-        assert_eq!(FearBucket::from_score(1.0), FearBucket::High);
+    fn test_normalized_rect_from_pixels() {
+        // The same face position at common capture resolutions
+        for (frame_width, frame_height) in [(640, 480), (1280, 720), (1920, 1080)] {
+            let (w, h) = (frame_width / 4, frame_height / 4);
+            let rect = NormalizedRect::from_pixels(frame_width / 2, frame_height / 4, w, h, frame_width, frame_height)
+                .unwrap();
+            assert_eq!(rect, NormalizedRect { x: 0.5, y: 0.25, width: 0.25, height: 0.25 });
+            assert_eq!(rect.center(), (0.625, 0.375));
+        }
+
+        // Boxes hanging over the edge are clipped to the frame
+        let rect = NormalizedRect::from_pixels(-64, 400, 192, 160, 640, 480).unwrap();
+        assert_eq!(rect, NormalizedRect { x: 0.0, y: 400.0 / 480.0, width: 0.2, height: 80.0 / 480.0 });
+
+        assert_eq!(NormalizedRect::from_pixels(700, 10, 50, 50, 640, 480), None);
+        assert_eq!(NormalizedRect::from_pixels(10, 10, 0, 50, 640, 480), None);
+        assert_eq!(NormalizedRect::from_pixels(10, 10, 50, 50, 0, 0), None);
+    }
+
+    #[test]
+    fn test_frame_face_center_follows_bbox() {
+        let frame = FearFrame::new(0.5, [0.0; 7], 0.9, true, Duration::ZERO);
+        assert_eq!((frame.face_bbox, frame.face_center), (None, None));
+
+        let bbox = NormalizedRect { x: 0.1, y: 0.2, width: 0.4, height: 0.2 };
+        let frame = frame.with_face_bbox(Some(bbox));
+        assert_eq!(frame.face_center, Some((0.3, 0.3)));
+        assert_eq!(frame.with_face_bbox(None).face_center, None);
     }
 
     #[test]
@@ -199,12 +469,25 @@ mod tests {
         let emotion_logits = [0.1, 0.1, 0.8, 0.1, 0.1, 0.1, 0.1];
         let score = FearScore::new_calibrated(0.75, emotion_logits, 0.9);
 
-        assert_eq!(score.value, 0.75);
+        assert_eq!(score.value(), Some(0.75));
+        assert_eq!(score.provisional_value(), 0.75);
         assert_eq!(score.extract_fear_logit(), 0.8);
         assert!(score.calibrated);
         assert_eq!(score.confidence, 0.9);
     }
 
+    #[test]
+    fn test_uncalibrated_fear_score_is_provisional() {
+        let emotion_logits = [0.1, 0.1, 0.8, 0.1, 0.1, 0.1, 0.1];
+        let score = FearScore::new_uncalibrated(emotion_logits, 0.6);
+
+        assert_eq!(score.value(), None);
+        assert_eq!(score.provisional_value(), PROVISIONAL_FEAR);
+        assert_eq!(score.extract_fear_logit(), 0.8);
+        assert!(!score.calibrated);
+        assert_eq!(score.confidence, 0.6);
+    }
+
     #[test]
     fn test_fear_frame_creation() {
         let emotion_logits = [0.1, 0.1, 0.8, 0.1, 0.1, 0.1, 0.1];
@@ -222,11 +505,46 @@ mod tests {
         assert_eq!(frame.inference_latency, Duration::from_millis(5));
     }
 
+    #[test]
+    fn test_fear_frame_timestamp_is_capture_time() {
+        let captured = UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+        let first = FearFrame::new(0.5, [0.0; 7], 0.9, true, Duration::ZERO).with_capture_time(captured);
+        let second = FearFrame::new(0.5, [0.0; 7], 0.9, true, Duration::ZERO)
+            .with_capture_time(captured + Duration::from_millis(100))
+            .with_sequence(7);
+        assert_eq!((first.timestamp_us(), second.timestamp_us()), (1_700_000_000_000_000, 1_700_000_000_100_000));
+        assert_eq!(second.sequence, 7);
+
+        // Stamped when built; reading the timestamp later does not move it
+        let before = FearFrame::new(0.5, [0.0; 7], 0.9, true, Duration::ZERO).timestamp_us();
+        let frame = FearFrame::new(0.5, [0.0; 7], 0.9, true, Duration::ZERO);
+        let stamped = frame.timestamp_us();
+        std::thread::sleep(Duration::from_millis(20));
+        assert!(stamped >= before);
+        assert_eq!(frame.timestamp_us(), stamped);
+    }
+
+    #[test]
+    fn test_fear_frame_wider_layout() {
+        let layout = EmotionLayout::new(10, 4).unwrap();
+        let mut values = vec![0.1; 10];
+        values[4] = 0.9;
+        let frame = FearFrame::new(0.75, EmotionLogits::new(values, layout).unwrap(), 0.9, true, Duration::ZERO);
+
+        assert_eq!(frame.emotion_logits.len(), 10);
+        assert_eq!(frame.extract_fear_logit(), 0.9);
+        assert!(frame.emotion_logits.to_standard().is_err());
+    }
+
     #[test]
     fn test_camera_device() {
         let device = CameraDevice::new(0, "Test Camera".to_string(), (640, 480));
         assert_eq!(device.id, 0);
         assert_eq!(device.name, "Test Camera");
         assert_eq!(device.resolution, (640, 480));
+        assert!(device.supported_resolutions.is_empty());
+
+        let device = device.with_supported_resolutions(vec![(1280, 720), (640, 480)]);
+        assert_eq!(device.supported_resolutions, [(1280, 720), (640, 480)]);
     }
 }
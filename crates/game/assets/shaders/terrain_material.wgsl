@@ -0,0 +1,63 @@
+// Fear-reactive terrain: ripples along the normals and a pulsing red tint,
+// both scaled by the distortion intensity. Uniforms are written every frame
+// by update_shader_uniforms_system.
+
+#import bevy_pbr::{
+    mesh_functions,
+    view_transformations::position_world_to_clip,
+}
+
+struct TerrainUniforms {
+    base_color: vec4<f32>,
+    distortion_intensity: f32,
+    fear_level: f32,
+    time: f32,
+}
+
+@group(2) @binding(0) var<uniform> terrain: TerrainUniforms;
+
+struct Vertex {
+    @builtin(instance_index) instance_index: u32,
+    @location(0) position: vec3<f32>,
+    @location(1) normal: vec3<f32>,
+}
+
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) world_position: vec3<f32>,
+    @location(1) world_normal: vec3<f32>,
+}
+
+// World units the surface moves at full distortion
+const RIPPLE_HEIGHT: f32 = 0.8;
+const FEAR_TINT: vec3<f32> = vec3<f32>(0.55, 0.04, 0.06);
+const LIGHT_DIRECTION: vec3<f32> = vec3<f32>(0.37, 0.86, 0.35);
+
+@vertex
+fn vertex(vertex: Vertex) -> VertexOutput {
+    let world_from_local = mesh_functions::get_world_from_local(vertex.instance_index);
+    var world_position = mesh_functions::mesh_position_local_to_world(world_from_local, vec4<f32>(vertex.position, 1.0));
+    let world_normal = mesh_functions::mesh_normal_local_to_world(vertex.normal, vertex.instance_index);
+
+    let t = terrain.time;
+    let ripple = sin(world_position.x * 0.35 + t * 1.7) * cos(world_position.z * 0.3 - t * 1.3);
+    let offset = world_normal * ripple * terrain.distortion_intensity * RIPPLE_HEIGHT;
+    world_position = world_position + vec4<f32>(offset, 0.0);
+
+    var out: VertexOutput;
+    out.clip_position = position_world_to_clip(world_position.xyz);
+    out.world_position = world_position.xyz;
+    out.world_normal = world_normal;
+    return out;
+}
+
+@fragment
+fn fragment(in: VertexOutput) -> @location(0) vec4<f32> {
+    let diffuse = 0.35 + 0.65 * max(dot(normalize(in.world_normal), LIGHT_DIRECTION), 0.0);
+
+    // Faster pulse with more fear, deeper tint with more distortion
+    let pulse = 0.5 + 0.5 * sin(terrain.time * (1.0 + 5.0 * terrain.fear_level) + in.world_position.y * 0.2);
+    let tint = clamp(terrain.distortion_intensity * (0.6 + 0.4 * pulse), 0.0, 1.0);
+    let color = mix(terrain.base_color.rgb, FEAR_TINT, tint);
+    return vec4<f32>(color * diffuse, terrain.base_color.a);
+}
@@ -0,0 +1,123 @@
+//! Volumetric terrain rebuilt from fear
+//!
+//! Plays the mock sensor's step pattern (low → high → low), holding each
+//! level for two seconds. Every fear bucket change rebuilds the marching
+//! cubes terrain a few chunks per frame: calm hills at Low, leaning ridges
+//! at Medium and torn overhangs at High. The `TerrainMaterial` shader
+//! ripples the ground and bleeds it red as the smoothed fear rises, between
+//! rebuilds as well as across them. Chunks are spawned over the columns
+//! `TerrainStreamingPlugin` loads around the origin; this example adds the
+//! layer below ground so valleys deeper than one chunk stay closed.
+//!
+//! ```bash
+//! cargo run -p spectremesh --example fear_terrain
+//! ```
+
+use bevy::prelude::*;
+use spectre_sensor::mock_patterns;
+use spectremesh::{
+    components::TerrainChunk,
+    resources::{DensityTerrain, FearState, TerrainSettings},
+    FearSensorPlugin, FearSource, SpectreMeshPlugin, TerrainMaterial, TerrainStreamConfig, TerrainStreamingPlugin,
+};
+use spectremesh_core::TerrainConfig;
+
+/// Mock samples each step level is held for (about 30 per second)
+const SAMPLES_PER_LEVEL: usize = 60;
+
+#[derive(Component)]
+struct OverlayText;
+
+#[derive(Resource)]
+struct GroundMaterial(Handle<TerrainMaterial>);
+
+fn main() {
+    let sequence = mock_patterns::step()
+        .into_iter()
+        .flat_map(|fear| std::iter::repeat_n(fear, SAMPLES_PER_LEVEL))
+        .collect();
+    let config = TerrainConfig { render_distance: 4, ..Default::default() };
+
+    App::new()
+        .add_plugins(DefaultPlugins.set(WindowPlugin {
+            primary_window: Some(Window {
+                title: "SpectreMesh - Fear Terrain".to_string(),
+                ..default()
+            }),
+            ..default()
+        }))
+        .add_plugins((SpectreMeshPlugin, FearSensorPlugin { source: FearSource::Mock(sequence) }))
+        .add_plugins(TerrainStreamingPlugin::new(
+            TerrainStreamConfig::default().with_render_distance(config.render_distance),
+        ))
+        .insert_resource(DensityTerrain::new(&config, 7).with_chunks_per_frame(6))
+        .insert_resource(TerrainSettings(config))
+        .insert_resource(ClearColor(Color::srgb(0.1, 0.1, 0.15)))
+        .add_systems(Startup, setup_scene)
+        .add_systems(Update, (dress_new_chunks, update_overlay))
+        .run();
+}
+
+fn setup_scene(mut commands: Commands, mut materials: ResMut<Assets<TerrainMaterial>>) {
+    commands.insert_resource(GroundMaterial(materials.add(TerrainMaterial::new(Color::srgb(0.35, 0.3, 0.28)))));
+
+    let center = Vec3::new(0.0, TerrainConfig::default().base_height, 0.0);
+    commands.spawn((
+        DirectionalLight {
+            illuminance: 8000.0,
+            shadows_enabled: true,
+            ..default()
+        },
+        Transform::from_xyz(40.0, 120.0, 60.0).looking_at(center, Vec3::Y),
+    ));
+    // Chunks are centred on the origin, so look down over them rather than at them from afar
+    commands.spawn((
+        Camera3d::default(),
+        Transform::from_translation(center + Vec3::new(0.0, 80.0, 12.0)).looking_at(center - Vec3::Z * 24.0, Vec3::Y),
+    ));
+
+    commands.spawn((
+        Text::new(""),
+        TextFont { font_size: 16.0, ..default() },
+        TextColor(Color::srgb(0.8, 0.9, 0.8)),
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(12.0),
+            left: Val::Px(12.0),
+            ..default()
+        },
+        OverlayText,
+    ));
+}
+
+/// Give new chunks the terrain material, and each ground chunk a chunk below it
+fn dress_new_chunks(
+    mut commands: Commands,
+    material: Res<GroundMaterial>,
+    settings: Res<TerrainSettings>,
+    chunks: Query<(Entity, &TerrainChunk), Added<TerrainChunk>>,
+) {
+    let ground = (settings.0.base_height / settings.0.chunk_size as f32).floor() as i32;
+    for (entity, chunk) in &chunks {
+        commands.entity(entity).insert(MeshMaterial3d(material.0.clone()));
+        if chunk.coord.y == ground {
+            commands.spawn(TerrainChunk::new(chunk.coord.down()).with_lod(chunk.lod));
+        }
+    }
+}
+
+fn update_overlay(
+    fear_state: Res<FearState>,
+    terrain: Res<DensityTerrain>,
+    mut overlay: Query<&mut Text, With<OverlayText>>,
+) {
+    for mut text in &mut overlay {
+        text.0 = format!(
+            "Fear: {:.2}\nBucket: {:?}\nDistortion: {:.2}\nChunks pending: {}",
+            fear_state.current_fear,
+            fear_state.current_bucket,
+            fear_state.get_distortion_intensity(),
+            terrain.pending()
+        );
+    }
+}
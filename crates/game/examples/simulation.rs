@@ -0,0 +1,222 @@
+//! Sensor simulation: the full visual pipeline without a camera or models
+//!
+//! Replays a scripted two-minute fear arc (calm → rising → panic → recovery)
+//! through `MockFearSensor`. The ground material, spires and camera react to
+//! the fear bucket, a debug overlay shows the live fear state and captions
+//! describe what should be visible in each phase.
+//!
+//! ```bash
+//! cargo run -p spectremesh --example simulation
+//! # Play a custom arc instead of the embedded one
+//! cargo run -p spectremesh --example simulation -- my_arc.toml
+//! ```
+
+use bevy::prelude::*;
+use spectremesh::{
+    modulation::FearModulation,
+    resources::{FearState, SensorStatus},
+    simulation::{ActiveSimulation, SimulationCaption, SimulationState},
+    SimulationPlugin, SimulationScript, SpectreMeshPlugin,
+};
+
+/// Ground material tinted by the distortion intensity
+#[derive(Resource)]
+struct GroundMaterial(Handle<StandardMaterial>);
+
+/// Spires that grow with fear
+#[derive(Component)]
+struct Spire {
+    base_height: f32,
+}
+
+/// Camera resting position, offset by fear-driven shake
+#[derive(Component)]
+struct ShakeCamera {
+    rest: Vec3,
+}
+
+#[derive(Component)]
+struct DebugOverlayText;
+
+#[derive(Component)]
+struct CaptionText;
+
+fn main() {
+    let script = match std::env::args().nth(1) {
+        Some(path) => SimulationScript::load(&path).unwrap_or_else(|e| {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }),
+        None => SimulationScript::builtin(),
+    };
+
+    App::new()
+        .add_plugins(DefaultPlugins.set(WindowPlugin {
+            primary_window: Some(Window {
+                title: "SpectreMesh - Sensor Simulation".to_string(),
+                ..default()
+            }),
+            ..default()
+        }))
+        .add_plugins((SpectreMeshPlugin, SimulationPlugin::new(script)))
+        .insert_resource(ClearColor(Color::srgb(0.1, 0.1, 0.15)))
+        .add_systems(Startup, (setup_scene, setup_overlay))
+        .add_systems(Update, (
+            react_to_fear,
+            shake_camera,
+            update_debug_overlay,
+            update_caption,
+        ))
+        .run();
+}
+
+fn setup_scene(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    let ground = materials.add(StandardMaterial {
+        base_color: Color::srgb(0.2, 0.25, 0.3),
+        perceptual_roughness: 0.9,
+        ..default()
+    });
+    commands.spawn((
+        Mesh3d(meshes.add(Plane3d::default().mesh().size(40.0, 40.0))),
+        MeshMaterial3d(ground.clone()),
+    ));
+    commands.insert_resource(GroundMaterial(ground));
+
+    let spire_mesh = meshes.add(Cuboid::new(1.0, 1.0, 1.0));
+    let spire_material = materials.add(StandardMaterial {
+        base_color: Color::srgb(0.12, 0.12, 0.16),
+        ..default()
+    });
+    for i in 0..12 {
+        let angle = i as f32 / 12.0 * std::f32::consts::TAU;
+        let base_height = 1.0 + (i % 3) as f32 * 0.5;
+        commands.spawn((
+            Mesh3d(spire_mesh.clone()),
+            MeshMaterial3d(spire_material.clone()),
+            Transform::from_xyz(angle.cos() * 10.0, base_height / 2.0, angle.sin() * 10.0)
+                .with_scale(Vec3::new(0.8, base_height, 0.8)),
+            Spire { base_height },
+        ));
+    }
+
+    commands.spawn((
+        DirectionalLight {
+            illuminance: 8000.0,
+            shadows_enabled: true,
+            ..default()
+        },
+        Transform::from_xyz(4.0, 10.0, 6.0).looking_at(Vec3::ZERO, Vec3::Y),
+    ));
+
+    let rest = Vec3::new(0.0, 12.0, 22.0);
+    commands.spawn((
+        Camera3d::default(),
+        Transform::from_translation(rest).looking_at(Vec3::ZERO, Vec3::Y),
+        ShakeCamera { rest },
+    ));
+}
+
+fn setup_overlay(mut commands: Commands) {
+    commands.spawn((
+        Text::new(""),
+        TextFont { font_size: 16.0, ..default() },
+        TextColor(Color::srgb(0.8, 0.9, 0.8)),
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(12.0),
+            left: Val::Px(12.0),
+            ..default()
+        },
+        DebugOverlayText,
+    ));
+
+    commands.spawn((
+        Text::new(""),
+        TextFont { font_size: 22.0, ..default() },
+        TextColor(Color::WHITE),
+        Node {
+            position_type: PositionType::Absolute,
+            bottom: Val::Px(24.0),
+            left: Val::Px(24.0),
+            right: Val::Px(24.0),
+            ..default()
+        },
+        CaptionText,
+    ));
+}
+
+/// Tint the ground and grow the spires with the distortion intensity
+fn react_to_fear(
+    fear_state: Res<FearState>,
+    ground: Res<GroundMaterial>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut spires: Query<(&Spire, &mut Transform)>,
+) {
+    let intensity = fear_state.get_distortion_intensity();
+
+    if let Some(material) = materials.get_mut(&ground.0) {
+        material.base_color = Color::srgb(0.2 + 0.5 * intensity, 0.25 - 0.15 * intensity, 0.3 - 0.2 * intensity);
+        material.emissive = LinearRgba::rgb(1.5 * intensity * intensity, 0.0, 0.0);
+    }
+
+    for (spire, mut transform) in &mut spires {
+        let height = spire.base_height * (1.0 + 3.0 * intensity);
+        transform.scale.y = height;
+        transform.translation.y = height / 2.0;
+    }
+}
+
+/// Shake the camera with the fear level in the High bucket, kicked by startle transients
+fn shake_camera(
+    time: Res<Time>,
+    fear_state: Res<FearState>,
+    modulation: Res<FearModulation>,
+    mut cameras: Query<(&ShakeCamera, &mut Transform)>,
+) {
+    let sustained = ((fear_state.current_fear - 0.66) / 0.34).clamp(0.0, 1.0) * 0.4;
+    let amplitude = sustained + modulation.transient() * 0.8;
+    let t = time.elapsed_secs();
+    for (camera, mut transform) in &mut cameras {
+        let offset = Vec3::new((t * 37.0).sin(), (t * 29.0).cos(), 0.0) * amplitude;
+        *transform = Transform::from_translation(camera.rest + offset).looking_at(Vec3::ZERO, Vec3::Y);
+    }
+}
+
+fn update_debug_overlay(
+    fear_state: Res<FearState>,
+    status: Res<SensorStatus>,
+    simulation: Res<ActiveSimulation>,
+    state: Res<SimulationState>,
+    mut overlay: Query<&mut Text, With<DebugOverlayText>>,
+) {
+    let phase = &simulation.0.phases[state.phase];
+    for mut text in &mut overlay {
+        text.0 = format!(
+            "Phase: {} ({:.0}s / {:.0}s)\nSensor: {:?}\nFear: {:.3} (scripted {:.3})\nStartle: {:.2} (peak {:.2})\nBucket: {:?}\nDistortion: {:.2}\nCalibrated: {}",
+            phase.name,
+            state.elapsed,
+            simulation.0.total_duration(),
+            *status,
+            fear_state.current_fear,
+            simulation.0.fear_at(state.elapsed),
+            fear_state.current_startle,
+            fear_state.peak_startle(),
+            fear_state.current_bucket,
+            fear_state.get_distortion_intensity(),
+            fear_state.calibrated,
+        );
+    }
+}
+
+fn update_caption(caption: Res<SimulationCaption>, mut texts: Query<&mut Text, With<CaptionText>>) {
+    if !caption.is_changed() {
+        return;
+    }
+    for mut text in &mut texts {
+        text.0 = caption.text.clone();
+    }
+}
@@ -0,0 +1,481 @@
+//! Fear-reactive fog and ambient lighting
+//!
+//! The art direction pairs terrain with the air around it: dense, cold fog
+//! at high fear and warm, clear air when calm. [`FearAtmosphere`] maps the
+//! fear level through an [`AtmosphereConfig`] onto the fog of every 3D
+//! camera, the [`AmbientLight`] and any [`DirectionalLight`]. Each parameter
+//! has its own calm and fearful values, response curve and slew rate, so
+//! fog can roll in slowly while the light cools quickly. With a flicker term
+//! configured, the startle transient makes the lights stutter.
+//!
+//! Like the fear band controller, the atmosphere only follows usable fear:
+//! uncalibrated, unavailable or low-confidence readings hold the last
+//! usable level. Brightness parameters scale the scene's own values, and
+//! disabling the atmosphere puts every value it touched back as it was,
+//! removing fog it added to cameras that had none.
+
+use bevy::prelude::*;
+use std::collections::HashMap;
+use crate::{
+    modulation::{update_fear_modulation, FearModulation, Slew, SlewMode},
+    resources::FearState,
+};
+
+/// Shape of a parameter's response to fear
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum AtmosphereCurve {
+    /// Proportional to fear
+    #[default]
+    Linear,
+    /// Slow at both ends, fastest through medium fear
+    SmoothStep,
+    /// Holds back until fear is high
+    EaseIn,
+    /// Responds early, then levels off
+    EaseOut,
+}
+
+impl AtmosphereCurve {
+    /// Map fear [0.0, 1.0] onto the response [0.0, 1.0]
+    pub fn apply(self, fear: f32) -> f32 {
+        let x = fear.clamp(0.0, 1.0);
+        match self {
+            Self::Linear => x,
+            Self::SmoothStep => x * x * (3.0 - 2.0 * x),
+            Self::EaseIn => x * x,
+            Self::EaseOut => 1.0 - (1.0 - x) * (1.0 - x),
+        }
+    }
+}
+
+/// One atmosphere parameter: its range over fear, curve and slew rate
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AtmosphereParam {
+    /// Value with no fear
+    pub calm: f32,
+    /// Value at full fear; may be below `calm`
+    pub fearful: f32,
+    pub curve: AtmosphereCurve,
+    /// Most the value moves per second, in its own units
+    pub slew_rate: f32,
+}
+
+impl AtmosphereParam {
+    /// Linear response moving across the whole range in one second
+    pub fn new(calm: f32, fearful: f32) -> Self {
+        Self {
+            calm,
+            fearful,
+            curve: AtmosphereCurve::Linear,
+            slew_rate: (fearful - calm).abs(),
+        }
+    }
+
+    /// Set the response curve
+    pub fn with_curve(mut self, curve: AtmosphereCurve) -> Self {
+        self.curve = curve;
+        self
+    }
+
+    /// Take `seconds` to move across the whole range
+    pub fn with_transition(mut self, seconds: f32) -> Self {
+        self.slew_rate = (self.fearful - self.calm).abs() / seconds.max(f32::EPSILON);
+        self
+    }
+
+    /// Value the parameter heads toward at `fear`
+    pub fn target(&self, fear: f32) -> f32 {
+        self.calm + (self.fearful - self.calm) * self.curve.apply(fear)
+    }
+}
+
+/// Light stutter driven by the startle transient
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AtmosphereFlicker {
+    /// Largest share of brightness a full startle takes away [0.0, 1.0]
+    pub depth: f32,
+    /// Base flicker frequency in Hz
+    pub frequency: f32,
+}
+
+/// Fear atmosphere settings
+#[derive(Debug, Clone, PartialEq)]
+pub struct AtmosphereConfig {
+    /// Exponential fog density, per world unit
+    pub fog_density: AtmosphereParam,
+    /// Fog color temperature in kelvin
+    pub fog_temperature: AtmosphereParam,
+    /// Multiplier of the scene's ambient brightness
+    pub ambient_scale: AtmosphereParam,
+    /// Ambient color temperature in kelvin
+    pub ambient_temperature: AtmosphereParam,
+    /// Multiplier of each directional light's illuminance
+    pub sun_scale: AtmosphereParam,
+    /// Directional light color temperature in kelvin
+    pub sun_temperature: AtmosphereParam,
+    /// Startle flicker, none when `None`
+    pub flicker: Option<AtmosphereFlicker>,
+    /// Share of fear the atmosphere follows [0.0, 1.0]
+    pub gain: f32,
+    /// Minimum model confidence for a fear reading to count
+    pub min_confidence: f32,
+}
+
+impl Default for AtmosphereConfig {
+    fn default() -> Self {
+        Self::subtle()
+    }
+}
+
+impl AtmosphereConfig {
+    /// Light haze and a gentle cooling, changing over several seconds
+    pub fn subtle() -> Self {
+        Self {
+            fog_density: AtmosphereParam::new(0.002, 0.015)
+                .with_curve(AtmosphereCurve::SmoothStep)
+                .with_transition(6.0),
+            fog_temperature: AtmosphereParam::new(5500.0, 7500.0).with_transition(6.0),
+            ambient_scale: AtmosphereParam::new(1.0, 0.75).with_transition(4.0),
+            ambient_temperature: AtmosphereParam::new(4500.0, 7000.0).with_transition(4.0),
+            sun_scale: AtmosphereParam::new(1.0, 0.8).with_transition(4.0),
+            sun_temperature: AtmosphereParam::new(5000.0, 6500.0).with_transition(4.0),
+            flicker: None,
+            gain: 1.0,
+            min_confidence: 0.5,
+        }
+    }
+
+    /// Fog walls and a cold, dim, flickering scene at high fear
+    pub fn theatrical() -> Self {
+        Self {
+            fog_density: AtmosphereParam::new(0.001, 0.06)
+                .with_curve(AtmosphereCurve::EaseIn)
+                .with_transition(3.0),
+            fog_temperature: AtmosphereParam::new(4000.0, 10000.0).with_transition(2.0),
+            ambient_scale: AtmosphereParam::new(1.0, 0.35)
+                .with_curve(AtmosphereCurve::EaseOut)
+                .with_transition(1.5),
+            ambient_temperature: AtmosphereParam::new(3200.0, 11000.0).with_transition(1.5),
+            sun_scale: AtmosphereParam::new(1.0, 0.25)
+                .with_curve(AtmosphereCurve::EaseOut)
+                .with_transition(1.5),
+            sun_temperature: AtmosphereParam::new(3500.0, 9000.0).with_transition(1.5),
+            flicker: Some(AtmosphereFlicker { depth: 0.6, frequency: 9.0 }),
+            gain: 1.0,
+            min_confidence: 0.5,
+        }
+    }
+
+    /// Set the share of fear followed
+    pub fn with_gain(mut self, gain: f32) -> Self {
+        self.gain = gain.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Set or remove the startle flicker
+    pub fn with_flicker(mut self, flicker: Option<AtmosphereFlicker>) -> Self {
+        self.flicker = flicker;
+        self
+    }
+
+    /// Set the minimum confidence
+    pub fn with_min_confidence(mut self, confidence: f32) -> Self {
+        self.min_confidence = confidence.clamp(0.0, 1.0);
+        self
+    }
+}
+
+/// Atmosphere values in effect
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AtmosphereLevels {
+    pub fog_density: f32,
+    pub fog_temperature: f32,
+    pub ambient_scale: f32,
+    pub ambient_temperature: f32,
+    pub sun_scale: f32,
+    pub sun_temperature: f32,
+    /// Brightness multiplier from the startle flicker, 1.0 without one
+    pub flicker: f32,
+}
+
+/// Slewed values of every parameter
+#[derive(Debug, Clone)]
+struct ParamSlews([Slew; 6]);
+
+impl ParamSlews {
+    fn new(config: &AtmosphereConfig) -> Self {
+        let mut slews = Self(params(config).map(|param| Slew::linear(param.slew_rate)));
+        slews.reset(config);
+        slews
+    }
+
+    /// Jump every value back to calm
+    fn reset(&mut self, config: &AtmosphereConfig) {
+        for (slew, param) in self.0.iter_mut().zip(params(config)) {
+            slew.reset(param.calm);
+        }
+    }
+
+    fn step(&mut self, config: &AtmosphereConfig, fear: f32, dt: f32) {
+        for (slew, param) in self.0.iter_mut().zip(params(config)) {
+            slew.step(param.target(fear), dt);
+        }
+    }
+}
+
+fn params(config: &AtmosphereConfig) -> [AtmosphereParam; 6] {
+    [
+        config.fog_density,
+        config.fog_temperature,
+        config.ambient_scale,
+        config.ambient_temperature,
+        config.sun_scale,
+        config.sun_temperature,
+    ]
+}
+
+/// Scene values from before the atmosphere took over
+#[derive(Debug, Clone)]
+struct SavedScene {
+    ambient: AmbientLight,
+    /// Each camera's fog, `None` where the atmosphere added it
+    fogs: HashMap<Entity, Option<DistanceFog>>,
+    /// Each directional light's color and illuminance
+    suns: HashMap<Entity, (Color, f32)>,
+}
+
+/// Fear-driven fog and lighting state
+#[derive(Resource, Debug, Clone)]
+pub struct FearAtmosphere {
+    config: AtmosphereConfig,
+    enabled: bool,
+    /// Last usable fear level, after the gain
+    fear: f32,
+    slews: ParamSlews,
+    flicker: f32,
+    /// Seconds of flicker animation
+    flicker_time: f32,
+    saved: Option<SavedScene>,
+}
+
+impl Default for FearAtmosphere {
+    fn default() -> Self {
+        Self::new(AtmosphereConfig::default())
+    }
+}
+
+impl FearAtmosphere {
+    /// Enabled atmosphere starting calm
+    pub fn new(config: AtmosphereConfig) -> Self {
+        Self {
+            slews: ParamSlews::new(&config),
+            config,
+            enabled: true,
+            fear: 0.0,
+            flicker: 1.0,
+            flicker_time: 0.0,
+            saved: None,
+        }
+    }
+
+    pub fn config(&self) -> &AtmosphereConfig {
+        &self.config
+    }
+
+    /// Switch to `config`, keeping the current values and slewing from them
+    pub fn set_config(&mut self, config: AtmosphereConfig) {
+        for (slew, param) in self.slews.0.iter_mut().zip(params(&config)) {
+            slew.mode = SlewMode::Linear { rate: param.slew_rate };
+        }
+        self.config = config;
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Enable or disable; disabling restores the scene on the next update
+    /// and re-enabling starts again from calm
+    pub fn set_enabled(&mut self, enabled: bool) {
+        if enabled && !self.enabled {
+            self.fear = 0.0;
+            self.slews.reset(&self.config);
+        }
+        self.enabled = enabled;
+    }
+
+    /// Values in effect
+    pub fn levels(&self) -> AtmosphereLevels {
+        let [fog_density, fog_temperature, ambient_scale, ambient_temperature, sun_scale, sun_temperature] =
+            self.slews.0.each_ref().map(Slew::value);
+        AtmosphereLevels {
+            fog_density,
+            fog_temperature,
+            ambient_scale,
+            ambient_temperature,
+            sun_scale,
+            sun_temperature,
+            flicker: self.flicker,
+        }
+    }
+
+    /// Advance by `dt` seconds toward `fear`, or hold when it is `None`
+    pub fn step(&mut self, fear: Option<f32>, startle: f32, dt: f32) {
+        if let Some(fear) = fear {
+            self.fear = fear.clamp(0.0, 1.0) * self.config.gain;
+        }
+        self.slews.step(&self.config, self.fear, dt);
+
+        self.flicker_time += dt;
+        self.flicker = match self.config.flicker {
+            Some(flicker) => {
+                1.0 - flicker.depth.clamp(0.0, 1.0) * startle.clamp(0.0, 1.0) * flicker_noise(flicker.frequency, self.flicker_time)
+            }
+            None => 1.0,
+        };
+    }
+}
+
+/// Irregular flicker in [0.0, 1.0] from two detuned sines
+fn flicker_noise(frequency: f32, time: f32) -> f32 {
+    let phase = std::f32::consts::TAU * frequency * time;
+    (0.5 + 0.3 * phase.sin() + 0.2 * (2.3 * phase + 1.7).sin()).clamp(0.0, 1.0)
+}
+
+/// Color of a black body at `kelvin` (1000 K to 40000 K)
+pub fn color_temperature(kelvin: f32) -> Color {
+    let t = kelvin.clamp(1000.0, 40000.0) / 100.0;
+    let red = if t <= 66.0 { 255.0 } else { 329.69873 * (t - 60.0).powf(-0.13320476) };
+    let green = if t <= 66.0 {
+        99.4708 * t.ln() - 161.11957
+    } else {
+        288.12216 * (t - 60.0).powf(-0.075514846)
+    };
+    let blue = if t >= 66.0 {
+        255.0
+    } else if t <= 19.0 {
+        0.0
+    } else {
+        138.51773 * (t - 10.0).ln() - 305.0448
+    };
+    Color::srgb(
+        red.clamp(0.0, 255.0) / 255.0,
+        green.clamp(0.0, 255.0) / 255.0,
+        blue.clamp(0.0, 255.0) / 255.0,
+    )
+}
+
+/// Fear level the atmosphere may follow, if it can be trusted
+fn usable_fear(fear_state: &FearState, min_confidence: f32) -> Option<f32> {
+    let usable = fear_state.calibrated
+        && fear_state.fear_available()
+        && fear_state.current_confidence >= min_confidence;
+    usable.then_some(fear_state.current_fear)
+}
+
+/// Drive fog and lights from fear, or restore the scene once disabled
+#[allow(clippy::too_many_arguments)]
+pub fn update_fear_atmosphere(
+    time: Res<Time>,
+    fear_state: Res<FearState>,
+    modulation: Res<FearModulation>,
+    mut atmosphere: ResMut<FearAtmosphere>,
+    mut ambient: ResMut<AmbientLight>,
+    mut cameras: Query<(Entity, Option<&mut DistanceFog>), With<Camera3d>>,
+    mut suns: Query<(Entity, &mut DirectionalLight)>,
+    mut commands: Commands,
+) {
+    if !atmosphere.enabled {
+        if let Some(saved) = atmosphere.saved.take() {
+            restore_scene(saved, &mut ambient, &mut cameras, &mut suns, &mut commands);
+        }
+        return;
+    }
+
+    let fear = usable_fear(&fear_state, atmosphere.config.min_confidence);
+    atmosphere.step(fear, modulation.transient(), time.delta_secs());
+    let levels = atmosphere.levels();
+
+    let saved = atmosphere.saved.get_or_insert_with(|| SavedScene {
+        ambient: ambient.clone(),
+        fogs: HashMap::new(),
+        suns: HashMap::new(),
+    });
+
+    ambient.color = color_temperature(levels.ambient_temperature);
+    ambient.brightness = saved.ambient.brightness * levels.ambient_scale * levels.flicker;
+
+    let fog_color = color_temperature(levels.fog_temperature);
+    let falloff = FogFalloff::Exponential { density: levels.fog_density };
+    for (entity, fog) in &mut cameras {
+        match fog {
+            Some(mut fog) => {
+                saved.fogs.entry(entity).or_insert_with(|| Some(fog.clone()));
+                fog.color = fog_color;
+                fog.falloff = falloff.clone();
+            }
+            None => {
+                saved.fogs.insert(entity, None);
+                commands.entity(entity).insert(DistanceFog {
+                    color: fog_color,
+                    falloff: falloff.clone(),
+                    ..default()
+                });
+            }
+        }
+    }
+
+    let sun_color = color_temperature(levels.sun_temperature);
+    for (entity, mut sun) in &mut suns {
+        let (_, illuminance) = *saved.suns.entry(entity).or_insert((sun.color, sun.illuminance));
+        sun.color = sun_color;
+        sun.illuminance = illuminance * levels.sun_scale * levels.flicker;
+    }
+}
+
+/// Put back every value the atmosphere changed
+fn restore_scene(
+    saved: SavedScene,
+    ambient: &mut AmbientLight,
+    cameras: &mut Query<(Entity, Option<&mut DistanceFog>), With<Camera3d>>,
+    suns: &mut Query<(Entity, &mut DirectionalLight)>,
+    commands: &mut Commands,
+) {
+    *ambient = saved.ambient;
+    for (entity, fog) in cameras.iter_mut() {
+        match (saved.fogs.get(&entity), fog) {
+            (Some(Some(original)), Some(mut fog)) => *fog = original.clone(),
+            (Some(None), Some(_)) => {
+                commands.entity(entity).remove::<DistanceFog>();
+            }
+            _ => {}
+        }
+    }
+    for (entity, mut sun) in suns.iter_mut() {
+        if let Some(&(color, illuminance)) = saved.suns.get(&entity) {
+            sun.color = color;
+            sun.illuminance = illuminance;
+        }
+    }
+}
+
+/// Plugin driving fog and lighting from fear; add after `SpectreMeshPlugin`
+#[derive(Default)]
+pub struct FearAtmospherePlugin {
+    pub config: AtmosphereConfig,
+}
+
+impl FearAtmospherePlugin {
+    pub fn new(config: AtmosphereConfig) -> Self {
+        Self { config }
+    }
+}
+
+impl Plugin for FearAtmospherePlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .init_resource::<AmbientLight>()
+            .insert_resource(FearAtmosphere::new(self.config.clone()))
+            .add_systems(Update, update_fear_atmosphere.after(update_fear_modulation));
+    }
+}
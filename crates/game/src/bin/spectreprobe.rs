@@ -6,22 +6,38 @@
 //! Now uses modern YuNet CNN-based face detection instead of legacy Haar cascades.
 
 use spectremesh_core::{FearConfig, CameraError};
-use spectre_sensor::compat::{FearSensor, MockFearSensor, YuNetFearSensor};
+use spectre_sensor::compat::{FearSensor, MockFearSensor, ReplayFearSensor, YuNetFearSensor};
+use spectre_sensor::logging::install_console;
+use std::path::{Path, PathBuf};
 use std::time::Duration;
 use tokio::time::timeout;
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
-    // Initialize logging
-    tracing_subscriber::fmt::init();
-
-    println!("SpectreMesh Camera Probe v0.1.0");
-    println!("Testing camera permissions and fear detection capabilities...\n");
-
     // Parse command line arguments
     let args: Vec<String> = std::env::args().collect();
     let use_mock = args.contains(&"--mock".to_string());
     let test_both = args.contains(&"--test-both".to_string());
+    let flag_value = |flag: &str| args.iter().position(|arg| arg == flag).and_then(|i| args.get(i + 1));
+    let record = flag_value("--record").map(PathBuf::from);
+
+    // Initialize logging (SPECTRE_LOG and SPECTRE_LOG_FORMAT, or --log-format)
+    let mut logging = spectre_sensor::SensorConfig::from_env().logging;
+    if let Some(format) = flag_value("--log-format") {
+        logging.format = format.parse()?;
+    }
+    install_console(&logging)?;
+
+    println!("SpectreMesh Camera Probe v0.1.0");
+    println!("Testing camera permissions and fear detection capabilities...\n");
+
+    if let Some(path) = flag_value("--replay") {
+        let speed = match flag_value("--speed") {
+            Some(speed) => speed.parse().map_err(|_| format!("Invalid --speed: {}", speed))?,
+            None => 1.0,
+        };
+        return replay_session(Path::new(path), speed, args.contains(&"--loop".to_string())).await;
+    }
 
     if use_mock {
         println!("🎭 Running in MOCK mode (--mock flag detected)");
@@ -46,11 +62,11 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     if test_both {
         println!("  Testing both Mock and YuNet implementations...");
         test_fear_detection_mock().await?;
-        test_fear_detection_yunet().await?;
+        test_fear_detection_yunet(record.as_deref()).await?;
     } else if use_mock {
         test_fear_detection_mock().await?;
     } else {
-        test_fear_detection_yunet().await?;
+        test_fear_detection_yunet(record.as_deref()).await?;
     }
 
     // Test 3: Platform-specific configuration
@@ -235,7 +251,7 @@ async fn test_fear_detection_mock() -> Result<(), Box<dyn std::error::Error>> {
         match timeout(Duration::from_millis(100), receiver.recv()).await {
             Ok(Ok(score)) => {
                 println!("      Frame {}: Fear={:.3}, Confidence={:.3}, Calibrated={}",
-                    i + 1, score.value, score.confidence, score.calibrated);
+                    i + 1, score.provisional_value(), score.confidence, score.calibrated);
             }
             Ok(Err(_)) => {
                 println!("      ❌ Channel closed unexpectedly");
@@ -256,15 +272,25 @@ async fn test_fear_detection_mock() -> Result<(), Box<dyn std::error::Error>> {
     Ok(())
 }
 
-async fn test_fear_detection_yunet() -> Result<(), Box<dyn std::error::Error>> {
+async fn test_fear_detection_yunet(record: Option<&Path>) -> Result<(), Box<dyn std::error::Error>> {
     println!("  🎯 Testing YuNet Fear Detection:");
     let mut sensor = YuNetFearSensor::new();
+    if let Some(path) = record {
+        println!("    Recording frames to {}", path.display());
+        sensor = sensor.with_record_path(path);
+    }
     let config = FearConfig::default();
+    println!("    Embedded face model: {}", spectre_sensor::YUNET_MODEL_INFO);
 
     // Initialize sensor
     print!("    Initializing YuNet sensor... ");
     match sensor.initialize(&config).await {
-        Ok(_) => println!("✅"),
+        Ok(_) => {
+            println!("✅");
+            for model in sensor.model_info() {
+                println!("      Model: {}", model);
+            }
+        }
         Err(e) => {
             println!("❌");
             println!("      Error: {}", e);
@@ -290,7 +316,7 @@ async fn test_fear_detection_yunet() -> Result<(), Box<dyn std::error::Error>> {
                     Ok(Ok(score)) => {
                         frame_count += 1;
                         println!("      Frame {}: Fear={:.3}, Confidence={:.3}, Calibrated={}",
-                            frame_count, score.value, score.confidence, score.calibrated);
+                            frame_count, score.provisional_value(), score.confidence, score.calibrated);
                     }
                     Ok(Err(_)) => {
                         println!("      ❌ Channel closed unexpectedly");
@@ -324,44 +350,62 @@ async fn test_fear_detection_yunet() -> Result<(), Box<dyn std::error::Error>> {
     Ok(())
 }
 
+/// Play a recorded session back through `ReplayFearSensor` and print its scores
+async fn replay_session(path: &Path, speed: f32, looping: bool) -> Result<(), Box<dyn std::error::Error>> {
+    println!("🎞️  Replaying {} at {}x{}", path.display(), speed, if looping { ", looping" } else { "" });
+    let mut sensor = ReplayFearSensor::open(path)?.with_speed(speed).with_looping(looping);
+    let header = &sensor.recording().header;
+    println!("  Recorded by spectre_sensor {} at {} FPS, {} frames over {:.1}s",
+        header.sensor_version, header.config.target_fps, sensor.recording().frames.len(),
+        sensor.recording().duration().as_secs_f32());
+
+    sensor.initialize(&FearConfig::default()).await?;
+    let receiver = sensor.start().await?;
+    while let Ok(score) = receiver.recv().await {
+        println!("    Frame {}: Fear={:.3}, Confidence={:.3}, Calibrated={}",
+            score.sequence, score.provisional_value(), score.confidence, score.calibrated);
+    }
+    sensor.stop().await?;
+
+    println!("\n✅ Replay finished");
+    Ok(())
+}
+
 async fn test_calibration_system_mock() -> Result<(), Box<dyn std::error::Error>> {
     // Create sensor with consistent fear values for calibration
-    let mut sensor = MockFearSensor::new(vec![0.2; 20]); // Consistent values
+    let mut sensor = MockFearSensor::constant_pattern(0.2);
     let config = FearConfig {
         calibration_duration: Duration::from_millis(500), // Short calibration for testing
         camera: spectremesh_core::CameraConfig {
-            fps: 20, // 20 FPS = 10 samples for 0.5 seconds
+            fps: 20,
             ..Default::default()
         },
         ..FearConfig::default()
     };
+    let expected_samples = config.calibration_samples();
 
     // Initialize and start sensor
     sensor.initialize(&config).await?;
     let receiver = sensor.start().await?;
 
     println!("  🎭 Testing Mock Calibration System:");
+    println!("    Expecting calibration after {} samples ({:?} at {} FPS)",
+        expected_samples, config.calibration_duration, config.camera.fps);
     println!("    Monitoring calibration progress:");
-    
-    let mut calibrated = false;
-    for i in 0..30 {
-        // Check calibration status
-        let progress = sensor.calibration_progress();
-        let is_calibrated = sensor.is_calibrated();
-        
-        println!("      Step {}: Progress={:.1}%, Calibrated={}",
-            i + 1, progress * 100.0, is_calibrated);
-
-        if is_calibrated && !calibrated {
-            println!("    ✅ Calibration completed!");
-            calibrated = true;
-            break;
-        }
 
-        // Receive a score to advance the calibration
+    // Scores are flagged calibrated from the sample that completes calibration
+    let mut samples = 0;
+    let mut calibrated = false;
+    while samples < expected_samples * 2 {
         match timeout(Duration::from_millis(100), receiver.recv()).await {
-            Ok(Ok(_)) => {
-                // Score received, continue
+            Ok(Ok(score)) => {
+                samples += 1;
+                println!("      Sample {}: Progress={:.1}%, Calibrated={}",
+                    samples, sensor.calibration_progress() * 100.0, score.calibrated);
+                if score.calibrated {
+                    calibrated = true;
+                    break;
+                }
             }
             Ok(Err(_)) => {
                 println!("    ❌ Channel closed during calibration");
@@ -377,6 +421,10 @@ async fn test_calibration_system_mock() -> Result<(), Box<dyn std::error::Error>
     if !calibrated {
         return Err("Calibration did not complete in expected time".into());
     }
+    if samples != expected_samples {
+        return Err(format!("Calibration completed after {} samples, expected {}", samples, expected_samples).into());
+    }
+    println!("    ✅ Calibration completed!");
 
     // Test normalized fear values after calibration
     println!("  Testing normalized fear values:");
@@ -385,7 +433,7 @@ async fn test_calibration_system_mock() -> Result<(), Box<dyn std::error::Error>
             Ok(Ok(score)) => {
                 if score.calibrated {
                     println!("    Normalized fear: {:.3} (from raw logit: {:.3})", 
-                        score.value, score.extract_fear_logit());
+                        score.provisional_value(), score.extract_fear_logit());
                 }
             }
             _ => break,
@@ -450,7 +498,7 @@ async fn test_calibration_system_yunet() -> Result<(), Box<dyn std::error::Error
                             Ok(Ok(score)) => {
                                 if score.calibrated {
                                     println!("      Real fear: {:.3} (from raw logit: {:.3})",
-                                        score.value, score.extract_fear_logit());
+                                        score.provisional_value(), score.extract_fear_logit());
                                 }
                             }
                             _ => break,
@@ -516,7 +564,7 @@ mod tests {
 
     #[tokio::test]
     async fn test_spectreprobe_fear_detection_yunet() {
-        assert!(test_fear_detection_yunet().await.is_ok());
+        assert!(test_fear_detection_yunet(None).await.is_ok());
     }
 
     #[tokio::test]
@@ -1,4 +1,40 @@
-//! ECS Components for SpectreMesh (placeholder for M0)
+//! ECS Components for SpectreMesh
 
-// Placeholder for future Bevy components
-// Will be implemented in M0.5 (Shader-Warp) milestone
+use bevy::prelude::*;
+use spectremesh_terrain::ChunkCoord;
+
+/// Marks the entity (usually the player or camera) whose position scars nearby terrain chunks
+#[derive(Component, Debug, Default, Clone, Copy)]
+pub struct FearMemoryFocus;
+
+/// A volumetric terrain chunk; its mesh is built and rebuilt in the background by
+/// `update_terrain_system` and `collect_terrain_meshes_system` from the
+/// [`DensityTerrain`](crate::resources::DensityTerrain) resource
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+#[require(Transform, Visibility)]
+pub struct TerrainChunk {
+    pub coord: ChunkCoord,
+    /// Level of detail from the chunk's distance to the camera; 0 is full detail
+    /// and each level halves the samples along every axis
+    pub lod: u8,
+    /// The chunk's mesh is missing or out of date
+    pub dirty: bool,
+}
+
+impl TerrainChunk {
+    /// Full-detail chunk waiting for its first mesh
+    pub fn new(coord: ChunkCoord) -> Self {
+        Self { coord, lod: 0, dirty: true }
+    }
+
+    pub fn with_lod(mut self, lod: u8) -> Self {
+        self.lod = lod;
+        self
+    }
+
+    /// Level of detail for a chunk `distance` chunks from the camera,
+    /// dropping one level each time the distance doubles
+    pub fn lod_for_distance(distance: u32) -> u8 {
+        distance.max(1).ilog2() as u8
+    }
+}
@@ -0,0 +1,135 @@
+//! On-screen sensor debug overlay
+//!
+//! [`SensorOverlayPlugin`] draws the current fear, bucket, calibration and
+//! sensor rate in a corner of the screen, with a red "SIGNAL LOST" banner
+//! while [`SensorHealth`] reports no frames. It reads only [`FearState`],
+//! [`SensorHealth`] and the sensor diagnostics, so it works with any fear
+//! source. F3 toggles it; so does flipping [`SensorOverlay::visible`].
+
+use bevy::{diagnostic::DiagnosticsStore, prelude::*};
+use crate::{
+    diagnostics::{sensor_diagnostic, SensorDiagnosticsPlugin, SensorHealth, SENSOR_FPS, SENSOR_FRAMES_DROPPED},
+    resources::FearState,
+};
+
+/// Whether the sensor overlay is shown and the key that toggles it
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SensorOverlay {
+    pub visible: bool,
+    pub toggle_key: KeyCode,
+}
+
+impl Default for SensorOverlay {
+    /// Hidden unless built with the `debug-overlay` feature, toggled with F3
+    fn default() -> Self {
+        Self { visible: cfg!(feature = "debug-overlay"), toggle_key: KeyCode::F3 }
+    }
+}
+
+/// Root node of the overlay
+#[derive(Component)]
+struct SensorOverlayRoot;
+
+/// Text listing the sensor readings
+#[derive(Component)]
+struct SensorOverlayText;
+
+/// Banner shown while the signal is lost
+#[derive(Component)]
+struct SignalLostBanner;
+
+/// Plugin drawing the sensor debug overlay; adds [`SensorDiagnosticsPlugin`]
+/// when it is missing
+pub struct SensorOverlayPlugin;
+
+impl Plugin for SensorOverlayPlugin {
+    fn build(&self, app: &mut App) {
+        if !app.is_plugin_added::<SensorDiagnosticsPlugin>() {
+            app.add_plugins(SensorDiagnosticsPlugin);
+        }
+        app.init_resource::<SensorOverlay>()
+            .add_systems(Startup, spawn_sensor_overlay)
+            .add_systems(Update, (toggle_sensor_overlay, update_sensor_overlay).chain());
+    }
+}
+
+fn spawn_sensor_overlay(mut commands: Commands, overlay: Res<SensorOverlay>) {
+    let visibility = if overlay.visible { Visibility::Inherited } else { Visibility::Hidden };
+    commands
+        .spawn((
+            SensorOverlayRoot,
+            Node {
+                position_type: PositionType::Absolute,
+                top: Val::Px(8.0),
+                left: Val::Px(8.0),
+                flex_direction: FlexDirection::Column,
+                row_gap: Val::Px(4.0),
+                padding: UiRect::all(Val::Px(6.0)),
+                ..default()
+            },
+            BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.6)),
+            visibility,
+        ))
+        .with_children(|overlay| {
+            overlay.spawn((
+                SignalLostBanner,
+                Text::new("SIGNAL LOST"),
+                TextFont { font_size: 20.0, ..default() },
+                TextColor(Color::WHITE),
+                BackgroundColor(Color::srgb(0.8, 0.0, 0.0)),
+                Visibility::Hidden,
+            ));
+            overlay.spawn((
+                SensorOverlayText,
+                Text::new(""),
+                TextFont { font_size: 14.0, ..default() },
+                TextColor(Color::WHITE),
+            ));
+        });
+}
+
+/// Show or hide the overlay when its key is pressed
+fn toggle_sensor_overlay(keys: Option<Res<ButtonInput<KeyCode>>>, mut overlay: ResMut<SensorOverlay>) {
+    if keys.is_some_and(|keys| keys.just_pressed(overlay.toggle_key)) {
+        overlay.visible = !overlay.visible;
+    }
+}
+
+fn update_sensor_overlay(
+    overlay: Res<SensorOverlay>,
+    fear_state: Res<FearState>,
+    health: Res<SensorHealth>,
+    store: Res<DiagnosticsStore>,
+    mut root: Query<&mut Visibility, (With<SensorOverlayRoot>, Without<SignalLostBanner>)>,
+    mut banner: Query<&mut Visibility, (With<SignalLostBanner>, Without<SensorOverlayRoot>)>,
+    mut text: Query<&mut Text, With<SensorOverlayText>>,
+) {
+    for mut visibility in &mut root {
+        visibility.set_if_neq(if overlay.visible { Visibility::Inherited } else { Visibility::Hidden });
+    }
+    if !overlay.visible {
+        return;
+    }
+
+    for mut visibility in &mut banner {
+        visibility.set_if_neq(if health.signal_lost { Visibility::Inherited } else { Visibility::Hidden });
+    }
+
+    let fps = sensor_diagnostic(&store, &SENSOR_FPS).unwrap_or(0.0);
+    let dropped = sensor_diagnostic(&store, &SENSOR_FRAMES_DROPPED).unwrap_or(0.0);
+    let readings = format!(
+        "Fear:        {:.2} (smoothed {:.2})\nBucket:      {:?}\nCalibration: {:.0}%\nSensor:      {:.1} FPS, {} dropped\nLast frame:  {:.1}s ago",
+        fear_state.current_fear,
+        fear_state.smoothed_fear,
+        fear_state.current_bucket,
+        health.calibration_progress * 100.0,
+        fps,
+        dropped as u64,
+        health.frame_age.as_secs_f32(),
+    );
+    for mut text in &mut text {
+        if text.0 != readings {
+            text.0 = readings.clone();
+        }
+    }
+}
@@ -0,0 +1,132 @@
+//! Sensor health as Bevy diagnostics
+//!
+//! [`SensorDiagnosticsPlugin`] measures the fear level, the rate frames
+//! arrive at, the frames the sensor dropped and how long ago a frame was
+//! last applied. Everything is read from [`FearState`], so the numbers mean
+//! the same with the mock, ONNX and remote sensors. [`SensorHealth`] keeps
+//! the latest values and flags a lost signal for the debug overlay and
+//! anything else that wants to react to it.
+
+use bevy::{
+    diagnostic::{Diagnostic, DiagnosticPath, Diagnostics, DiagnosticsStore, RegisterDiagnostic},
+    prelude::*,
+};
+use crate::{events::CalibrationProgressEvent, resources::FearState, systems::update_fear_system};
+use std::time::Duration;
+
+/// Current fear level [0.0, 1.0]
+pub const SENSOR_FEAR: DiagnosticPath = DiagnosticPath::const_new("sensor/fear");
+/// Frames received per second
+pub const SENSOR_FPS: DiagnosticPath = DiagnosticPath::const_new("sensor/fps");
+/// Frames the sensor dropped since the game started
+pub const SENSOR_FRAMES_DROPPED: DiagnosticPath = DiagnosticPath::const_new("sensor/frames_dropped");
+/// Seconds since a frame was last applied to `FearState`
+pub const SENSOR_FRAME_AGE: DiagnosticPath = DiagnosticPath::const_new("sensor/frame_age");
+/// Calibration progress in percent
+pub const SENSOR_CALIBRATION: DiagnosticPath = DiagnosticPath::const_new("sensor/calibration");
+
+/// Default `SensorHealth::signal_lost_after`
+pub const DEFAULT_SIGNAL_LOST_AFTER: Duration = Duration::from_secs(1);
+
+/// Latest sensor health, updated every frame by [`SensorDiagnosticsPlugin`]
+#[derive(Resource, Debug, Clone, PartialEq)]
+pub struct SensorHealth {
+    /// The signal counts as lost once no frame has been applied for this long
+    pub signal_lost_after: Duration,
+    /// No frame has been applied for `signal_lost_after`
+    pub signal_lost: bool,
+    /// Time since a frame was last applied
+    pub frame_age: Duration,
+    /// Calibration progress [0.0, 1.0]; 1.0 once frames arrive calibrated
+    pub calibration_progress: f32,
+}
+
+impl Default for SensorHealth {
+    fn default() -> Self {
+        Self {
+            signal_lost_after: DEFAULT_SIGNAL_LOST_AFTER,
+            signal_lost: false,
+            frame_age: Duration::ZERO,
+            calibration_progress: 0.0,
+        }
+    }
+}
+
+impl SensorHealth {
+    /// Set how long without frames counts as a lost signal
+    pub fn with_signal_lost_after(mut self, after: Duration) -> Self {
+        self.signal_lost_after = after;
+        self
+    }
+}
+
+/// Plugin registering the sensor diagnostics and keeping [`SensorHealth`]
+/// up to date
+pub struct SensorDiagnosticsPlugin;
+
+impl Plugin for SensorDiagnosticsPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .init_resource::<FearState>()
+            .init_resource::<SensorHealth>()
+            .add_event::<CalibrationProgressEvent>()
+            .register_diagnostic(Diagnostic::new(SENSOR_FEAR))
+            .register_diagnostic(Diagnostic::new(SENSOR_FPS).with_suffix(" FPS"))
+            .register_diagnostic(Diagnostic::new(SENSOR_FRAMES_DROPPED))
+            .register_diagnostic(Diagnostic::new(SENSOR_FRAME_AGE).with_suffix("s"))
+            .register_diagnostic(Diagnostic::new(SENSOR_CALIBRATION).with_suffix("%"))
+            .add_systems(Update, update_sensor_diagnostics.after(update_fear_system));
+    }
+}
+
+/// Measure the sensor diagnostics from `FearState` and flag a lost signal
+fn update_sensor_diagnostics(
+    time: Res<Time<Real>>,
+    fear_state: Res<FearState>,
+    mut health: ResMut<SensorHealth>,
+    mut calibration: EventReader<CalibrationProgressEvent>,
+    mut diagnostics: Diagnostics,
+    mut frames_seen: Local<u64>,
+) {
+    // The first update has no time step; its frames count toward the next
+    let delta = time.delta_secs_f64();
+    if delta > 0.0 {
+        let received = fear_state.frames_received - *frames_seen;
+        *frames_seen = fear_state.frames_received;
+        diagnostics.add_measurement(&SENSOR_FPS, || received as f64 / delta);
+    }
+
+    if let Some(progress) = calibration.read().last() {
+        health.calibration_progress = if progress.completed { 1.0 } else { progress.progress.clamp(0.0, 1.0) };
+    }
+    if fear_state.calibrated {
+        health.calibration_progress = 1.0;
+    }
+
+    health.frame_age = fear_state.last_update.elapsed();
+    let signal_lost = health.frame_age > health.signal_lost_after;
+    if signal_lost != health.signal_lost {
+        if signal_lost {
+            tracing::warn!("Sensor signal lost: no frame for {:.1}s", health.frame_age.as_secs_f32());
+        } else {
+            tracing::info!("Sensor signal restored");
+        }
+        health.signal_lost = signal_lost;
+    }
+
+    diagnostics.add_measurement(&SENSOR_FEAR, || fear_state.current_fear as f64);
+    diagnostics.add_measurement(&SENSOR_FRAMES_DROPPED, || fear_state.frames_dropped as f64);
+    diagnostics.add_measurement(&SENSOR_FRAME_AGE, || health.frame_age.as_secs_f64());
+    diagnostics.add_measurement(&SENSOR_CALIBRATION, || health.calibration_progress as f64 * 100.0);
+}
+
+/// Latest value of a sensor diagnostic, averaged over its history for
+/// `SENSOR_FPS`, which swings between updates with and without a frame
+pub fn sensor_diagnostic(store: &DiagnosticsStore, path: &DiagnosticPath) -> Option<f64> {
+    let diagnostic = store.get(path)?;
+    if *path == SENSOR_FPS {
+        diagnostic.average()
+    } else {
+        diagnostic.value()
+    }
+}
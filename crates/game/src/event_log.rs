@@ -0,0 +1,81 @@
+//! Game event log for post-session analysis
+//!
+//! Events are timed in game time: `Time<Real>` elapsed since the first
+//! update, the same clock the remote source sends in clock sync pings. Each
+//! new [`ClockSync`] estimate is logged as well, so `session_diff` can map
+//! the log onto the sensor's recording of the same session.
+
+use bevy::prelude::*;
+use spectre_sensor::{clock_sync::ClockSyncEstimate, session_diff::GameLogEvent};
+use std::path::Path;
+use std::time::Duration;
+use crate::resources::ClockSync;
+
+/// Gameplay events and clock sync estimates, in the order they were recorded
+#[derive(Resource, Debug, Clone, Default)]
+pub struct GameEventLog {
+    events: Vec<GameLogEvent>,
+}
+
+impl GameEventLog {
+    /// Record a named event at the current game time
+    pub fn record(&mut self, time: &Time<Real>, label: impl Into<String>) {
+        self.record_at(time.elapsed(), label);
+    }
+
+    /// Record a named event at `game_time`
+    pub fn record_at(&mut self, game_time: Duration, label: impl Into<String>) {
+        self.events.push(GameLogEvent::Event {
+            game_time_us: game_time.as_micros() as u64,
+            label: label.into(),
+        });
+    }
+
+    /// Record the clock mapping in effect at `game_time`
+    pub fn record_clock_sync(&mut self, game_time: Duration, clock_sync: ClockSyncEstimate) {
+        self.events.push(GameLogEvent::ClockSync {
+            game_time_us: game_time.as_micros() as u64,
+            clock_sync,
+        });
+    }
+
+    pub fn events(&self) -> &[GameLogEvent] {
+        &self.events
+    }
+
+    /// Most recently logged clock sync estimate
+    pub fn latest_clock_sync(&self) -> Option<&ClockSyncEstimate> {
+        self.events.iter().rev().find_map(|event| match event {
+            GameLogEvent::ClockSync { clock_sync, .. } => Some(clock_sync),
+            GameLogEvent::Event { .. } => None,
+        })
+    }
+
+    /// One JSON event per line, readable with `GameLog::from_jsonl`
+    pub fn to_jsonl(&self) -> String {
+        self.events
+            .iter()
+            .filter_map(|event| serde_json::to_string(event).ok())
+            .map(|line| line + "\n")
+            .collect()
+    }
+
+    pub fn write(&self, path: &Path) -> std::io::Result<()> {
+        std::fs::write(path, self.to_jsonl())
+    }
+}
+
+/// Log every new clock sync estimate
+pub fn log_clock_sync(
+    clock_sync: Res<ClockSync>,
+    time: Option<Res<Time<Real>>>,
+    mut log: ResMut<GameEventLog>,
+) {
+    if !clock_sync.is_changed() {
+        return;
+    }
+    if let Some(estimate) = clock_sync.estimate {
+        let game_time = time.map_or(Duration::ZERO, |time| time.elapsed());
+        log.record_clock_sync(game_time, estimate);
+    }
+}
@@ -0,0 +1,94 @@
+//! Bevy events raised by the fear sensor integration
+
+use bevy::{ecs::system::SystemParam, prelude::*};
+use crate::resources::SensorCommand;
+use spectremesh_core::{types::FearBucket, Emotion, EmotionVector};
+use std::time::Duration;
+
+/// Calibration progress reported by the sensor
+#[derive(Event, Debug, Clone, PartialEq)]
+pub struct CalibrationProgressEvent {
+    /// Progress [0.0, 1.0]
+    pub progress: f32,
+    /// Whether calibration is complete
+    pub completed: bool,
+    /// Mean and standard deviation of the sensor's baseline, when reported
+    pub baseline: Option<(f32, f32)>,
+}
+
+/// Fault or informational notice reported by the sensor
+#[derive(Event, Debug, Clone, PartialEq)]
+pub struct SensorFaultEvent {
+    /// Human-readable message
+    pub message: String,
+    /// Error code for programmatic handling
+    pub error_code: String,
+    /// Whether the sensor recovers without intervention
+    pub recoverable: bool,
+}
+
+/// Outcome of a command queued through `SensorCommands`
+#[derive(Event, Debug, Clone, PartialEq)]
+pub struct SensorCommandResult {
+    /// The command that was executed
+    pub command: SensorCommand,
+    /// Whether the sensor accepted the command
+    pub success: bool,
+    /// Error message when the command failed
+    pub error: Option<String>,
+    /// Command output, e.g. the bundle path for `CaptureBugReport`
+    pub detail: Option<String>,
+}
+
+/// The dominant emotion of `FearState::current_emotions` changed, e.g. to
+/// surprise for a jump scare
+#[derive(Event, Debug, Clone, PartialEq)]
+pub struct DominantEmotionChanged {
+    pub previous: Emotion,
+    pub current: Emotion,
+    /// Emotion probabilities of the frame that made the change
+    pub emotions: EmotionVector,
+}
+
+/// `FearState::current_bucket` moved, after hysteresis and dwell
+#[derive(Event, Debug, Clone, PartialEq)]
+pub struct FearBucketChanged {
+    pub from: FearBucket,
+    pub to: FearBucket,
+    /// Fear score of the frame that made the change
+    pub score: f32,
+}
+
+/// The sensor became calibrated; raised again after a recalibration
+#[derive(Event, Debug, Clone, PartialEq)]
+pub struct CalibrationCompleted {
+    /// Mean of the sensor's baseline; `None` from sensors that do not report it
+    pub baseline_mean: Option<f32>,
+    /// Standard deviation of the sensor's baseline; `None` from sensors that
+    /// do not report it
+    pub baseline_std: Option<f32>,
+}
+
+/// No frame has been applied for `GameConfig::signal_timeout`
+#[derive(Event, Debug, Clone, PartialEq)]
+pub struct FearSignalLost {
+    /// Time since a frame was last applied
+    pub since_last_frame: Duration,
+}
+
+/// Frames are applied again after a [`FearSignalLost`]
+#[derive(Event, Debug, Clone, PartialEq)]
+pub struct FearSignalRecovered {
+    /// Time between the last frame before the loss and the first after it
+    pub gap: Duration,
+}
+
+/// Writers for the events `update_fear_system` raises
+#[derive(SystemParam)]
+pub struct FearEventWriters<'w> {
+    pub dominant_changes: EventWriter<'w, DominantEmotionChanged>,
+    pub bucket_changes: EventWriter<'w, FearBucketChanged>,
+    pub calibration_completed: EventWriter<'w, CalibrationCompleted>,
+    pub signal_lost: EventWriter<'w, FearSignalLost>,
+    pub signal_recovered: EventWriter<'w, FearSignalRecovered>,
+}
@@ -0,0 +1,378 @@
+//! Fear band controller for adaptive difficulty
+//!
+//! The design goal is to keep players mostly in the Medium fear band.
+//! [`FearBandController`] measures how long the fear state spends in each
+//! bucket over a rolling horizon, compares that with a target distribution
+//! and turns the difference into a signed intensity adjustment: positive
+//! means the game should ramp up, negative that it should ease off. A
+//! [`FearBandChanged`] event fires whenever the adjustment crosses into a
+//! different [`BandAction`].
+//!
+//! Uncalibrated, unavailable or low-confidence fear says nothing about the
+//! player, so those periods freeze the controller: the window stops rolling
+//! and the last adjustment is held until usable readings return.
+
+use bevy::prelude::*;
+use spectremesh_core::types::FearBucket;
+use std::collections::VecDeque;
+use std::time::Duration;
+use crate::{
+    resources::{FearState, GameMetrics},
+    systems::update_fear_system,
+};
+
+/// Share of time spent in each fear bucket, summing to 1
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BandDistribution {
+    pub low: f32,
+    pub medium: f32,
+    pub high: f32,
+}
+
+impl BandDistribution {
+    /// Distribution proportional to the given weights; all Medium if they sum to zero
+    pub fn new(low: f32, medium: f32, high: f32) -> Self {
+        let [low, medium, high] = [low, medium, high].map(|weight| weight.max(0.0));
+        let total = low + medium + high;
+        if total <= 0.0 {
+            return Self { low: 0.0, medium: 1.0, high: 0.0 };
+        }
+        Self {
+            low: low / total,
+            medium: medium / total,
+            high: high / total,
+        }
+    }
+
+    /// Share of `bucket`
+    pub fn share(&self, bucket: FearBucket) -> f32 {
+        match bucket {
+            FearBucket::Low => self.low,
+            FearBucket::Medium => self.medium,
+            FearBucket::High => self.high,
+        }
+    }
+}
+
+impl Default for BandDistribution {
+    /// 20% Low, 60% Medium, 20% High
+    fn default() -> Self {
+        Self { low: 0.2, medium: 0.6, high: 0.2 }
+    }
+}
+
+/// Time spent in each fear bucket
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct BucketDwell {
+    pub low: Duration,
+    pub medium: Duration,
+    pub high: Duration,
+}
+
+impl BucketDwell {
+    /// Time spent in `bucket`
+    pub fn get(&self, bucket: FearBucket) -> Duration {
+        match bucket {
+            FearBucket::Low => self.low,
+            FearBucket::Medium => self.medium,
+            FearBucket::High => self.high,
+        }
+    }
+
+    fn get_mut(&mut self, bucket: FearBucket) -> &mut Duration {
+        match bucket {
+            FearBucket::Low => &mut self.low,
+            FearBucket::Medium => &mut self.medium,
+            FearBucket::High => &mut self.high,
+        }
+    }
+
+    /// Add `dt` spent in `bucket`
+    pub fn add(&mut self, bucket: FearBucket, dt: Duration) {
+        *self.get_mut(bucket) += dt;
+    }
+
+    pub fn total(&self) -> Duration {
+        self.low + self.medium + self.high
+    }
+
+    /// Share of the total spent in each bucket; `None` before any time was recorded
+    pub fn distribution(&self) -> Option<BandDistribution> {
+        if self.total().is_zero() {
+            return None;
+        }
+        Some(BandDistribution::new(
+            self.low.as_secs_f32(),
+            self.medium.as_secs_f32(),
+            self.high.as_secs_f32(),
+        ))
+    }
+}
+
+/// What the game should do about its intensity
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum BandAction {
+    /// Too much High fear: back off
+    EaseOff,
+    /// Close enough to the target distribution
+    #[default]
+    Hold,
+    /// Too much Low fear: push harder
+    RampUp,
+}
+
+/// Fear band controller settings
+#[derive(Debug, Clone, PartialEq)]
+pub struct FearBandConfig {
+    /// Rolling window of usable fear time the distribution is measured over
+    pub horizon: Duration,
+    /// Usable fear time needed before the controller produces an adjustment
+    pub warmup: Duration,
+    /// Distribution the controller steers toward
+    pub target: BandDistribution,
+    /// Proportional gain from band error to adjustment
+    pub gain: f32,
+    /// Adjustment magnitude at which `EaseOff` or `RampUp` is signalled
+    pub action_threshold: f32,
+    /// Minimum model confidence for a fear reading to count
+    pub min_confidence: f32,
+}
+
+impl Default for FearBandConfig {
+    fn default() -> Self {
+        Self {
+            horizon: Duration::from_secs(120),
+            warmup: Duration::from_secs(10),
+            target: BandDistribution::default(),
+            gain: 1.5,
+            action_threshold: 0.3,
+            min_confidence: 0.5,
+        }
+    }
+}
+
+impl FearBandConfig {
+    /// Set the rolling horizon
+    pub fn with_horizon(mut self, horizon: Duration) -> Self {
+        self.horizon = horizon;
+        self
+    }
+
+    /// Set the warmup time
+    pub fn with_warmup(mut self, warmup: Duration) -> Self {
+        self.warmup = warmup;
+        self
+    }
+
+    /// Set the target distribution
+    pub fn with_target(mut self, target: BandDistribution) -> Self {
+        self.target = target;
+        self
+    }
+
+    /// Set the proportional gain
+    pub fn with_gain(mut self, gain: f32) -> Self {
+        self.gain = gain.max(0.0);
+        self
+    }
+
+    /// Set the action threshold
+    pub fn with_action_threshold(mut self, threshold: f32) -> Self {
+        self.action_threshold = threshold.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Set the minimum confidence
+    pub fn with_min_confidence(mut self, confidence: f32) -> Self {
+        self.min_confidence = confidence.clamp(0.0, 1.0);
+        self
+    }
+}
+
+/// Proportional controller steering the time-in-bucket distribution
+#[derive(Resource, Debug, Clone)]
+pub struct FearBandController {
+    config: FearBandConfig,
+    /// Usable fear time per bucket, oldest first
+    window: VecDeque<(FearBucket, Duration)>,
+    /// Sum of `window` per bucket
+    dwell: BucketDwell,
+    adjustment: f32,
+    action: BandAction,
+    frozen: bool,
+}
+
+impl Default for FearBandController {
+    fn default() -> Self {
+        Self::new(FearBandConfig::default())
+    }
+}
+
+impl FearBandController {
+    pub fn new(config: FearBandConfig) -> Self {
+        Self {
+            config,
+            window: VecDeque::new(),
+            dwell: BucketDwell::default(),
+            adjustment: 0.0,
+            action: BandAction::Hold,
+            frozen: true,
+        }
+    }
+
+    pub fn config(&self) -> &FearBandConfig {
+        &self.config
+    }
+
+    /// Intensity adjustment [-1.0, 1.0]: positive ramps up, negative eases off
+    pub fn adjustment(&self) -> f32 {
+        self.adjustment
+    }
+
+    /// Action the current adjustment calls for
+    pub fn action(&self) -> BandAction {
+        self.action
+    }
+
+    /// Whether the last update had no usable fear reading
+    pub fn is_frozen(&self) -> bool {
+        self.frozen
+    }
+
+    /// Time per bucket within the horizon
+    pub fn dwell(&self) -> BucketDwell {
+        self.dwell
+    }
+
+    /// Time-in-bucket distribution within the horizon
+    pub fn distribution(&self) -> Option<BandDistribution> {
+        self.dwell.distribution()
+    }
+
+    /// Advance by `dt` spent in `bucket`, or `None` when fear was unusable
+    ///
+    /// Returns the new action when the adjustment crossed a threshold.
+    pub fn update(&mut self, bucket: Option<FearBucket>, dt: Duration) -> Option<BandAction> {
+        let Some(bucket) = bucket else {
+            self.frozen = true;
+            return None;
+        };
+        self.frozen = false;
+        self.record(bucket, dt);
+
+        let distribution = self.distribution().filter(|_| self.dwell.total() >= self.config.warmup)?;
+
+        // Excess Low calls for more intensity, excess High for less
+        let target = self.config.target;
+        let error = (distribution.low - target.low) - (distribution.high - target.high);
+        self.adjustment = (self.config.gain * error).clamp(-1.0, 1.0);
+
+        let action = if self.adjustment >= self.config.action_threshold {
+            BandAction::RampUp
+        } else if self.adjustment <= -self.config.action_threshold {
+            BandAction::EaseOff
+        } else {
+            BandAction::Hold
+        };
+        if action == self.action {
+            return None;
+        }
+        self.action = action;
+        Some(action)
+    }
+
+    /// Discard the window and return to Hold
+    pub fn reset(&mut self) {
+        *self = Self::new(self.config.clone());
+    }
+
+    fn record(&mut self, bucket: FearBucket, dt: Duration) {
+        match self.window.back_mut() {
+            Some((last, time)) if *last == bucket => *time += dt,
+            _ => self.window.push_back((bucket, dt)),
+        }
+        self.dwell.add(bucket, dt);
+
+        // Trim the oldest time until the window fits the horizon
+        let mut excess = self.dwell.total().saturating_sub(self.config.horizon);
+        while !excess.is_zero() {
+            let Some((oldest, time)) = self.window.front_mut() else {
+                break;
+            };
+            let trimmed = excess.min(*time);
+            *time -= trimmed;
+            *self.dwell.get_mut(*oldest) -= trimmed;
+            excess -= trimmed;
+            if time.is_zero() {
+                self.window.pop_front();
+            }
+        }
+    }
+}
+
+/// The fear band adjustment crossed into a different action
+#[derive(Event, Debug, Clone, PartialEq)]
+pub struct FearBandChanged {
+    pub action: BandAction,
+    /// Adjustment [-1.0, 1.0] when the action changed
+    pub adjustment: f32,
+    /// Time-in-bucket distribution within the horizon
+    pub distribution: BandDistribution,
+}
+
+/// Bucket of the current fear level, if it can be trusted
+fn usable_bucket(fear_state: &FearState, min_confidence: f32) -> Option<FearBucket> {
+    let usable = fear_state.calibrated
+        && fear_state.fear_available()
+        && fear_state.current_confidence >= min_confidence;
+    usable.then_some(fear_state.current_bucket)
+}
+
+/// Feed the controller with the frame's fear bucket and record the band in metrics
+pub fn update_fear_band(
+    time: Res<Time>,
+    fear_state: Res<FearState>,
+    mut controller: ResMut<FearBandController>,
+    mut metrics: ResMut<GameMetrics>,
+    mut changed: EventWriter<FearBandChanged>,
+) {
+    let dt = time.delta();
+    let bucket = usable_bucket(&fear_state, controller.config().min_confidence);
+    let action = controller.update(bucket, dt);
+
+    match bucket {
+        Some(bucket) => metrics.time_in_bucket.add(bucket, dt),
+        None => metrics.frozen_time += dt,
+    }
+    metrics.fear_band = controller.distribution();
+    metrics.band_adjustment = controller.adjustment();
+
+    if let (Some(action), Some(distribution)) = (action, controller.distribution()) {
+        tracing::info!(
+            "Fear band {:?}: adjustment {:+.2}, low {:.0}% medium {:.0}% high {:.0}%",
+            action,
+            controller.adjustment(),
+            distribution.low * 100.0,
+            distribution.medium * 100.0,
+            distribution.high * 100.0
+        );
+        changed.write(FearBandChanged {
+            action,
+            adjustment: controller.adjustment(),
+            distribution,
+        });
+    }
+}
+
+/// Plugin registering the fear band controller, its event and metrics
+pub struct FearBandPlugin;
+
+impl Plugin for FearBandPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .init_resource::<FearBandController>()
+            .init_resource::<GameMetrics>()
+            .add_event::<FearBandChanged>()
+            .add_systems(Update, update_fear_band.after(update_fear_system));
+    }
+}
@@ -0,0 +1,492 @@
+//! Event-sourced `FearState` history for replay scrubbing and rollback
+//!
+//! With a [`FearJournal`] resource present, every change the game commits to
+//! [`FearState`] (sensor frames, legacy scores, terrain rebuilds, bucket
+//! threshold, fear mapping and face smoothing changes) is recorded as a [`FearEvent`]
+//! stamped with game time. Bucket commits and rebuild triggers follow from
+//! the frames, so replaying the events reproduces them. A full
+//! [`FearStateSnapshot`] is taken every `snapshot_interval`, and
+//! [`FearJournal::reconstruct_at`] restores the nearest earlier snapshot and
+//! replays the events after it.
+//!
+//! Memory is bounded by `max_events` and `max_snapshots`
+//! ([`FearJournalConfig::max_bytes`]). History is lost from the front: the
+//! oldest events are folded into the earliest snapshot, so everything kept
+//! stays reconstructible. Events are timed on the game clock the
+//! [`GameEventLog`](crate::GameEventLog) uses, so an exported journal lines up
+//! with the sensor trace through the log's clock sync estimates.
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use spectremesh_core::config::{DistortionCurve, FearMapping};
+use spectremesh_core::types::{
+    EmotionVector, FearBucket, FearBucketSmoother, FearBucketThresholds, FearFrame, FearScore, SensorCapability,
+};
+use std::collections::VecDeque;
+use std::path::Path;
+use std::time::Duration;
+use thiserror::Error;
+use crate::resources::{FearState, STARTLE_HISTORY_LEN};
+
+/// A change committed to [`FearState`], replayed by [`FearState::apply`]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum FearEvent {
+    /// A sensor frame was applied
+    FrameApplied {
+        fear_score: f32,
+        confidence: f32,
+        calibrated: bool,
+        startle: f32,
+        capability: SensorCapability,
+        face_center: Option<(f32, f32)>,
+        /// Journals from before face tracking was reported only hold frames
+        /// with a face
+        #[serde(default = "face_present")]
+        face_present: bool,
+        /// Emotion probabilities; `None` for models of another layout and
+        /// in journals from before they were recorded
+        #[serde(default)]
+        emotions: Option<EmotionVector>,
+    },
+    /// A legacy score was applied
+    ScoreApplied {
+        value: f32,
+        confidence: f32,
+        calibrated: bool,
+        #[serde(default = "face_present")]
+        face_present: bool,
+    },
+    /// Terrain was rebuilt for the current bucket
+    TerrainRebuilt,
+    /// The bucket thresholds changed
+    ThresholdsChanged { thresholds: FearBucketThresholds },
+    /// The whole fear mapping changed, from `GameConfig::fear_mapping`
+    MappingChanged { mapping: FearMapping },
+    /// The face smoothing weight changed
+    FaceSmoothingChanged { weight: f32 },
+}
+
+impl FearEvent {
+    /// The parts of `frame` that `FearState` uses
+    pub fn frame(frame: &FearFrame) -> Self {
+        Self::FrameApplied {
+            fear_score: frame.fear_score,
+            confidence: frame.confidence,
+            calibrated: frame.calibrated,
+            startle: frame.startle,
+            capability: frame.capability,
+            face_center: frame.face_center,
+            face_present: frame.face_present,
+            emotions: frame.emotions(),
+        }
+    }
+
+    /// The parts of a legacy score that `FearState` uses
+    pub fn score(score: &FearScore) -> Self {
+        Self::ScoreApplied {
+            value: score.provisional_value(),
+            confidence: score.confidence,
+            calibrated: score.calibrated,
+            face_present: score.face_present,
+        }
+    }
+}
+
+/// Everything in [`FearState`] that events change; the frame subscription,
+/// the wall-clock `last_update` and the smoothed values advanced by
+/// [`FearState::tick`] are left out
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FearStateSnapshot {
+    pub current_fear: f32,
+    pub current_startle: f32,
+    pub current_confidence: f32,
+    pub startle_history: VecDeque<f32>,
+    pub current_bucket: FearBucket,
+    pub previous_bucket: FearBucket,
+    pub bucket_thresholds: FearBucketThresholds,
+    /// Journals from before the mapping was configurable used the default
+    /// intensities and no curve
+    #[serde(default = "default_intensities")]
+    pub distortion_intensities: [f32; 3],
+    #[serde(default)]
+    pub distortion_curve: Option<DistortionCurve>,
+    #[serde(default)]
+    pub bucket_smoother: FearBucketSmoother,
+    pub calibrated: bool,
+    pub sensor_capability: SensorCapability,
+    pub terrain_needs_rebuild: bool,
+    pub face_center: Option<(f32, f32)>,
+    pub face_smoothing: f32,
+    #[serde(default = "face_present")]
+    pub face_present: bool,
+    #[serde(default)]
+    pub current_emotions: EmotionVector,
+}
+
+/// Default of fields recording whether a face was in frame
+fn face_present() -> bool {
+    true
+}
+
+/// Default of `FearStateSnapshot::distortion_intensities`
+fn default_intensities() -> [f32; 3] {
+    FearBucket::DISTORTION_INTENSITIES
+}
+
+impl From<&FearState> for FearStateSnapshot {
+    fn from(state: &FearState) -> Self {
+        Self {
+            current_fear: state.current_fear,
+            current_startle: state.current_startle,
+            current_confidence: state.current_confidence,
+            startle_history: state.startle_history.clone(),
+            current_bucket: state.current_bucket,
+            previous_bucket: state.previous_bucket,
+            bucket_thresholds: state.fear_mapping.thresholds,
+            distortion_intensities: state.fear_mapping.intensities,
+            distortion_curve: state.fear_mapping.curve,
+            bucket_smoother: state.bucket_smoother,
+            calibrated: state.calibrated,
+            sensor_capability: state.sensor_capability,
+            terrain_needs_rebuild: state.terrain_needs_rebuild,
+            face_center: state.face_center,
+            face_smoothing: state.face_smoothing,
+            face_present: state.face_present,
+            current_emotions: state.current_emotions,
+        }
+    }
+}
+
+impl FearStateSnapshot {
+    /// Overwrite `state` with this snapshot, keeping its frame subscription
+    pub fn restore(&self, state: &mut FearState) {
+        state.current_fear = self.current_fear;
+        state.current_startle = self.current_startle;
+        state.current_confidence = self.current_confidence;
+        state.startle_history.clone_from(&self.startle_history);
+        state.current_bucket = self.current_bucket;
+        state.previous_bucket = self.previous_bucket;
+        state.fear_mapping = FearMapping {
+            thresholds: self.bucket_thresholds,
+            intensities: self.distortion_intensities,
+            curve: self.distortion_curve,
+        };
+        state.bucket_smoother = self.bucket_smoother;
+        state.calibrated = self.calibrated;
+        state.sensor_capability = self.sensor_capability;
+        state.terrain_needs_rebuild = self.terrain_needs_rebuild;
+        state.face_center = self.face_center;
+        state.face_smoothing = self.face_smoothing;
+        state.face_present = self.face_present;
+        state.current_emotions = self.current_emotions;
+    }
+
+    /// A `FearState` without a frame subscription holding this snapshot
+    pub fn to_state(&self) -> FearState {
+        let mut state = FearState::default();
+        self.restore(&mut state);
+        state
+    }
+}
+
+/// A recorded event
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct JournalEntry {
+    /// Position in the session's event sequence
+    pub seq: u64,
+    /// Game time the event was committed at, in microseconds
+    pub game_time_us: u64,
+    pub event: FearEvent,
+}
+
+/// A full state, taken before the event numbered `seq`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct JournalSnapshot {
+    /// Sequence number of the first event not included
+    pub seq: u64,
+    /// Game time of the last event included, in microseconds
+    pub game_time_us: u64,
+    pub state: FearStateSnapshot,
+}
+
+/// One line of an exported journal
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+enum JournalLine {
+    Snapshot(JournalSnapshot),
+    Event(JournalEntry),
+}
+
+/// Snapshot cadence and memory bounds of a [`FearJournal`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct FearJournalConfig {
+    /// Game time between full snapshots
+    pub snapshot_interval: Duration,
+    /// Most events kept; older ones are folded into the earliest snapshot
+    pub max_events: usize,
+    /// Most snapshots kept, at least one; the oldest go with the events
+    /// before the next
+    pub max_snapshots: usize,
+}
+
+impl Default for FearJournalConfig {
+    fn default() -> Self {
+        // About ten minutes of history at 30 FPS
+        Self {
+            snapshot_interval: Duration::from_secs(5),
+            max_events: 18_000,
+            max_snapshots: 120,
+        }
+    }
+}
+
+impl FearJournalConfig {
+    /// Set the game time between snapshots
+    pub fn with_snapshot_interval(mut self, interval: Duration) -> Self {
+        self.snapshot_interval = interval;
+        self
+    }
+
+    /// Set the most events kept
+    pub fn with_max_events(mut self, max_events: usize) -> Self {
+        self.max_events = max_events;
+        self
+    }
+
+    /// Set the most snapshots kept
+    pub fn with_max_snapshots(mut self, max_snapshots: usize) -> Self {
+        self.max_snapshots = max_snapshots.max(1);
+        self
+    }
+
+    /// Most memory the kept events and snapshots can take
+    pub fn max_bytes(&self) -> usize {
+        let snapshot =
+            std::mem::size_of::<JournalSnapshot>() + STARTLE_HISTORY_LEN * std::mem::size_of::<f32>();
+        self.max_events * std::mem::size_of::<JournalEntry>() + self.max_snapshots.max(1) * snapshot
+    }
+}
+
+/// Errors reading an exported journal
+#[derive(Debug, Error)]
+pub enum FearJournalError {
+    #[error("Line {line}: {message}")]
+    Parse { line: usize, message: String },
+
+    #[error("Journal does not start with a snapshot")]
+    MissingSnapshot,
+
+    #[error("Failed to read journal: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+/// Timestamped [`FearEvent`]s with periodic snapshots, oldest first
+#[derive(Resource, Debug, Clone, Default)]
+pub struct FearJournal {
+    config: FearJournalConfig,
+    /// The front is the base every kept event follows
+    snapshots: VecDeque<JournalSnapshot>,
+    events: VecDeque<JournalEntry>,
+    next_seq: u64,
+}
+
+impl FearJournal {
+    pub fn new(config: FearJournalConfig) -> Self {
+        Self {
+            config,
+            ..Default::default()
+        }
+    }
+
+    pub fn config(&self) -> &FearJournalConfig {
+        &self.config
+    }
+
+    /// Events kept, oldest first
+    pub fn events(&self) -> impl Iterator<Item = &JournalEntry> {
+        self.events.iter()
+    }
+
+    /// Snapshots kept, oldest first
+    pub fn snapshots(&self) -> impl Iterator<Item = &JournalSnapshot> {
+        self.snapshots.iter()
+    }
+
+    /// Number of events kept
+    pub fn len(&self) -> usize {
+        self.events.len()
+    }
+
+    /// Whether no events are kept
+    pub fn is_empty(&self) -> bool {
+        self.events.is_empty()
+    }
+
+    /// Earliest game time that can be reconstructed
+    pub fn start(&self) -> Option<Duration> {
+        self.snapshots.front().map(|base| Duration::from_micros(base.game_time_us))
+    }
+
+    /// Apply `event` to `state` as a live change and record it at `game_time`
+    ///
+    /// The first commit snapshots `state` as it was before, as the base the
+    /// journal replays from.
+    pub fn commit(&mut self, state: &mut FearState, game_time: Duration, event: FearEvent) {
+        let game_time_us = game_time.as_micros() as u64;
+        if self.snapshots.is_empty() {
+            self.snapshots.push_back(JournalSnapshot {
+                seq: self.next_seq,
+                game_time_us,
+                state: FearStateSnapshot::from(&*state),
+            });
+        }
+
+        state.commit(&event);
+        self.events.push_back(JournalEntry { seq: self.next_seq, game_time_us, event });
+        self.next_seq += 1;
+
+        let interval_us = self.config.snapshot_interval.as_micros() as u64;
+        let due = match self.snapshots.back() {
+            Some(last) => game_time_us.saturating_sub(last.game_time_us) >= interval_us,
+            None => true,
+        };
+        if due {
+            self.snapshots.push_back(JournalSnapshot {
+                seq: self.next_seq,
+                game_time_us,
+                state: FearStateSnapshot::from(&*state),
+            });
+        }
+        self.evict();
+    }
+
+    /// Drop history beyond the configured bounds
+    fn evict(&mut self) {
+        // Too many snapshots: everything before the second one goes
+        while self.snapshots.len() > self.config.max_snapshots.max(1) {
+            self.snapshots.pop_front();
+            let base = self.snapshots.front().map_or(self.next_seq, |base| base.seq);
+            while self.events.front().is_some_and(|entry| entry.seq < base) {
+                self.events.pop_front();
+            }
+        }
+
+        // Too many events: fold the oldest into the base snapshot
+        while self.events.len() > self.config.max_events {
+            let Some(entry) = self.events.pop_front() else {
+                break;
+            };
+            if self.snapshots.get(1).is_some_and(|next| next.seq <= entry.seq + 1) {
+                self.snapshots.pop_front();
+            } else if let Some(base) = self.snapshots.front_mut() {
+                let mut state = base.state.to_state();
+                state.apply(&entry.event);
+                base.state = FearStateSnapshot::from(&state);
+                base.seq = entry.seq + 1;
+                base.game_time_us = entry.game_time_us;
+            }
+        }
+    }
+
+    /// `FearState` once everything committed up to `game_time` was applied
+    ///
+    /// `None` before [`start`](Self::start), whose history is gone. The
+    /// result has no frame subscription; [`FearStateSnapshot::restore`]
+    /// loads a reconstructed state into the live one.
+    pub fn reconstruct_at(&self, game_time: Duration) -> Option<FearState> {
+        let game_time_us = game_time.as_micros() as u64;
+        let index = self
+            .snapshots
+            .partition_point(|snapshot| snapshot.game_time_us <= game_time_us)
+            .checked_sub(1)?;
+        let snapshot = &self.snapshots[index];
+
+        let mut state = snapshot.state.to_state();
+        for entry in self
+            .events
+            .iter()
+            .skip_while(|entry| entry.seq < snapshot.seq)
+            .take_while(|entry| entry.game_time_us <= game_time_us)
+        {
+            state.apply(&entry.event);
+        }
+        Some(state)
+    }
+
+    /// Snapshots and events in sequence order, one JSON object per line,
+    /// readable with [`from_jsonl`](Self::from_jsonl)
+    pub fn to_jsonl(&self) -> String {
+        let mut snapshots = self.snapshots.iter().peekable();
+        let mut lines = String::new();
+        for entry in &self.events {
+            while let Some(snapshot) = snapshots.next_if(|snapshot| snapshot.seq <= entry.seq) {
+                push_line(&mut lines, &JournalLine::Snapshot(snapshot.clone()));
+            }
+            push_line(&mut lines, &JournalLine::Event(*entry));
+        }
+        for snapshot in snapshots {
+            push_line(&mut lines, &JournalLine::Snapshot(snapshot.clone()));
+        }
+        lines
+    }
+
+    pub fn write(&self, path: &Path) -> std::io::Result<()> {
+        std::fs::write(path, self.to_jsonl())
+    }
+
+    /// Read an exported journal, keeping what fits within `config`'s bounds
+    pub fn from_jsonl(config: FearJournalConfig, content: &str) -> Result<Self, FearJournalError> {
+        let mut journal = Self::new(config);
+        for (index, line) in content.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
+            let line = serde_json::from_str(line).map_err(|e| FearJournalError::Parse {
+                line: index + 1,
+                message: e.to_string(),
+            })?;
+            match line {
+                JournalLine::Snapshot(snapshot) => {
+                    journal.next_seq = journal.next_seq.max(snapshot.seq);
+                    journal.snapshots.push_back(snapshot);
+                }
+                JournalLine::Event(_) if journal.snapshots.is_empty() => {
+                    return Err(FearJournalError::MissingSnapshot);
+                }
+                JournalLine::Event(entry) => {
+                    journal.next_seq = entry.seq + 1;
+                    journal.events.push_back(entry);
+                }
+            }
+        }
+        journal.evict();
+        Ok(journal)
+    }
+
+    /// Read an exported journal from disk
+    pub fn load(config: FearJournalConfig, path: &Path) -> Result<Self, FearJournalError> {
+        Self::from_jsonl(config, &std::fs::read_to_string(path)?)
+    }
+}
+
+fn push_line(lines: &mut String, line: &JournalLine) {
+    if let Ok(json) = serde_json::to_string(line) {
+        lines.push_str(&json);
+        lines.push('\n');
+    }
+}
+
+/// Commit `event` to `state`, through the journal when there is one
+pub fn commit_fear_event(
+    state: &mut FearState,
+    journal: Option<&mut FearJournal>,
+    game_time: Duration,
+    event: FearEvent,
+) {
+    match journal {
+        Some(journal) => journal.commit(state, game_time, event),
+        None => state.commit(&event),
+    }
+}
@@ -1,12 +1,59 @@
 //! SpectreMesh game library
 
+pub mod atmosphere;
 pub mod components;
+pub mod debug_overlay;
+pub mod diagnostics;
+pub mod event_log;
+pub mod events;
+pub mod fear_band;
+pub mod fear_journal;
+pub mod loading;
+pub mod modulation;
+pub mod remote;
 pub mod resources;
+pub mod simulation;
 pub mod systems;
+pub mod terrain_material;
+pub mod terrain_mesh;
+pub mod terrain_stream;
+pub mod triggers;
 
 use bevy::prelude::*;
-use resources::FearState;
-use systems::{update_fear_system, update_terrain_system, update_shader_uniforms_system};
+use events::{
+    CalibrationCompleted, CalibrationProgressEvent, DominantEmotionChanged, FearBucketChanged, FearSignalLost,
+    FearSignalRecovered,
+};
+use fear_band::FearBandPlugin;
+use modulation::{update_fear_modulation, FearModulationPlugin};
+use resources::{FearState, GameConfig, TerrainMemory, TerrainSettings};
+use systems::{
+    collect_terrain_meshes_system, smooth_fear_system, spawn_terrain_chunks_system, update_fear_memory_system,
+    update_fear_system, update_shader_uniforms_system, update_terrain_system,
+};
+use terrain_material::TerrainMaterialPlugin;
+
+pub use atmosphere::{
+    AtmosphereConfig, AtmosphereCurve, AtmosphereFlicker, AtmosphereLevels, AtmosphereParam, FearAtmosphere,
+    FearAtmospherePlugin,
+};
+pub use debug_overlay::{SensorOverlay, SensorOverlayPlugin};
+pub use diagnostics::{SensorDiagnosticsPlugin, SensorHealth};
+pub use event_log::GameEventLog;
+pub use fear_band::{BandAction, BandDistribution, FearBandChanged, FearBandConfig, FearBandController};
+pub use fear_journal::{FearEvent, FearJournal, FearJournalConfig, FearStateSnapshot};
+pub use loading::{CalibrationGatePlugin, LoadingGates, LoadingPlugin, LoadingState};
+pub use modulation::{FearModulation, Slew, SlewMode};
+pub use remote::{
+    connect_fear_sensor, install_frame_source, FearSensorPlugin, FearSource, RemoteFearSource, ReplayFearSource, SensorMode,
+    SensorSettings,
+};
+pub use simulation::{SimulationPlugin, SimulationScript};
+pub use terrain_material::{TerrainMaterial, TerrainMaterialPlugin, TerrainUniforms};
+pub use terrain_stream::{
+    TerrainGenMilestone, TerrainGenProgress, TerrainStreamConfig, TerrainStreamer, TerrainStreamingPlugin,
+};
+pub use triggers::{TriggerRule, TriggerRulesPlugin};
 
 /// SpectreMesh game plugin
 pub struct SpectreMeshPlugin;
@@ -16,24 +63,62 @@ impl Plugin for SpectreMeshPlugin {
         app
             // Add resources
             .init_resource::<FearState>()
+            .init_resource::<GameConfig>()
+            .init_resource::<TerrainMemory>()
+            .init_resource::<TerrainSettings>()
+            .add_event::<DominantEmotionChanged>()
+            .add_event::<FearBucketChanged>()
+            .add_event::<CalibrationCompleted>()
+            .add_event::<FearSignalLost>()
+            .add_event::<FearSignalRecovered>()
+            .add_event::<CalibrationProgressEvent>()
+            .add_plugins((FearModulationPlugin, FearBandPlugin, TerrainMaterialPlugin))
 
             // Add systems
             .add_systems(Update, (
                 update_fear_system,
-                update_terrain_system.after(update_fear_system),
-                update_shader_uniforms_system.after(update_fear_system),
+                smooth_fear_system.after(update_fear_system),
+                update_fear_memory_system.after(update_fear_system),
+                spawn_terrain_chunks_system,
+                collect_terrain_meshes_system.after(spawn_terrain_chunks_system),
+                update_terrain_system
+                    .after(update_fear_memory_system)
+                    .after(collect_terrain_meshes_system),
+                update_shader_uniforms_system
+                    .after(update_fear_modulation)
+                    .after(update_fear_memory_system),
             ));
     }
 }
 
 /// Create a basic SpectreMesh app for M0.5 development
+///
+/// The fear sensor is picked by the [`SensorMode`] resource, YuNet unless
+/// another mode is inserted before the first update.
 pub fn create_spectremesh_app() -> App {
     let mut app = App::new();
 
     app
         .add_plugins(DefaultPlugins)
-        .add_plugins(SpectreMeshPlugin)
+        .add_plugins((SpectreMeshPlugin, FearSensorPlugin { source: FearSource::Configured }))
+        // Chunks leave as soon as they fall outside the render distance
+        .add_plugins(TerrainStreamingPlugin::new(TerrainStreamConfig::default().with_unload_distance(0)))
+        .add_plugins(SensorOverlayPlugin)
         .insert_resource(ClearColor(Color::srgb(0.1, 0.1, 0.15)));
 
     app
 }
+
+/// Create the SpectreMesh game without a window or renderer, for tests and tools
+///
+/// Terrain streaming needs mesh assets and is left out.
+pub fn create_headless_spectremesh_app() -> App {
+    let mut app = App::new();
+
+    app
+        .add_plugins(MinimalPlugins)
+        .add_plugins((SpectreMeshPlugin, FearSensorPlugin { source: FearSource::Configured }))
+        .add_plugins(SensorDiagnosticsPlugin);
+
+    app
+}
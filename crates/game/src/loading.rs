@@ -0,0 +1,114 @@
+//! Loading state gated on independent readiness checks
+//!
+//! The game starts in [`LoadingState::Loading`] and moves to
+//! [`LoadingState::Ready`] once every gate registered in [`LoadingGates`] is
+//! open. Gates know nothing about each other: terrain streaming can hold
+//! loading until the chunks around the spawn point exist, and
+//! [`CalibrationGatePlugin`] until the sensor is calibrated. With both
+//! enabled, loading ends when the later of the two opens.
+//!
+//! Gates are opened by systems in [`LoadingGateSet`], which runs in
+//! `PreUpdate` ahead of the transition, so the state changes in the same
+//! frame as the last gate opens.
+
+use bevy::prelude::*;
+use std::collections::BTreeMap;
+use crate::resources::FearState;
+
+/// Gate held by [`CalibrationGatePlugin`]
+pub const CALIBRATION_GATE: &str = "calibration";
+
+/// Whether the game is still loading
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LoadingState {
+    #[default]
+    Loading,
+    Ready,
+}
+
+/// Named gates that must all open before loading ends
+#[derive(Resource, Debug, Clone, Default)]
+pub struct LoadingGates {
+    gates: BTreeMap<&'static str, bool>,
+}
+
+impl LoadingGates {
+    /// Add a closed gate; registering an existing gate leaves it as it is
+    pub fn register(&mut self, gate: &'static str) {
+        self.gates.entry(gate).or_insert(false);
+    }
+
+    /// Open a registered gate; unregistered gates are ignored
+    pub fn open(&mut self, gate: &'static str) {
+        if let Some(open) = self.gates.get_mut(gate) {
+            *open = true;
+        }
+    }
+
+    pub fn is_open(&self, gate: &str) -> bool {
+        self.gates.get(gate).copied().unwrap_or(false)
+    }
+
+    /// Gates still holding loading back
+    pub fn closed(&self) -> impl Iterator<Item = &'static str> + '_ {
+        self.gates.iter().filter(|(_, open)| !**open).map(|(gate, _)| *gate)
+    }
+
+    pub fn all_open(&self) -> bool {
+        self.closed().next().is_none()
+    }
+}
+
+/// Systems that open loading gates
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct LoadingGateSet;
+
+/// End loading once every gate is open
+pub fn finish_loading(gates: Res<LoadingGates>, mut state: ResMut<LoadingState>) {
+    if *state == LoadingState::Loading && gates.all_open() {
+        *state = LoadingState::Ready;
+        tracing::info!("Loading complete");
+    }
+}
+
+/// Loading state and gates
+pub struct LoadingPlugin;
+
+impl Plugin for LoadingPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .init_resource::<LoadingState>()
+            .init_resource::<LoadingGates>()
+            .add_systems(PreUpdate, finish_loading.after(LoadingGateSet));
+    }
+}
+
+/// Register `gate`, adding [`LoadingPlugin`] if no other plugin has
+pub fn register_loading_gate(app: &mut App, gate: &'static str) {
+    if !app.is_plugin_added::<LoadingPlugin>() {
+        app.add_plugins(LoadingPlugin);
+    }
+    app.world_mut().resource_mut::<LoadingGates>().register(gate);
+}
+
+/// Open the calibration gate once fear is calibrated, or known to be unavailable
+fn open_calibration_gate(fear_state: Res<FearState>, mut gates: ResMut<LoadingGates>) {
+    if fear_state.calibrated || !fear_state.fear_available() {
+        gates.open(CALIBRATION_GATE);
+    }
+}
+
+/// Hold loading until the sensor finishes calibrating
+///
+/// Without a usable sensor (fear unavailable) the gate opens right away so
+/// the game can fall back to scripted behaviour.
+pub struct CalibrationGatePlugin;
+
+impl Plugin for CalibrationGatePlugin {
+    fn build(&self, app: &mut App) {
+        register_loading_gate(app, CALIBRATION_GATE);
+        app
+            .init_resource::<FearState>()
+            .add_systems(PreUpdate, open_calibration_gate.in_set(LoadingGateSet));
+    }
+}
@@ -0,0 +1,133 @@
+//! Slewed modulation signals for visual and post-processing effects
+//!
+//! Sensor values change in steps at the sensor frame rate. Effects read
+//! [`FearModulation`] instead: `intensity` follows the distortion intensity
+//! (continuous in the smoothed fear level) at a limited rate in both
+//! directions, while `transient` follows the startle signal with an
+//! immediate attack and a slow release, so a jump scare lands
+//! on the frame it is detected and then fades out. `anticipation` follows
+//! how far fear has moved through its bucket, so effects can build up before
+//! the next bucket is reached.
+
+use bevy::prelude::*;
+use crate::{resources::FearState, systems::smooth_fear_system};
+
+/// How a [`Slew`] moves toward its target
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SlewMode {
+    /// Move toward the target by at most `rate` per second in either direction
+    Linear { rate: f32 },
+    /// Jump to higher targets immediately, fall by at most `release` per second
+    AttackRelease { release: f32 },
+}
+
+/// A value that follows a target at a limited rate
+#[derive(Debug, Clone, PartialEq)]
+pub struct Slew {
+    /// Slewing behaviour
+    pub mode: SlewMode,
+    value: f32,
+}
+
+impl Slew {
+    /// Create a slew starting at zero
+    pub fn new(mode: SlewMode) -> Self {
+        Self { mode, value: 0.0 }
+    }
+
+    /// Symmetric rate-limited slew
+    pub fn linear(rate: f32) -> Self {
+        Self::new(SlewMode::Linear { rate })
+    }
+
+    /// Immediate attack, rate-limited release
+    pub fn attack_release(release: f32) -> Self {
+        Self::new(SlewMode::AttackRelease { release })
+    }
+
+    /// Current value
+    pub fn value(&self) -> f32 {
+        self.value
+    }
+
+    /// Jump to `value` without slewing
+    pub fn reset(&mut self, value: f32) {
+        self.value = value;
+    }
+
+    /// Advance toward `target` by `dt` seconds and return the new value
+    pub fn step(&mut self, target: f32, dt: f32) -> f32 {
+        let (rise, fall) = match self.mode {
+            SlewMode::Linear { rate } => (rate * dt, rate * dt),
+            SlewMode::AttackRelease { release } => (f32::INFINITY, release * dt),
+        };
+
+        self.value = if target > self.value {
+            (self.value + rise).min(target)
+        } else {
+            (self.value - fall).max(target)
+        };
+        self.value
+    }
+}
+
+/// Slewed fear signals for effects
+#[derive(Resource, Debug, Clone)]
+pub struct FearModulation {
+    /// Distortion intensity, smoothed in both directions
+    pub intensity: Slew,
+    /// Startle transient: instant attack, slow release
+    pub transient: Slew,
+    /// Progress through the current fear bucket, smoothed in both directions
+    pub anticipation: Slew,
+}
+
+impl Default for FearModulation {
+    fn default() -> Self {
+        Self {
+            intensity: Slew::linear(0.5), // Full range in 2s
+            transient: Slew::attack_release(2.0), // Full startle fades in 0.5s
+            anticipation: Slew::linear(1.0), // Across a bucket in 1s
+        }
+    }
+}
+
+impl FearModulation {
+    /// Smoothed distortion intensity [0.0, 1.0]
+    pub fn intensity(&self) -> f32 {
+        self.intensity.value()
+    }
+
+    /// Startle transient [0.0, 1.0] for short effects (flash, shake, chromatic kick)
+    pub fn transient(&self) -> f32 {
+        self.transient.value()
+    }
+
+    /// Smoothed bucket progress [0.0, 1.0]; near 1 fear is about to reach the next bucket
+    pub fn anticipation(&self) -> f32 {
+        self.anticipation.value()
+    }
+}
+
+/// Advance the modulation signals toward the current fear state
+pub fn update_fear_modulation(
+    time: Res<Time>,
+    fear_state: Res<FearState>,
+    mut modulation: ResMut<FearModulation>,
+) {
+    let dt = time.delta_secs();
+    modulation.intensity.step(fear_state.get_distortion_intensity(), dt);
+    modulation.transient.step(fear_state.current_startle, dt);
+    modulation.anticipation.step(fear_state.bucket_progress(), dt);
+}
+
+/// Plugin registering the modulation resource and system
+pub struct FearModulationPlugin;
+
+impl Plugin for FearModulationPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .init_resource::<FearModulation>()
+            .add_systems(Update, update_fear_modulation.after(smooth_fear_system));
+    }
+}
@@ -0,0 +1,910 @@
+//! Fear sensor plugin with an out-of-process gRPC source
+//!
+//! Production deployments run the sensor as a separate daemon. With
+//! [`FearSource::Remote`] the plugin connects to it from a background tokio
+//! runtime, converts streamed scores into the same `FearFrame` flow the
+//! in-process sensor uses, and reports connection state through
+//! [`SensorStatus`]. Lost connections are retried with backoff, but a
+//! stream ending with a `SENSOR_STOPPED` fault means the daemon shut its
+//! sensor down, so the worker settles on [`SensorStatus::Stopped`] instead. [`FearSource::Mock`] replays a fixed fear sequence
+//! through `MockFearSensor` for running without hardware.
+//!
+//! With [`FearSource::Configured`] the source is picked at startup by
+//! [`connect_fear_sensor`] from the [`SensorMode`] resource: the mock step
+//! pattern, the in-process YuNet sensor configured by [`SensorSettings`], a
+//! daemon, or a recorded session. A YuNet sensor that fails to start or whose
+//! stream ends, a daemon the worker gives up on, and a recording that cannot
+//! be played degrade to the mock with a warning.
+//!
+//! The remote worker also pings the daemon periodically to estimate the
+//! offset between game time and the sensor's clocks. The estimate is
+//! published as the [`ClockSync`] resource, logged to the [`GameEventLog`]
+//! and sent back with the next ping so the daemon records it as well.
+//!
+//! On constrained links the stream can be compressed and delta-encoded
+//! ([`RemoteFearSource::with_compression`], [`RemoteFearSource::with_delta_encoding`]);
+//! full scores are rebuilt before they become frames.
+
+use bevy::prelude::*;
+use spectre_sensor::{
+    clock_sync::{ClockSample, ClockSyncEstimate, ClockSyncEstimator},
+    compat::{FearSensor, MockFearSensor, ReplayFearSensor, YuNetFearSensor},
+    delta::DeltaConfig,
+    fanout::FrameSubscriber,
+    grpc_client::{CompressionEncoding, SensorClient},
+    proto::{sensor_event, CalibrationResponse, Score, SensorEvent},
+    score_face_present, score_logits,
+    mock_patterns,
+    startle::StartleDetector,
+    transport::SensorTransport,
+    types::SENSOR_STOPPED,
+};
+use spectremesh_core::{
+    types::{FearFrame, FearScore, NormalizedRect},
+    FearConfig,
+};
+use async_channel::{Receiver, Sender, TrySendError};
+use futures::StreamExt;
+use std::future::Future;
+use std::path::PathBuf;
+use std::sync::{Arc, OnceLock};
+use std::time::{Duration, Instant, UNIX_EPOCH};
+use crate::{
+    event_log::{log_clock_sync, GameEventLog},
+    events::{CalibrationProgressEvent, SensorCommandResult, SensorFaultEvent},
+    resources::{BugReportKey, ClockSync, FearFrames, FearState, SensorCommand, SensorCommands, SensorStatus},
+};
+
+/// Frames buffered between the background runtime and the game
+const FRAME_BUFFER: usize = 4;
+
+/// Where the game gets fear frames from
+#[derive(Debug, Clone, Default)]
+pub enum FearSource {
+    /// The game starts the sensor itself and passes its receiver to [`install_frame_source`]
+    #[default]
+    InProcess,
+    /// Connect to a sensor daemon over gRPC
+    Remote(RemoteFearSource),
+    /// Loop over a fear sequence with `MockFearSensor` (about 30 samples per second)
+    Mock(Vec<f32>),
+    /// Pick the source at startup from the [`SensorMode`] resource
+    Configured,
+}
+
+/// Sensor [`connect_fear_sensor`] starts for [`FearSource::Configured`]
+#[derive(Resource, Debug, Clone, Default)]
+pub enum SensorMode {
+    /// Loop over the mock step pattern, each level held for about a second
+    Mock,
+    /// Run the YuNet sensor in process with the [`SensorSettings`] config
+    #[default]
+    Yunet,
+    /// Connect to a sensor daemon over gRPC
+    Grpc(RemoteFearSource),
+    /// Play back a recorded session, the same fear curve on every run
+    Replay(ReplayFearSource),
+}
+
+/// Configuration for the in-process sensor started by [`connect_fear_sensor`]
+#[derive(Resource, Debug, Clone, Default)]
+pub struct SensorSettings(pub FearConfig);
+
+/// Connection settings for a remote sensor daemon
+#[derive(Debug, Clone)]
+pub struct RemoteFearSource {
+    /// Daemon address: `host:port`, a Unix socket path or `\\.\pipe\<name>`
+    pub address: String,
+    /// Optional bearer token sent with every request
+    pub auth_token: Option<String>,
+    /// Delay before the first reconnection attempt
+    pub reconnect_delay: Duration,
+    /// Upper bound for the exponential reconnection backoff
+    pub max_reconnect_delay: Duration,
+    /// Consecutive failed attempts before giving up (None retries forever)
+    pub max_attempts: Option<u32>,
+    /// Time between clock synchronization pings
+    pub clock_sync_interval: Duration,
+    /// Send scores as deltas against periodic full scores
+    pub delta: Option<DeltaConfig>,
+    /// Compress messages in both directions
+    pub compression: Option<CompressionEncoding>,
+}
+
+impl RemoteFearSource {
+    /// Connect to the daemon at `address` with default retry settings
+    pub fn new(address: impl Into<String>) -> Self {
+        Self {
+            address: address.into(),
+            auth_token: None,
+            reconnect_delay: Duration::from_millis(500),
+            max_reconnect_delay: Duration::from_secs(10),
+            max_attempts: None,
+            clock_sync_interval: Duration::from_secs(2),
+            delta: None,
+            compression: None,
+        }
+    }
+
+    /// Set the bearer token
+    pub fn with_auth_token(mut self, token: impl Into<String>) -> Self {
+        self.auth_token = Some(token.into());
+        self
+    }
+
+    /// Set the initial and maximum reconnection delay
+    pub fn with_reconnect_delay(mut self, initial: Duration, max: Duration) -> Self {
+        self.reconnect_delay = initial;
+        self.max_reconnect_delay = max.max(initial);
+        self
+    }
+
+    /// Give up after `attempts` consecutive failures
+    pub fn with_max_attempts(mut self, attempts: u32) -> Self {
+        self.max_attempts = Some(attempts);
+        self
+    }
+
+    /// Set the time between clock synchronization pings
+    pub fn with_clock_sync_interval(mut self, interval: Duration) -> Self {
+        self.clock_sync_interval = interval;
+        self
+    }
+
+    /// Stream scores as deltas against periodic full scores
+    pub fn with_delta_encoding(mut self, config: DeltaConfig) -> Self {
+        self.delta = Some(config);
+        self
+    }
+
+    /// Compress messages with `encoding`
+    pub fn with_compression(mut self, encoding: CompressionEncoding) -> Self {
+        self.compression = Some(encoding);
+        self
+    }
+}
+
+/// A session recorded with `SensorConfig::record_path`
+#[derive(Debug, Clone)]
+pub struct ReplayFearSource {
+    /// Recording to play
+    pub path: PathBuf,
+    /// Playback speed relative to the recording
+    pub speed: f32,
+    /// Start over after the last frame instead of stopping
+    pub looping: bool,
+}
+
+impl ReplayFearSource {
+    /// Play the recording at `path` once, at the recorded speed
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            speed: 1.0,
+            looping: false,
+        }
+    }
+
+    /// Play `speed` times faster than recorded
+    pub fn with_speed(mut self, speed: f32) -> Self {
+        self.speed = speed;
+        self
+    }
+
+    /// Start over after the last frame, until the game shuts down
+    pub fn with_looping(mut self, looping: bool) -> Self {
+        self.looping = looping;
+        self
+    }
+}
+
+/// Plugin that connects `FearState` to a fear sensor source
+#[derive(Default)]
+pub struct FearSensorPlugin {
+    pub source: FearSource,
+}
+
+impl FearSensorPlugin {
+    /// Consume fear frames from a remote sensor daemon
+    pub fn remote(source: RemoteFearSource) -> Self {
+        Self { source: FearSource::Remote(source) }
+    }
+}
+
+impl Plugin for FearSensorPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .init_resource::<FearState>()
+            .init_resource::<SensorStatus>()
+            .init_resource::<ClockSync>()
+            .init_resource::<GameEventLog>()
+            .add_event::<CalibrationProgressEvent>()
+            .add_event::<SensorFaultEvent>()
+            .add_event::<SensorCommandResult>();
+
+        match &self.source {
+            FearSource::InProcess => {}
+            FearSource::Remote(remote) => {
+                let (frame_sender, frame_receiver) = async_channel::bounded(FRAME_BUFFER);
+                let (notice_sender, notice_receiver) = async_channel::unbounded();
+                let (command_sender, command_receiver) = async_channel::bounded(16);
+                let game_clock = GameClockOrigin::default();
+
+                spawn_remote_worker(
+                    remote.clone(),
+                    Arc::clone(&game_clock.0),
+                    frame_sender,
+                    notice_sender,
+                    command_receiver,
+                );
+
+                install_frame_source(app, frame_receiver);
+                app
+                    .insert_resource(SensorStatus::Connecting)
+                    .insert_resource(SensorCommands::new(command_sender))
+                    .insert_resource(RemoteNotices(notice_receiver))
+                    .insert_resource(game_clock)
+                    .init_resource::<BugReportKey>()
+                    .add_systems(PreUpdate, (
+                        share_game_clock_origin,
+                        apply_remote_notices,
+                        log_clock_sync.after(apply_remote_notices),
+                    ))
+                    .add_systems(Update, capture_bug_report_on_key);
+            }
+            FearSource::Mock(sequence) => {
+                let (frame_sender, frame_receiver) = async_channel::bounded(FRAME_BUFFER);
+                let (notice_sender, notice_receiver) = async_channel::unbounded();
+
+                spawn_mock_worker(sequence.clone(), frame_sender, notice_sender);
+
+                install_frame_source(app, frame_receiver);
+                app
+                    .insert_resource(SensorStatus::Connecting)
+                    .insert_resource(RemoteNotices(notice_receiver))
+                    .add_systems(PreUpdate, apply_remote_notices);
+            }
+            FearSource::Configured => {
+                // The resources these systems need exist once the startup
+                // system has run, and only for the mode it started
+                app
+                    .init_resource::<SensorMode>()
+                    .init_resource::<SensorSettings>()
+                    .add_systems(Startup, connect_fear_sensor)
+                    .add_systems(PreUpdate, (
+                        share_game_clock_origin.run_if(resource_exists::<GameClockOrigin>),
+                        apply_remote_notices.run_if(resource_exists::<RemoteNotices>),
+                        log_clock_sync.after(apply_remote_notices),
+                    ))
+                    .add_systems(Update, capture_bug_report_on_key.run_if(resource_exists::<SensorCommands>));
+            }
+        }
+    }
+}
+
+/// Start the sensor chosen by [`SensorMode`] and subscribe `FearState` to its frames
+///
+/// Runs at startup for [`FearSource::Configured`]. The sensor runs on its
+/// own thread; failures show up in [`SensorStatus`] and fall back to the
+/// mock rather than stopping the game.
+pub fn connect_fear_sensor(
+    mut commands: Commands,
+    mode: Res<SensorMode>,
+    settings: Res<SensorSettings>,
+    mut fear_state: ResMut<FearState>,
+) {
+    let (frame_sender, frame_receiver) = async_channel::bounded(FRAME_BUFFER);
+    let (notice_sender, notice_receiver) = async_channel::unbounded();
+
+    tracing::info!("Connecting fear sensor: {:?}", *mode);
+    match mode.clone() {
+        SensorMode::Mock => spawn_mock_worker(mock_step_sequence(), frame_sender, notice_sender),
+        SensorMode::Yunet => {
+            let config = settings.0.clone();
+            spawn_sensor_thread("yunet", notice_sender, move |notices| run_yunet(config, frame_sender, notices));
+        }
+        SensorMode::Grpc(source) => {
+            let (command_sender, command_receiver) = async_channel::bounded(16);
+            let game_clock = GameClockOrigin::default();
+            let origin = Arc::clone(&game_clock.0);
+            spawn_sensor_thread("remote", notice_sender, move |notices| async move {
+                if let Err(reason) = run_remote(source, origin, frame_sender.clone(), notices.clone(), command_receiver).await {
+                    tracing::warn!("Remote sensor unavailable ({}), falling back to the mock sensor", reason);
+                    run_mock(mock_step_sequence(), frame_sender, notices).await;
+                }
+            });
+            commands.insert_resource(SensorCommands::new(command_sender));
+            commands.insert_resource(game_clock);
+            commands.init_resource::<BugReportKey>();
+        }
+        SensorMode::Replay(source) => {
+            spawn_sensor_thread("replay", notice_sender, move |notices| run_replay(source, frame_sender, notices));
+        }
+    }
+
+    let frames = FearFrames::new(frame_receiver);
+    fear_state.receiver = Some(frames.subscribe());
+    commands.insert_resource(frames);
+    commands.insert_resource(SensorStatus::Connecting);
+    commands.insert_resource(RemoteNotices(notice_receiver));
+}
+
+/// Share a sensor's frame receiver through [`FearFrames`] and subscribe `FearState` to it
+pub fn install_frame_source(app: &mut App, receiver: Receiver<FearFrame>) {
+    let frames = FearFrames::new(receiver);
+    app.init_resource::<FearState>();
+    app.world_mut().resource_mut::<FearState>().receiver = Some(frames.subscribe());
+    app.insert_resource(frames);
+}
+
+/// Updates sent from the background runtime to the Bevy world
+#[derive(Debug, Clone)]
+enum RemoteNotice {
+    Status(SensorStatus),
+    Calibration(CalibrationProgressEvent),
+    Fault(SensorFaultEvent),
+    Command(SensorCommandResult),
+    ClockSync(ClockSyncEstimate),
+}
+
+/// Receiving half of the remote worker's notice channel
+#[derive(Resource)]
+struct RemoteNotices(Receiver<RemoteNotice>);
+
+/// Instant of the game's first update, shared with the remote worker so
+/// pings carry the same game time as `Time<Real>::elapsed`
+#[derive(Resource, Default)]
+struct GameClockOrigin(Arc<OnceLock<Instant>>);
+
+/// Publish the first update instant once `Time<Real>` knows it
+fn share_game_clock_origin(time: Option<Res<Time<Real>>>, origin: Res<GameClockOrigin>) {
+    if let Some(first_update) = time.and_then(|time| time.first_update()) {
+        origin.0.get_or_init(|| first_update);
+    }
+}
+
+/// Apply status changes and forward sensor events into Bevy
+fn apply_remote_notices(
+    notices: Res<RemoteNotices>,
+    mut status: ResMut<SensorStatus>,
+    mut clock_sync: ResMut<ClockSync>,
+    mut calibration: EventWriter<CalibrationProgressEvent>,
+    mut faults: EventWriter<SensorFaultEvent>,
+    mut results: EventWriter<SensorCommandResult>,
+) {
+    while let Ok(notice) = notices.0.try_recv() {
+        match notice {
+            RemoteNotice::Status(new_status) => {
+                if *status != new_status {
+                    tracing::info!("Remote sensor status: {:?} -> {:?}", *status, new_status);
+                    *status = new_status;
+                }
+            }
+            RemoteNotice::Calibration(event) => {
+                calibration.write(event);
+            }
+            RemoteNotice::Fault(event) => {
+                faults.write(event);
+            }
+            RemoteNotice::Command(event) => {
+                if let (SensorCommand::CaptureBugReport, Some(path)) = (event.command, &event.detail) {
+                    tracing::info!("Sensor bug report written to {}", path);
+                }
+                results.write(event);
+            }
+            RemoteNotice::ClockSync(estimate) => {
+                clock_sync.estimate = Some(estimate);
+            }
+        }
+    }
+}
+
+/// Request a sensor bug report when the debug key is pressed
+fn capture_bug_report_on_key(
+    keys: Option<Res<ButtonInput<KeyCode>>>,
+    key: Res<BugReportKey>,
+    commands: Res<SensorCommands>,
+) {
+    if keys.is_some_and(|keys| keys.just_pressed(key.0)) && !commands.capture_bug_report() {
+        tracing::warn!("Sensor command queue is closed, bug report not requested");
+    }
+}
+
+/// Run `run` on a dedicated `fear-sensor-<label>` thread with its own tokio
+/// runtime, reporting a failure to start through `notices`
+fn spawn_sensor_thread<F, Fut>(label: &'static str, notices: Sender<RemoteNotice>, run: F)
+where
+    F: FnOnce(Sender<RemoteNotice>) -> Fut + Send + 'static,
+    Fut: Future<Output = ()>,
+{
+    let failure_notices = notices.clone();
+    let spawned = std::thread::Builder::new()
+        .name(format!("fear-sensor-{}", label))
+        .spawn(move || {
+            let runtime = match tokio::runtime::Builder::new_current_thread().enable_all().build() {
+                Ok(runtime) => runtime,
+                Err(e) => {
+                    let _ = notices.try_send(RemoteNotice::Status(SensorStatus::Failed {
+                        reason: format!("Failed to start runtime: {}", e),
+                    }));
+                    return;
+                }
+            };
+            runtime.block_on(run(notices));
+        });
+
+    if let Err(e) = spawned {
+        let _ = failure_notices.try_send(RemoteNotice::Status(SensorStatus::Failed {
+            reason: format!("Failed to spawn {} sensor thread: {}", label, e),
+        }));
+    }
+}
+
+/// Run the remote client on a dedicated thread with its own tokio runtime
+fn spawn_remote_worker(
+    source: RemoteFearSource,
+    game_clock: Arc<OnceLock<Instant>>,
+    frames: Sender<FearFrame>,
+    notices: Sender<RemoteNotice>,
+    commands: Receiver<SensorCommand>,
+) {
+    spawn_sensor_thread("remote", notices, move |notices| async move {
+        // Giving up is already reported through the status
+        let _ = run_remote(source, game_clock, frames, notices, commands).await;
+    });
+}
+
+/// Samples before mock frames count as calibrated; replayed sequences are
+/// already normalized, so there is no baseline worth waiting 30 seconds for
+const MOCK_CALIBRATION_SAMPLES: usize = 20;
+
+/// Run `MockFearSensor` on a dedicated thread with its own tokio runtime
+fn spawn_mock_worker(sequence: Vec<f32>, frames: Sender<FearFrame>, notices: Sender<RemoteNotice>) {
+    spawn_sensor_thread("mock", notices, move |notices| run_mock(sequence, frames, notices));
+}
+
+/// Mock samples each level of the step pattern is held for (about a second)
+const MOCK_STEP_HOLD: usize = 30;
+
+/// The mock step pattern with each level held for [`MOCK_STEP_HOLD`] samples
+fn mock_step_sequence() -> Vec<f32> {
+    mock_patterns::step()
+        .into_iter()
+        .flat_map(|fear| std::iter::repeat_n(fear, MOCK_STEP_HOLD))
+        .collect()
+}
+
+/// Forward mock scores until the game drops its receivers
+async fn run_mock(sequence: Vec<f32>, frames: Sender<FearFrame>, notices: Sender<RemoteNotice>) {
+    if sequence.is_empty() {
+        let _ = notices.try_send(RemoteNotice::Status(SensorStatus::Failed {
+            reason: "Mock fear sequence is empty".to_string(),
+        }));
+        return;
+    }
+
+    let mut sensor = MockFearSensor::new(sequence).with_calibration_target(MOCK_CALIBRATION_SAMPLES);
+    let started = match sensor.initialize(&FearConfig::default()).await {
+        Ok(()) => sensor.start().await,
+        Err(e) => Err(e),
+    };
+    let scores = match started {
+        Ok(scores) => scores,
+        Err(e) => {
+            let _ = notices.try_send(RemoteNotice::Status(SensorStatus::Failed {
+                reason: format!("Mock sensor failed to start: {}", e),
+            }));
+            return;
+        }
+    };
+
+    if notices.try_send(RemoteNotice::Status(SensorStatus::Running)).is_err() {
+        return;
+    }
+
+    // The mock has no sensor pipeline, so derive startle here like the sensor does
+    let stream_start = Instant::now();
+    let mut startle_detector = StartleDetector::default();
+    while let Ok(score) = scores.recv().await {
+        let frame = mock_score_to_frame(score);
+        let startle = startle_detector.update(stream_start.elapsed(), frame.fear_score, frame.calibrated);
+        match frames.try_send(frame.with_startle(startle)) {
+            Ok(()) | Err(TrySendError::Full(_)) => {}
+            Err(TrySendError::Closed(_)) => break,
+        }
+    }
+}
+
+/// Forward YuNet sensor frames, falling back to the mock step pattern when
+/// the sensor cannot start or its stream ends while the game is running
+async fn run_yunet(config: FearConfig, frames: Sender<FearFrame>, notices: Sender<RemoteNotice>) {
+    let mut sensor = YuNetFearSensor::new();
+    let reason = match start_yunet(&mut sensor, &config).await {
+        Ok(mut subscriber) => {
+            if notices.try_send(RemoteNotice::Status(SensorStatus::Running)).is_err() {
+                return;
+            }
+            loop {
+                let Some(frame) = subscriber.recv().await else {
+                    break "frame stream ended".to_string();
+                };
+                match frames.try_send(frame) {
+                    Ok(()) | Err(TrySendError::Full(_)) => {}
+                    Err(TrySendError::Closed(_)) => {
+                        let _ = sensor.stop().await;
+                        return;
+                    }
+                }
+            }
+        }
+        Err(reason) => reason,
+    };
+
+    tracing::warn!("YuNet sensor unavailable ({}), falling back to the mock sensor", reason);
+    let _ = sensor.stop().await;
+    run_mock(mock_step_sequence(), frames, notices).await;
+}
+
+/// Initialize and start the YuNet sensor, subscribing to its frames
+async fn start_yunet(sensor: &mut YuNetFearSensor, config: &FearConfig) -> Result<FrameSubscriber<FearFrame>, String> {
+    sensor.initialize(config).await.map_err(|e| e.to_string())?;
+    // Scores are not needed; the frames carry everything the game uses
+    drop(sensor.start().await.map_err(|e| e.to_string())?);
+    sensor.subscribe_frames().ok_or_else(|| "started sensor has no frame stream".to_string())
+}
+
+/// Forward every frame of a recorded session, falling back to the mock step
+/// pattern when the recording cannot be played
+///
+/// Frames wait for room in the channel instead of being dropped, so every
+/// run feeds the game the same curve. A replay that plays to the end
+/// settles on [`SensorStatus::Stopped`].
+async fn run_replay(source: ReplayFearSource, frames: Sender<FearFrame>, notices: Sender<RemoteNotice>) {
+    let reason = match start_replay(&source).await {
+        Ok((mut sensor, mut subscriber)) => {
+            if notices.try_send(RemoteNotice::Status(SensorStatus::Running)).is_err() {
+                return;
+            }
+            while let Some(frame) = subscriber.recv().await {
+                if frames.send(frame).await.is_err() {
+                    let _ = sensor.stop().await;
+                    return;
+                }
+            }
+            let _ = notices.try_send(RemoteNotice::Status(SensorStatus::Stopped {
+                reason: format!("Replay of {} finished", source.path.display()),
+            }));
+            return;
+        }
+        Err(reason) => reason,
+    };
+
+    tracing::warn!("Recording unavailable ({}), falling back to the mock sensor", reason);
+    run_mock(mock_step_sequence(), frames, notices).await;
+}
+
+/// Load and start the recording, subscribing to its frames
+async fn start_replay(source: &ReplayFearSource) -> Result<(ReplayFearSensor, FrameSubscriber<FearFrame>), String> {
+    let mut sensor = ReplayFearSensor::open(&source.path)
+        .map_err(|e| e.to_string())?
+        .with_speed(source.speed)
+        .with_looping(source.looping);
+    sensor.initialize(&FearConfig::default()).await.map_err(|e| e.to_string())?;
+    // Scores are not needed; the frames carry everything the game uses
+    drop(sensor.start().await.map_err(|e| e.to_string())?);
+    let subscriber = sensor.subscribe_frames().ok_or_else(|| "started replay has no frame stream".to_string())?;
+    Ok((sensor, subscriber))
+}
+
+/// Convert a legacy mock score into a frame
+fn mock_score_to_frame(score: FearScore) -> FearFrame {
+    FearFrame::new(
+        score.provisional_value(),
+        score.emotion_logits,
+        score.confidence,
+        score.calibrated,
+        Duration::ZERO,
+    )
+    .with_face_present(score.face_present)
+    .with_capture_time(score.captured_at)
+    .with_sequence(score.sequence)
+}
+
+/// Why a streaming session ended
+enum SessionEnd {
+    /// The game dropped its side of the channels
+    Shutdown,
+    /// The connection or stream failed
+    Disconnected(String),
+    /// The sensor announced it stopped; reconnecting would not bring it back
+    Stopped(String),
+}
+
+/// Connect, stream and reconnect until the game shuts down, retries run out
+/// or the sensor announces it stopped
+///
+/// Returns the failure reason when retries run out.
+async fn run_remote(
+    source: RemoteFearSource,
+    game_clock: Arc<OnceLock<Instant>>,
+    frames: Sender<FearFrame>,
+    notices: Sender<RemoteNotice>,
+    commands: Receiver<SensorCommand>,
+) -> Result<(), String> {
+    let mut attempt = 0u32;
+    let mut delay = source.reconnect_delay;
+
+    loop {
+        match connect(&source).await {
+            Ok(client) => {
+                attempt = 0;
+                delay = source.reconnect_delay;
+                let clock_sync = ClockSyncContext {
+                    origin: &game_clock,
+                    interval: source.clock_sync_interval,
+                };
+                match stream_session(client, source.delta, clock_sync, &frames, &notices, &commands).await {
+                    SessionEnd::Shutdown => return Ok(()),
+                    SessionEnd::Disconnected(reason) => {
+                        tracing::warn!("Remote sensor stream lost: {}", reason);
+                    }
+                    SessionEnd::Stopped(reason) => {
+                        tracing::info!("Remote sensor stopped: {}", reason);
+                        let _ = notices.try_send(RemoteNotice::Status(SensorStatus::Stopped { reason }));
+                        return Ok(());
+                    }
+                }
+            }
+            Err(e) => tracing::warn!("Failed to connect to sensor at {}: {}", source.address, e),
+        }
+
+        attempt += 1;
+        if source.max_attempts.is_some_and(|max| attempt > max) {
+            let reason = format!("Gave up after {} attempts", attempt - 1);
+            let _ = notices.try_send(RemoteNotice::Status(SensorStatus::Failed { reason: reason.clone() }));
+            return Err(reason);
+        }
+        if notices.try_send(RemoteNotice::Status(SensorStatus::Reconnecting { attempt })).is_err() {
+            return Ok(());
+        }
+
+        tokio::time::sleep(delay).await;
+        delay = (delay * 2).min(source.max_reconnect_delay);
+
+        // Commands queued while disconnected cannot reach the sensor
+        while let Ok(command) = commands.try_recv() {
+            let _ = notices.try_send(RemoteNotice::Command(SensorCommandResult {
+                command,
+                success: false,
+                error: Some("Sensor is not connected".to_string()),
+                detail: None,
+            }));
+        }
+    }
+}
+
+/// Connect to the daemon described by `source`
+async fn connect(
+    source: &RemoteFearSource,
+) -> Result<SensorClient, Box<dyn std::error::Error + Send + Sync>> {
+    let transport: SensorTransport = source.address.parse()?;
+    let mut client = SensorClient::connect(&transport).await?;
+    if let Some(encoding) = source.compression {
+        client = client.with_compression(encoding);
+    }
+    Ok(match &source.auth_token {
+        Some(token) => client.with_auth_token(token.clone()),
+        None => client,
+    })
+}
+
+/// What the remote worker needs to run clock synchronization pings
+struct ClockSyncContext<'a> {
+    /// Instant of the game's first update, unset until the first update ran
+    origin: &'a OnceLock<Instant>,
+    interval: Duration,
+}
+
+/// Forward one event stream until it ends or the game shuts down
+async fn stream_session(
+    mut client: SensorClient,
+    delta: Option<DeltaConfig>,
+    clock_sync: ClockSyncContext<'_>,
+    frames: &Sender<FearFrame>,
+    notices: &Sender<RemoteNotice>,
+    commands: &Receiver<SensorCommand>,
+) -> SessionEnd {
+    let mut control = client.clone();
+    let stream = match delta {
+        Some(config) => client.stream_events_delta(config).await.map(StreamExt::boxed),
+        None => client.stream_events().await.map(StreamExt::boxed),
+    };
+    let mut stream = match stream {
+        Ok(stream) => stream,
+        Err(status) => return SessionEnd::Disconnected(status.to_string()),
+    };
+
+    if notices.try_send(RemoteNotice::Status(SensorStatus::Running)).is_err() {
+        return SessionEnd::Shutdown;
+    }
+
+    let mut estimator = ClockSyncEstimator::default();
+    let mut ping_timer = tokio::time::interval(clock_sync.interval.max(Duration::from_millis(1)));
+    ping_timer.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+    let mut pinging = true;
+
+    loop {
+        tokio::select! {
+            event = stream.next() => match event {
+                Some(Ok(event)) => {
+                    let stopped = stop_reason(&event);
+                    if !forward_event(event, frames, notices) {
+                        return SessionEnd::Shutdown;
+                    }
+                    if let Some(reason) = stopped {
+                        return SessionEnd::Stopped(reason);
+                    }
+                }
+                Some(Err(status)) => return SessionEnd::Disconnected(status.to_string()),
+                None => return SessionEnd::Disconnected("stream ended".to_string()),
+            },
+            command = commands.recv() => match command {
+                Ok(command) => {
+                    let result = execute_command(&mut control, command).await;
+                    if notices.try_send(RemoteNotice::Command(result)).is_err() {
+                        return SessionEnd::Shutdown;
+                    }
+                }
+                Err(_) => return SessionEnd::Shutdown,
+            },
+            _ = ping_timer.tick(), if pinging => {
+                let Some(origin) = clock_sync.origin.get() else {
+                    continue;
+                };
+                match ping(&mut control, *origin, &mut estimator).await {
+                    Ok(Some(estimate)) => {
+                        if notices.try_send(RemoteNotice::ClockSync(estimate)).is_err() {
+                            return SessionEnd::Shutdown;
+                        }
+                    }
+                    Ok(None) => {}
+                    Err(reason) => {
+                        // Older daemons do not implement Ping; don't retry until reconnecting
+                        tracing::debug!("Clock sync disabled for this session: {}", reason);
+                        pinging = false;
+                    }
+                }
+            },
+        }
+    }
+}
+
+/// One clock synchronization round trip, timed against the game's first update
+async fn ping(
+    client: &mut SensorClient,
+    origin: Instant,
+    estimator: &mut ClockSyncEstimator,
+) -> Result<Option<ClockSyncEstimate>, String> {
+    let game_sent = origin.elapsed();
+    let reply = client
+        .ping(game_sent, estimator.estimate())
+        .await
+        .map_err(|status| status.message().to_string())?;
+    let game_received = origin.elapsed();
+
+    Ok(estimator.add(ClockSample {
+        game_sent,
+        sensor_monotonic: Duration::from_micros(reply.sensor_monotonic_us),
+        sensor_wall_us: reply.sensor_wall_us,
+        game_received,
+    }))
+}
+
+/// Reason carried by a `SENSOR_STOPPED` fault, the last event of a stream
+fn stop_reason(event: &SensorEvent) -> Option<String> {
+    match &event.event {
+        Some(sensor_event::Event::SensorFault(fault)) if fault.error_code == SENSOR_STOPPED => {
+            Some(fault.message.clone())
+        }
+        _ => None,
+    }
+}
+
+/// Route a streamed event to the frame channel or the notice channel
+///
+/// Returns false once the game has dropped its receivers.
+fn forward_event(
+    event: SensorEvent,
+    frames: &Sender<FearFrame>,
+    notices: &Sender<RemoteNotice>,
+) -> bool {
+    match event.event {
+        Some(sensor_event::Event::Score(score)) => match frames.try_send(event_frame(&score, event.timestamp_us)) {
+            Ok(()) => true,
+            Err(TrySendError::Full(_)) => {
+                tracing::debug!("Dropped remote frame due to back-pressure");
+                true
+            }
+            Err(TrySendError::Closed(_)) => false,
+        },
+        Some(sensor_event::Event::CalibrationProgress(progress)) => notices
+            .try_send(RemoteNotice::Calibration(CalibrationProgressEvent {
+                progress: progress.progress,
+                completed: progress.completed,
+                baseline: progress.baseline.map(|baseline| (baseline.mean, baseline.std_dev)),
+            }))
+            .is_ok(),
+        Some(sensor_event::Event::SensorFault(fault)) => notices
+            .try_send(RemoteNotice::Fault(SensorFaultEvent {
+                message: fault.message,
+                error_code: fault.error_code,
+                recoverable: fault.recoverable,
+            }))
+            .is_ok(),
+        _ => true,
+    }
+}
+
+/// Send a command to the daemon
+async fn execute_command(client: &mut SensorClient, command: SensorCommand) -> SensorCommandResult {
+    let response = match command {
+        SensorCommand::FreezeCalibration(true) => client.freeze_calibration().await.map(calibration_outcome),
+        SensorCommand::FreezeCalibration(false) => client.unfreeze_calibration().await.map(calibration_outcome),
+        SensorCommand::ResetCalibration => client.reset_calibration().await.map(calibration_outcome),
+        SensorCommand::CaptureBugReport => client.capture_bug_report().await.map(|response| {
+            let path = Some(response.bundle_path).filter(|path| !path.is_empty());
+            (response.success, response.error_message, path)
+        }),
+    };
+
+    match response {
+        Ok((success, error, detail)) => SensorCommandResult {
+            command,
+            success,
+            error,
+            detail,
+        },
+        Err(status) => SensorCommandResult {
+            command,
+            success: false,
+            error: Some(status.message().to_string()),
+            detail: None,
+        },
+    }
+}
+
+/// Success, error and detail of a calibration response
+fn calibration_outcome(response: CalibrationResponse) -> (bool, Option<String>, Option<String>) {
+    (response.success, response.error_message, None)
+}
+
+/// Convert a streamed score into the frame type consumed by `FearState`
+pub fn score_to_frame(score: &Score) -> FearFrame {
+    FearFrame::new(
+        score.normalized_fear,
+        score_logits(score),
+        score.confidence,
+        score.calibrated,
+        Duration::from_micros(score.inference_latency_us),
+    )
+    .with_startle(score.startle)
+    .with_capability(score.capability().into())
+    .with_face_bbox(score.face_bbox.as_ref().map(|bbox| NormalizedRect {
+        x: bbox.x,
+        y: bbox.y,
+        width: bbox.width,
+        height: bbox.height,
+    }))
+    .with_face_present(score_face_present(score))
+    .with_sequence(score.frame_sequence)
+}
+
+/// Convert a streamed score, stamped with its event's capture time when
+/// the daemon set one
+fn event_frame(score: &Score, timestamp_us: u64) -> FearFrame {
+    let frame = score_to_frame(score);
+    match timestamp_us {
+        0 => frame,
+        micros => frame.with_capture_time(UNIX_EPOCH + Duration::from_micros(micros)),
+    }
+}
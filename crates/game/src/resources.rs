@@ -1,98 +1,420 @@
 //! ECS Resources for SpectreMesh
 
-use bevy::prelude::*;
-use spectremesh_core::types::{FearScore, FearFrame, FearBucket};
-use async_channel::Receiver;
-use std::time::Instant;
+use bevy::{
+    prelude::*,
+    tasks::{block_on, AsyncComputeTaskPool, Task},
+};
+use spectremesh_core::types::{
+    Emotion, EmotionVector, FearScore, FearFrame, FearBucket, FearBucketSmoother, SensorCapability,
+};
+use async_channel::{Receiver, Sender};
+use crate::components::TerrainChunk;
+use crate::fear_band::{BandDistribution, BucketDwell};
+use crate::fear_journal::FearEvent;
+use crate::terrain_mesh::density_mesh;
+use spectre_sensor::{
+    clock_sync::ClockSyncEstimate,
+    fanout::{FrameFanout, FrameSubscriber},
+};
+use spectremesh_core::{ConfigError, FearMapping, TerrainConfig};
+use spectremesh_terrain::{
+    ChunkCoord, ChunkParams, ChunkStore, DensityChunk, DensityMesh, FearMemoryConfig, MarchingCubesGenerator,
+    MeshPoolStats, NoiseField, StoredChunk, TerrainMap, TerrainSave,
+};
+use std::collections::{HashMap, VecDeque};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// Startle samples kept in `FearState::startle_history` (about 4s at 30 FPS)
+pub const STARTLE_HISTORY_LEN: usize = 120;
+
+/// Default `FearState::face_smoothing`: the newest face center's weight
+pub const DEFAULT_FACE_SMOOTHING: f32 = 0.2;
+
+/// Default `GameConfig::fear_time_constant`
+pub const DEFAULT_FEAR_TIME_CONSTANT: Duration = Duration::from_millis(500);
+
+/// Default `GameConfig::min_frame_confidence`
+pub const DEFAULT_MIN_FRAME_CONFIDENCE: f32 = 0.1;
+
+/// Default `GameConfig::signal_timeout`
+pub const DEFAULT_SIGNAL_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// Game-side tuning, applied to [`FearState`] by `update_fear_system` and
+/// `smooth_fear_system`
+#[derive(Resource, Debug, Clone, PartialEq)]
+pub struct GameConfig {
+    /// Time constant of `FearState::smoothed_fear`: after a step in fear
+    /// the smoothed value has covered 63% of it in this long
+    pub fear_time_constant: Duration,
+    /// Frames measuring fear with less confidence than this are skipped
+    pub min_frame_confidence: f32,
+    /// `FearSignalLost` is raised once no frame has been applied for this long
+    pub signal_timeout: Duration,
+    /// Bucket boundaries and distortion intensities; an invalid mapping is
+    /// rejected and the one in use kept
+    pub fear_mapping: FearMapping,
+}
+
+impl Default for GameConfig {
+    fn default() -> Self {
+        Self {
+            fear_time_constant: DEFAULT_FEAR_TIME_CONSTANT,
+            min_frame_confidence: DEFAULT_MIN_FRAME_CONFIDENCE,
+            signal_timeout: DEFAULT_SIGNAL_TIMEOUT,
+            fear_mapping: FearMapping::default(),
+        }
+    }
+}
+
+impl GameConfig {
+    /// Set the fear smoothing time constant; zero follows fear unsmoothed
+    pub fn with_fear_time_constant(mut self, time_constant: Duration) -> Self {
+        self.fear_time_constant = time_constant;
+        self
+    }
+
+    /// Set the confidence below which frames are skipped; zero keeps them all
+    pub fn with_min_frame_confidence(mut self, confidence: f32) -> Self {
+        self.min_frame_confidence = confidence.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Set how long without frames counts as a lost signal
+    pub fn with_signal_timeout(mut self, timeout: Duration) -> Self {
+        self.signal_timeout = timeout;
+        self
+    }
+
+    /// Set how fear maps to buckets and distortion intensities
+    pub fn with_fear_mapping(mut self, mapping: FearMapping) -> Self {
+        self.fear_mapping = mapping;
+        self
+    }
+
+    /// Validate the configuration
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        self.fear_mapping.validate()
+    }
+}
 
 /// Resource for managing fear sensor state and integration
 #[derive(Resource)]
 pub struct FearState {
     /// Current normalized fear level [0.0, 1.0]
     pub current_fear: f32,
+    /// `current_fear` exponentially smoothed by [`FearState::tick`] [0.0, 1.0]
+    pub smoothed_fear: f32,
+    /// Time constant of `smoothed_fear` (see [`GameConfig`])
+    pub fear_time_constant: Duration,
+    /// Current startle (rate-of-change) signal [0.0, 1.0]
+    pub current_startle: f32,
+    /// Model confidence of the current fear level [0.0, 1.0]
+    pub current_confidence: f32,
+    /// Frames measuring fear with less confidence are skipped (see
+    /// [`GameConfig`])
+    pub min_frame_confidence: f32,
+    /// Recent startle values, oldest first
+    pub startle_history: VecDeque<f32>,
     /// Current fear bucket for terrain updates
     pub current_bucket: FearBucket,
     /// Previous fear bucket (to detect changes)
     pub previous_bucket: FearBucket,
+    /// Bucket boundaries and the distortion intensities fear maps to
+    pub fear_mapping: FearMapping,
+    /// Hysteresis and dwell applied before `current_bucket` follows fear
+    pub bucket_smoother: FearBucketSmoother,
     /// Whether the sensor is calibrated
     pub calibrated: bool,
-    /// Receiver for fear frames from sensor
-    pub receiver: Option<Receiver<FearFrame>>,
+    /// Mean and standard deviation of the sensor's baseline, from the last
+    /// calibration progress that reported it
+    pub baseline: Option<(f32, f32)>,
+    /// What the sensor can currently measure; fall back to scripted
+    /// behaviour when fear is unavailable
+    pub sensor_capability: SensorCapability,
+    /// Subscription to the sensor's frame stream (see `FearFrames`)
+    pub receiver: Option<FrameSubscriber<FearFrame>>,
     /// Last update timestamp
     pub last_update: Instant,
-    /// Distortion intensity for shader uniforms
+    /// The signal counts as lost once no frame has been applied for this
+    /// long (see [`GameConfig`])
+    pub signal_timeout: Duration,
+    /// Frames stopped arriving for `signal_timeout` and have not resumed
+    pub signal_lost: bool,
+    /// Frames taken from `receiver`, applied or skipped
+    pub frames_received: u64,
+    /// Frames the sensor numbered but the game never saw, from jumps in
+    /// `FearFrame::sequence`
+    pub frames_dropped: u64,
+    /// Sequence number of the last numbered frame received
+    pub last_sequence: u64,
+    /// Distortion intensity for shader uniforms, a continuous function of
+    /// `smoothed_fear`; terrain meshes use the bucket's intensity instead
     pub distortion_intensity: f32,
     /// Whether terrain needs rebuilding
     pub terrain_needs_rebuild: bool,
+    /// Smoothed face center in normalized frame coordinates; `None` while no
+    /// face is in frame
+    pub face_center: Option<(f32, f32)>,
+    /// Weight of each new face center in `face_center` (1.0 follows the raw
+    /// center, smaller values move more smoothly)
+    pub face_smoothing: f32,
+    /// Whether the sensor sees a face; while it does not, fear and the
+    /// terrain hold where they were
+    pub face_present: bool,
+    /// Emotion probabilities of the last frame measuring them; neutral
+    /// until one arrives, and held while no face is in frame
+    pub current_emotions: EmotionVector,
 }
 
 impl Default for FearState {
     fn default() -> Self {
+        let neutral_fear = 0.3;
         Self {
-            current_fear: 0.3, // Neutral fear level
+            current_fear: neutral_fear,
+            smoothed_fear: neutral_fear,
+            fear_time_constant: DEFAULT_FEAR_TIME_CONSTANT,
+            current_startle: 0.0,
+            current_confidence: 0.0,
+            min_frame_confidence: DEFAULT_MIN_FRAME_CONFIDENCE,
+            startle_history: VecDeque::with_capacity(STARTLE_HISTORY_LEN),
             current_bucket: FearBucket::Low,
             previous_bucket: FearBucket::Low,
+            fear_mapping: FearMapping::default(),
+            bucket_smoother: FearBucketSmoother::default(),
             calibrated: false,
+            baseline: None,
+            sensor_capability: SensorCapability::Full,
             receiver: None,
             last_update: Instant::now(),
-            distortion_intensity: 0.1, // Low distortion by default
+            signal_timeout: DEFAULT_SIGNAL_TIMEOUT,
+            signal_lost: false,
+            frames_received: 0,
+            frames_dropped: 0,
+            last_sequence: 0,
+            distortion_intensity: FearMapping::default().distortion_intensity(neutral_fear),
             terrain_needs_rebuild: false,
+            face_center: None,
+            face_smoothing: DEFAULT_FACE_SMOOTHING,
+            face_present: true,
+            current_emotions: EmotionVector::default(),
         }
     }
 }
 
 impl FearState {
-    /// Update fear state from a new frame
+    /// Update fear state from a new frame, unless it is too unsure to use
     pub fn update_from_frame(&mut self, frame: FearFrame) {
-        self.current_fear = frame.fear_score;
-        self.calibrated = frame.calibrated;
-        self.last_update = Instant::now();
+        if self.accepts_frame(&frame) {
+            self.commit(&FearEvent::frame(&frame));
+        }
+    }
 
-        // Update fear bucket and check for changes
-        self.previous_bucket = self.current_bucket;
-        self.current_bucket = FearBucket::from_score(frame.fear_score);
+    /// Count a frame taken from the receiver, and any frames missing
+    /// before it
+    ///
+    /// A sequence number at or below the last one means the sensor started
+    /// counting again, not that frames went missing.
+    pub fn count_frame(&mut self, frame: &FearFrame) {
+        self.frames_received += 1;
+        if frame.sequence == 0 {
+            return;
+        }
+        if self.last_sequence > 0 && frame.sequence > self.last_sequence {
+            self.frames_dropped += frame.sequence - self.last_sequence - 1;
+        }
+        self.last_sequence = frame.sequence;
+    }
 
-        // Update distortion intensity for shaders
-        self.distortion_intensity = self.current_bucket.distortion_intensity();
+    /// Whether `frame` is confident enough to apply
+    ///
+    /// Frames below `min_frame_confidence` are skipped whole, leaving the
+    /// last fear level, so they never reach the journal either. Frames that
+    /// carry no fear measurement, for lack of a face or of emotion
+    /// inference, are always applied for their face and capability.
+    pub fn accepts_frame(&self, frame: &FearFrame) -> bool {
+        let accepted = !frame.face_present
+            || !frame.capability.fear_available()
+            || frame.confidence >= self.min_frame_confidence;
+        if !accepted {
+            tracing::trace!("Skipping frame with confidence {:.2}", frame.confidence);
+        }
+        accepted
+    }
 
-        // Mark terrain for rebuild if bucket changed
-        if self.current_bucket != self.previous_bucket {
-            self.terrain_needs_rebuild = true;
+    /// Update fear state from legacy FearScore
+    pub fn update_from_score(&mut self, score: FearScore) {
+        self.commit(&FearEvent::score(&score));
+    }
+
+    /// Apply a live change: logs capability and bucket changes and stamps
+    /// `last_update` for measurements
+    ///
+    /// [`FearJournal::commit`](crate::fear_journal::FearJournal::commit)
+    /// also records the event for replay.
+    pub fn commit(&mut self, event: &FearEvent) {
+        let (capability, bucket) = (self.sensor_capability, self.current_bucket);
+        self.apply(event);
+
+        if matches!(event, FearEvent::FrameApplied { .. } | FearEvent::ScoreApplied { .. }) {
+            self.last_update = Instant::now();
+        }
+        if self.sensor_capability != capability {
+            tracing::warn!("Sensor capability changed: {:?} -> {:?}", capability, self.sensor_capability);
+        }
+        if self.current_bucket != bucket {
             tracing::info!(
                 "Fear bucket changed: {:?} -> {:?}, marking terrain for rebuild",
-                self.previous_bucket,
+                bucket,
                 self.current_bucket
             );
         }
     }
 
-    /// Update fear state from legacy FearScore
-    pub fn update_from_score(&mut self, score: FearScore) {
-        self.current_fear = score.value;
-        self.calibrated = score.calibrated;
-        self.last_update = Instant::now();
+    /// Apply `event` to the state
+    ///
+    /// The result depends only on the event and the state, never on the
+    /// clock, so replaying a journal reproduces the live state exactly.
+    pub fn apply(&mut self, event: &FearEvent) {
+        match *event {
+            FearEvent::FrameApplied {
+                fear_score,
+                confidence,
+                calibrated,
+                startle,
+                capability,
+                face_center,
+                face_present,
+                emotions,
+            } => {
+                self.sensor_capability = capability;
+                self.face_present = face_present;
+                self.update_face_center(face_center);
 
-        // Update fear bucket and check for changes
-        self.previous_bucket = self.current_bucket;
-        self.current_bucket = FearBucket::from_score(score.value);
+                // Keep the last fear level when the sensor cannot measure it,
+                // so the terrain holds still until it can
+                if !face_present || !capability.fear_available() {
+                    self.record_startle(0.0);
+                    return;
+                }
+
+                self.current_fear = fear_score;
+                self.current_confidence = confidence;
+                if let Some(emotions) = emotions {
+                    self.current_emotions = emotions;
+                }
+                self.record_startle(startle);
+                self.calibrated = calibrated;
+                self.commit_bucket(fear_score);
+            }
+            FearEvent::ScoreApplied { value, confidence, calibrated, face_present } => {
+                self.face_present = face_present;
+                self.record_startle(0.0); // Legacy scores carry no startle
+                self.face_center = None; // Legacy scores carry no face position
+                if !face_present {
+                    return;
+                }
 
-        // Update distortion intensity for shaders
-        self.distortion_intensity = self.current_bucket.distortion_intensity();
+                self.current_fear = value;
+                self.current_confidence = confidence;
+                self.calibrated = calibrated;
+                self.commit_bucket(value);
+            }
+            FearEvent::TerrainRebuilt => self.terrain_needs_rebuild = false,
+            FearEvent::ThresholdsChanged { thresholds } => self.fear_mapping.thresholds = thresholds,
+            FearEvent::MappingChanged { mapping } => self.fear_mapping = mapping,
+            FearEvent::FaceSmoothingChanged { weight } => self.face_smoothing = weight,
+        }
+    }
+
+    /// Move the current bucket toward `score` through the smoother, marking
+    /// terrain for rebuild when the bucket changes
+    fn commit_bucket(&mut self, score: f32) {
+        self.previous_bucket = self.current_bucket;
+        self.current_bucket = self.bucket_smoother.next(self.current_bucket, score, &self.fear_mapping.thresholds);
 
-        // Mark terrain for rebuild if bucket changed
         if self.current_bucket != self.previous_bucket {
             self.terrain_needs_rebuild = true;
-            tracing::info!(
-                "Fear bucket changed: {:?} -> {:?}, marking terrain for rebuild",
-                self.previous_bucket,
-                self.current_bucket
-            );
         }
     }
 
+    /// Advance `smoothed_fear` toward `current_fear` by `dt` and derive the
+    /// shader distortion intensity from it
+    ///
+    /// Frames only move the target, so the smoothed value keeps easing
+    /// toward the latest sample between frames and while none arrive. It
+    /// approaches the target without overshooting and stays in [0.0, 1.0].
+    pub fn tick(&mut self, dt: Duration) {
+        let target = self.current_fear;
+        if !target.is_nan() {
+            let time_constant = self.fear_time_constant.as_secs_f32();
+            let alpha = if time_constant > 0.0 {
+                1.0 - (-dt.as_secs_f32() / time_constant).exp()
+            } else {
+                1.0
+            };
+            let step = (target.clamp(0.0, 1.0) - self.smoothed_fear) * alpha;
+            self.smoothed_fear = (self.smoothed_fear + step).clamp(0.0, 1.0);
+        }
+        self.distortion_intensity = self.fear_mapping.distortion_intensity(self.smoothed_fear);
+    }
+
+    /// Where the face sits relative to the middle of the frame, each axis in
+    /// [-1.0, 1.0] (x right, y down); `None` while no face is in frame
+    pub fn face_offset_from_center(&self) -> Option<Vec2> {
+        self.face_center.map(|(x, y)| Vec2::new(x * 2.0 - 1.0, y * 2.0 - 1.0))
+    }
+
+    /// Blend a frame's face center into the smoothed one
+    ///
+    /// A frame without a face clears it rather than leaving a stale position,
+    /// and a face that reappears starts from where it is.
+    fn update_face_center(&mut self, center: Option<(f32, f32)>) {
+        let weight = self.face_smoothing.clamp(0.0, 1.0);
+        self.face_center = center.map(|(x, y)| match self.face_center {
+            Some((smoothed_x, smoothed_y)) => (
+                smoothed_x + (x - smoothed_x) * weight,
+                smoothed_y + (y - smoothed_y) * weight,
+            ),
+            None => (x, y),
+        });
+    }
+
+    /// The most probable emotion in `current_emotions`
+    pub fn dominant_emotion(&self) -> Emotion {
+        self.current_emotions.dominant()
+    }
+
+    /// Whether `current_fear` reflects a live measurement
+    pub fn fear_available(&self) -> bool {
+        self.sensor_capability.fear_available()
+    }
+
+    /// Set the current startle and append it to the history
+    fn record_startle(&mut self, startle: f32) {
+        self.current_startle = startle;
+        if self.startle_history.len() == STARTLE_HISTORY_LEN {
+            self.startle_history.pop_front();
+        }
+        self.startle_history.push_back(startle);
+    }
+
+    /// Largest startle in the history
+    pub fn peak_startle(&self) -> f32 {
+        self.startle_history.iter().copied().fold(0.0, f32::max)
+    }
+
+    /// How far fear has travelled through the current bucket [0.0, 1.0]
+    ///
+    /// Measured against the committed `current_bucket`, so a score that has
+    /// crossed a threshold the bucket has not followed yet reads as 0 or 1.
+    pub fn bucket_progress(&self) -> f32 {
+        self.current_bucket.progress(self.current_fear, &self.fear_mapping.thresholds)
+    }
+
     /// Mark terrain rebuild as complete
     pub fn terrain_rebuilt(&mut self) {
-        self.terrain_needs_rebuild = false;
+        self.apply(&FearEvent::TerrainRebuilt);
     }
 
     /// Check if terrain needs rebuilding
@@ -105,3 +427,425 @@ impl FearState {
         self.distortion_intensity
     }
 }
+
+/// Connection state of the fear sensor as seen by the game
+#[derive(Resource, Debug, Clone, PartialEq, Default)]
+pub enum SensorStatus {
+    /// No sensor source has been started
+    #[default]
+    Idle,
+    /// First connection attempt in progress
+    Connecting,
+    /// Receiving fear frames
+    Running,
+    /// Connection lost, retrying
+    Reconnecting { attempt: u32 },
+    /// Gave up connecting
+    Failed { reason: String },
+    /// The sensor shut down; the game does not reconnect
+    Stopped { reason: String },
+}
+
+/// Shared fear frame stream; every subscriber sees every frame
+///
+/// Clones of a raw frame receiver compete for frames, so consumers besides
+/// `FearState` (recorders, metrics, effect sinks) subscribe here instead.
+#[derive(Resource, Clone)]
+pub struct FearFrames(FrameFanout<FearFrame>);
+
+impl FearFrames {
+    /// Take ownership of the receiving half of a frame channel
+    pub fn new(receiver: Receiver<FearFrame>) -> Self {
+        Self(FrameFanout::new(receiver))
+    }
+
+    /// Subscribe to frames sent from now on
+    pub fn subscribe(&self) -> FrameSubscriber<FearFrame> {
+        self.0.subscribe()
+    }
+}
+
+/// Calibration control actions game code can send to the sensor
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SensorCommand {
+    /// Freeze (true) or unfreeze (false) calibration
+    FreezeCalibration(bool),
+    /// Discard the baseline and recalibrate
+    ResetCalibration,
+    /// Write a bundle of the last few seconds of sensor activity
+    CaptureBugReport,
+}
+
+/// Queue of sensor commands drained by the active sensor source
+#[derive(Resource, Clone)]
+pub struct SensorCommands {
+    sender: Sender<SensorCommand>,
+}
+
+impl SensorCommands {
+    /// Wrap the sending half of a command channel
+    pub fn new(sender: Sender<SensorCommand>) -> Self {
+        Self { sender }
+    }
+
+    /// Queue a command, returning false if the sensor source is gone
+    pub fn send(&self, command: SensorCommand) -> bool {
+        self.sender.try_send(command).is_ok()
+    }
+
+    /// Freeze calibration at its current baseline
+    pub fn freeze_calibration(&self) -> bool {
+        self.send(SensorCommand::FreezeCalibration(true))
+    }
+
+    /// Resume calibration updates
+    pub fn unfreeze_calibration(&self) -> bool {
+        self.send(SensorCommand::FreezeCalibration(false))
+    }
+
+    /// Discard the baseline and recalibrate
+    pub fn reset_calibration(&self) -> bool {
+        self.send(SensorCommand::ResetCalibration)
+    }
+
+    /// Ask the sensor to write a bug report bundle
+    pub fn capture_bug_report(&self) -> bool {
+        self.send(SensorCommand::CaptureBugReport)
+    }
+}
+
+/// Debug key that captures a sensor bug report
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BugReportKey(pub KeyCode);
+
+impl Default for BugReportKey {
+    fn default() -> Self {
+        Self(KeyCode::F9)
+    }
+}
+
+/// Mapping from game time to the sensor's clocks, kept current by the remote source
+#[derive(Resource, Debug, Clone, Copy, Default, PartialEq)]
+pub struct ClockSync {
+    /// Latest estimate; `None` until the first ping round trip
+    pub estimate: Option<ClockSyncEstimate>,
+}
+
+impl ClockSync {
+    /// Sensor wall clock minus game time, in microseconds
+    pub fn offset_us(&self) -> Option<i64> {
+        self.estimate.map(|estimate| estimate.wall_offset_us)
+    }
+
+    /// Trust in the estimate [0.0, 1.0]; zero before the first round trip
+    pub fn confidence(&self) -> f32 {
+        self.estimate.map_or(0.0, |estimate| estimate.confidence)
+    }
+
+    /// Sensor wall timestamp, in microseconds since Unix epoch, of a game time
+    pub fn game_to_sensor_wall_us(&self, game_time: Duration) -> Option<i64> {
+        self.estimate.map(|estimate| estimate.game_to_sensor_wall_us(game_time))
+    }
+}
+
+/// Terrain fear memory for the session, saved with the world
+#[derive(Resource, Debug, Clone, Default)]
+pub struct TerrainMemory {
+    pub map: TerrainMap,
+    /// Chunks whose meshes must be rebuilt because their memory changed,
+    /// until `update_terrain_system` marks them dirty or regenerates them
+    pub pending_rebuilds: Vec<ChunkCoord>,
+    /// Scar blend factor of the chunk the focus entity is in, for material uniforms
+    pub focus_scar_blend: f32,
+}
+
+impl TerrainMemory {
+    pub fn new(config: FearMemoryConfig) -> Self {
+        Self {
+            map: TerrainMap::new(config),
+            ..Default::default()
+        }
+    }
+
+    /// Restore memory from a world save
+    pub fn from_save(save: &TerrainSave, config: FearMemoryConfig) -> Self {
+        Self {
+            map: TerrainMap::from_save(save, config),
+            ..Default::default()
+        }
+    }
+}
+
+/// Terrain layout for the session: the chunk size and ground height of the
+/// chunks `spawn_terrain_chunks_system` keeps over the streamed columns
+#[derive(Resource, Debug, Clone, Default)]
+pub struct TerrainSettings(pub TerrainConfig);
+
+/// Volumetric terrain, meshed from a fear-warped noise field
+///
+/// Each [`TerrainChunk`](crate::components::TerrainChunk) entity, spawned
+/// over the columns [`TerrainStreamer`](crate::TerrainStreamer) loads or by
+/// hand without one, gets a
+/// marching cubes mesh of [`NoiseField`] sampled at the fear bucket's
+/// distortion intensity. Meshes are built on the async compute pool:
+/// `update_terrain_system` queues dirty chunks and starts up to
+/// `max_in_flight` of them, nearest the camera first, and
+/// `collect_terrain_meshes_system` swaps in at most `chunks_per_frame`
+/// finished meshes per frame, fewer once `frame_budget` is spent. A fear
+/// bucket change marks every chunk dirty, so the change never lands in a
+/// single frame. Chunks are scarred by the fear memory of the memory chunk
+/// their centre lies in, and remeshed when `update_fear_memory_system`
+/// reports that memory changed. Build it from the same config as
+/// [`TerrainSettings`] so meshes line up with the chunks. With a
+/// [`ChunkStore`], chunks generated
+/// before, in this session or an earlier one, are read back instead of
+/// sampled and meshed again.
+#[derive(Resource)]
+pub struct DensityTerrain {
+    field: Arc<NoiseField>,
+    mesher: MarchingCubesGenerator,
+    chunk_size: u32,
+    /// Cache of generated chunks and the parameters they are keyed by
+    cache: Option<ChunkCache>,
+    chunks_per_frame: usize,
+    max_in_flight: usize,
+    frame_budget: Duration,
+    queue: VecDeque<(Entity, TerrainChunk)>,
+    /// Level of detail each mesh is being built at, and its task
+    in_flight: HashMap<Entity, (u8, Task<Mesh>)>,
+    /// Distortion intensity of the rebuild in progress
+    rebuilding: Option<f32>,
+}
+
+impl DensityTerrain {
+    /// Terrain in `config.chunk_size` chunks; the same seed always gives the same terrain
+    pub fn new(config: &TerrainConfig, seed: u64) -> Self {
+        Self {
+            field: Arc::new(NoiseField::new(config, seed)),
+            mesher: MarchingCubesGenerator::default(),
+            chunk_size: config.chunk_size,
+            cache: None,
+            chunks_per_frame: 4,
+            max_in_flight: 8,
+            frame_budget: Duration::from_millis(4),
+            queue: VecDeque::new(),
+            in_flight: HashMap::new(),
+            rebuilding: None,
+        }
+    }
+
+    /// Read chunks back from `store` when it has them, and keep the ones
+    /// generated there; `config` and `seed` must be the ones the terrain was
+    /// built with
+    pub fn with_chunk_store(mut self, store: Arc<ChunkStore>, config: &TerrainConfig, seed: u64) -> Self {
+        self.cache = Some(ChunkCache {
+            store,
+            params: ChunkParams::new(config, seed),
+        });
+        self
+    }
+
+    /// Swap in at most `chunks` chunk meshes per frame (at least one)
+    pub fn with_chunks_per_frame(mut self, chunks: usize) -> Self {
+        self.chunks_per_frame = chunks.max(1);
+        self
+    }
+
+    /// Build at most `chunks` chunk meshes at once (at least one)
+    pub fn with_max_in_flight(mut self, chunks: usize) -> Self {
+        self.max_in_flight = chunks.max(1);
+        self
+    }
+
+    /// Stop swapping in meshes for the frame once this much time is spent;
+    /// at least one finished mesh is swapped in every frame
+    pub fn with_frame_budget(mut self, budget: Duration) -> Self {
+        self.frame_budget = budget;
+        self
+    }
+
+    pub fn chunk_size(&self) -> u32 {
+        self.chunk_size
+    }
+
+    pub fn chunks_per_frame(&self) -> usize {
+        self.chunks_per_frame
+    }
+
+    pub fn max_in_flight(&self) -> usize {
+        self.max_in_flight
+    }
+
+    pub fn frame_budget(&self) -> Duration {
+        self.frame_budget
+    }
+
+    /// Chunks waiting for a mesh, queued or being built
+    pub fn pending(&self) -> usize {
+        self.queue.len() + self.in_flight.len()
+    }
+
+    /// Chunk meshes being built, or built and waiting to be swapped in
+    pub fn in_flight(&self) -> usize {
+        self.in_flight.len()
+    }
+
+    /// Distortion intensity of the rebuild in progress, if any
+    pub fn rebuilding(&self) -> Option<f32> {
+        self.rebuilding
+    }
+
+    /// World transform placing a chunk's mesh at its origin
+    pub fn chunk_transform(&self, coord: ChunkCoord) -> Transform {
+        Transform::from_translation(Vec3::from_array(coord.to_world_origin(self.chunk_size as f32)))
+    }
+
+    /// Chunk of the fear memory map the centre of the chunk at `coord` lies in
+    pub fn memory_chunk(&self, coord: ChunkCoord) -> ChunkCoord {
+        let chunk_size = self.chunk_size as f32;
+        ChunkCoord::from_world(coord.to_world_origin(chunk_size).map(|v| v + chunk_size / 2.0))
+    }
+
+    /// Mesh of the chunk at `coord` and level of detail `lod` for a
+    /// distortion intensity, scarred by `fear_memory`
+    pub fn mesh_chunk(&self, coord: ChunkCoord, lod: u8, intensity: f32, fear_memory: f32) -> DensityMesh {
+        let (field, cache) = (&self.field, self.cache.as_ref());
+        sample_density_mesh(field, self.mesher, cache, coord, self.chunk_size, lod, intensity, fear_memory)
+    }
+
+    /// Queue every chunk in `chunks` for a rebuild at `intensity`, dropping
+    /// the one in progress along with the meshes it was building
+    pub(crate) fn start_rebuild(&mut self, intensity: f32, chunks: impl IntoIterator<Item = (Entity, TerrainChunk)>) {
+        self.queue.clear();
+        self.in_flight.clear();
+        self.queue.extend(chunks);
+        self.rebuilding = Some(intensity);
+    }
+
+    /// Queue dirty chunks that are not being built once the queued ones are all started
+    pub(crate) fn queue_dirty(&mut self, chunks: impl IntoIterator<Item = (Entity, TerrainChunk)>) {
+        if self.queue.is_empty() {
+            let in_flight = &self.in_flight;
+            self.queue.extend(chunks.into_iter().filter(|(entity, _)| !in_flight.contains_key(entity)));
+        }
+    }
+
+    /// Drop the mesh in flight for `entity`, built from what its chunk was
+    /// before; the chunk is queued again while dirty
+    pub(crate) fn restart(&mut self, entity: Entity) {
+        self.in_flight.remove(&entity);
+    }
+
+    /// Drop queued chunks and meshes in flight whose chunk entity is gone
+    pub(crate) fn retain_chunks(&mut self, exists: impl Fn(Entity) -> bool) {
+        self.queue.retain(|(entity, _)| exists(*entity));
+        self.in_flight.retain(|entity, _| exists(*entity));
+    }
+
+    /// Start building queued chunk meshes at `intensity` up to
+    /// `max_in_flight`, those nearest `camera` first, each scarred by its
+    /// current fear memory in `memory`
+    pub(crate) fn dispatch(&mut self, intensity: f32, camera: Option<Vec3>, memory: &TerrainMap) {
+        if self.in_flight.len() >= self.max_in_flight || self.queue.is_empty() {
+            return;
+        }
+        if let Some(camera) = camera {
+            let centre = ChunkCoord::from_world_pos(camera.to_array(), self.chunk_size as f32);
+            let distance = |coord: &ChunkCoord| {
+                let (dx, dy, dz) = (coord.x - centre.x, coord.y - centre.y, coord.z - centre.z);
+                (coord.chebyshev_distance(&centre), dx * dx + dy * dy + dz * dz)
+            };
+            self.queue.make_contiguous().sort_by_key(|(_, chunk)| distance(&chunk.coord));
+        }
+
+        let pool = AsyncComputeTaskPool::get();
+        while self.in_flight.len() < self.max_in_flight {
+            let Some((entity, chunk)) = self.queue.pop_front() else {
+                break;
+            };
+            let field = Arc::clone(&self.field);
+            let (mesher, chunk_size, cache) = (self.mesher, self.chunk_size, self.cache.clone());
+            let fear_memory = memory.fear_memory(self.memory_chunk(chunk.coord));
+            let task = pool.spawn(async move {
+                let (coord, lod) = (chunk.coord, chunk.lod);
+                let mesh = sample_density_mesh(&field, mesher, cache.as_ref(), coord, chunk_size, lod, intensity, fear_memory);
+                density_mesh(&mesh)
+            });
+            self.in_flight.insert(entity, (chunk.lod, task));
+        }
+    }
+
+    /// A chunk mesh that has finished building, its chunk's entity and the
+    /// level of detail it was built at
+    pub(crate) fn take_finished(&mut self) -> Option<(Entity, Mesh, u8)> {
+        let entity = *self.in_flight.iter().find(|(_, (_, task))| task.is_finished())?.0;
+        let (lod, task) = self.in_flight.remove(&entity)?;
+        Some((entity, block_on(task), lod))
+    }
+
+    /// End the rebuild in progress once every queued chunk is rebuilt
+    pub(crate) fn finish_rebuild(&mut self) -> bool {
+        if self.pending() > 0 {
+            return false;
+        }
+        self.rebuilding = None;
+        true
+    }
+}
+
+/// Chunk store of a [`DensityTerrain`] and the parameters its chunks share
+#[derive(Clone)]
+struct ChunkCache {
+    store: Arc<ChunkStore>,
+    params: ChunkParams,
+}
+
+/// Marching cubes mesh of `field` over the chunk at `coord`, shared by
+/// [`DensityTerrain::mesh_chunk`] and the mesh tasks; read from and kept in
+/// `cache` when there is one
+#[allow(clippy::too_many_arguments)]
+fn sample_density_mesh(
+    field: &NoiseField,
+    mesher: MarchingCubesGenerator,
+    cache: Option<&ChunkCache>,
+    coord: ChunkCoord,
+    chunk_size: u32,
+    lod: u8,
+    intensity: f32,
+    fear_memory: f32,
+) -> DensityMesh {
+    let density = |[x, y, z]: [f32; 3]| field.sample_scarred(x, y, z, intensity, fear_memory);
+    let mesh = |chunk: &DensityChunk| mesher.generate_bordered_with_lod(chunk, lod, density);
+    let Some(cache) = cache else {
+        return mesh(&DensityChunk::from_fn(coord, chunk_size, density));
+    };
+
+    let params = cache.params.clone().with_distortion_intensity(intensity).with_fear_memory(fear_memory).with_lod(lod);
+    let stored = cache.store.load_or_generate(coord, &params, || {
+        let chunk = DensityChunk::from_fn(coord, chunk_size, density);
+        StoredChunk {
+            mesh: Some(mesh(&chunk)),
+            density: chunk,
+        }
+    });
+    stored.mesh.unwrap_or_else(|| mesh(&stored.density))
+}
+
+/// Session measurements for tuning and post-session review
+#[derive(Resource, Debug, Clone, Default)]
+pub struct GameMetrics {
+    /// Usable fear time per bucket over the whole session
+    pub time_in_bucket: BucketDwell,
+    /// Time without a usable fear reading
+    pub frozen_time: Duration,
+    /// Time-in-bucket distribution over the fear band controller's horizon
+    pub fear_band: Option<BandDistribution>,
+    /// Latest fear band intensity adjustment [-1.0, 1.0]
+    pub band_adjustment: f32,
+    /// Chunk mesh buffer pool hits, misses and pooled bytes
+    pub mesh_pool: MeshPoolStats,
+    /// Chunk mesh assets added, for new chunks or a changed topology
+    pub chunk_meshes_added: u64,
+    /// Chunk mesh assets rewritten in place on regeneration
+    pub chunk_meshes_rewritten: u64,
+}
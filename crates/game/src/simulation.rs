@@ -0,0 +1,265 @@
+//! Scripted sensor simulation for running the game without hardware
+//!
+//! A [`SimulationScript`] describes a fear arc as a list of phases plus
+//! captions expressed as trigger rules. [`SimulationPlugin`] replays the arc
+//! through `MockFearSensor`, tracks which phase is playing and shows the
+//! caption of the last trigger rule that fired.
+
+use bevy::prelude::*;
+use serde::Deserialize;
+use std::path::Path;
+use thiserror::Error;
+use crate::{
+    remote::{FearSensorPlugin, FearSource},
+    triggers::{evaluate_trigger_rules, TriggerFired, TriggerRule, TriggerRules, TriggerRulesPlugin},
+};
+
+/// Arc used when no script file is given
+const BUILTIN_SCRIPT: &str = include_str!("../assets/simulation.toml");
+
+/// Simulation script errors
+#[derive(Debug, Error)]
+pub enum SimulationError {
+    #[error("Failed to read simulation script {path}: {message}")]
+    Io { path: String, message: String },
+
+    #[error("Failed to parse simulation script: {0}")]
+    Parse(String),
+
+    #[error("Invalid simulation script: {0}")]
+    Invalid(String),
+}
+
+/// One segment of the fear arc
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct SimulationPhase {
+    /// Phase name shown in the overlay
+    pub name: String,
+    /// Length of the phase in seconds
+    pub duration_secs: f32,
+    /// Fear at the start of the phase
+    pub fear_start: f32,
+    /// Fear at the end of the phase
+    pub fear_end: f32,
+}
+
+/// Caption shown when its trigger rule fires
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct CaptionRule {
+    #[serde(flatten)]
+    pub rule: TriggerRule,
+    /// Text describing what should be visible
+    pub text: String,
+}
+
+/// A data-driven fear arc
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct SimulationScript {
+    /// Rate at which the mock sensor emits samples
+    #[serde(default = "default_sample_rate")]
+    pub sample_rate_hz: f32,
+    /// Phases played back to back
+    pub phases: Vec<SimulationPhase>,
+    /// Captions keyed on trigger rules
+    #[serde(default)]
+    pub captions: Vec<CaptionRule>,
+}
+
+fn default_sample_rate() -> f32 {
+    30.0 // MockFearSensor emits roughly every 33ms
+}
+
+impl SimulationScript {
+    /// The calm → rising → panic → recovery arc embedded in the binary
+    pub fn builtin() -> Self {
+        Self::from_toml(BUILTIN_SCRIPT).expect("embedded simulation script is valid")
+    }
+
+    /// Parse and validate a script
+    pub fn from_toml(content: &str) -> Result<Self, SimulationError> {
+        let script: Self = toml::from_str(content).map_err(|e| SimulationError::Parse(e.to_string()))?;
+        script.validate()?;
+        Ok(script)
+    }
+
+    /// Load a script from disk
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, SimulationError> {
+        let path = path.as_ref();
+        let content = std::fs::read_to_string(path).map_err(|e| SimulationError::Io {
+            path: path.display().to_string(),
+            message: e.to_string(),
+        })?;
+        Self::from_toml(&content)
+    }
+
+    fn validate(&self) -> Result<(), SimulationError> {
+        if self.phases.is_empty() {
+            return Err(SimulationError::Invalid("script has no phases".to_string()));
+        }
+        if self.sample_rate_hz <= 0.0 {
+            return Err(SimulationError::Invalid("sample_rate_hz must be positive".to_string()));
+        }
+        for phase in &self.phases {
+            if phase.duration_secs <= 0.0 {
+                return Err(SimulationError::Invalid(format!(
+                    "phase '{}' must have a positive duration",
+                    phase.name
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// Length of one playthrough in seconds
+    pub fn total_duration(&self) -> f32 {
+        self.phases.iter().map(|phase| phase.duration_secs).sum()
+    }
+
+    /// Index of the phase playing at `t` seconds (the last phase once the arc ends)
+    pub fn phase_at(&self, t: f32) -> usize {
+        let mut start = 0.0;
+        for (index, phase) in self.phases.iter().enumerate() {
+            if t < start + phase.duration_secs {
+                return index;
+            }
+            start += phase.duration_secs;
+        }
+        self.phases.len() - 1
+    }
+
+    /// Scripted fear at `t` seconds
+    pub fn fear_at(&self, t: f32) -> f32 {
+        let mut start = 0.0;
+        for phase in &self.phases {
+            if t < start + phase.duration_secs {
+                let progress = ((t - start) / phase.duration_secs).clamp(0.0, 1.0);
+                return phase.fear_start + (phase.fear_end - phase.fear_start) * progress;
+            }
+            start += phase.duration_secs;
+        }
+        self.phases.last().map_or(0.0, |phase| phase.fear_end)
+    }
+
+    /// The arc sampled at `sample_rate_hz`, as fed to `MockFearSensor`
+    pub fn fear_sequence(&self) -> Vec<f32> {
+        let samples = (self.total_duration() * self.sample_rate_hz).ceil().max(1.0) as usize;
+        (0..samples)
+            .map(|i| self.fear_at(i as f32 / self.sample_rate_hz).clamp(0.0, 1.0))
+            .collect()
+    }
+}
+
+/// Playback position of the simulation
+#[derive(Resource, Debug, Clone, Default)]
+pub struct SimulationState {
+    /// Seconds into the current playthrough
+    pub elapsed: f32,
+    /// Index of the phase playing now
+    pub phase: usize,
+    /// Completed playthroughs
+    pub cycles: u32,
+    /// Names of phases in the order they started
+    pub phases_entered: Vec<String>,
+}
+
+/// Caption currently on screen
+#[derive(Resource, Debug, Clone, Default, PartialEq)]
+pub struct SimulationCaption {
+    /// Rule that produced the caption
+    pub rule: Option<String>,
+    /// Caption text
+    pub text: String,
+}
+
+/// Loaded script, shared by the simulation systems
+#[derive(Resource, Debug, Clone)]
+pub struct ActiveSimulation(pub SimulationScript);
+
+/// Drives the game from a scripted fear arc instead of a camera
+pub struct SimulationPlugin {
+    pub script: SimulationScript,
+}
+
+impl SimulationPlugin {
+    /// Play `script`
+    pub fn new(script: SimulationScript) -> Self {
+        Self { script }
+    }
+}
+
+impl Default for SimulationPlugin {
+    fn default() -> Self {
+        Self::new(SimulationScript::builtin())
+    }
+}
+
+impl Plugin for SimulationPlugin {
+    fn build(&self, app: &mut App) {
+        if !app.is_plugin_added::<TriggerRulesPlugin>() {
+            app.add_plugins(TriggerRulesPlugin);
+        }
+        app.add_plugins(FearSensorPlugin {
+            source: FearSource::Mock(self.script.fear_sequence()),
+        });
+
+        {
+            let mut rules = app.world_mut().resource_mut::<TriggerRules>();
+            for caption in &self.script.captions {
+                rules.add(caption.rule.clone());
+            }
+        }
+
+        let first_phase = self.script.phases[0].name.clone();
+        app
+            .insert_resource(ActiveSimulation(self.script.clone()))
+            .insert_resource(SimulationState {
+                phases_entered: vec![first_phase],
+                ..SimulationState::default()
+            })
+            .init_resource::<SimulationCaption>()
+            .add_systems(Update, (
+                advance_simulation,
+                show_triggered_captions.after(evaluate_trigger_rules),
+            ));
+    }
+}
+
+/// Advance the playback clock and record phase changes
+pub fn advance_simulation(
+    time: Res<Time>,
+    simulation: Res<ActiveSimulation>,
+    mut state: ResMut<SimulationState>,
+) {
+    let script = &simulation.0;
+    let total = script.total_duration();
+
+    state.elapsed += time.delta_secs();
+    let wrapped = state.elapsed >= total;
+    if wrapped {
+        state.elapsed %= total;
+        state.cycles += 1;
+        tracing::info!("Simulation arc completed ({} cycles)", state.cycles);
+    }
+
+    let phase = script.phase_at(state.elapsed);
+    if phase != state.phase || wrapped {
+        let name = script.phases[phase].name.clone();
+        tracing::info!("Simulation phase: {}", name);
+        state.phase = phase;
+        state.phases_entered.push(name);
+    }
+}
+
+/// Replace the caption when a caption rule fires
+pub fn show_triggered_captions(
+    simulation: Res<ActiveSimulation>,
+    mut fired: EventReader<TriggerFired>,
+    mut caption: ResMut<SimulationCaption>,
+) {
+    for event in fired.read() {
+        if let Some(rule) = simulation.0.captions.iter().find(|c| c.rule.name == event.rule) {
+            caption.rule = Some(rule.rule.name.clone());
+            caption.text = rule.text.clone();
+        }
+    }
+}
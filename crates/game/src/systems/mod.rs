@@ -1,62 +1,373 @@
 //! ECS Systems for SpectreMesh
 
 use bevy::prelude::*;
-use crate::resources::FearState;
+use crate::{
+    components::{FearMemoryFocus, TerrainChunk},
+    events::{
+        CalibrationCompleted, CalibrationProgressEvent, DominantEmotionChanged, FearBucketChanged, FearEventWriters,
+        FearSignalLost, FearSignalRecovered,
+    },
+    fear_journal::{commit_fear_event, FearEvent, FearJournal},
+    modulation::FearModulation,
+    resources::{DensityTerrain, FearState, GameConfig, TerrainMemory, TerrainSettings},
+    terrain_material::TerrainMaterial,
+    terrain_stream::TerrainStreamer,
+};
+use spectremesh_terrain::{ChunkCoord, CHUNK_SIZE};
+use spectremesh_core::types::FearBucket;
 #[allow(unused_imports)] // Used in update_from_frame method parameter
 use spectremesh_core::types::FearFrame;
+use std::collections::HashSet;
+use std::ops::Range;
+use std::time::{Duration, Instant};
+
+/// System to update fear state from sensor input, raising
+/// [`DominantEmotionChanged`], [`FearBucketChanged`] and
+/// [`CalibrationCompleted`] for every frame that makes the change, and
+/// [`FearSignalLost`] and [`FearSignalRecovered`] as frames stop and resume
+pub fn update_fear_system(
+    mut fear_state: ResMut<FearState>,
+    mut journal: Option<ResMut<FearJournal>>,
+    time: Option<Res<Time<Real>>>,
+    config: Option<Res<GameConfig>>,
+    mut calibration: EventReader<CalibrationProgressEvent>,
+    mut events: FearEventWriters,
+) {
+    let game_time = time.map_or(Duration::ZERO, |time| time.elapsed());
+    if let Some(config) = config.filter(|config| config.is_changed()) {
+        fear_state.min_frame_confidence = config.min_frame_confidence;
+        fear_state.signal_timeout = config.signal_timeout;
+        match config.validate() {
+            Ok(()) if config.fear_mapping != fear_state.fear_mapping => {
+                let event = FearEvent::MappingChanged { mapping: config.fear_mapping };
+                commit_fear_event(&mut fear_state, journal.as_deref_mut(), game_time, event);
+            }
+            Ok(()) => {}
+            Err(e) => tracing::warn!("Keeping the current fear mapping: {}", e),
+        }
+    }
+    for baseline in calibration.read().filter_map(|progress| progress.baseline) {
+        fear_state.baseline = Some(baseline);
+    }
 
-/// System to update fear state from sensor input
-pub fn update_fear_system(mut fear_state: ResMut<FearState>) {
     // Collect frames first to avoid borrow conflicts
     let mut frames = Vec::new();
-    if let Some(receiver) = &fear_state.receiver {
+    if let Some(receiver) = fear_state.receiver.as_mut() {
         // Process all available fear frames (non-blocking)
-        while let Ok(frame) = receiver.try_recv() {
+        while let Some(frame) = receiver.try_recv() {
             frames.push(frame);
         }
     }
 
-    // Update state with collected frames
+    // Update state with the frames confident enough to use, journaling them
+    // when asked to
+    let previous_update = fear_state.last_update;
     for frame in frames {
-        fear_state.update_from_frame(frame);
+        fear_state.count_frame(&frame);
+        if fear_state.accepts_frame(&frame) {
+            let previous = fear_state.dominant_emotion();
+            let (bucket, calibrated) = (fear_state.current_bucket, fear_state.calibrated);
+            commit_fear_event(&mut fear_state, journal.as_deref_mut(), game_time, FearEvent::frame(&frame));
+            let current = fear_state.dominant_emotion();
+            if current != previous {
+                tracing::debug!("Dominant emotion changed: {} -> {}", previous, current);
+                events.dominant_changes.write(DominantEmotionChanged {
+                    previous,
+                    current,
+                    emotions: fear_state.current_emotions,
+                });
+            }
+            if fear_state.current_bucket != bucket {
+                events.bucket_changes.write(FearBucketChanged {
+                    from: bucket,
+                    to: fear_state.current_bucket,
+                    score: fear_state.current_fear,
+                });
+            }
+            if fear_state.calibrated && !calibrated {
+                tracing::info!("Sensor calibrated: baseline {:?}", fear_state.baseline);
+                events.calibration_completed.write(CalibrationCompleted {
+                    baseline_mean: fear_state.baseline.map(|(mean, _)| mean),
+                    baseline_std: fear_state.baseline.map(|(_, std)| std),
+                });
+            }
+        }
+    }
+
+    // There is no signal to lose before the first frame
+    let since_last_frame = fear_state.last_update.elapsed();
+    let signal_lost = fear_state.frames_received > 0 && since_last_frame > fear_state.signal_timeout;
+    if signal_lost != fear_state.signal_lost {
+        fear_state.signal_lost = signal_lost;
+        if signal_lost {
+            tracing::warn!("Fear signal lost: no frame for {:.1}s", since_last_frame.as_secs_f32());
+            events.signal_lost.write(FearSignalLost { since_last_frame });
+        } else {
+            let gap = fear_state.last_update.saturating_duration_since(previous_update);
+            tracing::info!("Fear signal recovered after {:.1}s", gap.as_secs_f32());
+            events.signal_recovered.write(FearSignalRecovered { gap });
+        }
+    }
+}
+
+/// System to ease the smoothed fear level toward the latest sample
+pub fn smooth_fear_system(time: Res<Time>, config: Res<GameConfig>, mut fear_state: ResMut<FearState>) {
+    if config.is_changed() {
+        fear_state.fear_time_constant = config.fear_time_constant;
     }
+    fear_state.tick(time.delta());
 }
 
-/// System to update terrain based on fear level changes
+/// System to accumulate terrain fear memory around the focus entity
+pub fn update_fear_memory_system(
+    time: Res<Time>,
+    fear_state: Res<FearState>,
+    focus: Query<&Transform, With<FearMemoryFocus>>,
+    mut memory: ResMut<TerrainMemory>,
+) {
+    let Ok(transform) = focus.single() else {
+        return;
+    };
+    let position = transform.translation.to_array();
+
+    // Without a usable fear level nothing new is remembered, but memory still fades
+    let bucket = if fear_state.calibrated && fear_state.fear_available() {
+        fear_state.current_bucket
+    } else {
+        FearBucket::Low
+    };
+    memory.map.integrate_fear_memory(position, bucket, time.delta());
+
+    let rebuilds = memory.map.take_memory_rebuilds(time.elapsed());
+    if !rebuilds.is_empty() {
+        tracing::debug!("Terrain memory rebuild: {} chunks", rebuilds.len());
+        memory.pending_rebuilds.extend(rebuilds);
+    }
+
+    memory.focus_scar_blend = memory
+        .map
+        .chunk(ChunkCoord::from_world(position))
+        .map_or(0.0, |chunk| chunk.scar_blend());
+}
+
+/// Keep a ground-level terrain chunk over every column the
+/// [`TerrainStreamer`] has loaded, and despawn chunks over columns it
+/// unloaded; detail drops with horizontal distance from the camera
+///
+/// Without a streamer, chunks are spawned by hand and left alone.
+pub fn spawn_terrain_chunks_system(
+    mut commands: Commands,
+    settings: Res<TerrainSettings>,
+    streamer: Option<Res<TerrainStreamer>>,
+    camera: Query<&Transform, With<Camera>>,
+    mut chunks: Query<(Entity, &mut TerrainChunk)>,
+) {
+    let Some(streamer) = streamer else {
+        return;
+    };
+    let config = &settings.0;
+    let chunk_size = config.chunk_size as f32;
+    // Ground sits at base_height; its chunk layer follows the camera in x and z only
+    let ground = (config.base_height / chunk_size).floor() as i32;
+    let centre = camera.single().ok().map(|camera| {
+        let coord = ChunkCoord::from_world_pos(camera.translation.to_array(), chunk_size);
+        ChunkCoord::new(coord.x, ground, coord.z)
+    });
+    // Full detail everywhere without a camera
+    let lod_at = |coord: ChunkCoord| {
+        let horizontal = ChunkCoord::new(coord.x, ground, coord.z);
+        centre.map_or(0, |centre| TerrainChunk::lod_for_distance(horizontal.chebyshev_distance(&centre)))
+    };
+    // A chunk belongs to the streamed column its centre lies in
+    let column = |coord: ChunkCoord| {
+        let centre = ChunkCoord::from_world(coord.to_world_origin(chunk_size).map(|v| v + chunk_size / 2.0));
+        ChunkCoord::new(centre.x, 0, centre.z)
+    };
+
+    let mut present = HashSet::new();
+    for (entity, mut chunk) in &mut chunks {
+        if streamer.chunk(column(chunk.coord)).is_none() {
+            commands.entity(entity).despawn();
+            continue;
+        }
+        let lod = lod_at(chunk.coord);
+        if chunk.lod != lod {
+            // Remeshed at the new level; the old mesh stays until then
+            chunk.lod = lod;
+            chunk.dirty = true;
+        }
+        present.insert(chunk.coord);
+    }
+
+    for generated in streamer.chunks() {
+        for x in column_span(generated.coord.x, chunk_size) {
+            for z in column_span(generated.coord.z, chunk_size) {
+                let coord = ChunkCoord::new(x, ground, z);
+                if !present.contains(&coord) {
+                    commands.spawn(TerrainChunk::new(coord).with_lod(lod_at(coord)));
+                }
+            }
+        }
+    }
+}
+
+/// Chunks of `chunk_size` world units along one axis whose centre lies in
+/// the streamed column at `column`
+fn column_span(column: i32, chunk_size: f32) -> Range<i32> {
+    let first = |column: i32| (column as f32 * CHUNK_SIZE / chunk_size - 0.5).ceil() as i32;
+    first(column)..first(column + 1)
+}
+
+/// System to update terrain on [`FearBucketChanged`], queueing dirty
+/// chunks and starting their meshes on the async compute pool
+///
+/// Chunks whose fear memory changed are remeshed on their own: density
+/// chunks are marked dirty and streamed chunks regenerated.
+#[allow(clippy::too_many_arguments)]
 pub fn update_terrain_system(
+    mut bucket_changes: EventReader<FearBucketChanged>,
     mut fear_state: ResMut<FearState>,
-    // TODO: Add terrain query when terrain system is implemented
-    // mut terrain_query: Query<&mut TerrainComponent>,
+    mut memory: ResMut<TerrainMemory>,
+    mut journal: Option<ResMut<FearJournal>>,
+    time: Option<Res<Time<Real>>>,
+    mut terrain: Option<ResMut<DensityTerrain>>,
+    mut streamer: Option<ResMut<TerrainStreamer>>,
+    meshes: Option<Res<Assets<Mesh>>>,
+    camera: Query<&Transform, With<Camera>>,
+    mut chunks: Query<(Entity, &mut TerrainChunk)>,
 ) {
-    if fear_state.needs_terrain_rebuild() {
-        // TODO: Implement terrain mesh rebuilding based on fear bucket
-        // For now, just log the change and mark as complete
-        tracing::info!(
-            "Terrain update triggered: fear={:.3}, bucket={:?}, distortion={:.3}",
-            fear_state.current_fear,
-            fear_state.current_bucket,
-            fear_state.get_distortion_intensity()
-        );
+    let has_density = terrain.is_some() && meshes.is_some();
+    if (has_density || streamer.is_some()) && !memory.pending_rebuilds.is_empty() {
+        let scarred: HashSet<_> = memory.pending_rebuilds.drain(..).collect();
+        if let Some(terrain) = terrain.as_deref_mut().filter(|_| has_density) {
+            for (entity, mut chunk) in &mut chunks {
+                if scarred.contains(&terrain.memory_chunk(chunk.coord)) {
+                    chunk.dirty = true;
+                    terrain.restart(entity);
+                }
+            }
+        }
+        if let Some(streamer) = streamer.as_deref_mut() {
+            // Streamed chunks are columns scarred by the memory of layer 0
+            for coord in scarred.into_iter().filter(|coord| coord.y == 0) {
+                if streamer.chunk(coord).is_some() {
+                    streamer.regenerate(coord);
+                }
+            }
+        }
+    }
+
+    // Meshes follow the committed bucket; the continuous intensity would
+    // restart the rebuild on every frame
+    let intensity = fear_state.fear_mapping.intensity(fear_state.current_bucket);
+    // `needs_terrain_rebuild` stays set until the rebuild finishes, for
+    // readers of the flag
+    let bucket_changed = bucket_changes.read().count() > 0;
 
-        // YOUR CODE: Use fear_state.get_distortion_intensity() to modify terrain/visuals
-        // This is the integration point mentioned in the API documentation
+    if let Some(terrain) = terrain.as_deref_mut().filter(|_| meshes.is_some()) {
+        // Chunks the camera left behind need no mesh
+        terrain.retain_chunks(|entity| chunks.contains(entity));
+        if bucket_changed && terrain.rebuilding() != Some(intensity) {
+            tracing::info!(
+                "Terrain rebuild started: fear={:.3}, bucket={:?}, distortion={:.3}, chunks={}",
+                fear_state.current_fear,
+                fear_state.current_bucket,
+                intensity,
+                chunks.iter().len()
+            );
+            for (_, mut chunk) in &mut chunks {
+                chunk.dirty = true;
+            }
+            terrain.start_rebuild(intensity, chunks.iter().map(|(entity, chunk)| (entity, *chunk)));
+        } else {
+            terrain.queue_dirty(
+                chunks.iter().filter(|(_, chunk)| chunk.dirty).map(|(entity, chunk)| (entity, *chunk)),
+            );
+        }
 
-        fear_state.terrain_rebuilt();
+        // Meshes are built at the intensity of the frame they start in; a
+        // bucket change drops the ones in flight and starts them again
+        terrain.dispatch(intensity, camera.single().ok().map(|camera| camera.translation), &memory.map);
+
+        if terrain.rebuilding().is_none() || !terrain.finish_rebuild() {
+            return;
+        }
+    } else if !bucket_changed {
+        return;
+    }
+
+    tracing::info!(
+        "Terrain rebuilt: fear={:.3}, bucket={:?}, distortion={:.3}",
+        fear_state.current_fear,
+        fear_state.current_bucket,
+        intensity
+    );
+
+    // A full rebuild also picks up every chunk's current fear memory
+    memory.map.mark_all_built();
+    memory.pending_rebuilds.clear();
+    let game_time = time.map_or(Duration::ZERO, |time| time.elapsed());
+    commit_fear_event(&mut fear_state, journal.as_deref_mut(), game_time, FearEvent::TerrainRebuilt);
+}
+
+/// Swap in chunk meshes finished on the async compute pool, at most
+/// `chunks_per_frame` a frame and no more once the frame budget is spent
+pub fn collect_terrain_meshes_system(
+    mut commands: Commands,
+    terrain: Option<ResMut<DensityTerrain>>,
+    meshes: Option<ResMut<Assets<Mesh>>>,
+    mut chunks: Query<&mut TerrainChunk>,
+) {
+    let (Some(mut terrain), Some(mut meshes)) = (terrain, meshes) else {
+        return;
+    };
+    let started = Instant::now();
+    let mut applied = 0;
+    while applied < terrain.chunks_per_frame() && (applied == 0 || started.elapsed() < terrain.frame_budget()) {
+        let Some((entity, mesh, lod)) = terrain.take_finished() else {
+            break;
+        };
+        // Despawned while its mesh was being built
+        let Ok(mut chunk) = chunks.get_mut(entity) else {
+            continue;
+        };
+        commands.entity(entity).insert((Mesh3d(meshes.add(mesh)), terrain.chunk_transform(chunk.coord)));
+        // The camera may have moved the chunk to another level meanwhile
+        chunk.dirty = chunk.lod != lod;
+        applied += 1;
     }
 }
 
 /// System to update shader uniforms based on fear level
 pub fn update_shader_uniforms_system(
+    time: Res<Time>,
     fear_state: Res<FearState>,
-    // TODO: Add material query when shader system is implemented
-    // mut materials: ResMut<Assets<TerrainMaterial>>,
+    modulation: Res<FearModulation>,
+    memory: Res<TerrainMemory>,
+    materials: Option<ResMut<Assets<TerrainMaterial>>>,
 ) {
-    // Update distortion intensity uniform every frame for smooth transitions
+    // Update uniforms every frame for smooth transitions; the distortion
+    // intensity is already continuous in the smoothed fear level
     let distortion_intensity = fear_state.get_distortion_intensity();
+    let startle_transient = modulation.transient();
+    let bucket_progress = modulation.anticipation();
+    let scar_blend = memory.focus_scar_blend;
+
+    if let Some(mut materials) = materials {
+        for (_, material) in materials.iter_mut() {
+            material.uniforms.distortion_intensity = distortion_intensity;
+            material.uniforms.fear_level = fear_state.smoothed_fear;
+            material.uniforms.time = time.elapsed_secs();
+        }
+    }
 
-    // TODO: Update actual shader uniforms
-    // For now, just trace the value for debugging
     if fear_state.calibrated {
-        tracing::trace!("Shader uniform update: distortion_intensity={:.3}", distortion_intensity);
+        tracing::trace!(
+            "Shader uniform update: distortion_intensity={:.3}, startle_transient={:.3}, bucket_progress={:.3}, scar_blend={:.3}",
+            distortion_intensity,
+            startle_transient,
+            bucket_progress,
+            scar_blend
+        );
     }
 }
@@ -0,0 +1,118 @@
+//! Terrain material driven by fear
+//!
+//! [`TerrainMaterial`] renders meshes with `shaders/terrain_material.wgsl`,
+//! which ripples the surface along its normals and bleeds it toward red as
+//! the distortion intensity rises, pulsing faster with the fear level.
+//! `update_shader_uniforms_system` writes the uniforms of every terrain
+//! material each frame from [`FearState`](crate::resources::FearState).
+
+use bevy::{
+    pbr::{MaterialPipeline, MaterialPipelineKey, PbrPlugin},
+    prelude::*,
+    render::{
+        mesh::MeshVertexBufferLayoutRef,
+        render_resource::{AsBindGroup, RenderPipelineDescriptor, ShaderRef, SpecializedMeshPipelineError},
+    },
+};
+
+/// Shader for [`TerrainMaterial`], relative to the asset folder
+pub const TERRAIN_SHADER_PATH: &str = "shaders/terrain_material.wgsl";
+
+pub use uniforms::TerrainUniforms;
+
+// The `ShaderType` derive emits field checks that newer compilers report as
+// unused functions
+#[allow(dead_code)]
+mod uniforms {
+    use bevy::{
+        color::{ColorToComponents, LinearRgba},
+        math::Vec4,
+        render::render_resource::ShaderType,
+    };
+
+    /// Uniforms read by the terrain shader
+    #[derive(ShaderType, Debug, Clone, Copy, PartialEq)]
+    pub struct TerrainUniforms {
+        /// Color of calm ground (linear RGBA)
+        pub base_color: Vec4,
+        /// Distortion intensity [0.1, 1.0]: ripple height and red tint
+        pub distortion_intensity: f32,
+        /// Smoothed fear level [0.0, 1.0]: pulse rate
+        pub fear_level: f32,
+        /// Seconds since startup, animating the ripples and pulse
+        pub time: f32,
+    }
+
+    impl Default for TerrainUniforms {
+        fn default() -> Self {
+            Self {
+                base_color: LinearRgba::rgb(0.35, 0.3, 0.28).to_vec4(),
+                distortion_intensity: 0.1,
+                fear_level: 0.0,
+                time: 0.0,
+            }
+        }
+    }
+}
+
+/// Material for fear-reactive terrain
+#[derive(Asset, TypePath, AsBindGroup, Debug, Clone, Default)]
+pub struct TerrainMaterial {
+    #[uniform(0)]
+    pub uniforms: TerrainUniforms,
+}
+
+impl TerrainMaterial {
+    /// A terrain material with `color` for calm ground
+    pub fn new(color: Color) -> Self {
+        Self {
+            uniforms: TerrainUniforms {
+                base_color: color.to_linear().to_vec4(),
+                ..default()
+            },
+        }
+    }
+}
+
+impl Material for TerrainMaterial {
+    fn vertex_shader() -> ShaderRef {
+        TERRAIN_SHADER_PATH.into()
+    }
+
+    fn fragment_shader() -> ShaderRef {
+        TERRAIN_SHADER_PATH.into()
+    }
+
+    fn specialize(
+        _pipeline: &MaterialPipeline<Self>,
+        descriptor: &mut RenderPipelineDescriptor,
+        layout: &MeshVertexBufferLayoutRef,
+        _key: MaterialPipelineKey<Self>,
+    ) -> Result<(), SpecializedMeshPipelineError> {
+        // Terrain meshes may carry vertex colors too; the shader reads
+        // positions and normals only
+        let vertex_layout = layout.0.get_layout(&[
+            Mesh::ATTRIBUTE_POSITION.at_shader_location(0),
+            Mesh::ATTRIBUTE_NORMAL.at_shader_location(1),
+        ])?;
+        descriptor.vertex.buffers = vec![vertex_layout];
+        Ok(())
+    }
+}
+
+/// Registers [`TerrainMaterial`]
+///
+/// With the PBR renderer present the material is rendered; headless apps
+/// with only an `AssetPlugin` still get the asset type, so the uniforms can
+/// be checked without a GPU. Without either the uniform update skips it.
+pub struct TerrainMaterialPlugin;
+
+impl Plugin for TerrainMaterialPlugin {
+    fn build(&self, app: &mut App) {
+        if app.is_plugin_added::<PbrPlugin>() {
+            app.add_plugins(MaterialPlugin::<TerrainMaterial>::default());
+        } else if app.is_plugin_added::<AssetPlugin>() {
+            app.init_asset::<TerrainMaterial>();
+        }
+    }
+}
@@ -0,0 +1,182 @@
+//! Bevy meshes from terrain chunk meshes
+//!
+//! [`chunk_mesh`] converts a [`ChunkMesh`] from the terrain crate's
+//! [`ChunkMesher`](spectremesh_terrain::ChunkMesher). Baked vertex colors
+//! become [`Mesh::ATTRIBUTE_COLOR`], which `StandardMaterial` multiplies into
+//! its base color, so fear shows without a custom material.
+//!
+//! [`density_mesh`] converts a marching cubes [`DensityMesh`] for the
+//! volumetric [`TerrainChunk`](crate::components::TerrainChunk)s.
+//!
+//! [`sync_chunk_meshes_system`] gives each chunk streamed with meshing one
+//! entity. A regenerated chunk whose topology is unchanged has its mesh
+//! asset rewritten in place, under the same handle, so the renderer updates
+//! its buffers instead of dropping one asset and uploading another.
+
+use bevy::{
+    asset::RenderAssetUsages,
+    prelude::*,
+    render::{
+        mesh::{Indices, PrimitiveTopology, VertexAttributeValues},
+        primitives::Aabb,
+    },
+};
+use spectremesh_terrain::{ChunkCoord, ChunkMesh, DensityMesh};
+use std::collections::HashMap;
+use crate::{resources::GameMetrics, terrain_stream::TerrainStreamer};
+
+/// Bevy mesh of `chunk`, in chunk-local coordinates
+pub fn chunk_mesh(chunk: &ChunkMesh) -> Mesh {
+    let mut mesh = Mesh::new(PrimitiveTopology::TriangleList, RenderAssetUsages::default())
+        .with_inserted_attribute(Mesh::ATTRIBUTE_POSITION, chunk.positions.clone())
+        .with_inserted_attribute(Mesh::ATTRIBUTE_NORMAL, chunk.normals.clone())
+        .with_inserted_indices(Indices::U32(chunk.indices.clone()));
+    if let Some(colors) = &chunk.colors {
+        mesh.insert_attribute(Mesh::ATTRIBUTE_COLOR, colors.clone());
+    }
+    mesh
+}
+
+/// Bevy mesh of a marching cubes `chunk`, in chunk-local coordinates
+pub fn density_mesh(chunk: &DensityMesh) -> Mesh {
+    Mesh::new(PrimitiveTopology::TriangleList, RenderAssetUsages::default())
+        .with_inserted_attribute(Mesh::ATTRIBUTE_POSITION, chunk.positions.clone())
+        .with_inserted_attribute(Mesh::ATTRIBUTE_NORMAL, chunk.normals.clone())
+        .with_inserted_indices(Indices::U32(chunk.indices.clone()))
+}
+
+/// World transform placing a chunk mesh at its chunk's origin
+pub fn chunk_transform(chunk: &ChunkMesh) -> Transform {
+    // Heights are already world heights, only x and z are chunk-local
+    let [x, _, z] = chunk.coord.origin();
+    Transform::from_xyz(x, 0.0, z)
+}
+
+/// Rewrite `mesh`, built by [`chunk_mesh`] from a chunk of the same
+/// [`MeshTopology`], with `chunk`'s vertices, reusing its buffers
+pub fn rewrite_chunk_mesh(mesh: &mut Mesh, chunk: &ChunkMesh) {
+    if let Some(VertexAttributeValues::Float32x3(positions)) = mesh.attribute_mut(Mesh::ATTRIBUTE_POSITION) {
+        positions.clone_from(&chunk.positions);
+    }
+    if let Some(VertexAttributeValues::Float32x3(normals)) = mesh.attribute_mut(Mesh::ATTRIBUTE_NORMAL) {
+        normals.clone_from(&chunk.normals);
+    }
+    if let (Some(VertexAttributeValues::Float32x4(colors)), Some(baked)) =
+        (mesh.attribute_mut(Mesh::ATTRIBUTE_COLOR), &chunk.colors)
+    {
+        colors.clone_from(baked);
+    }
+    if let Some(Indices::U32(indices)) = mesh.indices_mut() {
+        indices.clone_from(&chunk.indices);
+    }
+}
+
+/// What two chunk meshes must share for one to be rewritten into the other
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MeshTopology {
+    pub vertices: usize,
+    pub indices: usize,
+    pub colored: bool,
+}
+
+impl MeshTopology {
+    pub fn of(chunk: &ChunkMesh) -> Self {
+        Self {
+            vertices: chunk.positions.len(),
+            indices: chunk.indices.len(),
+            colored: chunk.colors.is_some(),
+        }
+    }
+}
+
+/// Material for streamed chunk meshes, tinted by their vertex colors
+#[derive(Resource, Debug, Clone)]
+pub struct ChunkMaterial(pub Handle<StandardMaterial>);
+
+#[derive(Debug)]
+struct ChunkMeshEntry {
+    entity: Entity,
+    mesh: Handle<Mesh>,
+    topology: MeshTopology,
+}
+
+/// Entities and mesh assets of streamed chunks
+#[derive(Resource, Debug, Default)]
+pub struct ChunkMeshes {
+    entries: HashMap<ChunkCoord, ChunkMeshEntry>,
+}
+
+impl ChunkMeshes {
+    pub fn entity(&self, coord: ChunkCoord) -> Option<Entity> {
+        self.entries.get(&coord).map(|entry| entry.entity)
+    }
+
+    pub fn mesh(&self, coord: ChunkCoord) -> Option<&Handle<Mesh>> {
+        self.entries.get(&coord).map(|entry| &entry.mesh)
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+/// Spawn, update and despawn chunk mesh entities to match the streamer
+pub fn sync_chunk_meshes_system(
+    mut commands: Commands,
+    mut streamer: ResMut<TerrainStreamer>,
+    mut chunk_meshes: ResMut<ChunkMeshes>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    material: Option<Res<ChunkMaterial>>,
+    metrics: Option<ResMut<GameMetrics>>,
+) {
+    let changes = streamer.take_mesh_changes();
+    for coord in changes.unloaded {
+        if let Some(entry) = chunk_meshes.entries.remove(&coord) {
+            commands.entity(entry.entity).despawn();
+            meshes.remove(&entry.mesh);
+        }
+    }
+
+    let (mut added, mut rewritten) = (0, 0);
+    for coord in changes.updated {
+        let Some(chunk) = streamer.chunk(coord).and_then(|generated| generated.mesh.as_ref()) else {
+            continue;
+        };
+        let topology = MeshTopology::of(chunk);
+        match chunk_meshes.entries.get_mut(&coord) {
+            Some(entry) if entry.topology == topology && meshes.contains(&entry.mesh) => {
+                rewrite_chunk_mesh(meshes.get_mut(&entry.mesh).unwrap(), chunk);
+                // Bounds are computed once per entity; the heights moved
+                commands.entity(entry.entity).remove::<Aabb>();
+                rewritten += 1;
+            }
+            Some(entry) => {
+                let previous = std::mem::replace(&mut entry.mesh, meshes.add(chunk_mesh(chunk)));
+                meshes.remove(&previous);
+                entry.topology = topology;
+                commands.entity(entry.entity).insert(Mesh3d(entry.mesh.clone())).remove::<Aabb>();
+                added += 1;
+            }
+            None => {
+                let mesh = meshes.add(chunk_mesh(chunk));
+                let mut entity = commands.spawn((Mesh3d(mesh.clone()), chunk_transform(chunk)));
+                if let Some(material) = &material {
+                    entity.insert(MeshMaterial3d(material.0.clone()));
+                }
+                let entity = entity.id();
+                chunk_meshes.entries.insert(coord, ChunkMeshEntry { entity, mesh, topology });
+                added += 1;
+            }
+        }
+    }
+
+    if let Some(mut metrics) = metrics {
+        metrics.mesh_pool = streamer.mesh_pool().stats();
+        metrics.chunk_meshes_added += added;
+        metrics.chunk_meshes_rewritten += rewritten;
+    }
+}
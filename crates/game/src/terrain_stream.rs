@@ -0,0 +1,629 @@
+//! Terrain chunk streaming with progress reporting
+//!
+//! [`TerrainStreamer`] generates every chunk within `render_distance` of the
+//! focus (the [`FearMemoryFocus`] entity, or the origin without one) on the
+//! async compute pool, nearest first. [`TerrainGenProgress`] tracks the
+//! counts and an ETA from measured per-chunk generation time, and
+//! [`TerrainGenMilestone`] fires once for each configured percentage, so a
+//! loading screen has something to show while the first chunks appear.
+//!
+//! With [`TerrainStreamConfig::block_until_initial_ring_complete`] the
+//! terrain holds a loading gate (see [`crate::loading`]) until the chunks
+//! within `initial_ring_radius` of the spawn point exist; the rest keep
+//! streaming afterwards.
+//!
+//! A fear bucket change cancels the jobs in flight, since they were started
+//! for the old bucket. Their chunks go back in the queue and are counted
+//! once, when a job for the current bucket completes.
+//!
+//! With [`TerrainStreamConfig::meshing`] each job also meshes its chunk, into
+//! buffers checked out of a [`MeshBufferPool`] for the configured
+//! resolution. The buffers go back to the pool when the chunk is regenerated
+//! or unloaded (see [`TerrainStreamConfig::unload_distance`]), and
+//! [`crate::terrain_mesh::sync_chunk_meshes_system`] keeps one mesh entity
+//! per chunk.
+//!
+//! The streamed columns also decide where volumetric terrain goes:
+//! [`crate::systems::spawn_terrain_chunks_system`] keeps a ground-level
+//! [`TerrainChunk`](crate::components::TerrainChunk) over each loaded column
+//! for [`DensityTerrain`](crate::resources::DensityTerrain) to mesh.
+
+use bevy::{
+    prelude::*,
+    tasks::{block_on, AsyncComputeTaskPool, Task},
+};
+use spectremesh_core::types::FearBucket;
+use spectremesh_core::TerrainConfig;
+use spectremesh_terrain::{
+    Chunk, ChunkCoord, ChunkMesh, ChunkMesher, MeshBufferPool, MeshBuffers, MeshPoolConfig, MesherConfig,
+    TerrainGenerator,
+};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use thiserror::Error;
+use crate::{
+    components::FearMemoryFocus,
+    loading::{register_loading_gate, LoadingGateSet, LoadingGates, LoadingPlugin},
+    resources::{FearState, TerrainMemory},
+    terrain_mesh::{sync_chunk_meshes_system, ChunkMaterial, ChunkMeshes},
+};
+
+/// Gate held until the initial ring of chunks exists
+pub const TERRAIN_GATE: &str = "terrain";
+
+/// Weight of the newest sample in the per-chunk generation time average
+const CHUNK_TIME_SMOOTHING: f64 = 0.2;
+
+/// Chunk generation failure
+#[derive(Debug, Clone, Error)]
+#[error("Chunk generation failed: {0}")]
+pub struct ChunkGenError(pub String);
+
+/// Produces chunk height fields; runs on the async compute pool
+pub trait ChunkGenerator: Send + Sync + 'static {
+    /// Heights over `chunk`'s footprint on a `(resolution + 1)²` grid
+    fn generate(&self, chunk: &Chunk, bucket: FearBucket, resolution: usize) -> Result<Vec<f32>, ChunkGenError>;
+}
+
+impl ChunkGenerator for TerrainGenerator {
+    fn generate(&self, chunk: &Chunk, _bucket: FearBucket, resolution: usize) -> Result<Vec<f32>, ChunkGenError> {
+        Ok(self.chunk_heights(chunk, resolution))
+    }
+}
+
+/// A streamed chunk
+#[derive(Debug, Clone, PartialEq)]
+pub struct GeneratedChunk {
+    pub coord: ChunkCoord,
+    /// Fear bucket the chunk was generated for
+    pub bucket: FearBucket,
+    pub heights: Vec<f32>,
+    /// Surface mesh, when streaming meshes
+    pub mesh: Option<ChunkMesh>,
+}
+
+/// Streaming parameters
+#[derive(Resource, Debug, Clone, PartialEq)]
+pub struct TerrainStreamConfig {
+    /// Chunks generated around the focus in each horizontal direction
+    pub render_distance: u32,
+    /// Height samples along each chunk edge
+    pub resolution: usize,
+    /// Generation jobs running at once
+    pub max_in_flight: usize,
+    /// Radius around the spawn point that must exist before loading ends
+    pub initial_ring_radius: u32,
+    /// Hold the loading state until the initial ring is complete
+    pub block_until_initial_ring_complete: bool,
+    /// Percentages announced by [`TerrainGenMilestone`], ascending
+    pub milestones: Vec<u8>,
+    /// Mesh chunks as they are generated; `None` streams height fields only
+    pub meshing: Option<MesherConfig>,
+    /// Cap on memory held by pooled mesh buffers
+    pub mesh_pool: MeshPoolConfig,
+    /// Unload chunks farther than this from the focus (never closer than
+    /// `render_distance`); `None` keeps every chunk
+    pub unload_distance: Option<u32>,
+}
+
+impl Default for TerrainStreamConfig {
+    fn default() -> Self {
+        Self {
+            render_distance: TerrainConfig::default().render_distance,
+            resolution: 16,
+            max_in_flight: 4,
+            initial_ring_radius: 1,
+            block_until_initial_ring_complete: false,
+            milestones: vec![25, 50, 75, 100],
+            meshing: None,
+            mesh_pool: MeshPoolConfig::default(),
+            unload_distance: None,
+        }
+    }
+}
+
+impl TerrainStreamConfig {
+    pub fn with_render_distance(mut self, render_distance: u32) -> Self {
+        self.render_distance = render_distance;
+        self
+    }
+
+    pub fn with_max_in_flight(mut self, max_in_flight: usize) -> Self {
+        self.max_in_flight = max_in_flight.max(1);
+        self
+    }
+
+    pub fn with_initial_ring_radius(mut self, radius: u32) -> Self {
+        self.initial_ring_radius = radius;
+        self
+    }
+
+    /// Hold the loading state until the initial ring is complete
+    pub fn with_block_until_initial_ring_complete(mut self, block: bool) -> Self {
+        self.block_until_initial_ring_complete = block;
+        self
+    }
+
+    /// Mesh chunks as they are generated
+    pub fn with_meshing(mut self, mesher: MesherConfig) -> Self {
+        self.meshing = Some(mesher);
+        self
+    }
+
+    pub fn with_mesh_pool(mut self, mesh_pool: MeshPoolConfig) -> Self {
+        self.mesh_pool = mesh_pool;
+        self
+    }
+
+    /// Unload chunks farther than `distance` from the focus
+    pub fn with_unload_distance(mut self, distance: u32) -> Self {
+        self.unload_distance = Some(distance);
+        self
+    }
+}
+
+/// Chunk generation progress, for loading screens
+#[derive(Resource, Debug, Clone, Default, PartialEq)]
+pub struct TerrainGenProgress {
+    /// Chunks requested: generated, failed, in flight or queued
+    pub total: usize,
+    pub completed: usize,
+    pub failed: usize,
+    pub in_flight: usize,
+    /// Jobs cancelled by fear bucket changes
+    pub cancelled: usize,
+    /// Smoothed generation time of one chunk
+    pub mean_chunk_time: Option<Duration>,
+    /// Time left for the outstanding chunks at the measured rate
+    pub eta: Option<Duration>,
+    /// Every chunk of the initial ring is generated or failed
+    pub initial_ring_complete: bool,
+}
+
+impl TerrainGenProgress {
+    /// Chunks not yet generated or failed
+    pub fn remaining(&self) -> usize {
+        self.total - self.completed - self.failed
+    }
+
+    /// Settled share of the requested chunks in [0, 1]; 1 when nothing is requested
+    pub fn fraction(&self) -> f32 {
+        if self.total == 0 {
+            return 1.0;
+        }
+        (self.completed + self.failed) as f32 / self.total as f32
+    }
+
+    /// Whole percent of the requested chunks settled
+    pub fn percent(&self) -> u8 {
+        if self.total == 0 {
+            return 100;
+        }
+        ((self.completed + self.failed) * 100 / self.total) as u8
+    }
+}
+
+/// Generation crossed a configured percentage; each fires once
+#[derive(Event, Debug, Clone, PartialEq)]
+pub struct TerrainGenMilestone {
+    pub percent: u8,
+    pub completed: usize,
+    pub total: usize,
+}
+
+/// Outcome of one generation job and how long it took
+struct ChunkJob {
+    result: Result<Vec<f32>, ChunkGenError>,
+    mesh: Option<ChunkMesh>,
+    /// Buffers checked out for a mesh that was never built
+    unused: Option<MeshBuffers>,
+    elapsed: Duration,
+}
+
+/// Chunks whose mesh entities need updating
+#[derive(Debug, Default)]
+pub(crate) struct MeshChanges {
+    /// Chunks with a new mesh
+    pub updated: Vec<ChunkCoord>,
+    /// Chunks that had a mesh and were unloaded
+    pub unloaded: Vec<ChunkCoord>,
+}
+
+/// Chunk generation queue and results
+#[derive(Resource)]
+pub struct TerrainStreamer {
+    generator: Arc<dyn ChunkGenerator>,
+    /// Chunk the focus started in; the initial ring is centred on it
+    spawn: Option<ChunkCoord>,
+    focus: Option<ChunkCoord>,
+    bucket: Option<FearBucket>,
+    queue: VecDeque<ChunkCoord>,
+    in_flight: HashMap<ChunkCoord, Task<ChunkJob>>,
+    chunks: HashMap<ChunkCoord, GeneratedChunk>,
+    failed: HashSet<ChunkCoord>,
+    /// Generated chunks queued or in flight to be generated again
+    stale: HashSet<ChunkCoord>,
+    cancelled: usize,
+    mean_chunk_time: Option<Duration>,
+    /// Milestones already announced
+    milestones_fired: usize,
+    mesher: Option<Arc<ChunkMesher>>,
+    mesh_pool: MeshBufferPool,
+    mesh_changes: MeshChanges,
+}
+
+impl TerrainStreamer {
+    pub fn new(generator: Arc<dyn ChunkGenerator>) -> Self {
+        Self {
+            generator,
+            spawn: None,
+            focus: None,
+            bucket: None,
+            queue: VecDeque::new(),
+            in_flight: HashMap::new(),
+            chunks: HashMap::new(),
+            failed: HashSet::new(),
+            stale: HashSet::new(),
+            cancelled: 0,
+            mean_chunk_time: None,
+            milestones_fired: 0,
+            mesher: None,
+            mesh_pool: MeshBufferPool::default(),
+            mesh_changes: MeshChanges::default(),
+        }
+    }
+
+    pub fn chunk(&self, coord: ChunkCoord) -> Option<&GeneratedChunk> {
+        self.chunks.get(&coord)
+    }
+
+    pub fn chunks(&self) -> impl Iterator<Item = &GeneratedChunk> {
+        self.chunks.values()
+    }
+
+    pub fn spawn(&self) -> Option<ChunkCoord> {
+        self.spawn
+    }
+
+    /// Buffers of unloaded and regenerated chunk meshes, kept for new ones
+    pub fn mesh_pool(&self) -> &MeshBufferPool {
+        &self.mesh_pool
+    }
+
+    /// Generate `coord` again, e.g. after its fear memory changed
+    ///
+    /// The current chunk stays until the new one is ready, and its mesh
+    /// entity keeps its mesh asset, rewritten in place.
+    pub fn regenerate(&mut self, coord: ChunkCoord) {
+        self.failed.remove(&coord);
+        if self.in_flight.contains_key(&coord) || self.queue.contains(&coord) {
+            return;
+        }
+        if self.chunks.contains_key(&coord) {
+            self.stale.insert(coord);
+        }
+        self.queue.push_back(coord);
+        self.sort_queue();
+    }
+
+    /// Follow changes to the meshing settings
+    fn configure_meshing(&mut self, config: &TerrainStreamConfig) {
+        self.mesher = config.meshing.clone().map(|mesher| Arc::new(ChunkMesher::new(mesher)));
+        self.mesh_pool.set_config(config.mesh_pool);
+    }
+
+    pub(crate) fn take_mesh_changes(&mut self) -> MeshChanges {
+        std::mem::take(&mut self.mesh_changes)
+    }
+
+    /// Queue the chunks around `focus` that are not generated, failed or in flight, nearest first
+    fn retarget(&mut self, focus: ChunkCoord, config: &TerrainStreamConfig) {
+        self.spawn.get_or_insert(focus);
+        self.focus = Some(focus);
+        let centre = ChunkCoord::new(focus.x, 0, focus.z);
+
+        if let Some(unload_distance) = config.unload_distance {
+            let unload_distance = unload_distance.max(config.render_distance);
+            let far: Vec<ChunkCoord> = self
+                .chunks
+                .keys()
+                .filter(|coord| coord.chebyshev_distance(&centre) > unload_distance)
+                .copied()
+                .collect();
+            for coord in far {
+                self.stale.remove(&coord);
+                let chunk = self.chunks.remove(&coord).unwrap();
+                if chunk.mesh.is_some() {
+                    self.mesh_changes.updated.retain(|updated| *updated != coord);
+                    self.mesh_changes.unloaded.push(coord);
+                }
+                self.recycle(chunk);
+            }
+            self.failed.retain(|coord| coord.chebyshev_distance(&centre) <= unload_distance);
+        }
+
+        let regenerating: Vec<ChunkCoord> = self.queue.iter().filter(|coord| self.stale.contains(coord)).copied().collect();
+        self.queue = ChunkCoord::ring_iter(centre, config.render_distance)
+            .filter(|coord| self.is_unrequested(coord) && !regenerating.contains(coord))
+            .chain(regenerating.iter().copied())
+            .collect();
+        self.sort_queue();
+    }
+
+    fn is_unrequested(&self, coord: &ChunkCoord) -> bool {
+        (!self.chunks.contains_key(coord) || self.stale.contains(coord))
+            && !self.failed.contains(coord)
+            && !self.in_flight.contains_key(coord)
+    }
+
+    /// Return a replaced or unloaded chunk's mesh buffers to the pool
+    fn recycle(&mut self, chunk: GeneratedChunk) {
+        if let Some(mesh) = chunk.mesh {
+            self.mesh_pool.give_back(mesh.into_buffers());
+        }
+    }
+
+    fn sort_queue(&mut self) {
+        let Some(focus) = self.focus else {
+            return;
+        };
+        let distance = |coord: &ChunkCoord| {
+            let (dx, dz) = (coord.x - focus.x, coord.z - focus.z);
+            (coord.chebyshev_distance(&ChunkCoord::new(focus.x, 0, focus.z)), dx * dx + dz * dz)
+        };
+        self.queue.make_contiguous().sort_by_key(|coord| (distance(coord), *coord));
+    }
+
+    /// Drop the jobs in flight and queue their chunks again
+    fn cancel_in_flight(&mut self) {
+        let cancelled: Vec<ChunkCoord> = self.in_flight.drain().map(|(coord, _)| coord).collect();
+        self.cancelled += cancelled.len();
+        self.queue.extend(cancelled);
+        self.sort_queue();
+    }
+
+    /// Collect finished jobs
+    fn poll(&mut self, bucket: FearBucket) {
+        let finished: Vec<ChunkCoord> = self
+            .in_flight
+            .iter()
+            .filter(|(_, task)| task.is_finished())
+            .map(|(coord, _)| *coord)
+            .collect();
+
+        for coord in finished {
+            let task = self.in_flight.remove(&coord).unwrap();
+            let job = block_on(task);
+            self.record_chunk_time(job.elapsed);
+            if let Some(unused) = job.unused {
+                self.mesh_pool.give_back(unused);
+            }
+            let regenerated = self.stale.remove(&coord);
+            match job.result {
+                Ok(heights) => {
+                    if job.mesh.is_some() {
+                        self.mesh_changes.updated.push(coord);
+                    }
+                    let chunk = GeneratedChunk { coord, bucket, heights, mesh: job.mesh };
+                    if let Some(previous) = self.chunks.insert(coord, chunk) {
+                        self.recycle(previous);
+                    }
+                }
+                // A failed regeneration keeps the chunk it was replacing
+                Err(e) if regenerated => {
+                    tracing::warn!("Chunk {:?} not regenerated: {}", coord, e);
+                }
+                Err(e) => {
+                    tracing::warn!("Chunk {:?} not generated: {}", coord, e);
+                    self.failed.insert(coord);
+                }
+            }
+        }
+    }
+
+    fn record_chunk_time(&mut self, elapsed: Duration) {
+        self.mean_chunk_time = Some(match self.mean_chunk_time {
+            Some(mean) => mean.mul_f64(1.0 - CHUNK_TIME_SMOOTHING) + elapsed.mul_f64(CHUNK_TIME_SMOOTHING),
+            None => elapsed,
+        });
+    }
+
+    /// Start queued jobs up to `max_in_flight`, meshing at `intensity`
+    fn dispatch(
+        &mut self,
+        bucket: FearBucket,
+        intensity: f32,
+        config: &TerrainStreamConfig,
+        memory: Option<&TerrainMemory>,
+    ) {
+        let pool = AsyncComputeTaskPool::get();
+        while self.in_flight.len() < config.max_in_flight {
+            let Some(coord) = self.queue.pop_front() else {
+                break;
+            };
+            let mut chunk = Chunk::new(coord);
+            chunk.fear_memory = memory.map_or(0.0, |memory| memory.map.fear_memory(coord));
+            let generator = Arc::clone(&self.generator);
+            let resolution = config.resolution;
+            let mesher = self.mesher.clone();
+            let buffers = mesher.is_some().then(|| self.mesh_pool.checkout(resolution + 1));
+            let task = pool.spawn(async move {
+                let started = Instant::now();
+                let result = generator.generate(&chunk, bucket, resolution);
+                let (mesh, unused) = match (&result, mesher, buffers) {
+                    (Ok(heights), Some(mesher), Some(buffers)) => {
+                        match mesher.mesh_into(&chunk, heights, intensity, buffers) {
+                            Ok(mesh) => (Some(mesh), None),
+                            Err(e) => {
+                                tracing::warn!("Chunk {:?} not meshed: {}", chunk.coord, e);
+                                (None, None)
+                            }
+                        }
+                    }
+                    (_, _, buffers) => (None, buffers),
+                };
+                ChunkJob { result, mesh, unused, elapsed: started.elapsed() }
+            });
+            self.in_flight.insert(coord, task);
+        }
+    }
+
+    /// Whether every chunk within `radius` of the spawn point is generated or failed
+    fn initial_ring_complete(&self, radius: u32) -> bool {
+        let Some(spawn) = self.spawn else {
+            return false;
+        };
+        ChunkCoord::ring_iter(ChunkCoord::new(spawn.x, 0, spawn.z), radius)
+            .all(|coord| self.chunks.contains_key(&coord) || self.failed.contains(&coord))
+    }
+
+    fn progress(&self, config: &TerrainStreamConfig) -> TerrainGenProgress {
+        let completed = self.chunks.len();
+        let failed = self.failed.len();
+        let in_flight = self.in_flight.len();
+        // Regenerations are already counted as completed
+        let regenerating = self.stale.len();
+        let remaining = in_flight + self.queue.len() - regenerating;
+        let eta = self.mean_chunk_time.map(|mean| {
+            let parallel = config.max_in_flight.min(remaining).max(1);
+            mean.mul_f64(remaining as f64 / parallel as f64)
+        });
+
+        TerrainGenProgress {
+            total: completed + failed + remaining,
+            completed,
+            failed,
+            in_flight,
+            cancelled: self.cancelled,
+            mean_chunk_time: self.mean_chunk_time,
+            eta,
+            initial_ring_complete: self.initial_ring_complete(config.initial_ring_radius),
+        }
+    }
+}
+
+/// Retarget, collect and dispatch chunk jobs, then publish progress
+#[allow(clippy::too_many_arguments)]
+pub fn stream_terrain_system(
+    config: Res<TerrainStreamConfig>,
+    mut streamer: ResMut<TerrainStreamer>,
+    mut progress: ResMut<TerrainGenProgress>,
+    mut gates: ResMut<LoadingGates>,
+    mut milestones: EventWriter<TerrainGenMilestone>,
+    focus: Query<&Transform, With<FearMemoryFocus>>,
+    fear_state: Option<Res<FearState>>,
+    memory: Option<Res<TerrainMemory>>,
+) {
+    // Terrain is a height field, so only the horizontal position matters
+    let focus = focus.single().map_or(ChunkCoord::new(0, 0, 0), |transform| {
+        let coord = ChunkCoord::from_world(transform.translation.to_array());
+        ChunkCoord::new(coord.x, 0, coord.z)
+    });
+    if config.is_changed() {
+        streamer.configure_meshing(&config);
+    }
+    // A new render or unload distance takes effect without the focus moving
+    if streamer.focus != Some(focus) || config.is_changed() {
+        streamer.retarget(focus, &config);
+    }
+
+    let (bucket, intensity) = fear_state.map_or((FearBucket::Low, FearBucket::Low.distortion_intensity()), |fear_state| {
+        (fear_state.current_bucket, fear_state.fear_mapping.intensity(fear_state.current_bucket))
+    });
+    if streamer.bucket.is_some_and(|previous| previous != bucket) {
+        streamer.cancel_in_flight();
+    }
+    streamer.bucket = Some(bucket);
+
+    streamer.poll(bucket);
+    streamer.dispatch(bucket, intensity, &config, memory.as_deref());
+
+    let next = streamer.progress(&config);
+    if next.initial_ring_complete && !progress.initial_ring_complete {
+        tracing::info!("Initial terrain ring complete: {} of {} chunks", next.completed, next.total);
+    }
+    if next.initial_ring_complete {
+        gates.open(TERRAIN_GATE);
+    }
+
+    if next.total > 0 {
+        let percent = next.percent();
+        while let Some(&milestone) = config.milestones.get(streamer.milestones_fired) {
+            if milestone > percent {
+                break;
+            }
+            milestones.write(TerrainGenMilestone {
+                percent: milestone,
+                completed: next.completed,
+                total: next.total,
+            });
+            streamer.milestones_fired += 1;
+        }
+    }
+
+    if *progress != next {
+        *progress = next;
+    }
+}
+
+/// Streams terrain chunks around the focus and reports progress
+pub struct TerrainStreamingPlugin {
+    config: TerrainStreamConfig,
+    generator: Arc<dyn ChunkGenerator>,
+}
+
+impl TerrainStreamingPlugin {
+    pub fn new(config: TerrainStreamConfig) -> Self {
+        Self {
+            config,
+            generator: Arc::new(TerrainGenerator::default()),
+        }
+    }
+
+    /// Generate chunks with `generator` instead of the default [`TerrainGenerator`]
+    pub fn with_generator(mut self, generator: impl ChunkGenerator) -> Self {
+        self.generator = Arc::new(generator);
+        self
+    }
+}
+
+impl Default for TerrainStreamingPlugin {
+    fn default() -> Self {
+        Self::new(TerrainStreamConfig::default())
+    }
+}
+
+impl Plugin for TerrainStreamingPlugin {
+    fn build(&self, app: &mut App) {
+        // Without blocking the gate is never registered; opening it is then a no-op
+        if self.config.block_until_initial_ring_complete {
+            register_loading_gate(app, TERRAIN_GATE);
+        } else if !app.is_plugin_added::<LoadingPlugin>() {
+            app.add_plugins(LoadingPlugin);
+        }
+
+        app
+            .insert_resource(self.config.clone())
+            .insert_resource(TerrainStreamer::new(Arc::clone(&self.generator)))
+            .init_resource::<TerrainGenProgress>()
+            .add_event::<TerrainGenMilestone>()
+            .add_systems(PreUpdate, stream_terrain_system.in_set(LoadingGateSet));
+
+        if self.config.meshing.is_some() {
+            app.init_resource::<ChunkMeshes>()
+                .add_systems(PreUpdate, sync_chunk_meshes_system.after(stream_terrain_system));
+        }
+    }
+
+    fn finish(&self, app: &mut App) {
+        // Vertex colors tint a plain white material; headless apps have none
+        if self.config.meshing.is_none() || app.world().contains_resource::<ChunkMaterial>() {
+            return;
+        }
+        if let Some(mut materials) = app.world_mut().get_resource_mut::<Assets<StandardMaterial>>() {
+            let material = materials.add(StandardMaterial::default());
+            app.insert_resource(ChunkMaterial(material));
+        }
+    }
+}
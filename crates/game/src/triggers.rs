@@ -0,0 +1,146 @@
+//! Data-driven trigger rules evaluated against the current fear state
+//!
+//! A rule fires a [`TriggerFired`] event when its condition becomes true
+//! (rising edge), at most once per cooldown. Game code reacts to the event by
+//! rule name, which keeps designer-facing rule tables out of system code.
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use crate::{resources::FearState, systems::update_fear_system};
+
+/// Condition checked against `FearState` every frame
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TriggerCondition {
+    /// Normalized fear at or above the threshold
+    FearAbove(f32),
+    /// Normalized fear below the threshold
+    FearBelow(f32),
+    /// Startle signal at or above the threshold
+    StartleAbove(f32),
+}
+
+impl TriggerCondition {
+    /// Whether the condition holds for the given state
+    pub fn is_met(&self, fear_state: &FearState) -> bool {
+        match *self {
+            TriggerCondition::FearAbove(threshold) => fear_state.current_fear >= threshold,
+            TriggerCondition::FearBelow(threshold) => fear_state.current_fear < threshold,
+            TriggerCondition::StartleAbove(threshold) => fear_state.current_startle >= threshold,
+        }
+    }
+}
+
+/// A named condition with an optional cooldown
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TriggerRule {
+    /// Name reported in `TriggerFired`
+    pub name: String,
+    /// When the rule fires
+    pub when: TriggerCondition,
+    /// Minimum seconds between two firings
+    #[serde(default)]
+    pub cooldown_secs: f32,
+}
+
+impl TriggerRule {
+    /// Create a rule without cooldown
+    pub fn new(name: impl Into<String>, when: TriggerCondition) -> Self {
+        Self {
+            name: name.into(),
+            when,
+            cooldown_secs: 0.0,
+        }
+    }
+
+    /// Set the cooldown
+    pub fn with_cooldown(mut self, seconds: f32) -> Self {
+        self.cooldown_secs = seconds.max(0.0);
+        self
+    }
+}
+
+/// Edge and cooldown tracking for one rule
+#[derive(Debug, Clone, Default)]
+struct RuleState {
+    active: bool,
+    last_fired: Option<f32>,
+}
+
+/// Registered trigger rules
+#[derive(Resource, Debug, Default)]
+pub struct TriggerRules {
+    rules: Vec<(TriggerRule, RuleState)>,
+}
+
+impl TriggerRules {
+    /// Register a rule
+    pub fn add(&mut self, rule: TriggerRule) {
+        self.rules.push((rule, RuleState::default()));
+    }
+
+    /// Registered rules in evaluation order
+    pub fn rules(&self) -> impl Iterator<Item = &TriggerRule> {
+        self.rules.iter().map(|(rule, _)| rule)
+    }
+
+    /// Evaluate every rule at `now` seconds and return the ones that fired
+    pub fn evaluate(&mut self, fear_state: &FearState, now: f32) -> Vec<TriggerFired> {
+        let mut fired = Vec::new();
+        for (rule, state) in &mut self.rules {
+            let met = rule.when.is_met(fear_state);
+            let rising = met && !state.active;
+            state.active = met;
+            if !rising {
+                continue;
+            }
+
+            let cooled = state
+                .last_fired
+                .is_none_or(|last| now - last >= rule.cooldown_secs);
+            if cooled {
+                state.last_fired = Some(now);
+                fired.push(TriggerFired {
+                    rule: rule.name.clone(),
+                    fear: fear_state.current_fear,
+                });
+            }
+        }
+        fired
+    }
+}
+
+/// A trigger rule fired this frame
+#[derive(Event, Debug, Clone, PartialEq)]
+pub struct TriggerFired {
+    /// Name of the rule
+    pub rule: String,
+    /// Fear level when it fired
+    pub fear: f32,
+}
+
+/// Evaluate trigger rules after fear state has been updated
+pub fn evaluate_trigger_rules(
+    fear_state: Res<FearState>,
+    time: Res<Time>,
+    mut rules: ResMut<TriggerRules>,
+    mut fired: EventWriter<TriggerFired>,
+) {
+    for event in rules.evaluate(&fear_state, time.elapsed_secs()) {
+        tracing::debug!("Trigger '{}' fired at fear={:.3}", event.rule, event.fear);
+        fired.write(event);
+    }
+}
+
+/// Plugin registering the trigger rule resource, event and evaluation system
+pub struct TriggerRulesPlugin;
+
+impl Plugin for TriggerRulesPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .init_resource::<TriggerRules>()
+            .add_event::<TriggerFired>()
+            .add_systems(Update, evaluate_trigger_rules.after(update_fear_system));
+    }
+}
+
@@ -0,0 +1,142 @@
+//! Fear atmosphere: a scripted fear trace moves fog and ambient light
+//! through the configured ranges at the configured slew rates, holds on
+//! unusable readings and snaps the scene back when disabled
+
+mod common;
+
+use bevy::{prelude::*, time::TimeUpdateStrategy};
+use common::frame_with;
+use spectremesh::{
+    atmosphere::color_temperature, install_frame_source, AtmosphereConfig, AtmosphereLevels, AtmosphereParam,
+    FearAtmosphere, FearAtmospherePlugin, SpectreMeshPlugin,
+};
+use std::time::Duration;
+
+const TICK: Duration = Duration::from_millis(100);
+
+/// Linear responses: fog over 5s, ambient light over 2s
+fn config() -> AtmosphereConfig {
+    AtmosphereConfig {
+        fog_density: AtmosphereParam::new(0.0, 0.05).with_transition(5.0),
+        fog_temperature: AtmosphereParam::new(5000.0, 8000.0).with_transition(5.0),
+        ambient_scale: AtmosphereParam::new(1.0, 0.5).with_transition(2.0),
+        ambient_temperature: AtmosphereParam::new(4000.0, 9000.0).with_transition(2.0),
+        sun_scale: AtmosphereParam::new(1.0, 0.5).with_transition(2.0),
+        sun_temperature: AtmosphereParam::new(5000.0, 7000.0).with_transition(2.0),
+        ..AtmosphereConfig::subtle()
+    }
+}
+
+fn levels(app: &App) -> AtmosphereLevels {
+    app.world().resource::<FearAtmosphere>().levels()
+}
+
+fn assert_close(actual: f32, expected: f32) {
+    assert!((actual - expected).abs() < 1e-4, "expected {}, got {}", expected, actual);
+}
+
+#[test]
+fn test_presets_differ_in_strength() {
+    let subtle = AtmosphereConfig::subtle();
+    let theatrical = AtmosphereConfig::theatrical();
+    assert!(theatrical.fog_density.fearful > subtle.fog_density.fearful);
+    assert!(theatrical.ambient_scale.fearful < subtle.ambient_scale.fearful);
+    assert!(subtle.flicker.is_none());
+    assert!(theatrical.flicker.is_some());
+
+    // Endpoints hold whatever the curve
+    for param in [theatrical.fog_density, theatrical.ambient_scale, subtle.fog_density] {
+        assert_close(param.target(0.0), param.calm);
+        assert_close(param.target(1.0), param.fearful);
+    }
+
+    // Colder is bluer
+    let warm = color_temperature(3000.0).to_srgba();
+    let cold = color_temperature(10000.0).to_srgba();
+    assert!(warm.red > warm.blue);
+    assert!(cold.blue > cold.red);
+}
+
+#[test]
+fn test_fear_trace_moves_atmosphere_at_slew_rates_and_restores_on_disable() {
+    let (sender, receiver) = async_channel::unbounded();
+
+    let mut app = App::new();
+    app.add_plugins((MinimalPlugins, SpectreMeshPlugin, FearAtmospherePlugin::new(config())))
+        .insert_resource(TimeUpdateStrategy::ManualDuration(TICK))
+        .insert_resource(AmbientLight { brightness: 200.0, ..default() });
+    install_frame_source(&mut app, receiver);
+
+    let original_fog = DistanceFog { color: Color::srgb(0.3, 0.3, 0.3), ..default() };
+    let fogged = app.world_mut().spawn((Camera3d::default(), original_fog.clone())).id();
+    let clear = app.world_mut().spawn(Camera3d::default()).id();
+    let sun = app.world_mut().spawn(DirectionalLight { illuminance: 10_000.0, ..default() }).id();
+
+    // Calm: the atmosphere sits at its calm values
+    sender.try_send(frame_with(0.0, 0.9, true)).unwrap();
+    for _ in 0..10 {
+        app.update();
+    }
+    let calm = levels(&app);
+    assert_close(calm.fog_density, 0.0);
+    assert_close(calm.ambient_temperature, 4000.0);
+    assert_eq!(app.world().resource::<AmbientLight>().color, color_temperature(4000.0));
+    assert_close(app.world().resource::<AmbientLight>().brightness, 200.0);
+    assert!(app.world().get::<DistanceFog>(clear).is_some());
+
+    // Terrified: fog thickens and light cools no faster than configured
+    sender.try_send(frame_with(1.0, 0.9, true)).unwrap();
+    let mut previous = levels(&app);
+    for _ in 0..60 {
+        app.update();
+        let now = levels(&app);
+        let fog_step = now.fog_density - previous.fog_density;
+        let ambient_step = now.ambient_temperature - previous.ambient_temperature;
+        assert!((0.0..=0.01 * 0.1 + 1e-6).contains(&fog_step), "fog stepped {}", fog_step);
+        assert!((0.0..=2500.0 * 0.1 + 1e-2).contains(&ambient_step), "ambient stepped {}", ambient_step);
+        assert!((0.0..=0.05).contains(&now.fog_density));
+        assert!((4000.0..=9000.0).contains(&now.ambient_temperature));
+        previous = now;
+    }
+    let fearful = levels(&app);
+    assert_close(fearful.fog_density, 0.05);
+    assert_close(fearful.ambient_temperature, 9000.0);
+    assert_close(fearful.ambient_scale, 0.5);
+
+    let ambient = app.world().resource::<AmbientLight>();
+    assert_eq!(ambient.color, color_temperature(9000.0));
+    assert_close(ambient.brightness, 100.0);
+    let fog = app.world().get::<DistanceFog>(fogged).unwrap();
+    assert!(matches!(fog.falloff, FogFalloff::Exponential { density } if (density - 0.05).abs() < 1e-4));
+    assert_eq!(fog.color, color_temperature(8000.0));
+    let light = app.world().get::<DirectionalLight>(sun).unwrap();
+    assert_close(light.illuminance, 5_000.0);
+    assert_eq!(light.color, color_temperature(7000.0));
+
+    // Calm but uncalibrated: the atmosphere holds
+    sender.try_send(frame_with(0.0, 0.9, false)).unwrap();
+    for _ in 0..20 {
+        app.update();
+    }
+    assert_eq!(levels(&app), fearful);
+
+    // Disabled: everything snaps back, added fog is removed
+    app.world_mut().resource_mut::<FearAtmosphere>().set_enabled(false);
+    app.update();
+    app.update();
+    let ambient = app.world().resource::<AmbientLight>();
+    assert_eq!(ambient.color, AmbientLight::default().color);
+    assert_eq!(ambient.brightness, 200.0);
+    let fog = app.world().get::<DistanceFog>(fogged).unwrap();
+    assert_eq!(fog.color, original_fog.color);
+    assert!(matches!(fog.falloff, FogFalloff::Linear { .. }));
+    assert!(app.world().get::<DistanceFog>(clear).is_none());
+    let light = app.world().get::<DirectionalLight>(sun).unwrap();
+    assert_eq!(light.illuminance, 10_000.0);
+    assert_eq!(light.color, DirectionalLight::default().color);
+
+    // Re-enabled: starts again from calm
+    app.world_mut().resource_mut::<FearAtmosphere>().set_enabled(true);
+    app.update();
+    assert!(levels(&app).fog_density <= 0.01 * 0.1 + 1e-6);
+}
@@ -0,0 +1,46 @@
+//! Bucket hysteresis: fear hovering at a threshold leaves the bucket and the
+//! terrain alone, while a sustained jump is followed within the dwell
+
+mod common;
+
+use common::frame;
+use spectremesh::resources::FearState;
+use spectremesh_core::types::{FearBucket, FearBucketSmoother};
+
+#[test]
+fn test_oscillation_at_a_threshold_keeps_the_bucket() {
+    let mut state = FearState::default();
+
+    for i in 0..300 {
+        state.update_from_frame(frame(if i % 2 == 0 { 0.32 } else { 0.34 }));
+        assert_eq!(state.current_bucket, FearBucket::Low, "frame {}", i);
+        assert!(!state.needs_terrain_rebuild(), "frame {}", i);
+    }
+}
+
+#[test]
+fn test_sustained_jump_changes_bucket_within_the_dwell() {
+    let mut state = FearState::default();
+    let dwell = state.bucket_smoother.dwell_frames;
+
+    let mut changed_at = None;
+    for i in 1..=dwell {
+        state.update_from_frame(frame(0.8));
+        if state.current_bucket == FearBucket::High {
+            changed_at.get_or_insert(i);
+        }
+    }
+    assert_eq!(changed_at, Some(dwell));
+    assert_eq!(state.previous_bucket, FearBucket::Low);
+    assert!(state.needs_terrain_rebuild());
+}
+
+#[test]
+fn test_immediate_smoother_follows_every_frame() {
+    let mut state = FearState { bucket_smoother: FearBucketSmoother::IMMEDIATE, ..Default::default() };
+
+    state.update_from_frame(frame(0.34));
+    assert_eq!(state.current_bucket, FearBucket::Medium);
+    state.update_from_frame(frame(0.32));
+    assert_eq!(state.current_bucket, FearBucket::Low);
+}
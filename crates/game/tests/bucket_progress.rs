@@ -0,0 +1,105 @@
+//! Bucket progress: how close fear is to the next bucket, for meters and
+//! anticipation effects
+
+mod common;
+
+use bevy::prelude::*;
+use bevy::time::TimeUpdateStrategy;
+use common::frame;
+use spectremesh::{resources::FearState, install_frame_source, FearModulation, SpectreMeshPlugin};
+use spectremesh_core::{
+    types::{FearBucket, FearBucketThresholds},
+    FearMapping,
+};
+use std::time::Duration;
+
+/// Hold `fear` long enough for the bucket to follow it
+fn sustain(state: &mut FearState, fear: f32) {
+    for _ in 0..state.bucket_smoother.dwell_frames {
+        state.update_from_frame(frame(fear));
+    }
+}
+
+fn assert_close(actual: f32, expected: f32) {
+    assert!((actual - expected).abs() < 1e-5, "{} != {}", actual, expected);
+}
+
+#[test]
+fn test_bucket_progress_follows_fear() {
+    let mut state = FearState::default();
+
+    sustain(&mut state, 0.0);
+    assert_eq!(state.current_bucket, FearBucket::Low);
+    assert_eq!(state.bucket_progress(), 0.0);
+
+    sustain(&mut state, 0.5);
+    assert_eq!(state.current_bucket, FearBucket::Medium);
+    assert_close(state.bucket_progress(), 0.17 / 0.33);
+
+    sustain(&mut state, 1.0);
+    assert_eq!(state.current_bucket, FearBucket::High);
+    assert_eq!(state.bucket_progress(), 1.0);
+}
+
+#[test]
+fn test_bucket_progress_respects_custom_thresholds() {
+    let mut state = FearState {
+        fear_mapping: FearMapping::default().with_thresholds(FearBucketThresholds { medium: 0.2, high: 0.8 }),
+        ..Default::default()
+    };
+
+    // Medium under the defaults, still Low here
+    sustain(&mut state, 0.1);
+    assert_eq!(state.current_bucket, FearBucket::Low);
+    assert_close(state.bucket_progress(), 0.5);
+
+    sustain(&mut state, 0.7);
+    assert_eq!(state.current_bucket, FearBucket::Medium);
+    assert_close(state.bucket_progress(), 0.5 / 0.6);
+
+    let distance = state.current_bucket.distance_to_next(state.current_fear, &state.fear_mapping.thresholds);
+    assert_close(distance.unwrap(), 0.1 / 0.6);
+}
+
+#[test]
+fn test_pending_transition_reports_against_committed_bucket() {
+    // Fear has crossed into Medium but the bucket has not followed yet
+    let state = FearState {
+        current_fear: 0.45,
+        current_bucket: FearBucket::Low,
+        ..Default::default()
+    };
+
+    assert_eq!(state.bucket_progress(), 1.0);
+    let distance = state.current_bucket.distance_to_next(state.current_fear, &state.fear_mapping.thresholds);
+    assert_eq!(distance, Some(0.0));
+    assert_eq!(FearBucket::High.distance_to_next(0.9, &state.fear_mapping.thresholds), None);
+}
+
+#[test]
+fn test_bucket_progress_drives_anticipation() {
+    let (sender, receiver) = async_channel::unbounded();
+
+    let mut app = App::new();
+    app.add_plugins((MinimalPlugins, SpectreMeshPlugin))
+        .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(100)));
+    install_frame_source(&mut app, receiver);
+    app.update();
+
+    // Just short of High: anticipation climbs toward the top of the meter
+    let mut previous = 0.0;
+    for _ in 0..5 {
+        sender.try_send(frame(0.64)).unwrap();
+        app.update();
+        let anticipation = app.world().resource::<FearModulation>().anticipation();
+        assert!(anticipation > previous, "anticipation {}", anticipation);
+        previous = anticipation;
+    }
+
+    for _ in 0..10 {
+        app.update();
+    }
+    let expected = app.world().resource::<FearState>().bucket_progress();
+    assert_close(app.world().resource::<FearModulation>().anticipation(), expected);
+    assert!(expected > 0.9);
+}
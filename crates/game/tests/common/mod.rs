@@ -0,0 +1,72 @@
+//! Fixtures shared by the integration tests: scripted fear frames and an
+//! app harness for the streamed, meshed terrain
+//!
+//! Each test file uses a different part of this module.
+#![allow(dead_code)]
+
+use bevy::prelude::*;
+use spectremesh::{
+    components::TerrainChunk, install_frame_source, resources::TerrainSettings, SpectreMeshPlugin,
+    TerrainGenProgress, TerrainStreamConfig, TerrainStreamingPlugin,
+};
+use spectremesh_core::{types::FearFrame, TerrainConfig};
+use std::time::Duration;
+
+/// A calibrated, confident frame with neutral emotion logits
+pub fn frame(fear: f32) -> FearFrame {
+    frame_with(fear, 0.9, true)
+}
+
+/// A frame with neutral emotion logits
+pub fn frame_with(fear: f32, confidence: f32, calibrated: bool) -> FearFrame {
+    FearFrame::new(fear, [0.0; 7], confidence, calibrated, Duration::ZERO)
+}
+
+/// The game's plugins with the terrain streamer, meshes and the given
+/// terrain settings, but no chunk meshing or frame source yet
+pub fn terrain_app(config: TerrainConfig, stream: TerrainStreamConfig) -> App {
+    let mut app = App::new();
+    app.add_plugins((MinimalPlugins, AssetPlugin::default(), SpectreMeshPlugin, TerrainStreamingPlugin::new(stream)))
+        .init_asset::<Mesh>()
+        .insert_resource(TerrainSettings(config));
+    app
+}
+
+/// Feed the app's fear state from a channel; the sender scripts the frames
+pub fn frame_source(app: &mut App) -> async_channel::Sender<FearFrame> {
+    let (sender, receiver) = async_channel::unbounded();
+    install_frame_source(app, receiver);
+    sender
+}
+
+/// Update until `done` holds, giving background tasks time between frames
+pub fn run_until(app: &mut App, mut done: impl FnMut(&mut App) -> bool) {
+    for _ in 0..20_000 {
+        app.update();
+        if done(app) {
+            return;
+        }
+        std::thread::sleep(Duration::from_millis(1));
+    }
+    panic!("app never settled");
+}
+
+/// Every column around the focus is streamed
+pub fn streamed(app: &App) -> bool {
+    let progress = app.world().resource::<TerrainGenProgress>();
+    progress.total > 0 && progress.remaining() == 0
+}
+
+/// Every column is streamed and no chunk waits for a mesh
+pub fn meshed(app: &mut App) -> bool {
+    let mut chunks = app.world_mut().query::<&TerrainChunk>();
+    streamed(app) && !chunks.iter(app.world()).any(|chunk| chunk.dirty)
+}
+
+pub fn update_until_streamed(app: &mut App) {
+    run_until(app, |app| streamed(app));
+}
+
+pub fn update_until_meshed(app: &mut App) {
+    run_until(app, meshed);
+}
@@ -0,0 +1,102 @@
+//! The full emotion breakdown reaches `FearState`, and gameplay hears about
+//! the dominant emotion through `DominantEmotionChanged`, once per change
+
+use bevy::prelude::*;
+use spectremesh::{
+    events::DominantEmotionChanged,
+    fear_journal::{FearEvent, FearJournal, FearJournalConfig},
+    install_frame_source,
+    resources::FearState,
+    SpectreMeshPlugin,
+};
+use spectremesh_core::types::{Emotion, EmotionLayout, EmotionLogits, FearFrame};
+use std::time::Duration;
+
+/// A frame whose logits favour `emotion`
+fn frame(emotion: Emotion) -> FearFrame {
+    let mut logits = [0.0; 7];
+    logits[emotion.index()] = 3.0;
+    FearFrame::new(0.4, logits, 0.9, true, Duration::ZERO)
+}
+
+#[derive(Resource, Default)]
+struct SeenChanges(Vec<(Emotion, Emotion)>);
+
+fn collect_changes(mut events: EventReader<DominantEmotionChanged>, mut seen: ResMut<SeenChanges>) {
+    seen.0.extend(events.read().map(|event| (event.previous, event.current)));
+}
+
+#[test]
+fn test_frames_carry_the_emotion_breakdown() {
+    let emotions = frame(Emotion::Surprise).emotions().unwrap();
+    assert_eq!(emotions.dominant(), Emotion::Surprise);
+    assert!(emotions.surprise() > 0.7);
+
+    // Another model's channels have no names
+    let wide = FearFrame::new(0.4, EmotionLogits::zeros(EmotionLayout::new(10, 2).unwrap()), 0.9, true, Duration::ZERO);
+    assert_eq!(wide.emotions(), None);
+
+    let mut state = FearState::default();
+    assert_eq!(state.dominant_emotion(), Emotion::Neutral);
+    state.update_from_frame(frame(Emotion::Happy));
+    assert_eq!(state.dominant_emotion(), Emotion::Happy);
+    assert_eq!(state.current_emotions, frame(Emotion::Happy).emotions().unwrap());
+
+    // Held through frames that measure nothing
+    state.update_from_frame(wide);
+    state.update_from_frame(frame(Emotion::Fear).with_face_present(false));
+    assert_eq!(state.dominant_emotion(), Emotion::Happy);
+}
+
+#[test]
+fn test_emotions_replay_from_the_journal() {
+    let mut journal = FearJournal::new(FearJournalConfig::default());
+    let mut state = FearState::default();
+    for (second, emotion) in [Emotion::Sad, Emotion::Angry].into_iter().enumerate() {
+        journal.commit(&mut state, Duration::from_secs(second as u64), FearEvent::frame(&frame(emotion)));
+    }
+    assert_eq!(journal.reconstruct_at(Duration::from_secs(0)).unwrap().dominant_emotion(), Emotion::Sad);
+    assert_eq!(journal.reconstruct_at(Duration::from_secs(1)).unwrap().current_emotions, state.current_emotions);
+
+    // Frames recorded before emotions were leave them where they were
+    let legacy = r#"{"type":"frame_applied","fear_score":0.4,"confidence":0.9,"calibrated":true,"startle":0.0,"capability":"full","face_center":null}"#;
+    match serde_json::from_str(legacy).unwrap() {
+        FearEvent::FrameApplied { emotions, .. } => assert_eq!(emotions, None),
+        other => panic!("Expected a frame, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_dominant_emotion_change_fires_once() {
+    let (sender, receiver) = async_channel::unbounded();
+    let mut app = App::new();
+    app.add_plugins((MinimalPlugins, SpectreMeshPlugin))
+        .init_resource::<SeenChanges>()
+        .add_systems(Update, collect_changes);
+    install_frame_source(&mut app, receiver);
+
+    let updates = [
+        vec![Emotion::Neutral, Emotion::Neutral],
+        vec![Emotion::Surprise, Emotion::Surprise, Emotion::Surprise],
+        vec![Emotion::Surprise],
+        // Two changes within one update are both reported
+        vec![Emotion::Fear, Emotion::Happy],
+        vec![Emotion::Happy],
+    ];
+    for frames in updates {
+        for emotion in frames {
+            sender.try_send(frame(emotion)).unwrap();
+        }
+        app.update();
+    }
+
+    assert_eq!(
+        app.world().resource::<SeenChanges>().0,
+        [
+            (Emotion::Neutral, Emotion::Surprise),
+            (Emotion::Surprise, Emotion::Fear),
+            (Emotion::Fear, Emotion::Happy),
+        ]
+    );
+    assert_eq!(app.world().resource::<FearState>().dominant_emotion(), Emotion::Happy);
+}
@@ -0,0 +1,62 @@
+//! Emotion layouts other than the standard seven classes: a ten-class
+//! score from the sensor reaches `FearState` whole, with fear read from the
+//! index it was sent with
+
+use spectre_sensor::proto::Score;
+use spectremesh::{remote::score_to_frame, resources::FearState};
+use spectremesh_core::{EmotionLayout, EmotionLogits, FEAR_INDEX};
+
+/// Ten logits with fear at index 7, as a ten-class daemon sends them
+fn wide_score(fear_logit: f32) -> Score {
+    let mut emotion_logits = vec![0.1; 10];
+    emotion_logits[7] = fear_logit;
+    Score {
+        normalized_fear: 0.8,
+        raw_fear_logit: fear_logit,
+        confidence: 0.9,
+        calibrated: true,
+        emotion_logits,
+        fear_index: Some(7),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_ten_class_score_reaches_fear_state() {
+    let frame = score_to_frame(&wide_score(2.5));
+    assert_eq!(frame.emotion_logits.layout(), EmotionLayout::new(10, 7).unwrap());
+    assert_eq!(frame.emotion_logits.len(), 10);
+    assert_eq!(frame.extract_fear_logit(), 2.5);
+    assert_eq!(frame.emotion_logits[FEAR_INDEX], 0.1);
+
+    let mut fear_state = FearState::default();
+    fear_state.update_from_frame(frame);
+    assert_eq!(fear_state.current_fear, 0.8);
+    assert!(fear_state.calibrated);
+    assert!(fear_state.fear_available());
+}
+
+#[test]
+fn test_older_daemons_send_the_standard_layout() {
+    let score = Score {
+        emotion_logits: vec![0.0, 0.0, 1.5, 0.0, 0.0, 0.0, 0.0],
+        fear_index: None,
+        ..wide_score(1.5)
+    };
+    let frame = score_to_frame(&score);
+    assert!(frame.emotion_logits.layout().is_standard());
+    assert_eq!(frame.extract_fear_logit(), 1.5);
+    assert_eq!(frame.emotion_logits.to_standard().unwrap(), [0.0, 0.0, 1.5, 0.0, 0.0, 0.0, 0.0]);
+}
+
+#[test]
+fn test_logits_outside_their_layout_are_dropped() {
+    // Fear past the end: zeros, never a guess at which logit was meant
+    let frame = score_to_frame(&Score { fear_index: Some(10), ..wide_score(2.5) });
+    assert_eq!(frame.emotion_logits, EmotionLogits::default());
+    assert_eq!(frame.fear_score, 0.8);
+
+    // Wider logits are never cut down to the standard seven
+    assert!(frame.emotion_logits.to_standard().is_ok());
+    assert!(score_to_frame(&wide_score(2.5)).emotion_logits.to_standard().is_err());
+}
@@ -0,0 +1,78 @@
+//! Face position from the sensor: smoothed face center on `FearState` and
+//! the face box carried through streamed scores
+
+mod common;
+
+use common::frame;
+use spectre_sensor::proto::{NormalizedRect as ProtoRect, Score};
+use spectremesh::{remote::score_to_frame, resources::FearState};
+use spectremesh_core::types::NormalizedRect;
+
+/// A 0.2 × 0.2 face box centered on (`x`, `y`)
+fn face_at(x: f32, y: f32) -> Option<NormalizedRect> {
+    Some(NormalizedRect { x: x - 0.1, y: y - 0.1, width: 0.2, height: 0.2 })
+}
+
+fn assert_close(actual: (f32, f32), expected: (f32, f32)) {
+    assert!(
+        (actual.0 - expected.0).abs() < 1e-5 && (actual.1 - expected.1).abs() < 1e-5,
+        "{:?} != {:?}",
+        actual,
+        expected
+    );
+}
+
+#[test]
+fn test_face_center_smoothing() {
+    let mut fear_state = FearState { face_smoothing: 0.5, ..FearState::default() };
+    assert_eq!(fear_state.face_center, None);
+    assert_eq!(fear_state.face_offset_from_center(), None);
+
+    // The first sighting is taken as is, later ones are blended in
+    fear_state.update_from_frame(frame(0.4).with_face_bbox(face_at(0.5, 0.5)));
+    assert_close(fear_state.face_center.unwrap(), (0.5, 0.5));
+    fear_state.update_from_frame(frame(0.4).with_face_bbox(face_at(0.7, 0.3)));
+    assert_close(fear_state.face_center.unwrap(), (0.6, 0.4));
+    fear_state.update_from_frame(frame(0.4).with_face_bbox(face_at(0.7, 0.3)));
+    assert_close(fear_state.face_center.unwrap(), (0.65, 0.35));
+
+    let offset = fear_state.face_offset_from_center().unwrap();
+    assert_close((offset.x, offset.y), (0.3, -0.3));
+
+    // No smoothing follows the raw center
+    fear_state.face_smoothing = 1.0;
+    fear_state.update_from_frame(frame(0.4).with_face_bbox(face_at(0.2, 0.8)));
+    assert_close(fear_state.face_center.unwrap(), (0.2, 0.8));
+}
+
+#[test]
+fn test_frames_without_face_clear_position() {
+    let mut fear_state = FearState::default();
+    fear_state.update_from_frame(frame(0.4).with_face_bbox(face_at(0.3, 0.6)));
+    assert!(fear_state.face_center.is_some());
+
+    fear_state.update_from_frame(frame(0.4).with_face_bbox(None));
+    assert_eq!(fear_state.face_center, None);
+    assert_eq!(fear_state.face_offset_from_center(), None);
+
+    // A face that comes back starts where it is, not where it was
+    fear_state.update_from_frame(frame(0.4).with_face_bbox(face_at(0.8, 0.2)));
+    assert_close(fear_state.face_center.unwrap(), (0.8, 0.2));
+}
+
+#[test]
+fn test_streamed_score_face_box() {
+    let score = Score {
+        normalized_fear: 0.4,
+        calibrated: true,
+        face_bbox: Some(ProtoRect { x: 0.25, y: 0.5, width: 0.25, height: 0.25 }),
+        ..Default::default()
+    };
+    let frame = score_to_frame(&score);
+    assert_eq!(frame.face_bbox, Some(NormalizedRect { x: 0.25, y: 0.5, width: 0.25, height: 0.25 }));
+    assert_eq!(frame.face_center, Some((0.375, 0.625)));
+
+    // Older daemons, or ones that keep face positions private, leave it unset
+    let frame = score_to_frame(&Score { face_bbox: None, ..score });
+    assert_eq!((frame.face_bbox, frame.face_center), (None, None));
+}
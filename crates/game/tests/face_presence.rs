@@ -0,0 +1,73 @@
+//! Lost face tracking: frames without a face reach `FearState` as a flag and
+//! leave the fear level, bucket and journal replay where they were
+
+mod common;
+
+use common::{frame, frame_with};
+use spectre_sensor::proto::Score;
+use spectremesh::{
+    fear_journal::{FearEvent, FearJournal, FearJournalConfig},
+    remote::score_to_frame,
+    resources::FearState,
+};
+use spectremesh_core::types::{FearBucket, FearFrame};
+use std::time::Duration;
+
+/// A frame without a face, repeating `fear` as the sensor does
+fn lost(fear: f32) -> FearFrame {
+    frame_with(fear, 0.0, true).with_face_present(false)
+}
+
+#[test]
+fn test_score_face_presence_conversion() {
+    let mut score = Score { normalized_fear: 0.4, calibrated: true, ..Default::default() };
+    // Older daemons leave the field unset
+    assert!(score_to_frame(&score).face_present);
+
+    score.face_present = Some(false);
+    assert!(!score_to_frame(&score).face_present);
+}
+
+#[test]
+fn test_lost_face_freezes_fear() {
+    let mut state = FearState::default();
+    for _ in 0..10 {
+        state.update_from_frame(frame(0.9));
+    }
+    assert_eq!(state.current_bucket, FearBucket::High);
+
+    // Zero confidence does not keep the flag out
+    state.update_from_frame(lost(0.1));
+    assert!(!state.face_present);
+    assert_eq!((state.current_fear, state.current_confidence), (0.9, 0.9));
+    assert_eq!(state.current_bucket, FearBucket::High);
+    assert_eq!(state.face_center, None);
+    state.tick(Duration::from_secs(5));
+    let frozen = state.distortion_intensity;
+    state.tick(Duration::from_secs(5));
+    assert_eq!(state.distortion_intensity, frozen);
+
+    state.update_from_frame(frame(0.5));
+    assert!(state.face_present);
+    assert_eq!(state.current_fear, 0.5);
+}
+
+#[test]
+fn test_face_presence_replays_from_the_journal() {
+    let mut journal = FearJournal::new(FearJournalConfig::default());
+    let mut state = FearState::default();
+    for (second, event) in [frame(0.7), lost(0.7)].iter().map(FearEvent::frame).enumerate() {
+        journal.commit(&mut state, Duration::from_secs(second as u64), event);
+    }
+
+    let replayed = journal.reconstruct_at(Duration::from_secs(1)).unwrap();
+    assert!(!replayed.face_present);
+    assert_eq!(replayed.current_fear, 0.7);
+
+    // Events recorded before the flag existed had a face
+    let legacy = r#"{"type":"frame_applied","fear_score":0.4,"confidence":0.9,"calibrated":true,"startle":0.0,"capability":"full","face_center":null}"#;
+    match serde_json::from_str(legacy).unwrap() {
+        FearEvent::FrameApplied { face_present, .. } => assert!(face_present),
+        other => panic!("Expected a frame, got {:?}", other),
+    }
+}
@@ -0,0 +1,151 @@
+//! Fear band controller: scripted fear traces with known time-in-bucket
+//! distributions, threshold events and freezing on unusable readings
+
+mod common;
+
+use bevy::{prelude::*, time::TimeUpdateStrategy};
+use common::frame_with;
+use spectremesh::{
+    install_frame_source,
+    resources::GameMetrics,
+    BandAction, BandDistribution, FearBandChanged, FearBandConfig, FearBandController, SpectreMeshPlugin,
+};
+use spectremesh_core::types::{FearBucket, FearBucketSmoother};
+use std::time::Duration;
+
+const TICK: Duration = Duration::from_millis(100);
+
+/// Ten ticks with the given number of Low, Medium and High ticks, interleaved
+fn cycle(low: usize, medium: usize, high: usize) -> Vec<FearBucket> {
+    let mut cycle = Vec::new();
+    cycle.extend(std::iter::repeat_n(FearBucket::Low, low));
+    cycle.extend(std::iter::repeat_n(FearBucket::Medium, medium));
+    cycle.extend(std::iter::repeat_n(FearBucket::High, high));
+    assert_eq!(cycle.len(), 10);
+    cycle
+}
+
+/// Replay `seconds` of a repeating cycle and return the actions signalled
+fn replay(controller: &mut FearBandController, cycle: &[FearBucket], seconds: usize) -> Vec<BandAction> {
+    cycle
+        .iter()
+        .cycle()
+        .take(seconds * 10)
+        .filter_map(|bucket| controller.update(Some(*bucket), TICK))
+        .collect()
+}
+
+fn assert_close(actual: f32, expected: f32) {
+    assert!((actual - expected).abs() < 1e-3, "expected {}, got {}", expected, actual);
+}
+
+#[test]
+fn test_scripted_traces_give_known_adjustments() {
+    let config = FearBandConfig::default()
+        .with_horizon(Duration::from_secs(60))
+        .with_warmup(Duration::from_secs(10))
+        .with_gain(1.5)
+        .with_action_threshold(0.3);
+    let mut controller = FearBandController::new(config);
+
+    // Nothing is signalled during warmup, even for an all-Low trace
+    assert!(replay(&mut controller, &cycle(10, 0, 0), 9).is_empty());
+    assert_eq!(controller.adjustment(), 0.0);
+    controller.reset();
+
+    // On target: 20/60/20 holds
+    assert!(replay(&mut controller, &cycle(2, 6, 2), 60).is_empty());
+    assert_close(controller.adjustment(), 0.0);
+    assert_eq!(controller.action(), BandAction::Hold);
+
+    // 50/30/20 has 30% too much Low: ramp up by 1.5 * 0.3
+    let actions = replay(&mut controller, &cycle(5, 3, 2), 60);
+    assert_eq!(actions, [BandAction::RampUp]);
+    let distribution = controller.distribution().unwrap();
+    assert_close(distribution.low, 0.5);
+    assert_close(distribution.high, 0.2);
+    assert_close(controller.adjustment(), 0.45);
+
+    // 10/50/40: 10% too little Low and 20% too much High, ease off by 1.5 * 0.3
+    let actions = replay(&mut controller, &cycle(1, 5, 4), 60);
+    assert_eq!(actions, [BandAction::Hold, BandAction::EaseOff]);
+    assert_close(controller.adjustment(), -0.45);
+    assert_eq!(controller.dwell().total(), Duration::from_secs(60));
+
+    // All High saturates at -1
+    replay(&mut controller, &cycle(0, 0, 10), 60);
+    assert_eq!(controller.adjustment(), -1.0);
+    assert_eq!(controller.distribution(), Some(BandDistribution::new(0.0, 0.0, 1.0)));
+}
+
+/// Band events seen by game code
+#[derive(Resource, Default)]
+struct SeenBandEvents(Vec<FearBandChanged>);
+
+fn collect_band_events(mut events: EventReader<FearBandChanged>, mut seen: ResMut<SeenBandEvents>) {
+    seen.0.extend(events.read().cloned());
+}
+
+#[test]
+fn test_controller_freezes_during_unusable_fear() {
+    let (sender, receiver) = async_channel::unbounded();
+
+    let mut app = App::new();
+    app.add_plugins((MinimalPlugins, SpectreMeshPlugin))
+        .insert_resource(TimeUpdateStrategy::ManualDuration(TICK))
+        .insert_resource(FearBandController::new(
+            FearBandConfig::default().with_horizon(Duration::from_secs(30)),
+        ))
+        .init_resource::<SeenBandEvents>()
+        .add_systems(Update, collect_band_events);
+    install_frame_source(&mut app, receiver);
+
+    // Twenty seconds of calm: ramp up
+    sender.try_send(frame_with(0.1, 0.9, true)).unwrap();
+    for _ in 0..200 {
+        app.update();
+    }
+    let events = &app.world().resource::<SeenBandEvents>().0;
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].action, BandAction::RampUp);
+    assert_eq!(events[0].distribution, BandDistribution::new(1.0, 0.0, 0.0));
+
+    let controller = app.world().resource::<FearBandController>();
+    let held_adjustment = controller.adjustment();
+    let held_dwell = controller.dwell();
+    assert!(held_adjustment > 0.3);
+    assert!(!controller.is_frozen());
+
+    // Terrified but unreadable, then uncalibrated: nothing moves
+    sender.try_send(frame_with(0.9, 0.2, true)).unwrap();
+    for _ in 0..150 {
+        app.update();
+    }
+    sender.try_send(frame_with(0.9, 0.9, false)).unwrap();
+    for _ in 0..150 {
+        app.update();
+    }
+
+    let controller = app.world().resource::<FearBandController>();
+    assert!(controller.is_frozen());
+    assert_eq!(controller.adjustment(), held_adjustment);
+    assert_eq!(controller.dwell(), held_dwell);
+    assert_eq!(app.world().resource::<SeenBandEvents>().0.len(), 1);
+
+    let metrics = app.world().resource::<GameMetrics>();
+    assert_eq!(metrics.frozen_time, Duration::from_secs(30));
+    assert_eq!(metrics.time_in_bucket.high, Duration::ZERO);
+    assert_eq!(metrics.band_adjustment, held_adjustment);
+    assert_eq!(metrics.fear_band, Some(BandDistribution::new(1.0, 0.0, 0.0)));
+
+    // Usable High fear resumes the controller, which eases off
+    for _ in 0..FearBucketSmoother::DEFAULT.dwell_frames {
+        sender.try_send(frame_with(0.9, 0.9, true)).unwrap();
+    }
+    for _ in 0..300 {
+        app.update();
+    }
+    let actions: Vec<_> = app.world().resource::<SeenBandEvents>().0.iter().map(|event| event.action).collect();
+    assert_eq!(actions, [BandAction::RampUp, BandAction::Hold, BandAction::EaseOff]);
+    assert!(app.world().resource::<GameMetrics>().time_in_bucket.high > Duration::from_secs(29));
+}
@@ -0,0 +1,129 @@
+//! Gameplay hears about fear through events rather than polling
+//! `FearState`: one `FearBucketChanged` per bucket transition, one
+//! `CalibrationCompleted` each time the sensor becomes calibrated, and one
+//! `FearSignalLost`/`FearSignalRecovered` as frames stop and resume
+
+mod common;
+
+use bevy::prelude::*;
+use common::frame_with;
+use spectremesh::{
+    events::{CalibrationCompleted, CalibrationProgressEvent, FearBucketChanged, FearSignalLost, FearSignalRecovered},
+    install_frame_source,
+    resources::{FearState, GameConfig},
+    SpectreMeshPlugin,
+};
+use spectremesh_core::types::{FearBucket, FearBucketSmoother, FearFrame};
+use std::time::Duration;
+
+const SIGNAL_TIMEOUT: Duration = Duration::from_millis(50);
+
+#[derive(Resource, Default)]
+struct Seen {
+    buckets: Vec<(FearBucket, FearBucket, f32)>,
+    calibrations: Vec<CalibrationCompleted>,
+    lost: Vec<FearSignalLost>,
+    recovered: Vec<FearSignalRecovered>,
+}
+
+fn collect(
+    mut buckets: EventReader<FearBucketChanged>,
+    mut calibrations: EventReader<CalibrationCompleted>,
+    mut lost: EventReader<FearSignalLost>,
+    mut recovered: EventReader<FearSignalRecovered>,
+    mut seen: ResMut<Seen>,
+) {
+    seen.buckets.extend(buckets.read().map(|event| (event.from, event.to, event.score)));
+    seen.calibrations.extend(calibrations.read().cloned());
+    seen.lost.extend(lost.read().cloned());
+    seen.recovered.extend(recovered.read().cloned());
+}
+
+fn app() -> (App, async_channel::Sender<FearFrame>) {
+    let (sender, receiver) = async_channel::unbounded();
+    let mut app = App::new();
+    app.add_plugins((MinimalPlugins, SpectreMeshPlugin))
+        .insert_resource(GameConfig::default().with_signal_timeout(SIGNAL_TIMEOUT))
+        .init_resource::<Seen>()
+        .add_systems(Update, collect);
+    install_frame_source(&mut app, receiver);
+    (app, sender)
+}
+
+/// Send `frames` and run one update
+fn play(app: &mut App, sender: &async_channel::Sender<FearFrame>, frames: impl IntoIterator<Item = FearFrame>) {
+    for frame in frames {
+        sender.try_send(frame).unwrap();
+    }
+    app.update();
+}
+
+#[test]
+fn test_bucket_change_fires_once_per_transition() {
+    let (mut app, sender) = app();
+    let dwell = FearBucketSmoother::DEFAULT.dwell_frames as usize;
+
+    // Held past the dwell, the bucket moves once however many frames follow
+    play(&mut app, &sender, std::iter::repeat_n(frame_with(0.9, 0.9, true), dwell + 5));
+    play(&mut app, &sender, std::iter::repeat_n(frame_with(0.95, 0.9, true), 10));
+    assert_eq!(app.world().resource::<Seen>().buckets, vec![(FearBucket::Low, FearBucket::High, 0.9)]);
+
+    play(&mut app, &sender, std::iter::repeat_n(frame_with(0.1, 0.9, true), dwell));
+    assert_eq!(
+        app.world().resource::<Seen>().buckets,
+        vec![(FearBucket::Low, FearBucket::High, 0.9), (FearBucket::High, FearBucket::Low, 0.1)]
+    );
+
+    // Terrain took the event; without a terrain to mesh the rebuild is done at once
+    assert!(!app.world().resource::<FearState>().needs_terrain_rebuild());
+}
+
+#[test]
+fn test_calibration_completed_fires_once_per_calibration() {
+    let (mut app, sender) = app();
+
+    play(&mut app, &sender, std::iter::repeat_n(frame_with(0.3, 0.9, false), 5));
+    app.world_mut().send_event(CalibrationProgressEvent {
+        progress: 1.0,
+        completed: true,
+        baseline: Some((0.25, 0.05)),
+    });
+    play(&mut app, &sender, std::iter::repeat_n(frame_with(0.3, 0.9, true), 5));
+    play(&mut app, &sender, std::iter::repeat_n(frame_with(0.3, 0.9, true), 5));
+
+    let completed = CalibrationCompleted { baseline_mean: Some(0.25), baseline_std: Some(0.05) };
+    assert_eq!(app.world().resource::<Seen>().calibrations, vec![completed.clone()]);
+
+    // A recalibration completes again; frames only say calibrated or not
+    play(&mut app, &sender, [frame_with(0.3, 0.9, false), frame_with(0.3, 0.9, true), frame_with(0.3, 0.9, true)]);
+    assert_eq!(app.world().resource::<Seen>().calibrations, vec![completed.clone(), completed]);
+}
+
+#[test]
+fn test_signal_lost_and_recovered_fire_once() {
+    let (mut app, sender) = app();
+
+    // No signal to lose before the first frame
+    std::thread::sleep(SIGNAL_TIMEOUT * 2);
+    app.update();
+    assert!(app.world().resource::<Seen>().lost.is_empty());
+
+    play(&mut app, &sender, [frame_with(0.3, 0.9, true)]);
+    std::thread::sleep(SIGNAL_TIMEOUT * 2);
+    app.update();
+    app.update();
+    {
+        let seen = app.world().resource::<Seen>();
+        assert_eq!(seen.lost.len(), 1);
+        assert!(seen.lost[0].since_last_frame > SIGNAL_TIMEOUT);
+        assert!(seen.recovered.is_empty());
+    }
+    assert!(app.world().resource::<FearState>().signal_lost);
+
+    play(&mut app, &sender, [frame_with(0.3, 0.9, true)]);
+    play(&mut app, &sender, [frame_with(0.3, 0.9, true)]);
+    let seen = app.world().resource::<Seen>();
+    assert_eq!((seen.lost.len(), seen.recovered.len()), (1, 1));
+    assert!(seen.recovered[0].gap >= SIGNAL_TIMEOUT * 2);
+    assert!(!app.world().resource::<FearState>().signal_lost);
+}
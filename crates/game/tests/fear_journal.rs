@@ -0,0 +1,166 @@
+//! Fear journal: replaying a scripted session from snapshots reproduces the
+//! `FearState` captured live at every step, across bucket commits, terrain
+//! rebuilds, mapping changes and export
+
+mod common;
+
+use bevy::prelude::*;
+use bevy::time::TimeUpdateStrategy;
+use common::frame_with;
+use spectremesh::{
+    install_frame_source,
+    resources::FearState,
+    FearEvent, FearJournal, FearJournalConfig, FearStateSnapshot, SimulationScript, SpectreMeshPlugin,
+};
+use spectremesh_core::{
+    types::{FearBucketThresholds, FearFrame, NormalizedRect, SensorCapability},
+    DistortionCurve, FearMapping,
+};
+use std::time::Duration;
+
+fn frame(index: usize, fear: f32) -> FearFrame {
+    let capability = if index % 50 == 49 {
+        SensorCapability::EmotionOffline
+    } else {
+        SensorCapability::Full
+    };
+    let face = (!index.is_multiple_of(7)).then_some(NormalizedRect {
+        x: 0.3 + (index % 5) as f32 * 0.05,
+        y: 0.4,
+        width: 0.2,
+        height: 0.25,
+    });
+    frame_with(fear, 0.9, index > 10)
+        .with_startle((index % 13) as f32 / 13.0)
+        .with_capability(capability)
+        .with_face_bbox(face)
+}
+
+/// Feed the built-in script's arc through the game, one frame per 100 ms
+/// update, returning the state captured after each update with its game time
+fn run_scripted_session(journal: FearJournal) -> (App, Vec<(Duration, FearStateSnapshot)>) {
+    let (sender, receiver) = async_channel::unbounded();
+    let mut app = App::new();
+    app.add_plugins((MinimalPlugins, SpectreMeshPlugin))
+        .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(100)))
+        .insert_resource(journal);
+    install_frame_source(&mut app, receiver);
+    app.update();
+
+    let mut captured = Vec::new();
+    let sequence = SimulationScript::builtin().fear_sequence();
+    for (index, fear) in sequence.iter().step_by(10).enumerate() {
+        sender.try_send(frame(index, *fear)).unwrap();
+        app.update();
+        if index == 200 {
+            // A tuning change mid-session is journaled like any other
+            app.world_mut().resource_scope(|world, mut fear_state: Mut<FearState>| {
+                let game_time = world.resource::<Time<Real>>().elapsed();
+                let mut journal = world.resource_mut::<FearJournal>();
+                let mapping = FearMapping::default()
+                    .with_intensities([0.2, 0.6, 1.2])
+                    .with_curve(DistortionCurve::Smoothstep);
+                journal.commit(&mut fear_state, game_time, FearEvent::MappingChanged { mapping });
+                let thresholds = FearBucketThresholds { medium: 0.25, high: 0.6 };
+                journal.commit(&mut fear_state, game_time, FearEvent::ThresholdsChanged { thresholds });
+                journal.commit(&mut fear_state, game_time, FearEvent::FaceSmoothingChanged { weight: 0.5 });
+            });
+        }
+
+        let game_time = app.world().resource::<Time<Real>>().elapsed();
+        captured.push((game_time, FearStateSnapshot::from(app.world().resource::<FearState>())));
+    }
+    (app, captured)
+}
+
+fn reconstructed(journal: &FearJournal, game_time: Duration) -> Option<FearStateSnapshot> {
+    journal.reconstruct_at(game_time).map(|state| FearStateSnapshot::from(&state))
+}
+
+#[test]
+fn test_reconstruct_matches_live_state() {
+    let config = FearJournalConfig::default().with_snapshot_interval(Duration::from_secs(1));
+    let (app, captured) = run_scripted_session(FearJournal::new(config));
+    let journal = app.world().resource::<FearJournal>();
+
+    // 360 frames, three tuning changes and a terrain rebuild per bucket change
+    let rebuilds = journal.events().filter(|entry| entry.event == FearEvent::TerrainRebuilt).count();
+    assert!(rebuilds >= 3, "only {} rebuilds", rebuilds);
+    assert_eq!(journal.len(), 360 + 3 + rebuilds);
+    assert!(journal.snapshots().count() >= 30);
+
+    for (game_time, live) in &captured {
+        assert_eq!(reconstructed(journal, *game_time).as_ref(), Some(live), "at {:?}", game_time);
+    }
+
+    // Either side of each bucket commit, including between snapshots
+    let commits: Vec<usize> = (1..captured.len())
+        .filter(|&i| captured[i].1.current_bucket != captured[i - 1].1.current_bucket)
+        .collect();
+    assert!(commits.len() >= 3, "{:?}", commits);
+    for i in commits {
+        let (before, after) = (&captured[i - 1], &captured[i]);
+        assert_eq!(after.1.previous_bucket, before.1.current_bucket);
+        assert_eq!(reconstructed(journal, before.0).unwrap(), before.1);
+        assert_eq!(reconstructed(journal, after.0).unwrap(), after.1);
+        let midway = before.0 + (after.0 - before.0) / 2;
+        assert_eq!(reconstructed(journal, midway).unwrap(), before.1);
+    }
+
+    assert_eq!(captured[200].1.face_smoothing, 0.5);
+    // Snapshots keep the whole mapping, not just its thresholds
+    assert_eq!(captured[200].1.distortion_intensities, [0.2, 0.6, 1.2]);
+    assert_eq!(captured[200].1.distortion_curve, Some(DistortionCurve::Smoothstep));
+    assert_eq!(captured[200].1.to_state().fear_mapping.curve, Some(DistortionCurve::Smoothstep));
+    assert!(reconstructed(journal, journal.start().unwrap() - Duration::from_micros(1)).is_none());
+}
+
+#[test]
+fn test_bounds_fold_history_into_snapshots() {
+    let config = FearJournalConfig::default()
+        .with_snapshot_interval(Duration::from_secs(1))
+        .with_max_events(50)
+        .with_max_snapshots(4);
+    let (app, captured) = run_scripted_session(FearJournal::new(config.clone()));
+    let journal = app.world().resource::<FearJournal>();
+
+    assert!(journal.len() <= 50);
+    assert!(journal.snapshots().count() <= 4);
+    assert!(config.max_bytes() < FearJournalConfig::default().max_bytes());
+
+    // Everything since the start of the kept history is still exact
+    let start = journal.start().unwrap();
+    let kept: Vec<_> = captured.iter().filter(|(game_time, _)| *game_time >= start).collect();
+    assert!(kept.len() >= 3, "kept {}", kept.len());
+    for (game_time, live) in kept {
+        assert_eq!(reconstructed(journal, *game_time).as_ref(), Some(live), "at {:?}", game_time);
+    }
+    assert!(reconstructed(journal, captured[0].0).is_none());
+}
+
+#[test]
+fn test_export_round_trip() {
+    let config = FearJournalConfig::default().with_snapshot_interval(Duration::from_secs(2));
+    let (app, captured) = run_scripted_session(FearJournal::new(config.clone()));
+    let journal = app.world().resource::<FearJournal>();
+
+    let exported = journal.to_jsonl();
+    assert!(exported.starts_with("{\"kind\":\"snapshot\""));
+    assert!(exported.contains("\"type\":\"terrain_rebuilt\""));
+    assert!(exported.contains("\"type\":\"thresholds_changed\""));
+    assert!(exported.contains("\"type\":\"mapping_changed\""));
+
+    let path = std::env::temp_dir().join(format!("spectre_fear_journal_{}.jsonl", std::process::id()));
+    journal.write(&path).unwrap();
+    let loaded = FearJournal::load(config.clone(), &path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    assert_eq!(loaded.len(), journal.len());
+    assert_eq!(loaded.snapshots().count(), journal.snapshots().count());
+    for (game_time, live) in captured.iter().step_by(17) {
+        assert_eq!(reconstructed(&loaded, *game_time).as_ref(), Some(live), "at {:?}", game_time);
+    }
+
+    let events_only: String = exported.lines().skip(1).map(|line| format!("{}\n", line)).collect();
+    assert!(FearJournal::from_jsonl(config, &events_only).is_err());
+}
@@ -0,0 +1,167 @@
+//! Terrain fear memory: chunks around the focus entity remember High fear,
+//! keep their scars through a world save and get their meshes rebuilt
+//! with the scars cut in
+
+mod common;
+
+use bevy::{prelude::*, time::TimeUpdateStrategy};
+use common::{frame, run_until};
+use spectremesh::{
+    components::{FearMemoryFocus, TerrainChunk},
+    install_frame_source,
+    resources::{DensityTerrain, FearState, TerrainMemory},
+    SpectreMeshPlugin,
+};
+use spectremesh_core::{
+    types::{FearBucket, FearBucketSmoother, FearFrame},
+    TerrainConfig,
+};
+use spectremesh_terrain::{Chunk, ChunkCoord, FearMemoryConfig, TerrainGenerator, TerrainSave};
+use std::collections::HashMap;
+use std::time::Duration;
+
+#[test]
+fn test_lingering_in_high_fear_scars_the_chunk() {
+    let (sender, receiver) = async_channel::unbounded();
+
+    let mut app = App::new();
+    app.add_plugins((MinimalPlugins, SpectreMeshPlugin))
+        .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(100)));
+    install_frame_source(&mut app, receiver);
+
+    let lair = ChunkCoord::new(0, 0, 0);
+    let focus = app
+        .world_mut()
+        .spawn((FearMemoryFocus, Transform::from_translation(Vec3::from_array(lair.center()))))
+        .id();
+
+    // Thirty seconds of High fear in one spot
+    for _ in 0..FearBucketSmoother::DEFAULT.dwell_frames {
+        sender.try_send(frame(0.9)).unwrap();
+    }
+    for _ in 0..300 {
+        app.update();
+    }
+
+    let memory = app.world().resource::<TerrainMemory>();
+    let lair_memory = memory.map.fear_memory(lair);
+    assert!(lair_memory > 0.0);
+    for neighbor in [ChunkCoord::new(1, 0, 0), ChunkCoord::new(0, 0, -1), ChunkCoord::new(0, 1, 0)] {
+        assert!(lair_memory > memory.map.fear_memory(neighbor));
+    }
+    assert!(memory.focus_scar_blend > 0.0);
+    assert!(memory.pending_rebuilds.contains(&lair));
+
+    // Walking away calm scars nothing new, and the lair keeps its memory
+    for _ in 0..FearBucketSmoother::DEFAULT.dwell_frames {
+        sender.try_send(frame(0.1)).unwrap();
+    }
+    let away = Vec3::new(200.0, 8.0, 200.0);
+    app.world_mut().entity_mut(focus).get_mut::<Transform>().unwrap().translation = away;
+    for _ in 0..100 {
+        app.update();
+    }
+
+    let memory = app.world().resource::<TerrainMemory>();
+    assert!(memory.map.fear_memory(lair) > lair_memory * 0.95);
+    assert_eq!(memory.map.fear_memory(ChunkCoord::from_world(away.to_array())), 0.0);
+    assert_eq!(memory.focus_scar_blend, 0.0);
+
+    // The scarred chunk generates different heights, before and after a save
+    let generator = TerrainGenerator::default();
+    let clean = generator.chunk_heights(&Chunk::new(lair), 16);
+    let scarred = generator.chunk_heights(memory.map.chunk(lair).unwrap(), 16);
+    assert_ne!(scarred, clean);
+
+    let save = TerrainSave::from_json(&memory.map.to_save().to_json().unwrap()).unwrap();
+    let restored = TerrainMemory::from_save(&save, FearMemoryConfig::default());
+    assert_eq!(restored.map.fear_memory(lair), memory.map.fear_memory(lair));
+    assert_eq!(generator.chunk_heights(restored.map.chunk(lair).unwrap(), 16), scarred);
+}
+
+fn chunk_positions(app: &mut App) -> HashMap<ChunkCoord, Vec<[f32; 3]>> {
+    let mut query = app.world_mut().query::<(&TerrainChunk, &Mesh3d)>();
+    let meshes = app.world().resource::<Assets<Mesh>>();
+    query
+        .iter(app.world())
+        .map(|(chunk, mesh)| {
+            let mesh = meshes.get(&mesh.0).unwrap();
+            (chunk.coord, mesh.attribute(Mesh::ATTRIBUTE_POSITION).unwrap().as_float3().unwrap().to_vec())
+        })
+        .collect()
+}
+
+/// Update with a `fear` frame each time until no chunk waits for a mesh
+fn update_until_meshed(app: &mut App, sender: &async_channel::Sender<FearFrame>, fear: f32) {
+    sender.try_send(frame(fear)).unwrap();
+    run_until(app, |app| {
+        let done = app.world().resource::<DensityTerrain>().pending() == 0;
+        if !done {
+            sender.try_send(frame(fear)).unwrap();
+        }
+        done
+    });
+}
+
+#[test]
+fn test_scars_are_cut_into_density_meshes() {
+    let (sender, receiver) = async_channel::unbounded();
+    let config = TerrainConfig { chunk_size: 8, ..Default::default() };
+    // Fast scarring that does not fade, rebuilt on any change at most twice a second
+    let memory = FearMemoryConfig {
+        accumulation_rate: 0.2,
+        half_life_secs: 0.0,
+        rebuild_threshold: 1e-6,
+        min_rebuild_interval_secs: 0.5,
+        ..Default::default()
+    };
+    let mut app = App::new();
+    app.add_plugins((MinimalPlugins, AssetPlugin::default(), SpectreMeshPlugin))
+        .init_asset::<Mesh>()
+        .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(100)))
+        .insert_resource(DensityTerrain::new(&config, 11))
+        .insert_resource(TerrainMemory::new(memory));
+    install_frame_source(&mut app, receiver);
+
+    // Four chunks around the ground surface at base_height 64
+    for (x, y) in [(0, 7), (0, 8), (1, 7), (1, 8)] {
+        app.world_mut().spawn(TerrainChunk::new(ChunkCoord::new(x, y, 0)));
+    }
+
+    // The High bucket's rebuild, before anything is remembered
+    for _ in 0..FearBucketSmoother::DEFAULT.dwell_frames {
+        sender.try_send(frame(0.9)).unwrap();
+    }
+    app.update();
+    update_until_meshed(&mut app, &sender, 0.9);
+    assert_eq!(app.world().resource::<FearState>().current_bucket, FearBucket::High);
+    let rebuilt = chunk_positions(&mut app);
+
+    // The focus arrives in the memory chunk above the upper two chunks
+    let lair = app.world().resource::<DensityTerrain>().memory_chunk(ChunkCoord::new(0, 8, 0));
+    assert_eq!(lair, ChunkCoord::new(0, 4, 0));
+    app.world_mut().spawn((FearMemoryFocus, Transform::from_translation(Vec3::from_array(lair.center()))));
+
+    // Memory-driven rebuilds alone cut the scars in as memory grows to its cap
+    for _ in 0..300 {
+        update_until_meshed(&mut app, &sender, 0.9);
+    }
+    assert_eq!(app.world().resource::<FearState>().current_bucket, FearBucket::High);
+    for coord in [lair, ChunkCoord::new(0, 3, 0)] {
+        assert_eq!(app.world().resource::<TerrainMemory>().map.fear_memory(coord), 1.0);
+    }
+    assert!(app.world().resource::<TerrainMemory>().pending_rebuilds.is_empty());
+
+    let scarred = chunk_positions(&mut app);
+    assert_eq!(scarred.len(), 4);
+    assert_ne!(scarred, rebuilt);
+    let intensity = FearBucket::High.distortion_intensity();
+    let terrain = app.world().resource::<DensityTerrain>();
+    let memory = &app.world().resource::<TerrainMemory>().map;
+    for (coord, positions) in &scarred {
+        let fear_memory = memory.fear_memory(terrain.memory_chunk(*coord));
+        assert_eq!(*positions, terrain.mesh_chunk(*coord, 0, intensity, fear_memory).positions);
+    }
+    let upper = ChunkCoord::new(0, 8, 0);
+    assert_ne!(scarred[&upper], terrain.mesh_chunk(upper, 0, intensity, 0.0).positions);
+}
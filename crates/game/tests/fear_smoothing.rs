@@ -0,0 +1,155 @@
+//! Smoothed fear: `FearState::tick` eases toward the latest sample without
+//! overshooting, and the shader distortion intensity follows it continuously
+
+mod common;
+
+use bevy::prelude::*;
+use bevy::time::TimeUpdateStrategy;
+use common::frame;
+use spectremesh::{
+    install_frame_source,
+    resources::{FearState, GameConfig},
+    SpectreMeshPlugin,
+};
+use spectremesh_core::{
+    types::{FearBucket, FearBucketThresholds},
+    DistortionCurve, FearMapping,
+};
+use std::time::Duration;
+
+const TICK: Duration = Duration::from_millis(16);
+
+/// Smoothed values over `ticks` ticks after a step to `fear`
+fn step_response(state: &mut FearState, fear: f32, ticks: usize) -> Vec<f32> {
+    state.update_from_frame(frame(fear));
+    (0..ticks)
+        .map(|_| {
+            state.tick(TICK);
+            state.smoothed_fear
+        })
+        .collect()
+}
+
+#[test]
+fn test_step_input_converges_monotonically() {
+    let mut state = FearState::default();
+
+    // Five time constants up to full fear, then back down
+    let rising = step_response(&mut state, 1.0, 160);
+    assert!(rising.windows(2).all(|pair| pair[1] >= pair[0]));
+    assert!(rising.iter().all(|fear| (0.0..=1.0).contains(fear)));
+    assert!(*rising.last().unwrap() > 0.99);
+    // About 63% of the way after one time constant (31 ticks of 16 ms)
+    assert!((rising[30] - (0.3 + 0.7 * 0.632)).abs() < 0.02, "{}", rising[30]);
+
+    let falling = step_response(&mut state, 0.0, 160);
+    assert!(falling.windows(2).all(|pair| pair[1] <= pair[0]));
+    assert!(falling.iter().all(|fear| (0.0..=1.0).contains(fear)));
+    assert!(*falling.last().unwrap() < 0.01);
+}
+
+#[test]
+fn test_smoothing_never_overshoots() {
+    let mut state = FearState::default();
+
+    // Long ticks, out-of-range scores and no smoothing at all stay in range
+    state.update_from_frame(frame(1.5));
+    state.tick(Duration::from_secs(60));
+    assert_eq!(state.smoothed_fear, 1.0);
+
+    state.fear_time_constant = Duration::ZERO;
+    state.update_from_frame(frame(-0.5));
+    state.tick(TICK);
+    assert_eq!(state.smoothed_fear, 0.0);
+    assert_eq!(state.get_distortion_intensity(), 0.1);
+}
+
+#[test]
+fn test_distortion_intensity_has_no_steps() {
+    let mut state = FearState::default();
+    state.update_from_frame(frame(0.95));
+
+    let mut previous = state.get_distortion_intensity();
+    for _ in 0..300 {
+        state.tick(TICK);
+        let intensity = state.get_distortion_intensity();
+        assert!(intensity >= previous && intensity - previous < 0.05, "{} -> {}", previous, intensity);
+        previous = intensity;
+    }
+    assert!(previous > 0.99);
+}
+
+#[test]
+fn test_fear_mapping_shapes_intensity_and_buckets() {
+    let mut state = FearState {
+        fear_time_constant: Duration::ZERO,
+        fear_mapping: FearMapping::default()
+            .with_thresholds(FearBucketThresholds { medium: 0.5, high: 0.9 })
+            .with_intensities([0.0, 0.3, 2.0])
+            .with_curve(DistortionCurve::Gamma { exponent: 2.0 }),
+        ..Default::default()
+    };
+
+    for _ in 0..5 {
+        state.update_from_frame(frame(0.6));
+    }
+    state.tick(TICK);
+    assert_eq!(state.current_bucket, FearBucket::Medium);
+    // The shader follows the curve from the Low to the High intensity
+    assert!((state.get_distortion_intensity() - 2.0 * 0.36).abs() < 1e-5);
+    assert_eq!(state.fear_mapping.intensity(state.current_bucket), 0.3);
+
+    // High under the defaults, still Medium here
+    for _ in 0..5 {
+        state.update_from_frame(frame(0.8));
+    }
+    assert_eq!(state.current_bucket, FearBucket::Medium);
+}
+
+#[test]
+fn test_game_config_sets_the_time_constant() {
+    let (sender, receiver) = async_channel::unbounded();
+
+    let mut app = App::new();
+    app.add_plugins((MinimalPlugins, SpectreMeshPlugin))
+        .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(100)))
+        .insert_resource(GameConfig::default().with_fear_time_constant(Duration::from_secs(2)));
+    install_frame_source(&mut app, receiver);
+    app.update();
+
+    // One frame of high fear, then the smoothed value keeps easing toward it
+    sender.try_send(frame(0.9)).unwrap();
+    let mut previous = app.world().resource::<FearState>().smoothed_fear;
+    for _ in 0..10 {
+        app.update();
+        let smoothed = app.world().resource::<FearState>().smoothed_fear;
+        assert!(smoothed > previous && smoothed < 0.9, "{}", smoothed);
+        previous = smoothed;
+    }
+
+    let fear_state = app.world().resource::<FearState>();
+    assert_eq!(fear_state.fear_time_constant, Duration::from_secs(2));
+    // Still short of halfway after a second with a two second time constant
+    assert!(previous < 0.3 + 0.6 * 0.5, "{}", previous);
+}
+
+#[test]
+fn test_game_config_sets_a_valid_fear_mapping() {
+    let mapping = FearMapping::default()
+        .with_thresholds(FearBucketThresholds { medium: 0.2, high: 0.5 })
+        .with_intensities([0.05, 0.4, 0.8])
+        .with_curve(DistortionCurve::Smoothstep);
+
+    let mut app = App::new();
+    app.add_plugins((MinimalPlugins, SpectreMeshPlugin))
+        .insert_resource(GameConfig::default().with_fear_mapping(mapping));
+    app.update();
+    assert_eq!(app.world().resource::<FearState>().fear_mapping, mapping);
+
+    // A mapping that fails validation is rejected and the one in use kept
+    let reversed = mapping.with_thresholds(FearBucketThresholds { medium: 0.7, high: 0.3 });
+    assert!(GameConfig::default().with_fear_mapping(reversed).validate().is_err());
+    app.world_mut().resource_mut::<GameConfig>().fear_mapping = reversed;
+    app.update();
+    assert_eq!(app.world().resource::<FearState>().fear_mapping, mapping);
+}
@@ -0,0 +1,58 @@
+//! Frame confidence: frames measuring fear below the configured floor are
+//! skipped, leaving the last fear level, and never reach the journal
+
+mod common;
+
+use bevy::prelude::*;
+use common::frame_with;
+use spectremesh::{
+    fear_journal::{FearJournal, FearJournalConfig},
+    install_frame_source,
+    resources::{FearState, GameConfig},
+    SpectreMeshPlugin,
+};
+use spectremesh_core::types::SensorCapability;
+
+#[test]
+fn test_low_confidence_frames_are_skipped() {
+    let mut state = FearState { min_frame_confidence: 0.4, ..FearState::default() };
+
+    state.update_from_frame(frame_with(0.6, 0.8, true));
+    assert_eq!(state.current_fear, 0.6);
+    assert_eq!(state.current_confidence, 0.8);
+
+    state.update_from_frame(frame_with(0.95, 0.2, true));
+    state.update_from_frame(frame_with(0.95, f32::NAN, true));
+    assert_eq!(state.current_fear, 0.6);
+    assert_eq!(state.current_confidence, 0.8);
+
+    // Frames without a fear measurement still report what the sensor can do
+    state.update_from_frame(frame_with(0.0, 0.0, true).with_capability(SensorCapability::EmotionOffline));
+    assert_eq!(state.sensor_capability, SensorCapability::EmotionOffline);
+    assert_eq!(state.current_fear, 0.6);
+
+    // A zero floor keeps everything
+    state.min_frame_confidence = 0.0;
+    state.update_from_frame(frame_with(0.95, 0.0, true));
+    assert_eq!(state.current_fear, 0.95);
+}
+
+#[test]
+fn test_game_config_sets_the_confidence_floor() {
+    let (sender, receiver) = async_channel::unbounded();
+
+    let mut app = App::new();
+    app.add_plugins((MinimalPlugins, SpectreMeshPlugin))
+        .insert_resource(GameConfig::default().with_min_frame_confidence(0.5))
+        .insert_resource(FearJournal::new(FearJournalConfig::default()));
+    install_frame_source(&mut app, receiver);
+    app.update();
+    assert_eq!(app.world().resource::<FearState>().min_frame_confidence, 0.5);
+
+    sender.try_send(frame_with(0.2, 0.9, true)).unwrap();
+    sender.try_send(frame_with(0.9, 0.3, true)).unwrap();
+    app.update();
+
+    assert_eq!(app.world().resource::<FearState>().current_fear, 0.2);
+    assert_eq!(app.world().resource::<FearJournal>().len(), 1);
+}
@@ -0,0 +1,73 @@
+//! Several in-process consumers share one sensor through `FearFrames`
+
+mod common;
+
+use bevy::prelude::*;
+use common::frame;
+use spectre_sensor::fanout::FrameSubscriber;
+use spectremesh::{
+    install_frame_source,
+    resources::{FearFrames, FearState},
+    SpectreMeshPlugin,
+};
+use spectremesh_core::types::FearFrame;
+
+/// A second consumer next to `FearState`, e.g. a session recorder
+#[derive(Resource)]
+struct Recorder {
+    frames: FrameSubscriber<FearFrame>,
+    seen: Vec<f32>,
+}
+
+fn record(recorder: Option<ResMut<Recorder>>) {
+    let Some(mut recorder) = recorder else {
+        return;
+    };
+    while let Some(frame) = recorder.frames.try_recv() {
+        recorder.seen.push(frame.fear_score);
+    }
+}
+
+#[test]
+fn test_consumers_each_receive_every_frame() {
+    let (sender, receiver) = async_channel::unbounded();
+
+    let mut app = App::new();
+    app.add_plugins((MinimalPlugins, SpectreMeshPlugin))
+        .add_systems(Update, record);
+    install_frame_source(&mut app, receiver);
+
+    let frames = app.world().resource::<FearFrames>().clone();
+    app.insert_resource(Recorder {
+        frames: frames.subscribe(),
+        seen: Vec::new(),
+    });
+    let mut metrics = frames.subscribe();
+
+    let sent = [0.1, 0.2, 0.3, 0.4, 0.5, 0.6];
+    for chunk in sent.chunks(2) {
+        for &fear in chunk {
+            sender.try_send(frame(fear)).unwrap();
+        }
+        app.update();
+    }
+
+    assert_eq!(app.world().resource::<Recorder>().seen, sent);
+    assert_eq!(app.world().resource::<FearState>().current_fear, 0.6);
+
+    // A consumer outside the schedule reads the same stream at its own pace
+    let mut metrics_seen = Vec::new();
+    while let Some(frame) = metrics.try_recv() {
+        metrics_seen.push(frame.fear_score);
+    }
+    assert_eq!(metrics_seen, sent);
+    assert_eq!(metrics.lagged(), 0);
+
+    // Removing one consumer leaves the others untouched
+    app.world_mut().remove_resource::<Recorder>();
+    drop(metrics);
+    sender.try_send(frame(0.9)).unwrap();
+    app.update();
+    assert_eq!(app.world().resource::<FearState>().current_fear, 0.9);
+    assert!(!sender.is_closed());
+}
@@ -0,0 +1,530 @@
+//! Integration tests for the remote (gRPC) fear source
+//!
+//! Runs a mock sensor daemon in-process and drives a headless Bevy app that
+//! consumes it through `FearSensorPlugin::remote`.
+
+use bevy::prelude::*;
+use spectre_sensor::proto::{
+    sensor_service_server::{SensorService, SensorServiceServer},
+    *,
+};
+use spectre_sensor::delta::{DeltaConfig, StreamEncoder};
+use spectre_sensor::grpc_client::CompressionEncoding;
+use spectre_sensor::session_diff::GameLog;
+use spectremesh::{
+    events::{SensorCommandResult, SensorFaultEvent},
+    resources::{BugReportKey, ClockSync, FearState, SensorCommand, SensorCommands, SensorStatus},
+    FearSensorPlugin, GameEventLog, RemoteFearSource, SpectreMeshPlugin,
+};
+use std::net::SocketAddr;
+use std::pin::Pin;
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc, Mutex,
+};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use tokio::sync::watch;
+use tokio::task::JoinHandle;
+use tokio_stream::{wrappers::ReceiverStream, Stream};
+use tonic::{transport::Server, Request, Response, Status};
+
+/// Mock daemon that streams a constant high fear score and records control actions
+struct MockSensorService {
+    actions: Arc<Mutex<Vec<String>>>,
+    pings: Arc<Mutex<Vec<PingRequest>>>,
+    /// Number of `StreamEvents` calls
+    streams: Arc<AtomicUsize>,
+    /// Score deltas sent on delta-encoded streams
+    deltas: Arc<AtomicUsize>,
+    /// Stop the sensor after this many scores per stream
+    stop_after: Option<usize>,
+    started: Instant,
+    shutdown: watch::Receiver<bool>,
+}
+
+#[tonic::async_trait]
+impl SensorService for MockSensorService {
+    type StreamEventsStream = Pin<Box<dyn Stream<Item = Result<SensorEvent, Status>> + Send>>;
+
+    async fn stream_events(
+        &self,
+        request: Request<StreamRequest>,
+    ) -> Result<Response<Self::StreamEventsStream>, Status> {
+        let (tx, rx) = tokio::sync::mpsc::channel(16);
+        let mut shutdown = self.shutdown.clone();
+        let stop_after = self.stop_after;
+        let mut encoder = StreamEncoder::new(DeltaConfig::from_request(request.get_ref()));
+        let deltas = Arc::clone(&self.deltas);
+        self.streams.fetch_add(1, Ordering::SeqCst);
+
+        tokio::spawn(async move {
+            for sent in 0.. {
+                if stop_after == Some(sent) {
+                    let stopped = SensorEvent {
+                        timestamp_us: 0,
+                        sequence: 0,
+                        event: Some(sensor_event::Event::SensorFault(SensorFault {
+                            severity: FaultSeverity::Info as i32,
+                            message: "Stopped by request".to_string(),
+                            error_code: "SENSOR_STOPPED".to_string(),
+                            recoverable: false,
+                        })),
+                    };
+                    let _ = tx.send(Ok(encoder.encode(stopped))).await;
+                    break;
+                }
+                tokio::select! {
+                    _ = shutdown.changed() => break,
+                    _ = tokio::time::sleep(Duration::from_millis(20)) => {}
+                }
+                let event = SensorEvent {
+                    timestamp_us: 0,
+                    sequence: 0,
+                    event: Some(sensor_event::Event::Score(Score {
+                        normalized_fear: 0.8,
+                        raw_fear_logit: 1.0,
+                        confidence: 0.9,
+                        calibrated: true,
+                        emotion_logits: vec![0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 0.0],
+                        inference_latency_us: 1000,
+                        startle: 0.0,
+                        capability: SensorCapability::Full as i32,
+                        face_bbox: None,
+                        face_center: None,
+                        fear_index: None,
+                        face_present: None,
+                        frame_sequence: 0,
+                    })),
+                };
+                let event = encoder.encode(event);
+                if matches!(event.event, Some(sensor_event::Event::ScoreDelta(_))) {
+                    deltas.fetch_add(1, Ordering::SeqCst);
+                }
+                if tx.send(Ok(event)).await.is_err() {
+                    break;
+                }
+            }
+        });
+
+        Ok(Response::new(Box::pin(ReceiverStream::new(rx))))
+    }
+
+    async fn get_status(
+        &self,
+        _request: Request<StatusRequest>,
+    ) -> Result<Response<StatusResponse>, Status> {
+        Ok(Response::new(StatusResponse::default()))
+    }
+
+    async fn control_calibration(
+        &self,
+        request: Request<CalibrationControl>,
+    ) -> Result<Response<CalibrationResponse>, Status> {
+        let action = match request.into_inner().action {
+            Some(calibration_control::Action::ResetCalibration(_)) => "reset",
+            Some(calibration_control::Action::FreezeCalibration(true)) => "freeze",
+            Some(calibration_control::Action::FreezeCalibration(false)) => "unfreeze",
+            Some(calibration_control::Action::StartCalibration(_)) => "start",
+            None => "none",
+        };
+        self.actions.lock().unwrap().push(action.to_string());
+
+        Ok(Response::new(CalibrationResponse {
+            success: true,
+            error_message: None,
+            phase: None,
+        }))
+    }
+
+    async fn insert_marker(
+        &self,
+        _request: Request<MarkerRequest>,
+    ) -> Result<Response<MarkerResponse>, Status> {
+        Ok(Response::new(MarkerResponse {
+            success: true,
+            timestamp_us: 0,
+            error_message: None,
+        }))
+    }
+
+    async fn purge_now(
+        &self,
+        _request: Request<PurgeRequest>,
+    ) -> Result<Response<PurgeResponse>, Status> {
+        Ok(Response::new(PurgeResponse::default()))
+    }
+
+    async fn capture_bug_report(
+        &self,
+        _request: Request<BugReportRequest>,
+    ) -> Result<Response<BugReportResponse>, Status> {
+        self.actions.lock().unwrap().push("bug_report".to_string());
+        Ok(Response::new(BugReportResponse {
+            success: true,
+            bundle_path: "/tmp/spectre_bug_reports/bug_report_1".to_string(),
+            frame_count: 300,
+            ..Default::default()
+        }))
+    }
+
+    async fn get_model_info(
+        &self,
+        _request: Request<ModelInfoRequest>,
+    ) -> Result<Response<ModelInfoResponse>, Status> {
+        Ok(Response::new(ModelInfoResponse::default()))
+    }
+
+    async fn ping(
+        &self,
+        request: Request<PingRequest>,
+    ) -> Result<Response<PingResponse>, Status> {
+        let sensor_wall_us = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_micros() as u64;
+        let request = request.into_inner();
+        let game_time_us = request.game_time_us;
+        self.pings.lock().unwrap().push(request);
+
+        Ok(Response::new(PingResponse {
+            game_time_us,
+            sensor_monotonic_us: self.started.elapsed().as_micros() as u64,
+            sensor_wall_us,
+        }))
+    }
+
+    async fn measure_once(
+        &self,
+        _request: Request<MeasureRequest>,
+    ) -> Result<Response<MeasureResponse>, Status> {
+        Err(Status::unimplemented("not used by the game"))
+    }
+
+    async fn swap_emotion_model(
+        &self,
+        _request: Request<SwapModelRequest>,
+    ) -> Result<Response<SwapModelResponse>, Status> {
+        Err(Status::unimplemented("not used by the game"))
+    }
+
+    async fn list_clients(
+        &self,
+        _request: Request<ListClientsRequest>,
+    ) -> Result<Response<ListClientsResponse>, Status> {
+        Err(Status::unimplemented("not used by the game"))
+    }
+
+    async fn get_metrics_history(
+        &self,
+        _request: Request<MetricsHistoryRequest>,
+    ) -> Result<Response<MetricsHistoryResponse>, Status> {
+        Err(Status::unimplemented("not used by the game"))
+    }
+
+    async fn update_config(
+        &self,
+        _request: Request<UpdateConfigRequest>,
+    ) -> Result<Response<UpdateConfigResponse>, Status> {
+        Err(Status::unimplemented("not used by the game"))
+    }
+
+    async fn configure(
+        &self,
+        _request: Request<ConfigureRequest>,
+    ) -> Result<Response<ConfigureResponse>, Status> {
+        Err(Status::unimplemented("not used by the game"))
+    }
+
+    async fn set_log_level(
+        &self,
+        _request: Request<SetLogLevelRequest>,
+    ) -> Result<Response<SetLogLevelResponse>, Status> {
+        Err(Status::unimplemented("not used by the game"))
+    }
+
+    async fn get_logs(
+        &self,
+        _request: Request<GetLogsRequest>,
+    ) -> Result<Response<GetLogsResponse>, Status> {
+        Err(Status::unimplemented("not used by the game"))
+    }
+}
+
+/// Running mock daemon
+struct MockServer {
+    pings: Arc<Mutex<Vec<PingRequest>>>,
+    streams: Arc<AtomicUsize>,
+    deltas: Arc<AtomicUsize>,
+    shutdown: watch::Sender<bool>,
+    handle: JoinHandle<()>,
+}
+
+impl MockServer {
+    async fn start(addr: SocketAddr, actions: Arc<Mutex<Vec<String>>>) -> Self {
+        Self::start_with(addr, actions, None).await
+    }
+
+    async fn start_with(addr: SocketAddr, actions: Arc<Mutex<Vec<String>>>, stop_after: Option<usize>) -> Self {
+        let (shutdown, shutdown_rx) = watch::channel(false);
+        let pings = Arc::new(Mutex::new(Vec::new()));
+        let streams = Arc::new(AtomicUsize::new(0));
+        let deltas = Arc::new(AtomicUsize::new(0));
+        let service = MockSensorService {
+            actions,
+            pings: Arc::clone(&pings),
+            streams: Arc::clone(&streams),
+            deltas: Arc::clone(&deltas),
+            stop_after,
+            started: Instant::now(),
+            shutdown: shutdown_rx.clone(),
+        };
+        let mut signal = shutdown_rx;
+
+        let handle = tokio::spawn(async move {
+            Server::builder()
+                .add_service(
+                    SensorServiceServer::new(service)
+                        .accept_compressed(CompressionEncoding::Gzip)
+                        .send_compressed(CompressionEncoding::Gzip),
+                )
+                .serve_with_shutdown(addr, async move {
+                    let _ = signal.changed().await;
+                })
+                .await
+                .expect("mock server failed");
+        });
+
+        // Give the listener a moment to bind
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        Self { pings, streams, deltas, shutdown, handle }
+    }
+
+    async fn stop(self) {
+        let _ = self.shutdown.send(true);
+        let _ = tokio::time::timeout(Duration::from_secs(5), self.handle).await;
+    }
+}
+
+/// Command results seen by game code
+#[derive(Resource, Default)]
+struct SeenResults(Vec<SensorCommandResult>);
+
+fn collect_results(mut events: EventReader<SensorCommandResult>, mut seen: ResMut<SeenResults>) {
+    seen.0.extend(events.read().cloned());
+}
+
+/// Sensor faults seen by game code
+#[derive(Resource, Default)]
+struct SeenFaults(Vec<SensorFaultEvent>);
+
+fn collect_faults(mut events: EventReader<SensorFaultEvent>, mut seen: ResMut<SeenFaults>) {
+    seen.0.extend(events.read().cloned());
+}
+
+fn free_addr() -> SocketAddr {
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    listener.local_addr().unwrap()
+}
+
+/// Update the app until `done` holds or roughly five seconds pass
+async fn pump_until(app: &mut App, mut done: impl FnMut(&App) -> bool) -> bool {
+    for _ in 0..500 {
+        app.update();
+        if done(app) {
+            return true;
+        }
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    false
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_remote_source_streams_reconnects_and_controls() {
+    let addr = free_addr();
+    let actions = Arc::new(Mutex::new(Vec::new()));
+    let server = MockServer::start(addr, Arc::clone(&actions)).await;
+
+    let source = RemoteFearSource::new(addr.to_string())
+        .with_reconnect_delay(Duration::from_millis(50), Duration::from_millis(200));
+
+    let mut app = App::new();
+    app.add_plugins((MinimalPlugins, SpectreMeshPlugin, FearSensorPlugin::remote(source)))
+        .init_resource::<SeenResults>()
+        .init_resource::<ButtonInput<KeyCode>>()
+        .add_systems(Update, collect_results);
+
+    // Frames reach FearState
+    assert!(
+        pump_until(&mut app, |app| {
+            let state = app.world().resource::<FearState>();
+            state.calibrated && (state.current_fear - 0.8).abs() < 1e-6
+        })
+        .await,
+        "remote frames never reached FearState"
+    );
+    assert_eq!(*app.world().resource::<SensorStatus>(), SensorStatus::Running);
+
+    // Server restart goes through Reconnecting and back to Running
+    server.stop().await;
+    assert!(
+        pump_until(&mut app, |app| {
+            matches!(app.world().resource::<SensorStatus>(), SensorStatus::Reconnecting { .. })
+        })
+        .await,
+        "status never switched to Reconnecting"
+    );
+
+    let server = MockServer::start(addr, Arc::clone(&actions)).await;
+    assert!(
+        pump_until(&mut app, |app| *app.world().resource::<SensorStatus>() == SensorStatus::Running).await,
+        "status never returned to Running"
+    );
+
+    // A reset command round-trips to the daemon and back as an event
+    assert!(app.world().resource::<SensorCommands>().reset_calibration());
+    assert!(
+        pump_until(&mut app, |app| !app.world().resource::<SeenResults>().0.is_empty()).await,
+        "reset command result never arrived"
+    );
+
+    let result = &app.world().resource::<SeenResults>().0[0];
+    assert_eq!(result.command, SensorCommand::ResetCalibration);
+    assert!(result.success);
+    assert_eq!(*actions.lock().unwrap(), vec!["reset".to_string()]);
+
+    // The debug key requests a bug report and the bundle path comes back
+    let key = app.world().resource::<BugReportKey>().0;
+    app.world_mut().resource_mut::<ButtonInput<KeyCode>>().press(key);
+    app.update();
+    app.world_mut().resource_mut::<ButtonInput<KeyCode>>().reset_all();
+    assert!(
+        pump_until(&mut app, |app| app.world().resource::<SeenResults>().0.len() == 2).await,
+        "bug report result never arrived"
+    );
+
+    let result = &app.world().resource::<SeenResults>().0[1];
+    assert_eq!(result.command, SensorCommand::CaptureBugReport);
+    assert!(result.success);
+    assert_eq!(result.detail.as_deref(), Some("/tmp/spectre_bug_reports/bug_report_1"));
+    assert_eq!(actions.lock().unwrap().last().map(String::as_str), Some("bug_report"));
+
+    server.stop().await;
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_clock_sync_published_logged_and_reported() {
+    let addr = free_addr();
+    let server = MockServer::start(addr, Arc::new(Mutex::new(Vec::new()))).await;
+    let source = RemoteFearSource::new(addr.to_string()).with_clock_sync_interval(Duration::from_millis(20));
+
+    let mut app = App::new();
+    app.add_plugins((MinimalPlugins, SpectreMeshPlugin, FearSensorPlugin::remote(source)));
+
+    assert!(
+        pump_until(&mut app, |app| {
+            app.world().resource::<ClockSync>().estimate.is_some_and(|estimate| estimate.samples >= 5)
+        })
+        .await,
+        "clock sync estimate never converged"
+    );
+
+    // Game time zero is the first update; the mock answers with this machine's wall clock
+    let estimate = app.world().resource::<ClockSync>().estimate.unwrap();
+    let first_update = app.world().resource::<Time<Real>>().first_update().unwrap();
+    let first_update_wall = SystemTime::now() - first_update.elapsed();
+    let expected_us = first_update_wall.duration_since(UNIX_EPOCH).unwrap().as_micros() as i64;
+    let slack_us = 5_000; // Reading both clocks above is not atomic
+    assert!(
+        (estimate.wall_offset_us - expected_us).unsigned_abs() <= estimate.error_us + slack_us,
+        "offset {} vs expected {} (error bound {})",
+        estimate.wall_offset_us,
+        expected_us,
+        estimate.error_us
+    );
+    assert!(estimate.confidence > 0.0);
+
+    // Later pings carry the estimate so the daemon can record it
+    assert!(server.pings.lock().unwrap().iter().any(|ping| ping.clock_sync.is_some()));
+
+    // The game's event log carries the mapping alongside gameplay events
+    app.world_mut().resource_mut::<GameEventLog>().record_at(Duration::from_secs(3), "boss_start");
+    let log = GameLog::from_jsonl(&app.world().resource::<GameEventLog>().to_jsonl()).unwrap();
+    assert_eq!(log.events, vec![(3_000_000, "boss_start".to_string())]);
+    let logged = log.clock_sync.unwrap();
+    assert!(logged.error_us <= estimate.error_us);
+    assert!((logged.wall_offset_us - expected_us).unsigned_abs() <= logged.error_us + slack_us);
+
+    server.stop().await;
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_sensor_stop_is_not_retried() {
+    let addr = free_addr();
+    let server = MockServer::start_with(addr, Arc::new(Mutex::new(Vec::new())), Some(5)).await;
+    let source = RemoteFearSource::new(addr.to_string())
+        .with_reconnect_delay(Duration::from_millis(20), Duration::from_millis(20));
+
+    let mut app = App::new();
+    app.add_plugins((MinimalPlugins, SpectreMeshPlugin, FearSensorPlugin::remote(source)))
+        .init_resource::<SeenFaults>()
+        .add_systems(Update, collect_faults);
+
+    assert!(
+        pump_until(&mut app, |app| {
+            matches!(app.world().resource::<SensorStatus>(), SensorStatus::Stopped { .. })
+        })
+        .await,
+        "status never switched to Stopped"
+    );
+    assert_eq!(
+        *app.world().resource::<SensorStatus>(),
+        SensorStatus::Stopped { reason: "Stopped by request".to_string() }
+    );
+
+    // Game code sees the terminal fault too
+    assert!(
+        pump_until(&mut app, |app| {
+            app.world().resource::<SeenFaults>().0.iter().any(|fault| fault.error_code == "SENSOR_STOPPED")
+        })
+        .await,
+        "stop fault never reached game code"
+    );
+
+    // Several reconnect delays later the worker still has not come back
+    for _ in 0..20 {
+        app.update();
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    assert_eq!(server.streams.load(Ordering::SeqCst), 1);
+    assert!(matches!(app.world().resource::<SensorStatus>(), SensorStatus::Stopped { .. }));
+
+    server.stop().await;
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_compressed_delta_stream_reaches_fear_state() {
+    let addr = free_addr();
+    let server = MockServer::start(addr, Arc::new(Mutex::new(Vec::new()))).await;
+    let source = RemoteFearSource::new(addr.to_string())
+        .with_delta_encoding(DeltaConfig::default())
+        .with_compression(CompressionEncoding::Gzip);
+
+    let mut app = App::new();
+    app.add_plugins((MinimalPlugins, SpectreMeshPlugin, FearSensorPlugin::remote(source)));
+
+    // Scores arrive as deltas after the first, but frames carry full values
+    assert!(
+        pump_until(&mut app, |app| {
+            let state = app.world().resource::<FearState>();
+            state.calibrated && (state.current_fear - 0.8).abs() < 1e-6
+        })
+        .await,
+        "delta-encoded frames never reached FearState"
+    );
+    assert!(
+        pump_until(&mut app, |_| server.deltas.load(Ordering::SeqCst) >= 3).await,
+        "the mock never sent deltas"
+    );
+    let state = app.world().resource::<FearState>();
+    assert!((state.current_fear - 0.8).abs() < 1e-6);
+    assert!((state.current_confidence - 0.9).abs() < 1e-6);
+
+    server.stop().await;
+}
@@ -0,0 +1,61 @@
+//! Sensor capability handling: degraded frames reach `FearState` and an
+//! offline emotion model leaves the last fear level untouched
+
+mod common;
+
+use bevy::prelude::*;
+use common::frame;
+use spectre_sensor::proto::{Score, SensorCapability as ProtoCapability};
+use spectremesh::{install_frame_source, remote::score_to_frame, resources::FearState, SpectreMeshPlugin};
+use spectremesh_core::types::SensorCapability;
+
+#[test]
+fn test_score_capability_conversion() {
+    let mut score = Score {
+        normalized_fear: 0.4,
+        calibrated: true,
+        ..Default::default()
+    };
+    // Older daemons leave the field unset
+    assert_eq!(score_to_frame(&score).capability, SensorCapability::Full);
+
+    score.capability = ProtoCapability::HoldLastValue as i32;
+    assert_eq!(score_to_frame(&score).capability, SensorCapability::HoldLastValue);
+
+    score.capability = ProtoCapability::EmotionOffline as i32;
+    let frame = score_to_frame(&score);
+    assert_eq!(frame.capability, SensorCapability::EmotionOffline);
+    assert!(!frame.fear_available());
+}
+
+#[test]
+fn test_capability_flag_reaches_fear_state() {
+    let (sender, receiver) = async_channel::unbounded();
+
+    let mut app = App::new();
+    app.add_plugins((MinimalPlugins, SpectreMeshPlugin));
+    install_frame_source(&mut app, receiver);
+
+    sender.try_send(frame(0.7).with_capability(SensorCapability::Full)).unwrap();
+    app.update();
+    let fear_state = app.world().resource::<FearState>();
+    assert_eq!(fear_state.sensor_capability, SensorCapability::Full);
+    assert_eq!(fear_state.current_fear, 0.7);
+
+    // Held values still drive fear
+    sender.try_send(frame(0.72).with_capability(SensorCapability::HoldLastValue)).unwrap();
+    app.update();
+    let fear_state = app.world().resource::<FearState>();
+    assert_eq!(fear_state.sensor_capability, SensorCapability::HoldLastValue);
+    assert!(fear_state.fear_available());
+    assert_eq!(fear_state.current_fear, 0.72);
+
+    // Offline frames carry no usable fear
+    sender.try_send(frame(0.0).with_capability(SensorCapability::EmotionOffline)).unwrap();
+    app.update();
+    let fear_state = app.world().resource::<FearState>();
+    assert_eq!(fear_state.sensor_capability, SensorCapability::EmotionOffline);
+    assert!(!fear_state.fear_available());
+    assert_eq!(fear_state.current_fear, 0.72);
+    assert_eq!(fear_state.current_startle, 0.0);
+}
@@ -0,0 +1,59 @@
+//! Sensor health diagnostics: fear, frame rate and dropped frames come from
+//! the frames `FearState` receives, and the signal counts as lost once
+//! frames stop arriving
+
+mod common;
+
+use bevy::{diagnostic::DiagnosticsStore, prelude::*};
+use common::frame;
+use spectremesh::{
+    diagnostics::{sensor_diagnostic, SENSOR_FEAR, SENSOR_FPS, SENSOR_FRAMES_DROPPED, SENSOR_FRAME_AGE},
+    install_frame_source,
+    resources::FearState,
+    SensorDiagnosticsPlugin, SensorHealth, SpectreMeshPlugin,
+};
+use std::time::Duration;
+
+fn diagnostic(app: &App, path: &bevy::diagnostic::DiagnosticPath) -> f64 {
+    sensor_diagnostic(app.world().resource::<DiagnosticsStore>(), path).unwrap()
+}
+
+#[test]
+fn test_signal_lost_after_receiver_dropped() {
+    let (sender, receiver) = async_channel::unbounded();
+    let mut app = App::new();
+    app.add_plugins((MinimalPlugins, SpectreMeshPlugin, SensorDiagnosticsPlugin))
+        .insert_resource(SensorHealth::default().with_signal_lost_after(Duration::from_millis(200)));
+    install_frame_source(&mut app, receiver);
+
+    // Frames 3 and 4 never arrive
+    for (fear, sequence) in [(0.2, 1), (0.4, 2), (0.6, 5)] {
+        sender.try_send(frame(fear).with_sequence(sequence)).unwrap();
+    }
+    app.update();
+    std::thread::sleep(Duration::from_millis(10));
+    app.update();
+
+    let state = app.world().resource::<FearState>();
+    assert_eq!((state.frames_received, state.frames_dropped), (3, 2));
+    assert_eq!(diagnostic(&app, &SENSOR_FRAMES_DROPPED), 2.0);
+    assert!((diagnostic(&app, &SENSOR_FEAR) - 0.6).abs() < 1e-6);
+    assert!(diagnostic(&app, &SENSOR_FPS) > 0.0);
+    assert!(!app.world().resource::<SensorHealth>().signal_lost);
+
+    drop(app.world_mut().resource_mut::<FearState>().receiver.take());
+    std::thread::sleep(Duration::from_millis(250));
+    app.update();
+
+    let health = app.world().resource::<SensorHealth>();
+    assert!(health.signal_lost);
+    assert!(health.frame_age >= Duration::from_millis(200), "{:?}", health.frame_age);
+    assert!(diagnostic(&app, &SENSOR_FRAME_AGE) >= 0.2);
+
+    // Frames arriving again restore the signal
+    let (sender, receiver) = async_channel::unbounded();
+    install_frame_source(&mut app, receiver);
+    sender.try_send(frame(0.5).with_sequence(1)).unwrap();
+    app.update();
+    assert!(!app.world().resource::<SensorHealth>().signal_lost);
+}
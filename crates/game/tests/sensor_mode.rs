@@ -0,0 +1,113 @@
+//! The configured sensor source: `connect_fear_sensor` starts the sensor
+//! picked by `SensorMode` and subscribes `FearState` to its frames
+
+use bevy::prelude::*;
+use spectre_sensor::fanout::FrameSubscriber;
+use spectremesh::{
+    connect_fear_sensor, create_headless_spectremesh_app,
+    resources::{FearFrames, FearState, SensorStatus},
+    RemoteFearSource, ReplayFearSource, SensorMode,
+};
+use spectremesh_core::types::FearFrame;
+use std::time::{Duration, Instant};
+
+/// A 15 FPS session recorded by the sensor, shared with its replay tests
+const RECORDING: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/../../spectre_sensor/tests/fixtures/session.jsonl");
+
+/// Fear of every frame the game received, subscribed before the first arrives
+#[derive(Resource)]
+struct Received(FrameSubscriber<FearFrame>, Vec<f32>);
+
+fn subscribe_received(mut commands: Commands, frames: Res<FearFrames>) {
+    commands.insert_resource(Received(frames.subscribe(), Vec::new()));
+}
+
+fn collect_received(mut received: ResMut<Received>) {
+    let Received(frames, fear) = &mut *received;
+    while let Some(frame) = frames.try_recv() {
+        fear.push(frame.fear_score);
+    }
+}
+
+/// Update `app` until `done` holds or `timeout` passes
+fn update_until(app: &mut App, timeout: Duration, mut done: impl FnMut(&mut App) -> bool) -> bool {
+    let deadline = Instant::now() + timeout;
+    while Instant::now() < deadline {
+        app.update();
+        if done(app) {
+            return true;
+        }
+        std::thread::sleep(Duration::from_millis(10));
+    }
+    false
+}
+
+#[test]
+fn test_mock_mode_drives_fear_state() {
+    let mut app = create_headless_spectremesh_app();
+    app.insert_resource(SensorMode::Mock);
+
+    app.update();
+    assert!(app.world().resource::<FearState>().receiver.is_some());
+    let initial = app.world().resource::<FearState>().current_fear;
+
+    let changed = update_until(&mut app, Duration::from_secs(2), |app| {
+        app.world().resource::<FearState>().current_fear != initial
+    });
+    assert!(changed, "fear stayed at {}", initial);
+    assert_eq!(*app.world().resource::<SensorStatus>(), SensorStatus::Running);
+}
+
+#[test]
+fn test_failed_remote_sensor_falls_back_to_mock() {
+    // Nothing listens on port 1, and the first failed attempt gives up
+    let source = RemoteFearSource::new("http://127.0.0.1:1")
+        .with_reconnect_delay(Duration::from_millis(10), Duration::from_millis(10))
+        .with_max_attempts(0);
+    let mut app = create_headless_spectremesh_app();
+    app.insert_resource(SensorMode::Grpc(source));
+
+    app.update();
+    let initial = app.world().resource::<FearState>().current_fear;
+
+    let changed = update_until(&mut app, Duration::from_secs(5), |app| {
+        app.world().resource::<FearState>().current_fear != initial
+    });
+    assert!(changed, "fear stayed at {} after the remote sensor gave up", initial);
+}
+
+#[test]
+fn test_replay_mode_feeds_the_same_curve_every_run() {
+    let run = || {
+        let mut app = create_headless_spectremesh_app();
+        app.insert_resource(SensorMode::Replay(ReplayFearSource::new(RECORDING).with_speed(4.0)))
+            .add_systems(Startup, subscribe_received.after(connect_fear_sensor))
+            .add_systems(Update, collect_received);
+
+        let finished = update_until(&mut app, Duration::from_secs(5), |app| {
+            matches!(app.world().resource::<SensorStatus>(), SensorStatus::Stopped { .. })
+        });
+        assert!(finished, "replay still {:?}", app.world().resource::<SensorStatus>());
+        app.update();
+        app.world_mut().remove_resource::<Received>().unwrap().1
+    };
+
+    let first = run();
+    assert_eq!(first.len(), 12);
+    assert_eq!(first[..3], [0.5; 3]);
+    assert_eq!(first.last(), Some(&0.66));
+    assert_eq!(run(), first);
+}
+
+#[test]
+fn test_missing_recording_falls_back_to_mock() {
+    let mut app = create_headless_spectremesh_app();
+    app.insert_resource(SensorMode::Replay(ReplayFearSource::new("/nonexistent/session.jsonl")));
+
+    app.update();
+    let initial = app.world().resource::<FearState>().current_fear;
+    let changed = update_until(&mut app, Duration::from_secs(2), |app| {
+        app.world().resource::<FearState>().current_fear != initial
+    });
+    assert!(changed, "fear stayed at {} without a recording", initial);
+}
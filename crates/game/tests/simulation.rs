@@ -0,0 +1,81 @@
+//! Headless smoke test for the sensor simulation example
+//!
+//! Builds the example's plugin set without rendering, fast-forwards virtual
+//! time through a full scripted arc and checks the phases, captions and mock
+//! sensor frames.
+
+use bevy::prelude::*;
+use bevy::time::TimeUpdateStrategy;
+use spectremesh::{
+    resources::{FearState, SensorStatus},
+    simulation::{SimulationCaption, SimulationState},
+    SimulationPlugin, SimulationScript, SpectreMeshPlugin,
+};
+use std::time::Duration;
+
+#[test]
+fn test_simulation_runs_scripted_arc() {
+    let script = SimulationScript::builtin();
+    let phase_names: Vec<String> = script.phases.iter().map(|phase| phase.name.clone()).collect();
+    assert_eq!(phase_names, ["calm", "rising", "panic", "recovery"]);
+    assert!((script.total_duration() - 120.0).abs() < f32::EPSILON);
+
+    let mut app = App::new();
+    app.add_plugins((MinimalPlugins, SpectreMeshPlugin, SimulationPlugin::new(script)))
+        .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(500)));
+    app.world_mut().resource_mut::<Time<Virtual>>().set_max_delta(Duration::from_secs(1));
+
+    // 260 half-second steps cover one 120s arc plus a few seconds of the next
+    for _ in 0..260 {
+        app.update();
+        std::thread::sleep(Duration::from_millis(10));
+    }
+
+    let state = app.world().resource::<SimulationState>();
+    assert_eq!(state.cycles, 1);
+    assert_eq!(state.phases_entered[..5], ["calm", "rising", "panic", "recovery", "calm"]);
+
+    // The mock sensor delivered frames from the start of the calm phase
+    assert_eq!(*app.world().resource::<SensorStatus>(), SensorStatus::Running);
+    let fear_state = app.world().resource::<FearState>();
+    assert!(fear_state.calibrated, "mock sensor never finished calibrating");
+    assert!(fear_state.current_fear < 0.33);
+
+    let caption = app.world().resource::<SimulationCaption>();
+    assert_eq!(caption.rule.as_deref(), Some("calm"));
+    assert!(caption.text.starts_with("Calm"));
+}
+
+#[test]
+fn test_script_arc_and_overrides() {
+    let script = SimulationScript::builtin();
+    assert!(script.fear_at(0.0) < 0.33);
+    assert!(script.fear_at(50.0) > script.fear_at(35.0));
+    assert!(script.fear_at(75.0) > 0.66);
+    assert!(script.fear_at(119.0) < 0.33);
+    assert_eq!(script.phase_at(70.0), 2);
+    assert_eq!(script.fear_sequence().len(), 3600);
+
+    let custom = SimulationScript::from_toml(
+        r#"
+        sample_rate_hz = 10.0
+
+        [[phases]]
+        name = "spike"
+        duration_secs = 2.0
+        fear_start = 0.0
+        fear_end = 1.0
+
+        [[captions]]
+        name = "scream"
+        when = { fear_above = 0.9 }
+        text = "Scream"
+        "#,
+    )
+    .unwrap();
+    assert_eq!(custom.fear_sequence().len(), 20);
+    assert!((custom.fear_at(1.0) - 0.5).abs() < 1e-6);
+    assert_eq!(custom.captions[0].rule.name, "scream");
+
+    assert!(SimulationScript::from_toml("phases = []").is_err());
+}
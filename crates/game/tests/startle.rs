@@ -0,0 +1,107 @@
+//! Startle signal handling in the game: fear state, trigger rules and
+//! attack/release modulation
+
+mod common;
+
+use bevy::prelude::*;
+use bevy::time::TimeUpdateStrategy;
+use common::frame;
+use spectremesh::{
+    resources::{FearState, STARTLE_HISTORY_LEN},
+    triggers::{TriggerCondition, TriggerRule, TriggerRules},
+    install_frame_source, FearModulation, Slew, SpectreMeshPlugin,
+};
+use std::time::Duration;
+
+#[test]
+fn test_attack_release_slew() {
+    let mut slew = Slew::attack_release(2.0);
+
+    // Immediate attack
+    assert_eq!(slew.step(0.8, 0.016), 0.8);
+
+    // Slow release at 2.0 per second
+    assert!((slew.step(0.0, 0.1) - 0.6).abs() < 1e-6);
+    assert!((slew.step(0.0, 0.1) - 0.4).abs() < 1e-6);
+
+    // A new spike during the release attacks again
+    assert_eq!(slew.step(1.0, 0.016), 1.0);
+    assert_eq!(slew.step(0.0, 1.0), 0.0);
+}
+
+#[test]
+fn test_linear_slew() {
+    let mut slew = Slew::linear(0.5);
+    assert!((slew.step(1.0, 0.5) - 0.25).abs() < 1e-6);
+    assert!((slew.step(1.0, 2.0) - 1.0).abs() < 1e-6);
+    assert!((slew.step(0.0, 1.0) - 0.5).abs() < 1e-6);
+}
+
+#[test]
+fn test_fear_state_startle_history() {
+    let mut fear_state = FearState::default();
+    fear_state.update_from_frame(frame(0.4).with_startle(0.0));
+    fear_state.update_from_frame(frame(0.8).with_startle(0.9));
+    fear_state.update_from_frame(frame(0.8).with_startle(0.3));
+
+    assert_eq!(fear_state.current_startle, 0.3);
+    assert_eq!(fear_state.peak_startle(), 0.9);
+    assert_eq!(fear_state.startle_history, [0.0, 0.9, 0.3]);
+
+    for _ in 0..STARTLE_HISTORY_LEN {
+        fear_state.update_from_frame(frame(0.8).with_startle(0.0));
+    }
+    assert_eq!(fear_state.startle_history.len(), STARTLE_HISTORY_LEN);
+    assert_eq!(fear_state.peak_startle(), 0.0);
+}
+
+#[test]
+fn test_startle_trigger_rule() {
+    let mut rules = TriggerRules::default();
+    rules.add(TriggerRule::new("jump_scare", TriggerCondition::StartleAbove(0.5)).with_cooldown(2.0));
+
+    let mut fear_state = FearState::default();
+    let mut fired = Vec::new();
+    for (t, startle) in [(0.0, 0.0), (0.1, 0.7), (0.2, 1.0), (0.3, 0.0), (1.0, 0.8), (3.0, 0.0), (3.1, 0.6)] {
+        fear_state.update_from_frame(frame(0.5).with_startle(startle));
+        fired.extend(rules.evaluate(&fear_state, t).into_iter().map(|event| (t, event.rule)));
+    }
+
+    // Rising edges at 0.1 and 3.1; the one at 1.0 is inside the cooldown
+    assert_eq!(fired, [(0.1, "jump_scare".to_string()), (3.1, "jump_scare".to_string())]);
+
+    let condition: TriggerCondition = toml::from_str::<TriggerRule>("name = \"x\"\nwhen = { startle_above = 0.4 }")
+        .unwrap()
+        .when;
+    assert_eq!(condition, TriggerCondition::StartleAbove(0.4));
+}
+
+#[test]
+fn test_startle_drives_transient_modulation() {
+    let (sender, receiver) = async_channel::unbounded();
+
+    let mut app = App::new();
+    app.add_plugins((MinimalPlugins, SpectreMeshPlugin))
+        .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(100)));
+    install_frame_source(&mut app, receiver);
+    app.update();
+
+    sender.try_send(frame(0.9).with_startle(1.0)).unwrap();
+    app.update();
+    assert_eq!(app.world().resource::<FearModulation>().transient(), 1.0);
+
+    // The sensor emits zero startle once the spike has passed
+    let mut previous = 1.0;
+    for _ in 0..4 {
+        sender.try_send(frame(0.9).with_startle(0.0)).unwrap();
+        app.update();
+        let transient = app.world().resource::<FearModulation>().transient();
+        assert!(transient < previous && transient > 0.0, "transient {}", transient);
+        previous = transient;
+    }
+
+    for _ in 0..5 {
+        app.update();
+    }
+    assert_eq!(app.world().resource::<FearModulation>().transient(), 0.0);
+}
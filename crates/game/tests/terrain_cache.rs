@@ -0,0 +1,72 @@
+//! With a chunk store, spawned chunks are generated once: a later session
+//! with the same seed and settings reads every mesh back, and the meshes
+//! match the ones generated
+
+mod common;
+
+use bevy::prelude::*;
+use common::{frame, frame_source, terrain_app, update_until_meshed};
+use spectremesh::{components::TerrainChunk, resources::DensityTerrain, TerrainStreamConfig};
+use spectremesh_core::TerrainConfig;
+use spectremesh_terrain::{storage::DEFAULT_MAX_BYTES, ChunkCoord, ChunkStore};
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Arc;
+
+const SEED: u64 = 5;
+
+fn app(store: &Arc<ChunkStore>) -> App {
+    let config = TerrainConfig { chunk_size: 8, ..Default::default() };
+    // Three columns across, each covered by two chunks
+    let stream = TerrainStreamConfig::default().with_render_distance(1);
+    let terrain = DensityTerrain::new(&config, SEED)
+        .with_chunk_store(Arc::clone(store), &config, SEED)
+        .with_chunks_per_frame(16)
+        .with_max_in_flight(16);
+    let mut app = terrain_app(config, stream);
+    app.insert_resource(terrain);
+    let sender = frame_source(&mut app);
+    app.world_mut().spawn((Camera::default(), Transform::from_xyz(4.0, 68.0, 4.0)));
+    sender.try_send(frame(0.1)).unwrap();
+    app
+}
+
+/// Mesh every chunk in view; the vertex positions of each
+fn mesh_chunks(app: &mut App) -> HashMap<ChunkCoord, Vec<[f32; 3]>> {
+    update_until_meshed(app);
+    let mut query = app.world_mut().query::<(&TerrainChunk, &Mesh3d)>();
+    let meshes = app.world().resource::<Assets<Mesh>>();
+    query
+        .iter(app.world())
+        .map(|(chunk, mesh)| {
+            assert!(!chunk.dirty, "{:?} still waits for a mesh", chunk.coord);
+            let positions = meshes.get(&mesh.0).unwrap().attribute(Mesh::ATTRIBUTE_POSITION).unwrap();
+            (chunk.coord, positions.as_float3().unwrap().to_vec())
+        })
+        .collect()
+}
+
+fn open_store(dir: &Path) -> Arc<ChunkStore> {
+    Arc::new(ChunkStore::open(dir, DEFAULT_MAX_BYTES).unwrap())
+}
+
+#[test]
+fn test_second_session_reads_chunks_back() {
+    let dir = std::env::temp_dir().join(format!("spectremesh_terrain_cache_{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+
+    let store = open_store(&dir);
+    let generated = mesh_chunks(&mut app(&store));
+    assert_eq!(generated.len(), 36);
+    let stats = store.stats();
+    assert_eq!((stats.hits, stats.misses), (0, 36));
+    assert_eq!(store.len(), 36);
+
+    let store = open_store(&dir);
+    let cached = mesh_chunks(&mut app(&store));
+    assert_eq!(store.stats().hits, 36);
+    assert_eq!(store.stats().misses, 0);
+    assert_eq!(cached, generated);
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
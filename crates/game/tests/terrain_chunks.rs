@@ -0,0 +1,87 @@
+//! Terrain chunk spawning: a square of ground chunks covers the columns the
+//! terrain streamer loads around the focus, and detail follows the camera
+
+mod common;
+
+use bevy::prelude::*;
+use common::{terrain_app, update_until_streamed};
+use spectremesh::{
+    components::{FearMemoryFocus, TerrainChunk},
+    TerrainStreamConfig,
+};
+use spectremesh_core::TerrainConfig;
+use spectremesh_terrain::ChunkCoord;
+use std::collections::HashSet;
+
+fn app(config: TerrainConfig, render_distance: u32) -> App {
+    terrain_app(config, TerrainStreamConfig::default().with_render_distance(render_distance).with_unload_distance(0))
+}
+
+fn chunk_coords(app: &mut App) -> HashSet<(i32, i32, i32)> {
+    let mut query = app.world_mut().query::<&TerrainChunk>();
+    query.iter(app.world()).map(|chunk| (chunk.coord.x, chunk.coord.y, chunk.coord.z)).collect()
+}
+
+fn chunk_entities(app: &mut App) -> HashSet<Entity> {
+    app.world_mut().query_filtered::<Entity, With<TerrainChunk>>().iter(app.world()).collect()
+}
+
+fn square(x: i32, z: i32, radius: i32) -> HashSet<(i32, i32, i32)> {
+    // base_height 64 in 16-unit chunks puts the ground in layer 4
+    (x - radius..=x + radius).flat_map(|x| (z - radius..=z + radius).map(move |z| (x, 4, z))).collect()
+}
+
+#[test]
+fn test_chunks_follow_the_streamed_columns() {
+    let mut app = app(TerrainConfig::default(), 4);
+    let camera = app.world_mut().spawn((Camera::default(), Transform::from_xyz(8.0, 100.0, 8.0))).id();
+    let focus = app.world_mut().spawn((FearMemoryFocus, Transform::from_xyz(8.0, 64.0, 8.0))).id();
+    update_until_streamed(&mut app);
+    assert_eq!(chunk_entities(&mut app).len(), 81);
+    assert_eq!(chunk_coords(&mut app), square(0, 0, 4));
+
+    // Nearby chunks keep full detail and start dirty
+    let mut query = app.world_mut().query::<&TerrainChunk>();
+    for chunk in query.iter(app.world()) {
+        assert!(chunk.dirty);
+        let distance = chunk.coord.chebyshev_distance(&ChunkCoord::new(0, 4, 0));
+        assert_eq!(chunk.lod, TerrainChunk::lod_for_distance(distance));
+    }
+    assert_eq!(TerrainChunk::lod_for_distance(0), 0);
+    assert_eq!(TerrainChunk::lod_for_distance(1), 0);
+    assert_eq!(TerrainChunk::lod_for_distance(4), 2);
+
+    // Two columns east and one north: a column and a row are swapped out
+    let before = chunk_entities(&mut app);
+    app.world_mut().get_mut::<Transform>(focus).unwrap().translation = Vec3::new(40.0, 64.0, -8.0);
+    app.world_mut().get_mut::<Transform>(camera).unwrap().translation = Vec3::new(40.0, 100.0, -8.0);
+    update_until_streamed(&mut app);
+    assert_eq!(chunk_coords(&mut app), square(2, -1, 4));
+    let after = chunk_entities(&mut app);
+    assert_eq!(after.len(), 81);
+    // Chunks in both squares are kept, not respawned
+    assert_eq!(before.intersection(&after).count(), square(0, 0, 4).intersection(&square(2, -1, 4)).count());
+
+    // A chunk kept across the move takes its new distance's detail level
+    let mut query = app.world_mut().query::<&TerrainChunk>();
+    let kept = query.iter(app.world()).find(|chunk| chunk.coord == ChunkCoord::new(0, 4, -4)).unwrap();
+    assert_eq!(kept.lod, 1);
+
+    // A shorter render distance unloads the outer ring, and its chunks go with it
+    app.world_mut().resource_mut::<TerrainStreamConfig>().render_distance = 2;
+    app.update();
+    assert_eq!(chunk_coords(&mut app), square(2, -1, 2));
+}
+
+#[test]
+fn test_smaller_chunks_tile_each_column() {
+    // 8-unit chunks: two per 16-unit column along each axis, ground in layer 8
+    let mut app = app(TerrainConfig { chunk_size: 8, ..Default::default() }, 1);
+    update_until_streamed(&mut app);
+
+    let expected: HashSet<_> = (-2..4).flat_map(|x| (-2..4).map(move |z| (x, 8, z))).collect();
+    assert_eq!(chunk_coords(&mut app), expected);
+    // Without a camera every chunk keeps full detail
+    let mut query = app.world_mut().query::<&TerrainChunk>();
+    assert!(query.iter(app.world()).all(|chunk| chunk.lod == 0));
+}
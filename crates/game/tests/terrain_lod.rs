@@ -0,0 +1,80 @@
+//! Chunks further from the camera are meshed with fewer cells, and a chunk
+//! whose distance band changes as the camera moves is meshed again at its
+//! new level of detail
+
+mod common;
+
+use bevy::prelude::*;
+use common::{frame, frame_source, terrain_app, update_until_meshed};
+use spectremesh::{
+    components::TerrainChunk,
+    resources::{DensityTerrain, FearState},
+    TerrainStreamConfig,
+};
+use spectremesh_core::{types::FearFrame, TerrainConfig};
+use spectremesh_terrain::ChunkCoord;
+use std::collections::HashMap;
+
+fn app() -> (App, async_channel::Sender<FearFrame>) {
+    let config = TerrainConfig { chunk_size: 8, ..Default::default() };
+    // Five columns across, each covered by two chunks
+    let stream = TerrainStreamConfig::default().with_render_distance(2);
+    let mut app = terrain_app(config.clone(), stream);
+    app.insert_resource(DensityTerrain::new(&config, 5).with_chunks_per_frame(16).with_max_in_flight(16));
+    let sender = frame_source(&mut app);
+    // On the ground, in chunk (0, 8, 0)
+    app.world_mut().spawn((Camera::default(), Transform::from_xyz(4.0, 68.0, 4.0)));
+    (app, sender)
+}
+
+/// Level of detail and mesh vertex count of every chunk
+fn chunk_meshes(app: &mut App) -> HashMap<ChunkCoord, (u8, usize)> {
+    let mut query = app.world_mut().query::<(&TerrainChunk, &Mesh3d)>();
+    let meshes = app.world().resource::<Assets<Mesh>>();
+    query
+        .iter(app.world())
+        .map(|(chunk, mesh)| {
+            assert!(!chunk.dirty, "{:?} still waits for a mesh", chunk.coord);
+            (chunk.coord, (chunk.lod, meshes.get(&mesh.0).unwrap().count_vertices()))
+        })
+        .collect()
+}
+
+#[test]
+fn test_chunks_remesh_when_their_band_changes() {
+    let (mut app, sender) = app();
+    sender.try_send(frame(0.1)).unwrap();
+    app.update();
+    update_until_meshed(&mut app);
+
+    let intensity = app.world().resource::<FearState>().current_bucket.distortion_intensity();
+    let before = chunk_meshes(&mut app);
+    assert_eq!(before.len(), 100);
+    let (home, east) = (ChunkCoord::new(0, 8, 0), ChunkCoord::new(3, 8, 0));
+    assert_eq!((before[&home].0, before[&east].0), (0, 1));
+    // Each mesh is the one its level gives, skirts and all
+    let terrain = app.world().resource::<DensityTerrain>();
+    for (coord, (lod, vertices)) in &before {
+        assert_eq!(terrain.mesh_chunk(*coord, *lod, intensity, 0.0).positions.len(), *vertices);
+    }
+
+    // Three chunks east the two swap bands
+    let mut camera = app.world_mut().query_filtered::<&mut Transform, With<Camera>>();
+    camera.single_mut(app.world_mut()).unwrap().translation.x += 24.0;
+    app.update();
+    update_until_meshed(&mut app);
+
+    let after = chunk_meshes(&mut app);
+    assert_eq!((after[&home].0, after[&east].0), (1, 0));
+    let terrain = app.world().resource::<DensityTerrain>();
+    for coord in [home, east] {
+        let fine = terrain.mesh_chunk(coord, 0, intensity, 0.0).surface_vertices;
+        let coarse = terrain.mesh_chunk(coord, 1, intensity, 0.0).surface_vertices;
+        assert!(coarse * 2 < fine, "{:?}: {} vertices at level 1, {} at level 0", coord, coarse, fine);
+    }
+    assert!(after[&home].1 < before[&home].1);
+    assert!(after[&east].1 > before[&east].1);
+    // A chunk that kept its band kept its mesh
+    let north = ChunkCoord::new(1, 8, 3);
+    assert_eq!(after[&north], before[&north]);
+}
@@ -0,0 +1,39 @@
+//! Terrain material: every `TerrainMaterial` asset's uniforms follow the
+//! fear state each frame
+
+mod common;
+
+use bevy::prelude::*;
+use bevy::time::TimeUpdateStrategy;
+use common::frame;
+use spectremesh::{install_frame_source, resources::FearState, SpectreMeshPlugin, TerrainMaterial};
+use std::time::Duration;
+
+#[test]
+fn test_material_uniforms_track_fear_state() {
+    let (sender, receiver) = async_channel::unbounded();
+
+    let mut app = App::new();
+    app.add_plugins((MinimalPlugins, AssetPlugin::default(), SpectreMeshPlugin))
+        .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(100)));
+    install_frame_source(&mut app, receiver);
+    let handle = app.world_mut().resource_mut::<Assets<TerrainMaterial>>().add(TerrainMaterial::default());
+    app.update();
+
+    // Still rising after two seconds, short of the High midpoint
+    sender.try_send(frame(0.75)).unwrap();
+    let mut previous = 0.0;
+    for _ in 0..20 {
+        app.update();
+        let fear_state = app.world().resource::<FearState>();
+        let uniforms = app.world().resource::<Assets<TerrainMaterial>>().get(&handle).unwrap().uniforms;
+
+        assert_eq!(uniforms.distortion_intensity, fear_state.get_distortion_intensity());
+        assert_eq!(uniforms.fear_level, fear_state.smoothed_fear);
+        assert!(uniforms.distortion_intensity > previous, "{}", uniforms.distortion_intensity);
+        previous = uniforms.distortion_intensity;
+    }
+    let uniforms = app.world().resource::<Assets<TerrainMaterial>>().get(&handle).unwrap().uniforms;
+    assert!(uniforms.time > 1.9, "{}", uniforms.time);
+    assert!(uniforms.distortion_intensity > 0.8);
+}
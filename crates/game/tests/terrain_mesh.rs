@@ -0,0 +1,33 @@
+//! Bevy meshes from terrain chunk meshes, with and without baked colors
+
+use bevy::math::Vec3;
+use bevy::render::mesh::{Mesh, VertexAttributeValues};
+use spectremesh::terrain_mesh::{chunk_mesh, chunk_transform};
+use spectremesh_terrain::{Chunk, ChunkCoord, ChunkMesher, ColorBakeConfig, MesherConfig, TerrainGenerator};
+
+fn colors(mesh: &Mesh) -> Option<&Vec<[f32; 4]>> {
+    match mesh.attribute(Mesh::ATTRIBUTE_COLOR)? {
+        VertexAttributeValues::Float32x4(colors) => Some(colors),
+        other => panic!("unexpected color format {other:?}"),
+    }
+}
+
+#[test]
+fn test_baked_colors_become_vertex_colors() {
+    let chunk = Chunk::new(ChunkCoord::new(1, 0, 2));
+    let heights = TerrainGenerator::default().chunk_heights(&chunk, 8);
+    let baking = ChunkMesher::new(MesherConfig {
+        bake_vertex_colors: Some(ColorBakeConfig::default()),
+    });
+
+    let calm = chunk_mesh(&baking.mesh(&chunk, &heights, 0.0).unwrap());
+    let afraid = chunk_mesh(&baking.mesh(&chunk, &heights, 1.0).unwrap());
+    assert_eq!(calm.count_vertices(), 81);
+    assert_eq!(calm.indices().unwrap().len(), 8 * 8 * 6);
+    assert_eq!(colors(&calm).unwrap().len(), 81);
+    assert_ne!(colors(&calm), colors(&afraid));
+
+    let plain = ChunkMesher::default().mesh(&chunk, &heights, 1.0).unwrap();
+    assert!(colors(&chunk_mesh(&plain)).is_none());
+    assert_eq!(chunk_transform(&plain).translation, Vec3::new(16.0, 0.0, 32.0));
+}
@@ -0,0 +1,117 @@
+//! Chunk meshes are built on the async compute pool: however many chunks go
+//! dirty at once, each frame swaps in no more meshes than its cap, nearest
+//! the camera first, and every chunk gets its mesh in the end
+
+mod common;
+
+use bevy::prelude::*;
+use common::{frame, frame_source, meshed, run_until, terrain_app, update_until_streamed};
+use spectremesh::{
+    components::{FearMemoryFocus, TerrainChunk},
+    resources::{DensityTerrain, FearState},
+    TerrainStreamConfig,
+};
+use spectremesh_core::{types::{FearBucketSmoother, FearFrame}, TerrainConfig};
+use spectremesh_terrain::ChunkCoord;
+use std::collections::{HashMap, HashSet};
+use std::time::Duration;
+
+/// Meshes swapped in per frame at most
+const CAP: usize = 3;
+
+/// Meshes built at once at most
+const IN_FLIGHT: usize = 6;
+
+/// Render distance 3 streams a 7 by 7 square of columns around the focus,
+/// each covered by 2 by 2 chunks
+const CHUNKS: usize = 196;
+
+fn app(terrain: impl FnOnce(DensityTerrain) -> DensityTerrain) -> (App, async_channel::Sender<FearFrame>) {
+    let config = TerrainConfig { chunk_size: 8, ..Default::default() };
+    let stream = TerrainStreamConfig::default().with_render_distance(3).with_unload_distance(0);
+    let mut app = terrain_app(config.clone(), stream);
+    let sender = frame_source(&mut app);
+    // On the ground, in chunk (0, 8, 0)
+    app.world_mut().spawn((Camera::default(), FearMemoryFocus, Transform::from_xyz(4.0, 68.0, 4.0)));
+
+    // Every chunk is spawned before any is meshed, so all go dirty at once
+    update_until_streamed(&mut app);
+    app.insert_resource(terrain(DensityTerrain::new(&config, 5)));
+    (app, sender)
+}
+
+fn mesh_handles(app: &mut App) -> HashMap<ChunkCoord, Option<AssetId<Mesh>>> {
+    let mut query = app.world_mut().query::<(&TerrainChunk, Option<&Mesh3d>)>();
+    query.iter(app.world()).map(|(chunk, mesh)| (chunk.coord, mesh.map(|mesh| mesh.id()))).collect()
+}
+
+/// Update until no chunk waits for a mesh, returning the chunks each update swapped a mesh in for
+fn update_until_meshed(app: &mut App) -> Vec<Vec<ChunkCoord>> {
+    let mut updates = Vec::new();
+    let mut before = mesh_handles(app);
+    run_until(app, |app| {
+        assert!(app.world().resource::<DensityTerrain>().in_flight() <= IN_FLIGHT);
+        let after = mesh_handles(app);
+        let swapped = after
+            .iter()
+            .filter(|(coord, mesh)| before.get(coord).is_some_and(|previous| previous != *mesh))
+            .map(|(coord, _)| *coord)
+            .collect();
+        updates.push(swapped);
+        before = after;
+        meshed(app)
+    });
+    updates
+}
+
+#[test]
+fn test_mass_rebuild_stays_within_frame_cap() {
+    let (mut app, sender) = app(|terrain| {
+        terrain.with_chunks_per_frame(CAP).with_max_in_flight(IN_FLIGHT).with_frame_budget(Duration::from_millis(50))
+    });
+    sender.try_send(frame(0.1)).unwrap();
+    app.update();
+    assert_eq!(mesh_handles(&mut app).len(), CHUNKS);
+
+    let updates = update_until_meshed(&mut app);
+    assert!(updates.iter().all(|swapped| swapped.len() <= CAP));
+    assert!(mesh_handles(&mut app).values().all(Option::is_some));
+    // The first chunks started are the ones around the camera
+    let centre = ChunkCoord::new(0, 8, 0);
+    let first = updates.iter().find(|swapped| !swapped.is_empty()).unwrap();
+    assert!(first.iter().all(|coord| coord.chebyshev_distance(&centre) <= 1), "{:?}", first);
+
+    // A bucket change dirties every chunk at once
+    let calm = mesh_handles(&mut app);
+    for _ in 0..FearBucketSmoother::DEFAULT.dwell_frames {
+        sender.try_send(frame(0.95)).unwrap();
+    }
+    let updates = update_until_meshed(&mut app);
+    assert!(updates.iter().all(|swapped| swapped.len() <= CAP));
+    let rebuilt: HashSet<ChunkCoord> = updates.into_iter().flatten().collect();
+    assert_eq!(rebuilt.len(), CHUNKS);
+    let afraid = mesh_handles(&mut app);
+    assert!(calm.iter().all(|(coord, mesh)| afraid[coord] != *mesh));
+    assert!(!app.world().resource::<FearState>().needs_terrain_rebuild());
+}
+
+#[test]
+fn test_spent_budget_swaps_one_mesh_per_frame() {
+    let (mut app, sender) = app(|terrain| {
+        terrain.with_chunks_per_frame(CAP).with_max_in_flight(IN_FLIGHT).with_frame_budget(Duration::ZERO)
+    });
+    sender.try_send(frame(0.1)).unwrap();
+    for _ in 0..3 {
+        app.update();
+    }
+
+    // Chunks over the columns the focus leaves behind are dropped, queued or in flight
+    let mut camera = app.world_mut().query_filtered::<&mut Transform, With<Camera>>();
+    camera.single_mut(app.world_mut()).unwrap().translation.x += 800.0;
+    let updates = update_until_meshed(&mut app);
+    assert!(updates.iter().all(|swapped| swapped.len() <= 1));
+    assert_eq!(updates.iter().map(Vec::len).sum::<usize>(), CHUNKS);
+    let meshes = mesh_handles(&mut app);
+    assert_eq!(meshes.len(), CHUNKS);
+    assert!(meshes.iter().all(|(coord, mesh)| coord.x > 90 && mesh.is_some()));
+}
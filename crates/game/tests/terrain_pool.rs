@@ -0,0 +1,105 @@
+//! Streamed chunk meshes: buffers recycled through the pool as the focus
+//! moves, and regenerated chunks rewritten under their old mesh handle
+
+mod common;
+
+use bevy::prelude::*;
+use common::run_until;
+use spectremesh::{
+    components::FearMemoryFocus, resources::GameMetrics, terrain_mesh::ChunkMeshes, TerrainStreamConfig,
+    TerrainStreamer, TerrainStreamingPlugin,
+};
+use spectremesh_terrain::{ChunkCoord, MeshPoolConfig, MesherConfig, CHUNK_SIZE};
+
+/// Render distance 1 around the focus
+const CHUNKS: usize = 9;
+
+fn app(config: TerrainStreamConfig) -> App {
+    let mut app = App::new();
+    app.add_plugins((MinimalPlugins, AssetPlugin::default()))
+        .init_asset::<Mesh>()
+        .init_resource::<GameMetrics>()
+        .add_plugins(TerrainStreamingPlugin::new(config));
+    app.world_mut().spawn((FearMemoryFocus, Transform::default()));
+    app
+}
+
+fn config() -> TerrainStreamConfig {
+    TerrainStreamConfig::default()
+        .with_render_distance(1)
+        .with_meshing(MesherConfig::default())
+        .with_unload_distance(1)
+}
+
+fn metrics(app: &App) -> &GameMetrics {
+    app.world().resource::<GameMetrics>()
+}
+
+fn chunk_meshes(app: &App) -> &ChunkMeshes {
+    app.world().resource::<ChunkMeshes>()
+}
+
+fn mesh_entities(app: &mut App) -> usize {
+    app.world_mut().query::<&Mesh3d>().iter(app.world()).count()
+}
+
+#[test]
+fn test_regenerated_chunk_keeps_its_mesh_handle() {
+    let mut app = app(config());
+    run_until(&mut app, |app| chunk_meshes(app).len() == CHUNKS);
+    assert_eq!(metrics(&app).mesh_pool.misses, CHUNKS as u64);
+    assert_eq!(metrics(&app).chunk_meshes_added, CHUNKS as u64);
+
+    let origin = ChunkCoord::new(0, 0, 0);
+    let handle = chunk_meshes(&app).mesh(origin).unwrap().id();
+    app.world_mut().resource_mut::<TerrainStreamer>().regenerate(origin);
+    run_until(&mut app, |app| metrics(app).chunk_meshes_rewritten == 1);
+
+    assert_eq!(chunk_meshes(&app).mesh(origin).unwrap().id(), handle);
+    assert_eq!(app.world().resource::<Assets<Mesh>>().len(), CHUNKS);
+    assert_eq!(metrics(&app).chunk_meshes_added, CHUNKS as u64);
+    // The replaced chunk's buffers serve the next regeneration
+    assert_eq!(metrics(&app).mesh_pool.misses, CHUNKS as u64 + 1);
+
+    app.world_mut().resource_mut::<TerrainStreamer>().regenerate(origin);
+    run_until(&mut app, |app| metrics(app).chunk_meshes_rewritten == 2);
+    assert_eq!(metrics(&app).mesh_pool.hits, 1);
+    assert_eq!(chunk_meshes(&app).mesh(origin).unwrap().id(), handle);
+}
+
+#[test]
+fn test_unloaded_chunks_feed_new_ones() {
+    let mut app = app(config());
+    run_until(&mut app, |app| chunk_meshes(app).len() == CHUNKS);
+
+    // One chunk east: the western column unloads, the eastern one loads
+    let mut focus = app.world_mut().query_filtered::<&mut Transform, With<FearMemoryFocus>>();
+    focus.single_mut(app.world_mut()).unwrap().translation.x = CHUNK_SIZE * 1.5;
+    run_until(&mut app, |app| metrics(app).chunk_meshes_added == CHUNKS as u64 + 3);
+
+    let meshes = chunk_meshes(&app);
+    assert!((-1..=1).all(|z| meshes.entity(ChunkCoord::new(-1, 0, z)).is_none()));
+    assert!((-1..=1).all(|z| meshes.entity(ChunkCoord::new(2, 0, z)).is_some()));
+    let pool = metrics(&app).mesh_pool;
+    assert_eq!((pool.hits, pool.misses), (3, CHUNKS as u64));
+    assert_eq!(pool.pooled_buffers, 0);
+
+    app.update();
+    assert_eq!(mesh_entities(&mut app), CHUNKS);
+    assert_eq!(app.world().resource::<Assets<Mesh>>().len(), CHUNKS);
+}
+
+#[test]
+fn test_pool_cap_frees_unloaded_buffers() {
+    let mut app = app(config().with_mesh_pool(MeshPoolConfig { max_bytes: 0 }));
+    run_until(&mut app, |app| chunk_meshes(app).len() == CHUNKS);
+
+    let mut focus = app.world_mut().query_filtered::<&mut Transform, With<FearMemoryFocus>>();
+    focus.single_mut(app.world_mut()).unwrap().translation.z = -CHUNK_SIZE * 0.5;
+    run_until(&mut app, |app| metrics(app).chunk_meshes_added == CHUNKS as u64 + 3);
+
+    let pool = metrics(&app).mesh_pool;
+    assert_eq!(pool.trimmed, 3);
+    assert_eq!((pool.hits, pool.misses), (0, CHUNKS as u64 + 3));
+    assert_eq!(pool.pooled_bytes, 0);
+}
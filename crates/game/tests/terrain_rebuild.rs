@@ -0,0 +1,101 @@
+//! Volumetric terrain: a fear bucket change rebuilds every chunk's mesh in
+//! the background, swapping in a few chunks per frame, and clears the
+//! rebuild flag once all are done
+
+mod common;
+
+use bevy::prelude::*;
+use bevy::time::TimeUpdateStrategy;
+use common::{frame, run_until};
+use spectremesh::{
+    components::TerrainChunk,
+    install_frame_source,
+    resources::{DensityTerrain, FearState},
+    SpectreMeshPlugin,
+};
+use spectremesh_core::{types::FearBucketSmoother, TerrainConfig};
+use spectremesh_terrain::ChunkCoord;
+use std::time::Duration;
+
+fn mesh_handles(app: &mut App) -> Vec<Option<Handle<Mesh>>> {
+    let mut query = app.world_mut().query::<(&TerrainChunk, Option<&Mesh3d>)>();
+    let mut handles: Vec<_> = query
+        .iter(app.world())
+        .map(|(chunk, mesh)| (chunk.coord, mesh.map(|mesh| mesh.0.clone())))
+        .collect();
+    handles.sort_by_key(|(coord, _)| (coord.x, coord.y, coord.z));
+    handles.into_iter().map(|(_, handle)| handle).collect()
+}
+
+fn positions(app: &App, handles: &[Option<Handle<Mesh>>]) -> Vec<Vec<[f32; 3]>> {
+    let meshes = app.world().resource::<Assets<Mesh>>();
+    handles
+        .iter()
+        .map(|handle| {
+            let mesh = meshes.get(handle.as_ref().unwrap()).unwrap();
+            mesh.attribute(Mesh::ATTRIBUTE_POSITION).unwrap().as_float3().unwrap().to_vec()
+        })
+        .collect()
+}
+
+/// Update until no chunk waits for a mesh, returning the most meshes one update swapped in
+fn update_until_meshed(app: &mut App) -> usize {
+    let mut most = 0;
+    let mut before = mesh_handles(app);
+    run_until(app, |app| {
+        let after = mesh_handles(app);
+        most = most.max(before.iter().zip(&after).filter(|(a, b)| a != b).count());
+        before = after;
+        app.world().resource::<DensityTerrain>().pending() == 0
+    });
+    most
+}
+
+#[test]
+fn test_bucket_change_rebuilds_chunk_meshes_within_budget() {
+    let (sender, receiver) = async_channel::unbounded();
+    let config = TerrainConfig { chunk_size: 8, ..Default::default() };
+    let mut app = App::new();
+    app.add_plugins((MinimalPlugins, AssetPlugin::default(), SpectreMeshPlugin))
+        .init_asset::<Mesh>()
+        .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(100)))
+        .insert_resource(DensityTerrain::new(&config, 11).with_chunks_per_frame(2));
+    install_frame_source(&mut app, receiver);
+
+    // Four chunks around the ground surface at base_height 64
+    for (x, y) in [(0, 7), (0, 8), (1, 7), (1, 8)] {
+        app.world_mut().spawn(TerrainChunk::new(ChunkCoord::new(x, y, 0)));
+    }
+
+    // New chunks are meshed at the budget too, starting in the background
+    sender.try_send(frame(0.1)).unwrap();
+    app.update();
+    assert!(mesh_handles(&mut app).iter().all(Option::is_none));
+    assert_eq!(app.world().resource::<DensityTerrain>().in_flight(), 4);
+    assert!(update_until_meshed(&mut app) <= 2);
+    let calm = mesh_handles(&mut app);
+    assert!(calm.iter().all(Option::is_some));
+    assert!(!app.world().resource::<FearState>().needs_terrain_rebuild());
+
+    // High fear, held long enough for the bucket to follow: every chunk is
+    // rebuilt, two per frame at most, and the flag holds until the last
+    for _ in 0..FearBucketSmoother::DEFAULT.dwell_frames {
+        sender.try_send(frame(0.95)).unwrap();
+    }
+    app.update();
+    assert_eq!(mesh_handles(&mut app), calm);
+    assert!(app.world().resource::<FearState>().needs_terrain_rebuild());
+    assert_eq!(app.world().resource::<DensityTerrain>().pending(), 4);
+
+    assert!(update_until_meshed(&mut app) <= 2);
+    let afraid = mesh_handles(&mut app);
+    assert!(calm.iter().zip(&afraid).all(|(a, b)| a != b));
+    assert_ne!(positions(&app, &calm), positions(&app, &afraid));
+    assert!(!app.world().resource::<FearState>().needs_terrain_rebuild());
+
+    // Nothing changes while the bucket holds
+    sender.try_send(frame(0.9)).unwrap();
+    app.update();
+    assert_eq!(mesh_handles(&mut app), afraid);
+    assert_eq!(app.world().resource::<DensityTerrain>().pending(), 0);
+}
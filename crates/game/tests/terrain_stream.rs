@@ -0,0 +1,184 @@
+//! Terrain streaming progress: counts, milestones and the loading gate,
+//! with a generator slowed down enough that loading takes many frames
+
+mod common;
+
+use bevy::prelude::*;
+use common::run_until;
+use spectremesh::{
+    resources::FearState,
+    terrain_stream::{ChunkGenError, ChunkGenerator},
+    CalibrationGatePlugin, LoadingState, TerrainGenMilestone, TerrainGenProgress, TerrainStreamConfig,
+    TerrainStreamer, TerrainStreamingPlugin,
+};
+use spectremesh_core::types::FearBucket;
+use spectremesh_terrain::{Chunk, ChunkCoord};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Render distance 2 around the origin
+const CHUNKS: usize = 25;
+
+/// Generator that sleeps per chunk and can fail one coordinate
+#[derive(Clone, Default)]
+struct SlowGenerator {
+    delay: Duration,
+    calls: Arc<AtomicUsize>,
+    fail: Option<ChunkCoord>,
+}
+
+impl ChunkGenerator for SlowGenerator {
+    fn generate(&self, chunk: &Chunk, _bucket: FearBucket, resolution: usize) -> Result<Vec<f32>, ChunkGenError> {
+        self.calls.fetch_add(1, Ordering::SeqCst);
+        std::thread::sleep(self.delay);
+        if self.fail == Some(chunk.coord) {
+            return Err(ChunkGenError("synthetic failure".to_string()));
+        }
+        Ok(vec![0.0; (resolution + 1) * (resolution + 1)])
+    }
+}
+
+fn config() -> TerrainStreamConfig {
+    TerrainStreamConfig::default()
+        .with_render_distance(2)
+        .with_max_in_flight(2)
+        .with_block_until_initial_ring_complete(true)
+}
+
+fn app(generator: SlowGenerator) -> App {
+    let mut app = App::new();
+    app.add_plugins((MinimalPlugins, TerrainStreamingPlugin::new(config()).with_generator(generator)));
+    app
+}
+
+fn progress(app: &App) -> TerrainGenProgress {
+    app.world().resource::<TerrainGenProgress>().clone()
+}
+
+fn loading_state(app: &App) -> LoadingState {
+    *app.world().resource::<LoadingState>()
+}
+
+fn inner_ring() -> impl Iterator<Item = ChunkCoord> {
+    (-1..=1).flat_map(|x| (-1..=1).map(move |z| ChunkCoord::new(x, 0, z)))
+}
+
+#[test]
+fn test_progress_milestones_and_loading_gate() {
+    let generator = SlowGenerator { delay: Duration::from_millis(5), ..Default::default() };
+    let mut app = app(generator.clone());
+
+    let mut cursor = app.world().resource::<Events<TerrainGenMilestone>>().get_cursor();
+    let mut milestones = Vec::new();
+    let mut last = TerrainGenProgress::default();
+    let mut ring_completed_at = None;
+    let mut frame = 0;
+
+    run_until(
+        &mut app,
+        |app| {
+            frame += 1;
+            let progress = progress(app);
+            let events = app.world().resource::<Events<TerrainGenMilestone>>();
+            milestones.extend(cursor.read(events).map(|milestone| milestone.percent));
+
+            assert_eq!(progress.total, CHUNKS);
+            assert!(progress.completed >= last.completed, "{:?} after {:?}", progress, last);
+            assert!(progress.completed + progress.in_flight <= progress.total);
+            assert!(progress.in_flight <= 2);
+
+            // Loading ends in the very frame the inner ring exists
+            let streamer = app.world().resource::<TerrainStreamer>();
+            let ring_exists = inner_ring().all(|coord| streamer.chunk(coord).is_some());
+            assert_eq!(progress.initial_ring_complete, ring_exists);
+            assert_eq!(loading_state(app) == LoadingState::Ready, ring_exists);
+            if ring_exists && ring_completed_at.is_none() {
+                ring_completed_at = Some(frame);
+                assert!(progress.completed < CHUNKS, "outer chunks should still be streaming");
+            }
+            last = progress;
+            last.completed == CHUNKS
+        },
+    );
+
+    let progress = progress(&app);
+    assert_eq!(progress.failed, 0);
+    assert_eq!(progress.in_flight, 0);
+    assert_eq!(progress.eta, Some(Duration::ZERO));
+    assert!(progress.mean_chunk_time.unwrap() >= Duration::from_millis(5));
+    assert_eq!(generator.calls.load(Ordering::SeqCst), CHUNKS);
+    assert!(ring_completed_at.is_some());
+
+    // A few more frames announce nothing new
+    for _ in 0..5 {
+        app.update();
+    }
+    let events = app.world().resource::<Events<TerrainGenMilestone>>();
+    milestones.extend(cursor.read(events).map(|milestone| milestone.percent));
+    assert_eq!(milestones, vec![25, 50, 75, 100]);
+}
+
+#[test]
+fn test_bucket_change_requeues_without_double_counting() {
+    let generator = SlowGenerator { delay: Duration::from_millis(5), ..Default::default() };
+    let mut app = app(generator.clone());
+    app.init_resource::<FearState>();
+
+    // Change bucket while jobs for the old one are running
+    run_until(&mut app, |app| progress(app).in_flight > 0);
+    app.world_mut().resource_mut::<FearState>().current_bucket = FearBucket::High;
+    let completed_before_change = progress(&app).completed;
+
+    let mut last_completed = completed_before_change;
+    run_until(
+        &mut app,
+        |app| {
+            let progress = progress(app);
+            assert!(progress.completed >= last_completed);
+            assert!(progress.completed <= CHUNKS);
+            assert_eq!(progress.total, CHUNKS);
+            last_completed = progress.completed;
+            progress.completed == CHUNKS
+        },
+    );
+
+    assert!(progress(&app).cancelled > 0);
+    assert!(generator.calls.load(Ordering::SeqCst) >= CHUNKS);
+
+    // Cancelled chunks were generated again for the new bucket, and counted once
+    let streamer = app.world().resource::<TerrainStreamer>();
+    assert_eq!(streamer.chunks().count(), CHUNKS);
+    let low = streamer.chunks().filter(|chunk| chunk.bucket == FearBucket::Low).count();
+    assert_eq!(low, completed_before_change);
+}
+
+#[test]
+fn test_failed_chunk_is_counted_once() {
+    let failing = ChunkCoord::new(2, 0, 2);
+    let generator = SlowGenerator { fail: Some(failing), ..Default::default() };
+    let mut app = app(generator);
+
+    run_until(&mut app, |app| progress(app).remaining() == 0);
+
+    let progress = progress(&app);
+    assert_eq!(progress.failed, 1);
+    assert_eq!(progress.completed, CHUNKS - 1);
+    assert_eq!(progress.percent(), 100);
+    assert!(app.world().resource::<TerrainStreamer>().chunk(failing).is_none());
+    assert_eq!(loading_state(&app), LoadingState::Ready);
+}
+
+#[test]
+fn test_loading_waits_for_every_gate() {
+    let mut app = app(SlowGenerator::default());
+    app.add_plugins(CalibrationGatePlugin);
+
+    run_until(&mut app, |app| progress(app).initial_ring_complete);
+    app.update();
+    assert_eq!(loading_state(&app), LoadingState::Loading);
+
+    app.world_mut().resource_mut::<FearState>().calibrated = true;
+    app.update();
+    assert_eq!(loading_state(&app), LoadingState::Ready);
+}
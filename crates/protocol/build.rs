@@ -0,0 +1,12 @@
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // Browsers reach the daemon over grpc-web and embedded sensors skip the
+    // network, so without the transport feature (and always on wasm32) only
+    // the messages and a client generic over its transport are generated
+    let native = std::env::var("CARGO_CFG_TARGET_ARCH").as_deref() != Ok("wasm32");
+    let transport = native && std::env::var_os("CARGO_FEATURE_TRANSPORT").is_some();
+    tonic_build::configure()
+        .build_server(transport)
+        .build_transport(transport)
+        .compile_protos(&["proto/sensor.proto"], &["proto"])?;
+    Ok(())
+}
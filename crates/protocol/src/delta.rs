@@ -0,0 +1,500 @@
+//! Delta encoding of score events for constrained links
+//!
+//! At 30 Hz most of a score repeats the one before it: the emotion logits,
+//! confidence and startle barely move while fear drifts. A stream opened with
+//! `StreamRequest::delta` sends a full `Score` (a keyframe), then compact
+//! `ScoreDelta` events carrying only the fear change against that keyframe,
+//! and the frame's sequence number, until another value moves beyond its
+//! epsilon (the face box by the value epsilon), calibration, capability or
+//! face presence change, or `keyframe_interval` scores have passed.
+//!
+//! Deltas are taken against the keyframe rather than the previous event, so
+//! rounding does not accumulate and a lost delta affects nothing else. Every
+//! event carries a per-stream `sequence`; [`FearStreamConsumer`] treats a
+//! jump as a possibly lost keyframe and drops deltas until the next full
+//! score, which the server sends right after it falls behind.
+
+use crate::proto::{sensor_event, Score, ScoreDelta, SensorEvent, StreamRequest};
+use spectremesh_core::FEAR_INDEX;
+
+/// Scores per keyframe when the request leaves it unset
+pub const DEFAULT_KEYFRAME_INTERVAL: u32 = 30;
+
+/// Non-fear logit change a delta may leave out when the request leaves it unset
+pub const DEFAULT_LOGIT_EPSILON: f32 = 0.05;
+
+/// Confidence or startle change a delta may leave out when the request leaves it unset
+pub const DEFAULT_VALUE_EPSILON: f32 = 0.02;
+
+/// When a delta stream sends a full score
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DeltaConfig {
+    /// A full score at least every this many scores (1 sends only full scores)
+    pub keyframe_interval: u32,
+    /// Largest change of a non-fear emotion logit carried over from the keyframe
+    pub logit_epsilon: f32,
+    /// Largest change of confidence or startle carried over from the keyframe
+    pub value_epsilon: f32,
+}
+
+impl Default for DeltaConfig {
+    fn default() -> Self {
+        Self {
+            keyframe_interval: DEFAULT_KEYFRAME_INTERVAL,
+            logit_epsilon: DEFAULT_LOGIT_EPSILON,
+            value_epsilon: DEFAULT_VALUE_EPSILON,
+        }
+    }
+}
+
+impl DeltaConfig {
+    /// Send a full score at least every `interval` scores
+    pub fn with_keyframe_interval(mut self, interval: u32) -> Self {
+        self.keyframe_interval = interval.max(1);
+        self
+    }
+
+    /// Set the largest non-fear logit change a delta may leave out
+    pub fn with_logit_epsilon(mut self, epsilon: f32) -> Self {
+        self.logit_epsilon = epsilon;
+        self
+    }
+
+    /// Set the largest confidence or startle change a delta may leave out
+    pub fn with_value_epsilon(mut self, epsilon: f32) -> Self {
+        self.value_epsilon = epsilon;
+        self
+    }
+
+    /// Settings of a stream request; `None` unless it asked for deltas
+    ///
+    /// Unset (zero) fields take the defaults, as do negative or non-finite epsilons.
+    pub fn from_request(request: &StreamRequest) -> Option<Self> {
+        if !request.delta {
+            return None;
+        }
+        let epsilon = |value: f32, default: f32| if value.is_finite() && value > 0.0 { value } else { default };
+        Some(Self {
+            keyframe_interval: match request.keyframe_interval {
+                0 => DEFAULT_KEYFRAME_INTERVAL,
+                interval => interval,
+            },
+            logit_epsilon: epsilon(request.logit_epsilon, DEFAULT_LOGIT_EPSILON),
+            value_epsilon: epsilon(request.value_epsilon, DEFAULT_VALUE_EPSILON),
+        })
+    }
+
+    /// Ask for a delta stream with these settings
+    pub fn apply(&self, request: &mut StreamRequest) {
+        request.delta = true;
+        request.keyframe_interval = self.keyframe_interval;
+        request.logit_epsilon = self.logit_epsilon;
+        request.value_epsilon = self.value_epsilon;
+    }
+
+    /// Whether `score` may be sent as a delta against `keyframe`
+    fn within_epsilon(&self, keyframe: &Score, score: &Score) -> bool {
+        let close = |a: f32, b: f32, epsilon: f32| (a - b).abs() <= epsilon;
+        let face_close = match (&keyframe.face_bbox, &score.face_bbox) {
+            (Some(a), Some(b)) => [(a.x, b.x), (a.y, b.y), (a.width, b.width), (a.height, b.height)]
+                .into_iter()
+                .all(|(a, b)| close(a, b, self.value_epsilon)),
+            (None, None) => true,
+            _ => false,
+        };
+
+        face_close
+            && keyframe.calibrated == score.calibrated
+            && keyframe.capability == score.capability
+            && keyframe.emotion_logits.len() == score.emotion_logits.len()
+            && keyframe.fear_index == score.fear_index
+            && keyframe.face_present == score.face_present
+            && close(keyframe.confidence, score.confidence, self.value_epsilon)
+            && close(keyframe.startle, score.startle, self.value_epsilon)
+            && keyframe
+                .emotion_logits
+                .iter()
+                .zip(&score.emotion_logits)
+                .enumerate()
+                .filter(|(index, _)| *index != fear_index(score))
+                .all(|(_, (a, b))| close(*a, *b, self.logit_epsilon))
+    }
+}
+
+/// Sends scores as keyframes and deltas against them
+#[derive(Debug, Clone)]
+pub struct DeltaEncoder {
+    config: DeltaConfig,
+    keyframe: Option<Score>,
+    /// Deltas sent since the keyframe
+    since_keyframe: u32,
+}
+
+impl DeltaEncoder {
+    pub fn new(config: DeltaConfig) -> Self {
+        Self {
+            config,
+            keyframe: None,
+            since_keyframe: 0,
+        }
+    }
+
+    /// Encode the next score as a delta against the keyframe, or as the new keyframe
+    pub fn encode(&mut self, score: Score) -> sensor_event::Event {
+        if let Some(keyframe) = &self.keyframe {
+            if self.since_keyframe + 1 < self.config.keyframe_interval && self.config.within_epsilon(keyframe, &score) {
+                self.since_keyframe += 1;
+                return sensor_event::Event::ScoreDelta(ScoreDelta {
+                    fear_delta: score.normalized_fear - keyframe.normalized_fear,
+                    fear_logit_delta: score.raw_fear_logit - keyframe.raw_fear_logit,
+                    frame_sequence: score.frame_sequence,
+                });
+            }
+        }
+
+        self.since_keyframe = 0;
+        self.keyframe = Some(score.clone());
+        sensor_event::Event::Score(score)
+    }
+
+    /// Send the next score in full
+    pub fn resync(&mut self) {
+        self.keyframe = None;
+    }
+}
+
+/// Numbers the events of one stream and, on delta streams, encodes its scores
+#[derive(Debug, Clone)]
+pub struct StreamEncoder {
+    /// Sequence number of the last event
+    sequence: u64,
+    delta: Option<DeltaEncoder>,
+}
+
+impl StreamEncoder {
+    pub fn new(delta: Option<DeltaConfig>) -> Self {
+        Self {
+            sequence: 0,
+            delta: delta.map(DeltaEncoder::new),
+        }
+    }
+
+    /// Number `event` and encode its score
+    pub fn encode(&mut self, mut event: SensorEvent) -> SensorEvent {
+        self.sequence += 1;
+        event.sequence = self.sequence;
+        event.event = match (&mut self.delta, event.event.take()) {
+            (Some(delta), Some(sensor_event::Event::Score(score))) => Some(delta.encode(score)),
+            (_, other) => other,
+        };
+        event
+    }
+
+    /// Account for `count` events that never reached the stream
+    ///
+    /// The client sees the jump in sequence numbers, so the next score is
+    /// sent in full for it to resynchronize on.
+    pub fn skip(&mut self, count: u64) {
+        self.sequence += count;
+        if let Some(delta) = &mut self.delta {
+            delta.resync();
+        }
+    }
+}
+
+/// Rebuilds full scores from a delta stream
+///
+/// Consumers downstream see the events a full stream would have carried.
+/// Values a delta leaves out repeat the keyframe's, so they are within the
+/// stream's epsilons of the sensor's; fear and the fear logit match up to
+/// float rounding. Events on plain streams pass through unchanged.
+#[derive(Debug, Clone, Default)]
+pub struct FearStreamConsumer {
+    keyframe: Option<Score>,
+    /// Sequence number of the last event, if the daemon numbers them
+    last_sequence: Option<u64>,
+    gaps: u64,
+    dropped_deltas: u64,
+}
+
+impl FearStreamConsumer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Turn a received event into the one a full stream would have carried
+    ///
+    /// Returns `None` for a delta whose keyframe is unknown: before the
+    /// first full score, or after a gap until the next one.
+    pub fn consume(&mut self, mut event: SensorEvent) -> Option<SensorEvent> {
+        self.check_sequence(event.sequence);
+        match event.event.take() {
+            Some(sensor_event::Event::ScoreDelta(delta)) => match &self.keyframe {
+                Some(keyframe) => {
+                    event.event = Some(sensor_event::Event::Score(apply_delta(keyframe, &delta)));
+                    Some(event)
+                }
+                None => {
+                    self.dropped_deltas += 1;
+                    None
+                }
+            },
+            Some(sensor_event::Event::Score(score)) => {
+                self.keyframe = Some(score.clone());
+                event.event = Some(sensor_event::Event::Score(score));
+                Some(event)
+            }
+            other => {
+                event.event = other;
+                Some(event)
+            }
+        }
+    }
+
+    /// Jumps in sequence numbers seen so far
+    pub fn gaps(&self) -> u64 {
+        self.gaps
+    }
+
+    /// Deltas dropped for lack of a keyframe
+    pub fn dropped_deltas(&self) -> u64 {
+        self.dropped_deltas
+    }
+
+    fn check_sequence(&mut self, sequence: u64) {
+        // Daemons that do not number events send 0
+        if sequence == 0 {
+            return;
+        }
+        if let Some(last) = self.last_sequence {
+            if sequence > last + 1 {
+                tracing::debug!("Sensor stream skipped {} events, waiting for a full score", sequence - last - 1);
+                self.gaps += 1;
+                self.keyframe = None;
+            }
+        }
+        self.last_sequence = Some(sequence);
+    }
+}
+
+/// The full score `delta` stands for
+fn apply_delta(keyframe: &Score, delta: &ScoreDelta) -> Score {
+    let mut score = keyframe.clone();
+    score.normalized_fear += delta.fear_delta;
+    score.raw_fear_logit += delta.fear_logit_delta;
+    score.frame_sequence = delta.frame_sequence;
+    if let Some(fear_logit) = score.emotion_logits.get_mut(fear_index(keyframe)) {
+        *fear_logit += delta.fear_logit_delta;
+    }
+    score
+}
+
+/// Where `score` puts the fear logit, standard for older daemons
+fn fear_index(score: &Score) -> usize {
+    score.fear_index.map_or(FEAR_INDEX, |index| index as usize)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::proto::SensorCapability;
+
+    fn score(fear: f32) -> Score {
+        let mut emotion_logits = vec![0.3, -0.2, 0.0, 0.5, 0.1, -0.4, 0.2];
+        emotion_logits[FEAR_INDEX] = fear * 4.0 - 2.0;
+        Score {
+            normalized_fear: fear,
+            raw_fear_logit: fear * 4.0 - 2.0,
+            confidence: 0.9,
+            calibrated: true,
+            emotion_logits,
+            inference_latency_us: 4000,
+            startle: 0.0,
+            capability: SensorCapability::Full as i32,
+            face_bbox: None,
+            face_center: None,
+            fear_index: None,
+            face_present: None,
+            frame_sequence: 0,
+        }
+    }
+
+    fn event(score: Score) -> SensorEvent {
+        SensorEvent {
+            timestamp_us: 1000,
+            sequence: 0,
+            event: Some(sensor_event::Event::Score(score)),
+        }
+    }
+
+    fn is_delta(event: &SensorEvent) -> bool {
+        matches!(event.event, Some(sensor_event::Event::ScoreDelta(_)))
+    }
+
+    fn rebuilt(event: Option<SensorEvent>) -> Score {
+        match event.and_then(|event| event.event) {
+            Some(sensor_event::Event::Score(score)) => score,
+            other => panic!("Expected a score, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_round_trip_rebuilds_scores() {
+        let mut encoder = StreamEncoder::new(Some(DeltaConfig::default()));
+        let mut consumer = FearStreamConsumer::new();
+
+        for (index, fear) in [0.3, 0.31, 0.29, 0.45, 0.7, 0.12].into_iter().enumerate() {
+            let frame_sequence = 10 + 2 * index as u64;
+            let sent = encoder.encode(event(Score { frame_sequence, ..score(fear) }));
+            assert_eq!(sent.sequence, index as u64 + 1);
+            assert_eq!(is_delta(&sent), index > 0);
+
+            let received = rebuilt(consumer.consume(sent));
+            assert_eq!(received.frame_sequence, frame_sequence);
+            assert!((received.normalized_fear - fear).abs() < 1e-6);
+            assert!((received.raw_fear_logit - score(fear).raw_fear_logit).abs() < 1e-6);
+            assert!((received.emotion_logits[FEAR_INDEX] - received.raw_fear_logit).abs() < 1e-6);
+        }
+        assert_eq!(consumer.gaps(), 0);
+    }
+
+    #[test]
+    fn test_deltas_follow_the_score_fear_index() {
+        // Ten classes with fear at 5: the standard fear index is just another channel
+        let wide = |fear: f32| {
+            let mut emotion_logits = vec![0.1; 10];
+            emotion_logits[5] = fear * 4.0 - 2.0;
+            Score { emotion_logits, fear_index: Some(5), ..score(fear) }
+        };
+        let mut encoder = StreamEncoder::new(Some(DeltaConfig::default()));
+        let mut consumer = FearStreamConsumer::new();
+
+        for fear in [0.3, 0.34, 0.28] {
+            let received = rebuilt(consumer.consume(encoder.encode(event(wide(fear)))));
+            assert_eq!(received.emotion_logits.len(), 10);
+            assert!((received.emotion_logits[5] - received.raw_fear_logit).abs() < 1e-6);
+            assert_eq!(received.emotion_logits[FEAR_INDEX], 0.1);
+        }
+
+        // A change of layout is never left to a delta
+        let sent = encoder.encode(event(Score { fear_index: Some(4), ..wide(0.28) }));
+        assert!(!is_delta(&sent));
+    }
+
+    #[test]
+    fn test_full_scores_on_interval_and_changes() {
+        let mut encoder = DeltaEncoder::new(DeltaConfig::default().with_keyframe_interval(3));
+        let full = |event: &sensor_event::Event| matches!(event, sensor_event::Event::Score(_));
+
+        let kinds: Vec<bool> = (0..7).map(|_| full(&encoder.encode(score(0.4)))).collect();
+        assert_eq!(kinds, [true, false, false, true, false, false, true]);
+
+        let mut encoder = DeltaEncoder::new(DeltaConfig::default());
+        assert!(full(&encoder.encode(score(0.4))));
+
+        // Within the epsilons: delta
+        let mut drifted = score(0.4);
+        drifted.emotion_logits[0] += 0.04;
+        drifted.confidence -= 0.01;
+        assert!(!full(&encoder.encode(drifted)));
+
+        // Beyond them, or a state change: full
+        let mut moved = score(0.4);
+        moved.emotion_logits[0] += 0.06;
+        assert!(full(&encoder.encode(moved)));
+        let mut startled = score(0.4);
+        startled.startle = 0.9;
+        assert!(full(&encoder.encode(startled)));
+        let mut uncalibrated = score(0.4);
+        uncalibrated.startle = 0.9;
+        uncalibrated.calibrated = false;
+        assert!(full(&encoder.encode(uncalibrated)));
+        assert!(full(&encoder.encode(score(0.4))));
+        assert!(full(&encoder.encode(Score { face_present: Some(false), ..score(0.4) })));
+    }
+
+    #[test]
+    fn test_face_moves_send_full_scores() {
+        use crate::proto::NormalizedRect;
+
+        let facing = |x: f32| Score {
+            face_bbox: Some(NormalizedRect { x, y: 0.3, width: 0.2, height: 0.25 }),
+            ..score(0.4)
+        };
+        let mut encoder = DeltaEncoder::new(DeltaConfig::default());
+        let full = |event: sensor_event::Event| matches!(event, sensor_event::Event::Score(_));
+
+        assert!(full(encoder.encode(facing(0.4))));
+        assert!(!full(encoder.encode(facing(0.41))));
+        assert!(full(encoder.encode(facing(0.45))));
+
+        // Losing or finding the face is never left to a delta
+        assert!(full(encoder.encode(score(0.4))));
+        assert!(!full(encoder.encode(score(0.4))));
+        assert!(full(encoder.encode(facing(0.45))));
+    }
+
+    #[test]
+    fn test_gap_drops_deltas_until_full_score() {
+        let startled = |fear: f32| Score { startle: 0.9, ..score(fear) };
+        let mut encoder = StreamEncoder::new(Some(DeltaConfig::default()));
+        let mut consumer = FearStreamConsumer::new();
+
+        assert!(consumer.consume(encoder.encode(event(score(0.3)))).is_some());
+        assert!(consumer.consume(encoder.encode(event(score(0.32)))).is_some());
+
+        // A keyframe is lost; the delta after it refers to a score the client never saw
+        let lost = encoder.encode(event(startled(0.5)));
+        assert!(!is_delta(&lost));
+        let orphan = encoder.encode(event(startled(0.52)));
+        assert!(is_delta(&orphan));
+        assert!(consumer.consume(orphan).is_none());
+        assert_eq!(consumer.gaps(), 1);
+        assert_eq!(consumer.dropped_deltas(), 1);
+
+        // The server falls behind and resynchronizes with a full score
+        encoder.skip(5);
+        let resync = encoder.encode(event(startled(0.6)));
+        assert!(!is_delta(&resync));
+        assert_eq!(rebuilt(consumer.consume(resync)).normalized_fear, 0.6);
+        assert_eq!(consumer.gaps(), 2);
+
+        let after = encoder.encode(event(startled(0.62)));
+        assert!(is_delta(&after));
+        let after = rebuilt(consumer.consume(after));
+        assert!((after.normalized_fear - 0.62).abs() < 1e-6);
+        assert_eq!(after.startle, 0.9);
+    }
+
+    #[test]
+    fn test_config_from_request() {
+        assert_eq!(DeltaConfig::from_request(&StreamRequest::default()), None);
+
+        let request = StreamRequest {
+            delta: true,
+            value_epsilon: -1.0,
+            ..Default::default()
+        };
+        assert_eq!(DeltaConfig::from_request(&request), Some(DeltaConfig::default()));
+
+        let config = DeltaConfig::default().with_keyframe_interval(10).with_logit_epsilon(0.1);
+        let mut request = StreamRequest::default();
+        config.apply(&mut request);
+        assert_eq!(DeltaConfig::from_request(&request), Some(config));
+    }
+
+    #[test]
+    fn test_plain_stream_passes_through() {
+        let mut encoder = StreamEncoder::new(None);
+        let mut consumer = FearStreamConsumer::new();
+
+        let sent = encoder.encode(event(score(0.5)));
+        assert_eq!(sent.sequence, 1);
+        assert_eq!(consumer.consume(sent.clone()), Some(sent));
+
+        // Unnumbered events from older daemons never count as gaps
+        assert!(consumer.consume(event(score(0.5))).is_some());
+        assert!(consumer.consume(event(score(0.5))).is_some());
+        assert_eq!(consumer.gaps(), 0);
+    }
+}
@@ -0,0 +1,126 @@
+//! Wire protocol between the sensor daemon and its clients
+//!
+//! The generated gRPC messages and service, delta encoding of score streams
+//! and conversions between proto and core types. Nothing here touches the
+//! camera, the models or sockets, so it also builds for
+//! `wasm32-unknown-unknown`; there only the messages and the client, generic
+//! over its transport, are generated, which is all a grpc-web client needs.
+
+pub mod delta;
+
+// gRPC generated code
+pub mod proto {
+    tonic::include_proto!("spectre.sensor.v1");
+}
+
+use proto::{Score, SensorCapability};
+use spectremesh_core::{types, EmotionLayout, EmotionLogits, FearScore, FEAR_INDEX};
+use std::time::{Duration, UNIX_EPOCH};
+
+impl From<types::SensorCapability> for SensorCapability {
+    fn from(capability: types::SensorCapability) -> Self {
+        match capability {
+            types::SensorCapability::Full => SensorCapability::Full,
+            types::SensorCapability::HoldLastValue => SensorCapability::HoldLastValue,
+            types::SensorCapability::EmotionOffline => SensorCapability::EmotionOffline,
+        }
+    }
+}
+
+impl From<SensorCapability> for types::SensorCapability {
+    fn from(capability: SensorCapability) -> Self {
+        match capability {
+            SensorCapability::Unspecified | SensorCapability::Full => types::SensorCapability::Full,
+            SensorCapability::HoldLastValue => types::SensorCapability::HoldLastValue,
+            SensorCapability::EmotionOffline => types::SensorCapability::EmotionOffline,
+        }
+    }
+}
+
+/// The score's emotion logits in the layout it was sent with
+///
+/// Older daemons leave `fear_index` unset and send seven logits with fear at
+/// index 2. Logits that don't fit their layout are dropped for zeros rather
+/// than guessed at.
+pub fn score_logits(score: &Score) -> EmotionLogits {
+    if score.emotion_logits.is_empty() {
+        return EmotionLogits::default();
+    }
+    let fear_index = score.fear_index.map_or(FEAR_INDEX, |index| index as usize);
+    EmotionLayout::new(score.emotion_logits.len(), fear_index)
+        .and_then(|layout| EmotionLogits::new(score.emotion_logits.clone(), layout))
+        .unwrap_or_else(|e| {
+            tracing::debug!("Ignoring emotion logits of remote score: {}", e);
+            EmotionLogits::default()
+        })
+}
+
+/// Whether the score saw a face; older daemons leave `face_present` unset
+/// because they only send scores with a face
+pub fn score_face_present(score: &Score) -> bool {
+    score.face_present.unwrap_or(true)
+}
+
+/// Convert a daemon's score into a FearScore
+///
+/// `timestamp_us` is the event's capture time; unset, the score is stamped
+/// on arrival.
+pub fn score_to_fear_score(score: &Score, timestamp_us: u64) -> FearScore {
+    let emotion_logits = score_logits(score);
+    let fear_score = if score.calibrated {
+        FearScore::new_calibrated(score.normalized_fear, emotion_logits, score.confidence)
+    } else {
+        FearScore::new_uncalibrated(emotion_logits, score.confidence)
+    };
+    let fear_score = fear_score
+        .with_face_present(score_face_present(score))
+        .with_sequence(score.frame_sequence);
+    match timestamp_us {
+        0 => fear_score,
+        micros => fear_score.with_capture_time(UNIX_EPOCH + Duration::from_micros(micros)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn score(emotion_logits: Vec<f32>, fear_index: Option<u32>) -> Score {
+        Score {
+            normalized_fear: 0.7,
+            confidence: 0.9,
+            calibrated: true,
+            emotion_logits,
+            fear_index,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_legacy_scores_keep_fear_at_index_two() {
+        let legacy = score(vec![0.0, 0.0, 1.5, 0.0, 0.0, 0.0, 0.0], None);
+        let logits = score_logits(&legacy);
+        assert_eq!(logits.layout(), EmotionLayout::STANDARD);
+        assert_eq!(logits.fear(), 1.5);
+        // Older daemons only send scores with a face
+        assert!(score_face_present(&legacy));
+
+        let wide = score(vec![0.0, 0.0, 0.0, 0.0, 0.0, 2.5, 0.0, 0.0], Some(5));
+        assert_eq!(score_logits(&wide).fear(), 2.5);
+        // Logits that don't fit their layout are dropped
+        assert_eq!(score_logits(&score(vec![1.0; 3], Some(5))), EmotionLogits::default());
+    }
+
+    #[test]
+    fn test_scores_convert_to_fear_scores() {
+        let sent = Score { face_present: Some(false), frame_sequence: 12, ..score(vec![0.0; 7], None) };
+        let fear_score = score_to_fear_score(&sent, 2_000_000);
+        assert_eq!(fear_score.value(), Some(0.7));
+        assert!(!fear_score.face_present);
+        assert_eq!(fear_score.sequence, 12);
+        assert_eq!(fear_score.captured_at, UNIX_EPOCH + Duration::from_secs(2));
+
+        let uncalibrated = score_to_fear_score(&Score { calibrated: false, ..sent }, 0);
+        assert_eq!(uncalibrated.value(), None);
+    }
+}
@@ -1,4 +1,809 @@
-//! Terrain chunk management (placeholder for M0)
+//! Terrain chunk management
+//!
+//! A [`TerrainMap`] tracks per-chunk state that outlives a mesh rebuild, such
+//! as fear memory. Chunks are cubes of [`CHUNK_SIZE`] world units addressed
+//! by [`ChunkCoord`]. A [`DensityChunk`] holds the density samples marching
+//! cubes meshes a chunk from.
+//!
+//! World positions map to chunks by flooring, so chunk `-1` spans
+//! `[-CHUNK_SIZE, 0)` and a position exactly on a boundary belongs to the
+//! chunk above it. Streaming works on the horizontal (x/z) plane:
+//! [`ChunkCoord::ring_iter`] and the neighbor accessors keep `y` fixed.
 
-// Placeholder for chunk management
-// Will be implemented in M2 (Caves) milestone
+use crate::memory::{scar_blend, FearMemoryConfig};
+use serde::{Deserialize, Serialize};
+use spectremesh_core::{FearBucket, TerrainConfig, TerrainError};
+use std::collections::HashMap;
+use std::time::Duration;
+
+/// Edge length of a chunk in world units
+pub const CHUNK_SIZE: f32 = 16.0;
+
+/// Integer chunk position; chunk (0, 0, 0) spans [0, CHUNK_SIZE) on every axis
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct ChunkCoord {
+    pub x: i32,
+    pub y: i32,
+    pub z: i32,
+}
+
+impl ChunkCoord {
+    pub fn new(x: i32, y: i32, z: i32) -> Self {
+        Self { x, y, z }
+    }
+
+    /// Chunk containing a world position
+    pub fn from_world(position: [f32; 3]) -> Self {
+        Self::from_world_pos(position, CHUNK_SIZE)
+    }
+
+    /// Chunk containing a world position, for chunks of `chunk_size` world units
+    pub fn from_world_pos(position: [f32; 3], chunk_size: f32) -> Self {
+        Self::world_to_local(position, chunk_size).0
+    }
+
+    /// Chunk containing a world position and the position relative to its
+    /// minimum corner, each component in `[0, chunk_size)`
+    pub fn world_to_local(position: [f32; 3], chunk_size: f32) -> (Self, [f32; 3]) {
+        let [(x, local_x), (y, local_y), (z, local_z)] =
+            position.map(|v| split_axis(v, chunk_size));
+        (Self { x, y, z }, [local_x, local_y, local_z])
+    }
+
+    /// Chunk containing a global cell index and the cell within it, for
+    /// chunks `cells_per_chunk` cells wide
+    pub fn from_cell(cell: [i32; 3], cells_per_chunk: u32) -> (Self, [u32; 3]) {
+        let cells = cells_per_chunk.max(1) as i32;
+        let [x, y, z] = cell.map(|v| v.div_euclid(cells));
+        (Self { x, y, z }, cell.map(|v| v.rem_euclid(cells) as u32))
+    }
+
+    /// World position of the chunk's minimum corner
+    pub fn origin(&self) -> [f32; 3] {
+        self.to_world_origin(CHUNK_SIZE)
+    }
+
+    /// World position of the chunk's minimum corner, for chunks of `chunk_size` world units
+    pub fn to_world_origin(&self, chunk_size: f32) -> [f32; 3] {
+        [self.x, self.y, self.z].map(|v| v as f32 * chunk_size)
+    }
+
+    /// World position of the chunk's centre
+    pub fn center(&self) -> [f32; 3] {
+        self.origin().map(|v| v + CHUNK_SIZE / 2.0)
+    }
+
+    /// Coordinate moved by whole chunks
+    pub fn offset(&self, dx: i32, dy: i32, dz: i32) -> Self {
+        Self::new(self.x + dx, self.y + dy, self.z + dz)
+    }
+
+    /// Chunk towards -z, the direction a default camera faces
+    pub fn north(&self) -> Self {
+        self.offset(0, 0, -1)
+    }
+
+    /// Chunk towards +z
+    pub fn south(&self) -> Self {
+        self.offset(0, 0, 1)
+    }
+
+    /// Chunk towards +x
+    pub fn east(&self) -> Self {
+        self.offset(1, 0, 0)
+    }
+
+    /// Chunk towards -x
+    pub fn west(&self) -> Self {
+        self.offset(-1, 0, 0)
+    }
+
+    /// Chunk above
+    pub fn up(&self) -> Self {
+        self.offset(0, 1, 0)
+    }
+
+    /// Chunk below
+    pub fn down(&self) -> Self {
+        self.offset(0, -1, 0)
+    }
+
+    /// The six chunks sharing a face with this one: east, west, up, down, south, north
+    pub fn face_neighbors(&self) -> [Self; 6] {
+        [self.east(), self.west(), self.up(), self.down(), self.south(), self.north()]
+    }
+
+    /// Sum of the per-axis distances in chunks
+    pub fn manhattan_distance(&self, other: &Self) -> u32 {
+        self.x
+            .abs_diff(other.x)
+            .saturating_add(self.y.abs_diff(other.y))
+            .saturating_add(self.z.abs_diff(other.z))
+    }
+
+    /// Largest per-axis distance in chunks; `render_distance` rings are at equal Chebyshev distance
+    pub fn chebyshev_distance(&self, other: &Self) -> u32 {
+        self.x
+            .abs_diff(other.x)
+            .max(self.y.abs_diff(other.y))
+            .max(self.z.abs_diff(other.z))
+    }
+
+    /// The four chunks sharing an edge with this one on the x/z plane
+    pub fn neighbors(&self) -> [Self; 4] {
+        [
+            self.offset(1, 0, 0),
+            self.offset(0, 0, 1),
+            self.offset(-1, 0, 0),
+            self.offset(0, 0, -1),
+        ]
+    }
+
+    /// The eight chunks around this one on the x/z plane, diagonals included
+    pub fn neighbors_with_diagonals(&self) -> [Self; 8] {
+        [
+            self.offset(1, 0, 0),
+            self.offset(1, 0, 1),
+            self.offset(0, 0, 1),
+            self.offset(-1, 0, 1),
+            self.offset(-1, 0, 0),
+            self.offset(-1, 0, -1),
+            self.offset(0, 0, -1),
+            self.offset(1, 0, -1),
+        ]
+    }
+
+    /// Every chunk on the x/z plane within `radius` of `center`, nearest rings first
+    ///
+    /// Rings are at increasing Chebyshev distance, starting with `center`
+    /// itself; `(2 * radius + 1)²` coordinates in all.
+    pub fn ring_iter(center: Self, radius: u32) -> RingIter {
+        RingIter {
+            center,
+            radius,
+            ring: 0,
+            index: 0,
+        }
+    }
+}
+
+/// Whole chunks and remainder of one world axis
+///
+/// Flooring the quotient alone can disagree with the remainder by one chunk
+/// when the division rounds, leaving a local position of `-0.0001` or
+/// exactly `chunk_size`; the chunk follows the remainder instead. A value
+/// too close below a boundary to be told apart from it lands on the boundary.
+fn split_axis(value: f32, chunk_size: f32) -> (i32, f32) {
+    let mut chunk = (value / chunk_size).floor();
+    let mut local = value - chunk * chunk_size;
+    if local < 0.0 {
+        chunk -= 1.0;
+        local += chunk_size;
+    }
+    if local >= chunk_size {
+        chunk += 1.0;
+        local = (local - chunk_size).max(0.0);
+    }
+    (chunk as i32, local.max(0.0))
+}
+
+/// Chunks around a centre, ring by ring; see [`ChunkCoord::ring_iter`]
+#[derive(Debug, Clone)]
+pub struct RingIter {
+    center: ChunkCoord,
+    radius: u32,
+    ring: u32,
+    /// Position along the current ring's perimeter
+    index: u32,
+}
+
+impl Iterator for RingIter {
+    type Item = ChunkCoord;
+
+    fn next(&mut self) -> Option<ChunkCoord> {
+        if self.ring > self.radius {
+            return None;
+        }
+        if self.ring == 0 {
+            self.ring = 1;
+            return Some(self.center);
+        }
+
+        // Walk the perimeter one side at a time, each side stopping short of the next corner
+        let ring = self.ring as i32;
+        let side = 2 * ring;
+        let along = (self.index as i32) % side;
+        let (dx, dz) = match self.index as i32 / side {
+            0 => (-ring + along, -ring),
+            1 => (ring, -ring + along),
+            2 => (ring - along, ring),
+            _ => (-ring, ring - along),
+        };
+
+        self.index += 1;
+        if self.index == 8 * self.ring {
+            self.ring += 1;
+            self.index = 0;
+        }
+        Some(self.center.offset(dx, 0, dz))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let total = |radius: u32| (2 * radius as usize + 1).pow(2);
+        let remaining = match self.ring {
+            ring if ring > self.radius => 0,
+            0 => total(self.radius),
+            ring => total(self.radius) - total(ring - 1) - self.index as usize,
+        };
+        (remaining, Some(remaining))
+    }
+}
+
+impl ExactSizeIterator for RingIter {}
+
+/// Persistent state of one chunk
+#[derive(Debug, Clone, PartialEq)]
+pub struct Chunk {
+    pub coord: ChunkCoord,
+    /// Accumulated time spent in High fear near this chunk, in [0, 1]
+    pub fear_memory: f32,
+    /// Fear memory the current mesh was built with
+    built_memory: f32,
+}
+
+impl Chunk {
+    pub fn new(coord: ChunkCoord) -> Self {
+        Self {
+            coord,
+            fear_memory: 0.0,
+            built_memory: 0.0,
+        }
+    }
+
+    /// Material scar blend factor for this chunk
+    pub fn scar_blend(&self) -> f32 {
+        scar_blend(self.fear_memory)
+    }
+
+    /// Record that the mesh now reflects the current memory
+    pub fn mark_built(&mut self) {
+        self.built_memory = self.fear_memory;
+    }
+}
+
+/// Density samples of one chunk for marching cubes
+///
+/// A `size`³ grid with one sample per world unit, starting at the chunk's
+/// minimum corner; the surface lies where density crosses zero. Samples on
+/// the far faces come from the neighbouring chunks (see
+/// [`ChunkCoord::face_neighbors`]), so adjacent meshes share their boundary.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DensityChunk {
+    coord: ChunkCoord,
+    size: u32,
+    /// Samples in x-fastest order
+    densities: Vec<f32>,
+}
+
+/// One sample of a [`DensityChunk`]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DensityCell {
+    /// Cell within the chunk, each component in `[0, size)`
+    pub local: [u32; 3],
+    /// World position of the sample
+    pub world: [f32; 3],
+    pub density: f32,
+}
+
+impl DensityChunk {
+    /// Chunk of `size`³ samples, all zero
+    pub fn new(coord: ChunkCoord, size: u32) -> Self {
+        let len = (size as usize).pow(3);
+        Self {
+            coord,
+            size,
+            densities: vec![0.0; len],
+        }
+    }
+
+    /// Chunk sized by the terrain configuration's `chunk_size`
+    pub fn from_config(coord: ChunkCoord, config: &TerrainConfig) -> Self {
+        Self::new(coord, config.chunk_size)
+    }
+
+    /// Chunk of `size`³ samples in x-fastest order, or `None` if there are not that many
+    pub fn from_densities(coord: ChunkCoord, size: u32, densities: Vec<f32>) -> Option<Self> {
+        (densities.len() == (size as usize).pow(3)).then_some(Self { coord, size, densities })
+    }
+
+    /// Chunk sampled from `density` at every cell's world position
+    pub fn from_fn(coord: ChunkCoord, size: u32, density: impl FnMut([f32; 3]) -> f32) -> Self {
+        let mut chunk = Self::new(coord, size);
+        chunk.fill(density);
+        chunk
+    }
+
+    pub fn coord(&self) -> ChunkCoord {
+        self.coord
+    }
+
+    /// Samples per axis
+    pub fn size(&self) -> u32 {
+        self.size
+    }
+
+    /// World position of the chunk's minimum corner
+    pub fn origin(&self) -> [f32; 3] {
+        self.coord.to_world_origin(self.size as f32)
+    }
+
+    /// World position of cell (x, y, z); the cell need not be inside the chunk
+    pub fn world_position(&self, x: u32, y: u32, z: u32) -> [f32; 3] {
+        let [ox, oy, oz] = self.origin();
+        [ox + x as f32, oy + y as f32, oz + z as f32]
+    }
+
+    /// Every sample in x-fastest order
+    pub fn densities(&self) -> &[f32] {
+        &self.densities
+    }
+
+    pub fn get_density(&self, x: u32, y: u32, z: u32) -> Result<f32, TerrainError> {
+        Ok(self.densities[self.index(x, y, z)?])
+    }
+
+    pub fn set_density(&mut self, x: u32, y: u32, z: u32, value: f32) -> Result<(), TerrainError> {
+        let index = self.index(x, y, z)?;
+        self.densities[index] = value;
+        Ok(())
+    }
+
+    /// Overwrite every sample with `density` at its world position
+    pub fn fill(&mut self, mut density: impl FnMut([f32; 3]) -> f32) {
+        for index in 0..self.densities.len() {
+            let [x, y, z] = self.local(index);
+            self.densities[index] = density(self.world_position(x, y, z));
+        }
+    }
+
+    /// Every sample with its cell and world position, x fastest
+    pub fn cells(&self) -> impl ExactSizeIterator<Item = DensityCell> + '_ {
+        self.densities.iter().enumerate().map(move |(index, &density)| {
+            let local = self.local(index);
+            DensityCell {
+                local,
+                world: self.world_position(local[0], local[1], local[2]),
+                density,
+            }
+        })
+    }
+
+    fn index(&self, x: u32, y: u32, z: u32) -> Result<usize, TerrainError> {
+        if x >= self.size || y >= self.size || z >= self.size {
+            return Err(TerrainError::InvalidChunkCoordinates {
+                x: x as i32,
+                y: y as i32,
+                z: z as i32,
+            });
+        }
+        let size = self.size as usize;
+        Ok(x as usize + size * (y as usize + size * z as usize))
+    }
+
+    fn local(&self, index: usize) -> [u32; 3] {
+        let size = self.size as usize;
+        [index % size, index / size % size, index / (size * size)].map(|v| v as u32)
+    }
+}
+
+/// All chunks the session has touched
+#[derive(Debug, Clone)]
+pub struct TerrainMap {
+    chunks: HashMap<ChunkCoord, Chunk>,
+    memory: FearMemoryConfig,
+    /// Time of the last memory-driven rebuild batch
+    last_memory_rebuild: Option<Duration>,
+}
+
+impl TerrainMap {
+    pub fn new(memory: FearMemoryConfig) -> Self {
+        Self {
+            chunks: HashMap::new(),
+            memory,
+            last_memory_rebuild: None,
+        }
+    }
+
+    pub fn memory_config(&self) -> &FearMemoryConfig {
+        &self.memory
+    }
+
+    pub fn chunk(&self, coord: ChunkCoord) -> Option<&Chunk> {
+        self.chunks.get(&coord)
+    }
+
+    /// Chunk at `coord`, created on first use
+    pub fn chunk_mut(&mut self, coord: ChunkCoord) -> &mut Chunk {
+        self.chunks.entry(coord).or_insert_with(|| Chunk::new(coord))
+    }
+
+    pub fn chunks(&self) -> impl Iterator<Item = &Chunk> {
+        self.chunks.values()
+    }
+
+    pub fn len(&self) -> usize {
+        self.chunks.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.chunks.is_empty()
+    }
+
+    /// Fear memory at `coord`; chunks never visited have none
+    pub fn fear_memory(&self, coord: ChunkCoord) -> f32 {
+        self.chunks.get(&coord).map_or(0.0, |chunk| chunk.fear_memory)
+    }
+
+    /// Decay every chunk's memory over `dt`, then accumulate around the player while fear is High
+    pub fn integrate_fear_memory(&mut self, player: [f32; 3], bucket: FearBucket, dt: Duration) {
+        let decay = self.memory.decay_factor(dt);
+        for chunk in self.chunks.values_mut() {
+            chunk.fear_memory *= decay;
+        }
+
+        if bucket != FearBucket::High || self.memory.accumulation_rate <= 0.0 {
+            return;
+        }
+
+        let gain = self.memory.accumulation_rate * dt.as_secs_f32();
+        let reach = (self.memory.radius / CHUNK_SIZE).ceil() as i32 + 1;
+        let here = ChunkCoord::from_world(player);
+        for dx in -reach..=reach {
+            for dy in -reach..=reach {
+                for dz in -reach..=reach {
+                    let coord = ChunkCoord::new(here.x + dx, here.y + dy, here.z + dz);
+                    let weight = self.memory.falloff(distance(coord.center(), player));
+                    if weight > 0.0 {
+                        let chunk = self.chunk_mut(coord);
+                        chunk.fear_memory = (chunk.fear_memory + gain * weight).min(1.0);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Chunks whose memory drifted far enough from their mesh to need a rebuild
+    ///
+    /// Rate-limited by `min_rebuild_interval_secs`, independently of rebuilds
+    /// caused by fear bucket changes. Returned chunks are marked built.
+    pub fn take_memory_rebuilds(&mut self, now: Duration) -> Vec<ChunkCoord> {
+        if let Some(last) = self.last_memory_rebuild {
+            if now.saturating_sub(last) < self.memory.min_rebuild_interval() {
+                return Vec::new();
+            }
+        }
+
+        let threshold = self.memory.rebuild_threshold;
+        let mut stale: Vec<ChunkCoord> = self
+            .chunks
+            .values_mut()
+            .filter(|chunk| (chunk.fear_memory - chunk.built_memory).abs() >= threshold)
+            .map(|chunk| {
+                chunk.mark_built();
+                chunk.coord
+            })
+            .collect();
+
+        if !stale.is_empty() {
+            stale.sort();
+            self.last_memory_rebuild = Some(now);
+        }
+        stale
+    }
+
+    /// Record that every chunk was rebuilt, e.g. after a fear bucket change
+    pub fn mark_all_built(&mut self) {
+        for chunk in self.chunks.values_mut() {
+            chunk.mark_built();
+        }
+    }
+}
+
+impl Default for TerrainMap {
+    fn default() -> Self {
+        Self::new(FearMemoryConfig::default())
+    }
+}
+
+fn distance(a: [f32; 3], b: [f32; 3]) -> f32 {
+    a.iter().zip(&b).map(|(a, b)| (a - b) * (a - b)).sum::<f32>().sqrt()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TICK: Duration = Duration::from_millis(100);
+
+    fn linger(map: &mut TerrainMap, position: [f32; 3], bucket: FearBucket, secs: u32) {
+        for _ in 0..secs * 10 {
+            map.integrate_fear_memory(position, bucket, TICK);
+        }
+    }
+
+    #[test]
+    fn test_chunk_coord_from_world() {
+        assert_eq!(ChunkCoord::from_world([0.0, 0.0, 0.0]), ChunkCoord::new(0, 0, 0));
+        assert_eq!(ChunkCoord::from_world([15.9, 16.0, -0.1]), ChunkCoord::new(0, 1, -1));
+        assert_eq!(ChunkCoord::new(1, 0, -1).center(), [24.0, 8.0, -8.0]);
+    }
+
+    #[test]
+    fn test_world_to_local_negative_and_boundary_positions() {
+        let cases = [
+            ([0.0, 0.0, 0.0], ChunkCoord::new(0, 0, 0), [0.0, 0.0, 0.0]),
+            (
+                [16.0, -16.0, 32.0],
+                ChunkCoord::new(1, -1, 2),
+                [0.0, 0.0, 0.0],
+            ),
+            (
+                [-0.0, -16.5, -32.0],
+                ChunkCoord::new(0, -2, -2),
+                [0.0, 15.5, 0.0],
+            ),
+            (
+                [-0.25, 15.75, -15.75],
+                ChunkCoord::new(-1, 0, -1),
+                [15.75, 15.75, 0.25],
+            ),
+            (
+                [-1600.0, 1599.5, -1.0],
+                ChunkCoord::new(-100, 99, -1),
+                [0.0, 15.5, 15.0],
+            ),
+        ];
+        for (position, chunk, local) in cases {
+            assert_eq!(
+                ChunkCoord::world_to_local(position, CHUNK_SIZE),
+                (chunk, local),
+                "{:?}",
+                position
+            );
+            assert_eq!(ChunkCoord::from_world_pos(position, CHUNK_SIZE), chunk);
+        }
+
+        // Values just below a boundary must not round up to a local position of exactly `chunk_size`
+        let position = [-f32::EPSILON, -1e-7, f32::from_bits(16.0f32.to_bits() - 1)];
+        let (chunk, local) = ChunkCoord::world_to_local(position, CHUNK_SIZE);
+        assert!(
+            local.iter().all(|&v| (0.0..CHUNK_SIZE).contains(&v)),
+            "{:?}",
+            local
+        );
+        let origin = chunk.to_world_origin(CHUNK_SIZE);
+        assert!((0..3).all(|axis| (origin[axis] + local[axis] - position[axis]).abs() < 1e-5));
+    }
+
+    #[test]
+    fn test_world_local_round_trip_stays_in_chunk() {
+        for chunk_size in [1.0, 7.5, CHUNK_SIZE, 100.0] {
+            for step in -400..=400 {
+                let v = step as f32 * 0.37 * chunk_size / 4.0;
+                let position = [v, -v, v * 0.5];
+                let (chunk, local) = ChunkCoord::world_to_local(position, chunk_size);
+                assert!(
+                    local.iter().all(|&l| (0.0..chunk_size).contains(&l)),
+                    "{:?} -> {:?}",
+                    position,
+                    local
+                );
+
+                let origin = chunk.to_world_origin(chunk_size);
+                for axis in 0..3 {
+                    let rebuilt = origin[axis] + local[axis];
+                    assert!(
+                        (rebuilt - position[axis]).abs() <= chunk_size * 1e-5,
+                        "{:?} rebuilt {}",
+                        position,
+                        rebuilt
+                    );
+                }
+                assert_eq!(ChunkCoord::from_world_pos(origin, chunk_size), chunk);
+            }
+        }
+    }
+
+    #[test]
+    fn test_from_cell_floors_negative_cells() {
+        assert_eq!(
+            ChunkCoord::from_cell([0, 15, 16], 16),
+            (ChunkCoord::new(0, 0, 1), [0, 15, 0])
+        );
+        assert_eq!(
+            ChunkCoord::from_cell([-1, -16, -17], 16),
+            (ChunkCoord::new(-1, -1, -2), [15, 0, 15])
+        );
+        for cell in -64..64 {
+            let (chunk, local) = ChunkCoord::from_cell([cell, 0, 0], 8);
+            assert_eq!(chunk.x * 8 + local[0] as i32, cell);
+        }
+    }
+
+    #[test]
+    fn test_chunk_distances_and_neighbors() {
+        let a = ChunkCoord::new(-2, 1, 3);
+        let b = ChunkCoord::new(1, 0, -1);
+        assert_eq!(a.manhattan_distance(&b), 8);
+        assert_eq!(a.chebyshev_distance(&b), 4);
+        assert_eq!(
+            ChunkCoord::new(i32::MIN, 0, 0).chebyshev_distance(&ChunkCoord::new(i32::MAX, 0, 0)),
+            u32::MAX
+        );
+
+        let neighbors = a.neighbors();
+        assert!(neighbors
+            .iter()
+            .all(|n| a.manhattan_distance(n) == 1 && n.y == a.y));
+        let all = a.neighbors_with_diagonals();
+        assert!(all
+            .iter()
+            .all(|n| a.chebyshev_distance(n) == 1 && n.y == a.y));
+        assert!(neighbors.iter().all(|n| all.contains(n)));
+        assert_eq!(
+            all.iter().collect::<std::collections::HashSet<_>>().len(),
+            8
+        );
+    }
+
+    #[test]
+    fn test_face_neighbors() {
+        let coord = ChunkCoord::new(2, -1, 5);
+        assert_eq!(coord.north(), ChunkCoord::new(2, -1, 4));
+        assert_eq!(coord.south(), ChunkCoord::new(2, -1, 6));
+        assert_eq!(coord.east(), ChunkCoord::new(3, -1, 5));
+        assert_eq!(coord.west(), ChunkCoord::new(1, -1, 5));
+        assert_eq!(coord.up(), ChunkCoord::new(2, 0, 5));
+        assert_eq!(coord.down(), ChunkCoord::new(2, -2, 5));
+        assert!(coord.face_neighbors().iter().all(|n| n.manhattan_distance(&coord) == 1));
+        assert_eq!(coord.north().south(), coord);
+    }
+
+    #[test]
+    fn test_density_chunk_fill_and_read_back() {
+        let config = TerrainConfig { chunk_size: 8, ..TerrainConfig::default() };
+        let mut chunk = DensityChunk::from_config(ChunkCoord::new(-1, 0, 2), &config);
+        assert_eq!(chunk.size(), 8);
+        assert_eq!(chunk.origin(), [-8.0, 0.0, 16.0]);
+        assert_eq!(chunk.get_density(3, 3, 3).unwrap(), 0.0);
+
+        // Ground plane at y = 2.5
+        chunk.fill(|[_, y, _]| 2.5 - y);
+        assert_eq!(chunk.get_density(0, 2, 7).unwrap(), 0.5);
+        assert_eq!(chunk.get_density(7, 3, 0).unwrap(), -0.5);
+
+        chunk.set_density(7, 7, 7, 9.0).unwrap();
+        assert_eq!(chunk.get_density(7, 7, 7).unwrap(), 9.0);
+
+        let cells: Vec<DensityCell> = chunk.cells().collect();
+        assert_eq!(cells.len(), 512);
+        assert_eq!(cells[0].local, [0, 0, 0]);
+        assert_eq!(cells[1].local, [1, 0, 0]);
+        for cell in &cells {
+            let [x, y, z] = cell.local;
+            assert_eq!(cell.world, chunk.world_position(x, y, z));
+            assert_eq!(cell.density, chunk.get_density(x, y, z).unwrap());
+        }
+        assert_eq!(cells[511].world, [-1.0, 7.0, 23.0]);
+
+        let same = DensityChunk::from_fn(chunk.coord(), 8, |[_, y, _]| 2.5 - y);
+        assert_eq!(same.get_density(4, 1, 4).unwrap(), chunk.get_density(4, 1, 4).unwrap());
+    }
+
+    #[test]
+    fn test_density_chunk_rejects_out_of_range_cells() {
+        let mut chunk = DensityChunk::new(ChunkCoord::new(0, 0, 0), 4);
+        for (x, y, z) in [(4, 0, 0), (0, 4, 0), (0, 0, 4), (u32::MAX, 3, 3)] {
+            assert!(matches!(
+                chunk.get_density(x, y, z),
+                Err(TerrainError::InvalidChunkCoordinates { .. })
+            ));
+            assert!(chunk.set_density(x, y, z, 1.0).is_err());
+        }
+        assert!(chunk.get_density(3, 3, 3).is_ok());
+        assert!(chunk.cells().all(|cell| cell.density == 0.0));
+
+        let empty = DensityChunk::new(ChunkCoord::new(0, 0, 0), 0);
+        assert_eq!(empty.cells().len(), 0);
+        assert!(empty.get_density(0, 0, 0).is_err());
+    }
+
+    #[test]
+    fn test_ring_iter_covers_square_nearest_first() {
+        for radius in 0..6 {
+            let center = ChunkCoord::new(-3, 2, 5);
+            let rings = ChunkCoord::ring_iter(center, radius);
+            assert_eq!(rings.len(), (2 * radius as usize + 1).pow(2));
+
+            let coords: Vec<_> = rings.collect();
+            let unique: std::collections::HashSet<_> = coords.iter().copied().collect();
+            assert_eq!(unique.len(), coords.len(), "duplicate at radius {}", radius);
+            assert_eq!(coords[0], center);
+            assert!(coords
+                .iter()
+                .all(|c| c.y == center.y && center.chebyshev_distance(c) <= radius));
+            assert!(coords
+                .windows(2)
+                .all(|w| center.chebyshev_distance(&w[0]) <= center.chebyshev_distance(&w[1])));
+        }
+
+        let mut rings = ChunkCoord::ring_iter(ChunkCoord::new(0, 0, 0), 2);
+        rings.nth(3);
+        assert_eq!(rings.len(), 21);
+    }
+
+    #[test]
+    fn test_lingering_chunk_remembers_more_than_neighbors() {
+        let mut map = TerrainMap::default();
+        let home = ChunkCoord::new(2, 0, 3);
+        linger(&mut map, home.center(), FearBucket::High, 30);
+
+        let memory = map.fear_memory(home);
+        assert!(memory > 0.5, "memory {}", memory);
+        for neighbor in [ChunkCoord::new(3, 0, 3), ChunkCoord::new(2, 0, 2), ChunkCoord::new(2, 1, 3)] {
+            let neighbor_memory = map.fear_memory(neighbor);
+            assert!(neighbor_memory > 0.0 && neighbor_memory < memory);
+        }
+        assert_eq!(map.fear_memory(ChunkCoord::new(10, 0, 10)), 0.0);
+    }
+
+    #[test]
+    fn test_only_high_fear_accumulates() {
+        let mut map = TerrainMap::default();
+        let home = ChunkCoord::new(0, 0, 0);
+        linger(&mut map, home.center(), FearBucket::Medium, 30);
+        assert_eq!(map.fear_memory(home), 0.0);
+
+        linger(&mut map, home.center(), FearBucket::High, 10);
+        let scarred = map.fear_memory(home);
+
+        // Memory fades very slowly once fear subsides
+        linger(&mut map, home.center(), FearBucket::Low, 60);
+        let faded = map.fear_memory(home);
+        assert!(faded < scarred && faded > scarred * 0.95);
+    }
+
+    #[test]
+    fn test_memory_saturates() {
+        let mut map = TerrainMap::default();
+        let home = ChunkCoord::new(0, 0, 0);
+        linger(&mut map, home.center(), FearBucket::High, 200);
+        assert_eq!(map.fear_memory(home), 1.0);
+        assert_eq!(map.chunk(home).unwrap().scar_blend(), 1.0);
+    }
+
+    #[test]
+    fn test_memory_rebuilds_are_rate_limited() {
+        let mut map = TerrainMap::default();
+        let home = ChunkCoord::new(0, 0, 0);
+        let interval = map.memory_config().min_rebuild_interval();
+
+        // Below the threshold nothing is rebuilt
+        map.integrate_fear_memory(home.center(), FearBucket::High, TICK);
+        assert!(map.take_memory_rebuilds(Duration::ZERO).is_empty());
+
+        linger(&mut map, home.center(), FearBucket::High, 5);
+        let rebuilt = map.take_memory_rebuilds(Duration::from_secs(5));
+        assert!(rebuilt.contains(&home));
+
+        // More memory, but too soon after the last batch
+        linger(&mut map, home.center(), FearBucket::High, 5);
+        assert!(map.take_memory_rebuilds(Duration::from_secs(5) + interval / 2).is_empty());
+        assert!(map.take_memory_rebuilds(Duration::from_secs(5) + interval).contains(&home));
+
+        // A bucket-change rebuild also covers memory
+        linger(&mut map, home.center(), FearBucket::High, 5);
+        map.mark_all_built();
+        assert!(map.take_memory_rebuilds(Duration::from_secs(60)).is_empty());
+    }
+}
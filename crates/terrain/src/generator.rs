@@ -1,4 +1,740 @@
-//! Terrain generation traits and implementations (placeholder for M0)
+//! Terrain generation
+//!
+//! [`TerrainGenerator`] samples a height field from seeded noise. Chunks with
+//! fear memory get a scar term: thin cracks cut into the surface, deeper the
+//! more the chunk remembers.
+//!
+//! [`MarchingCubesGenerator`] meshes volumetric terrain instead: the surface
+//! where a [`DensityChunk`]'s samples cross an isolevel, with overhangs and
+//! caves a height field cannot express. Density above the isolevel is solid.
+//! Distant chunks can be meshed at a coarser level of detail, with skirts
+//! along their faces hiding the cracks against finer neighbours.
 
-// Placeholder for terrain generation
-// Will be implemented in M2 (Caves) milestone
+mod tables;
+
+use crate::chunk::{Chunk, ChunkCoord, DensityChunk, CHUNK_SIZE};
+use fastnoise_lite::{FastNoiseLite, FractalType, NoiseType};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use tables::{CORNERS, EDGES, EDGE_TABLE, TRI_TABLE};
+
+/// Sharpness of scar cracks; higher values make thinner cracks
+const CRACK_SHARPNESS: i32 = 4;
+
+/// Height field parameters
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GeneratorConfig {
+    /// Noise seed; the same seed always yields the same terrain
+    pub seed: i32,
+    /// Height of the terrain's mean level
+    pub base_height: f32,
+    /// Peak height deviation from the mean level
+    pub amplitude: f32,
+    /// Frequency of the base height noise
+    pub frequency: f32,
+    /// Depth of scar cracks in a chunk with full fear memory
+    pub scar_depth: f32,
+    /// Frequency of the scar crack pattern
+    pub crack_frequency: f32,
+}
+
+impl Default for GeneratorConfig {
+    fn default() -> Self {
+        Self {
+            seed: 1337,
+            base_height: 0.0,
+            amplitude: 8.0,
+            frequency: 0.02,
+            scar_depth: 3.0,
+            crack_frequency: 0.15,
+        }
+    }
+}
+
+/// Noise the scar crack pattern is read from
+pub(crate) fn crack_noise(seed: i32, frequency: f32) -> FastNoiseLite {
+    let mut noise = FastNoiseLite::with_seed(seed);
+    noise.set_noise_type(Some(NoiseType::OpenSimplex2));
+    noise.set_frequency(Some(frequency));
+    noise
+}
+
+/// Crack pattern in [0, 1], peaking along the zero crossings of the crack noise
+pub(crate) fn crack(noise: &FastNoiseLite, x: f32, z: f32) -> f32 {
+    (1.0 - noise.get_noise_2d(x, z).abs())
+        .clamp(0.0, 1.0)
+        .powi(CRACK_SHARPNESS)
+}
+
+/// Seeded height field generator
+pub struct TerrainGenerator {
+    config: GeneratorConfig,
+    height_noise: FastNoiseLite,
+    crack_noise: FastNoiseLite,
+}
+
+impl TerrainGenerator {
+    pub fn new(config: GeneratorConfig) -> Self {
+        let mut height_noise = FastNoiseLite::with_seed(config.seed);
+        height_noise.set_noise_type(Some(NoiseType::OpenSimplex2));
+        height_noise.set_fractal_type(Some(FractalType::FBm));
+        height_noise.set_frequency(Some(config.frequency));
+
+        let crack_noise = crack_noise(config.seed.wrapping_add(1), config.crack_frequency);
+
+        Self {
+            config,
+            height_noise,
+            crack_noise,
+        }
+    }
+
+    pub fn config(&self) -> &GeneratorConfig {
+        &self.config
+    }
+
+    /// Unscarred surface height at world (x, z)
+    pub fn base_height(&self, x: f32, z: f32) -> f32 {
+        self.config.base_height + self.config.amplitude * self.height_noise.get_noise_2d(x, z)
+    }
+
+    /// Downward displacement from scars, proportional to fear memory
+    pub fn scar_displacement(&self, x: f32, z: f32, fear_memory: f32) -> f32 {
+        self.config.scar_depth * fear_memory.clamp(0.0, 1.0) * crack(&self.crack_noise, x, z)
+    }
+
+    /// Surface height at world (x, z) for a chunk with `fear_memory`
+    pub fn height_at(&self, x: f32, z: f32, fear_memory: f32) -> f32 {
+        self.base_height(x, z) - self.scar_displacement(x, z, fear_memory)
+    }
+
+    /// Heights over a chunk's footprint on a `(resolution + 1)²` grid, row by row along z
+    pub fn chunk_heights(&self, chunk: &Chunk, resolution: usize) -> Vec<f32> {
+        let resolution = resolution.max(1);
+        let [origin_x, _, origin_z] = chunk.coord.origin();
+        let step = CHUNK_SIZE / resolution as f32;
+
+        let mut heights = Vec::with_capacity((resolution + 1) * (resolution + 1));
+        for row in 0..=resolution {
+            for column in 0..=resolution {
+                let x = origin_x + column as f32 * step;
+                let z = origin_z + row as f32 * step;
+                heights.push(self.height_at(x, z, chunk.fear_memory));
+            }
+        }
+        heights
+    }
+}
+
+impl Default for TerrainGenerator {
+    fn default() -> Self {
+        Self::new(GeneratorConfig::default())
+    }
+}
+
+/// Triangle mesh of the isosurface in one density chunk
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DensityMesh {
+    pub coord: ChunkCoord,
+    /// Level of detail: one cell spans `2^lod` samples along each axis
+    pub lod: u8,
+    /// Vertex positions relative to the chunk's origin
+    pub positions: Vec<[f32; 3]>,
+    /// Unit vertex normals, pointing out of the solid
+    pub normals: Vec<[f32; 3]>,
+    /// Triangle list, counter-clockwise seen from outside the solid
+    pub indices: Vec<u32>,
+    /// Vertices on the isosurface; any after them belong to skirts
+    pub surface_vertices: usize,
+}
+
+impl DensityMesh {
+    fn empty(coord: ChunkCoord, lod: u8) -> Self {
+        Self {
+            coord,
+            lod,
+            positions: Vec::new(),
+            normals: Vec::new(),
+            indices: Vec::new(),
+            surface_vertices: 0,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.indices.is_empty()
+    }
+
+    pub fn triangle_count(&self) -> usize {
+        self.indices.len() / 3
+    }
+}
+
+/// Marching cubes over density chunks
+///
+/// Each cell between eight samples contributes up to five triangles, with
+/// vertices interpolated along the cell edges the surface crosses and
+/// normals from the density gradient. A vertex only depends on the samples
+/// at either end of its edge and their neighbours, taken in world order, so
+/// two chunks meshing a shared face place its vertices identically.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MarchingCubesGenerator {
+    isolevel: f32,
+}
+
+impl MarchingCubesGenerator {
+    pub fn new(isolevel: f32) -> Self {
+        Self { isolevel }
+    }
+
+    pub fn isolevel(&self) -> f32 {
+        self.isolevel
+    }
+
+    /// Mesh the cells between `chunk`'s own samples
+    ///
+    /// Cells reaching into neighbouring chunks are left out, so the mesh
+    /// stops one cell short of the far faces; see
+    /// [`generate_seamless`](Self::generate_seamless) to close them.
+    pub fn generate(&self, chunk: &DensityChunk) -> DensityMesh {
+        self.generate_seamless(chunk, |_| None)
+    }
+
+    /// Mesh `chunk` including the cells it shares with its neighbours
+    ///
+    /// `neighbors` looks up the chunk at a coordinate; its samples finish
+    /// the cells on the far faces and the normals on every face. Chunks of
+    /// another size count as missing, and cells with a missing corner are
+    /// left out.
+    pub fn generate_seamless<'a>(
+        &self,
+        chunk: &DensityChunk,
+        neighbors: impl Fn(ChunkCoord) -> Option<&'a DensityChunk>,
+    ) -> DensityMesh {
+        let size = chunk.size() as i32;
+        let origin = [chunk.coord().x, chunk.coord().y, chunk.coord().z].map(|v| v.wrapping_mul(size));
+        let mut found: HashMap<ChunkCoord, Option<&DensityChunk>> = HashMap::new();
+        let window = SampleWindow::gather(chunk, 1, |local| {
+            let (coord, [x, y, z]) = ChunkCoord::from_cell(offset(origin, local), size as u32);
+            let neighbor = *found
+                .entry(coord)
+                .or_insert_with(|| neighbors(coord).filter(|neighbor| neighbor.size() == chunk.size()));
+            neighbor.and_then(|neighbor| neighbor.get_density(x, y, z).ok())
+        });
+        self.march(chunk, &window)
+    }
+
+    /// Mesh `chunk` including the cells it shares with its neighbours,
+    /// sampling the layer around it from `density`
+    ///
+    /// For chunks filled from a density function: `density` stands in for
+    /// the neighbours, so meshes meet without keeping the neighbours around.
+    /// It must be the function `chunk` was filled from.
+    pub fn generate_bordered(&self, chunk: &DensityChunk, density: impl Fn([f32; 3]) -> f32) -> DensityMesh {
+        self.march_bordered(chunk, 1, density)
+    }
+
+    /// Mesh the cells between every `2^lod`-th of `chunk`'s own samples,
+    /// with skirts along the faces
+    ///
+    /// Each level roughly quarters the vertex count. `lod` is lowered until
+    /// `2^lod` divides the chunk size. As with [`generate`](Self::generate)
+    /// the mesh stops one cell short of the far faces; see
+    /// [`generate_bordered_with_lod`](Self::generate_bordered_with_lod) to
+    /// close them.
+    ///
+    /// The skirts are strips in the chunk's face planes, hanging from the
+    /// mesh's open edges `2^(lod + 1)` units into the solid and visible from
+    /// both sides. Two chunks meshed at different levels meet the face along
+    /// different outlines; the skirts cover the crack between them when the
+    /// levels differ by at most one.
+    pub fn generate_with_lod(&self, chunk: &DensityChunk, lod: u8) -> DensityMesh {
+        let stride = 1 << Self::clamp_lod(chunk, lod);
+        let mut mesh = self.march(chunk, &SampleWindow::gather(chunk, stride, |_| None));
+        add_skirts(&mut mesh, chunk.size() as f32);
+        mesh
+    }
+
+    /// [`generate_with_lod`](Self::generate_with_lod), sampling the layer
+    /// around the chunk from `density` as
+    /// [`generate_bordered`](Self::generate_bordered) does
+    pub fn generate_bordered_with_lod(
+        &self,
+        chunk: &DensityChunk,
+        lod: u8,
+        density: impl Fn([f32; 3]) -> f32,
+    ) -> DensityMesh {
+        let mut mesh = self.march_bordered(chunk, 1 << Self::clamp_lod(chunk, lod), density);
+        add_skirts(&mut mesh, chunk.size() as f32);
+        mesh
+    }
+
+    fn march_bordered(&self, chunk: &DensityChunk, stride: i32, density: impl Fn([f32; 3]) -> f32) -> DensityMesh {
+        let origin = chunk.origin();
+        let window = SampleWindow::gather(chunk, stride, |local| {
+            Some(density(std::array::from_fn(|i| origin[i] + local[i] as f32)))
+        });
+        self.march(chunk, &window)
+    }
+
+    /// The highest level up to `lod` whose cells tile the chunk
+    fn clamp_lod(chunk: &DensityChunk, lod: u8) -> u8 {
+        let size = chunk.size().max(1);
+        (0..=lod.min(31)).rev().find(|&lod| size.is_multiple_of(1 << lod)).unwrap_or(0)
+    }
+
+    fn march(&self, chunk: &DensityChunk, window: &SampleWindow) -> DensityMesh {
+        let coord = chunk.coord();
+        let lod = window.stride.trailing_zeros() as u8;
+        let (min, max) = window.range;
+        if max < self.isolevel || min >= self.isolevel {
+            return DensityMesh::empty(coord, lod);
+        }
+
+        let size = window.size;
+        let mut vertices: HashMap<([i32; 3], usize), u32> = HashMap::new();
+        let mut mesh = DensityMesh::empty(coord, lod);
+        for z in 0..size {
+            for y in 0..size {
+                for x in 0..size {
+                    let Some(densities) = window.cell([x, y, z]) else {
+                        continue;
+                    };
+                    let cube = densities
+                        .iter()
+                        .enumerate()
+                        .filter(|(_, &density)| density < self.isolevel)
+                        .fold(0usize, |cube, (corner, _)| cube | 1 << corner);
+                    if EDGE_TABLE[cube] == 0 {
+                        continue;
+                    }
+
+                    for &edge in TRI_TABLE[cube].iter().take_while(|&&edge| edge >= 0) {
+                        let [a, b] = EDGES[edge as usize].map(|corner| offset([x, y, z], CORNERS[corner]));
+                        let (lower, upper) = if a <= b { (a, b) } else { (b, a) };
+                        let axis = (0..3).find(|&axis| lower[axis] != upper[axis]).unwrap_or(0);
+                        let index = *vertices.entry((lower, axis)).or_insert_with(|| {
+                            mesh.positions.push(self.edge_vertex(window, lower, upper, axis, &mut mesh.normals));
+                            (mesh.positions.len() - 1) as u32
+                        });
+                        mesh.indices.push(index);
+                    }
+                }
+            }
+        }
+        mesh.surface_vertices = mesh.positions.len();
+        mesh
+    }
+
+    /// Position of the surface on the edge from `lower` to `upper`, pushing its normal
+    fn edge_vertex(
+        &self,
+        window: &SampleWindow,
+        lower: [i32; 3],
+        upper: [i32; 3],
+        axis: usize,
+        normals: &mut Vec<[f32; 3]>,
+    ) -> [f32; 3] {
+        let (from, to) = (window.density(lower), window.density(upper));
+        let t = ((self.isolevel - from) / (to - from)).clamp(0.0, 1.0);
+
+        let [from_gradient, to_gradient] = [lower, upper].map(|sample| window.gradient(sample));
+        let gradient: [f32; 3] = std::array::from_fn(|i| from_gradient[i] + (to_gradient[i] - from_gradient[i]) * t);
+        // Density rises into the solid, so outward is down the gradient
+        normals.push(normalize_or_up(gradient.map(|v| -v)));
+
+        let mut position = lower.map(|v| v as f32);
+        position[axis] += t;
+        position.map(|v| v * window.stride as f32)
+    }
+}
+
+impl Default for MarchingCubesGenerator {
+    fn default() -> Self {
+        Self::new(0.0)
+    }
+}
+
+/// A chunk's samples and one layer around it, for cells and gradients across its faces
+///
+/// Indices count every `stride`-th sample of the chunk.
+struct SampleWindow {
+    /// Cells per axis
+    size: i32,
+    stride: i32,
+    /// Samples per axis, from -1 to `size + 1`
+    side: usize,
+    samples: Vec<Option<f32>>,
+    /// Lowest and highest sample a cell corner can read
+    range: (f32, f32),
+}
+
+impl SampleWindow {
+    /// Every `stride`-th sample of `chunk`, which `stride` must divide;
+    /// `border` gives the samples outside it by chunk-local cell
+    fn gather(chunk: &DensityChunk, stride: i32, mut border: impl FnMut([i32; 3]) -> Option<f32>) -> Self {
+        let chunk_size = chunk.size() as i32;
+        let size = chunk_size / stride;
+        let side = size as usize + 3;
+        let mut samples = Vec::with_capacity(side.pow(3));
+        let mut range = (f32::INFINITY, f32::NEG_INFINITY);
+        for z in -1..=size + 1 {
+            for y in -1..=size + 1 {
+                for x in -1..=size + 1 {
+                    let local = [x, y, z].map(|v| v * stride);
+                    let sample = if local.iter().all(|v| (0..chunk_size).contains(v)) {
+                        chunk.get_density(local[0] as u32, local[1] as u32, local[2] as u32).ok()
+                    } else {
+                        border(local)
+                    };
+                    if let Some(density) = sample.filter(|_| [x, y, z].iter().all(|v| (0..=size).contains(v))) {
+                        range = (range.0.min(density), range.1.max(density));
+                    }
+                    samples.push(sample);
+                }
+            }
+        }
+        Self { size, stride, side, samples, range }
+    }
+
+    fn get(&self, [x, y, z]: [i32; 3]) -> Option<f32> {
+        if [x, y, z].iter().any(|v| !(-1..=self.size + 1).contains(v)) {
+            return None;
+        }
+        let [x, y, z] = [x, y, z].map(|v| (v + 1) as usize);
+        self.samples[x + self.side * (y + self.side * z)]
+    }
+
+    /// Density at a sample known to exist
+    fn density(&self, sample: [i32; 3]) -> f32 {
+        self.get(sample).unwrap_or_default()
+    }
+
+    /// The eight corner densities of the cell at `cell`, if all are known
+    fn cell(&self, cell: [i32; 3]) -> Option<[f32; 8]> {
+        let mut densities = [0.0; 8];
+        for (density, corner) in densities.iter_mut().zip(CORNERS) {
+            *density = self.get(offset(cell, corner))?;
+        }
+        Some(densities)
+    }
+
+    /// Density gradient at a sample: central differences, one-sided where a neighbour is missing
+    fn gradient(&self, sample: [i32; 3]) -> [f32; 3] {
+        let here = self.density(sample);
+        std::array::from_fn(|axis| {
+            let mut step = [0; 3];
+            step[axis] = 1;
+            let ahead = self.get(offset(sample, step));
+            let behind = self.get(offset(sample, step.map(|v| -v)));
+            match (behind, ahead) {
+                (Some(behind), Some(ahead)) => (ahead - behind) / 2.0,
+                (None, Some(ahead)) => ahead - here,
+                (Some(behind), None) => here - behind,
+                (None, None) => 0.0,
+            }
+        })
+    }
+}
+
+/// Skirts along the open edges of `mesh` that lie on a face of its `size`
+/// chunk, reaching two of its cells into the solid
+fn add_skirts(mesh: &mut DensityMesh, size: f32) {
+    let depth = (2u32 << mesh.lod) as f32;
+    let mut uses: HashMap<(u32, u32), u32> = HashMap::new();
+    for triangle in mesh.indices.chunks(3) {
+        for i in 0..3 {
+            let (a, b) = (triangle[i], triangle[(i + 1) % 3]);
+            *uses.entry((a.min(b), a.max(b))).or_default() += 1;
+        }
+    }
+    let mut open: Vec<(u32, u32)> = uses.into_iter().filter(|&(_, count)| count == 1).map(|(edge, _)| edge).collect();
+    // Sorted so the same chunk always gets the same mesh
+    open.sort_unstable();
+
+    for (a, b) in open {
+        let [from, to] = [a, b].map(|vertex| mesh.positions[vertex as usize]);
+        let Some(face) = (0..3).find(|&axis| from[axis] == to[axis] && (from[axis] == 0.0 || from[axis] == size)) else {
+            continue;
+        };
+        // Across the edge within the face plane, turned toward the solid
+        let along: [f32; 3] = std::array::from_fn(|i| to[i] - from[i]);
+        let mut across = [0.0; 3];
+        across[(face + 1) % 3] = along[(face + 2) % 3];
+        across[(face + 2) % 3] = -along[(face + 1) % 3];
+        let [from_normal, to_normal] = [a, b].map(|vertex| mesh.normals[vertex as usize]);
+        if (0..3).map(|i| across[i] * (from_normal[i] + to_normal[i])).sum::<f32>() > 0.0 {
+            across = across.map(|v| -v);
+        }
+        let length = (across[0] * across[0] + across[1] * across[1] + across[2] * across[2]).sqrt();
+        if length <= f32::EPSILON {
+            continue;
+        }
+
+        let first = mesh.positions.len() as u32;
+        for (position, normal) in [(from, from_normal), (to, to_normal)] {
+            mesh.positions.push(position);
+            mesh.positions.push(std::array::from_fn(|i| position[i] + across[i] / length * depth));
+            mesh.normals.extend([normal, normal]);
+        }
+        // Both windings, so the crack is covered from either side
+        let [top_from, bottom_from, top_to, bottom_to] = [0, 1, 2, 3].map(|i| first + i);
+        mesh.indices.extend([top_from, bottom_from, top_to, top_to, bottom_from, bottom_to]);
+        mesh.indices.extend([top_from, top_to, bottom_from, top_to, bottom_to, bottom_from]);
+    }
+}
+
+fn offset(a: [i32; 3], b: [i32; 3]) -> [i32; 3] {
+    std::array::from_fn(|i| a[i].wrapping_add(b[i]))
+}
+
+/// Unit vector along `v`, or straight up where the gradient vanishes
+fn normalize_or_up(v: [f32; 3]) -> [f32; 3] {
+    let length = (v[0] * v[0] + v[1] * v[1] + v[2] * v[2]).sqrt();
+    if length > f32::EPSILON {
+        v.map(|c| c / length)
+    } else {
+        [0.0, 1.0, 0.0]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_same_seed_same_terrain() {
+        let a = TerrainGenerator::default();
+        let b = TerrainGenerator::default();
+        let other = TerrainGenerator::new(GeneratorConfig { seed: 7, ..Default::default() });
+        let chunk = Chunk::new(ChunkCoord::new(1, 0, -2));
+
+        assert_eq!(a.chunk_heights(&chunk, 8), b.chunk_heights(&chunk, 8));
+        assert_ne!(a.chunk_heights(&chunk, 8), other.chunk_heights(&chunk, 8));
+        assert_eq!(a.chunk_heights(&chunk, 8).len(), 81);
+    }
+
+    fn sphere(center: [f32; 3], radius: f32) -> impl Fn([f32; 3]) -> f32 {
+        move |p| radius - (0..3).map(|i| (p[i] - center[i]).powi(2)).sum::<f32>().sqrt()
+    }
+
+    fn length(v: [f32; 3]) -> f32 {
+        (v[0] * v[0] + v[1] * v[1] + v[2] * v[2]).sqrt()
+    }
+
+    /// Every directed edge has its reverse exactly once: closed and consistently wound
+    fn assert_watertight(mesh: &DensityMesh) {
+        let mut edges: HashMap<(u32, u32), u32> = HashMap::new();
+        for triangle in mesh.indices.chunks(3) {
+            for i in 0..3 {
+                *edges.entry((triangle[i], triangle[(i + 1) % 3])).or_default() += 1;
+            }
+        }
+        for (&(a, b), &count) in &edges {
+            assert_eq!(count, 1, "edge {}-{} repeated", a, b);
+            assert_eq!(edges.get(&(b, a)), Some(&1), "edge {}-{} is open", a, b);
+        }
+    }
+
+    #[test]
+    fn test_tables_only_use_crossed_edges() {
+        for cube in 0..256 {
+            let crossed = EDGES
+                .iter()
+                .enumerate()
+                .filter(|(_, [a, b])| (cube >> a & 1) != (cube >> b & 1))
+                .fold(0u16, |bits, (edge, _)| bits | 1 << edge);
+            assert_eq!(EDGE_TABLE[cube], crossed, "case {}", cube);
+
+            let edges: Vec<i8> = TRI_TABLE[cube].iter().copied().take_while(|&edge| edge >= 0).collect();
+            assert!(edges.len().is_multiple_of(3));
+            assert!(TRI_TABLE[cube][edges.len()..].iter().all(|&edge| edge == -1));
+            assert!(edges.iter().all(|&edge| crossed & 1 << edge != 0), "case {}", cube);
+            assert_eq!(edges.is_empty(), crossed == 0, "case {}", cube);
+        }
+    }
+
+    #[test]
+    fn test_sphere_mesh_is_closed_with_unit_outward_normals() {
+        let chunk = DensityChunk::from_fn(ChunkCoord::new(0, 0, 0), 16, sphere([8.0, 7.5, 8.25], 5.0));
+        let mesh = MarchingCubesGenerator::default().generate(&chunk);
+
+        assert!(mesh.triangle_count() > 100, "{} triangles", mesh.triangle_count());
+        assert_eq!(mesh.positions.len(), mesh.normals.len());
+        assert_watertight(&mesh);
+        for (position, normal) in mesh.positions.iter().zip(&mesh.normals) {
+            assert!((length(*normal) - 1.0).abs() < 1e-5);
+            let radial = [position[0] - 8.0, position[1] - 7.5, position[2] - 8.25];
+            assert!((length(radial) - 5.0).abs() < 0.1, "{:?} off the sphere", position);
+            assert!((0..3).map(|i| radial[i] * normal[i]).sum::<f32>() > 0.9 * length(radial));
+        }
+
+        // Triangles face the way their normals point
+        for triangle in mesh.indices.chunks(3) {
+            let [a, b, c] = [0, 1, 2].map(|i| mesh.positions[triangle[i] as usize]);
+            let (u, v) = ([b[0] - a[0], b[1] - a[1], b[2] - a[2]], [c[0] - a[0], c[1] - a[1], c[2] - a[2]]);
+            let face = [u[1] * v[2] - u[2] * v[1], u[2] * v[0] - u[0] * v[2], u[0] * v[1] - u[1] * v[0]];
+            let normal = mesh.normals[triangle[0] as usize];
+            assert!((0..3).map(|i| face[i] * normal[i]).sum::<f32>() > 0.0);
+        }
+    }
+
+    #[test]
+    fn test_uniform_chunks_are_empty() {
+        let generator = MarchingCubesGenerator::new(0.5);
+        for density in [-1.0, 0.49, 0.5, 3.0] {
+            let chunk = DensityChunk::from_fn(ChunkCoord::new(2, -1, 0), 8, |_| density);
+            assert!(generator.generate(&chunk).is_empty());
+        }
+        assert!(generator.generate(&DensityChunk::new(ChunkCoord::new(0, 0, 0), 0)).is_empty());
+        assert!(generator.generate(&DensityChunk::new(ChunkCoord::new(0, 0, 0), 1)).is_empty());
+    }
+
+    #[test]
+    fn test_adjacent_chunks_share_boundary_vertices() {
+        // A sphere straddling the x = 8 face between two chunks
+        let density = sphere([8.0, 4.3, 3.7], 3.2);
+        let chunks: HashMap<ChunkCoord, DensityChunk> = [ChunkCoord::new(0, 0, 0), ChunkCoord::new(1, 0, 0)]
+            .into_iter()
+            .map(|coord| (coord, DensityChunk::from_fn(coord, 8, &density)))
+            .collect();
+        let generator = MarchingCubesGenerator::default();
+        let mesh = |coord| generator.generate_seamless(&chunks[&coord], |coord| chunks.get(&coord));
+        let (west, east) = (mesh(ChunkCoord::new(0, 0, 0)), mesh(ChunkCoord::new(1, 0, 0)));
+
+        let on_face = |mesh: &DensityMesh, x: f32| -> Vec<([u32; 3], [u32; 3])> {
+            let mut vertices: Vec<_> = mesh
+                .positions
+                .iter()
+                .zip(&mesh.normals)
+                .filter(|(position, _)| position[0] == x)
+                .map(|(position, normal)| ([8.0, position[1], position[2]].map(f32::to_bits), normal.map(f32::to_bits)))
+                .collect();
+            vertices.sort();
+            vertices
+        };
+        let shared = on_face(&west, 8.0);
+        assert!(!shared.is_empty());
+        assert_eq!(shared, on_face(&east, 0.0));
+
+        // Sampling the border from the density function closes every face, matching across it too
+        let bordered = |coord| generator.generate_bordered(&chunks[&coord], &density);
+        let (west_bordered, east_bordered) = (bordered(ChunkCoord::new(0, 0, 0)), bordered(ChunkCoord::new(1, 0, 0)));
+        assert_eq!(on_face(&west_bordered, 8.0), on_face(&east_bordered, 0.0));
+        let positions = |vertices: Vec<([u32; 3], [u32; 3])>| vertices.into_iter().map(|(position, _)| position).collect::<Vec<_>>();
+        let bordered_positions = positions(on_face(&west_bordered, 8.0));
+        assert!(positions(shared).iter().all(|position| bordered_positions.contains(position)));
+        assert!(west_bordered.triangle_count() > west.triangle_count());
+
+        // Without neighbours the west chunk stops a cell short of the face
+        let alone = generator.generate(&chunks[&ChunkCoord::new(0, 0, 0)]);
+        assert!(alone.positions.iter().all(|position| position[0] <= 7.0));
+        assert!(alone.triangle_count() < west.triangle_count());
+    }
+
+    /// Rolling ground around y = 16, crossing every side face of a chunk
+    fn hills([x, y, z]: [f32; 3]) -> f32 {
+        16.3 + 2.0 * (x / 5.0).sin() * (z / 7.0).cos() - y
+    }
+
+    fn distance_to_segment(p: [f32; 3], [a, b]: [[f32; 3]; 2]) -> f32 {
+        let ab: [f32; 3] = std::array::from_fn(|i| b[i] - a[i]);
+        let ap: [f32; 3] = std::array::from_fn(|i| p[i] - a[i]);
+        let t = ((0..3).map(|i| ab[i] * ap[i]).sum::<f32>() / (0..3).map(|i| ab[i] * ab[i]).sum::<f32>()).clamp(0.0, 1.0);
+        length(std::array::from_fn(|i| ap[i] - ab[i] * t))
+    }
+
+    /// Surface edges used by one triangle with both ends where `on_face` holds
+    fn face_outline(mesh: &DensityMesh, on_face: impl Fn([f32; 3]) -> bool) -> Vec<[[f32; 3]; 2]> {
+        let mut uses: HashMap<(u32, u32), u32> = HashMap::new();
+        for triangle in mesh.indices.chunks(3).filter(|t| t.iter().all(|&i| (i as usize) < mesh.surface_vertices)) {
+            for i in 0..3 {
+                let (a, b) = (triangle[i], triangle[(i + 1) % 3]);
+                *uses.entry((a.min(b), a.max(b))).or_default() += 1;
+            }
+        }
+        uses.into_iter()
+            .filter(|&(_, count)| count == 1)
+            .map(|((a, b), _)| [a, b].map(|i| mesh.positions[i as usize]))
+            .filter(|ends| ends.iter().all(|&end| on_face(end)))
+            .collect()
+    }
+
+    #[test]
+    fn test_each_level_quarters_the_vertices() {
+        let chunk = DensityChunk::from_fn(ChunkCoord::new(0, 0, 0), 32, hills);
+        let generator = MarchingCubesGenerator::default();
+        let meshes: Vec<DensityMesh> = (0..3).map(|lod| generator.generate_bordered_with_lod(&chunk, lod, hills)).collect();
+
+        for pair in meshes.windows(2) {
+            let ratio = pair[0].surface_vertices as f32 / pair[1].surface_vertices as f32;
+            assert!((3.0..5.0).contains(&ratio), "{} -> {} vertices", pair[0].surface_vertices, pair[1].surface_vertices);
+            assert!(pair[1].positions.len() < pair[0].positions.len());
+        }
+        for (lod, mesh) in meshes.iter().enumerate() {
+            assert_eq!(mesh.lod, lod as u8);
+            assert!(mesh.positions.len() > mesh.surface_vertices, "no skirts at level {}", lod);
+            assert_eq!(mesh.positions.len(), mesh.normals.len());
+        }
+
+        // Level 0 meshes what `generate` does, plus skirts
+        let plain = generator.generate(&chunk);
+        let lod0 = generator.generate_with_lod(&chunk, 0);
+        assert_eq!(lod0.positions[..lod0.surface_vertices], plain.positions[..]);
+        assert_eq!(plain.surface_vertices, plain.positions.len());
+        // Cells never reach past the chunk
+        assert_eq!(generator.generate_with_lod(&DensityChunk::from_fn(ChunkCoord::new(0, 0, 0), 8, hills), 9).lod, 3);
+        assert_eq!(generator.generate_with_lod(&DensityChunk::from_fn(ChunkCoord::new(0, 0, 0), 12, hills), 3).lod, 2);
+    }
+
+    #[test]
+    fn test_neighbouring_levels_meet_along_the_shared_face() {
+        let generator = MarchingCubesGenerator::default();
+        let mesh = |x, lod| {
+            let chunk = DensityChunk::from_fn(ChunkCoord::new(x, 1, 0), 16, hills);
+            generator.generate_bordered_with_lod(&chunk, lod, hills)
+        };
+        // The fine chunk's east face is the coarse chunk's west face, x = 16
+        let (fine, coarse) = (mesh(0, 0), mesh(1, 1));
+        let fine_outline = face_outline(&fine, |p| p[0] == 16.0);
+        let coarse_outline: Vec<[[f32; 3]; 2]> =
+            face_outline(&coarse, |p| p[0] == 0.0).into_iter().map(|ends| ends.map(|[_, y, z]| [16.0, y, z])).collect();
+        assert!(fine_outline.len() > coarse_outline.len() && !coarse_outline.is_empty());
+
+        // Every vertex of either outline lies on the other
+        let epsilon = 0.05;
+        for (outline, other) in [(&fine_outline, &coarse_outline), (&coarse_outline, &fine_outline)] {
+            for &point in outline.iter().flatten() {
+                let nearest = other.iter().map(|&segment| distance_to_segment(point, segment)).fold(f32::INFINITY, f32::min);
+                assert!(nearest < epsilon, "{:?} is {} off the other outline", point, nearest);
+            }
+        }
+
+        // The coarse skirt on the face hangs two coarse cells below its outline
+        let skirt: Vec<[f32; 3]> = coarse.positions[coarse.surface_vertices..].iter().filter(|p| p[0] == 0.0).copied().collect();
+        assert!(!skirt.is_empty());
+        let lowest = |points: &mut dyn Iterator<Item = [f32; 3]>| points.map(|p| p[1]).fold(f32::INFINITY, f32::min);
+        let outline_low = lowest(&mut coarse_outline.iter().flatten().copied());
+        assert!((lowest(&mut skirt.into_iter()) - (outline_low - 4.0)).abs() < 0.5);
+    }
+
+    #[test]
+    fn test_scars_deepen_with_memory() {
+        let generator = TerrainGenerator::default();
+        let mut chunk = Chunk::new(ChunkCoord::new(0, 0, 0));
+        let clean = generator.chunk_heights(&chunk, 16);
+
+        chunk.fear_memory = 0.5;
+        let scarred = generator.chunk_heights(&chunk, 16);
+        chunk.fear_memory = 1.0;
+        let deeply_scarred = generator.chunk_heights(&chunk, 16);
+
+        // Scars only ever cut downward, and deeper with more memory
+        for ((clean, scarred), deep) in clean.iter().zip(&scarred).zip(&deeply_scarred) {
+            assert!(scarred <= clean && deep <= scarred);
+        }
+        let depth = |heights: &[f32]| clean.iter().zip(heights).map(|(c, h)| c - h).sum::<f32>();
+        assert!(depth(&scarred) > 0.0);
+        assert!((depth(&deeply_scarred) - 2.0 * depth(&scarred)).abs() < 1e-2);
+    }
+}
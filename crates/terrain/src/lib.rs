@@ -3,8 +3,18 @@
 pub mod generator;
 pub mod noise;
 pub mod chunk;
+pub mod memory;
+pub mod save;
+pub mod storage;
+pub mod mesh;
+pub mod pool;
 
-// Re-export main types (commented out for M0 - will be enabled in M0.5+)
-// pub use generator::*;
-// pub use noise::*;
-// pub use chunk::*;
+// Re-export main types
+pub use chunk::{Chunk, ChunkCoord, DensityCell, DensityChunk, RingIter, TerrainMap, CHUNK_SIZE};
+pub use generator::{DensityMesh, GeneratorConfig, MarchingCubesGenerator, TerrainGenerator};
+pub use memory::FearMemoryConfig;
+pub use mesh::{ChunkMesh, ChunkMesher, ColorBakeConfig, ColorBakePreset, ColorGradient, ColorStop, MeshError, MesherConfig};
+pub use pool::{MeshBufferPool, MeshBuffers, MeshPoolConfig, MeshPoolStats};
+pub use save::{SaveError, TerrainSave};
+pub use storage::{ChunkParams, ChunkStore, ChunkStoreStats, StorageError, StoredChunk};
+pub use noise::{FractalParams, NoiseField};
@@ -0,0 +1,104 @@
+//! Terrain fear memory: persistent scarring of chunks that experienced high fear
+//!
+//! While the player is in the High bucket, chunks near them accumulate
+//! `fear_memory` in [0, 1]. Memory decays very slowly once fear subsides, so
+//! the world keeps a visible history of the session: the generator cuts scar
+//! cracks proportional to memory and the material darkens by
+//! [`scar_blend`].
+
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// How chunks remember fear
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FearMemoryConfig {
+    /// Memory gained per second in the High bucket by a chunk centred on the player
+    pub accumulation_rate: f32,
+    /// Distance from the player, in world units, within which chunks accumulate memory
+    pub radius: f32,
+    /// Seconds for memory to halve (zero or less disables decay)
+    pub half_life_secs: f32,
+    /// Smallest change in a chunk's memory that warrants rebuilding its mesh
+    pub rebuild_threshold: f32,
+    /// Minimum seconds between memory-driven rebuild batches
+    pub min_rebuild_interval_secs: f32,
+}
+
+impl Default for FearMemoryConfig {
+    fn default() -> Self {
+        Self {
+            accumulation_rate: 0.02,
+            radius: 24.0,
+            half_life_secs: 1800.0,
+            rebuild_threshold: 0.05,
+            min_rebuild_interval_secs: 5.0,
+        }
+    }
+}
+
+impl FearMemoryConfig {
+    /// Multiplier applied to memory over `dt`
+    pub fn decay_factor(&self, dt: Duration) -> f32 {
+        if self.half_life_secs <= 0.0 {
+            return 1.0;
+        }
+        0.5f32.powf(dt.as_secs_f32() / self.half_life_secs)
+    }
+
+    /// Share of the accumulation rate a chunk `distance` away from the player receives
+    pub fn falloff(&self, distance: f32) -> f32 {
+        if self.radius <= 0.0 {
+            return 0.0;
+        }
+        (1.0 - distance / self.radius).clamp(0.0, 1.0)
+    }
+
+    /// Minimum time between memory-driven rebuild batches
+    pub fn min_rebuild_interval(&self) -> Duration {
+        Duration::from_secs_f32(self.min_rebuild_interval_secs.max(0.0))
+    }
+}
+
+/// Material scar blend factor for a chunk's fear memory
+///
+/// Smoothstep, so faint memories stay nearly invisible and strong ones saturate.
+pub fn scar_blend(fear_memory: f32) -> f32 {
+    let t = fear_memory.clamp(0.0, 1.0);
+    t * t * (3.0 - 2.0 * t)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decay_is_slow() {
+        let config = FearMemoryConfig::default();
+        let minute = config.decay_factor(Duration::from_secs(60));
+        assert!(minute > 0.97 && minute < 1.0);
+
+        let half_life = Duration::from_secs_f32(config.half_life_secs);
+        assert!((config.decay_factor(half_life) - 0.5).abs() < 1e-4);
+
+        let no_decay = FearMemoryConfig { half_life_secs: 0.0, ..config };
+        assert_eq!(no_decay.decay_factor(Duration::from_secs(3600)), 1.0);
+    }
+
+    #[test]
+    fn test_falloff() {
+        let config = FearMemoryConfig::default();
+        assert_eq!(config.falloff(0.0), 1.0);
+        assert!((config.falloff(config.radius / 2.0) - 0.5).abs() < 1e-6);
+        assert_eq!(config.falloff(config.radius * 2.0), 0.0);
+    }
+
+    #[test]
+    fn test_scar_blend() {
+        assert_eq!(scar_blend(0.0), 0.0);
+        assert_eq!(scar_blend(0.5), 0.5);
+        assert_eq!(scar_blend(1.0), 1.0);
+        assert_eq!(scar_blend(3.0), 1.0);
+        assert!(scar_blend(0.1) < 0.1);
+    }
+}
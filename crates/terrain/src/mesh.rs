@@ -0,0 +1,499 @@
+//! Chunk meshing
+//!
+//! [`ChunkMesher`] turns a chunk's height field (see
+//! [`TerrainGenerator::chunk_heights`]) into a triangle mesh. With
+//! [`MesherConfig::bake_vertex_colors`] it also bakes the fear response into
+//! per-vertex RGBA, for renderers that show vertex colors but make custom
+//! materials a hassle (Bevy's `StandardMaterial` multiplies its base color by
+//! them).
+//!
+//! A vertex color starts from the height gradient, then the slope, fear
+//! memory and fear gradients are laid over it in turn, each sample's alpha
+//! saying how strongly it covers what is below. Baking is a pure function of
+//! the mesh, the chunk's fear level and its fear memory, so the same inputs
+//! always give the same colors.
+//!
+//! [`TerrainGenerator::chunk_heights`]: crate::generator::TerrainGenerator::chunk_heights
+
+use crate::chunk::{Chunk, ChunkCoord, CHUNK_SIZE};
+use crate::memory::scar_blend;
+use crate::pool::MeshBuffers;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+/// Meshing errors
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum MeshError {
+    #[error("Height field of {0} samples is not a square grid of at least 2x2")]
+    NotSquare(usize),
+}
+
+/// A color at a position along a gradient
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ColorStop {
+    /// Position along the gradient, in [0, 1]
+    pub at: f32,
+    /// Linear RGBA; alpha is how strongly the color covers the layers below
+    pub color: [f32; 4],
+}
+
+impl ColorStop {
+    pub fn new(at: f32, color: [f32; 4]) -> Self {
+        Self { at, color }
+    }
+}
+
+/// Piecewise linear color ramp
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ColorGradient {
+    /// Stops in ascending order of position
+    pub stops: Vec<ColorStop>,
+}
+
+impl ColorGradient {
+    /// Gradient through `stops`, in any order
+    pub fn new(mut stops: Vec<ColorStop>) -> Self {
+        stops.sort_by(|a, b| a.at.total_cmp(&b.at));
+        Self { stops }
+    }
+
+    /// Color at `t`, holding the end colors beyond the first and last stops
+    ///
+    /// An empty gradient is fully transparent.
+    pub fn sample(&self, t: f32) -> [f32; 4] {
+        let (Some(first), Some(last)) = (self.stops.first(), self.stops.last()) else {
+            return [0.0; 4];
+        };
+        if t.is_nan() || t <= first.at {
+            return first.color;
+        }
+        if t >= last.at {
+            return last.color;
+        }
+        let upper = self.stops.iter().position(|stop| stop.at > t).unwrap_or(self.stops.len() - 1);
+        let (a, b) = (&self.stops[upper - 1], &self.stops[upper]);
+        let span = b.at - a.at;
+        let weight = if span > 0.0 { (t - a.at) / span } else { 1.0 };
+        lerp(a.color, b.color, weight)
+    }
+}
+
+/// Built-in vertex color palettes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ColorBakePreset {
+    /// Earthy terrain that glows ember red with fear and chars with memory
+    Ember,
+    /// Earthy terrain that drains to ash grey with fear and memory
+    Pallor,
+}
+
+/// How fear, height and slope become vertex colors
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ColorBakeConfig {
+    /// Base color by normalized height
+    pub height: ColorGradient,
+    /// Heights mapped to 0 and 1 along the height gradient
+    pub height_range: [f32; 2],
+    /// Overlay by slope, 0 for flat ground and 1 for a vertical face
+    pub slope: ColorGradient,
+    /// Overlay by the chunk's fear memory scar blend
+    pub fear_memory: ColorGradient,
+    /// Overlay by the chunk's fear level
+    pub fear: ColorGradient,
+}
+
+impl ColorBakeConfig {
+    pub fn preset(preset: ColorBakePreset) -> Self {
+        match preset {
+            ColorBakePreset::Ember => Self::ember(),
+            ColorBakePreset::Pallor => Self::pallor(),
+        }
+    }
+
+    /// See [`ColorBakePreset::Ember`]
+    pub fn ember() -> Self {
+        Self {
+            fear_memory: overlay([0.06, 0.04, 0.04], 0.7),
+            fear: overlay([0.6, 0.06, 0.02], 0.65),
+            ..Self::earth()
+        }
+    }
+
+    /// See [`ColorBakePreset::Pallor`]
+    pub fn pallor() -> Self {
+        Self {
+            fear_memory: overlay([0.3, 0.3, 0.3], 0.6),
+            fear: overlay([0.62, 0.62, 0.62], 0.7),
+            ..Self::earth()
+        }
+    }
+
+    /// Shared base: mud in the hollows, grass in between, pale stone on the
+    /// peaks and bare rock on steep faces, spanning the default generator's
+    /// height range
+    fn earth() -> Self {
+        Self {
+            height: ColorGradient::new(vec![
+                ColorStop::new(0.0, [0.3, 0.25, 0.17, 1.0]),
+                ColorStop::new(0.5, [0.28, 0.4, 0.18, 1.0]),
+                ColorStop::new(1.0, [0.72, 0.71, 0.68, 1.0]),
+            ]),
+            height_range: [-8.0, 8.0],
+            slope: ColorGradient::new(vec![
+                ColorStop::new(0.2, [0.36, 0.34, 0.31, 0.0]),
+                ColorStop::new(0.7, [0.36, 0.34, 0.31, 0.85]),
+            ]),
+            fear_memory: ColorGradient::new(Vec::new()),
+            fear: ColorGradient::new(Vec::new()),
+        }
+    }
+
+    /// Color of a vertex at `height` with unit `normal`
+    fn vertex_color(&self, height: f32, normal: [f32; 3], fear_level: f32, memory_blend: f32) -> [f32; 4] {
+        let [low, high] = self.height_range;
+        let height_t = if high > low { (height - low) / (high - low) } else { 0.5 };
+        let slope = (1.0 - normal[1]).clamp(0.0, 1.0);
+
+        let mut color = self.height.sample(height_t.clamp(0.0, 1.0));
+        color[3] = 1.0;
+        for layer in [
+            self.slope.sample(slope),
+            self.fear_memory.sample(memory_blend),
+            self.fear.sample(fear_level),
+        ] {
+            let coverage = layer[3].clamp(0.0, 1.0);
+            color = lerp(color, [layer[0], layer[1], layer[2], 1.0], coverage);
+        }
+        color
+    }
+}
+
+impl Default for ColorBakeConfig {
+    fn default() -> Self {
+        Self::ember()
+    }
+}
+
+/// Meshing options
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MesherConfig {
+    /// Bake fear into vertex colors; `None` leaves meshes uncolored
+    pub bake_vertex_colors: Option<ColorBakeConfig>,
+}
+
+/// Fear a mesh's colors were baked for
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct BakeStamp {
+    fear_level: f32,
+    fear_memory: f32,
+}
+
+/// Triangle mesh of one chunk's surface
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChunkMesh {
+    pub coord: ChunkCoord,
+    /// Vertex positions; x and z relative to the chunk's origin, y the world height
+    pub positions: Vec<[f32; 3]>,
+    /// Unit vertex normals
+    pub normals: Vec<[f32; 3]>,
+    /// Triangle list, counter-clockwise seen from above
+    pub indices: Vec<u32>,
+    /// Linear RGBA per vertex, when colors are baked
+    pub colors: Option<Vec<[f32; 4]>>,
+    baked_for: Option<BakeStamp>,
+}
+
+impl ChunkMesh {
+    /// Whether the colors were baked for this fear level and memory
+    ///
+    /// Always true for meshes without baked colors.
+    pub fn colors_current(&self, fear_level: f32, fear_memory: f32) -> bool {
+        match self.baked_for {
+            Some(stamp) => stamp == BakeStamp { fear_level, fear_memory },
+            None => true,
+        }
+    }
+
+    /// The mesh's buffers, for a [`MeshBufferPool`](crate::pool::MeshBufferPool)
+    pub fn into_buffers(self) -> MeshBuffers {
+        let side = grid_side(self.positions.len()).unwrap_or(0);
+        MeshBuffers::from_parts(
+            side,
+            self.positions,
+            self.normals,
+            self.indices,
+            self.colors.unwrap_or_default(),
+        )
+    }
+}
+
+/// Builds chunk meshes from height fields
+#[derive(Debug, Clone, Default)]
+pub struct ChunkMesher {
+    config: MesherConfig,
+}
+
+impl ChunkMesher {
+    pub fn new(config: MesherConfig) -> Self {
+        Self { config }
+    }
+
+    pub fn config(&self) -> &MesherConfig {
+        &self.config
+    }
+
+    /// Mesh `chunk` from its heights on a square grid, row by row along z,
+    /// baking colors for `fear_level` and the chunk's fear memory if enabled
+    pub fn mesh(&self, chunk: &Chunk, heights: &[f32], fear_level: f32) -> Result<ChunkMesh, MeshError> {
+        self.mesh_into(chunk, heights, fear_level, MeshBuffers::default())
+    }
+
+    /// [`mesh`](Self::mesh), writing into `buffers` rather than allocating
+    ///
+    /// Buffers of the mesh's LOD class are filled without reallocating.
+    pub fn mesh_into(
+        &self,
+        chunk: &Chunk,
+        heights: &[f32],
+        fear_level: f32,
+        buffers: MeshBuffers,
+    ) -> Result<ChunkMesh, MeshError> {
+        let side = grid_side(heights.len())?;
+        let step = CHUNK_SIZE / (side - 1) as f32;
+        let height = |column: usize, row: usize| heights[row * side + column];
+
+        let MeshBuffers { mut positions, mut normals, mut indices, colors, .. } = buffers;
+        positions.clear();
+        normals.clear();
+        indices.clear();
+        positions.reserve(heights.len());
+        normals.reserve(heights.len());
+        indices.reserve((side - 1) * (side - 1) * 6);
+        for row in 0..side {
+            for column in 0..side {
+                positions.push([column as f32 * step, height(column, row), row as f32 * step]);
+
+                // Central differences, one-sided at the edges
+                let (left, right) = (column.saturating_sub(1), (column + 1).min(side - 1));
+                let (back, front) = (row.saturating_sub(1), (row + 1).min(side - 1));
+                let dx = (height(right, row) - height(left, row)) / ((right - left) as f32 * step);
+                let dz = (height(column, front) - height(column, back)) / ((front - back) as f32 * step);
+                normals.push(normalize([-dx, 1.0, -dz]));
+            }
+        }
+
+        for row in 0..side - 1 {
+            for column in 0..side - 1 {
+                let corner = (row * side + column) as u32;
+                let below = corner + side as u32;
+                indices.extend_from_slice(&[corner, below, corner + 1, corner + 1, below, below + 1]);
+            }
+        }
+
+        let mut mesh = ChunkMesh {
+            coord: chunk.coord,
+            positions,
+            normals,
+            indices,
+            // Handed to the bake when colors are baked
+            colors: self.config.bake_vertex_colors.is_some().then_some(colors),
+            baked_for: None,
+        };
+        self.refresh_colors(&mut mesh, fear_level, chunk.fear_memory);
+        Ok(mesh)
+    }
+
+    /// Rebake `mesh`'s colors if they were baked for another fear level or memory
+    ///
+    /// Returns whether the colors changed. Call it when a chunk is
+    /// regenerated for a new fear level without its shape changing.
+    pub fn refresh_colors(&self, mesh: &mut ChunkMesh, fear_level: f32, fear_memory: f32) -> bool {
+        let Some(bake) = &self.config.bake_vertex_colors else {
+            return false;
+        };
+        let stamp = BakeStamp { fear_level, fear_memory };
+        if mesh.colors.is_some() && mesh.baked_for == Some(stamp) {
+            return false;
+        }
+
+        let fear_level = fear_level.clamp(0.0, 1.0);
+        let memory_blend = scar_blend(fear_memory);
+        let mut colors = mesh.colors.take().unwrap_or_default();
+        colors.clear();
+        colors.extend(
+            mesh.positions
+                .iter()
+                .zip(&mesh.normals)
+                .map(|(position, &normal)| bake.vertex_color(position[1], normal, fear_level, memory_blend)),
+        );
+        mesh.colors = Some(colors);
+        mesh.baked_for = Some(stamp);
+        true
+    }
+}
+
+/// Vertices along each edge of a square height grid of `len` samples
+fn grid_side(len: usize) -> Result<usize, MeshError> {
+    let side = (len as f64).sqrt().round() as usize;
+    if side < 2 || side * side != len {
+        return Err(MeshError::NotSquare(len));
+    }
+    Ok(side)
+}
+
+fn lerp(a: [f32; 4], b: [f32; 4], t: f32) -> [f32; 4] {
+    std::array::from_fn(|i| a[i] + (b[i] - a[i]) * t)
+}
+
+fn normalize(v: [f32; 3]) -> [f32; 3] {
+    let length = (v[0] * v[0] + v[1] * v[1] + v[2] * v[2]).sqrt();
+    v.map(|c| c / length)
+}
+
+/// Gradient laying `color` over the surface, from not at all to `strength`
+fn overlay(color: [f32; 3], strength: f32) -> ColorGradient {
+    let [r, g, b] = color;
+    ColorGradient::new(vec![
+        ColorStop::new(0.0, [r, g, b, 0.0]),
+        ColorStop::new(1.0, [r, g, b, strength]),
+    ])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generator::TerrainGenerator;
+    use crate::pool::MeshBufferPool;
+
+    const RESOLUTION: usize = 8;
+
+    fn baked(preset: ColorBakePreset, fear_level: f32) -> ChunkMesh {
+        let mesher = ChunkMesher::new(MesherConfig {
+            bake_vertex_colors: Some(ColorBakeConfig::preset(preset)),
+        });
+        let chunk = Chunk::new(ChunkCoord::new(2, 0, -1));
+        let heights = TerrainGenerator::default().chunk_heights(&chunk, RESOLUTION);
+        mesher.mesh(&chunk, &heights, fear_level).unwrap()
+    }
+
+    fn sampled(mesh: &ChunkMesh) -> Vec<[f32; 4]> {
+        let colors = mesh.colors.as_ref().unwrap();
+        (0..colors.len()).step_by(7).map(|i| colors[i]).collect()
+    }
+
+    #[test]
+    fn test_mesh_shape() {
+        let chunk = Chunk::new(ChunkCoord::new(0, 0, 0));
+        let heights = TerrainGenerator::default().chunk_heights(&chunk, RESOLUTION);
+        let mesh = ChunkMesher::default().mesh(&chunk, &heights, 0.5).unwrap();
+
+        assert_eq!(mesh.positions.len(), (RESOLUTION + 1) * (RESOLUTION + 1));
+        assert_eq!(mesh.normals.len(), mesh.positions.len());
+        assert_eq!(mesh.indices.len(), RESOLUTION * RESOLUTION * 6);
+        assert_eq!(mesh.positions.last().unwrap()[0], CHUNK_SIZE);
+        assert!(mesh.colors.is_none());
+
+        // Every triangle faces up
+        for triangle in mesh.indices.chunks(3) {
+            let [a, b, c] = [0, 1, 2].map(|i| mesh.positions[triangle[i] as usize]);
+            let (ab, ac) = ([b[0] - a[0], b[2] - a[2]], [c[0] - a[0], c[2] - a[2]]);
+            assert!(ab[1] * ac[0] - ab[0] * ac[1] > 0.0);
+        }
+
+        assert_eq!(ChunkMesher::default().mesh(&chunk, &heights[1..], 0.5), Err(MeshError::NotSquare(80)));
+        assert_eq!(ChunkMesher::default().mesh(&chunk, &[0.0], 0.5), Err(MeshError::NotSquare(1)));
+    }
+
+    #[test]
+    fn test_fear_colors_follow_preset() {
+        for preset in [ColorBakePreset::Ember, ColorBakePreset::Pallor] {
+            let calm = baked(preset, 0.0);
+            let afraid = baked(preset, 1.0);
+            assert_eq!(calm.colors.as_ref().unwrap().len(), calm.positions.len());
+            assert_eq!(afraid.colors.as_ref().unwrap().len(), afraid.positions.len());
+            assert_ne!(calm.colors, afraid.colors);
+            assert_eq!(calm.positions, afraid.positions);
+
+            for (calm, afraid) in sampled(&calm).into_iter().zip(sampled(&afraid)) {
+                assert_eq!((calm[3], afraid[3]), (1.0, 1.0));
+                match preset {
+                    // Redder
+                    ColorBakePreset::Ember => assert!(afraid[0] - afraid[1] > calm[0] - calm[1]),
+                    // Greyer
+                    ColorBakePreset::Pallor => {
+                        let saturation = |c: [f32; 4]| c[0].max(c[1]).max(c[2]) - c[0].min(c[1]).min(c[2]);
+                        assert!(saturation(afraid) < saturation(calm));
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_bake_is_deterministic_and_revalidated() {
+        assert_eq!(baked(ColorBakePreset::Ember, 0.3), baked(ColorBakePreset::Ember, 0.3));
+
+        let mesher = ChunkMesher::new(MesherConfig {
+            bake_vertex_colors: Some(ColorBakeConfig::default()),
+        });
+        let mut mesh = baked(ColorBakePreset::Ember, 0.0);
+        assert!(mesh.colors_current(0.0, 0.0));
+        assert!(!mesh.colors_current(1.0, 0.0));
+
+        // Same fear keeps the colors; new fear or memory rebakes them
+        assert!(!mesher.refresh_colors(&mut mesh, 0.0, 0.0));
+        assert!(mesher.refresh_colors(&mut mesh, 1.0, 0.0));
+        assert_eq!(mesh.colors, baked(ColorBakePreset::Ember, 1.0).colors);
+        assert!(mesh.colors_current(1.0, 0.0));
+        let before = mesh.colors.clone();
+        assert!(mesher.refresh_colors(&mut mesh, 1.0, 0.8));
+        assert_ne!(mesh.colors, before);
+
+        // Without baking nothing is ever stale
+        assert!(!ChunkMesher::default().refresh_colors(&mut mesh, 0.0, 0.0));
+    }
+
+    #[test]
+    fn test_mesh_into_reuses_buffers() {
+        let mesher = ChunkMesher::new(MesherConfig {
+            bake_vertex_colors: Some(ColorBakeConfig::default()),
+        });
+        let chunk = Chunk::new(ChunkCoord::new(0, 0, 0));
+        let heights = TerrainGenerator::default().chunk_heights(&chunk, RESOLUTION);
+        let expected = mesher.mesh(&chunk, &heights, 0.5).unwrap();
+
+        let mut pool = MeshBufferPool::default();
+        let buffers = pool.checkout(RESOLUTION + 1);
+        let positions = buffers.positions.as_ptr();
+        let mesh = mesher.mesh_into(&chunk, &heights, 0.5, buffers).unwrap();
+        assert_eq!(mesh, expected);
+        assert_eq!(mesh.positions.as_ptr(), positions);
+
+        // Back and out again: same allocation, same mesh
+        pool.give_back(mesh.into_buffers());
+        let buffers = pool.checkout(RESOLUTION + 1);
+        assert_eq!(buffers.side(), RESOLUTION + 1);
+        let colors = buffers.colors.as_ptr();
+        let mesh = mesher.mesh_into(&chunk, &heights, 0.5, buffers).unwrap();
+        assert_eq!(mesh, expected);
+        assert_eq!(mesh.positions.as_ptr(), positions);
+        assert_eq!(mesh.colors.as_ref().unwrap().as_ptr(), colors);
+        assert_eq!(pool.stats().hits, 1);
+    }
+
+    #[test]
+    fn test_gradient_sampling() {
+        let gradient = ColorGradient::new(vec![
+            ColorStop::new(1.0, [1.0, 1.0, 1.0, 1.0]),
+            ColorStop::new(0.0, [0.0, 0.0, 0.0, 0.0]),
+            ColorStop::new(0.5, [1.0, 0.0, 0.0, 1.0]),
+        ]);
+        assert_eq!(gradient.sample(-1.0), [0.0; 4]);
+        assert_eq!(gradient.sample(0.25), [0.5, 0.0, 0.0, 0.5]);
+        assert_eq!(gradient.sample(0.75), [1.0, 0.5, 0.5, 1.0]);
+        assert_eq!(gradient.sample(2.0), [1.0; 4]);
+        assert_eq!(ColorGradient::new(Vec::new()).sample(0.5), [0.0; 4]);
+    }
+}
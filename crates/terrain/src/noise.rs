@@ -1,4 +1,298 @@
-//! Noise generation for terrain (placeholder for M0)
+//! Fear-warped noise density
+//!
+//! [`NoiseField`] is a density function for [`DensityChunk`]s built from
+//! [`TerrainConfig`]: ground up to `base_height`, roughened by fractal noise
+//! sampled every `1 / noise_scale` world units. Density above zero is solid.
+//!
+//! Fear does not scale the noise, it warps the space the noise is read
+//! from: each sample is pushed along a turbulence field by up to
+//! `fear_multiplier` world units, weighted by the fear level and the
+//! distortion intensity of its bucket in the field's [`FearMapping`]. At
+//! fear 0 the terrain is untouched; at high fear hills lean, fold over and
+//! tear into overhangs. Meshes rebuilt per bucket sample at the bucket's
+//! intensity alone with [`sample_at_intensity`](NoiseField::sample_at_intensity).
+//!
+//! Fear memory left behind in a chunk cuts scars into it: the crack pattern
+//! of the height field generator lowers the ground by up to `scar_depth`
+//! world units, see [`sample_scarred`](NoiseField::sample_scarred).
+//!
+//! [`DensityChunk`]: crate::chunk::DensityChunk
 
-// Placeholder for noise generation
-// Will be implemented in M0.5 (Shader-Warp) milestone
+use fastnoise_lite::{FastNoiseLite, FractalType, NoiseType};
+use spectremesh_core::{FearBucket, FearMapping, TerrainConfig};
+
+use crate::generator::{crack, crack_noise, GeneratorConfig};
+
+/// Frequency of the warp field relative to the base noise; warping at a
+/// coarser scale bends whole landforms rather than adding grain
+const WARP_FREQUENCY_RATIO: f32 = 0.5;
+
+/// Octave settings of the base fractal noise
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FractalParams {
+    /// Layers of noise summed together
+    pub octaves: u32,
+    /// Frequency multiplier from one octave to the next
+    pub lacunarity: f32,
+    /// Amplitude multiplier from one octave to the next
+    pub persistence: f32,
+}
+
+impl Default for FractalParams {
+    fn default() -> Self {
+        Self {
+            octaves: 4,
+            lacunarity: 2.0,
+            persistence: 0.5,
+        }
+    }
+}
+
+/// Seeded 3D density with fear-driven domain warping
+pub struct NoiseField {
+    base_height: f32,
+    /// Height of the noise's peaks above and troughs below `base_height`
+    amplitude: f32,
+    fear_multiplier: f32,
+    /// Buckets and intensities fear levels are read through
+    fear_mapping: FearMapping,
+    fractal: FractalParams,
+    base: FastNoiseLite,
+    /// One field per warp axis
+    warp: [FastNoiseLite; 3],
+    /// Depth of scars at full fear memory
+    scar_depth: f32,
+    crack_noise: FastNoiseLite,
+}
+
+impl NoiseField {
+    /// Field for `config`; the same config and seed always give the same densities
+    pub fn new(config: &TerrainConfig, seed: u64) -> Self {
+        // FastNoise seeds are 32 bits; fold the high half in so every bit counts
+        let seed = (seed ^ (seed >> 32)) as u32 as i32;
+        let frequency = config.noise_scale;
+
+        let mut field = Self {
+            base_height: config.base_height,
+            amplitude: 16.0,
+            fear_multiplier: config.fear_multiplier,
+            fear_mapping: FearMapping::default(),
+            fractal: FractalParams::default(),
+            base: FastNoiseLite::with_seed(seed),
+            warp: [1, 2, 3].map(|offset| {
+                let mut warp = FastNoiseLite::with_seed(seed.wrapping_add(offset));
+                warp.set_noise_type(Some(NoiseType::OpenSimplex2));
+                warp.set_fractal_type(Some(FractalType::FBm));
+                warp.set_fractal_octaves(Some(2));
+                warp.set_frequency(Some(frequency * WARP_FREQUENCY_RATIO));
+                warp
+            }),
+            scar_depth: GeneratorConfig::default().scar_depth,
+            crack_noise: crack_noise(seed.wrapping_add(4), GeneratorConfig::default().crack_frequency),
+        };
+        field.base.set_noise_type(Some(NoiseType::OpenSimplex2));
+        field.base.set_fractal_type(Some(FractalType::FBm));
+        field.base.set_frequency(Some(frequency));
+        field.apply_fractal();
+        field
+    }
+
+    /// Use other octave settings for the base noise
+    pub fn with_fractal(mut self, fractal: FractalParams) -> Self {
+        self.fractal = fractal;
+        self.apply_fractal();
+        self
+    }
+
+    /// Set how far the noise lifts and lowers the ground, in world units
+    pub fn with_amplitude(mut self, amplitude: f32) -> Self {
+        self.amplitude = amplitude;
+        self
+    }
+
+    /// Read fear levels through `mapping`'s buckets and intensities
+    pub fn with_fear_mapping(mut self, mapping: FearMapping) -> Self {
+        self.fear_mapping = mapping;
+        self
+    }
+
+    /// Set how deep scars cut at full fear memory, in world units
+    pub fn with_scar_depth(mut self, scar_depth: f32) -> Self {
+        self.scar_depth = scar_depth;
+        self
+    }
+
+    pub fn fractal(&self) -> FractalParams {
+        self.fractal
+    }
+
+    /// Distance samples are warped by at full turbulence for a fear level in [0, 1]
+    pub fn warp_strength(&self, fear: f32) -> f32 {
+        let fear = fear.clamp(0.0, 1.0);
+        let bucket = FearBucket::from_score_with(fear, &self.fear_mapping);
+        self.intensity_warp_strength(self.fear_mapping.intensity(bucket)) * fear
+    }
+
+    /// Distance samples are warped by at full turbulence for a distortion
+    /// intensity in [0, 1], such as a fear bucket's
+    pub fn intensity_warp_strength(&self, intensity: f32) -> f32 {
+        self.fear_multiplier * intensity.clamp(0.0, 1.0)
+    }
+
+    /// Density at world (x, y, z) for a fear level in [0, 1]
+    pub fn sample(&self, x: f32, y: f32, z: f32, fear: f32) -> f32 {
+        self.sample_warped(x, y, z, self.warp_strength(fear))
+    }
+
+    /// Density at world (x, y, z) for a distortion intensity in [0, 1]
+    ///
+    /// The intensity is used as is rather than read from a fear level's
+    /// bucket, so the whole bucket meshes alike.
+    pub fn sample_at_intensity(&self, x: f32, y: f32, z: f32, intensity: f32) -> f32 {
+        self.sample_warped(x, y, z, self.intensity_warp_strength(intensity))
+    }
+
+    /// Density at world (x, y, z) for a distortion intensity in [0, 1] in a
+    /// chunk with `fear_memory` in [0, 1]
+    pub fn sample_scarred(&self, x: f32, y: f32, z: f32, intensity: f32, fear_memory: f32) -> f32 {
+        self.sample_at_intensity(x, y, z, intensity) - self.scar_displacement(x, z, fear_memory)
+    }
+
+    /// Downward displacement from scars, proportional to fear memory
+    pub fn scar_displacement(&self, x: f32, z: f32, fear_memory: f32) -> f32 {
+        self.scar_depth * fear_memory.clamp(0.0, 1.0) * crack(&self.crack_noise, x, z)
+    }
+
+    /// Density at world (x, y, z) with samples warped by up to `strength`
+    fn sample_warped(&self, x: f32, y: f32, z: f32, strength: f32) -> f32 {
+        let [wx, wy, wz] = if strength > 0.0 {
+            self.warp.each_ref().map(|warp| warp.get_noise_3d(x, y, z) * strength)
+        } else {
+            [0.0; 3]
+        };
+        let (x, y, z) = (x + wx, y + wy, z + wz);
+
+        self.base_height - y + self.amplitude * self.base.get_noise_3d(x, y, z)
+    }
+
+    fn apply_fractal(&mut self) {
+        let FractalParams { octaves, lacunarity, persistence } = self.fractal;
+        self.base.set_fractal_octaves(Some(octaves.clamp(1, i32::MAX as u32) as i32));
+        self.base.set_fractal_lacunarity(Some(lacunarity));
+        self.base.set_fractal_gain(Some(persistence));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use spectremesh_core::FearBucketThresholds;
+
+    fn grid() -> impl Iterator<Item = [f32; 3]> {
+        (0..8).flat_map(|x| (0..8).flat_map(move |y| (0..8).map(move |z| [x as f32 * 7.0, 40.0 + y as f32 * 4.0, z as f32 * 7.0])))
+    }
+
+    fn samples(field: &NoiseField, fear: f32) -> Vec<f32> {
+        grid().map(|[x, y, z]| field.sample(x, y, z, fear)).collect()
+    }
+
+    #[test]
+    fn test_same_seed_same_densities() {
+        let config = TerrainConfig::default();
+        let a = NoiseField::new(&config, 42);
+        let b = NoiseField::new(&config, 42);
+        let other = NoiseField::new(&config, 42 | 1 << 40);
+
+        for fear in [0.0, 0.5, 1.0] {
+            assert_eq!(samples(&a, fear), samples(&b, fear));
+            assert_ne!(samples(&a, fear), samples(&other, fear));
+        }
+
+        let coarse = NoiseField::new(&config, 42).with_fractal(FractalParams { octaves: 1, ..Default::default() });
+        assert_ne!(samples(&a, 0.0), samples(&coarse, 0.0));
+    }
+
+    #[test]
+    fn test_fear_warps_the_terrain() {
+        let config = TerrainConfig::default();
+        let field = NoiseField::new(&config, 7);
+        let calm = samples(&field, 0.0);
+        let terrified = samples(&field, 1.0);
+        let mean_change =
+            calm.iter().zip(&terrified).map(|(a, b)| (a - b).abs()).sum::<f32>() / calm.len() as f32;
+        assert!(mean_change > 0.5, "mean change {}", mean_change);
+
+        // Calm terrain does not depend on the fear multiplier
+        let tame = NoiseField::new(&TerrainConfig { fear_multiplier: 0.0, ..config.clone() }, 7);
+        assert_eq!(samples(&tame, 0.0), calm);
+        assert_eq!(samples(&tame, 1.0), calm);
+
+        // Warp grows with fear and its bucket
+        assert_eq!(field.warp_strength(0.0), 0.0);
+        assert!(field.warp_strength(0.2) < field.warp_strength(0.5));
+        assert_eq!(field.warp_strength(1.0), config.fear_multiplier);
+        assert_eq!(field.warp_strength(3.0), field.warp_strength(1.0));
+    }
+
+    #[test]
+    fn test_warp_follows_the_fear_mapping() {
+        let config = TerrainConfig::default();
+        let field = NoiseField::new(&config, 7);
+        let mapping = FearMapping::default()
+            .with_thresholds(FearBucketThresholds { medium: 0.2, high: 0.4 })
+            .with_intensities([0.0, 0.3, 0.6]);
+        let mapped = NoiseField::new(&config, 7).with_fear_mapping(mapping);
+
+        // The default mapping is the fixed buckets
+        assert_eq!(field.warp_strength(0.5), config.fear_multiplier * FearBucket::Medium.distortion_intensity() * 0.5);
+        assert_eq!(mapped.warp_strength(0.1), 0.0);
+        assert_eq!(mapped.warp_strength(0.3), config.fear_multiplier * 0.3 * 0.3);
+        assert_eq!(mapped.warp_strength(0.5), config.fear_multiplier * 0.6 * 0.5);
+        assert_eq!(samples(&mapped, 0.1), samples(&field, 0.0));
+    }
+
+    #[test]
+    fn test_intensity_is_not_weighted_again() {
+        let config = TerrainConfig::default();
+        let field = NoiseField::new(&config, 7);
+        let intensity = FearBucket::Medium.distortion_intensity();
+        assert_eq!(field.intensity_warp_strength(intensity), config.fear_multiplier * intensity);
+        assert_eq!(field.intensity_warp_strength(0.0), 0.0);
+        assert_eq!(field.intensity_warp_strength(2.0), config.fear_multiplier);
+
+        // The same warp as full fear with the multiplier scaled by the intensity
+        let at_intensity: Vec<_> = grid().map(|[x, y, z]| field.sample_at_intensity(x, y, z, intensity)).collect();
+        let scaled = NoiseField::new(&TerrainConfig { fear_multiplier: config.fear_multiplier * intensity, ..config.clone() }, 7);
+        assert_eq!(at_intensity, samples(&scaled, 1.0));
+        // Read as a fear level, the intensity would be weighted twice
+        assert_ne!(at_intensity, samples(&field, intensity));
+    }
+
+    #[test]
+    fn test_fear_memory_scars_the_ground() {
+        let config = TerrainConfig::default();
+        let field = NoiseField::new(&config, 7);
+        let unscarred: Vec<_> = grid().map(|[x, y, z]| field.sample_at_intensity(x, y, z, 0.5)).collect();
+        let scarred: Vec<_> = grid().map(|[x, y, z]| field.sample_scarred(x, y, z, 0.5, 1.0)).collect();
+        let untouched: Vec<_> = grid().map(|[x, y, z]| field.sample_scarred(x, y, z, 0.5, 0.0)).collect();
+        assert_eq!(untouched, unscarred);
+
+        // Scars only ever lower the ground, and never deeper than scar_depth
+        let cuts: Vec<_> = unscarred.iter().zip(&scarred).map(|(a, b)| a - b).collect();
+        assert!(cuts.iter().all(|cut| (0.0..=3.0).contains(cut)));
+        assert!(cuts.iter().any(|cut| *cut > 0.1));
+
+        let shallow = NoiseField::new(&config, 7).with_scar_depth(0.0);
+        assert_eq!(grid().map(|[x, y, z]| shallow.sample_scarred(x, y, z, 0.5, 1.0)).collect::<Vec<_>>(), unscarred);
+    }
+
+    #[test]
+    fn test_ground_is_solid_below_and_empty_above() {
+        let config = TerrainConfig::default();
+        let field = NoiseField::new(&config, 3).with_amplitude(4.0);
+        for fear in [0.0, 1.0] {
+            assert!(field.sample(10.0, config.base_height - 20.0, -5.0, fear) > 0.0);
+            assert!(field.sample(10.0, config.base_height + 20.0, -5.0, fear) < 0.0);
+        }
+    }
+}
@@ -0,0 +1,239 @@
+//! Reusable chunk mesh buffers
+//!
+//! Streaming a moving world meshes thousands of chunks, each needing vertex
+//! and index buffers of a size set by its level of detail. Instead of
+//! allocating them for every mesh and freeing them when the chunk goes away,
+//! [`MeshBufferPool`] keeps returned buffers by LOD class and hands them out
+//! again. Pooled memory is capped; beyond the cap the least recently
+//! returned buffers are freed.
+
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::mem::size_of;
+
+/// Vertex and index buffers for one chunk mesh
+///
+/// The LOD class is the number of vertices along a chunk edge; buffers of
+/// one class fit any mesh of that class without reallocating.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MeshBuffers {
+    side: usize,
+    pub(crate) positions: Vec<[f32; 3]>,
+    pub(crate) normals: Vec<[f32; 3]>,
+    pub(crate) indices: Vec<u32>,
+    pub(crate) colors: Vec<[f32; 4]>,
+}
+
+impl MeshBuffers {
+    /// Empty buffers with room for a mesh with `side` vertices along each edge
+    pub fn with_side(side: usize) -> Self {
+        let vertices = side * side;
+        let indices = side.saturating_sub(1).pow(2) * 6;
+        Self {
+            side,
+            positions: Vec::with_capacity(vertices),
+            normals: Vec::with_capacity(vertices),
+            indices: Vec::with_capacity(indices),
+            colors: Vec::with_capacity(vertices),
+        }
+    }
+
+    /// LOD class: vertices along each chunk edge
+    pub fn side(&self) -> usize {
+        self.side
+    }
+
+    /// Bytes allocated, used or not
+    pub fn bytes(&self) -> usize {
+        self.positions.capacity() * size_of::<[f32; 3]>()
+            + self.normals.capacity() * size_of::<[f32; 3]>()
+            + self.indices.capacity() * size_of::<u32>()
+            + self.colors.capacity() * size_of::<[f32; 4]>()
+    }
+
+    pub(crate) fn from_parts(
+        side: usize,
+        positions: Vec<[f32; 3]>,
+        normals: Vec<[f32; 3]>,
+        indices: Vec<u32>,
+        colors: Vec<[f32; 4]>,
+    ) -> Self {
+        Self { side, positions, normals, indices, colors }
+    }
+
+    fn clear(&mut self) {
+        self.positions.clear();
+        self.normals.clear();
+        self.indices.clear();
+        self.colors.clear();
+    }
+}
+
+/// How much a pool may hold
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MeshPoolConfig {
+    /// Pooled bytes beyond which the least recently returned buffers are freed
+    pub max_bytes: usize,
+}
+
+impl Default for MeshPoolConfig {
+    fn default() -> Self {
+        // About 470 chunks at the default 17x17 vertices with colors
+        Self { max_bytes: 8 * 1024 * 1024 }
+    }
+}
+
+/// Pool activity since it was created
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MeshPoolStats {
+    /// Checkouts served from the pool
+    pub hits: u64,
+    /// Checkouts that allocated new buffers
+    pub misses: u64,
+    /// Buffers freed to stay under the cap
+    pub trimmed: u64,
+    /// Bytes held by pooled buffers
+    pub pooled_bytes: usize,
+    /// Buffers waiting in the pool
+    pub pooled_buffers: usize,
+}
+
+/// Chunk mesh buffers kept for reuse, by LOD class
+#[derive(Debug, Clone, Default)]
+pub struct MeshBufferPool {
+    config: MeshPoolConfig,
+    /// Least recently returned first
+    free: VecDeque<MeshBuffers>,
+    stats: MeshPoolStats,
+}
+
+impl MeshBufferPool {
+    pub fn new(config: MeshPoolConfig) -> Self {
+        Self {
+            config,
+            ..Default::default()
+        }
+    }
+
+    pub fn config(&self) -> &MeshPoolConfig {
+        &self.config
+    }
+
+    /// Change the cap, trimming at once if the pool is over the new one
+    pub fn set_config(&mut self, config: MeshPoolConfig) {
+        self.config = config;
+        self.trim_to(config.max_bytes);
+    }
+
+    pub fn stats(&self) -> MeshPoolStats {
+        self.stats
+    }
+
+    /// Empty buffers for a mesh with `side` vertices along each edge,
+    /// the most recently returned ones of that class if any
+    pub fn checkout(&mut self, side: usize) -> MeshBuffers {
+        match self.free.iter().rposition(|buffers| buffers.side == side) {
+            Some(index) => {
+                let buffers = self.free.remove(index).expect("index from rposition");
+                self.stats.hits += 1;
+                self.stats.pooled_bytes -= buffers.bytes();
+                self.stats.pooled_buffers -= 1;
+                buffers
+            }
+            None => {
+                self.stats.misses += 1;
+                MeshBuffers::with_side(side)
+            }
+        }
+    }
+
+    /// Keep `buffers` for the next checkout of their class, freeing the
+    /// least recently returned buffers if the pool is over its cap
+    pub fn give_back(&mut self, mut buffers: MeshBuffers) {
+        buffers.clear();
+        self.stats.pooled_bytes += buffers.bytes();
+        self.stats.pooled_buffers += 1;
+        self.free.push_back(buffers);
+        self.trim_to(self.config.max_bytes);
+    }
+
+    /// Free the least recently returned buffers until at most `max_bytes` are pooled
+    pub fn trim_to(&mut self, max_bytes: usize) {
+        while self.stats.pooled_bytes > max_bytes {
+            let Some(oldest) = self.free.pop_front() else {
+                break;
+            };
+            self.stats.pooled_bytes -= oldest.bytes();
+            self.stats.pooled_buffers -= 1;
+            self.stats.trimmed += 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_checkout_and_return_by_class() {
+        let mut pool = MeshBufferPool::default();
+        let coarse = pool.checkout(9);
+        let fine = pool.checkout(17);
+        assert_eq!((coarse.side(), fine.side()), (9, 17));
+        assert!(coarse.positions.capacity() >= 81 && coarse.indices.capacity() >= 8 * 8 * 6);
+        assert_eq!(pool.stats().misses, 2);
+
+        let (coarse_bytes, fine_bytes) = (coarse.bytes(), fine.bytes());
+        pool.give_back(coarse);
+        pool.give_back(fine);
+        assert_eq!(pool.stats().pooled_bytes, coarse_bytes + fine_bytes);
+        assert_eq!(pool.stats().pooled_buffers, 2);
+
+        // Each class gets its own buffers back; a third class misses
+        assert_eq!(pool.checkout(17).bytes(), fine_bytes);
+        assert_eq!(pool.checkout(9).bytes(), coarse_bytes);
+        assert_eq!(pool.checkout(33).side(), 33);
+        assert_eq!(
+            pool.stats(),
+            MeshPoolStats { hits: 2, misses: 3, trimmed: 0, pooled_bytes: 0, pooled_buffers: 0 }
+        );
+    }
+
+    #[test]
+    fn test_returned_buffers_are_emptied() {
+        let mut pool = MeshBufferPool::default();
+        let mut buffers = pool.checkout(2);
+        buffers.positions.push([1.0; 3]);
+        buffers.indices.extend([0, 1, 2]);
+        pool.give_back(buffers);
+
+        let buffers = pool.checkout(2);
+        assert!(buffers.positions.is_empty() && buffers.indices.is_empty());
+        assert!(buffers.positions.capacity() >= 4);
+    }
+
+    #[test]
+    fn test_cap_trims_least_recently_returned() {
+        let size = MeshBuffers::with_side(17).bytes();
+        let mut pool = MeshBufferPool::new(MeshPoolConfig { max_bytes: size * 3 });
+        let checked_out: Vec<MeshBuffers> = (0..5).map(|_| pool.checkout(17)).collect();
+        let coarse = pool.checkout(5);
+        pool.give_back(coarse);
+        for buffers in checked_out {
+            pool.give_back(buffers);
+        }
+
+        // The coarse buffers went back first, so they went first
+        let stats = pool.stats();
+        assert!(stats.pooled_bytes <= size * 3);
+        assert_eq!(stats.pooled_buffers, 3);
+        assert_eq!(stats.trimmed, 3);
+        assert_eq!(pool.checkout(5).bytes(), MeshBuffers::with_side(5).bytes());
+        assert_eq!(pool.stats().misses, 7);
+
+        pool.trim_to(0);
+        assert_eq!(pool.stats().pooled_buffers, 0);
+        assert_eq!(pool.stats().pooled_bytes, 0);
+    }
+}
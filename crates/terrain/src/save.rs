@@ -0,0 +1,190 @@
+//! Terrain state in world saves
+//!
+//! Meshes are regenerated from the seed on load; only state the generator
+//! cannot reproduce, such as fear memory, is saved.
+
+use crate::chunk::{ChunkCoord, TerrainMap};
+use crate::memory::FearMemoryConfig;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use thiserror::Error;
+
+/// Current save format version
+pub const SAVE_VERSION: u32 = 1;
+
+/// Save and restore errors
+#[derive(Error, Debug)]
+pub enum SaveError {
+    #[error("Failed to access world save {path}: {message}")]
+    Io { path: String, message: String },
+
+    #[error("Invalid world save: {0}")]
+    Format(String),
+
+    #[error("Unsupported world save version {0}")]
+    UnsupportedVersion(u32),
+}
+
+/// Saved state of one chunk
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChunkSave {
+    pub coord: ChunkCoord,
+    pub fear_memory: f32,
+}
+
+/// Terrain section of a world save
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TerrainSave {
+    pub version: u32,
+    /// Chunks with any fear memory, ordered by coordinate
+    pub chunks: Vec<ChunkSave>,
+}
+
+impl TerrainSave {
+    pub fn to_json(&self) -> Result<String, SaveError> {
+        serde_json::to_string_pretty(self).map_err(|e| SaveError::Format(e.to_string()))
+    }
+
+    pub fn from_json(json: &str) -> Result<Self, SaveError> {
+        let save: Self = serde_json::from_str(json).map_err(|e| SaveError::Format(e.to_string()))?;
+        if save.version > SAVE_VERSION {
+            return Err(SaveError::UnsupportedVersion(save.version));
+        }
+        Ok(save)
+    }
+
+    pub fn write(&self, path: &Path) -> Result<(), SaveError> {
+        std::fs::write(path, self.to_json()?).map_err(|e| io_error(path, e))
+    }
+
+    pub fn read(path: &Path) -> Result<Self, SaveError> {
+        let json = std::fs::read_to_string(path).map_err(|e| io_error(path, e))?;
+        Self::from_json(&json)
+    }
+}
+
+impl TerrainMap {
+    /// State to keep in a world save
+    pub fn to_save(&self) -> TerrainSave {
+        let mut chunks: Vec<ChunkSave> = self
+            .chunks()
+            .filter(|chunk| chunk.fear_memory > 0.0)
+            .map(|chunk| ChunkSave {
+                coord: chunk.coord,
+                fear_memory: chunk.fear_memory,
+            })
+            .collect();
+        chunks.sort_by_key(|chunk| chunk.coord);
+
+        TerrainSave {
+            version: SAVE_VERSION,
+            chunks,
+        }
+    }
+
+    /// Map restored from a world save; meshes are rebuilt with the saved memory
+    pub fn from_save(save: &TerrainSave, memory: FearMemoryConfig) -> Self {
+        let mut map = Self::new(memory);
+        for saved in &save.chunks {
+            let chunk = map.chunk_mut(saved.coord);
+            chunk.fear_memory = saved.fear_memory.clamp(0.0, 1.0);
+            chunk.mark_built();
+        }
+        map
+    }
+}
+
+fn io_error(path: &Path, error: std::io::Error) -> SaveError {
+    SaveError::Io {
+        path: path.display().to_string(),
+        message: error.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generator::TerrainGenerator;
+    use spectremesh_core::FearBucket;
+    use std::time::Duration;
+
+    /// Scripted session: walk in calm, linger in one chunk while terrified, walk on
+    fn scripted_session() -> (TerrainMap, ChunkCoord) {
+        let mut map = TerrainMap::default();
+        let tick = Duration::from_millis(100);
+        let lair = ChunkCoord::new(1, 0, 1);
+
+        for step in 0..100 {
+            let x = step as f32 * 0.3;
+            map.integrate_fear_memory([x, 8.0, 8.0], FearBucket::Low, tick);
+        }
+        for _ in 0..400 {
+            map.integrate_fear_memory(lair.center(), FearBucket::High, tick);
+        }
+        for step in 0..100 {
+            let z = 24.0 + step as f32 * 0.5;
+            map.integrate_fear_memory([24.0, 8.0, z], FearBucket::Medium, tick);
+        }
+        (map, lair)
+    }
+
+    #[test]
+    fn test_scripted_session_scars_the_lair() {
+        let (map, lair) = scripted_session();
+        let generator = TerrainGenerator::default();
+
+        let lair_memory = map.fear_memory(lair);
+        let neighbors = [(1, 0, 0), (-1, 0, 0), (0, 0, 1), (0, 0, -1), (0, 1, 0), (0, -1, 0)]
+            .map(|(dx, dy, dz)| ChunkCoord::new(lair.x + dx, lair.y + dy, lair.z + dz));
+        for neighbor in neighbors {
+            assert!(lair_memory > map.fear_memory(neighbor));
+        }
+
+        // The lair's surface is cut deeper than a neighbor's relative to clean terrain
+        let depth = |coord: ChunkCoord| {
+            let chunk = map.chunk(coord).unwrap();
+            let mut clean = chunk.clone();
+            clean.fear_memory = 0.0;
+            generator
+                .chunk_heights(&clean, 16)
+                .iter()
+                .zip(generator.chunk_heights(chunk, 16))
+                .map(|(clean, scarred)| clean - scarred)
+                .sum::<f32>()
+        };
+        let neighbor = neighbors[0];
+        assert!(depth(lair) > 0.0);
+        assert!(depth(lair) > depth(neighbor));
+    }
+
+    #[test]
+    fn test_memory_survives_save_and_restore() {
+        let (map, lair) = scripted_session();
+        let dir = std::env::temp_dir().join(format!("spectremesh_terrain_save_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("terrain.json");
+
+        let save = map.to_save();
+        assert!(!save.chunks.is_empty());
+        save.write(&path).unwrap();
+
+        let restored = TerrainMap::from_save(&TerrainSave::read(&path).unwrap(), FearMemoryConfig::default());
+        for chunk in map.chunks() {
+            assert_eq!(restored.fear_memory(chunk.coord), chunk.fear_memory);
+        }
+        assert_eq!(restored.chunk(lair).unwrap().scar_blend(), map.chunk(lair).unwrap().scar_blend());
+
+        // Restored meshes already reflect the saved memory
+        let mut restored = restored;
+        assert!(restored.take_memory_rebuilds(Duration::ZERO).is_empty());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_newer_save_version_rejected() {
+        let json = r#"{"version": 99, "chunks": []}"#;
+        assert!(matches!(TerrainSave::from_json(json), Err(SaveError::UnsupportedVersion(99))));
+        assert!(matches!(TerrainSave::from_json("{"), Err(SaveError::Format(_))));
+    }
+}
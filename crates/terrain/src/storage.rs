@@ -0,0 +1,601 @@
+//! On-disk cache of generated chunks
+//!
+//! A [`ChunkStore`] keeps chunk density grids, and optionally their meshes,
+//! in a directory, one file per chunk. Files are keyed by world seed,
+//! [`ChunkCoord`] and a hash of the [`ChunkParams`] they were generated
+//! with, so terrain built at another fear level or with other settings is
+//! never reused. Each file is a small header (magic, format version, CRC-32
+//! of the payload) followed by the chunk in bincode, compressed with zstd.
+//!
+//! [`ChunkStore::load_or_generate`] regenerates a chunk whose file is
+//! missing, corrupt or from another format version, and stores the result.
+//! The directory is bounded in size: storing a chunk evicts the least
+//! recently used files until the total fits again.
+
+use crate::chunk::{ChunkCoord, DensityChunk};
+use crate::generator::DensityMesh;
+use serde::{Deserialize, Serialize};
+use spectremesh_core::TerrainConfig;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::SystemTime;
+use thiserror::Error;
+
+/// Current chunk file format version
+pub const STORE_VERSION: u32 = 1;
+
+/// Default bound on the total size of a store's files
+pub const DEFAULT_MAX_BYTES: u64 = 256 * 1024 * 1024;
+
+/// First bytes of every chunk file
+const MAGIC: [u8; 4] = *b"SMCH";
+/// Magic, version and checksum
+const HEADER_LEN: usize = 12;
+/// Extension of chunk files; anything else in the directory is left alone
+const EXTENSION: &str = "chunk";
+/// zstd level; the library default, quick to write as chunks stream in
+const COMPRESSION_LEVEL: i32 = 3;
+
+/// Chunk store errors
+#[derive(Error, Debug)]
+pub enum StorageError {
+    #[error("Failed to access chunk store {path}: {message}")]
+    Io { path: String, message: String },
+
+    #[error("Invalid chunk file: {0}")]
+    Format(String),
+
+    #[error("Unsupported chunk file version {0}")]
+    UnsupportedVersion(u32),
+
+    #[error("Chunk file checksum mismatch: expected {expected:08x}, found {actual:08x}")]
+    Checksum { expected: u32, actual: u32 },
+}
+
+/// Everything a chunk's density and mesh depend on besides its coordinate
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChunkParams {
+    /// World seed
+    pub seed: u64,
+    /// Samples per axis
+    pub chunk_size: u32,
+    pub base_height: f32,
+    pub fear_multiplier: f32,
+    pub noise_scale: f32,
+    /// Fear level the density was sampled at
+    pub fear: f32,
+    /// Distortion intensity the density was sampled at instead of a fear
+    /// level, with [`NoiseField::sample_at_intensity`](crate::NoiseField::sample_at_intensity)
+    pub distortion_intensity: Option<f32>,
+    /// Fear memory the density was scarred by, with
+    /// [`NoiseField::sample_scarred`](crate::NoiseField::sample_scarred)
+    pub fear_memory: f32,
+    /// Level of detail of the mesh
+    pub lod: u8,
+}
+
+impl ChunkParams {
+    /// Parameters of chunks generated from `config` and `seed`, at fear 0 and full detail
+    pub fn new(config: &TerrainConfig, seed: u64) -> Self {
+        Self {
+            seed,
+            chunk_size: config.chunk_size,
+            base_height: config.base_height,
+            fear_multiplier: config.fear_multiplier,
+            noise_scale: config.noise_scale,
+            fear: 0.0,
+            distortion_intensity: None,
+            fear_memory: 0.0,
+            lod: 0,
+        }
+    }
+
+    /// Set the fear level the density is sampled at
+    pub fn with_fear(mut self, fear: f32) -> Self {
+        self.fear = fear;
+        self
+    }
+
+    /// Set the distortion intensity the density is sampled at, in place of the fear level
+    pub fn with_distortion_intensity(mut self, intensity: f32) -> Self {
+        self.distortion_intensity = Some(intensity);
+        self
+    }
+
+    /// Set the fear memory the density is scarred by
+    pub fn with_fear_memory(mut self, fear_memory: f32) -> Self {
+        self.fear_memory = fear_memory;
+        self
+    }
+
+    /// Set the mesh's level of detail
+    pub fn with_lod(mut self, lod: u8) -> Self {
+        self.lod = lod;
+        self
+    }
+
+    /// Hash of every parameter, stable across runs and platforms (64-bit FNV-1a)
+    pub fn hash(&self) -> u64 {
+        let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+        let mut write = |bytes: &[u8]| {
+            for &byte in bytes {
+                hash = (hash ^ byte as u64).wrapping_mul(0x0100_0000_01b3);
+            }
+        };
+        write(&self.seed.to_le_bytes());
+        write(&self.chunk_size.to_le_bytes());
+        for value in [self.base_height, self.fear_multiplier, self.noise_scale, self.fear] {
+            write(&value.to_bits().to_le_bytes());
+        }
+        if let Some(intensity) = self.distortion_intensity {
+            write(&intensity.to_bits().to_le_bytes());
+        }
+        // Unscarred chunks keep the hash they were stored under before scars
+        if self.fear_memory != 0.0 {
+            write(&self.fear_memory.to_bits().to_le_bytes());
+        }
+        write(&[self.lod]);
+        hash
+    }
+}
+
+/// A chunk as kept in a [`ChunkStore`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct StoredChunk {
+    pub density: DensityChunk,
+    /// Mesh built from `density`, when the caller stores one
+    pub mesh: Option<DensityMesh>,
+}
+
+/// Lookups since the store was opened
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ChunkStoreStats {
+    /// Chunks read back from disk
+    pub hits: u64,
+    /// Chunks generated because none was stored
+    pub misses: u64,
+    /// Chunks regenerated because the stored file was unreadable
+    pub invalid: u64,
+    /// Files removed to stay within the size bound
+    pub evicted: u64,
+}
+
+/// Serialized form of a [`StoredChunk`], with its key to catch misplaced files
+#[derive(Serialize, Deserialize)]
+struct ChunkRecord {
+    seed: u64,
+    coord: ChunkCoord,
+    params_hash: u64,
+    size: u32,
+    densities: Vec<f32>,
+    mesh: Option<DensityMesh>,
+}
+
+/// One file in the store
+struct Entry {
+    bytes: u64,
+    /// Order of last use; higher is more recent
+    last_used: u64,
+}
+
+#[derive(Default)]
+struct Index {
+    entries: HashMap<PathBuf, Entry>,
+    total_bytes: u64,
+    /// Use counter handing out `last_used`
+    clock: u64,
+    stats: ChunkStoreStats,
+}
+
+impl Index {
+    fn touch(&mut self, path: &Path) {
+        self.clock += 1;
+        if let Some(entry) = self.entries.get_mut(path) {
+            entry.last_used = self.clock;
+        }
+    }
+
+    fn insert(&mut self, path: PathBuf, bytes: u64) {
+        self.clock += 1;
+        let entry = Entry { bytes, last_used: self.clock };
+        if let Some(old) = self.entries.insert(path, entry) {
+            self.total_bytes -= old.bytes;
+        }
+        self.total_bytes += bytes;
+    }
+
+    fn remove(&mut self, path: &Path) {
+        if let Some(entry) = self.entries.remove(path) {
+            self.total_bytes -= entry.bytes;
+        }
+    }
+}
+
+/// Size-bounded directory of generated chunks
+///
+/// Safe to share between threads; lookups and stores of different chunks
+/// only contend for the in-memory index.
+pub struct ChunkStore {
+    dir: PathBuf,
+    max_bytes: u64,
+    index: Mutex<Index>,
+}
+
+impl ChunkStore {
+    /// Open the store in `dir`, creating it if needed, keeping its files
+    /// within `max_bytes`
+    ///
+    /// Files already there are ranked for eviction by modification time.
+    pub fn open(dir: impl Into<PathBuf>, max_bytes: u64) -> Result<Self, StorageError> {
+        let dir = dir.into();
+        fs::create_dir_all(&dir).map_err(|e| io_error(&dir, e))?;
+
+        let mut files = Vec::new();
+        for entry in fs::read_dir(&dir).map_err(|e| io_error(&dir, e))? {
+            let entry = entry.map_err(|e| io_error(&dir, e))?;
+            let path = entry.path();
+            if path.extension().is_none_or(|extension| extension != EXTENSION) {
+                continue;
+            }
+            let Ok(metadata) = entry.metadata() else {
+                continue;
+            };
+            let modified = metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH);
+            files.push((modified, path, metadata.len()));
+        }
+        files.sort();
+
+        let mut index = Index::default();
+        for (_, path, bytes) in files {
+            index.insert(path, bytes);
+        }
+        let store = Self { dir, max_bytes, index: Mutex::new(index) };
+        store.evict(&mut store.index.lock().unwrap(), None);
+        Ok(store)
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    pub fn max_bytes(&self) -> u64 {
+        self.max_bytes
+    }
+
+    /// Chunk files in the store
+    pub fn len(&self) -> usize {
+        self.index.lock().unwrap().entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Total size of the store's files
+    pub fn total_bytes(&self) -> u64 {
+        self.index.lock().unwrap().total_bytes
+    }
+
+    pub fn stats(&self) -> ChunkStoreStats {
+        self.index.lock().unwrap().stats
+    }
+
+    /// File the chunk at `coord` generated with `params` is kept in
+    pub fn path(&self, coord: ChunkCoord, params: &ChunkParams) -> PathBuf {
+        self.dir.join(format!(
+            "{:016x}_{}_{}_{}_{:016x}.{}",
+            params.seed,
+            coord.x,
+            coord.y,
+            coord.z,
+            params.hash(),
+            EXTENSION
+        ))
+    }
+
+    /// The stored chunk at `coord` generated with `params`, if any
+    pub fn load(&self, coord: ChunkCoord, params: &ChunkParams) -> Result<Option<StoredChunk>, StorageError> {
+        let path = self.path(coord, params);
+        let bytes = match fs::read(&path) {
+            Ok(bytes) => bytes,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(io_error(&path, e)),
+        };
+        let chunk = decode(&bytes, coord, params)?;
+
+        self.index.lock().unwrap().touch(&path);
+        // Keep the order of use for the next session; a failure only costs ranking
+        if let Ok(file) = fs::File::options().write(true).open(&path) {
+            let _ = file.set_modified(SystemTime::now());
+        }
+        Ok(Some(chunk))
+    }
+
+    /// Keep `chunk`, generated at `coord` with `params`, evicting the least
+    /// recently used files if the store grows past its bound
+    pub fn store(&self, coord: ChunkCoord, params: &ChunkParams, chunk: &StoredChunk) -> Result<(), StorageError> {
+        let bytes = encode(coord, params, chunk)?;
+        let path = self.path(coord, params);
+        // Write aside and rename, so a reader never sees half a file
+        let partial = path.with_extension("partial");
+        fs::write(&partial, &bytes).map_err(|e| io_error(&partial, e))?;
+        fs::rename(&partial, &path).map_err(|e| io_error(&path, e))?;
+
+        let mut index = self.index.lock().unwrap();
+        index.insert(path.clone(), bytes.len() as u64);
+        self.evict(&mut index, Some(&path));
+        Ok(())
+    }
+
+    /// The stored chunk at `coord` generated with `params`, or the one
+    /// `generate` builds when none is stored or the stored one is unreadable
+    ///
+    /// A generated chunk is stored for next time. Storage failures are
+    /// logged and never keep the chunk from the caller.
+    pub fn load_or_generate(
+        &self,
+        coord: ChunkCoord,
+        params: &ChunkParams,
+        generate: impl FnOnce() -> StoredChunk,
+    ) -> StoredChunk {
+        match self.load(coord, params) {
+            Ok(Some(chunk)) => {
+                self.index.lock().unwrap().stats.hits += 1;
+                return chunk;
+            }
+            Ok(None) => self.index.lock().unwrap().stats.misses += 1,
+            Err(e) => {
+                tracing::warn!("Regenerating chunk {:?}: {}", coord, e);
+                self.index.lock().unwrap().stats.invalid += 1;
+            }
+        }
+
+        let chunk = generate();
+        if let Err(e) = self.store(coord, params, &chunk) {
+            tracing::warn!("Failed to store chunk {:?}: {}", coord, e);
+        }
+        chunk
+    }
+
+    /// Remove least recently used files until the store fits, sparing `keep`
+    fn evict(&self, index: &mut Index, keep: Option<&Path>) {
+        while index.total_bytes > self.max_bytes {
+            let oldest = index
+                .entries
+                .iter()
+                .filter(|(path, _)| Some(path.as_path()) != keep)
+                .min_by_key(|(_, entry)| entry.last_used)
+                .map(|(path, _)| path.clone());
+            let Some(path) = oldest else {
+                break;
+            };
+            if let Err(e) = fs::remove_file(&path) {
+                if e.kind() != std::io::ErrorKind::NotFound {
+                    tracing::warn!("Failed to evict {}: {}", path.display(), e);
+                }
+            }
+            index.remove(&path);
+            index.stats.evicted += 1;
+        }
+    }
+}
+
+/// Chunk file contents for `chunk`
+fn encode(coord: ChunkCoord, params: &ChunkParams, chunk: &StoredChunk) -> Result<Vec<u8>, StorageError> {
+    let record = ChunkRecord {
+        seed: params.seed,
+        coord,
+        params_hash: params.hash(),
+        size: chunk.density.size(),
+        densities: chunk.density.densities().to_vec(),
+        mesh: chunk.mesh.clone(),
+    };
+    let serialized = bincode::serialize(&record).map_err(|e| StorageError::Format(e.to_string()))?;
+    let payload =
+        zstd::encode_all(serialized.as_slice(), COMPRESSION_LEVEL).map_err(|e| StorageError::Format(e.to_string()))?;
+
+    let mut bytes = Vec::with_capacity(HEADER_LEN + payload.len());
+    bytes.extend_from_slice(&MAGIC);
+    bytes.extend_from_slice(&STORE_VERSION.to_le_bytes());
+    bytes.extend_from_slice(&crc32fast::hash(&payload).to_le_bytes());
+    bytes.extend_from_slice(&payload);
+    Ok(bytes)
+}
+
+/// Chunk read back from file contents, checked against the key it was looked up by
+fn decode(bytes: &[u8], coord: ChunkCoord, params: &ChunkParams) -> Result<StoredChunk, StorageError> {
+    if bytes.len() < HEADER_LEN || bytes[..4] != MAGIC {
+        return Err(StorageError::Format("not a chunk file".to_string()));
+    }
+    let word = |at: usize| u32::from_le_bytes(bytes[at..at + 4].try_into().unwrap());
+    let version = word(4);
+    if version != STORE_VERSION {
+        return Err(StorageError::UnsupportedVersion(version));
+    }
+    let payload = &bytes[HEADER_LEN..];
+    let (expected, actual) = (word(8), crc32fast::hash(payload));
+    if expected != actual {
+        return Err(StorageError::Checksum { expected, actual });
+    }
+
+    let serialized = zstd::decode_all(payload).map_err(|e| StorageError::Format(e.to_string()))?;
+    let record: ChunkRecord = bincode::deserialize(&serialized).map_err(|e| StorageError::Format(e.to_string()))?;
+    if record.seed != params.seed || record.coord != coord || record.params_hash != params.hash() {
+        return Err(StorageError::Format(format!("file holds chunk {:?} of another world or parameters", record.coord)));
+    }
+    let density = DensityChunk::from_densities(coord, record.size, record.densities)
+        .ok_or_else(|| StorageError::Format(format!("sample count does not match size {}", record.size)))?;
+    Ok(StoredChunk { density, mesh: record.mesh })
+}
+
+fn io_error(path: &Path, error: std::io::Error) -> StorageError {
+    StorageError::Io {
+        path: path.display().to_string(),
+        message: error.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generator::MarchingCubesGenerator;
+    use crate::noise::NoiseField;
+    use std::cell::Cell;
+
+    const SEED: u64 = 42;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("spectremesh_chunk_store_{}_{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    fn config() -> TerrainConfig {
+        TerrainConfig { chunk_size: 8, base_height: 4.0, ..Default::default() }
+    }
+
+    /// Chunk and mesh sampled from the noise field the way the game builds them
+    fn generate(coord: ChunkCoord, params: &ChunkParams) -> StoredChunk {
+        let field = NoiseField::new(&config(), params.seed);
+        let density = |[x, y, z]: [f32; 3]| field.sample(x, y, z, params.fear);
+        let chunk = DensityChunk::from_fn(coord, params.chunk_size, density);
+        let mesh = MarchingCubesGenerator::default().generate_bordered_with_lod(&chunk, params.lod, density);
+        StoredChunk { density: chunk, mesh: Some(mesh) }
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let dir = temp_dir("round_trip");
+        let store = ChunkStore::open(&dir, DEFAULT_MAX_BYTES).unwrap();
+        let params = ChunkParams::new(&config(), SEED).with_fear(0.7).with_lod(1);
+        let coord = ChunkCoord::new(-1, 0, 2);
+        let chunk = generate(coord, &params);
+        assert!(!chunk.mesh.as_ref().unwrap().is_empty());
+
+        assert_eq!(store.load(coord, &params).unwrap(), None);
+        store.store(coord, &params, &chunk).unwrap();
+        assert_eq!(store.load(coord, &params).unwrap(), Some(chunk.clone()));
+        assert_eq!(store.len(), 1);
+
+        // Another session finds the chunk, and the generator is not called
+        let reopened = ChunkStore::open(&dir, DEFAULT_MAX_BYTES).unwrap();
+        let loaded = reopened.load_or_generate(coord, &params, || unreachable!());
+        assert_eq!(loaded, chunk);
+        assert_eq!(reopened.stats(), ChunkStoreStats { hits: 1, ..Default::default() });
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_corrupt_file_is_regenerated() {
+        let dir = temp_dir("corrupt");
+        let store = ChunkStore::open(&dir, DEFAULT_MAX_BYTES).unwrap();
+        let params = ChunkParams::new(&config(), SEED);
+        let coord = ChunkCoord::new(0, 0, 0);
+        let chunk = store.load_or_generate(coord, &params, || generate(coord, &params));
+
+        let path = store.path(coord, &params);
+        let mut bytes = fs::read(&path).unwrap();
+        let middle = bytes.len() / 2;
+        bytes[middle] ^= 0x5a;
+        fs::write(&path, &bytes).unwrap();
+        assert!(matches!(store.load(coord, &params), Err(StorageError::Checksum { .. })));
+
+        let generated = Cell::new(0);
+        let regenerated = store.load_or_generate(coord, &params, || {
+            generated.set(generated.get() + 1);
+            generate(coord, &params)
+        });
+        assert_eq!(regenerated, chunk);
+        assert_eq!(generated.get(), 1);
+        assert_eq!(store.stats().invalid, 1);
+
+        // The rewritten file reads back
+        assert_eq!(store.load(coord, &params).unwrap(), Some(chunk));
+
+        // Files from another format version are regenerated too
+        let mut bytes = fs::read(&path).unwrap();
+        bytes[4..8].copy_from_slice(&(STORE_VERSION + 1).to_le_bytes());
+        fs::write(&path, &bytes).unwrap();
+        assert!(matches!(store.load(coord, &params), Err(StorageError::UnsupportedVersion(_))));
+        fs::write(&path, b"SMCH").unwrap();
+        assert!(matches!(store.load(coord, &params), Err(StorageError::Format(_))));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_params_hash_tracks_generation_settings() {
+        let params = ChunkParams::new(&config(), SEED);
+        let hash = params.hash();
+        assert_eq!(ChunkParams::new(&config(), SEED).hash(), hash);
+        assert_eq!(params.clone().with_fear_memory(0.0).hash(), hash);
+
+        let stronger = TerrainConfig { fear_multiplier: config().fear_multiplier * 2.0, ..config() };
+        let variants = [
+            ChunkParams::new(&stronger, SEED),
+            ChunkParams::new(&config(), SEED + 1),
+            params.clone().with_fear(0.5),
+            params.clone().with_distortion_intensity(0.0),
+            params.clone().with_distortion_intensity(0.5),
+            params.clone().with_fear_memory(0.25),
+            params.clone().with_lod(1),
+        ];
+        for variant in &variants {
+            assert_ne!(variant.hash(), hash, "{:?}", variant);
+        }
+
+        // Terrain cached with the old fear multiplier is not reused
+        let dir = temp_dir("params");
+        let store = ChunkStore::open(&dir, DEFAULT_MAX_BYTES).unwrap();
+        let coord = ChunkCoord::new(3, 0, 3);
+        let calm = params.clone().with_fear(1.0);
+        store.load_or_generate(coord, &calm, || generate(coord, &calm));
+        let warped = ChunkParams::new(&stronger, SEED).with_fear(1.0);
+        let generated = Cell::new(false);
+        store.load_or_generate(coord, &warped, || {
+            generated.set(true);
+            generate(coord, &warped)
+        });
+        assert!(generated.get());
+        assert_eq!(store.stats().misses, 2);
+        assert_eq!(store.len(), 2);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_least_recently_used_evicted() {
+        let dir = temp_dir("evict");
+        let params = ChunkParams::new(&config(), SEED);
+        let coords = [0, 1, 2, 3].map(|x| ChunkCoord::new(x, 0, 0));
+        let chunks = coords.map(|coord| generate(coord, &params));
+
+        // Room for the first three chunks, or for all but the second
+        let probe = ChunkStore::open(dir.join("probe"), DEFAULT_MAX_BYTES).unwrap();
+        let sizes = coords.map(|coord| {
+            probe.store(coord, &params, &chunks[coord.x as usize]).unwrap();
+            fs::metadata(probe.path(coord, &params)).unwrap().len()
+        });
+        let bound = (sizes[0] + sizes[1] + sizes[2]).max(sizes[0] + sizes[2] + sizes[3]);
+        let store = ChunkStore::open(dir.join("store"), bound).unwrap();
+
+        for (coord, chunk) in coords.iter().zip(&chunks).take(3) {
+            store.store(*coord, &params, chunk).unwrap();
+        }
+        // Using the first chunk leaves the second the least recently used
+        assert!(store.load(coords[0], &params).unwrap().is_some());
+        store.store(coords[3], &params, &chunks[3]).unwrap();
+
+        assert!(store.total_bytes() <= store.max_bytes());
+        assert_eq!(store.stats().evicted, 1);
+        assert_eq!(store.load(coords[1], &params).unwrap(), None);
+        for index in [0, 2, 3] {
+            assert_eq!(store.load(coords[index], &params).unwrap().as_ref(), Some(&chunks[index]));
+        }
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,75 @@
+//! Calibration control: wait for a baseline, freeze it, reset it and wait again
+//!
+//! ```text
+//! cargo run -p spectre-sensor --example calibration --features mock -- --mock
+//! ```
+
+use clap::Parser;
+use spectre_sensor::{
+    grpc_client::{calibration_rejection, SensorClient},
+    mock::{spawn_mock_daemon, MockSensorConfig},
+    phases::PhaseOutcome,
+    SensorConfig, SensorTransport,
+};
+use std::time::Duration;
+
+#[derive(Parser, Debug)]
+pub struct Args {
+    /// Serve a mock sensor in this process instead of connecting to the daemon
+    #[arg(long)]
+    pub mock: bool,
+
+    /// Daemon address: socket path, pipe:<name> or host:port (defaults to SPECTRE_GRPC_SOCKET)
+    #[arg(long)]
+    pub address: Option<String>,
+
+    /// Seconds to wait for each calibration
+    #[arg(long, default_value = "30")]
+    pub duration: f64,
+}
+
+pub async fn run(args: Args) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let transport: SensorTransport = match (args.mock, &args.address) {
+        (true, _) => spawn_mock_daemon(MockSensorConfig::default()).await?,
+        (false, Some(address)) => address.parse()?,
+        (false, None) => SensorTransport::from_config(&SensorConfig::from_env())?,
+    };
+    let mut client = SensorClient::connect(&transport).await?;
+    let timeout = Duration::from_secs_f64(args.duration);
+
+    let mut phases = client.phases().await?;
+    match phases.wait_for_calibrated(timeout).await? {
+        PhaseOutcome::Reached(phase) if phase.is_calibrated() => println!("baseline ready: {:?}", phase),
+        outcome => return Err(format!("no baseline: {:?}", outcome).into()),
+    }
+
+    // Freeze the baseline for a scripted scare so it cannot drift
+    client.freeze_calibration().await?;
+    println!("frozen");
+
+    // Illegal controls are rejected with the phase that refused them
+    match client.reset_calibration().await {
+        Err(status) => match calibration_rejection(&status) {
+            Some((phase, action)) => println!("{} refused while {}", action, phase),
+            None => return Err(status.into()),
+        },
+        Ok(_) => println!("reset accepted while frozen"),
+    }
+
+    client.unfreeze_calibration().await?;
+    client.reset_calibration().await?;
+    println!("reset: keep a neutral face");
+
+    // A fresh stream starts from the current phase, past the old baseline
+    let mut phases = client.phases().await?;
+    match phases.wait_for_calibrated(timeout).await? {
+        PhaseOutcome::Reached(phase) if phase.is_calibrated() => println!("recalibrated: {:?}", phase),
+        outcome => return Err(format!("no baseline after reset: {:?}", outcome).into()),
+    }
+    Ok(())
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    run(Args::parse()).await
+}
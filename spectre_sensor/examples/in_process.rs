@@ -0,0 +1,71 @@
+//! In-process sensor read through a polling handle
+//!
+//! The sensor runs on a background task and a game loop polls the newest
+//! frame once per tick, never waiting on the camera.
+//!
+//! ```text
+//! cargo run -p spectre-sensor --example in_process --features mock -- --mock
+//! cargo run -p spectre-sensor --example in_process --features mock   # camera
+//! ```
+
+use clap::Parser;
+use spectre_sensor::{
+    mock::{MockEmotionSensor, MockSensorConfig},
+    EmotionSensor, FearBucket, FearFrame, FrameFanout, SensorConfig,
+};
+use std::time::{Duration, Instant};
+
+#[derive(Parser, Debug)]
+pub struct Args {
+    /// Play a mock pattern instead of opening the camera
+    #[arg(long)]
+    pub mock: bool,
+
+    /// Seconds to run for
+    #[arg(long, default_value = "30")]
+    pub duration: f64,
+}
+
+pub async fn run(args: Args) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    // Both sensors hand out a channel of frames; dropping it stops them
+    let frames = if args.mock {
+        MockEmotionSensor::new(MockSensorConfig::default()).start().await?
+    } else {
+        let mut sensor = EmotionSensor::new(SensorConfig::from_env());
+        sensor.initialize().await?;
+        sensor.start().await?
+    };
+
+    // Each system that wants frames polls its own subscriber
+    let fanout = FrameFanout::new(frames);
+    let mut handle = fanout.subscribe();
+    let mut latest: Option<FearFrame> = None;
+
+    let deadline = Instant::now() + Duration::from_secs_f64(args.duration);
+    let mut game_tick = tokio::time::interval(Duration::from_millis(250));
+    while Instant::now() < deadline {
+        game_tick.tick().await;
+
+        // Take everything that arrived since the last tick, keep the newest
+        while let Some(frame) = handle.try_recv() {
+            latest = Some(frame);
+        }
+
+        match &latest {
+            Some(frame) if frame.calibrated => println!(
+                "fear {:.2} ({:?}), confidence {:.2}",
+                frame.fear_score,
+                FearBucket::from_score(frame.fear_score),
+                frame.confidence
+            ),
+            Some(_) => println!("calibrating: keep a neutral face"),
+            None => println!("waiting for the first frame"),
+        }
+    }
+    Ok(())
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    run(Args::parse()).await
+}
@@ -0,0 +1,63 @@
+//! Single-shot measurement: one scored frame on demand, no stream
+//!
+//! ```text
+//! cargo run -p spectre-sensor --example measure --features mock -- --mock
+//! ```
+
+use clap::Parser;
+use spectre_sensor::{
+    grpc_client::SensorClient,
+    mock::{spawn_mock_daemon, MockSensorConfig},
+    FearBucket, SensorConfig, SensorTransport,
+};
+use std::time::Duration;
+use tonic::Code;
+
+#[derive(Parser, Debug)]
+pub struct Args {
+    /// Serve a mock sensor in this process instead of connecting to the daemon
+    #[arg(long)]
+    pub mock: bool,
+
+    /// Daemon address: socket path, pipe:<name> or host:port (defaults to SPECTRE_GRPC_SOCKET)
+    #[arg(long)]
+    pub address: Option<String>,
+
+    /// Seconds to wait for a face
+    #[arg(long, default_value = "5")]
+    pub duration: f64,
+}
+
+pub async fn run(args: Args) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let transport: SensorTransport = match (args.mock, &args.address) {
+        (true, _) => spawn_mock_daemon(MockSensorConfig::default()).await?,
+        (false, Some(address)) => address.parse()?,
+        (false, None) => SensorTransport::from_config(&SensorConfig::from_env())?,
+    };
+    let mut client = SensorClient::connect(&transport).await?;
+
+    match client.measure_once(Duration::from_secs_f64(args.duration)).await {
+        Ok(response) => {
+            let score = response.score.ok_or("measurement carried no score")?;
+            println!(
+                "fear {:.2} ({:?}), confidence {:.2}{}",
+                score.normalized_fear,
+                FearBucket::from_score(score.normalized_fear),
+                score.confidence,
+                if score.calibrated { "" } else { ", not calibrated yet" }
+            );
+            Ok(())
+        }
+        // No face in time is an answer, not a failure
+        Err(status) if status.code() == Code::DeadlineExceeded => {
+            println!("no face within {:.1}s", args.duration);
+            Ok(())
+        }
+        Err(status) => Err(status.into()),
+    }
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    run(Args::parse()).await
+}
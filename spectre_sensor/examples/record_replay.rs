@@ -0,0 +1,85 @@
+//! Record a session to JSONL with markers, then replay it at its own pace
+//!
+//! ```text
+//! cargo run -p spectre-sensor --example record_replay --features mock -- --mock --output session.jsonl
+//! ```
+
+use clap::Parser;
+use futures::StreamExt;
+use spectre_sensor::{
+    grpc_client::SensorClient,
+    mock::{spawn_mock_daemon, MockSensorConfig},
+    session_diff::{SessionTrace, TraceEvent},
+    SensorConfig, SensorTransport,
+};
+use std::{io::Write, path::PathBuf, time::Duration};
+
+#[derive(Parser, Debug)]
+pub struct Args {
+    /// Serve a mock sensor in this process instead of connecting to the daemon
+    #[arg(long)]
+    pub mock: bool,
+
+    /// Daemon address: socket path, pipe:<name> or host:port (defaults to SPECTRE_GRPC_SOCKET)
+    #[arg(long)]
+    pub address: Option<String>,
+
+    /// Seconds to record for
+    #[arg(long, default_value = "30")]
+    pub duration: f64,
+
+    /// Trace file to write
+    #[arg(long, default_value = "session.jsonl")]
+    pub output: PathBuf,
+
+    /// Replay speed multiplier
+    #[arg(long, default_value = "4")]
+    pub speed: f64,
+}
+
+pub async fn run(args: Args) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let transport: SensorTransport = match (args.mock, &args.address) {
+        (true, _) => spawn_mock_daemon(MockSensorConfig::default()).await?,
+        (false, Some(address)) => address.parse()?,
+        (false, None) => SensorTransport::from_config(&SensorConfig::from_env())?,
+    };
+    let mut client = SensorClient::connect(&transport).await?;
+    let mut events = Box::pin(client.stream_events().await?);
+
+    // The game drops a marker each second on its own connection
+    let mut marker_client = client.clone();
+    let markers = tokio::spawn(async move {
+        for second in 1u32.. {
+            tokio::time::sleep(Duration::from_secs(1)).await;
+            if marker_client.insert_marker(format!("beat-{}", second)).await.is_err() {
+                break;
+            }
+        }
+    });
+
+    let mut file = std::io::BufWriter::new(std::fs::File::create(&args.output)?);
+    let deadline = tokio::time::Instant::now() + Duration::from_secs_f64(args.duration);
+    while let Ok(Some(event)) = tokio::time::timeout_at(deadline, events.next()).await {
+        if let Some(line) = TraceEvent::from_sensor_event(&event?) {
+            writeln!(file, "{}", serde_json::to_string(&line)?)?;
+        }
+    }
+    markers.abort();
+    file.flush()?;
+
+    let trace = SessionTrace::load(&args.output)?;
+    println!("recorded {} scores, {} markers", trace.scores.len(), trace.markers.len());
+    let mut previous = 0.0;
+    for &(time, fear) in &trace.scores {
+        tokio::time::sleep(Duration::from_secs_f64((time - previous).max(0.0) / args.speed)).await;
+        previous = time;
+        let label = trace.markers.iter().rev().find(|(at, _)| *at <= time).map(|(_, label)| label.as_str());
+        println!("{:7.2}s  fear {:.2}  {}", time, fear, label.unwrap_or(""));
+    }
+    Ok(())
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    run(Args::parse()).await
+}
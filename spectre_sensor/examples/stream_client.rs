@@ -0,0 +1,72 @@
+//! gRPC streaming client with a callback on every fear bucket change
+//!
+//! ```text
+//! cargo run -p spectre-sensor --example stream_client --features mock -- --mock
+//! cargo run -p spectre-sensor --example stream_client --features mock -- --address /tmp/spectre_sensor.sock
+//! ```
+
+use clap::Parser;
+use futures::StreamExt;
+use spectre_sensor::{
+    grpc_client::{extract_scores, SensorClient},
+    mock::{spawn_mock_daemon, MockSensorConfig},
+    FearBucket, SensorConfig, SensorTransport,
+};
+use std::time::Duration;
+
+#[derive(Parser, Debug)]
+pub struct Args {
+    /// Serve a mock sensor in this process instead of connecting to the daemon
+    #[arg(long)]
+    pub mock: bool,
+
+    /// Daemon address: socket path, pipe:<name> or host:port (defaults to SPECTRE_GRPC_SOCKET)
+    #[arg(long)]
+    pub address: Option<String>,
+
+    /// Seconds to stream for
+    #[arg(long, default_value = "30")]
+    pub duration: f64,
+}
+
+pub async fn run(args: Args) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let transport: SensorTransport = match (args.mock, &args.address) {
+        (true, _) => spawn_mock_daemon(MockSensorConfig::default()).await?,
+        (false, Some(address)) => address.parse()?,
+        (false, None) => SensorTransport::from_config(&SensorConfig::from_env())?,
+    };
+    let mut client = SensorClient::connect(&transport).await?;
+    let mut scores = Box::pin(extract_scores(client.stream_scores().await?));
+
+    let mut bucket: Option<FearBucket> = None;
+    let on_change = |from: Option<FearBucket>, to: FearBucket, fear: f32| {
+        println!("fear bucket {:?} -> {:?} at {:.2}", from, to, fear);
+    };
+
+    let deadline = tokio::time::Instant::now() + Duration::from_secs_f64(args.duration);
+    loop {
+        let score = match tokio::time::timeout_at(deadline, scores.next()).await {
+            Ok(Some(score)) => score?,
+            Ok(None) => {
+                println!("sensor stopped");
+                break;
+            }
+            Err(_) => break,
+        };
+        // Scores before calibration completes are not comparable yet
+        if !score.calibrated {
+            continue;
+        }
+        let current = FearBucket::from_score(score.normalized_fear);
+        if bucket != Some(current) {
+            on_change(bucket, current, score.normalized_fear);
+            bucket = Some(current);
+        }
+    }
+    Ok(())
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    run(Args::parse()).await
+}
@@ -0,0 +1,618 @@
+//! Resolution-independent frame annotations
+//!
+//! Overlays are described in normalized [0, 1] frame coordinates and rendered
+//! onto a Mat with a single [`AnnotationStyle`], so the same annotation looks
+//! the same on a 320x240 thumbnail and a 1920x1080 capture.
+
+use opencv::{
+    core::{self, Mat, Point, Rect, Scalar, Size},
+    imgproc,
+    prelude::*,
+};
+use crate::yunet::FaceDetection;
+
+/// Rectangle in normalized frame coordinates [0.0, 1.0]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct NormalizedRect {
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+}
+
+impl NormalizedRect {
+    /// Create a new normalized rectangle
+    pub fn new(x: f32, y: f32, width: f32, height: f32) -> Self {
+        Self { x, y, width, height }
+    }
+
+    /// Create a rectangle of the given size centered on (cx, cy)
+    pub fn centered(cx: f32, cy: f32, width: f32, height: f32) -> Self {
+        Self::new(cx - width / 2.0, cy - height / 2.0, width, height)
+    }
+
+    /// Normalize a pixel rectangle against the frame it was detected in
+    pub fn from_pixels(rect: Rect, frame_size: Size) -> Self {
+        let w = frame_size.width.max(1) as f32;
+        let h = frame_size.height.max(1) as f32;
+        Self::new(
+            rect.x as f32 / w,
+            rect.y as f32 / h,
+            rect.width as f32 / w,
+            rect.height as f32 / h,
+        )
+    }
+
+    /// Convert to a pixel rectangle for a frame of the given size
+    pub fn to_pixels(&self, frame_size: Size) -> Rect {
+        let w = frame_size.width as f32;
+        let h = frame_size.height as f32;
+        let x1 = (self.x * w).round() as i32;
+        let y1 = (self.y * h).round() as i32;
+        let x2 = ((self.x + self.width) * w).round() as i32;
+        let y2 = ((self.y + self.height) * h).round() as i32;
+        Rect::new(x1, y1, x2 - x1, y2 - y1)
+    }
+}
+
+/// Where a text block is placed within the frame
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Anchor {
+    TopLeft,
+    TopCenter,
+    TopRight,
+    Center,
+    BottomLeft,
+    BottomCenter,
+    BottomRight,
+}
+
+/// Shared colors and sizes for every annotation
+///
+/// Sizes are expressed for a frame of `reference_height` pixels and scaled
+/// linearly to the actual frame height when rendering.
+#[derive(Debug, Clone)]
+pub struct AnnotationStyle {
+    /// Face box color when confidence meets `confidence_threshold`
+    pub face_color: Scalar,
+    /// Face box color when confidence is below `confidence_threshold`
+    pub weak_face_color: Scalar,
+    /// Guide box color
+    pub guide_color: Scalar,
+    /// Landmark dot color
+    pub landmark_color: Scalar,
+    /// Default text color
+    pub text_color: Scalar,
+    /// Progress bar track color
+    pub progress_track_color: Scalar,
+    /// Progress bar fill color
+    pub progress_fill_color: Scalar,
+    /// Font scale for regular text
+    pub font_scale: f64,
+    /// Font scale for captions and box labels
+    pub caption_scale: f64,
+    /// Stroke thickness for face boxes and regular text
+    pub thickness: i32,
+    /// Landmark dot radius
+    pub landmark_radius: i32,
+    /// Margin between anchored text and the frame edge
+    pub margin: i32,
+    /// Confidence at which a face box is drawn with `face_color`
+    pub confidence_threshold: f32,
+    /// Frame height the sizes above are tuned for
+    pub reference_height: i32,
+}
+
+impl Default for AnnotationStyle {
+    fn default() -> Self {
+        Self {
+            face_color: Scalar::new(0.0, 255.0, 0.0, 0.0),        // Green
+            weak_face_color: Scalar::new(0.0, 0.0, 255.0, 0.0),   // Red
+            guide_color: Scalar::new(0.0, 255.0, 255.0, 0.0),     // Yellow
+            landmark_color: Scalar::new(255.0, 0.0, 255.0, 0.0),  // Magenta
+            text_color: Scalar::new(255.0, 255.0, 255.0, 0.0),    // White
+            progress_track_color: Scalar::new(64.0, 64.0, 64.0, 0.0),
+            progress_fill_color: Scalar::new(0.0, 200.0, 255.0, 0.0),
+            font_scale: 0.7,
+            caption_scale: 0.5,
+            thickness: 2,
+            landmark_radius: 3,
+            margin: 10,
+            confidence_threshold: 0.6,
+            reference_height: 480,
+        }
+    }
+}
+
+impl AnnotationStyle {
+    /// Scale factor for a frame of the given size
+    fn scale(&self, frame_size: Size) -> f64 {
+        frame_size.height as f64 / self.reference_height.max(1) as f64
+    }
+
+    /// Scale a pixel length, never going below one pixel
+    fn scaled(&self, value: i32, frame_size: Size) -> i32 {
+        ((value as f64 * self.scale(frame_size)).round() as i32).max(1)
+    }
+}
+
+/// A single drawable element
+#[derive(Debug, Clone)]
+enum Element {
+    FaceBox { rect: NormalizedRect, confidence: f32 },
+    GuideBox { rect: NormalizedRect, label: Option<String> },
+    Landmarks { points: Vec<(f32, f32)> },
+    Text { anchor: Anchor, lines: Vec<String>, color: Option<Scalar>, caption: bool },
+    ProgressBar { rect: NormalizedRect, progress: f32 },
+}
+
+/// Builder for a set of overlays rendered with a shared style
+#[derive(Debug, Clone, Default)]
+pub struct FrameAnnotation {
+    style: AnnotationStyle,
+    elements: Vec<Element>,
+}
+
+impl FrameAnnotation {
+    /// Create an empty annotation with the default style
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create an empty annotation with a custom style
+    pub fn with_style(style: AnnotationStyle) -> Self {
+        Self {
+            style,
+            elements: Vec::new(),
+        }
+    }
+
+    /// Get the style used for rendering
+    pub fn style(&self) -> &AnnotationStyle {
+        &self.style
+    }
+
+    /// Add a face box labelled with its confidence
+    pub fn face_box(mut self, rect: NormalizedRect, confidence: f32) -> Self {
+        self.elements.push(Element::FaceBox { rect, confidence });
+        self
+    }
+
+    /// Add a face box, its confidence, and its landmarks from a YuNet detection
+    pub fn face_detection(self, detection: &FaceDetection, frame_size: Size) -> Self {
+        let w = frame_size.width.max(1) as f32;
+        let h = frame_size.height.max(1) as f32;
+        let points: Vec<(f32, f32)> = detection
+            .landmarks
+            .iter()
+            .map(|p| (p.x as f32 / w, p.y as f32 / h))
+            .collect();
+
+        self.face_box(NormalizedRect::from_pixels(detection.bbox, frame_size), detection.confidence)
+            .landmarks(points)
+    }
+
+    /// Add a positioning guide box with an optional label above it
+    pub fn guide_box(mut self, rect: NormalizedRect, label: Option<&str>) -> Self {
+        self.elements.push(Element::GuideBox {
+            rect,
+            label: label.map(str::to_string),
+        });
+        self
+    }
+
+    /// Add landmark dots at normalized positions
+    pub fn landmarks(mut self, points: impl IntoIterator<Item = (f32, f32)>) -> Self {
+        self.elements.push(Element::Landmarks {
+            points: points.into_iter().collect(),
+        });
+        self
+    }
+
+    /// Add a text block in the default text color
+    pub fn text(mut self, anchor: Anchor, text: impl Into<String>) -> Self {
+        self.elements.push(Element::Text {
+            anchor,
+            lines: split_lines(text.into()),
+            color: None,
+            caption: false,
+        });
+        self
+    }
+
+    /// Add a text block in a specific color
+    pub fn text_colored(mut self, anchor: Anchor, text: impl Into<String>, color: Scalar) -> Self {
+        self.elements.push(Element::Text {
+            anchor,
+            lines: split_lines(text.into()),
+            color: Some(color),
+            caption: false,
+        });
+        self
+    }
+
+    /// Add a small caption text block
+    pub fn caption(mut self, anchor: Anchor, text: impl Into<String>) -> Self {
+        self.elements.push(Element::Text {
+            anchor,
+            lines: split_lines(text.into()),
+            color: None,
+            caption: true,
+        });
+        self
+    }
+
+    /// Add a progress bar filled to `progress` [0.0, 1.0]
+    pub fn progress_bar(mut self, rect: NormalizedRect, progress: f32) -> Self {
+        self.elements.push(Element::ProgressBar { rect, progress });
+        self
+    }
+
+    /// Render all elements onto the frame in insertion order
+    pub fn render(&self, frame: &mut Mat) -> opencv::Result<()> {
+        let size = frame.size()?;
+        if size.width <= 0 || size.height <= 0 {
+            return Ok(());
+        }
+
+        for element in &self.elements {
+            match element {
+                Element::FaceBox { rect, confidence } => {
+                    self.draw_face_box(frame, size, rect, *confidence)?;
+                }
+                Element::GuideBox { rect, label } => {
+                    self.draw_guide_box(frame, size, rect, label.as_deref())?;
+                }
+                Element::Landmarks { points } => {
+                    self.draw_landmarks(frame, size, points)?;
+                }
+                Element::Text { anchor, lines, color, caption } => {
+                    self.draw_text(frame, size, *anchor, lines, *color, *caption)?;
+                }
+                Element::ProgressBar { rect, progress } => {
+                    self.draw_progress_bar(frame, size, rect, *progress)?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn draw_face_box(
+        &self,
+        frame: &mut Mat,
+        size: Size,
+        rect: &NormalizedRect,
+        confidence: f32,
+    ) -> opencv::Result<()> {
+        let style = &self.style;
+        let color = if confidence >= style.confidence_threshold {
+            style.face_color
+        } else {
+            style.weak_face_color
+        };
+        let pixels = rect.to_pixels(size);
+        let thickness = style.scaled(style.thickness, size);
+
+        imgproc::rectangle(frame, pixels, color, thickness, imgproc::LINE_8, 0)?;
+
+        let label = format!("{:.0}%", confidence * 100.0);
+        self.draw_label_above(frame, size, pixels, &label, color)
+    }
+
+    fn draw_guide_box(
+        &self,
+        frame: &mut Mat,
+        size: Size,
+        rect: &NormalizedRect,
+        label: Option<&str>,
+    ) -> opencv::Result<()> {
+        let style = &self.style;
+        let pixels = rect.to_pixels(size);
+        let thickness = style.scaled(1, size);
+
+        imgproc::rectangle(frame, pixels, style.guide_color, thickness, imgproc::LINE_8, 0)?;
+
+        match label {
+            Some(label) => self.draw_label_above(frame, size, pixels, label, style.guide_color),
+            None => Ok(()),
+        }
+    }
+
+    fn draw_label_above(
+        &self,
+        frame: &mut Mat,
+        size: Size,
+        pixels: Rect,
+        label: &str,
+        color: Scalar,
+    ) -> opencv::Result<()> {
+        let style = &self.style;
+        let font_scale = style.caption_scale * style.scale(size);
+        let thickness = style.scaled(1, size);
+        let gap = style.scaled(style.margin / 2, size);
+
+        let mut baseline = 0;
+        let text_size = imgproc::get_text_size(
+            label,
+            imgproc::FONT_HERSHEY_SIMPLEX,
+            font_scale,
+            thickness,
+            &mut baseline,
+        )?;
+
+        // Keep the label inside the frame when the box touches the top edge
+        let y = (pixels.y - gap).max(text_size.height);
+        let x = pixels.x.clamp(0, (size.width - text_size.width).max(0));
+
+        imgproc::put_text(
+            frame,
+            label,
+            Point::new(x, y),
+            imgproc::FONT_HERSHEY_SIMPLEX,
+            font_scale,
+            color,
+            thickness,
+            imgproc::LINE_8,
+            false,
+        )
+    }
+
+    fn draw_landmarks(&self, frame: &mut Mat, size: Size, points: &[(f32, f32)]) -> opencv::Result<()> {
+        let style = &self.style;
+        let radius = style.scaled(style.landmark_radius, size);
+
+        for &(x, y) in points {
+            let center = Point::new(
+                (x * size.width as f32).round() as i32,
+                (y * size.height as f32).round() as i32,
+            );
+            imgproc::circle(frame, center, radius, style.landmark_color, -1, imgproc::LINE_8, 0)?;
+        }
+
+        Ok(())
+    }
+
+    fn draw_text(
+        &self,
+        frame: &mut Mat,
+        size: Size,
+        anchor: Anchor,
+        lines: &[String],
+        color: Option<Scalar>,
+        caption: bool,
+    ) -> opencv::Result<()> {
+        if lines.is_empty() {
+            return Ok(());
+        }
+
+        let style = &self.style;
+        let base_scale = if caption { style.caption_scale } else { style.font_scale };
+        let font_scale = base_scale * style.scale(size);
+        let thickness = if caption { style.scaled(1, size) } else { style.scaled(style.thickness, size) };
+        let margin = style.scaled(style.margin, size);
+        let color = color.unwrap_or(style.text_color);
+
+        // Measure every line so the block can be anchored as a whole
+        let mut line_sizes = Vec::with_capacity(lines.len());
+        for line in lines {
+            let mut baseline = 0;
+            let text_size = imgproc::get_text_size(
+                line,
+                imgproc::FONT_HERSHEY_SIMPLEX,
+                font_scale,
+                thickness,
+                &mut baseline,
+            )?;
+            line_sizes.push((text_size, baseline));
+        }
+
+        let line_height = line_sizes
+            .iter()
+            .map(|(s, baseline)| s.height + baseline)
+            .max()
+            .unwrap_or(0);
+        let block_height = line_height * lines.len() as i32;
+
+        let top = match anchor {
+            Anchor::TopLeft | Anchor::TopCenter | Anchor::TopRight => margin,
+            Anchor::Center => (size.height - block_height) / 2,
+            Anchor::BottomLeft | Anchor::BottomCenter | Anchor::BottomRight => {
+                size.height - margin - block_height
+            }
+        };
+
+        for (i, (line, (text_size, _))) in lines.iter().zip(&line_sizes).enumerate() {
+            let x = match anchor {
+                Anchor::TopLeft | Anchor::BottomLeft => margin,
+                Anchor::TopCenter | Anchor::Center | Anchor::BottomCenter => {
+                    (size.width - text_size.width) / 2
+                }
+                Anchor::TopRight | Anchor::BottomRight => size.width - margin - text_size.width,
+            };
+            // put_text places the baseline at `org`, so offset by the glyph height
+            let y = top + line_height * i as i32 + text_size.height;
+
+            imgproc::put_text(
+                frame,
+                line,
+                Point::new(x, y),
+                imgproc::FONT_HERSHEY_SIMPLEX,
+                font_scale,
+                color,
+                thickness,
+                imgproc::LINE_8,
+                false,
+            )?;
+        }
+
+        Ok(())
+    }
+
+    fn draw_progress_bar(
+        &self,
+        frame: &mut Mat,
+        size: Size,
+        rect: &NormalizedRect,
+        progress: f32,
+    ) -> opencv::Result<()> {
+        let style = &self.style;
+        let track = clip_to_frame(rect.to_pixels(size), size);
+        if track.width <= 0 || track.height <= 0 {
+            return Ok(());
+        }
+
+        fill_rect(frame, track, style.progress_track_color)?;
+
+        let fill_width = (track.width as f32 * progress.clamp(0.0, 1.0)).round() as i32;
+        if fill_width > 0 {
+            let fill = Rect::new(track.x, track.y, fill_width, track.height);
+            fill_rect(frame, fill, style.progress_fill_color)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Split a text block into individual lines
+fn split_lines(text: String) -> Vec<String> {
+    text.lines().map(str::to_string).collect()
+}
+
+/// Clip a pixel rectangle to the frame bounds
+fn clip_to_frame(rect: Rect, size: Size) -> Rect {
+    let x1 = rect.x.clamp(0, size.width);
+    let y1 = rect.y.clamp(0, size.height);
+    let x2 = (rect.x + rect.width).clamp(0, size.width);
+    let y2 = (rect.y + rect.height).clamp(0, size.height);
+    Rect::new(x1, y1, x2 - x1, y2 - y1)
+}
+
+/// Fill a pixel rectangle exactly, without any anti-aliasing or edge rounding
+fn fill_rect(frame: &mut Mat, rect: Rect, color: Scalar) -> opencv::Result<()> {
+    let mut roi = Mat::roi_mut(frame, rect)?;
+    roi.set_to(&color, &core::no_array())?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use opencv::core::{Vec3b, CV_8UC3};
+
+    fn blank_frame(width: i32, height: i32) -> Mat {
+        Mat::zeros(height, width, CV_8UC3).unwrap().to_mat().unwrap()
+    }
+
+    fn pixel(frame: &Mat, x: i32, y: i32) -> [u8; 3] {
+        let p = frame.at_2d::<Vec3b>(y, x).unwrap();
+        [p[0], p[1], p[2]]
+    }
+
+    fn fnv1a(bytes: &[u8]) -> u64 {
+        let mut hash = 0xcbf29ce484222325u64;
+        for &b in bytes {
+            hash ^= b as u64;
+            hash = hash.wrapping_mul(0x100000001b3);
+        }
+        hash
+    }
+
+    fn sample_annotation() -> FrameAnnotation {
+        FrameAnnotation::new()
+            .face_box(NormalizedRect::new(0.25, 0.25, 0.5, 0.5), 0.9)
+            .landmarks(vec![(0.75, 0.8)])
+            .progress_bar(NormalizedRect::new(0.1, 0.9, 0.8, 0.05), 0.5)
+    }
+
+    fn assert_scaled_positions(width: i32, height: i32) {
+        let mut frame = blank_frame(width, height);
+        sample_annotation().render(&mut frame).unwrap();
+
+        let style = AnnotationStyle::default();
+        let green = [0, 255, 0];
+
+        // Left edge of the face box at mid-height
+        let face = NormalizedRect::new(0.25, 0.25, 0.5, 0.5).to_pixels(Size::new(width, height));
+        assert_eq!(pixel(&frame, face.x, face.y + face.height / 2), green);
+
+        // Box interior stays untouched
+        assert_eq!(pixel(&frame, width / 2, height / 2), [0, 0, 0]);
+
+        // Landmark dot center
+        let lx = (0.75 * width as f32).round() as i32;
+        let ly = (0.8 * height as f32).round() as i32;
+        assert_ne!(pixel(&frame, lx, ly), [0, 0, 0]);
+
+        // Progress bar: first quarter filled, last quarter only track
+        let bar = NormalizedRect::new(0.1, 0.9, 0.8, 0.05).to_pixels(Size::new(width, height));
+        let row = bar.y + bar.height / 2;
+        let fill = style.progress_fill_color;
+        let track = style.progress_track_color;
+        assert_eq!(
+            pixel(&frame, bar.x + bar.width / 4, row),
+            [fill[0] as u8, fill[1] as u8, fill[2] as u8]
+        );
+        assert_eq!(
+            pixel(&frame, bar.x + bar.width * 3 / 4, row),
+            [track[0] as u8, track[1] as u8, track[2] as u8]
+        );
+    }
+
+    #[test]
+    fn test_normalized_rect_conversion() {
+        let rect = NormalizedRect::new(0.25, 0.5, 0.5, 0.25);
+        assert_eq!(rect.to_pixels(Size::new(640, 480)), Rect::new(160, 240, 320, 120));
+        assert_eq!(rect.to_pixels(Size::new(1920, 1080)), Rect::new(480, 540, 960, 270));
+
+        let back = NormalizedRect::from_pixels(Rect::new(160, 240, 320, 120), Size::new(640, 480));
+        assert_eq!(back, rect);
+    }
+
+    #[test]
+    fn test_render_small_frame() {
+        assert_scaled_positions(320, 240);
+    }
+
+    #[test]
+    fn test_render_large_frame() {
+        assert_scaled_positions(1920, 1080);
+    }
+
+    #[test]
+    fn test_text_anchors_stay_in_frame() {
+        for &(width, height) in &[(320, 240), (1920, 1080)] {
+            let mut frame = blank_frame(width, height);
+            FrameAnnotation::new()
+                .text(Anchor::BottomRight, "FPS: 30.0")
+                .render(&mut frame)
+                .unwrap();
+
+            // Text is drawn in the bottom-right quadrant only
+            let top_left = Mat::roi(&frame, Rect::new(0, 0, width / 2, height / 2)).unwrap();
+            let bottom_right = Mat::roi(&frame, Rect::new(width / 2, height / 2, width / 2, height / 2)).unwrap();
+            let mut gray_tl = Mat::default();
+            let mut gray_br = Mat::default();
+            imgproc::cvt_color(&*top_left, &mut gray_tl, imgproc::COLOR_BGR2GRAY, 0).unwrap();
+            imgproc::cvt_color(&*bottom_right, &mut gray_br, imgproc::COLOR_BGR2GRAY, 0).unwrap();
+            assert_eq!(core::count_non_zero(&gray_tl).unwrap(), 0);
+            assert!(core::count_non_zero(&gray_br).unwrap() > 0);
+        }
+    }
+
+    #[test]
+    fn test_default_style_golden_hash() {
+        // Only primitives with exact, backend-independent rasterization
+        let mut frame = blank_frame(320, 240);
+        FrameAnnotation::new()
+            .guide_box(NormalizedRect::new(0.25, 0.25, 0.5, 0.5), None)
+            .progress_bar(NormalizedRect::new(0.1, 0.85, 0.8, 0.05), 0.5)
+            .render(&mut frame)
+            .unwrap();
+
+        let bytes = frame.data_bytes().unwrap();
+        assert_eq!(fnv1a(bytes), GOLDEN_320X240_HASH);
+    }
+
+    const GOLDEN_320X240_HASH: u64 = 0x843e_0cf4_5492_bfb5;
+}
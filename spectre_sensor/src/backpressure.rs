@@ -0,0 +1,261 @@
+//! What a full frame channel does with one more frame
+//!
+//! A bounded channel fed with `try_send` refuses the frame being sent once
+//! it is full, so a slow consumer works through stale frames while the
+//! fresh ones are thrown away: backwards for a real-time control signal.
+//! [`BackpressureSender`] applies a [`BackpressurePolicy`] instead, and the
+//! default one drops the oldest queued frame to make room, so however slow
+//! a consumer is, the frames it reads are the freshest ones.
+//!
+//! Any `async_channel` handle can receive, so the senders share a receiver
+//! of their own to drop the oldest frame with. It does not count as a
+//! consumer: once every other receiver is gone, sends fail as on a closed
+//! channel.
+
+use async_channel::{Receiver, SendError, Sender, TrySendError};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Longest a [`BackpressurePolicy::Block`] send waits for room before the
+/// frame is dropped, so a consumer that stopped reading cannot wedge the
+/// sensor past a stop request
+pub const BLOCK_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// What a full channel does with the frame being sent
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BackpressurePolicy {
+    /// Drop the frame being sent; the consumer reads the queued, older ones
+    DropNewest,
+    /// Drop the oldest queued frame, so the consumer always gets the freshest
+    #[default]
+    DropOldest,
+    /// Wait for the consumer to make room, up to [`BLOCK_TIMEOUT`]
+    Block,
+}
+
+impl BackpressurePolicy {
+    /// Name used in configuration and as the metrics label
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::DropNewest => "drop_newest",
+            Self::DropOldest => "drop_oldest",
+            Self::Block => "block",
+        }
+    }
+}
+
+impl FromStr for BackpressurePolicy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().replace('-', "_").as_str() {
+            "drop_newest" => Ok(Self::DropNewest),
+            "drop_oldest" | "latest" => Ok(Self::DropOldest),
+            "block" => Ok(Self::Block),
+            other => Err(format!(
+                "Unknown back-pressure policy: {} (expected drop_newest, drop_oldest or block)",
+                other
+            )),
+        }
+    }
+}
+
+impl fmt::Display for BackpressurePolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// What became of a frame handed to [`BackpressureSender::send`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Delivery {
+    /// Queued without dropping anything
+    Queued,
+    /// Queued after dropping the oldest queued frame
+    DroppedOldest,
+    /// Dropped: the channel was full, or stayed full past [`BLOCK_TIMEOUT`]
+    DroppedNewest,
+}
+
+impl Delivery {
+    /// Whether a frame was lost
+    pub fn dropped(self) -> bool {
+        self != Self::Queued
+    }
+}
+
+/// A bounded channel whose sender follows `policy`
+///
+/// `capacity` is raised to 1 if 0.
+pub fn channel<T>(capacity: usize, policy: BackpressurePolicy) -> (BackpressureSender<T>, Receiver<T>) {
+    let (sender, receiver) = async_channel::bounded(capacity.max(1));
+    let oldest = (policy == BackpressurePolicy::DropOldest).then(|| Arc::new(receiver.clone()));
+    (BackpressureSender { sender, oldest, policy }, receiver)
+}
+
+/// Sending half of a [`channel`]
+#[derive(Debug)]
+pub struct BackpressureSender<T> {
+    sender: Sender<T>,
+    /// Receiver the oldest frame is dropped through, under `DropOldest`
+    oldest: Option<Arc<Receiver<T>>>,
+    policy: BackpressurePolicy,
+}
+
+impl<T> Clone for BackpressureSender<T> {
+    fn clone(&self) -> Self {
+        Self {
+            sender: self.sender.clone(),
+            oldest: self.oldest.clone(),
+            policy: self.policy,
+        }
+    }
+}
+
+impl<T> BackpressureSender<T> {
+    /// Policy applied when the channel is full
+    pub fn policy(&self) -> BackpressurePolicy {
+        self.policy
+    }
+
+    /// Whether every consumer is gone
+    pub fn is_closed(&self) -> bool {
+        // The senders share one receiver of their own
+        let own = usize::from(self.oldest.is_some());
+        self.sender.is_closed() || self.sender.receiver_count() <= own
+    }
+
+    /// Send `item` under the policy; only `Block` ever waits
+    ///
+    /// Fails, handing `item` back, once every consumer is gone.
+    pub async fn send(&self, item: T) -> Result<Delivery, SendError<T>> {
+        if self.is_closed() {
+            self.sender.close();
+            return Err(SendError(item));
+        }
+        match self.policy {
+            BackpressurePolicy::DropNewest => match self.sender.try_send(item) {
+                Ok(()) => Ok(Delivery::Queued),
+                Err(TrySendError::Full(_)) => Ok(Delivery::DroppedNewest),
+                Err(TrySendError::Closed(item)) => Err(SendError(item)),
+            },
+            BackpressurePolicy::DropOldest => {
+                let mut item = item;
+                let mut delivery = Delivery::Queued;
+                loop {
+                    match self.sender.try_send(item) {
+                        Ok(()) => return Ok(delivery),
+                        Err(TrySendError::Full(back)) => {
+                            item = back;
+                            // The consumer may have made room meanwhile
+                            if let Some(oldest) = &self.oldest {
+                                if oldest.try_recv().is_ok() {
+                                    delivery = Delivery::DroppedOldest;
+                                }
+                            }
+                        }
+                        Err(TrySendError::Closed(item)) => return Err(SendError(item)),
+                    }
+                }
+            }
+            BackpressurePolicy::Block => match tokio::time::timeout(BLOCK_TIMEOUT, self.sender.send(item)).await {
+                Ok(Ok(())) => Ok(Delivery::Queued),
+                Ok(Err(closed)) => Err(closed),
+                Err(_) => Ok(Delivery::DroppedNewest),
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::FearFrame;
+    use std::sync::atomic::{AtomicU32, Ordering};
+    use tokio::time::sleep;
+
+    /// Fear a consumer reading every 10 ms sees of 1 ms frames sent under
+    /// `policy`, each with the newest fear sent by then
+    async fn slow_consumer(capacity: usize, policy: BackpressurePolicy) -> Vec<(f32, f32)> {
+        let (sender, receiver) = channel(capacity, policy);
+        let newest = Arc::new(AtomicU32::new(0));
+        let producer = {
+            let newest = Arc::clone(&newest);
+            tokio::spawn(async move {
+                for step in 0..=100 {
+                    let fear = step as f32 / 100.0;
+                    sender.send(FearFrame::new(fear, [0.0; 7], 0.9, true, Duration::ZERO)).await.unwrap();
+                    newest.store(fear.to_bits(), Ordering::Relaxed);
+                    sleep(Duration::from_millis(1)).await;
+                }
+            })
+        };
+
+        let mut seen = Vec::new();
+        while let Ok(frame) = receiver.recv().await {
+            seen.push((frame.fear_score, f32::from_bits(newest.load(Ordering::Relaxed))));
+            sleep(Duration::from_millis(10)).await;
+        }
+        producer.await.unwrap();
+        seen
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_drop_oldest_delivers_the_newest_fear() {
+        let seen = slow_consumer(1, BackpressurePolicy::DropOldest).await;
+        assert!(seen.len() < 20, "{} frames read", seen.len());
+        for (read, newest) in &seen {
+            assert_eq!(read, newest);
+        }
+        assert_eq!(seen.last().unwrap().0, 1.0);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_drop_newest_and_block_deliver_stale_fear() {
+        // Frames wait behind older ones while the newer ones are dropped;
+        // only the first, and the last once sending stops, can be fresh
+        let seen = slow_consumer(2, BackpressurePolicy::DropNewest).await;
+        let waited = &seen[1..seen.len() - 1];
+        assert!(waited.iter().all(|(read, newest)| newest - read > 0.05), "{:?}", seen);
+
+        // Every frame arrives, at the consumer's pace
+        let seen = slow_consumer(2, BackpressurePolicy::Block).await;
+        let read: Vec<f32> = seen.iter().map(|(read, _)| *read).collect();
+        let sent: Vec<f32> = (0..=100).map(|step| step as f32 / 100.0).collect();
+        assert_eq!(read, sent);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_full_channel_reports_drops_and_consumers_leaving() {
+        for (policy, delivery) in [
+            (BackpressurePolicy::DropNewest, Delivery::DroppedNewest),
+            (BackpressurePolicy::DropOldest, Delivery::DroppedOldest),
+            (BackpressurePolicy::Block, Delivery::DroppedNewest),
+        ] {
+            let (sender, receiver) = channel(1, policy);
+            assert_eq!(sender.send(1).await.unwrap(), Delivery::Queued);
+            assert_eq!(sender.send(2).await.unwrap(), delivery, "{}", policy);
+            let kept = if policy == BackpressurePolicy::DropOldest { 2 } else { 1 };
+            assert_eq!(receiver.try_recv().unwrap(), kept);
+
+            // The sender's own receiver does not keep the channel open
+            drop(receiver);
+            assert!(sender.is_closed());
+            assert_eq!(sender.send(3).await.unwrap_err().into_inner(), 3);
+        }
+    }
+
+    #[test]
+    fn test_policy_names() {
+        for policy in [BackpressurePolicy::DropNewest, BackpressurePolicy::DropOldest, BackpressurePolicy::Block] {
+            assert_eq!(policy.to_string().parse::<BackpressurePolicy>(), Ok(policy));
+        }
+        assert_eq!("drop-oldest".parse::<BackpressurePolicy>(), Ok(BackpressurePolicy::DropOldest));
+        assert!("drop_everything".parse::<BackpressurePolicy>().is_err());
+        assert_eq!(BackpressurePolicy::default(), BackpressurePolicy::DropOldest);
+    }
+}
@@ -1,18 +1,95 @@
 //! Camera Viewer with Face Detection for SpectreMesh
 //!
 //! This shows what the camera sees in real-time with face detection overlays
-//! to help debug positioning and lighting issues.
+//! to help debug positioning and lighting issues. With `--detect`, or after
+//! pressing 'd', the sensor's face detector runs on every frame and the
+//! overlay shows the face it found.
 
+use clap::Parser;
 use opencv::{
     videoio::{VideoCapture, CAP_ANY},
     prelude::{VideoCaptureTraitConst, VideoCaptureTrait, MatTraitConst},
-    core::{Mat, Point, Scalar, Rect},
-    imgproc,
+    core::Mat,
     highgui,
 };
+use spectre_sensor::annotate::{Anchor, AnnotationStyle, FrameAnnotation, NormalizedRect};
+use spectre_sensor::face_backend::{create_face_detector, FaceDetectorBackend};
+use spectre_sensor::yunet::{FaceDetection, YuNetError};
+use spectre_sensor::{config::SensorConfig, mirror::mirror_frame};
 use std::time::{Duration, Instant};
 
+#[derive(Parser)]
+#[command(name = "camera_viewer")]
+#[command(about = "Live camera preview for checking positioning and lighting")]
+struct Cli {
+    /// Run face detection on every frame from the start ('d' toggles it)
+    #[arg(long)]
+    detect: bool,
+}
+
+/// Face detection for the preview, loaded on first use
+struct LiveDetection {
+    config: SensorConfig,
+    detector: Option<Box<dyn FaceDetectorBackend>>,
+    /// Set once loading failed, so the preview stays usable without it
+    unavailable: bool,
+    enabled: bool,
+    /// Detections and time spent detecting since the last console report
+    runs: u32,
+    busy: Duration,
+}
+
+impl LiveDetection {
+    fn new(config: SensorConfig) -> Self {
+        Self { config, detector: None, unavailable: false, enabled: false, runs: 0, busy: Duration::ZERO }
+    }
+
+    /// Turn detection on or off, loading the detector the first time
+    fn set_enabled(&mut self, enabled: bool) {
+        if enabled && self.detector.is_none() && !self.unavailable {
+            println!("🧠 Loading face detector...");
+            let loaded = ort::init()
+                .commit()
+                .map_err(|e| e.to_string())
+                .and_then(|_| create_face_detector(&self.config).map_err(|e| e.to_string()));
+            match loaded {
+                Ok(detector) => {
+                    println!("✅ Face detector ready ({})", detector.name());
+                    self.detector = Some(detector);
+                }
+                Err(e) => {
+                    println!("⚠️  Face detector unavailable, showing the camera only: {}", e);
+                    self.unavailable = true;
+                }
+            }
+        }
+        self.enabled = enabled && self.detector.is_some();
+    }
+
+    /// Detect the most confident face, if detection is on
+    fn detect(&mut self, frame: &Mat) -> Option<Result<FaceDetection, YuNetError>> {
+        let detector = self.detector.as_mut().filter(|_| self.enabled)?;
+        let start = Instant::now();
+        let result = detector.get_largest_face(frame);
+        self.runs += 1;
+        self.busy += start.elapsed();
+        Some(result)
+    }
+
+    /// Detections per second of detector time and mean latency since the
+    /// last report, then start a new report
+    fn take_rate(&mut self) -> Option<(f32, f32)> {
+        let (runs, busy) = (std::mem::take(&mut self.runs), std::mem::take(&mut self.busy));
+        if runs == 0 || busy.is_zero() {
+            return None;
+        }
+        Some((runs as f32 / busy.as_secs_f32(), busy.as_secs_f32() * 1000.0 / runs as f32))
+    }
+}
+
 fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let cli = Cli::parse();
+
     println!("🎯 SpectreMesh Visual Camera Viewer");
     println!("===================================");
     println!("This will open a popup window showing your live camera feed!");
@@ -44,6 +121,15 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     println!("📐 Camera configured: {}x{} @ {:.1} FPS",
         actual_width as i32, actual_height as i32, actual_fps);
 
+    // Show frames the way the sensor will process them
+    let config = SensorConfig::from_env();
+    let mirror_input = config.mirror_input;
+    let mirrored = mirror_input.resolve(None);
+    println!("🪞 Mirroring: {} (flipping frames: {})", mirror_input, mirrored);
+
+    let mut detection = LiveDetection::new(config);
+    detection.set_enabled(cli.detect);
+
     // Create window for displaying camera feed
     let window_name = "SpectreMesh Camera Feed - Position Yourself Here!";
     highgui::named_window(window_name, highgui::WINDOW_AUTOSIZE)?;
@@ -54,6 +140,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     println!("   - Press 's' to save current frame");
     println!("   - Press SPACE to take a test photo");
     println!("   - Press 'f' to show FPS stats");
+    println!("   - Press 'd' to toggle live face detection");
     println!("📹 Position yourself in the camera view and check lighting!");
     println!("🎯 Target: 15-30 FPS for reliable face detection");
 
@@ -70,6 +157,14 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
 
         if camera.read(&mut frame)? && !frame.empty() {
             frame_count += 1;
+            if mirrored {
+                mirror_frame(&mut frame)?;
+            }
+
+            // Detect on the frame as the sensor would see it, before any overlay
+            if let Some(result) = detection.detect(&frame) {
+                add_detection_overlay(&mut frame, &result)?;
+            }
 
             // Add overlay information to the frame (only if not too frequent)
             if show_fps_stats || frame_count % 5 == 0 {
@@ -94,6 +189,9 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
 
                 println!("📸 Frame {}: {}x{} | Avg FPS: {:.1} | Recent FPS: {:.1} | Runtime: {:.0}s",
                     frame_count, size.width, size.height, fps, recent_fps, elapsed);
+                if let Some((detect_fps, detect_ms)) = detection.take_rate() {
+                    println!("🧠 Detection FPS: {:.1} ({:.1}ms per frame)", detect_fps, detect_ms);
+                }
 
                 // Performance assessment
                 if fps < 10.0 {
@@ -126,6 +224,10 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
                     show_fps_stats = !show_fps_stats;
                     println!("📊 FPS stats overlay: {}", if show_fps_stats { "ON" } else { "OFF" });
                 }
+                100 => { // 'd' - toggle face detection
+                    detection.set_enabled(!detection.enabled);
+                    println!("🧠 Face detection: {}", if detection.enabled { "ON" } else { "OFF" });
+                }
                 _ => {}
             }
 
@@ -185,78 +287,47 @@ fn add_overlay_info(frame: &mut Mat, frame_num: i32, elapsed: Duration, show_det
     };
 
     // Color code FPS: Red if low, Yellow if medium, Green if good
+    let style = AnnotationStyle::default();
     let fps_color = if fps < 10.0 {
-        Scalar::new(0.0, 0.0, 255.0, 0.0) // Red
+        style.weak_face_color
     } else if fps < 15.0 {
-        Scalar::new(0.0, 255.0, 255.0, 0.0) // Yellow
+        style.guide_color
     } else {
-        Scalar::new(0.0, 255.0, 0.0, 0.0) // Green
+        style.face_color
     };
 
-    imgproc::put_text(
-        frame,
-        &info_text,
-        Point::new(10, 30),
-        imgproc::FONT_HERSHEY_SIMPLEX,
-        0.7,
-        fps_color,
-        2,
-        imgproc::LINE_8,
-        false,
-    )?;
+    let mut annotation = FrameAnnotation::with_style(style)
+        .text_colored(Anchor::TopLeft, info_text, fps_color);
 
     // Only add detailed overlays if requested (for performance)
     if show_detailed {
-        // Add instructions
-        let instructions = "Q=Quit | S=Save | SPACE=Photo | F=Stats";
-        imgproc::put_text(
-            frame,
-            &instructions,
-            Point::new(10, size.height - 20),
-            imgproc::FONT_HERSHEY_SIMPLEX,
-            0.5,
-            Scalar::new(255.0, 255.0, 255.0, 0.0), // White text
-            1,
-            imgproc::LINE_8,
-            false,
-        )?;
-
-        // Add face detection area guide
-        let center_x = size.width / 2;
-        let center_y = size.height / 2;
-        let guide_size = 120; // Smaller for less processing
-
-        let face_rect = Rect::new(
-            center_x - guide_size/2,
-            center_y - guide_size/2,
-            guide_size,
-            guide_size
-        );
-
-        // Draw face detection guide rectangle
-        imgproc::rectangle(
-            frame,
-            face_rect,
-            Scalar::new(0.0, 255.0, 255.0, 0.0), // Yellow
-            1, // Thinner line for performance
-            imgproc::LINE_8,
-            0,
-        )?;
-
-        // Add face guide text
-        imgproc::put_text(
-            frame,
-            "Face Here",
-            Point::new(center_x - 40, center_y - guide_size/2 - 10),
-            imgproc::FONT_HERSHEY_SIMPLEX,
-            0.5,
-            Scalar::new(0.0, 255.0, 255.0, 0.0), // Yellow
-            1,
-            imgproc::LINE_8,
-            false,
-        )?;
+        annotation = annotation
+            .caption(Anchor::BottomLeft, "Q=Quit | S=Save | SPACE=Photo | F=Stats | D=Detect")
+            // Face detection area guide, sized relative to the frame
+            .guide_box(NormalizedRect::centered(0.5, 0.5, 0.1875, 0.25), Some("Face Here"));
     }
 
+    annotation.render(frame)?;
+
+    Ok(())
+}
+
+/// Draw the detected face, its landmarks and confidence, green above the
+/// confidence threshold and red below it
+fn add_detection_overlay(frame: &mut Mat, result: &Result<FaceDetection, YuNetError>) -> Result<(), Box<dyn std::error::Error>> {
+    let annotation = FrameAnnotation::new();
+    let annotation = match result {
+        Ok(face) => annotation.face_detection(face, frame.size()?),
+        Err(YuNetError::NoFacesDetected) => {
+            let color = annotation.style().weak_face_color;
+            annotation.text_colored(Anchor::TopRight, "No face", color)
+        }
+        Err(e) => {
+            let color = annotation.style().weak_face_color;
+            annotation.text_colored(Anchor::TopRight, format!("Detection failed: {}", e), color)
+        }
+    };
+    annotation.render(frame)?;
     Ok(())
 }
 
@@ -136,11 +136,11 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                 face_detected_count += 1;
                 
                 println!("😊 FACE DETECTED! Fear={:.3}, Confidence={:.3}, Calibrated={}", 
-                    score.value, score.confidence, score.calibrated);
+                    score.provisional_value(), score.confidence, score.calibrated);
                 
                 // Show fear bucket if calibrated
-                if score.calibrated {
-                    let bucket = FearBucket::from_score(score.value);
+                if let Some(value) = score.value() {
+                    let bucket = FearBucket::from_score(value);
                     println!("   📈 Fear Bucket: {:?} | Raw Fear Logit: {:.3}",
                         bucket, score.extract_fear_logit());
                 }
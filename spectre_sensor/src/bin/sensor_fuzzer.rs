@@ -1,115 +1,199 @@
 //! Sensor fuzzer for soak testing and simulation
-//! 
-//! Generates synthetic SensorEvent streams with configurable patterns
-//! and random faults for testing system resilience.
+//!
+//! Serves synthetic fear patterns, calibration runs and faults from a mock
+//! sensor daemon on a real socket, so games and tools connect to it as they
+//! would to `sensord`. `soak-test --verify` also streams the daemon's events
+//! back through a `SensorClient` and checks them against the soak invariants
+//! (see `spectre_sensor::soak`), exiting non-zero when any is violated so it
+//! can run as a nightly CI job.
+//!
+//! ```text
+//! sensor_fuzzer scores --pattern step --socket /tmp/fuzz.sock
+//! sensor_fuzzer soak-test --duration 3600 --verify
+//! ```
 
 use spectre_sensor::{
-    proto::{sensor_event, SensorEvent, Score, SensorFault, CalibrationProgress, FaultSeverity},
+    calibration_control::CalibrationAction,
+    grpc_client::SensorClient,
+    logging::{install_console, LogFormat},
+    mock::{spawn_mock_service_on, MockEmotionSensor, MockSensorConfig, MockSensorService},
+    proto::{ClientInfo, SensorEvent},
+    soak::{resident_memory, SoakLimits, SoakReport, SoakVerifier},
+    types::{FaultLevel, SensorFaultNotice},
+    SensorConfig, SensorTransport,
 };
-use clap::{Parser, Subcommand};
-use rand::Rng;
-use std::time::{Duration, SystemTime, UNIX_EPOCH};
-use tokio::time::{sleep, interval};
-use tracing::{info, warn, error};
+use clap::{Parser, Subcommand, ValueEnum};
+use futures::{Stream, StreamExt};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use std::pin::Pin;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::{
+    sync::{oneshot, Mutex},
+    task::JoinHandle,
+    time::sleep,
+};
+use tonic::Status;
+use tracing::{error, info, warn};
+
+type BoxError = Box<dyn std::error::Error + Send + Sync>;
+type EventStream = Pin<Box<dyn Stream<Item = Result<SensorEvent, Status>> + Send>>;
+
+/// Reading given to frames hit by a simulated inference error, far outside
+/// the [0, 1] of a real face, which normalization must still clamp
+const GLITCH_READING: f32 = 4.0;
 
 #[derive(Parser)]
 #[command(name = "sensor_fuzzer")]
-#[command(about = "Synthetic sensor event generator for testing")]
+#[command(about = "Serve synthetic sensor events for testing")]
 struct Cli {
     #[command(subcommand)]
     command: Commands,
+
+    /// Log records as pretty lines or JSON objects (overrides SPECTRE_LOG_FORMAT)
+    #[arg(long, global = true)]
+    log_format: Option<LogFormat>,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Pattern {
+    Sine,
+    Step,
+    Random,
+    Constant,
 }
 
 #[derive(Subcommand)]
 enum Commands {
-    /// Generate synthetic score events
+    /// Serve synthetic score events
     Scores {
-        /// Number of events to generate (0 = infinite)
+        /// Stop after about this many frames (0 = until Ctrl-C)
         #[arg(short, long, default_value = "0")]
         count: u64,
-        
-        /// Events per second
+
+        /// Frames per second
         #[arg(short, long, default_value = "30.0")]
         fps: f32,
-        
-        /// Fear pattern: sine, step, random, or constant
-        #[arg(short, long, default_value = "sine")]
-        pattern: String,
-        
+
+        /// Fear pattern
+        #[arg(short, long, value_enum, default_value = "sine")]
+        pattern: Pattern,
+
         /// Base fear level for patterns
         #[arg(short, long, default_value = "0.5")]
         base_fear: f32,
-        
-        /// Amplitude for sine/random patterns
+
+        /// Amplitude for sine, step and random patterns
         #[arg(short, long, default_value = "0.3")]
         amplitude: f32,
-        
-        /// Period in seconds for sine pattern
+
+        /// Period in seconds for sine, step and random patterns
         #[arg(long, default_value = "10.0")]
         period: f32,
-        
+
         /// Probability of inference errors (0.0-1.0)
         #[arg(long, default_value = "0.01")]
         error_rate: f32,
-        
-        /// Socket path for gRPC connection
+
+        /// Socket path, pipe:<name> or host:port to serve on
         #[arg(long, default_value = "/tmp/spectre_sensor.sock")]
-        socket: String,
+        socket: SensorTransport,
     },
-    
-    /// Generate calibration events
+
+    /// Serve back-to-back calibration runs, resetting after each completes
     Calibration {
         /// Calibration duration in seconds
         #[arg(short, long, default_value = "30.0")]
         duration: f32,
-        
-        /// Updates per second during calibration
+
+        /// Frames per second during calibration
         #[arg(short, long, default_value = "10.0")]
         fps: f32,
-        
-        /// Socket path for gRPC connection
+
+        /// Socket path, pipe:<name> or host:port to serve on
         #[arg(long, default_value = "/tmp/spectre_sensor.sock")]
-        socket: String,
+        socket: SensorTransport,
     },
-    
-    /// Generate fault events
+
+    /// Send fault events to connected clients
     Faults {
         /// Number of faults to generate
         #[arg(short, long, default_value = "10")]
         count: u64,
-        
+
         /// Interval between faults in seconds
         #[arg(short, long, default_value = "5.0")]
         interval: f32,
-        
-        /// Socket path for gRPC connection
+
+        /// Socket path, pipe:<name> or host:port to serve on
         #[arg(long, default_value = "/tmp/spectre_sensor.sock")]
-        socket: String,
+        socket: SensorTransport,
     },
-    
+
     /// Run comprehensive soak test
     SoakTest {
         /// Test duration in seconds
         #[arg(short, long, default_value = "300")]
         duration: u64,
-        
-        /// Socket path for gRPC connection
+
+        /// Socket path, pipe:<name> or host:port to serve on
         #[arg(long, default_value = "/tmp/spectre_sensor.sock")]
-        socket: String,
+        socket: SensorTransport,
+
+        /// Stream the events back and check the soak invariants, exiting non-zero on a violation
+        #[arg(long)]
+        verify: bool,
+
+        /// Calibration duration in seconds
+        #[arg(long, default_value = "5.0")]
+        calibration_secs: f32,
+
+        /// Reset calibration this often once calibrated, in seconds
+        #[arg(long, default_value = "60.0")]
+        recalibrate_every: f32,
+
+        /// Average time between random faults, in seconds
+        #[arg(long, default_value = "30.0")]
+        fault_every: f32,
+
+        /// Seconds before the resident memory baseline is taken
+        #[arg(long, default_value = "10.0")]
+        warmup: f32,
+
+        /// Most events that may be missing between two received ones
+        #[arg(long, default_value = "30")]
+        max_sequence_gap: u64,
+
+        /// Longest time between two scores, in milliseconds
+        #[arg(long, default_value = "1000")]
+        max_score_gap_ms: u64,
+
+        /// Share of events that may be dropped over the whole run
+        #[arg(long, default_value = "0.01")]
+        max_dropped_ratio: f64,
+
+        /// How far resident memory may grow after warm-up, in MiB
+        #[arg(long, default_value = "64")]
+        max_rss_growth_mb: u64,
     },
 }
 
 #[tokio::main]
-async fn main() -> Result<(), Box<dyn std::error::Error>> {
-    tracing_subscriber::fmt::init();
-    
+async fn main() -> Result<(), BoxError> {
     let cli = Cli::parse();
-    
+    let mut logging = SensorConfig::from_env().logging;
+    logging.format = cli.log_format.unwrap_or(logging.format);
+    install_console(&logging)?;
+
     match cli.command {
-        Commands::Scores { 
-            count, fps, pattern, base_fear, amplitude, period, error_rate, socket 
-        } => {
-            generate_scores(count, fps, &pattern, base_fear, amplitude, period, error_rate, &socket).await?;
+        Commands::Scores { count, fps, pattern, base_fear, amplitude, period, error_rate, socket } => {
+            let mut rng = StdRng::from_entropy();
+            let samples = fear_pattern(pattern, base_fear, amplitude, period * fps, &mut rng);
+            let config = MockSensorConfig::default()
+                .with_pattern(with_glitches(samples, error_rate, &mut rng))
+                .with_target_fps(fps);
+            let run_for = (count > 0).then(|| Duration::from_secs_f32(count as f32 / fps.max(1.0)));
+            serve_scores(&socket, config, run_for).await?;
         },
         Commands::Calibration { duration, fps, socket } => {
             generate_calibration(duration, fps, &socket).await?;
@@ -117,259 +201,405 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         Commands::Faults { count, interval, socket } => {
             generate_faults(count, interval, &socket).await?;
         },
-        Commands::SoakTest { duration, socket } => {
-            run_soak_test(duration, &socket).await?;
+        Commands::SoakTest {
+            duration,
+            socket,
+            verify,
+            calibration_secs,
+            recalibrate_every,
+            fault_every,
+            warmup,
+            max_sequence_gap,
+            max_score_gap_ms,
+            max_dropped_ratio,
+            max_rss_growth_mb,
+        } => {
+            let soak = SoakSettings {
+                duration: Duration::from_secs(duration),
+                calibration: Duration::from_secs_f32(calibration_secs),
+                recalibrate_every: Duration::from_secs_f32(recalibrate_every),
+                fault_every: Duration::from_secs_f32(fault_every),
+                warmup: Duration::from_secs_f32(warmup),
+            };
+            let limits = SoakLimits {
+                max_sequence_gap,
+                max_score_gap: Duration::from_millis(max_score_gap_ms),
+                max_dropped_ratio,
+                max_rss_growth: max_rss_growth_mb * 1024 * 1024,
+            };
+            let report = run_soak_test(&socket, soak, verify.then_some(limits)).await?;
+            if let Some(report) = report {
+                print_report(&report);
+                if !report.passed() {
+                    std::process::exit(1);
+                }
+            }
         },
     }
-    
+
     Ok(())
 }
 
-/// Generate synthetic score events
-async fn generate_scores(
-    count: u64,
-    fps: f32,
-    pattern: &str,
-    base_fear: f32,
-    amplitude: f32,
-    period: f32,
-    error_rate: f32,
-    _socket: &str,
-) -> Result<(), Box<dyn std::error::Error>> {
-    info!("Generating scores: pattern={}, fps={}, count={}", pattern, fps, count);
-
-    let frame_duration = Duration::from_secs_f32(1.0 / fps);
-    use rand::SeedableRng;
-    let mut rng = rand::rngs::StdRng::from_entropy();
-    let mut generated = 0u64;
-    let start_time = std::time::Instant::now();
-    
-    loop {
-        if count > 0 && generated >= count {
-            break;
+/// One loop of `pattern`, `samples` frames long
+fn fear_pattern(pattern: Pattern, base_fear: f32, amplitude: f32, samples: f32, rng: &mut StdRng) -> Vec<f32> {
+    let len = (samples.round() as usize).max(1);
+    let levels: Vec<f32> = match pattern {
+        Pattern::Sine => (0..len)
+            .map(|i| base_fear + amplitude * (2.0 * std::f32::consts::PI * i as f32 / len as f32).sin())
+            .collect(),
+        Pattern::Step => (0..len)
+            .map(|i| if i < len / 2 { base_fear - amplitude } else { base_fear + amplitude })
+            .collect(),
+        Pattern::Random => (0..len).map(|_| base_fear + amplitude * (rng.gen::<f32>() - 0.5) * 2.0).collect(),
+        Pattern::Constant => vec![base_fear],
+    };
+    levels.into_iter().map(|level| level.clamp(0.0, 1.0)).collect()
+}
+
+/// Replace a share `error_rate` of the samples with glitched readings
+fn with_glitches(samples: Vec<f32>, error_rate: f32, rng: &mut StdRng) -> Vec<f32> {
+    samples
+        .into_iter()
+        .map(|level| match rng.gen::<f32>() < error_rate {
+            true if rng.gen() => GLITCH_READING,
+            true => -GLITCH_READING,
+            false => level,
+        })
+        .collect()
+}
+
+/// A mock daemon serving fuzzed events
+struct FuzzDaemon {
+    transport: SensorTransport,
+    sensor: Arc<Mutex<MockEmotionSensor>>,
+    shutdown: oneshot::Sender<()>,
+    server: JoinHandle<()>,
+}
+
+impl FuzzDaemon {
+    async fn start(transport: &SensorTransport, config: MockSensorConfig) -> Result<Self, BoxError> {
+        let service = MockSensorService::new(MockEmotionSensor::new(config));
+        let sensor = service.sensor();
+        let (shutdown, stopped) = oneshot::channel();
+        let server = spawn_mock_service_on(transport, service, async move {
+            let _ = stopped.await;
+        })
+        .await?;
+        info!("Serving fuzzed sensor events on {}", transport);
+        Ok(Self { transport: transport.clone(), sensor, shutdown, server })
+    }
+
+    /// Stop the sensor so streams end, then the server, and remove the socket
+    async fn stop(self) {
+        let _ = self.sensor.lock().await.stop().await;
+        let _ = self.shutdown.send(());
+        if tokio::time::timeout(Duration::from_secs(5), self.server).await.is_err() {
+            warn!("Server did not stop within 5s");
         }
-        
-        let frame_start = std::time::Instant::now();
-        
-        // Generate fear score based on pattern
-        let elapsed = start_time.elapsed().as_secs_f32();
-        let fear_score = match pattern {
-            "sine" => {
-                base_fear + amplitude * (2.0 * std::f32::consts::PI * elapsed / period).sin()
-            },
-            "step" => {
-                if (elapsed / period) as u32 % 2 == 0 {
-                    base_fear - amplitude
-                } else {
-                    base_fear + amplitude
-                }
-            },
-            "random" => {
-                base_fear + amplitude * (rng.gen::<f32>() - 0.5) * 2.0
-            },
-            "constant" => base_fear,
-            _ => {
-                warn!("Unknown pattern '{}', using constant", pattern);
-                base_fear
-            }
-        }.clamp(0.0, 1.0);
-        
-        // Generate synthetic emotion logits
-        let mut emotion_logits = vec![0.1f32; 7];
-        emotion_logits[2] = fear_score * 0.8 + 0.1; // Fear logit
-        
-        // Simulate inference error
-        if rng.gen::<f32>() < error_rate {
-            error!("Simulated inference error at frame {}", generated);
-            continue;
+        if let SensorTransport::Unix(path) = &self.transport {
+            let _ = std::fs::remove_file(path);
         }
-        
-        // Create score event
-        let event = SensorEvent {
-            timestamp_us: SystemTime::now()
-                .duration_since(UNIX_EPOCH)
-                .unwrap()
-                .as_micros() as u64,
-            event: Some(sensor_event::Event::Score(Score {
-                normalized_fear: fear_score,
-                raw_fear_logit: emotion_logits[2],
-                confidence: 0.8 + rng.gen::<f32>() * 0.2, // 0.8-1.0
-                calibrated: generated > 30, // Calibrated after 30 frames
-                emotion_logits,
-                inference_latency_us: (3000 + rng.gen::<u64>() % 5000), // 3-8ms
-            })),
-        };
-        
-        // Print event (in real implementation, this would be sent via gRPC)
-        println!("Score: fear={:.3}, confidence={:.3}, calibrated={}", 
-                fear_score, 
-                if let Some(sensor_event::Event::Score(ref score)) = event.event {
-                    score.confidence
-                } else { 0.0 },
-                if let Some(sensor_event::Event::Score(ref score)) = event.event {
-                    score.calibrated
-                } else { false });
-        
-        generated += 1;
-        
-        // Maintain target FPS
-        let elapsed = frame_start.elapsed();
-        if elapsed < frame_duration {
-            sleep(frame_duration - elapsed).await;
+    }
+}
+
+/// Wait for Ctrl-C, or `run_for` when given
+async fn wait_or_interrupt(run_for: Option<Duration>) {
+    let run_for = async {
+        match run_for {
+            Some(run_for) => sleep(run_for).await,
+            None => std::future::pending().await,
         }
+    };
+    tokio::select! {
+        _ = run_for => {},
+        _ = tokio::signal::ctrl_c() => info!("Interrupted"),
     }
-    
-    info!("Generated {} score events", generated);
+}
+
+/// Serve score events until `run_for` passes or Ctrl-C
+async fn serve_scores(
+    transport: &SensorTransport,
+    config: MockSensorConfig,
+    run_for: Option<Duration>,
+) -> Result<(), BoxError> {
+    info!("Serving {} frames of fear pattern at {} FPS", config.pattern.len(), config.target_fps);
+    let daemon = FuzzDaemon::start(transport, config).await?;
+    wait_or_interrupt(run_for).await;
+    daemon.stop().await;
     Ok(())
 }
 
-/// Generate calibration progress events
-async fn generate_calibration(
-    duration: f32,
-    fps: f32,
-    _socket: &str,
-) -> Result<(), Box<dyn std::error::Error>> {
-    info!("Generating calibration events: duration={}s, fps={}", duration, fps);
-    
-    let total_frames = (duration * fps) as u64;
-    let frame_duration = Duration::from_secs_f32(1.0 / fps);
-    
-    for frame in 0..total_frames {
-        let progress = frame as f32 / total_frames as f32;
-        let completed = progress >= 1.0;
-        
-        let _event = SensorEvent {
-            timestamp_us: SystemTime::now()
-                .duration_since(UNIX_EPOCH)
-                .unwrap()
-                .as_micros() as u64,
-            event: Some(sensor_event::Event::CalibrationProgress(CalibrationProgress {
-                progress,
-                completed,
-                baseline: if completed {
-                    Some(spectre_sensor::proto::BaselineStats {
-                        mean: 0.3,
-                        std_dev: 0.15,
-                        sample_count: total_frames as u32,
-                    })
-                } else {
-                    None
-                },
-            })),
-        };
-        
-        println!("Calibration: progress={:.1}%, completed={}", progress * 100.0, completed);
-        
-        sleep(frame_duration).await;
+/// Serve calibration runs back to back until Ctrl-C; each starts once a client connects
+async fn generate_calibration(duration: f32, fps: f32, transport: &SensorTransport) -> Result<(), BoxError> {
+    info!("Serving calibration runs: duration={}s, fps={}", duration, fps);
+
+    let config = MockSensorConfig::default()
+        .with_target_fps(fps)
+        .with_calibration_period(Duration::from_secs_f32(duration));
+    let daemon = FuzzDaemon::start(transport, config).await?;
+    let mut phases = daemon.sensor.lock().await.subscribe_calibration();
+    let restart = async {
+        while phases.changed().await.is_ok() {
+            let phase = phases.borrow_and_update().clone();
+            info!("Calibration: progress={:.1}%, phase={}", phase.progress() * 100.0, phase.name());
+            if phase.is_calibrated() {
+                sleep(Duration::from_secs(1)).await;
+                if let Err(e) = daemon.sensor.lock().await.control_calibration(CalibrationAction::Reset).await {
+                    warn!("Failed to restart calibration: {}", e);
+                }
+            }
+        }
+    };
+    tokio::select! {
+        _ = restart => {},
+        _ = wait_or_interrupt(None) => {},
     }
-    
+
+    daemon.stop().await;
     info!("Calibration simulation complete");
     Ok(())
 }
 
-/// Generate fault events
-async fn generate_faults(
-    count: u64,
-    interval: f32,
-    _socket: &str,
-) -> Result<(), Box<dyn std::error::Error>> {
-    info!("Generating {} fault events with {}s interval", count, interval);
-    
-    let fault_types = [
-        ("CAMERA_DISCONNECTED", FaultSeverity::Error, true),
-        ("MODEL_INFERENCE_TIMEOUT", FaultSeverity::Warning, true),
-        ("FACE_DETECTION_FAILED", FaultSeverity::Info, true),
-        ("CALIBRATION_DRIFT", FaultSeverity::Warning, false),
-        ("SYSTEM_OVERLOAD", FaultSeverity::Critical, false),
+/// Pick one of the simulated faults
+fn random_fault(rng: &mut StdRng, number: u64) -> SensorFaultNotice {
+    const FAULT_TYPES: [(&str, FaultLevel, bool); 5] = [
+        ("CAMERA_DISCONNECTED", FaultLevel::Error, true),
+        ("MODEL_INFERENCE_TIMEOUT", FaultLevel::Warning, true),
+        ("FACE_DETECTION_FAILED", FaultLevel::Info, true),
+        ("CALIBRATION_DRIFT", FaultLevel::Warning, false),
+        ("SYSTEM_OVERLOAD", FaultLevel::Critical, false),
     ];
-    
-    use rand::SeedableRng;
-    let mut rng = rand::rngs::StdRng::from_entropy();
-    
-    for i in 0..count {
-        let (error_code, severity, recoverable) = fault_types[rng.gen_range(0..fault_types.len())];
-        
-        let _event = SensorEvent {
-            timestamp_us: SystemTime::now()
-                .duration_since(UNIX_EPOCH)
-                .unwrap()
-                .as_micros() as u64,
-            event: Some(sensor_event::Event::SensorFault(SensorFault {
-                severity: severity as i32,
-                message: format!("Simulated fault #{}: {}", i + 1, error_code),
-                error_code: error_code.to_string(),
-                recoverable,
-            })),
-        };
-        
-        println!("Fault: {} - {} (recoverable: {})", 
-                error_code, 
-                format!("Simulated fault #{}", i + 1), 
-                recoverable);
-        
-        if i < count - 1 {
+    let (error_code, severity, recoverable) = FAULT_TYPES[rng.gen_range(0..FAULT_TYPES.len())];
+    SensorFaultNotice::new(severity, format!("Simulated fault #{}: {}", number, error_code), error_code, recoverable)
+}
+
+/// Send fault events to connected clients, then stop
+async fn generate_faults(count: u64, interval: f32, transport: &SensorTransport) -> Result<(), BoxError> {
+    info!("Generating {} fault events with {}s interval", count, interval);
+
+    let daemon = FuzzDaemon::start(transport, MockSensorConfig::default()).await?;
+    let mut rng = StdRng::from_entropy();
+    let faults = async {
+        for i in 0..count {
             sleep(Duration::from_secs_f32(interval)).await;
+            let fault = random_fault(&mut rng, i + 1);
+            info!("Fault: {} (recoverable: {})", fault.message, fault.recoverable);
+            daemon.sensor.lock().await.inject_fault(fault);
         }
+    };
+    tokio::select! {
+        _ = faults => info!("Generated {} fault events", count),
+        _ = wait_or_interrupt(None) => {},
     }
-    
-    info!("Generated {} fault events", count);
+
+    daemon.stop().await;
     Ok(())
 }
 
-/// Run comprehensive soak test
-async fn run_soak_test(
-    duration: u64,
-    socket: &str,
-) -> Result<(), Box<dyn std::error::Error>> {
-    info!("Starting soak test for {} seconds", duration);
-    
-    let end_time = std::time::Instant::now() + Duration::from_secs(duration);
-    
-    // Spawn concurrent tasks for different event types
-    let score_task = tokio::spawn({
-        let socket = socket.to_string();
-        async move {
-            let _ = generate_scores(0, 30.0, "sine", 0.5, 0.3, 20.0, 0.02, &socket).await;
-            Ok::<(), Box<dyn std::error::Error + Send + Sync>>(())
+/// Timing of a soak run
+struct SoakSettings {
+    duration: Duration,
+    calibration: Duration,
+    recalibrate_every: Duration,
+    fault_every: Duration,
+    warmup: Duration,
+}
+
+/// A client streaming the daemon's events back through a [`SoakVerifier`]
+struct Verification {
+    client: SensorClient,
+    events: EventStream,
+    verifier: SoakVerifier,
+    /// A listing of this stream waiting for the events it counted
+    listed: Option<ClientInfo>,
+    ended: bool,
+}
+
+impl Verification {
+    async fn connect(transport: &SensorTransport, limits: SoakLimits) -> Result<Self, BoxError> {
+        let mut client = SensorClient::connect(transport).await?;
+        let events = Box::pin(client.stream_events().await?);
+        Ok(Self { client, events, verifier: SoakVerifier::new(limits), listed: None, ended: false })
+    }
+
+    async fn next_event(&mut self) -> Option<Result<SensorEvent, Status>> {
+        if self.ended {
+            return std::future::pending().await;
         }
-    });
-    
-    let calibration_task = tokio::spawn({
-        let socket = socket.to_string();
-        async move {
-            // Recalibrate every 60 seconds
-            let mut interval = interval(Duration::from_secs(60));
-            while std::time::Instant::now() < end_time {
-                interval.tick().await;
-                let _ = generate_calibration(30.0, 10.0, &socket).await;
-            }
-            Ok::<(), Box<dyn std::error::Error + Send + Sync>>(())
+        self.events.next().await
+    }
+
+    fn observe(&mut self, event: Option<Result<SensorEvent, Status>>, stopping: bool) {
+        match event {
+            Some(Ok(event)) => {
+                self.verifier.observe(&event);
+                let seen = self.verifier.last_sequence().unwrap_or(0);
+                if let Some(listed) = self.listed.take_if(|c| seen >= c.events_delivered + c.events_dropped + 2) {
+                    self.verifier.reconcile_dropped(&listed);
+                }
+            },
+            Some(Err(status)) => {
+                error!("Event stream failed: {}", status);
+                self.verifier.record_violation(format!("Event stream failed: {}", status.message()));
+                self.ended = true;
+            },
+            None => {
+                if !stopping {
+                    self.verifier.record_violation("Event stream ended before the soak test did");
+                }
+                self.ended = true;
+            },
+        }
+    }
+
+    /// List this stream's counters on the daemon, to reconcile once its events arrive
+    async fn list_self(&mut self) {
+        if self.listed.is_some() {
+            return;
+        }
+        let clients = match self.client.list_clients().await {
+            Ok(response) => response.clients,
+            Err(e) => {
+                warn!("Failed to list clients: {}", e);
+                return;
+            },
+        };
+        let pid = format!("pid {},", std::process::id());
+        let sole = clients.len() == 1;
+        self.listed = clients.into_iter().find(|client| sole || client.peer.contains(&pid));
+    }
+
+    /// Reset calibration through the daemon, allowing progress to start over
+    async fn reset_calibration(&mut self) {
+        self.verifier.recalibration_requested();
+        if let Err(e) = self.client.reset_calibration().await {
+            warn!("Failed to reset calibration: {}", e);
         }
-    });
-    
-    let fault_task = tokio::spawn({
-        let socket = socket.to_string();
-        async move {
-            // Generate random faults
-            use rand::SeedableRng;
-            let mut rng = rand::rngs::StdRng::from_entropy();
-            while std::time::Instant::now() < end_time {
-                let wait_time = Duration::from_secs(rng.gen_range(10..60));
-                sleep(wait_time).await;
-                let _ = generate_faults(1, 0.0, &socket).await;
+    }
+
+    /// Read the events still in flight once the sensor stopped
+    async fn drain(mut self) -> SoakReport {
+        while !self.ended {
+            match tokio::time::timeout(Duration::from_secs(5), self.events.next()).await {
+                Ok(event) => self.observe(event, true),
+                Err(_) => {
+                    self.verifier.record_violation("Event stream did not end after the sensor stopped");
+                    break;
+                },
             }
-            Ok::<(), Box<dyn std::error::Error + Send + Sync>>(())
         }
-    });
-    
-    // Wait for test duration
-    sleep(Duration::from_secs(duration)).await;
-    
-    // Cancel tasks
-    score_task.abort();
-    calibration_task.abort();
-    fault_task.abort();
-    
-    info!("Soak test completed");
-    Ok(())
+        self.verifier.finish()
+    }
+}
+
+/// Run comprehensive soak test, verifying the events against `limits` when given
+async fn run_soak_test(
+    transport: &SensorTransport,
+    soak: SoakSettings,
+    limits: Option<SoakLimits>,
+) -> Result<Option<SoakReport>, BoxError> {
+    info!("Starting soak test for {} seconds", soak.duration.as_secs());
+
+    let mut rng = StdRng::from_entropy();
+    let samples = fear_pattern(Pattern::Sine, 0.5, 0.3, 20.0 * 30.0, &mut rng);
+    let config = MockSensorConfig::default()
+        .with_pattern(with_glitches(samples, 0.02, &mut rng))
+        .with_target_fps(30.0)
+        .with_calibration_period(soak.calibration);
+    let daemon = FuzzDaemon::start(transport, config).await?;
+    let mut verification = match limits {
+        Some(limits) => Some(Verification::connect(transport, limits).await?),
+        None => None,
+    };
+
+    let started = Instant::now();
+    let deadline = sleep(soak.duration);
+    tokio::pin!(deadline);
+    let mut ticks = tokio::time::interval(Duration::from_secs(1));
+    let mut next_fault = started + soak.fault_every.mul_f32(rng.gen_range(0.5..1.5));
+    let mut last_reset = started;
+    let mut faults = 0;
+
+    loop {
+        tokio::select! {
+            _ = &mut deadline => break,
+            _ = tokio::signal::ctrl_c() => {
+                info!("Interrupted");
+                break;
+            },
+            event = next_verified(&mut verification) => {
+                if let Some(verification) = verification.as_mut() {
+                    verification.observe(event, false);
+                }
+            },
+            _ = ticks.tick() => {
+                if Instant::now() >= next_fault {
+                    faults += 1;
+                    daemon.sensor.lock().await.inject_fault(random_fault(&mut rng, faults));
+                    next_fault = Instant::now() + soak.fault_every.mul_f32(rng.gen_range(0.5..1.5));
+                }
+
+                let calibrated = daemon.sensor.lock().await.calibration_phase().is_calibrated();
+                if calibrated && last_reset.elapsed() >= soak.recalibrate_every {
+                    last_reset = Instant::now();
+                    match verification.as_mut() {
+                        Some(verification) => verification.reset_calibration().await,
+                        None => {
+                            let mut sensor = daemon.sensor.lock().await;
+                            if let Err(e) = sensor.control_calibration(CalibrationAction::Reset).await {
+                                warn!("Failed to reset calibration: {}", e);
+                            }
+                        },
+                    }
+                }
+
+                if let Some(verification) = verification.as_mut() {
+                    if let Some(rss) = resident_memory() {
+                        verification.verifier.record_memory(rss, started.elapsed() >= soak.warmup);
+                    }
+                    verification.list_self().await;
+                }
+            },
+        }
+    }
+
+    daemon.sensor.lock().await.insert_marker("soak test finished");
+    let report = match verification {
+        Some(verification) => {
+            let _ = daemon.sensor.lock().await.stop().await;
+            Some(verification.drain().await)
+        },
+        None => None,
+    };
+    daemon.stop().await;
+    info!("Soak test completed after {:.1}s", started.elapsed().as_secs_f32());
+    Ok(report)
+}
+
+/// Next event of the verifying stream; never resolves without one
+async fn next_verified(verification: &mut Option<Verification>) -> Option<Result<SensorEvent, Status>> {
+    match verification {
+        Some(verification) => verification.next_event().await,
+        None => std::future::pending().await,
+    }
+}
+
+fn print_report(report: &SoakReport) {
+    println!(
+        "Events: {} ({} scores, {} calibration updates, {} faults, {} markers)",
+        report.events, report.scores, report.calibration_updates, report.faults, report.markers
+    );
+    println!("Dropped: {}", report.dropped);
+    println!("Recalibrations: {}", report.recalibrations);
+    if let (Some(baseline), Some(peak)) = (report.rss_baseline, report.rss_peak) {
+        println!("Resident memory: {} KiB after warm-up, {} KiB at peak", baseline / 1024, peak / 1024);
+    }
+    if report.passed() {
+        println!("PASSED");
+    } else {
+        println!("FAILED: {} invariant violations", report.violations.len());
+        for violation in &report.violations {
+            println!("  {}", violation);
+        }
+    }
 }
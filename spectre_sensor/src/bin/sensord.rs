@@ -0,0 +1,249 @@
+//! Sensor daemon: the camera pipeline, its gRPC service and the metrics
+//! server in one process
+//!
+//! Settings come from `SensorConfig::from_env()`, with any flags given on
+//! the command line on top. The daemon exits non-zero when the camera or the
+//! models cannot be initialized; with `--mock`, synthetic ones stand in for
+//! them. The sensor starts with the first event stream, or at once with
+//! `--eager-start`, and every stream shares it. On SIGINT or SIGTERM it
+//! stops the sensor, lets open connections close and removes its Unix
+//! socket.
+//!
+//! ```text
+//! cargo run -p spectre-sensor --bin sensord -- --socket /tmp/spectre_sensor.sock
+//! cargo run -p spectre-sensor --bin sensord -- --mock
+//! cargo run -p spectre-sensor --bin sensord -- --mock --log-format json
+//! ```
+
+use clap::Parser;
+use spectre_sensor::{
+    bug_report::LogRingBuffer,
+    grpc_server::{serve_grpc_with_shutdown, SensorServiceImpl},
+    logging::{LogControl, LogError, LogFormat},
+    metrics::{start_metrics_server, SensorMetrics},
+    mock_patterns::MockPattern,
+    EmotionSensor, SensorConfig, SensorError, SensorTransport,
+};
+use std::path::PathBuf;
+use std::process::ExitCode;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt, Registry};
+
+/// How long open connections get to close once shutdown starts
+const SHUTDOWN_GRACE: Duration = Duration::from_secs(5);
+
+type BoxError = Box<dyn std::error::Error + Send + Sync>;
+
+#[derive(Parser, Debug)]
+#[command(name = "sensord")]
+#[command(about = "Run the fear sensor with its gRPC and metrics servers")]
+struct Args {
+    /// Camera index (overrides SPECTRE_CAMERA_ID)
+    #[arg(long)]
+    camera_id: Option<u32>,
+
+    /// Emotion model (ONNX) to load instead of the default
+    #[arg(long)]
+    model_path: Option<String>,
+
+    /// ONNX Runtime threads (overrides SPECTRE_THREADS)
+    #[arg(long)]
+    threads: Option<usize>,
+
+    /// gRPC address: socket path, pipe:<name> or host:port (overrides SPECTRE_GRPC_SOCKET)
+    #[arg(long)]
+    socket: Option<String>,
+
+    /// Port of /metrics, /health and /dashboard (overrides SPECTRE_METRICS_PORT)
+    #[arg(long)]
+    metrics_port: Option<u16>,
+
+    /// Freeze the baseline once the initial calibration completes
+    #[arg(long)]
+    freeze_calibration: bool,
+
+    /// Save the baseline here and restore it on restart (overrides SPECTRE_CALIBRATION_CACHE)
+    #[arg(long)]
+    calibration_cache: Option<PathBuf>,
+
+    /// Run on synthetic frames and models playing a sine wave of fear,
+    /// instead of the camera and models
+    #[arg(long)]
+    mock: bool,
+
+    /// Start the sensor now instead of with the first event stream
+    /// (overrides SPECTRE_EAGER_START)
+    #[arg(long)]
+    eager_start: bool,
+
+    /// Log records as pretty lines or JSON objects, one per line
+    /// (overrides SPECTRE_LOG_FORMAT)
+    #[arg(long)]
+    log_format: Option<LogFormat>,
+}
+
+impl Args {
+    /// `config` with the flags given on the command line applied
+    fn apply(&self, mut config: SensorConfig) -> SensorConfig {
+        if let Some(camera_id) = self.camera_id {
+            config.camera_id = camera_id;
+        }
+        if let Some(model_path) = &self.model_path {
+            config = config.with_model_path(model_path.clone());
+        }
+        if let Some(threads) = self.threads {
+            config.onnx_threads = threads;
+        }
+        if let Some(socket) = &self.socket {
+            config.grpc_socket_path = socket.clone();
+        }
+        if let Some(port) = self.metrics_port {
+            config.metrics_port = port;
+        }
+        config.freeze_calibration |= self.freeze_calibration;
+        if let Some(path) = &self.calibration_cache {
+            config.calibration_cache_path = Some(path.clone());
+        }
+        if self.mock && config.mock.is_none() {
+            config.mock = Some(MockPattern::default());
+        }
+        config.eager_start |= self.eager_start;
+        if let Some(format) = self.log_format {
+            config.logging.format = format;
+        }
+        config
+    }
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    let args = Args::parse();
+    let config = args.apply(SensorConfig::from_env());
+
+    if let Err(e) = run(config).await {
+        // Logging may not be installed yet
+        tracing::error!("{}", e);
+        eprintln!("sensord: {}", e);
+        return ExitCode::FAILURE;
+    }
+    ExitCode::SUCCESS
+}
+
+async fn run(config: SensorConfig) -> Result<(), BoxError> {
+    let (logs, log_lines) = install_logging(&config)?;
+    config.validate()?;
+    let transport = SensorTransport::from_config(&config)?;
+    let metrics = Arc::new(SensorMetrics::new()?);
+    let (stop, stopped) = tokio::sync::watch::channel(false);
+    let shutdown = async move {
+        let mut stopped = stopped;
+        let _ = stopped.wait_for(|stopped| *stopped).await;
+    };
+
+    let mut sensor = EmotionSensor::new(config.clone())
+        .with_log_buffer(log_lines)
+        .with_metrics(Arc::clone(&metrics));
+    sensor.initialize().await?;
+    // The sensor retries a camera that failed to open on start; a daemon
+    // should rather fail now, where the operator is looking
+    if sensor.get_state().init.is_some_and(|init| init.camera.is_none()) {
+        return Err(SensorError::CameraInit(format!("camera {} could not be opened", config.camera_id)).into());
+    }
+    let (history, liveness) = (sensor.metrics_history(), sensor.liveness());
+    let service = SensorServiceImpl::new(sensor)
+        .with_metrics(Arc::clone(&metrics))
+        .with_log_control(logs);
+    if config.eager_start {
+        service.start_sensor().await?;
+    }
+    let sensor = service.sensor();
+    let mut grpc = tokio::spawn(serve_grpc_with_shutdown(transport.clone(), service, shutdown));
+    let mut metrics_server = tokio::spawn(start_metrics_server(config.metrics_port, metrics, history, liveness));
+
+    tokio::select! {
+        signal = shutdown_signal() => tracing::info!("{} received, shutting down", signal),
+        result = &mut grpc => return Err(server_exit("gRPC server", result)),
+        result = &mut metrics_server => return Err(server_exit("Metrics server", result)),
+    }
+
+    // Stopping the sensor ends the event streams, so their connections close
+    if let Err(e) = sensor.lock().await.stop().await {
+        tracing::warn!("Failed to stop the sensor: {}", e);
+    }
+    stop.send_replace(true);
+    match tokio::time::timeout(SHUTDOWN_GRACE, grpc).await {
+        Ok(Ok(Ok(()))) => {}
+        Ok(result) => tracing::warn!("{}", server_exit("gRPC server", result)),
+        Err(_) => tracing::warn!("Connections still open after {:?}, closing them", SHUTDOWN_GRACE),
+    }
+    metrics_server.abort();
+    remove_socket(&transport);
+
+    tracing::info!("Sensor daemon stopped");
+    Ok(())
+}
+
+/// Console logging under `SetLogLevel` control, plus the bug report's log ring
+fn install_logging(config: &SensorConfig) -> Result<(Arc<LogControl>, LogRingBuffer), LogError> {
+    let (control, console) = LogControl::layer::<Registry>(&config.logging)?;
+    let lines = LogRingBuffer::new(config.bug_report.max_log_lines);
+    tracing_subscriber::registry()
+        .with(console)
+        .with(lines.clone())
+        .try_init()
+        .map_err(|e| LogError::Install(e.to_string()))?;
+    Ok((Arc::new(control), lines))
+}
+
+/// Wait for Ctrl-C, or SIGTERM on Unix, returning the signal's name
+async fn shutdown_signal() -> &'static str {
+    #[cfg(unix)]
+    {
+        use tokio::signal::unix::{signal, SignalKind};
+        match signal(SignalKind::terminate()) {
+            Ok(mut terminate) => tokio::select! {
+                _ = ctrl_c() => "SIGINT",
+                _ = terminate.recv() => "SIGTERM",
+            },
+            Err(e) => {
+                tracing::warn!("Cannot listen for SIGTERM: {}", e);
+                ctrl_c().await;
+                "SIGINT"
+            }
+        }
+    }
+    #[cfg(not(unix))]
+    {
+        ctrl_c().await;
+        "Ctrl-C"
+    }
+}
+
+/// Wait for Ctrl-C; without a handler, wait forever rather than shut down at once
+async fn ctrl_c() {
+    if let Err(e) = tokio::signal::ctrl_c().await {
+        tracing::warn!("Cannot listen for Ctrl-C: {}", e);
+        std::future::pending::<()>().await;
+    }
+}
+
+/// Error for a server task that ended before shutdown was asked for
+fn server_exit(name: &str, result: Result<Result<(), BoxError>, tokio::task::JoinError>) -> BoxError {
+    match result {
+        Ok(Ok(())) => format!("{} stopped unexpectedly", name).into(),
+        Ok(Err(e)) => format!("{} failed: {}", name, e).into(),
+        Err(e) => format!("{} panicked: {}", name, e).into(),
+    }
+}
+
+/// Remove the Unix socket so the next daemon starts without a stale one
+fn remove_socket(transport: &SensorTransport) {
+    if let SensorTransport::Unix(path) = transport {
+        match std::fs::remove_file(path) {
+            Ok(()) => tracing::info!("Removed {}", path.display()),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => tracing::warn!("Failed to remove {}: {}", path.display(), e),
+        }
+    }
+}
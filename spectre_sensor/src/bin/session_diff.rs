@@ -0,0 +1,85 @@
+//! Compare two recorded sensor sessions for A/B content testing
+//!
+//! Loads two JSONL traces, aligns them by duration or by named markers and
+//! prints per-window fear statistics and an effect size (B relative to A).
+//! Game event logs given with `--game-log-a`/`--game-log-b` are mapped onto
+//! the traces through their clock sync estimates, so game events can be used
+//! as alignment markers.
+
+use spectre_sensor::session_diff::{diff_sessions, Alignment, DiffOptions, GameLog, SessionTrace};
+use clap::Parser;
+use std::path::PathBuf;
+
+#[derive(Parser)]
+#[command(name = "session_diff")]
+#[command(about = "Diff the fear statistics of two recorded sessions")]
+struct Cli {
+    /// Baseline session trace (JSONL)
+    session_a: PathBuf,
+
+    /// Variant session trace (JSONL)
+    session_b: PathBuf,
+
+    /// Number of equal-duration windows (ignored when --markers is given)
+    #[arg(short, long, default_value = "10")]
+    windows: usize,
+
+    /// Comma-separated marker labels to align on, in order
+    #[arg(short, long, value_delimiter = ',')]
+    markers: Vec<String>,
+
+    /// Game event log (JSONL) recorded alongside session A
+    #[arg(long)]
+    game_log_a: Option<PathBuf>,
+
+    /// Game event log (JSONL) recorded alongside session B
+    #[arg(long)]
+    game_log_b: Option<PathBuf>,
+
+    /// Number of bootstrap resamples for the effect size interval
+    #[arg(long, default_value = "1000")]
+    bootstrap: usize,
+
+    /// Seed for the bootstrap RNG
+    #[arg(long, default_value = "42")]
+    seed: u64,
+
+    /// Print the report as JSON instead of text
+    #[arg(long)]
+    json: bool,
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let cli = Cli::parse();
+
+    let mut session_a = SessionTrace::load(&cli.session_a)?;
+    let mut session_b = SessionTrace::load(&cli.session_b)?;
+    if let Some(path) = &cli.game_log_a {
+        session_a.merge_game_log(&GameLog::load(path)?)?;
+    }
+    if let Some(path) = &cli.game_log_b {
+        session_b.merge_game_log(&GameLog::load(path)?)?;
+    }
+
+    let alignment = if cli.markers.is_empty() {
+        Alignment::Duration { windows: cli.windows }
+    } else {
+        Alignment::Markers(cli.markers)
+    };
+
+    let options = DiffOptions {
+        alignment,
+        bootstrap_resamples: cli.bootstrap,
+        seed: cli.seed,
+    };
+
+    let report = diff_sessions(&session_a, &session_b, &options)?;
+
+    if cli.json {
+        println!("{}", report.to_json());
+    } else {
+        print!("{}", report.to_text());
+    }
+
+    Ok(())
+}
@@ -0,0 +1,667 @@
+//! Command-line control of a running sensor daemon
+//!
+//! Connects to the daemon's gRPC service over any supported transport
+//! (`--socket` or `--tcp`, defaulting to the configured socket path) and runs
+//! one command against it. Exits non-zero when the daemon cannot be reached,
+//! a call fails or `wait-calibrated` times out; `--format json` prints every
+//! result as JSON for scripts.
+//!
+//! ```text
+//! spectre_ctl status
+//! spectre_ctl watch --json | jq .normalized_fear
+//! spectre_ctl calibrate reset
+//! spectre_ctl wait-calibrated --timeout 60
+//! ```
+
+use spectre_sensor::{
+    delta::DeltaConfig,
+    grpc_client::SensorClient,
+    proto::{sensor_event, CalibrationProgress, CalibrationResponse, CalibrationState, ClientInfo, EventType, GetLogsResponse, ListClientsResponse, LogLevel, MeasureResponse, MetricsHistoryResponse, PowerProfile, Score, SensorCapability, StatusResponse, UpdateConfigResponse},
+    SensorConfig, SensorTransport,
+};
+use clap::{Parser, Subcommand, ValueEnum};
+use futures::StreamExt;
+use std::io::Write;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+#[derive(Parser)]
+#[command(name = "spectre_ctl")]
+#[command(about = "Control a running sensor daemon")]
+struct Cli {
+    /// Daemon address: socket path, pipe:<name> or host:port (defaults to SPECTRE_GRPC_SOCKET or the configured path)
+    #[arg(short, long, visible_alias = "socket", global = true)]
+    address: Option<String>,
+
+    /// Daemon TCP address, host:port
+    #[arg(long, global = true, conflicts_with = "address")]
+    tcp: Option<String>,
+
+    /// Output format; `json` does what each command's `--json` does
+    #[arg(long, value_enum, global = true, default_value = "text")]
+    format: Format,
+
+    #[command(subcommand)]
+    command: Commands,
+}
+
+#[derive(Clone, Copy, PartialEq, ValueEnum)]
+enum Format {
+    Text,
+    Json,
+}
+
+#[derive(Subcommand)]
+enum Commands {
+    /// Show the sensor's state, calibration baseline and performance
+    Status {
+        /// Print the status as JSON instead of text
+        #[arg(long)]
+        json: bool,
+    },
+    /// Print fear scores as they arrive
+    Watch {
+        /// Stop after this many scores
+        #[arg(short = 'n', long)]
+        count: Option<usize>,
+
+        /// Print scores as JSON lines instead of a live-updating line
+        #[arg(long)]
+        json: bool,
+    },
+    /// Start, freeze, unfreeze or reset calibration
+    Calibrate {
+        #[arg(value_enum)]
+        action: CalibrateAction,
+    },
+    /// Wait until calibration completes
+    WaitCalibrated {
+        /// Give up after this many seconds
+        #[arg(short, long, default_value = "60")]
+        timeout: f64,
+    },
+    /// Capture and score one frame
+    Measure {
+        /// Give up when no face appears within this many milliseconds
+        #[arg(short, long, default_value = "5000")]
+        timeout_ms: u64,
+
+        /// Print the score as JSON instead of text
+        #[arg(long)]
+        json: bool,
+    },
+    /// List the open event streams and what each subscribed to
+    Clients {
+        /// Print the listing as JSON instead of a table
+        #[arg(long)]
+        json: bool,
+    },
+    /// Show recent FPS and inference latency as sparklines
+    Metrics {
+        /// How far back to look, in seconds
+        #[arg(short, long, default_value = "3600")]
+        since_secs: u64,
+
+        /// Print the samples as JSON instead of sparklines
+        #[arg(long)]
+        json: bool,
+    },
+    /// Override the frame rate and emotion interval, winning over the power governor
+    Rates {
+        /// Frames per second, 1 to 120
+        #[arg(long)]
+        fps: Option<f32>,
+
+        /// Run emotion inference every this many frames
+        #[arg(long)]
+        emotion_interval: Option<u32>,
+
+        /// Drop earlier overrides and follow the power governor again
+        #[arg(long)]
+        clear: bool,
+    },
+    /// Print recent log records kept by the daemon
+    Logs {
+        /// Only records after this sequence number
+        #[arg(short, long, default_value = "0")]
+        since: u64,
+
+        /// Least severe level to print: trace, debug, info, warn or error
+        #[arg(short, long, value_parser = parse_log_level, default_value = "trace")]
+        level: LogLevel,
+
+        /// Most records to print per request
+        #[arg(short = 'n', long, default_value = "100")]
+        limit: u32,
+
+        /// Keep polling for new records
+        #[arg(short, long)]
+        follow: bool,
+
+        /// Print records as JSON lines instead of text
+        #[arg(long)]
+        json: bool,
+    },
+    /// Replace the daemon's log filter, e.g. `debug` or `info,spectre_sensor::sensor=trace`
+    LogLevel {
+        filter: String,
+    },
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum CalibrateAction {
+    Start,
+    Freeze,
+    Unfreeze,
+    Reset,
+}
+
+/// Widest bar `watch` draws for the fear level
+const FEAR_BAR_WIDTH: usize = 20;
+
+/// Widest sparkline drawn; longer histories are averaged down to fit
+const SPARKLINE_WIDTH: usize = 60;
+
+/// Pause between polls of `logs --follow`
+const FOLLOW_INTERVAL: Duration = Duration::from_millis(500);
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let cli = Cli::parse();
+    let as_json = |json: bool| json || cli.format == Format::Json;
+
+    let transport: SensorTransport = match (&cli.address, &cli.tcp) {
+        (_, Some(tcp)) => format!("tcp://{}", tcp).parse()?,
+        (Some(address), None) => address.parse()?,
+        (None, None) => SensorTransport::from_config(&SensorConfig::from_env())?,
+    };
+    let mut client = SensorClient::connect(&transport).await?;
+
+    match cli.command {
+        Commands::Status { json } => {
+            let status = client.get_status().await?;
+            print_status(&status, as_json(json))?;
+        }
+        Commands::Watch { count, json } => {
+            let json = as_json(json);
+            let mut events = client.stream_scores().await?;
+            let mut seen = 0;
+            while count.is_none_or(|count| seen < count) {
+                let Some(event) = events.next().await else {
+                    return Err("The daemon closed the score stream".into());
+                };
+                let event = event?;
+                if let Some(sensor_event::Event::Score(score)) = event.event {
+                    print_score(&score, event.timestamp_us, json)?;
+                    seen += 1;
+                }
+            }
+            if !json {
+                println!();
+            }
+        }
+        Commands::Calibrate { action } => {
+            let response = match action {
+                CalibrateAction::Start => client.start_calibration().await?,
+                CalibrateAction::Freeze => client.freeze_calibration().await?,
+                CalibrateAction::Unfreeze => client.unfreeze_calibration().await?,
+                CalibrateAction::Reset => client.reset_calibration().await?,
+            };
+            print_calibration_response(&response, as_json(false))?;
+        }
+        Commands::WaitCalibrated { timeout } => {
+            let timeout = Duration::try_from_secs_f64(timeout).map_err(|e| format!("Invalid timeout {}: {}", timeout, e))?;
+            if !client.wait_for_calibration(timeout).await? {
+                return Err(format!("Not calibrated after {:?}", timeout).into());
+            }
+            if as_json(false) {
+                println!("{}", serde_json::json!({ "calibrated": true }));
+            } else {
+                println!("Calibrated");
+            }
+        }
+        Commands::Measure { timeout_ms, json } => {
+            let response = client.measure_once(Duration::from_millis(timeout_ms)).await?;
+            print_measurement(&response, as_json(json))?;
+        }
+        Commands::Clients { json } => {
+            let response = client.list_clients().await?;
+            print_clients(&response, as_json(json))?;
+        }
+        Commands::Metrics { since_secs, json } => {
+            let now_us = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_micros() as u64;
+            let from_us = now_us.saturating_sub(since_secs.saturating_mul(1_000_000)).max(1);
+            let response = client.get_metrics_history(from_us, 0).await?;
+            print_metrics(&response, as_json(json))?;
+        }
+        Commands::Rates { fps, emotion_interval, clear } => {
+            let response = client.update_config(fps, emotion_interval, clear).await?;
+            print_rates(&response, as_json(false))?;
+        }
+        Commands::Logs { mut since, level, limit, follow, json } => loop {
+            let page = client.get_logs(since, level, limit).await?;
+            print_logs(&page, as_json(json))?;
+            since = page.next_since;
+            if page.more {
+                continue;
+            }
+            if !follow {
+                break;
+            }
+            tokio::time::sleep(FOLLOW_INTERVAL).await;
+        },
+        Commands::LogLevel { filter } => {
+            let response = client.set_log_level(filter).await?;
+            if as_json(false) {
+                println!("{}", serde_json::json!({ "filter": response.filter, "previous_filter": response.previous_filter }));
+            } else {
+                println!("Log filter: {} (was {})", response.filter, response.previous_filter);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn print_measurement(response: &MeasureResponse, json: bool) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let score = response.score.clone().unwrap_or_default();
+    let capability = SensorCapability::try_from(score.capability).unwrap_or(SensorCapability::Unspecified);
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&score_json(&score, response.timestamp_us))?);
+        return Ok(());
+    }
+
+    println!("Fear:        {:.3}{}", score.normalized_fear, if score.calibrated { "" } else { " (uncalibrated)" });
+    println!("Fear logit:  {:.3}", score.raw_fear_logit);
+    println!("Confidence:  {:.2}", score.confidence);
+    println!("Latency:     {:.1} ms", score.inference_latency_us as f64 / 1000.0);
+    println!("Capability:  {}", capability.as_str_name());
+    println!("Timestamp:   {} us", response.timestamp_us);
+    Ok(())
+}
+
+fn print_status(status: &StatusResponse, json: bool) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let metrics = status.metrics.unwrap_or_default();
+    let capability = SensorCapability::try_from(status.capability).unwrap_or(SensorCapability::Unspecified);
+    let profile = PowerProfile::try_from(status.power_profile).unwrap_or(PowerProfile::Unspecified);
+
+    if json {
+        let value = serde_json::json!({
+            "running": status.running,
+            "subscribers": status.subscribers,
+            "calibration": status.calibration.as_ref().map(calibration_json),
+            "current_fps": metrics.current_fps,
+            "target_fps": status.target_fps,
+            "emotion_interval": status.emotion_interval,
+            "rates_overridden": status.rates_overridden,
+            "p95_inference_latency_us": metrics.p95_inference_latency_us,
+            "session_p95_inference_latency_us": metrics.session_p95_inference_latency_us,
+            "dropped_frames": metrics.dropped_frames,
+            "calibration_drift": metrics.calibration_drift,
+            "quality_level": metrics.quality_level,
+            "capability": capability.as_str_name(),
+            "power_profile": profile.as_str_name(),
+            "camera": status.camera.map(|camera| serde_json::json!({
+                "width": camera.width,
+                "height": camera.height,
+                "fps": camera.fps,
+            })),
+            "config": status.config.map(|config| serde_json::json!({
+                "camera_id": config.camera_id,
+                "target_fps": config.target_fps,
+                "face_confidence_threshold": config.face_confidence_threshold,
+                "calibration_frozen": config.calibration_frozen,
+                "onnx_threads": config.onnx_threads,
+            })),
+            "last_error": status.last_error.as_ref().map(|fault| &fault.message),
+            "stopped_reason": status.stopped_reason,
+        });
+        println!("{}", serde_json::to_string_pretty(&value)?);
+        return Ok(());
+    }
+
+    println!("Running:      {}", if status.running { "yes" } else { "no" });
+    if !status.stopped_reason.is_empty() {
+        println!("Stopped:      {}", status.stopped_reason);
+    }
+    println!("Streams:      {}", status.subscribers);
+    if let Some(calibration) = &status.calibration {
+        print_calibration(calibration);
+    }
+    println!(
+        "FPS:          {:.1} (target {}{})",
+        metrics.current_fps,
+        status.target_fps,
+        if status.rates_overridden { ", overridden" } else { "" }
+    );
+    println!(
+        "p95 latency:  {:.1} ms (session {:.1} ms)",
+        metrics.p95_inference_latency_us as f64 / 1000.0,
+        metrics.session_p95_inference_latency_us as f64 / 1000.0
+    );
+    println!("Dropped:      {} frames", metrics.dropped_frames);
+    println!("Quality:      level {}", metrics.quality_level);
+    println!("Capability:   {}", capability.as_str_name());
+    println!("Power:        {}", profile.as_str_name());
+    if let Some(camera) = status.camera {
+        println!("Camera:       {}x{} at {} fps", camera.width, camera.height, camera.fps);
+    }
+    if let Some(fault) = &status.last_error {
+        println!("Last error:   {}", fault.message);
+    }
+    Ok(())
+}
+
+fn print_calibration(calibration: &CalibrationProgress) {
+    println!("Calibration:  {} ({:.0}%)", calibration_state(calibration), calibration.progress * 100.0);
+    if !calibration.failure.is_empty() {
+        println!("Failure:      {}", calibration.failure);
+    }
+    if let Some(baseline) = &calibration.baseline {
+        println!(
+            "Baseline:     mean {:.3}, std dev {:.3}, {} samples",
+            baseline.mean, baseline.std_dev, baseline.sample_count
+        );
+    }
+}
+
+fn print_calibration_response(response: &CalibrationResponse, json: bool) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    if !response.success {
+        return Err(response.error_message.clone().unwrap_or_else(|| "Calibration control failed".to_string()).into());
+    }
+    let Some(phase) = &response.phase else {
+        return Ok(());
+    };
+    if json {
+        println!("{}", serde_json::to_string_pretty(&calibration_json(phase))?);
+    } else {
+        print_calibration(phase);
+    }
+    Ok(())
+}
+
+/// One score as a JSON line, or the live line redrawn in place
+fn print_score(score: &Score, timestamp_us: u64, json: bool) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let mut stdout = std::io::stdout().lock();
+    if json {
+        writeln!(stdout, "{}", serde_json::to_string(&score_json(score, timestamp_us))?)?;
+        return Ok(());
+    }
+
+    let filled = ((score.normalized_fear.clamp(0.0, 1.0) * FEAR_BAR_WIDTH as f32).round() as usize).min(FEAR_BAR_WIDTH);
+    let bar = format!("{}{}", "█".repeat(filled), " ".repeat(FEAR_BAR_WIDTH - filled));
+    let state = match (score.face_present, score.calibrated) {
+        (Some(false), _) => "no face",
+        (_, false) => "uncalibrated",
+        _ => "calibrated",
+    };
+    write!(
+        stdout,
+        "\r\x1b[KFear {:.3} ▕{}▏ confidence {:.2}  startle {:.2}  {}",
+        score.normalized_fear, bar, score.confidence, score.startle, state
+    )?;
+    stdout.flush()?;
+    Ok(())
+}
+
+fn score_json(score: &Score, timestamp_us: u64) -> serde_json::Value {
+    let capability = SensorCapability::try_from(score.capability).unwrap_or(SensorCapability::Unspecified);
+    serde_json::json!({
+        "timestamp_us": timestamp_us,
+        "normalized_fear": score.normalized_fear,
+        "raw_fear_logit": score.raw_fear_logit,
+        "confidence": score.confidence,
+        "calibrated": score.calibrated,
+        "startle": score.startle,
+        "face_present": score.face_present,
+        "emotion_logits": score.emotion_logits,
+        "inference_latency_us": score.inference_latency_us,
+        "capability": capability.as_str_name(),
+    })
+}
+
+fn calibration_json(calibration: &CalibrationProgress) -> serde_json::Value {
+    serde_json::json!({
+        "state": calibration_state(calibration),
+        "progress": calibration.progress,
+        "completed": calibration.completed,
+        "quality": calibration.quality,
+        "failure": calibration.failure,
+        "baseline": calibration.baseline.as_ref().map(|baseline| serde_json::json!({
+            "mean": baseline.mean,
+            "std_dev": baseline.std_dev,
+            "sample_count": baseline.sample_count,
+        })),
+    })
+}
+
+/// Calibration phase, e.g. `COLLECTING`; derived from progress for older daemons
+fn calibration_state(calibration: &CalibrationProgress) -> &'static str {
+    let state = match CalibrationState::try_from(calibration.state).unwrap_or(CalibrationState::Unspecified) {
+        CalibrationState::Unspecified if calibration.completed => CalibrationState::Calibrated,
+        CalibrationState::Unspecified if calibration.progress > 0.0 => CalibrationState::Collecting,
+        CalibrationState::Unspecified => CalibrationState::Idle,
+        state => state,
+    };
+    state.as_str_name().trim_start_matches("CALIBRATION_STATE_")
+}
+
+fn print_clients(response: &ListClientsResponse, json: bool) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    if json {
+        let clients: Vec<_> = response
+            .clients
+            .iter()
+            .map(|client| {
+                serde_json::json!({
+                    "client_id": client.client_id,
+                    "connected_at_us": client.connected_at_us,
+                    "peer": client.peer,
+                    "event_types": event_type_names(client),
+                    "delta": client.request.as_ref().is_some_and(|request| request.delta),
+                    "queue_depth": client.queue_depth,
+                    "events_delivered": client.events_delivered,
+                    "events_dropped": client.events_dropped,
+                    "last_activity_us": client.last_activity_us,
+                })
+            })
+            .collect();
+        println!("{}", serde_json::to_string_pretty(&clients)?);
+        return Ok(());
+    }
+
+    if response.clients.is_empty() {
+        println!("No open event streams");
+        return Ok(());
+    }
+
+    let now_us = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_micros() as u64;
+    let ago = |timestamp_us: u64| format!("{:.1}s", now_us.saturating_sub(timestamp_us) as f64 / 1e6);
+    println!(
+        "{:>4}  {:<28} {:>9}  {:<32} {:<14} {:>5} {:>10} {:>8} {:>8}",
+        "ID", "PEER", "CONNECTED", "EVENTS", "OPTIONS", "QUEUE", "DELIVERED", "DROPPED", "IDLE"
+    );
+    for client in &response.clients {
+        println!(
+            "{:>4}  {:<28} {:>9}  {:<32} {:<14} {:>5} {:>10} {:>8} {:>8}",
+            client.client_id,
+            client.peer,
+            ago(client.connected_at_us),
+            event_type_names(client).join(","),
+            stream_options(client),
+            client.queue_depth,
+            client.events_delivered,
+            client.events_dropped,
+            ago(client.last_activity_us),
+        );
+    }
+    Ok(())
+}
+
+fn print_metrics(response: &MetricsHistoryResponse, json: bool) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    if json {
+        let samples: Vec<_> = response
+            .samples
+            .iter()
+            .map(|sample| {
+                serde_json::json!({
+                    "timestamp_us": sample.timestamp_us,
+                    "fps": sample.fps,
+                    "p95_inference_latency_us": sample.p95_inference_latency_us,
+                    "dropped_frames": sample.dropped_frames,
+                    "calibration_drift": sample.calibration_drift,
+                })
+            })
+            .collect();
+        let value = serde_json::json!({
+            "interval_ms": response.interval_ms,
+            "capacity": response.capacity,
+            "samples": samples,
+        });
+        println!("{}", serde_json::to_string_pretty(&value)?);
+        return Ok(());
+    }
+
+    let (Some(first), Some(last)) = (response.samples.first(), response.samples.last()) else {
+        println!("No metrics samples yet");
+        return Ok(());
+    };
+
+    let fps: Vec<f64> = response.samples.iter().map(|sample| sample.fps as f64).collect();
+    let latency: Vec<f64> = response
+        .samples
+        .iter()
+        .map(|sample| sample.p95_inference_latency_us as f64 / 1000.0)
+        .collect();
+    println!(
+        "{} samples over {:.0}s, one every {:.0}s",
+        response.samples.len(),
+        (last.timestamp_us - first.timestamp_us) as f64 / 1e6,
+        response.interval_ms as f64 / 1000.0
+    );
+    println!("FPS          {}  {:.1} (min {:.1}, max {:.1})", sparkline(&fps), last.fps, min(&fps), max(&fps));
+    println!(
+        "p95 latency  {}  {:.1} ms (min {:.1}, max {:.1})",
+        sparkline(&latency),
+        latency[latency.len() - 1],
+        min(&latency),
+        max(&latency)
+    );
+    println!("Dropped      {} frames since start", last.dropped_frames);
+    Ok(())
+}
+
+fn print_rates(response: &UpdateConfigResponse, json: bool) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let profile = PowerProfile::try_from(response.power_profile).unwrap_or(PowerProfile::Unspecified);
+    if json {
+        let value = serde_json::json!({
+            "target_fps": response.target_fps,
+            "emotion_interval": response.emotion_interval,
+            "power_profile": profile.as_str_name(),
+            "overridden": response.overridden,
+        });
+        println!("{}", serde_json::to_string_pretty(&value)?);
+        return Ok(());
+    }
+
+    println!("Target FPS:        {}", response.target_fps);
+    println!("Emotion interval:  every {} frames", response.emotion_interval);
+    println!("Power profile:     {}", profile.as_str_name());
+    println!("Overridden:        {}", if response.overridden { "yes" } else { "no" });
+    Ok(())
+}
+
+fn print_logs(page: &GetLogsResponse, json: bool) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    if page.dropped > 0 {
+        eprintln!("({} records were overwritten before they could be read)", page.dropped);
+    }
+    for record in &page.records {
+        let level = LogLevel::try_from(record.level).unwrap_or(LogLevel::Unspecified);
+        let level = level.as_str_name().trim_start_matches("LOG_LEVEL_");
+        if json {
+            let fields: serde_json::Map<String, serde_json::Value> = record
+                .fields
+                .iter()
+                .map(|field| (field.name.clone(), field.value.clone().into()))
+                .collect();
+            let value = serde_json::json!({
+                "sequence": record.sequence,
+                "timestamp_us": record.timestamp_us,
+                "level": level,
+                "target": record.target,
+                "message": record.message,
+                "fields": fields,
+            });
+            println!("{}", serde_json::to_string(&value)?);
+            continue;
+        }
+
+        let fields: String = record.fields.iter().map(|field| format!(" {}={}", field.name, field.value)).collect();
+        println!(
+            "{}.{:06} {:>5} {}: {}{}",
+            record.timestamp_us / 1_000_000,
+            record.timestamp_us % 1_000_000,
+            level,
+            record.target,
+            record.message,
+            fields
+        );
+    }
+    Ok(())
+}
+
+/// `warn` and the like as a proto log level
+fn parse_log_level(level: &str) -> Result<LogLevel, String> {
+    LogLevel::from_str_name(&format!("LOG_LEVEL_{}", level.to_ascii_uppercase()))
+        .filter(|&level| level != LogLevel::Unspecified)
+        .ok_or_else(|| format!("Unknown log level: {} (expected trace, debug, info, warn or error)", level))
+}
+
+/// Unicode block sparkline of `values`, scaled between their minimum and maximum
+fn sparkline(values: &[f64]) -> String {
+    const BARS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+    let chunk = values.len().div_ceil(SPARKLINE_WIDTH);
+    let points: Vec<f64> = values
+        .chunks(chunk.max(1))
+        .map(|chunk| chunk.iter().sum::<f64>() / chunk.len() as f64)
+        .collect();
+    let (low, high) = (min(&points), max(&points));
+    points
+        .iter()
+        .map(|value| {
+            let level = if high > low { (value - low) / (high - low) } else { 0.5 };
+            BARS[((level * (BARS.len() - 1) as f64).round() as usize).min(BARS.len() - 1)]
+        })
+        .collect()
+}
+
+fn min(values: &[f64]) -> f64 {
+    values.iter().copied().fold(f64::INFINITY, f64::min)
+}
+
+fn max(values: &[f64]) -> f64 {
+    values.iter().copied().fold(f64::NEG_INFINITY, f64::max)
+}
+
+/// Requested event types, e.g. `score`; `all` without filters
+fn event_type_names(client: &ClientInfo) -> Vec<String> {
+    let event_types = client.request.as_ref().map(|request| request.event_types.as_slice()).unwrap_or_default();
+    if event_types.is_empty() {
+        return vec!["all".to_string()];
+    }
+    event_types
+        .iter()
+        .map(|&value| match EventType::try_from(value) {
+            Ok(event_type) => event_type.as_str_name().trim_start_matches("EVENT_TYPE_").to_ascii_lowercase(),
+            Err(_) => value.to_string(),
+        })
+        .collect()
+}
+
+/// Stream options the client asked for, e.g. `delta/30`
+fn stream_options(client: &ClientInfo) -> String {
+    match client.request.as_ref().and_then(DeltaConfig::from_request) {
+        Some(delta) => format!("delta/{}", delta.keyframe_interval),
+        None => "-".to_string(),
+    }
+}
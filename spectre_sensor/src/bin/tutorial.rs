@@ -0,0 +1,181 @@
+//! Guided first run against the sensor daemon, or a mock with `--mock`
+//!
+//! Walks through connecting, checking status, reading scores, calibrating
+//! and measuring once. Each stage is checked before moving on, and a failed
+//! stage explains what to look at before it exits.
+
+use clap::Parser;
+use futures::StreamExt;
+use spectre_sensor::{
+    grpc_client::{extract_scores, SensorClient},
+    mock::{spawn_mock_daemon, MockSensorConfig},
+    permissions::provide_camera_troubleshooting_guidance,
+    phases::PhaseOutcome,
+    FearBucket, SensorConfig, SensorTransport,
+};
+use std::time::Duration;
+
+#[derive(Parser)]
+#[command(name = "tutorial")]
+#[command(about = "Guided first run of the fear sensor client API")]
+struct Args {
+    /// Serve a mock sensor in this process instead of connecting to the daemon
+    #[arg(long)]
+    mock: bool,
+
+    /// Daemon address: socket path, pipe:<name> or host:port (defaults to SPECTRE_GRPC_SOCKET)
+    #[arg(short, long)]
+    address: Option<String>,
+
+    /// Seconds each stage may wait for the sensor
+    #[arg(long, default_value = "30")]
+    duration: f64,
+
+    /// Run every stage without waiting for Enter
+    #[arg(short, long)]
+    yes: bool,
+}
+
+/// A stage that did not pass, with what to check before trying again
+struct StageFailure {
+    message: String,
+    hint: &'static str,
+    /// Whether the camera is the likely cause
+    camera: bool,
+}
+
+impl StageFailure {
+    fn new(message: impl ToString, hint: &'static str) -> Self {
+        Self { message: message.to_string(), hint, camera: false }
+    }
+
+    fn camera(message: impl ToString, hint: &'static str) -> Self {
+        Self { camera: true, ..Self::new(message, hint) }
+    }
+}
+
+type StageResult<T> = Result<T, StageFailure>;
+
+#[tokio::main]
+async fn main() {
+    tracing_subscriber::fmt().with_target(false).without_time().init();
+    let args = Args::parse();
+
+    if let Err(failure) = run(&args).await {
+        eprintln!("\n✗ {}", failure.message);
+        eprintln!("  {}", failure.hint);
+        if failure.camera {
+            provide_camera_troubleshooting_guidance();
+        }
+        std::process::exit(1);
+    }
+}
+
+async fn run(args: &Args) -> StageResult<()> {
+    let timeout = Duration::from_secs_f64(args.duration);
+
+    stage(args, 1, "Connect", "Open a gRPC connection to the sensor.").await;
+    let transport = connect_transport(args).await?;
+    let mut client = SensorClient::connect(&transport).await.map_err(|e| {
+        StageFailure::new(
+            format!("Could not connect to {}: {}", transport, e),
+            "Start the sensor daemon first, or pass --address if it listens elsewhere. --mock needs neither.",
+        )
+    })?;
+    println!("✓ Connected to {}", transport);
+
+    stage(args, 2, "Status", "Ask the sensor whether it is running.").await;
+    let status = client
+        .get_status()
+        .await
+        .map_err(|e| StageFailure::new(format!("Status request failed: {}", e), "The daemon may be shutting down; restart it."))?;
+    if let Some(fault) = status.last_error.filter(|_| !status.running) {
+        return Err(StageFailure::camera(
+            format!("The sensor is not running: {}", fault.message),
+            "The sensor usually stops because it cannot open the camera.",
+        ));
+    }
+    println!("✓ Sensor running");
+
+    stage(args, 3, "Scores", "Stream fear scores; look at the camera.").await;
+    let mut scores = Box::pin(extract_scores(client.stream_scores().await.map_err(|e| {
+        StageFailure::new(format!("Could not open the score stream: {}", e), "Check the daemon log for stream errors.")
+    })?));
+    let mut received = 0u32;
+    let deadline = tokio::time::Instant::now() + timeout.min(Duration::from_secs(3));
+    while let Ok(Some(score)) = tokio::time::timeout_at(deadline, scores.next()).await {
+        let score = score.map_err(|e| StageFailure::new(format!("Score stream failed: {}", e), "Reconnect and try again."))?;
+        received += 1;
+        println!("  fear {:.2}, confidence {:.2}", score.normalized_fear, score.confidence);
+    }
+    if received == 0 {
+        return Err(StageFailure::camera(
+            "No scores arrived",
+            "Scores need a face in frame: check the camera is free, lit and pointed at you.",
+        ));
+    }
+    println!("✓ {} scores received", received);
+
+    stage(args, 4, "Calibrate", "Keep a neutral face while the sensor learns your baseline.").await;
+    let mut phases = client
+        .phases()
+        .await
+        .map_err(|e| StageFailure::new(format!("Could not follow calibration: {}", e), "Check the daemon log."))?;
+    match phases.wait_for_calibrated(timeout).await {
+        Ok(PhaseOutcome::Reached(phase)) if phase.is_calibrated() => println!("✓ Calibrated: {:?}", phase),
+        Ok(PhaseOutcome::Reached(phase)) => {
+            return Err(StageFailure::camera(
+                format!("Calibration failed: {:?}", phase),
+                "Calibration needs steady, confident readings: face the camera in even light.",
+            ))
+        }
+        Ok(outcome) => {
+            return Err(StageFailure::camera(
+                format!("Calibration did not finish: {:?}", outcome),
+                "Stay in frame with a neutral face, or raise --duration.",
+            ))
+        }
+        Err(e) => return Err(StageFailure::new(format!("Calibration stream failed: {}", e), "Reconnect and try again.")),
+    }
+
+    stage(args, 5, "Measure", "Score a single frame on demand.").await;
+    let score = client
+        .measure_once(timeout)
+        .await
+        .ok()
+        .and_then(|response| response.score)
+        .ok_or_else(|| StageFailure::camera("No face was measured", "Face the camera and try again."))?;
+    println!(
+        "✓ Fear {:.2} ({:?})",
+        score.normalized_fear,
+        FearBucket::from_score(score.normalized_fear)
+    );
+
+    println!("\nAll stages passed. Next, try the examples:");
+    for example in ["in_process", "stream_client", "calibration", "record_replay", "measure"] {
+        println!("  cargo run -p spectre-sensor --example {} --features mock -- --mock", example);
+    }
+    Ok(())
+}
+
+async fn connect_transport(args: &Args) -> StageResult<SensorTransport> {
+    if args.mock {
+        return spawn_mock_daemon(MockSensorConfig::default())
+            .await
+            .map_err(|e| StageFailure::new(format!("Could not start the mock sensor: {}", e), "This is a bug; please report it."));
+    }
+    let transport = match &args.address {
+        Some(address) => address.parse(),
+        None => SensorTransport::from_config(&SensorConfig::from_env()),
+    };
+    transport.map_err(|e| StageFailure::new(format!("Invalid address: {}", e), "Use a socket path, pipe:<name> or host:port."))
+}
+
+/// Announce a stage and, unless `--yes`, wait for Enter
+async fn stage(args: &Args, number: u32, name: &str, description: &str) {
+    println!("\n[{}/5] {}: {}", number, name, description);
+    if !args.yes {
+        println!("Press Enter to continue...");
+        let _ = tokio::task::spawn_blocking(|| std::io::stdin().read_line(&mut String::new())).await;
+    }
+}
@@ -0,0 +1,773 @@
+//! Bug report bundles: the last few seconds of sensor activity on demand
+//!
+//! "Fear spiked for no reason at the boss door" is hard to act on without
+//! data. The processing loop keeps a time-boxed ring of recent frames (with
+//! face crops when privacy mode is off) and [`LogRingBuffer`] keeps recent log
+//! lines. `CaptureBugReport` writes both, together with the configuration,
+//! calibration baseline, a status snapshot and platform info, into a
+//! timestamped directory:
+//!
+//! ```text
+//! bug_report_<created_us>/
+//!   manifest.json   platform info, counts and what was dropped
+//!   frames.jsonl    one FrameRecord per line, oldest first
+//!   crops/          PNG face crops named by frame timestamp (never in privacy mode)
+//!   config.json
+//!   baseline.json
+//!   status.json
+//!   logs.txt
+//!   metrics.jsonl   metrics history samples, oldest first
+//! ```
+//!
+//! `frames.jsonl` lines are `score` trace events, so a bundle can be fed to
+//! `session_diff` directly. Bundles are capped in size: face crops are dropped
+//! first, then the oldest frames, then the oldest metrics samples, then the
+//! oldest log lines.
+
+use crate::{
+    calibrator::BaselineStats,
+    conditioning::Conditioning,
+    config::SensorConfig,
+    metrics_history::MetricsSample,
+    sensor::SensorState,
+    normalization::InputNormalization,
+    startup::InitBreakdown,
+    types::{FearFrame, NormalizedRect, SensorCapability},
+};
+use spectremesh_core::{EmotionLayout, EmotionLogits, LogitsError};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::fmt::{self, Write as _};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use thiserror::Error;
+use tracing::field::{Field, Visit};
+use tracing_subscriber::{layer::Context, Layer};
+
+/// Bytes set aside for `manifest.json` when enforcing the size cap
+const MANIFEST_RESERVE_BYTES: u64 = 1024;
+
+/// Bug report errors
+#[derive(Debug, Error)]
+pub enum BugReportError {
+    #[error("Failed to write bug report '{path}': {message}")]
+    Io { path: String, message: String },
+
+    #[error("Failed to serialize {what}: {message}")]
+    Serialize { what: &'static str, message: String },
+}
+
+/// Bug report capture settings
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BugReportConfig {
+    /// How much recent history the frame ring keeps
+    #[serde(with = "spectremesh_core::duration")]
+    pub window: Duration,
+    /// Directory bundles are written into
+    pub output_dir: PathBuf,
+    /// Upper bound on the total size of a bundle
+    pub max_bundle_bytes: u64,
+    /// Log lines kept by [`LogRingBuffer`]
+    pub max_log_lines: usize,
+}
+
+impl Default for BugReportConfig {
+    fn default() -> Self {
+        Self {
+            window: Duration::from_secs(10),
+            output_dir: std::env::temp_dir().join("spectre_bug_reports"),
+            max_bundle_bytes: 8 * 1024 * 1024,
+            max_log_lines: 500,
+        }
+    }
+}
+
+impl BugReportConfig {
+    /// Validate the configuration
+    pub fn validate(&self) -> Result<(), String> {
+        if self.window.is_zero() {
+            return Err("Bug report window must be positive".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// One line of `frames.jsonl`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename = "score")]
+pub struct FrameRecord {
+    /// Capture time in microseconds since Unix epoch
+    pub timestamp_us: u64,
+    /// Normalized fear score
+    pub fear: f32,
+    pub startle: f32,
+    pub confidence: f32,
+    pub calibrated: bool,
+    pub capability: SensorCapability,
+    /// In the model's layout, however many channels it has
+    pub emotion_logits: Vec<f32>,
+    pub inference_latency_us: u64,
+    /// Older bundles were always computed on [0, 1] pixels
+    #[serde(default)]
+    pub input_normalization: InputNormalization,
+    /// Conditioning steps applied before calibration
+    #[serde(default)]
+    pub conditioning: Conditioning,
+    /// Frame was flipped horizontally after capture
+    #[serde(default)]
+    pub mirrored: bool,
+    /// Position in the sensor's frame count (0 when not numbered)
+    #[serde(default)]
+    pub sequence: u64,
+    /// Older bundles only recorded frames with a face
+    #[serde(default = "default_face_present")]
+    pub face_present: bool,
+    /// Scored face, when face position sharing is on
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub face_bbox: Option<NormalizedRect>,
+}
+
+fn default_face_present() -> bool {
+    true
+}
+
+impl FrameRecord {
+    /// Record a frame captured at `timestamp_us`
+    pub fn new(frame: &FearFrame, timestamp_us: u64) -> Self {
+        Self {
+            timestamp_us,
+            fear: frame.fear_score,
+            startle: frame.startle,
+            confidence: frame.confidence,
+            calibrated: frame.calibrated,
+            capability: frame.capability,
+            emotion_logits: frame.emotion_logits.to_vec(),
+            inference_latency_us: frame.inference_latency.as_micros() as u64,
+            input_normalization: frame.input_normalization,
+            conditioning: frame.conditioning,
+            mirrored: frame.mirrored,
+            sequence: frame.sequence,
+            face_present: frame.face_present,
+            face_bbox: frame.face_bbox,
+        }
+    }
+
+    /// Rebuild the recorded frame, captured at `timestamp_us`
+    ///
+    /// The logits are read in `layout`, which must have as many channels as
+    /// were recorded.
+    pub fn to_frame(&self, layout: EmotionLayout) -> Result<FearFrame, LogitsError> {
+        let logits = EmotionLogits::new(self.emotion_logits.clone(), layout)?;
+        Ok(FearFrame::new(self.fear, logits, self.confidence, self.calibrated, Duration::from_micros(self.inference_latency_us))
+            .with_startle(self.startle)
+            .with_capability(self.capability)
+            .with_input_normalization(self.input_normalization)
+            .with_conditioning(self.conditioning)
+            .with_mirrored(self.mirrored)
+            .with_face_bbox(self.face_bbox)
+            .with_face_present(self.face_present)
+            .with_capture_time(UNIX_EPOCH + Duration::from_micros(self.timestamp_us))
+            .with_sequence(self.sequence))
+    }
+}
+
+/// A recent frame and the face crop it was computed from
+#[derive(Debug, Clone, PartialEq)]
+pub struct BufferedFrame {
+    pub record: FrameRecord,
+    /// PNG-encoded face crop, only captured when privacy mode is off
+    pub crop: Option<Vec<u8>>,
+}
+
+/// Time-boxed ring of recent frames
+#[derive(Debug, Clone)]
+pub struct FrameRingBuffer {
+    window: Duration,
+    /// (monotonic time, frame) within the window
+    frames: VecDeque<(Duration, BufferedFrame)>,
+}
+
+impl FrameRingBuffer {
+    /// Create a buffer keeping `window` of history
+    pub fn new(window: Duration) -> Self {
+        Self {
+            window,
+            frames: VecDeque::new(),
+        }
+    }
+
+    /// Add a frame seen at monotonic time `now`, dropping frames older than the window
+    pub fn push(&mut self, now: Duration, frame: BufferedFrame) {
+        while self
+            .frames
+            .front()
+            .is_some_and(|&(t, _)| now.saturating_sub(t) > self.window)
+        {
+            self.frames.pop_front();
+        }
+        self.frames.push_back((now, frame));
+    }
+
+    /// Number of buffered frames
+    pub fn len(&self) -> usize {
+        self.frames.len()
+    }
+
+    /// Whether no frames are buffered
+    pub fn is_empty(&self) -> bool {
+        self.frames.is_empty()
+    }
+
+    /// Buffered frames, oldest first
+    pub fn snapshot(&self) -> Vec<BufferedFrame> {
+        self.frames.iter().map(|(_, frame)| frame.clone()).collect()
+    }
+
+    /// Drop all buffered frames
+    pub fn clear(&mut self) {
+        self.frames.clear();
+    }
+}
+
+/// Ring of recent log lines, filled as a `tracing` layer
+///
+/// Install it next to the usual formatter:
+///
+/// ```ignore
+/// let logs = LogRingBuffer::new(config.bug_report.max_log_lines);
+/// tracing_subscriber::registry()
+///     .with(tracing_subscriber::fmt::layer())
+///     .with(logs.clone())
+///     .init();
+/// let sensor = EmotionSensor::new(config).with_log_buffer(logs);
+/// ```
+#[derive(Debug, Clone)]
+pub struct LogRingBuffer {
+    capacity: usize,
+    lines: Arc<Mutex<VecDeque<String>>>,
+}
+
+impl LogRingBuffer {
+    /// Create a buffer keeping the last `capacity` lines
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            lines: Arc::new(Mutex::new(VecDeque::with_capacity(capacity))),
+        }
+    }
+
+    /// Append a line, dropping the oldest once full
+    pub fn push(&self, line: String) {
+        if self.capacity == 0 {
+            return;
+        }
+        let mut lines = self.lines.lock().unwrap();
+        if lines.len() == self.capacity {
+            lines.pop_front();
+        }
+        lines.push_back(line);
+    }
+
+    /// Buffered lines, oldest first
+    pub fn lines(&self) -> Vec<String> {
+        self.lines.lock().unwrap().iter().cloned().collect()
+    }
+}
+
+impl<S: tracing::Subscriber> Layer<S> for LogRingBuffer {
+    fn on_event(&self, event: &tracing::Event<'_>, _ctx: Context<'_, S>) {
+        let metadata = event.metadata();
+        let mut line = format!("{} {} {}:", now_us(SystemTime::now()), metadata.level(), metadata.target());
+        event.record(&mut LineVisitor(&mut line));
+        self.push(line);
+    }
+}
+
+/// Appends event fields to a log line
+struct LineVisitor<'a>(&'a mut String);
+
+impl Visit for LineVisitor<'_> {
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        if field.name() == "message" {
+            let _ = write!(self.0, " {:?}", value);
+        } else {
+            let _ = write!(self.0, " {}={:?}", field.name(), value);
+        }
+    }
+}
+
+/// The parts of `StatusResponse` worth keeping in a bundle
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StatusSnapshot {
+    pub running: bool,
+    pub calibrated: bool,
+    pub calibration_progress: f32,
+    pub capability: SensorCapability,
+    pub last_error: Option<String>,
+    pub current_fps: f32,
+    pub p95_inference_latency_us: u64,
+    pub dropped_frames: u64,
+    pub calibration_drift: f32,
+    #[serde(default)]
+    pub init: Option<InitBreakdown>,
+    /// Emotion model input convention in use
+    #[serde(default)]
+    pub input_normalization: Option<InputNormalization>,
+    #[serde(default)]
+    pub stopped_reason: Option<String>,
+    /// Whether captured frames are flipped horizontally, once the camera is open
+    #[serde(default)]
+    pub mirrored: Option<bool>,
+}
+
+impl From<&SensorState> for StatusSnapshot {
+    fn from(state: &SensorState) -> Self {
+        Self {
+            running: state.running,
+            calibrated: state.calibrated,
+            calibration_progress: state.calibration_progress,
+            capability: state.capability,
+            last_error: state.last_error.clone(),
+            current_fps: state.metrics.current_fps,
+            p95_inference_latency_us: state.metrics.p95_inference_latency.as_micros() as u64,
+            dropped_frames: state.metrics.dropped_frames,
+            calibration_drift: state.metrics.calibration_drift,
+            init: state.init,
+            input_normalization: state.input_normalization,
+            stopped_reason: state.stopped_reason.clone(),
+            mirrored: state.session.mirrored,
+        }
+    }
+}
+
+/// Host the daemon is running on
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PlatformInfo {
+    pub os: String,
+    pub arch: String,
+    pub family: String,
+    pub cpus: usize,
+    pub sensor_version: String,
+}
+
+impl PlatformInfo {
+    /// Describe the current host
+    pub fn current() -> Self {
+        Self {
+            os: std::env::consts::OS.to_string(),
+            arch: std::env::consts::ARCH.to_string(),
+            family: std::env::consts::FAMILY.to_string(),
+            cpus: num_cpus::get(),
+            sensor_version: env!("CARGO_PKG_VERSION").to_string(),
+        }
+    }
+}
+
+/// Contents of `manifest.json`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BugReportManifest {
+    /// Capture time in microseconds since Unix epoch
+    pub created_us: u64,
+    pub platform: PlatformInfo,
+    /// Whether face imagery was withheld
+    pub privacy_mode: bool,
+    /// Frames written to `frames.jsonl`
+    pub frames: usize,
+    /// Face crops written to `crops/`
+    pub crops: usize,
+    /// Lines written to `logs.txt`
+    pub log_lines: usize,
+    /// Oldest frames dropped to stay under the size cap
+    pub dropped_frames: usize,
+    /// Face crops dropped to stay under the size cap
+    pub dropped_crops: usize,
+    /// Oldest log lines dropped to stay under the size cap
+    pub dropped_log_lines: usize,
+    /// Samples written to `metrics.jsonl`
+    #[serde(default)]
+    pub metrics_samples: usize,
+    /// Oldest metrics samples dropped to stay under the size cap
+    #[serde(default)]
+    pub dropped_metrics_samples: usize,
+}
+
+/// A written bundle
+#[derive(Debug, Clone, PartialEq)]
+pub struct BugReportSummary {
+    /// Bundle directory
+    pub path: PathBuf,
+    pub manifest: BugReportManifest,
+    /// Total size of the files written
+    pub bytes: u64,
+}
+
+/// Everything that goes into a bundle, captured at request time
+#[derive(Debug, Clone)]
+pub struct BugReport {
+    /// Recent frames, oldest first
+    pub frames: Vec<BufferedFrame>,
+    pub config: SensorConfig,
+    pub baseline: Option<BaselineStats>,
+    pub status: StatusSnapshot,
+    /// Recent log lines, oldest first
+    pub logs: Vec<String>,
+    /// Metrics history, oldest first
+    pub metrics_history: Vec<MetricsSample>,
+    pub platform: PlatformInfo,
+}
+
+impl BugReport {
+    /// Write the bundle into `config.output_dir`
+    pub fn write(&self, config: &BugReportConfig) -> Result<BugReportSummary, BugReportError> {
+        self.write_at(config, SystemTime::now())
+    }
+
+    /// Write the bundle as if captured at `created`
+    pub fn write_at(&self, config: &BugReportConfig, created: SystemTime) -> Result<BugReportSummary, BugReportError> {
+        let created_us = now_us(created);
+        let privacy_mode = self.config.privacy_mode;
+
+        let config_json = to_json(&self.config, "config")?;
+        let baseline_json = to_json(&self.baseline, "baseline")?;
+        let status_json = to_json(&self.status, "status")?;
+
+        let mut frames = VecDeque::with_capacity(self.frames.len());
+        for frame in &self.frames {
+            let line = serde_json::to_string(&frame.record).map_err(|e| BugReportError::Serialize {
+                what: "frame",
+                message: e.to_string(),
+            })?;
+            frames.push_back((frame.record.timestamp_us, line));
+        }
+        let mut crops: VecDeque<(u64, &[u8])> = if privacy_mode {
+            VecDeque::new()
+        } else {
+            self.frames
+                .iter()
+                .filter_map(|frame| frame.crop.as_deref().map(|crop| (frame.record.timestamp_us, crop)))
+                .collect()
+        };
+        let mut logs: VecDeque<&str> = self.logs.iter().map(String::as_str).collect();
+        let mut samples = VecDeque::with_capacity(self.metrics_history.len());
+        for sample in &self.metrics_history {
+            samples.push_back(serde_json::to_string(sample).map_err(|e| BugReportError::Serialize {
+                what: "metrics sample",
+                message: e.to_string(),
+            })?);
+        }
+
+        // Trim to the size cap: crops first, then the oldest frames, metrics samples and logs
+        let mut total = MANIFEST_RESERVE_BYTES
+            + (config_json.len() + baseline_json.len() + status_json.len()) as u64
+            + frames.iter().map(|(_, line)| line.len() as u64 + 1).sum::<u64>()
+            + crops.iter().map(|(_, crop)| crop.len() as u64).sum::<u64>()
+            + samples.iter().map(|line| line.len() as u64 + 1).sum::<u64>()
+            + logs.iter().map(|line| line.len() as u64 + 1).sum::<u64>();
+        let (mut dropped_frames, mut dropped_crops, mut dropped_log_lines) = (0, 0, 0);
+        let mut dropped_metrics_samples = 0;
+        while total > config.max_bundle_bytes {
+            if let Some((_, crop)) = crops.pop_front() {
+                total -= crop.len() as u64;
+                dropped_crops += 1;
+            } else if let Some((_, line)) = frames.pop_front() {
+                total -= line.len() as u64 + 1;
+                dropped_frames += 1;
+            } else if let Some(line) = samples.pop_front() {
+                total -= line.len() as u64 + 1;
+                dropped_metrics_samples += 1;
+            } else if let Some(line) = logs.pop_front() {
+                total -= line.len() as u64 + 1;
+                dropped_log_lines += 1;
+            } else {
+                break;
+            }
+        }
+        if dropped_frames + dropped_crops + dropped_metrics_samples + dropped_log_lines > 0 {
+            tracing::warn!(
+                "Bug report over {} bytes, dropped {} crops, {} frames, {} metrics samples and {} log lines",
+                config.max_bundle_bytes,
+                dropped_crops,
+                dropped_frames,
+                dropped_metrics_samples,
+                dropped_log_lines
+            );
+        }
+
+        let manifest = BugReportManifest {
+            created_us,
+            platform: self.platform.clone(),
+            privacy_mode,
+            frames: frames.len(),
+            crops: crops.len(),
+            log_lines: logs.len(),
+            dropped_frames,
+            dropped_crops,
+            dropped_log_lines,
+            metrics_samples: samples.len(),
+            dropped_metrics_samples,
+        };
+
+        let path = config.output_dir.join(format!("bug_report_{}", created_us));
+        fs::create_dir_all(&config.output_dir).map_err(|e| io_error(&config.output_dir, e))?;
+        fs::create_dir(&path).map_err(|e| io_error(&path, e))?;
+
+        let mut bytes = 0;
+        let mut frames_jsonl = String::new();
+        for (_, line) in &frames {
+            frames_jsonl.push_str(line);
+            frames_jsonl.push('\n');
+        }
+        let mut logs_txt = String::new();
+        for line in &logs {
+            logs_txt.push_str(line);
+            logs_txt.push('\n');
+        }
+        let mut metrics_jsonl = String::new();
+        for line in &samples {
+            metrics_jsonl.push_str(line);
+            metrics_jsonl.push('\n');
+        }
+
+        bytes += write_file(&path.join("manifest.json"), to_json(&manifest, "manifest")?.as_bytes())?;
+        bytes += write_file(&path.join("frames.jsonl"), frames_jsonl.as_bytes())?;
+        bytes += write_file(&path.join("config.json"), config_json.as_bytes())?;
+        bytes += write_file(&path.join("baseline.json"), baseline_json.as_bytes())?;
+        bytes += write_file(&path.join("status.json"), status_json.as_bytes())?;
+        bytes += write_file(&path.join("logs.txt"), logs_txt.as_bytes())?;
+        bytes += write_file(&path.join("metrics.jsonl"), metrics_jsonl.as_bytes())?;
+
+        if !crops.is_empty() {
+            let crops_dir = path.join("crops");
+            fs::create_dir(&crops_dir).map_err(|e| io_error(&crops_dir, e))?;
+            for (timestamp_us, crop) in &crops {
+                bytes += write_file(&crops_dir.join(format!("{}.png", timestamp_us)), crop)?;
+            }
+        }
+
+        tracing::info!(
+            "Wrote bug report with {} frames and {} crops to {}",
+            manifest.frames,
+            manifest.crops,
+            path.display()
+        );
+
+        Ok(BugReportSummary { path, manifest, bytes })
+    }
+}
+
+fn now_us(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH).unwrap_or_default().as_micros() as u64
+}
+
+fn to_json<T: Serialize>(value: &T, what: &'static str) -> Result<String, BugReportError> {
+    serde_json::to_string_pretty(value).map_err(|e| BugReportError::Serialize {
+        what,
+        message: e.to_string(),
+    })
+}
+
+fn io_error(path: &Path, error: std::io::Error) -> BugReportError {
+    BugReportError::Io {
+        path: path.display().to_string(),
+        message: error.to_string(),
+    }
+}
+
+fn write_file(path: &Path, contents: &[u8]) -> Result<u64, BugReportError> {
+    fs::write(path, contents).map_err(|e| io_error(path, e))?;
+    Ok(contents.len() as u64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::session_diff::SessionTrace;
+    use tracing_subscriber::layer::SubscriberExt;
+
+    fn scratch_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("spectre_bug_report_{}_{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    fn buffered(timestamp_us: u64, fear: f32) -> BufferedFrame {
+        let frame = FearFrame::new(fear, [0.0, 0.0, fear, 0.0, 0.0, 0.0, 0.0], 0.9, true, Duration::from_millis(4));
+        BufferedFrame {
+            record: FrameRecord::new(&frame, timestamp_us),
+            crop: Some(vec![0x89, b'P', b'N', b'G', 0, 0, 0, 0]),
+        }
+    }
+
+    /// A 30 FPS ring filled for `seconds` with a 10 second window
+    fn filled_ring(seconds: u64) -> FrameRingBuffer {
+        let mut ring = FrameRingBuffer::new(Duration::from_secs(10));
+        for i in 0..seconds * 30 {
+            let t = Duration::from_micros(i * 1_000_000 / 30);
+            ring.push(t, buffered(1_000_000 + t.as_micros() as u64, (i % 30) as f32 / 30.0));
+        }
+        ring
+    }
+
+    fn report(frames: Vec<BufferedFrame>, privacy_mode: bool, logs: Vec<String>) -> BugReport {
+        BugReport {
+            frames,
+            config: SensorConfig::default().with_privacy_mode(privacy_mode),
+            baseline: None,
+            status: StatusSnapshot::from(&SensorState::default()),
+            logs,
+            metrics_history: Vec::new(),
+            platform: PlatformInfo::current(),
+        }
+    }
+
+    fn config_for(dir: &Path) -> BugReportConfig {
+        BugReportConfig {
+            output_dir: dir.to_path_buf(),
+            ..BugReportConfig::default()
+        }
+    }
+
+    #[test]
+    fn test_frame_ring_is_time_boxed() {
+        let ring = filled_ring(15);
+
+        // 10 seconds at 30 FPS, plus the frame exactly on the window edge
+        assert_eq!(ring.len(), 301);
+        let frames = ring.snapshot();
+        assert!(frames.windows(2).all(|pair| pair[0].record.timestamp_us < pair[1].record.timestamp_us));
+        assert_eq!(frames.last().unwrap().record.timestamp_us - frames[0].record.timestamp_us, 10_000_000);
+    }
+
+    #[test]
+    fn test_bundle_structure_and_trace() {
+        let dir = scratch_dir("structure");
+        let frames = filled_ring(12).snapshot();
+        let logs = vec!["1 INFO spectre_sensor: started".to_string()];
+
+        let mut history = crate::metrics_history::MetricsHistory::default();
+        for i in 0..3u64 {
+            history.record(i * 5_000_000, &crate::types::PerformanceMetrics::default());
+        }
+        let report = BugReport {
+            metrics_history: history.samples(),
+            ..report(frames.clone(), false, logs)
+        };
+        let summary = report.write(&config_for(&dir)).unwrap();
+
+        assert!(summary.path.starts_with(&dir));
+        for file in [
+            "manifest.json",
+            "frames.jsonl",
+            "config.json",
+            "baseline.json",
+            "status.json",
+            "logs.txt",
+            "metrics.jsonl",
+        ] {
+            assert!(summary.path.join(file).is_file(), "missing {}", file);
+        }
+
+        let manifest: BugReportManifest =
+            serde_json::from_str(&fs::read_to_string(summary.path.join("manifest.json")).unwrap()).unwrap();
+        assert_eq!(manifest, summary.manifest);
+        assert_eq!(manifest.frames, frames.len());
+        assert_eq!(manifest.crops, frames.len());
+        assert_eq!(manifest.platform.os, std::env::consts::OS);
+        assert_eq!(fs::read_dir(summary.path.join("crops")).unwrap().count(), frames.len());
+
+        // The trace is readable both as frame records and as a session_diff trace
+        let trace = fs::read_to_string(summary.path.join("frames.jsonl")).unwrap();
+        let records: Vec<FrameRecord> = trace.lines().map(|line| serde_json::from_str(line).unwrap()).collect();
+        assert_eq!(records, frames.iter().map(|frame| frame.record.clone()).collect::<Vec<_>>());
+        assert_eq!(SessionTrace::from_jsonl(&trace).unwrap().scores.len(), frames.len());
+
+        let status: StatusSnapshot =
+            serde_json::from_str(&fs::read_to_string(summary.path.join("status.json")).unwrap()).unwrap();
+        assert!(!status.running);
+
+        let metrics = fs::read_to_string(summary.path.join("metrics.jsonl")).unwrap();
+        let samples: Vec<MetricsSample> = metrics.lines().map(|line| serde_json::from_str(line).unwrap()).collect();
+        assert_eq!(samples, history.samples());
+        assert_eq!(manifest.metrics_samples, 3);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_privacy_mode_omits_imagery() {
+        let dir = scratch_dir("privacy");
+        let summary = report(filled_ring(2).snapshot(), true, Vec::new())
+            .write(&config_for(&dir))
+            .unwrap();
+
+        assert!(summary.manifest.privacy_mode);
+        assert_eq!(summary.manifest.crops, 0);
+        assert_eq!(summary.manifest.frames, 60);
+        assert!(!summary.path.join("crops").exists());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_size_cap_drops_crops_then_oldest_frames() {
+        let dir = scratch_dir("cap");
+        let frames = filled_ring(2).snapshot();
+        let report = report(frames.clone(), false, Vec::new());
+
+        // Room for the text files but not the crops
+        let uncapped = report.write_at(&config_for(&dir), UNIX_EPOCH + Duration::from_secs(1)).unwrap();
+        let manifest_bytes = fs::metadata(uncapped.path.join("manifest.json")).unwrap().len();
+        let crop_bytes = frames.len() as u64 * 8;
+        let config = BugReportConfig {
+            max_bundle_bytes: uncapped.bytes - manifest_bytes - crop_bytes + MANIFEST_RESERVE_BYTES,
+            ..config_for(&dir)
+        };
+        let summary = report.write_at(&config, UNIX_EPOCH + Duration::from_secs(2)).unwrap();
+        assert_eq!(summary.manifest.dropped_crops, frames.len());
+        assert_eq!(summary.manifest.dropped_frames, 0);
+        assert!(!summary.path.join("crops").exists());
+
+        // A tighter cap keeps only the newest frames
+        let config = BugReportConfig {
+            max_bundle_bytes: 8 * 1024,
+            ..config_for(&dir)
+        };
+        let summary = report.write_at(&config, UNIX_EPOCH + Duration::from_secs(3)).unwrap();
+        assert!(summary.bytes <= config.max_bundle_bytes);
+        assert!(summary.manifest.dropped_frames > 0);
+        let trace = fs::read_to_string(summary.path.join("frames.jsonl")).unwrap();
+        let last: FrameRecord = serde_json::from_str(trace.lines().last().unwrap()).unwrap();
+        assert_eq!(last, frames.last().unwrap().record);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_log_layer_feeds_bundle() {
+        let dir = scratch_dir("logs");
+        let logs = LogRingBuffer::new(2);
+        let subscriber = tracing_subscriber::registry().with(logs.clone());
+        tracing::subscriber::with_default(subscriber, || {
+            tracing::info!("first");
+            tracing::warn!(attempt = 3, "Frame processing failed");
+            tracing::error!("Sensor processing loop failed");
+        });
+
+        let lines = logs.lines();
+        assert_eq!(lines.len(), 2);
+        assert!(lines[0].contains(&format!("WARN {}:", module_path!())));
+        assert!(lines[0].contains("Frame processing failed") && lines[0].contains("attempt=3"));
+        assert!(lines[1].contains("ERROR"));
+
+        let summary = report(Vec::new(), true, lines).write(&config_for(&dir)).unwrap();
+        let written = fs::read_to_string(summary.path.join("logs.txt")).unwrap();
+        assert_eq!(summary.manifest.log_lines, 2);
+        assert!(written.contains("Sensor processing loop failed"));
+        assert!(!written.contains("first"));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,242 @@
+//! Calibration control against the phase state machine
+//!
+//! Start, freeze, unfreeze and reset are only legal in some
+//! [`CalibrationPhase`]s:
+//!
+//! | phase      | start      | freeze     | unfreeze   | reset      |
+//! |------------|------------|------------|------------|------------|
+//! | idle       | no-op      | rejected   | rejected   | no-op      |
+//! | collecting | no-op      | rejected   | rejected   | restarts   |
+//! | calibrated | rejected   | freezes    | no-op      | restarts   |
+//! | frozen     | rejected   | no-op      | unfreezes  | rejected   |
+//! | failed     | restarts   | rejected   | rejected   | restarts   |
+//!
+//! There is no baseline to freeze before calibration completes, a frozen
+//! baseline must be unfrozen before it is thrown away, and a calibrated one
+//! is recalibrated with reset rather than start. Repeating an action is
+//! harmless: a second freeze or unfreeze, a start while collecting and a
+//! reset while idle change nothing.
+//!
+//! While the sensor runs its processing loop owns the calibrator, so actions
+//! are queued through a [`CalibrationController`] and applied by the loop
+//! between two frames, never halfway through a sample. Actions from several
+//! clients are applied one at a time, each checked against the phase left
+//! by the one before.
+
+use crate::{
+    calibrator::AdaptiveCalibrator,
+    phases::{publish_phase, CalibrationPhase},
+    types::SensorCapability,
+};
+use std::fmt;
+use thiserror::Error;
+use tokio::sync::{mpsc, oneshot, watch};
+
+/// gRPC metadata key carrying the phase an action was rejected in
+pub const PHASE_METADATA: &str = "calibration-phase";
+/// gRPC metadata key carrying the rejected action
+pub const ACTION_METADATA: &str = "calibration-action";
+
+/// A calibration control request
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CalibrationAction {
+    /// Begin collecting a baseline, retrying after a failure
+    Start,
+    /// Hold the baseline still
+    Freeze,
+    /// Let the baseline follow the player again
+    Unfreeze,
+    /// Throw the baseline away and collect a new one
+    Reset,
+}
+
+impl CalibrationAction {
+    /// Lowercase name of the action, as reported in control rejections
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Start => "start",
+            Self::Freeze => "freeze",
+            Self::Unfreeze => "unfreeze",
+            Self::Reset => "reset",
+        }
+    }
+
+    /// Whether the action is legal in `phase`
+    pub fn check(&self, phase: &CalibrationPhase) -> Result<(), CalibrationControlError> {
+        use CalibrationPhase::*;
+        let legal = match self {
+            Self::Start => matches!(phase, Idle | Collecting { .. } | Failed { .. }),
+            Self::Freeze | Self::Unfreeze => matches!(phase, Calibrated { .. } | Frozen { .. }),
+            Self::Reset => !matches!(phase, Frozen { .. }),
+        };
+        if legal {
+            Ok(())
+        } else {
+            Err(CalibrationControlError::IllegalTransition {
+                phase: phase.clone(),
+                action: *self,
+            })
+        }
+    }
+
+    /// Check the action against `phase` and apply it to `calibrator`
+    pub fn apply(
+        &self,
+        calibrator: &mut AdaptiveCalibrator,
+        phase: &CalibrationPhase,
+    ) -> Result<(), CalibrationControlError> {
+        self.check(phase)?;
+        match (self, phase) {
+            // Already collecting, or not started yet
+            (Self::Start, CalibrationPhase::Idle | CalibrationPhase::Collecting { .. }) => {}
+            (Self::Start | Self::Reset, _) => calibrator.reset(),
+            (Self::Freeze, _) if !calibrator.is_frozen() => calibrator.freeze(),
+            (Self::Unfreeze, _) if calibrator.is_frozen() => calibrator.unfreeze(),
+            (Self::Freeze | Self::Unfreeze, _) => {}
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for CalibrationAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// Why a calibration action was not applied
+#[derive(Debug, Clone, PartialEq, Error)]
+pub enum CalibrationControlError {
+    #[error("Cannot {action} calibration while {phase}")]
+    IllegalTransition {
+        phase: CalibrationPhase,
+        action: CalibrationAction,
+    },
+
+    #[error("Sensor stopped before the calibration action was applied")]
+    Interrupted,
+}
+
+/// Calibration phase of a running pipeline
+pub fn pipeline_phase(calibrator: &AdaptiveCalibrator, capability: SensorCapability) -> CalibrationPhase {
+    if calibrator.is_calibrated() {
+        let quality = calibrator.quality();
+        if calibrator.is_frozen() {
+            CalibrationPhase::Frozen { quality }
+        } else {
+            CalibrationPhase::Calibrated { quality }
+        }
+    } else if capability == SensorCapability::EmotionOffline {
+        CalibrationPhase::Failed {
+            reason: "Emotion inference went offline before calibration completed".to_string(),
+        }
+    } else {
+        CalibrationPhase::Collecting { progress: calibrator.progress() }
+    }
+}
+
+struct ControlRequest {
+    action: CalibrationAction,
+    applied: oneshot::Sender<Result<CalibrationPhase, CalibrationControlError>>,
+}
+
+/// Hands calibration actions to a running processing loop
+#[derive(Clone)]
+pub struct CalibrationController {
+    requests: mpsc::UnboundedSender<ControlRequest>,
+}
+
+/// The processing loop's end of a [`CalibrationController`]
+pub struct CalibrationControlInbox {
+    requests: mpsc::UnboundedReceiver<ControlRequest>,
+}
+
+/// Connect a controller to the inbox of a processing loop
+pub fn control_channel() -> (CalibrationController, CalibrationControlInbox) {
+    let (requests, receiver) = mpsc::unbounded_channel();
+    (CalibrationController { requests }, CalibrationControlInbox { requests: receiver })
+}
+
+impl CalibrationController {
+    /// Queue `action` and wait for the loop to apply it, returning the phase it left
+    pub async fn send(&self, action: CalibrationAction) -> Result<CalibrationPhase, CalibrationControlError> {
+        let (applied, done) = oneshot::channel();
+        self.requests
+            .send(ControlRequest { action, applied })
+            .map_err(|_| CalibrationControlError::Interrupted)?;
+        done.await.map_err(|_| CalibrationControlError::Interrupted)?
+    }
+}
+
+impl CalibrationControlInbox {
+    /// Apply every queued action, in order, and publish the resulting phase
+    ///
+    /// Called by the processing loop between frames. Each action is checked
+    /// against the phase the previous one left. Returns how many were
+    /// applied; rejected actions leave the calibrator untouched.
+    pub fn apply_pending(
+        &mut self,
+        calibrator: &mut AdaptiveCalibrator,
+        capability: SensorCapability,
+        calibration: &watch::Sender<CalibrationPhase>,
+    ) -> usize {
+        let mut applied = 0;
+        while let Ok(ControlRequest { action, applied: reply }) = self.requests.try_recv() {
+            let result = action
+                .apply(calibrator, &pipeline_phase(calibrator, capability))
+                .map(|()| {
+                    applied += 1;
+                    let phase = pipeline_phase(calibrator, capability);
+                    publish_phase(calibration, phase.clone());
+                    phase
+                });
+            match &result {
+                Ok(phase) => tracing::info!("Calibration {} applied, now {}", action, phase),
+                Err(e) => tracing::debug!("{}", e),
+            }
+            // The caller may have given up waiting
+            let _ = reply.send(result);
+        }
+        applied
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_actions_are_idempotent() {
+        let mut calibrator = AdaptiveCalibrator::new(Duration::ZERO, 0.05);
+        for _ in 0..30 {
+            calibrator.add_sample(0.5).unwrap();
+        }
+        let calibrated = pipeline_phase(&calibrator, SensorCapability::Full);
+
+        CalibrationAction::Freeze.apply(&mut calibrator, &calibrated).unwrap();
+        let frozen = pipeline_phase(&calibrator, SensorCapability::Full);
+        assert!(matches!(frozen, CalibrationPhase::Frozen { .. }));
+        CalibrationAction::Freeze.apply(&mut calibrator, &frozen).unwrap();
+        assert_eq!(pipeline_phase(&calibrator, SensorCapability::Full), frozen);
+
+        CalibrationAction::Unfreeze.apply(&mut calibrator, &frozen).unwrap();
+        CalibrationAction::Unfreeze.apply(&mut calibrator, &calibrated).unwrap();
+        assert_eq!(pipeline_phase(&calibrator, SensorCapability::Full), calibrated);
+
+        // Starting while collecting keeps the samples collected so far
+        CalibrationAction::Reset.apply(&mut calibrator, &calibrated).unwrap();
+        calibrator.add_sample(0.5).unwrap();
+        let collecting = pipeline_phase(&calibrator, SensorCapability::Full);
+        CalibrationAction::Start.apply(&mut calibrator, &collecting).unwrap();
+        assert_eq!(calibrator.baseline_stats().sample_count, 1);
+    }
+
+    #[test]
+    fn test_rejection_names_phase_and_action() {
+        let error = CalibrationAction::Reset
+            .check(&CalibrationPhase::Frozen { quality: 0.5 })
+            .unwrap_err();
+        assert_eq!(error.to_string(), "Cannot reset calibration while frozen");
+    }
+}
@@ -3,10 +3,44 @@
 //! Implements continuous calibration using exponential moving averages
 //! for mean and variance estimation, with optional freezing capability.
 
-use std::time::{Duration, Instant};
+use std::path::Path;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 use serde::{Deserialize, Serialize};
+use spectremesh_core::emotion::{EmotionLayout, EmotionLogits, STANDARD_CHANNELS};
+use spectremesh_core::{weighted_fear_index, LogitsError, PROVISIONAL_FEAR};
 use thiserror::Error;
 
+/// Number of emotion channels produced by the bundled model
+pub const EMOTION_CHANNELS: usize = STANDARD_CHANNELS;
+
+/// Score reported while the initial calibration is still running
+const UNCALIBRATED_SCORE: f32 = PROVISIONAL_FEAR;
+
+/// Samples the initial calibration needs however short its period, unless
+/// set with [`AdaptiveCalibrator::with_min_samples`] (the sensor takes it from
+/// `SensorConfig::calibration_min_samples`)
+pub const DEFAULT_MIN_SAMPLES: usize = 30;
+
+/// Lower bound for baseline standard deviations
+const MIN_STD_DEV: f32 = 0.1;
+
+/// Samples behind a baseline considered fully established
+const ESTABLISHED_SAMPLES: u32 = 300;
+
+/// Initial-period samples further than this many robust deviations (scaled
+/// MAD) from the running median are discarded as outliers
+const OUTLIER_MAD_K: f32 = 5.0;
+
+/// Samples collected before outlier rejection starts
+const OUTLIER_MIN_SAMPLES: usize = 10;
+
+/// Consecutive outliers after which samples are accepted again, taking the
+/// change for a real shift rather than a startle (about a second at 30 fps)
+const MAX_CONSECUTIVE_OUTLIERS: u32 = 30;
+
+/// Scales a median absolute deviation to a normal standard deviation
+const MAD_TO_STD: f32 = 1.4826;
+
 /// Calibration errors
 #[derive(Debug, Error)]
 pub enum CalibrationError {
@@ -18,6 +52,154 @@ pub enum CalibrationError {
     
     #[error("Invalid calibration parameters: {reason}")]
     InvalidParameters { reason: String },
+
+    #[error("Calibration profile error: {0}")]
+    Profile(String),
+
+    #[error("Calibration profile is {age:?} old, older than {max_age:?}")]
+    StaleProfile { age: Duration, max_age: Duration },
+
+    #[error("Invalid logits: {0}")]
+    Logits(#[from] LogitsError),
+}
+
+/// Mean and standard deviation of one emotion channel
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ChannelStats {
+    pub mean: f32,
+    pub std_dev: f32,
+}
+
+impl Default for ChannelStats {
+    fn default() -> Self {
+        Self { mean: 0.0, std_dev: 1.0 }
+    }
+}
+
+impl ChannelStats {
+    /// Exponential moving average update used after the initial period
+    fn update_ema(&mut self, sample: f32, alpha: f32) {
+        let old_mean = self.mean;
+        self.mean = (1.0 - alpha) * self.mean + alpha * sample;
+
+        let delta = sample - old_mean;
+        let variance = self.std_dev.powi(2);
+        let new_variance = (1.0 - alpha) * variance + alpha * delta.powi(2);
+        self.std_dev = new_variance.sqrt().max(MIN_STD_DEV);
+    }
+
+    /// Whether the statistics can normalize logits (finite, positive spread)
+    fn is_usable(&self) -> bool {
+        self.mean.is_finite() && self.std_dev.is_finite() && self.std_dev > 0.0
+    }
+
+    /// Map a raw logit to [0, 1] via z-score and sigmoid
+    fn normalize(&self, raw: f32) -> f32 {
+        let z_score = (raw - self.mean) / self.std_dev;
+        let sigmoid = 1.0 / (1.0 + (-z_score).exp());
+        sigmoid.clamp(0.0, 1.0)
+    }
+}
+
+/// Welford's online mean and sample variance, with samples weighted as
+/// fractional counts (West's update)
+#[derive(Debug, Clone, Copy, Default)]
+struct RunningStats {
+    /// Sum of the sample weights; the sample count when all weigh 1
+    weight: f64,
+    mean: f64,
+    m2: f64,
+}
+
+impl RunningStats {
+    /// Continue from `stats` taken over `count` full-weight samples
+    fn seeded(stats: ChannelStats, count: u32) -> Self {
+        let m2 = (stats.std_dev as f64).powi(2) * count.saturating_sub(1) as f64;
+        Self { weight: count as f64, mean: stats.mean as f64, m2 }
+    }
+
+    fn push(&mut self, sample: f32, weight: f32) {
+        let (sample, weight) = (sample as f64, weight as f64);
+        self.weight += weight;
+        let delta = sample - self.mean;
+        self.mean += delta * weight / self.weight;
+        self.m2 += weight * delta * (sample - self.mean);
+    }
+
+    /// Mean and sample standard deviation; the spread keeps its default
+    /// until the weights add up to more than one sample
+    fn stats(&self) -> ChannelStats {
+        let std_dev = if self.weight > 1.0 {
+            ((self.m2 / (self.weight - 1.0)).sqrt() as f32).max(MIN_STD_DEV)
+        } else {
+            ChannelStats::default().std_dev
+        };
+        ChannelStats { mean: self.mean as f32, std_dev }
+    }
+}
+
+/// Accumulators of the initial calibration period
+#[derive(Debug, Clone, Default)]
+struct InitialCalibration {
+    /// Accepted fear logits, for the running median and MAD
+    fear_samples: Vec<f32>,
+    fear: RunningStats,
+    /// Outliers discarded since the last accepted sample
+    consecutive_outliers: u32,
+    /// Per-channel accumulators, when per-channel calibration is enabled
+    channels: Vec<RunningStats>,
+    /// Accumulator of the secondary channel, when one is tracked
+    secondary: RunningStats,
+}
+
+impl InitialCalibration {
+    /// Continue an initial period from a partial baseline
+    fn seeded(baseline: &BaselineStats) -> Self {
+        let count = baseline.sample_count;
+        Self {
+            fear_samples: Vec::new(),
+            fear: RunningStats::seeded(baseline.fear(), count),
+            consecutive_outliers: 0,
+            channels: baseline.channels.iter().flatten().map(|&stats| RunningStats::seeded(stats, count)).collect(),
+            secondary: baseline.secondary.map(|secondary| RunningStats::seeded(secondary.stats, count)).unwrap_or_default(),
+        }
+    }
+
+    /// Whether `fear_logit` is more than `OUTLIER_MAD_K` deviations from the
+    /// median of the accepted samples
+    ///
+    /// The deviation is the larger of the scaled MAD and the running standard
+    /// deviation, so a signal flipping between two levels (a MAD of zero) does
+    /// not reject one of them.
+    fn is_outlier(&self, fear_logit: f32) -> bool {
+        if self.fear_samples.len() < OUTLIER_MIN_SAMPLES {
+            return false;
+        }
+        let mut values = self.fear_samples.clone();
+        let center = median(&mut values);
+        for value in &mut values {
+            *value = (*value - center).abs();
+        }
+        let scale = (MAD_TO_STD * median(&mut values)).max(self.fear.stats().std_dev);
+        (fear_logit - center).abs() > OUTLIER_MAD_K * scale
+    }
+}
+
+/// A confidence weight clamped to [0.0, 1.0]; `None` when the sample
+/// should not count at all
+fn sample_weight(weight: f32) -> Option<f32> {
+    (weight > 0.0).then(|| weight.min(1.0))
+}
+
+/// Median of `values`, reordering them
+fn median(values: &mut [f32]) -> f32 {
+    values.sort_unstable_by(f32::total_cmp);
+    let mid = values.len() / 2;
+    if values.len().is_multiple_of(2) {
+        (values[mid - 1] + values[mid]) / 2.0
+    } else {
+        values[mid]
+    }
 }
 
 /// Baseline statistics for calibration
@@ -32,6 +214,50 @@ pub struct BaselineStats {
     /// Last update timestamp (skipped in serialization)
     #[serde(skip, default = "Instant::now")]
     pub last_update: Instant,
+    /// Per-channel baselines when per-channel calibration is enabled
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub channels: Option<Vec<ChannelStats>>,
+    /// Baseline of one channel besides fear, tracked without per-channel
+    /// calibration
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub secondary: Option<SecondaryBaseline>,
+}
+
+/// Baseline of the secondary channel
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct SecondaryBaseline {
+    /// Index of the channel in the layout
+    pub channel: usize,
+    pub stats: ChannelStats,
+}
+
+impl BaselineStats {
+    /// Fear channel statistics
+    fn fear(&self) -> ChannelStats {
+        ChannelStats { mean: self.mean, std_dev: self.std_dev }
+    }
+
+    fn set_fear(&mut self, stats: ChannelStats) {
+        self.mean = stats.mean;
+        self.std_dev = stats.std_dev;
+    }
+
+    /// Statistics for one channel, falling back to the fear baseline (the
+    /// channel at `fear_index`), the secondary baseline or defaults
+    pub fn channel(&self, idx: usize, fear_index: usize) -> ChannelStats {
+        match &self.channels {
+            Some(channels) => channels.get(idx).copied().unwrap_or_default(),
+            None if idx == fear_index => self.fear(),
+            None => self.secondary.filter(|secondary| secondary.channel == idx).map_or(ChannelStats::default(), |secondary| secondary.stats),
+        }
+    }
+
+    /// Whether channel `idx` has a baseline of its own
+    pub fn tracks(&self, idx: usize, fear_index: usize) -> bool {
+        idx == fear_index
+            || self.channels.is_some()
+            || self.secondary.is_some_and(|secondary| secondary.channel == idx)
+    }
 }
 
 impl Default for BaselineStats {
@@ -41,10 +267,102 @@ impl Default for BaselineStats {
             std_dev: 1.0,
             sample_count: 0,
             last_update: Instant::now(),
+            channels: None,
+            secondary: None,
         }
     }
 }
 
+/// Weights combining normalized emotion channels into a fear index
+///
+/// Positive weights raise the index as a channel rises; negative weights
+/// lower it (e.g. happiness counteracting fear).
+/// Channels without a weight count for nothing.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FearIndexWeights(pub Vec<f32>);
+
+impl FearIndexWeights {
+    /// The fear channel of `layout` only
+    pub fn fear_only(layout: EmotionLayout) -> Self {
+        let mut weights = vec![0.0; layout.channels];
+        weights[layout.fear_index] = 1.0;
+        Self(weights)
+    }
+}
+
+impl Default for FearIndexWeights {
+    /// Fear channel of the standard layout only
+    fn default() -> Self {
+        Self::fear_only(EmotionLayout::STANDARD)
+    }
+}
+
+impl std::str::FromStr for FearIndexWeights {
+    type Err = String;
+
+    /// One weight per channel, comma-separated, e.g. `0,0,1,-0.5,0,0,0`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.split(',')
+            .map(|weight| {
+                weight
+                    .trim()
+                    .parse::<f32>()
+                    .ok()
+                    .filter(|weight| weight.is_finite())
+                    .ok_or_else(|| format!("Invalid fear index weight '{}'", weight.trim()))
+            })
+            .collect::<Result<_, _>>()
+            .map(Self)
+    }
+}
+
+impl std::fmt::Display for FearIndexWeights {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let weights: Vec<_> = self.0.iter().map(f32::to_string).collect();
+        write!(f, "{}", weights.join(","))
+    }
+}
+
+/// Saved calibration state
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CalibrationProfile {
+    /// Creation time in microseconds since Unix epoch (read by the retention sweep)
+    pub created_us: u64,
+    /// EMA alpha in use when the profile was taken
+    pub alpha: f32,
+    /// Baseline statistics, including per-channel stats when enabled
+    pub baseline: BaselineStats,
+}
+
+impl CalibrationProfile {
+    /// Serialize as a single JSON line
+    pub fn to_json(&self) -> Result<String, CalibrationError> {
+        serde_json::to_string(self).map_err(|e| CalibrationError::Profile(e.to_string()))
+    }
+
+    /// Parse a profile from JSON
+    pub fn from_json(json: &str) -> Result<Self, CalibrationError> {
+        serde_json::from_str(json).map_err(|e| CalibrationError::Profile(e.to_string()))
+    }
+
+    /// Write the profile to disk
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), CalibrationError> {
+        std::fs::write(path, self.to_json()? + "\n").map_err(|e| CalibrationError::Profile(e.to_string()))
+    }
+
+    /// Read a profile from disk
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, CalibrationError> {
+        let json = std::fs::read_to_string(path).map_err(|e| CalibrationError::Profile(e.to_string()))?;
+        Self::from_json(&json)
+    }
+
+    /// Time since the profile was taken; zero for profiles from the future
+    pub fn age(&self) -> Duration {
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default();
+        now.saturating_sub(Duration::from_micros(self.created_us))
+    }
+}
+
 /// Adaptive fear calibrator with EMA updates
 pub struct AdaptiveCalibrator {
     /// Current baseline statistics
@@ -61,8 +379,14 @@ pub struct AdaptiveCalibrator {
     start_time: Instant,
     /// Whether initial calibration is complete
     initial_complete: bool,
+    /// Samples and accumulators of the initial period
+    initial: InitialCalibration,
     /// Previous mean for drift calculation
     previous_mean: f32,
+    /// Logits every sample must have, and where fear sits in them
+    layout: EmotionLayout,
+    /// Weights the fear score is built from; the fear channel alone when unset
+    fear_index_weights: Option<FearIndexWeights>,
 }
 
 impl AdaptiveCalibrator {
@@ -72,11 +396,14 @@ impl AdaptiveCalibrator {
             baseline: BaselineStats::default(),
             alpha,
             frozen: false,
-            min_samples: 30, // Minimum samples for stable statistics
+            min_samples: DEFAULT_MIN_SAMPLES,
             initial_period,
             start_time: Instant::now(),
             initial_complete: false,
+            initial: InitialCalibration::default(),
             previous_mean: 0.0,
+            layout: EmotionLayout::STANDARD,
+            fear_index_weights: None,
         }
     }
 
@@ -85,57 +412,212 @@ impl AdaptiveCalibrator {
         Self::new(initial_period, 0.05)
     }
 
+    /// Track a baseline for each emotion channel
+    pub fn with_per_channel_calibration(mut self, enabled: bool) -> Self {
+        self.baseline.channels = enabled.then(|| vec![ChannelStats::default(); self.layout.channels]);
+        self
+    }
+
+    /// Track a baseline for `channel` besides fear, e.g. surprise, without
+    /// per-channel calibration of every channel
+    ///
+    /// `None`, or the fear channel itself, tracks fear alone.
+    pub fn with_secondary_channel(mut self, channel: Option<usize>) -> Self {
+        self.baseline.secondary = channel
+            .filter(|&channel| channel != self.layout.fear_index)
+            .map(|channel| SecondaryBaseline { channel, stats: ChannelStats::default() });
+        self
+    }
+
+    /// Expect logits in `layout` (seven channels, fear at 2, by default)
+    pub fn with_layout(mut self, layout: EmotionLayout) -> Self {
+        self.layout = layout;
+        if let Some(channels) = &mut self.baseline.channels {
+            channels.resize(layout.channels, ChannelStats::default());
+        }
+        self
+    }
+
+    /// Score frames with the fear index of `weights` rather than the fear
+    /// channel alone; `None` scores fear alone
+    pub fn with_fear_index_weights(mut self, weights: Option<FearIndexWeights>) -> Self {
+        self.fear_index_weights = weights;
+        self
+    }
+
+    /// Require at least `min_samples` (one or more) before the initial
+    /// calibration completes, so a zero or very short period cannot
+    /// complete it on the first frames
+    pub fn with_min_samples(mut self, min_samples: usize) -> Self {
+        self.min_samples = min_samples.max(1);
+        self
+    }
+
+    /// Samples the initial calibration needs at least
+    pub fn min_samples(&self) -> usize {
+        self.min_samples
+    }
+
+    /// Samples the initial calibration takes at `fps`: the initial period's
+    /// worth, but never fewer than [`min_samples`](Self::min_samples)
+    pub fn target_samples(&self, fps: f32) -> usize {
+        let period_samples = (self.initial_period.as_secs_f32() * fps.max(0.0)).ceil() as usize;
+        period_samples.max(self.min_samples)
+    }
+
+    /// Logits the calibrator expects
+    pub fn layout(&self) -> EmotionLayout {
+        self.layout
+    }
+
+    /// Weights frames are scored with, if any
+    pub fn fear_index_weights(&self) -> Option<&FearIndexWeights> {
+        self.fear_index_weights.as_ref()
+    }
+
+    /// Whether per-channel baselines are tracked
+    pub fn per_channel_calibration(&self) -> bool {
+        self.baseline.channels.is_some()
+    }
+
+    /// Channel tracked besides fear, if any
+    pub fn secondary_channel(&self) -> Option<usize> {
+        self.baseline.secondary.map(|secondary| secondary.channel)
+    }
+
+    /// Add a full set of emotion logits
+    ///
+    /// Updates the fear baseline and, when enabled, every channel baseline
+    /// or the secondary one. Logits of another length than the layout's are
+    /// rejected.
+    pub fn add_logits(&mut self, emotion_logits: &[f32]) -> Result<(), CalibrationError> {
+        self.add_weighted_logits(emotion_logits, 1.0)
+    }
+
+    /// [`add_logits`](Self::add_logits) for a frame of the given confidence
+    ///
+    /// The weight, clamped to [0.0, 1.0], is how much the frame counts
+    /// towards the baseline: a fraction of a sample during the initial
+    /// period and a proportionally smaller EMA step after it. Frames of
+    /// zero or NaN weight are skipped.
+    pub fn add_weighted_logits(&mut self, emotion_logits: &[f32], weight: f32) -> Result<(), CalibrationError> {
+        if self.frozen {
+            return Err(CalibrationError::Frozen);
+        }
+        if emotion_logits.len() != self.layout.channels {
+            return Err(LogitsError::Length {
+                expected: self.layout.channels,
+                got: emotion_logits.len(),
+            }
+            .into());
+        }
+
+        let Some(weight) = sample_weight(weight) else {
+            return Ok(());
+        };
+        if emotion_logits.iter().any(|logit| !logit.is_finite()) {
+            return Ok(()); // Skip invalid samples
+        }
+
+        let fear_logit = emotion_logits[self.layout.fear_index];
+        if self.rejects(fear_logit) {
+            return Ok(());
+        }
+
+        let alpha = self.alpha * weight;
+        if let Some(channels) = &mut self.baseline.channels {
+            if self.initial_complete {
+                for (stats, &logit) in channels.iter_mut().zip(emotion_logits) {
+                    stats.update_ema(logit, alpha);
+                }
+            } else {
+                let running = &mut self.initial.channels;
+                running.resize(channels.len(), RunningStats::default());
+                for ((stats, running), &logit) in channels.iter_mut().zip(running.iter_mut()).zip(emotion_logits) {
+                    running.push(logit, weight);
+                    *stats = running.stats();
+                }
+            }
+        }
+        if let Some(secondary) = &mut self.baseline.secondary {
+            if let Some(&logit) = emotion_logits.get(secondary.channel) {
+                if self.initial_complete {
+                    secondary.stats.update_ema(logit, alpha);
+                } else {
+                    self.initial.secondary.push(logit, weight);
+                    secondary.stats = self.initial.secondary.stats();
+                }
+            }
+        }
+
+        self.record_sample(fear_logit, weight)
+    }
+
     /// Add a new fear logit sample
     pub fn add_sample(&mut self, fear_logit: f32) -> Result<(), CalibrationError> {
+        self.add_weighted_sample(fear_logit, 1.0)
+    }
+
+    /// [`add_sample`](Self::add_sample) weighted by confidence, as in
+    /// [`add_weighted_logits`](Self::add_weighted_logits)
+    pub fn add_weighted_sample(&mut self, fear_logit: f32, weight: f32) -> Result<(), CalibrationError> {
         if self.frozen {
             return Err(CalibrationError::Frozen);
         }
 
-        if !fear_logit.is_finite() {
-            return Ok(()); // Skip invalid samples
+        let Some(weight) = sample_weight(weight) else {
+            return Ok(());
+        };
+        if !fear_logit.is_finite() || self.rejects(fear_logit) {
+            return Ok(()); // Skip invalid samples and initial-period outliers
+        }
+
+        self.record_sample(fear_logit, weight)
+    }
+
+    /// Whether `fear_logit` is an outlier to leave out of the initial baseline
+    ///
+    /// A long enough run of outliers is a real shift and is let through.
+    fn rejects(&mut self, fear_logit: f32) -> bool {
+        if self.initial_complete || !self.initial.is_outlier(fear_logit) {
+            self.initial.consecutive_outliers = 0;
+            return false;
+        }
+        if self.initial.consecutive_outliers >= MAX_CONSECUTIVE_OUTLIERS {
+            return false;
         }
+        self.initial.consecutive_outliers += 1;
+        tracing::debug!("Discarding outlier {:.3} from the initial calibration", fear_logit);
+        true
+    }
 
+    /// Count an accepted fear sample and update the baseline with it
+    fn record_sample(&mut self, fear_logit: f32, weight: f32) -> Result<(), CalibrationError> {
         self.baseline.sample_count += 1;
         self.baseline.last_update = Instant::now();
 
         if !self.initial_complete {
             // During initial calibration, collect samples for batch statistics
-            self.update_initial_calibration(fear_logit)?;
+            self.update_initial_calibration(fear_logit, weight)?;
         } else {
             // After initial calibration, use EMA updates
-            self.update_ema(fear_logit);
+            self.update_ema(fear_logit, weight);
         }
 
         Ok(())
     }
 
     /// Update during initial calibration period
-    fn update_initial_calibration(&mut self, fear_logit: f32) -> Result<(), CalibrationError> {
-        // For initial calibration, we need to collect samples and compute statistics
-        // This is a simplified approach - in practice, you'd want to store samples
-        // and compute proper mean/variance
-        
-        if self.baseline.sample_count == 1 {
-            // First sample
-            self.baseline.mean = fear_logit;
-            self.baseline.std_dev = 1.0; // Default until we have more samples
-        } else {
-            // Update running statistics
-            let delta = fear_logit - self.baseline.mean;
-            self.baseline.mean += delta / self.baseline.sample_count as f32;
-            
-            // Simple variance estimation (not optimal but functional)
-            if self.baseline.sample_count > 1 {
-                let variance = self.baseline.std_dev.powi(2);
-                let new_variance = variance + (delta * delta - variance) / self.baseline.sample_count as f32;
-                self.baseline.std_dev = new_variance.sqrt().max(0.1); // Minimum std_dev
-            }
-        }
+    fn update_initial_calibration(&mut self, fear_logit: f32, weight: f32) -> Result<(), CalibrationError> {
+        self.initial.fear_samples.push(fear_logit);
+        self.initial.fear.push(fear_logit, weight);
+        self.baseline.set_fear(self.initial.fear.stats());
 
         // Check if initial calibration is complete
         let elapsed = self.start_time.elapsed();
         if elapsed >= self.initial_period && self.baseline.sample_count >= self.min_samples as u32 {
             self.initial_complete = true;
+            self.initial = InitialCalibration::default();
             self.previous_mean = self.baseline.mean;
             tracing::info!(
                 "Initial calibration complete: mean={:.3}, std_dev={:.3}, samples={}",
@@ -148,35 +630,87 @@ impl AdaptiveCalibrator {
         Ok(())
     }
 
-    /// Update using exponential moving average
-    fn update_ema(&mut self, fear_logit: f32) {
-        let old_mean = self.baseline.mean;
-        
-        // Update mean using EMA
-        self.baseline.mean = (1.0 - self.alpha) * self.baseline.mean + self.alpha * fear_logit;
-        
-        // Update variance using EMA
-        let delta = fear_logit - old_mean;
-        let variance = self.baseline.std_dev.powi(2);
-        let new_variance = (1.0 - self.alpha) * variance + self.alpha * delta.powi(2);
-        self.baseline.std_dev = new_variance.sqrt().max(0.1); // Minimum std_dev
+    /// Update using exponential moving average, the step scaled by `weight`
+    fn update_ema(&mut self, fear_logit: f32, weight: f32) {
+        let mut fear = self.baseline.fear();
+        fear.update_ema(fear_logit, self.alpha * weight);
+        self.baseline.set_fear(fear);
+    }
+
+    /// Cap tracked channels of `emotion_logits` at mean ± `k`·std of their baselines
+    ///
+    /// Only channels with a baseline are capped: fear, the secondary channel
+    /// and, with per-channel calibration, all the others. Nothing is capped
+    /// before the initial calibration completes. Returns the capped logits and whether any value changed.
+    pub fn winsorize(&self, emotion_logits: &EmotionLogits, k: f32) -> (EmotionLogits, bool) {
+        let mut capped = emotion_logits.clone();
+        if !self.initial_complete {
+            return (capped, false);
+        }
+
+        let fear_index = capped.layout().fear_index;
+        let mut changed = false;
+        for (idx, logit) in capped.iter_mut().enumerate() {
+            if !self.baseline.tracks(idx, fear_index) {
+                continue;
+            }
+            let stats = self.baseline.channel(idx, fear_index);
+            let bound = k * stats.std_dev;
+            let clamped = logit.clamp(stats.mean - bound, stats.mean + bound);
+            // NaN stays NaN so add_logits still skips the sample
+            if clamped != *logit && logit.is_finite() {
+                *logit = clamped;
+                changed = true;
+            }
+        }
+        (capped, changed)
     }
 
     /// Normalize a fear logit to [0, 1] range
     pub fn normalize_fear(&self, fear_logit: f32) -> f32 {
         if !self.is_calibrated() {
             // During calibration, return neutral fear
-            return 0.3;
+            return UNCALIBRATED_SCORE;
         }
 
-        // Z-score normalization
-        let z_score = (fear_logit - self.baseline.mean) / self.baseline.std_dev;
-        
-        // Convert to [0, 1] using sigmoid function
-        let sigmoid = 1.0 / (1.0 + (-z_score).exp());
-        
-        // Clamp to [0, 1] for safety
-        sigmoid.clamp(0.0, 1.0)
+        // Z-score normalization followed by a sigmoid
+        self.baseline.fear().normalize(fear_logit)
+    }
+
+    /// Normalize the raw logit of channel `idx` to [0, 1] against its own baseline
+    ///
+    /// Without per-channel calibration only the fear and secondary channels
+    /// have a baseline; other channels are normalized against mean 0 and
+    /// unit deviation.
+    pub fn normalize_channel(&self, idx: usize, raw: f32) -> f32 {
+        if !self.is_calibrated() {
+            return UNCALIBRATED_SCORE;
+        }
+        self.baseline.channel(idx, self.layout.fear_index).normalize(raw)
+    }
+
+    /// Weighted composite of normalized channels in [0, 1]
+    pub fn fear_index(&self, emotion_logits: &[f32], weights: &FearIndexWeights) -> f32 {
+        if !self.is_calibrated() {
+            return UNCALIBRATED_SCORE;
+        }
+
+        let normalized = emotion_logits
+            .iter()
+            .enumerate()
+            .map(|(idx, &raw)| self.normalize_channel(idx, raw));
+        weighted_fear_index(normalized, &weights.0).unwrap_or(UNCALIBRATED_SCORE)
+    }
+
+    /// Fear score of a frame's logits in [0, 1]
+    ///
+    /// The [`fear_index`](Self::fear_index) of the configured weights, or
+    /// the normalized fear channel when none are configured.
+    pub fn fear_score(&self, emotion_logits: &EmotionLogits) -> f32 {
+        match &self.fear_index_weights {
+            Some(weights) => self.fear_index(emotion_logits, weights),
+            None => self.normalize_fear(emotion_logits.fear()),
+        }
     }
 
     /// Check if calibration is complete
@@ -196,6 +730,17 @@ impl AdaptiveCalibrator {
         time_progress.min(sample_progress).min(1.0)
     }
 
+    /// How established the baseline is [0.0, 1.0]
+    ///
+    /// Zero until the initial calibration completes, then the share of
+    /// `ESTABLISHED_SAMPLES` (about ten seconds at 30 fps) seen so far.
+    pub fn quality(&self) -> f32 {
+        if !self.initial_complete {
+            return 0.0;
+        }
+        (self.baseline.sample_count as f32 / ESTABLISHED_SAMPLES as f32).min(1.0)
+    }
+
     /// Freeze calibration to prevent further updates
     pub fn freeze(&mut self) {
         self.frozen = true;
@@ -211,9 +756,16 @@ impl AdaptiveCalibrator {
 
     /// Reset calibration to initial state
     pub fn reset(&mut self) {
+        let per_channel = self.per_channel_calibration();
+        let secondary = self.secondary_channel();
         self.baseline = BaselineStats::default();
+        if per_channel {
+            self.baseline.channels = Some(vec![ChannelStats::default(); self.layout.channels]);
+        }
+        self.baseline.secondary = secondary.map(|channel| SecondaryBaseline { channel, stats: ChannelStats::default() });
         self.start_time = Instant::now();
         self.initial_complete = false;
+        self.initial = InitialCalibration::default();
         self.frozen = false;
         self.previous_mean = 0.0;
         tracing::info!("Calibration reset");
@@ -224,6 +776,76 @@ impl AdaptiveCalibrator {
         &self.baseline
     }
 
+    /// Capture the current calibration as a profile
+    pub fn snapshot(&self) -> CalibrationProfile {
+        CalibrationProfile {
+            created_us: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_micros() as u64,
+            alpha: self.alpha,
+            baseline: self.baseline.clone(),
+        }
+    }
+
+    /// Restore a saved profile, skipping the initial period if it had enough samples
+    ///
+    /// Per-channel baselines saved for another layout start over, as does
+    /// the secondary baseline when the profile tracked another channel.
+    pub fn restore(&mut self, profile: &CalibrationProfile) -> Result<(), CalibrationError> {
+        self.set_alpha(profile.alpha)?;
+        let secondary = self.secondary_channel().map(|channel| match profile.baseline.secondary {
+            Some(saved) if saved.channel == channel => saved,
+            _ => SecondaryBaseline { channel, stats: ChannelStats::default() },
+        });
+        self.baseline = profile.baseline.clone();
+        self.baseline.secondary = secondary;
+        self.baseline.last_update = Instant::now();
+        if let Some(channels) = &mut self.baseline.channels {
+            if channels.len() != self.layout.channels {
+                *channels = vec![ChannelStats::default(); self.layout.channels];
+            }
+        }
+        self.initial_complete = self.baseline.sample_count >= self.min_samples as u32;
+        self.initial = if self.initial_complete {
+            InitialCalibration::default()
+        } else {
+            InitialCalibration::seeded(&self.baseline)
+        };
+        self.previous_mean = self.baseline.mean;
+        Ok(())
+    }
+
+    /// Write the current calibration to `path`
+    ///
+    /// Goes through a temporary file beside `path`, so a crash mid-write
+    /// leaves the previous cache in place.
+    pub fn save_to_file(&self, path: impl AsRef<Path>) -> Result<(), CalibrationError> {
+        let path = path.as_ref();
+        let mut temp = path.as_os_str().to_owned();
+        temp.push(".tmp");
+        self.snapshot().save(&temp)?;
+        std::fs::rename(&temp, path).map_err(|e| CalibrationError::Profile(e.to_string()))
+    }
+
+    /// Restore the calibration saved at `path` if it is at most `max_age` old
+    ///
+    /// Unreadable, stale or non-finite profiles are rejected and leave the
+    /// calibrator as it was.
+    pub fn load_from_file(&mut self, path: impl AsRef<Path>, max_age: Duration) -> Result<(), CalibrationError> {
+        let profile = CalibrationProfile::load(path)?;
+        let age = profile.age();
+        if age > max_age {
+            return Err(CalibrationError::StaleProfile { age, max_age });
+        }
+        let baseline = &profile.baseline;
+        let mut channels = baseline.channels.iter().flatten().chain(baseline.secondary.iter().map(|secondary| &secondary.stats));
+        if !baseline.fear().is_usable() || channels.any(|stats| !stats.is_usable()) {
+            return Err(CalibrationError::Profile("baseline statistics are not finite and positive".to_string()));
+        }
+        self.restore(&profile)
+    }
+
     /// Calculate calibration drift (change in mean since last check)
     pub fn calculate_drift(&mut self) -> f32 {
         let current_drift = (self.baseline.mean - self.previous_mean).abs();
@@ -256,6 +878,7 @@ impl AdaptiveCalibrator {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use spectremesh_core::scoring::FEAR_INDEX;
     use std::time::Duration;
 
     #[test]
@@ -273,6 +896,30 @@ mod tests {
         assert_eq!(calibrator.alpha(), 0.05);
     }
 
+    #[test]
+    fn test_short_period_still_needs_min_samples() {
+        // A tenth of a second at 30 FPS is three frames
+        let mut calibrator = AdaptiveCalibrator::with_defaults(Duration::from_millis(100)).with_min_samples(10);
+        assert_eq!(calibrator.min_samples(), 10);
+        assert_eq!(calibrator.target_samples(30.0), 10);
+        assert_eq!(AdaptiveCalibrator::with_defaults(Duration::from_secs(2)).target_samples(30.0), 60);
+        assert_eq!(AdaptiveCalibrator::with_defaults(Duration::ZERO).target_samples(30.0), DEFAULT_MIN_SAMPLES);
+
+        std::thread::sleep(Duration::from_millis(110));
+        for i in 0..9 {
+            calibrator.add_sample(0.5 + i as f32 * 0.01).unwrap();
+            assert!(!calibrator.is_calibrated(), "calibrated after {} samples", i + 1);
+        }
+        assert!(calibrator.progress() < 1.0);
+        calibrator.add_sample(0.5).unwrap();
+        assert!(calibrator.is_calibrated());
+
+        let mut zero = AdaptiveCalibrator::new(Duration::ZERO, 0.05).with_min_samples(0);
+        assert_eq!(zero.min_samples(), 1);
+        zero.add_sample(0.5).unwrap();
+        assert!(zero.is_calibrated());
+    }
+
     #[test]
     fn test_initial_calibration() {
         let mut calibrator = AdaptiveCalibrator::new(Duration::from_millis(100), 0.05);
@@ -285,12 +932,19 @@ mod tests {
 
         // Wait for initial period to complete
         std::thread::sleep(Duration::from_millis(150));
+        assert_eq!(calibrator.quality(), 0.0);
         
         // Add one more sample to trigger completion check
         calibrator.add_sample(0.6).unwrap();
         
         assert!(calibrator.is_calibrated());
         assert_eq!(calibrator.progress(), 1.0);
+        assert_eq!(calibrator.quality(), 51.0 / ESTABLISHED_SAMPLES as f32);
+
+        for _ in 0..ESTABLISHED_SAMPLES {
+            calibrator.add_sample(0.6).unwrap();
+        }
+        assert_eq!(calibrator.quality(), 1.0);
     }
 
     #[test]
@@ -374,4 +1028,420 @@ mod tests {
         assert!((drift - 0.1).abs() < f32::EPSILON);
         assert_eq!(calibrator.previous_mean, 0.5); // Should be updated
     }
+
+    /// Alternate around `mean` so the channel's deviation is `spread`
+    fn synthetic_logits(step: usize) -> [f32; EMOTION_CHANNELS] {
+        let sign = if step.is_multiple_of(2) { 1.0 } else { -1.0 };
+        let mut logits = [0.0; EMOTION_CHANNELS];
+        for (idx, logit) in logits.iter_mut().enumerate() {
+            *logit = idx as f32 * 0.5 + sign * 0.2 * (idx + 1) as f32;
+        }
+        logits
+    }
+
+    #[test]
+    fn test_per_channel_baselines_converge_independently() {
+        let mut calibrator = AdaptiveCalibrator::new(Duration::ZERO, 0.05).with_per_channel_calibration(true);
+        assert!(calibrator.per_channel_calibration());
+
+        for step in 0..600 {
+            calibrator.add_logits(&synthetic_logits(step)).unwrap();
+        }
+        assert!(calibrator.is_calibrated());
+
+        let channels = calibrator.baseline_stats().channels.clone().unwrap();
+        for (idx, stats) in channels.iter().enumerate() {
+            let expected_mean = idx as f32 * 0.5;
+            let expected_std = 0.2 * (idx + 1) as f32;
+            assert!((stats.mean - expected_mean).abs() < 0.1, "channel {} mean {}", idx, stats.mean);
+            assert!((stats.std_dev - expected_std).abs() < 0.15 * expected_std, "channel {} std {}", idx, stats.std_dev);
+        }
+
+        // The legacy fear baseline tracks the fear channel
+        let fear = channels[FEAR_INDEX];
+        assert!((calibrator.baseline_stats().mean - fear.mean).abs() < 1e-5);
+        assert!((calibrator.normalize_fear(1.7) - calibrator.normalize_channel(FEAR_INDEX, 1.7)).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_secondary_channel_has_its_own_baseline() {
+        const SURPRISE: usize = 5;
+        let mut calibrator = AdaptiveCalibrator::new(Duration::ZERO, 0.05).with_secondary_channel(Some(SURPRISE));
+        assert_eq!(calibrator.secondary_channel(), Some(SURPRISE));
+        assert!(!calibrator.per_channel_calibration());
+        // Fear is always tracked, so it is no secondary channel
+        assert_eq!(AdaptiveCalibrator::new(Duration::ZERO, 0.05).with_secondary_channel(Some(FEAR_INDEX)).secondary_channel(), None);
+
+        for step in 0..600 {
+            calibrator.add_logits(&synthetic_logits(step)).unwrap();
+        }
+        let baseline = calibrator.baseline_stats();
+        let surprise = baseline.secondary.unwrap().stats;
+        assert!((surprise.mean - 2.5).abs() < 0.1, "surprise mean {}", surprise.mean);
+        assert!((surprise.std_dev - 1.2).abs() < 0.15 * 1.2, "surprise std {}", surprise.std_dev);
+        assert!((baseline.mean - 1.0).abs() < 0.1);
+        // Untracked channels keep the default baseline
+        assert_eq!(baseline.channel(3, FEAR_INDEX), ChannelStats::default());
+        assert!((calibrator.normalize_channel(SURPRISE, 2.5) - 0.5).abs() < 0.05);
+
+        // Only tracked channels are capped
+        let extreme = EmotionLogits::from([9.0, 0.0, 1.0, 0.0, 0.0, 9.0, 0.0]);
+        let (capped, changed) = calibrator.winsorize(&extreme, 3.0);
+        assert!(changed);
+        assert_eq!(capped[0], 9.0);
+        assert!(capped[SURPRISE] < 7.0);
+
+        // The baseline survives a profile, but not into a calibrator tracking another channel
+        let profile = CalibrationProfile::from_json(&calibrator.snapshot().to_json().unwrap()).unwrap();
+        let mut restored = AdaptiveCalibrator::new(Duration::ZERO, 0.05).with_secondary_channel(Some(SURPRISE));
+        restored.restore(&profile).unwrap();
+        assert_eq!(restored.baseline_stats().secondary, calibrator.baseline_stats().secondary);
+        let mut happy = AdaptiveCalibrator::new(Duration::ZERO, 0.05).with_secondary_channel(Some(3));
+        happy.restore(&profile).unwrap();
+        assert_eq!(happy.baseline_stats().secondary.unwrap().stats, ChannelStats::default());
+
+        calibrator.reset();
+        assert_eq!(calibrator.secondary_channel(), Some(SURPRISE));
+        assert_eq!(calibrator.baseline_stats().secondary.unwrap().stats, ChannelStats::default());
+    }
+
+    #[test]
+    fn test_fear_index_uses_normalized_channels() {
+        // A resting face that reads strongly "angry"
+        let resting = |step: usize| {
+            let jitter = if step.is_multiple_of(2) { 0.1 } else { -0.1 };
+            [2.0 + jitter, 0.0, jitter, 0.0, 0.0, 0.0, 0.0]
+        };
+        let mut weights = [0.0; EMOTION_CHANNELS];
+        weights[0] = 0.5;
+        weights[FEAR_INDEX] = 0.5;
+        let weights = FearIndexWeights(weights.to_vec());
+
+        let mut per_channel = AdaptiveCalibrator::new(Duration::ZERO, 0.05).with_per_channel_calibration(true);
+        let mut fear_only = AdaptiveCalibrator::new(Duration::ZERO, 0.05);
+        for step in 0..100 {
+            per_channel.add_logits(&resting(step)).unwrap();
+            fear_only.add_logits(&resting(step)).unwrap();
+        }
+
+        let at_rest = [2.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0];
+        assert!((per_channel.fear_index(&at_rest, &weights) - 0.5).abs() < 0.05);
+        // Without a per-channel baseline the resting anger skews the composite
+        assert!(fear_only.fear_index(&at_rest, &weights) > 0.65);
+
+        let scared = [2.0, 0.0, 0.5, 0.0, 0.0, 0.0, 0.0];
+        assert!(per_channel.fear_index(&scared, &weights) > 0.7);
+
+        // Negative weights invert a channel's contribution
+        let mut calming = [0.0; EMOTION_CHANNELS];
+        calming[0] = -1.0;
+        let calming = FearIndexWeights(calming.to_vec());
+        let angry = [3.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0];
+        assert!(per_channel.fear_index(&angry, &calming) < 0.1);
+        assert_eq!(FearIndexWeights::default().0[FEAR_INDEX], 1.0);
+    }
+
+    #[test]
+    fn test_fear_score_follows_configured_weights() {
+        let weights: FearIndexWeights = "0.5, 0, 0.5, 0, 0, 0, 0".parse().unwrap();
+        assert_eq!(weights.to_string().parse::<FearIndexWeights>().unwrap(), weights);
+        assert!("0,fear,1".parse::<FearIndexWeights>().is_err());
+        assert!("0,NaN,1".parse::<FearIndexWeights>().is_err());
+
+        let mut weighted = AdaptiveCalibrator::new(Duration::ZERO, 0.05)
+            .with_per_channel_calibration(true)
+            .with_fear_index_weights(Some(weights.clone()));
+        let mut fear_only = AdaptiveCalibrator::new(Duration::ZERO, 0.05).with_per_channel_calibration(true);
+        assert_eq!(fear_only.fear_index_weights(), None);
+        for step in 0..100 {
+            let jitter = if step % 2 == 0 { 0.1 } else { -0.1 };
+            let logits = [jitter, 0.0, jitter, 0.0, 0.0, 0.0, 0.0];
+            weighted.add_logits(&logits).unwrap();
+            fear_only.add_logits(&logits).unwrap();
+        }
+
+        // Fear alone without weights, the composite with them
+        let scared = EmotionLogits::from([0.0, 0.0, 0.5, 0.0, 0.0, 0.0, 0.0]);
+        assert_eq!(fear_only.fear_score(&scared), fear_only.normalize_fear(0.5));
+        assert_eq!(weighted.fear_score(&scared), weighted.fear_index(&scared, &weights));
+        assert!(weighted.fear_score(&scared) < fear_only.fear_score(&scared));
+
+        // Weights survive a reset
+        weighted.reset();
+        assert_eq!(weighted.fear_index_weights(), Some(&weights));
+    }
+
+    #[test]
+    fn test_layout_sets_channels_and_fear_index() {
+        let layout = EmotionLayout::new(10, 4).unwrap();
+        let mut calibrator = AdaptiveCalibrator::new(Duration::ZERO, 0.05)
+            .with_per_channel_calibration(true)
+            .with_layout(layout);
+        assert_eq!(calibrator.layout(), layout);
+
+        for step in 0..60u32 {
+            let mut logits = vec![0.0; 10];
+            logits[4] = if step.is_multiple_of(2) { 1.2 } else { 0.8 };
+            calibrator.add_logits(&logits).unwrap();
+        }
+        assert_eq!(calibrator.baseline_stats().channels.as_ref().unwrap().len(), 10);
+        assert!((calibrator.baseline_stats().mean - 1.0).abs() < 0.05);
+        assert_eq!(FearIndexWeights::fear_only(layout).0[4], 1.0);
+
+        // Seven logits no longer fit: rejected, not truncated or padded
+        let error = calibrator.add_logits(&[0.0; 7]).unwrap_err();
+        assert!(matches!(error, CalibrationError::Logits(LogitsError::Length { expected: 10, got: 7 })));
+
+        // A standard profile's per-channel stats do not carry over
+        let standard = AdaptiveCalibrator::new(Duration::ZERO, 0.05).with_per_channel_calibration(true);
+        calibrator.restore(&standard.snapshot()).unwrap();
+        assert_eq!(calibrator.baseline_stats().channels.as_ref().unwrap().len(), 10);
+    }
+
+    #[test]
+    fn test_profile_round_trip_with_channels() {
+        let mut calibrator = AdaptiveCalibrator::new(Duration::ZERO, 0.1).with_per_channel_calibration(true);
+        for step in 0..60 {
+            calibrator.add_logits(&synthetic_logits(step)).unwrap();
+        }
+
+        let profile = calibrator.snapshot();
+        let path = std::env::temp_dir().join(format!("spectre_profile_{}.json", std::process::id()));
+        profile.save(&path).unwrap();
+        let loaded = CalibrationProfile::load(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(loaded.baseline.channels, profile.baseline.channels);
+        assert_eq!(loaded.baseline.sample_count, 60);
+        assert_eq!(loaded.alpha, 0.1);
+
+        let mut restored = AdaptiveCalibrator::with_defaults(Duration::from_secs(30));
+        restored.restore(&loaded).unwrap();
+        assert!(restored.is_calibrated());
+        assert!(restored.per_channel_calibration());
+        for idx in 0..EMOTION_CHANNELS {
+            assert_eq!(restored.normalize_channel(idx, 1.0), calibrator.normalize_channel(idx, 1.0));
+        }
+
+        // Profiles saved without per-channel stats still load
+        let legacy = r#"{"created_us":1,"alpha":0.05,"baseline":{"mean":0.4,"std_dev":0.2,"sample_count":50}}"#;
+        let legacy = CalibrationProfile::from_json(legacy).unwrap();
+        assert!(legacy.baseline.channels.is_none());
+    }
+
+    fn cache_path(name: &str) -> std::path::PathBuf {
+        std::env::temp_dir().join(format!("spectre_cache_{}_{}.json", name, std::process::id()))
+    }
+
+    #[test]
+    fn test_cache_file_round_trip() {
+        let mut calibrator = AdaptiveCalibrator::new(Duration::ZERO, 0.1);
+        for step in 0..60 {
+            calibrator.add_sample(step as f32 * 0.01).unwrap();
+        }
+        let path = cache_path("round_trip");
+        calibrator.save_to_file(&path).unwrap();
+
+        // A fresh calibrator skips the initial period and keeps updating by EMA
+        let mut restored = AdaptiveCalibrator::with_defaults(Duration::from_secs(30));
+        restored.load_from_file(&path, Duration::from_secs(60)).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert!(restored.is_calibrated());
+        assert_eq!(restored.alpha(), 0.1);
+        assert_eq!(restored.baseline_stats().mean, calibrator.baseline_stats().mean);
+        assert_eq!(restored.normalize_fear(0.3), calibrator.normalize_fear(0.3));
+
+        let mean = restored.baseline_stats().mean;
+        restored.add_sample(5.0).unwrap();
+        assert!((restored.baseline_stats().mean - (0.9 * mean + 0.1 * 5.0)).abs() < 1e-5);
+    }
+
+    #[test]
+    fn test_stale_cache_file_is_rejected() {
+        let mut calibrator = AdaptiveCalibrator::new(Duration::ZERO, 0.05);
+        for _ in 0..40 {
+            calibrator.add_sample(0.5).unwrap();
+        }
+        let mut profile = calibrator.snapshot();
+        profile.created_us -= 2 * 3_600_000_000;
+        let path = cache_path("stale");
+        profile.save(&path).unwrap();
+
+        let mut restored = AdaptiveCalibrator::with_defaults(Duration::from_secs(30));
+        let result = restored.load_from_file(&path, Duration::from_secs(3600));
+        assert!(matches!(result, Err(CalibrationError::StaleProfile { .. })), "{:?}", result);
+        assert!(!restored.is_calibrated());
+
+        // The same profile is recent enough under a longer limit
+        restored.load_from_file(&path, Duration::from_secs(3 * 3600)).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert!(restored.is_calibrated());
+    }
+
+    #[test]
+    fn test_corrupt_cache_file_leaves_calibrator_untouched() {
+        let path = cache_path("corrupt");
+        let mut calibrator = AdaptiveCalibrator::with_defaults(Duration::from_secs(30));
+
+        std::fs::write(&path, "{\"created_us\":").unwrap();
+        assert!(matches!(calibrator.load_from_file(&path, Duration::MAX), Err(CalibrationError::Profile(_))));
+
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_micros();
+        let degenerate = format!(
+            r#"{{"created_us":{},"alpha":0.05,"baseline":{{"mean":0.4,"std_dev":0.0,"sample_count":50}}}}"#,
+            now
+        );
+        std::fs::write(&path, degenerate).unwrap();
+        assert!(matches!(calibrator.load_from_file(&path, Duration::MAX), Err(CalibrationError::Profile(_))));
+        std::fs::remove_file(&path).unwrap();
+
+        assert!(calibrator.load_from_file(&path, Duration::MAX).is_err());
+        assert!(!calibrator.is_calibrated());
+        assert_eq!(calibrator.baseline_stats().sample_count, 0);
+    }
+
+    /// Normally distributed samples (Box-Muller) from a seeded generator
+    fn normal_samples(seed: u64, count: usize, mean: f32, std_dev: f32) -> Vec<f32> {
+        use rand::{Rng, SeedableRng};
+        let mut rng = rand::rngs::StdRng::seed_from_u64(seed);
+        (0..count)
+            .map(|_| {
+                let (u1, u2): (f32, f32) = (rng.gen_range(f32::EPSILON..1.0), rng.gen());
+                mean + std_dev * (-2.0 * u1.ln()).sqrt() * (std::f32::consts::TAU * u2).cos()
+            })
+            .collect()
+    }
+
+    /// Mean and sample standard deviation computed in one batch
+    fn batch_stats(samples: &[f32]) -> ChannelStats {
+        let n = samples.len() as f64;
+        let mean = samples.iter().map(|&x| x as f64).sum::<f64>() / n;
+        let variance = samples.iter().map(|&x| (x as f64 - mean).powi(2)).sum::<f64>() / (n - 1.0);
+        ChannelStats { mean: mean as f32, std_dev: (variance.sqrt() as f32).max(MIN_STD_DEV) }
+    }
+
+    #[test]
+    fn test_initial_baseline_matches_batch_statistics() {
+        use rand::{Rng, SeedableRng};
+        let mut rng = rand::rngs::StdRng::seed_from_u64(7);
+        for seed in 0..40 {
+            // Uniform channels have no samples far enough out to be rejected
+            let count = rng.gen_range(2..300);
+            let logits: Vec<[f32; EMOTION_CHANNELS]> = (0..count)
+                .map(|_| {
+                    let mut logits = [0.0; EMOTION_CHANNELS];
+                    for (idx, logit) in logits.iter_mut().enumerate() {
+                        *logit = idx as f32 - 3.0 + rng.gen_range(-1.0..1.0) * (idx + 1) as f32 * 0.3;
+                    }
+                    logits
+                })
+                .collect();
+
+            let mut calibrator = AdaptiveCalibrator::new(Duration::from_secs(3600), 0.05).with_per_channel_calibration(true);
+            for sample in &logits {
+                calibrator.add_logits(sample).unwrap();
+            }
+            let baseline = calibrator.baseline_stats();
+            assert_eq!(baseline.sample_count, count as u32, "seed {}", seed);
+
+            for idx in 0..EMOTION_CHANNELS {
+                let channel: Vec<f32> = logits.iter().map(|sample| sample[idx]).collect();
+                let expected = batch_stats(&channel);
+                let actual = baseline.channels.as_ref().unwrap()[idx];
+                assert!((actual.mean - expected.mean).abs() < 1e-4, "seed {} channel {}: {:?} vs {:?}", seed, idx, actual, expected);
+                assert!(
+                    (actual.std_dev - expected.std_dev).abs() < 1e-4 * expected.std_dev.max(1.0),
+                    "seed {} channel {}: {:?} vs {:?}",
+                    seed,
+                    idx,
+                    actual,
+                    expected
+                );
+            }
+            assert_eq!(baseline.fear(), baseline.channels.as_ref().unwrap()[FEAR_INDEX]);
+        }
+
+        // A single sample keeps the default spread
+        let mut calibrator = AdaptiveCalibrator::new(Duration::from_secs(3600), 0.05);
+        calibrator.add_sample(0.7).unwrap();
+        assert_eq!(calibrator.baseline_stats().mean, 0.7);
+        assert_eq!(calibrator.baseline_stats().std_dev, ChannelStats::default().std_dev);
+    }
+
+    #[test]
+    fn test_single_outlier_barely_moves_initial_baseline() {
+        let (mean, std_dev) = (1.0, 0.2);
+        let clean = normal_samples(42, 100, mean, std_dev);
+        let mut startled = clean.clone();
+        startled.insert(50, mean + 10.0 * std_dev);
+
+        let baseline = |samples: &[f32]| {
+            let mut calibrator = AdaptiveCalibrator::new(Duration::from_secs(3600), 0.05);
+            for &sample in samples {
+                calibrator.add_sample(sample).unwrap();
+            }
+            calibrator.baseline_stats().fear()
+        };
+        let (expected, actual) = (baseline(&clean), baseline(&startled));
+        assert!((actual.mean - expected.mean).abs() < 0.05 * expected.mean.abs(), "{:?} vs {:?}", actual, expected);
+        assert!((actual.std_dev - expected.std_dev).abs() < 0.05 * expected.std_dev, "{:?} vs {:?}", actual, expected);
+
+        // Without rejection the spread would have grown by about 40%
+        assert!(batch_stats(&startled).std_dev > 1.3 * expected.std_dev);
+    }
+
+    #[test]
+    fn test_sustained_shift_is_not_rejected() {
+        let mut calibrator = AdaptiveCalibrator::new(Duration::from_secs(3600), 0.05);
+        for sample in normal_samples(3, 40, 0.0, 0.1) {
+            calibrator.add_sample(sample).unwrap();
+        }
+        for _ in 0..100 {
+            calibrator.add_sample(5.0).unwrap();
+        }
+        assert!(calibrator.baseline_stats().sample_count > 40 + 60);
+        assert!(calibrator.baseline_stats().mean > 2.0);
+    }
+
+    #[test]
+    fn test_low_confidence_samples_move_baseline_less() {
+        // Initial period: a half-weight sample counts as half a sample
+        let mut full = AdaptiveCalibrator::new(Duration::from_secs(3600), 0.05);
+        let mut weighted = AdaptiveCalibrator::new(Duration::from_secs(3600), 0.05);
+        for calibrator in [&mut full, &mut weighted] {
+            calibrator.add_sample(0.0).unwrap();
+            calibrator.add_sample(0.2).unwrap();
+        }
+        full.add_sample(0.9).unwrap();
+        weighted.add_weighted_sample(0.9, 0.5).unwrap();
+        assert!((full.baseline_stats().mean - 1.1 / 3.0).abs() < 1e-6);
+        assert!((weighted.baseline_stats().mean - 0.65 / 2.5).abs() < 1e-6);
+
+        // After it: the EMA step shrinks with the weight
+        let calibrated = |weight: f32| {
+            let mut calibrator = AdaptiveCalibrator::new(Duration::ZERO, 0.1);
+            for _ in 0..40 {
+                calibrator.add_sample(0.5).unwrap();
+            }
+            calibrator.add_weighted_sample(1.5, weight).unwrap();
+            calibrator.baseline_stats().mean - 0.5
+        };
+        assert!((calibrated(1.0) - 0.1).abs() < 1e-5);
+        assert!((calibrated(0.2) - 0.02).abs() < 1e-5);
+        assert!(calibrated(0.2) < calibrated(0.6));
+
+        // Zero and NaN weights are skipped; weights above 1 count as 1
+        assert_eq!(calibrated(0.0), 0.0);
+        assert_eq!(calibrated(f32::NAN), 0.0);
+        assert_eq!(calibrated(3.0), calibrated(1.0));
+
+        let mut per_channel = AdaptiveCalibrator::new(Duration::from_secs(3600), 0.05).with_per_channel_calibration(true);
+        per_channel.add_weighted_logits(&synthetic_logits(0), 0.0).unwrap();
+        assert_eq!(per_channel.baseline_stats().sample_count, 0);
+        per_channel.add_weighted_logits(&synthetic_logits(0), 0.25).unwrap();
+        per_channel.add_weighted_logits(&synthetic_logits(1), 1.0).unwrap();
+        let channels = per_channel.baseline_stats().channels.clone().unwrap();
+        let expected = (0.25 * synthetic_logits(0)[4] + synthetic_logits(1)[4]) / 1.25;
+        assert!((channels[4].mean - expected).abs() < 1e-5);
+    }
 }
@@ -0,0 +1,212 @@
+//! Resolution and frame rate negotiated with the camera
+//!
+//! Drivers are free to refuse a requested format, and most do so silently: a
+//! webcam asked for 1280x720 at 60 FPS may go on delivering 640x480 at 30.
+//! After opening the camera the sensor requests the configured format, reads
+//! back what the device actually settled on, warns about anything refused,
+//! and reports the negotiated values rather than the requested ones.
+
+use opencv::{
+    prelude::*,
+    videoio::{VideoCapture, CAP_PROP_BUFFERSIZE, CAP_PROP_FPS, CAP_PROP_FRAME_HEIGHT, CAP_PROP_FRAME_WIDTH},
+};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Difference in frame rate still counted as granted; drivers round
+const FPS_TOLERANCE: f32 = 0.5;
+
+/// What a camera delivers
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct CameraFormat {
+    pub width: u32,
+    pub height: u32,
+    /// Frames per second; 0 when the backend does not say
+    pub fps: f32,
+}
+
+impl fmt::Display for CameraFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}x{}@{}", self.width, self.height, self.fps)
+    }
+}
+
+/// Parse a `<width>x<height>` resolution such as `1280x720`
+pub fn parse_resolution(input: &str) -> Result<(u32, u32), String> {
+    let invalid = || format!("Invalid camera resolution {:?}, expected e.g. 1280x720", input);
+    let lower = input.to_ascii_lowercase();
+    let (width, height) = lower.split_once('x').ok_or_else(invalid)?;
+    match (width.trim().parse(), height.trim().parse()) {
+        (Ok(width), Ok(height)) if width > 0 && height > 0 => Ok((width, height)),
+        _ => Err(invalid()),
+    }
+}
+
+/// Camera properties the sensor sets and reads back
+pub trait CaptureProperties {
+    /// Request `value` for `property`; false when the backend rejects it outright
+    fn set_property(&mut self, property: i32, value: f64) -> bool;
+
+    /// Current value of `property`, 0 when unknown
+    fn property(&self, property: i32) -> f64;
+}
+
+impl CaptureProperties for VideoCapture {
+    fn set_property(&mut self, property: i32, value: f64) -> bool {
+        self.set(property, value).unwrap_or(false)
+    }
+
+    fn property(&self, property: i32) -> f64 {
+        self.get(property).unwrap_or(0.0)
+    }
+}
+
+/// The format asked for and the one the camera settled on
+#[derive(Debug, Clone, PartialEq)]
+pub struct Negotiated {
+    pub requested_resolution: Option<(u32, u32)>,
+    pub requested_fps: Option<f32>,
+    pub actual: CameraFormat,
+}
+
+impl Negotiated {
+    /// Requested properties the camera did not grant, described for a warning
+    pub fn refusals(&self) -> Vec<String> {
+        let actual = self.actual;
+        let mut refusals = Vec::new();
+        if let Some((width, height)) = self.requested_resolution {
+            if (width, height) != (actual.width, actual.height) {
+                refusals.push(format!("resolution {}x{} (got {}x{})", width, height, actual.width, actual.height));
+            }
+        }
+        // A backend that cannot tell its frame rate has not refused one
+        if let Some(fps) = self.requested_fps {
+            if actual.fps > 0.0 && (actual.fps - fps).abs() > FPS_TOLERANCE {
+                refusals.push(format!("{} FPS (got {})", fps, actual.fps));
+            }
+        }
+        refusals
+    }
+}
+
+/// Request `resolution` and `fps` from camera `camera_id`, then read back
+/// what it negotiated
+///
+/// The buffer is also cut to one frame: the capture thread wants the newest
+/// frame, not a queue of old ones.
+pub fn negotiate_format(
+    camera_id: u32,
+    capture: &mut impl CaptureProperties,
+    resolution: Option<(u32, u32)>,
+    fps: Option<f32>,
+) -> Negotiated {
+    if let Some((width, height)) = resolution {
+        capture.set_property(CAP_PROP_FRAME_WIDTH, width as f64);
+        capture.set_property(CAP_PROP_FRAME_HEIGHT, height as f64);
+    }
+    if let Some(fps) = fps {
+        capture.set_property(CAP_PROP_FPS, fps as f64);
+    }
+    // Backends without the property keep their own buffering
+    if !capture.set_property(CAP_PROP_BUFFERSIZE, 1.0) {
+        tracing::debug!("Camera {} does not support setting its buffer size", camera_id);
+    }
+
+    let negotiated = Negotiated {
+        requested_resolution: resolution,
+        requested_fps: fps,
+        actual: CameraFormat {
+            width: capture.property(CAP_PROP_FRAME_WIDTH).max(0.0) as u32,
+            height: capture.property(CAP_PROP_FRAME_HEIGHT).max(0.0) as u32,
+            fps: capture.property(CAP_PROP_FPS).max(0.0) as f32,
+        },
+    };
+    let refusals = negotiated.refusals();
+    if refusals.is_empty() {
+        tracing::info!("Camera {} format: {}", camera_id, negotiated.actual);
+    } else {
+        tracing::warn!(
+            "Camera {} refused {}, capturing at {}",
+            camera_id,
+            refusals.join(" and "),
+            negotiated.actual
+        );
+    }
+    negotiated
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    /// A driver that accepts every request and keeps only the properties it supports
+    struct FakeCapture {
+        supported: Vec<i32>,
+        values: HashMap<i32, f64>,
+    }
+
+    impl FakeCapture {
+        /// Delivers 640x480 at 30 FPS unless told otherwise
+        fn new(supported: &[i32]) -> Self {
+            let values = [(CAP_PROP_FRAME_WIDTH, 640.0), (CAP_PROP_FRAME_HEIGHT, 480.0), (CAP_PROP_FPS, 30.0)];
+            Self { supported: supported.to_vec(), values: values.into_iter().collect() }
+        }
+    }
+
+    impl CaptureProperties for FakeCapture {
+        fn set_property(&mut self, property: i32, value: f64) -> bool {
+            if self.supported.contains(&property) {
+                self.values.insert(property, value);
+            }
+            true
+        }
+
+        fn property(&self, property: i32) -> f64 {
+            self.values.get(&property).copied().unwrap_or(0.0)
+        }
+    }
+
+    #[test]
+    fn test_granted_format_is_read_back() {
+        let mut capture = FakeCapture::new(&[CAP_PROP_FRAME_WIDTH, CAP_PROP_FRAME_HEIGHT, CAP_PROP_FPS, CAP_PROP_BUFFERSIZE]);
+        let negotiated = negotiate_format(0, &mut capture, Some((1280, 720)), Some(60.0));
+
+        assert_eq!(negotiated.actual, CameraFormat { width: 1280, height: 720, fps: 60.0 });
+        assert!(negotiated.refusals().is_empty());
+        assert_eq!(capture.property(CAP_PROP_BUFFERSIZE), 1.0);
+    }
+
+    #[test]
+    fn test_silently_refused_resolution_is_reported() {
+        let mut capture = FakeCapture::new(&[CAP_PROP_FPS]);
+        let negotiated = negotiate_format(0, &mut capture, Some((1280, 720)), Some(60.0));
+
+        // The camera's real format, not the request
+        assert_eq!(negotiated.actual, CameraFormat { width: 640, height: 480, fps: 60.0 });
+        assert_eq!(negotiated.refusals(), ["resolution 1280x720 (got 640x480)"]);
+
+        // Nothing requested, nothing refused
+        let negotiated = negotiate_format(0, &mut capture, None, Some(59.7));
+        assert!(negotiated.refusals().is_empty());
+    }
+
+    #[test]
+    fn test_parse_resolution() {
+        assert_eq!(parse_resolution("1280x720"), Ok((1280, 720)));
+        assert_eq!(parse_resolution(" 640 X 480 "), Ok((640, 480)));
+        assert!(parse_resolution("1280").is_err());
+        assert!(parse_resolution("0x720").is_err());
+        assert!(parse_resolution("wide x tall").is_err());
+    }
+
+    #[test]
+    fn test_unknown_frame_rate_is_not_a_refusal() {
+        let mut capture = FakeCapture::new(&[CAP_PROP_FRAME_WIDTH, CAP_PROP_FRAME_HEIGHT]);
+        capture.values.remove(&CAP_PROP_FPS);
+        let negotiated = negotiate_format(0, &mut capture, Some((1280, 720)), Some(60.0));
+
+        assert_eq!(negotiated.actual.fps, 0.0);
+        assert!(negotiated.refusals().is_empty());
+    }
+}
@@ -0,0 +1,250 @@
+//! Camera discovery
+//!
+//! Opening every index in turn to see which answers is slow, can light a
+//! webcam's LED, and says nothing about a device but its number. On Linux the
+//! V4L2 nodes under `/dev` are asked for their name and frame sizes instead,
+//! without ever streaming. Elsewhere, and when no node answers, indices are
+//! still probed through OpenCV and named after the platform's capture backend.
+
+use opencv::{
+    prelude::{VideoCaptureTrait, VideoCaptureTraitConst},
+    videoio::{VideoCapture, CAP_ANY, CAP_PROP_FRAME_HEIGHT, CAP_PROP_FRAME_WIDTH},
+};
+use spectremesh_core::{CameraDevice, CameraError};
+
+/// Indices opened when the platform cannot list its cameras
+const PROBED_INDICES: u32 = 10;
+
+/// Cameras attached to this machine, with their names and, where the
+/// platform says, the resolutions they support
+///
+/// Blocking; probing indices can take seconds.
+pub fn enumerate_cameras() -> Result<Vec<CameraDevice>, CameraError> {
+    #[cfg(target_os = "linux")]
+    {
+        let cameras = v4l2::enumerate();
+        if !cameras.is_empty() {
+            return Ok(cameras);
+        }
+    }
+
+    let cameras: Vec<_> = (0..PROBED_INDICES)
+        .filter_map(|id| Some(CameraDevice::new(id, backend_camera_name(id), probe_resolution(id)?)))
+        .collect();
+    if cameras.is_empty() {
+        Err(CameraError::NoCamerasAvailable)
+    } else {
+        Ok(cameras)
+    }
+}
+
+/// Name for camera `id` when only its index is known
+pub fn backend_camera_name(id: u32) -> String {
+    #[cfg(target_os = "windows")]
+    return format!("DirectShow Camera {}", id);
+
+    #[cfg(target_os = "macos")]
+    return format!("AVFoundation Camera {}", id);
+
+    #[cfg(target_os = "linux")]
+    return format!("V4L2 Camera {}", id);
+
+    #[cfg(not(any(target_os = "windows", target_os = "macos", target_os = "linux")))]
+    return format!("Camera {}", id);
+}
+
+/// Frame size of camera `id` as opened by OpenCV, if it opens at all
+fn probe_resolution(id: u32) -> Option<(u32, u32)> {
+    let mut camera = VideoCapture::new(id as i32, CAP_ANY).ok()?;
+    if !camera.is_opened().unwrap_or(false) {
+        return None;
+    }
+    let width = camera.get(CAP_PROP_FRAME_WIDTH).unwrap_or(640.0) as u32;
+    let height = camera.get(CAP_PROP_FRAME_HEIGHT).unwrap_or(480.0) as u32;
+    let _ = camera.release();
+    Some((width, height))
+}
+
+/// Device listing through the V4L2 ioctls
+#[cfg(target_os = "linux")]
+mod v4l2 {
+    use spectremesh_core::CameraDevice;
+    use std::fs::{self, File, OpenOptions};
+    use std::io;
+    use std::os::fd::AsRawFd;
+    use std::os::unix::fs::OpenOptionsExt;
+
+    const VIDIOC_QUERYCAP: u32 = 0x8068_5600;
+    const VIDIOC_ENUM_FMT: u32 = 0xC040_5602;
+    const VIDIOC_ENUM_FRAMESIZES: u32 = 0xC02C_564A;
+
+    const CAP_VIDEO_CAPTURE: u32 = 0x0000_0001;
+    const CAP_DEVICE_CAPS: u32 = 0x8000_0000;
+    const BUF_TYPE_VIDEO_CAPTURE: u32 = 1;
+    const FRMSIZE_TYPE_DISCRETE: u32 = 1;
+
+    /// `struct v4l2_capability`
+    #[repr(C)]
+    #[derive(Default)]
+    struct Capability {
+        driver: [u8; 16],
+        card: [u8; 32],
+        bus_info: [u8; 32],
+        version: u32,
+        capabilities: u32,
+        device_caps: u32,
+        reserved: [u32; 3],
+    }
+
+    /// `struct v4l2_fmtdesc`
+    #[repr(C)]
+    #[derive(Default)]
+    struct FormatDesc {
+        index: u32,
+        kind: u32,
+        flags: u32,
+        description: [u8; 32],
+        pixel_format: u32,
+        mbus_code: u32,
+        reserved: [u32; 3],
+    }
+
+    /// `struct v4l2_frmsizeenum`
+    #[repr(C)]
+    #[derive(Default)]
+    struct FrameSize {
+        index: u32,
+        pixel_format: u32,
+        kind: u32,
+        /// Width and height when discrete, else minimum, maximum and step
+        /// of the width followed by those of the height
+        sizes: [u32; 6],
+        reserved: [u32; 2],
+    }
+
+    /// Every `/dev/video<N>` node that captures video, in index order
+    pub fn enumerate() -> Vec<CameraDevice> {
+        let Ok(entries) = fs::read_dir("/dev") else {
+            return Vec::new();
+        };
+        let mut indices: Vec<u32> = entries
+            .filter_map(|entry| entry.ok()?.file_name().to_str()?.strip_prefix("video")?.parse().ok())
+            .collect();
+        indices.sort_unstable();
+        indices.into_iter().filter_map(query).collect()
+    }
+
+    /// Name and frame sizes of `/dev/video<index>`, if it is a camera
+    fn query(index: u32) -> Option<CameraDevice> {
+        // Opened but never streamed, so the camera stays dark
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .custom_flags(libc::O_NONBLOCK)
+            .open(format!("/dev/video{}", index))
+            .ok()?;
+        let mut capability = Capability::default();
+        if !ioctl(&file, VIDIOC_QUERYCAP, &mut capability) {
+            return None;
+        }
+        // Metadata and output nodes of the same device are not cameras
+        let caps = if capability.capabilities & CAP_DEVICE_CAPS != 0 {
+            capability.device_caps
+        } else {
+            capability.capabilities
+        };
+        if caps & CAP_VIDEO_CAPTURE == 0 {
+            return None;
+        }
+
+        let resolutions = frame_sizes(&file);
+        let resolution = match resolutions.first() {
+            Some(&largest) => largest,
+            None => super::probe_resolution(index)?,
+        };
+        let name = format!("{} (V4L2)", c_string(&capability.card));
+        Some(CameraDevice::new(index, name, resolution).with_supported_resolutions(resolutions))
+    }
+
+    /// Frame sizes offered in any pixel format
+    fn frame_sizes(file: &File) -> Vec<(u32, u32)> {
+        let mut sizes = Vec::new();
+        for format_index in 0.. {
+            let mut format = FormatDesc { index: format_index, kind: BUF_TYPE_VIDEO_CAPTURE, ..Default::default() };
+            if !ioctl(file, VIDIOC_ENUM_FMT, &mut format) {
+                break;
+            }
+            for size_index in 0.. {
+                let mut size = FrameSize { index: size_index, pixel_format: format.pixel_format, ..Default::default() };
+                if !ioctl(file, VIDIOC_ENUM_FRAMESIZES, &mut size) {
+                    break;
+                }
+                if size.kind == FRMSIZE_TYPE_DISCRETE {
+                    sizes.push((size.sizes[0], size.sizes[1]));
+                } else {
+                    // A stepwise or continuous range comes as one entry
+                    sizes.push((size.sizes[1], size.sizes[4]));
+                    break;
+                }
+            }
+        }
+        largest_first(sizes)
+    }
+
+    /// Issue `request` on `file`, retrying when interrupted
+    fn ioctl<T>(file: &File, request: u32, arg: &mut T) -> bool {
+        loop {
+            // SAFETY: `arg` is a #[repr(C)] copy of the struct `request` takes
+            if unsafe { libc::ioctl(file.as_raw_fd(), request as _, arg as *mut T) } == 0 {
+                return true;
+            }
+            if io::Error::last_os_error().kind() != io::ErrorKind::Interrupted {
+                return false;
+            }
+        }
+    }
+
+    /// Text of a NUL-padded field
+    pub(super) fn c_string(bytes: &[u8]) -> String {
+        let end = bytes.iter().position(|&b| b == 0).unwrap_or(bytes.len());
+        String::from_utf8_lossy(&bytes[..end]).trim().to_string()
+    }
+
+    /// Distinct non-empty sizes, largest area first
+    pub(super) fn largest_first(mut sizes: Vec<(u32, u32)>) -> Vec<(u32, u32)> {
+        sizes.retain(|&(width, height)| width > 0 && height > 0);
+        sizes.sort_unstable_by_key(|&(width, height)| std::cmp::Reverse((width as u64 * height as u64, width)));
+        sizes.dedup();
+        sizes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_index_names_carry_the_backend() {
+        let name = backend_camera_name(3);
+        assert!(name.ends_with(" 3"));
+
+        #[cfg(target_os = "linux")]
+        assert!(name.contains("V4L2"));
+        #[cfg(target_os = "windows")]
+        assert!(name.contains("DirectShow"));
+        #[cfg(target_os = "macos")]
+        assert!(name.contains("AVFoundation"));
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_v4l2_fields() {
+        let mut card = [0u8; 32];
+        card[..16].copy_from_slice(b"Integrated Webca");
+        assert_eq!(v4l2::c_string(&card), "Integrated Webca");
+        assert_eq!(v4l2::c_string(b"UVC Camera"), "UVC Camera");
+
+        let sizes = vec![(640, 480), (1280, 720), (0, 0), (320, 240), (1280, 720), (1920, 1080)];
+        assert_eq!(v4l2::largest_first(sizes), [(1920, 1080), (1280, 720), (640, 480), (320, 240)]);
+    }
+}
@@ -0,0 +1,215 @@
+//! Capture stage: frames are read on a thread of their own
+//!
+//! Reading the camera and running the models in one loop means a slow
+//! inference leaves frames waiting in the camera's buffer, so every score
+//! describes a moment further in the past. The capture thread reads at the
+//! target rate and hands frames over through a single slot: a frame nobody
+//! took before the next one arrived is replaced and counted as stale, so the
+//! processing loop always scores the freshest frame, at most about one frame
+//! old, however long inference takes.
+
+use crate::power::ProfileRates;
+use crate::resume::FrameSource;
+use crate::sensor::SensorError;
+use async_channel::{bounded, Receiver, Sender};
+use opencv::{core::Mat, prelude::*};
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant, SystemTime};
+use tokio::sync::watch;
+
+/// A frame waiting for inference
+pub struct CapturedFrame {
+    pub frame: Mat,
+    /// When the read finished
+    pub captured_at: Instant,
+    /// Wall-clock time of the read, stamped on the frame's score
+    pub wall_time: SystemTime,
+    /// Frames read so far, this one included; a jump between scored frames
+    /// counts the ones dropped
+    pub sequence: u64,
+}
+
+impl CapturedFrame {
+    /// Time the frame has waited since capture
+    pub fn queue_delay(&self) -> Duration {
+        self.captured_at.elapsed()
+    }
+}
+
+/// Result of one read on the capture thread
+pub enum Capture {
+    Frame(CapturedFrame),
+    /// The source delivered no frame
+    Failed,
+}
+
+/// A frame source shared by the capture thread and the recovery steps
+///
+/// Reopening after a resume or a disconnect goes through the same lock the
+/// capture thread reads under, so the two never touch the device at once.
+pub struct SharedSource<S>(Arc<Mutex<S>>);
+
+impl<S> SharedSource<S> {
+    pub fn new(source: S) -> Self {
+        Self(Arc::new(Mutex::new(source)))
+    }
+
+    /// Run `f` on the source
+    pub fn with<T>(&self, f: impl FnOnce(&mut S) -> T) -> T {
+        f(&mut self.0.lock().unwrap())
+    }
+}
+
+impl<S> Clone for SharedSource<S> {
+    fn clone(&self) -> Self {
+        Self(Arc::clone(&self.0))
+    }
+}
+
+impl<S: FrameSource> FrameSource for SharedSource<S> {
+    fn read_frame(&mut self, frame: &mut Mat) -> bool {
+        self.with(|source| source.read_frame(frame))
+    }
+
+    fn reopen(&mut self) -> Result<(), SensorError> {
+        self.with(|source| source.reopen())
+    }
+}
+
+/// The capture thread and the slot it fills
+pub struct CaptureStage {
+    frames: Receiver<Capture>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl CaptureStage {
+    /// Start reading `source` at the target rate in `rates`; `on_stale` runs
+    /// for every frame replaced before it was taken
+    pub fn spawn<S>(
+        source: SharedSource<S>,
+        rates: watch::Receiver<ProfileRates>,
+        on_stale: impl Fn() + Send + 'static,
+    ) -> Result<Self, SensorError>
+    where
+        S: FrameSource + Send + 'static,
+    {
+        let (sender, frames) = bounded(1);
+        let thread = std::thread::Builder::new()
+            .name("spectre-capture".to_string())
+            .spawn(move || capture_loop(source, sender, rates, on_stale))
+            .map_err(|e| SensorError::CameraInit(format!("Failed to start capture thread: {}", e)))?;
+        Ok(Self { frames, thread: Some(thread) })
+    }
+
+    /// The freshest capture, waiting up to `timeout` for one; `None` if
+    /// nothing arrived in time
+    pub async fn next(&self, timeout: Duration) -> Option<Capture> {
+        tokio::time::timeout(timeout, self.frames.recv()).await.ok()?.ok()
+    }
+
+    /// Drop whatever was captured before a recovery step
+    pub fn discard(&self) {
+        while self.frames.try_recv().is_ok() {}
+    }
+
+    /// Stop the thread and wait for it to let go of the source
+    pub async fn stop(mut self) {
+        self.frames.close();
+        if let Some(thread) = self.thread.take() {
+            let _ = tokio::task::spawn_blocking(move || thread.join()).await;
+        }
+    }
+}
+
+impl Drop for CaptureStage {
+    fn drop(&mut self) {
+        // The thread notices on its next frame
+        self.frames.close();
+    }
+}
+
+fn capture_loop<S: FrameSource>(
+    mut source: SharedSource<S>,
+    sender: Sender<Capture>,
+    rates: watch::Receiver<ProfileRates>,
+    on_stale: impl Fn(),
+) {
+    let mut sequence = 0;
+    loop {
+        let started = Instant::now();
+        let mut frame = Mat::default();
+        let capture = if source.read_frame(&mut frame) && !frame.empty() {
+            sequence += 1;
+            Capture::Frame(CapturedFrame {
+                frame,
+                captured_at: Instant::now(),
+                wall_time: SystemTime::now(),
+                sequence,
+            })
+        } else {
+            Capture::Failed
+        };
+
+        match sender.force_send(capture) {
+            Ok(Some(Capture::Frame(_))) => on_stale(),
+            Ok(_) => {}
+            Err(_) => break,
+        }
+
+        let frame_duration = Duration::from_secs_f32(1.0 / rates.borrow().target_fps);
+        if let Some(rest) = frame_duration.checked_sub(started.elapsed()) {
+            std::thread::sleep(rest);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::synthetic::SyntheticCamera;
+    use std::sync::atomic::{AtomicU64, Ordering};
+
+    fn rates(target_fps: f32) -> watch::Receiver<ProfileRates> {
+        watch::channel(ProfileRates { target_fps, emotion_interval: 1 }).1
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_slow_consumer_gets_fresh_frames() {
+        let stale = Arc::new(AtomicU64::new(0));
+        let counter = Arc::clone(&stale);
+        let stage = CaptureStage::spawn(SharedSource::new(SyntheticCamera::default()), rates(100.0), move || {
+            counter.fetch_add(1, Ordering::Relaxed);
+        })
+        .unwrap();
+
+        // Inference five times slower than capture; a queue would fall
+        // further behind every frame
+        let mut delays = Vec::new();
+        let mut sequences = Vec::new();
+        for _ in 0..10 {
+            let Some(Capture::Frame(captured)) = stage.next(Duration::from_secs(1)).await else {
+                panic!("no frame captured");
+            };
+            delays.push(captured.queue_delay());
+            sequences.push(captured.sequence);
+            std::thread::sleep(Duration::from_millis(50));
+        }
+
+        assert!(delays.iter().all(|delay| *delay < Duration::from_millis(30)), "{:?}", delays);
+        // Skipped frames show up as jumps in the sequence
+        assert!(sequences.windows(2).all(|pair| pair[1] > pair[0] + 1), "{:?}", sequences);
+        assert!(stale.load(Ordering::Relaxed) >= 20);
+        stage.stop().await;
+    }
+
+    #[tokio::test]
+    async fn test_stop_releases_the_source() {
+        let source = SharedSource::new(SyntheticCamera::default());
+        let stage = CaptureStage::spawn(source.clone(), rates(200.0), || {}).unwrap();
+        assert!(matches!(stage.next(Duration::from_secs(1)).await, Some(Capture::Frame(_))));
+
+        stage.stop().await;
+        assert_eq!(Arc::strong_count(&source.0), 1);
+    }
+}
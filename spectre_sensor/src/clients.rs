@@ -0,0 +1,247 @@
+//! Bookkeeping of the event streams the daemon is serving
+//!
+//! Every `StreamEvents` call registers with the [`ClientRegistry`] and keeps
+//! a [`ClientHandle`] for as long as its stream runs; dropping the handle
+//! removes the client. Only metadata is kept (peer, filters, options and
+//! counters), never event contents. [`ClientRegistry::watch_open`] follows
+//! the number of open streams, for stopping the sensor once none is left.
+
+#[cfg(feature = "metrics")]
+use crate::metrics::SensorMetrics;
+use crate::proto::{ClientInfo, SensorEvent, StreamRequest};
+use crate::resume::Clock;
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tokio::sync::{mpsc, watch};
+use tonic::Status;
+
+/// Queue an event stream is fed through
+pub type ClientQueue = mpsc::Sender<Result<SensorEvent, Status>>;
+
+/// Open event streams, for `ListClients` and the connected clients gauge
+#[derive(Clone)]
+pub struct ClientRegistry {
+    shared: Arc<Shared>,
+}
+
+struct Shared {
+    clock: Arc<dyn Clock>,
+    clients: Mutex<Clients>,
+    /// Number of open streams
+    open: watch::Sender<usize>,
+    #[cfg(feature = "metrics")]
+    metrics: Mutex<Option<Arc<SensorMetrics>>>,
+}
+
+#[derive(Default)]
+struct Clients {
+    next_id: u64,
+    entries: BTreeMap<u64, Entry>,
+}
+
+struct Entry {
+    peer: String,
+    connected_at_us: u64,
+    request: StreamRequest,
+    /// Weak so that listing never keeps a finished stream open
+    queue: mpsc::WeakSender<Result<SensorEvent, Status>>,
+    counters: Arc<Counters>,
+}
+
+#[derive(Default)]
+struct Counters {
+    delivered: AtomicU64,
+    dropped: AtomicU64,
+    last_activity_us: AtomicU64,
+}
+
+impl ClientRegistry {
+    /// Create an empty registry stamping times from `clock`
+    pub fn new(clock: Arc<dyn Clock>) -> Self {
+        Self {
+            shared: Arc::new(Shared {
+                clock,
+                clients: Mutex::new(Clients::default()),
+                open: watch::Sender::new(0),
+                #[cfg(feature = "metrics")]
+                metrics: Mutex::new(None),
+            }),
+        }
+    }
+
+    /// Keep `metrics`' connected clients gauge up to date
+    #[cfg(feature = "metrics")]
+    pub fn set_metrics(&self, metrics: Arc<SensorMetrics>) {
+        metrics.set_connected_clients(self.len());
+        *self.shared.metrics.lock().unwrap() = Some(metrics);
+    }
+
+    /// Register a stream fed through `queue`; it stays listed until the handle drops
+    pub fn register(&self, peer: impl Into<String>, request: StreamRequest, queue: &ClientQueue) -> ClientHandle {
+        let now = self.shared.now_us();
+        let counters = Arc::new(Counters {
+            last_activity_us: AtomicU64::new(now),
+            ..Counters::default()
+        });
+        let id = {
+            let mut clients = self.shared.clients.lock().unwrap();
+            clients.next_id += 1;
+            let id = clients.next_id;
+            clients.entries.insert(id, Entry {
+                peer: peer.into(),
+                connected_at_us: now,
+                request,
+                queue: queue.downgrade(),
+                counters: Arc::clone(&counters),
+            });
+            id
+        };
+        self.shared.clients_changed();
+
+        ClientHandle {
+            id,
+            counters,
+            shared: Arc::clone(&self.shared),
+        }
+    }
+
+    /// Number of open streams
+    pub fn len(&self) -> usize {
+        self.shared.clients.lock().unwrap().entries.len()
+    }
+
+    /// Whether no stream is open
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Follow the number of open streams
+    pub fn watch_open(&self) -> watch::Receiver<usize> {
+        self.shared.open.subscribe()
+    }
+
+    /// Every open stream, oldest first
+    pub fn list(&self) -> Vec<ClientInfo> {
+        let clients = self.shared.clients.lock().unwrap();
+        clients
+            .entries
+            .iter()
+            .map(|(&id, entry)| ClientInfo {
+                client_id: id,
+                connected_at_us: entry.connected_at_us,
+                peer: entry.peer.clone(),
+                request: Some(entry.request.clone()),
+                queue_depth: entry
+                    .queue
+                    .upgrade()
+                    .map_or(0, |queue| (queue.max_capacity() - queue.capacity()) as u32),
+                events_delivered: entry.counters.delivered.load(Ordering::Relaxed),
+                events_dropped: entry.counters.dropped.load(Ordering::Relaxed),
+                last_activity_us: entry.counters.last_activity_us.load(Ordering::Relaxed),
+            })
+            .collect()
+    }
+}
+
+impl Shared {
+    fn now_us(&self) -> u64 {
+        unix_us(self.clock.wall())
+    }
+
+    fn clients_changed(&self) {
+        let open = self.clients.lock().unwrap().entries.len();
+        self.open.send_replace(open);
+        #[cfg(feature = "metrics")]
+        if let Some(metrics) = self.metrics.lock().unwrap().as_ref() {
+            metrics.set_connected_clients(open);
+        }
+    }
+}
+
+/// One registered stream's counters; dropping it unregisters the stream
+pub struct ClientHandle {
+    id: u64,
+    counters: Arc<Counters>,
+    shared: Arc<Shared>,
+}
+
+impl ClientHandle {
+    /// Opaque id the stream is listed under
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+
+    /// An event was queued for the client
+    pub fn delivered(&self) {
+        self.counters.delivered.fetch_add(1, Ordering::Relaxed);
+        self.counters.last_activity_us.store(self.shared.now_us(), Ordering::Relaxed);
+    }
+
+    /// The client missed `count` events after falling behind
+    pub fn dropped(&self, count: u64) {
+        self.counters.dropped.fetch_add(count, Ordering::Relaxed);
+    }
+}
+
+impl Drop for ClientHandle {
+    fn drop(&mut self) {
+        self.shared.clients.lock().unwrap().entries.remove(&self.id);
+        self.shared.clients_changed();
+    }
+}
+
+/// Microseconds since Unix epoch of a wall clock reading
+fn unix_us(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH).unwrap_or_default().as_micros() as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::proto::EventType;
+    use crate::resume::SystemClock;
+
+    #[test]
+    fn test_registry_lists_and_forgets_clients() {
+        let registry = ClientRegistry::new(Arc::new(SystemClock::new()));
+        #[cfg(feature = "metrics")]
+        let metrics = Arc::new(SensorMetrics::new().unwrap());
+        #[cfg(feature = "metrics")]
+        registry.set_metrics(Arc::clone(&metrics));
+
+        let open = registry.watch_open();
+        let (queue, _events) = mpsc::channel(8);
+        let request = StreamRequest {
+            event_types: vec![EventType::Score as i32],
+            ..Default::default()
+        };
+        let first = registry.register("loopback", request.clone(), &queue);
+        let second = registry.register("tcp://127.0.0.1:5000", StreamRequest::default(), &queue);
+        assert_ne!(first.id(), second.id());
+        assert_eq!(*open.borrow(), 2);
+        #[cfg(feature = "metrics")]
+        assert!(metrics.gather().unwrap().contains("spectre_connected_clients 2"));
+
+        queue.try_send(Ok(SensorEvent::default())).unwrap();
+        first.delivered();
+        first.dropped(3);
+        let listed = registry.list();
+        assert_eq!(listed.len(), 2);
+        assert_eq!(listed[0].client_id, first.id());
+        assert_eq!(listed[0].request, Some(request));
+        assert_eq!(listed[0].queue_depth, 1);
+        assert_eq!((listed[0].events_delivered, listed[0].events_dropped), (1, 3));
+        assert!(listed[0].last_activity_us >= listed[0].connected_at_us);
+        assert_eq!(listed[1].peer, "tcp://127.0.0.1:5000");
+
+        drop(first);
+        assert_eq!(registry.list().iter().map(|client| client.client_id).collect::<Vec<_>>(), vec![second.id()]);
+        drop(second);
+        assert!(registry.is_empty());
+        assert_eq!(*open.borrow(), 0);
+        #[cfg(feature = "metrics")]
+        assert!(metrics.gather().unwrap().contains("spectre_connected_clients 0"));
+    }
+}
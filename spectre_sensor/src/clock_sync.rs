@@ -0,0 +1,288 @@
+//! Clock synchronization between the game and the sensor
+//!
+//! The game counts time from its first update, the sensor stamps events with
+//! its own monotonic and wall clocks, and a daemon adds an unknown transport
+//! delay. The game periodically sends a `Ping` carrying its current time and
+//! the sensor answers with both of its clocks. Each round trip gives an
+//! NTP-style [`ClockSample`]: its offset is exact when both legs take equally
+//! long and otherwise wrong by at most half the round trip.
+//! [`ClockSyncEstimator`] keeps a window of recent samples and trusts the one
+//! with the shortest round trip, which bounds the offset most tightly.
+
+use crate::resume::Clock;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::time::{Duration, UNIX_EPOCH};
+
+/// Samples kept by [`ClockSyncEstimator::default`]
+pub const DEFAULT_WINDOW: usize = 8;
+
+/// Error bound at which a full window is worth half confidence
+const CONFIDENT_ERROR_US: f32 = 2_000.0;
+
+/// One ping round trip
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ClockSample {
+    /// Game time when the ping was sent
+    pub game_sent: Duration,
+    /// Sensor monotonic time when the ping was answered
+    pub sensor_monotonic: Duration,
+    /// Sensor wall time when the ping was answered, in microseconds since Unix epoch
+    pub sensor_wall_us: u64,
+    /// Game time when the reply arrived
+    pub game_received: Duration,
+}
+
+impl ClockSample {
+    pub fn round_trip(&self) -> Duration {
+        self.game_received.saturating_sub(self.game_sent)
+    }
+
+    /// Game time halfway through the round trip, in microseconds
+    fn game_midpoint_us(&self) -> i64 {
+        (self.game_sent.as_micros() as i64 + self.game_received.as_micros() as i64) / 2
+    }
+
+    /// Sensor wall clock minus game time, in microseconds
+    pub fn wall_offset_us(&self) -> i64 {
+        self.sensor_wall_us as i64 - self.game_midpoint_us()
+    }
+
+    /// Sensor monotonic clock minus game time, in microseconds
+    pub fn monotonic_offset_us(&self) -> i64 {
+        self.sensor_monotonic.as_micros() as i64 - self.game_midpoint_us()
+    }
+}
+
+/// Mapping from game time to sensor time
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ClockSyncEstimate {
+    /// Sensor wall clock (microseconds since Unix epoch) minus game time (microseconds)
+    pub wall_offset_us: i64,
+    /// Sensor monotonic clock minus game time, in microseconds
+    pub monotonic_offset_us: i64,
+    /// Bound on the offset error: half the round trip of the sample used
+    pub error_us: u64,
+    /// How far the estimate can be trusted, in [0, 1]
+    pub confidence: f32,
+    /// Round trips seen so far
+    pub samples: u32,
+}
+
+impl ClockSyncEstimate {
+    /// Sensor wall timestamp, in microseconds since Unix epoch, of a game time
+    pub fn game_to_sensor_wall_us(&self, game_time: Duration) -> i64 {
+        game_time.as_micros() as i64 + self.wall_offset_us
+    }
+
+    /// Game time in seconds of a sensor wall timestamp; negative before the game started
+    pub fn sensor_wall_to_game_secs(&self, wall_us: u64) -> f64 {
+        (wall_us as i64 - self.wall_offset_us) as f64 / 1_000_000.0
+    }
+
+    /// The estimate with the tighter error bound, preferring `other` on ties
+    pub fn tighter(self, other: Self) -> Self {
+        if other.error_us <= self.error_us { other } else { self }
+    }
+}
+
+/// A game estimate stamped into the sensor's event stream
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SensorClockSync {
+    /// When the sensor received the estimate, in microseconds since Unix epoch
+    pub timestamp_us: u64,
+    pub estimate: ClockSyncEstimate,
+}
+
+/// Offset estimator over a sliding window of round trips
+#[derive(Debug, Clone)]
+pub struct ClockSyncEstimator {
+    window: usize,
+    samples: VecDeque<ClockSample>,
+    total: u32,
+}
+
+impl ClockSyncEstimator {
+    pub fn new(window: usize) -> Self {
+        let window = window.max(1);
+        Self {
+            window,
+            samples: VecDeque::with_capacity(window),
+            total: 0,
+        }
+    }
+
+    /// Add a round trip and return the updated estimate
+    ///
+    /// Samples whose reply arrived before the ping was sent are discarded.
+    pub fn add(&mut self, sample: ClockSample) -> Option<ClockSyncEstimate> {
+        if sample.game_received >= sample.game_sent {
+            if self.samples.len() == self.window {
+                self.samples.pop_front();
+            }
+            self.samples.push_back(sample);
+            self.total = self.total.saturating_add(1);
+        }
+        self.estimate()
+    }
+
+    /// Estimate from the window's shortest round trip
+    pub fn estimate(&self) -> Option<ClockSyncEstimate> {
+        let best = self.samples.iter().min_by_key(|sample| sample.round_trip())?;
+        let error_us = (best.round_trip().as_micros() / 2) as u64;
+        let fill = self.samples.len() as f32 / self.window as f32;
+
+        Some(ClockSyncEstimate {
+            wall_offset_us: best.wall_offset_us(),
+            monotonic_offset_us: best.monotonic_offset_us(),
+            error_us,
+            confidence: fill * CONFIDENT_ERROR_US / (CONFIDENT_ERROR_US + error_us as f32),
+            samples: self.total,
+        })
+    }
+
+    pub fn reset(&mut self) {
+        self.samples.clear();
+        self.total = 0;
+    }
+}
+
+impl Default for ClockSyncEstimator {
+    fn default() -> Self {
+        Self::new(DEFAULT_WINDOW)
+    }
+}
+
+/// Monotonic time and wall time in microseconds since Unix epoch, as sent in a ping reply
+pub fn read_clock(clock: &dyn Clock) -> (Duration, u64) {
+    let wall_us = clock
+        .wall()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_micros() as u64;
+    (clock.monotonic(), wall_us)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::resume::ManualClock;
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+    use std::time::SystemTime;
+
+    /// Game clock started 100 s after a manually driven sensor clock
+    struct Link {
+        sensor: ManualClock,
+        game: Duration,
+    }
+
+    impl Link {
+        fn new() -> Self {
+            let sensor = ManualClock::new(SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000));
+            sensor.advance(Duration::from_secs(100));
+            Self { sensor, game: Duration::ZERO }
+        }
+
+        fn true_wall_offset_us(&self) -> i64 {
+            let (monotonic, wall_us) = read_clock(&self.sensor);
+            wall_us as i64 - (monotonic.as_micros() as i64 - 100_000_000)
+        }
+
+        fn advance(&mut self, by: Duration) {
+            self.sensor.advance(by);
+            self.game += by;
+        }
+
+        /// One ping with the given one-way delays
+        fn ping(&mut self, outbound: Duration, inbound: Duration) -> ClockSample {
+            let game_sent = self.game;
+            self.advance(outbound);
+            let (sensor_monotonic, sensor_wall_us) = read_clock(&self.sensor);
+            self.advance(inbound);
+            ClockSample {
+                game_sent,
+                sensor_monotonic,
+                sensor_wall_us,
+                game_received: self.game,
+            }
+        }
+    }
+
+    #[test]
+    fn test_symmetric_delay_gives_exact_offset() {
+        let mut link = Link::new();
+        let mut estimator = ClockSyncEstimator::default();
+        let delay = Duration::from_millis(3);
+
+        let estimate = estimator.add(link.ping(delay, delay)).unwrap();
+        assert_eq!(estimate.wall_offset_us, link.true_wall_offset_us());
+        assert_eq!(estimate.monotonic_offset_us, 100_000_000);
+        assert_eq!(estimate.error_us, 3_000);
+        assert_eq!(estimate.samples, 1);
+    }
+
+    #[test]
+    fn test_asymmetric_delays_converge_within_error_bound() {
+        let mut link = Link::new();
+        let mut estimator = ClockSyncEstimator::default();
+        let mut rng = StdRng::seed_from_u64(7);
+
+        // Uploads are slow and jittery, replies fast: every sample is biased
+        let mut first = None;
+        let mut estimate = None;
+        let mut worst_bound = 0;
+        for _ in 0..40 {
+            let outbound = Duration::from_micros(rng.gen_range(1_000..20_000));
+            let inbound = Duration::from_micros(rng.gen_range(200..2_000));
+            let sample = link.ping(outbound, inbound);
+            worst_bound = worst_bound.max(sample.round_trip().as_micros() as u64 / 2);
+            estimate = estimator.add(sample);
+            first.get_or_insert(estimate.unwrap());
+            link.advance(Duration::from_millis(500));
+
+            // The true offset always lies within the reported bound
+            let current = estimate.unwrap();
+            let error = (current.wall_offset_us - link.true_wall_offset_us()).unsigned_abs();
+            assert!(error <= current.error_us, "error {} exceeds bound {}", error, current.error_us);
+        }
+
+        let first = first.unwrap();
+        let estimate = estimate.unwrap();
+        assert!(estimate.error_us < worst_bound / 4, "bound {}", estimate.error_us);
+        assert!(estimate.error_us < 2_500, "bound {}", estimate.error_us);
+        assert!(estimate.confidence > first.confidence);
+        assert_eq!(estimate.samples, 40);
+
+        // Mapping a game time gives the sensor's wall clock at that moment
+        let (_, wall_us) = read_clock(&link.sensor);
+        let mapped = estimate.game_to_sensor_wall_us(link.game);
+        assert!((mapped - wall_us as i64).unsigned_abs() <= estimate.error_us);
+        let game_secs = estimate.sensor_wall_to_game_secs(wall_us);
+        assert!((game_secs - link.game.as_secs_f64()).abs() <= estimate.error_us as f64 / 1e6);
+    }
+
+    #[test]
+    fn test_outliers_age_out_of_window() {
+        let mut link = Link::new();
+        let mut estimator = ClockSyncEstimator::new(4);
+        let fast = Duration::from_micros(500);
+
+        estimator.add(link.ping(fast, fast));
+        for _ in 0..4 {
+            estimator.add(link.ping(Duration::from_millis(30), Duration::from_millis(10)));
+        }
+
+        // The fast sample left the window, so the bound reflects the slow link
+        let estimate = estimator.estimate().unwrap();
+        assert_eq!(estimate.error_us, 20_000);
+        assert!(estimator.add(ClockSample {
+            game_sent: Duration::from_secs(2),
+            game_received: Duration::from_secs(1),
+            ..link.ping(fast, fast)
+        }).is_some());
+        assert_eq!(estimator.estimate().unwrap().samples, 5);
+
+        estimator.reset();
+        assert!(estimator.estimate().is_none());
+    }
+}
@@ -3,21 +3,41 @@
 //! This module provides a compatibility wrapper that implements the legacy FearSensor trait
 //! for the modern EmotionSensor, enabling seamless migration from Haar cascade to YuNet
 //! face detection without breaking existing code.
+//!
+//! With the `stream` feature, [`GrpcFearSensor`] implements the same trait
+//! over a sensor daemon, so code written against `FearSensor` can consume a
+//! remote sensor. [`ReplayFearSensor`] plays back a session recorded with
+//! `SensorConfig::record_path`.
 
 use async_trait::async_trait;
-use spectremesh_core::{FearScore, FearConfig, CameraDevice, FearError, CameraError};
+use spectremesh_core::{FearScore, FearConfig, CameraDevice, FearError, CameraError, EmotionLayout};
+use spectremesh_core::math::compute_confidence;
 use crate::{
+    backpressure::{self, BackpressurePolicy},
     sensor::{EmotionSensor, SensorError},
     types::FearFrame,
+    fanout::{FrameFanout, FrameSubscriber},
     config::SensorConfig,
+    model_info::ModelInfo,
+    model_provider::ModelProvider,
+    cameras::{self, backend_camera_name},
+    mock_patterns,
+    recording::Recording,
 };
-use async_channel::Receiver;
-use std::time::Duration;
-use std::sync::{Arc, Mutex};
-use opencv::{
-    videoio::{VideoCapture, CAP_ANY},
-    prelude::{VideoCaptureTraitConst, VideoCaptureTrait},
+#[cfg(feature = "stream")]
+use crate::{
+    backpressure::BackpressureSender,
+    grpc_client::SensorClient,
+    proto::{sensor_event, Score},
+    transport::SensorTransport,
 };
+#[cfg(feature = "stream")]
+use futures::StreamExt;
+#[cfg(feature = "stream")]
+use spectre_protocol::score_to_fear_score;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime};
+use std::sync::{Arc, Mutex};
 
 /// Legacy FearSensor trait for compatibility
 #[async_trait]
@@ -44,7 +64,8 @@ pub trait FearSensor: Send + Sync {
 /// YuNet-based fear sensor that implements the legacy FearSensor trait
 pub struct YuNetFearSensor {
     emotion_sensor: EmotionSensor,
-    frame_receiver: Option<Receiver<FearFrame>>,
+    frames: Option<FrameFanout<FearFrame>>,
+    record_path: Option<PathBuf>,
 }
 
 impl YuNetFearSensor {
@@ -53,9 +74,28 @@ impl YuNetFearSensor {
         let config = SensorConfig::default();
         Self {
             emotion_sensor: EmotionSensor::new(config),
-            frame_receiver: None,
+            frames: None,
+            record_path: None,
         }
     }
+
+    /// Record every frame to `path`, for replay with [`ReplayFearSensor`]
+    pub fn with_record_path(mut self, path: impl Into<PathBuf>) -> Self {
+        self.record_path = Some(path.into());
+        self
+    }
+
+    /// Subscribe to raw fear frames alongside the `FearScore` stream
+    ///
+    /// Returns `None` until the sensor has been started.
+    pub fn subscribe_frames(&self) -> Option<FrameSubscriber<FearFrame>> {
+        self.frames.as_ref().map(FrameFanout::subscribe)
+    }
+
+    /// Provenance of the models in use; empty until initialized
+    pub fn model_info(&self) -> &[ModelInfo] {
+        self.emotion_sensor.model_info()
+    }
 }
 
 impl Default for YuNetFearSensor {
@@ -68,7 +108,8 @@ impl Default for YuNetFearSensor {
 impl FearSensor for YuNetFearSensor {
     async fn initialize(&mut self, config: &FearConfig) -> Result<(), FearError> {
         // Convert FearConfig to SensorConfig
-        let sensor_config = convert_fear_config_to_sensor_config(config);
+        let mut sensor_config = convert_fear_config_to_sensor_config(config);
+        sensor_config.record_path = self.record_path.clone();
         
         // Update the emotion sensor's configuration
         self.emotion_sensor = EmotionSensor::new(sensor_config);
@@ -85,24 +126,27 @@ impl FearSensor for YuNetFearSensor {
         let frame_receiver = self.emotion_sensor.start().await
             .map_err(convert_sensor_error_to_fear_error)?;
         
-        // Create a channel for FearScore output
-        let (score_sender, score_receiver) = async_channel::bounded(2);
+        // Create a channel for FearScore output, as full as the sensor's
+        let config = self.emotion_sensor.config();
+        let (score_sender, score_receiver) = backpressure::channel(config.channel_buffer_size, config.backpressure);
         
-        // Store the frame receiver for later cleanup
-        self.frame_receiver = Some(frame_receiver.clone());
+        // Share the frame stream with other in-process consumers
+        let frames = FrameFanout::new(frame_receiver);
+        let mut scores = frames.subscribe();
+        self.frames = Some(frames);
         
         // Spawn a task to convert FearFrame to FearScore
         tokio::spawn(async move {
-            while let Ok(fear_frame) = frame_receiver.recv().await {
+            while let Some(fear_frame) = scores.recv().await {
                 let fear_score = convert_fear_frame_to_fear_score(fear_frame);
                 
-                // Try to send with back-pressure handling
-                match score_sender.try_send(fear_score) {
-                    Ok(_) => {},
-                    Err(async_channel::TrySendError::Full(_)) => {
-                        tracing::debug!("Dropped frame due to back-pressure in YuNet sensor");
+                // Send under the sensor's back-pressure policy
+                match score_sender.send(fear_score).await {
+                    Ok(delivery) if delivery.dropped() => {
+                        tracing::debug!("Dropped a frame under {} back-pressure in YuNet sensor", score_sender.policy());
                     },
-                    Err(async_channel::TrySendError::Closed(_)) => {
+                    Ok(_) => {},
+                    Err(_) => {
                         break; // Receiver dropped
                     }
                 }
@@ -116,15 +160,35 @@ impl FearSensor for YuNetFearSensor {
         self.emotion_sensor.stop().await
             .map_err(convert_sensor_error_to_fear_error)?;
         
-        // Clear the frame receiver
-        self.frame_receiver = None;
+        // Release the frame stream
+        self.frames = None;
         
         Ok(())
     }
 
     async fn enumerate_cameras(&self) -> Result<Vec<CameraDevice>, CameraError> {
-        // Implement real camera enumeration using OpenCV
-        enumerate_cameras_opencv().await
+        let mut cameras = tokio::task::spawn_blocking(cameras::enumerate_cameras)
+            .await
+            .ok()
+            .and_then(Result::ok)
+            .unwrap_or_default();
+
+        // The sensor's own camera is busy and may not open a second time;
+        // report the format it actually negotiated
+        if let Some(format) = self.emotion_sensor.get_state().camera {
+            let id = self.emotion_sensor.config().camera_id;
+            let resolution = (format.width, format.height);
+            match cameras.iter_mut().find(|camera| camera.id == id) {
+                Some(camera) => camera.resolution = resolution,
+                None => cameras.push(CameraDevice::new(id, backend_camera_name(id), resolution)),
+            }
+        }
+
+        if cameras.is_empty() {
+            Err(CameraError::NoCamerasAvailable)
+        } else {
+            Ok(cameras)
+        }
     }
 
     fn is_calibrated(&self) -> bool {
@@ -141,85 +205,35 @@ impl FearSensor for YuNetFearSensor {
 
 }
 
-/// Real camera enumeration using OpenCV
-async fn enumerate_cameras_opencv() -> Result<Vec<CameraDevice>, CameraError> {
-    let mut cameras = Vec::new();
-
-    // Try to enumerate cameras 0-9 (reasonable range for most systems)
-    for id in 0..10 {
-        match VideoCapture::new(id, CAP_ANY) {
-            Ok(mut camera) => {
-                if camera.is_opened().unwrap_or(false) {
-                    // Get camera properties
-                    let width = camera.get(opencv::videoio::CAP_PROP_FRAME_WIDTH)
-                        .unwrap_or(640.0) as u32;
-                    let height = camera.get(opencv::videoio::CAP_PROP_FRAME_HEIGHT)
-                        .unwrap_or(480.0) as u32;
-
-                    // Platform-specific camera naming
-                    let name = get_camera_name(id);
-
-                    cameras.push(CameraDevice::new(id as u32, name, (width, height)));
-
-                    // Clean up camera handle
-                    let _ = camera.release();
-                }
-            },
-            Err(_) => continue, // Camera not available
-        }
-    }
-
-    if cameras.is_empty() {
-        Err(CameraError::NoCamerasAvailable)
-    } else {
-        Ok(cameras)
-    }
-}
-
-/// Get platform-specific camera name
-fn get_camera_name(id: i32) -> String {
-    #[cfg(target_os = "windows")]
-    return format!("DirectShow Camera {}", id);
-
-    #[cfg(target_os = "macos")]
-    return format!("AVFoundation Camera {}", id);
-
-    #[cfg(target_os = "linux")]
-    return format!("V4L2 Camera {}", id);
-
-    #[cfg(not(any(target_os = "windows", target_os = "macos", target_os = "linux")))]
-    return format!("Camera {}", id);
-}
-
 /// Convert FearConfig to SensorConfig
 fn convert_fear_config_to_sensor_config(fear_config: &FearConfig) -> SensorConfig {
     SensorConfig {
-        emotion_model_path: Some(fear_config.model_path.clone()),
+        emotion_model: Some(ModelProvider::file(&fear_config.model_path)),
         onnx_threads: num_cpus::get().min(4), // Reasonable default
-        freeze_calibration: false,
+        calibration_period: fear_config.calibration_duration,
+        inference_timeout: fear_config.inference_timeout,
         camera_id: fear_config.camera.device_id,
+        camera_resolution: Some((fear_config.camera.width, fear_config.camera.height)),
         target_fps: fear_config.camera.fps as f32,
-        channel_buffer_size: 2,
-        metrics_port: 9090,
-        grpc_socket_path: SensorConfig::default().grpc_socket_path, // Use platform-specific default
+        ..SensorConfig::default()
     }
 }
 
 /// Convert FearFrame to FearScore
 fn convert_fear_frame_to_fear_score(fear_frame: FearFrame) -> FearScore {
-    if fear_frame.calibrated {
+    let score = if fear_frame.calibrated {
         FearScore::new_calibrated(
             fear_frame.fear_score,
             fear_frame.emotion_logits,
             fear_frame.confidence,
         )
     } else {
-        FearScore::new_uncalibrated(
-            fear_frame.fear_score,
-            fear_frame.emotion_logits,
-            fear_frame.confidence,
-        )
-    }
+        FearScore::new_uncalibrated(fear_frame.emotion_logits, fear_frame.confidence)
+    };
+    score
+        .with_face_present(fear_frame.face_present)
+        .with_capture_time(fear_frame.captured_at)
+        .with_sequence(fear_frame.sequence)
 }
 
 /// Convert SensorError to FearError
@@ -227,12 +241,21 @@ fn convert_sensor_error_to_fear_error(sensor_error: SensorError) -> FearError {
     match sensor_error {
         SensorError::OnnxEnvironment(msg) => FearError::OnnxRuntime { message: msg },
         SensorError::ModelLoading(msg) => FearError::model_not_found(msg),
+        SensorError::ModelProvider(e) => FearError::model_not_found(e.to_string()),
+        SensorError::EmotionTensors(e) => FearError::ModelLoading { message: e.to_string() },
         SensorError::CameraInit(msg) => FearError::OnnxRuntime { message: format!("Camera init: {}", msg) },
         SensorError::FrameProcessing(msg) => FearError::OnnxRuntime { message: format!("Frame processing: {}", msg) },
         SensorError::FaceDetection(_) => FearError::NoFaceDetected,
         SensorError::Calibration(_) => FearError::OnnxRuntime { message: "Calibration error".to_string() },
         SensorError::ChannelError => FearError::OnnxRuntime { message: "Channel communication error".to_string() },
         SensorError::NotInitialized => FearError::OnnxRuntime { message: "Sensor not initialized".to_string() },
+        error @ SensorError::MeasureTimeout(_) => FearError::OnnxRuntime { message: error.to_string() },
+        error @ SensorError::InferenceTimeout { .. } => FearError::OnnxRuntime { message: error.to_string() },
+        SensorError::ModelSwap(e) => FearError::Configuration { message: format!("Model swap: {}", e) },
+        SensorError::CalibrationControl(e) => FearError::OnnxRuntime { message: format!("Calibration control: {}", e) },
+        SensorError::Reconfigure(e) => FearError::Configuration { message: e.to_string() },
+        SensorError::InvalidLogits(e) => e.into(),
+        SensorError::StopFailed(msg) => FearError::OnnxRuntime { message: format!("Stop: {}", msg) },
     }
 }
 
@@ -245,58 +268,88 @@ struct MockCalibrationState {
     target: usize,
 }
 
+impl MockCalibrationState {
+    fn new(target: usize) -> Self {
+        Self {
+            calibrated: false,
+            progress: 0.0,
+            samples: 0,
+            target: target.max(1),
+        }
+    }
+
+    /// Count one sample, returning whether calibration is complete
+    fn advance(&mut self) -> bool {
+        self.samples += 1;
+        self.progress = (self.samples as f32 / self.target as f32).min(1.0);
+        self.calibrated = self.samples >= self.target;
+        self.calibrated
+    }
+}
+
 /// Mock fear sensor for testing without hardware dependencies
+///
+/// Calibration completes after [`FearConfig::calibration_samples`] samples of
+/// the config passed to `initialize` (`calibration_duration` × `camera.fps`),
+/// unless [`MockFearSensor::with_calibration_target`] fixes the count.
 pub struct MockFearSensor {
     pub fear_sequence: Vec<f32>,
     pub current_index: usize,
+    calibration_target: Option<usize>,
     calibration_state: Arc<Mutex<MockCalibrationState>>,
 }
 
 impl MockFearSensor {
-    /// Create a new mock sensor with a constant fear level
+    /// Create a new mock sensor looping over `fear_sequence`
     pub fn new(fear_sequence: Vec<f32>) -> Self {
+        let target = FearConfig::default().calibration_samples();
         Self {
             fear_sequence,
             current_index: 0,
-            calibration_state: Arc::new(Mutex::new(MockCalibrationState {
-                calibrated: false,
-                progress: 0.0,
-                samples: 0,
-                target: 20, // 20 samples for calibration
-            })),
+            calibration_target: None,
+            calibration_state: Arc::new(Mutex::new(MockCalibrationState::new(target))),
         }
     }
 
     /// Create a step pattern sensor (low → high → low)
     pub fn step_pattern() -> Self {
-        Self::new(vec![0.1, 0.2, 0.3, 0.7, 0.8, 0.9, 0.8, 0.7, 0.3, 0.2, 0.1])
+        Self::new(mock_patterns::step())
     }
 
     /// Create a sine wave pattern sensor
     pub fn sine_pattern(center: f32, amplitude: f32, period: f32) -> Self {
-        let mut sequence = Vec::new();
-        for i in 0..100 {
-            let t = i as f32 * period / 100.0;
-            let value = center + amplitude * (t * 2.0 * std::f32::consts::PI).sin();
-            sequence.push(value.clamp(0.0, 1.0));
-        }
-        Self::new(sequence)
+        Self::new(mock_patterns::sine(center, amplitude, period))
+    }
+
+    /// Create a sensor with a constant fear level
+    pub fn constant_pattern(level: f32) -> Self {
+        Self::new(mock_patterns::constant(level, 1))
+    }
+
+    /// Calibrate after exactly `samples` samples (at least one), whatever the config
+    pub fn with_calibration_target(mut self, samples: usize) -> Self {
+        self.calibration_target = Some(samples.max(1));
+        *self.calibration_state.lock().unwrap() = MockCalibrationState::new(samples);
+        self
+    }
+
+    /// Samples the current calibration needs
+    pub fn calibration_target(&self) -> usize {
+        self.calibration_state.lock().unwrap().target
     }
 }
 
 #[async_trait]
 impl FearSensor for MockFearSensor {
-    async fn initialize(&mut self, _config: &FearConfig) -> Result<(), FearError> {
+    async fn initialize(&mut self, config: &FearConfig) -> Result<(), FearError> {
         self.current_index = 0;
-        let mut state = self.calibration_state.lock().unwrap();
-        state.calibrated = false;
-        state.progress = 0.0;
-        state.samples = 0;
+        let target = self.calibration_target.unwrap_or_else(|| config.calibration_samples());
+        *self.calibration_state.lock().unwrap() = MockCalibrationState::new(target);
         Ok(())
     }
 
     async fn start(&mut self) -> Result<async_channel::Receiver<FearScore>, FearError> {
-        let (sender, receiver) = async_channel::bounded(2);
+        let (sender, receiver) = backpressure::channel(2, BackpressurePolicy::default());
         let fear_sequence = self.fear_sequence.clone();
         let mut current_index = self.current_index;
         let calibration_state = Arc::clone(&self.calibration_state);
@@ -308,32 +361,26 @@ impl FearSensor for MockFearSensor {
                 current_index += 1;
 
                 // Update calibration progress
-                let calibrated = {
-                    let mut state = calibration_state.lock().unwrap();
-                    state.samples += 1;
-                    state.progress = (state.samples as f32 / state.target as f32).min(1.0);
-                    state.calibrated = state.samples >= state.target;
-                    state.calibrated
-                };
+                let calibrated = calibration_state.lock().unwrap().advance();
 
-                // Create mock emotion logits with fear at index 2
-                let mut emotion_logits = [0.1; 7];
-                emotion_logits[2] = fear_value; // Fear is at index 2
+                // Mock emotion logits reading as the fear value, and how sure they are
+                let emotion_logits = mock_patterns::emotion_logits(fear_value, EmotionLayout::STANDARD);
+                let confidence = compute_confidence(1.0, &emotion_logits);
 
                 // Create fear score
                 let score = if calibrated {
-                    FearScore::new_calibrated(fear_value, emotion_logits, 0.9)
+                    FearScore::new_calibrated(fear_value, emotion_logits, confidence)
                 } else {
-                    FearScore::new_uncalibrated(fear_value, emotion_logits, 0.9)
+                    FearScore::new_uncalibrated(emotion_logits, confidence)
                 };
 
-                // Try to send with back-pressure handling
-                match sender.try_send(score) {
-                    Ok(_) => {},
-                    Err(async_channel::TrySendError::Full(_)) => {
-                        tracing::debug!("Dropped frame due to back-pressure in mock sensor");
+                // Keep the freshest scores for a slow reader
+                match sender.send(score).await {
+                    Ok(delivery) if delivery.dropped() => {
+                        tracing::debug!("Dropped a frame under {} back-pressure in mock sensor", sender.policy());
                     },
-                    Err(async_channel::TrySendError::Closed(_)) => {
+                    Ok(_) => {},
+                    Err(_) => {
                         break; // Receiver dropped
                     }
                 }
@@ -365,9 +412,482 @@ impl FearSensor for MockFearSensor {
     }
 }
 
+/// Calibration state of the frames a replay has emitted so far
+#[derive(Debug, Clone, Copy, Default)]
+struct ReplayCalibration {
+    calibrated: bool,
+    progress: f32,
+}
+
+/// Fear sensor playing back a recorded session
+///
+/// Frames are emitted at their recorded capture times, relative to the
+/// first, divided by the playback speed, so a session recorded at any frame
+/// rate plays back as it was captured. Every recorded frame is delivered:
+/// a slow receiver delays playback rather than losing frames, so the same
+/// file always produces the same fear curve. Replayed frames are stamped
+/// with the time they are emitted; with looping, frame numbers carry on
+/// from one pass to the next.
+///
+/// Calibration state follows the frames: calibrated once a calibrated frame
+/// has been emitted, and until then progress towards the first one.
+pub struct ReplayFearSensor {
+    recording: Recording,
+    speed: f32,
+    looping: bool,
+    frames: Option<Vec<FearFrame>>,
+    calibration: Arc<Mutex<ReplayCalibration>>,
+    fanout: Option<FrameFanout<FearFrame>>,
+    task: Option<tokio::task::JoinHandle<()>>,
+}
+
+impl ReplayFearSensor {
+    /// Play back `recording` once, at the recorded speed
+    pub fn new(recording: Recording) -> Self {
+        Self {
+            recording,
+            speed: 1.0,
+            looping: false,
+            frames: None,
+            calibration: Arc::new(Mutex::new(ReplayCalibration::default())),
+            fanout: None,
+            task: None,
+        }
+    }
+
+    /// Load the recording at `path`
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, FearError> {
+        Recording::load(path).map(Self::new).map_err(|e| FearError::Configuration { message: e.to_string() })
+    }
+
+    /// Play `speed` times faster than recorded; infinity plays without waiting
+    pub fn with_speed(mut self, speed: f32) -> Self {
+        self.speed = speed;
+        self
+    }
+
+    /// Start over after the last frame, one frame interval later, until stopped
+    pub fn with_looping(mut self, looping: bool) -> Self {
+        self.looping = looping;
+        self
+    }
+
+    /// The recording being played
+    pub fn recording(&self) -> &Recording {
+        &self.recording
+    }
+
+    /// Subscribe to raw fear frames alongside the `FearScore` stream
+    ///
+    /// Returns `None` until the sensor has been started.
+    pub fn subscribe_frames(&self) -> Option<FrameSubscriber<FearFrame>> {
+        self.fanout.as_ref().map(FrameFanout::subscribe)
+    }
+
+    /// Whether playback is running
+    pub fn is_running(&self) -> bool {
+        self.task.as_ref().is_some_and(|task| !task.is_finished())
+    }
+}
+
+impl Drop for ReplayFearSensor {
+    fn drop(&mut self) {
+        if let Some(task) = self.task.take() {
+            task.abort();
+        }
+    }
+}
+
+#[async_trait]
+impl FearSensor for ReplayFearSensor {
+    async fn initialize(&mut self, _config: &FearConfig) -> Result<(), FearError> {
+        // The recording carries its own configuration
+        if self.speed.is_nan() || self.speed <= 0.0 {
+            return Err(FearError::Configuration {
+                message: format!("Replay speed must be positive, got {}", self.speed),
+            });
+        }
+        let frames = self.recording.to_frames().map_err(|e| FearError::Configuration { message: e.to_string() })?;
+        self.frames = Some(frames);
+        *self.calibration.lock().unwrap() = ReplayCalibration::default();
+        Ok(())
+    }
+
+    async fn start(&mut self) -> Result<async_channel::Receiver<FearScore>, FearError> {
+        if self.is_running() {
+            return Err(FearError::AlreadyRunning);
+        }
+        let frames = self.frames.clone().ok_or(FearError::NotInitialized)?;
+        let (frame_sender, frame_receiver) = async_channel::bounded(2);
+        let (score_sender, score_receiver) = async_channel::bounded(2);
+
+        let fanout = FrameFanout::new(frame_receiver);
+        let mut scores = fanout.subscribe();
+        self.fanout = Some(fanout);
+
+        let playback = Playback {
+            offsets: self.recording.offsets(),
+            gap: self.recording.frame_interval(),
+            frames,
+            speed: self.speed,
+            looping: self.looping,
+            calibration: Arc::clone(&self.calibration),
+        };
+        self.task = Some(tokio::spawn(playback.run(frame_sender)));
+
+        tokio::spawn(async move {
+            while let Some(fear_frame) = scores.recv().await {
+                if score_sender.send(convert_fear_frame_to_fear_score(fear_frame)).await.is_err() {
+                    break; // Receiver dropped
+                }
+            }
+        });
+
+        Ok(score_receiver)
+    }
+
+    async fn stop(&mut self) -> Result<(), FearError> {
+        if let Some(task) = self.task.take() {
+            task.abort();
+            let _ = task.await;
+        }
+        self.fanout = None;
+        Ok(())
+    }
+
+    async fn enumerate_cameras(&self) -> Result<Vec<CameraDevice>, CameraError> {
+        let header = &self.recording.header;
+        Ok(header
+            .camera
+            .map(|format| {
+                let id = header.config.camera_id;
+                CameraDevice::new(id, format!("Recorded camera {}", id), (format.width, format.height))
+            })
+            .into_iter()
+            .collect())
+    }
+
+    fn is_calibrated(&self) -> bool {
+        self.calibration.lock().unwrap().calibrated
+    }
+
+    fn calibration_progress(&self) -> f32 {
+        self.calibration.lock().unwrap().progress
+    }
+}
+
+/// Background half of [`ReplayFearSensor`]
+struct Playback {
+    frames: Vec<FearFrame>,
+    /// Recorded time of each frame after the first
+    offsets: Vec<Duration>,
+    /// Pause between the last frame and the first of the next pass
+    gap: Duration,
+    speed: f32,
+    looping: bool,
+    calibration: Arc<Mutex<ReplayCalibration>>,
+}
+
+impl Playback {
+    /// Emit the frames on schedule until done, or the receiver is dropped
+    async fn run(self, sender: async_channel::Sender<FearFrame>) {
+        let first_calibrated = self.frames.iter().position(|frame| frame.calibrated);
+        let pass_length = self.offsets.last().copied().unwrap_or_default() + self.gap;
+        let first_sequence = self.frames.first().map_or(0, |frame| frame.sequence);
+        let last_sequence = self.frames.last().map_or(0, |frame| frame.sequence);
+        // Unnumbered recordings stay unnumbered
+        let sequence_span = if first_sequence == 0 { 0 } else { last_sequence.saturating_sub(first_sequence) + 1 };
+        let start = tokio::time::Instant::now();
+
+        for pass in 0u32.. {
+            let pass_start = pass_length * pass;
+            for (index, (frame, offset)) in self.frames.iter().zip(&self.offsets).enumerate() {
+                tokio::time::sleep_until(start + (pass_start + *offset).div_f64(self.speed as f64)).await;
+
+                let mut frame = frame
+                    .clone()
+                    .with_capture_time(SystemTime::now())
+                    .with_sequence(frame.sequence + sequence_span * pass as u64);
+                frame.timestamp = Instant::now();
+                *self.calibration.lock().unwrap() = match first_calibrated {
+                    Some(first) if index >= first => ReplayCalibration { calibrated: true, progress: 1.0 },
+                    Some(first) => ReplayCalibration { calibrated: false, progress: index as f32 / first as f32 },
+                    None => ReplayCalibration::default(),
+                };
+                if sender.send(frame).await.is_err() {
+                    return;
+                }
+            }
+            if !self.looping {
+                return;
+            }
+        }
+    }
+}
+
+/// Calibration state last reported by a sensor daemon
+#[cfg(feature = "stream")]
+#[derive(Debug, Clone, Copy, Default)]
+struct RemoteCalibration {
+    calibrated: bool,
+    progress: f32,
+}
+
+#[cfg(feature = "stream")]
+impl RemoteCalibration {
+    fn from_status(status: &crate::proto::StatusResponse) -> Self {
+        status.calibration.as_ref().map_or_else(Self::default, |calibration| Self {
+            calibrated: calibration.completed,
+            progress: calibration.progress.clamp(0.0, 1.0),
+        })
+    }
+}
+
+/// Fear sensor backed by a sensor daemon over gRPC
+///
+/// `start` streams the daemon's scores on a background task, reconnecting
+/// with exponential backoff whenever the connection or stream drops. The
+/// daemon owns the camera and the model, so the `FearConfig` passed to
+/// `initialize` only matters to in-process sensors. Calibration state comes
+/// from the scores and a `GetStatus` poll while the daemon calibrates.
+#[cfg(feature = "stream")]
+pub struct GrpcFearSensor {
+    transport: SensorTransport,
+    auth_token: Option<String>,
+    initial_backoff: Duration,
+    max_backoff: Duration,
+    status_poll: Duration,
+    calibration: Arc<Mutex<RemoteCalibration>>,
+    client: Option<SensorClient>,
+    task: Option<tokio::task::JoinHandle<()>>,
+}
+
+#[cfg(feature = "stream")]
+impl GrpcFearSensor {
+    /// Create a sensor for the daemon listening on `transport`
+    pub fn new(transport: SensorTransport) -> Self {
+        Self {
+            transport,
+            auth_token: None,
+            initial_backoff: Duration::from_millis(250),
+            max_backoff: Duration::from_secs(8),
+            status_poll: Duration::from_millis(500),
+            calibration: Arc::new(Mutex::new(RemoteCalibration::default())),
+            client: None,
+            task: None,
+        }
+    }
+
+    /// Create a sensor for a daemon address, parsed like `SensorConfig::grpc_socket_path`
+    pub fn connect_to(address: &str) -> Result<Self, FearError> {
+        address.parse().map(Self::new).map_err(|e| FearError::Configuration {
+            message: format!("Invalid sensor address {:?}: {}", address, e),
+        })
+    }
+
+    /// Attach a bearer token to every request
+    pub fn with_auth_token(mut self, token: impl Into<String>) -> Self {
+        self.auth_token = Some(token.into());
+        self
+    }
+
+    /// Delay before the first reconnection attempt, doubling up to `max`
+    pub fn with_reconnect_backoff(mut self, initial: Duration, max: Duration) -> Self {
+        self.initial_backoff = initial;
+        self.max_backoff = max.max(initial);
+        self
+    }
+
+    /// Where the daemon is expected to listen
+    pub fn transport(&self) -> &SensorTransport {
+        &self.transport
+    }
+
+    /// Whether the streaming task is running
+    pub fn is_running(&self) -> bool {
+        self.task.as_ref().is_some_and(|task| !task.is_finished())
+    }
+}
+
+#[cfg(feature = "stream")]
+impl Drop for GrpcFearSensor {
+    fn drop(&mut self) {
+        if let Some(task) = self.task.take() {
+            task.abort();
+        }
+    }
+}
+
+#[cfg(feature = "stream")]
+#[async_trait]
+impl FearSensor for GrpcFearSensor {
+    async fn initialize(&mut self, _config: &FearConfig) -> Result<(), FearError> {
+        let mut client = connect_daemon(&self.transport, self.auth_token.as_deref())
+            .await
+            .map_err(|e| FearError::Channel {
+                message: format!("Failed to connect to sensor at {}: {}", self.transport, e),
+            })?;
+        let status = client.get_status().await.map_err(|e| FearError::Channel {
+            message: format!("Sensor at {} did not report its status: {}", self.transport, e.message()),
+        })?;
+        *self.calibration.lock().unwrap() = RemoteCalibration::from_status(&status);
+        self.client = Some(client);
+        Ok(())
+    }
+
+    async fn start(&mut self) -> Result<async_channel::Receiver<FearScore>, FearError> {
+        if self.is_running() {
+            return Err(FearError::AlreadyRunning);
+        }
+        let client = self.client.clone().ok_or(FearError::NotInitialized)?;
+        let (sender, receiver) = backpressure::channel(2, BackpressurePolicy::default());
+
+        let stream = RemoteScoreStream {
+            transport: self.transport.clone(),
+            auth_token: self.auth_token.clone(),
+            initial_backoff: self.initial_backoff,
+            max_backoff: self.max_backoff,
+            status_poll: self.status_poll,
+            calibration: Arc::clone(&self.calibration),
+        };
+        self.task = Some(tokio::spawn(stream.run(client, sender)));
+
+        Ok(receiver)
+    }
+
+    async fn stop(&mut self) -> Result<(), FearError> {
+        if let Some(task) = self.task.take() {
+            task.abort();
+            // Wait for the cancelled task so its stream is closed on return
+            let _ = task.await;
+        }
+        Ok(())
+    }
+
+    async fn enumerate_cameras(&self) -> Result<Vec<CameraDevice>, CameraError> {
+        // Cameras belong to the daemon, none are local
+        Ok(Vec::new())
+    }
+
+    fn is_calibrated(&self) -> bool {
+        self.calibration.lock().unwrap().calibrated
+    }
+
+    fn calibration_progress(&self) -> f32 {
+        self.calibration.lock().unwrap().progress
+    }
+}
+
+/// Connect to the daemon on `transport`, attaching `auth_token` if any
+#[cfg(feature = "stream")]
+async fn connect_daemon(
+    transport: &SensorTransport,
+    auth_token: Option<&str>,
+) -> Result<SensorClient, Box<dyn std::error::Error + Send + Sync>> {
+    let client = SensorClient::connect(transport).await?;
+    Ok(match auth_token {
+        Some(token) => client.with_auth_token(token),
+        None => client,
+    })
+}
+
+/// Background half of [`GrpcFearSensor`]
+#[cfg(feature = "stream")]
+struct RemoteScoreStream {
+    transport: SensorTransport,
+    auth_token: Option<String>,
+    initial_backoff: Duration,
+    max_backoff: Duration,
+    status_poll: Duration,
+    calibration: Arc<Mutex<RemoteCalibration>>,
+}
+
+#[cfg(feature = "stream")]
+impl RemoteScoreStream {
+    /// Stream scores into `sender`, reconnecting until the receiver is dropped
+    async fn run(self, client: SensorClient, sender: BackpressureSender<FearScore>) {
+        let mut client = Some(client);
+        let mut attempt = 0u32;
+
+        loop {
+            if let Some(client) = client.take() {
+                match self.stream(client, &sender).await {
+                    Ok(()) => return,
+                    Err(reason) => tracing::warn!("Sensor stream from {} lost: {}", self.transport, reason),
+                }
+                attempt = 0;
+            }
+
+            attempt += 1;
+            let delay = self.backoff(attempt);
+            tracing::info!("Reconnecting to sensor at {} in {:?} (attempt {})", self.transport, delay, attempt);
+            tokio::time::sleep(delay).await;
+            if sender.is_closed() {
+                return;
+            }
+
+            match connect_daemon(&self.transport, self.auth_token.as_deref()).await {
+                Ok(connected) => client = Some(connected),
+                Err(e) => tracing::warn!("Failed to reconnect to sensor at {}: {}", self.transport, e),
+            }
+        }
+    }
+
+    /// Forward one stream's scores; `Ok` once the receiver is dropped
+    async fn stream(&self, mut client: SensorClient, sender: &BackpressureSender<FearScore>) -> Result<(), String> {
+        let mut events = client.stream_scores().await.map_err(|e| e.message().to_string())?;
+        let mut poll = tokio::time::interval(self.status_poll);
+        poll.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+        loop {
+            tokio::select! {
+                event = events.next() => {
+                    let event = match event {
+                        Some(Ok(event)) => event,
+                        Some(Err(e)) => return Err(e.message().to_string()),
+                        None => return Err("stream ended".to_string()),
+                    };
+                    let timestamp_us = event.timestamp_us;
+                    let Some(sensor_event::Event::Score(score)) = event.event else {
+                        continue;
+                    };
+                    if score.calibrated {
+                        *self.calibration.lock().unwrap() = RemoteCalibration { calibrated: true, progress: 1.0 };
+                    }
+                    match sender.send(score_to_fear_score(&score, timestamp_us)).await {
+                        Ok(delivery) if delivery.dropped() => {
+                            tracing::debug!("Dropped a frame under {} back-pressure in gRPC sensor", sender.policy());
+                        },
+                        Ok(_) => {},
+                        Err(_) => return Ok(()),
+                    }
+                }
+                _ = poll.tick() => {
+                    // Scores only say whether calibration is done, not how far along it is
+                    if !self.calibration.lock().unwrap().calibrated {
+                        if let Ok(status) = client.get_status().await {
+                            *self.calibration.lock().unwrap() = RemoteCalibration::from_status(&status);
+                        }
+                    }
+                    if sender.is_closed() {
+                        return Ok(());
+                    }
+                }
+            }
+        }
+    }
+
+    /// Delay before reconnection attempt `attempt`, counting from 1
+    fn backoff(&self, attempt: u32) -> Duration {
+        let doublings = attempt.saturating_sub(1).min(31);
+        self.initial_backoff.saturating_mul(1 << doublings).min(self.max_backoff)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use spectremesh_core::PROVISIONAL_FEAR;
     use std::time::Duration;
 
     #[test]
@@ -387,9 +907,14 @@ mod tests {
 
         let sensor_config = convert_fear_config_to_sensor_config(&fear_config);
 
-        assert_eq!(sensor_config.emotion_model_path, Some("test_model.onnx".to_string()));
+        assert_eq!(sensor_config.emotion_model, Some(ModelProvider::file("test_model.onnx")));
         assert_eq!(sensor_config.camera_id, 1);
+        assert_eq!(sensor_config.camera_resolution, Some((1280, 720)));
         assert_eq!(sensor_config.target_fps, 60.0);
+        assert_eq!(sensor_config.calibration_period, Duration::from_secs(45));
+        assert_eq!(sensor_config.inference_timeout, Duration::from_millis(200));
+        assert_eq!(sensor_config.yunet_params(), crate::yunet::YuNetParams::default());
+        assert!(sensor_config.validate().is_ok());
     }
 
     #[test]
@@ -405,13 +930,27 @@ mod tests {
 
         let fear_score = convert_fear_frame_to_fear_score(fear_frame);
 
-        assert_eq!(fear_score.value, 0.75);
+        assert_eq!(fear_score.value(), Some(0.75));
         assert_eq!(fear_score.emotion_logits, emotion_logits);
         assert_eq!(fear_score.confidence, 0.9);
         assert!(fear_score.calibrated);
         assert_eq!(fear_score.extract_fear_logit(), 0.8);
     }
 
+    #[test]
+    fn test_uncalibrated_frame_converts_to_provisional_score() {
+        let emotion_logits = [0.1, 0.1, 0.8, 0.1, 0.1, 0.1, 0.1];
+        let fear_frame = FearFrame::new(0.75, emotion_logits, 0.9, false, Duration::ZERO).with_sequence(4);
+
+        let fear_score = convert_fear_frame_to_fear_score(fear_frame);
+
+        assert_eq!(fear_score.value(), None);
+        assert_eq!(fear_score.provisional_value(), PROVISIONAL_FEAR);
+        assert_eq!(fear_score.emotion_logits, emotion_logits);
+        assert_eq!((fear_score.confidence, fear_score.sequence), (0.9, 4));
+        assert!(!fear_score.calibrated);
+    }
+
     #[tokio::test]
     async fn test_mock_fear_sensor_basic_functionality() {
         let mut sensor = MockFearSensor::new(vec![0.3, 0.5, 0.7]);
@@ -445,6 +984,79 @@ mod tests {
         }
     }
 
+    /// Progress after each sample until calibration completes
+    async fn calibration_trajectory(sensor: &mut MockFearSensor, config: &FearConfig) -> Vec<f32> {
+        sensor.initialize(config).await.unwrap();
+        let mut state = sensor.calibration_state.lock().unwrap();
+        let mut trajectory = Vec::new();
+        while !state.calibrated {
+            state.advance();
+            trajectory.push(state.progress);
+        }
+        trajectory
+    }
+
+    #[tokio::test]
+    async fn test_mock_calibration_follows_config() {
+        for (duration_ms, fps) in [(500, 20), (30_000, 30), (1_000, 60), (10, 15)] {
+            let config = FearConfig {
+                calibration_duration: Duration::from_millis(duration_ms),
+                camera: spectremesh_core::CameraConfig { fps, ..Default::default() },
+                ..FearConfig::default()
+            };
+            let samples = config.calibration_samples();
+
+            // Every constructor calibrates after the same, config-derived number of samples
+            for mut sensor in [
+                MockFearSensor::new(vec![0.3]),
+                MockFearSensor::step_pattern(),
+                MockFearSensor::sine_pattern(0.5, 0.3, 2.0),
+                MockFearSensor::constant_pattern(0.2),
+            ] {
+                let trajectory = calibration_trajectory(&mut sensor, &config).await;
+                assert_eq!(sensor.calibration_target(), samples);
+                assert_eq!(trajectory.len(), samples, "{}ms at {} FPS", duration_ms, fps);
+                let expected: Vec<f32> = (1..=samples).map(|i| i as f32 / samples as f32).collect();
+                assert_eq!(trajectory, expected);
+                assert!(sensor.is_calibrated());
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_mock_calibration_target_override() {
+        let mut sensor = MockFearSensor::step_pattern().with_calibration_target(3);
+        assert_eq!(calibration_trajectory(&mut sensor, &FearConfig::default()).await.len(), 3);
+
+        // Re-initializing restarts calibration, still with the fixed count
+        let trajectory = calibration_trajectory(&mut sensor, &FearConfig::default()).await;
+        assert_eq!(trajectory, [1.0 / 3.0, 2.0 / 3.0, 1.0]);
+        assert_eq!(MockFearSensor::constant_pattern(0.5).with_calibration_target(0).calibration_target(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_mock_stream_calibrates_after_config_samples() {
+        let config = FearConfig {
+            calibration_duration: Duration::from_millis(100),
+            camera: spectremesh_core::CameraConfig { fps: 40, ..Default::default() },
+            ..FearConfig::default()
+        };
+        let mut sensor = MockFearSensor::constant_pattern(0.2);
+        sensor.initialize(&config).await.unwrap();
+        let scores = sensor.start().await.unwrap();
+
+        let mut received = 0;
+        loop {
+            let score = tokio::time::timeout(Duration::from_secs(1), scores.recv()).await.unwrap().unwrap();
+            received += 1;
+            if score.calibrated {
+                break;
+            }
+        }
+        assert_eq!(received, config.calibration_samples());
+        assert_eq!(sensor.calibration_progress(), 1.0);
+    }
+
     #[test]
     fn test_yunet_fear_sensor_creation() {
         let sensor = YuNetFearSensor::new();
@@ -0,0 +1,302 @@
+//! Logit conditioning ahead of calibration
+//!
+//! Harsh lighting occasionally makes the emotion model produce huge logits
+//! (|logit| > 20) for a few frames. They are finite, so the NaN guards let
+//! them through, and a single burst drags the EMA baseline far enough that
+//! the sigmoid saturates for minutes. [`LogitConditioner`] tames them in
+//! three optional steps:
+//!
+//! 1. clamp raw logits to a symmetric bound (on by default, at a generous
+//!    [`DEFAULT_LOGIT_CLAMP`]);
+//! 2. divide them by a temperature before fear extraction (off at 1.0);
+//! 3. winsorize the sample fed to the calibrator at baseline ± k·std (off by
+//!    default). The frame still reports the spike; only the baseline is
+//!    protected from it.
+//!
+//! Steps that changed a frame are recorded in its [`Conditioning`] flags.
+
+use crate::calibrator::AdaptiveCalibrator;
+use serde::{Deserialize, Serialize};
+use spectremesh_core::EmotionLogits;
+
+pub use spectremesh_core::types::Conditioning;
+
+/// Default symmetric bound on raw logits, well above anything but a spike
+pub const DEFAULT_LOGIT_CLAMP: f32 = 50.0;
+
+/// Logit conditioning configuration
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ConditioningConfig {
+    /// Raw logits are clamped to [-clamp, clamp]; `None` disables clamping
+    pub clamp: Option<f32>,
+    /// Logits are divided by this before fear extraction; 1.0 disables scaling
+    pub temperature: f32,
+    /// Calibration samples are capped at baseline mean ± k·std; `None` disables
+    pub winsorize_k: Option<f32>,
+}
+
+impl Default for ConditioningConfig {
+    fn default() -> Self {
+        Self {
+            clamp: Some(DEFAULT_LOGIT_CLAMP),
+            temperature: 1.0,
+            winsorize_k: None,
+        }
+    }
+}
+
+impl ConditioningConfig {
+    /// Pass logits through untouched
+    pub fn disabled() -> Self {
+        Self {
+            clamp: None,
+            temperature: 1.0,
+            winsorize_k: None,
+        }
+    }
+
+    /// Set the clamp bound
+    pub fn with_clamp(mut self, clamp: Option<f32>) -> Self {
+        self.clamp = clamp;
+        self
+    }
+
+    /// Set the temperature
+    pub fn with_temperature(mut self, temperature: f32) -> Self {
+        self.temperature = temperature;
+        self
+    }
+
+    /// Winsorize calibration samples at `k` standard deviations
+    pub fn with_winsorization(mut self, k: f32) -> Self {
+        self.winsorize_k = Some(k);
+        self
+    }
+
+    /// Check that bounds and temperature are positive and finite
+    pub fn validate(&self) -> Result<(), String> {
+        let positive = |value: f32| value.is_finite() && value > 0.0;
+        if self.clamp.is_some_and(|clamp| !positive(clamp)) {
+            return Err("Logit clamp bound must be positive".to_string());
+        }
+        if !positive(self.temperature) {
+            return Err("Logit temperature must be positive".to_string());
+        }
+        if self.winsorize_k.is_some_and(|k| !positive(k)) {
+            return Err("Winsorization k must be positive".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// Logits after conditioning, in the layout they came in
+#[derive(Debug, Clone, PartialEq)]
+pub struct Conditioned {
+    /// Clamped and temperature-scaled logits, used for the fear score
+    pub logits: EmotionLogits,
+    /// `logits` winsorized against the baseline, fed to the calibrator
+    pub sample: EmotionLogits,
+    pub applied: Conditioning,
+}
+
+/// Applies [`ConditioningConfig`] to emotion logits
+#[derive(Debug, Clone, Default)]
+pub struct LogitConditioner {
+    config: ConditioningConfig,
+}
+
+impl LogitConditioner {
+    pub fn new(config: ConditioningConfig) -> Self {
+        Self { config }
+    }
+
+    pub fn config(&self) -> &ConditioningConfig {
+        &self.config
+    }
+
+    /// Clamp and temperature-scale raw logits
+    ///
+    /// Non-finite logits are left alone so the calibrator still skips them.
+    pub fn condition(&self, raw: &EmotionLogits) -> (EmotionLogits, Conditioning) {
+        let mut logits = raw.clone();
+        let mut applied = Conditioning::default();
+
+        if let Some(bound) = self.config.clamp {
+            for logit in logits.iter_mut().filter(|logit| logit.is_finite()) {
+                if logit.abs() > bound {
+                    *logit = logit.clamp(-bound, bound);
+                    applied.clamped = true;
+                }
+            }
+        }
+
+        if self.config.temperature != 1.0 {
+            for logit in logits.iter_mut() {
+                *logit /= self.config.temperature;
+            }
+            applied.temperature_scaled = true;
+        }
+
+        (logits, applied)
+    }
+
+    /// Condition raw logits and derive the calibration sample from `calibrator`'s baseline
+    pub fn apply(&self, raw: &EmotionLogits, calibrator: &AdaptiveCalibrator) -> Conditioned {
+        let (logits, mut applied) = self.condition(raw);
+        let sample = match self.config.winsorize_k {
+            Some(k) => {
+                let (sample, winsorized) = calibrator.winsorize(&logits, k);
+                applied.winsorized = winsorized;
+                sample
+            }
+            None => logits.clone(),
+        };
+        Conditioned { logits, sample, applied }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::calibrator::EMOTION_CHANNELS;
+    use spectremesh_core::scoring::FEAR_INDEX;
+    use std::time::Duration;
+
+    const SPIKE: f32 = 40.0;
+    const SPIKE_FRAMES: usize = 5;
+
+    /// Fear logit alternating around 1.0 with deviation 0.5
+    fn resting(step: usize) -> EmotionLogits {
+        let mut logits = [0.0; EMOTION_CHANNELS];
+        logits[FEAR_INDEX] = if step.is_multiple_of(2) { 1.5 } else { 0.5 };
+        logits.into()
+    }
+
+    fn spike() -> EmotionLogits {
+        let mut logits = [0.0; EMOTION_CHANNELS];
+        logits[FEAR_INDEX] = SPIKE;
+        logits.into()
+    }
+
+    /// Calibrate on resting frames, inject a spike and return the fear
+    /// baseline (mean, std) after every post-spike frame
+    fn trajectory(conditioner: &LogitConditioner) -> (Vec<(f32, f32)>, Vec<Conditioning>) {
+        let mut calibrator = AdaptiveCalibrator::new(Duration::ZERO, 0.05);
+        let feed = |calibrator: &mut AdaptiveCalibrator, raw: EmotionLogits| {
+            let conditioned = conditioner.apply(&raw, calibrator);
+            calibrator.add_logits(&conditioned.sample).unwrap();
+            conditioned.applied
+        };
+
+        for step in 0..200 {
+            feed(&mut calibrator, resting(step));
+        }
+        assert!(calibrator.is_calibrated());
+
+        let applied = (0..SPIKE_FRAMES).map(|_| feed(&mut calibrator, spike())).collect();
+        let baselines = (0..300)
+            .map(|step| {
+                feed(&mut calibrator, resting(step));
+                let baseline = calibrator.baseline_stats();
+                (baseline.mean, baseline.std_dev)
+            })
+            .collect();
+        (baselines, applied)
+    }
+
+    /// Frames until the baseline is back near mean 1.0 and within 1.5× its resting std of 0.5
+    fn recovery_frames(baselines: &[(f32, f32)]) -> usize {
+        baselines
+            .iter()
+            .position(|&(mean, std_dev)| (mean - 1.0).abs() < 0.1 && std_dev < 0.75)
+            .expect("baseline never recovered")
+    }
+
+    #[test]
+    fn test_winsorization_shortens_spike_recovery() {
+        let (raw, raw_applied) = trajectory(&LogitConditioner::new(ConditioningConfig::disabled()));
+        let (conditioned, applied) =
+            trajectory(&LogitConditioner::new(ConditioningConfig::disabled().with_winsorization(3.0)));
+
+        assert!(raw_applied.iter().all(|applied| !applied.any()));
+        assert!(applied.iter().all(|applied| applied.winsorized && !applied.clamped));
+
+        // The raw baseline is dragged toward the spike; the winsorized one barely moves
+        assert!(raw[0].0 > 5.0, "raw mean {}", raw[0].0);
+        assert!(conditioned[0].0 < 2.0, "conditioned mean {}", conditioned[0].0);
+
+        let raw_recovery = recovery_frames(&raw);
+        let conditioned_recovery = recovery_frames(&conditioned);
+        assert!(conditioned_recovery <= 40, "conditioned recovery took {} frames", conditioned_recovery);
+        assert!(raw_recovery >= 100, "raw recovery took only {} frames", raw_recovery);
+    }
+
+    #[test]
+    fn test_default_conditioning_preserves_raw_behavior() {
+        // A spike below the default clamp passes through exactly as before
+        let (raw, _) = trajectory(&LogitConditioner::new(ConditioningConfig::disabled()));
+        let (defaults, applied) = trajectory(&LogitConditioner::default());
+        assert!(applied.iter().all(|applied| !applied.any()));
+        assert_eq!(raw, defaults);
+    }
+
+    #[test]
+    fn test_clamp_and_temperature() {
+        let conditioner = LogitConditioner::new(
+            ConditioningConfig::default().with_clamp(Some(10.0)).with_temperature(2.0),
+        );
+        let raw = [-25.0, 4.0, 12.0, f32::NAN, f32::INFINITY, 0.0, -1.0];
+        let (logits, applied) = conditioner.condition(&raw.into());
+
+        assert_eq!(logits[..3], [-5.0, 2.0, 5.0]);
+        assert!(logits[3].is_nan());
+        assert_eq!(logits[4], f32::INFINITY);
+        assert_eq!(logits[5..], [0.0, -0.5]);
+        assert!(applied.clamped && applied.temperature_scaled && !applied.winsorized);
+
+        let (_, applied) = LogitConditioner::default().condition(&[1.0; EMOTION_CHANNELS].into());
+        assert!(!applied.any());
+    }
+
+    #[test]
+    fn test_winsorization_waits_for_calibration() {
+        let conditioner = LogitConditioner::new(ConditioningConfig::default().with_winsorization(3.0));
+        let calibrator = AdaptiveCalibrator::new(Duration::from_secs(30), 0.05);
+        let conditioned = conditioner.apply(&spike(), &calibrator);
+        assert_eq!(conditioned.sample, conditioned.logits);
+        assert!(!conditioned.applied.winsorized);
+    }
+
+    #[test]
+    fn test_drift_is_measured_on_conditioned_samples() {
+        let drift_after_spike = |config: ConditioningConfig| {
+            let conditioner = LogitConditioner::new(config);
+            let mut calibrator = AdaptiveCalibrator::new(Duration::ZERO, 0.05);
+            for step in 0..200 {
+                let conditioned = conditioner.apply(&resting(step), &calibrator);
+                calibrator.add_logits(&conditioned.sample).unwrap();
+            }
+            calibrator.calculate_drift();
+            for _ in 0..SPIKE_FRAMES {
+                let conditioned = conditioner.apply(&spike(), &calibrator);
+                calibrator.add_logits(&conditioned.sample).unwrap();
+            }
+            calibrator.calculate_drift()
+        };
+
+        let raw = drift_after_spike(ConditioningConfig::disabled());
+        let winsorized = drift_after_spike(ConditioningConfig::disabled().with_winsorization(3.0));
+        assert!(raw > 5.0, "raw drift {}", raw);
+        assert!(winsorized < 1.0, "winsorized drift {}", winsorized);
+    }
+
+    #[test]
+    fn test_validate() {
+        assert!(ConditioningConfig::default().validate().is_ok());
+        assert!(ConditioningConfig::disabled().with_winsorization(2.5).validate().is_ok());
+        assert!(ConditioningConfig::default().with_temperature(0.0).validate().is_err());
+        assert!(ConditioningConfig::default().with_clamp(Some(-1.0)).validate().is_err());
+        assert!(ConditioningConfig::default().with_winsorization(f32::NAN).validate().is_err());
+    }
+}
@@ -1,40 +1,346 @@
 //! Configuration management for the spectre sensor
 
+use opencv::core::Size;
 use serde::{Deserialize, Serialize};
 use std::env;
+use std::path::PathBuf;
+use std::time::Duration;
+use crate::bug_report::BugReportConfig;
+use crate::calibrator::{FearIndexWeights, DEFAULT_MIN_SAMPLES};
+use crate::camera_format::parse_resolution;
+use crate::conditioning::ConditioningConfig;
+use crate::degradation::DegradationConfig;
+use crate::emotion_input::InputShape;
+use crate::metrics_history::MetricsHistoryConfig;
+use crate::face_backend::FaceDetectorKind;
+use crate::face_tracker::FaceTrackerConfig;
+use crate::heartbeat::HeartbeatConfig;
+use crate::logging::LoggingConfig;
+use crate::backpressure::BackpressurePolicy;
+use crate::mirror::MirrorInput;
+use crate::mock_patterns::MockPattern;
+use crate::model_info::ModelInfo;
+use crate::model_provider::ModelProvider;
+use crate::normalization::InputNormalization;
+use crate::power::PowerConfig;
+use crate::quality::QualityConfig;
+use crate::reconnect::ReconnectConfig;
+use crate::resume::ResumeConfig;
+use crate::retention::RetentionConfig;
+use crate::startle::StartleConfig;
+use crate::yunet::YuNetParams;
+use spectremesh_core::{Emotion, EmotionLayout};
 
 /// Sensor configuration with environment variable overrides
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SensorConfig {
-    /// Path to emotion model (can be overridden with --model-path)
-    pub emotion_model_path: Option<String>,
+    /// Emotion model file or first-run download, `assets/models/face_emotion.onnx`
+    /// when unset (overridable with --model-path, or SPECTRE_EMOTION_MODEL_URL
+    /// and SPECTRE_EMOTION_MODEL_SHA256)
+    #[serde(default)]
+    pub emotion_model: Option<ModelProvider>,
+    /// Name, license and hash of the model `emotion_model` provides
+    #[serde(default)]
+    pub emotion_model_info: Option<ModelInfo>,
+    /// Pixel convention the emotion model expects (overridable with
+    /// SPECTRE_INPUT_NORMALIZATION)
+    #[serde(default)]
+    pub input_normalization: InputNormalization,
+    /// How many logits the emotion model emits and which one is fear; a
+    /// model emitting any other count fails inference rather than being cut
+    /// down to size
+    #[serde(default)]
+    pub emotion_layout: EmotionLayout,
+    /// Emotion model input shape for models with dynamic dimensions; fixed
+    /// dimensions are read from the model (overridable with
+    /// SPECTRE_EMOTION_INPUT, e.g. `64x64x3:nhwc`)
+    #[serde(default)]
+    pub emotion_input_override: Option<InputShape>,
+    /// Emotion model output the logits are read from, the model's first
+    /// when unset (overridable with SPECTRE_EMOTION_OUTPUT)
+    #[serde(default)]
+    pub emotion_output: Option<String>,
+    /// Directory for ONNX Runtime optimized models so later launches skip
+    /// graph optimization (overridable with SPECTRE_MODEL_CACHE)
+    #[serde(default)]
+    pub optimized_model_cache: Option<PathBuf>,
     /// Number of ONNX runtime threads (overridable with SPECTRE_THREADS)
     pub onnx_threads: usize,
-    /// Whether to freeze calibration after initial period
+    /// Face detection backend (overridable with SPECTRE_FACE_DETECTOR)
+    #[serde(default)]
+    pub face_detector: FaceDetectorKind,
+    /// Minimum face detection confidence, in (0, 1); lower finds faces in dim
+    /// light at the cost of false ones (overridable with SPECTRE_FACE_CONFIDENCE)
+    #[serde(default = "default_face_confidence_threshold")]
+    pub face_confidence_threshold: f32,
+    /// Overlap above which the weaker of two face boxes is dropped, in (0, 1)
+    /// (overridable with SPECTRE_FACE_NMS)
+    #[serde(default = "default_face_nms_threshold")]
+    pub face_nms_threshold: f32,
+    /// Side of the square the ONNX Runtime face detector resizes frames to, a
+    /// multiple of 32; smaller is faster but misses distant faces
+    /// (overridable with SPECTRE_YUNET_INPUT_SIZE)
+    #[serde(default = "default_yunet_input_size")]
+    pub yunet_input_size: u32,
+    /// Which of several faces is scored, and how readily it changes
+    /// (policy overridable with SPECTRE_PRIMARY_FACE)
+    #[serde(default)]
+    pub face_tracking: FaceTrackerConfig,
+    /// Whether to lock the baseline once the initial calibration completes
+    /// (overridable with SPECTRE_FREEZE_CALIBRATION)
     pub freeze_calibration: bool,
+    /// How long the initial calibration collects a baseline for
+    #[serde(default = "default_calibration_period", with = "spectremesh_core::duration")]
+    pub calibration_period: Duration,
+    /// Samples the initial calibration needs however short its period
+    /// (overridable with SPECTRE_CALIBRATION_MIN_SAMPLES)
+    #[serde(default = "default_calibration_min_samples")]
+    pub calibration_min_samples: usize,
+    /// Track baselines for every emotion channel, not just fear
+    #[serde(default)]
+    pub per_channel_calibration: bool,
+    /// Track a baseline for one more emotion channel besides fear, e.g.
+    /// surprise (overridable with SPECTRE_SECONDARY_EMOTION, an emotion name
+    /// or a channel index)
+    #[serde(default)]
+    pub secondary_emotion_channel: Option<usize>,
+    /// Weights combining normalized emotion channels into the fear score,
+    /// one per channel; the fear channel alone when unset (overridable with
+    /// SPECTRE_FEAR_INDEX_WEIGHTS, e.g. `0,0,1,-0.5,0,0,0`). Channels other
+    /// than fear and the secondary emotion need `per_channel_calibration`
+    #[serde(default)]
+    pub fear_index_weights: Option<FearIndexWeights>,
+    /// File the baseline is saved to while running and restored from at
+    /// startup, so restarts skip the initial calibration
+    /// (overridable with SPECTRE_CALIBRATION_CACHE)
+    #[serde(default)]
+    pub calibration_cache_path: Option<PathBuf>,
+    /// Oldest saved baseline still restored at startup
+    #[serde(default = "default_calibration_cache_max_age", with = "spectremesh_core::duration")]
+    pub calibration_cache_max_age: Duration,
+    /// Logit clamping, temperature and winsorization ahead of calibration
+    /// (overridable with SPECTRE_LOGIT_CLAMP, SPECTRE_LOGIT_TEMPERATURE and
+    /// SPECTRE_WINSORIZE_K)
+    #[serde(default)]
+    pub conditioning: ConditioningConfig,
+    /// Jump scare detection window, gain and refractory period
+    #[serde(default)]
+    pub startle: StartleConfig,
+    /// Emotion inference failure thresholds
+    #[serde(default)]
+    pub degradation: DegradationConfig,
+    /// Longest face detection or emotion inference may take before its frame
+    /// is abandoned (overridable with SPECTRE_INFERENCE_TIMEOUT)
+    #[serde(default = "default_inference_timeout", with = "spectremesh_core::duration")]
+    pub inference_timeout: Duration,
+    /// Run on synthetic frames playing this fear pattern instead of the
+    /// camera and models, for headless runs and CI
+    #[serde(default)]
+    pub mock: Option<MockPattern>,
+    /// Time the synthetic emotion model spends on each face, to exercise
+    /// the pipeline against slow inference
+    #[serde(default, with = "spectremesh_core::duration")]
+    pub mock_inference_latency: Duration,
+    /// Time the synthetic face takes to sweep across the frame and back;
+    /// zero keeps it still in the middle
+    #[serde(default, with = "spectremesh_core::duration")]
+    pub mock_face_period: Duration,
     /// Camera device ID
     pub camera_id: u32,
+    /// Resolution requested from the camera, which may settle on another;
+    /// the driver's default when unset (overridable with
+    /// SPECTRE_CAMERA_RESOLUTION, e.g. `1280x720`)
+    #[serde(default)]
+    pub camera_resolution: Option<(u32, u32)>,
+    /// Flip frames horizontally right after capture (overridable with SPECTRE_MIRROR_INPUT)
+    #[serde(default)]
+    pub mirror_input: MirrorInput,
     /// Target FPS
     pub target_fps: f32,
+    /// Run emotion inference on every nth frame, reusing its logits in
+    /// between (overridable with SPECTRE_EMOTION_INTERVAL)
+    #[serde(default = "default_emotion_interval")]
+    pub emotion_interval: u32,
+    /// Run the face detector on every nth frame, carrying the tracked face
+    /// over in between (overridable with SPECTRE_DETECTION_INTERVAL)
+    #[serde(default = "default_detection_interval")]
+    pub detection_interval: u32,
+    /// Lower the frame rate on battery or under thermal pressure
+    #[serde(default)]
+    pub power: PowerConfig,
+    /// Step down to a smaller face detector input, a lower frame rate and
+    /// fewer emotion inferences while inference cannot keep up, and back up
+    /// once it can (overridable with SPECTRE_ADAPTIVE_QUALITY)
+    #[serde(default = "default_adaptive_quality")]
+    pub adaptive_quality: bool,
+    /// Bounds and timing of the adaptive quality ladder
+    #[serde(default)]
+    pub quality: QualityConfig,
     /// Channel buffer size for back-pressure
     pub channel_buffer_size: usize,
+    /// What a full frame channel does with the next frame (overridable with
+    /// SPECTRE_BACKPRESSURE)
+    #[serde(default)]
+    pub backpressure: BackpressurePolicy,
     /// Metrics server port
     pub metrics_port: u16,
-    /// gRPC server socket path
+    /// Sampling and retention of the in-memory metrics history
+    #[serde(default)]
+    pub metrics_history: MetricsHistoryConfig,
+    /// Liveness heartbeat for show control, and the staleness threshold of
+    /// `/health`
+    #[serde(default)]
+    pub heartbeat: HeartbeatConfig,
+    /// Console log filter and the in-memory ring behind `GetLogs`
+    #[serde(default)]
+    pub logging: LoggingConfig,
+    /// gRPC server address: Unix socket path, `\\.\pipe\<name>` or `host:port`
     pub grpc_socket_path: String,
+    /// Browser origins allowed to call the gRPC server over grpc-web, `*` for
+    /// any; empty serves plain gRPC only (overridable with
+    /// SPECTRE_GRPC_WEB_ORIGINS, comma-separated)
+    #[serde(default)]
+    pub grpc_web_origins: Vec<String>,
+    /// Start the sensor with the daemon rather than on the first event
+    /// stream (overridable with SPECTRE_EAGER_START)
+    #[serde(default)]
+    pub eager_start: bool,
+    /// Stop the sensor once no event stream has been open for this long;
+    /// the next stream starts it again. Zero keeps it running (overridable
+    /// with SPECTRE_IDLE_SHUTDOWN)
+    #[serde(default, with = "spectremesh_core::duration")]
+    pub idle_shutdown: Duration,
+    /// System sleep/resume handling
+    #[serde(default)]
+    pub resume: ResumeConfig,
+    /// Camera unplug detection and reconnection
+    #[serde(default)]
+    pub reconnect: ReconnectConfig,
+    /// Privacy retention periods and directories
+    #[serde(default)]
+    pub retention: RetentionConfig,
+    /// Never keep face imagery, not even in memory (overridable with SPECTRE_PRIVACY_MODE)
+    #[serde(default = "default_privacy_mode")]
+    pub privacy_mode: bool,
+    /// Report the face box and center on frames, privacy mode or not: it is a
+    /// box, not imagery (overridable with SPECTRE_SHARE_FACE_POSITION)
+    #[serde(default = "default_share_face_position")]
+    pub share_face_position: bool,
+    /// Recent history kept for bug report bundles
+    #[serde(default)]
+    pub bug_report: BugReportConfig,
+    /// File every published frame is recorded to, for replay with
+    /// `ReplayFearSensor`; truncated on each start (overridable with
+    /// SPECTRE_RECORD)
+    #[serde(default)]
+    pub record_path: Option<PathBuf>,
+}
+
+fn default_face_confidence_threshold() -> f32 {
+    YuNetParams::default().confidence_threshold
+}
+
+fn default_face_nms_threshold() -> f32 {
+    YuNetParams::default().nms_threshold
+}
+
+fn default_yunet_input_size() -> u32 {
+    YuNetParams::default().input_size.width as u32
+}
+
+fn default_calibration_period() -> Duration {
+    Duration::from_secs(30)
+}
+
+fn default_calibration_min_samples() -> usize {
+    DEFAULT_MIN_SAMPLES
+}
+
+fn default_calibration_cache_max_age() -> Duration {
+    Duration::from_secs(24 * 60 * 60)
+}
+
+fn default_inference_timeout() -> Duration {
+    Duration::from_millis(100)
+}
+
+fn default_emotion_interval() -> u32 {
+    1
+}
+
+fn default_detection_interval() -> u32 {
+    5
+}
+
+fn default_adaptive_quality() -> bool {
+    true
+}
+
+fn default_privacy_mode() -> bool {
+    true
+}
+
+fn default_share_face_position() -> bool {
+    true
 }
 
 impl Default for SensorConfig {
     fn default() -> Self {
         Self {
-            emotion_model_path: None, // Use embedded model by default
+            emotion_model: None, // Use embedded model by default
+            emotion_model_info: None,
+            input_normalization: InputNormalization::default(),
+            emotion_layout: EmotionLayout::default(),
+            emotion_input_override: None,
+            emotion_output: None,
+            optimized_model_cache: None,
             onnx_threads: Self::get_thread_count(),
+            face_detector: FaceDetectorKind::Auto,
+            face_confidence_threshold: default_face_confidence_threshold(),
+            face_nms_threshold: default_face_nms_threshold(),
+            yunet_input_size: default_yunet_input_size(),
+            face_tracking: FaceTrackerConfig::default(),
             freeze_calibration: false,
+            calibration_period: default_calibration_period(),
+            calibration_min_samples: default_calibration_min_samples(),
+            per_channel_calibration: false,
+            secondary_emotion_channel: None,
+            fear_index_weights: None,
+            calibration_cache_path: None,
+            calibration_cache_max_age: default_calibration_cache_max_age(),
+            conditioning: ConditioningConfig::default(),
+            startle: StartleConfig::default(),
+            degradation: DegradationConfig::default(),
+            inference_timeout: default_inference_timeout(),
+            mock: None,
+            mock_inference_latency: Duration::ZERO,
+            mock_face_period: Duration::ZERO,
             camera_id: 0,
+            camera_resolution: None,
+            mirror_input: MirrorInput::Auto,
             target_fps: 30.0,
+            emotion_interval: default_emotion_interval(),
+            detection_interval: default_detection_interval(),
+            power: PowerConfig::default(),
+            adaptive_quality: default_adaptive_quality(),
+            quality: QualityConfig::default(),
             channel_buffer_size: 2,
+            backpressure: BackpressurePolicy::default(),
             metrics_port: 9090,
+            metrics_history: MetricsHistoryConfig::default(),
+            heartbeat: HeartbeatConfig::default(),
+            logging: LoggingConfig::default(),
             grpc_socket_path: Self::default_socket_path(),
+            grpc_web_origins: Vec::new(),
+            eager_start: false,
+            idle_shutdown: Duration::ZERO,
+            resume: ResumeConfig::default(),
+            reconnect: ReconnectConfig::default(),
+            retention: RetentionConfig::default(),
+            privacy_mode: default_privacy_mode(),
+            share_face_position: default_share_face_position(),
+            bug_report: BugReportConfig::default(),
+            record_path: None,
         }
     }
 }
@@ -48,30 +354,234 @@ impl SensorConfig {
         config.onnx_threads = Self::get_thread_count();
         
         // Override other settings from environment variables
+        if let Ok(detector) = env::var("SPECTRE_FACE_DETECTOR") {
+            match detector.parse() {
+                Ok(kind) => config.face_detector = kind,
+                Err(e) => tracing::warn!("{}, using {:?}", e, config.face_detector),
+            }
+        }
+        
+        if let Ok(normalization) = env::var("SPECTRE_INPUT_NORMALIZATION") {
+            match normalization.parse() {
+                Ok(normalization) => config.input_normalization = normalization,
+                Err(e) => tracing::warn!("{}, using {}", e, config.input_normalization),
+            }
+        }
+        
+        if let Ok(shape) = env::var("SPECTRE_EMOTION_INPUT") {
+            match shape.parse() {
+                Ok(shape) => config.emotion_input_override = Some(shape),
+                Err(e) => tracing::warn!("{}, reading the shape from the model", e),
+            }
+        }
+        
+        if let Ok(output) = env::var("SPECTRE_EMOTION_OUTPUT") {
+            config.emotion_output = Some(output).filter(|output| !output.is_empty());
+        }
+        
+        if let Ok(confidence) = env::var("SPECTRE_FACE_CONFIDENCE") {
+            config.face_confidence_threshold = confidence.parse().unwrap_or_else(|_| default_face_confidence_threshold());
+        }
+        
+        if let Ok(nms) = env::var("SPECTRE_FACE_NMS") {
+            config.face_nms_threshold = nms.parse().unwrap_or_else(|_| default_face_nms_threshold());
+        }
+        
+        if let Ok(size) = env::var("SPECTRE_YUNET_INPUT_SIZE") {
+            config.yunet_input_size = size.parse().unwrap_or_else(|_| default_yunet_input_size());
+        }
+        
+        if let Ok(policy) = env::var("SPECTRE_PRIMARY_FACE") {
+            match policy.parse() {
+                Ok(policy) => config.face_tracking.policy = policy,
+                Err(e) => tracing::warn!("{}, using {}", e, config.face_tracking.policy),
+            }
+        }
+        
+        if let (Ok(url), Ok(sha256)) = (env::var("SPECTRE_EMOTION_MODEL_URL"), env::var("SPECTRE_EMOTION_MODEL_SHA256")) {
+            config.emotion_model = Some(ModelProvider::download(url, sha256));
+        }
+        
+        if let Ok(cache_dir) = env::var("SPECTRE_MODEL_CACHE") {
+            config.optimized_model_cache = (!cache_dir.is_empty()).then(|| PathBuf::from(cache_dir));
+        }
+        
         if let Ok(freeze) = env::var("SPECTRE_FREEZE_CALIBRATION") {
             config.freeze_calibration = freeze.parse().unwrap_or(false);
         }
         
+        if let Ok(min_samples) = env::var("SPECTRE_CALIBRATION_MIN_SAMPLES") {
+            match min_samples.parse() {
+                Ok(min_samples) => config.calibration_min_samples = min_samples,
+                Err(e) => tracing::warn!(
+                    "Invalid SPECTRE_CALIBRATION_MIN_SAMPLES: {}, using {}",
+                    e,
+                    config.calibration_min_samples
+                ),
+            }
+        }
+        
+        if let Ok(per_channel) = env::var("SPECTRE_PER_CHANNEL_CALIBRATION") {
+            config.per_channel_calibration = per_channel.parse().unwrap_or(false);
+        }
+        
+        if let Ok(secondary) = env::var("SPECTRE_SECONDARY_EMOTION") {
+            // "off" tracks fear alone
+            match secondary.trim() {
+                "" | "off" => config.secondary_emotion_channel = None,
+                channel => match channel.parse().or_else(|_| channel.parse::<Emotion>().map(Emotion::index)) {
+                    Ok(channel) => config.secondary_emotion_channel = Some(channel),
+                    Err(e) => tracing::warn!("{}, tracking fear alone", e),
+                },
+            }
+        }
+        
+        if let Ok(weights) = env::var("SPECTRE_FEAR_INDEX_WEIGHTS") {
+            // "off" scores the fear channel alone
+            match weights.trim() {
+                "" | "off" => config.fear_index_weights = None,
+                weights => match weights.parse() {
+                    Ok(weights) => config.fear_index_weights = Some(weights),
+                    Err(e) => tracing::warn!("{}, scoring fear alone", e),
+                },
+            }
+        }
+        
+        if let Ok(cache) = env::var("SPECTRE_CALIBRATION_CACHE") {
+            config.calibration_cache_path = (!cache.is_empty()).then(|| PathBuf::from(cache));
+        }
+        
+        if let Ok(record) = env::var("SPECTRE_RECORD") {
+            config.record_path = (!record.is_empty()).then(|| PathBuf::from(record));
+        }
+        
+        if let Ok(clamp) = env::var("SPECTRE_LOGIT_CLAMP") {
+            // "off" disables clamping
+            config.conditioning.clamp = clamp.parse().ok();
+        }
+        
+        if let Ok(temperature) = env::var("SPECTRE_LOGIT_TEMPERATURE") {
+            config.conditioning.temperature = temperature.parse().unwrap_or(1.0);
+        }
+        
+        if let Ok(k) = env::var("SPECTRE_WINSORIZE_K") {
+            config.conditioning.winsorize_k = k.parse().ok();
+        }
+        
+        if let Ok(timeout) = env::var("SPECTRE_INFERENCE_TIMEOUT") {
+            match spectremesh_core::duration::parse(&timeout) {
+                Ok(timeout) => config.inference_timeout = timeout,
+                Err(e) => tracing::warn!("{}, using {:?}", e, config.inference_timeout),
+            }
+        }
+        
         if let Ok(camera_id) = env::var("SPECTRE_CAMERA_ID") {
             config.camera_id = camera_id.parse().unwrap_or(0);
         }
         
+        if let Ok(resolution) = env::var("SPECTRE_CAMERA_RESOLUTION") {
+            match parse_resolution(&resolution) {
+                Ok(resolution) => config.camera_resolution = Some(resolution),
+                Err(e) => tracing::warn!("{}, using the camera's default", e),
+            }
+        }
+        
+        if let Ok(mirror) = env::var("SPECTRE_MIRROR_INPUT") {
+            match mirror.parse() {
+                Ok(mirror) => config.mirror_input = mirror,
+                Err(e) => tracing::warn!("{}, using {}", e, config.mirror_input),
+            }
+        }
+        
         if let Ok(fps) = env::var("SPECTRE_TARGET_FPS") {
             config.target_fps = fps.parse().unwrap_or(30.0);
         }
         
+        if let Ok(interval) = env::var("SPECTRE_EMOTION_INTERVAL") {
+            config.emotion_interval = interval.parse().unwrap_or(1);
+        }
+        
+        if let Ok(interval) = env::var("SPECTRE_DETECTION_INTERVAL") {
+            config.detection_interval = interval.parse().unwrap_or_else(|_| default_detection_interval());
+        }
+        
+        if let Ok(governor) = env::var("SPECTRE_POWER_GOVERNOR") {
+            config.power.enabled = governor.parse().unwrap_or(false);
+        }
+        
+        if let Ok(adaptive) = env::var("SPECTRE_ADAPTIVE_QUALITY") {
+            config.adaptive_quality = adaptive.parse().unwrap_or(true);
+        }
+        
         if let Ok(buffer_size) = env::var("SPECTRE_BUFFER_SIZE") {
             config.channel_buffer_size = buffer_size.parse().unwrap_or(2);
         }
         
+        if let Ok(policy) = env::var("SPECTRE_BACKPRESSURE") {
+            match policy.parse() {
+                Ok(policy) => config.backpressure = policy,
+                Err(e) => tracing::warn!("{}, using {}", e, config.backpressure),
+            }
+        }
+        
         if let Ok(port) = env::var("SPECTRE_METRICS_PORT") {
             config.metrics_port = port.parse().unwrap_or(9090);
         }
         
+        if let Ok(target) = env::var("SPECTRE_HEARTBEAT") {
+            // "off" or empty disables the heartbeat
+            match target.as_str() {
+                "" | "off" => config.heartbeat.target = None,
+                target => match target.parse() {
+                    Ok(target) => config.heartbeat.target = Some(target),
+                    Err(e) => tracing::warn!("{}, heartbeat disabled", e),
+                },
+            }
+        }
+        
+        if let Ok(filter) = env::var("SPECTRE_LOG") {
+            config.logging.filter = filter;
+        }
+        
+        if let Ok(format) = env::var("SPECTRE_LOG_FORMAT") {
+            match format.parse() {
+                Ok(format) => config.logging.format = format,
+                Err(e) => tracing::warn!("{}, using {}", e, config.logging.format),
+            }
+        }
+        
         if let Ok(socket_path) = env::var("SPECTRE_GRPC_SOCKET") {
             config.grpc_socket_path = socket_path;
         }
         
+        if let Ok(origins) = env::var("SPECTRE_GRPC_WEB_ORIGINS") {
+            config.grpc_web_origins = origins
+                .split(',')
+                .map(str::trim)
+                .filter(|origin| !origin.is_empty())
+                .map(str::to_string)
+                .collect();
+        }
+        
+        if let Ok(eager) = env::var("SPECTRE_EAGER_START") {
+            config.eager_start = eager.parse().unwrap_or(false);
+        }
+        
+        if let Ok(idle) = env::var("SPECTRE_IDLE_SHUTDOWN") {
+            match spectremesh_core::duration::parse(&idle) {
+                Ok(idle) => config.idle_shutdown = idle,
+                Err(e) => tracing::warn!("{}, keeping the sensor running without streams", e),
+            }
+        }
+        
+        if let Ok(privacy_mode) = env::var("SPECTRE_PRIVACY_MODE") {
+            config.privacy_mode = privacy_mode.parse().unwrap_or(true);
+        }
+        
+        if let Ok(share) = env::var("SPECTRE_SHARE_FACE_POSITION") {
+            config.share_face_position = share.parse().unwrap_or(true);
+        }
+        
         config
     }
     
@@ -108,7 +618,49 @@ impl SensorConfig {
     
     /// Set emotion model path (for --model-path override)
     pub fn with_model_path(mut self, path: String) -> Self {
-        self.emotion_model_path = Some(path);
+        self.emotion_model = Some(ModelProvider::file(path));
+        self
+    }
+
+    /// Set where the emotion model comes from
+    pub fn with_emotion_model(mut self, provider: ModelProvider) -> Self {
+        self.emotion_model = Some(provider);
+        self
+    }
+
+    /// Describe the emotion model for attribution
+    pub fn with_emotion_model_info(mut self, info: ModelInfo) -> Self {
+        self.emotion_model_info = Some(info);
+        self
+    }
+    
+    /// Set the emotion model input convention
+    pub fn with_input_normalization(mut self, normalization: InputNormalization) -> Self {
+        self.input_normalization = normalization;
+        self
+    }
+    
+    /// Set the emotion model's output layout
+    pub fn with_emotion_layout(mut self, layout: EmotionLayout) -> Self {
+        self.emotion_layout = layout;
+        self
+    }
+    
+    /// Set the emotion model input shape, for models with dynamic dimensions
+    pub fn with_emotion_input_override(mut self, shape: InputShape) -> Self {
+        self.emotion_input_override = Some(shape);
+        self
+    }
+    
+    /// Read the emotion logits from the output named `name`
+    pub fn with_emotion_output(mut self, name: impl Into<String>) -> Self {
+        self.emotion_output = Some(name.into());
+        self
+    }
+    
+    /// Persist optimized models under `dir`
+    pub fn with_optimized_model_cache(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.optimized_model_cache = Some(dir.into());
         self
     }
     
@@ -118,42 +670,240 @@ impl SensorConfig {
         self
     }
     
+    /// Set how long the initial calibration collects for
+    pub fn with_calibration_period(mut self, period: Duration) -> Self {
+        self.calibration_period = period;
+        self
+    }
+    
+    /// Require at least `min_samples` before the initial calibration completes
+    pub fn with_calibration_min_samples(mut self, min_samples: usize) -> Self {
+        self.calibration_min_samples = min_samples;
+        self
+    }
+    
+    /// Track a baseline for `channel` besides fear; `None` tracks fear alone
+    pub fn with_secondary_emotion_channel(mut self, channel: Option<usize>) -> Self {
+        self.secondary_emotion_channel = channel;
+        self
+    }
+    
+    /// Score frames with the fear index of `weights`; `None` scores the fear
+    /// channel alone
+    pub fn with_fear_index_weights(mut self, weights: Option<FearIndexWeights>) -> Self {
+        self.fear_index_weights = weights;
+        self
+    }
+    
+    /// Save the baseline to `path` and restore it on the next start
+    pub fn with_calibration_cache(mut self, path: impl Into<PathBuf>) -> Self {
+        self.calibration_cache_path = Some(path.into());
+        self
+    }
+    
+    /// Record every published frame to `path`
+    pub fn with_record_path(mut self, path: impl Into<PathBuf>) -> Self {
+        self.record_path = Some(path.into());
+        self
+    }
+    
+    /// Run on synthetic frames playing `pattern` instead of the camera and models
+    pub fn with_mock(mut self, pattern: MockPattern) -> Self {
+        self.mock = Some(pattern);
+        self
+    }
+
+    /// Abandon frames whose detection or emotion inference takes longer than `timeout`
+    pub fn with_inference_timeout(mut self, timeout: Duration) -> Self {
+        self.inference_timeout = timeout;
+        self
+    }
+
+    /// Make the synthetic emotion model take `latency` per face
+    pub fn with_mock_inference_latency(mut self, latency: Duration) -> Self {
+        self.mock_inference_latency = latency;
+        self
+    }
+
+    /// Sweep the synthetic face across the frame and back once per `period`
+    pub fn with_mock_face_period(mut self, period: Duration) -> Self {
+        self.mock_face_period = period;
+        self
+    }
+    
     /// Set camera ID
     pub fn with_camera_id(mut self, camera_id: u32) -> Self {
         self.camera_id = camera_id;
         self
     }
     
+    /// Request `width`x`height` from the camera
+    pub fn with_camera_resolution(mut self, width: u32, height: u32) -> Self {
+        self.camera_resolution = Some((width, height));
+        self
+    }
+    
+    /// Set whether captured frames are flipped horizontally
+    pub fn with_mirror_input(mut self, mirror_input: MirrorInput) -> Self {
+        self.mirror_input = mirror_input;
+        self
+    }
+    
     /// Set target FPS
     pub fn with_target_fps(mut self, fps: f32) -> Self {
         self.target_fps = fps.max(1.0).min(120.0); // Reasonable bounds
         self
     }
     
+    /// Run emotion inference on every `interval`th frame
+    pub fn with_emotion_interval(mut self, interval: u32) -> Self {
+        self.emotion_interval = interval.max(1);
+        self
+    }
+    
+    /// Run the face detector on every `interval`th frame
+    pub fn with_detection_interval(mut self, interval: u32) -> Self {
+        self.detection_interval = interval.max(1);
+        self
+    }
+    
+    /// Set the power governor policy
+    pub fn with_power(mut self, power: PowerConfig) -> Self {
+        self.power = power;
+        self
+    }
+    
+    /// Set whether quality steps down while inference cannot keep up
+    pub fn with_adaptive_quality(mut self, adaptive: bool) -> Self {
+        self.adaptive_quality = adaptive;
+        self
+    }
+    
+    /// Set the bounds and timing of the adaptive quality ladder
+    pub fn with_quality(mut self, quality: QualityConfig) -> Self {
+        self.quality = quality;
+        self
+    }
+    
     /// Set ONNX thread count
     pub fn with_onnx_threads(mut self, threads: usize) -> Self {
         self.onnx_threads = threads.max(1); // Ensure at least 1 thread
         self
     }
     
+    /// Set face detection backend
+    pub fn with_face_detector(mut self, kind: FaceDetectorKind) -> Self {
+        self.face_detector = kind;
+        self
+    }
+    
     /// Set channel buffer size
     pub fn with_buffer_size(mut self, size: usize) -> Self {
         self.channel_buffer_size = size.max(1); // Ensure at least 1
         self
     }
     
+    /// Set what a full frame channel does with the next frame
+    pub fn with_backpressure(mut self, policy: BackpressurePolicy) -> Self {
+        self.backpressure = policy;
+        self
+    }
+    
     /// Set metrics port
     pub fn with_metrics_port(mut self, port: u16) -> Self {
         self.metrics_port = port;
         self
     }
     
+    /// Set metrics history sampling and retention
+    pub fn with_metrics_history(mut self, metrics_history: MetricsHistoryConfig) -> Self {
+        self.metrics_history = metrics_history;
+        self
+    }
+    
+    /// Set the liveness heartbeat
+    pub fn with_heartbeat(mut self, heartbeat: HeartbeatConfig) -> Self {
+        self.heartbeat = heartbeat;
+        self
+    }
+    
+    /// Set the log filter and ring
+    pub fn with_logging(mut self, logging: LoggingConfig) -> Self {
+        self.logging = logging;
+        self
+    }
+    
     /// Set gRPC socket path
     pub fn with_grpc_socket(mut self, path: String) -> Self {
         self.grpc_socket_path = path;
         self
     }
     
+    /// Serve grpc-web to browser consoles from `origins` (`*` for any)
+    pub fn with_grpc_web_origins<S: Into<String>>(mut self, origins: impl IntoIterator<Item = S>) -> Self {
+        self.grpc_web_origins = origins.into_iter().map(Into::into).collect();
+        self
+    }
+    
+    /// Start the sensor with the daemon rather than on the first event stream
+    pub fn with_eager_start(mut self, eager: bool) -> Self {
+        self.eager_start = eager;
+        self
+    }
+    
+    /// Stop the sensor once no event stream has been open for `idle`; zero
+    /// keeps it running
+    pub fn with_idle_shutdown(mut self, idle: Duration) -> Self {
+        self.idle_shutdown = idle;
+        self
+    }
+    
+    /// Set logit conditioning
+    pub fn with_conditioning(mut self, conditioning: ConditioningConfig) -> Self {
+        self.conditioning = conditioning;
+        self
+    }
+    
+    /// Set privacy mode (false allows face crops in bug reports)
+    pub fn with_privacy_mode(mut self, privacy_mode: bool) -> Self {
+        self.privacy_mode = privacy_mode;
+        self
+    }
+    
+    /// Set whether frames carry the face box and center
+    pub fn with_share_face_position(mut self, share: bool) -> Self {
+        self.share_face_position = share;
+        self
+    }
+    
+    /// Set the minimum face detection confidence
+    pub fn with_face_confidence_threshold(mut self, threshold: f32) -> Self {
+        self.face_confidence_threshold = threshold;
+        self
+    }
+    
+    /// Set the overlap above which weaker face boxes are suppressed
+    pub fn with_face_nms_threshold(mut self, threshold: f32) -> Self {
+        self.face_nms_threshold = threshold;
+        self
+    }
+    
+    /// Set the square input size of the ONNX Runtime face detector
+    pub fn with_yunet_input_size(mut self, size: u32) -> Self {
+        self.yunet_input_size = size;
+        self
+    }
+    
+    /// Face detector thresholds and input size
+    pub fn yunet_params(&self) -> YuNetParams {
+        let size = self.yunet_input_size.min(i32::MAX as u32) as i32;
+        YuNetParams {
+            confidence_threshold: self.face_confidence_threshold,
+            nms_threshold: self.face_nms_threshold,
+            input_size: Size::new(size, size),
+        }
+    }
+    
     /// Validate configuration
     pub fn validate(&self) -> Result<(), String> {
         if self.onnx_threads == 0 {
@@ -164,6 +914,26 @@ impl SensorConfig {
             return Err("Target FPS must be positive".to_string());
         }
         
+        if self.emotion_interval == 0 {
+            return Err("Emotion interval must be at least 1".to_string());
+        }
+        
+        if self.detection_interval == 0 {
+            return Err("Face detection interval must be at least 1".to_string());
+        }
+        
+        if self.camera_resolution.is_some_and(|(width, height)| width == 0 || height == 0) {
+            return Err("Camera resolution must be positive".to_string());
+        }
+        
+        if self.inference_timeout.is_zero() {
+            return Err("Inference timeout must be positive".to_string());
+        }
+        
+        if self.calibration_min_samples == 0 {
+            return Err("Calibration minimum samples must be at least 1".to_string());
+        }
+        
         if self.channel_buffer_size == 0 {
             return Err("Channel buffer size must be at least 1".to_string());
         }
@@ -172,6 +942,76 @@ impl SensorConfig {
             return Err("gRPC socket path cannot be empty".to_string());
         }
         
+        if self.grpc_web_origins.iter().any(|origin| origin.trim().is_empty()) {
+            return Err("grpc-web origins cannot be empty".to_string());
+        }
+        
+        if self.calibration_cache_path.as_ref().is_some_and(|path| path.as_os_str().is_empty()) {
+            return Err("Calibration cache path cannot be empty".to_string());
+        }
+        
+        if self.record_path.as_ref().is_some_and(|path| path.as_os_str().is_empty()) {
+            return Err("Record path cannot be empty".to_string());
+        }
+        
+        if let Some(provider) = &self.emotion_model {
+            provider.validate()?;
+        }
+        self.yunet_params().validate()?;
+        self.face_tracking.validate()?;
+        self.emotion_layout.validate().map_err(|e| e.to_string())?;
+        if let Some(shape) = &self.emotion_input_override {
+            shape.validate()?;
+        }
+        if self.emotion_output.as_ref().is_some_and(|output| output.is_empty()) {
+            return Err("Emotion output name cannot be empty".to_string());
+        }
+        if let Some(channel) = self.secondary_emotion_channel.filter(|&channel| channel >= self.emotion_layout.channels) {
+            return Err(format!(
+                "Secondary emotion channel {} is outside the model's {} channels",
+                channel, self.emotion_layout.channels
+            ));
+        }
+        if let Some(weights) = &self.fear_index_weights {
+            if weights.0.len() != self.emotion_layout.channels {
+                return Err(format!(
+                    "{} fear index weights given for the model's {} channels",
+                    weights.0.len(),
+                    self.emotion_layout.channels
+                ));
+            }
+            if weights.0.iter().all(|&weight| weight == 0.0) {
+                return Err("Fear index weights cannot all be zero".to_string());
+            }
+            // An untracked channel would enter the index as a raw logit
+            let untracked = weights.0.iter().enumerate().position(|(channel, &weight)| {
+                weight != 0.0
+                    && !self.per_channel_calibration
+                    && channel != self.emotion_layout.fear_index
+                    && self.secondary_emotion_channel != Some(channel)
+            });
+            if let Some(channel) = untracked {
+                return Err(format!(
+                    "Fear index weight on channel {} needs per-channel calibration or that channel as the secondary emotion",
+                    channel
+                ));
+            }
+        }
+        if let Some(pattern) = &self.mock {
+            pattern.validate()?;
+        }
+        self.conditioning.validate()?;
+        self.startle.validate()?;
+        self.resume.validate()?;
+        self.reconnect.validate()?;
+        self.retention.validate()?;
+        self.bug_report.validate()?;
+        self.metrics_history.validate()?;
+        self.heartbeat.validate()?;
+        self.logging.validate()?;
+        self.power.validate()?;
+        self.quality.validate()?;
+        
         Ok(())
     }
 }
@@ -179,19 +1019,33 @@ impl SensorConfig {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::emotion_input::TensorLayout;
+    use crate::face_tracker::PrimaryFacePolicy;
+    use crate::heartbeat::HeartbeatTarget;
+    use crate::logging::LogFormat;
     use std::env;
+    use std::time::Duration;
 
     #[test]
     fn test_default_config() {
         let config = SensorConfig::default();
 
-        assert!(config.emotion_model_path.is_none());
+        assert!(config.emotion_model.is_none());
         assert!(config.onnx_threads > 0);
         assert!(!config.freeze_calibration);
+        assert_eq!(config.calibration_period, Duration::from_secs(30));
+        assert_eq!(config.calibration_min_samples, DEFAULT_MIN_SAMPLES);
+        assert!(config.mock.is_none());
         assert_eq!(config.camera_id, 0);
         assert_eq!(config.target_fps, 30.0);
         assert_eq!(config.channel_buffer_size, 2);
         assert_eq!(config.metrics_port, 9090);
+        assert!(config.privacy_mode);
+        assert!(config.share_face_position);
+        assert_eq!(config.heartbeat.target, None);
+        assert_eq!(config.yunet_params(), YuNetParams::default());
+        assert!(config.adaptive_quality);
+        assert_eq!(config.detection_interval, 5);
 
         // Test platform-specific socket paths
         #[cfg(target_os = "windows")]
@@ -213,6 +1067,8 @@ mod tests {
         
         // Valid config should pass
         assert!(config.validate().is_ok());
+        let quality = QualityConfig { headroom: 0.0, ..QualityConfig::default() };
+        assert!(config.clone().with_quality(quality).validate().is_err());
         
         // Invalid thread count
         config.onnx_threads = 0;
@@ -224,6 +1080,16 @@ mod tests {
         assert!(config.validate().is_err());
         config.target_fps = 30.0;
         
+        // Inference on no frame at all
+        config.emotion_interval = 0;
+        assert!(config.validate().is_err());
+        config.emotion_interval = 1;
+        
+        // Detection on no frame at all
+        config.detection_interval = 0;
+        assert!(config.validate().is_err());
+        config.detection_interval = 1;
+        
         // Invalid buffer size
         config.channel_buffer_size = 0;
         assert!(config.validate().is_err());
@@ -232,6 +1098,51 @@ mod tests {
         // Invalid socket path
         config.grpc_socket_path = String::new();
         assert!(config.validate().is_err());
+        config.grpc_socket_path = "/tmp/test.sock".to_string();
+        
+        // Blank grpc-web origin
+        config = config.with_grpc_web_origins(["https://console.example", " "]);
+        assert!(config.validate().is_err());
+        config = config.with_grpc_web_origins(["*"]);
+        assert!(config.validate().is_ok());
+        config.grpc_web_origins.clear();
+        
+        // Invalid heartbeat interval
+        config.heartbeat.interval = Duration::ZERO;
+        assert!(config.validate().is_err());
+        config.heartbeat.interval = Duration::from_secs(1);
+        
+        // Log filter that does not parse
+        config.logging.filter = "spectre_sensor=loud".to_string();
+        assert!(config.validate().is_err());
+        config.logging.filter = "info".to_string();
+        
+        // Zero durations where zero means nothing
+        config.startle.window = Duration::ZERO;
+        assert_eq!(config.validate(), Err("Startle window must be positive".to_string()));
+        config.startle = StartleConfig::default();
+        config.retention.sweep_interval = Duration::ZERO;
+        assert!(config.validate().is_err());
+        config.retention.sweep_interval = Duration::from_secs(60);
+        config.inference_timeout = Duration::ZERO;
+        assert!(config.validate().is_err());
+        config.inference_timeout = Duration::from_millis(100);
+        config.camera_resolution = Some((1280, 0));
+        assert!(config.validate().is_err());
+        config.camera_resolution = None;
+        
+        // A zero refractory period just disables it
+        config.startle.refractory = Duration::ZERO;
+        assert!(config.validate().is_ok());
+        
+        // Face detector thresholds outside (0, 1) and input sizes off the model's strides
+        for threshold in [0.0, 1.0, -0.2, 1.5, f32::NAN] {
+            assert!(config.clone().with_face_confidence_threshold(threshold).validate().is_err(), "{}", threshold);
+            assert!(config.clone().with_face_nms_threshold(threshold).validate().is_err(), "{}", threshold);
+        }
+        for size in [0, 16, 100] {
+            assert!(config.clone().with_yunet_input_size(size).validate().is_err(), "{}", size);
+        }
     }
 
     #[test]
@@ -241,50 +1152,252 @@ mod tests {
             .with_freeze_calibration(true)
             .with_camera_id(1)
             .with_target_fps(60.0)
+            .with_emotion_interval(2)
+            .with_detection_interval(3)
             .with_onnx_threads(4)
             .with_buffer_size(5)
+            .with_backpressure(BackpressurePolicy::Block)
             .with_metrics_port(8080)
-            .with_grpc_socket("/tmp/test.sock".to_string());
+            .with_grpc_socket("/tmp/test.sock".to_string())
+            .with_privacy_mode(false)
+            .with_share_face_position(false)
+            .with_optimized_model_cache("/tmp/spectre_models")
+            .with_input_normalization(InputNormalization::MinusOneToOne)
+            .with_emotion_layout(EmotionLayout::new(10, 4).unwrap())
+            .with_emotion_input_override("64x64x3:nhwc".parse().unwrap())
+            .with_emotion_output("logits")
+            .with_face_confidence_threshold(0.35)
+            .with_face_nms_threshold(0.4)
+            .with_yunet_input_size(320);
         
-        assert_eq!(config.emotion_model_path, Some("test_model.onnx".to_string()));
+        assert_eq!(config.emotion_model, Some(ModelProvider::file("test_model.onnx")));
         assert!(config.freeze_calibration);
         assert_eq!(config.camera_id, 1);
         assert_eq!(config.target_fps, 60.0);
+        assert_eq!(config.emotion_interval, 2);
+        assert_eq!(config.detection_interval, 3);
         assert_eq!(config.onnx_threads, 4);
         assert_eq!(config.channel_buffer_size, 5);
+        assert_eq!(config.backpressure, BackpressurePolicy::Block);
         assert_eq!(config.metrics_port, 8080);
         assert_eq!(config.grpc_socket_path, "/tmp/test.sock");
+        assert!(!config.privacy_mode);
+        assert!(!config.share_face_position);
+        assert_eq!(config.optimized_model_cache, Some(PathBuf::from("/tmp/spectre_models")));
+        assert_eq!(config.calibration_cache_path, Some(PathBuf::from("/tmp/spectre_baseline.json")));
+        assert_eq!(config.input_normalization, InputNormalization::MinusOneToOne);
+        assert_eq!(config.emotion_layout, EmotionLayout { channels: 10, fear_index: 4 });
+        assert_eq!(config.emotion_input_override.unwrap().tensor_shape(), [1, 64, 64, 3]);
+        assert_eq!(config.emotion_output.as_deref(), Some("logits"));
+        let params = config.yunet_params();
+        assert_eq!((params.confidence_threshold, params.nms_threshold), (0.35, 0.4));
+        assert_eq!(params.input_size, Size::new(320, 320));
+        assert!(config.validate().is_ok());
+
+        let config = SensorConfig::default().with_emotion_layout(EmotionLayout { channels: 4, fear_index: 4 });
+        assert!(config.validate().is_err());
+        let shape = InputShape { width: 64, height: 64, channels: 2, layout: TensorLayout::Nchw };
+        assert!(SensorConfig::default().with_emotion_input_override(shape).validate().is_err());
+        let config = SensorConfig::default().with_secondary_emotion_channel(Some(Emotion::Surprise.index()));
+        assert_eq!(config.secondary_emotion_channel, Some(5));
+        assert!(config.validate().is_ok());
+        assert!(config.with_secondary_emotion_channel(Some(7)).validate().is_err());
+        let calming = FearIndexWeights(vec![0.0, 0.0, 1.0, -0.5, 0.0, 0.0, 0.0]);
+        let config = SensorConfig::default().with_fear_index_weights(Some(calming.clone()));
+        // Channel 3 has no baseline of its own unless it is tracked
+        assert!(config.validate().is_err());
+        assert!(SensorConfig { per_channel_calibration: true, ..config.clone() }.validate().is_ok());
+        assert!(config.with_secondary_emotion_channel(Some(3)).validate().is_ok());
+        let fear_only = FearIndexWeights(vec![0.0, 0.0, 2.0, 0.0, 0.0, 0.0, 0.0]);
+        assert!(SensorConfig::default().with_fear_index_weights(Some(fear_only)).validate().is_ok());
+        let short = FearIndexWeights(vec![0.0, 0.0, 1.0]);
+        assert!(SensorConfig::default().with_fear_index_weights(Some(short)).validate().is_err());
+        let silent = FearIndexWeights(vec![0.0; 7]);
+        assert!(SensorConfig::default().with_fear_index_weights(Some(silent)).validate().is_err());
+        let config = SensorConfig::default().with_record_path("/tmp/spectre_session.jsonl");
+        assert_eq!(config.record_path, Some(PathBuf::from("/tmp/spectre_session.jsonl")));
+        assert!(config.eager_start);
+        assert_eq!(config.idle_shutdown, Duration::from_secs(300));
+        assert!(config.validate().is_ok());
+        assert!(config.with_record_path("").validate().is_err());
+
+        let config = SensorConfig::default()
+            .with_mock(MockPattern::Step)
+            .with_calibration_period(Duration::from_secs(2))
+            .with_calibration_min_samples(10);
+        assert_eq!(config.mock, Some(MockPattern::Step));
+        assert_eq!(config.calibration_period, Duration::from_secs(2));
+        assert_eq!(config.calibration_min_samples, 10);
+        assert!(config.validate().is_ok());
+        assert!(config.clone().with_calibration_min_samples(0).validate().is_err());
+        let config = config.with_mock(MockPattern::Sequence { samples: vec![] });
+        assert!(config.validate().is_err());
     }
 
     #[test]
     fn test_env_override() {
         // Set environment variables
         env::set_var("SPECTRE_THREADS", "8");
+        env::set_var("SPECTRE_FACE_DETECTOR", "opencv");
         env::set_var("SPECTRE_FREEZE_CALIBRATION", "true");
         env::set_var("SPECTRE_CAMERA_ID", "2");
+        env::set_var("SPECTRE_MIRROR_INPUT", "on");
         env::set_var("SPECTRE_TARGET_FPS", "60.0");
+        env::set_var("SPECTRE_EMOTION_INTERVAL", "3");
+        env::set_var("SPECTRE_DETECTION_INTERVAL", "2");
+        env::set_var("SPECTRE_POWER_GOVERNOR", "true");
+        env::set_var("SPECTRE_ADAPTIVE_QUALITY", "false");
         env::set_var("SPECTRE_BUFFER_SIZE", "4");
         env::set_var("SPECTRE_METRICS_PORT", "8080");
         env::set_var("SPECTRE_GRPC_SOCKET", "/tmp/test.sock");
+        env::set_var("SPECTRE_PRIVACY_MODE", "false");
+        env::set_var("SPECTRE_MODEL_CACHE", "/tmp/spectre_models");
+        env::set_var("SPECTRE_CALIBRATION_CACHE", "/tmp/spectre_baseline.json");
+        env::set_var("SPECTRE_INPUT_NORMALIZATION", "mean_std:0.5,0.25");
+        env::set_var("SPECTRE_LOGIT_CLAMP", "off");
+        env::set_var("SPECTRE_LOGIT_TEMPERATURE", "2.0");
+        env::set_var("SPECTRE_WINSORIZE_K", "3");
+        env::set_var("SPECTRE_HEARTBEAT", "file:/run/spectre/alive");
+        env::set_var("SPECTRE_GRPC_WEB_ORIGINS", "https://console.example, http://localhost:8080,");
+        env::set_var("SPECTRE_FACE_CONFIDENCE", "0.4");
+        env::set_var("SPECTRE_FACE_NMS", "0.5");
+        env::set_var("SPECTRE_YUNET_INPUT_SIZE", "320");
+        env::set_var("SPECTRE_PRIMARY_FACE", "sticky");
+        env::set_var("SPECTRE_EMOTION_MODEL_URL", "https://models.example/emotion.onnx");
+        env::set_var("SPECTRE_EMOTION_MODEL_SHA256", "AB".repeat(32));
+        env::set_var("SPECTRE_INFERENCE_TIMEOUT", "250ms");
+        env::set_var("SPECTRE_CAMERA_RESOLUTION", "1280x720");
+        env::set_var("SPECTRE_CALIBRATION_MIN_SAMPLES", "90");
+        env::set_var("SPECTRE_SECONDARY_EMOTION", "surprise");
+        env::set_var("SPECTRE_FEAR_INDEX_WEIGHTS", "0, 0, 1, -0.5, 0, 0, 0");
+        env::set_var("SPECTRE_RECORD", "/tmp/spectre_session.jsonl");
+        env::set_var("SPECTRE_EAGER_START", "true");
+        env::set_var("SPECTRE_IDLE_SHUTDOWN", "5m");
+        env::set_var("SPECTRE_LOG_FORMAT", "json");
+        env::set_var("SPECTRE_BACKPRESSURE", "drop-newest");
+        env::set_var("SPECTRE_EMOTION_INPUT", "64x64x1");
+        env::set_var("SPECTRE_EMOTION_OUTPUT", "scores");
         
         let config = SensorConfig::from_env();
         
         assert_eq!(config.onnx_threads, 8);
+        assert_eq!(config.face_detector, FaceDetectorKind::OpenCv);
         assert!(config.freeze_calibration);
         assert_eq!(config.camera_id, 2);
+        assert_eq!(config.mirror_input, MirrorInput::On);
         assert_eq!(config.target_fps, 60.0);
+        assert_eq!(config.emotion_interval, 3);
+        assert_eq!(config.detection_interval, 2);
+        assert!(config.power.enabled);
+        assert!(!config.adaptive_quality);
         assert_eq!(config.channel_buffer_size, 4);
         assert_eq!(config.metrics_port, 8080);
         assert_eq!(config.grpc_socket_path, "/tmp/test.sock");
+        assert!(!config.privacy_mode);
+        assert_eq!(config.optimized_model_cache, Some(PathBuf::from("/tmp/spectre_models")));
+        assert_eq!(config.input_normalization, InputNormalization::MeanStd { mean: 0.5, std: 0.25 });
+        assert_eq!(
+            config.heartbeat.target,
+            Some(HeartbeatTarget::File { path: PathBuf::from("/run/spectre/alive") })
+        );
+        assert_eq!(config.conditioning, ConditioningConfig::disabled().with_temperature(2.0).with_winsorization(3.0));
+        assert_eq!(config.grpc_web_origins, ["https://console.example", "http://localhost:8080"]);
+        assert_eq!(config.face_confidence_threshold, 0.4);
+        assert_eq!(config.face_nms_threshold, 0.5);
+        assert_eq!(config.yunet_input_size, 320);
+        assert_eq!(config.face_tracking.policy, PrimaryFacePolicy::Sticky);
+        assert_eq!(
+            config.emotion_model,
+            Some(ModelProvider::download("https://models.example/emotion.onnx", "ab".repeat(32)))
+        );
+        assert_eq!(config.inference_timeout, Duration::from_millis(250));
+        assert_eq!(config.camera_resolution, Some((1280, 720)));
+        assert_eq!(config.secondary_emotion_channel, Some(Emotion::Surprise.index()));
+        assert_eq!(config.calibration_min_samples, 90);
+        assert_eq!(config.fear_index_weights, Some(FearIndexWeights(vec![0.0, 0.0, 1.0, -0.5, 0.0, 0.0, 0.0])));
+        assert_eq!(config.record_path, Some(PathBuf::from("/tmp/spectre_session.jsonl")));
+        assert_eq!(config.logging.format, LogFormat::Json);
+        assert_eq!(config.backpressure, BackpressurePolicy::DropNewest);
+        assert_eq!(config.emotion_input_override, Some(InputShape { width: 64, height: 64, channels: 1, layout: TensorLayout::Nchw }));
+        assert_eq!(config.emotion_output.as_deref(), Some("scores"));
         
         // Clean up environment variables
         env::remove_var("SPECTRE_THREADS");
+        env::remove_var("SPECTRE_FACE_DETECTOR");
         env::remove_var("SPECTRE_FREEZE_CALIBRATION");
         env::remove_var("SPECTRE_CAMERA_ID");
+        env::remove_var("SPECTRE_MIRROR_INPUT");
         env::remove_var("SPECTRE_TARGET_FPS");
+        env::remove_var("SPECTRE_EMOTION_INTERVAL");
+        env::remove_var("SPECTRE_DETECTION_INTERVAL");
+        env::remove_var("SPECTRE_POWER_GOVERNOR");
+        env::remove_var("SPECTRE_ADAPTIVE_QUALITY");
         env::remove_var("SPECTRE_BUFFER_SIZE");
         env::remove_var("SPECTRE_METRICS_PORT");
         env::remove_var("SPECTRE_GRPC_SOCKET");
+        env::remove_var("SPECTRE_PRIVACY_MODE");
+        env::remove_var("SPECTRE_MODEL_CACHE");
+        env::remove_var("SPECTRE_CALIBRATION_CACHE");
+        env::remove_var("SPECTRE_INPUT_NORMALIZATION");
+        env::remove_var("SPECTRE_LOGIT_CLAMP");
+        env::remove_var("SPECTRE_LOGIT_TEMPERATURE");
+        env::remove_var("SPECTRE_WINSORIZE_K");
+        env::remove_var("SPECTRE_HEARTBEAT");
+        env::remove_var("SPECTRE_GRPC_WEB_ORIGINS");
+        env::remove_var("SPECTRE_FACE_CONFIDENCE");
+        env::remove_var("SPECTRE_FACE_NMS");
+        env::remove_var("SPECTRE_YUNET_INPUT_SIZE");
+        env::remove_var("SPECTRE_PRIMARY_FACE");
+        env::remove_var("SPECTRE_EMOTION_MODEL_URL");
+        env::remove_var("SPECTRE_EMOTION_MODEL_SHA256");
+        env::remove_var("SPECTRE_INFERENCE_TIMEOUT");
+        env::remove_var("SPECTRE_CAMERA_RESOLUTION");
+        env::remove_var("SPECTRE_CALIBRATION_MIN_SAMPLES");
+        env::remove_var("SPECTRE_SECONDARY_EMOTION");
+        env::remove_var("SPECTRE_FEAR_INDEX_WEIGHTS");
+        env::remove_var("SPECTRE_RECORD");
+        env::remove_var("SPECTRE_EAGER_START");
+        env::remove_var("SPECTRE_IDLE_SHUTDOWN");
+        env::remove_var("SPECTRE_LOG_FORMAT");
+        env::remove_var("SPECTRE_BACKPRESSURE");
+        env::remove_var("SPECTRE_EMOTION_INPUT");
+        env::remove_var("SPECTRE_EMOTION_OUTPUT");
+    }
+
+    #[test]
+    fn test_durations_in_config_files() {
+        let json = serde_json::to_value(SensorConfig::default()).unwrap();
+        assert_eq!(json["startle"]["window"], "250ms");
+        assert_eq!(json["startle"]["refractory"], "1s 500ms");
+        assert_eq!(json["resume"]["gap_threshold"], "3s");
+        assert_eq!(json["reconnect"]["disconnect_after"], "2s");
+        assert_eq!(json["reconnect"]["initial_backoff"], "250ms");
+        assert_eq!(json["retention"]["sweep_interval"], "1h");
+        assert_eq!(json["bug_report"]["window"], "10s");
+        assert_eq!(json["inference_timeout"], "100ms");
+        assert_eq!(json["metrics_history"]["interval"], "5s");
+        assert_eq!(json["heartbeat"]["stale_after"], "2s");
+        assert_eq!(json["power"]["poll_interval"], "5s");
+        assert_eq!(json["calibration_period"], "30s");
+        assert_eq!(json["calibration_cache_max_age"], "1day");
+        
+        // Numeric seconds and { secs, nanos } from older files still load
+        let mut json = json;
+        json["startle"]["window"] = serde_json::json!(0.5);
+        json["resume"]["gap_threshold"] = serde_json::json!({ "secs": 5, "nanos": 0 });
+        json["retention"]["sweep_interval"] = serde_json::json!(600);
+        json["heartbeat"]["interval"] = serde_json::json!("750ms");
+        let config: SensorConfig = serde_json::from_value(json.clone()).unwrap();
+        assert_eq!(config.startle.window, Duration::from_millis(500));
+        assert_eq!(config.startle.refractory, Duration::from_millis(1500));
+        assert_eq!(config.resume.gap_threshold, Duration::from_secs(5));
+        assert_eq!(config.retention.sweep_interval, Duration::from_secs(600));
+        assert_eq!(config.heartbeat.interval, Duration::from_millis(750));
+        assert!(config.validate().is_ok());
+        
+        json["bug_report"]["window"] = serde_json::json!("30 parsecs");
+        let error = serde_json::from_value::<SensorConfig>(json).unwrap_err().to_string();
+        assert!(error.starts_with("Invalid duration \"30 parsecs\": unknown time unit \"parsecs\""), "{}", error);
     }
 
     #[test]
@@ -0,0 +1,444 @@
+//! Graceful degradation when emotion inference fails mid-session
+//!
+//! A GPU driver fault or thermal event can leave the emotion session
+//! erroring on every frame. Instead of freezing fear and logging per frame,
+//! [`EmotionPipeline`] walks a ladder:
+//!
+//! 1. After `hold_after_failures` consecutive failures, fear is held at the
+//!    last good value with confidence falling on every further failure.
+//! 2. After `rebuild_after_failures` more, the emotion session is rebuilt
+//!    from scratch, once per sensor run.
+//! 3. If the rebuild fails, or the rebuilt session fails just as long,
+//!    emotion inference is declared offline: frames carry face presence only
+//!    and fear is flagged unavailable.
+//!
+//! Every transition is broadcast as a typed fault and reflected in the
+//! sensor capability reported by `GetStatus`. Inference over the time budget
+//! is not a rung of its own, see [`crate::inference_timeout`].
+
+use crate::{
+    inference_timeout::TimedEmotionBackend,
+    sensor::SensorError,
+    types::{FaultLevel, SensorCapability, SensorFaultNotice},
+};
+use opencv::core::Mat;
+use ort::session::Session;
+use serde::{Deserialize, Serialize};
+use spectremesh_core::EmotionLogits;
+use std::time::Duration;
+use tokio::sync::broadcast;
+
+/// Fault code: inference is failing, fear is held at the last good value
+pub const EMOTION_DEGRADED: &str = "EMOTION_DEGRADED";
+/// Fault code: the emotion session is being rebuilt
+pub const EMOTION_REBUILDING: &str = "EMOTION_REBUILDING";
+/// Fault code: inference succeeded again after degrading
+pub const EMOTION_RECOVERED: &str = "EMOTION_RECOVERED";
+/// Fault code: emotion inference is offline for the rest of the run
+pub const EMOTION_OFFLINE: &str = "EMOTION_OFFLINE";
+
+/// Degradation ladder thresholds
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DegradationConfig {
+    /// Consecutive failures before holding the last value
+    pub hold_after_failures: u32,
+    /// Further failures while holding before the session is rebuilt
+    pub rebuild_after_failures: u32,
+}
+
+impl Default for DegradationConfig {
+    fn default() -> Self {
+        Self {
+            hold_after_failures: 5,     // ~170ms at 30 FPS
+            rebuild_after_failures: 60, // ~2s more of held values
+        }
+    }
+}
+
+/// Runs emotion inference on a cropped face
+pub trait EmotionBackend: Send {
+    /// Emotion logits in model order, as many as the model's layout has channels
+    fn infer(&mut self, face: &Mat) -> Result<EmotionLogits, SensorError>;
+
+    /// The ONNX Runtime session behind the backend, kept for the next run
+    fn into_session(self: Box<Self>) -> Option<Session> {
+        None
+    }
+}
+
+/// Creates a fresh emotion backend for the rebuild step
+pub type EmotionBackendFactory = Box<dyn FnMut() -> Result<Box<dyn EmotionBackend>, SensorError> + Send>;
+
+/// Result of running one face through the pipeline
+#[derive(Debug, Clone, PartialEq)]
+pub enum EmotionOutcome {
+    /// Fresh logits from the model
+    Live(EmotionLogits),
+    /// Inference failed; the last good logits with confidence scaled down
+    Held { logits: EmotionLogits, confidence_scale: f32 },
+    /// Emotion inference is offline
+    Offline,
+}
+
+/// Emotion backend wrapped in the degradation ladder
+pub struct EmotionPipeline {
+    backend: Box<dyn EmotionBackend>,
+    rebuild: EmotionBackendFactory,
+    config: DegradationConfig,
+    faults: broadcast::Sender<SensorFaultNotice>,
+    capability: SensorCapability,
+    consecutive_failures: u32,
+    rebuild_attempted: bool,
+    last_logits: Option<EmotionLogits>,
+    /// Longest a face is waited on, when inference runs on a worker thread
+    timeout: Option<Duration>,
+}
+
+impl EmotionPipeline {
+    /// Wrap `backend`, using `rebuild` for the one-time session rebuild
+    pub fn new(
+        backend: Box<dyn EmotionBackend>,
+        rebuild: EmotionBackendFactory,
+        config: DegradationConfig,
+        faults: broadcast::Sender<SensorFaultNotice>,
+    ) -> Self {
+        Self {
+            backend,
+            rebuild,
+            config,
+            faults,
+            capability: SensorCapability::Full,
+            consecutive_failures: 0,
+            rebuild_attempted: false,
+            last_logits: None,
+            timeout: None,
+        }
+    }
+
+    /// Run inference on a worker thread and abandon faces it takes longer
+    /// than `timeout` on, for this backend and every later one
+    pub fn with_inference_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self.backend = Box::new(TimedEmotionBackend::spawn(self.backend, timeout));
+        self
+    }
+
+    /// Current rung of the ladder
+    pub fn capability(&self) -> SensorCapability {
+        self.capability
+    }
+
+    /// Consecutive inference failures
+    pub fn consecutive_failures(&self) -> u32 {
+        self.consecutive_failures
+    }
+
+    /// Whether the one-time rebuild has been used
+    pub fn rebuild_attempted(&self) -> bool {
+        self.rebuild_attempted
+    }
+
+    /// Run inference on a face crop
+    ///
+    /// Failures below the hold threshold are returned as errors, matching
+    /// the behaviour of a healthy session dropping the odd frame.
+    pub fn infer(&mut self, face: &Mat) -> Result<EmotionOutcome, SensorError> {
+        if self.capability == SensorCapability::EmotionOffline {
+            return Ok(EmotionOutcome::Offline);
+        }
+
+        let error = match self.backend.infer(face) {
+            Ok(logits) => {
+                if self.capability == SensorCapability::HoldLastValue {
+                    self.transition(
+                        SensorCapability::Full,
+                        FaultLevel::Info,
+                        format!("Emotion inference recovered after {} failures", self.consecutive_failures),
+                        EMOTION_RECOVERED,
+                    );
+                }
+                self.consecutive_failures = 0;
+                self.last_logits = Some(logits.clone());
+                return Ok(EmotionOutcome::Live(logits));
+            }
+            // Over budget is reported by the loop, not counted as a failure
+            Err(e @ SensorError::InferenceTimeout { .. }) => return Err(e),
+            Err(e) => e,
+        };
+
+        self.consecutive_failures += 1;
+        let hold_after = self.config.hold_after_failures.max(1);
+        let rebuild_after = hold_after + self.config.rebuild_after_failures;
+
+        if self.capability == SensorCapability::Full && self.consecutive_failures >= hold_after {
+            self.transition(
+                SensorCapability::HoldLastValue,
+                FaultLevel::Warning,
+                format!("Emotion inference failing ({}), holding last fear value", error),
+                EMOTION_DEGRADED,
+            );
+        }
+
+        if self.capability == SensorCapability::HoldLastValue && self.consecutive_failures >= rebuild_after {
+            if self.rebuild_attempted {
+                self.go_offline(format!("Rebuilt emotion session keeps failing: {}", error));
+                return Ok(EmotionOutcome::Offline);
+            }
+            if let Err(e) = self.rebuild_backend() {
+                self.go_offline(format!("Emotion session rebuild failed: {}", e));
+                return Ok(EmotionOutcome::Offline);
+            }
+        }
+
+        match (self.capability, &self.last_logits) {
+            (SensorCapability::HoldLastValue, Some(logits)) => Ok(EmotionOutcome::Held {
+                logits: logits.clone(),
+                confidence_scale: self.held_confidence(),
+            }),
+            _ => Err(error),
+        }
+    }
+
+    /// Run another model from the next face on
+    ///
+    /// The ladder starts afresh: capability is full again, the failure count
+    /// and held logits are cleared, and `rebuild` gets its own one-time
+    /// rebuild.
+    pub fn swap_backend(&mut self, backend: Box<dyn EmotionBackend>, rebuild: EmotionBackendFactory) {
+        self.backend = self.timed(backend);
+        self.rebuild = rebuild;
+        self.capability = SensorCapability::Full;
+        self.consecutive_failures = 0;
+        self.rebuild_attempted = false;
+        self.last_logits = None;
+    }
+
+    /// The backend in use, once the run is over
+    pub fn into_backend(self) -> Box<dyn EmotionBackend> {
+        self.backend
+    }
+
+    /// Confidence scale for held values, falling from 1 toward 0 as the
+    /// ladder approaches the rebuild step
+    fn held_confidence(&self) -> f32 {
+        let held = self.consecutive_failures.saturating_sub(self.config.hold_after_failures.max(1) - 1);
+        1.0 - held as f32 / (self.config.rebuild_after_failures + 1) as f32
+    }
+
+    /// Replace the backend with a freshly built one
+    fn rebuild_backend(&mut self) -> Result<(), SensorError> {
+        self.rebuild_attempted = true;
+        let _ = self.faults.send(SensorFaultNotice::new(
+            FaultLevel::Warning,
+            format!("Rebuilding emotion session after {} failures", self.consecutive_failures),
+            EMOTION_REBUILDING,
+            true,
+        ));
+        tracing::warn!("Rebuilding emotion session after {} failures", self.consecutive_failures);
+
+        let backend = (self.rebuild)()?;
+        self.backend = self.timed(backend);
+        // The rebuilt session gets a full hold window before going offline
+        self.consecutive_failures = self.config.hold_after_failures.max(1);
+        Ok(())
+    }
+
+    /// `backend` on a worker thread when a timeout is set
+    fn timed(&self, backend: Box<dyn EmotionBackend>) -> Box<dyn EmotionBackend> {
+        match self.timeout {
+            Some(timeout) => Box::new(TimedEmotionBackend::spawn(backend, timeout)),
+            None => backend,
+        }
+    }
+
+    /// Declare emotion inference offline for the rest of the run
+    fn go_offline(&mut self, message: String) {
+        self.transition(SensorCapability::EmotionOffline, FaultLevel::Critical, message, EMOTION_OFFLINE);
+        self.last_logits = None;
+    }
+
+    /// Move to another rung and broadcast the matching fault
+    fn transition(&mut self, capability: SensorCapability, severity: FaultLevel, message: String, code: &str) {
+        tracing::warn!("Sensor capability {:?} -> {:?}: {}", self.capability, capability, message);
+        self.capability = capability;
+        let recoverable = capability != SensorCapability::EmotionOffline;
+        let _ = self.faults.send(SensorFaultNotice::new(severity, message, code, recoverable));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::VecDeque;
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    };
+
+    const LOGITS: [f32; 7] = [0.0, 0.0, 1.5, 0.0, 0.0, 0.0, 0.0];
+
+    /// Replays scripted results, then fails forever
+    struct FakeBackend {
+        script: VecDeque<bool>,
+    }
+
+    impl FakeBackend {
+        fn boxed(script: &[bool]) -> Box<dyn EmotionBackend> {
+            Box::new(Self { script: script.iter().copied().collect() })
+        }
+    }
+
+    impl EmotionBackend for FakeBackend {
+        fn infer(&mut self, _face: &Mat) -> Result<EmotionLogits, SensorError> {
+            if self.script.pop_front().unwrap_or(false) {
+                Ok(LOGITS.into())
+            } else {
+                Err(SensorError::FrameProcessing("GPU fault".to_string()))
+            }
+        }
+    }
+
+    fn config() -> DegradationConfig {
+        DegradationConfig {
+            hold_after_failures: 3,
+            rebuild_after_failures: 4,
+        }
+    }
+
+    /// Pipeline whose rebuild returns `rebuilt` (or fails when None), with a rebuild counter
+    fn pipeline(
+        script: &[bool],
+        rebuilt: Option<Vec<bool>>,
+    ) -> (EmotionPipeline, Arc<AtomicUsize>, broadcast::Receiver<SensorFaultNotice>) {
+        let rebuilds = Arc::new(AtomicUsize::new(0));
+        let counter = Arc::clone(&rebuilds);
+        let factory: EmotionBackendFactory = Box::new(move || {
+            counter.fetch_add(1, Ordering::SeqCst);
+            match &rebuilt {
+                Some(script) => Ok(FakeBackend::boxed(script)),
+                None => Err(SensorError::ModelLoading("device lost".to_string())),
+            }
+        });
+        let (faults, receiver) = broadcast::channel(16);
+        (EmotionPipeline::new(FakeBackend::boxed(script), factory, config(), faults), rebuilds, receiver)
+    }
+
+    fn fault_codes(receiver: &mut broadcast::Receiver<SensorFaultNotice>) -> Vec<String> {
+        std::iter::from_fn(|| receiver.try_recv().ok()).map(|notice| notice.error_code).collect()
+    }
+
+    #[test]
+    fn test_ladder_holds_rebuilds_once_then_goes_offline() {
+        let (mut pipeline, rebuilds, mut faults) = pipeline(&[true], None);
+        let face = Mat::default();
+
+        assert_eq!(pipeline.infer(&face).unwrap(), EmotionOutcome::Live(LOGITS.into()));
+
+        // Failures below the hold threshold surface as errors
+        assert!(pipeline.infer(&face).is_err());
+        assert!(pipeline.infer(&face).is_err());
+        assert_eq!(pipeline.capability(), SensorCapability::Full);
+
+        // Third failure: hold the last logits with falling confidence
+        let mut previous_scale = 1.0;
+        for _ in 0..4 {
+            match pipeline.infer(&face).unwrap() {
+                EmotionOutcome::Held { logits, confidence_scale } => {
+                    assert_eq!(logits, LOGITS);
+                    assert!(confidence_scale < previous_scale && confidence_scale > 0.0);
+                    previous_scale = confidence_scale;
+                }
+                other => panic!("expected held outcome, got {:?}", other),
+            }
+        }
+        assert_eq!(pipeline.capability(), SensorCapability::HoldLastValue);
+        assert_eq!(rebuilds.load(Ordering::SeqCst), 0);
+
+        // Seventh failure: rebuild fails, emotion goes offline
+        assert_eq!(pipeline.infer(&face).unwrap(), EmotionOutcome::Offline);
+        assert_eq!(pipeline.capability(), SensorCapability::EmotionOffline);
+        assert_eq!(rebuilds.load(Ordering::SeqCst), 1);
+
+        // Offline is final and never retries the rebuild
+        for _ in 0..20 {
+            assert_eq!(pipeline.infer(&face).unwrap(), EmotionOutcome::Offline);
+        }
+        assert_eq!(rebuilds.load(Ordering::SeqCst), 1);
+        assert_eq!(fault_codes(&mut faults), [EMOTION_DEGRADED, EMOTION_REBUILDING, EMOTION_OFFLINE]);
+    }
+
+    #[test]
+    fn test_successful_rebuild_recovers() {
+        let (mut pipeline, rebuilds, mut faults) = pipeline(&[true], Some(vec![false, true]));
+        let face = Mat::default();
+        pipeline.infer(&face).unwrap();
+
+        // 7 failures reach the rebuild, the rebuilt backend fails once more then recovers
+        for _ in 0..7 {
+            let _ = pipeline.infer(&face);
+        }
+        assert_eq!(rebuilds.load(Ordering::SeqCst), 1);
+        assert_eq!(pipeline.capability(), SensorCapability::HoldLastValue);
+        assert!(matches!(pipeline.infer(&face).unwrap(), EmotionOutcome::Held { .. }));
+        assert_eq!(pipeline.infer(&face).unwrap(), EmotionOutcome::Live(LOGITS.into()));
+        assert_eq!(pipeline.capability(), SensorCapability::Full);
+        assert_eq!(pipeline.consecutive_failures(), 0);
+        assert_eq!(fault_codes(&mut faults), [EMOTION_DEGRADED, EMOTION_REBUILDING, EMOTION_RECOVERED]);
+    }
+
+    #[test]
+    fn test_rebuilt_session_failing_goes_offline_without_second_rebuild() {
+        let (mut pipeline, rebuilds, mut faults) = pipeline(&[true], Some(Vec::new()));
+        let face = Mat::default();
+        pipeline.infer(&face).unwrap();
+
+        let mut outcomes = Vec::new();
+        for _ in 0..30 {
+            outcomes.push(pipeline.infer(&face).ok());
+        }
+
+        // Rebuild at failure 7, offline after another full hold window
+        assert_eq!(rebuilds.load(Ordering::SeqCst), 1);
+        assert_eq!(pipeline.capability(), SensorCapability::EmotionOffline);
+        assert!(pipeline.rebuild_attempted());
+        let first_offline = outcomes.iter().position(|o| *o == Some(EmotionOutcome::Offline)).unwrap();
+        assert_eq!(first_offline, 10);
+        assert_eq!(fault_codes(&mut faults), [EMOTION_DEGRADED, EMOTION_REBUILDING, EMOTION_OFFLINE]);
+    }
+
+    #[test]
+    fn test_swapped_backend_starts_the_ladder_afresh() {
+        let (mut pipeline, rebuilds, mut faults) = pipeline(&[true], None);
+        let face = Mat::default();
+        for _ in 0..10 {
+            let _ = pipeline.infer(&face);
+        }
+        assert_eq!(pipeline.capability(), SensorCapability::EmotionOffline);
+        fault_codes(&mut faults);
+
+        pipeline.swap_backend(FakeBackend::boxed(&[true]), Box::new(|| Ok(FakeBackend::boxed(&[true]))));
+        assert_eq!(pipeline.capability(), SensorCapability::Full);
+        assert!(!pipeline.rebuild_attempted());
+        assert_eq!(pipeline.infer(&face).unwrap(), EmotionOutcome::Live(LOGITS.into()));
+
+        // The swapped-in model gets its own rebuild, through its own factory
+        for _ in 0..7 {
+            let _ = pipeline.infer(&face);
+        }
+        assert!(pipeline.rebuild_attempted());
+        assert_eq!(pipeline.capability(), SensorCapability::HoldLastValue);
+        assert_eq!(rebuilds.load(Ordering::SeqCst), 1);
+        assert_eq!(fault_codes(&mut faults), [EMOTION_DEGRADED, EMOTION_REBUILDING]);
+    }
+
+    #[test]
+    fn test_transient_failures_do_not_degrade() {
+        let (mut pipeline, _, mut faults) = pipeline(&[true, false, false, true, false, true], None);
+        let face = Mat::default();
+        for _ in 0..6 {
+            let _ = pipeline.infer(&face);
+        }
+        assert_eq!(pipeline.capability(), SensorCapability::Full);
+        assert!(fault_codes(&mut faults).is_empty());
+    }
+}
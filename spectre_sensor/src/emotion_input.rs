@@ -0,0 +1,355 @@
+//! Emotion model input and output tensors, read from the model
+//!
+//! FER models differ in what they take: 48x48 or 64x64 faces, grayscale or
+//! RGB, channels first (NCHW) or last (NHWC), under whatever tensor names
+//! they were exported with. Once a session loads, the dimensions of its
+//! first input say how to prepare the face crop, and the logits come from
+//! its first output unless another one is configured. A model whose input
+//! dimensions are dynamic cannot say, so its shape is configured instead
+//! with `SensorConfig::emotion_input_override`.
+
+use crate::normalization::INPUT_SIZE;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+use thiserror::Error;
+
+/// Fewest values an emotion output can hold, one per basic emotion
+pub const MIN_LOGITS: usize = 7;
+
+/// Where the channels sit in the input tensor
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TensorLayout {
+    /// `[batch, channels, height, width]`
+    #[default]
+    Nchw,
+    /// `[batch, height, width, channels]`
+    Nhwc,
+}
+
+impl FromStr for TensorLayout {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "nchw" => Ok(Self::Nchw),
+            "nhwc" => Ok(Self::Nhwc),
+            other => Err(format!("Unknown tensor layout: {} (expected nchw or nhwc)", other)),
+        }
+    }
+}
+
+impl fmt::Display for TensorLayout {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Nchw => f.write_str("nchw"),
+            Self::Nhwc => f.write_str("nhwc"),
+        }
+    }
+}
+
+/// One face as the emotion model takes it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InputShape {
+    pub width: usize,
+    pub height: usize,
+    /// 1 for grayscale, 3 for RGB
+    pub channels: usize,
+    #[serde(default)]
+    pub layout: TensorLayout,
+}
+
+impl Default for InputShape {
+    /// The bundled model's 48x48 grayscale NCHW input
+    fn default() -> Self {
+        Self { width: INPUT_SIZE, height: INPUT_SIZE, channels: 1, layout: TensorLayout::Nchw }
+    }
+}
+
+impl InputShape {
+    /// Dimensions of the input tensor for a single face
+    pub fn tensor_shape(&self) -> [usize; 4] {
+        match self.layout {
+            TensorLayout::Nchw => [1, self.channels, self.height, self.width],
+            TensorLayout::Nhwc => [1, self.height, self.width, self.channels],
+        }
+    }
+
+    /// Number of values in the input tensor
+    pub fn tensor_len(&self) -> usize {
+        self.width * self.height * self.channels
+    }
+
+    /// Check the shape is one the face crop can be prepared for
+    pub fn validate(&self) -> Result<(), String> {
+        if self.width == 0 || self.height == 0 {
+            return Err(format!("Emotion input {} has no pixels", self));
+        }
+        if !matches!(self.channels, 1 | 3) {
+            return Err(format!("Emotion input {} must have 1 or 3 channels", self));
+        }
+        Ok(())
+    }
+
+    /// Arrange pixels, given row by row with each pixel's channels together,
+    /// in the order of the input tensor
+    pub fn arrange(&self, interleaved: Vec<f32>) -> Vec<f32> {
+        if self.layout == TensorLayout::Nhwc || self.channels == 1 {
+            return interleaved;
+        }
+        let plane = self.width * self.height;
+        let mut planar = vec![0.0; interleaved.len()];
+        for (index, value) in interleaved.into_iter().enumerate() {
+            planar[(index % self.channels) * plane + index / self.channels] = value;
+        }
+        planar
+    }
+
+    /// Input tensor values for a square grayscale image of side `side`, such
+    /// as the normalization fixture faces: resized by nearest neighbour and
+    /// repeated across the channels
+    pub fn from_gray(&self, pixels: &[f32], side: usize) -> Vec<f32> {
+        let mut interleaved = Vec::with_capacity(self.tensor_len());
+        for row in 0..self.height {
+            for col in 0..self.width {
+                let value = pixels[(row * side / self.height) * side + col * side / self.width];
+                interleaved.extend(std::iter::repeat_n(value, self.channels));
+            }
+        }
+        self.arrange(interleaved)
+    }
+}
+
+impl FromStr for InputShape {
+    type Err = String;
+
+    /// `WIDTHxHEIGHTxCHANNELS`, optionally followed by `:nchw` or `:nhwc`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("Invalid emotion input shape: {} (expected e.g. 64x64x3:nhwc)", s);
+        let (dims, layout) = match s.split_once(':') {
+            Some((dims, layout)) => (dims, layout.trim().parse()?),
+            None => (s, TensorLayout::default()),
+        };
+        let dims = dims
+            .split('x')
+            .map(|dim| dim.trim().parse::<usize>())
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|_| invalid())?;
+        let [width, height, channels] = dims[..] else {
+            return Err(invalid());
+        };
+        let shape = Self { width, height, channels, layout };
+        shape.validate()?;
+        Ok(shape)
+    }
+}
+
+impl fmt::Display for InputShape {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}x{}x{}:{}", self.width, self.height, self.channels, self.layout)
+    }
+}
+
+/// Why an emotion model's tensors do not fit the pipeline
+#[derive(Debug, Clone, PartialEq, Error)]
+pub enum EmotionInputError {
+    #[error("Emotion model has no inputs")]
+    NoInput,
+
+    #[error("Emotion model has no outputs")]
+    NoOutput,
+
+    #[error("Emotion model has no output tensor named '{0}'")]
+    MissingOutput(String),
+
+    #[error("Emotion model input '{name}' takes {found}, expected 32-bit floats")]
+    ElementType { name: String, found: String },
+
+    #[error(
+        "Emotion model input '{name}' has shape {found:?}, expected [1, channels, height, width] \
+         or [1, height, width, channels] with 1 or 3 channels"
+    )]
+    UnsupportedShape { name: String, found: Vec<i64> },
+
+    #[error("Emotion model input '{name}' has dynamic shape {found:?}; set emotion_input_override")]
+    DynamicShape { name: String, found: Vec<i64> },
+
+    #[error("Emotion model input '{name}' has shape {found:?}, but emotion_input_override {shape} expects {expected:?}")]
+    OverrideMismatch { name: String, shape: InputShape, expected: [usize; 4], found: Vec<i64> },
+
+    #[error("Emotion model output '{name}' has shape {found:?}, expected at least {min} values", min = MIN_LOGITS)]
+    OutputTooSmall { name: String, found: Vec<i64> },
+}
+
+/// Input tensor of an emotion model
+#[derive(Debug, Clone, PartialEq)]
+pub struct EmotionInput {
+    pub name: String,
+    pub shape: InputShape,
+}
+
+/// Where an emotion model takes the face and gives the logits
+#[derive(Debug, Clone, PartialEq)]
+pub struct EmotionTensors {
+    pub input: EmotionInput,
+    pub output: String,
+}
+
+impl fmt::Display for EmotionTensors {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {} -> {}", self.input.name, self.input.shape, self.output)
+    }
+}
+
+/// Shape of the input `name` with dimensions `dims`, where dynamic
+/// dimensions are negative
+///
+/// With `override_shape` the model's fixed dimensions only have to agree
+/// with it; without, every dimension but the batch must be fixed, and the
+/// layout is NCHW if the second holds 1 or 3 channels, else NHWC if the last does.
+pub fn resolve_input(
+    name: &str,
+    dims: &[i64],
+    override_shape: Option<InputShape>,
+) -> Result<EmotionInput, EmotionInputError> {
+    let unsupported = || EmotionInputError::UnsupportedShape { name: name.to_string(), found: dims.to_vec() };
+    if dims.len() != 4 || dims[0] > 1 {
+        return Err(unsupported());
+    }
+
+    if let Some(shape) = override_shape {
+        let expected = shape.tensor_shape();
+        let agrees = dims.iter().zip(expected).all(|(&dim, expected)| dim < 0 || dim as usize == expected);
+        if !agrees {
+            return Err(EmotionInputError::OverrideMismatch {
+                name: name.to_string(),
+                shape,
+                expected,
+                found: dims.to_vec(),
+            });
+        }
+        return Ok(EmotionInput { name: name.to_string(), shape });
+    }
+
+    if dims[1..].iter().any(|&dim| dim <= 0) {
+        return Err(EmotionInputError::DynamicShape { name: name.to_string(), found: dims.to_vec() });
+    }
+    let dim = |index: usize| dims[index] as usize;
+    let shape = if matches!(dims[1], 1 | 3) {
+        InputShape { width: dim(3), height: dim(2), channels: dim(1), layout: TensorLayout::Nchw }
+    } else if matches!(dims[3], 1 | 3) {
+        InputShape { width: dim(2), height: dim(1), channels: dim(3), layout: TensorLayout::Nhwc }
+    } else {
+        return Err(unsupported());
+    };
+    Ok(EmotionInput { name: name.to_string(), shape })
+}
+
+/// Name of the output the logits are read from: `configured`, or else the
+/// first of `outputs`, given as names and dimensions
+///
+/// The output must hold at least [`MIN_LOGITS`] values; one with dynamic
+/// dimensions is taken on trust until its first inference.
+pub fn resolve_output<'a>(
+    outputs: impl IntoIterator<Item = (&'a str, &'a [i64])>,
+    configured: Option<&str>,
+) -> Result<String, EmotionInputError> {
+    let mut outputs = outputs.into_iter();
+    let (name, dims) = match configured {
+        Some(configured) => outputs
+            .find(|(name, _)| *name == configured)
+            .ok_or_else(|| EmotionInputError::MissingOutput(configured.to_string()))?,
+        None => outputs.next().ok_or(EmotionInputError::NoOutput)?,
+    };
+
+    let fixed = dims.iter().all(|&dim| dim >= 0);
+    if fixed && dims.iter().product::<i64>() < MIN_LOGITS as i64 {
+        return Err(EmotionInputError::OutputTooSmall { name: name.to_string(), found: dims.to_vec() });
+    }
+    Ok(name.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_input_reads_size_channels_and_layout() {
+        let gray = resolve_input("input", &[1, 1, 48, 48], None).unwrap();
+        assert_eq!(gray.shape, InputShape::default());
+
+        let rgb = resolve_input("pixels", &[-1, 64, 64, 3], None).unwrap();
+        assert_eq!(rgb.name, "pixels");
+        assert_eq!(rgb.shape, InputShape { width: 64, height: 64, channels: 3, layout: TensorLayout::Nhwc });
+        assert_eq!(rgb.shape.tensor_shape(), [1, 64, 64, 3]);
+
+        let error = resolve_input("input", &[1, 4, 48, 48], None).unwrap_err();
+        assert!(error.to_string().contains("[1, 4, 48, 48]"), "{}", error);
+        assert!(matches!(resolve_input("input", &[1, 48, 48], None), Err(EmotionInputError::UnsupportedShape { .. })));
+        assert!(matches!(resolve_input("input", &[8, 1, 48, 48], None), Err(EmotionInputError::UnsupportedShape { .. })));
+    }
+
+    #[test]
+    fn test_dynamic_input_needs_an_override_that_agrees() {
+        let dims = [-1, 1, -1, -1];
+        let error = resolve_input("input", &dims, None).unwrap_err();
+        assert!(matches!(error, EmotionInputError::DynamicShape { .. }));
+        assert!(error.to_string().contains("emotion_input_override"), "{}", error);
+
+        let shape: InputShape = "64x64x1".parse().unwrap();
+        assert_eq!(resolve_input("input", &dims, Some(shape)).unwrap().shape, shape);
+
+        // The fixed channel count contradicts an RGB override
+        let rgb: InputShape = "64x64x3:nchw".parse().unwrap();
+        let error = resolve_input("input", &dims, Some(rgb)).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Emotion model input 'input' has shape [-1, 1, -1, -1], but emotion_input_override \
+             64x64x3:nchw expects [1, 3, 64, 64]"
+        );
+    }
+
+    #[test]
+    fn test_resolve_output_takes_the_first_or_the_configured_one() {
+        let outputs = [("features", &[1, 128][..]), ("logits", &[1, 7][..]), ("fear", &[1, 1][..])];
+        assert_eq!(resolve_output(outputs, None).unwrap(), "features");
+        assert_eq!(resolve_output(outputs, Some("logits")).unwrap(), "logits");
+        assert_eq!(resolve_output([("scores", &[-1, 8][..])], None).unwrap(), "scores");
+
+        assert_eq!(
+            resolve_output(outputs, Some("fear")).unwrap_err().to_string(),
+            "Emotion model output 'fear' has shape [1, 1], expected at least 7 values"
+        );
+        assert_eq!(resolve_output(outputs, Some("output")), Err(EmotionInputError::MissingOutput("output".into())));
+        assert_eq!(resolve_output([], None), Err(EmotionInputError::NoOutput));
+    }
+
+    #[test]
+    fn test_arrange_and_from_gray() {
+        let shape = InputShape { width: 2, height: 1, channels: 3, layout: TensorLayout::Nchw };
+        assert_eq!(shape.arrange(vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0]), [1.0, 4.0, 2.0, 5.0, 3.0, 6.0]);
+        let nhwc = InputShape { layout: TensorLayout::Nhwc, ..shape };
+        assert_eq!(nhwc.arrange(vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0]), [1.0, 2.0, 3.0, 4.0, 5.0, 6.0]);
+
+        // 2x2 grayscale up to 4x4, repeated over three channel planes
+        let gray = [0.1, 0.2, 0.3, 0.4];
+        let rgb = InputShape { width: 4, height: 4, channels: 3, layout: TensorLayout::Nchw }.from_gray(&gray, 2);
+        assert_eq!(rgb.len(), 48);
+        assert_eq!(&rgb[..4], [0.1, 0.1, 0.2, 0.2]);
+        assert_eq!(&rgb[16..20], [0.1, 0.1, 0.2, 0.2]);
+        assert_eq!(rgb[15], 0.4);
+        assert_eq!(InputShape::default().from_gray(&[0.5; INPUT_SIZE * INPUT_SIZE], INPUT_SIZE).len(), 2304);
+    }
+
+    #[test]
+    fn test_shape_names() {
+        for shape in [InputShape::default(), "64x32x3:nhwc".parse().unwrap()] {
+            assert_eq!(shape.to_string().parse::<InputShape>(), Ok(shape));
+        }
+        assert_eq!("48x48x1".parse::<InputShape>().unwrap().layout, TensorLayout::Nchw);
+        assert!("48x48x2".parse::<InputShape>().is_err());
+        assert!("48x48".parse::<InputShape>().is_err());
+        assert!("48x48x1:chw".parse::<InputShape>().is_err());
+    }
+}
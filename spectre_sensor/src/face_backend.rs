@@ -0,0 +1,326 @@
+//! Pluggable face detection backends
+//!
+//! The default backend runs the embedded YuNet model through ONNX Runtime.
+//! Builds with the `opencv-face-detector` feature can also run the same model
+//! through OpenCV's native `FaceDetectorYN` (requires OpenCV 4.8+ with the
+//! dnn module), either on request or as a fallback when the ONNX Runtime
+//! session cannot be created. Both backends produce identical
+//! [`FaceDetection`] values so the rest of the pipeline is unchanged.
+
+use opencv::{
+    core::{Mat, Point, Rect, Size},
+    prelude::*,
+};
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+use crate::{
+    config::SensorConfig,
+    model_info::ModelInfo,
+    model_provider::ModelProvider,
+    yunet::{FaceDetection, YuNetDetector, YuNetError, YuNetParams},
+};
+
+/// Values per face in `FaceDetectorYN` output: box, 5 landmarks, score
+const FACE_ROW_LEN: usize = 15;
+
+/// A face detector the sensor can run frames through
+pub trait FaceDetectorBackend: Send {
+    /// Short backend name for logs and metrics
+    fn name(&self) -> &'static str;
+
+    /// Provenance of the model the backend runs
+    fn model_info(&self) -> &ModelInfo;
+
+    /// Detect all faces in the image
+    fn detect_faces(&mut self, image: &Mat) -> Result<Vec<FaceDetection>, YuNetError>;
+
+    /// Drop detections scoring below `threshold` from the next image on;
+    /// backends without a score threshold keep every detection
+    fn set_confidence_threshold(&mut self, _threshold: f32) {}
+
+    /// Resize frames to `size` before detection from the next image on;
+    /// backends detecting at the frame's own size ignore it
+    fn set_input_size(&mut self, _size: Size) {}
+
+    /// Get the most confident face
+    fn get_largest_face(&mut self, image: &Mat) -> Result<FaceDetection, YuNetError> {
+        self.detect_faces(image)?
+            .into_iter()
+            .max_by(|a, b| a.confidence.total_cmp(&b.confidence))
+            .ok_or(YuNetError::NoFacesDetected)
+    }
+}
+
+impl FaceDetectorBackend for YuNetDetector {
+    fn name(&self) -> &'static str {
+        "onnxruntime"
+    }
+
+    fn model_info(&self) -> &ModelInfo {
+        YuNetDetector::model_info(self)
+    }
+
+    fn detect_faces(&mut self, image: &Mat) -> Result<Vec<FaceDetection>, YuNetError> {
+        YuNetDetector::detect_faces(self, image)
+    }
+
+    fn set_confidence_threshold(&mut self, threshold: f32) {
+        YuNetDetector::set_confidence_threshold(self, threshold)
+    }
+
+    fn set_input_size(&mut self, size: Size) {
+        YuNetDetector::set_input_size(self, size)
+    }
+}
+
+/// Which face detection backend to use
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FaceDetectorKind {
+    /// ONNX Runtime, falling back to OpenCV when the session cannot be created
+    #[default]
+    Auto,
+    /// ONNX Runtime only
+    Ort,
+    /// OpenCV `FaceDetectorYN` only
+    OpenCv,
+}
+
+impl FromStr for FaceDetectorKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "auto" => Ok(Self::Auto),
+            "ort" | "onnxruntime" => Ok(Self::Ort),
+            "opencv" => Ok(Self::OpenCv),
+            other => Err(format!("Unknown face detector backend: {}", other)),
+        }
+    }
+}
+
+/// Create the face detector selected in the configuration
+pub fn create_face_detector(config: &SensorConfig) -> Result<Box<dyn FaceDetectorBackend>, YuNetError> {
+    let params = config.yunet_params();
+    let ort_detector = || match &config.emotion_model {
+        Some(ModelProvider::File { path }) => {
+            YuNetDetector::from_file(&path.to_string_lossy(), config.onnx_threads, None, params)
+        }
+        #[cfg(feature = "embedded-models")]
+        _ => YuNetDetector::new(config.onnx_threads, params),
+        #[cfg(not(feature = "embedded-models"))]
+        _ => Err(YuNetError::BackendUnavailable(
+            "built without the embedded-models feature, a model path is required".to_string(),
+        )),
+    };
+
+    match config.face_detector {
+        FaceDetectorKind::Ort => Ok(Box::new(ort_detector()?)),
+        FaceDetectorKind::OpenCv => opencv_detector(params),
+        FaceDetectorKind::Auto => match ort_detector() {
+            Ok(detector) => Ok(Box::new(detector)),
+            Err(YuNetError::SessionCreation(reason)) if cfg!(feature = "opencv-face-detector") => {
+                tracing::warn!("ONNX Runtime face detector unavailable ({}), using OpenCV FaceDetectorYN", reason);
+                opencv_detector(params)
+            }
+            Err(e) => Err(e),
+        },
+    }
+}
+
+#[cfg(all(feature = "opencv-face-detector", feature = "embedded-models"))]
+fn opencv_detector(params: YuNetParams) -> Result<Box<dyn FaceDetectorBackend>, YuNetError> {
+    Ok(Box::new(OpenCvYuNetDetector::new(params)?))
+}
+
+#[cfg(not(feature = "opencv-face-detector"))]
+fn opencv_detector(_params: YuNetParams) -> Result<Box<dyn FaceDetectorBackend>, YuNetError> {
+    Err(YuNetError::BackendUnavailable(
+        "built without the opencv-face-detector feature".to_string(),
+    ))
+}
+
+#[cfg(all(feature = "opencv-face-detector", not(feature = "embedded-models")))]
+fn opencv_detector(_params: YuNetParams) -> Result<Box<dyn FaceDetectorBackend>, YuNetError> {
+    Err(YuNetError::BackendUnavailable(
+        "built without the embedded-models feature".to_string(),
+    ))
+}
+
+/// Convert one `FaceDetectorYN` output row into a detection
+///
+/// Rows hold `[x, y, w, h, right eye, left eye, nose tip, right mouth corner,
+/// left mouth corner, score]` with each landmark as an `(x, y)` pair.
+pub fn detection_from_row(row: &[f32]) -> Option<FaceDetection> {
+    if row.len() < FACE_ROW_LEN {
+        return None;
+    }
+
+    let bbox = Rect::new(row[0] as i32, row[1] as i32, row[2] as i32, row[3] as i32);
+    let landmarks = row[4..14]
+        .chunks_exact(2)
+        .map(|point| Point::new(point[0] as i32, point[1] as i32))
+        .collect();
+
+    Some(FaceDetection {
+        bbox,
+        confidence: row[14],
+        landmarks,
+    })
+}
+
+/// Convert a `FaceDetectorYN` output Mat (N x 15, CV_32F) into detections
+pub fn detections_from_mat(faces: &Mat) -> Result<Vec<FaceDetection>, YuNetError> {
+    let mut detections = Vec::with_capacity(faces.rows().max(0) as usize);
+    for row in 0..faces.rows() {
+        let values = faces.at_row::<f32>(row).map_err(|_| YuNetError::InvalidOutput)?;
+        detections.push(detection_from_row(values).ok_or(YuNetError::InvalidOutput)?);
+    }
+    Ok(detections)
+}
+
+/// YuNet through OpenCV's `FaceDetectorYN`, without ONNX Runtime
+#[cfg(feature = "opencv-face-detector")]
+pub struct OpenCvYuNetDetector {
+    detector: opencv::core::Ptr<opencv::objdetect::FaceDetectorYN>,
+    input_size: opencv::core::Size,
+    model_info: ModelInfo,
+}
+
+#[cfg(feature = "opencv-face-detector")]
+impl OpenCvYuNetDetector {
+    /// Create a detector from the embedded YuNet model
+    #[cfg(feature = "embedded-models")]
+    pub fn new(params: YuNetParams) -> Result<Self, YuNetError> {
+        Self::from_bytes(crate::YUNET_MODEL_BYTES, params)
+    }
+
+    /// Create a detector from model bytes
+    ///
+    /// `FaceDetectorYN` loads models from disk, so the bytes are written to a
+    /// temporary file that is removed once the network is loaded.
+    pub fn from_bytes(model_bytes: &[u8], params: YuNetParams) -> Result<Self, YuNetError> {
+        let path = std::env::temp_dir().join(format!("spectre_yunet_{}.onnx", std::process::id()));
+        std::fs::write(&path, model_bytes).map_err(|e| YuNetError::SessionCreation(e.to_string()))?;
+        let created = Self::load(&path.to_string_lossy(), crate::yunet::bytes_model_info(model_bytes), params);
+        let _ = std::fs::remove_file(&path);
+        created
+    }
+
+    /// Create a detector from a model file, described by `model_info` if given
+    pub fn from_file(model_path: &str, model_info: Option<ModelInfo>, params: YuNetParams) -> Result<Self, YuNetError> {
+        let model_info = ModelInfo::for_file(model_path, model_info)?;
+        Self::load(model_path, model_info, params)
+    }
+
+    fn load(model_path: &str, model_info: ModelInfo, params: YuNetParams) -> Result<Self, YuNetError> {
+        let input_size = params.input_size;
+        let detector = opencv::objdetect::FaceDetectorYN::create(
+            model_path,
+            "",
+            input_size,
+            params.confidence_threshold,
+            params.nms_threshold,
+            5000,
+            0,
+            0,
+        )
+        .map_err(|e| YuNetError::SessionCreation(e.to_string()))?;
+
+        Ok(Self {
+            detector,
+            input_size,
+            model_info,
+        })
+    }
+}
+
+#[cfg(feature = "opencv-face-detector")]
+impl FaceDetectorBackend for OpenCvYuNetDetector {
+    fn name(&self) -> &'static str {
+        "opencv"
+    }
+
+    fn model_info(&self) -> &ModelInfo {
+        &self.model_info
+    }
+
+    fn detect_faces(&mut self, image: &Mat) -> Result<Vec<FaceDetection>, YuNetError> {
+        // Detect at full resolution so coordinates come back in image space
+        let size = image.size().map_err(|e| YuNetError::Preprocessing(e.to_string()))?;
+        if size != self.input_size {
+            self.detector
+                .set_input_size(size)
+                .map_err(|e| YuNetError::Preprocessing(e.to_string()))?;
+            self.input_size = size;
+        }
+
+        let mut faces = Mat::default();
+        self.detector
+            .detect(image, &mut faces)
+            .map_err(|e| YuNetError::Inference(e.to_string()))?;
+
+        detections_from_mat(&faces)
+    }
+
+    fn set_confidence_threshold(&mut self, threshold: f32) {
+        if let Err(e) = self.detector.set_score_threshold(threshold) {
+            tracing::warn!("Failed to set the face confidence threshold: {}", e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ROW: [f32; FACE_ROW_LEN] = [
+        100.4, 50.9, 80.0, 96.0, // box
+        120.0, 80.0, 160.0, 81.0, // eyes
+        140.0, 100.0, // nose tip
+        125.0, 120.0, 155.0, 121.0, // mouth corners
+        0.92, // score
+    ];
+
+    #[test]
+    fn test_detection_from_row() {
+        let detection = detection_from_row(&ROW).unwrap();
+
+        assert_eq!(detection.bbox, Rect::new(100, 50, 80, 96));
+        assert_eq!(detection.confidence, 0.92);
+        assert_eq!(
+            detection.landmarks,
+            vec![
+                Point::new(120, 80),
+                Point::new(160, 81),
+                Point::new(140, 100),
+                Point::new(125, 120),
+                Point::new(155, 121),
+            ]
+        );
+        assert!(detection_from_row(&ROW[..14]).is_none());
+    }
+
+    #[test]
+    fn test_detections_from_mat() {
+        let mut second = ROW;
+        second[0] = 300.0;
+        second[14] = 0.7;
+        let faces = Mat::from_slice_2d(&[ROW, second]).unwrap();
+
+        let detections = detections_from_mat(&faces).unwrap();
+        assert_eq!(detections.len(), 2);
+        assert_eq!(detections[1].bbox.x, 300);
+        assert_eq!(detections[1].confidence, 0.7);
+
+        assert!(detections_from_mat(&Mat::default()).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_face_detector_kind_parsing() {
+        assert_eq!("opencv".parse::<FaceDetectorKind>(), Ok(FaceDetectorKind::OpenCv));
+        assert_eq!("ONNXRuntime".parse::<FaceDetectorKind>(), Ok(FaceDetectorKind::Ort));
+        assert_eq!(FaceDetectorKind::default(), FaceDetectorKind::Auto);
+        assert!("haar".parse::<FaceDetectorKind>().is_err());
+    }
+}
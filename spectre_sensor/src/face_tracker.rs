@@ -0,0 +1,511 @@
+//! Primary face tracking across frames
+//!
+//! With two people in view the most confident detection can change hands
+//! from one frame to the next, and every change mixes one person's emotion
+//! into the other's fear score and calibration. The tracker associates
+//! detections with the previous frame's faces by overlap (or, for a fast
+//! move, centroid distance), so each face keeps a track id, and scores one
+//! primary subject. A different face takes over only after winning the
+//! primary policy for `switch_frames` frames in a row; while the primary is
+//! briefly missed, the frame is reported without a face rather than scored
+//! on someone else.
+//!
+//! Faces move little from one frame to the next, so with a detection
+//! interval above 1 the tracker carries the primary face over between
+//! detections instead of running the detector on every frame. A carried-over
+//! face that scores far less confidently than it did when last detected
+//! is dropped, and the frame detected after all.
+
+use crate::yunet::FaceDetection;
+use opencv::core::{Rect, Size};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+/// How the primary subject is chosen among the faces in view
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PrimaryFacePolicy {
+    /// The largest face, usually the one nearest the camera
+    #[default]
+    Largest,
+    /// The face nearest the center of the frame
+    Central,
+    /// Whoever was scored first, for as long as their track lasts
+    Sticky,
+}
+
+impl FromStr for PrimaryFacePolicy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "largest" => Ok(Self::Largest),
+            "central" | "center" => Ok(Self::Central),
+            "sticky" => Ok(Self::Sticky),
+            other => Err(format!("Unknown primary face policy: {}", other)),
+        }
+    }
+}
+
+impl fmt::Display for PrimaryFacePolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Largest => "largest",
+            Self::Central => "central",
+            Self::Sticky => "sticky",
+        })
+    }
+}
+
+/// Face tracking configuration
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FaceTrackerConfig {
+    /// How the primary subject is chosen
+    pub policy: PrimaryFacePolicy,
+    /// Overlap at which a detection continues a track
+    pub min_iou: f32,
+    /// Centroid distance, in face widths, at which a detection without
+    /// overlap still continues a track
+    pub max_centroid_distance: f32,
+    /// Frames a track survives without a detection
+    pub max_missed_frames: u32,
+    /// Consecutive frames another face must win the policy before it
+    /// becomes the primary
+    pub switch_frames: u32,
+}
+
+impl Default for FaceTrackerConfig {
+    fn default() -> Self {
+        Self {
+            policy: PrimaryFacePolicy::default(),
+            min_iou: 0.3,
+            max_centroid_distance: 0.5,
+            max_missed_frames: 5, // About 170ms at 30 FPS
+            switch_frames: 15,
+        }
+    }
+}
+
+impl FaceTrackerConfig {
+    /// Validate the configuration
+    pub fn validate(&self) -> Result<(), String> {
+        if !(self.min_iou > 0.0 && self.min_iou <= 1.0) {
+            return Err("Face tracking IoU must be in (0, 1]".to_string());
+        }
+        if !(self.max_centroid_distance.is_finite() && self.max_centroid_distance >= 0.0) {
+            return Err("Face tracking centroid distance cannot be negative".to_string());
+        }
+        if self.switch_frames == 0 {
+            return Err("Face tracking switch frames must be at least 1".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// Carried-over faces are cropped this much wider than their box on every
+/// side, as a fraction of its size, so a face that moved stays in the crop
+pub const CARRY_OVER_MARGIN: f32 = 0.1;
+
+/// A carried-over face scoring below this fraction of the confidence it had
+/// when last detected is detected again
+pub const REDETECT_CONFIDENCE_RATIO: f32 = 0.5;
+
+/// The face a frame is scored on
+#[derive(Debug, Clone)]
+pub struct PrimaryFace {
+    pub detection: FaceDetection,
+    /// Stable across frames for as long as the face is tracked
+    pub track_id: u32,
+    /// Faces detected in the frame, the primary included
+    pub faces_seen: u32,
+}
+
+#[derive(Debug, Clone)]
+struct Track {
+    id: u32,
+    bbox: Rect,
+    /// Frames since the last matching detection
+    missed: u32,
+}
+
+/// The last detected primary face, reused until the next detection
+#[derive(Debug, Clone)]
+struct CarriedFace {
+    face: PrimaryFace,
+    /// Frames it has been carried over since it was detected
+    frames: u32,
+    /// Confidence of the frame it was detected in, once scored
+    confidence: Option<f32>,
+}
+
+/// Associates detections across frames and picks the primary subject
+#[derive(Debug, Clone)]
+pub struct FaceTracker {
+    config: FaceTrackerConfig,
+    tracks: Vec<Track>,
+    next_id: u32,
+    primary: Option<u32>,
+    /// Track winning the policy over the primary, and for how many frames
+    challenger: Option<(u32, u32)>,
+    /// Run the detector on every nth frame
+    detection_interval: u32,
+    carried: Option<CarriedFace>,
+}
+
+impl FaceTracker {
+    /// Create a tracker
+    pub fn new(config: FaceTrackerConfig) -> Self {
+        Self {
+            config,
+            tracks: Vec::new(),
+            next_id: 1,
+            primary: None,
+            challenger: None,
+            detection_interval: 1,
+            carried: None,
+        }
+    }
+
+    /// Run the detector on every `interval`th frame, carrying the primary
+    /// face over in between
+    pub fn with_detection_interval(mut self, interval: u32) -> Self {
+        self.detection_interval = interval.max(1);
+        self
+    }
+
+    /// Tracking configuration
+    pub fn config(&self) -> &FaceTrackerConfig {
+        &self.config
+    }
+
+    /// Track id of the primary subject
+    pub fn primary(&self) -> Option<u32> {
+        self.primary
+    }
+
+    /// Forget every track, e.g. after the camera was reopened
+    pub fn reset(&mut self) {
+        self.tracks.clear();
+        self.primary = None;
+        self.challenger = None;
+        self.carried = None;
+    }
+
+    /// The primary face to score this frame without running the detector,
+    /// or `None` when a detection is due
+    ///
+    /// Only a face found by the last detection is carried over; a frame
+    /// without one is always detected.
+    pub fn carry_over(&mut self) -> Option<PrimaryFace> {
+        let carried = self.carried.as_mut()?;
+        if carried.frames + 1 >= self.detection_interval {
+            return None;
+        }
+        carried.frames += 1;
+        Some(carried.face.clone())
+    }
+
+    /// Whether the last frame's primary face was carried over rather than detected
+    pub fn carried_over(&self) -> bool {
+        self.carried.as_ref().is_some_and(|carried| carried.frames > 0)
+    }
+
+    /// Note the confidence a frame was scored with, returning `false` when
+    /// a carried-over face scored so poorly that the frame needs detecting
+    ///
+    /// The face is then no longer carried over.
+    pub fn scored(&mut self, confidence: f32) -> bool {
+        let Some(carried) = self.carried.as_mut() else {
+            return true;
+        };
+        match carried.confidence {
+            Some(detected) if carried.frames > 0 && confidence < detected * REDETECT_CONFIDENCE_RATIO => {
+                tracing::debug!("Carried-over face scored {:.2} against {:.2}, detecting again", confidence, detected);
+                self.carried = None;
+                false
+            }
+            Some(_) => true,
+            None => {
+                carried.confidence = Some(confidence);
+                true
+            }
+        }
+    }
+
+    /// Add a frame's detections and return the face to score, if any
+    ///
+    /// `None` either means no face was detected or that the primary subject
+    /// is momentarily missing.
+    pub fn update(&mut self, detections: Vec<FaceDetection>, frame: Size) -> Option<PrimaryFace> {
+        let faces_seen = detections.len() as u32;
+        let ids = self.associate(&detections);
+
+        self.tracks.retain(|track| track.missed <= self.config.max_missed_frames);
+        if self.primary.is_some_and(|primary| !self.tracks.iter().any(|track| track.id == primary)) {
+            self.primary = None;
+            self.challenger = None;
+        }
+
+        let best = ids
+            .iter()
+            .zip(&detections)
+            .max_by(|(_, a), (_, b)| self.rank(a, frame).total_cmp(&self.rank(b, frame)))
+            .map(|(&id, _)| id);
+        self.primary = match (self.primary, best) {
+            (None, best) => best,
+            (Some(primary), _) if !ids.contains(&primary) => Some(primary),
+            (Some(primary), Some(best)) if best != primary && self.config.policy != PrimaryFacePolicy::Sticky => {
+                let streak = match self.challenger {
+                    Some((id, streak)) if id == best => streak + 1,
+                    _ => 1,
+                };
+                if streak >= self.config.switch_frames {
+                    self.challenger = None;
+                    Some(best)
+                } else {
+                    self.challenger = Some((best, streak));
+                    Some(primary)
+                }
+            }
+            (primary, _) => {
+                self.challenger = None;
+                primary
+            }
+        };
+
+        let face = self.primary.and_then(|primary| {
+            let index = ids.iter().position(|&id| id == primary)?;
+            Some(PrimaryFace {
+                detection: detections.into_iter().nth(index)?,
+                track_id: primary,
+                faces_seen,
+            })
+        });
+        self.carried = face.clone().map(|face| CarriedFace { face, frames: 0, confidence: None });
+        face
+    }
+
+    /// Match detections to tracks, best overlap first, returning each
+    /// detection's track id; unmatched detections start new tracks
+    fn associate(&mut self, detections: &[FaceDetection]) -> Vec<u32> {
+        let mut pairs = Vec::new();
+        for (t, track) in self.tracks.iter().enumerate() {
+            for (d, detection) in detections.iter().enumerate() {
+                let overlap = iou(&track.bbox, &detection.bbox);
+                let distance = centroid_distance(&track.bbox, &detection.bbox);
+                if overlap >= self.config.min_iou || distance <= self.config.max_centroid_distance {
+                    pairs.push((overlap, distance, t, d));
+                }
+            }
+        }
+        pairs.sort_by(|a, b| b.0.total_cmp(&a.0).then(a.1.total_cmp(&b.1)));
+
+        let mut ids = vec![None; detections.len()];
+        let mut matched = vec![false; self.tracks.len()];
+        for (_, _, t, d) in pairs {
+            if matched[t] || ids[d].is_some() {
+                continue;
+            }
+            matched[t] = true;
+            ids[d] = Some(self.tracks[t].id);
+            self.tracks[t].bbox = detections[d].bbox;
+            self.tracks[t].missed = 0;
+        }
+        for (track, matched) in self.tracks.iter_mut().zip(matched) {
+            if !matched {
+                track.missed += 1;
+            }
+        }
+
+        ids.into_iter()
+            .zip(detections)
+            .map(|(id, detection)| {
+                id.unwrap_or_else(|| {
+                    let id = self.next_id;
+                    self.next_id += 1;
+                    self.tracks.push(Track { id, bbox: detection.bbox, missed: 0 });
+                    id
+                })
+            })
+            .collect()
+    }
+
+    /// Policy score of a detection; higher wins
+    fn rank(&self, detection: &FaceDetection, frame: Size) -> f32 {
+        let bbox = detection.bbox;
+        match self.config.policy {
+            PrimaryFacePolicy::Largest | PrimaryFacePolicy::Sticky => bbox.area() as f32,
+            PrimaryFacePolicy::Central => {
+                let (x, y) = center(&bbox);
+                -(x - frame.width as f32 / 2.0).hypot(y - frame.height as f32 / 2.0)
+            }
+        }
+    }
+}
+
+fn center(rect: &Rect) -> (f32, f32) {
+    (rect.x as f32 + rect.width as f32 / 2.0, rect.y as f32 + rect.height as f32 / 2.0)
+}
+
+/// `rect` grown by `margin` of its size on every side, within `frame`
+pub fn expand_rect(rect: &Rect, margin: f32, frame: Size) -> Rect {
+    let (dx, dy) = ((rect.width as f32 * margin) as i32, (rect.height as f32 * margin) as i32);
+    let (x1, y1) = ((rect.x - dx).max(0), (rect.y - dy).max(0));
+    let (x2, y2) = ((rect.x + rect.width + dx).min(frame.width), (rect.y + rect.height + dy).min(frame.height));
+    Rect::new(x1, y1, (x2 - x1).max(0), (y2 - y1).max(0))
+}
+
+/// Intersection over union of two boxes
+fn iou(a: &Rect, b: &Rect) -> f32 {
+    let width = (a.x + a.width).min(b.x + b.width) - a.x.max(b.x);
+    let height = (a.y + a.height).min(b.y + b.height) - a.y.max(b.y);
+    if width <= 0 || height <= 0 {
+        return 0.0;
+    }
+    let intersection = (width * height) as f32;
+    intersection / ((a.area() + b.area()) as f32 - intersection)
+}
+
+/// Distance between the box centers, in widths of the wider box
+fn centroid_distance(a: &Rect, b: &Rect) -> f32 {
+    let ((ax, ay), (bx, by)) = (center(a), center(b));
+    (ax - bx).hypot(ay - by) / a.width.max(b.width).max(1) as f32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FRAME: Size = Size { width: 640, height: 480 };
+
+    fn face(x: i32, y: i32, size: i32, confidence: f32) -> FaceDetection {
+        FaceDetection {
+            bbox: Rect::new(x, y, size, size),
+            confidence,
+            landmarks: Vec::new(),
+        }
+    }
+
+    fn tracker(policy: PrimaryFacePolicy) -> FaceTracker {
+        FaceTracker::new(FaceTrackerConfig { policy, switch_frames: 3, ..FaceTrackerConfig::default() })
+    }
+
+    #[test]
+    fn test_iou() {
+        let a = Rect::new(0, 0, 10, 10);
+        assert_eq!(iou(&a, &a), 1.0);
+        assert_eq!(iou(&a, &Rect::new(20, 0, 10, 10)), 0.0);
+        assert!((iou(&a, &Rect::new(5, 0, 10, 10)) - 50.0 / 150.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_swapping_confidence_does_not_flap() {
+        let mut tracker = tracker(PrimaryFacePolicy::Largest);
+        let mut primaries = Vec::new();
+        for frame in 0..20 {
+            // Two people, drifting a little, trading the most confident detection
+            let (near, far) = if frame % 2 == 0 { (0.6, 0.9) } else { (0.9, 0.6) };
+            let detections = vec![face(100 + frame, 100, 120, near), face(400 - frame, 120, 80, far)];
+            let primary = tracker.update(detections, FRAME).unwrap();
+            assert_eq!(primary.faces_seen, 2);
+            primaries.push((primary.track_id, primary.detection.bbox.width));
+        }
+        // The larger face keeps its track the whole time
+        assert!(primaries.iter().all(|&primary| primary == (1, 120)), "{:?}", primaries);
+    }
+
+    #[test]
+    fn test_switch_needs_consecutive_wins() {
+        let mut tracker = tracker(PrimaryFacePolicy::Largest);
+        let small = |size| vec![face(100, 100, 100, 0.9), face(400, 100, size, 0.9)];
+        assert_eq!(tracker.update(small(60), FRAME).unwrap().track_id, 1);
+
+        // The second face grows past the first; a one-frame lead is not enough
+        assert_eq!(tracker.update(small(140), FRAME).unwrap().track_id, 1);
+        assert_eq!(tracker.update(small(60), FRAME).unwrap().track_id, 1);
+        assert_eq!(tracker.update(small(140), FRAME).unwrap().track_id, 1);
+        assert_eq!(tracker.update(small(140), FRAME).unwrap().track_id, 1);
+        assert_eq!(tracker.update(small(140), FRAME).unwrap().track_id, 2);
+        assert_eq!(tracker.primary(), Some(2));
+    }
+
+    #[test]
+    fn test_missing_primary_is_not_replaced_until_its_track_expires() {
+        let mut tracker = tracker(PrimaryFacePolicy::Sticky);
+        tracker.update(vec![face(100, 100, 100, 0.9)], FRAME).unwrap();
+
+        // The subject turns away while someone else is in view
+        for _ in 0..FaceTrackerConfig::default().max_missed_frames {
+            assert!(tracker.update(vec![face(400, 100, 140, 0.9)], FRAME).is_none());
+        }
+        let primary = tracker.update(vec![face(400, 100, 140, 0.9)], FRAME).unwrap();
+        assert_eq!(primary.track_id, 2);
+
+        // Sticky keeps the new subject even when the first returns larger
+        for _ in 0..10 {
+            let primary = tracker.update(vec![face(100, 100, 200, 0.9), face(400, 100, 140, 0.9)], FRAME);
+            assert_eq!(primary.unwrap().track_id, 2);
+        }
+    }
+
+    #[test]
+    fn test_central_policy_and_fast_moves() {
+        let mut tracker = tracker(PrimaryFacePolicy::Central);
+        let primary = tracker.update(vec![face(20, 20, 150, 0.9), face(290, 210, 60, 0.9)], FRAME).unwrap();
+        assert_eq!((primary.track_id, primary.detection.bbox.width), (2, 60));
+
+        // A fast move with little overlap still continues the track
+        let primary = tracker.update(vec![face(20, 20, 150, 0.9), face(315, 225, 60, 0.9)], FRAME).unwrap();
+        assert_eq!(primary.track_id, 2);
+
+        tracker.reset();
+        assert!(tracker.update(Vec::new(), FRAME).is_none());
+        assert_eq!(tracker.update(vec![face(290, 210, 60, 0.9)], FRAME).unwrap().track_id, 3);
+    }
+
+    #[test]
+    fn test_carry_over_between_detections() {
+        let mut tracker = tracker(PrimaryFacePolicy::Largest).with_detection_interval(3);
+        assert!(tracker.carry_over().is_none());
+        let detected = tracker.update(vec![face(100, 100, 100, 0.9)], FRAME).unwrap();
+        assert!(tracker.scored(0.8));
+
+        // Two frames reuse the detection, the third is detected again
+        for _ in 0..2 {
+            let carried = tracker.carry_over().unwrap();
+            assert_eq!((carried.track_id, carried.detection.bbox), (detected.track_id, detected.detection.bbox));
+            assert!(tracker.carried_over());
+            assert!(tracker.scored(0.7));
+        }
+        assert!(tracker.carry_over().is_none());
+        tracker.update(vec![face(104, 100, 100, 0.9)], FRAME).unwrap();
+        assert!(!tracker.carried_over());
+
+        // A carried-over face scoring at half its detected confidence is dropped
+        assert!(tracker.scored(0.8));
+        assert!(tracker.carry_over().is_some());
+        assert!(!tracker.scored(0.3));
+        assert!(tracker.carry_over().is_none());
+
+        // Nothing to carry over without a face
+        assert!(tracker.update(Vec::new(), FRAME).is_none());
+        assert!(tracker.carry_over().is_none());
+    }
+
+    #[test]
+    fn test_expand_rect_stays_in_frame() {
+        assert_eq!(expand_rect(&Rect::new(100, 100, 100, 50), 0.1, FRAME), Rect::new(90, 95, 120, 60));
+        assert_eq!(expand_rect(&Rect::new(0, 440, 100, 40), 0.1, FRAME), Rect::new(0, 436, 110, 44));
+    }
+
+    #[test]
+    fn test_config_validation() {
+        assert!(FaceTrackerConfig::default().validate().is_ok());
+        assert!(FaceTrackerConfig { min_iou: 0.0, ..FaceTrackerConfig::default() }.validate().is_err());
+        assert!(FaceTrackerConfig { switch_frames: 0, ..FaceTrackerConfig::default() }.validate().is_err());
+        assert_eq!("center".parse(), Ok(PrimaryFacePolicy::Central));
+        assert!("loudest".parse::<PrimaryFacePolicy>().is_err());
+    }
+}
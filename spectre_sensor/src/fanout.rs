@@ -0,0 +1,358 @@
+//! Frame fan-out for several in-process consumers of one sensor
+//!
+//! `async_channel::Receiver` is a competing-consumer queue: cloning it
+//! interleaves frames between the clones instead of duplicating them.
+//! [`FrameFanout`] takes ownership of the sensor's receiver and delivers every
+//! frame to every [`FrameSubscriber`].
+//!
+//! There is no forwarding task. Whichever subscriber asks first pulls pending
+//! frames from the sensor into a shared ring, so a frame sent before
+//! `try_recv` is visible to it immediately, which keeps Bevy systems
+//! deterministic. Each subscriber reads the ring at its own pace; one that
+//! falls more than the ring capacity behind skips to the latest frame and
+//! counts the frames it missed.
+
+use crate::types::FearFrame;
+use async_channel::Receiver;
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+use tokio::sync::Notify;
+
+/// Frames kept for slower subscribers
+pub const DEFAULT_FANOUT_CAPACITY: usize = 64;
+
+/// Shares one frame receiver between any number of subscribers
+pub struct FrameFanout<T = FearFrame> {
+    shared: Arc<Shared<T>>,
+}
+
+/// One consumer's view of a [`FrameFanout`]
+///
+/// Dropping a subscriber has no effect on the others.
+pub struct FrameSubscriber<T = FearFrame> {
+    shared: Arc<Shared<T>>,
+    /// Sequence number of the next frame to deliver
+    next: u64,
+    /// Frames skipped after falling behind
+    lagged: u64,
+}
+
+struct Shared<T> {
+    source: Receiver<T>,
+    ring: Mutex<Ring<T>>,
+    /// Held while pulling from `source` so frames enter the ring in order
+    pump: tokio::sync::Mutex<()>,
+    /// Wakes async subscribers when frames enter the ring
+    pushed: Notify,
+}
+
+struct Ring<T> {
+    frames: VecDeque<T>,
+    capacity: usize,
+    /// Sequence number the next pushed frame will get
+    next_seq: u64,
+}
+
+impl<T> Ring<T> {
+    fn push(&mut self, frame: T) {
+        if self.frames.len() == self.capacity {
+            self.frames.pop_front();
+        }
+        self.frames.push_back(frame);
+        self.next_seq += 1;
+    }
+
+    /// Sequence number of the oldest frame still in the ring
+    fn first_seq(&self) -> u64 {
+        self.next_seq - self.frames.len() as u64
+    }
+}
+
+impl<T: Clone + Send> FrameFanout<T> {
+    /// Take ownership of `source` with the default ring capacity
+    pub fn new(source: Receiver<T>) -> Self {
+        Self::with_capacity(source, DEFAULT_FANOUT_CAPACITY)
+    }
+
+    /// Take ownership of `source`, keeping up to `capacity` frames for slow subscribers
+    pub fn with_capacity(source: Receiver<T>, capacity: usize) -> Self {
+        let capacity = capacity.max(1);
+        Self {
+            shared: Arc::new(Shared {
+                source,
+                ring: Mutex::new(Ring {
+                    frames: VecDeque::with_capacity(capacity),
+                    capacity,
+                    next_seq: 0,
+                }),
+                pump: tokio::sync::Mutex::new(()),
+                pushed: Notify::new(),
+            }),
+        }
+    }
+
+    /// Subscribe to frames sent from now on
+    pub fn subscribe(&self) -> FrameSubscriber<T> {
+        FrameSubscriber {
+            shared: Arc::clone(&self.shared),
+            next: self.shared.ring.lock().unwrap().next_seq,
+            lagged: 0,
+        }
+    }
+
+    /// Whether the sensor has stopped sending frames
+    pub fn is_closed(&self) -> bool {
+        self.shared.source.is_closed()
+    }
+
+    /// Whether `other` shares this fanout's receiver
+    pub fn same_source(&self, other: &FrameFanout<T>) -> bool {
+        Arc::ptr_eq(&self.shared, &other.shared)
+    }
+}
+
+impl<T> Clone for FrameFanout<T> {
+    fn clone(&self) -> Self {
+        Self {
+            shared: Arc::clone(&self.shared),
+        }
+    }
+}
+
+impl<T: Clone + Send> Shared<T> {
+    /// Move every pending source frame into the ring
+    ///
+    /// Callers must hold `pump`.
+    fn pull_pending(&self) {
+        let mut pulled = false;
+        while let Ok(frame) = self.source.try_recv() {
+            self.ring.lock().unwrap().push(frame);
+            pulled = true;
+        }
+        if pulled {
+            self.pushed.notify_waiters();
+        }
+    }
+
+    fn push(&self, frame: T) {
+        self.ring.lock().unwrap().push(frame);
+        self.pushed.notify_waiters();
+    }
+}
+
+impl<T: Clone + Send> FrameSubscriber<T> {
+    /// Next frame if one is available, without waiting
+    pub fn try_recv(&mut self) -> Option<T> {
+        // Another subscriber holding the pump will push what it pulls
+        if let Ok(_pump) = self.shared.pump.try_lock() {
+            self.shared.pull_pending();
+        }
+        self.read()
+    }
+
+    /// Wait for the next frame; `None` once the sensor has stopped and every frame was read
+    pub async fn recv(&mut self) -> Option<T> {
+        let shared = Arc::clone(&self.shared);
+        loop {
+            // Register for wakeups before checking, so a push in between is not missed
+            let pushed = shared.pushed.notified();
+            tokio::pin!(pushed);
+            pushed.as_mut().enable();
+
+            if let Some(frame) = self.try_recv() {
+                return Some(frame);
+            }
+
+            if shared.source.is_closed() && shared.source.is_empty() {
+                // A pump holder may still be pushing the last frame
+                let _pump = shared.pump.lock().await;
+                return self.read();
+            }
+
+            tokio::select! {
+                _pump = shared.pump.lock() => {
+                    if let Ok(frame) = shared.source.recv().await {
+                        shared.push(frame);
+                    }
+                }
+                _ = &mut pushed => {}
+            }
+        }
+    }
+
+    /// Frames skipped because this subscriber fell behind
+    pub fn lagged(&self) -> u64 {
+        self.lagged
+    }
+
+    fn read(&mut self) -> Option<T> {
+        let ring = self.shared.ring.lock().unwrap();
+        if self.next >= ring.next_seq {
+            return None;
+        }
+
+        let first = ring.first_seq();
+        if self.next < first {
+            // Fell behind the ring: skip to the latest frame
+            self.lagged += ring.next_seq - 1 - self.next;
+            self.next = ring.next_seq;
+            return ring.frames.back().cloned();
+        }
+
+        let frame = ring.frames[(self.next - first) as usize].clone();
+        self.next += 1;
+        Some(frame)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn drain(subscriber: &mut FrameSubscriber<u32>, into: &mut Vec<u32>) {
+        while let Some(frame) = subscriber.try_recv() {
+            into.push(frame);
+        }
+    }
+
+    #[test]
+    fn test_every_subscriber_sees_every_frame() {
+        let (sender, receiver) = async_channel::unbounded();
+        let fanout = FrameFanout::new(receiver);
+        let mut subscribers = [fanout.subscribe(), fanout.subscribe(), fanout.subscribe()];
+        let mut received = [Vec::new(), Vec::new(), Vec::new()];
+
+        // Read after every frame, every third frame and once at the end
+        for frame in 0..50 {
+            sender.try_send(frame).unwrap();
+            drain(&mut subscribers[0], &mut received[0]);
+            if frame % 3 == 0 {
+                drain(&mut subscribers[1], &mut received[1]);
+            }
+        }
+        drain(&mut subscribers[1], &mut received[1]);
+        drain(&mut subscribers[2], &mut received[2]);
+
+        let expected: Vec<u32> = (0..50).collect();
+        for (subscriber, frames) in subscribers.iter().zip(&received) {
+            assert_eq!(*frames, expected);
+            assert_eq!(subscriber.lagged(), 0);
+        }
+    }
+
+    #[test]
+    fn test_slow_subscriber_skips_to_latest() {
+        let (sender, receiver) = async_channel::unbounded();
+        let fanout = FrameFanout::with_capacity(receiver, 8);
+        let mut fast = fanout.subscribe();
+        let mut slow = fanout.subscribe();
+        let mut received = Vec::new();
+
+        for frame in 0..20 {
+            sender.try_send(frame).unwrap();
+            drain(&mut fast, &mut received);
+        }
+        assert_eq!(received, (0..20).collect::<Vec<_>>());
+        assert_eq!(fast.lagged(), 0);
+
+        // Frames 0..19 are gone from the ring except the last eight
+        assert_eq!(slow.try_recv(), Some(19));
+        assert_eq!(slow.lagged(), 19);
+        assert_eq!(slow.try_recv(), None);
+
+        // Caught up again
+        sender.try_send(20).unwrap();
+        assert_eq!(slow.try_recv(), Some(20));
+        assert_eq!(slow.lagged(), 19);
+    }
+
+    #[test]
+    fn test_dropped_subscriber_does_not_affect_others() {
+        let (sender, receiver) = async_channel::unbounded();
+        let fanout = FrameFanout::new(receiver);
+        let mut first = fanout.subscribe();
+        let second = fanout.subscribe();
+        let mut third = fanout.subscribe();
+        let (mut a, mut b) = (Vec::new(), Vec::new());
+
+        for frame in 0..5 {
+            sender.try_send(frame).unwrap();
+        }
+        drop(second);
+        drain(&mut first, &mut a);
+        for frame in 5..10 {
+            sender.try_send(frame).unwrap();
+        }
+
+        // Subscribers keep the stream alive without the fanout handle
+        drop(fanout);
+        drain(&mut first, &mut a);
+        drain(&mut third, &mut b);
+        assert_eq!(a, (0..10).collect::<Vec<_>>());
+        assert_eq!(b, (0..10).collect::<Vec<_>>());
+        assert!(!sender.is_closed());
+
+        // The sensor sees a closed channel once everyone is gone
+        drop(first);
+        drop(third);
+        assert!(sender.is_closed());
+    }
+
+    #[test]
+    fn test_late_subscriber_starts_at_next_frame() {
+        let (sender, receiver) = async_channel::unbounded();
+        let fanout = FrameFanout::new(receiver);
+        let mut early = fanout.subscribe();
+
+        sender.try_send(1).unwrap();
+        assert_eq!(early.try_recv(), Some(1));
+
+        let mut late = fanout.subscribe();
+        sender.try_send(2).unwrap();
+        assert_eq!(late.try_recv(), Some(2));
+        assert_eq!(early.try_recv(), Some(2));
+    }
+
+    #[tokio::test]
+    async fn test_async_subscribers_at_different_speeds() {
+        let (sender, receiver) = async_channel::bounded(4);
+        let fanout = FrameFanout::with_capacity(receiver, 32);
+
+        let tasks: Vec<_> = [Duration::ZERO, Duration::from_millis(3), Duration::from_millis(25)]
+            .into_iter()
+            .map(|delay| {
+                let mut subscriber = fanout.subscribe();
+                tokio::spawn(async move {
+                    let mut received = Vec::new();
+                    while let Some(frame) = subscriber.recv().await {
+                        received.push(frame);
+                        tokio::time::sleep(delay).await;
+                    }
+                    (received, subscriber.lagged())
+                })
+            })
+            .collect();
+        drop(fanout);
+
+        for frame in 0..40u32 {
+            sender.send(frame).await.unwrap();
+            tokio::time::sleep(Duration::from_millis(1)).await;
+        }
+        drop(sender);
+
+        let mut results = Vec::new();
+        for task in tasks {
+            results.push(task.await.unwrap());
+        }
+
+        for (received, lagged) in &results {
+            // Every frame is either delivered in order or counted as skipped
+            assert!(received.windows(2).all(|pair| pair[0] < pair[1]));
+            assert_eq!(received.last(), Some(&39));
+            assert_eq!(received.len() as u64 + lagged, 40);
+        }
+        assert_eq!(results[0], ((0..40).collect(), 0));
+        assert!(results[2].1 > 0, "slow subscriber never lagged");
+    }
+}
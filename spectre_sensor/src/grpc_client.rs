@@ -4,28 +4,42 @@ use crate::proto::{
     sensor_service_client::SensorServiceClient,
     *,
 };
-use tonic::{transport::Channel, Request, Status};
+use crate::transport::SensorTransport;
+use crate::clock_sync::ClockSyncEstimate;
+use crate::delta::{DeltaConfig, FearStreamConsumer};
+use crate::phases::CalibrationPhases;
+use crate::calibration_control::{ACTION_METADATA, PHASE_METADATA};
+use tonic::{metadata::MetadataValue, transport::Channel, Request, Status};
 use futures::StreamExt;
 use std::time::Duration;
 
+pub use tonic::codec::CompressionEncoding;
+
 /// Client wrapper for sensor service
+#[derive(Clone)]
 pub struct SensorClient {
     client: SensorServiceClient<Channel>,
+    auth_token: Option<String>,
 }
 
 impl SensorClient {
-    /// Connect to sensor service via Unix socket (simplified to TCP for now)
-    pub async fn connect_unix(socket_path: &str) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
-        // For now, use TCP instead of Unix socket due to tonic compatibility issues
-        // In production, this would use proper Unix socket support
-        let _ = socket_path; // Suppress unused warning
-        let channel = Channel::from_static("http://127.0.0.1:50051")
-            .connect()
-            .await?;
+    /// Connect to sensor service over any transport
+    pub async fn connect(transport: &SensorTransport) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        let channel = transport.connect().await?;
 
         let client = SensorServiceClient::new(channel);
 
-        Ok(Self { client })
+        Ok(Self { client, auth_token: None })
+    }
+
+    /// Connect to sensor service via Unix socket
+    pub async fn connect_unix(socket_path: &str) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        Self::connect(&SensorTransport::Unix(socket_path.into())).await
+    }
+
+    /// Connect to sensor service via Windows named pipe (`\\.\pipe\<name>`)
+    pub async fn connect_named_pipe(pipe_name: &str) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        Self::connect(&SensorTransport::NamedPipe(pipe_name.to_string())).await
     }
     
     /// Connect to sensor service via TCP
@@ -36,13 +50,43 @@ impl SensorClient {
         
         let client = SensorServiceClient::new(channel);
         
-        Ok(Self { client })
+        Ok(Self { client, auth_token: None })
+    }
+    
+    /// Attach a bearer token to every request
+    pub fn with_auth_token(mut self, token: impl Into<String>) -> Self {
+        self.auth_token = Some(token.into());
+        self
+    }
+    
+    /// Compress requests and ask the daemon to compress responses
+    ///
+    /// Each message is compressed on its own, which pays off for larger
+    /// messages; small stream events can grow.
+    pub fn with_compression(mut self, encoding: CompressionEncoding) -> Self {
+        self.client = self.client.send_compressed(encoding).accept_compressed(encoding);
+        self
+    }
+    
+    /// Wrap a message in a request carrying the auth token, if any
+    fn request<T>(&self, message: T) -> Request<T> {
+        let mut request = Request::new(message);
+        if let Some(token) = &self.auth_token {
+            match format!("Bearer {}", token).parse::<MetadataValue<_>>() {
+                Ok(value) => {
+                    request.metadata_mut().insert("authorization", value);
+                },
+                Err(_) => tracing::warn!("Auth token is not valid header text, sending without it"),
+            }
+        }
+        request
     }
     
     /// Stream all sensor events
     pub async fn stream_events(&mut self) -> Result<impl StreamExt<Item = Result<SensorEvent, Status>>, Status> {
-        let request = Request::new(StreamRequest {
+        let request = self.request(StreamRequest {
             event_types: vec![], // No filters, get all events
+            ..Default::default()
         });
         
         let response = self.client.stream_events(request).await?;
@@ -51,34 +95,64 @@ impl SensorClient {
     
     /// Stream only score events
     pub async fn stream_scores(&mut self) -> Result<impl StreamExt<Item = Result<SensorEvent, Status>>, Status> {
-        let request = Request::new(StreamRequest {
+        let request = self.request(StreamRequest {
             event_types: vec![EventType::Score as i32],
+            ..Default::default()
         });
         
         let response = self.client.stream_events(request).await?;
         Ok(response.into_inner())
     }
     
+    /// Stream all sensor events, with scores delta-encoded on the wire
+    ///
+    /// Full scores are rebuilt before they are returned (see
+    /// [`FearStreamConsumer`]), so the stream reads like `stream_events`.
+    pub async fn stream_events_delta(&mut self, config: DeltaConfig) -> Result<impl StreamExt<Item = Result<SensorEvent, Status>>, Status> {
+        let mut message = StreamRequest::default();
+        config.apply(&mut message);
+        let request = self.request(message);
+        
+        let response = self.client.stream_events(request).await?;
+        Ok(reconstruct_deltas(response.into_inner()))
+    }
+    
     /// Stream only calibration progress events
     pub async fn stream_calibration(&mut self) -> Result<impl StreamExt<Item = Result<SensorEvent, Status>>, Status> {
-        let request = Request::new(StreamRequest {
+        let request = self.request(StreamRequest {
             event_types: vec![EventType::CalibrationProgress as i32],
+            ..Default::default()
         });
         
         let response = self.client.stream_events(request).await?;
         Ok(response.into_inner())
     }
     
+    /// Stream the daemon's calibration phases, starting with the current one
+    ///
+    /// The stream ends when the sensor stops; see [`CalibrationPhases`] for
+    /// waiting on milestones.
+    pub async fn phases(&mut self) -> Result<CalibrationPhases, Status> {
+        let request = self.request(StreamRequest {
+            event_types: vec![EventType::CalibrationProgress as i32],
+            initial_calibration: true,
+            ..Default::default()
+        });
+        
+        let response = self.client.stream_events(request).await?;
+        Ok(CalibrationPhases::from_events(response.into_inner()))
+    }
+    
     /// Get current sensor status
     pub async fn get_status(&mut self) -> Result<StatusResponse, Status> {
-        let request = Request::new(StatusRequest {});
+        let request = self.request(StatusRequest {});
         let response = self.client.get_status(request).await?;
         Ok(response.into_inner())
     }
     
     /// Start calibration
     pub async fn start_calibration(&mut self) -> Result<CalibrationResponse, Status> {
-        let request = Request::new(CalibrationControl {
+        let request = self.request(CalibrationControl {
             action: Some(calibration_control::Action::StartCalibration(true)),
         });
         
@@ -88,7 +162,7 @@ impl SensorClient {
     
     /// Freeze calibration
     pub async fn freeze_calibration(&mut self) -> Result<CalibrationResponse, Status> {
-        let request = Request::new(CalibrationControl {
+        let request = self.request(CalibrationControl {
             action: Some(calibration_control::Action::FreezeCalibration(true)),
         });
         
@@ -98,7 +172,7 @@ impl SensorClient {
     
     /// Unfreeze calibration
     pub async fn unfreeze_calibration(&mut self) -> Result<CalibrationResponse, Status> {
-        let request = Request::new(CalibrationControl {
+        let request = self.request(CalibrationControl {
             action: Some(calibration_control::Action::FreezeCalibration(false)),
         });
         
@@ -108,7 +182,7 @@ impl SensorClient {
     
     /// Reset calibration
     pub async fn reset_calibration(&mut self) -> Result<CalibrationResponse, Status> {
-        let request = Request::new(CalibrationControl {
+        let request = self.request(CalibrationControl {
             action: Some(calibration_control::Action::ResetCalibration(true)),
         });
         
@@ -116,6 +190,149 @@ impl SensorClient {
         Ok(response.into_inner())
     }
     
+    /// Stamp a named marker into the sensor stream
+    pub async fn insert_marker(&mut self, label: impl Into<String>) -> Result<MarkerResponse, Status> {
+        let request = self.request(MarkerRequest {
+            label: label.into(),
+        });
+        
+        let response = self.client.insert_marker(request).await?;
+        Ok(response.into_inner())
+    }
+    
+    /// Run a retention sweep now; `dry_run` overrides the daemon setting when given
+    pub async fn purge_now(&mut self, dry_run: Option<bool>) -> Result<PurgeResponse, Status> {
+        let request = self.request(PurgeRequest { dry_run });
+        
+        let response = self.client.purge_now(request).await?;
+        Ok(response.into_inner())
+    }
+    
+    /// Write a bug report bundle on the daemon's host
+    pub async fn capture_bug_report(&mut self) -> Result<BugReportResponse, Status> {
+        let request = self.request(BugReportRequest {});
+        
+        let response = self.client.capture_bug_report(request).await?;
+        Ok(response.into_inner())
+    }
+    
+    /// Open event streams, with their filters, options and counters
+    pub async fn list_clients(&mut self) -> Result<ListClientsResponse, Status> {
+        let request = self.request(ListClientsRequest {});
+        
+        let response = self.client.list_clients(request).await?;
+        Ok(response.into_inner())
+    }
+    
+    /// Metrics history samples taken between `from_us` and `to_us`, in
+    /// microseconds since Unix epoch; 0 leaves a bound open
+    pub async fn get_metrics_history(&mut self, from_us: u64, to_us: u64) -> Result<MetricsHistoryResponse, Status> {
+        let request = self.request(MetricsHistoryRequest { from_us, to_us });
+        
+        let response = self.client.get_metrics_history(request).await?;
+        Ok(response.into_inner())
+    }
+    
+    /// Name, version, license and hash of every model the daemon runs
+    pub async fn get_model_info(&mut self) -> Result<ModelInfoResponse, Status> {
+        let request = self.request(ModelInfoRequest {});
+        
+        let response = self.client.get_model_info(request).await?;
+        Ok(response.into_inner())
+    }
+    
+    /// Clock synchronization round trip, sharing the caller's current estimate
+    pub async fn ping(
+        &mut self,
+        game_time: Duration,
+        clock_sync: Option<ClockSyncEstimate>,
+    ) -> Result<PingResponse, Status> {
+        let request = self.request(PingRequest {
+            game_time_us: game_time.as_micros() as u64,
+            clock_sync: clock_sync.map(Into::into),
+        });
+        
+        let response = self.client.ping(request).await?;
+        Ok(response.into_inner())
+    }
+    
+    /// Capture and score one frame, waiting up to `timeout` for a face
+    pub async fn measure_once(&mut self, timeout: Duration) -> Result<MeasureResponse, Status> {
+        let request = self.request(MeasureRequest {
+            timeout_ms: timeout.as_millis().min(u32::MAX as u128) as u32,
+        });
+        
+        let response = self.client.measure_once(request).await?;
+        Ok(response.into_inner())
+    }
+    
+    /// Replace the daemon's emotion model with the ONNX file at `path` on its host
+    ///
+    /// `reset_calibration` restarts calibration for the new model. A model
+    /// that fails validation is rejected with `INVALID_ARGUMENT` and the
+    /// current one keeps running.
+    pub async fn swap_emotion_model(&mut self, path: impl Into<String>, reset_calibration: bool) -> Result<SwapModelResponse, Status> {
+        self.swap_model(swap_model_request::Model::Path(path.into()), reset_calibration).await
+    }
+    
+    /// Replace the daemon's emotion model with the ONNX model in `bytes`
+    pub async fn swap_emotion_model_bytes(&mut self, bytes: Vec<u8>, reset_calibration: bool) -> Result<SwapModelResponse, Status> {
+        self.swap_model(swap_model_request::Model::ModelBytes(bytes), reset_calibration).await
+    }
+    
+    async fn swap_model(&mut self, model: swap_model_request::Model, reset_calibration: bool) -> Result<SwapModelResponse, Status> {
+        let request = self.request(SwapModelRequest {
+            model: Some(model),
+            reset_calibration: Some(reset_calibration),
+        });
+        
+        let response = self.client.swap_emotion_model(request).await?;
+        Ok(response.into_inner())
+    }
+    
+    /// Override the daemon's frame rate and emotion interval; `None` keeps
+    /// the current override, and `clear_overrides` hands the rates back to
+    /// the power governor first
+    pub async fn update_config(
+        &mut self,
+        target_fps: Option<f32>,
+        emotion_interval: Option<u32>,
+        clear_overrides: bool,
+    ) -> Result<UpdateConfigResponse, Status> {
+        let request = self.request(UpdateConfigRequest { target_fps, emotion_interval, clear_overrides });
+
+        let response = self.client.update_config(request).await?;
+        Ok(response.into_inner())
+    }
+
+    /// Change the daemon's camera, frame rate, face threshold or calibration
+    /// freeze without restarting it; fields left `None` keep their value.
+    /// The response names requested fields that only a restart applies.
+    pub async fn configure(&mut self, change: ConfigureRequest) -> Result<ConfigureResponse, Status> {
+        let request = self.request(change);
+
+        let response = self.client.configure(request).await?;
+        Ok(response.into_inner())
+    }
+
+    /// Replace the daemon's log filter (`EnvFilter` syntax, e.g. `debug`);
+    /// a filter that does not parse fails with `INVALID_ARGUMENT`
+    pub async fn set_log_level(&mut self, filter: impl Into<String>) -> Result<SetLogLevelResponse, Status> {
+        let request = self.request(SetLogLevelRequest { filter: filter.into() });
+
+        let response = self.client.set_log_level(request).await?;
+        Ok(response.into_inner())
+    }
+
+    /// Up to `limit` recent log records after sequence number `since`, at
+    /// least as severe as `min_level`; pass `next_since` back for the next page
+    pub async fn get_logs(&mut self, since: u64, min_level: LogLevel, limit: u32) -> Result<GetLogsResponse, Status> {
+        let request = self.request(GetLogsRequest { since, min_level: min_level as i32, limit });
+
+        let response = self.client.get_logs(request).await?;
+        Ok(response.into_inner())
+    }
+
     /// Wait for calibration to complete
     pub async fn wait_for_calibration(&mut self, timeout: Duration) -> Result<bool, Box<dyn std::error::Error + Send + Sync>> {
         let start = std::time::Instant::now();
@@ -151,6 +368,19 @@ impl SensorClient {
     }
 }
 
+/// Helper function to rebuild full scores in a delta-encoded event stream
+pub fn reconstruct_deltas(events: impl StreamExt<Item = Result<SensorEvent, Status>>) -> impl StreamExt<Item = Result<SensorEvent, Status>> {
+    let mut consumer = FearStreamConsumer::new();
+    events
+        .filter_map(move |event_result| {
+            let event = match event_result {
+                Ok(event) => consumer.consume(event).map(Ok),
+                Err(e) => Some(Err(e)),
+            };
+            async move { event }
+        })
+}
+
 /// Helper function to extract scores from event stream
 pub fn extract_scores(events: impl StreamExt<Item = Result<SensorEvent, Status>>) -> impl StreamExt<Item = Result<Score, Status>> {
     events
@@ -187,6 +417,14 @@ pub fn extract_calibration_progress(
         })
 }
 
+/// Phase and action named by a calibration control rejected as illegal
+///
+/// `None` for any other failure.
+pub fn calibration_rejection(status: &Status) -> Option<(&str, &str)> {
+    let field = |key: &str| status.metadata().get(key)?.to_str().ok();
+    Some((field(PHASE_METADATA)?, field(ACTION_METADATA)?))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -199,6 +437,7 @@ mod tests {
         // Test that we can create requests
         let stream_request = StreamRequest {
             event_types: vec![EventType::Score as i32],
+            ..Default::default()
         };
         assert_eq!(stream_request.event_types.len(), 1);
         
@@ -211,12 +450,29 @@ mod tests {
         assert!(calibration_request.action.is_some());
     }
 
+    #[tokio::test]
+    async fn test_auth_token_metadata() {
+        let channel = Channel::from_static("http://127.0.0.1:50051").connect_lazy();
+        let client = SensorClient {
+            client: SensorServiceClient::new(channel),
+            auth_token: None,
+        };
+
+        let request = client.request(StatusRequest {});
+        assert!(request.metadata().get("authorization").is_none());
+
+        let client = client.with_auth_token("secret");
+        let request = client.request(StatusRequest {});
+        assert_eq!(request.metadata().get("authorization").unwrap(), "Bearer secret");
+    }
+
     #[tokio::test]
     async fn test_stream_filtering() {
         // Create a mock stream of events
         let events = vec![
             Ok(SensorEvent {
                 timestamp_us: 1000,
+                sequence: 0,
                 event: Some(sensor_event::Event::Score(Score {
                     normalized_fear: 0.5,
                     raw_fear_logit: 0.3,
@@ -224,18 +480,28 @@ mod tests {
                     calibrated: true,
                     emotion_logits: vec![0.1; 7],
                     inference_latency_us: 5000,
+                    startle: 0.0,
+                    capability: SensorCapability::Full as i32,
+                    face_bbox: None,
+                    face_center: None,
+                    fear_index: None,
+                    face_present: None,
+                    frame_sequence: 0,
                 })),
             }),
             Ok(SensorEvent {
                 timestamp_us: 2000,
+                sequence: 0,
                 event: Some(sensor_event::Event::CalibrationProgress(CalibrationProgress {
                     progress: 0.5,
                     completed: false,
                     baseline: None,
+                    ..Default::default()
                 })),
             }),
             Ok(SensorEvent {
                 timestamp_us: 3000,
+                sequence: 0,
                 event: Some(sensor_event::Event::Score(Score {
                     normalized_fear: 0.7,
                     raw_fear_logit: 0.5,
@@ -243,6 +509,13 @@ mod tests {
                     calibrated: true,
                     emotion_logits: vec![0.2; 7],
                     inference_latency_us: 4000,
+                    startle: 0.0,
+                    capability: SensorCapability::Full as i32,
+                    face_bbox: None,
+                    face_center: None,
+                    fear_index: None,
+                    face_present: None,
+                    frame_sequence: 0,
                 })),
             }),
         ];
@@ -5,28 +5,177 @@ use crate::{
         sensor_service_server::{SensorService, SensorServiceServer},
         *,
     },
-    types::FearFrame,
-    sensor::EmotionSensor,
+    types::{self, FaultLevel, FearFrame, SensorFaultNotice, SensorMarker},
+    sensor::{EmotionSensor, SensorError},
+    measure::DEFAULT_MEASURE_TIMEOUT,
+    model_swap::{self, ModelSource},
+    phases::CalibrationPhase,
+    calibration_control::{CalibrationAction, CalibrationControlError, ACTION_METADATA, PHASE_METADATA},
+    delta::{DeltaConfig, StreamEncoder},
+    clients::ClientRegistry,
+    metrics_history::MetricsSample,
+    fanout::{FrameFanout, FrameSubscriber},
+    calibrator,
+    retention::{PurgeReport, PurgeTotals, RetentionManager},
+    bug_report::{BugReportError, BugReportSummary},
+    transport::{PeerInfo, SensorTransport},
+    model_info,
+    clock_sync::{read_clock, ClockSyncEstimate, SensorClockSync},
+    startup,
+    camera_format,
+    resume::{Clock, SystemClock},
+    power::{self, RateOverride},
+    reconfigure::{self, ConfigChange, ReconfigureError, TARGET_FPS_RANGE},
+    logging::{self, LogControl, LogError},
 };
-use async_channel::Receiver;
+#[cfg(feature = "metrics")]
+use crate::metrics::SensorMetrics;
 use std::sync::Arc;
-use tokio::sync::Mutex;
+use tokio::sync::{broadcast, watch, Mutex};
 use tokio_stream::{wrappers::ReceiverStream, Stream};
-use tonic::{transport::Server, Request, Response, Status, Code};
+use tonic::{codec::CompressionEncoding, codegen::http::HeaderValue, metadata::MetadataValue, transport::Server, Request, Response, Status, Code};
+use tonic_web::GrpcWebLayer;
+use tower_http::cors::{AllowOrigin, Any, CorsLayer};
+use std::future::Future;
 use std::pin::Pin;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 /// gRPC service implementation
 pub struct SensorServiceImpl {
     sensor: Arc<Mutex<EmotionSensor>>,
+    /// Frames of the running sensor, shared by every open stream
+    frames: Arc<Mutex<Option<FrameFanout<FearFrame>>>>,
+    retention: Arc<RetentionManager>,
+    clock: Arc<dyn Clock>,
+    /// Open event streams
+    clients: ClientRegistry,
+    /// Live log filter and recent records, when the daemon installed them
+    logs: Option<Arc<LogControl>>,
 }
 
 impl SensorServiceImpl {
     /// Create new service implementation
     pub fn new(sensor: EmotionSensor) -> Self {
+        let retention = RetentionManager::new(sensor.config().retention.clone());
+        let clock: Arc<dyn Clock> = Arc::new(SystemClock::new());
         Self {
             sensor: Arc::new(Mutex::new(sensor)),
+            frames: Arc::new(Mutex::new(None)),
+            retention: Arc::new(retention),
+            clients: ClientRegistry::new(Arc::clone(&clock)),
+            clock,
+            logs: None,
         }
     }
+
+    /// Answer pings, and stamp client activity, from `clock` instead of the system clock
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clients = ClientRegistry::new(Arc::clone(&clock));
+        self.clock = clock;
+        self
+    }
+
+    /// Report the number of open event streams through `metrics`
+    #[cfg(feature = "metrics")]
+    pub fn with_metrics(self, metrics: Arc<SensorMetrics>) -> Self {
+        self.clients.set_metrics(metrics);
+        self
+    }
+
+    /// Serve `SetLogLevel` and `GetLogs` from `logs`
+    pub fn with_log_control(mut self, logs: Arc<LogControl>) -> Self {
+        self.logs = Some(logs);
+        self
+    }
+
+    /// Log control, or FAILED_PRECONDITION when the daemon did not install one
+    fn log_control(&self) -> Result<&LogControl, Status> {
+        self.logs
+            .as_deref()
+            .ok_or_else(|| Status::new(Code::FailedPrecondition, "Log control is not installed in this daemon"))
+    }
+
+    /// Retention manager shared with the background sweep task
+    pub fn retention(&self) -> Arc<RetentionManager> {
+        Arc::clone(&self.retention)
+    }
+
+    /// The served sensor, for the daemon to read and stop around the server
+    pub fn sensor(&self) -> Arc<Mutex<EmotionSensor>> {
+        Arc::clone(&self.sensor)
+    }
+
+    /// Start the sensor ahead of the first event stream
+    ///
+    /// Streams otherwise start it when the first one opens. Either way it
+    /// runs once for every stream, and keeps running when they close unless
+    /// `idle_shutdown` is set.
+    pub async fn start_sensor(&self) -> Result<(), SensorError> {
+        let mut sensor = self.sensor.lock().await;
+        self.running_frames(&mut sensor).await.map(drop)
+    }
+
+    /// Frames of the running sensor, starting it if it is not
+    ///
+    /// Callers hold the sensor lock, always taken before `frames`.
+    async fn running_frames(&self, sensor: &mut EmotionSensor) -> Result<FrameFanout<FearFrame>, SensorError> {
+        let mut shared = self.frames.lock().await;
+        if let Some(fanout) = shared.as_ref().filter(|fanout| !fanout.is_closed()) {
+            return Ok(fanout.clone());
+        }
+
+        let fanout = shared.insert(FrameFanout::new(sensor.start().await?)).clone();
+        let idle = sensor.config().idle_shutdown;
+        if !idle.is_zero() {
+            tokio::spawn(stop_when_idle(
+                Arc::clone(&self.sensor),
+                Arc::clone(&self.frames),
+                fanout.clone(),
+                self.clients.watch_open(),
+                idle,
+            ));
+        }
+        Ok(fanout)
+    }
+}
+
+/// Stop the sensor once no stream has been open for `idle`
+///
+/// Watches one run of the sensor, the one `fanout` reads; a sensor stopped
+/// or restarted meanwhile is left alone.
+async fn stop_when_idle(
+    sensor: Arc<Mutex<EmotionSensor>>,
+    frames: Arc<Mutex<Option<FrameFanout<FearFrame>>>>,
+    fanout: FrameFanout<FearFrame>,
+    mut open: watch::Receiver<usize>,
+    idle: Duration,
+) {
+    loop {
+        if open.wait_for(|&open| open == 0).await.is_err() {
+            return;
+        }
+        match tokio::time::timeout(idle, open.wait_for(|&open| open > 0)).await {
+            Ok(Ok(_)) => continue,
+            Ok(Err(_)) => return,
+            Err(_) => {}
+        }
+
+        let mut sensor = sensor.lock().await;
+        let mut frames = frames.lock().await;
+        if fanout.is_closed() || !frames.as_ref().is_some_and(|current| current.same_source(&fanout)) {
+            return;
+        }
+        // A stream may have opened while waiting for the locks
+        if *open.borrow() > 0 {
+            continue;
+        }
+        frames.take();
+        tracing::info!("No event stream for {:?}, stopping the sensor", idle);
+        if let Err(e) = sensor.stop().await {
+            tracing::warn!("Failed to stop the idle sensor: {}", e);
+        }
+        return;
+    }
 }
 
 #[tonic::async_trait]
@@ -38,22 +187,38 @@ impl SensorService for SensorServiceImpl {
         &self,
         request: Request<StreamRequest>,
     ) -> Result<Response<Self::StreamEventsStream>, Status> {
+        let peer = request
+            .extensions()
+            .get::<PeerInfo>()
+            .map_or_else(|| "unknown".to_string(), ToString::to_string);
         let req = request.into_inner();
-        let event_types = req.event_types;
         
-        tracing::info!("Starting sensor event stream with filters: {:?}", event_types);
+        tracing::info!(
+            "Starting sensor event stream for {} with filters: {:?}, delta encoding: {:?}",
+            peer,
+            req.event_types,
+            DeltaConfig::from_request(&req),
+        );
         
-        // Start the sensor
-        let receiver = {
+        // Start the sensor, or join the streams already reading it. The
+        // stream is registered before the sensor is released, so an idle
+        // shutdown never stops the sensor under it.
+        let stream = {
             let mut sensor = self.sensor.lock().await;
-            sensor.start().await.map_err(|e| {
+            let fanout = self.running_frames(&mut sensor).await.map_err(|e| {
                 Status::new(Code::Internal, format!("Failed to start sensor: {}", e))
-            })?
+            })?;
+            let notices = StreamNotices {
+                markers: sensor.subscribe_markers(),
+                faults: sensor.subscribe_faults(),
+                clock_syncs: sensor.subscribe_clock_syncs(),
+                model_swaps: sensor.subscribe_model_swaps(),
+                power: sensor.subscribe_power(),
+                calibration: sensor.subscribe_calibration(),
+            };
+            create_event_stream(fanout.subscribe(), notices, req, &self.clients, peer)
         };
         
-        // Create event stream
-        let stream = create_event_stream(receiver, event_types);
-        
         Ok(Response::new(Box::pin(stream)))
     }
 
@@ -64,21 +229,21 @@ impl SensorService for SensorServiceImpl {
     ) -> Result<Response<StatusResponse>, Status> {
         let sensor = self.sensor.lock().await;
         let state = sensor.get_state();
+        let phase = CalibrationProgress::from(&sensor.calibration_phase());
+        let power = sensor.power();
+        let rates = power.rates();
         
         let response = StatusResponse {
             running: state.running,
             calibration: Some(CalibrationProgress {
                 progress: state.calibration_progress,
                 completed: state.calibrated,
-                baseline: if state.calibrated {
-                    Some(BaselineStats {
-                        mean: 0.0, // Would need to get from calibrator
-                        std_dev: 1.0,
-                        sample_count: 0,
-                    })
-                } else {
-                    None
-                },
+                baseline: state
+                    .baseline
+                    .as_ref()
+                    .filter(|_| state.calibrated)
+                    .map(baseline_stats),
+                ..phase
             }),
             last_error: state.last_error.map(|msg| SensorFault {
                 severity: FaultSeverity::Error as i32,
@@ -91,87 +256,925 @@ impl SensorService for SensorServiceImpl {
                 p95_inference_latency_us: state.metrics.p95_inference_latency.as_micros() as u64,
                 dropped_frames: state.metrics.dropped_frames,
                 calibration_drift: state.metrics.calibration_drift,
+                quality_level: state.metrics.quality_level,
+                session_p95_inference_latency_us: state.metrics.session_p95_inference_latency.as_micros() as u64,
             }),
+            retention: Some(retention_stats(&self.retention.totals())),
+            capability: SensorCapability::from(state.capability) as i32,
+            models: sensor.model_info().iter().map(model_info_message).collect(),
+            init: state.init.as_ref().map(init_breakdown),
+            input_normalization: state.input_normalization.map(|n| n.to_string()).unwrap_or_default(),
+            stopped_reason: state.stopped_reason.unwrap_or_default(),
+            mirrored: state.session.mirrored,
+            heartbeat_target: sensor.heartbeat_target().map(ToString::to_string).unwrap_or_default(),
+            power_profile: PowerProfile::from(power.profile()) as i32,
+            target_fps: rates.target_fps,
+            emotion_interval: rates.emotion_interval,
+            rates_overridden: power.overrides().is_set(),
+            camera: state.camera.map(camera_format),
+            config: Some(sensor_configuration(&sensor.effective_config())),
+            subscribers: self.clients.len() as u32,
         };
         
         Ok(Response::new(response))
     }
 
     /// Control calibration
+    ///
+    /// Actions illegal in the current phase fail with `FAILED_PRECONDITION`
+    /// and name the phase and action in the `calibration-phase` and
+    /// `calibration-action` metadata.
     async fn control_calibration(
         &self,
         request: Request<CalibrationControl>,
     ) -> Result<Response<CalibrationResponse>, Status> {
         let req = request.into_inner();
-        let mut sensor = self.sensor.lock().await;
         
-        let result = match req.action {
-            Some(calibration_control::Action::StartCalibration(_)) => {
-                sensor.reset_calibration()
-            },
-            Some(calibration_control::Action::FreezeCalibration(freeze)) => {
-                sensor.control_calibration(freeze)
-            },
-            Some(calibration_control::Action::ResetCalibration(_)) => {
-                sensor.reset_calibration()
-            },
+        let action = match req.action {
+            Some(calibration_control::Action::StartCalibration(_)) => CalibrationAction::Start,
+            Some(calibration_control::Action::FreezeCalibration(true)) => CalibrationAction::Freeze,
+            Some(calibration_control::Action::FreezeCalibration(false)) => CalibrationAction::Unfreeze,
+            Some(calibration_control::Action::ResetCalibration(_)) => CalibrationAction::Reset,
             None => {
                 return Ok(Response::new(CalibrationResponse {
                     success: false,
                     error_message: Some("No action specified".to_string()),
+                    phase: None,
                 }));
             }
         };
         
-        let response = match result {
-            Ok(_) => CalibrationResponse {
-                success: true,
-                error_message: None,
+        let phase = self
+            .sensor
+            .lock()
+            .await
+            .control_calibration(action)
+            .await
+            .map_err(calibration_control_status)?;
+        
+        Ok(Response::new(CalibrationResponse {
+            success: true,
+            error_message: None,
+            phase: Some(CalibrationProgress::from(&phase)),
+        }))
+    }
+
+    /// Insert a named marker into the event stream
+    async fn insert_marker(
+        &self,
+        request: Request<MarkerRequest>,
+    ) -> Result<Response<MarkerResponse>, Status> {
+        let label = request.into_inner().label;
+        if label.trim().is_empty() {
+            return Ok(Response::new(MarkerResponse {
+                success: false,
+                timestamp_us: 0,
+                error_message: Some("Marker label cannot be empty".to_string()),
+            }));
+        }
+
+        let marker = self.sensor.lock().await.insert_marker(label);
+
+        Ok(Response::new(MarkerResponse {
+            success: true,
+            timestamp_us: marker.timestamp_us,
+            error_message: None,
+        }))
+    }
+
+    /// Run a retention sweep immediately
+    async fn purge_now(
+        &self,
+        request: Request<PurgeRequest>,
+    ) -> Result<Response<PurgeResponse>, Status> {
+        let retention = Arc::clone(&self.retention);
+        let dry_run = request.into_inner().dry_run.unwrap_or(retention.config().dry_run);
+
+        tracing::info!("Retention sweep requested over gRPC (dry run: {})", dry_run);
+        let report = tokio::task::spawn_blocking(move || {
+            retention.sweep_at(std::time::SystemTime::now(), dry_run)
+        })
+        .await
+        .map_err(|e| Status::new(Code::Internal, format!("Retention sweep failed: {}", e)))?;
+
+        Ok(Response::new(purge_response(report)))
+    }
+
+    /// Write a bug report bundle of recent sensor activity
+    async fn capture_bug_report(
+        &self,
+        _request: Request<BugReportRequest>,
+    ) -> Result<Response<BugReportResponse>, Status> {
+        let (report, config) = {
+            let sensor = self.sensor.lock().await;
+            (sensor.bug_report(), sensor.config().bug_report.clone())
+        };
+
+        tracing::info!("Bug report requested over gRPC");
+        let result = tokio::task::spawn_blocking(move || report.write(&config))
+            .await
+            .map_err(|e| Status::new(Code::Internal, format!("Bug report capture failed: {}", e)))?;
+
+        Ok(Response::new(bug_report_response(result)))
+    }
+
+    /// List the open event streams
+    async fn list_clients(
+        &self,
+        _request: Request<ListClientsRequest>,
+    ) -> Result<Response<ListClientsResponse>, Status> {
+        Ok(Response::new(ListClientsResponse {
+            clients: self.clients.list(),
+        }))
+    }
+
+    /// Report the metrics history samples in the requested range
+    async fn get_metrics_history(
+        &self,
+        request: Request<MetricsHistoryRequest>,
+    ) -> Result<Response<MetricsHistoryResponse>, Status> {
+        let req = request.into_inner();
+        if req.to_us != 0 && req.from_us > req.to_us {
+            return Err(Status::new(
+                Code::InvalidArgument,
+                format!("Empty metrics history range: from {} after to {}", req.from_us, req.to_us),
+            ));
+        }
+
+        let history = self.sensor.lock().await.metrics_history();
+        let history = history.lock().unwrap();
+        Ok(Response::new(MetricsHistoryResponse {
+            samples: history
+                .range(req.from_us, req.to_us)
+                .iter()
+                .map(metrics_history_sample)
+                .collect(),
+            interval_ms: history.interval().as_millis() as u64,
+            capacity: history.capacity() as u32,
+        }))
+    }
+
+    /// Report the models the sensor runs
+    async fn get_model_info(
+        &self,
+        _request: Request<ModelInfoRequest>,
+    ) -> Result<Response<ModelInfoResponse>, Status> {
+        let sensor = self.sensor.lock().await;
+        Ok(Response::new(ModelInfoResponse {
+            models: sensor.model_info().iter().map(model_info_message).collect(),
+        }))
+    }
+
+    /// Answer a clock synchronization ping
+    async fn ping(
+        &self,
+        request: Request<PingRequest>,
+    ) -> Result<Response<PingResponse>, Status> {
+        // Read the clocks first so waiting on the sensor lock cannot skew the reply
+        let (monotonic, wall_us) = read_clock(self.clock.as_ref());
+        let req = request.into_inner();
+
+        if let Some(clock_sync) = req.clock_sync {
+            self.sensor.lock().await.record_clock_sync(SensorClockSync {
+                timestamp_us: wall_us,
+                estimate: clock_sync.into(),
+            });
+        }
+
+        Ok(Response::new(PingResponse {
+            game_time_us: req.game_time_us,
+            sensor_monotonic_us: monotonic.as_micros() as u64,
+            sensor_wall_us: wall_us,
+        }))
+    }
+
+    /// Capture and score one frame
+    async fn measure_once(
+        &self,
+        request: Request<MeasureRequest>,
+    ) -> Result<Response<MeasureResponse>, Status> {
+        let timeout = match request.into_inner().timeout_ms {
+            0 => DEFAULT_MEASURE_TIMEOUT,
+            timeout_ms => Duration::from_millis(timeout_ms.into()),
+        };
+
+        // Wait on a running pipeline without holding the sensor, so other calls go through
+        let live = {
+            let sensor = self.sensor.lock().await;
+            sensor.get_state().running.then(|| sensor.live_frames())
+        };
+        let result = match live {
+            Some(live) => live.next(timeout).await,
+            None => self.sensor.lock().await.measure_once(timeout).await,
+        };
+        let fear_frame = result.map_err(|e| {
+            let code = match &e {
+                SensorError::MeasureTimeout(_) => Code::DeadlineExceeded,
+                SensorError::NotInitialized => Code::FailedPrecondition,
+                _ => Code::Internal,
+            };
+            Status::new(code, format!("Measurement failed: {}", e))
+        })?;
+
+        Ok(Response::new(MeasureResponse {
+            timestamp_us: fear_frame.timestamp_us(),
+            score: Some(score_message(&fear_frame)),
+        }))
+    }
+
+    /// Replace the emotion model without dropping streams
+    async fn swap_emotion_model(
+        &self,
+        request: Request<SwapModelRequest>,
+    ) -> Result<Response<SwapModelResponse>, Status> {
+        let req = request.into_inner();
+        let source = match req.model {
+            Some(swap_model_request::Model::Path(path)) => ModelSource::Path(path.into()),
+            Some(swap_model_request::Model::ModelBytes(bytes)) => ModelSource::Bytes(bytes.into()),
+            None => return Err(Status::new(Code::InvalidArgument, "No model path or bytes given")),
+        };
+        let reset_calibration = req.reset_calibration.unwrap_or(true);
+
+        // Load and validate without holding the sensor, so other calls go through
+        let config = {
+            let sensor = self.sensor.lock().await;
+            if sensor.model_info().is_empty() {
+                return Err(Status::new(Code::FailedPrecondition, "Sensor not initialized"));
+            }
+            sensor.config().clone()
+        };
+        let model = tokio::task::spawn_blocking(move || EmotionSensor::load_replacement_model(&config, source))
+            .await
+            .map_err(|e| Status::new(Code::Internal, format!("Model validation task failed: {}", e)))?
+            .map_err(|e| Status::new(Code::InvalidArgument, format!("Model rejected, current model kept: {}", e)))?;
+
+        let swapped = self
+            .sensor
+            .lock()
+            .await
+            .install_emotion_model(model, reset_calibration)
+            .await
+            .map_err(|e| {
+                let code = match &e {
+                    SensorError::NotInitialized => Code::FailedPrecondition,
+                    SensorError::ModelSwap(model_swap::ModelSwapError::Interrupted) => Code::Aborted,
+                    _ => Code::Internal,
+                };
+                Status::new(code, format!("Model swap failed: {}", e))
+            })?;
+
+        Ok(Response::new(SwapModelResponse {
+            timestamp_us: swapped.timestamp_us,
+            swap: Some(model_swapped_message(&swapped)),
+        }))
+    }
+
+    /// Change the frame rate and emotion interval, winning over the power governor
+    async fn update_config(
+        &self,
+        request: Request<UpdateConfigRequest>,
+    ) -> Result<Response<UpdateConfigResponse>, Status> {
+        let req = request.into_inner();
+        if let Some(fps) = req.target_fps.filter(|fps| !TARGET_FPS_RANGE.contains(fps)) {
+            return Err(Status::new(Code::InvalidArgument, format!("Target FPS must be between 1 and 120, got {}", fps)));
+        }
+        if req.emotion_interval == Some(0) {
+            return Err(Status::new(Code::InvalidArgument, "Emotion interval must be at least 1"));
+        }
+
+        let power = self.sensor.lock().await.power();
+        let current = if req.clear_overrides { RateOverride::default() } else { power.overrides() };
+        let rates = power.set_override(RateOverride {
+            target_fps: req.target_fps.or(current.target_fps),
+            emotion_interval: req.emotion_interval.or(current.emotion_interval),
+        });
+
+        Ok(Response::new(UpdateConfigResponse {
+            target_fps: rates.target_fps,
+            emotion_interval: rates.emotion_interval,
+            power_profile: PowerProfile::from(power.profile()) as i32,
+            overridden: power.overrides().is_set(),
+        }))
+    }
+
+    /// Change settings of the sensor without restarting it
+    async fn configure(
+        &self,
+        request: Request<ConfigureRequest>,
+    ) -> Result<Response<ConfigureResponse>, Status> {
+        let req = request.into_inner();
+        let change = ConfigChange {
+            camera_id: req.camera_id,
+            target_fps: req.target_fps,
+            face_confidence_threshold: req.face_confidence_threshold,
+            freeze_calibration: req.freeze_calibration,
+            onnx_threads: req.onnx_threads.map(|threads| threads as usize),
+        };
+
+        let reconfigured = self.sensor.lock().await.configure(change).await.map_err(|e| match e {
+            SensorError::CalibrationControl(_) => calibration_control_status(e),
+            SensorError::Reconfigure(ReconfigureError::Invalid(_)) => Status::new(Code::InvalidArgument, e.to_string()),
+            SensorError::Reconfigure(ReconfigureError::Interrupted) => Status::new(Code::Aborted, e.to_string()),
+            e => Status::new(Code::FailedPrecondition, e.to_string()),
+        })?;
+
+        Ok(Response::new(ConfigureResponse {
+            config: Some(sensor_configuration(&reconfigured.config)),
+            restart_required: reconfigured.restart_required.iter().map(ToString::to_string).collect(),
+        }))
+    }
+
+    /// Replace the log filter
+    async fn set_log_level(
+        &self,
+        request: Request<SetLogLevelRequest>,
+    ) -> Result<Response<SetLogLevelResponse>, Status> {
+        let filter = request.into_inner().filter;
+        let previous_filter = self.log_control()?.set_filter(&filter).map_err(|e| match e {
+            LogError::InvalidFilter { .. } => Status::new(Code::InvalidArgument, e.to_string()),
+            e => Status::new(Code::Internal, e.to_string()),
+        })?;
+        Ok(Response::new(SetLogLevelResponse { previous_filter, filter }))
+    }
+
+    /// Page through recent log records
+    async fn get_logs(
+        &self,
+        request: Request<GetLogsRequest>,
+    ) -> Result<Response<GetLogsResponse>, Status> {
+        let req = request.into_inner();
+        let min_level = match LogLevel::try_from(req.min_level).unwrap_or(LogLevel::Unspecified) {
+            LogLevel::Unspecified | LogLevel::Trace => tracing::Level::TRACE,
+            LogLevel::Debug => tracing::Level::DEBUG,
+            LogLevel::Info => tracing::Level::INFO,
+            LogLevel::Warn => tracing::Level::WARN,
+            LogLevel::Error => tracing::Level::ERROR,
+        };
+        let page = self.log_control()?.logs(req.since, min_level, req.limit as usize);
+        Ok(Response::new(GetLogsResponse {
+            records: page.records.iter().map(log_record_message).collect(),
+            next_since: page.next_since,
+            more: page.more,
+            dropped: page.dropped,
+        }))
+    }
+}
+
+/// Convert calibrator baseline statistics into their proto form
+pub(crate) fn baseline_stats(baseline: &calibrator::BaselineStats) -> BaselineStats {
+    BaselineStats {
+        mean: baseline.mean,
+        std_dev: baseline.std_dev,
+        sample_count: baseline.sample_count,
+        channels: baseline
+            .channels
+            .iter()
+            .flatten()
+            .map(|channel| ChannelBaseline {
+                mean: channel.mean,
+                std_dev: channel.std_dev,
+            })
+            .collect(),
+    }
+}
+
+impl From<startup::ModelCacheStatus> for ModelCacheStatus {
+    fn from(status: startup::ModelCacheStatus) -> Self {
+        match status {
+            startup::ModelCacheStatus::Disabled => ModelCacheStatus::Disabled,
+            startup::ModelCacheStatus::Miss => ModelCacheStatus::Miss,
+            startup::ModelCacheStatus::Hit => ModelCacheStatus::Hit,
+        }
+    }
+}
+
+impl From<power::PowerProfile> for PowerProfile {
+    fn from(profile: power::PowerProfile) -> Self {
+        match profile {
+            power::PowerProfile::Performance => PowerProfile::Performance,
+            power::PowerProfile::Balanced => PowerProfile::Balanced,
+            power::PowerProfile::Saver => PowerProfile::Saver,
+        }
+    }
+}
+
+impl From<tracing::Level> for LogLevel {
+    fn from(level: tracing::Level) -> Self {
+        match level {
+            tracing::Level::TRACE => Self::Trace,
+            tracing::Level::DEBUG => Self::Debug,
+            tracing::Level::INFO => Self::Info,
+            tracing::Level::WARN => Self::Warn,
+            _ => Self::Error,
+        }
+    }
+}
+
+impl From<ClockSyncEstimate> for ClockSync {
+    fn from(estimate: ClockSyncEstimate) -> Self {
+        ClockSync {
+            wall_offset_us: estimate.wall_offset_us,
+            monotonic_offset_us: estimate.monotonic_offset_us,
+            error_us: estimate.error_us,
+            confidence: estimate.confidence,
+            samples: estimate.samples,
+        }
+    }
+}
+
+impl From<ClockSync> for ClockSyncEstimate {
+    fn from(clock_sync: ClockSync) -> Self {
+        ClockSyncEstimate {
+            wall_offset_us: clock_sync.wall_offset_us,
+            monotonic_offset_us: clock_sync.monotonic_offset_us,
+            error_us: clock_sync.error_us,
+            confidence: clock_sync.confidence,
+            samples: clock_sync.samples,
+        }
+    }
+}
+
+impl From<&CalibrationPhase> for CalibrationProgress {
+    fn from(phase: &CalibrationPhase) -> Self {
+        let state = match phase {
+            CalibrationPhase::Idle => CalibrationState::Idle,
+            CalibrationPhase::Collecting { .. } => CalibrationState::Collecting,
+            CalibrationPhase::Calibrated { .. } => CalibrationState::Calibrated,
+            CalibrationPhase::Frozen { .. } => CalibrationState::Frozen,
+            CalibrationPhase::Failed { .. } => CalibrationState::Failed,
+        };
+        CalibrationProgress {
+            progress: phase.progress(),
+            completed: phase.is_calibrated(),
+            baseline: None,
+            state: state as i32,
+            quality: phase.quality().unwrap_or(0.0),
+            failure: match phase {
+                CalibrationPhase::Failed { reason } => reason.clone(),
+                _ => String::new(),
+            },
+            frozen: matches!(phase, CalibrationPhase::Frozen { .. }),
+        }
+    }
+}
+
+impl From<&CalibrationProgress> for CalibrationPhase {
+    fn from(progress: &CalibrationProgress) -> Self {
+        match CalibrationState::try_from(progress.state).unwrap_or(CalibrationState::Unspecified) {
+            CalibrationState::Idle => CalibrationPhase::Idle,
+            CalibrationState::Collecting => CalibrationPhase::Collecting { progress: progress.progress },
+            CalibrationState::Calibrated => CalibrationPhase::Calibrated { quality: progress.quality },
+            CalibrationState::Frozen => CalibrationPhase::Frozen { quality: progress.quality },
+            CalibrationState::Failed => CalibrationPhase::Failed { reason: progress.failure.clone() },
+            CalibrationState::Unspecified if progress.completed => {
+                CalibrationPhase::Calibrated { quality: progress.quality }
             },
-            Err(e) => CalibrationResponse {
+            CalibrationState::Unspecified => CalibrationPhase::Collecting { progress: progress.progress },
+        }
+    }
+}
+
+/// Status for a calibration action that was not applied
+pub(crate) fn calibration_control_status(error: SensorError) -> Status {
+    let code = match &error {
+        SensorError::CalibrationControl(CalibrationControlError::Interrupted) => Code::Aborted,
+        _ => Code::FailedPrecondition,
+    };
+    let mut status = Status::new(code, error.to_string());
+    if let SensorError::CalibrationControl(CalibrationControlError::IllegalTransition { phase, action }) = &error {
+        let metadata = status.metadata_mut();
+        metadata.insert(PHASE_METADATA, MetadataValue::from_static(phase.name()));
+        metadata.insert(ACTION_METADATA, MetadataValue::from_static(action.name()));
+    }
+    status
+}
+
+/// Convert a calibration phase into a calibration progress event
+fn calibration_event(phase: &CalibrationPhase) -> SensorEvent {
+    SensorEvent {
+        timestamp_us: SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_micros() as u64,
+        sequence: 0,
+        event: Some(sensor_event::Event::CalibrationProgress(phase.into())),
+    }
+}
+
+/// Convert model metadata into its proto form
+fn model_info_message(info: &model_info::ModelInfo) -> ModelInfo {
+    ModelInfo {
+        name: info.name.to_string(),
+        version: info.version.to_string(),
+        source_url: info.source_url.to_string(),
+        license: info.license.to_string(),
+        sha256: info.sha256.to_string(),
+        embedded: info.embedded,
+    }
+}
+
+/// Convert a captured log record into its proto form
+fn log_record_message(record: &logging::LogRecord) -> LogRecord {
+    LogRecord {
+        sequence: record.sequence,
+        timestamp_us: record.timestamp_us,
+        level: LogLevel::from(record.level) as i32,
+        target: record.target.clone(),
+        message: record.message.clone(),
+        fields: record
+            .fields
+            .iter()
+            .map(|(name, value)| LogField { name: name.clone(), value: value.clone() })
+            .collect(),
+    }
+}
+
+/// Convert a metrics history sample into its proto form
+fn metrics_history_sample(sample: &MetricsSample) -> MetricsHistorySample {
+    MetricsHistorySample {
+        timestamp_us: sample.timestamp_us,
+        fps: sample.fps,
+        p95_inference_latency_us: sample.p95_inference_latency_us,
+        dropped_frames: sample.dropped_frames,
+        calibration_drift: sample.calibration_drift,
+    }
+}
+
+/// Convert initialization timings into their status message
+fn init_breakdown(init: &startup::InitBreakdown) -> InitBreakdown {
+    InitBreakdown {
+        face_detector_us: init.face_detector.as_micros() as u64,
+        emotion_model_us: init.emotion_model.as_micros() as u64,
+        calibrator_us: init.calibrator.as_micros() as u64,
+        camera_us: init.camera.map(|camera| camera.as_micros() as u64),
+        total_us: init.total.as_micros() as u64,
+        emotion_cache: ModelCacheStatus::from(init.emotion_cache) as i32,
+    }
+}
+
+/// Convert a negotiated camera format into its message
+fn camera_format(format: camera_format::CameraFormat) -> CameraFormat {
+    CameraFormat {
+        width: format.width,
+        height: format.height,
+        fps: format.fps,
+    }
+}
+
+/// Convert the settings in effect into their message
+fn sensor_configuration(config: &reconfigure::EffectiveConfig) -> SensorConfiguration {
+    SensorConfiguration {
+        camera_id: config.camera_id,
+        target_fps: config.target_fps,
+        face_confidence_threshold: config.face_confidence_threshold,
+        calibration_frozen: config.calibration_frozen,
+        onnx_threads: config.onnx_threads as u32,
+    }
+}
+
+/// Convert retention totals into status statistics
+fn retention_stats(totals: &PurgeTotals) -> RetentionStats {
+    RetentionStats {
+        recordings_purged: totals.recordings_purged,
+        profiles_purged: totals.profiles_purged,
+        session_logs_purged: totals.session_logs_purged,
+        purge_failures: totals.failures,
+        last_sweep_us: totals.last_sweep_us,
+    }
+}
+
+/// Convert a sweep report into a purge response
+fn purge_response(report: PurgeReport) -> PurgeResponse {
+    PurgeResponse {
+        success: report.failures.is_empty(),
+        dry_run: report.dry_run,
+        files_scanned: report.scanned as u32,
+        purged: report
+            .purged
+            .into_iter()
+            .map(|file| PurgedFile {
+                path: file.path.display().to_string(),
+                category: file.category.as_str().to_string(),
+                age_secs: file.age.as_secs(),
+            })
+            .collect(),
+        failures: report
+            .failures
+            .into_iter()
+            .map(|failure| PurgeFailure {
+                path: failure.path.display().to_string(),
+                category: failure.category.as_str().to_string(),
+                error: failure.error,
+            })
+            .collect(),
+    }
+}
+
+/// Convert a bundle write result into a bug report response
+fn bug_report_response(result: Result<BugReportSummary, BugReportError>) -> BugReportResponse {
+    match result {
+        Ok(summary) => BugReportResponse {
+            success: true,
+            bundle_path: summary.path.display().to_string(),
+            frame_count: summary.manifest.frames as u32,
+            crop_count: summary.manifest.crops as u32,
+            bundle_bytes: summary.bytes,
+            error_message: None,
+        },
+        Err(e) => {
+            tracing::warn!("Bug report capture failed: {}", e);
+            BugReportResponse {
                 success: false,
                 error_message: Some(e.to_string()),
-            },
-        };
-        
-        Ok(Response::new(response))
+                ..Default::default()
+            }
+        }
+    }
+}
+
+/// Convert a fear frame into a score event
+fn score_event(fear_frame: &FearFrame) -> SensorEvent {
+    SensorEvent {
+        timestamp_us: fear_frame.timestamp_us(),
+        sequence: 0,
+        event: Some(sensor_event::Event::Score(score_message(fear_frame))),
+    }
+}
+
+/// Convert a fear frame into its proto score
+pub(crate) fn score_message(fear_frame: &FearFrame) -> Score {
+    Score {
+        normalized_fear: fear_frame.fear_score,
+        raw_fear_logit: fear_frame.extract_fear_logit(),
+        confidence: fear_frame.confidence,
+        calibrated: fear_frame.calibrated,
+        emotion_logits: fear_frame.emotion_logits.to_vec(),
+        inference_latency_us: fear_frame.inference_latency.as_micros() as u64,
+        startle: fear_frame.startle,
+        capability: SensorCapability::from(fear_frame.capability) as i32,
+        face_bbox: fear_frame.face_bbox.map(|bbox| NormalizedRect {
+            x: bbox.x,
+            y: bbox.y,
+            width: bbox.width,
+            height: bbox.height,
+        }),
+        face_center: fear_frame.face_center.map(|(x, y)| NormalizedPoint { x, y }),
+        fear_index: Some(fear_frame.emotion_logits.layout().fear_index as u32),
+        face_present: Some(fear_frame.face_present),
+        frame_sequence: fear_frame.sequence,
+    }
+}
+
+/// Convert a sensor marker into a marker event
+fn marker_event(marker: SensorMarker) -> SensorEvent {
+    SensorEvent {
+        timestamp_us: marker.timestamp_us,
+        sequence: 0,
+        event: Some(sensor_event::Event::Marker(Marker { label: marker.label })),
+    }
+}
+
+/// Convert a recorded clock estimate into a clock sync event
+fn clock_sync_event(sync: SensorClockSync) -> SensorEvent {
+    SensorEvent {
+        timestamp_us: sync.timestamp_us,
+        sequence: 0,
+        event: Some(sensor_event::Event::ClockSync(sync.estimate.into())),
+    }
+}
+
+/// Convert a model swap notice into its proto form
+fn model_swapped_message(swapped: &model_swap::ModelSwapped) -> ModelSwapped {
+    ModelSwapped {
+        old_sha256: swapped.old_sha256.clone(),
+        new_sha256: swapped.new_sha256.clone(),
+        calibration_reset: swapped.calibration_reset,
+    }
+}
+
+/// Convert a model swap notice into a model swapped event
+fn model_swapped_event(swapped: model_swap::ModelSwapped) -> SensorEvent {
+    SensorEvent {
+        timestamp_us: swapped.timestamp_us,
+        sequence: 0,
+        event: Some(sensor_event::Event::ModelSwapped(model_swapped_message(&swapped))),
+    }
+}
+
+/// Convert a power governor notice into a power profile event
+fn power_profile_event(change: power::PowerProfileChanged) -> SensorEvent {
+    SensorEvent {
+        timestamp_us: change.timestamp_us,
+        sequence: 0,
+        event: Some(sensor_event::Event::PowerProfileChanged(PowerProfileChanged {
+            from: PowerProfile::from(change.from) as i32,
+            to: PowerProfile::from(change.to) as i32,
+            reason: change.reason,
+            target_fps: change.rates.target_fps,
+            emotion_interval: change.rates.emotion_interval,
+            overridden: change.overridden,
+        })),
+    }
+}
+
+/// Convert a sensor fault notice into a fault event
+fn fault_event(fault: SensorFaultNotice) -> SensorEvent {
+    let severity = match fault.severity {
+        FaultLevel::Info => FaultSeverity::Info,
+        FaultLevel::Warning => FaultSeverity::Warning,
+        FaultLevel::Error => FaultSeverity::Error,
+        FaultLevel::Critical => FaultSeverity::Critical,
+    };
+    SensorEvent {
+        timestamp_us: fault.timestamp_us,
+        sequence: 0,
+        event: Some(sensor_event::Event::SensorFault(SensorFault {
+            severity: severity as i32,
+            message: fault.message,
+            error_code: fault.error_code,
+            recoverable: fault.recoverable,
+        })),
+    }
+}
+
+/// Out-of-band sensor notices merged into the event stream
+pub(crate) struct StreamNotices {
+    pub(crate) markers: broadcast::Receiver<SensorMarker>,
+    pub(crate) faults: broadcast::Receiver<SensorFaultNotice>,
+    pub(crate) clock_syncs: broadcast::Receiver<SensorClockSync>,
+    pub(crate) model_swaps: broadcast::Receiver<model_swap::ModelSwapped>,
+    pub(crate) power: broadcast::Receiver<power::PowerProfileChanged>,
+    pub(crate) calibration: watch::Receiver<CalibrationPhase>,
+}
+
+/// Notice to end a stream with once the sensor's frames ran out
+fn stop_notice(faults: &mut broadcast::Receiver<SensorFaultNotice>) -> SensorFaultNotice {
+    // The sensor announces why it stopped before closing its frame channel
+    loop {
+        match faults.try_recv() {
+            Ok(fault) if fault.is_stopped() => return fault,
+            Ok(_) | Err(broadcast::error::TryRecvError::Lagged(_)) => continue,
+            Err(_) => return SensorFaultNotice::stopped("Sensor stopped"),
+        }
     }
 }
 
-/// Create event stream from fear frame receiver
-fn create_event_stream(
-    receiver: Receiver<FearFrame>,
-    event_filters: Vec<i32>,
+/// Create event stream from a fear frame subscription and notice subscriptions
+///
+/// Calibration progress events carry phase changes; with
+/// `initial_calibration` the stream starts with the current phase. When the
+/// sensor stops, the stream sends a final `SENSOR_STOPPED` fault,
+/// whatever the filters, and ends. Events are numbered; anything skipped
+/// after falling behind shows as a jump in sequence numbers, and with delta
+/// encoding the next score is sent in full. The stream is listed in
+/// `clients` until it ends or the client goes away.
+pub(crate) fn create_event_stream(
+    mut frames: FrameSubscriber<FearFrame>,
+    notices: StreamNotices,
+    request: StreamRequest,
+    clients: &ClientRegistry,
+    peer: String,
 ) -> impl Stream<Item = Result<SensorEvent, Status>> {
     let (tx, rx) = tokio::sync::mpsc::channel(100);
+    let delta = DeltaConfig::from_request(&request);
+    let initial_calibration = request.initial_calibration;
     
     // Convert event type filters
-    let filters: Vec<EventType> = event_filters
-        .into_iter()
-        .filter_map(|i| EventType::try_from(i).ok())
+    let filters: Vec<EventType> = request
+        .event_types
+        .iter()
+        .filter_map(|&i| EventType::try_from(i).ok())
         .collect();
+    let client = clients.register(peer, request, &tx);
     
-    // Spawn task to convert fear frames to sensor events
+    // Spawn task to convert fear frames, markers and faults to sensor events
     tokio::spawn(async move {
-        while let Ok(fear_frame) = receiver.recv().await {
-            let event = SensorEvent {
-                timestamp_us: fear_frame.timestamp_us(),
-                event: Some(sensor_event::Event::Score(Score {
-                    normalized_fear: fear_frame.fear_score,
-                    raw_fear_logit: fear_frame.extract_fear_logit(),
-                    confidence: fear_frame.confidence,
-                    calibrated: fear_frame.calibrated,
-                    emotion_logits: fear_frame.emotion_logits.to_vec(),
-                    inference_latency_us: fear_frame.inference_latency.as_micros() as u64,
-                })),
+        let StreamNotices { mut markers, mut faults, mut clock_syncs, mut model_swaps, mut power, mut calibration } = notices;
+        let mut markers_open = true;
+        let mut faults_open = true;
+        let mut clock_syncs_open = true;
+        let mut model_swaps_open = true;
+        let mut power_open = true;
+        let mut calibration_open = true;
+        let mut encoder = StreamEncoder::new(delta);
+        let mut frames_lagged = frames.lagged();
+
+        // Late subscribers learn the phase without waiting for it to change
+        let current_phase = calibration_event(&calibration.borrow_and_update());
+        if initial_calibration && should_send_event(&current_phase, &filters) {
+            if tx.send(Ok(encoder.encode(current_phase))).await.is_err() {
+                return;
+            }
+            client.delivered();
+        }
+
+        let stopped = loop {
+            let event = tokio::select! {
+                // Leave the client list as soon as the client goes away
+                _ = tx.closed() => return,
+                frame = frames.recv() => match frame {
+                    Some(fear_frame) => {
+                        let skipped = frames.lagged() - frames_lagged;
+                        if skipped > 0 {
+                            tracing::warn!("Stream lagged, skipped {} frames", skipped);
+                            encoder.skip(skipped);
+                            client.dropped(skipped);
+                            frames_lagged = frames.lagged();
+                        }
+                        score_event(&fear_frame)
+                    },
+                    None => break stop_notice(&mut faults),
+                },
+                marker = markers.recv(), if markers_open => match marker {
+                    Ok(marker) => marker_event(marker),
+                    Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                        tracing::warn!("Stream lagged, skipped {} markers", skipped);
+                        encoder.skip(skipped);
+                        client.dropped(skipped);
+                        continue;
+                    },
+                    Err(broadcast::error::RecvError::Closed) => {
+                        markers_open = false;
+                        continue;
+                    },
+                },
+                fault = faults.recv(), if faults_open => match fault {
+                    Ok(fault) if fault.is_stopped() => break fault,
+                    Ok(fault) => fault_event(fault),
+                    Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                        tracing::warn!("Stream lagged, skipped {} faults", skipped);
+                        encoder.skip(skipped);
+                        client.dropped(skipped);
+                        continue;
+                    },
+                    Err(broadcast::error::RecvError::Closed) => {
+                        faults_open = false;
+                        continue;
+                    },
+                },
+                clock_sync = clock_syncs.recv(), if clock_syncs_open => match clock_sync {
+                    Ok(sync) => clock_sync_event(sync),
+                    Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                        tracing::warn!("Stream lagged, skipped {} clock syncs", skipped);
+                        encoder.skip(skipped);
+                        client.dropped(skipped);
+                        continue;
+                    },
+                    Err(broadcast::error::RecvError::Closed) => {
+                        clock_syncs_open = false;
+                        continue;
+                    },
+                },
+                swapped = model_swaps.recv(), if model_swaps_open => match swapped {
+                    Ok(swapped) => model_swapped_event(swapped),
+                    Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                        tracing::warn!("Stream lagged, skipped {} model swaps", skipped);
+                        encoder.skip(skipped);
+                        client.dropped(skipped);
+                        continue;
+                    },
+                    Err(broadcast::error::RecvError::Closed) => {
+                        model_swaps_open = false;
+                        continue;
+                    },
+                },
+                change = power.recv(), if power_open => match change {
+                    Ok(change) => power_profile_event(change),
+                    Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                        tracing::warn!("Stream lagged, skipped {} power profile changes", skipped);
+                        encoder.skip(skipped);
+                        client.dropped(skipped);
+                        continue;
+                    },
+                    Err(broadcast::error::RecvError::Closed) => {
+                        power_open = false;
+                        continue;
+                    },
+                },
+                changed = calibration.changed(), if calibration_open => match changed {
+                    Ok(()) => {
+                        let phase = calibration.borrow_and_update().clone();
+                        calibration_event(&phase)
+                    },
+                    Err(_) => {
+                        calibration_open = false;
+                        continue;
+                    },
+                },
             };
             
             // Apply filters
             if should_send_event(&event, &filters) {
-                if tx.try_send(Ok(event)).is_err() {
-                    break; // Receiver dropped or channel full
+                if tx.try_send(Ok(encoder.encode(event))).is_err() {
+                    return; // Receiver dropped or channel full
                 }
+                client.delivered();
             }
+        };
+
+        // Dropping `tx` afterwards ends the stream
+        if tx.send(Ok(encoder.encode(fault_event(stopped)))).await.is_ok() {
+            client.delivered();
         }
     });
     
@@ -188,36 +1191,111 @@ fn should_send_event(event: &SensorEvent, filters: &[EventType]) -> bool {
         Some(sensor_event::Event::CalibrationProgress(_)) => {
             filters.contains(&EventType::CalibrationProgress)
         },
-        Some(sensor_event::Event::Score(_)) => {
+        Some(sensor_event::Event::Score(_)) | Some(sensor_event::Event::ScoreDelta(_)) => {
             filters.contains(&EventType::Score)
         },
         Some(sensor_event::Event::SensorFault(_)) => {
             filters.contains(&EventType::SensorFault)
         },
+        Some(sensor_event::Event::Marker(_)) => {
+            filters.contains(&EventType::Marker)
+        },
+        Some(sensor_event::Event::ClockSync(_)) => {
+            filters.contains(&EventType::ClockSync)
+        },
+        Some(sensor_event::Event::ModelSwapped(_)) => {
+            filters.contains(&EventType::ModelSwapped)
+        },
+        Some(sensor_event::Event::PowerProfileChanged(_)) => {
+            filters.contains(&EventType::PowerProfile)
+        },
         None => false,
     }
 }
 
-/// Start gRPC server on TCP (simplified for compatibility)
+/// Wrap `service` for serving, with gzip and zstd compression
+///
+/// Responses are compressed only for clients that ask for it
+/// (`SensorClient::with_compression`).
+fn sensor_service(service: SensorServiceImpl) -> SensorServiceServer<SensorServiceImpl> {
+    SensorServiceServer::new(service)
+        .accept_compressed(CompressionEncoding::Gzip)
+        .accept_compressed(CompressionEncoding::Zstd)
+        .send_compressed(CompressionEncoding::Gzip)
+        .send_compressed(CompressionEncoding::Zstd)
+}
+
+/// Serve the sensor on `transport` until the server fails
+///
+/// Existing socket path strings convert with `str::parse` or
+/// [`SensorTransport::from_config`].
 pub async fn start_grpc_server(
-    _socket_path: &str,
+    transport: SensorTransport,
     sensor: EmotionSensor,
 ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
-    let service = SensorServiceImpl::new(sensor);
-    let server = SensorServiceServer::new(service);
+    serve_grpc_with_shutdown(transport, SensorServiceImpl::new(sensor), std::future::pending()).await
+}
+
+/// Serve `service` on `transport` until `shutdown` completes
+///
+/// Once `shutdown` completes no new connections are accepted and the call
+/// returns when the open ones close; stop the sensor first so event streams
+/// end instead of holding their connections open.
+pub async fn serve_grpc_with_shutdown(
+    transport: SensorTransport,
+    service: SensorServiceImpl,
+    shutdown: impl Future<Output = ()> + Send,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let web_origins = service.sensor.lock().await.config().grpc_web_origins.clone();
+    if service.retention.config().is_enabled() {
+        service.retention().spawn();
+    }
+    let server = sensor_service(service);
 
-    let addr = "127.0.0.1:50051".parse()?;
+    let incoming = transport.listen().await?;
 
-    tracing::info!("gRPC server listening on TCP: {}", addr);
+    tracing::info!("gRPC server listening on {}: {}", transport.kind(), transport);
 
-    Server::builder()
-        .add_service(server)
-        .serve(addr)
-        .await?;
+    if web_origins.is_empty() {
+        Server::builder()
+            .add_service(server)
+            .serve_with_incoming_shutdown(incoming, shutdown)
+            .await?;
+    } else {
+        // Browsers speak grpc-web over HTTP/1.1 and need CORS to reach us
+        tracing::info!("Accepting grpc-web from {}", web_origins.join(", "));
+        Server::builder()
+            .accept_http1(true)
+            .layer(grpc_web_cors(&web_origins))
+            .layer(GrpcWebLayer::new())
+            .add_service(server)
+            .serve_with_incoming_shutdown(incoming, shutdown)
+            .await?;
+    }
 
     Ok(())
 }
 
+/// CORS for browser consoles served from `origins`, `*` allowing any
+fn grpc_web_cors(origins: &[String]) -> CorsLayer {
+    let allow_origin = if origins.iter().any(|origin| origin == "*") {
+        AllowOrigin::any()
+    } else {
+        AllowOrigin::list(origins.iter().filter_map(|origin| match origin.parse::<HeaderValue>() {
+            Ok(value) => Some(value),
+            Err(_) => {
+                tracing::warn!("Ignoring invalid grpc-web origin {:?}", origin);
+                None
+            }
+        }))
+    };
+    CorsLayer::new()
+        .allow_origin(allow_origin)
+        .allow_methods(Any)
+        .allow_headers(Any)
+        .expose_headers(Any)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -231,16 +1309,88 @@ mod tests {
     }
 
     #[test]
-    fn test_event_filtering() {
-        let event = SensorEvent {
-            timestamp_us: 0,
-            event: Some(sensor_event::Event::Score(Score {
-                normalized_fear: 0.5,
-                raw_fear_logit: 0.3,
-                confidence: 0.9,
-                calibrated: true,
+    fn test_score_carries_face_position() {
+        let frame = FearFrame::new(0.5, [0.1; 7], 0.9, true, Duration::from_millis(4));
+        let score = score_message(&frame);
+        assert_eq!((score.face_bbox, score.face_center), (None, None));
+
+        let bbox = types::NormalizedRect { x: 0.25, y: 0.5, width: 0.25, height: 0.25 };
+        let score = score_message(&frame.with_face_bbox(Some(bbox)));
+        assert_eq!(score.face_bbox, Some(NormalizedRect { x: 0.25, y: 0.5, width: 0.25, height: 0.25 }));
+        assert_eq!(score.face_center, Some(NormalizedPoint { x: 0.375, y: 0.625 }));
+    }
+
+    #[test]
+    fn test_score_event_is_stamped_at_capture() {
+        let captured = SystemTime::now() - Duration::from_millis(80);
+        let frame = FearFrame::new(0.5, [0.1; 7], 0.9, true, Duration::from_millis(4))
+            .with_capture_time(captured)
+            .with_sequence(42);
+        let event = score_event(&frame);
+
+        let captured_us = captured.duration_since(UNIX_EPOCH).unwrap().as_micros() as u64;
+        assert_eq!(event.timestamp_us, captured_us);
+        match event.event {
+            Some(sensor_event::Event::Score(score)) => assert_eq!(score.frame_sequence, 42),
+            other => panic!("expected score event, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_face_loss_reaches_the_stream() {
+        use crate::grpc_client::SensorClient;
+        use futures::StreamExt;
+
+        let (frame_sender, frame_receiver) = async_channel::bounded(16);
+        let service = SensorServiceImpl::new(EmotionSensor::new(SensorConfig::default()));
+        *service.frames.lock().await = Some(FrameFanout::new(frame_receiver));
+
+        let transport = SensorTransport::loopback();
+        let incoming = transport.listen().await.unwrap();
+        let server = tokio::spawn(
+            Server::builder()
+                .add_service(SensorServiceServer::new(service))
+                .serve_with_incoming(incoming),
+        );
+        let mut client = SensorClient::connect(&transport).await.unwrap();
+        let mut scores = client.stream_scores().await.unwrap().boxed();
+
+        let measured = FearFrame::new(0.6, [0.0; 7], 0.9, true, Duration::from_millis(5));
+        let lost = FearFrame::new(0.6, [0.0; 7], 0.0, true, Duration::from_millis(1)).with_face_present(false);
+        let mut present = Vec::new();
+        for frame in [measured.clone(), lost, measured] {
+            frame_sender.send(frame).await.unwrap();
+            let event = tokio::time::timeout(Duration::from_secs(2), scores.next()).await.unwrap().unwrap().unwrap();
+            match event.event {
+                Some(sensor_event::Event::Score(score)) => present.push(score.face_present),
+                other => panic!("Expected a score, got {:?}", other),
+            }
+        }
+        assert_eq!(present, [Some(true), Some(false), Some(true)]);
+
+        drop(frame_sender);
+        server.abort();
+    }
+
+    #[test]
+    fn test_event_filtering() {
+        let event = SensorEvent {
+            timestamp_us: 0,
+            sequence: 0,
+            event: Some(sensor_event::Event::Score(Score {
+                normalized_fear: 0.5,
+                raw_fear_logit: 0.3,
+                confidence: 0.9,
+                calibrated: true,
                 emotion_logits: vec![0.1; 7],
                 inference_latency_us: 5000,
+                startle: 0.0,
+                capability: SensorCapability::Full as i32,
+                face_bbox: None,
+                face_center: None,
+                fear_index: None,
+                face_present: None,
+                frame_sequence: 0,
             })),
         };
         
@@ -270,5 +1420,1040 @@ mod tests {
         assert!(!status.running); // Should not be running initially
         assert!(status.calibration.is_some());
         assert!(status.metrics.is_some());
+        assert!(status.models.is_empty()); // Nothing loaded before initialize
+        assert!(status.heartbeat_target.is_empty()); // No heartbeat before start
+        assert_eq!(status.power_profile, PowerProfile::Performance as i32);
+        assert_eq!((status.target_fps, status.emotion_interval), (30.0, 1));
+        assert!(!status.rates_overridden);
+    }
+
+    #[tokio::test]
+    async fn test_get_model_info_before_initialize() {
+        let service = SensorServiceImpl::new(EmotionSensor::new(SensorConfig::default()));
+        let response = service
+            .get_model_info(Request::new(ModelInfoRequest {}))
+            .await
+            .unwrap()
+            .into_inner();
+        assert!(response.models.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_measure_once_before_initialize() {
+        let service = SensorServiceImpl::new(EmotionSensor::new(SensorConfig::default()));
+        let status = service
+            .measure_once(Request::new(MeasureRequest { timeout_ms: 100 }))
+            .await
+            .unwrap_err();
+        assert_eq!(status.code(), Code::FailedPrecondition);
+    }
+
+    #[tokio::test]
+    async fn test_swap_emotion_model_rejects_bad_requests() {
+        let service = SensorServiceImpl::new(EmotionSensor::new(SensorConfig::default()));
+
+        let status = service
+            .swap_emotion_model(Request::new(SwapModelRequest { model: None, reset_calibration: None }))
+            .await
+            .unwrap_err();
+        assert_eq!(status.code(), Code::InvalidArgument);
+
+        let status = service
+            .swap_emotion_model(Request::new(SwapModelRequest {
+                model: Some(swap_model_request::Model::Path("new.onnx".to_string())),
+                reset_calibration: None,
+            }))
+            .await
+            .unwrap_err();
+        assert_eq!(status.code(), Code::FailedPrecondition);
+    }
+
+    #[tokio::test]
+    async fn test_update_config_overrides_until_cleared() {
+        let service = SensorServiceImpl::new(EmotionSensor::new(SensorConfig::default()));
+        let update = |target_fps, emotion_interval, clear_overrides| {
+            service.update_config(Request::new(UpdateConfigRequest { target_fps, emotion_interval, clear_overrides }))
+        };
+
+        for (fps, interval) in [(Some(0.5), None), (Some(240.0), None), (None, Some(0))] {
+            let status = update(fps, interval, false).await.unwrap_err();
+            assert_eq!(status.code(), Code::InvalidArgument);
+        }
+
+        let response = update(Some(15.0), None, false).await.unwrap().into_inner();
+        assert_eq!((response.target_fps, response.emotion_interval), (15.0, 1));
+        assert!(response.overridden);
+        // Unset fields keep the earlier override
+        let response = update(None, Some(4), false).await.unwrap().into_inner();
+        assert_eq!((response.target_fps, response.emotion_interval), (15.0, 4));
+
+        let status = service.get_status(Request::new(StatusRequest {})).await.unwrap().into_inner();
+        assert_eq!((status.target_fps, status.emotion_interval), (15.0, 4));
+        assert!(status.rates_overridden);
+
+        let response = update(None, None, true).await.unwrap().into_inner();
+        assert_eq!((response.target_fps, response.emotion_interval), (30.0, 1));
+        assert_eq!(response.power_profile, PowerProfile::Performance as i32);
+        assert!(!response.overridden);
+    }
+
+    #[tokio::test]
+    async fn test_log_level_and_log_pages() {
+        use crate::logging::LoggingConfig;
+        use tracing_subscriber::layer::SubscriberExt;
+
+        let service = SensorServiceImpl::new(EmotionSensor::new(SensorConfig::default()));
+        let status = service.get_logs(Request::new(GetLogsRequest::default())).await.unwrap_err();
+        assert_eq!(status.code(), Code::FailedPrecondition);
+
+        let config = LoggingConfig {
+            filter: "warn".to_string(),
+            ring_level: "warn".to_string(),
+            ring_capacity: 64,
+            ..LoggingConfig::default()
+        };
+        let (logs, layer) = LogControl::layer(&config).unwrap();
+        let _guard = tracing::subscriber::set_default(tracing_subscriber::registry().with(layer));
+        let service = service.with_log_control(Arc::new(logs));
+        let set_level = |filter: &str| service.set_log_level(Request::new(SetLogLevelRequest { filter: filter.to_string() }));
+        let get_logs = |since, min_level: LogLevel, limit| {
+            service.get_logs(Request::new(GetLogsRequest { since, min_level: min_level as i32, limit }))
+        };
+
+        tracing::debug!(target: "log_rpc_test", "before the filter changed");
+        let status = set_level("log_rpc_test=chatty").await.unwrap_err();
+        assert_eq!(status.code(), Code::InvalidArgument);
+        let response = set_level("warn,log_rpc_test=debug").await.unwrap().into_inner();
+        assert_eq!(response.previous_filter, "warn");
+        for line in 0..5 {
+            tracing::debug!(target: "log_rpc_test", line, "debug line");
+        }
+        tracing::warn!(target: "log_rpc_test", "careful");
+
+        // Pages of two, oldest first, until nothing is left
+        let mut since = 0;
+        let mut records = Vec::new();
+        loop {
+            let page = get_logs(since, LogLevel::Unspecified, 2).await.unwrap().into_inner();
+            assert!(page.records.len() <= 2);
+            assert_eq!(page.dropped, 0);
+            records.extend(page.records);
+            since = page.next_since;
+            if !page.more {
+                break;
+            }
+        }
+        let messages: Vec<&str> = records.iter().map(|record| record.message.as_str()).collect();
+        assert_eq!(messages, ["debug line", "debug line", "debug line", "debug line", "debug line", "careful"]);
+        assert!(records.windows(2).all(|pair| pair[0].sequence < pair[1].sequence));
+        assert_eq!(records[3].level, LogLevel::Debug as i32);
+        assert_eq!(records[3].fields, [LogField { name: "line".to_string(), value: "3".to_string() }]);
+        assert_eq!(records[5].target, "log_rpc_test");
+
+        let page = get_logs(0, LogLevel::Warn, 0).await.unwrap().into_inner();
+        assert_eq!(page.records.iter().map(|record| record.message.as_str()).collect::<Vec<_>>(), ["careful"]);
+        assert_eq!(page.records[0].level, LogLevel::Warn as i32);
+        // Caught up
+        assert!(get_logs(since, LogLevel::Unspecified, 0).await.unwrap().into_inner().records.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_ping_answers_with_sensor_clocks_and_records_estimate() {
+        use crate::resume::ManualClock;
+        use std::time::{Duration, SystemTime};
+
+        let clock = Arc::new(ManualClock::new(SystemTime::UNIX_EPOCH + Duration::from_secs(1_000)));
+        clock.advance(Duration::from_millis(250));
+        let sensor = EmotionSensor::new(SensorConfig::default());
+        let mut recorded = sensor.subscribe_clock_syncs();
+        let service = SensorServiceImpl::new(sensor).with_clock(clock.clone());
+
+        let estimate = ClockSyncEstimate {
+            wall_offset_us: 999_000_000,
+            monotonic_offset_us: -1_000,
+            error_us: 400,
+            confidence: 0.8,
+            samples: 12,
+        };
+        let reply = service
+            .ping(Request::new(PingRequest {
+                game_time_us: 42,
+                clock_sync: Some(estimate.into()),
+            }))
+            .await
+            .unwrap()
+            .into_inner();
+        assert_eq!(reply.game_time_us, 42);
+        assert_eq!(reply.sensor_monotonic_us, 250_000);
+        assert_eq!(reply.sensor_wall_us, 1_000_250_000);
+
+        // The estimate reaches recordings as a clock sync event
+        let event = clock_sync_event(recorded.try_recv().unwrap());
+        assert_eq!(event.timestamp_us, reply.sensor_wall_us);
+        assert!(should_send_event(&event, &[EventType::ClockSync]));
+        assert!(!should_send_event(&event, &[EventType::Score]));
+        match event.event {
+            Some(sensor_event::Event::ClockSync(clock_sync)) => {
+                assert_eq!(ClockSyncEstimate::from(clock_sync), estimate);
+            }
+            other => panic!("expected clock sync event, got {:?}", other),
+        }
+
+        // Pings without an estimate record nothing
+        service
+            .ping(Request::new(PingRequest { game_time_us: 43, clock_sync: None }))
+            .await
+            .unwrap();
+        assert!(recorded.try_recv().is_err());
+    }
+
+    #[test]
+    fn test_model_info_response_serialization() {
+        use prost::Message;
+
+        let external = model_info::ModelInfo::hash_only("face_emotion.onnx", model_info::sha256_hex(b"model"));
+        let response = ModelInfoResponse {
+            models: [crate::YUNET_MODEL_INFO, external]
+                .iter()
+                .map(model_info_message)
+                .collect(),
+        };
+
+        let decoded = ModelInfoResponse::decode(response.encode_to_vec().as_slice()).unwrap();
+        assert_eq!(decoded, response);
+
+        let yunet = &decoded.models[0];
+        assert_eq!(yunet.name, "YuNet");
+        assert_eq!(yunet.license, "MIT");
+        assert_eq!(yunet.sha256, crate::YUNET_MODEL_INFO.sha256);
+        assert!(yunet.embedded);
+
+        let fallback = &decoded.models[1];
+        assert_eq!(fallback.name, "face_emotion.onnx");
+        assert_eq!(fallback.license, model_info::UNKNOWN);
+        assert_eq!(fallback.sha256.len(), 64);
+        assert!(!fallback.embedded);
+    }
+
+    #[tokio::test]
+    async fn test_insert_marker() {
+        let sensor = EmotionSensor::new(SensorConfig::default());
+        let mut markers = sensor.subscribe_markers();
+        let service = SensorServiceImpl::new(sensor);
+
+        let response = service
+            .insert_marker(Request::new(MarkerRequest { label: "variant_b".to_string() }))
+            .await
+            .unwrap()
+            .into_inner();
+        assert!(response.success);
+        assert!(response.timestamp_us > 0);
+
+        let event = marker_event(markers.try_recv().unwrap());
+        assert_eq!(event.timestamp_us, response.timestamp_us);
+        assert!(should_send_event(&event, &[EventType::Marker]));
+        assert!(!should_send_event(&event, &[EventType::Score]));
+
+        let rejected = service
+            .insert_marker(Request::new(MarkerRequest { label: "  ".to_string() }))
+            .await
+            .unwrap()
+            .into_inner();
+        assert!(!rejected.success);
+    }
+
+    #[tokio::test]
+    async fn test_purge_now_reports_deleted_files() {
+        use crate::retention::tests::{aged_file, config_for, scratch_dir};
+        use std::time::Duration;
+
+        let root = scratch_dir("rpc");
+        let day = Duration::from_secs(24 * 60 * 60);
+        aged_file(&root.join("recordings/old.jsonl"), day * 10);
+        aged_file(&root.join("profiles/current.json"), day);
+
+        let mut config = SensorConfig::default();
+        config.retention = config_for(&root);
+        let service = SensorServiceImpl::new(EmotionSensor::new(config));
+
+        let preview = service
+            .purge_now(Request::new(PurgeRequest { dry_run: Some(true) }))
+            .await
+            .unwrap()
+            .into_inner();
+        assert!(preview.dry_run);
+        assert_eq!(preview.purged.len(), 1);
+        assert!(root.join("recordings/old.jsonl").exists());
+
+        let response = service
+            .purge_now(Request::new(PurgeRequest { dry_run: None }))
+            .await
+            .unwrap()
+            .into_inner();
+        assert!(response.success);
+        assert!(!response.dry_run);
+        assert_eq!(response.files_scanned, 2);
+        assert_eq!(response.purged.len(), 1);
+        assert_eq!(response.purged[0].category, "recordings");
+        assert!(response.purged[0].path.ends_with("old.jsonl"));
+        assert!(response.purged[0].age_secs >= 10 * 24 * 60 * 60 - 60);
+        assert!(!root.join("recordings/old.jsonl").exists());
+        assert!(root.join("profiles/current.json").exists());
+
+        let status = service
+            .get_status(Request::new(StatusRequest {}))
+            .await
+            .unwrap()
+            .into_inner();
+        let retention = status.retention.unwrap();
+        assert_eq!(retention.recordings_purged, 1);
+        assert!(retention.last_sweep_us.is_some());
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_capture_bug_report() {
+        let root = std::env::temp_dir().join(format!("spectre_bug_report_rpc_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&root);
+
+        let mut config = SensorConfig::default();
+        config.bug_report.output_dir = root.clone();
+        let service = SensorServiceImpl::new(EmotionSensor::new(config));
+
+        let response = service
+            .capture_bug_report(Request::new(BugReportRequest {}))
+            .await
+            .unwrap()
+            .into_inner();
+        assert!(response.success, "{:?}", response.error_message);
+        assert_eq!(response.frame_count, 0);
+        assert_eq!(response.crop_count, 0);
+        assert!(response.bundle_bytes > 0);
+
+        let bundle = std::path::Path::new(&response.bundle_path);
+        assert!(bundle.starts_with(&root));
+        assert!(bundle.join("status.json").is_file());
+        assert!(bundle.join("config.json").is_file());
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    /// Serve an idle sensor on `transport` and query its status through `SensorClient`
+    async fn assert_status_round_trip(transport: SensorTransport) {
+        use crate::grpc_client::SensorClient;
+        use std::time::Duration;
+
+        let server = tokio::spawn(start_grpc_server(
+            transport.clone(),
+            EmotionSensor::new(SensorConfig::default()),
+        ));
+
+        // The listener binds asynchronously
+        let mut client = None;
+        for _ in 0..50 {
+            match SensorClient::connect(&transport).await {
+                Ok(connected) => {
+                    client = Some(connected);
+                    break;
+                },
+                Err(_) => tokio::time::sleep(Duration::from_millis(20)).await,
+            }
+        }
+        let mut client = client.unwrap_or_else(|| panic!("Could not connect over {}", transport));
+
+        let status = client.get_status().await.unwrap();
+        assert!(!status.running);
+        assert!(status.calibration.is_some());
+
+        let marker = client.insert_marker("transport").await.unwrap();
+        assert!(marker.success);
+
+        server.abort();
+    }
+
+    #[tokio::test]
+    async fn test_loopback_transport_round_trip() {
+        assert_status_round_trip(SensorTransport::loopback()).await;
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_unix_socket_round_trip() {
+        let path = std::env::temp_dir().join(format!("spectre_grpc_{}.sock", std::process::id()));
+        assert_status_round_trip(SensorTransport::Unix(path.clone())).await;
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[cfg(windows)]
+    #[tokio::test]
+    async fn test_named_pipe_round_trip() {
+        let name = format!("spectre_grpc_{}", std::process::id());
+        assert_status_round_trip(SensorTransport::named_pipe(&name)).await;
+    }
+
+    #[test]
+    fn test_baseline_stats_conversion() {
+        use crate::calibrator::AdaptiveCalibrator;
+        use std::time::Duration;
+
+        let mut calibrator = AdaptiveCalibrator::new(Duration::ZERO, 0.05).with_per_channel_calibration(true);
+        calibrator.add_logits(&[0.1, 0.2, 0.3, 0.4, 0.5, 0.6, 0.7]).unwrap();
+        let stats = baseline_stats(calibrator.baseline_stats());
+
+        assert_eq!(stats.sample_count, 1);
+        assert_eq!(stats.channels.len(), 7);
+        assert_eq!(stats.channels[2].mean, stats.mean);
+
+        let fear_only = AdaptiveCalibrator::with_defaults(Duration::from_secs(30));
+        assert!(baseline_stats(fear_only.baseline_stats()).channels.is_empty());
+    }
+
+    #[test]
+    fn test_capability_conversion() {
+        for capability in [
+            types::SensorCapability::Full,
+            types::SensorCapability::HoldLastValue,
+            types::SensorCapability::EmotionOffline,
+        ] {
+            assert_eq!(types::SensorCapability::from(SensorCapability::from(capability)), capability);
+        }
+        assert_eq!(types::SensorCapability::from(SensorCapability::Unspecified), types::SensorCapability::Full);
+
+        let frame = FearFrame::new(0.0, [0.0; 7], 0.9, true, std::time::Duration::ZERO)
+            .with_capability(types::SensorCapability::EmotionOffline);
+        match score_event(&frame).event {
+            Some(sensor_event::Event::Score(score)) => {
+                assert_eq!(score.capability(), SensorCapability::EmotionOffline);
+            }
+            other => panic!("expected score event, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_fault_event_conversion() {
+        let notice = SensorFaultNotice::new(
+            FaultLevel::Info,
+            "System resumed after 600.0s suspend",
+            crate::resume::SYSTEM_RESUMED,
+            true,
+        );
+        let event = fault_event(notice.clone());
+
+        assert_eq!(event.timestamp_us, notice.timestamp_us);
+        match event.event {
+            Some(sensor_event::Event::SensorFault(fault)) => {
+                assert_eq!(fault.severity, FaultSeverity::Info as i32);
+                assert_eq!(fault.error_code, "SYSTEM_RESUMED");
+                assert!(fault.recoverable);
+            },
+            other => panic!("Expected fault event, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_model_swapped_event_conversion() {
+        let swapped = model_swap::ModelSwapped::new("aaaa", "bbbb", true);
+        let event = model_swapped_event(swapped.clone());
+
+        assert_eq!(event.timestamp_us, swapped.timestamp_us);
+        assert!(should_send_event(&event, &[EventType::ModelSwapped]));
+        assert!(!should_send_event(&event, &[EventType::Score]));
+        match event.event {
+            Some(sensor_event::Event::ModelSwapped(message)) => {
+                assert_eq!(message.old_sha256, "aaaa");
+                assert_eq!(message.new_sha256, "bbbb");
+                assert!(message.calibration_reset);
+            },
+            other => panic!("Expected model swapped event, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_power_profile_event_conversion() {
+        let change = power::PowerProfileChanged {
+            from: power::PowerProfile::Performance,
+            to: power::PowerProfile::Saver,
+            reason: "on battery at 15%, 60°C".to_string(),
+            rates: power::ProfileRates { target_fps: 10.0, emotion_interval: 3 },
+            overridden: false,
+            timestamp_us: 1_700_000_000_000_000,
+        };
+        let event = power_profile_event(change);
+
+        assert_eq!(event.timestamp_us, 1_700_000_000_000_000);
+        assert!(should_send_event(&event, &[EventType::PowerProfile]));
+        assert!(!should_send_event(&event, &[EventType::Score]));
+        match event.event {
+            Some(sensor_event::Event::PowerProfileChanged(message)) => {
+                assert_eq!(message.from(), PowerProfile::Performance);
+                assert_eq!(message.to(), PowerProfile::Saver);
+                assert_eq!(message.reason, "on battery at 15%, 60°C");
+                assert_eq!((message.target_fps, message.emotion_interval), (10.0, 3));
+                assert!(!message.overridden);
+            },
+            other => panic!("Expected power profile event, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_sensor_stop_ends_every_stream() {
+        use crate::grpc_client::SensorClient;
+        use futures::StreamExt;
+        use std::time::Duration;
+
+        // Stand in for a started sensor with frames fed by the test
+        let (frame_sender, frame_receiver) = async_channel::bounded(16);
+        let service = SensorServiceImpl::new(EmotionSensor::new(SensorConfig::default()));
+        *service.frames.lock().await = Some(FrameFanout::new(frame_receiver));
+        let sensor = Arc::clone(&service.sensor);
+
+        let transport = SensorTransport::loopback();
+        let incoming = transport.listen().await.unwrap();
+        let server = tokio::spawn(
+            Server::builder()
+                .add_service(SensorServiceServer::new(service))
+                .serve_with_incoming(incoming),
+        );
+
+        let mut client = SensorClient::connect(&transport).await.unwrap();
+        let all_events = client.stream_events().await.unwrap();
+        let scores_only = client.stream_scores().await.unwrap();
+        let mut streams = [all_events.boxed(), scores_only.boxed()];
+
+        let frame = FearFrame::new(0.4, [0.0; 7], 0.9, true, Duration::from_millis(5));
+        frame_sender.send(frame).await.unwrap();
+        for stream in &mut streams {
+            let event = stream.next().await.unwrap().unwrap();
+            assert!(matches!(event.event, Some(sensor_event::Event::Score(_))));
+        }
+
+        sensor.lock().await.stop().await.unwrap();
+
+        // Both streams get the terminal fault, filters notwithstanding, then end
+        for stream in &mut streams {
+            let ending = tokio::time::timeout(Duration::from_secs(2), async {
+                let last = stream.next().await;
+                (last, stream.next().await)
+            });
+            let (last, end) = ending.await.expect("stream did not end after stop");
+            match last.unwrap().unwrap().event {
+                Some(sensor_event::Event::SensorFault(fault)) => {
+                    assert_eq!(fault.error_code, types::SENSOR_STOPPED);
+                    assert_eq!(fault.severity, FaultSeverity::Info as i32);
+                    assert!(!fault.recoverable);
+                },
+                other => panic!("Expected stop fault, got {:?}", other),
+            }
+            assert!(end.is_none());
+        }
+
+        let status = client.get_status().await.unwrap();
+        assert!(!status.running);
+        assert_eq!(status.stopped_reason, "Stopped by request");
+
+        drop(frame_sender);
+        server.abort();
+    }
+
+    #[tokio::test]
+    async fn test_phases_start_with_current_phase_and_end_on_stop() {
+        use crate::grpc_client::SensorClient;
+        use crate::phases::PhaseOutcome;
+        use futures::StreamExt;
+        use std::time::Duration;
+
+        let (frame_sender, frame_receiver) = async_channel::bounded(16);
+        let service = SensorServiceImpl::new(EmotionSensor::new(SensorConfig::default()));
+        *service.frames.lock().await = Some(FrameFanout::new(frame_receiver));
+        let sensor = Arc::clone(&service.sensor);
+
+        let transport = SensorTransport::loopback();
+        let incoming = transport.listen().await.unwrap();
+        let server = tokio::spawn(
+            Server::builder()
+                .add_service(SensorServiceServer::new(service))
+                .serve_with_incoming(incoming),
+        );
+
+        // Nothing changes while subscribed, yet the phase arrives
+        let mut client = SensorClient::connect(&transport).await.unwrap();
+        let mut phases = client.phases().await.unwrap();
+        let outcome = phases
+            .wait_for_phase(|phase| *phase == CalibrationPhase::Idle, Duration::from_secs(2))
+            .await
+            .unwrap();
+        assert_eq!(outcome, PhaseOutcome::Reached(CalibrationPhase::Idle));
+
+        // Streams that did not ask for it start with the next event as before
+        let mut events = client.stream_events().await.unwrap().boxed();
+        frame_sender
+            .send(FearFrame::new(0.4, [0.0; 7], 0.9, false, Duration::from_millis(5)))
+            .await
+            .unwrap();
+        let event = events.next().await.unwrap().unwrap();
+        assert!(matches!(event.event, Some(sensor_event::Event::Score(_))));
+
+        sensor.lock().await.stop().await.unwrap();
+        let outcome = phases.wait_for_calibrated(Duration::from_secs(2)).await.unwrap();
+        assert_eq!(outcome, PhaseOutcome::SensorStopped);
+
+        drop(frame_sender);
+        server.abort();
+    }
+
+    #[tokio::test]
+    async fn test_calibration_control_rejects_illegal_transitions() {
+        use crate::calibrator::AdaptiveCalibrator;
+        use crate::grpc_client::{calibration_rejection, SensorClient};
+        use crate::types::SensorCapability;
+
+        let service = SensorServiceImpl::new(EmotionSensor::new(SensorConfig::default()));
+        let sensor = Arc::clone(&service.sensor);
+        let transport = SensorTransport::loopback();
+        let incoming = transport.listen().await.unwrap();
+        let server = tokio::spawn(
+            Server::builder()
+                .add_service(SensorServiceServer::new(service))
+                .serve_with_incoming(incoming),
+        );
+        let mut client = SensorClient::connect(&transport).await.unwrap();
+
+        // Nothing to control before initialization, and no phase to blame
+        let status = client.freeze_calibration().await.unwrap_err();
+        assert_eq!(status.code(), Code::FailedPrecondition);
+        assert_eq!(calibration_rejection(&status), None);
+
+        // Stand-in for the processing loop, applying actions between samples
+        let mut inbox = sensor.lock().await.attach_calibration_inbox();
+        let calibrator = Arc::new(std::sync::Mutex::new(AdaptiveCalibrator::new(Duration::ZERO, 0.05)));
+        let (phase, _) = watch::channel(CalibrationPhase::Idle);
+        let pipeline = tokio::spawn({
+            let calibrator = Arc::clone(&calibrator);
+            async move {
+                loop {
+                    inbox.apply_pending(&mut calibrator.lock().unwrap(), SensorCapability::Full, &phase);
+                    tokio::time::sleep(Duration::from_millis(1)).await;
+                }
+            }
+        });
+
+        let status = client.freeze_calibration().await.unwrap_err();
+        assert_eq!(status.code(), Code::FailedPrecondition);
+        assert_eq!(calibration_rejection(&status), Some(("collecting", "freeze")));
+        let response = client.start_calibration().await.unwrap();
+        assert_eq!(response.phase.unwrap().state, CalibrationState::Collecting as i32);
+
+        for _ in 0..30 {
+            calibrator.lock().unwrap().add_sample(0.5).unwrap();
+        }
+        let response = client.freeze_calibration().await.unwrap();
+        assert!(response.success);
+        let phase = response.phase.unwrap();
+        assert_eq!(phase.state, CalibrationState::Frozen as i32);
+        assert!(phase.frozen);
+
+        let status = client.reset_calibration().await.unwrap_err();
+        assert_eq!(calibration_rejection(&status), Some(("frozen", "reset")));
+        assert!(calibrator.lock().unwrap().is_calibrated());
+
+        let response = client.unfreeze_calibration().await.unwrap();
+        let phase = response.phase.unwrap();
+        assert_eq!(phase.state, CalibrationState::Calibrated as i32);
+        assert!(!phase.frozen);
+        assert!(!calibrator.lock().unwrap().is_frozen());
+
+        // A loop that went away fails the action instead of hanging
+        pipeline.abort();
+        let _ = pipeline.await;
+        let status = client.reset_calibration().await.unwrap_err();
+        assert_eq!(status.code(), Code::Aborted);
+
+        server.abort();
+    }
+
+    #[tokio::test]
+    #[cfg(feature = "metrics")]
+    async fn test_list_clients_reports_filters_counters_and_disconnects() {
+        use crate::grpc_client::SensorClient;
+        use futures::StreamExt;
+
+        let (frame_sender, frame_receiver) = async_channel::bounded(16);
+        let metrics = Arc::new(SensorMetrics::new().unwrap());
+        let service = SensorServiceImpl::new(EmotionSensor::new(SensorConfig::default()))
+            .with_metrics(Arc::clone(&metrics));
+        *service.frames.lock().await = Some(FrameFanout::new(frame_receiver));
+
+        let transport = SensorTransport::loopback();
+        let incoming = transport.listen().await.unwrap();
+        let server = tokio::spawn(
+            Server::builder()
+                .add_service(SensorServiceServer::new(service))
+                .serve_with_incoming(incoming),
+        );
+
+        let mut admin = SensorClient::connect(&transport).await.unwrap();
+        let mut game = SensorClient::connect(&transport).await.unwrap();
+        let mut monitor = SensorClient::connect(&transport).await.unwrap();
+        let mut everything = game.stream_events().await.unwrap().boxed();
+        let calibration = monitor.stream_calibration().await.unwrap().boxed();
+
+        for fear in [0.2, 0.4, 0.6] {
+            frame_sender.send(FearFrame::new(fear, [0.0; 7], 0.9, true, Duration::from_millis(5))).await.unwrap();
+            let event = tokio::time::timeout(Duration::from_secs(2), everything.next()).await.unwrap().unwrap().unwrap();
+            assert!(matches!(event.event, Some(sensor_event::Event::Score(_))));
+        }
+
+        let clients = admin.list_clients().await.unwrap().clients;
+        assert_eq!(clients.len(), 2);
+        let (all, filtered) = (&clients[0], &clients[1]);
+        assert!(all.client_id != filtered.client_id);
+        assert_eq!(all.peer, "loopback");
+        assert_eq!(all.request.as_ref().unwrap().event_types, Vec::<i32>::new());
+        assert_eq!(
+            filtered.request.as_ref().unwrap().event_types,
+            vec![EventType::CalibrationProgress as i32]
+        );
+        assert_eq!((all.events_delivered, all.events_dropped, all.queue_depth), (3, 0, 0));
+        assert_eq!((filtered.events_delivered, filtered.events_dropped), (0, 0));
+        assert!(all.last_activity_us > all.connected_at_us);
+        assert_eq!(filtered.last_activity_us, filtered.connected_at_us);
+        assert!(metrics.gather().unwrap().contains("spectre_connected_clients 2"));
+
+        // A client that goes away is listed no longer, without waiting for an event
+        drop(everything);
+        let remaining = tokio::time::timeout(Duration::from_secs(2), async {
+            loop {
+                let clients = admin.list_clients().await.unwrap().clients;
+                if clients.len() == 1 {
+                    return clients;
+                }
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .expect("disconnected client still listed");
+        assert_eq!(remaining[0].client_id, filtered.client_id);
+        assert!(metrics.gather().unwrap().contains("spectre_connected_clients 1"));
+
+        drop(calibration);
+        drop(frame_sender);
+        server.abort();
+    }
+
+    #[tokio::test]
+    async fn test_get_metrics_history_range() {
+        use crate::grpc_client::SensorClient;
+
+        let sensor = EmotionSensor::new(SensorConfig::default());
+        let history = sensor.metrics_history();
+        for i in 0..12u64 {
+            let metrics = types::PerformanceMetrics {
+                current_fps: 20.0 + i as f32,
+                p95_inference_latency: Duration::from_millis(6),
+                dropped_frames: i,
+                ..types::PerformanceMetrics::default()
+            };
+            history.lock().unwrap().record(1_000_000 + i * 5_000_000, &metrics);
+        }
+
+        let transport = SensorTransport::loopback();
+        let incoming = transport.listen().await.unwrap();
+        let server = tokio::spawn(
+            Server::builder()
+                .add_service(SensorServiceServer::new(SensorServiceImpl::new(sensor)))
+                .serve_with_incoming(incoming),
+        );
+        let mut client = SensorClient::connect(&transport).await.unwrap();
+
+        let all = client.get_metrics_history(0, 0).await.unwrap();
+        assert_eq!((all.samples.len(), all.interval_ms, all.capacity), (12, 5000, 720));
+        assert_eq!(all.samples[0].timestamp_us, 1_000_000);
+        assert_eq!(all.samples[11].dropped_frames, 11);
+
+        let range = client.get_metrics_history(21_000_000, 31_000_000).await.unwrap();
+        let fps: Vec<f32> = range.samples.iter().map(|sample| sample.fps).collect();
+        assert_eq!(fps, [24.0, 25.0, 26.0]);
+        assert!(range.samples.iter().all(|sample| sample.p95_inference_latency_us == 6_000));
+
+        let since = client.get_metrics_history(51_000_000, 0).await.unwrap();
+        assert_eq!(since.samples.len(), 2);
+
+        let status = client.get_metrics_history(31_000_000, 21_000_000).await.unwrap_err();
+        assert_eq!(status.code(), Code::InvalidArgument);
+
+        server.abort();
+    }
+
+    /// Server end of a loopback connection that counts the bytes sent to the client
+    struct CountingStream {
+        inner: crate::transport::TransportStream,
+        written: Arc<std::sync::atomic::AtomicU64>,
+    }
+
+    impl tokio::io::AsyncRead for CountingStream {
+        fn poll_read(
+            mut self: Pin<&mut Self>,
+            cx: &mut std::task::Context<'_>,
+            buf: &mut tokio::io::ReadBuf<'_>,
+        ) -> std::task::Poll<std::io::Result<()>> {
+            Pin::new(&mut self.inner).poll_read(cx, buf)
+        }
+    }
+
+    impl tokio::io::AsyncWrite for CountingStream {
+        fn poll_write(
+            mut self: Pin<&mut Self>,
+            cx: &mut std::task::Context<'_>,
+            buf: &[u8],
+        ) -> std::task::Poll<std::io::Result<usize>> {
+            let written = Pin::new(&mut self.inner).poll_write(cx, buf);
+            if let std::task::Poll::Ready(Ok(count)) = &written {
+                self.written.fetch_add(*count as u64, std::sync::atomic::Ordering::Relaxed);
+            }
+            written
+        }
+
+        fn poll_flush(mut self: Pin<&mut Self>, cx: &mut std::task::Context<'_>) -> std::task::Poll<std::io::Result<()>> {
+            Pin::new(&mut self.inner).poll_flush(cx)
+        }
+
+        fn poll_shutdown(mut self: Pin<&mut Self>, cx: &mut std::task::Context<'_>) -> std::task::Poll<std::io::Result<()>> {
+            Pin::new(&mut self.inner).poll_shutdown(cx)
+        }
+    }
+
+    impl tonic::transport::server::Connected for CountingStream {
+        type ConnectInfo = ();
+
+        fn connect_info(&self) -> Self::ConnectInfo {}
+    }
+
+    /// A served sensor fed by the test, with a count of the bytes sent to clients
+    struct CountedServer {
+        frames: async_channel::Sender<FearFrame>,
+        transport: SensorTransport,
+        written: Arc<std::sync::atomic::AtomicU64>,
+        server: tokio::task::JoinHandle<Result<(), tonic::transport::Error>>,
+    }
+
+    impl CountedServer {
+        async fn start(fanout_capacity: usize) -> Self {
+            use futures::StreamExt;
+
+            let (frames, frame_receiver) = async_channel::bounded(64);
+            let service = SensorServiceImpl::new(EmotionSensor::new(SensorConfig::default()));
+            *service.frames.lock().await = Some(FrameFanout::with_capacity(frame_receiver, fanout_capacity));
+
+            let transport = SensorTransport::loopback();
+            let written = Arc::new(std::sync::atomic::AtomicU64::new(0));
+            let counter = Arc::clone(&written);
+            let incoming = transport.listen().await.unwrap().map(move |stream| {
+                stream.map(|inner| CountingStream { inner, written: Arc::clone(&counter) })
+            });
+            let server = tokio::spawn(
+                Server::builder()
+                    .add_service(sensor_service(service))
+                    .serve_with_incoming(incoming),
+            );
+
+            Self { frames, transport, written, server }
+        }
+
+        fn written(&self) -> u64 {
+            self.written.load(std::sync::atomic::Ordering::Relaxed)
+        }
+    }
+
+    /// A seated player at 30 Hz: fear drifts, the rest barely moves, one jump scare
+    fn synthetic_frames(count: usize) -> Vec<FearFrame> {
+        use rand::{rngs::StdRng, Rng, SeedableRng};
+        use spectremesh_core::FEAR_INDEX;
+
+        let mut rng = StdRng::seed_from_u64(448);
+        let mut fear = 0.3f32;
+        (0..count)
+            .map(|index| {
+                fear = (fear + rng.gen_range(-0.02..0.02)).clamp(0.0, 1.0);
+                let mut logits = [0.3, -0.2, 0.0, 0.5, 0.1, -0.4, 0.2];
+                for logit in &mut logits {
+                    *logit += rng.gen_range(-0.01..0.01);
+                }
+                logits[FEAR_INDEX] = fear * 4.0 - 2.0;
+                let confidence = 0.9 + rng.gen_range(-0.005..0.005);
+                let latency = Duration::from_micros(rng.gen_range(4000..4500));
+                let startle = if index == count / 2 { 0.9 } else { 0.0 };
+                FearFrame::new(fear, logits, confidence, true, latency).with_startle(startle)
+            })
+            .collect()
+    }
+
+    /// Send `frames` one at a time over a fresh server; the bytes sent and the scores read
+    async fn measure_stream(
+        frames: &[FearFrame],
+        delta: Option<DeltaConfig>,
+        compression: Option<CompressionEncoding>,
+    ) -> (u64, Vec<Score>) {
+        use crate::grpc_client::SensorClient;
+        use futures::StreamExt;
+
+        let served = CountedServer::start(crate::fanout::DEFAULT_FANOUT_CAPACITY).await;
+        let mut client = SensorClient::connect(&served.transport).await.unwrap();
+        if let Some(encoding) = compression {
+            client = client.with_compression(encoding);
+        }
+        let mut events = match delta {
+            Some(config) => client.stream_events_delta(config).await.unwrap().boxed(),
+            None => client.stream_events().await.unwrap().boxed(),
+        };
+
+        let mut scores = Vec::with_capacity(frames.len());
+        for frame in frames {
+            served.frames.send(frame.clone()).await.unwrap();
+            let event = tokio::time::timeout(Duration::from_secs(2), events.next())
+                .await
+                .expect("no event for a frame")
+                .unwrap()
+                .unwrap();
+            match event.event {
+                Some(sensor_event::Event::Score(score)) => scores.push(score),
+                other => panic!("Expected a rebuilt score, got {:?}", other),
+            }
+        }
+
+        let written = served.written();
+        served.server.abort();
+        (written, scores)
+    }
+
+    #[tokio::test]
+    async fn test_delta_stream_bandwidth_and_reconstruction() {
+        let frames = synthetic_frames(300);
+        let config = DeltaConfig::default();
+
+        let (plain_bytes, plain) = measure_stream(&frames, None, None).await;
+        let (delta_bytes, rebuilt) = measure_stream(&frames, Some(config), None).await;
+        let (gzip_bytes, gzip_plain) = measure_stream(&frames, None, Some(CompressionEncoding::Gzip)).await;
+        let (compressed_bytes, compressed) =
+            measure_stream(&frames, Some(config), Some(CompressionEncoding::Zstd)).await;
+
+        let percent = |bytes: u64| 100.0 * bytes as f64 / plain_bytes as f64;
+        println!(
+            "{} scores: plain {} B, gzip {} B ({:.0}%), delta {} B ({:.0}%), delta+zstd {} B ({:.0}%)",
+            frames.len(),
+            plain_bytes,
+            gzip_bytes,
+            percent(gzip_bytes),
+            delta_bytes,
+            percent(delta_bytes),
+            compressed_bytes,
+            percent(compressed_bytes),
+        );
+        assert!(percent(delta_bytes) < 60.0, "delta encoding saved too little");
+
+        // Consumers see the scores a plain stream carries
+        assert_eq!(plain, frames.iter().map(score_message).collect::<Vec<_>>());
+        assert_eq!(gzip_plain, plain);
+        for received in [&rebuilt, &compressed] {
+            for (expected, actual) in plain.iter().zip(received) {
+                assert!((actual.normalized_fear - expected.normalized_fear).abs() < 1e-6);
+                assert!((actual.raw_fear_logit - expected.raw_fear_logit).abs() < 1e-6);
+                for (index, (actual, expected)) in actual.emotion_logits.iter().zip(&expected.emotion_logits).enumerate() {
+                    let epsilon = if index == spectremesh_core::FEAR_INDEX { 1e-6 } else { config.logit_epsilon };
+                    assert!((actual - expected).abs() <= epsilon, "logit {}: {} vs {}", index, actual, expected);
+                }
+                assert!((actual.confidence - expected.confidence).abs() <= config.value_epsilon);
+                assert_eq!(actual.startle, expected.startle);
+                assert_eq!(actual.calibrated, expected.calibrated);
+                assert_eq!(actual.capability, expected.capability);
+            }
+            assert_eq!(received.len(), plain.len());
+        }
+    }
+
+    #[tokio::test]
+    async fn test_wider_emotion_layout_streams_whole() {
+        use spectremesh_core::{EmotionLayout, EmotionLogits};
+
+        // Ten classes with fear at 7, where the standard layout has none
+        let layout = EmotionLayout::new(10, 7).unwrap();
+        let frames: Vec<FearFrame> = (0..5)
+            .map(|index| {
+                let mut values = vec![0.1; 10];
+                values[7] = index as f32;
+                let logits = EmotionLogits::new(values, layout).unwrap();
+                FearFrame::new(index as f32 / 5.0, logits, 0.9, true, Duration::from_millis(5))
+            })
+            .collect();
+
+        for delta in [None, Some(DeltaConfig::default())] {
+            let (_, scores) = measure_stream(&frames, delta, None).await;
+            for (index, score) in scores.iter().enumerate() {
+                assert_eq!(score.emotion_logits.len(), 10);
+                assert_eq!(score.fear_index, Some(7));
+                assert!((score.raw_fear_logit - index as f32).abs() < 1e-6);
+                assert!((score.emotion_logits[7] - index as f32).abs() < 1e-6);
+            }
+        }
+    }
+
+    /// Next event of a raw stream, as received on the wire
+    async fn next_event(events: &mut tonic::Streaming<SensorEvent>) -> SensorEvent {
+        tokio::time::timeout(Duration::from_secs(2), events.message())
+            .await
+            .expect("no event")
+            .unwrap()
+            .expect("stream ended")
+    }
+
+    #[tokio::test]
+    async fn test_delta_stream_resyncs_after_gap() {
+        use crate::delta::FearStreamConsumer;
+        use crate::proto::sensor_service_client::SensorServiceClient;
+
+        // A ring of four frames: a burst of twelve leaves the stream behind
+        let served = CountedServer::start(4).await;
+        let mut client = SensorServiceClient::new(served.transport.connect().await.unwrap());
+        let mut request = StreamRequest::default();
+        DeltaConfig::default().apply(&mut request);
+        let mut events = client.stream_events(request).await.unwrap().into_inner();
+        let mut consumer = FearStreamConsumer::new();
+        let frames = synthetic_frames(20);
+
+        served.frames.send(frames[0].clone()).await.unwrap();
+        let first = next_event(&mut events).await;
+        assert_eq!(first.sequence, 1);
+        assert!(matches!(first.event, Some(sensor_event::Event::Score(_))));
+        served.frames.send(frames[1].clone()).await.unwrap();
+        let second = next_event(&mut events).await;
+        assert!(matches!(second.event, Some(sensor_event::Event::ScoreDelta(_))));
+        assert!(consumer.consume(first).is_some() && consumer.consume(second).is_some());
+
+        // Queued without yielding, so the server reads the whole burst at once
+        for frame in &frames[2..14] {
+            served.frames.try_send(frame.clone()).unwrap();
+        }
+        let resync = next_event(&mut events).await;
+        assert!(resync.sequence > 3, "sequence {} shows no gap", resync.sequence);
+        let expected = score_message(&frames[13]);
+        match consumer.consume(resync).and_then(|event| event.event) {
+            Some(sensor_event::Event::Score(score)) => assert_eq!(score, expected),
+            other => panic!("Expected a full score after the gap, got {:?}", other),
+        }
+        assert_eq!(consumer.gaps(), 1);
+
+        // Deltas resume against the new keyframe
+        served.frames.send(frames[14].clone()).await.unwrap();
+        let after = next_event(&mut events).await;
+        assert!(matches!(after.event, Some(sensor_event::Event::ScoreDelta(_))));
+        match consumer.consume(after).and_then(|event| event.event) {
+            Some(sensor_event::Event::Score(score)) => {
+                assert!((score.normalized_fear - frames[14].fear_score).abs() < 1e-6);
+            }
+            other => panic!("Expected a rebuilt score, got {:?}", other),
+        }
+        assert_eq!(consumer.dropped_deltas(), 0);
+
+        served.server.abort();
     }
 }
@@ -0,0 +1,410 @@
+//! Liveness heartbeat for show-control dead-man's switches
+//!
+//! Installations rig a physical fallback: when the fear system dies, a relay
+//! switches the lighting to a safe preset. With a [`HeartbeatTarget`]
+//! configured the sensor beats every `interval` for as long as it is healthy
+//! by the same criteria as the metrics server's `/health` endpoint: the
+//! pipeline is running and its last captured frame is younger than
+//! `stale_after`. A stalled pipeline stops the beats within `stale_after`
+//! plus one interval, even while the daemon process lives on, and they
+//! resume with the next fresh frame.
+//!
+//! A beat is one ASCII line, `OK <fps> <fear>\n`, written either to a serial
+//! port (with the `serial-heartbeat` feature) or into a watchdog file whose
+//! modification time an external monitor checks.
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use thiserror::Error;
+use tokio::time::MissedTickBehavior;
+
+/// Baud rate of serial targets that do not name one
+pub const DEFAULT_BAUD_RATE: u32 = 9600;
+
+/// Where heartbeats go
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum HeartbeatTarget {
+    /// Rewrite a watchdog file, refreshing its modification time
+    File { path: PathBuf },
+    /// Write to a serial port
+    Serial {
+        port: String,
+        #[serde(default = "default_baud_rate")]
+        baud_rate: u32,
+    },
+}
+
+fn default_baud_rate() -> u32 {
+    DEFAULT_BAUD_RATE
+}
+
+impl fmt::Display for HeartbeatTarget {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::File { path } => write!(f, "file:{}", path.display()),
+            Self::Serial { port, baud_rate } => write!(f, "serial:{}@{}", port, baud_rate),
+        }
+    }
+}
+
+impl FromStr for HeartbeatTarget {
+    type Err = String;
+
+    /// `file:<path>` or `serial:<port>[@<baud rate>]`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.split_once(':') {
+            Some(("file", path)) if !path.is_empty() => Ok(Self::File { path: PathBuf::from(path) }),
+            Some(("serial", port)) => {
+                let (port, baud_rate) = match port.rsplit_once('@') {
+                    Some((port, baud_rate)) => (
+                        port,
+                        baud_rate
+                            .parse()
+                            .map_err(|_| format!("Invalid heartbeat baud rate: {}", baud_rate))?,
+                    ),
+                    None => (port, DEFAULT_BAUD_RATE),
+                };
+                if port.is_empty() {
+                    return Err(format!("Heartbeat target without a serial port: {}", s));
+                }
+                Ok(Self::Serial { port: port.to_string(), baud_rate })
+            }
+            _ => Err(format!("Unknown heartbeat target: {} (expected file:<path> or serial:<port>)", s)),
+        }
+    }
+}
+
+/// Heartbeat target, pace and staleness threshold
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HeartbeatConfig {
+    /// Where to beat; no heartbeat without one (overridable with SPECTRE_HEARTBEAT)
+    pub target: Option<HeartbeatTarget>,
+    /// Time between beats
+    #[serde(with = "spectremesh_core::duration")]
+    pub interval: Duration,
+    /// Age of the last captured frame past which the pipeline counts as
+    /// stalled, for `/health` as well as the heartbeat
+    #[serde(with = "spectremesh_core::duration")]
+    pub stale_after: Duration,
+}
+
+impl Default for HeartbeatConfig {
+    fn default() -> Self {
+        Self {
+            target: None,
+            interval: Duration::from_secs(1),
+            stale_after: Duration::from_secs(2),
+        }
+    }
+}
+
+impl HeartbeatConfig {
+    /// Validate the configuration
+    pub fn validate(&self) -> Result<(), String> {
+        if self.interval.is_zero() {
+            return Err("Heartbeat interval must be positive".to_string());
+        }
+        if self.stale_after.is_zero() {
+            return Err("Heartbeat staleness threshold must be positive".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// Heartbeat errors
+#[derive(Debug, Error)]
+pub enum HeartbeatError {
+    #[error("Serial heartbeat to {0} needs the serial-heartbeat feature")]
+    SerialUnsupported(String),
+
+    #[cfg(feature = "serial-heartbeat")]
+    #[error("Failed to open serial port {port}: {source}")]
+    SerialOpen {
+        port: String,
+        #[source]
+        source: serialport::Error,
+    },
+
+    #[error("Heartbeat write failed: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+/// Why the pipeline is not healthy
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Error)]
+pub enum Unhealthy {
+    #[error("Pipeline not running")]
+    Stopped,
+
+    #[error("No frame captured yet")]
+    NoFrames,
+
+    #[error("Last frame captured {0:?} ago")]
+    Stale(Duration),
+}
+
+/// Readings carried by a beat
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Vitals {
+    pub fps: f32,
+    /// Latest fear score, 0.0 before the first face
+    pub fear: f32,
+}
+
+impl Vitals {
+    /// The ASCII line sent for one beat
+    pub fn beat_line(&self) -> String {
+        format!("OK {:.1} {:.3}\n", self.fps, self.fear)
+    }
+}
+
+#[derive(Debug, Default)]
+struct Pulse {
+    running: bool,
+    last_frame: Option<Instant>,
+    vitals: Vitals,
+}
+
+/// Pipeline liveness, fed by the processing loop and read by `/health` and
+/// the heartbeat
+#[derive(Debug)]
+pub struct Liveness {
+    stale_after: Duration,
+    pulse: Mutex<Pulse>,
+}
+
+impl Liveness {
+    pub fn new(stale_after: Duration) -> Self {
+        Self {
+            stale_after,
+            pulse: Mutex::new(Pulse::default()),
+        }
+    }
+
+    /// Age of the last frame past which the pipeline counts as stalled
+    pub fn stale_after(&self) -> Duration {
+        self.stale_after
+    }
+
+    /// Mark the pipeline running; it is healthy from its first frame on
+    pub fn start(&self) {
+        *self.pulse.lock().unwrap() = Pulse { running: true, ..Pulse::default() };
+    }
+
+    /// Mark the pipeline stopped
+    pub fn stop(&self) {
+        self.pulse.lock().unwrap().running = false;
+    }
+
+    /// Note a frame captured at `now`
+    pub fn record_frame(&self, now: Instant) {
+        self.pulse.lock().unwrap().last_frame = Some(now);
+    }
+
+    /// Note the latest fear score
+    pub fn record_fear(&self, fear: f32) {
+        self.pulse.lock().unwrap().vitals.fear = fear;
+    }
+
+    /// Note the current frame rate
+    pub fn record_fps(&self, fps: f32) {
+        self.pulse.lock().unwrap().vitals.fps = fps;
+    }
+
+    /// The latest readings if the pipeline is healthy at `now`
+    pub fn check(&self, now: Instant) -> Result<Vitals, Unhealthy> {
+        let pulse = self.pulse.lock().unwrap();
+        if !pulse.running {
+            return Err(Unhealthy::Stopped);
+        }
+        let age = now.saturating_duration_since(pulse.last_frame.ok_or(Unhealthy::NoFrames)?);
+        if age > self.stale_after {
+            return Err(Unhealthy::Stale(age));
+        }
+        Ok(pulse.vitals)
+    }
+}
+
+impl Default for Liveness {
+    fn default() -> Self {
+        Self::new(HeartbeatConfig::default().stale_after)
+    }
+}
+
+/// An opened heartbeat target
+trait BeatSink: Send {
+    fn beat(&mut self, line: &str) -> std::io::Result<()>;
+}
+
+struct FileSink {
+    path: PathBuf,
+}
+
+impl BeatSink for FileSink {
+    fn beat(&mut self, line: &str) -> std::io::Result<()> {
+        // Rewriting the file refreshes its modification time
+        std::fs::write(&self.path, line)
+    }
+}
+
+#[cfg(feature = "serial-heartbeat")]
+struct SerialSink {
+    port: Box<dyn serialport::SerialPort>,
+}
+
+#[cfg(feature = "serial-heartbeat")]
+impl BeatSink for SerialSink {
+    fn beat(&mut self, line: &str) -> std::io::Result<()> {
+        use std::io::Write;
+        self.port.write_all(line.as_bytes())?;
+        self.port.flush()
+    }
+}
+
+/// Beats to a [`HeartbeatTarget`] while the pipeline is healthy
+pub struct Heartbeat {
+    target: HeartbeatTarget,
+    interval: Duration,
+    sink: Box<dyn BeatSink>,
+}
+
+impl Heartbeat {
+    /// Open the configured target, `None` if there is none
+    pub fn open(config: &HeartbeatConfig) -> Result<Option<Self>, HeartbeatError> {
+        let Some(target) = config.target.clone() else {
+            return Ok(None);
+        };
+        let sink: Box<dyn BeatSink> = match &target {
+            HeartbeatTarget::File { path } => Box::new(FileSink { path: path.clone() }),
+            #[cfg(feature = "serial-heartbeat")]
+            HeartbeatTarget::Serial { port, baud_rate } => {
+                let port = serialport::new(port, *baud_rate)
+                    .timeout(config.interval)
+                    .open()
+                    .map_err(|source| HeartbeatError::SerialOpen { port: port.clone(), source })?;
+                Box::new(SerialSink { port })
+            }
+            #[cfg(not(feature = "serial-heartbeat"))]
+            HeartbeatTarget::Serial { port, .. } => return Err(HeartbeatError::SerialUnsupported(port.clone())),
+        };
+        Ok(Some(Self {
+            target,
+            interval: config.interval,
+            sink,
+        }))
+    }
+
+    /// Where the beats go
+    pub fn target(&self) -> &HeartbeatTarget {
+        &self.target
+    }
+
+    /// Beat every interval while `liveness` is healthy, until the pipeline stops
+    ///
+    /// Beats pause, rather than end, while no frame arrives, and a failed
+    /// write is retried with the next beat.
+    pub async fn run(mut self, liveness: Arc<Liveness>) {
+        let mut ticks = tokio::time::interval(self.interval);
+        ticks.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        let mut paused = false;
+        let mut failing = false;
+        loop {
+            ticks.tick().await;
+            match liveness.check(Instant::now()) {
+                Ok(vitals) => {
+                    if paused {
+                        tracing::info!("Heartbeat to {} resumed", self.target);
+                        paused = false;
+                    }
+                    match self.sink.beat(&vitals.beat_line()) {
+                        Ok(()) => failing = false,
+                        Err(e) if !failing => {
+                            tracing::warn!("Heartbeat to {} failed: {}", self.target, e);
+                            failing = true;
+                        }
+                        Err(_) => {}
+                    }
+                }
+                Err(Unhealthy::Stopped) => {
+                    tracing::info!("Heartbeat to {} stopped with the pipeline", self.target);
+                    return;
+                }
+                Err(reason) => {
+                    if !paused {
+                        tracing::warn!("Heartbeat to {} paused: {}", self.target, reason);
+                        paused = true;
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_target_parsing() {
+        let file: HeartbeatTarget = "file:/run/spectre/alive".parse().unwrap();
+        assert_eq!(file, HeartbeatTarget::File { path: PathBuf::from("/run/spectre/alive") });
+        assert_eq!(file.to_string(), "file:/run/spectre/alive");
+
+        let serial: HeartbeatTarget = "serial:/dev/ttyUSB0".parse().unwrap();
+        assert_eq!(serial, HeartbeatTarget::Serial { port: "/dev/ttyUSB0".to_string(), baud_rate: 9600 });
+        let serial: HeartbeatTarget = "serial:COM3@115200".parse().unwrap();
+        assert_eq!(serial.to_string(), "serial:COM3@115200");
+        assert_eq!(serial.to_string().parse::<HeartbeatTarget>().unwrap(), serial);
+
+        assert!("file:".parse::<HeartbeatTarget>().is_err());
+        assert!("serial:@9600".parse::<HeartbeatTarget>().is_err());
+        assert!("serial:COM3@fast".parse::<HeartbeatTarget>().is_err());
+        assert!("gpio:17".parse::<HeartbeatTarget>().is_err());
+    }
+
+    #[test]
+    fn test_liveness_criteria() {
+        let liveness = Liveness::new(Duration::from_secs(2));
+        let t0 = Instant::now();
+        assert_eq!(liveness.check(t0), Err(Unhealthy::Stopped));
+
+        liveness.start();
+        assert_eq!(liveness.check(t0), Err(Unhealthy::NoFrames));
+
+        liveness.record_frame(t0);
+        liveness.record_fps(29.96);
+        liveness.record_fear(0.4);
+        let vitals = liveness.check(t0 + Duration::from_secs(2)).unwrap();
+        assert_eq!(vitals.beat_line(), "OK 30.0 0.400\n");
+        assert_eq!(
+            liveness.check(t0 + Duration::from_secs(3)),
+            Err(Unhealthy::Stale(Duration::from_secs(3)))
+        );
+
+        // A restart waits for a fresh frame
+        liveness.stop();
+        assert_eq!(liveness.check(t0), Err(Unhealthy::Stopped));
+        liveness.start();
+        assert_eq!(liveness.check(t0), Err(Unhealthy::NoFrames));
+    }
+
+    #[test]
+    fn test_serial_needs_feature() {
+        let config = HeartbeatConfig {
+            target: Some("serial:/dev/null-port".parse().unwrap()),
+            ..HeartbeatConfig::default()
+        };
+        let result = Heartbeat::open(&config);
+        #[cfg(not(feature = "serial-heartbeat"))]
+        assert!(matches!(result, Err(HeartbeatError::SerialUnsupported(_))));
+        #[cfg(feature = "serial-heartbeat")]
+        assert!(result.is_err());
+
+        assert!(Heartbeat::open(&HeartbeatConfig::default()).unwrap().is_none());
+    }
+}
@@ -0,0 +1,146 @@
+//! Time budget for face detection and emotion inference
+//!
+//! ONNX Runtime calls are synchronous, so a hung or pathologically slow run
+//! would hold the processing loop for as long as it lasts. Emotion inference
+//! runs on a worker thread of its own and the loop waits for it only up to
+//! `inference_timeout`: past that the frame is abandoned, the calibrator
+//! never sees it, and a result arriving later is thrown away. YuNet
+//! detection feeds the face tracker on the loop itself, so it is held to the
+//! same budget after the fact: a detection over budget abandons its frame.
+//!
+//! Every abandoned frame raises a `MODEL_INFERENCE_TIMEOUT` warning. A
+//! timeout leaves the degradation ladder alone, but a model still busy with
+//! the timed-out face when the next one arrives counts as a failure, so a
+//! session that hangs for good is held, rebuilt and taken offline like one
+//! that errors.
+
+use crate::degradation::EmotionBackend;
+use crate::sensor::SensorError;
+use crate::types::{FaultLevel, SensorFaultNotice};
+use opencv::{core::Mat, prelude::*};
+use ort::session::Session;
+use spectremesh_core::EmotionLogits;
+use std::sync::mpsc::{channel, Receiver, RecvTimeoutError, Sender, TryRecvError};
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+/// Fault code: a frame was abandoned because inference ran over budget
+pub const MODEL_INFERENCE_TIMEOUT: &str = "MODEL_INFERENCE_TIMEOUT";
+
+/// Stage name for a face detection over budget
+pub const FACE_DETECTION: &str = "Face detection";
+/// Stage name for an emotion inference over budget
+pub const EMOTION_INFERENCE: &str = "Emotion inference";
+
+/// Fault raised for a frame abandoned with `error`
+pub fn timeout_notice(error: &SensorError) -> SensorFaultNotice {
+    SensorFaultNotice::new(FaultLevel::Warning, error.to_string(), MODEL_INFERENCE_TIMEOUT, true)
+}
+
+/// Emotion backend running on a worker thread, waited on for at most a budget
+pub struct TimedEmotionBackend {
+    faces: Sender<Mat>,
+    results: Receiver<Result<EmotionLogits, SensorError>>,
+    worker: JoinHandle<Box<dyn EmotionBackend>>,
+    budget: Duration,
+    /// A timed-out face is still being scored
+    busy: bool,
+}
+
+impl TimedEmotionBackend {
+    /// Move `backend` onto a worker thread and wait `budget` for each face
+    pub fn spawn(mut backend: Box<dyn EmotionBackend>, budget: Duration) -> Self {
+        let (faces, requests) = channel::<Mat>();
+        let (replies, results) = channel();
+        let worker = std::thread::Builder::new()
+            .name("spectre-emotion".to_string())
+            .spawn(move || {
+                for face in requests {
+                    if replies.send(backend.infer(&face)).is_err() {
+                        break;
+                    }
+                }
+                backend
+            })
+            .expect("failed to start the emotion inference thread");
+        Self { faces, results, worker, budget, busy: false }
+    }
+}
+
+impl EmotionBackend for TimedEmotionBackend {
+    fn infer(&mut self, face: &Mat) -> Result<EmotionLogits, SensorError> {
+        let stopped = || SensorError::FrameProcessing("Emotion inference thread stopped".to_string());
+        if self.busy {
+            // The late result belongs to an abandoned frame
+            match self.results.try_recv() {
+                Ok(_) => self.busy = false,
+                Err(TryRecvError::Empty) => {
+                    return Err(SensorError::FrameProcessing(format!(
+                        "Emotion model still busy after {:?}",
+                        self.budget
+                    )))
+                }
+                Err(TryRecvError::Disconnected) => return Err(stopped()),
+            }
+        }
+
+        let face = face.try_clone().map_err(|e| SensorError::FrameProcessing(e.to_string()))?;
+        self.faces.send(face).map_err(|_| stopped())?;
+        match self.results.recv_timeout(self.budget) {
+            Ok(result) => result,
+            Err(RecvTimeoutError::Timeout) => {
+                self.busy = true;
+                Err(SensorError::InferenceTimeout { stage: EMOTION_INFERENCE, budget: self.budget })
+            }
+            Err(RecvTimeoutError::Disconnected) => Err(stopped()),
+        }
+    }
+
+    /// Waits for a face still being scored before handing the session back
+    fn into_session(self: Box<Self>) -> Option<Session> {
+        drop(self.faces);
+        self.worker.join().ok()?.into_session()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Takes as long as the next scripted latency
+    struct SlowBackend {
+        latencies: std::vec::IntoIter<u64>,
+    }
+
+    impl EmotionBackend for SlowBackend {
+        fn infer(&mut self, _face: &Mat) -> Result<EmotionLogits, SensorError> {
+            std::thread::sleep(Duration::from_millis(self.latencies.next().unwrap_or(0)));
+            Ok([0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 0.0].into())
+        }
+    }
+
+    #[test]
+    fn test_slow_inference_is_abandoned() {
+        let backend = SlowBackend { latencies: vec![0, 200, 0].into_iter() };
+        let mut timed = TimedEmotionBackend::spawn(Box::new(backend), Duration::from_millis(50));
+        let face = Mat::default();
+
+        assert!(timed.infer(&face).is_ok());
+        match timed.infer(&face) {
+            Err(error @ SensorError::InferenceTimeout { .. }) => {
+                let notice = timeout_notice(&error);
+                assert_eq!(notice.error_code, MODEL_INFERENCE_TIMEOUT);
+                assert_eq!(notice.severity, FaultLevel::Warning);
+                assert!(notice.recoverable);
+            }
+            other => panic!("Expected a timeout, got {:?}", other),
+        }
+        // The model is still on the abandoned face
+        assert!(matches!(timed.infer(&face), Err(SensorError::FrameProcessing(_))));
+
+        // Its late result is dropped, not handed to the next face
+        std::thread::sleep(Duration::from_millis(200));
+        assert!(timed.infer(&face).is_ok());
+        assert!(Box::new(timed).into_session().is_none());
+    }
+}
@@ -0,0 +1,331 @@
+//! Streaming inference latency percentiles in bounded memory
+//!
+//! A [`LatencyHistogram`] counts latencies into fixed log-linear buckets,
+//! HDR histogram style: values under 16 µs get a bucket each, and every
+//! power of two above is split into 16 equal buckets, so a bucket is never
+//! wider than 1/16th of the values in it. Latencies from 1 µs to about
+//! 67 s fit in 368 counters; longer ones count in the last bucket.
+//! Recording never allocates, and a percentile is read back as the upper
+//! edge of the bucket it falls in.
+//!
+//! [`LatencyTracker`] keeps one histogram for the whole session and a
+//! sliding window of ten one-second histograms. The processing loop calls
+//! [`LatencyTracker::advance`] on every metrics update, a second apart, so
+//! the window covers the last ten seconds.
+
+use std::fmt;
+use std::time::Duration;
+
+/// Linear buckets per power of two, as a power of two
+const SUB_BUCKET_BITS: u32 = 4;
+/// Linear buckets per power of two
+const SUB_BUCKETS: usize = 1 << SUB_BUCKET_BITS;
+/// Latencies are counted in microseconds up to 2^MAX_BITS
+const MAX_BITS: u32 = 26;
+/// Buckets in a histogram: the linear ones under `SUB_BUCKETS` µs and
+/// `SUB_BUCKETS` for every power of two above
+const BUCKETS: usize = SUB_BUCKETS * (MAX_BITS - SUB_BUCKET_BITS + 1) as usize;
+
+/// One-second histograms in the sliding window
+pub const WINDOW_SECONDS: usize = 10;
+
+/// Latency counts in fixed log-linear buckets
+#[derive(Clone, PartialEq, Eq)]
+pub struct LatencyHistogram {
+    counts: [u32; BUCKETS],
+    total: u64,
+    max_us: u64,
+}
+
+impl Default for LatencyHistogram {
+    fn default() -> Self {
+        Self {
+            counts: [0; BUCKETS],
+            total: 0,
+            max_us: 0,
+        }
+    }
+}
+
+impl fmt::Debug for LatencyHistogram {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LatencyHistogram")
+            .field("count", &self.total)
+            .field("p50", &self.p50())
+            .field("p95", &self.p95())
+            .field("p99", &self.p99())
+            .field("max", &self.max())
+            .finish()
+    }
+}
+
+impl LatencyHistogram {
+    /// Create an empty histogram
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Count one latency
+    pub fn record(&mut self, latency: Duration) {
+        let us = latency.as_micros().min(u64::MAX as u128) as u64;
+        let bucket = &mut self.counts[bucket_index(us)];
+        *bucket = bucket.saturating_add(1);
+        self.total += 1;
+        self.max_us = self.max_us.max(us);
+    }
+
+    /// Add every latency counted in `other`
+    pub fn merge(&mut self, other: &LatencyHistogram) {
+        for (count, other) in self.counts.iter_mut().zip(other.counts.iter()) {
+            *count = count.saturating_add(*other);
+        }
+        self.total += other.total;
+        self.max_us = self.max_us.max(other.max_us);
+    }
+
+    /// Forget every latency
+    pub fn clear(&mut self) {
+        self.counts = [0; BUCKETS];
+        self.total = 0;
+        self.max_us = 0;
+    }
+
+    /// Latencies counted
+    pub fn count(&self) -> u64 {
+        self.total
+    }
+
+    /// Whether no latency was counted
+    pub fn is_empty(&self) -> bool {
+        self.total == 0
+    }
+
+    /// Longest latency counted
+    pub fn max(&self) -> Duration {
+        Duration::from_micros(self.max_us)
+    }
+
+    /// Latency under which `quantile` [0.0, 1.0] of the counted ones fall,
+    /// to within a bucket; zero when empty
+    pub fn percentile(&self, quantile: f64) -> Duration {
+        if self.total == 0 {
+            return Duration::ZERO;
+        }
+        let rank = ((quantile.clamp(0.0, 1.0) * self.total as f64).ceil() as u64).max(1);
+        let mut seen = 0u64;
+        for (index, &count) in self.counts.iter().enumerate() {
+            seen += count as u64;
+            if seen >= rank {
+                let (lower, width) = bucket_bounds(index);
+                return Duration::from_micros((lower + width - 1).min(self.max_us));
+            }
+        }
+        self.max()
+    }
+
+    /// Median latency
+    pub fn p50(&self) -> Duration {
+        self.percentile(0.50)
+    }
+
+    /// 95th percentile latency
+    pub fn p95(&self) -> Duration {
+        self.percentile(0.95)
+    }
+
+    /// 99th percentile latency
+    pub fn p99(&self) -> Duration {
+        self.percentile(0.99)
+    }
+
+    /// Width of the bucket `latency` is counted in
+    pub fn bucket_width(latency: Duration) -> Duration {
+        let us = latency.as_micros().min(u64::MAX as u128) as u64;
+        Duration::from_micros(bucket_bounds(bucket_index(us)).1)
+    }
+}
+
+/// Bucket a latency in microseconds is counted in
+fn bucket_index(us: u64) -> usize {
+    if us < SUB_BUCKETS as u64 {
+        return us as usize;
+    }
+    let top_bit = (63 - us.leading_zeros()).min(MAX_BITS - 1);
+    let shift = top_bit - SUB_BUCKET_BITS;
+    let sub_bucket = ((us >> shift) as usize).min(2 * SUB_BUCKETS - 1) - SUB_BUCKETS;
+    SUB_BUCKETS * (shift as usize + 1) + sub_bucket
+}
+
+/// Lowest latency in microseconds and width of a bucket
+fn bucket_bounds(index: usize) -> (u64, u64) {
+    if index < SUB_BUCKETS {
+        return (index as u64, 1);
+    }
+    let shift = (index / SUB_BUCKETS - 1) as u32;
+    let sub_bucket = (index % SUB_BUCKETS) as u64;
+    ((SUB_BUCKETS as u64 + sub_bucket) << shift, 1 << shift)
+}
+
+/// Inference latency percentiles over the last ten seconds and the session
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LatencyTracker {
+    /// One histogram per second of the window, oldest overwritten first
+    seconds: [LatencyHistogram; WINDOW_SECONDS],
+    /// Second of the window being recorded into
+    current: usize,
+    /// Every latency since the sensor started
+    session: LatencyHistogram,
+}
+
+impl LatencyTracker {
+    /// Create an empty tracker
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Count one latency in the window and the session
+    pub fn record(&mut self, latency: Duration) {
+        self.seconds[self.current].record(latency);
+        self.session.record(latency);
+    }
+
+    /// Start the next second of the window, dropping the oldest
+    pub fn advance(&mut self) {
+        self.current = (self.current + 1) % WINDOW_SECONDS;
+        self.seconds[self.current].clear();
+    }
+
+    /// Forget the window, keeping the session
+    pub fn reset_window(&mut self) {
+        self.seconds.iter_mut().for_each(LatencyHistogram::clear);
+        self.current = 0;
+    }
+
+    /// Latencies of the last ten seconds
+    pub fn window(&self) -> LatencyHistogram {
+        let mut window = LatencyHistogram::new();
+        for second in &self.seconds {
+            window.merge(second);
+        }
+        window
+    }
+
+    /// Latencies since the sensor started
+    pub fn session(&self) -> &LatencyHistogram {
+        &self.session
+    }
+
+    /// Median latency over the window
+    pub fn p50(&self) -> Duration {
+        self.window().p50()
+    }
+
+    /// 95th percentile latency over the window
+    pub fn p95(&self) -> Duration {
+        self.window().p95()
+    }
+
+    /// 99th percentile latency over the window
+    pub fn p99(&self) -> Duration {
+        self.window().p99()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Exact percentile of sorted latencies, by the same rank rule
+    fn exact(sorted: &[Duration], quantile: f64) -> Duration {
+        let rank = ((quantile * sorted.len() as f64).ceil() as usize).max(1);
+        sorted[rank - 1]
+    }
+
+    fn assert_within_bucket(estimate: Duration, truth: Duration) {
+        let width = LatencyHistogram::bucket_width(truth);
+        let error = estimate.abs_diff(truth);
+        assert!(error < width, "estimate {:?} vs {:?}, bucket width {:?}", estimate, truth, width);
+    }
+
+    #[test]
+    fn test_buckets_are_contiguous() {
+        let mut next = 0;
+        for index in 0..BUCKETS {
+            let (lower, width) = bucket_bounds(index);
+            assert_eq!(lower, next, "bucket {}", index);
+            assert_eq!(bucket_index(lower), index);
+            assert_eq!(bucket_index(lower + width - 1), index);
+            // Never wider than 1/16th of the values in it
+            assert!(width == 1 || width * SUB_BUCKETS as u64 <= lower);
+            next = lower + width;
+        }
+        assert_eq!(next, 1 << MAX_BITS);
+        assert_eq!(bucket_index(u64::MAX), BUCKETS - 1);
+    }
+
+    #[test]
+    fn test_uniform_distribution() {
+        let mut histogram = LatencyHistogram::new();
+        let latencies: Vec<Duration> = (1..=10_000).map(|us| Duration::from_micros(us * 3)).collect();
+        latencies.iter().for_each(|&latency| histogram.record(latency));
+
+        assert_eq!(histogram.count(), 10_000);
+        for quantile in [0.5, 0.95, 0.99] {
+            assert_within_bucket(histogram.percentile(quantile), exact(&latencies, quantile));
+        }
+        assert_eq!(histogram.percentile(1.0), Duration::from_micros(30_000));
+    }
+
+    #[test]
+    fn test_long_tail_distribution() {
+        // 90% fast frames around 8 ms, 10% slow ones around 120 ms
+        let mut latencies: Vec<Duration> = (0..9_000)
+            .map(|i| Duration::from_micros(7_000 + (i * 7_919) % 2_000))
+            .chain((0..1_000).map(|i| Duration::from_micros(100_000 + (i * 104_729) % 40_000)))
+            .collect();
+        let mut histogram = LatencyHistogram::new();
+        latencies.iter().for_each(|&latency| histogram.record(latency));
+        latencies.sort();
+
+        for quantile in [0.5, 0.95, 0.99] {
+            assert_within_bucket(histogram.percentile(quantile), exact(&latencies, quantile));
+        }
+        assert!(histogram.p50() < Duration::from_millis(10));
+        assert!(histogram.p95() > Duration::from_millis(100));
+    }
+
+    #[test]
+    fn test_empty_and_out_of_range() {
+        let mut histogram = LatencyHistogram::new();
+        assert_eq!(histogram.p95(), Duration::ZERO);
+
+        histogram.record(Duration::from_secs(3_600));
+        assert_eq!(histogram.count(), 1);
+        assert!(histogram.p99() >= Duration::from_micros((1 << MAX_BITS) - 1));
+    }
+
+    #[test]
+    fn test_window_slides_session_keeps() {
+        let mut tracker = LatencyTracker::new();
+        for _ in 0..100 {
+            tracker.record(Duration::from_millis(50));
+        }
+        for _ in 0..WINDOW_SECONDS {
+            tracker.advance();
+            for _ in 0..100 {
+                tracker.record(Duration::from_millis(5));
+            }
+        }
+
+        // The slow second has slid out of the window but not the session
+        assert_eq!(tracker.window().count(), 1_000);
+        assert_within_bucket(tracker.p99(), Duration::from_millis(5));
+        assert_eq!(tracker.session().count(), 1_100);
+        assert_within_bucket(tracker.session().p95(), Duration::from_millis(50));
+
+        tracker.reset_window();
+        assert!(tracker.window().is_empty());
+        assert_eq!(tracker.p95(), Duration::ZERO);
+        assert_eq!(tracker.session().count(), 1_100);
+    }
+}
@@ -1,41 +1,118 @@
 //! High-performance emotion detection sensor with gRPC streaming
 //!
 //! This crate provides the next-generation fear detection pipeline with:
-//! - YuNet face detection (replacing Haar cascades)
-//! - Optimized ONNX Runtime with configurable threading
-//! - gRPC streaming with back-pressure handling
-//! - Adaptive calibration with EMA updates
-//! - Comprehensive metrics and monitoring
+//! - YuNet face detection (replacing Haar cascades), with an optional OpenCV
+//!   `FaceDetectorYN` backend, and tracking of one primary face
+//! - Optimized ONNX Runtime emotion inference for models of any layout,
+//!   hot-swappable and bounded by a time budget
+//! - Adaptive calibration with EMA updates, phases and a cached baseline
+//! - gRPC streaming over TCP, Unix sockets, named pipes or grpc-web, shared
+//!   by every client, with back-pressure and optional delta encoding
+//! - Startle detection, bug report bundles and clock sync with the game
+//! - Graceful degradation, adaptive quality and camera reconnection
+//! - Recording, replay, mock and synthetic sensors for headless testing
+//! - Comprehensive metrics, structured logs and monitoring
+//!
+//! See `CHANGELOG_M0.2.md` at the repository root for each feature in full.
 
 pub mod types;
+pub mod latency;
 pub mod yunet;
+pub mod face_backend;
+pub mod face_tracker;
 pub mod calibrator;
+pub mod degradation;
+pub mod inference_timeout;
+pub mod startle;
 pub mod sensor;
+#[cfg(feature = "stream")]
 pub mod grpc_server;
+#[cfg(feature = "stream")]
 pub mod grpc_client;
+#[cfg(feature = "metrics")]
 pub mod metrics;
 pub mod config;
 pub mod compat;
+pub mod mock_patterns;
+pub mod synthetic;
 pub mod permissions;
+pub mod annotate;
+pub mod session_diff;
+pub mod resume;
+pub mod reconnect;
+pub mod retention;
+pub mod bug_report;
+pub mod recording;
+pub mod fanout;
+pub mod backpressure;
+#[cfg(feature = "stream")]
+pub mod transport;
+pub mod model_info;
+pub mod model_provider;
+pub mod clock_sync;
+pub mod startup;
+pub mod capture;
+pub mod camera_format;
+pub mod cameras;
+pub mod normalization;
+pub mod emotion_input;
+pub mod conditioning;
+pub mod measure;
+pub mod model_swap;
+pub mod mirror;
+#[cfg(feature = "stream")]
+pub mod clients;
+pub mod phases;
+pub mod calibration_control;
+pub mod reconfigure;
+pub mod metrics_history;
+pub mod heartbeat;
+pub mod power;
+pub mod quality;
+pub mod logging;
+#[cfg(feature = "mock")]
+pub mod mock;
+#[cfg(feature = "stream")]
+pub mod soak;
 
 // Re-export main types
 pub use types::{FearFrame, FearBucket, PerformanceMetrics};
 pub use sensor::{EmotionSensor, SensorError};
 pub use calibrator::{AdaptiveCalibrator, CalibrationError, BaselineStats};
 pub use config::SensorConfig;
+pub use fanout::{FrameFanout, FrameSubscriber};
+pub use backpressure::BackpressurePolicy;
+#[cfg(feature = "stream")]
+pub use transport::{SensorTransport, TransportError};
+pub use model_info::ModelInfo;
+pub use model_provider::ModelProvider;
+pub use clock_sync::{ClockSyncEstimate, ClockSyncEstimator};
 
 // Re-export compatibility layer for legacy API
-pub use compat::{YuNetFearSensor, MockFearSensor};
+pub use compat::{YuNetFearSensor, MockFearSensor, ReplayFearSensor};
+#[cfg(feature = "stream")]
+pub use compat::GrpcFearSensor;
 
-// gRPC generated code
-pub mod proto {
-    tonic::include_proto!("spectre.sensor.v1");
-}
+// gRPC generated code and delta streams, shared with browser clients
+pub use spectre_protocol::{delta, proto, score_face_present, score_logits, score_to_fear_score};
 
 // Embedded YuNet model (345 KB)
+#[cfg(feature = "embedded-models")]
 pub const YUNET_MODEL_BYTES: &[u8] = include_bytes!("../models/face_detection_yunet.onnx");
 
-#[cfg(test)]
+/// Provenance of the embedded YuNet model, also used to recognize it on disk
+pub const YUNET_MODEL_INFO: ModelInfo = ModelInfo {
+    name: std::borrow::Cow::Borrowed("YuNet"),
+    version: std::borrow::Cow::Borrowed("2023mar"),
+    source_url: std::borrow::Cow::Borrowed(
+        "https://github.com/opencv/opencv_zoo/raw/master/models/face_detection_yunet/face_detection_yunet_2023mar.onnx",
+    ),
+    license: std::borrow::Cow::Borrowed("MIT"),
+    sha256: std::borrow::Cow::Borrowed("8f2383e4dd3cfbb4553ea8718107fc0423210dc964f9f4280604804ed2552fa4"),
+    embedded: true,
+};
+
+#[cfg(all(test, feature = "embedded-models"))]
 mod tests {
     use super::*;
 
@@ -44,4 +121,11 @@ mod tests {
         assert!(!YUNET_MODEL_BYTES.is_empty());
         assert_eq!(YUNET_MODEL_BYTES.len(), 232589); // Expected YuNet model size (2023mar version)
     }
+
+    #[test]
+    fn test_yunet_model_info_matches_embedded_bytes() {
+        assert_eq!(model_info::sha256_hex(YUNET_MODEL_BYTES), YUNET_MODEL_INFO.sha256);
+        assert!(YUNET_MODEL_INFO.embedded);
+        assert!(YUNET_MODEL_INFO.has_provenance());
+    }
 }
@@ -0,0 +1,610 @@
+//! Remote log control for daemons nobody can attach a terminal to
+//!
+//! Venue operators cannot reach journald on the camera box. [`LogControl`]
+//! owns the daemon's log filter behind a `tracing_subscriber` reload handle,
+//! so `SetLogLevel` can raise it to debug and drop it back without a restart,
+//! and a [`LogRing`] keeps the last records in memory for `GetLogs`.
+//!
+//! The ring keeps every record at `ring_level` or above whatever the console
+//! filter says, plus anything the filter lets through on top: with the
+//! console at `warn` the ring still holds `info`, and after
+//! `SetLogLevel("debug")` it holds debug lines too.
+//!
+//! The ring is a fixed array of slots, each behind its own mutex. Writers
+//! claim a slot with one atomic increment, so they never wait on each other
+//! or on a reader walking the whole buffer, only on a reader copying that
+//! same slot.
+//!
+//! Console records are human-readable lines by default. With
+//! [`LogFormat::Json`] each is one JSON object per line, carrying the spans
+//! it happened in, and every span closing writes a record of its own. The
+//! sensor's processing loop opens a debug-level `frame` span per frame, with
+//! a child span per stage that records its `duration_us`, so with
+//! `SPECTRE_LOG=info,spectre_sensor::sensor=debug` per-stage latencies can be
+//! pulled out of the daemon's output:
+//!
+//! ```text
+//! sensord --log-format json 2>&1 | jq -c 'select(.fields.message == "close" and .span.name != "frame")
+//!     | {sequence: .spans[0].sequence, stage: .span.name, us: .span.duration_us}'
+//! ```
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+use thiserror::Error;
+use tracing::field::{Field, Visit};
+use tracing::{Level, Subscriber};
+use tracing_subscriber::filter::{EnvFilter, FilterExt, LevelFilter};
+use tracing_subscriber::fmt::format::FmtSpan;
+use tracing_subscriber::fmt::MakeWriter;
+use tracing_subscriber::layer::{Context, SubscriberExt};
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{reload, Layer};
+
+/// Records returned by a `GetLogs` page that does not ask for a size
+pub const DEFAULT_PAGE_SIZE: usize = 100;
+
+/// Most records one `GetLogs` page returns
+pub const MAX_PAGE_SIZE: usize = 1000;
+
+/// Log control errors
+#[derive(Debug, Error)]
+pub enum LogError {
+    #[error("Invalid log filter '{filter}': {message}")]
+    InvalidFilter { filter: String, message: String },
+
+    #[error("Failed to apply log filter: {0}")]
+    Reload(String),
+
+    #[error("Failed to install the log subscriber: {0}")]
+    Install(String),
+}
+
+/// How records are written to the console
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LogFormat {
+    /// Human-readable lines
+    #[default]
+    Pretty,
+    /// One JSON object per record, with its spans, plus one per span closed
+    Json,
+}
+
+impl FromStr for LogFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "pretty" | "text" => Ok(Self::Pretty),
+            "json" => Ok(Self::Json),
+            other => Err(format!("Unknown log format: {} (expected pretty or json)", other)),
+        }
+    }
+}
+
+impl fmt::Display for LogFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Pretty => "pretty",
+            Self::Json => "json",
+        })
+    }
+}
+
+/// Daemon logging settings
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LoggingConfig {
+    /// Console filter at startup, in `EnvFilter` syntax (overridable with SPECTRE_LOG)
+    pub filter: String,
+    /// Console record format (overridable with SPECTRE_LOG_FORMAT)
+    pub format: LogFormat,
+    /// Least severe level the ring keeps regardless of the console filter
+    pub ring_level: String,
+    /// Records kept in memory for `GetLogs`
+    pub ring_capacity: usize,
+}
+
+impl Default for LoggingConfig {
+    fn default() -> Self {
+        Self {
+            filter: "info".to_string(),
+            format: LogFormat::Pretty,
+            ring_level: "info".to_string(),
+            ring_capacity: 2000,
+        }
+    }
+}
+
+impl LoggingConfig {
+    /// Validate the configuration
+    pub fn validate(&self) -> Result<(), String> {
+        parse_filter(&self.filter).map_err(|e| e.to_string())?;
+        parse_level(&self.ring_level)?;
+        if self.ring_capacity == 0 {
+            return Err("Log ring capacity must be at least 1".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// One captured log record
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LogRecord {
+    /// Position in the log, starting at 1
+    pub sequence: u64,
+    /// Microseconds since Unix epoch
+    pub timestamp_us: u64,
+    pub level: Level,
+    pub target: String,
+    pub message: String,
+    /// Fields other than the message, in recording order
+    pub fields: Vec<(String, String)>,
+}
+
+/// Records read from a [`LogRing`] after a cursor
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct LogPage {
+    /// Oldest first
+    pub records: Vec<LogRecord>,
+    /// Cursor for the next page
+    pub next_since: u64,
+    /// Records past `next_since` were already kept when the page was read
+    pub more: bool,
+    /// Records after the cursor that were overwritten before being read
+    pub dropped: u64,
+}
+
+/// Bounded ring of recent log records, filled as a `tracing` layer
+#[derive(Clone)]
+pub struct LogRing {
+    shared: Arc<RingShared>,
+}
+
+struct RingShared {
+    slots: Box<[Mutex<Option<LogRecord>>]>,
+    /// Sequence number the next record gets
+    next: AtomicU64,
+}
+
+impl LogRing {
+    /// Create a ring keeping the last `capacity` records (at least one)
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            shared: Arc::new(RingShared {
+                slots: (0..capacity.max(1)).map(|_| Mutex::new(None)).collect(),
+                next: AtomicU64::new(1),
+            }),
+        }
+    }
+
+    /// Most records kept at once
+    pub fn capacity(&self) -> usize {
+        self.shared.slots.len()
+    }
+
+    /// Sequence number of the newest record, 0 before the first
+    pub fn last_sequence(&self) -> u64 {
+        self.shared.next.load(Ordering::Acquire) - 1
+    }
+
+    /// Append a record, overwriting the oldest once full; returns its sequence number
+    pub fn push(&self, mut record: LogRecord) -> u64 {
+        let sequence = self.shared.next.fetch_add(1, Ordering::AcqRel);
+        record.sequence = sequence;
+        let mut slot = self.slot(sequence).lock().unwrap();
+        // A writer that lapped this one may already hold a newer record here
+        if !matches!(slot.as_ref(), Some(newer) if newer.sequence > sequence) {
+            *slot = Some(record);
+        }
+        sequence
+    }
+
+    /// Up to `limit` records after `since` at least as severe as `min_level`
+    ///
+    /// A `limit` of 0 uses [`DEFAULT_PAGE_SIZE`]; larger limits are capped at
+    /// [`MAX_PAGE_SIZE`].
+    pub fn read(&self, since: u64, min_level: Level, limit: usize) -> LogPage {
+        let limit = match limit {
+            0 => DEFAULT_PAGE_SIZE,
+            limit => limit.min(MAX_PAGE_SIZE),
+        };
+        let newest = self.last_sequence();
+        let oldest = newest.saturating_sub(self.capacity() as u64) + 1;
+        let start = since.saturating_add(1).max(oldest);
+        let mut page = LogPage {
+            next_since: start - 1,
+            dropped: (start - 1).saturating_sub(since),
+            ..LogPage::default()
+        };
+
+        for sequence in start..=newest {
+            if page.records.len() == limit {
+                page.more = true;
+                break;
+            }
+            let slot = self.slot(sequence).lock().unwrap();
+            match slot.as_ref() {
+                Some(record) if record.sequence == sequence => {
+                    // Lower levels are more severe
+                    if record.level <= min_level {
+                        page.records.push(record.clone());
+                    }
+                }
+                // Lapped by writers while this page was read
+                Some(record) if record.sequence > sequence => page.dropped += 1,
+                // Claimed but not written yet; the next page picks it up
+                _ => break,
+            }
+            page.next_since = sequence;
+        }
+        page
+    }
+
+    fn slot(&self, sequence: u64) -> &Mutex<Option<LogRecord>> {
+        &self.shared.slots[(sequence % self.shared.slots.len() as u64) as usize]
+    }
+}
+
+impl fmt::Debug for LogRing {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LogRing")
+            .field("capacity", &self.capacity())
+            .field("last_sequence", &self.last_sequence())
+            .finish()
+    }
+}
+
+impl<S: Subscriber> Layer<S> for LogRing {
+    fn on_event(&self, event: &tracing::Event<'_>, _ctx: Context<'_, S>) {
+        let metadata = event.metadata();
+        let mut visitor = RecordVisitor::default();
+        event.record(&mut visitor);
+        self.push(LogRecord {
+            sequence: 0,
+            timestamp_us: SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_micros() as u64,
+            level: *metadata.level(),
+            target: metadata.target().to_string(),
+            message: visitor.message,
+            fields: visitor.fields,
+        });
+    }
+}
+
+/// Splits event fields into the message and the rest
+#[derive(Default)]
+struct RecordVisitor {
+    message: String,
+    fields: Vec<(String, String)>,
+}
+
+impl Visit for RecordVisitor {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == "message" {
+            self.message = value.to_string();
+        } else {
+            self.fields.push((field.name().to_string(), value.to_string()));
+        }
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        if field.name() == "message" {
+            self.message = format!("{:?}", value);
+        } else {
+            self.fields.push((field.name().to_string(), format!("{:?}", value)));
+        }
+    }
+}
+
+/// Applies a new filter to both reload layers
+type Reload = Box<dyn Fn(&str) -> Result<(), LogError> + Send + Sync>;
+
+/// The daemon's live log filter and its ring of recent records
+///
+/// ```ignore
+/// let logs = LogControl::install(&config.logging)?;
+/// let service = SensorServiceImpl::new(sensor).with_log_control(logs);
+/// ```
+pub struct LogControl {
+    ring: LogRing,
+    filter: Mutex<String>,
+    reload: Reload,
+}
+
+impl LogControl {
+    /// Console output under a reloadable filter, and the ring, as one layer
+    ///
+    /// For subscribers built by hand; [`LogControl::install`] sets up the
+    /// usual global one.
+    pub fn layer<S>(config: &LoggingConfig) -> Result<(Self, impl Layer<S>), LogError>
+    where
+        S: Subscriber + for<'a> LookupSpan<'a> + 'static,
+    {
+        let floor = parse_level(&config.ring_level).map_err(|message| LogError::InvalidFilter {
+            filter: config.ring_level.clone(),
+            message,
+        })?;
+        let (console_filter, console) = reload::Layer::new(parse_filter(&config.filter)?);
+        let (ring_filter, ring_extra) = reload::Layer::new(parse_filter(&config.filter)?);
+        let ring = LogRing::new(config.ring_capacity);
+
+        let layer = console_layer(config.format, std::io::stdout)
+            .with_filter(console_filter)
+            .and_then(ring.clone().with_filter(floor.or(ring_filter)));
+        let reload: Reload = Box::new(move |filter| {
+            console.reload(parse_filter(filter)?).map_err(|e| LogError::Reload(e.to_string()))?;
+            ring_extra.reload(parse_filter(filter)?).map_err(|e| LogError::Reload(e.to_string()))
+        });
+
+        let control = Self {
+            ring,
+            filter: Mutex::new(config.filter.clone()),
+            reload,
+        };
+        Ok((control, layer))
+    }
+
+    /// Install the layer as the global subscriber
+    pub fn install(config: &LoggingConfig) -> Result<Arc<Self>, LogError> {
+        let (control, layer) = Self::layer(config)?;
+        tracing_subscriber::registry()
+            .with(layer)
+            .try_init()
+            .map_err(|e| LogError::Install(e.to_string()))?;
+        Ok(Arc::new(control))
+    }
+
+    /// Filter in effect
+    pub fn filter(&self) -> String {
+        self.filter.lock().unwrap().clone()
+    }
+
+    /// Replace the filter, returning the previous one
+    ///
+    /// A filter that does not parse leaves the current one in place.
+    pub fn set_filter(&self, filter: &str) -> Result<String, LogError> {
+        parse_filter(filter)?;
+        let mut current = self.filter.lock().unwrap();
+        (self.reload)(filter)?;
+        tracing::info!("Log filter changed from '{}' to '{}'", current, filter);
+        Ok(std::mem::replace(&mut *current, filter.to_string()))
+    }
+
+    /// Recent records, see [`LogRing::read`]
+    pub fn logs(&self, since: u64, min_level: Level, limit: usize) -> LogPage {
+        self.ring.read(since, min_level, limit)
+    }
+
+    /// Ring the records are kept in
+    pub fn ring(&self) -> &LogRing {
+        &self.ring
+    }
+}
+
+impl fmt::Debug for LogControl {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LogControl")
+            .field("filter", &self.filter())
+            .field("ring", &self.ring)
+            .finish()
+    }
+}
+
+/// Console output alone as the global subscriber, for tools that need no log control
+pub fn install_console(config: &LoggingConfig) -> Result<(), LogError> {
+    tracing_subscriber::registry()
+        .with(console_layer(config.format, std::io::stdout).with_filter(parse_filter(&config.filter)?))
+        .try_init()
+        .map_err(|e| LogError::Install(e.to_string()))
+}
+
+/// Records in `format`, written to `writer`
+///
+/// JSON records carry the span they happened in and the spans around it,
+/// and each span writes a `close` record with the fields recorded on it.
+pub fn console_layer<S, W>(format: LogFormat, writer: W) -> Box<dyn Layer<S> + Send + Sync>
+where
+    S: Subscriber + for<'a> LookupSpan<'a> + 'static,
+    W: for<'w> MakeWriter<'w> + Send + Sync + 'static,
+{
+    let layer = tracing_subscriber::fmt::layer().with_writer(writer);
+    match format {
+        LogFormat::Pretty => layer.boxed(),
+        LogFormat::Json => layer
+            .json()
+            .with_current_span(true)
+            .with_span_list(true)
+            .with_span_events(FmtSpan::CLOSE)
+            .boxed(),
+    }
+}
+
+/// Parse a filter in `EnvFilter` syntax, e.g. `info,spectre_sensor::sensor=debug`
+pub fn parse_filter(filter: &str) -> Result<EnvFilter, LogError> {
+    let invalid = |message: String| LogError::InvalidFilter {
+        filter: filter.to_string(),
+        message,
+    };
+    if filter.trim().is_empty() {
+        return Err(invalid("empty filter".to_string()));
+    }
+    EnvFilter::builder().parse(filter).map_err(|e| invalid(e.to_string()))
+}
+
+fn parse_level(level: &str) -> Result<LevelFilter, String> {
+    level
+        .parse()
+        .map_err(|_| format!("Invalid log ring level: {} (expected off, error, warn, info, debug or trace)", level))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(level: Level, message: &str) -> LogRecord {
+        LogRecord {
+            sequence: 0,
+            timestamp_us: 0,
+            level,
+            target: "test".to_string(),
+            message: message.to_string(),
+            fields: Vec::new(),
+        }
+    }
+
+    fn messages(page: &LogPage) -> Vec<&str> {
+        page.records.iter().map(|record| record.message.as_str()).collect()
+    }
+
+    /// Console output kept in memory
+    #[derive(Clone, Default)]
+    struct Captured(Arc<Mutex<Vec<u8>>>);
+
+    impl Captured {
+        fn records(&self) -> Vec<serde_json::Value> {
+            let output = self.0.lock().unwrap();
+            output
+                .split(|&byte| byte == b'\n')
+                .filter(|line| !line.is_empty())
+                .map(|line| serde_json::from_slice(line).unwrap())
+                .collect()
+        }
+    }
+
+    impl std::io::Write for Captured {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    impl<'a> MakeWriter<'a> for Captured {
+        type Writer = Self;
+
+        fn make_writer(&'a self) -> Self::Writer {
+            self.clone()
+        }
+    }
+
+    #[test]
+    fn test_ring_pages_and_reports_overwritten_records() {
+        let ring = LogRing::new(4);
+        for i in 1..=6 {
+            assert_eq!(ring.push(record(Level::INFO, &i.to_string())), i);
+        }
+
+        // 1 and 2 were overwritten
+        let page = ring.read(0, Level::TRACE, 3);
+        assert_eq!(messages(&page), ["3", "4", "5"]);
+        assert_eq!((page.next_since, page.more, page.dropped), (5, true, 2));
+
+        let page = ring.read(page.next_since, Level::TRACE, 3);
+        assert_eq!(messages(&page), ["6"]);
+        assert_eq!((page.next_since, page.more, page.dropped), (6, false, 0));
+
+        let page = ring.read(page.next_since, Level::TRACE, 3);
+        assert!(page.records.is_empty());
+        assert_eq!(page.next_since, 6);
+    }
+
+    #[test]
+    fn test_ring_filters_by_severity() {
+        let ring = LogRing::new(8);
+        for (level, message) in [(Level::DEBUG, "d"), (Level::WARN, "w"), (Level::INFO, "i"), (Level::ERROR, "e")] {
+            ring.push(record(level, message));
+        }
+
+        assert_eq!(messages(&ring.read(0, Level::WARN, 0)), ["w", "e"]);
+        assert_eq!(messages(&ring.read(0, Level::INFO, 0)), ["w", "i", "e"]);
+        assert_eq!(messages(&ring.read(0, Level::TRACE, 0)), ["d", "w", "i", "e"]);
+
+        // Filtered records still move the cursor
+        let page = ring.read(0, Level::ERROR, 1);
+        assert_eq!((messages(&page), page.next_since, page.more), (vec!["e"], 4, false));
+    }
+
+    #[test]
+    fn test_invalid_filters_are_rejected() {
+        assert!(parse_filter("info,spectre_sensor::sensor=debug").is_ok());
+        assert!(matches!(parse_filter("spectre_sensor=loud"), Err(LogError::InvalidFilter { .. })));
+        assert!(parse_filter(" ").is_err());
+        assert!(LoggingConfig { ring_level: "chatty".to_string(), ..LoggingConfig::default() }.validate().is_err());
+        assert!(LoggingConfig { ring_capacity: 0, ..LoggingConfig::default() }.validate().is_err());
+    }
+
+    #[test]
+    fn test_json_records_carry_their_spans() {
+        assert_eq!("JSON".parse::<LogFormat>(), Ok(LogFormat::Json));
+        assert_eq!(LogFormat::Pretty.to_string().parse::<LogFormat>(), Ok(LogFormat::Pretty));
+        assert!("xml".parse::<LogFormat>().is_err());
+
+        let output = Captured::default();
+        let layer = console_layer(LogFormat::Json, output.clone()).with_filter(parse_filter("debug").unwrap());
+        tracing::subscriber::with_default(tracing_subscriber::registry().with(layer), || {
+            let frame = tracing::debug_span!("frame", sequence = 42u64);
+            let _frame = frame.enter();
+            let stage = tracing::debug_span!("detect", duration_us = tracing::field::Empty);
+            stage.in_scope(|| tracing::info!(faces = 1, "detected"));
+            stage.record("duration_us", 250u64);
+        });
+
+        let records = output.records();
+        let detected = records.iter().find(|record| record["fields"]["message"] == "detected").unwrap();
+        assert_eq!(detected["fields"]["faces"], 1);
+        assert_eq!(detected["span"]["name"], "detect");
+        assert_eq!(detected["spans"][0]["sequence"], 42);
+
+        // Fields recorded after the span opened are in its close record
+        let closed = |name: &str| {
+            records
+                .iter()
+                .find(|record| record["fields"]["message"] == "close" && record["span"]["name"] == name)
+                .unwrap_or_else(|| panic!("no close record for {}", name))
+        };
+        assert_eq!(closed("detect")["span"]["duration_us"], 250);
+        assert_eq!(closed("detect")["spans"][0]["name"], "frame");
+        assert_eq!(closed("frame")["span"]["sequence"], 42);
+    }
+
+    #[test]
+    fn test_ring_keeps_its_floor_and_follows_the_filter() {
+        let config = LoggingConfig {
+            filter: "warn".to_string(),
+            ring_level: "info".to_string(),
+            ring_capacity: 16,
+            ..LoggingConfig::default()
+        };
+        let (control, layer) = LogControl::layer(&config).unwrap();
+        let subscriber = tracing_subscriber::registry().with(layer);
+
+        tracing::subscriber::with_default(subscriber, || {
+            tracing::info!(stage = "startup", "kept below the console filter");
+            tracing::debug!("below the floor");
+            assert!(control.set_filter("spectre_sensor=loud").is_err());
+            assert_eq!(control.set_filter("debug").unwrap(), "warn");
+            tracing::debug!(frame = 7, "now kept");
+        });
+
+        let page = control.logs(0, Level::TRACE, 0);
+        let kept: Vec<_> = page
+            .records
+            .iter()
+            .filter(|record| record.target == module_path!())
+            .collect();
+        assert_eq!(kept.len(), 2);
+        assert_eq!(kept[0].message, "kept below the console filter");
+        assert_eq!(kept[0].fields, [("stage".to_string(), "startup".to_string())]);
+        assert_eq!((kept[1].level, kept[1].message.as_str()), (Level::DEBUG, "now kept"));
+        assert_eq!(kept[1].fields, [("frame".to_string(), "7".to_string())]);
+        assert_eq!(control.filter(), "debug");
+    }
+}
@@ -0,0 +1,210 @@
+//! Single-shot fear measurements
+//!
+//! Integrations that only need an occasional reading (a questionnaire
+//! prompt, a health check) use [`EmotionSensor::measure_once`] instead of
+//! holding a stream open. When the sensor is idle the measurement reads its
+//! own frames through a [`FrameScorer`]; when it is running, the next frame
+//! of the pipeline is duplicated through [`LiveFrames`], so subscribers
+//! still receive every frame.
+//!
+//! [`EmotionSensor::measure_once`]: crate::sensor::EmotionSensor::measure_once
+
+use crate::{
+    backpressure::{BackpressureSender, Delivery},
+    resume::FrameSource,
+    sensor::SensorError,
+    types::FearFrame,
+};
+use async_channel::SendError;
+use opencv::{core::Mat, prelude::*};
+use std::time::Duration;
+use tokio::sync::broadcast;
+use tokio::time::sleep;
+
+/// How long a measurement waits for a face unless told otherwise
+pub const DEFAULT_MEASURE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Scores single camera frames for a standalone measurement
+pub trait FrameScorer: Send {
+    /// Score `frame`, or `Ok(None)` when it shows no face
+    fn score(&mut self, frame: &Mat) -> Result<Option<FearFrame>, SensorError>;
+}
+
+/// Read frames from `source` until one shows a face
+///
+/// Empty reads and faceless frames are retried every `poll_interval`; other
+/// scoring errors end the measurement. Fails with
+/// [`SensorError::MeasureTimeout`] when no face appears within `timeout`.
+pub async fn measure_source(
+    source: &mut (dyn FrameSource + Send),
+    scorer: &mut dyn FrameScorer,
+    timeout: Duration,
+    poll_interval: Duration,
+) -> Result<FearFrame, SensorError> {
+    let attempt = async {
+        loop {
+            let mut frame = Mat::default();
+            if source.read_frame(&mut frame) && !frame.empty() {
+                if let Some(fear_frame) = scorer.score(&frame)? {
+                    return Ok(fear_frame);
+                }
+            }
+            sleep(poll_interval).await;
+        }
+    };
+    tokio::time::timeout(timeout, attempt)
+        .await
+        .unwrap_or(Err(SensorError::MeasureTimeout(timeout)))
+}
+
+/// Copies of the frames a running pipeline sends, for pending measurements
+///
+/// Frames are only duplicated while a measurement is waiting; the
+/// pipeline's own channel is unaffected either way.
+#[derive(Debug, Clone)]
+pub struct LiveFrames {
+    sender: broadcast::Sender<FearFrame>,
+}
+
+impl Default for LiveFrames {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LiveFrames {
+    pub fn new() -> Self {
+        let (sender, _) = broadcast::channel(4);
+        Self { sender }
+    }
+
+    /// Send `frame` down the pipeline's channel, copying it to waiting measurements first
+    pub async fn publish(
+        &self,
+        frame: FearFrame,
+        frames: &BackpressureSender<FearFrame>,
+    ) -> Result<Delivery, SendError<FearFrame>> {
+        if self.sender.receiver_count() > 0 {
+            let _ = self.sender.send(frame.clone());
+        }
+        frames.send(frame).await
+    }
+
+    /// Wait for the next published frame
+    ///
+    /// The pipeline only publishes frames with a face, so running out of
+    /// time means none was seen.
+    pub async fn next(&self, timeout: Duration) -> Result<FearFrame, SensorError> {
+        let mut receiver = self.sender.subscribe();
+        let attempt = async {
+            loop {
+                match receiver.recv().await {
+                    Ok(frame) => return Ok(frame),
+                    Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                    Err(broadcast::error::RecvError::Closed) => return Err(SensorError::ChannelError),
+                }
+            }
+        };
+        tokio::time::timeout(timeout, attempt)
+            .await
+            .unwrap_or(Err(SensorError::MeasureTimeout(timeout)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backpressure::{channel, BackpressurePolicy};
+    use opencv::core::{Scalar, CV_8UC1};
+
+    /// Synthetic camera producing blank frames
+    #[derive(Default)]
+    struct SyntheticSource {
+        reads: usize,
+    }
+
+    impl FrameSource for SyntheticSource {
+        fn read_frame(&mut self, frame: &mut Mat) -> bool {
+            self.reads += 1;
+            *frame = Mat::new_rows_cols_with_default(48, 48, CV_8UC1, Scalar::all(0.0)).unwrap();
+            true
+        }
+
+        fn reopen(&mut self) -> Result<(), SensorError> {
+            Ok(())
+        }
+    }
+
+    /// Finds a face from the `face_from`th frame onwards, if ever
+    struct SyntheticScorer {
+        scored: usize,
+        face_from: Option<usize>,
+    }
+
+    impl FrameScorer for SyntheticScorer {
+        fn score(&mut self, _frame: &Mat) -> Result<Option<FearFrame>, SensorError> {
+            self.scored += 1;
+            Ok(self
+                .face_from
+                .filter(|&first| self.scored >= first)
+                .map(|_| FearFrame::new(0.4, [0.0; 7], 0.9, false, Duration::from_millis(3))))
+        }
+    }
+
+    async fn measure(face_from: Option<usize>) -> (Result<FearFrame, SensorError>, usize) {
+        let mut source = SyntheticSource::default();
+        let mut scorer = SyntheticScorer { scored: 0, face_from };
+        let result = measure_source(&mut source, &mut scorer, Duration::from_secs(2), Duration::from_millis(50)).await;
+        (result, source.reads)
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_standalone_measurement_waits_for_a_face() {
+        let (result, reads) = measure(Some(3)).await;
+        let frame = result.unwrap();
+        assert_eq!(frame.fear_score, 0.4);
+        assert_eq!(reads, 3);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_standalone_measurement_times_out_without_a_face() {
+        let (result, reads) = measure(None).await;
+        assert!(matches!(result, Err(SensorError::MeasureTimeout(timeout)) if timeout == Duration::from_secs(2)));
+        // One read per poll interval until the deadline
+        assert!((40..=41).contains(&reads), "{} reads", reads);
+    }
+
+    #[tokio::test]
+    async fn test_live_measurement_duplicates_frames() {
+        let live = LiveFrames::new();
+        let (sender, receiver) = channel(16, BackpressurePolicy::DropOldest);
+
+        let pipeline = {
+            let live = live.clone();
+            tokio::spawn(async move {
+                for step in 0..10 {
+                    let frame = FearFrame::new(step as f32 / 10.0, [0.0; 7], 0.9, true, Duration::ZERO);
+                    live.publish(frame, &sender).await.unwrap();
+                    sleep(Duration::from_millis(5)).await;
+                }
+            })
+        };
+
+        let measured = live.next(Duration::from_secs(1)).await.unwrap();
+        pipeline.await.unwrap();
+
+        let delivered: Vec<f32> = std::iter::from_fn(|| receiver.try_recv().ok())
+            .map(|frame| frame.fear_score)
+            .collect();
+        let expected: Vec<f32> = (0..10).map(|step| step as f32 / 10.0).collect();
+        assert_eq!(delivered, expected);
+        assert!(delivered.contains(&measured.fear_score));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_live_measurement_times_out_without_frames() {
+        let live = LiveFrames::new();
+        let result = live.next(Duration::from_secs(3)).await;
+        assert!(matches!(result, Err(SensorError::MeasureTimeout(_))));
+    }
+}
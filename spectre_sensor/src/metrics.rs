@@ -1,19 +1,31 @@
 //! Prometheus metrics server for sensor monitoring
+//!
+//! Besides `/metrics` for Prometheus, the server exposes the sensor's
+//! [`MetricsHistory`] as JSON on `/metrics/history` and draws it on a small
+//! `/dashboard` page, for setups without a Prometheus stack. `/health`
+//! answers 503 unless the pipeline is running and capturing frames, the same
+//! [`Liveness`] criteria the heartbeat follows.
 
 use prometheus::{
-    Counter, Gauge, Histogram, Registry, TextEncoder,
+    Counter, CounterVec, Gauge, GaugeVec, Histogram, Registry, TextEncoder,
     HistogramOpts, Opts,
 };
 use axum::{
-    extract::State,
+    extract::{Query, State},
     http::StatusCode,
-    response::{IntoResponse, Response},
+    response::{Html, IntoResponse, Response},
     routing::get,
-    Router,
+    Json, Router,
 };
-use std::sync::Arc;
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
 use tokio::net::TcpListener;
 use tower::ServiceBuilder;
+use crate::backpressure::BackpressurePolicy;
+use crate::heartbeat::Liveness;
+use crate::latency::{LatencyHistogram, LatencyTracker};
+use crate::metrics_history::{MetricsHistory, MetricsSample};
 use crate::types::PerformanceMetrics;
 
 /// Prometheus metrics collection
@@ -23,7 +35,10 @@ pub struct SensorMetrics {
     
     // Counters
     frames_processed: Counter,
-    frames_dropped: Counter,
+    frames_dropped: CounterVec,
+    stale_frames: Counter,
+    face_detections: Counter,
+    carried_over_faces: Counter,
     inference_errors: Counter,
     calibration_resets: Counter,
     
@@ -31,6 +46,9 @@ pub struct SensorMetrics {
     current_fps: Gauge,
     calibration_progress: Gauge,
     calibration_drift: Gauge,
+    connected_clients: Gauge,
+    quality_level: Gauge,
+    inference_latency_quantiles: GaugeVec,
     
     // Histograms
     inference_latency: Histogram,
@@ -47,9 +65,24 @@ impl SensorMetrics {
             "Total number of frames processed by the sensor"
         ))?;
         
-        let frames_dropped = Counter::with_opts(Opts::new(
-            "spectre_frames_dropped_total", 
-            "Total number of frames dropped due to back-pressure"
+        let frames_dropped = CounterVec::new(Opts::new(
+            "spectre_frames_dropped_total",
+            "Total number of frames dropped due to back-pressure, by back-pressure policy"
+        ), &["policy"])?;
+        
+        let stale_frames = Counter::with_opts(Opts::new(
+            "spectre_stale_frames_total",
+            "Captured frames replaced by a fresher one before inference"
+        ))?;
+        
+        let face_detections = Counter::with_opts(Opts::new(
+            "spectre_face_detections_total",
+            "Frames scored after running the face detector"
+        ))?;
+        
+        let carried_over_faces = Counter::with_opts(Opts::new(
+            "spectre_carried_over_faces_total",
+            "Frames scored on the face carried over from the last detection"
         ))?;
         
         let inference_errors = Counter::with_opts(Opts::new(
@@ -77,6 +110,16 @@ impl SensorMetrics {
             "Calibration drift (change in baseline mean)"
         ))?;
         
+        let connected_clients = Gauge::with_opts(Opts::new(
+            "spectre_connected_clients",
+            "Event streams currently open"
+        ))?;
+        
+        let quality_level = Gauge::with_opts(Opts::new(
+            "spectre_quality_level",
+            "Rung of the adaptive quality ladder, 0 at full quality"
+        ))?;
+        
         let inference_latency = Histogram::with_opts(HistogramOpts::new(
             "spectre_inference_latency_seconds",
             "Inference latency in seconds"
@@ -87,22 +130,38 @@ impl SensorMetrics {
         // Register metrics
         registry.register(Box::new(frames_processed.clone()))?;
         registry.register(Box::new(frames_dropped.clone()))?;
+        registry.register(Box::new(stale_frames.clone()))?;
+        registry.register(Box::new(face_detections.clone()))?;
+        registry.register(Box::new(carried_over_faces.clone()))?;
         registry.register(Box::new(inference_errors.clone()))?;
         registry.register(Box::new(calibration_resets.clone()))?;
         registry.register(Box::new(current_fps.clone()))?;
         registry.register(Box::new(calibration_progress.clone()))?;
         registry.register(Box::new(calibration_drift.clone()))?;
+        registry.register(Box::new(connected_clients.clone()))?;
+        registry.register(Box::new(quality_level.clone()))?;
+        let inference_latency_quantiles = GaugeVec::new(Opts::new(
+            "spectre_inference_latency_quantile_seconds",
+            "Inference latency percentiles over the last ten seconds (span=\"window\") or the session"
+        ), &["span", "quantile"])?;
         registry.register(Box::new(inference_latency.clone()))?;
+        registry.register(Box::new(inference_latency_quantiles.clone()))?;
         
         Ok(Self {
             registry,
             frames_processed,
             frames_dropped,
+            stale_frames,
+            face_detections,
+            carried_over_faces,
             inference_errors,
             calibration_resets,
             current_fps,
             calibration_progress,
             calibration_drift,
+            connected_clients,
+            quality_level,
+            inference_latency_quantiles,
             inference_latency,
         })
     }
@@ -112,9 +171,23 @@ impl SensorMetrics {
         self.frames_processed.inc();
     }
     
-    /// Record a dropped frame
-    pub fn record_frame_dropped(&self) {
-        self.frames_dropped.inc();
+    /// Record a frame dropped under back-pressure `policy`
+    pub fn record_frame_dropped(&self, policy: BackpressurePolicy) {
+        self.frames_dropped.with_label_values(&[policy.as_str()]).inc();
+    }
+    
+    /// Record a captured frame that went stale before inference
+    pub fn record_stale_frame(&self) {
+        self.stale_frames.inc();
+    }
+    
+    /// Record a scored frame whose face was detected, or carried over
+    pub fn record_face_detection(&self, carried_over: bool) {
+        if carried_over {
+            self.carried_over_faces.inc();
+        } else {
+            self.face_detections.inc();
+        }
     }
     
     /// Record an inference error
@@ -142,16 +215,42 @@ impl SensorMetrics {
         self.calibration_drift.set(drift as f64);
     }
     
+    /// Update the number of open event streams
+    pub fn set_connected_clients(&self, count: usize) {
+        self.connected_clients.set(count as f64);
+    }
+    
+    /// Update the rung of the adaptive quality ladder
+    pub fn update_quality_level(&self, level: u32) {
+        self.quality_level.set(level as f64);
+    }
+    
     /// Record inference latency
     pub fn record_inference_latency(&self, latency_seconds: f64) {
         self.inference_latency.observe(latency_seconds);
     }
     
+    /// Update the p50, p95 and p99 inference latency gauges of the window
+    /// and the session
+    pub fn update_latency_percentiles(&self, latency: &LatencyTracker) {
+        self.set_latency_quantiles("window", &latency.window());
+        self.set_latency_quantiles("session", latency.session());
+    }
+
+    fn set_latency_quantiles(&self, span: &str, histogram: &LatencyHistogram) {
+        for (quantile, value) in [("0.5", histogram.p50()), ("0.95", histogram.p95()), ("0.99", histogram.p99())] {
+            self.inference_latency_quantiles
+                .with_label_values(&[span, quantile])
+                .set(value.as_secs_f64());
+        }
+    }
+    
     /// Update all metrics from performance metrics
     pub fn update_from_performance_metrics(&self, metrics: &PerformanceMetrics) {
         self.update_fps(metrics.current_fps);
         self.update_calibration_drift(metrics.calibration_drift);
-        self.record_inference_latency(metrics.p95_inference_latency.as_secs_f64());
+        self.update_quality_level(metrics.quality_level);
+        self.update_latency_percentiles(&metrics.inference_latency);
     }
     
     /// Get metrics as Prometheus text format
@@ -166,20 +265,53 @@ impl SensorMetrics {
 #[derive(Clone)]
 pub struct MetricsState {
     pub metrics: Arc<SensorMetrics>,
+    /// The sensor's metrics history, see [`crate::sensor::EmotionSensor::metrics_history`]
+    pub history: Arc<Mutex<MetricsHistory>>,
+    /// The sensor's pipeline liveness, see [`crate::sensor::EmotionSensor::liveness`]
+    pub liveness: Arc<Liveness>,
+}
+
+/// Query of `/metrics/history`, in microseconds since Unix epoch
+#[derive(Debug, Default, Deserialize)]
+pub struct HistoryQuery {
+    /// Oldest sample to return, open when absent
+    #[serde(default)]
+    pub from_us: u64,
+    /// Newest sample to return, open when absent
+    #[serde(default)]
+    pub to_us: u64,
+}
+
+/// Body of `/metrics/history`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HistoryResponse {
+    /// Time between samples
+    pub interval_ms: u64,
+    /// Most samples the sensor keeps
+    pub capacity: usize,
+    /// Samples in the requested range, oldest first
+    pub samples: Vec<MetricsSample>,
+}
+
+/// Routes served by the metrics server
+pub fn router(state: MetricsState) -> Router {
+    Router::new()
+        .route("/metrics", get(metrics_handler))
+        .route("/metrics/history", get(history_handler))
+        .route("/dashboard", get(dashboard_handler))
+        .route("/health", get(health_handler))
+        .with_state(state)
+        .layer(ServiceBuilder::new())
 }
 
 /// Start metrics server on specified port
 pub async fn start_metrics_server(
     port: u16,
     metrics: Arc<SensorMetrics>,
+    history: Arc<Mutex<MetricsHistory>>,
+    liveness: Arc<Liveness>,
 ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
-    let state = MetricsState { metrics };
-    
-    let app = Router::new()
-        .route("/metrics", get(metrics_handler))
-        .route("/health", get(health_handler))
-        .with_state(state)
-        .layer(ServiceBuilder::new());
+    let app = router(MetricsState { metrics, history, liveness });
     
     let addr = format!("0.0.0.0:{}", port);
     let listener = TcpListener::bind(&addr).await?;
@@ -211,9 +343,82 @@ async fn metrics_handler(State(state): State<MetricsState>) -> Response {
     }
 }
 
-/// Health check endpoint
-async fn health_handler() -> Response {
-    (StatusCode::OK, "OK").into_response()
+/// Metrics history endpoint handler
+async fn history_handler(
+    State(state): State<MetricsState>,
+    Query(query): Query<HistoryQuery>,
+) -> Json<HistoryResponse> {
+    let history = state.history.lock().unwrap();
+    Json(HistoryResponse {
+        interval_ms: history.interval().as_millis() as u64,
+        capacity: history.capacity(),
+        samples: history.range(query.from_us, query.to_us),
+    })
+}
+
+/// Dashboard page drawing the metrics history
+async fn dashboard_handler() -> Html<&'static str> {
+    Html(DASHBOARD_HTML)
+}
+
+/// Self-contained dashboard: polls `/metrics/history` and draws sparklines
+const DASHBOARD_HTML: &str = r##"<!DOCTYPE html>
+<html>
+<head>
+<meta charset="utf-8">
+<title>SpectreMesh sensor</title>
+<style>
+  body { font-family: sans-serif; background: #111; color: #ddd; margin: 2em; }
+  .chart { margin-bottom: 2em; }
+  .chart h2 { font-size: 1em; font-weight: normal; margin: 0 0 0.3em; }
+  .chart span { color: #fff; font-weight: bold; }
+  svg { width: 100%; height: 80px; background: #1b1b1b; }
+  polyline { fill: none; stroke-width: 1.5; }
+</style>
+</head>
+<body>
+<h1>SpectreMesh sensor</h1>
+<div class="chart"><h2>FPS <span id="fps-now">-</span></h2>
+  <svg viewBox="0 0 1000 80" preserveAspectRatio="none"><polyline id="fps" stroke="#6cf"/></svg></div>
+<div class="chart"><h2>p95 inference latency (ms) <span id="latency-now">-</span></h2>
+  <svg viewBox="0 0 1000 80" preserveAspectRatio="none"><polyline id="latency" stroke="#fc6"/></svg></div>
+<div class="chart"><h2>Calibration drift <span id="drift-now">-</span></h2>
+  <svg viewBox="0 0 1000 80" preserveAspectRatio="none"><polyline id="drift" stroke="#c6f"/></svg></div>
+<p id="summary"></p>
+<script>
+function draw(id, values) {
+  const max = Math.max(...values, 1e-6);
+  const step = values.length > 1 ? 1000 / (values.length - 1) : 0;
+  const points = values.map((v, i) => `${(i * step).toFixed(1)},${(78 - 76 * v / max).toFixed(1)}`);
+  document.getElementById(id).setAttribute("points", points.join(" "));
+  document.getElementById(id + "-now").textContent = values.length ? values[values.length - 1].toFixed(2) : "-";
+}
+async function refresh() {
+  try {
+    const history = await (await fetch("/metrics/history")).json();
+    const samples = history.samples;
+    draw("fps", samples.map(s => s.fps));
+    draw("latency", samples.map(s => s.p95_inference_latency_us / 1000));
+    draw("drift", samples.map(s => s.calibration_drift));
+    document.getElementById("summary").textContent =
+      `${samples.length} of ${history.capacity} samples, one every ${history.interval_ms / 1000} s`;
+  } catch (e) {
+    document.getElementById("summary").textContent = `Sensor unreachable: ${e}`;
+  }
+}
+refresh();
+setInterval(refresh, 5000);
+</script>
+</body>
+</html>
+"##;
+
+/// Health check endpoint: OK while the pipeline runs and frames are fresh
+async fn health_handler(State(state): State<MetricsState>) -> Response {
+    match state.liveness.check(Instant::now()) {
+        Ok(_) => (StatusCode::OK, "OK").into_response(),
+        Err(reason) => (StatusCode::SERVICE_UNAVAILABLE, reason.to_string()).into_response(),
+    }
 }
 
 #[cfg(test)]
@@ -236,23 +441,30 @@ mod tests {
         
         // Record some metrics
         metrics.record_frame_processed();
-        metrics.record_frame_dropped();
+        metrics.record_frame_dropped(BackpressurePolicy::DropOldest);
         metrics.record_inference_error();
         metrics.record_calibration_reset();
+        metrics.record_face_detection(false);
+        metrics.record_face_detection(true);
+        metrics.record_face_detection(true);
         metrics.update_fps(30.0);
         metrics.update_calibration_progress(0.5);
         metrics.update_calibration_drift(0.1);
+        metrics.set_connected_clients(2);
         metrics.record_inference_latency(0.005);
         
         // Gather metrics and check they contain our data
         let gathered = metrics.gather().unwrap();
         assert!(gathered.contains("spectre_frames_processed_total"));
-        assert!(gathered.contains("spectre_frames_dropped_total"));
+        assert!(gathered.contains("spectre_frames_dropped_total{policy=\"drop_oldest\"} 1"));
         assert!(gathered.contains("spectre_inference_errors_total"));
         assert!(gathered.contains("spectre_calibration_resets_total"));
+        assert!(gathered.contains("spectre_face_detections_total 1"));
+        assert!(gathered.contains("spectre_carried_over_faces_total 2"));
         assert!(gathered.contains("spectre_current_fps"));
         assert!(gathered.contains("spectre_calibration_progress"));
         assert!(gathered.contains("spectre_calibration_drift"));
+        assert!(gathered.contains("spectre_connected_clients 2"));
         assert!(gathered.contains("spectre_inference_latency_seconds"));
     }
 
@@ -260,20 +472,28 @@ mod tests {
     fn test_performance_metrics_update() {
         let metrics = SensorMetrics::new().unwrap();
         
-        let perf_metrics = PerformanceMetrics {
+        let mut perf_metrics = PerformanceMetrics {
             current_fps: 25.5,
-            p95_inference_latency: Duration::from_millis(8),
             dropped_frames: 5,
             calibration_drift: 0.15,
-            last_update: std::time::Instant::now(),
+            quality_level: 2,
+            ..PerformanceMetrics::default()
         };
+        for _ in 0..20 {
+            perf_metrics.record_inference_latency(Duration::from_millis(8));
+        }
+        perf_metrics.update_inference_latency();
+        perf_metrics.record_inference_latency(Duration::from_millis(2));
         
         metrics.update_from_performance_metrics(&perf_metrics);
         
         let gathered = metrics.gather().unwrap();
         assert!(gathered.contains("25.5")); // FPS
-        assert!(gathered.contains("0.008")); // Latency in seconds
+        // Latency in seconds, the one fast frame below the 50th percentile
+        assert!(gathered.contains(r#"spectre_inference_latency_quantile_seconds{quantile="0.95",span="window"} 0.008"#));
+        assert!(gathered.contains(r#"spectre_inference_latency_quantile_seconds{quantile="0.5",span="session"} 0.008"#));
         assert!(gathered.contains("0.15")); // Drift
+        assert!(gathered.contains("spectre_quality_level 2"));
     }
 
     #[tokio::test]
@@ -282,7 +502,9 @@ mod tests {
         
         // Test that we can create the router without error
         let state = MetricsState { 
-            metrics: metrics.clone() 
+            metrics: metrics.clone(),
+            history: Arc::new(Mutex::new(MetricsHistory::default())),
+            liveness: Arc::new(Liveness::default()),
         };
         
         let _app: axum::Router<MetricsState> = Router::new()
@@ -293,4 +515,92 @@ mod tests {
         // We can't easily test the full server without binding to a port
         // but we can test the handler functions
     }
+
+    async fn get_body(app: Router, uri: &str) -> (StatusCode, String) {
+        use tower::ServiceExt;
+        let request = axum::http::Request::get(uri).body(axum::body::Body::empty()).unwrap();
+        let response = app.oneshot(request).await.unwrap();
+        let status = response.status();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        (status, String::from_utf8(body.to_vec()).unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_history_endpoint() {
+        let history = Arc::new(Mutex::new(MetricsHistory::default()));
+        for i in 0..10u64 {
+            let perf_metrics = PerformanceMetrics {
+                current_fps: 30.0 - i as f32,
+                p95_inference_latency: Duration::from_millis(8),
+                ..PerformanceMetrics::default()
+            };
+            history.lock().unwrap().record(1_000_000 + i * 5_000_000, &perf_metrics);
+        }
+        let app = router(MetricsState {
+            metrics: Arc::new(SensorMetrics::new().unwrap()),
+            history,
+            liveness: Arc::new(Liveness::default()),
+        });
+
+        let (status, body) = get_body(app.clone(), "/metrics/history").await;
+        assert_eq!(status, StatusCode::OK);
+        let json: serde_json::Value = serde_json::from_str(&body).unwrap();
+        assert_eq!(json["interval_ms"], 5000);
+        assert_eq!(json["capacity"], 720);
+        let samples = json["samples"].as_array().unwrap();
+        assert_eq!(samples.len(), 10);
+        assert_eq!(samples[0]["timestamp_us"], 1_000_000);
+        assert_eq!(samples[0]["fps"], 30.0);
+        assert_eq!(samples[0]["p95_inference_latency_us"], 8_000);
+        assert_eq!(samples[0]["dropped_frames"], 0);
+        assert_eq!(samples[0]["calibration_drift"], 0.0);
+
+        let (_, body) = get_body(app, "/metrics/history?from_us=11000000&to_us=21000000").await;
+        let response: HistoryResponse = serde_json::from_str(&body).unwrap();
+        let timestamps: Vec<u64> = response.samples.iter().map(|s| s.timestamp_us).collect();
+        assert_eq!(timestamps, [11_000_000, 16_000_000, 21_000_000]);
+    }
+
+    #[tokio::test]
+    async fn test_dashboard_page() {
+        let app = router(MetricsState {
+            metrics: Arc::new(SensorMetrics::new().unwrap()),
+            history: Arc::new(Mutex::new(MetricsHistory::default())),
+            liveness: Arc::new(Liveness::default()),
+        });
+
+        let (status, body) = get_body(app.clone(), "/dashboard").await;
+        assert_eq!(status, StatusCode::OK);
+        assert!(body.starts_with("<!DOCTYPE html>"));
+        assert!(body.contains("fetch(\"/metrics/history\")"));
+
+        // The Prometheus endpoint still serves alongside the history
+        let (status, body) = get_body(app, "/metrics").await;
+        assert_eq!(status, StatusCode::OK);
+        assert!(body.contains("spectre_current_fps"));
+    }
+
+    #[tokio::test]
+    async fn test_health_follows_liveness() {
+        let liveness = Arc::new(Liveness::new(Duration::from_secs(2)));
+        let app = router(MetricsState {
+            metrics: Arc::new(SensorMetrics::new().unwrap()),
+            history: Arc::new(Mutex::new(MetricsHistory::default())),
+            liveness: Arc::clone(&liveness),
+        });
+
+        let (status, body) = get_body(app.clone(), "/health").await;
+        assert_eq!((status, body.as_str()), (StatusCode::SERVICE_UNAVAILABLE, "Pipeline not running"));
+
+        liveness.start();
+        liveness.record_frame(Instant::now());
+        let (status, body) = get_body(app.clone(), "/health").await;
+        assert_eq!((status, body.as_str()), (StatusCode::OK, "OK"));
+
+        // Running but stalled
+        liveness.record_frame(Instant::now() - Duration::from_secs(5));
+        let (status, body) = get_body(app, "/health").await;
+        assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+        assert!(body.starts_with("Last frame captured"));
+    }
 }
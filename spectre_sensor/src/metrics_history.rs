@@ -0,0 +1,253 @@
+//! Performance metrics history for dashboards without Prometheus
+//!
+//! The processing loop snapshots its [`PerformanceMetrics`] into a
+//! [`MetricsHistory`] every `interval` (5 s by default), keeping `retention`
+//! worth of samples (an hour by default). `GetMetricsHistory`, the metrics
+//! server's `/metrics/history` JSON endpoint and its `/dashboard` page all
+//! read the same history, and bug report bundles include it.
+//!
+//! Memory use is strictly bounded: the ring holds at most
+//! [`MetricsHistoryConfig::capacity`] samples of 32 bytes each, allocated up
+//! front and never grown. The default keeps 720 samples, about 23 KiB;
+//! whatever the configuration, the ring never exceeds [`MAX_SAMPLES`]
+//! samples (about 2.6 MiB).
+
+use crate::types::PerformanceMetrics;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::time::Duration;
+
+/// Upper bound on the samples any history keeps, a day at one per second
+pub const MAX_SAMPLES: usize = 24 * 60 * 60;
+
+/// Shortest sampling interval; the processing loop updates its metrics once a second
+pub const MIN_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Metrics history sampling and retention
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MetricsHistoryConfig {
+    /// Time between samples, at least [`MIN_INTERVAL`]
+    #[serde(with = "spectremesh_core::duration")]
+    pub interval: Duration,
+    /// How far back the history reaches
+    #[serde(with = "spectremesh_core::duration")]
+    pub retention: Duration,
+}
+
+impl Default for MetricsHistoryConfig {
+    fn default() -> Self {
+        Self {
+            interval: Duration::from_secs(5),
+            retention: Duration::from_secs(60 * 60),
+        }
+    }
+}
+
+impl MetricsHistoryConfig {
+    /// Samples needed to cover `retention`, between 1 and [`MAX_SAMPLES`]
+    pub fn capacity(&self) -> usize {
+        let interval = self.interval.max(MIN_INTERVAL).as_millis();
+        let samples = self.retention.as_millis().div_ceil(interval);
+        (samples as usize).clamp(1, MAX_SAMPLES)
+    }
+
+    /// Validate the configuration
+    pub fn validate(&self) -> Result<(), String> {
+        if self.interval.is_zero() || self.retention.is_zero() {
+            return Err("Metrics history interval and retention must be positive".to_string());
+        }
+        Ok(())
+    }
+
+    /// Most memory the history's samples can take
+    pub fn max_bytes(&self) -> usize {
+        self.capacity() * std::mem::size_of::<MetricsSample>()
+    }
+}
+
+/// Performance metrics at one point in time
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct MetricsSample {
+    /// Microseconds since Unix epoch
+    pub timestamp_us: u64,
+    pub fps: f32,
+    pub p95_inference_latency_us: u64,
+    /// Frames dropped since the sensor started
+    pub dropped_frames: u64,
+    pub calibration_drift: f32,
+}
+
+impl MetricsSample {
+    /// Snapshot `metrics` taken at `timestamp_us`
+    pub fn new(timestamp_us: u64, metrics: &PerformanceMetrics) -> Self {
+        Self {
+            timestamp_us,
+            fps: metrics.current_fps,
+            p95_inference_latency_us: metrics.p95_inference_latency.as_micros() as u64,
+            dropped_frames: metrics.dropped_frames,
+            calibration_drift: metrics.calibration_drift,
+        }
+    }
+}
+
+/// Bounded ring of periodic metrics samples, oldest first
+#[derive(Debug, Clone)]
+pub struct MetricsHistory {
+    interval: Duration,
+    capacity: usize,
+    samples: VecDeque<MetricsSample>,
+}
+
+impl MetricsHistory {
+    pub fn new(config: &MetricsHistoryConfig) -> Self {
+        let capacity = config.capacity();
+        Self {
+            interval: config.interval.max(MIN_INTERVAL),
+            capacity,
+            samples: VecDeque::with_capacity(capacity),
+        }
+    }
+
+    /// Time between samples
+    pub fn interval(&self) -> Duration {
+        self.interval
+    }
+
+    /// Most samples kept
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Number of samples kept
+    pub fn len(&self) -> usize {
+        self.samples.len()
+    }
+
+    /// Whether no samples were recorded yet
+    pub fn is_empty(&self) -> bool {
+        self.samples.is_empty()
+    }
+
+    /// Sample `metrics` at `timestamp_us` if an interval passed since the last sample
+    ///
+    /// Returns whether a sample was taken.
+    pub fn record(&mut self, timestamp_us: u64, metrics: &PerformanceMetrics) -> bool {
+        let interval_us = self.interval.as_micros() as u64;
+        let due = match self.samples.back() {
+            Some(last) => timestamp_us.abs_diff(last.timestamp_us) >= interval_us,
+            None => true,
+        };
+        if due {
+            self.push(MetricsSample::new(timestamp_us, metrics));
+        }
+        due
+    }
+
+    /// Append a sample, evicting the oldest once full
+    pub fn push(&mut self, sample: MetricsSample) {
+        if self.samples.len() == self.capacity {
+            self.samples.pop_front();
+        }
+        self.samples.push_back(sample);
+    }
+
+    /// Samples taken between `from_us` and `to_us` inclusive, oldest first
+    ///
+    /// A zero bound leaves that end open.
+    pub fn range(&self, from_us: u64, to_us: u64) -> Vec<MetricsSample> {
+        let to_us = if to_us == 0 { u64::MAX } else { to_us };
+        self.samples
+            .iter()
+            .filter(|sample| (from_us..=to_us).contains(&sample.timestamp_us))
+            .copied()
+            .collect()
+    }
+
+    /// Every sample kept, oldest first
+    pub fn samples(&self) -> Vec<MetricsSample> {
+        self.samples.iter().copied().collect()
+    }
+}
+
+impl Default for MetricsHistory {
+    fn default() -> Self {
+        Self::new(&MetricsHistoryConfig::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn metrics(fps: f32) -> PerformanceMetrics {
+        PerformanceMetrics {
+            current_fps: fps,
+            p95_inference_latency: Duration::from_millis(7),
+            ..PerformanceMetrics::default()
+        }
+    }
+
+    fn history(capacity: u64) -> MetricsHistory {
+        MetricsHistory::new(&MetricsHistoryConfig {
+            interval: Duration::from_secs(5),
+            retention: Duration::from_secs(5 * capacity),
+        })
+    }
+
+    #[test]
+    fn test_capacity_is_bounded() {
+        let config = MetricsHistoryConfig::default();
+        assert_eq!(config.capacity(), 720);
+        assert_eq!(std::mem::size_of::<MetricsSample>(), 32);
+        assert_eq!(config.max_bytes(), 720 * 32);
+
+        // Partial intervals round up, silly settings stay bounded
+        let config = MetricsHistoryConfig { interval: Duration::from_secs(4), retention: Duration::from_secs(10) };
+        assert_eq!(config.capacity(), 3);
+        let config = MetricsHistoryConfig { interval: Duration::ZERO, retention: Duration::from_secs(365 * 86_400) };
+        assert_eq!(config.capacity(), MAX_SAMPLES);
+        assert_eq!(MetricsHistory::new(&config).interval(), MIN_INTERVAL);
+        let config = MetricsHistoryConfig { interval: Duration::from_secs(5), retention: Duration::ZERO };
+        assert_eq!(config.capacity(), 1);
+    }
+
+    #[test]
+    fn test_ring_evicts_oldest_first() {
+        let mut history = history(4);
+        let allocated = history.samples.capacity();
+        for i in 0..10u64 {
+            assert!(history.record(i * 5_000_000, &metrics(i as f32)));
+        }
+        assert_eq!(history.len(), 4);
+        assert_eq!(history.samples.capacity(), allocated);
+        let fps: Vec<f32> = history.samples().iter().map(|sample| sample.fps).collect();
+        assert_eq!(fps, [6.0, 7.0, 8.0, 9.0]);
+    }
+
+    #[test]
+    fn test_samples_once_per_interval() {
+        let mut history = history(10);
+        assert!(history.record(1_000_000, &metrics(30.0)));
+        assert!(!history.record(2_000_000, &metrics(31.0)));
+        assert!(!history.record(5_999_999, &metrics(32.0)));
+        assert!(history.record(6_000_000, &metrics(33.0)));
+        assert_eq!(history.len(), 2);
+        assert_eq!(history.samples()[1].p95_inference_latency_us, 7_000);
+    }
+
+    #[test]
+    fn test_range_query() {
+        let mut history = history(100);
+        for i in 0..20u64 {
+            history.record(i * 5_000_000, &metrics(i as f32));
+        }
+
+        let range = history.range(20_000_000, 40_000_000);
+        let fps: Vec<f32> = range.iter().map(|sample| sample.fps).collect();
+        assert_eq!(fps, [4.0, 5.0, 6.0, 7.0, 8.0]);
+        assert_eq!(history.range(90_000_000, 0).len(), 2);
+        assert_eq!(history.range(0, 0).len(), 20);
+        assert!(history.range(200_000_000, 0).is_empty());
+    }
+}
@@ -0,0 +1,180 @@
+//! Horizontally mirrored camera feeds
+//!
+//! Many webcams, and some OS camera settings, deliver selfie-style frames
+//! flipped left to right. Emotion recognition barely notices, but everything
+//! derived from positions does: YuNet names the eye on the image's left the
+//! subject's right eye, so on a mirrored feed eye identity and the sign of
+//! [`FaceDetection::yaw`](crate::yunet::FaceDetection::yaw) come out
+//! inverted, as do face-position effects and monitoring thumbnails.
+//!
+//! [`MirrorInput`] decides whether frames are flipped back right after
+//! capture, so detections, landmarks, crops and annotations all agree with
+//! the frame the rest of the pipeline sees.
+
+use opencv::core::{self, Mat};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+/// Whether captured frames are flipped horizontally before processing
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MirrorInput {
+    /// Follow the camera backend's hint, leaving frames as they are without one
+    #[default]
+    Auto,
+    /// Always flip frames
+    On,
+    /// Never flip frames
+    Off,
+}
+
+impl MirrorInput {
+    /// Whether to flip frames, given the backend's report of whether it mirrors
+    pub fn resolve(self, backend_hint: Option<bool>) -> bool {
+        match (self, backend_hint) {
+            (Self::On, _) => true,
+            (Self::Off, _) => false,
+            (Self::Auto, Some(mirrored)) => mirrored,
+            (Self::Auto, None) => {
+                tracing::info!(
+                    "Camera backend does not report mirroring; frames are left as captured \
+                     (set SPECTRE_MIRROR_INPUT=on if thumbnails look reversed)"
+                );
+                false
+            }
+        }
+    }
+}
+
+impl fmt::Display for MirrorInput {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Auto => "auto",
+            Self::On => "on",
+            Self::Off => "off",
+        })
+    }
+}
+
+impl FromStr for MirrorInput {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "auto" => Ok(Self::Auto),
+            "on" | "true" => Ok(Self::On),
+            "off" | "false" => Ok(Self::Off),
+            other => Err(format!("Unknown mirror input setting: {}", other)),
+        }
+    }
+}
+
+/// Flip a frame left to right in place
+pub fn mirror_frame(frame: &mut Mat) -> opencv::Result<()> {
+    let mut flipped = Mat::default();
+    core::flip(&*frame, &mut flipped, 1)?;
+    *frame = flipped;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::yunet::FaceDetection;
+    use opencv::{
+        core::{Point, Rect, Scalar, CV_8UC1},
+        prelude::*,
+    };
+
+    const EYE_ROW: i32 = 16;
+    const NOSE_ROW: i32 = 28;
+    const RIGHT_EYE: u8 = 255;
+    const LEFT_EYE: u8 = 128;
+
+    /// A face as an unmirrored camera sees it: the subject's right eye on the
+    /// image's left, told apart by brightness, and the nose turned toward the
+    /// subject's left
+    fn subject_frame() -> Mat {
+        let mut frame = Mat::new_rows_cols_with_default(48, 64, CV_8UC1, Scalar::all(0.0)).unwrap();
+        *frame.at_2d_mut::<u8>(EYE_ROW, 20).unwrap() = RIGHT_EYE;
+        *frame.at_2d_mut::<u8>(EYE_ROW, 44).unwrap() = LEFT_EYE;
+        *frame.at_2d_mut::<u8>(NOSE_ROW, 38).unwrap() = 255;
+        frame
+    }
+
+    fn lit_columns(frame: &Mat, row: i32) -> Vec<i32> {
+        (0..frame.cols()).filter(|&x| *frame.at_2d::<u8>(row, x).unwrap() > 0).collect()
+    }
+
+    /// Label landmarks the way YuNet does, by position in the image
+    fn detect(frame: &Mat) -> FaceDetection {
+        let eyes = lit_columns(frame, EYE_ROW);
+        let nose = Point::new(lit_columns(frame, NOSE_ROW)[0], NOSE_ROW);
+        FaceDetection {
+            bbox: Rect::new(0, 0, frame.cols(), frame.rows()),
+            confidence: 0.9,
+            landmarks: vec![Point::new(eyes[0], EYE_ROW), Point::new(eyes[1], EYE_ROW), nose, nose, nose],
+        }
+    }
+
+    fn processed(feed_mirrored: bool, setting: MirrorInput) -> (Mat, FaceDetection) {
+        let mut frame = subject_frame();
+        if feed_mirrored {
+            mirror_frame(&mut frame).unwrap();
+        }
+        if setting.resolve(None) {
+            mirror_frame(&mut frame).unwrap();
+        }
+        let detection = detect(&frame);
+        (frame, detection)
+    }
+
+    fn brightness(frame: &Mat, point: Point) -> u8 {
+        *frame.at_2d::<u8>(point.y, point.x).unwrap()
+    }
+
+    #[test]
+    fn test_unmirrored_feed_left_alone() {
+        let (frame, detection) = processed(false, MirrorInput::Off);
+        assert_eq!(brightness(&frame, detection.right_eye().unwrap()), RIGHT_EYE);
+        assert_eq!(brightness(&frame, detection.left_eye().unwrap()), LEFT_EYE);
+        assert!(detection.yaw().unwrap() > 0.0);
+    }
+
+    #[test]
+    fn test_mirrored_feed_flipped_back() {
+        let (frame, detection) = processed(true, MirrorInput::On);
+        assert_eq!(brightness(&frame, detection.right_eye().unwrap()), RIGHT_EYE);
+        assert_eq!(brightness(&frame, detection.left_eye().unwrap()), LEFT_EYE);
+        assert!(detection.yaw().unwrap() > 0.0);
+
+        // The same face through the wrong setting swaps the eyes and the yaw sign
+        for (feed_mirrored, setting) in [(true, MirrorInput::Off), (false, MirrorInput::On)] {
+            let (frame, detection) = processed(feed_mirrored, setting);
+            assert_eq!(brightness(&frame, detection.right_eye().unwrap()), LEFT_EYE);
+            assert!(detection.yaw().unwrap() < 0.0);
+        }
+    }
+
+    #[test]
+    fn test_mirror_frame_moves_columns() {
+        let mut frame = subject_frame();
+        mirror_frame(&mut frame).unwrap();
+        assert_eq!(lit_columns(&frame, EYE_ROW), vec![63 - 44, 63 - 20]);
+        assert_eq!(frame.size().unwrap(), subject_frame().size().unwrap());
+    }
+
+    #[test]
+    fn test_mirror_input_resolution() {
+        assert!(MirrorInput::On.resolve(Some(false)));
+        assert!(!MirrorInput::Off.resolve(Some(true)));
+        assert!(MirrorInput::Auto.resolve(Some(true)));
+        assert!(!MirrorInput::Auto.resolve(None));
+
+        assert_eq!("ON".parse::<MirrorInput>(), Ok(MirrorInput::On));
+        assert_eq!("false".parse::<MirrorInput>(), Ok(MirrorInput::Off));
+        assert_eq!(MirrorInput::default(), MirrorInput::Auto);
+        assert!("sideways".parse::<MirrorInput>().is_err());
+    }
+}
@@ -0,0 +1,652 @@
+//! Mock sensor for trying integrations without a camera or models
+//!
+//! [`MockEmotionSensor`] plays a pattern from [`crate::mock_patterns`]
+//! through the real [`AdaptiveCalibrator`] at a fixed frame rate. Frames,
+//! calibration phases, calibration control and single-shot measurements
+//! behave as they do on a camera, with the same method names as
+//! [`EmotionSensor`](crate::EmotionSensor), so code written against the mock
+//! moves to the real sensor by swapping the constructor.
+//!
+//! With `stream`, [`MockSensorService`] serves the mock over gRPC with the
+//! daemon's own event streams, and [`spawn_mock_daemon`] runs it on an
+//! in-process loopback transport for clients to connect to, or
+//! [`spawn_mock_daemon_on`] on a socket for clients in other processes.
+//! [`spawn_mock_service_on`] serves a service built by the caller, who keeps
+//! its sensor to drive, until a shutdown signal.
+
+use crate::{
+    backpressure::{self, BackpressurePolicy},
+    calibration_control::{control_channel, pipeline_phase, CalibrationAction, CalibrationController},
+    calibrator::{AdaptiveCalibrator, BaselineStats},
+    clock_sync::SensorClockSync,
+    measure::LiveFrames,
+    mock_patterns,
+    phases::{publish_phase, CalibrationPhase, CalibrationPhases},
+    sensor::SensorError,
+    types::{FearFrame, SensorCapability, SensorFaultNotice, SensorMarker},
+};
+use async_channel::Receiver;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use spectremesh_core::math::compute_confidence;
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc,
+};
+use std::time::{Duration, Instant};
+use tokio::sync::{broadcast, watch};
+
+/// Mock sensor settings
+#[derive(Debug, Clone, PartialEq)]
+pub struct MockSensorConfig {
+    /// Raw fear logits, one per frame, looped
+    pub pattern: Vec<f32>,
+    /// Frames per second
+    pub target_fps: f32,
+    /// Time the calibrator collects a baseline for
+    pub calibration_period: Duration,
+    /// Largest random offset added to each logit, so the baseline has spread
+    pub noise: f32,
+    /// Seed for the noise, for repeatable runs
+    pub seed: u64,
+    /// Frames buffered for a slow reader before frames are dropped
+    pub channel_buffer_size: usize,
+    /// Which frames a full buffer drops, or whether it waits for the reader
+    pub backpressure: BackpressurePolicy,
+}
+
+impl Default for MockSensorConfig {
+    fn default() -> Self {
+        Self {
+            pattern: mock_patterns::sine(0.5, 0.3, 1.0),
+            target_fps: 30.0,
+            calibration_period: Duration::from_secs(2),
+            noise: 0.05,
+            seed: 0,
+            channel_buffer_size: 4,
+            backpressure: BackpressurePolicy::default(),
+        }
+    }
+}
+
+impl MockSensorConfig {
+    /// Play `pattern` instead of the default sine wave; an empty one plays 0.5
+    pub fn with_pattern(mut self, pattern: Vec<f32>) -> Self {
+        self.pattern = pattern;
+        self
+    }
+
+    /// Set the frame rate
+    pub fn with_target_fps(mut self, fps: f32) -> Self {
+        self.target_fps = fps;
+        self
+    }
+
+    /// Set how long calibration collects for
+    pub fn with_calibration_period(mut self, period: Duration) -> Self {
+        self.calibration_period = period;
+        self
+    }
+
+    /// Set what a full buffer does with the next frame
+    pub fn with_backpressure(mut self, policy: BackpressurePolicy) -> Self {
+        self.backpressure = policy;
+        self
+    }
+}
+
+/// Camera-free stand-in for [`EmotionSensor`](crate::EmotionSensor)
+pub struct MockEmotionSensor {
+    config: MockSensorConfig,
+    /// Cleared to stop the running loop
+    running: Option<Arc<AtomicBool>>,
+    calibration: watch::Sender<CalibrationPhase>,
+    /// Baseline snapshot, published with the calibration phase
+    baseline: watch::Sender<Option<BaselineStats>>,
+    calibration_controller: Option<CalibrationController>,
+    live_frames: LiveFrames,
+    markers: broadcast::Sender<SensorMarker>,
+    faults: broadcast::Sender<SensorFaultNotice>,
+    clock_syncs: broadcast::Sender<SensorClockSync>,
+}
+
+impl MockEmotionSensor {
+    pub fn new(config: MockSensorConfig) -> Self {
+        let (calibration, _) = watch::channel(CalibrationPhase::Idle);
+        let (baseline, _) = watch::channel(None);
+        let (markers, _) = broadcast::channel(16);
+        let (faults, _) = broadcast::channel(16);
+        let (clock_syncs, _) = broadcast::channel(16);
+        Self {
+            config,
+            running: None,
+            calibration,
+            baseline,
+            calibration_controller: None,
+            live_frames: LiveFrames::new(),
+            markers,
+            faults,
+            clock_syncs,
+        }
+    }
+
+    pub fn config(&self) -> &MockSensorConfig {
+        &self.config
+    }
+
+    /// Whether frames are being produced
+    pub fn is_running(&self) -> bool {
+        self.running.as_ref().is_some_and(|running| running.load(Ordering::Relaxed))
+    }
+
+    /// Start producing frames, restarting calibration
+    pub async fn start(&mut self) -> Result<Receiver<FearFrame>, SensorError> {
+        self.stop().await?;
+
+        let (sender, receiver) = backpressure::channel(self.config.channel_buffer_size, self.config.backpressure);
+        let running = Arc::new(AtomicBool::new(true));
+        let (controller, mut controls) = control_channel();
+        self.running = Some(Arc::clone(&running));
+        self.calibration_controller = Some(controller);
+
+        let mut config = self.config.clone();
+        if config.pattern.is_empty() {
+            config.pattern = mock_patterns::constant(0.5, 1);
+        }
+        let calibration = self.calibration.clone();
+        let baseline = self.baseline.clone();
+        let live_frames = self.live_frames.clone();
+        let faults = self.faults.clone();
+        tokio::spawn(async move {
+            let mut calibrator = AdaptiveCalibrator::with_defaults(config.calibration_period);
+            let mut rng = StdRng::seed_from_u64(config.seed);
+            let mut ticks = tokio::time::interval(Duration::from_secs_f32(1.0 / config.target_fps.max(1.0)));
+            let mut last_published = Instant::now();
+            let capability = SensorCapability::Full;
+            publish_phase(&calibration, pipeline_phase(&calibrator, capability));
+
+            for &level in config.pattern.iter().cycle() {
+                ticks.tick().await;
+                if !running.load(Ordering::Relaxed) {
+                    break;
+                }
+                controls.apply_pending(&mut calibrator, capability, &calibration);
+
+                let frame = mock_frame(&mut calibrator, level + rng.gen_range(-1.0..=1.0) * config.noise);
+                if live_frames.publish(frame, &sender).await.is_err() {
+                    break;
+                }
+
+                // Like the camera pipeline: completion right away, progress once a second
+                let completed = calibrator.is_calibrated() && !calibration.borrow().is_calibrated();
+                if completed || last_published.elapsed() >= Duration::from_secs(1) {
+                    baseline.send_replace(Some(calibrator.baseline_stats().clone()));
+                    publish_phase(&calibration, pipeline_phase(&calibrator, capability));
+                    last_published = Instant::now();
+                }
+            }
+
+            running.store(false, Ordering::Relaxed);
+            let _ = faults.send(SensorFaultNotice::stopped("Mock sensor stopped"));
+        });
+
+        Ok(receiver)
+    }
+
+    /// Stop producing frames; open receivers end after the frames already sent
+    pub async fn stop(&mut self) -> Result<(), SensorError> {
+        if let Some(running) = self.running.take() {
+            running.store(false, Ordering::Relaxed);
+        }
+        self.calibration_controller = None;
+        Ok(())
+    }
+
+    /// Next frame of the running mock, or one uncalibrated frame while stopped
+    pub async fn measure_once(&mut self, timeout: Duration) -> Result<FearFrame, SensorError> {
+        if self.is_running() {
+            return self.live_frames.next(timeout).await;
+        }
+        let mut calibrator = AdaptiveCalibrator::with_defaults(self.config.calibration_period);
+        let level = self.config.pattern.first().copied().unwrap_or(0.5);
+        Ok(mock_frame(&mut calibrator, level))
+    }
+
+    /// Copies of running frames for single-shot measurements
+    pub fn live_frames(&self) -> LiveFrames {
+        self.live_frames.clone()
+    }
+
+    /// Start, freeze, unfreeze or reset calibration, as on the real sensor
+    pub async fn control_calibration(&mut self, action: CalibrationAction) -> Result<CalibrationPhase, SensorError> {
+        if let Some(controller) = self.calibration_controller.clone().filter(|_| self.is_running()) {
+            return Ok(controller.send(action).await?);
+        }
+        action.check(&CalibrationPhase::Idle)?;
+        publish_phase(&self.calibration, CalibrationPhase::Idle);
+        Ok(CalibrationPhase::Idle)
+    }
+
+    /// Current calibration phase
+    pub fn calibration_phase(&self) -> CalibrationPhase {
+        self.calibration.borrow().clone()
+    }
+
+    /// Latest baseline of the running calibration, published with the
+    /// phase; `None` before the first publish
+    pub fn baseline(&self) -> Option<BaselineStats> {
+        self.baseline.borrow().clone()
+    }
+
+    /// Subscribe to calibration phase changes
+    pub fn subscribe_calibration(&self) -> watch::Receiver<CalibrationPhase> {
+        self.calibration.subscribe()
+    }
+
+    /// Calibration phases, starting with the current one
+    pub fn phases(&self) -> CalibrationPhases {
+        let faults = self.faults.subscribe();
+        CalibrationPhases::watch(self.calibration.subscribe(), faults, !self.is_running())
+    }
+
+    /// Record a named marker for every stream
+    pub fn insert_marker(&self, label: impl Into<String>) -> SensorMarker {
+        let marker = SensorMarker::new(label);
+        let _ = self.markers.send(marker.clone());
+        marker
+    }
+
+    pub fn subscribe_markers(&self) -> broadcast::Receiver<SensorMarker> {
+        self.markers.subscribe()
+    }
+
+    pub fn subscribe_faults(&self) -> broadcast::Receiver<SensorFaultNotice> {
+        self.faults.subscribe()
+    }
+
+    /// Send `notice` to every stream as if the sensor had raised it
+    pub fn inject_fault(&self, notice: SensorFaultNotice) {
+        let _ = self.faults.send(notice);
+    }
+
+    /// Record a clock estimate reported by the game
+    pub fn record_clock_sync(&self, sync: SensorClockSync) {
+        let _ = self.clock_syncs.send(sync);
+    }
+
+    pub fn subscribe_clock_syncs(&self) -> broadcast::Receiver<SensorClockSync> {
+        self.clock_syncs.subscribe()
+    }
+}
+
+/// Score one raw fear logit, teaching it to the calibrator
+fn mock_frame(calibrator: &mut AdaptiveCalibrator, fear_logit: f32) -> FearFrame {
+    let logits = mock_patterns::emotion_logits(fear_logit, calibrator.layout());
+    let confidence = compute_confidence(1.0, &logits);
+    if !calibrator.is_frozen() {
+        let _ = calibrator.add_weighted_logits(&logits, confidence);
+    }
+    FearFrame::new(
+        calibrator.fear_score(&logits),
+        logits,
+        confidence,
+        calibrator.is_calibrated(),
+        Duration::ZERO,
+    )
+}
+
+#[cfg(feature = "stream")]
+pub use service::{spawn_mock_daemon, spawn_mock_daemon_on, spawn_mock_service_on, MockSensorService};
+
+#[cfg(feature = "stream")]
+mod service {
+    use super::*;
+    use crate::{
+        clients::ClientRegistry,
+        fanout::FrameFanout,
+        grpc_server::{baseline_stats, calibration_control_status, create_event_stream, score_message, StreamNotices},
+        measure::DEFAULT_MEASURE_TIMEOUT,
+        proto::{
+            sensor_service_server::{SensorService, SensorServiceServer},
+            *,
+        },
+        clock_sync::read_clock,
+        resume::{Clock, SystemClock},
+        transport::{PeerInfo, SensorTransport, TransportError},
+    };
+    use std::future::Future;
+    use std::pin::Pin;
+    use tokio::{sync::Mutex, task::JoinHandle};
+    use tokio_stream::Stream;
+    use tonic::{transport::Server, Code, Request, Response, Status};
+
+    /// gRPC service over a [`MockEmotionSensor`]
+    ///
+    /// Streams, status, calibration control, markers, pings, measurements
+    /// and the client listing behave like the daemon's; calls about models,
+    /// retention, logs and rates are `UNIMPLEMENTED`.
+    pub struct MockSensorService {
+        sensor: Arc<Mutex<MockEmotionSensor>>,
+        frames: Mutex<Option<FrameFanout<FearFrame>>>,
+        clients: ClientRegistry,
+        clock: Arc<dyn Clock>,
+    }
+
+    impl MockSensorService {
+        pub fn new(sensor: MockEmotionSensor) -> Self {
+            let clock: Arc<dyn Clock> = Arc::new(SystemClock::new());
+            Self {
+                sensor: Arc::new(Mutex::new(sensor)),
+                frames: Mutex::new(None),
+                clients: ClientRegistry::new(Arc::clone(&clock)),
+                clock,
+            }
+        }
+
+        /// The served sensor, for injecting faults and reading its phase
+        pub fn sensor(&self) -> Arc<Mutex<MockEmotionSensor>> {
+            Arc::clone(&self.sensor)
+        }
+    }
+
+    fn unimplemented<T>(call: &str) -> Result<Response<T>, Status> {
+        Err(Status::unimplemented(format!("{} is not available from the mock sensor", call)))
+    }
+
+    #[tonic::async_trait]
+    impl SensorService for MockSensorService {
+        type StreamEventsStream = Pin<Box<dyn Stream<Item = Result<SensorEvent, Status>> + Send>>;
+
+        async fn stream_events(
+            &self,
+            request: Request<StreamRequest>,
+        ) -> Result<Response<Self::StreamEventsStream>, Status> {
+            let peer = request
+                .extensions()
+                .get::<PeerInfo>()
+                .map_or_else(|| "unknown".to_string(), ToString::to_string);
+            let (frames, notices) = {
+                let mut sensor = self.sensor.lock().await;
+                let mut shared = self.frames.lock().await;
+                let fanout = match shared.as_ref().filter(|fanout| !fanout.is_closed()) {
+                    Some(fanout) => fanout.clone(),
+                    None => {
+                        let receiver = sensor.start().await.map_err(|e| {
+                            Status::new(Code::Internal, format!("Failed to start sensor: {}", e))
+                        })?;
+                        shared.insert(FrameFanout::new(receiver)).clone()
+                    }
+                };
+                let notices = StreamNotices {
+                    markers: sensor.subscribe_markers(),
+                    faults: sensor.subscribe_faults(),
+                    clock_syncs: sensor.subscribe_clock_syncs(),
+                    // The mock never swaps models or changes power profile
+                    model_swaps: broadcast::channel(1).1,
+                    power: broadcast::channel(1).1,
+                    calibration: sensor.subscribe_calibration(),
+                };
+                (fanout.subscribe(), notices)
+            };
+            let stream = create_event_stream(frames, notices, request.into_inner(), &self.clients, peer);
+            Ok(Response::new(Box::pin(stream)))
+        }
+
+        async fn get_status(
+            &self,
+            _request: Request<StatusRequest>,
+        ) -> Result<Response<StatusResponse>, Status> {
+            let sensor = self.sensor.lock().await;
+            let phase = sensor.calibration_phase();
+            Ok(Response::new(StatusResponse {
+                running: sensor.is_running(),
+                calibration: Some(CalibrationProgress {
+                    baseline: sensor.baseline().filter(|_| phase.is_calibrated()).as_ref().map(baseline_stats),
+                    ..CalibrationProgress::from(&phase)
+                }),
+                capability: crate::proto::SensorCapability::Full as i32,
+                target_fps: sensor.config().target_fps,
+                emotion_interval: 1,
+                subscribers: self.clients.len() as u32,
+                ..Default::default()
+            }))
+        }
+
+        async fn control_calibration(
+            &self,
+            request: Request<CalibrationControl>,
+        ) -> Result<Response<CalibrationResponse>, Status> {
+            let action = match request.into_inner().action {
+                Some(calibration_control::Action::StartCalibration(_)) => CalibrationAction::Start,
+                Some(calibration_control::Action::FreezeCalibration(true)) => CalibrationAction::Freeze,
+                Some(calibration_control::Action::FreezeCalibration(false)) => CalibrationAction::Unfreeze,
+                Some(calibration_control::Action::ResetCalibration(_)) => CalibrationAction::Reset,
+                None => {
+                    return Ok(Response::new(CalibrationResponse {
+                        success: false,
+                        error_message: Some("No action specified".to_string()),
+                        phase: None,
+                    }));
+                }
+            };
+            let phase = self
+                .sensor
+                .lock()
+                .await
+                .control_calibration(action)
+                .await
+                .map_err(calibration_control_status)?;
+            Ok(Response::new(CalibrationResponse {
+                success: true,
+                error_message: None,
+                phase: Some(CalibrationProgress::from(&phase)),
+            }))
+        }
+
+        async fn insert_marker(
+            &self,
+            request: Request<MarkerRequest>,
+        ) -> Result<Response<MarkerResponse>, Status> {
+            let label = request.into_inner().label;
+            if label.trim().is_empty() {
+                return Ok(Response::new(MarkerResponse {
+                    success: false,
+                    timestamp_us: 0,
+                    error_message: Some("Marker label cannot be empty".to_string()),
+                }));
+            }
+            let marker = self.sensor.lock().await.insert_marker(label);
+            Ok(Response::new(MarkerResponse {
+                success: true,
+                timestamp_us: marker.timestamp_us,
+                error_message: None,
+            }))
+        }
+
+        async fn purge_now(&self, _request: Request<PurgeRequest>) -> Result<Response<PurgeResponse>, Status> {
+            unimplemented("PurgeNow")
+        }
+
+        async fn capture_bug_report(
+            &self,
+            _request: Request<BugReportRequest>,
+        ) -> Result<Response<BugReportResponse>, Status> {
+            unimplemented("CaptureBugReport")
+        }
+
+        async fn get_model_info(
+            &self,
+            _request: Request<ModelInfoRequest>,
+        ) -> Result<Response<ModelInfoResponse>, Status> {
+            // No models behind the mock
+            Ok(Response::new(ModelInfoResponse::default()))
+        }
+
+        async fn ping(&self, request: Request<PingRequest>) -> Result<Response<PingResponse>, Status> {
+            let (monotonic, wall_us) = read_clock(self.clock.as_ref());
+            let req = request.into_inner();
+            if let Some(clock_sync) = req.clock_sync {
+                self.sensor.lock().await.record_clock_sync(SensorClockSync {
+                    timestamp_us: wall_us,
+                    estimate: clock_sync.into(),
+                });
+            }
+            Ok(Response::new(PingResponse {
+                game_time_us: req.game_time_us,
+                sensor_monotonic_us: monotonic.as_micros() as u64,
+                sensor_wall_us: wall_us,
+            }))
+        }
+
+        async fn measure_once(
+            &self,
+            request: Request<MeasureRequest>,
+        ) -> Result<Response<MeasureResponse>, Status> {
+            let timeout = match request.into_inner().timeout_ms {
+                0 => DEFAULT_MEASURE_TIMEOUT,
+                timeout_ms => Duration::from_millis(timeout_ms.into()),
+            };
+            // Wait on running frames without holding the sensor
+            let live = {
+                let sensor = self.sensor.lock().await;
+                sensor.is_running().then(|| sensor.live_frames())
+            };
+            let result = match live {
+                Some(live) => live.next(timeout).await,
+                None => self.sensor.lock().await.measure_once(timeout).await,
+            };
+            let fear_frame = result.map_err(|e| {
+                let code = match &e {
+                    SensorError::MeasureTimeout(_) => Code::DeadlineExceeded,
+                    _ => Code::Internal,
+                };
+                Status::new(code, format!("Measurement failed: {}", e))
+            })?;
+            Ok(Response::new(MeasureResponse {
+                timestamp_us: fear_frame.timestamp_us(),
+                score: Some(score_message(&fear_frame)),
+            }))
+        }
+
+        async fn swap_emotion_model(
+            &self,
+            _request: Request<SwapModelRequest>,
+        ) -> Result<Response<SwapModelResponse>, Status> {
+            unimplemented("SwapEmotionModel")
+        }
+
+        async fn list_clients(
+            &self,
+            _request: Request<ListClientsRequest>,
+        ) -> Result<Response<ListClientsResponse>, Status> {
+            Ok(Response::new(ListClientsResponse {
+                clients: self.clients.list(),
+            }))
+        }
+
+        async fn get_metrics_history(
+            &self,
+            _request: Request<MetricsHistoryRequest>,
+        ) -> Result<Response<MetricsHistoryResponse>, Status> {
+            unimplemented("GetMetricsHistory")
+        }
+
+        async fn update_config(
+            &self,
+            _request: Request<UpdateConfigRequest>,
+        ) -> Result<Response<UpdateConfigResponse>, Status> {
+            unimplemented("UpdateConfig")
+        }
+
+        async fn configure(
+            &self,
+            _request: Request<ConfigureRequest>,
+        ) -> Result<Response<ConfigureResponse>, Status> {
+            unimplemented("Configure")
+        }
+
+        async fn set_log_level(
+            &self,
+            _request: Request<SetLogLevelRequest>,
+        ) -> Result<Response<SetLogLevelResponse>, Status> {
+            unimplemented("SetLogLevel")
+        }
+
+        async fn get_logs(&self, _request: Request<GetLogsRequest>) -> Result<Response<GetLogsResponse>, Status> {
+            unimplemented("GetLogs")
+        }
+    }
+
+    /// Serve a mock sensor on a fresh loopback transport in this process
+    ///
+    /// Returns the transport to connect [`SensorClient`](crate::grpc_client::SensorClient)s
+    /// to; the server runs until the runtime shuts down.
+    pub async fn spawn_mock_daemon(config: MockSensorConfig) -> Result<SensorTransport, TransportError> {
+        let transport = SensorTransport::loopback();
+        spawn_mock_daemon_on(&transport, config).await?;
+        Ok(transport)
+    }
+
+    /// Serve a mock sensor on `transport`, e.g. a Unix socket other
+    /// processes can connect to; the server runs until the runtime shuts down
+    pub async fn spawn_mock_daemon_on(transport: &SensorTransport, config: MockSensorConfig) -> Result<(), TransportError> {
+        let service = MockSensorService::new(MockEmotionSensor::new(config));
+        spawn_mock_service_on(transport, service, std::future::pending()).await?;
+        Ok(())
+    }
+
+    /// Serve `service` on `transport` until `shutdown` completes
+    ///
+    /// The transport is listening when this returns. As with
+    /// [`serve_grpc_with_shutdown`](crate::grpc_server::serve_grpc_with_shutdown),
+    /// stop the sensor first so open streams end; the returned task finishes
+    /// once they have.
+    pub async fn spawn_mock_service_on(
+        transport: &SensorTransport,
+        service: MockSensorService,
+        shutdown: impl Future<Output = ()> + Send + 'static,
+    ) -> Result<JoinHandle<()>, TransportError> {
+        let incoming = transport.listen().await?;
+        Ok(tokio::spawn(async move {
+            if let Err(e) = Server::builder()
+                .add_service(SensorServiceServer::new(service))
+                .serve_with_incoming_shutdown(incoming, shutdown)
+                .await
+            {
+                tracing::error!("Mock sensor service failed: {}", e);
+            }
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::phases::PhaseOutcome;
+
+    fn fast() -> MockSensorConfig {
+        MockSensorConfig::default()
+            .with_target_fps(200.0)
+            .with_calibration_period(Duration::from_millis(100))
+    }
+
+    #[tokio::test]
+    async fn test_mock_calibrates_and_follows_controls() {
+        let mut sensor = MockEmotionSensor::new(fast());
+        let frames = sensor.start().await.unwrap();
+        let mut phases = sensor.phases();
+        let calibrated = phases.wait_for_calibrated(Duration::from_secs(5)).await.unwrap();
+        assert!(matches!(calibrated, PhaseOutcome::Reached(_)), "{:?}", calibrated);
+
+        let frozen = sensor.control_calibration(CalibrationAction::Freeze).await.unwrap();
+        assert!(matches!(frozen, CalibrationPhase::Frozen { .. }));
+        assert!(sensor.control_calibration(CalibrationAction::Reset).await.is_err());
+
+        let frame = sensor.measure_once(Duration::from_secs(1)).await.unwrap();
+        assert!(frame.calibrated);
+        assert!((0.0..=1.0).contains(&frame.fear_score));
+
+        sensor.stop().await.unwrap();
+        while frames.recv().await.is_ok() {}
+        assert!(!sensor.is_running());
+    }
+}
@@ -0,0 +1,144 @@
+//! Fear sequences replayed by mock sensors
+//!
+//! Mocks loop over one of these sequences, one value per frame. Building
+//! every pattern here keeps a "step" or "sine" the same shape whichever mock
+//! replays it. Values are clamped to [0.0, 1.0]. [`MockPattern`] names one
+//! in configuration.
+
+use serde::{Deserialize, Serialize};
+use spectremesh_core::{EmotionLayout, EmotionLogits};
+
+/// Samples in one period of [`sine`]
+pub const SINE_SAMPLES: usize = 100;
+
+/// Logit of the channels that are neither fear nor its counterpart in
+/// [`emotion_logits`]
+const BACKGROUND_LOGIT: f32 = -3.0;
+
+/// Low → high → low
+pub fn step() -> Vec<f32> {
+    vec![0.1, 0.2, 0.3, 0.7, 0.8, 0.9, 0.8, 0.7, 0.3, 0.2, 0.1]
+}
+
+/// [`SINE_SAMPLES`] samples of a sine wave around `center`, completing
+/// `period` cycles
+pub fn sine(center: f32, amplitude: f32, period: f32) -> Vec<f32> {
+    (0..SINE_SAMPLES)
+        .map(|i| {
+            let t = i as f32 * period / SINE_SAMPLES as f32;
+            (center + amplitude * (t * 2.0 * std::f32::consts::PI).sin()).clamp(0.0, 1.0)
+        })
+        .collect()
+}
+
+/// `len` samples of `level`, at least one
+pub fn constant(level: f32, len: usize) -> Vec<f32> {
+    vec![level.clamp(0.0, 1.0); len.max(1)]
+}
+
+/// Emotion logits for a mock frame reading `fear`
+///
+/// The fear channel holds `fear` and the last other channel (neutral in the
+/// standard layout) `1 - fear`, with the rest well below, so mock frames are
+/// about as confident as a real face's rather than near-uniform.
+pub fn emotion_logits(fear: f32, layout: EmotionLayout) -> EmotionLogits {
+    let mut logits = EmotionLogits::zeros(layout);
+    logits.fill(BACKGROUND_LOGIT);
+    let counterpart = if layout.fear_index + 1 == layout.channels { 0 } else { layout.channels - 1 };
+    logits[counterpart] = 1.0 - fear;
+    logits[layout.fear_index] = fear;
+    logits
+}
+
+/// A pattern chosen in configuration, such as [`SensorConfig::mock`](crate::SensorConfig::mock)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum MockPattern {
+    /// [`step`]
+    Step,
+    /// [`sine`]
+    Sine { center: f32, amplitude: f32, period: f32 },
+    /// [`constant`]
+    Constant { level: f32 },
+    /// These samples, as given
+    Sequence { samples: Vec<f32> },
+}
+
+impl Default for MockPattern {
+    fn default() -> Self {
+        Self::Sine { center: 0.5, amplitude: 0.3, period: 1.0 }
+    }
+}
+
+impl MockPattern {
+    /// One loop of the pattern
+    pub fn samples(&self) -> Vec<f32> {
+        match self {
+            Self::Step => step(),
+            Self::Sine { center, amplitude, period } => sine(*center, *amplitude, *period),
+            Self::Constant { level } => constant(*level, 1),
+            Self::Sequence { samples } => samples.clone(),
+        }
+    }
+
+    /// Validate the pattern
+    pub fn validate(&self) -> Result<(), String> {
+        match self {
+            Self::Sequence { samples } if samples.is_empty() => Err("Mock sequence cannot be empty".to_string()),
+            _ if self.samples().iter().any(|fear| !fear.is_finite()) => {
+                Err("Mock pattern values must be finite".to_string())
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_patterns_stay_in_range() {
+        let step = step();
+        assert_eq!(step.first(), step.last());
+        assert!(step.iter().any(|&fear| fear > 0.8));
+
+        let sine = sine(0.5, 0.8, 2.0);
+        assert_eq!(sine.len(), SINE_SAMPLES);
+        assert!(sine.iter().all(|fear| (0.0..=1.0).contains(fear)));
+        assert_eq!(sine[0], 0.5);
+        assert_eq!(sine[12], 1.0);
+
+        assert_eq!(constant(0.2, 3), [0.2; 3]);
+        assert_eq!(constant(1.5, 0), [1.0]);
+    }
+
+    #[test]
+    fn test_configured_patterns() {
+        assert_eq!(MockPattern::Step.samples(), step());
+        assert_eq!(MockPattern::default().samples(), sine(0.5, 0.3, 1.0));
+        assert_eq!(MockPattern::Constant { level: 0.4 }.samples(), [0.4]);
+
+        let pattern: MockPattern = serde_json::from_str(r#"{"kind": "sine", "center": 0.5, "amplitude": 0.2, "period": 2.0}"#).unwrap();
+        assert_eq!(pattern, MockPattern::Sine { center: 0.5, amplitude: 0.2, period: 2.0 });
+
+        assert!(MockPattern::Sequence { samples: vec![] }.validate().is_err());
+        assert!(MockPattern::Sequence { samples: vec![0.2, f32::NAN] }.validate().is_err());
+        assert!(MockPattern::Sine { center: 0.5, amplitude: 0.2, period: 2.0 }.validate().is_ok());
+    }
+
+    #[test]
+    fn test_mock_logits_are_confident() {
+        use spectremesh_core::math::compute_confidence;
+
+        for fear in step() {
+            let logits = emotion_logits(fear, EmotionLayout::STANDARD);
+            assert_eq!(logits.fear(), fear);
+            assert!(compute_confidence(1.0, &logits) > 0.6, "{} for fear {}", compute_confidence(1.0, &logits), fear);
+        }
+
+        // The counterpart moves out of the way of a last-channel fear
+        let logits = emotion_logits(0.8, EmotionLayout::new(5, 4).unwrap());
+        assert_eq!(logits.as_slice(), [0.2, -3.0, -3.0, -3.0, 0.8]);
+    }
+}
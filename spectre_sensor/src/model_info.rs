@@ -0,0 +1,219 @@
+//! Provenance and license metadata for neural models
+//!
+//! Installers show attribution for every model the sensor runs. Embedded
+//! models carry compiled-in metadata such as [`crate::YUNET_MODEL_INFO`].
+//! External models are described by the caller, and their file is hashed to
+//! check the description. Without a description they get a hash-only record
+//! that names the file.
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::borrow::Cow;
+use std::fmt;
+use std::fs::File;
+use std::io::{self, Read};
+use std::path::Path;
+use thiserror::Error;
+
+/// Value of metadata fields nobody supplied
+pub const UNKNOWN: &str = "unknown";
+
+/// Model metadata errors
+#[derive(Debug, Error)]
+pub enum ModelInfoError {
+    #[error("Failed to hash model {path}: {message}")]
+    Io { path: String, message: String },
+
+    #[error("Model {name} has SHA-256 {actual}, but its metadata describes {expected}")]
+    HashMismatch {
+        name: String,
+        expected: String,
+        actual: String,
+    },
+}
+
+/// Name, version, origin and license of a model, tied to its bytes by hash
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ModelInfo {
+    pub name: Cow<'static, str>,
+    pub version: Cow<'static, str>,
+    /// Where the model was obtained
+    pub source_url: Cow<'static, str>,
+    /// SPDX license identifier
+    pub license: Cow<'static, str>,
+    /// Lowercase hex SHA-256 of the model file
+    pub sha256: Cow<'static, str>,
+    /// Compiled into the binary rather than loaded from disk
+    pub embedded: bool,
+}
+
+impl ModelInfo {
+    /// Record for a model known only by name and hash
+    pub fn hash_only(name: impl Into<String>, sha256: impl Into<String>) -> Self {
+        Self {
+            name: Cow::Owned(name.into()),
+            version: Cow::Borrowed(UNKNOWN),
+            source_url: Cow::Borrowed(UNKNOWN),
+            license: Cow::Borrowed(UNKNOWN),
+            sha256: Cow::Owned(sha256.into()),
+            embedded: false,
+        }
+    }
+
+    /// Metadata for the model file at `path`
+    ///
+    /// Supplied metadata must match the file's hash; an empty `sha256` is
+    /// filled in. Without metadata, a hash-only record named after the file is
+    /// returned.
+    pub fn for_file(path: impl AsRef<Path>, info: Option<ModelInfo>) -> Result<Self, ModelInfoError> {
+        let path = path.as_ref();
+        let sha256 = sha256_file(path).map_err(|e| ModelInfoError::Io {
+            path: path.display().to_string(),
+            message: e.to_string(),
+        })?;
+
+        let Some(mut info) = info else {
+            let name = path
+                .file_name()
+                .map_or_else(|| path.display().to_string(), |name| name.to_string_lossy().into_owned());
+            return Ok(Self::hash_only(name, sha256));
+        };
+
+        if info.sha256.is_empty() {
+            info.sha256 = Cow::Owned(sha256);
+        } else if !info.sha256.eq_ignore_ascii_case(&sha256) {
+            return Err(ModelInfoError::HashMismatch {
+                name: info.name.into_owned(),
+                expected: info.sha256.into_owned(),
+                actual: sha256,
+            });
+        }
+        info.embedded = false;
+        Ok(info)
+    }
+
+    /// Whether anything beyond the name and hash is known
+    pub fn has_provenance(&self) -> bool {
+        self.license != UNKNOWN || self.source_url != UNKNOWN
+    }
+}
+
+impl fmt::Display for ModelInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let short_hash = self.sha256.get(..12).unwrap_or(&self.sha256);
+        write!(
+            f,
+            "{} {} ({} license, sha256 {}, {}) from {}",
+            self.name,
+            self.version,
+            self.license,
+            short_hash,
+            if self.embedded { "embedded" } else { "external" },
+            self.source_url
+        )
+    }
+}
+
+/// Lowercase hex SHA-256 of `bytes`
+pub fn sha256_hex(bytes: &[u8]) -> String {
+    hex(&Sha256::digest(bytes))
+}
+
+/// Lowercase hex SHA-256 of a file, read in chunks
+pub fn sha256_file(path: impl AsRef<Path>) -> io::Result<String> {
+    let mut file = File::open(path)?;
+    let mut hasher = Sha256::new();
+    let mut buffer = vec![0u8; 64 * 1024];
+    loop {
+        let read = file.read(&mut buffer)?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+    }
+    Ok(hex(&hasher.finalize()))
+}
+
+fn hex(digest: &[u8]) -> String {
+    digest.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn model_file(name: &str, bytes: &[u8]) -> std::path::PathBuf {
+        let dir = std::env::temp_dir().join(format!("spectre_model_info_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(name);
+        std::fs::write(&path, bytes).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_sha256_hex() {
+        assert_eq!(
+            sha256_hex(b"abc"),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+        let path = model_file("abc.onnx", b"abc");
+        assert_eq!(sha256_file(&path).unwrap(), sha256_hex(b"abc"));
+    }
+
+    #[test]
+    fn test_external_model_without_metadata_gets_hash_only_record() {
+        let path = model_file("face_emotion.onnx", b"not really a model");
+        let info = ModelInfo::for_file(&path, None).unwrap();
+
+        assert_eq!(info.name, "face_emotion.onnx");
+        assert_eq!(info.sha256, sha256_hex(b"not really a model"));
+        assert_eq!(info.license, UNKNOWN);
+        assert_eq!(info.version, UNKNOWN);
+        assert!(!info.embedded);
+        assert!(!info.has_provenance());
+    }
+
+    #[test]
+    fn test_external_model_metadata_is_checked_against_file() {
+        let path = model_file("emotion.onnx", b"emotion model");
+        let described = ModelInfo {
+            name: "FER+".into(),
+            version: "8".into(),
+            source_url: "https://github.com/onnx/models".into(),
+            license: "MIT".into(),
+            sha256: "".into(),
+            embedded: true,
+        };
+
+        // A missing hash is filled in; embedded never applies to a file
+        let info = ModelInfo::for_file(&path, Some(described.clone())).unwrap();
+        assert_eq!(info.sha256, sha256_hex(b"emotion model"));
+        assert_eq!(info.license, "MIT");
+        assert!(!info.embedded);
+        assert!(info.has_provenance());
+
+        // Metadata written for other bytes is rejected
+        let stale = ModelInfo {
+            sha256: sha256_hex(b"older model").into(),
+            ..described
+        };
+        assert!(matches!(
+            ModelInfo::for_file(&path, Some(stale)),
+            Err(ModelInfoError::HashMismatch { .. })
+        ));
+
+        assert!(matches!(
+            ModelInfo::for_file(path.with_file_name("missing.onnx"), None),
+            Err(ModelInfoError::Io { .. })
+        ));
+    }
+
+    #[test]
+    fn test_display() {
+        let info = ModelInfo::hash_only("custom.onnx", sha256_hex(b"abc"));
+        assert_eq!(
+            info.to_string(),
+            "custom.onnx unknown (unknown license, sha256 ba7816bf8f01, external) from unknown"
+        );
+    }
+}
@@ -0,0 +1,280 @@
+//! Where the emotion recognition model comes from
+//!
+//! YuNet is small enough to compile into the binary, but no emotion model is
+//! bundled with the sources, so the sensor either reads one from disk or
+//! downloads it on first run. A download is pinned to a SHA-256: the file is
+//! fetched once into a cache directory (the platform data directory unless
+//! configured), named by its hash, and checked both after downloading and
+//! whenever the cached copy is reused. A file with the wrong hash is never
+//! handed to ONNX Runtime.
+
+use crate::model_info::sha256_file;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs::{self, File};
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+
+/// Emotion model file used when no provider is configured
+pub const DEFAULT_EMOTION_MODEL_PATH: &str = "assets/models/face_emotion.onnx";
+
+/// Model provider errors
+#[derive(Debug, Error)]
+pub enum ModelProviderError {
+    #[error("Downloaded model from {url} has SHA-256 {actual}, expected {expected}")]
+    ChecksumMismatch {
+        url: String,
+        expected: String,
+        actual: String,
+    },
+
+    #[error("Failed to download model from {url}: {reason}")]
+    Download { url: String, reason: String },
+
+    #[error("Model download needs the `model-download` feature: {0}")]
+    DownloadUnavailable(String),
+
+    #[error("No platform data directory for the model cache; configure one")]
+    NoCacheDir,
+
+    #[error("Model cache I/O failed at {path}: {message}")]
+    Io { path: String, message: String },
+}
+
+impl ModelProviderError {
+    fn io(path: &Path, error: io::Error) -> Self {
+        Self::Io {
+            path: path.display().to_string(),
+            message: error.to_string(),
+        }
+    }
+}
+
+/// Source of the emotion recognition model
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ModelProvider {
+    /// An ONNX file on disk
+    File { path: PathBuf },
+    /// Fetched from `url` on first run and cached by hash
+    Download {
+        /// `http://`, `https://` or `file://` URL
+        url: String,
+        /// Lowercase hex SHA-256 the download must have
+        sha256: String,
+        /// Cache directory; the platform data directory when unset
+        #[serde(default)]
+        cache_dir: Option<PathBuf>,
+    },
+}
+
+impl ModelProvider {
+    pub fn file(path: impl Into<PathBuf>) -> Self {
+        Self::File { path: path.into() }
+    }
+
+    /// Download from `url` into the platform data directory, pinned to `sha256`
+    pub fn download(url: impl Into<String>, sha256: impl Into<String>) -> Self {
+        Self::Download {
+            url: url.into(),
+            sha256: sha256.into().to_ascii_lowercase(),
+            cache_dir: None,
+        }
+    }
+
+    /// Cache downloads in `dir`; has no effect on a file provider
+    pub fn with_cache_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        if let Self::Download { cache_dir, .. } = &mut self {
+            *cache_dir = Some(dir.into());
+        }
+        self
+    }
+
+    /// Default download cache, under the platform data directory
+    pub fn default_cache_dir() -> Option<PathBuf> {
+        dirs::data_dir().map(|dir| dir.join("spectremesh").join("models"))
+    }
+
+    /// Validate the provider
+    pub fn validate(&self) -> Result<(), String> {
+        match self {
+            Self::File { path } if path.as_os_str().is_empty() => {
+                Err("Emotion model path cannot be empty".to_string())
+            }
+            Self::File { .. } => Ok(()),
+            Self::Download { url, sha256, cache_dir } => {
+                if url.trim().is_empty() {
+                    return Err("Emotion model URL cannot be empty".to_string());
+                }
+                if sha256.len() != 64 || !sha256.chars().all(|c| c.is_ascii_hexdigit()) {
+                    return Err(format!("Emotion model SHA-256 must be 64 hex digits, got {:?}", sha256));
+                }
+                if cache_dir.as_ref().is_some_and(|dir| dir.as_os_str().is_empty()) {
+                    return Err("Emotion model cache directory cannot be empty".to_string());
+                }
+                Ok(())
+            }
+        }
+    }
+
+    /// Path of the model file, downloading it first if it isn't cached yet
+    ///
+    /// Blocks for the whole download; call it from a blocking thread.
+    pub fn resolve(&self) -> Result<PathBuf, ModelProviderError> {
+        let (url, sha256, cache_dir) = match self {
+            Self::File { path } => return Ok(path.clone()),
+            Self::Download { url, sha256, cache_dir } => (url, sha256.to_ascii_lowercase(), cache_dir),
+        };
+        let dir = match cache_dir {
+            Some(dir) => dir.clone(),
+            None => Self::default_cache_dir().ok_or(ModelProviderError::NoCacheDir)?,
+        };
+        let path = dir.join(format!("{}.onnx", sha256));
+
+        // A cached copy is trusted only while its hash still matches
+        if path.is_file() {
+            match sha256_file(&path) {
+                Ok(actual) if actual == sha256 => return Ok(path),
+                Ok(actual) => tracing::warn!("Cached model {} has SHA-256 {}, downloading again", path.display(), actual),
+                Err(e) => tracing::warn!("Failed to hash cached model {}: {}, downloading again", path.display(), e),
+            }
+        }
+
+        fs::create_dir_all(&dir).map_err(|e| ModelProviderError::io(&dir, e))?;
+        tracing::info!("Downloading emotion model from {}", url);
+        let partial = dir.join(format!("{}.onnx.part", sha256));
+        let actual = match download(url, &partial) {
+            Ok(actual) => actual,
+            Err(e) => {
+                let _ = fs::remove_file(&partial);
+                return Err(e);
+            }
+        };
+        if actual != sha256 {
+            let _ = fs::remove_file(&partial);
+            return Err(ModelProviderError::ChecksumMismatch {
+                url: url.clone(),
+                expected: sha256,
+                actual,
+            });
+        }
+        fs::rename(&partial, &path).map_err(|e| ModelProviderError::io(&path, e))?;
+        Ok(path)
+    }
+}
+
+/// Copy `url` to `target`, returning the SHA-256 of what was written
+fn download(url: &str, target: &Path) -> Result<String, ModelProviderError> {
+    let failed = |reason: String| ModelProviderError::Download { url: url.to_string(), reason };
+    let mut reader: Box<dyn Read> = match url.strip_prefix("file://") {
+        Some(path) => Box::new(File::open(path).map_err(|e| failed(e.to_string()))?),
+        None => open_remote(url)?,
+    };
+
+    let mut file = File::create(target).map_err(|e| ModelProviderError::io(target, e))?;
+    let mut hasher = Sha256::new();
+    let mut buffer = vec![0u8; 64 * 1024];
+    loop {
+        let read = reader.read(&mut buffer).map_err(|e| failed(e.to_string()))?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+        file.write_all(&buffer[..read]).map_err(|e| ModelProviderError::io(target, e))?;
+    }
+    file.sync_all().map_err(|e| ModelProviderError::io(target, e))?;
+    Ok(hasher.finalize().iter().map(|byte| format!("{:02x}", byte)).collect())
+}
+
+#[cfg(feature = "model-download")]
+fn open_remote(url: &str) -> Result<Box<dyn Read>, ModelProviderError> {
+    use ureq::tls::{TlsConfig, TlsProvider};
+
+    let agent: ureq::Agent = ureq::Agent::config_builder()
+        .tls_config(TlsConfig::builder().provider(TlsProvider::NativeTls).build())
+        .build()
+        .into();
+    let response = agent.get(url).call().map_err(|e| ModelProviderError::Download {
+        url: url.to_string(),
+        reason: e.to_string(),
+    })?;
+    Ok(Box::new(response.into_body().into_reader()))
+}
+
+#[cfg(not(feature = "model-download"))]
+fn open_remote(url: &str) -> Result<Box<dyn Read>, ModelProviderError> {
+    Err(ModelProviderError::DownloadUnavailable(url.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model_info::sha256_hex;
+
+    const MODEL: &[u8] = b"pretend this is an emotion model";
+
+    fn scratch(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("spectre_model_provider_{}_{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let source = dir.join("source.onnx");
+        fs::write(&source, MODEL).unwrap();
+        dir
+    }
+
+    fn provider(dir: &Path, sha256: &str) -> ModelProvider {
+        let url = format!("file://{}", dir.join("source.onnx").display());
+        ModelProvider::download(url, sha256).with_cache_dir(dir.join("cache"))
+    }
+
+    #[test]
+    fn test_download_is_cached_by_hash() {
+        let dir = scratch("cached");
+        let sha256 = sha256_hex(MODEL);
+        let provider = provider(&dir, &sha256);
+
+        let path = provider.resolve().unwrap();
+        assert_eq!(path, dir.join("cache").join(format!("{}.onnx", sha256)));
+        assert_eq!(fs::read(&path).unwrap(), MODEL);
+
+        // The second run reuses the cache even with the source gone
+        fs::remove_file(dir.join("source.onnx")).unwrap();
+        assert_eq!(provider.resolve().unwrap(), path);
+
+        // A tampered cache is not trusted
+        fs::write(&path, b"tampered").unwrap();
+        assert!(matches!(provider.resolve(), Err(ModelProviderError::Download { .. })));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_checksum_mismatch_is_rejected() {
+        let dir = scratch("mismatch");
+        let expected = sha256_hex(b"a different model");
+
+        match provider(&dir, &expected).resolve() {
+            Err(ModelProviderError::ChecksumMismatch { expected: wanted, actual, .. }) => {
+                assert_eq!(wanted, expected);
+                assert_eq!(actual, sha256_hex(MODEL));
+            }
+            other => panic!("Expected a checksum mismatch, got {:?}", other),
+        }
+        // Nothing is left behind for the next run to pick up
+        assert_eq!(fs::read_dir(dir.join("cache")).unwrap().count(), 0);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_validate() {
+        assert!(ModelProvider::file(DEFAULT_EMOTION_MODEL_PATH).validate().is_ok());
+        assert!(ModelProvider::file("").validate().is_err());
+        assert!(ModelProvider::download("https://example.com/model.onnx", sha256_hex(MODEL)).validate().is_ok());
+        assert!(ModelProvider::download("", sha256_hex(MODEL)).validate().is_err());
+        assert!(ModelProvider::download("https://example.com/model.onnx", "abc").validate().is_err());
+
+        let provider: ModelProvider =
+            serde_json::from_str(r#"{"kind":"download","url":"file:///m.onnx","sha256":"00"}"#).unwrap();
+        assert_eq!(provider, ModelProvider::download("file:///m.onnx", "00"));
+    }
+}
@@ -0,0 +1,381 @@
+//! Replacing the emotion model while the sensor runs
+//!
+//! A new model is loaded and validated away from the processing loop: its
+//! session's input and output must fit the pipeline, and a smoke inference
+//! on a fixture face crop must produce finite logits, as many as the
+//! configured emotion layout has channels. Only then is it handed to the
+//! loop through a [`ModelSwapper`], and the loop installs it between two
+//! frames, so streams see no gap. A model that fails validation
+//! never reaches the loop and the running one carries on.
+//!
+//! Logits from another model sit on another scale, so by default the swap
+//! restarts calibration; frames keep flowing, flagged uncalibrated, until
+//! the new baseline settles. Every swap is broadcast as a [`ModelSwapped`]
+//! notice carrying both model hashes.
+
+use crate::{
+    calibrator::AdaptiveCalibrator,
+    degradation::{EmotionBackend, EmotionBackendFactory, EmotionPipeline},
+    emotion_input::EmotionInputError,
+    model_info::ModelInfo,
+    normalization::{fixture_faces, InputNormalization, INPUT_SIZE},
+};
+use opencv::{
+    core::{Mat, Scalar, Vec3b, CV_8UC3},
+    prelude::*,
+};
+use spectremesh_core::EmotionLogits;
+use std::fmt;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+use thiserror::Error;
+use tokio::sync::{broadcast, mpsc, oneshot};
+
+/// Where a replacement emotion model comes from
+#[derive(Clone)]
+pub enum ModelSource {
+    /// ONNX file on the sensor's host
+    Path(PathBuf),
+    /// ONNX model sent by the client
+    Bytes(Arc<[u8]>),
+}
+
+impl fmt::Display for ModelSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ModelSource::Path(path) => write!(f, "{}", path.display()),
+            ModelSource::Bytes(bytes) => write!(f, "{} uploaded bytes", bytes.len()),
+        }
+    }
+}
+
+impl fmt::Debug for ModelSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "ModelSource({})", self)
+    }
+}
+
+/// Why a replacement model was not installed
+#[derive(Debug, Error)]
+pub enum ModelSwapError {
+    #[error("Failed to load model: {0}")]
+    Load(String),
+
+    #[error("{0}")]
+    Tensors(#[from] EmotionInputError),
+
+    #[error("Smoke inference on a fixture face failed: {0}")]
+    SmokeInference(String),
+
+    #[error("Smoke inference produced non-finite logits {0:?}")]
+    NonFiniteLogits(Vec<f32>),
+
+    #[error("Sensor stopped before the new model was installed")]
+    Interrupted,
+}
+
+/// Notice that the emotion model was replaced
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ModelSwapped {
+    /// SHA-256 of the model that was running
+    pub old_sha256: String,
+    /// SHA-256 of the model running now
+    pub new_sha256: String,
+    /// Whether calibration restarted with the new model
+    pub calibration_reset: bool,
+    /// When the new model took over, in microseconds since Unix epoch
+    pub timestamp_us: u64,
+}
+
+impl ModelSwapped {
+    /// Notice stamped with the current time
+    pub fn new(old_sha256: impl Into<String>, new_sha256: impl Into<String>, calibration_reset: bool) -> Self {
+        Self {
+            old_sha256: old_sha256.into(),
+            new_sha256: new_sha256.into(),
+            calibration_reset,
+            timestamp_us: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_micros() as u64,
+        }
+    }
+}
+
+/// The first fixture face as a BGR crop, like those cut from camera frames
+pub fn fixture_crop() -> opencv::Result<Mat> {
+    let size = INPUT_SIZE as i32;
+    let mut crop = Mat::new_rows_cols_with_default(size, size, CV_8UC3, Scalar::all(0.0))?;
+    let face = &fixture_faces()[0];
+    for (pixel, value) in crop.data_typed_mut::<Vec3b>()?.iter_mut().zip(face) {
+        *pixel = Vec3b::all((value * 255.0).round() as u8);
+    }
+    Ok(crop)
+}
+
+/// Run the fixture crop through `backend`, requiring finite logits
+pub fn smoke_test(backend: &mut dyn EmotionBackend) -> Result<EmotionLogits, ModelSwapError> {
+    let crop = fixture_crop().map_err(|e| ModelSwapError::SmokeInference(e.to_string()))?;
+    let logits = backend
+        .infer(&crop)
+        .map_err(|e| ModelSwapError::SmokeInference(e.to_string()))?;
+    if logits.iter().any(|logit| !logit.is_finite()) {
+        return Err(ModelSwapError::NonFiniteLogits(logits.into_vec()));
+    }
+    Ok(logits)
+}
+
+/// A validated emotion model ready to replace the running one
+pub struct PreparedModel {
+    pub backend: Box<dyn EmotionBackend>,
+    /// Rebuilds the new model for the degradation ladder
+    pub rebuild: EmotionBackendFactory,
+    pub info: ModelInfo,
+    pub normalization: InputNormalization,
+}
+
+struct SwapRequest {
+    model: PreparedModel,
+    old_sha256: String,
+    reset_calibration: bool,
+    installed: oneshot::Sender<ModelSwapped>,
+}
+
+/// Hands prepared models to a running processing loop
+#[derive(Clone)]
+pub struct ModelSwapper {
+    requests: mpsc::UnboundedSender<SwapRequest>,
+}
+
+/// The processing loop's end of a [`ModelSwapper`]
+pub struct ModelSwapInbox {
+    requests: mpsc::UnboundedReceiver<SwapRequest>,
+    notices: broadcast::Sender<ModelSwapped>,
+}
+
+/// Connect a swapper to the inbox of a processing loop, which announces
+/// installed models on `notices`
+pub fn swap_channel(notices: broadcast::Sender<ModelSwapped>) -> (ModelSwapper, ModelSwapInbox) {
+    let (requests, receiver) = mpsc::unbounded_channel();
+    (ModelSwapper { requests }, ModelSwapInbox { requests: receiver, notices })
+}
+
+impl ModelSwapper {
+    /// Queue `model` to replace the model hashed `old_sha256`, and wait until the loop installs it
+    pub async fn swap(
+        &self,
+        model: PreparedModel,
+        old_sha256: impl Into<String>,
+        reset_calibration: bool,
+    ) -> Result<ModelSwapped, ModelSwapError> {
+        let (installed, done) = oneshot::channel();
+        self.requests
+            .send(SwapRequest { model, old_sha256: old_sha256.into(), reset_calibration, installed })
+            .map_err(|_| ModelSwapError::Interrupted)?;
+        done.await.map_err(|_| ModelSwapError::Interrupted)
+    }
+}
+
+impl ModelSwapInbox {
+    /// Install a queued model, if any
+    ///
+    /// Called by the processing loop between frames. The degradation ladder
+    /// starts afresh with the new backend, and calibration restarts if the
+    /// swap asked for it.
+    pub fn install_pending(
+        &mut self,
+        emotion: &mut EmotionPipeline,
+        calibrator: &mut AdaptiveCalibrator,
+        normalization: &mut InputNormalization,
+    ) -> Option<ModelSwapped> {
+        let SwapRequest { model, old_sha256, reset_calibration, installed } = self.requests.try_recv().ok()?;
+
+        emotion.swap_backend(model.backend, model.rebuild);
+        *normalization = model.normalization;
+        if reset_calibration {
+            calibrator.reset();
+        }
+
+        let swapped = ModelSwapped::new(old_sha256, model.info.sha256.clone(), reset_calibration);
+        tracing::info!(
+            "Emotion model swapped to {}{}",
+            model.info,
+            if reset_calibration { ", recalibrating" } else { "" }
+        );
+        // No subscribers just means nobody is streaming right now
+        let _ = self.notices.send(swapped.clone());
+        let _ = installed.send(swapped.clone());
+        Some(swapped)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        degradation::{DegradationConfig, EmotionOutcome},
+        sensor::SensorError,
+    };
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::time::Duration;
+
+    const OLD_LOGITS: [f32; 7] = [0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 0.0];
+    const NEW_LOGITS: [f32; 7] = [0.0, 0.0, 3.0, 0.0, 0.0, 0.0, 0.0];
+
+    /// Answers every face with the same logits
+    struct StubBackend([f32; 7]);
+
+    impl EmotionBackend for StubBackend {
+        fn infer(&mut self, _face: &Mat) -> Result<EmotionLogits, SensorError> {
+            Ok(self.0.into())
+        }
+    }
+
+    /// Fails every inference
+    struct BrokenBackend;
+
+    impl EmotionBackend for BrokenBackend {
+        fn infer(&mut self, _face: &Mat) -> Result<EmotionLogits, SensorError> {
+            Err(SensorError::FrameProcessing("Missing output tensor".to_string()))
+        }
+    }
+
+    fn pipeline(logits: [f32; 7]) -> EmotionPipeline {
+        let (faults, _) = broadcast::channel(16);
+        EmotionPipeline::new(
+            Box::new(StubBackend(logits)),
+            Box::new(move || Ok(Box::new(StubBackend(logits)) as Box<dyn EmotionBackend>)),
+            DegradationConfig::default(),
+            faults,
+        )
+    }
+
+    fn prepared(logits: [f32; 7]) -> PreparedModel {
+        PreparedModel {
+            backend: Box::new(StubBackend(logits)),
+            rebuild: Box::new(move || Ok(Box::new(StubBackend(logits)) as Box<dyn EmotionBackend>)),
+            info: ModelInfo::hash_only("new.onnx", "bbbb"),
+            normalization: InputNormalization::MinusOneToOne,
+        }
+    }
+
+    fn calibrated() -> AdaptiveCalibrator {
+        let mut calibrator = AdaptiveCalibrator::new(Duration::ZERO, 0.05);
+        for _ in 0..30 {
+            calibrator.add_logits(&OLD_LOGITS).unwrap();
+        }
+        assert!(calibrator.is_calibrated());
+        calibrator
+    }
+
+    #[tokio::test]
+    async fn test_swap_leaves_no_frame_gap() {
+        let (notices, mut notified) = broadcast::channel(4);
+        let (swapper, mut inbox) = swap_channel(notices);
+        let stop = Arc::new(AtomicBool::new(false));
+
+        // Processing loop: install pending models between frames, as the sensor does
+        let processing = {
+            let stop = Arc::clone(&stop);
+            std::thread::spawn(move || {
+                let mut emotion = pipeline(OLD_LOGITS);
+                let mut calibrator = calibrated();
+                let mut normalization = InputNormalization::ZeroToOne;
+                let face = Mat::default();
+                let mut outcomes = Vec::new();
+                let mut installed_at = None;
+                while !stop.load(Ordering::SeqCst) || installed_at.is_none() {
+                    if inbox.install_pending(&mut emotion, &mut calibrator, &mut normalization).is_some() {
+                        installed_at = Some(outcomes.len());
+                    }
+                    outcomes.push(emotion.infer(&face).ok());
+                    std::thread::sleep(Duration::from_millis(1));
+                }
+                (outcomes, installed_at.unwrap(), normalization)
+            })
+        };
+
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        let swapped = swapper.swap(prepared(NEW_LOGITS), "aaaa", true).await.unwrap();
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        stop.store(true, Ordering::SeqCst);
+        let (outcomes, installed_at, normalization) = processing.join().unwrap();
+
+        // Every frame was scored: the old model's up to the swap, the new one's after
+        assert!(installed_at > 0 && installed_at < outcomes.len());
+        let expected: Vec<_> = (0..outcomes.len())
+            .map(|frame| Some(EmotionOutcome::Live(if frame < installed_at { OLD_LOGITS } else { NEW_LOGITS }.into())))
+            .collect();
+        assert_eq!(outcomes, expected);
+        assert_eq!(normalization, InputNormalization::MinusOneToOne);
+
+        assert_eq!(swapped.old_sha256, "aaaa");
+        assert_eq!(swapped.new_sha256, "bbbb");
+        assert_eq!(notified.try_recv().unwrap(), swapped);
+        assert!(notified.try_recv().is_err());
+    }
+
+    /// Swap through a loop with a calibrated calibrator, returning the calibrator afterwards
+    async fn swap_with(reset_calibration: bool) -> (AdaptiveCalibrator, ModelSwapped) {
+        let (notices, _) = broadcast::channel(4);
+        let (swapper, mut inbox) = swap_channel(notices);
+        let swap = tokio::spawn(async move { swapper.swap(prepared(NEW_LOGITS), "aaaa", reset_calibration).await });
+
+        let mut emotion = pipeline(OLD_LOGITS);
+        let mut calibrator = calibrated();
+        let mut normalization = InputNormalization::ZeroToOne;
+        let installed = loop {
+            if let Some(swapped) = inbox.install_pending(&mut emotion, &mut calibrator, &mut normalization) {
+                break swapped;
+            }
+            tokio::task::yield_now().await;
+        };
+        assert_eq!(swap.await.unwrap().unwrap(), installed);
+        (calibrator, installed)
+    }
+
+    #[tokio::test]
+    async fn test_calibration_reset_follows_flag() {
+        let (calibrator, swapped) = swap_with(true).await;
+        assert!(swapped.calibration_reset);
+        assert!(!calibrator.is_calibrated());
+        assert_eq!(calibrator.baseline_stats().sample_count, 0);
+
+        let (calibrator, swapped) = swap_with(false).await;
+        assert!(!swapped.calibration_reset);
+        assert!(calibrator.is_calibrated());
+        assert_eq!(calibrator.baseline_stats().sample_count, 30);
+    }
+
+    #[tokio::test]
+    async fn test_failed_validation_keeps_old_model() {
+        let error = smoke_test(&mut BrokenBackend).unwrap_err();
+        assert!(matches!(error, ModelSwapError::SmokeInference(_)));
+        assert!(error.to_string().contains("Missing output tensor"), "{}", error);
+
+        let error = smoke_test(&mut StubBackend([f32::NAN; 7])).unwrap_err();
+        assert!(matches!(error, ModelSwapError::NonFiniteLogits(_)));
+        assert_eq!(smoke_test(&mut StubBackend(NEW_LOGITS)).unwrap(), NEW_LOGITS);
+
+        // A rejected model is never queued, so the loop keeps scoring with the old one
+        let (notices, mut notified) = broadcast::channel(4);
+        let (_swapper, mut inbox) = swap_channel(notices);
+        let mut emotion = pipeline(OLD_LOGITS);
+        let mut calibrator = calibrated();
+        let mut normalization = InputNormalization::ZeroToOne;
+        assert!(inbox.install_pending(&mut emotion, &mut calibrator, &mut normalization).is_none());
+        assert_eq!(emotion.infer(&Mat::default()).unwrap(), EmotionOutcome::Live(OLD_LOGITS.into()));
+        assert!(calibrator.is_calibrated());
+        assert!(notified.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_swap_fails_once_the_loop_is_gone() {
+        let (notices, _) = broadcast::channel(4);
+        let (swapper, inbox) = swap_channel(notices);
+        drop(inbox);
+        let result = swapper.swap(prepared(NEW_LOGITS), "aaaa", true).await;
+        assert!(matches!(result, Err(ModelSwapError::Interrupted)));
+    }
+}
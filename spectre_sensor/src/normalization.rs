@@ -0,0 +1,265 @@
+//! Emotion model input normalization conventions
+//!
+//! Face crops are converted to pixels in [0, 1]; models trained
+//! with another convention expect those values shifted or scaled first.
+//! Feeding the wrong convention does not fail, it biases every logit, so the
+//! convention can be configured explicitly or chosen with
+//! [`InputNormalization::Auto`]: a model that names its convention in its
+//! metadata (under [`MODEL_METADATA_KEY`]) gets it, and any other model is
+//! run on synthetic fixture faces under each [`CANDIDATES`] entry, keeping
+//! the convention whose outputs are the most decisive (lowest entropy).
+
+use spectremesh_core::math;
+use std::fmt;
+
+pub use spectremesh_core::normalization::{InputNormalization, IMAGENET_GRAY};
+
+/// Custom model metadata key naming the input convention, in
+/// [`InputNormalization`]'s string form
+pub const MODEL_METADATA_KEY: &str = "input_normalization";
+
+/// Side of the square grayscale fixture faces, and of the bundled emotion
+/// model's input
+pub const INPUT_SIZE: usize = 48;
+
+/// Conventions tried by the self-check, preferred first on ties
+pub const CANDIDATES: [InputNormalization; 3] = [
+    InputNormalization::ZeroToOne,
+    InputNormalization::MinusOneToOne,
+    IMAGENET_GRAY,
+];
+
+/// Mean entropies closer than this count as a tie
+const ENTROPY_TIE: f32 = 1e-3;
+
+/// Pick a concrete convention for `normalization`
+///
+/// Configured conventions are kept. `Auto` uses the model's metadata value
+/// if it parses, and otherwise runs [`self_check`] with `infer`, which maps
+/// a normalized [`INPUT_SIZE`]² buffer to emotion logits.
+pub fn resolve_normalization<L: AsRef<[f32]>, E>(
+    normalization: InputNormalization,
+    metadata: Option<&str>,
+    infer: impl FnMut(&[f32]) -> Result<L, E>,
+) -> Result<Resolved, E> {
+    if normalization != InputNormalization::Auto {
+        return Ok(Resolved { normalization, source: NormalizationSource::Configured });
+    }
+
+    if let Some(value) = metadata {
+        match value.parse::<InputNormalization>() {
+            Ok(normalization) if normalization != InputNormalization::Auto => {
+                return Ok(Resolved { normalization, source: NormalizationSource::ModelMetadata });
+            }
+            Ok(_) | Err(_) => tracing::warn!("Ignoring model metadata {}={:?}", MODEL_METADATA_KEY, value),
+        }
+    }
+
+    let check = self_check(&CANDIDATES, infer)?;
+    Ok(Resolved {
+        normalization: check.chosen,
+        source: NormalizationSource::SelfCheck(check),
+    })
+}
+
+/// Where a resolved convention came from
+#[derive(Debug, Clone, PartialEq)]
+pub enum NormalizationSource {
+    Configured,
+    ModelMetadata,
+    SelfCheck(SelfCheck),
+}
+
+/// A concrete convention and how it was chosen
+#[derive(Debug, Clone, PartialEq)]
+pub struct Resolved {
+    pub normalization: InputNormalization,
+    pub source: NormalizationSource,
+}
+
+impl fmt::Display for Resolved {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.source {
+            NormalizationSource::Configured => write!(f, "{} (configured)", self.normalization),
+            NormalizationSource::ModelMetadata => write!(f, "{} (model metadata)", self.normalization),
+            NormalizationSource::SelfCheck(check) => {
+                write!(f, "{} (self-check, mean output entropy:", self.normalization)?;
+                for (candidate, entropy) in &check.entropies {
+                    write!(f, " {}={:.3}", candidate, entropy)?;
+                }
+                write!(f, ")")
+            }
+        }
+    }
+}
+
+/// Outcome of running the fixture faces under each candidate convention
+#[derive(Debug, Clone, PartialEq)]
+pub struct SelfCheck {
+    pub chosen: InputNormalization,
+    /// Mean softmax entropy of the outputs per candidate, in candidate order
+    pub entropies: Vec<(InputNormalization, f32)>,
+}
+
+/// Choose the candidate whose outputs on the fixture faces are most decisive
+///
+/// A model fed its own convention separates expressions; fed another, its
+/// outputs drift toward uniform. Non-finite outputs count as maximally
+/// uncertain, and near ties go to the earlier candidate.
+pub fn self_check<L: AsRef<[f32]>, E>(
+    candidates: &[InputNormalization],
+    mut infer: impl FnMut(&[f32]) -> Result<L, E>,
+) -> Result<SelfCheck, E> {
+    let faces = fixture_faces();
+    let mut entropies = Vec::with_capacity(candidates.len());
+
+    for candidate in candidates {
+        let mut total = 0.0;
+        for face in &faces {
+            let mut pixels = face.clone();
+            candidate.apply(&mut pixels);
+            total += softmax_entropy(infer(&pixels)?.as_ref());
+        }
+        entropies.push((*candidate, total / faces.len() as f32));
+    }
+
+    let mut chosen = (InputNormalization::ZeroToOne, f32::INFINITY);
+    for &(candidate, entropy) in &entropies {
+        if entropy < chosen.1 - ENTROPY_TIE {
+            chosen = (candidate, entropy);
+        }
+    }
+    Ok(SelfCheck { chosen: chosen.0, entropies })
+}
+
+/// Entropy in nats of the softmax of `logits`; `ln n` when any of the `n`
+/// logits is not finite, so a convention overflowing the model never looks
+/// confident
+pub fn softmax_entropy(logits: &[f32]) -> f32 {
+    if logits.iter().any(|logit| !logit.is_finite()) {
+        return (logits.len() as f32).ln();
+    }
+    math::softmax_entropy(logits)
+}
+
+/// Synthetic grayscale faces in [0, 1], [`INPUT_SIZE`]² each
+///
+/// A lit oval with dark eyes and a mouth, at several exposures and mouth
+/// openings so the check does not hinge on one brightness level.
+pub fn fixture_faces() -> Vec<Vec<f32>> {
+    [(0.75, 0.15, 2.0), (0.55, 0.10, 5.0), (0.90, 0.25, 3.0)]
+        .into_iter()
+        .map(|(skin, feature, mouth_open)| synthetic_face(skin, feature, mouth_open))
+        .collect()
+}
+
+fn synthetic_face(skin: f32, feature: f32, mouth_open: f32) -> Vec<f32> {
+    let center = INPUT_SIZE as f32 / 2.0;
+    let inside = |x: f32, y: f32, cx: f32, cy: f32, rx: f32, ry: f32| {
+        ((x - cx) / rx).powi(2) + ((y - cy) / ry).powi(2) <= 1.0
+    };
+
+    let mut pixels = Vec::with_capacity(INPUT_SIZE * INPUT_SIZE);
+    for row in 0..INPUT_SIZE {
+        for col in 0..INPUT_SIZE {
+            let (x, y) = (col as f32 + 0.5, row as f32 + 0.5);
+            let value = if !inside(x, y, center, center, 17.0, 21.0) {
+                0.05 // Background
+            } else if inside(x, y, center - 7.0, center - 5.0, 3.5, 2.0)
+                || inside(x, y, center + 7.0, center - 5.0, 3.5, 2.0)
+                || inside(x, y, center, center + 10.0, 6.0, mouth_open)
+            {
+                feature
+            } else {
+                // Light falls off toward the edge of the face
+                let distance = ((x - center).powi(2) + (y - center).powi(2)).sqrt() / center;
+                skin * (1.0 - 0.3 * distance)
+            };
+            pixels.push(value.clamp(0.0, 1.0));
+        }
+    }
+    pixels
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_softmax_entropy() {
+        assert!((softmax_entropy(&[0.0; 7]) - 7f32.ln()).abs() < 1e-5);
+        assert!(softmax_entropy(&[20.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0]) < 1e-3);
+        assert_eq!(softmax_entropy(&[f32::NAN; 7]), 7f32.ln());
+        assert!((softmax_entropy(&[0.0; 10]) - 10f32.ln()).abs() < 1e-5);
+    }
+
+    /// Stub model trained on `expected`: decisive only when the input statistics match it
+    fn stub_model(expected: InputNormalization) -> impl FnMut(&[f32]) -> Result<[f32; 7], String> {
+        let mut reference = fixture_faces()[0].clone();
+        expected.apply(&mut reference);
+        let (ref_min, ref_max) = range(&reference);
+
+        move |pixels| {
+            let (min, max) = range(pixels);
+            let mismatch = (min - ref_min).abs() + (max - ref_max).abs();
+            // Fear channel dominates for matching input and fades into noise otherwise
+            let strength = 8.0 * (-4.0 * mismatch).exp();
+            let mut logits = [0.0; 7];
+            logits[2] = strength;
+            logits[6] = strength * 0.3;
+            Ok(logits)
+        }
+    }
+
+    fn range(pixels: &[f32]) -> (f32, f32) {
+        pixels.iter().fold((f32::INFINITY, f32::NEG_INFINITY), |(min, max), p| (min.min(*p), max.max(*p)))
+    }
+
+    #[test]
+    fn test_self_check_picks_the_convention_the_model_was_trained_on() {
+        for expected in CANDIDATES {
+            let check = self_check(&CANDIDATES, stub_model(expected)).unwrap();
+            assert_eq!(check.chosen, expected, "{:?}", check.entropies);
+            assert_eq!(check.entropies.len(), CANDIDATES.len());
+        }
+
+        // A model that ignores its input gives no reason to leave the default
+        let flat = self_check(&CANDIDATES, |_: &[f32]| Ok::<_, String>([1.0; 7])).unwrap();
+        assert_eq!(flat.chosen, InputNormalization::ZeroToOne);
+
+        // Inference errors abort the check
+        assert!(self_check(&CANDIDATES, |_: &[f32]| Err::<[f32; 7], _>("no session")).is_err());
+    }
+
+    #[test]
+    fn test_resolve_prefers_configuration_then_metadata() {
+        let never = |_: &[f32]| -> Result<[f32; 7], String> { panic!("self-check should not run") };
+
+        let configured = resolve_normalization(InputNormalization::MinusOneToOne, Some("zero_to_one"), never).unwrap();
+        assert_eq!(configured.normalization, InputNormalization::MinusOneToOne);
+        assert_eq!(configured.source, NormalizationSource::Configured);
+
+        let from_metadata = resolve_normalization(InputNormalization::Auto, Some("mean_std:0.5,0.25"), never).unwrap();
+        assert_eq!(from_metadata.normalization, InputNormalization::MeanStd { mean: 0.5, std: 0.25 });
+        assert_eq!(from_metadata.source, NormalizationSource::ModelMetadata);
+
+        // Unusable metadata falls through to the self-check
+        let checked =
+            resolve_normalization(InputNormalization::Auto, Some("auto"), stub_model(InputNormalization::MinusOneToOne))
+                .unwrap();
+        assert_eq!(checked.normalization, InputNormalization::MinusOneToOne);
+        assert!(matches!(checked.source, NormalizationSource::SelfCheck(_)));
+        assert!(checked.to_string().starts_with("minus_one_to_one (self-check"));
+    }
+
+    #[test]
+    fn test_fixture_faces() {
+        let faces = fixture_faces();
+        assert_eq!(faces.len(), 3);
+        for face in &faces {
+            assert_eq!(face.len(), INPUT_SIZE * INPUT_SIZE);
+            let (min, max) = range(face);
+            assert!(min >= 0.0 && max <= 1.0 && max - min > 0.3);
+        }
+    }
+}
@@ -0,0 +1,405 @@
+//! Calibration phases for scripted experiences
+//!
+//! Scripts gate content on calibration milestones ("start act 1 once the
+//! sensor is calibrated, play the fallback intro if it cannot be"). The
+//! sensor publishes its current [`CalibrationPhase`], and
+//! [`CalibrationPhases`] streams it, in process from
+//! [`EmotionSensor::phases`] or remotely from [`SensorClient::phases`].
+//!
+//! Every phase stream starts with the phase the sensor is in when it is
+//! opened, so a script subscribing after calibration completed sees
+//! `Calibrated` right away instead of waiting for a transition that already
+//! happened. Streams end when the sensor stops.
+//!
+//! [`EmotionSensor::phases`]: crate::sensor::EmotionSensor::phases
+//! [`SensorClient::phases`]: crate::grpc_client::SensorClient::phases
+
+use crate::{
+    proto::{sensor_event, SensorEvent},
+    types::SensorFaultNotice,
+};
+use futures::{Stream, StreamExt};
+use std::fmt;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::time::Duration;
+use tokio::sync::{broadcast, watch};
+use tonic::Status;
+
+/// Where the sensor's calibration stands
+#[derive(Debug, Clone, PartialEq)]
+pub enum CalibrationPhase {
+    /// The sensor has not started collecting samples
+    Idle,
+    /// Baseline samples are being collected
+    Collecting {
+        /// Progress [0.0, 1.0]
+        progress: f32,
+    },
+    /// The baseline is established and scores are calibrated
+    Calibrated {
+        /// How established the baseline is [0.0, 1.0]
+        quality: f32,
+    },
+    /// Calibrated, with the baseline held still by a freeze
+    Frozen {
+        /// How established the baseline was when frozen [0.0, 1.0]
+        quality: f32,
+    },
+    /// Calibration cannot complete this run
+    Failed {
+        /// Why, e.g. emotion inference went offline
+        reason: String,
+    },
+}
+
+impl CalibrationPhase {
+    /// Whether scores are calibrated
+    pub fn is_calibrated(&self) -> bool {
+        matches!(self, Self::Calibrated { .. } | Self::Frozen { .. })
+    }
+
+    /// Whether calibration cannot complete
+    pub fn is_failed(&self) -> bool {
+        matches!(self, Self::Failed { .. })
+    }
+
+    /// Progress [0.0, 1.0]; 1 once calibrated, 0 when idle or failed
+    pub fn progress(&self) -> f32 {
+        match self {
+            Self::Collecting { progress } => *progress,
+            Self::Calibrated { .. } | Self::Frozen { .. } => 1.0,
+            Self::Idle | Self::Failed { .. } => 0.0,
+        }
+    }
+
+    /// Baseline quality, once calibrated
+    pub fn quality(&self) -> Option<f32> {
+        match self {
+            Self::Calibrated { quality } | Self::Frozen { quality } => Some(*quality),
+            _ => None,
+        }
+    }
+
+    /// Lowercase name of the phase, as reported in control rejections
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Idle => "idle",
+            Self::Collecting { .. } => "collecting",
+            Self::Calibrated { .. } => "calibrated",
+            Self::Frozen { .. } => "frozen",
+            Self::Failed { .. } => "failed",
+        }
+    }
+}
+
+impl fmt::Display for CalibrationPhase {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// How a wait on calibration phases ended
+#[derive(Debug, Clone, PartialEq)]
+pub enum PhaseOutcome {
+    /// The phase that ended the wait
+    Reached(CalibrationPhase),
+    /// No phase ended the wait in time
+    Timeout {
+        /// Phase the sensor was last seen in, if any arrived
+        last_phase: Option<CalibrationPhase>,
+    },
+    /// The sensor stopped before any phase ended the wait
+    SensorStopped,
+}
+
+/// Stream of the sensor's calibration phases, current phase first
+///
+/// Ends when the sensor stops. The `wait_for_*` combinators consume phases
+/// from the stream, so they can be chained on one subscription.
+pub struct CalibrationPhases {
+    phases: Pin<Box<dyn Stream<Item = Result<CalibrationPhase, Status>> + Send>>,
+    last_phase: Option<CalibrationPhase>,
+}
+
+impl CalibrationPhases {
+    /// Wrap a stream of phases that starts with the current phase
+    pub fn new(phases: impl Stream<Item = Result<CalibrationPhase, Status>> + Send + 'static) -> Self {
+        Self {
+            phases: Box::pin(phases),
+            last_phase: None,
+        }
+    }
+
+    /// Phases carried by the calibration progress events of `events`
+    ///
+    /// The stream must have been opened with `initial_calibration` for the
+    /// current phase to come first. Other events are skipped.
+    pub fn from_events(events: impl Stream<Item = Result<SensorEvent, Status>> + Send + 'static) -> Self {
+        Self::new(events.filter_map(|event_result| async move {
+            match event_result {
+                Ok(SensorEvent {
+                    event: Some(sensor_event::Event::CalibrationProgress(progress)),
+                    ..
+                }) => Some(Ok(CalibrationPhase::from(&progress))),
+                Ok(_) => None,
+                Err(e) => Some(Err(e)),
+            }
+        }))
+    }
+
+    /// Phases published through `phase`, ending with the `SENSOR_STOPPED` notice
+    ///
+    /// `stopped` ends the stream after the current phase, for a sensor that
+    /// announced its stop before `faults` subscribed.
+    pub(crate) fn watch(
+        mut phase: watch::Receiver<CalibrationPhase>,
+        faults: broadcast::Receiver<SensorFaultNotice>,
+        stopped: bool,
+    ) -> Self {
+        let initial = phase.borrow_and_update().clone();
+        let changes = futures::stream::unfold((phase, faults), move |(mut phase, mut faults)| async move {
+            if stopped {
+                return None;
+            }
+            loop {
+                tokio::select! {
+                    changed = phase.changed() => {
+                        // A dropped sender means the sensor is gone
+                        changed.ok()?;
+                        let current = phase.borrow_and_update().clone();
+                        return Some((Ok(current), (phase, faults)));
+                    },
+                    fault = faults.recv() => match fault {
+                        Ok(fault) if fault.is_stopped() => return None,
+                        Ok(_) | Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                        Err(broadcast::error::RecvError::Closed) => return None,
+                    },
+                }
+            }
+        });
+        Self::new(futures::stream::once(std::future::ready(Ok(initial))).chain(changes))
+    }
+
+    /// Phase last received from the stream
+    pub fn last_phase(&self) -> Option<&CalibrationPhase> {
+        self.last_phase.as_ref()
+    }
+
+    /// Wait up to `timeout` for a phase matching `predicate`
+    ///
+    /// Fails only if the stream reports an error.
+    pub async fn wait_for_phase(
+        &mut self,
+        mut predicate: impl FnMut(&CalibrationPhase) -> bool,
+        timeout: Duration,
+    ) -> Result<PhaseOutcome, Status> {
+        let wait = async {
+            while let Some(phase) = self.next().await {
+                let phase = phase?;
+                if predicate(&phase) {
+                    return Ok(PhaseOutcome::Reached(phase));
+                }
+            }
+            Ok(PhaseOutcome::SensorStopped)
+        };
+        match tokio::time::timeout(timeout, wait).await {
+            Ok(outcome) => outcome,
+            Err(_) => Ok(PhaseOutcome::Timeout {
+                last_phase: self.last_phase.clone(),
+            }),
+        }
+    }
+
+    /// Wait up to `timeout` for calibration to settle
+    ///
+    /// Reaches `Calibrated`, or `Failed` when calibration cannot complete,
+    /// so a script can branch to its fallback without waiting out the
+    /// timeout.
+    pub async fn wait_for_calibrated(&mut self, timeout: Duration) -> Result<PhaseOutcome, Status> {
+        self.wait_for_phase(|phase| phase.is_calibrated() || phase.is_failed(), timeout)
+            .await
+    }
+
+    /// Wait up to `timeout` for a calibrated baseline of at least `min` quality
+    ///
+    /// Like [`CalibrationPhases::wait_for_calibrated`], `Failed` ends the wait too.
+    pub async fn wait_for_quality(&mut self, min: f32, timeout: Duration) -> Result<PhaseOutcome, Status> {
+        self.wait_for_phase(
+            |phase| phase.quality().is_some_and(|quality| quality >= min) || phase.is_failed(),
+            timeout,
+        )
+        .await
+    }
+}
+
+impl Stream for CalibrationPhases {
+    type Item = Result<CalibrationPhase, Status>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let item = futures::ready!(self.phases.as_mut().poll_next(cx));
+        if let Some(Ok(phase)) = &item {
+            self.last_phase = Some(phase.clone());
+        }
+        Poll::Ready(item)
+    }
+}
+
+/// Publish `phase` to subscribers if it differs from the current one
+pub(crate) fn publish_phase(sender: &watch::Sender<CalibrationPhase>, phase: CalibrationPhase) {
+    sender.send_if_modified(|current| {
+        if *current == phase {
+            return false;
+        }
+        *current = phase;
+        true
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::proto::{CalibrationProgress, CalibrationState, SensorFault};
+
+    /// Calibration progress event for `phase`, as the daemon sends it
+    fn progress_event(phase: &CalibrationPhase) -> Result<SensorEvent, Status> {
+        Ok(SensorEvent {
+            timestamp_us: 0,
+            sequence: 0,
+            event: Some(sensor_event::Event::CalibrationProgress(CalibrationProgress::from(phase))),
+        })
+    }
+
+    /// Remote phases fed by the test through a channel, like a live stream
+    fn scripted() -> (tokio::sync::mpsc::UnboundedSender<Result<SensorEvent, Status>>, CalibrationPhases) {
+        let (sender, receiver) = tokio::sync::mpsc::unbounded_channel();
+        let events = tokio_stream::wrappers::UnboundedReceiverStream::new(receiver);
+        (sender, CalibrationPhases::from_events(events))
+    }
+
+    fn collecting(progress: f32) -> CalibrationPhase {
+        CalibrationPhase::Collecting { progress }
+    }
+
+    #[tokio::test]
+    async fn test_wait_for_calibrated_follows_scripted_phases() {
+        let (events, mut phases) = scripted();
+        for phase in [CalibrationPhase::Idle, collecting(0.2), collecting(0.8)] {
+            events.send(progress_event(&phase)).unwrap();
+        }
+        // A marker between phases is not a phase
+        events
+            .send(Ok(SensorEvent {
+                event: Some(sensor_event::Event::Marker(Default::default())),
+                ..Default::default()
+            }))
+            .unwrap();
+        events.send(progress_event(&CalibrationPhase::Calibrated { quality: 0.1 })).unwrap();
+        events.send(progress_event(&CalibrationPhase::Calibrated { quality: 0.6 })).unwrap();
+
+        let outcome = phases.wait_for_calibrated(Duration::from_secs(1)).await.unwrap();
+        assert_eq!(outcome, PhaseOutcome::Reached(CalibrationPhase::Calibrated { quality: 0.1 }));
+
+        // The same subscription carries on where the last wait stopped
+        let outcome = phases.wait_for_quality(0.5, Duration::from_secs(1)).await.unwrap();
+        assert_eq!(outcome, PhaseOutcome::Reached(CalibrationPhase::Calibrated { quality: 0.6 }));
+        assert_eq!(phases.last_phase(), Some(&CalibrationPhase::Calibrated { quality: 0.6 }));
+        drop(events);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_wait_times_out_with_last_phase() {
+        let (events, mut phases) = scripted();
+        events.send(progress_event(&collecting(0.4))).unwrap();
+
+        let outcome = phases.wait_for_calibrated(Duration::from_secs(30)).await.unwrap();
+        assert_eq!(outcome, PhaseOutcome::Timeout { last_phase: Some(collecting(0.4)) });
+
+        // Nothing at all arrives on a stalled stream
+        let (_stalled, mut stalled_phases) = scripted();
+        let outcome = stalled_phases.wait_for_quality(0.5, Duration::from_secs(30)).await.unwrap();
+        assert_eq!(outcome, PhaseOutcome::Timeout { last_phase: None });
+    }
+
+    #[tokio::test]
+    async fn test_failure_and_stop_end_waits() {
+        let (events, mut phases) = scripted();
+        let failed = CalibrationPhase::Failed { reason: "Emotion inference offline".to_string() };
+        events.send(progress_event(&collecting(0.5))).unwrap();
+        events.send(progress_event(&failed)).unwrap();
+        let outcome = phases.wait_for_quality(0.9, Duration::from_secs(1)).await.unwrap();
+        assert_eq!(outcome, PhaseOutcome::Reached(failed.clone()));
+
+        // The daemon's stop fault is the last event before the stream ends
+        events
+            .send(Ok(SensorEvent {
+                event: Some(sensor_event::Event::SensorFault(SensorFault {
+                    error_code: crate::types::SENSOR_STOPPED.to_string(),
+                    ..Default::default()
+                })),
+                ..Default::default()
+            }))
+            .unwrap();
+        drop(events);
+        let outcome = phases.wait_for_calibrated(Duration::from_secs(1)).await.unwrap();
+        assert_eq!(outcome, PhaseOutcome::SensorStopped);
+        assert_eq!(phases.last_phase(), Some(&failed));
+
+        // Stream errors are reported rather than taken for a stop
+        let (events, mut phases) = scripted();
+        events.send(Err(Status::unavailable("connection reset"))).unwrap();
+        let error = phases.wait_for_calibrated(Duration::from_secs(1)).await.unwrap_err();
+        assert_eq!(error.code(), tonic::Code::Unavailable);
+    }
+
+    #[tokio::test]
+    async fn test_late_subscriber_sees_current_phase() {
+        let (phase, _) = watch::channel(CalibrationPhase::Idle);
+        let (faults, _) = broadcast::channel(4);
+        publish_phase(&phase, collecting(0.5));
+        publish_phase(&phase, CalibrationPhase::Calibrated { quality: 0.3 });
+
+        // Subscribing after calibration completed still reaches it at once
+        let mut phases = CalibrationPhases::watch(phase.subscribe(), faults.subscribe(), false);
+        let outcome = phases.wait_for_calibrated(Duration::from_millis(10)).await.unwrap();
+        assert_eq!(outcome, PhaseOutcome::Reached(CalibrationPhase::Calibrated { quality: 0.3 }));
+
+        // Later transitions follow, until the sensor stops
+        let waiting = tokio::spawn(async move {
+            let reached = phases.wait_for_quality(1.0, Duration::from_secs(1)).await.unwrap();
+            (reached, phases.wait_for_calibrated(Duration::from_secs(1)).await.unwrap())
+        });
+        tokio::task::yield_now().await;
+        publish_phase(&phase, CalibrationPhase::Calibrated { quality: 1.0 });
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        faults.send(SensorFaultNotice::stopped("Stopped by request")).unwrap();
+        let (reached, after_stop) = waiting.await.unwrap();
+        assert_eq!(reached, PhaseOutcome::Reached(CalibrationPhase::Calibrated { quality: 1.0 }));
+        assert_eq!(after_stop, PhaseOutcome::SensorStopped);
+
+        // A sensor that already stopped gives its last phase, then ends
+        let mut phases = CalibrationPhases::watch(phase.subscribe(), faults.subscribe(), true);
+        assert_eq!(phases.next().await.unwrap().unwrap(), CalibrationPhase::Calibrated { quality: 1.0 });
+        assert!(phases.next().await.is_none());
+    }
+
+    #[test]
+    fn test_phase_progress_conversion() {
+        for phase in [
+            CalibrationPhase::Idle,
+            collecting(0.25),
+            CalibrationPhase::Calibrated { quality: 0.5 },
+            CalibrationPhase::Frozen { quality: 0.5 },
+            CalibrationPhase::Failed { reason: "offline".to_string() },
+        ] {
+            assert_eq!(CalibrationPhase::from(&CalibrationProgress::from(&phase)), phase);
+        }
+
+        // Older daemons only report progress and completion
+        let legacy = CalibrationProgress { progress: 0.4, ..Default::default() };
+        assert_eq!(legacy.state, CalibrationState::Unspecified as i32);
+        assert_eq!(CalibrationPhase::from(&legacy), collecting(0.4));
+        let legacy = CalibrationProgress { progress: 1.0, completed: true, ..Default::default() };
+        assert_eq!(CalibrationPhase::from(&legacy), CalibrationPhase::Calibrated { quality: 0.0 });
+    }
+}
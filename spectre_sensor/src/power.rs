@@ -0,0 +1,635 @@
+//! Power-aware frame rate governor for battery-powered laptops
+//!
+//! Sustained 30 FPS inference drains a demo laptop's battery and heats it
+//! until thermal throttling wrecks latency anyway. With the governor enabled
+//! the sensor polls a [`PowerMonitor`] and picks a [`PowerProfile`]:
+//!
+//! - `Performance` on mains power and below `warm_c`: the configured rates.
+//! - `Balanced` on battery, or from `warm_c`: the `balanced` rates.
+//! - `Saver` on battery below `low_battery` charge, or from `hot_c`: the
+//!   `saver` rates.
+//!
+//! A profile's rates are a target FPS and an emotion inference interval:
+//! with an interval of n, emotion inference runs on every nth frame and the
+//! frames in between reuse its logits, while face detection still runs on
+//! every frame. A condition holds until it clears by the configured
+//! hysteresis, so a reading hovering at a threshold does not flap between
+//! profiles. Every change is logged, reported by `GetStatus` and streamed as
+//! a `PowerProfileChanged` event.
+//!
+//! Rates set through `UpdateConfig` ([`PowerControl::set_override`]) always
+//! win: the profile keeps following the readings, but the overridden rates
+//! stay until the override is cleared.
+//!
+//! Reading the battery and temperatures needs the `power-governor` feature;
+//! without it [`system_monitor`] fails and the sensor runs at the
+//! configured rates.
+
+use crate::heartbeat::{Liveness, Unhealthy};
+use serde::{Deserialize, Serialize};
+use spectremesh_core::EmotionLogits;
+use std::fmt;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use thiserror::Error;
+use tokio::sync::{broadcast, watch};
+use tokio::time::MissedTickBehavior;
+
+/// How hard the sensor works, from the power supply and temperatures
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PowerProfile {
+    /// Configured rates
+    #[default]
+    Performance,
+    /// On battery or warm
+    Balanced,
+    /// Low battery or hot
+    Saver,
+}
+
+impl PowerProfile {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Performance => "performance",
+            Self::Balanced => "balanced",
+            Self::Saver => "saver",
+        }
+    }
+}
+
+impl fmt::Display for PowerProfile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// Frame rate and emotion inference cadence
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ProfileRates {
+    pub target_fps: f32,
+    /// Run emotion inference on every nth frame
+    pub emotion_interval: u32,
+}
+
+impl ProfileRates {
+    fn validate(&self, profile: PowerProfile) -> Result<(), String> {
+        if self.target_fps <= 0.0 {
+            return Err(format!("Target FPS of the {} power profile must be positive", profile));
+        }
+        if self.emotion_interval == 0 {
+            return Err(format!("Emotion interval of the {} power profile must be at least 1", profile));
+        }
+        Ok(())
+    }
+}
+
+/// Governor policy: thresholds, hysteresis and the rates of the reduced profiles
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PowerConfig {
+    /// Follow the power supply and temperatures (overridable with SPECTRE_POWER_GOVERNOR)
+    pub enabled: bool,
+    /// Time between readings
+    #[serde(with = "spectremesh_core::duration")]
+    pub poll_interval: Duration,
+    /// Rates on battery or when warm
+    pub balanced: ProfileRates,
+    /// Rates on low battery or when hot
+    pub saver: ProfileRates,
+    /// Battery charge in [0, 1] below which running on battery means saver
+    pub low_battery: f32,
+    /// Hottest sensor temperature from which the sensor runs balanced
+    pub warm_c: f32,
+    /// Hottest sensor temperature from which the sensor runs saver
+    pub hot_c: f32,
+    /// How far a temperature must drop below a threshold to step back up
+    pub temperature_hysteresis_c: f32,
+    /// How far the charge must rise above `low_battery` to leave saver
+    pub battery_hysteresis: f32,
+}
+
+impl Default for PowerConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            poll_interval: Duration::from_secs(5),
+            balanced: ProfileRates { target_fps: 20.0, emotion_interval: 2 },
+            saver: ProfileRates { target_fps: 10.0, emotion_interval: 3 },
+            low_battery: 0.2,
+            warm_c: 80.0,
+            hot_c: 90.0,
+            temperature_hysteresis_c: 5.0,
+            battery_hysteresis: 0.05,
+        }
+    }
+}
+
+impl PowerConfig {
+    /// Validate the configuration
+    pub fn validate(&self) -> Result<(), String> {
+        if self.poll_interval.is_zero() {
+            return Err("Power poll interval must be positive".to_string());
+        }
+        self.balanced.validate(PowerProfile::Balanced)?;
+        self.saver.validate(PowerProfile::Saver)?;
+        if !(0.0..=1.0).contains(&self.low_battery) {
+            return Err(format!("Low battery threshold must be within [0, 1], got {}", self.low_battery));
+        }
+        if self.warm_c > self.hot_c {
+            return Err(format!("Warm temperature {}°C is above hot {}°C", self.warm_c, self.hot_c));
+        }
+        if self.temperature_hysteresis_c < 0.0 || self.battery_hysteresis < 0.0 {
+            return Err("Power hysteresis cannot be negative".to_string());
+        }
+        Ok(())
+    }
+
+    fn rates(&self, profile: PowerProfile, performance: ProfileRates) -> ProfileRates {
+        match profile {
+            PowerProfile::Performance => performance,
+            PowerProfile::Balanced => self.balanced,
+            PowerProfile::Saver => self.saver,
+        }
+    }
+}
+
+/// One look at the power supply and temperatures
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct PowerReading {
+    /// Running off the battery rather than mains power
+    pub on_battery: bool,
+    /// Battery charge in [0, 1], if there is a battery
+    pub battery_charge: Option<f32>,
+    /// Hottest temperature sensor in °C, if any can be read
+    pub temperature_c: Option<f32>,
+}
+
+/// Power monitor errors
+#[derive(Debug, Error)]
+pub enum PowerError {
+    #[error("Power monitoring needs the power-governor feature")]
+    Unsupported,
+
+    #[error("Failed to read power state: {0}")]
+    Read(String),
+}
+
+/// Source of power readings
+pub trait PowerMonitor: Send {
+    fn read(&mut self) -> Result<PowerReading, PowerError>;
+}
+
+/// The machine's batteries and temperature sensors
+#[cfg(feature = "power-governor")]
+pub struct SystemPowerMonitor {
+    batteries: starship_battery::Manager,
+    components: sysinfo::Components,
+}
+
+#[cfg(feature = "power-governor")]
+impl SystemPowerMonitor {
+    pub fn new() -> Result<Self, PowerError> {
+        Ok(Self {
+            batteries: starship_battery::Manager::new().map_err(|e| PowerError::Read(e.to_string()))?,
+            components: sysinfo::Components::new_with_refreshed_list(),
+        })
+    }
+}
+
+#[cfg(feature = "power-governor")]
+impl PowerMonitor for SystemPowerMonitor {
+    fn read(&mut self) -> Result<PowerReading, PowerError> {
+        let batteries: Vec<starship_battery::Battery> = self
+            .batteries
+            .batteries()
+            .map_err(|e| PowerError::Read(e.to_string()))?
+            .filter_map(Result::ok)
+            .collect();
+        let on_battery = batteries
+            .iter()
+            .any(|battery| battery.state() == starship_battery::State::Discharging);
+        let battery_charge = (!batteries.is_empty()).then(|| {
+            batteries.iter().map(|battery| battery.state_of_charge().value).sum::<f32>() / batteries.len() as f32
+        });
+
+        self.components.refresh(false);
+        let temperature_c = self
+            .components
+            .list()
+            .iter()
+            .filter_map(|component| component.temperature())
+            .filter(|temperature| temperature.is_finite())
+            .reduce(f32::max);
+        Ok(PowerReading { on_battery, battery_charge, temperature_c })
+    }
+}
+
+/// Monitor of this machine's power supply and temperatures
+pub fn system_monitor() -> Result<Box<dyn PowerMonitor>, PowerError> {
+    #[cfg(feature = "power-governor")]
+    return Ok(Box::new(SystemPowerMonitor::new()?));
+    #[cfg(not(feature = "power-governor"))]
+    Err(PowerError::Unsupported)
+}
+
+/// Rates set by an operator, winning over the governor
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct RateOverride {
+    pub target_fps: Option<f32>,
+    pub emotion_interval: Option<u32>,
+}
+
+impl RateOverride {
+    /// Whether anything is overridden
+    pub fn is_set(&self) -> bool {
+        self.target_fps.is_some() || self.emotion_interval.is_some()
+    }
+
+    fn apply(&self, rates: ProfileRates) -> ProfileRates {
+        ProfileRates {
+            target_fps: self.target_fps.unwrap_or(rates.target_fps),
+            emotion_interval: self.emotion_interval.unwrap_or(rates.emotion_interval),
+        }
+    }
+}
+
+/// The governor moved to another profile
+#[derive(Debug, Clone, PartialEq)]
+pub struct PowerProfileChanged {
+    pub from: PowerProfile,
+    pub to: PowerProfile,
+    /// What the reading showed, e.g. `on battery at 54%`
+    pub reason: String,
+    /// Rates in effect from now on
+    pub rates: ProfileRates,
+    /// Whether an operator override kept the rates from following the profile
+    pub overridden: bool,
+    /// Microseconds since Unix epoch
+    pub timestamp_us: u64,
+}
+
+/// Profile selection with hysteresis
+#[derive(Debug, Clone)]
+pub struct PowerGovernor {
+    config: PowerConfig,
+    /// Rates of the performance profile
+    performance: ProfileRates,
+    profile: PowerProfile,
+    /// Profile the temperature alone calls for
+    thermal: PowerProfile,
+    battery_low: bool,
+    overrides: RateOverride,
+}
+
+impl PowerGovernor {
+    pub fn new(config: PowerConfig, performance: ProfileRates) -> Self {
+        Self {
+            config,
+            performance,
+            profile: PowerProfile::Performance,
+            thermal: PowerProfile::Performance,
+            battery_low: false,
+            overrides: RateOverride::default(),
+        }
+    }
+
+    pub fn profile(&self) -> PowerProfile {
+        self.profile
+    }
+
+    pub fn overrides(&self) -> RateOverride {
+        self.overrides
+    }
+
+    /// Rates in effect: the profile's, with any override on top
+    pub fn rates(&self) -> ProfileRates {
+        self.overrides.apply(self.config.rates(self.profile, self.performance))
+    }
+
+    /// Replace the operator override; an unset one hands the rates back to the profile
+    pub fn set_override(&mut self, overrides: RateOverride) {
+        self.overrides = overrides;
+    }
+
+    /// Take in a reading, returning the change of profile if there is one
+    pub fn observe(&mut self, reading: &PowerReading) -> Option<PowerProfileChanged> {
+        self.thermal = self.thermal_profile(reading.temperature_c);
+        self.battery_low = match reading.battery_charge {
+            Some(charge) if self.battery_low => charge < self.config.low_battery + self.config.battery_hysteresis,
+            Some(charge) => charge < self.config.low_battery,
+            None => false,
+        };
+        let supply = match (reading.on_battery, self.battery_low) {
+            (false, _) => PowerProfile::Performance,
+            (true, false) => PowerProfile::Balanced,
+            (true, true) => PowerProfile::Saver,
+        };
+
+        let profile = supply.max(self.thermal);
+        if profile == self.profile {
+            return None;
+        }
+        let from = std::mem::replace(&mut self.profile, profile);
+        Some(PowerProfileChanged {
+            from,
+            to: profile,
+            reason: describe(reading),
+            rates: self.rates(),
+            overridden: self.overrides.is_set(),
+            timestamp_us: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_micros() as u64,
+        })
+    }
+
+    /// Temperature conditions stay until the temperature drops below their
+    /// threshold by the hysteresis
+    fn thermal_profile(&self, temperature_c: Option<f32>) -> PowerProfile {
+        let Some(temperature) = temperature_c else {
+            return PowerProfile::Performance;
+        };
+        let PowerConfig { warm_c, hot_c, temperature_hysteresis_c: hysteresis, .. } = self.config;
+        match self.thermal {
+            PowerProfile::Saver if temperature > hot_c - hysteresis => PowerProfile::Saver,
+            _ if temperature >= hot_c => PowerProfile::Saver,
+            PowerProfile::Balanced | PowerProfile::Saver if temperature > warm_c - hysteresis => PowerProfile::Balanced,
+            _ if temperature >= warm_c => PowerProfile::Balanced,
+            _ => PowerProfile::Performance,
+        }
+    }
+}
+
+/// What a reading shows, for logs and events
+fn describe(reading: &PowerReading) -> String {
+    let supply = match (reading.on_battery, reading.battery_charge) {
+        (true, Some(charge)) => format!("on battery at {:.0}%", charge * 100.0),
+        (true, None) => "on battery".to_string(),
+        (false, _) => "on mains power".to_string(),
+    };
+    match reading.temperature_c {
+        Some(temperature) => format!("{}, {:.0}°C", supply, temperature),
+        None => supply,
+    }
+}
+
+/// The governor shared by the polling task, `UpdateConfig` and the processing loop
+pub struct PowerControl {
+    governor: Mutex<PowerGovernor>,
+    poll_interval: Duration,
+    rates: watch::Sender<ProfileRates>,
+    changes: broadcast::Sender<PowerProfileChanged>,
+}
+
+impl PowerControl {
+    /// Governor starting in the performance profile at `performance` rates
+    pub fn new(config: PowerConfig, performance: ProfileRates) -> Self {
+        let poll_interval = config.poll_interval;
+        let governor = PowerGovernor::new(config, performance);
+        let (rates, _) = watch::channel(governor.rates());
+        let (changes, _) = broadcast::channel(16);
+        Self {
+            governor: Mutex::new(governor),
+            poll_interval,
+            rates,
+            changes,
+        }
+    }
+
+    pub fn profile(&self) -> PowerProfile {
+        self.governor.lock().unwrap().profile()
+    }
+
+    pub fn overrides(&self) -> RateOverride {
+        self.governor.lock().unwrap().overrides()
+    }
+
+    /// Rates in effect
+    pub fn rates(&self) -> ProfileRates {
+        *self.rates.borrow()
+    }
+
+    /// Rates in effect, updated as they change
+    pub fn subscribe_rates(&self) -> watch::Receiver<ProfileRates> {
+        self.rates.subscribe()
+    }
+
+    /// Profile changes
+    pub fn subscribe(&self) -> broadcast::Receiver<PowerProfileChanged> {
+        self.changes.subscribe()
+    }
+
+    /// Read `monitor` once and apply the reading
+    pub fn poll(&self, monitor: &mut dyn PowerMonitor) -> Result<Option<PowerProfileChanged>, PowerError> {
+        let reading = monitor.read()?;
+        let change = {
+            let mut governor = self.governor.lock().unwrap();
+            let change = governor.observe(&reading);
+            self.rates.send_if_modified(|rates| replace_if_changed(rates, governor.rates()));
+            change
+        };
+        if let Some(change) = &change {
+            tracing::info!(
+                "Power profile {} -> {} ({}): {} FPS, emotion inference every {} frames{}",
+                change.from,
+                change.to,
+                change.reason,
+                change.rates.target_fps,
+                change.rates.emotion_interval,
+                if change.overridden { ", kept by override" } else { "" }
+            );
+            // No subscribers just means nobody is streaming right now
+            let _ = self.changes.send(change.clone());
+        }
+        Ok(change)
+    }
+
+    /// Replace the operator override, returning the rates now in effect
+    pub fn set_override(&self, overrides: RateOverride) -> ProfileRates {
+        let rates = {
+            let mut governor = self.governor.lock().unwrap();
+            governor.set_override(overrides);
+            governor.rates()
+        };
+        self.rates.send_if_modified(|current| replace_if_changed(current, rates));
+        tracing::info!(
+            "Rate override {:?}: {} FPS, emotion inference every {} frames",
+            overrides,
+            rates.target_fps,
+            rates.emotion_interval
+        );
+        rates
+    }
+
+    /// Poll `monitor` every poll interval until the pipeline stops
+    ///
+    /// A failed reading keeps the current profile and is retried with the next poll.
+    pub async fn run(self: Arc<Self>, mut monitor: Box<dyn PowerMonitor>, liveness: Arc<Liveness>) {
+        let mut ticks = tokio::time::interval(self.poll_interval);
+        ticks.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        let mut failing = false;
+        loop {
+            ticks.tick().await;
+            if liveness.check(Instant::now()) == Err(Unhealthy::Stopped) {
+                return;
+            }
+            match self.poll(monitor.as_mut()) {
+                Ok(_) => failing = false,
+                Err(e) if !failing => {
+                    tracing::warn!("Power governor keeps the {} profile: {}", self.profile(), e);
+                    failing = true;
+                }
+                Err(_) => {}
+            }
+        }
+    }
+}
+
+fn replace_if_changed(current: &mut ProfileRates, rates: ProfileRates) -> bool {
+    let changed = *current != rates;
+    *current = rates;
+    changed
+}
+
+/// Which frames run emotion inference, and the logits reused on the others
+#[derive(Debug, Clone, Default)]
+pub struct EmotionCadence {
+    /// Frames since emotion inference last ran
+    skipped: u32,
+    last: Option<EmotionLogits>,
+}
+
+impl EmotionCadence {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Logits to reuse for this frame, or `None` when inference is due
+    pub fn reuse(&mut self, interval: u32) -> Option<EmotionLogits> {
+        let last = self.last.as_ref()?;
+        if self.skipped + 1 >= interval {
+            return None;
+        }
+        self.skipped += 1;
+        Some(last.clone())
+    }
+
+    /// Note fresh logits from inference; `None` when it gave none to reuse
+    pub fn record(&mut self, logits: Option<&EmotionLogits>) {
+        self.skipped = 0;
+        self.last = logits.cloned();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PERFORMANCE: ProfileRates = ProfileRates { target_fps: 30.0, emotion_interval: 1 };
+
+    fn reading(on_battery: bool, charge: f32, temperature: f32) -> PowerReading {
+        PowerReading {
+            on_battery,
+            battery_charge: Some(charge),
+            temperature_c: Some(temperature),
+        }
+    }
+
+    #[test]
+    fn test_profiles_follow_supply_and_temperature() {
+        let mut governor = PowerGovernor::new(PowerConfig::default(), PERFORMANCE);
+        assert!(governor.observe(&reading(false, 1.0, 50.0)).is_none());
+        assert_eq!(governor.rates(), PERFORMANCE);
+
+        let change = governor.observe(&reading(true, 0.54, 50.0)).unwrap();
+        assert_eq!((change.from, change.to), (PowerProfile::Performance, PowerProfile::Balanced));
+        assert_eq!(change.reason, "on battery at 54%, 50°C");
+        assert_eq!(change.rates, PowerConfig::default().balanced);
+
+        assert_eq!(governor.observe(&reading(true, 0.1, 50.0)).unwrap().to, PowerProfile::Saver);
+        assert_eq!(governor.observe(&reading(false, 0.1, 50.0)).unwrap().to, PowerProfile::Performance);
+
+        // The hotter condition wins
+        assert_eq!(governor.observe(&reading(false, 1.0, 92.0)).unwrap().to, PowerProfile::Saver);
+        assert!(governor.observe(&reading(true, 0.9, 92.0)).is_none());
+
+        // No sensors, no conditions
+        let unknown = PowerReading::default();
+        assert_eq!(governor.observe(&unknown).unwrap().to, PowerProfile::Performance);
+    }
+
+    #[test]
+    fn test_temperature_hysteresis() {
+        let mut governor = PowerGovernor::new(PowerConfig::default(), PERFORMANCE);
+        let profiles: Vec<PowerProfile> = [79.9, 80.0, 79.0, 76.0, 80.5, 75.1, 75.0, 79.9, 90.0, 86.0, 85.0]
+            .into_iter()
+            .map(|temperature| {
+                governor.observe(&reading(false, 1.0, temperature));
+                governor.profile()
+            })
+            .collect();
+        use PowerProfile::*;
+        assert_eq!(
+            profiles,
+            [Performance, Balanced, Balanced, Balanced, Balanced, Balanced, Performance, Performance, Saver, Saver, Balanced]
+        );
+    }
+
+    #[test]
+    fn test_override_keeps_rates() {
+        let mut governor = PowerGovernor::new(PowerConfig::default(), PERFORMANCE);
+        governor.set_override(RateOverride { target_fps: Some(60.0), emotion_interval: None });
+        assert_eq!(governor.rates(), ProfileRates { target_fps: 60.0, emotion_interval: 1 });
+
+        let change = governor.observe(&reading(true, 0.1, 50.0)).unwrap();
+        assert!(change.overridden);
+        assert_eq!(change.rates, ProfileRates { target_fps: 60.0, emotion_interval: 3 });
+
+        governor.set_override(RateOverride::default());
+        assert_eq!(governor.rates(), PowerConfig::default().saver);
+    }
+
+    #[test]
+    fn test_emotion_cadence() {
+        let logits = EmotionLogits::from([0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 0.0]);
+        let mut cadence = EmotionCadence::new();
+        assert!(cadence.reuse(3).is_none(), "nothing to reuse yet");
+        cadence.record(Some(&logits));
+
+        let reused: Vec<bool> = (0..6)
+            .map(|_| match cadence.reuse(3) {
+                Some(reused) => {
+                    assert_eq!(reused, logits);
+                    true
+                }
+                None => {
+                    cadence.record(Some(&logits));
+                    false
+                }
+            })
+            .collect();
+        assert_eq!(reused, [true, true, false, true, true, false]);
+
+        // Every frame runs inference at interval 1, and a failed one leaves nothing to reuse
+        assert!(cadence.reuse(1).is_none());
+        cadence.record(None);
+        assert!(cadence.reuse(3).is_none());
+    }
+
+    #[test]
+    fn test_config_validation() {
+        assert!(PowerConfig::default().validate().is_ok());
+        let invalid = [
+            PowerConfig { poll_interval: Duration::ZERO, ..PowerConfig::default() },
+            PowerConfig { saver: ProfileRates { target_fps: 0.0, emotion_interval: 3 }, ..PowerConfig::default() },
+            PowerConfig { balanced: ProfileRates { target_fps: 20.0, emotion_interval: 0 }, ..PowerConfig::default() },
+            PowerConfig { low_battery: 1.5, ..PowerConfig::default() },
+            PowerConfig { warm_c: 95.0, ..PowerConfig::default() },
+            PowerConfig { battery_hysteresis: -0.1, ..PowerConfig::default() },
+        ];
+        for config in invalid {
+            assert!(config.validate().is_err(), "{:?}", config);
+        }
+    }
+}
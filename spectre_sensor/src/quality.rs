@@ -0,0 +1,417 @@
+//! Adaptive quality under sustained latency pressure
+//!
+//! On weak hardware the pipeline cannot hold 30 FPS with a 640x640 face
+//! detector input, and frames fall ever further behind. With
+//! `adaptive_quality` on, the processing loop hands every frame's inference
+//! latency to a [`QualityController`], which takes the p95 over the last
+//! `window` and holds it against the time a frame may take at the current
+//! rates: `emotion_interval / target_fps`. Over that budget for a whole
+//! window, the sensor steps one rung down a ladder of
+//!
+//! 1. smaller face detector inputs, 640 then 480 then 320 pixels, down to
+//!    `min_yunet_input_size`;
+//! 2. lower frame rates, halving down to `min_target_fps`;
+//! 3. emotion inference on fewer frames, up to `max_emotion_interval`.
+//!
+//! Once the p95 has stayed under `headroom` of the budget of the rung above
+//! for `recovery`, it steps back up. Every step is logged, shows in
+//! [`PerformanceMetrics::quality_level`](crate::types::PerformanceMetrics)
+//! and the `spectre_quality_level` gauge, and is sent as a
+//! `QUALITY_CHANGED` notice.
+//!
+//! The ladder caps the rates of the power governor and operator overrides
+//! rather than replacing them, so a governor already running slower keeps
+//! its rates.
+
+use crate::power::ProfileRates;
+use crate::types::{FaultLevel, SensorFaultNotice};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::time::Duration;
+
+/// Fault code: the sensor moved to another rung of the quality ladder
+pub const QUALITY_CHANGED: &str = "QUALITY_CHANGED";
+
+/// Face detector input sizes the ladder steps through, largest first
+const YUNET_INPUT_SIZES: [u32; 3] = [640, 480, 320];
+
+/// Fewest latencies a step is decided on
+const MIN_SAMPLES: usize = 5;
+
+/// Bounds and timing of the quality ladder
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct QualityConfig {
+    /// Smallest face detector input the ladder steps down to
+    pub min_yunet_input_size: u32,
+    /// Lowest frame rate the ladder steps down to
+    pub min_target_fps: f32,
+    /// Most frames one emotion inference may be reused for
+    pub max_emotion_interval: u32,
+    /// Latencies the p95 is taken over; pressure must last this long to step down
+    #[serde(with = "spectremesh_core::duration")]
+    pub window: Duration,
+    /// How long the headroom must last to step back up
+    #[serde(with = "spectremesh_core::duration")]
+    pub recovery: Duration,
+    /// Fraction of the budget of the rung above that the p95 must stay under
+    /// to step back up
+    pub headroom: f32,
+}
+
+impl Default for QualityConfig {
+    fn default() -> Self {
+        Self {
+            min_yunet_input_size: 320,
+            min_target_fps: 10.0,
+            max_emotion_interval: 3,
+            window: Duration::from_secs(2),
+            recovery: Duration::from_secs(5),
+            headroom: 0.6,
+        }
+    }
+}
+
+impl QualityConfig {
+    /// Validate the configuration
+    pub fn validate(&self) -> Result<(), String> {
+        let size = self.min_yunet_input_size;
+        if size < 32 || !size.is_multiple_of(32) {
+            return Err(format!("Minimum YuNet input size must be a positive multiple of 32, got {}", size));
+        }
+        if self.min_target_fps <= 0.0 {
+            return Err("Minimum target FPS must be positive".to_string());
+        }
+        if self.max_emotion_interval == 0 {
+            return Err("Maximum emotion interval must be at least 1".to_string());
+        }
+        if self.window.is_zero() || self.recovery.is_zero() {
+            return Err("Quality window and recovery period must be positive".to_string());
+        }
+        if !(self.headroom > 0.0 && self.headroom < 1.0) {
+            return Err(format!("Quality headroom must be in (0, 1), got {}", self.headroom));
+        }
+        Ok(())
+    }
+}
+
+/// What one rung of the ladder runs with
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct QualityRung {
+    /// Side of the square face detector input
+    pub yunet_input_size: u32,
+    /// Highest frame rate, if this rung lowers it
+    pub max_target_fps: Option<f32>,
+    /// Fewest frames one emotion inference covers
+    pub min_emotion_interval: u32,
+}
+
+impl QualityRung {
+    /// `rates` held to this rung
+    pub fn apply(&self, rates: ProfileRates) -> ProfileRates {
+        ProfileRates {
+            target_fps: self.max_target_fps.map_or(rates.target_fps, |max| rates.target_fps.min(max)),
+            emotion_interval: rates.emotion_interval.max(self.min_emotion_interval),
+        }
+    }
+}
+
+/// The sensor moved to another rung
+#[derive(Debug, Clone, PartialEq)]
+pub struct QualityChange {
+    pub from: u32,
+    pub to: u32,
+    /// What the sensor runs with from now on
+    pub rung: QualityRung,
+    /// Rates in effect from now on
+    pub rates: ProfileRates,
+    /// p95 inference latency that called for the step
+    pub p95_latency: Duration,
+    /// Frame time the p95 was held against: the budget of the rung left
+    /// when stepping down, of the rung returned to when stepping up
+    pub budget: Duration,
+}
+
+impl QualityChange {
+    /// Whether quality went down
+    pub fn is_step_down(&self) -> bool {
+        self.to > self.from
+    }
+
+    /// Informational notice announcing the change
+    pub fn notice(&self) -> SensorFaultNotice {
+        SensorFaultNotice::new(FaultLevel::Info, self.to_string(), QUALITY_CHANGED, true)
+    }
+}
+
+impl std::fmt::Display for QualityChange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Quality level {} -> {} (p95 inference latency {:.1} ms {} the {:.1} ms budget): face detector input {}, {} FPS, emotion inference every {} frames",
+            self.from,
+            self.to,
+            self.p95_latency.as_secs_f64() * 1000.0,
+            if self.is_step_down() { "over" } else { "under" },
+            self.budget.as_secs_f64() * 1000.0,
+            self.rung.yunet_input_size,
+            self.rates.target_fps,
+            self.rates.emotion_interval
+        )
+    }
+}
+
+/// Rung selection from recent inference latencies
+#[derive(Debug, Clone)]
+pub struct QualityController {
+    config: QualityConfig,
+    /// Full quality first
+    ladder: Vec<QualityRung>,
+    level: usize,
+    /// Monotonic time and latency of recent frames, oldest first
+    latencies: VecDeque<(Duration, Duration)>,
+    /// When the current rung started
+    since: Duration,
+}
+
+impl QualityController {
+    /// Controller at full quality: a `yunet_input_size` detector input at
+    /// `performance` rates
+    pub fn new(config: QualityConfig, yunet_input_size: u32, performance: ProfileRates, now: Duration) -> Self {
+        let ladder = Self::ladder(&config, yunet_input_size, performance);
+        Self { config, ladder, level: 0, latencies: VecDeque::new(), since: now }
+    }
+
+    /// Rungs from full quality down: detector inputs, then frame rates,
+    /// then emotion intervals, each rung keeping the reductions above it
+    fn ladder(config: &QualityConfig, yunet_input_size: u32, performance: ProfileRates) -> Vec<QualityRung> {
+        let mut rung = QualityRung { yunet_input_size, max_target_fps: None, min_emotion_interval: 1 };
+        let mut ladder = vec![rung];
+        for size in YUNET_INPUT_SIZES {
+            if size < rung.yunet_input_size && size >= config.min_yunet_input_size {
+                rung.yunet_input_size = size;
+                ladder.push(rung);
+            }
+        }
+        let mut fps = performance.target_fps;
+        while fps > config.min_target_fps {
+            fps = (fps / 2.0).max(config.min_target_fps);
+            rung.max_target_fps = Some(fps);
+            ladder.push(rung);
+        }
+        let mut interval = performance.emotion_interval;
+        while interval < config.max_emotion_interval {
+            interval += 1;
+            rung.min_emotion_interval = interval;
+            ladder.push(rung);
+        }
+        ladder
+    }
+
+    /// Current rung, 0 at full quality
+    pub fn level(&self) -> u32 {
+        self.level as u32
+    }
+
+    pub fn rung(&self) -> QualityRung {
+        self.ladder[self.level]
+    }
+
+    /// `rates` held to the current rung
+    pub fn apply(&self, rates: ProfileRates) -> ProfileRates {
+        self.rung().apply(rates)
+    }
+
+    /// Take in a frame scored in `latency` at `rates` (before the ladder
+    /// applies), returning the change of rung if there is one
+    pub fn observe(&mut self, now: Duration, latency: Duration, rates: ProfileRates) -> Option<QualityChange> {
+        self.latencies.push_back((now, latency));
+        let kept = self.config.window.max(self.config.recovery);
+        while self.latencies.front().is_some_and(|&(at, _)| now.saturating_sub(at) > kept) {
+            self.latencies.pop_front();
+        }
+
+        // Each rung is judged on latencies of its own, over a whole window
+        let on_rung = now.saturating_sub(self.since);
+        if on_rung < self.config.window {
+            return None;
+        }
+        let budget = frame_budget(self.ladder[self.level].apply(rates));
+        let p95 = self.p95(now, self.config.window)?;
+        if p95 > budget && self.level + 1 < self.ladder.len() {
+            return Some(self.step(self.level + 1, rates, p95, budget));
+        }
+        if self.level > 0 && on_rung >= self.config.recovery {
+            let above = frame_budget(self.ladder[self.level - 1].apply(rates));
+            let p95 = self.p95(now, self.config.recovery)?;
+            if p95.as_secs_f32() < above.as_secs_f32() * self.config.headroom {
+                return Some(self.step(self.level - 1, rates, p95, above));
+            }
+        }
+        None
+    }
+
+    /// p95 of the latencies within `period` of `now`, if there are enough
+    fn p95(&self, now: Duration, period: Duration) -> Option<Duration> {
+        let mut recent: Vec<Duration> = self
+            .latencies
+            .iter()
+            .filter(|&&(at, _)| now.saturating_sub(at) <= period)
+            .map(|&(_, latency)| latency)
+            .collect();
+        if recent.len() < MIN_SAMPLES {
+            return None;
+        }
+        recent.sort();
+        let index = ((recent.len() as f32 * 0.95) as usize).min(recent.len() - 1);
+        Some(recent[index])
+    }
+
+    fn step(&mut self, level: usize, rates: ProfileRates, p95_latency: Duration, budget: Duration) -> QualityChange {
+        let from = std::mem::replace(&mut self.level, level);
+        self.since = self.latencies.back().map_or(self.since, |&(at, _)| at);
+        self.latencies.clear();
+        let rung = self.rung();
+        QualityChange {
+            from: from as u32,
+            to: level as u32,
+            rung,
+            rates: rung.apply(rates),
+            p95_latency,
+            budget,
+        }
+    }
+}
+
+/// Time a frame may take at `rates`: inference runs once per emotion interval
+fn frame_budget(rates: ProfileRates) -> Duration {
+    Duration::from_secs_f32(rates.emotion_interval as f32 / rates.target_fps)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PERFORMANCE: ProfileRates = ProfileRates { target_fps: 30.0, emotion_interval: 1 };
+
+    fn controller() -> QualityController {
+        QualityController::new(QualityConfig::default(), 640, PERFORMANCE, Duration::ZERO)
+    }
+
+    /// Frames 50 ms apart, each scored in `latency`, from `start` for `period`
+    fn run(
+        controller: &mut QualityController,
+        start: Duration,
+        period: Duration,
+        latency: Duration,
+    ) -> Vec<QualityChange> {
+        let frames = (period.as_millis() / 50) as u32;
+        (1..=frames)
+            .filter_map(|frame| controller.observe(start + Duration::from_millis(50) * frame, latency, PERFORMANCE))
+            .collect()
+    }
+
+    #[test]
+    fn test_ladder() {
+        let rungs: Vec<(u32, Option<f32>, u32)> = controller()
+            .ladder
+            .iter()
+            .map(|rung| (rung.yunet_input_size, rung.max_target_fps, rung.min_emotion_interval))
+            .collect();
+        assert_eq!(
+            rungs,
+            [
+                (640, None, 1),
+                (480, None, 1),
+                (320, None, 1),
+                (320, Some(15.0), 1),
+                (320, Some(10.0), 1),
+                (320, Some(10.0), 2),
+                (320, Some(10.0), 3),
+            ]
+        );
+
+        // Bounds cut rungs off, and a smaller input starts further down
+        let config = QualityConfig { min_yunet_input_size: 480, min_target_fps: 15.0, max_emotion_interval: 1, ..QualityConfig::default() };
+        let ladder = QualityController::ladder(&config, 480, PERFORMANCE);
+        assert_eq!(ladder.len(), 2);
+        assert_eq!(ladder[1].apply(PERFORMANCE), ProfileRates { target_fps: 15.0, emotion_interval: 1 });
+
+        // A governor already slower than the cap keeps its rate
+        let saver = ProfileRates { target_fps: 10.0, emotion_interval: 3 };
+        assert_eq!(ladder[1].apply(saver), saver);
+
+        // Full quality leaves an override below the configured interval alone
+        let every_other = ProfileRates { target_fps: 30.0, emotion_interval: 2 };
+        let ladder = QualityController::ladder(&QualityConfig::default(), 640, every_other);
+        assert_eq!(ladder[0].apply(PERFORMANCE), PERFORMANCE);
+        assert_eq!(ladder.len(), 6);
+    }
+
+    #[test]
+    fn test_steps_down_under_pressure_and_back_up() {
+        let mut controller = controller();
+        // 33 ms a frame at 30 FPS; 25 ms fits
+        assert!(run(&mut controller, Duration::ZERO, Duration::from_secs(10), Duration::from_millis(25)).is_empty());
+
+        // 40 ms does not: one step per window, until 15 FPS gives 66 ms
+        let start = Duration::from_secs(10);
+        let changes = run(&mut controller, start, Duration::from_secs(10), Duration::from_millis(40));
+        let levels: Vec<(u32, u32)> = changes.iter().map(|change| (change.from, change.to)).collect();
+        assert_eq!(levels, [(0, 1), (1, 2), (2, 3)]);
+        assert!(changes.iter().all(QualityChange::is_step_down));
+        assert_eq!(changes[0].rung.yunet_input_size, 480);
+        assert_eq!(changes[2].rates, ProfileRates { target_fps: 15.0, emotion_interval: 1 });
+        assert_eq!(changes[0].budget.as_micros(), 33_333);
+
+        // Fast again: back up a rung per recovery period
+        let start = Duration::from_secs(20);
+        let changes = run(&mut controller, start, Duration::from_secs(20), Duration::from_millis(5));
+        let levels: Vec<(u32, u32)> = changes.iter().map(|change| (change.from, change.to)).collect();
+        assert_eq!(levels, [(3, 2), (2, 1), (1, 0)]);
+        assert_eq!(controller.apply(PERFORMANCE), PERFORMANCE);
+        assert_eq!(controller.rung().yunet_input_size, 640);
+
+        let notice = changes[0].notice();
+        assert_eq!((notice.severity, notice.error_code.as_str()), (FaultLevel::Info, QUALITY_CHANGED));
+        assert!(notice.message.starts_with("Quality level 3 -> 2"), "{}", notice.message);
+    }
+
+    #[test]
+    fn test_no_step_up_without_headroom() {
+        let mut controller = controller();
+        run(&mut controller, Duration::ZERO, Duration::from_secs(2), Duration::from_millis(40));
+        assert_eq!(controller.level(), 1);
+
+        // 25 ms fits at 480 but is not well under the 33 ms budget of 640
+        run(&mut controller, Duration::from_secs(2), Duration::from_secs(20), Duration::from_millis(25));
+        assert_eq!(controller.level(), 1);
+    }
+
+    #[test]
+    fn test_brief_spikes_are_ignored() {
+        let mut controller = controller();
+        let mut now = Duration::ZERO;
+        for frame in 0..400 {
+            now += Duration::from_millis(50);
+            // One frame in 50 is slow, under the p95
+            let latency = if frame % 50 == 0 { Duration::from_millis(200) } else { Duration::from_millis(10) };
+            assert!(controller.observe(now, latency, PERFORMANCE).is_none());
+        }
+    }
+
+    #[test]
+    fn test_config_validation() {
+        assert!(QualityConfig::default().validate().is_ok());
+        let invalid = [
+            QualityConfig { min_yunet_input_size: 300, ..QualityConfig::default() },
+            QualityConfig { min_target_fps: 0.0, ..QualityConfig::default() },
+            QualityConfig { max_emotion_interval: 0, ..QualityConfig::default() },
+            QualityConfig { window: Duration::ZERO, ..QualityConfig::default() },
+            QualityConfig { headroom: 1.0, ..QualityConfig::default() },
+        ];
+        for config in invalid {
+            assert!(config.validate().is_err(), "{:?}", config);
+        }
+    }
+}
@@ -0,0 +1,241 @@
+//! Runtime reconfiguration without restarting the daemon
+//!
+//! `Configure` changes several settings at once. The frame rate is held
+//! through the power governor's override, as with `UpdateConfig`, and the
+//! calibration freeze goes through calibration control, so both keep their
+//! own rules. The camera and the face detector belong to the processing
+//! loop, so changes to them are queued through a [`Reconfigurer`] and
+//! applied between two frames: the face confidence threshold from the next
+//! frame on, a camera id by opening the new camera before the current one is
+//! released, so a camera that fails to open leaves capture where it was.
+//! The ONNX Runtime thread count is fixed when the sessions are built; a
+//! request for another is reported as needing a restart and changes nothing.
+//!
+//! Every value is checked before anything is applied, and a freeze the
+//! calibration phase does not allow is rejected up front, so a rejected
+//! request changes nothing.
+
+use std::ops::RangeInclusive;
+use thiserror::Error;
+use tokio::sync::{mpsc, oneshot};
+
+/// Frame rates an operator may set, through `Configure` or `UpdateConfig`
+pub const TARGET_FPS_RANGE: RangeInclusive<f32> = 1.0..=120.0;
+
+/// Field reported when a different ONNX Runtime thread count is requested
+pub const ONNX_THREADS: &str = "onnx_threads";
+
+/// Settings to change; `None` keeps the current value
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct ConfigChange {
+    pub camera_id: Option<u32>,
+    pub target_fps: Option<f32>,
+    pub face_confidence_threshold: Option<f32>,
+    /// Freeze (`true`) or unfreeze (`false`) the calibration baseline
+    pub freeze_calibration: Option<bool>,
+    pub onnx_threads: Option<usize>,
+}
+
+impl ConfigChange {
+    /// Check every requested value is in range
+    pub fn validate(&self) -> Result<(), ReconfigureError> {
+        if let Some(fps) = self.target_fps.filter(|fps| !TARGET_FPS_RANGE.contains(fps)) {
+            return Err(ReconfigureError::Invalid(format!(
+                "Target FPS must be between {} and {}, got {}",
+                TARGET_FPS_RANGE.start(),
+                TARGET_FPS_RANGE.end(),
+                fps
+            )));
+        }
+        if let Some(threshold) = self.face_confidence_threshold.filter(|t| !(*t > 0.0 && *t < 1.0)) {
+            return Err(ReconfigureError::Invalid(format!(
+                "Face confidence threshold must be in (0, 1), got {}",
+                threshold
+            )));
+        }
+        if self.onnx_threads == Some(0) {
+            return Err(ReconfigureError::Invalid("ONNX threads must be at least 1".to_string()));
+        }
+        Ok(())
+    }
+
+    /// The part of the change the processing loop applies
+    pub fn pipeline(&self) -> PipelineChange {
+        PipelineChange {
+            camera_id: self.camera_id,
+            face_confidence_threshold: self.face_confidence_threshold,
+        }
+    }
+
+    /// Requested fields that only a restart would apply
+    pub fn restart_required(&self, current: &EffectiveConfig) -> Vec<&'static str> {
+        let mut fields = Vec::new();
+        if self.onnx_threads.is_some_and(|threads| threads != current.onnx_threads) {
+            fields.push(ONNX_THREADS);
+        }
+        fields
+    }
+}
+
+/// Settings in effect
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct EffectiveConfig {
+    pub camera_id: u32,
+    pub target_fps: f32,
+    pub face_confidence_threshold: f32,
+    pub calibration_frozen: bool,
+    pub onnx_threads: usize,
+}
+
+/// Outcome of a change
+#[derive(Debug, Clone, PartialEq)]
+pub struct Reconfigured {
+    /// Settings in effect afterwards
+    pub config: EffectiveConfig,
+    /// Requested fields left for a restart
+    pub restart_required: Vec<&'static str>,
+}
+
+/// Why a change was not applied
+#[derive(Debug, Clone, PartialEq, Error)]
+pub enum ReconfigureError {
+    #[error("{0}")]
+    Invalid(String),
+
+    #[error("Camera {camera_id} could not be opened, capture continues unchanged: {reason}")]
+    CameraUnavailable { camera_id: u32, reason: String },
+
+    #[error("Sensor stopped before the change was applied")]
+    Interrupted,
+}
+
+/// Changes the processing loop applies between frames
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct PipelineChange {
+    pub camera_id: Option<u32>,
+    pub face_confidence_threshold: Option<f32>,
+}
+
+impl PipelineChange {
+    /// Whether there is anything to apply
+    pub fn is_empty(&self) -> bool {
+        self.camera_id.is_none() && self.face_confidence_threshold.is_none()
+    }
+}
+
+struct ChangeRequest {
+    change: PipelineChange,
+    applied: oneshot::Sender<Result<(), ReconfigureError>>,
+}
+
+/// Hands changes to a running processing loop
+#[derive(Clone)]
+pub struct Reconfigurer {
+    requests: mpsc::UnboundedSender<ChangeRequest>,
+}
+
+/// The processing loop's end of a [`Reconfigurer`]
+pub struct ReconfigureInbox {
+    requests: mpsc::UnboundedReceiver<ChangeRequest>,
+}
+
+/// Connect a reconfigurer to the inbox of a processing loop
+pub fn reconfigure_channel() -> (Reconfigurer, ReconfigureInbox) {
+    let (requests, receiver) = mpsc::unbounded_channel();
+    (Reconfigurer { requests }, ReconfigureInbox { requests: receiver })
+}
+
+impl Reconfigurer {
+    /// Queue `change` and wait for the loop to apply it
+    pub async fn send(&self, change: PipelineChange) -> Result<(), ReconfigureError> {
+        let (applied, done) = oneshot::channel();
+        self.requests
+            .send(ChangeRequest { change, applied })
+            .map_err(|_| ReconfigureError::Interrupted)?;
+        done.await.map_err(|_| ReconfigureError::Interrupted)?
+    }
+}
+
+impl ReconfigureInbox {
+    /// Apply every queued change, in order, with `apply`
+    ///
+    /// Called by the processing loop between frames. Returns how many
+    /// changes were applied; `apply` must leave the pipeline untouched when
+    /// it fails.
+    pub fn apply_pending(&mut self, mut apply: impl FnMut(PipelineChange) -> Result<(), ReconfigureError>) -> usize {
+        let mut applied = 0;
+        while let Ok(ChangeRequest { change, applied: reply }) = self.requests.try_recv() {
+            let result = apply(change);
+            match &result {
+                Ok(()) => {
+                    applied += 1;
+                    tracing::info!("Reconfigured: {:?}", change);
+                }
+                Err(e) => tracing::warn!("{}", e),
+            }
+            // The caller may have given up waiting
+            let _ = reply.send(result);
+        }
+        applied
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CURRENT: EffectiveConfig = EffectiveConfig {
+        camera_id: 0,
+        target_fps: 30.0,
+        face_confidence_threshold: 0.6,
+        calibration_frozen: false,
+        onnx_threads: 4,
+    };
+
+    #[test]
+    fn test_values_are_checked() {
+        assert!(ConfigChange::default().validate().is_ok());
+        for change in [
+            ConfigChange { target_fps: Some(0.5), ..Default::default() },
+            ConfigChange { target_fps: Some(240.0), ..Default::default() },
+            ConfigChange { face_confidence_threshold: Some(1.0), ..Default::default() },
+            ConfigChange { face_confidence_threshold: Some(f32::NAN), ..Default::default() },
+            ConfigChange { onnx_threads: Some(0), ..Default::default() },
+        ] {
+            assert!(matches!(change.validate(), Err(ReconfigureError::Invalid(_))), "{:?}", change);
+        }
+    }
+
+    #[test]
+    fn test_onnx_threads_need_a_restart() {
+        let same = ConfigChange { onnx_threads: Some(4), target_fps: Some(10.0), ..Default::default() };
+        assert!(same.restart_required(&CURRENT).is_empty());
+        let more = ConfigChange { onnx_threads: Some(8), ..Default::default() };
+        assert_eq!(more.restart_required(&CURRENT), [ONNX_THREADS]);
+        assert!(more.pipeline().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_changes_are_applied_by_the_loop() {
+        let (reconfigurer, mut inbox) = reconfigure_channel();
+        let change = PipelineChange { camera_id: Some(2), face_confidence_threshold: None };
+        let sent = tokio::spawn({
+            let reconfigurer = reconfigurer.clone();
+            async move { reconfigurer.send(change).await }
+        });
+
+        let mut seen = Vec::new();
+        while seen.is_empty() {
+            tokio::task::yield_now().await;
+            inbox.apply_pending(|change| {
+                seen.push(change);
+                Err(ReconfigureError::CameraUnavailable { camera_id: 2, reason: "busy".to_string() })
+            });
+        }
+        assert_eq!(seen, [change]);
+        assert!(matches!(sent.await.unwrap(), Err(ReconfigureError::CameraUnavailable { .. })));
+
+        drop(inbox);
+        assert_eq!(reconfigurer.send(change).await, Err(ReconfigureError::Interrupted));
+    }
+}
@@ -0,0 +1,333 @@
+//! Camera hot-unplug detection and reconnection for the capture loop
+//!
+//! An unplugged webcam does not raise an error; its reads just start
+//! failing. Once reads have kept failing for a while the camera is treated
+//! as disconnected: clients get a recoverable `CAMERA_DISCONNECTED` error,
+//! and the camera is reopened with exponential backoff until a frame comes
+//! through again. Clients then get `CAMERA_RECONNECTED` and frames resume on
+//! the same streams.
+
+use crate::{
+    resume::FrameSource,
+    sensor::SensorError,
+    types::{FaultLevel, SensorFaultNotice},
+};
+use opencv::{core::Mat, prelude::*};
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant};
+use tokio::sync::broadcast;
+
+/// Error code of the fault emitted when the camera stops delivering frames
+pub const CAMERA_DISCONNECTED: &str = "CAMERA_DISCONNECTED";
+/// Error code of the fault emitted once the camera delivers frames again
+pub const CAMERA_RECONNECTED: &str = "CAMERA_RECONNECTED";
+
+/// Longest the reconnection loop sleeps before checking for a stop
+const STOP_POLL: Duration = Duration::from_millis(100);
+
+/// Camera disconnection and reconnection settings
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReconnectConfig {
+    /// How long reads must keep failing before the camera counts as disconnected
+    #[serde(with = "spectremesh_core::duration")]
+    pub disconnect_after: Duration,
+    /// Consecutive failed reads also needed, so one slow read is not an unplug
+    pub min_failed_reads: u32,
+    /// Delay before the first reconnection attempt, doubling after each failure
+    #[serde(with = "spectremesh_core::duration")]
+    pub initial_backoff: Duration,
+    /// Longest delay between reconnection attempts
+    #[serde(with = "spectremesh_core::duration")]
+    pub max_backoff: Duration,
+    /// Attempts before the sensor gives up and stops; 0 retries forever
+    pub max_attempts: u32,
+    /// Keep the baseline across a reconnect rather than calibrating again
+    pub preserve_calibration: bool,
+}
+
+impl Default for ReconnectConfig {
+    fn default() -> Self {
+        Self {
+            disconnect_after: Duration::from_secs(2),
+            min_failed_reads: 10,
+            initial_backoff: Duration::from_millis(250),
+            max_backoff: Duration::from_secs(8),
+            max_attempts: 10,
+            preserve_calibration: true,
+        }
+    }
+}
+
+impl ReconnectConfig {
+    /// Validate the configuration
+    pub fn validate(&self) -> Result<(), String> {
+        if self.disconnect_after.is_zero() || self.initial_backoff.is_zero() {
+            return Err("Camera disconnect threshold and reconnect backoff must be positive".to_string());
+        }
+        if self.min_failed_reads == 0 {
+            return Err("Camera disconnect needs at least one failed read".to_string());
+        }
+        if self.max_backoff < self.initial_backoff {
+            return Err("Camera reconnect max backoff must be at least the initial backoff".to_string());
+        }
+        Ok(())
+    }
+
+    /// Delay before reconnection attempt `attempt`, counting from 1
+    pub fn backoff(&self, attempt: u32) -> Duration {
+        let doublings = attempt.saturating_sub(1).min(31);
+        self.initial_backoff.saturating_mul(1 << doublings).min(self.max_backoff)
+    }
+}
+
+/// How a reconnection ended
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReconnectOutcome {
+    /// The camera delivers frames again
+    Reconnected { attempts: u32, down_for: Duration },
+    /// The sensor was stopped while reconnecting
+    Stopped,
+    /// Every attempt failed
+    GaveUp { attempts: u32 },
+}
+
+/// Watches capture for a disconnected camera and brings it back
+pub struct CaptureWatchdog {
+    config: ReconnectConfig,
+    faults: broadcast::Sender<SensorFaultNotice>,
+    failed_reads: u32,
+    /// Monotonic time of the first read in the current failing run
+    failing_since: Option<Duration>,
+}
+
+impl CaptureWatchdog {
+    pub fn new(config: ReconnectConfig, faults: broadcast::Sender<SensorFaultNotice>) -> Self {
+        Self { config, faults, failed_reads: 0, failing_since: None }
+    }
+
+    /// Whether the calibration is kept across a reconnect
+    pub fn preserves_calibration(&self) -> bool {
+        self.config.preserve_calibration
+    }
+
+    /// Record a successful read
+    pub fn record_frame(&mut self) {
+        self.failed_reads = 0;
+        self.failing_since = None;
+    }
+
+    /// Record a failed read at monotonic time `now`; true once the camera
+    /// counts as disconnected, which also reports the disconnection
+    pub fn record_failure(&mut self, now: Duration) -> bool {
+        self.failed_reads += 1;
+        let since = *self.failing_since.get_or_insert(now);
+        if self.failed_reads < self.config.min_failed_reads || now.saturating_sub(since) < self.config.disconnect_after {
+            return false;
+        }
+
+        tracing::warn!("Camera delivered no frames for {} reads, reconnecting", self.failed_reads);
+        let _ = self.faults.send(SensorFaultNotice::new(
+            FaultLevel::Error,
+            format!(
+                "Camera stopped delivering frames ({} failed reads over {:.1}s); reconnecting",
+                self.failed_reads,
+                now.saturating_sub(since).as_secs_f32()
+            ),
+            CAMERA_DISCONNECTED,
+            true,
+        ));
+        self.record_frame();
+        true
+    }
+
+    /// Reopen `source` with backoff until it delivers a frame, `is_running`
+    /// turns false or the attempts run out
+    pub async fn reconnect<S: FrameSource>(
+        &mut self,
+        source: &mut S,
+        is_running: impl Fn() -> bool,
+    ) -> ReconnectOutcome {
+        let started = Instant::now();
+        let mut frame = Mat::default();
+        let mut attempt = 0;
+        loop {
+            attempt += 1;
+            if !sleep_while(self.config.backoff(attempt), &is_running).await {
+                return ReconnectOutcome::Stopped;
+            }
+
+            match source.reopen() {
+                Ok(()) if source.read_frame(&mut frame) && !frame.empty() => {
+                    let down_for = started.elapsed();
+                    tracing::info!("Camera reconnected after {} attempts ({:?})", attempt, down_for);
+                    let _ = self.faults.send(SensorFaultNotice::new(
+                        FaultLevel::Info,
+                        format!("Camera reconnected after {} attempts ({:.1}s)", attempt, down_for.as_secs_f32()),
+                        CAMERA_RECONNECTED,
+                        true,
+                    ));
+                    return ReconnectOutcome::Reconnected { attempts: attempt, down_for };
+                }
+                Ok(()) => tracing::debug!("Camera reopened but delivered no frame (attempt {})", attempt),
+                Err(e) => tracing::debug!("Camera reconnection attempt {} failed: {}", attempt, e),
+            }
+
+            if self.config.max_attempts != 0 && attempt >= self.config.max_attempts {
+                tracing::error!("Camera did not come back after {} attempts", attempt);
+                return ReconnectOutcome::GaveUp { attempts: attempt };
+            }
+        }
+    }
+}
+
+/// Sleep for `delay` unless `is_running` turns false first; whether it stayed true
+async fn sleep_while(delay: Duration, is_running: &impl Fn() -> bool) -> bool {
+    let deadline = Instant::now() + delay;
+    loop {
+        if !is_running() {
+            return false;
+        }
+        let left = deadline.saturating_duration_since(Instant::now());
+        if left.is_zero() {
+            return true;
+        }
+        tokio::time::sleep(left.min(STOP_POLL)).await;
+    }
+}
+
+/// Error the processing loop stops with once reconnection gives up
+pub fn gave_up_error(attempts: u32) -> SensorError {
+    SensorError::CameraInit(format!("Camera disconnected and did not come back after {} attempts", attempts))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use opencv::core::{Scalar, CV_8UC1};
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    /// Delivers `frames` frames, then nothing until reopened `unplugged_for` times
+    struct FlakyCapture {
+        frames: usize,
+        unplugged_for: usize,
+        reopens: usize,
+    }
+
+    impl FrameSource for FlakyCapture {
+        fn read_frame(&mut self, frame: &mut Mat) -> bool {
+            if self.frames == 0 && self.reopens < self.unplugged_for {
+                return false;
+            }
+            self.frames = self.frames.saturating_sub(1);
+            *frame = Mat::new_rows_cols_with_default(4, 4, CV_8UC1, Scalar::all(0.0)).unwrap();
+            true
+        }
+
+        fn reopen(&mut self) -> Result<(), SensorError> {
+            self.reopens += 1;
+            if self.reopens < self.unplugged_for {
+                return Err(SensorError::CameraInit("no such device".to_string()));
+            }
+            Ok(())
+        }
+    }
+
+    fn fast() -> ReconnectConfig {
+        ReconnectConfig {
+            disconnect_after: Duration::from_millis(100),
+            min_failed_reads: 3,
+            initial_backoff: Duration::from_millis(1),
+            max_backoff: Duration::from_millis(4),
+            max_attempts: 5,
+            preserve_calibration: true,
+        }
+    }
+
+    #[test]
+    fn test_backoff_doubles_up_to_the_limit() {
+        let config = ReconnectConfig::default();
+        assert_eq!(config.backoff(1), Duration::from_millis(250));
+        assert_eq!(config.backoff(2), Duration::from_millis(500));
+        assert_eq!(config.backoff(4), Duration::from_secs(2));
+        assert_eq!(config.backoff(6), Duration::from_secs(8));
+        assert_eq!(config.backoff(u32::MAX), Duration::from_secs(8));
+        assert!(config.validate().is_ok());
+        assert!(ReconnectConfig { min_failed_reads: 0, ..fast() }.validate().is_err());
+        assert!(ReconnectConfig { max_backoff: Duration::ZERO, ..fast() }.validate().is_err());
+    }
+
+    #[test]
+    fn test_disconnect_needs_sustained_failures() {
+        let (faults, mut receiver) = broadcast::channel(4);
+        let mut watchdog = CaptureWatchdog::new(fast(), faults);
+        let ms = Duration::from_millis;
+
+        // Enough reads, too short
+        assert!(!watchdog.record_failure(ms(0)));
+        assert!(!watchdog.record_failure(ms(10)));
+        assert!(!watchdog.record_failure(ms(20)));
+        // A frame starts the run over
+        watchdog.record_frame();
+        // Long enough, too few reads
+        assert!(!watchdog.record_failure(ms(200)));
+        assert!(!watchdog.record_failure(ms(400)));
+        assert!(receiver.try_recv().is_err());
+
+        assert!(watchdog.record_failure(ms(500)));
+        let fault = receiver.try_recv().unwrap();
+        assert_eq!(fault.error_code, CAMERA_DISCONNECTED);
+        assert_eq!(fault.severity, FaultLevel::Error);
+        assert!(fault.recoverable);
+    }
+
+    #[tokio::test]
+    async fn test_reconnects_after_unplug() {
+        let (faults, mut receiver) = broadcast::channel(8);
+        let mut watchdog = CaptureWatchdog::new(fast(), faults);
+        let mut capture = FlakyCapture { frames: 5, unplugged_for: 3, reopens: 0 };
+        let mut frame = Mat::default();
+
+        // Frames flow, then the camera is pulled
+        for _ in 0..5 {
+            assert!(capture.read_frame(&mut frame));
+            watchdog.record_frame();
+        }
+        let mut now = Duration::ZERO;
+        while capture.read_frame(&mut frame) || !watchdog.record_failure(now) {
+            now += Duration::from_millis(33);
+        }
+        assert_eq!(receiver.try_recv().unwrap().error_code, CAMERA_DISCONNECTED);
+
+        // Two failed reopens, then it is back
+        let outcome = watchdog.reconnect(&mut capture, || true).await;
+        assert!(matches!(outcome, ReconnectOutcome::Reconnected { attempts: 3, .. }), "{:?}", outcome);
+        let fault = receiver.try_recv().unwrap();
+        assert_eq!(fault.error_code, CAMERA_RECONNECTED);
+        assert_eq!(fault.severity, FaultLevel::Info);
+        assert!(capture.read_frame(&mut frame));
+    }
+
+    #[tokio::test]
+    async fn test_reconnect_gives_up_or_stops() {
+        let (faults, mut receiver) = broadcast::channel(8);
+        let mut watchdog = CaptureWatchdog::new(fast(), faults);
+
+        let mut gone = FlakyCapture { frames: 0, unplugged_for: usize::MAX, reopens: 0 };
+        assert_eq!(watchdog.reconnect(&mut gone, || true).await, ReconnectOutcome::GaveUp { attempts: 5 });
+        assert_eq!(gone.reopens, 5);
+        assert!(receiver.try_recv().is_err());
+
+        // A stop ends the attempts early
+        let running = AtomicBool::new(true);
+        let mut gone = FlakyCapture { frames: 0, unplugged_for: usize::MAX, reopens: 0 };
+        let outcome = watchdog
+            .reconnect(&mut gone, || {
+                let still = running.load(Ordering::Relaxed);
+                running.store(false, Ordering::Relaxed);
+                still
+            })
+            .await;
+        assert_eq!(outcome, ReconnectOutcome::Stopped);
+        assert!(gone.reopens <= 1);
+    }
+}
@@ -0,0 +1,334 @@
+//! Session recording for deterministic replay
+//!
+//! Tuning terrain response against a live webcam cannot be repeated. With
+//! `SensorConfig::record_path` set, the processing loop writes every frame it
+//! publishes to a JSONL file, and `ReplayFearSensor` plays the file back
+//! through the `FearSensor` trait with the recorded timing:
+//!
+//! ```text
+//! {"type":"recording","version":1,...}   RecordingHeader: versions, layout, camera, config
+//! {"type":"score","timestamp_us":...}    one FrameRecord per frame, oldest first
+//! ```
+//!
+//! Frame lines are the `score` lines of bug report bundles. Playback follows
+//! the frames' own capture times rather than a frame rate, so files recorded
+//! at any rate, or across a rate change, replay as they were captured. A
+//! file cut short by a crash loses at most its last, partial line.
+
+use crate::{
+    bug_report::FrameRecord,
+    camera_format::CameraFormat,
+    config::SensorConfig,
+    types::FearFrame,
+};
+use serde::{Deserialize, Serialize};
+use spectremesh_core::{EmotionLayout, LogitsError};
+use std::fs::{self, File};
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use thiserror::Error;
+
+/// Format version written to new recordings
+pub const RECORDING_VERSION: u32 = 1;
+
+/// Frames written between flushes, about a second at 30 FPS
+const FLUSH_EVERY: u64 = 30;
+
+/// Recording errors
+#[derive(Debug, Error)]
+pub enum RecordingError {
+    #[error("Failed to access recording '{path}': {message}")]
+    Io { path: String, message: String },
+
+    #[error("Invalid recording line {line}: {message}")]
+    Parse { line: usize, message: String },
+
+    #[error("Recording does not start with a header")]
+    MissingHeader,
+
+    #[error("Recording version {found} is newer than the supported version {supported}")]
+    UnsupportedVersion { found: u32, supported: u32 },
+
+    #[error("Recording contains no frames")]
+    Empty,
+
+    #[error("Frame {index} does not fit the recorded emotion layout: {source}")]
+    Logits { index: usize, source: LogitsError },
+
+    #[error("Emotion layout changed from {recorded:?} to {found:?} during the recording")]
+    LayoutChanged { recorded: EmotionLayout, found: EmotionLayout },
+}
+
+/// First line of a recording
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename = "recording")]
+pub struct RecordingHeader {
+    /// Format version, [`RECORDING_VERSION`] when written
+    pub version: u32,
+    /// Version of the sensor crate that wrote the file
+    pub sensor_version: String,
+    /// Wall-clock time recording started, in microseconds since Unix epoch
+    pub started_at_us: u64,
+    /// Layout of every frame's emotion logits
+    pub emotion_layout: EmotionLayout,
+    /// Format the camera negotiated, when known
+    #[serde(default)]
+    pub camera: Option<CameraFormat>,
+    /// Configuration of the recording sensor
+    pub config: SensorConfig,
+}
+
+impl RecordingHeader {
+    /// Header for a recording starting now with `config`
+    pub fn new(config: &SensorConfig, camera: Option<CameraFormat>) -> Self {
+        Self {
+            version: RECORDING_VERSION,
+            sensor_version: env!("CARGO_PKG_VERSION").to_string(),
+            started_at_us: SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_micros() as u64,
+            emotion_layout: config.emotion_layout,
+            camera,
+            config: config.clone(),
+        }
+    }
+}
+
+/// Writes frames to a recording file
+///
+/// Lines are buffered and flushed about once a second and on drop.
+pub struct FrameRecorder {
+    path: PathBuf,
+    writer: BufWriter<File>,
+    layout: EmotionLayout,
+    frames: u64,
+}
+
+impl FrameRecorder {
+    /// Create (or truncate) `path` and write `header` to it
+    pub fn create(path: impl AsRef<Path>, header: &RecordingHeader) -> Result<Self, RecordingError> {
+        let path = path.as_ref();
+        if let Some(parent) = path.parent().filter(|parent| !parent.as_os_str().is_empty()) {
+            fs::create_dir_all(parent).map_err(|e| io_error(path, e))?;
+        }
+        let file = File::create(path).map_err(|e| io_error(path, e))?;
+        let mut recorder = Self {
+            path: path.to_path_buf(),
+            writer: BufWriter::new(file),
+            layout: header.emotion_layout,
+            frames: 0,
+        };
+        recorder.write_line(header)?;
+        recorder.flush()?;
+        Ok(recorder)
+    }
+
+    /// Append `frame`
+    ///
+    /// Fails if the frame's logits are in another layout than the header's,
+    /// since replay could not read them back.
+    pub fn record(&mut self, frame: &FearFrame) -> Result<(), RecordingError> {
+        let found = frame.emotion_logits.layout();
+        if found != self.layout {
+            return Err(RecordingError::LayoutChanged { recorded: self.layout, found });
+        }
+        self.write_line(&FrameRecord::new(frame, frame.timestamp_us()))?;
+        self.frames += 1;
+        if self.frames.is_multiple_of(FLUSH_EVERY) {
+            self.flush()?;
+        }
+        Ok(())
+    }
+
+    /// Write buffered lines to the file
+    pub fn flush(&mut self) -> Result<(), RecordingError> {
+        self.writer.flush().map_err(|e| io_error(&self.path, e))
+    }
+
+    /// Frames recorded so far
+    pub fn frames(&self) -> u64 {
+        self.frames
+    }
+
+    /// File being written
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    fn write_line(&mut self, line: &impl Serialize) -> Result<(), RecordingError> {
+        serde_json::to_writer(&mut self.writer, line).map_err(|e| io_error(&self.path, e))?;
+        self.writer.write_all(b"\n").map_err(|e| io_error(&self.path, e))
+    }
+}
+
+impl Drop for FrameRecorder {
+    fn drop(&mut self) {
+        if let Err(e) = self.flush() {
+            tracing::warn!("{}", e);
+        }
+    }
+}
+
+/// A recording read back from disk
+#[derive(Debug, Clone)]
+pub struct Recording {
+    pub header: RecordingHeader,
+    /// Frames in the order they were recorded
+    pub frames: Vec<FrameRecord>,
+}
+
+impl Recording {
+    /// Load a recording from disk
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, RecordingError> {
+        let path = path.as_ref();
+        let content = fs::read_to_string(path).map_err(|e| io_error(path, e))?;
+        Self::from_jsonl(&content)
+    }
+
+    /// Parse a recording
+    ///
+    /// A last line that does not parse is taken to be cut short and is
+    /// dropped; anywhere else it is an error.
+    pub fn from_jsonl(content: &str) -> Result<Self, RecordingError> {
+        let mut lines = content.lines().enumerate().filter(|(_, line)| !line.trim().is_empty()).peekable();
+        let (_, first) = lines.next().ok_or(RecordingError::MissingHeader)?;
+        let header: RecordingHeader = serde_json::from_str(first).map_err(|_| RecordingError::MissingHeader)?;
+        if header.version > RECORDING_VERSION {
+            return Err(RecordingError::UnsupportedVersion { found: header.version, supported: RECORDING_VERSION });
+        }
+
+        let mut frames = Vec::new();
+        while let Some((index, line)) = lines.next() {
+            match serde_json::from_str(line) {
+                Ok(frame) => frames.push(frame),
+                Err(e) if lines.peek().is_none() => {
+                    tracing::warn!("Ignoring truncated last line {} of recording: {}", index + 1, e);
+                }
+                Err(e) => return Err(RecordingError::Parse { line: index + 1, message: e.to_string() }),
+            }
+        }
+        Ok(Self { header, frames })
+    }
+
+    /// Rebuild every frame, checking each fits the recorded layout
+    pub fn to_frames(&self) -> Result<Vec<FearFrame>, RecordingError> {
+        if self.frames.is_empty() {
+            return Err(RecordingError::Empty);
+        }
+        self.frames
+            .iter()
+            .enumerate()
+            .map(|(index, record)| {
+                record.to_frame(self.header.emotion_layout).map_err(|source| RecordingError::Logits { index, source })
+            })
+            .collect()
+    }
+
+    /// Time of each frame after the first
+    ///
+    /// Capture times are wall-clock, so a clock stepped back during the
+    /// session would send them backwards; such frames keep the previous
+    /// frame's time.
+    pub fn offsets(&self) -> Vec<Duration> {
+        let origin = self.frames.first().map_or(0, |frame| frame.timestamp_us);
+        let mut latest = 0;
+        self.frames
+            .iter()
+            .map(|frame| {
+                latest = latest.max(frame.timestamp_us.saturating_sub(origin));
+                Duration::from_micros(latest)
+            })
+            .collect()
+    }
+
+    /// Time from the first frame to the last
+    pub fn duration(&self) -> Duration {
+        self.offsets().last().copied().unwrap_or_default()
+    }
+
+    /// Typical time between frames: the median spacing, or the configured
+    /// frame rate's when there are fewer than two frames
+    pub fn frame_interval(&self) -> Duration {
+        let offsets = self.offsets();
+        let mut intervals: Vec<Duration> = offsets.windows(2).map(|pair| pair[1] - pair[0]).collect();
+        if intervals.is_empty() {
+            return Duration::from_secs_f32(1.0 / self.header.config.target_fps.max(1.0));
+        }
+        intervals.sort_unstable();
+        intervals[intervals.len() / 2]
+    }
+}
+
+fn io_error(path: &Path, error: impl std::fmt::Display) -> RecordingError {
+    RecordingError::Io {
+        path: path.display().to_string(),
+        message: error.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn scratch_file(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("spectre_recording_{}_{}.jsonl", name, std::process::id()))
+    }
+
+    fn frame(fear: f32, timestamp_us: u64, sequence: u64) -> FearFrame {
+        FearFrame::new(fear, [0.0, 0.0, fear, 0.0, 0.0, 0.0, 0.0], 0.9, sequence > 2, Duration::from_millis(4))
+            .with_capture_time(UNIX_EPOCH + Duration::from_micros(timestamp_us))
+            .with_sequence(sequence)
+    }
+
+    #[test]
+    fn test_frames_round_trip() {
+        let path = scratch_file("round_trip");
+        let header = RecordingHeader::new(&SensorConfig::default(), None);
+        let frames = [frame(0.2, 1_000_000, 1), frame(0.4, 1_033_000, 2), frame(0.6, 1_066_000, 3).with_face_present(false)];
+        {
+            let mut recorder = FrameRecorder::create(&path, &header).unwrap();
+            for frame in &frames {
+                recorder.record(frame).unwrap();
+            }
+            assert_eq!(recorder.frames(), 3);
+        }
+
+        let recording = Recording::load(&path).unwrap();
+        assert_eq!(recording.header.version, RECORDING_VERSION);
+        let replayed = recording.to_frames().unwrap();
+        for (original, replayed) in frames.iter().zip(&replayed) {
+            assert_eq!(FrameRecord::new(replayed, replayed.timestamp_us()), FrameRecord::new(original, original.timestamp_us()));
+        }
+        assert_eq!(recording.offsets(), [Duration::ZERO, Duration::from_millis(33), Duration::from_millis(66)]);
+        assert_eq!(recording.frame_interval(), Duration::from_millis(33));
+
+        // Another model's frames cannot join the file
+        let mut recorder = FrameRecorder::create(&path, &header).unwrap();
+        let wide = FearFrame::new(0.5, spectremesh_core::EmotionLogits::zeros(EmotionLayout::new(10, 2).unwrap()), 0.9, true, Duration::ZERO);
+        assert!(matches!(recorder.record(&wide), Err(RecordingError::LayoutChanged { .. })));
+        let _ = fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_damaged_recordings() {
+        let header = serde_json::to_string(&RecordingHeader::new(&SensorConfig::default(), None)).unwrap();
+        let line = |fear: f32, timestamp_us: u64| serde_json::to_string(&FrameRecord::new(&frame(fear, timestamp_us, 1), timestamp_us)).unwrap();
+
+        // Cut short mid-line by a crash
+        let truncated = format!("{}\n{}\n{}\n{{\"type\":\"score\",\"timest", header, line(0.2, 10), line(0.3, 20));
+        assert_eq!(Recording::from_jsonl(&truncated).unwrap().frames.len(), 2);
+        let corrupt = format!("{}\nnot json\n{}\n", header, line(0.2, 10));
+        assert!(matches!(Recording::from_jsonl(&corrupt), Err(RecordingError::Parse { line: 2, .. })));
+
+        assert!(matches!(Recording::from_jsonl(&line(0.2, 10)), Err(RecordingError::MissingHeader)));
+        let future = header.replace("\"version\":1", "\"version\":99");
+        assert!(matches!(Recording::from_jsonl(&future), Err(RecordingError::UnsupportedVersion { found: 99, .. })));
+        assert!(matches!(Recording::from_jsonl(&header).unwrap().to_frames(), Err(RecordingError::Empty)));
+
+        // A clock stepped back holds the frame at the previous time
+        let stepped = format!("{}\n{}\n{}\n{}\n", header, line(0.2, 1_000), line(0.3, 51_000), line(0.4, 31_000));
+        let recording = Recording::from_jsonl(&stepped).unwrap();
+        assert_eq!(recording.offsets(), [Duration::ZERO, Duration::from_millis(50), Duration::from_millis(50)]);
+        assert_eq!(recording.duration(), Duration::from_millis(50));
+    }
+}
@@ -0,0 +1,478 @@
+//! System sleep/resume detection for the capture loop
+//!
+//! When a laptop suspends, the monotonic clock may or may not advance
+//! depending on the platform, while the wall clock always does. A large
+//! divergence between the two, or a single gap far beyond the frame period,
+//! is treated as a resume: the camera is reopened, the pacing and FPS window
+//! are reset, drift evaluation is skipped for one window and clients get an
+//! informational `SYSTEM_RESUMED` fault explaining the gap.
+
+use crate::{
+    calibrator::AdaptiveCalibrator,
+    sensor::{SensorError, SensorState},
+    types::{FaultLevel, SensorFaultNotice},
+};
+use opencv::core::Mat;
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+use std::time::{Duration, Instant, SystemTime};
+use tokio::sync::broadcast;
+
+/// Error code carried by the fault emitted after a resume
+pub const SYSTEM_RESUMED: &str = "SYSTEM_RESUMED";
+
+/// Source of monotonic and wall-clock time
+pub trait Clock: Send + Sync {
+    /// Monotonic time since an arbitrary origin
+    fn monotonic(&self) -> Duration;
+    /// Current wall-clock time
+    fn wall(&self) -> SystemTime;
+}
+
+/// Clock backed by `Instant` and `SystemTime`
+#[derive(Debug, Clone)]
+pub struct SystemClock {
+    origin: Instant,
+}
+
+impl SystemClock {
+    /// Create a clock whose monotonic origin is now
+    pub fn new() -> Self {
+        Self { origin: Instant::now() }
+    }
+}
+
+impl Default for SystemClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Clock for SystemClock {
+    fn monotonic(&self) -> Duration {
+        self.origin.elapsed()
+    }
+
+    fn wall(&self) -> SystemTime {
+        SystemTime::now()
+    }
+}
+
+/// Manually driven clock for tests
+#[derive(Debug)]
+pub struct ManualClock {
+    now: Mutex<(Duration, SystemTime)>,
+}
+
+impl ManualClock {
+    /// Create a clock starting at the given wall time
+    pub fn new(wall: SystemTime) -> Self {
+        Self { now: Mutex::new((Duration::ZERO, wall)) }
+    }
+
+    /// Advance both clocks, as during normal operation
+    pub fn advance(&self, by: Duration) {
+        let mut now = self.now.lock().unwrap();
+        now.0 += by;
+        now.1 += by;
+    }
+
+    /// Advance only the wall clock, as during a suspend that pauses the monotonic clock
+    pub fn suspend(&self, by: Duration) {
+        self.now.lock().unwrap().1 += by;
+    }
+}
+
+impl Clock for ManualClock {
+    fn monotonic(&self) -> Duration {
+        self.now.lock().unwrap().0
+    }
+
+    fn wall(&self) -> SystemTime {
+        self.now.lock().unwrap().1
+    }
+}
+
+/// Sleep/resume handling configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResumeConfig {
+    /// Monotonic gap between loop iterations treated as a resume
+    #[serde(with = "spectremesh_core::duration")]
+    pub gap_threshold: Duration,
+    /// Wall-clock minus monotonic divergence treated as a resume
+    #[serde(with = "spectremesh_core::duration")]
+    pub divergence_threshold: Duration,
+    /// Restart the calibration warm-up after a resume
+    pub recalibrate: bool,
+}
+
+impl Default for ResumeConfig {
+    fn default() -> Self {
+        Self {
+            gap_threshold: Duration::from_secs(3),
+            divergence_threshold: Duration::from_secs(3),
+            recalibrate: false,
+        }
+    }
+}
+
+impl ResumeConfig {
+    /// Validate the configuration
+    pub fn validate(&self) -> Result<(), String> {
+        if self.gap_threshold.is_zero() || self.divergence_threshold.is_zero() {
+            return Err("Resume thresholds must be positive".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// A detected resume from system sleep
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ResumeEvent {
+    /// Best estimate of how long the system was suspended
+    pub gap: Duration,
+}
+
+/// Detects suspend gaps by comparing consecutive clock readings
+#[derive(Debug, Clone)]
+pub struct ResumeDetector {
+    last_monotonic: Duration,
+    last_wall: SystemTime,
+    gap_threshold: Duration,
+    divergence_threshold: Duration,
+}
+
+impl ResumeDetector {
+    /// Create a detector primed with the current clock reading
+    pub fn new(config: &ResumeConfig, clock: &dyn Clock) -> Self {
+        Self {
+            last_monotonic: clock.monotonic(),
+            last_wall: clock.wall(),
+            gap_threshold: config.gap_threshold,
+            divergence_threshold: config.divergence_threshold,
+        }
+    }
+
+    /// Take a clock reading and report a resume if one happened since the last check
+    pub fn check(&mut self, clock: &dyn Clock) -> Option<ResumeEvent> {
+        let monotonic = clock.monotonic();
+        let wall = clock.wall();
+
+        let monotonic_delta = monotonic.saturating_sub(self.last_monotonic);
+        // A wall clock stepping backwards (NTP) is not a suspend
+        let wall_delta = wall.duration_since(self.last_wall).unwrap_or(Duration::ZERO);
+
+        self.last_monotonic = monotonic;
+        self.last_wall = wall;
+
+        if wall_delta.saturating_sub(monotonic_delta) >= self.divergence_threshold {
+            Some(ResumeEvent { gap: wall_delta })
+        } else if monotonic_delta >= self.gap_threshold {
+            Some(ResumeEvent { gap: monotonic_delta })
+        } else {
+            None
+        }
+    }
+}
+
+/// A camera-like source of frames that can be reopened
+pub trait FrameSource {
+    /// Read the next frame, returning false if none was available
+    fn read_frame(&mut self, frame: &mut Mat) -> bool;
+    /// Close and reopen the underlying device
+    fn reopen(&mut self) -> Result<(), SensorError>;
+}
+
+/// Frame count for the current FPS window
+#[derive(Debug, Clone)]
+pub struct MetricsWindow {
+    /// Monotonic time the window started
+    pub started: Duration,
+    /// Frames processed in this window
+    pub frame_count: u64,
+}
+
+impl MetricsWindow {
+    /// Start a new window at `now`
+    pub fn new(now: Duration) -> Self {
+        Self {
+            started: now,
+            frame_count: 0,
+        }
+    }
+
+    /// Time covered by this window
+    pub fn elapsed(&self, now: Duration) -> Duration {
+        now.saturating_sub(self.started)
+    }
+
+    /// Discard the current window and start a new one at `now`
+    pub fn reset(&mut self, now: Duration) {
+        self.started = now;
+        self.frame_count = 0;
+    }
+}
+
+/// Time accounting for a sensor session
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SessionSummary {
+    /// Number of resumes from system sleep
+    pub suspend_count: u32,
+    /// Total time spent suspended
+    pub suspended: Duration,
+    /// Total time spent capturing and processing frames
+    pub processing: Duration,
+    /// Whether captured frames are flipped horizontally, once the camera is open
+    pub mirrored: Option<bool>,
+}
+
+/// What the guard did in response to a resume
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ResumeOutcome {
+    pub reopened: bool,
+    pub recalibrated: bool,
+}
+
+/// Applies the resume policy to the capture loop
+pub struct ResumeGuard {
+    detector: ResumeDetector,
+    config: ResumeConfig,
+    faults: broadcast::Sender<SensorFaultNotice>,
+    suppress_drift: bool,
+}
+
+impl ResumeGuard {
+    /// Create a guard primed with the current clock reading
+    pub fn new(
+        config: ResumeConfig,
+        clock: &dyn Clock,
+        faults: broadcast::Sender<SensorFaultNotice>,
+    ) -> Self {
+        Self {
+            detector: ResumeDetector::new(&config, clock),
+            config,
+            faults,
+            suppress_drift: false,
+        }
+    }
+
+    /// Check the clock for a resume since the previous iteration
+    pub fn poll(&mut self, clock: &dyn Clock) -> Option<ResumeEvent> {
+        self.detector.check(clock)
+    }
+
+    /// Restart detection from now after a deliberate pause, such as a camera
+    /// reconnect, so the pause is not taken for a resume
+    pub fn rearm(&mut self, clock: &dyn Clock) {
+        self.detector = ResumeDetector::new(&self.config, clock);
+        self.suppress_drift = true;
+    }
+
+    /// Recover the capture loop after a resume
+    pub fn handle<S: FrameSource>(
+        &mut self,
+        event: ResumeEvent,
+        source: &mut S,
+        calibrator: &mut AdaptiveCalibrator,
+        window: &mut MetricsWindow,
+        state: &Mutex<SensorState>,
+        now: Duration,
+    ) -> ResumeOutcome {
+        tracing::info!("System resume detected after {:?}, recovering capture loop", event.gap);
+
+        // Stale frames are common after resume until the device is reopened
+        let reopened = match source.reopen() {
+            Ok(()) => true,
+            Err(e) => {
+                tracing::warn!("Failed to reopen camera after resume: {}", e);
+                false
+            }
+        };
+
+        if self.config.recalibrate {
+            calibrator.reset();
+        }
+
+        window.reset(now);
+        self.suppress_drift = true;
+
+        {
+            let mut state_guard = state.lock().unwrap();
+            state_guard.session.suspend_count += 1;
+            state_guard.session.suspended += event.gap;
+            state_guard.metrics.current_fps = 0.0;
+            // Latencies from before the sleep say nothing about the camera now
+            state_guard.metrics.inference_latency.reset_window();
+        }
+
+        let _ = self.faults.send(SensorFaultNotice::new(
+            FaultLevel::Info,
+            format!("System resumed after {:.1}s suspend", event.gap.as_secs_f32()),
+            SYSTEM_RESUMED,
+            true,
+        ));
+
+        ResumeOutcome {
+            reopened,
+            recalibrated: self.config.recalibrate,
+        }
+    }
+
+    /// Calibration drift for the window just finished, or zero if suppressed by a resume
+    pub fn take_drift(&mut self, calibrator: &mut AdaptiveCalibrator) -> f32 {
+        // Always advance the drift reference so the next window starts clean
+        let drift = calibrator.calculate_drift();
+        if std::mem::take(&mut self.suppress_drift) {
+            0.0
+        } else {
+            drift
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct MockCapture {
+        reopen_count: usize,
+    }
+
+    impl FrameSource for MockCapture {
+        fn read_frame(&mut self, _frame: &mut Mat) -> bool {
+            true
+        }
+
+        fn reopen(&mut self) -> Result<(), SensorError> {
+            self.reopen_count += 1;
+            Ok(())
+        }
+    }
+
+    fn guard(clock: &ManualClock) -> (ResumeGuard, broadcast::Receiver<SensorFaultNotice>) {
+        let (faults, receiver) = broadcast::channel(4);
+        (ResumeGuard::new(ResumeConfig::default(), clock, faults), receiver)
+    }
+
+    #[test]
+    fn test_normal_frames_are_not_resumes() {
+        let clock = ManualClock::new(SystemTime::UNIX_EPOCH + Duration::from_secs(1_000));
+        let mut detector = ResumeDetector::new(&ResumeConfig::default(), &clock);
+
+        for _ in 0..100 {
+            clock.advance(Duration::from_millis(33));
+            assert_eq!(detector.check(&clock), None);
+        }
+    }
+
+    #[test]
+    fn test_detects_wall_clock_divergence_and_long_gaps() {
+        let clock = ManualClock::new(SystemTime::UNIX_EPOCH + Duration::from_secs(1_000));
+        let mut detector = ResumeDetector::new(&ResumeConfig::default(), &clock);
+
+        // Monotonic clock paused during suspend
+        clock.suspend(Duration::from_secs(600));
+        clock.advance(Duration::from_millis(33));
+        let event = detector.check(&clock).unwrap();
+        assert_eq!(event.gap, Duration::from_secs(600) + Duration::from_millis(33));
+
+        // Monotonic clock kept counting during suspend
+        clock.advance(Duration::from_secs(120));
+        assert_eq!(detector.check(&clock).unwrap().gap, Duration::from_secs(120));
+    }
+
+    #[test]
+    fn test_resume_recovers_capture_loop() {
+        let clock = ManualClock::new(SystemTime::UNIX_EPOCH + Duration::from_secs(1_000));
+        let (mut guard, mut faults) = guard(&clock);
+        let mut capture = MockCapture { reopen_count: 0 };
+        let mut calibrator = AdaptiveCalibrator::with_defaults(Duration::from_secs(30));
+        let state = Mutex::new(SensorState::default());
+
+        let mut window = MetricsWindow::new(clock.monotonic());
+        window.frame_count = 12;
+        {
+            let mut state_guard = state.lock().unwrap();
+            state_guard.metrics.current_fps = 30.0;
+            state_guard.metrics.record_inference_latency(Duration::from_millis(5));
+        }
+
+        clock.suspend(Duration::from_secs(900));
+        let event = guard.poll(&clock).unwrap();
+        let outcome = guard.handle(event, &mut capture, &mut calibrator, &mut window, &state, clock.monotonic());
+
+        assert!(outcome.reopened);
+        assert!(!outcome.recalibrated);
+        assert_eq!(capture.reopen_count, 1);
+
+        // Metrics window restarted at the resume point
+        assert_eq!(window.frame_count, 0);
+        assert_eq!(window.started, clock.monotonic());
+
+        // Suspend time recorded separately from processing time
+        {
+            let state = state.lock().unwrap();
+            assert_eq!(state.session.suspend_count, 1);
+            assert_eq!(state.session.suspended, Duration::from_secs(900));
+            assert_eq!(state.session.processing, Duration::ZERO);
+            assert_eq!(state.metrics.current_fps, 0.0);
+            assert!(state.metrics.inference_latency.window().is_empty());
+            assert_eq!(state.metrics.inference_latency.session().count(), 1);
+        }
+
+        // Clients are told why there was a gap
+        let fault = faults.try_recv().unwrap();
+        assert_eq!(fault.error_code, SYSTEM_RESUMED);
+        assert_eq!(fault.severity, FaultLevel::Info);
+        assert!(fault.recoverable);
+    }
+
+    #[test]
+    fn test_drift_suppressed_for_one_window() {
+        let clock = ManualClock::new(SystemTime::UNIX_EPOCH + Duration::from_secs(1_000));
+        let (mut guard, _faults) = guard(&clock);
+        let mut capture = MockCapture { reopen_count: 0 };
+        let mut calibrator = AdaptiveCalibrator::new(Duration::ZERO, 0.5);
+        let state = Mutex::new(SensorState::default());
+        let mut window = MetricsWindow::new(clock.monotonic());
+
+        for _ in 0..40 {
+            calibrator.add_sample(1.0).unwrap();
+        }
+
+        clock.suspend(Duration::from_secs(60));
+        let event = guard.poll(&clock).unwrap();
+        guard.handle(event, &mut capture, &mut calibrator, &mut window, &state, clock.monotonic());
+
+        // The baseline moves across the resume, but that window reports no drift
+        calibrator.add_sample(3.0).unwrap();
+        assert_eq!(guard.take_drift(&mut calibrator), 0.0);
+
+        calibrator.add_sample(3.0).unwrap();
+        assert!(guard.take_drift(&mut calibrator) > 0.0);
+    }
+
+    #[test]
+    fn test_recalibrate_on_resume() {
+        let clock = ManualClock::new(SystemTime::UNIX_EPOCH);
+        let (faults, _receiver) = broadcast::channel(4);
+        let config = ResumeConfig { recalibrate: true, ..Default::default() };
+        let mut guard = ResumeGuard::new(config, &clock, faults);
+        let mut capture = MockCapture { reopen_count: 0 };
+        let mut calibrator = AdaptiveCalibrator::new(Duration::ZERO, 0.05);
+        let state = Mutex::new(SensorState::default());
+        let mut window = MetricsWindow::new(clock.monotonic());
+
+        for _ in 0..40 {
+            calibrator.add_sample(0.5).unwrap();
+        }
+        assert!(calibrator.is_calibrated());
+
+        let outcome = guard.handle(
+            ResumeEvent { gap: Duration::from_secs(10) },
+            &mut capture, &mut calibrator, &mut window, &state, clock.monotonic(),
+        );
+        assert!(outcome.recalibrated);
+        assert!(!calibrator.is_calibrated());
+    }
+}
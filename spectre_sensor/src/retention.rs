@@ -0,0 +1,484 @@
+//! Privacy retention: automatic purging of recordings, profiles and session logs
+//!
+//! Venues with data-protection obligations configure a retention period per
+//! category. The daemon sweeps the configured directories on startup and then
+//! periodically, deleting files older than their category's retention. A file's
+//! age comes from the `created_us` field of its first JSON line when present
+//! (our own file header) and from its modification time otherwise.
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io::{BufRead, BufReader, Read};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::task::JoinHandle;
+
+/// Header field carrying a file's creation time in microseconds since Unix epoch
+pub const CREATED_US_FIELD: &str = "created_us";
+
+/// Longest header line inspected for an embedded creation time
+const MAX_HEADER_BYTES: u64 = 4096;
+
+const SECS_PER_DAY: u64 = 24 * 60 * 60;
+
+/// Retention settings per data category
+///
+/// A category without a retention period is kept indefinitely.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RetentionConfig {
+    /// Days to keep recordings and debug captures
+    pub recordings_days: Option<u32>,
+    /// Days to keep calibration profiles
+    pub profiles_days: Option<u32>,
+    /// Days to keep session logs
+    pub session_logs_days: Option<u32>,
+    /// Directories holding recordings and debug captures
+    pub recordings_dirs: Vec<PathBuf>,
+    /// Directories holding calibration profiles
+    pub profiles_dirs: Vec<PathBuf>,
+    /// Directories holding session logs
+    pub session_logs_dirs: Vec<PathBuf>,
+    /// Time between background sweeps
+    #[serde(with = "spectremesh_core::duration")]
+    pub sweep_interval: Duration,
+    /// Report what would be deleted without deleting anything
+    pub dry_run: bool,
+}
+
+impl Default for RetentionConfig {
+    fn default() -> Self {
+        Self {
+            recordings_days: None,
+            profiles_days: None,
+            session_logs_days: None,
+            recordings_dirs: Vec::new(),
+            profiles_dirs: Vec::new(),
+            session_logs_dirs: Vec::new(),
+            sweep_interval: Duration::from_secs(60 * 60),
+            dry_run: false,
+        }
+    }
+}
+
+impl RetentionConfig {
+    /// Validate the configuration
+    pub fn validate(&self) -> Result<(), String> {
+        if self.sweep_interval.is_zero() {
+            return Err("Retention sweep interval must be positive".to_string());
+        }
+        Ok(())
+    }
+
+    /// Whether any category has both a retention period and a directory
+    pub fn is_enabled(&self) -> bool {
+        RetentionCategory::ALL
+            .iter()
+            .any(|category| self.retention(*category).is_some() && !self.dirs(*category).is_empty())
+    }
+
+    /// Retention period for a category
+    pub fn retention(&self, category: RetentionCategory) -> Option<Duration> {
+        let days = match category {
+            RetentionCategory::Recordings => self.recordings_days,
+            RetentionCategory::Profiles => self.profiles_days,
+            RetentionCategory::SessionLogs => self.session_logs_days,
+        };
+        days.map(|days| Duration::from_secs(days as u64 * SECS_PER_DAY))
+    }
+
+    /// Directories scanned for a category
+    pub fn dirs(&self, category: RetentionCategory) -> &[PathBuf] {
+        match category {
+            RetentionCategory::Recordings => &self.recordings_dirs,
+            RetentionCategory::Profiles => &self.profiles_dirs,
+            RetentionCategory::SessionLogs => &self.session_logs_dirs,
+        }
+    }
+}
+
+/// Kind of data subject to retention
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RetentionCategory {
+    Recordings,
+    Profiles,
+    SessionLogs,
+}
+
+impl RetentionCategory {
+    /// All categories in sweep order
+    pub const ALL: [RetentionCategory; 3] = [
+        RetentionCategory::Recordings,
+        RetentionCategory::Profiles,
+        RetentionCategory::SessionLogs,
+    ];
+
+    /// Stable name used in logs and rpc reports
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RetentionCategory::Recordings => "recordings",
+            RetentionCategory::Profiles => "profiles",
+            RetentionCategory::SessionLogs => "session_logs",
+        }
+    }
+}
+
+/// A file removed (or selected for removal in dry-run mode) by a sweep
+#[derive(Debug, Clone, PartialEq)]
+pub struct PurgedFile {
+    pub path: PathBuf,
+    pub category: RetentionCategory,
+    pub age: Duration,
+}
+
+/// A file or directory a sweep could not process
+#[derive(Debug, Clone, PartialEq)]
+pub struct PurgeFailure {
+    pub path: PathBuf,
+    pub category: RetentionCategory,
+    pub error: String,
+}
+
+/// Result of a single sweep
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PurgeReport {
+    /// Whether files were only reported rather than deleted
+    pub dry_run: bool,
+    /// Number of files inspected
+    pub scanned: usize,
+    /// Files past their retention period
+    pub purged: Vec<PurgedFile>,
+    /// Files or directories that could not be inspected or deleted
+    pub failures: Vec<PurgeFailure>,
+}
+
+impl PurgeReport {
+    /// Number of purged files in a category
+    pub fn purged_in(&self, category: RetentionCategory) -> usize {
+        self.purged.iter().filter(|file| file.category == category).count()
+    }
+}
+
+/// Running totals reported in `StatusResponse`
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PurgeTotals {
+    pub recordings_purged: u64,
+    pub profiles_purged: u64,
+    pub session_logs_purged: u64,
+    pub failures: u64,
+    /// Completion time of the last sweep in microseconds since Unix epoch
+    pub last_sweep_us: Option<u64>,
+}
+
+impl PurgeTotals {
+    fn record(&mut self, report: &PurgeReport, finished: SystemTime) {
+        if !report.dry_run {
+            self.recordings_purged += report.purged_in(RetentionCategory::Recordings) as u64;
+            self.profiles_purged += report.purged_in(RetentionCategory::Profiles) as u64;
+            self.session_logs_purged += report.purged_in(RetentionCategory::SessionLogs) as u64;
+        }
+        self.failures += report.failures.len() as u64;
+        self.last_sweep_us = Some(
+            finished.duration_since(UNIX_EPOCH).unwrap_or_default().as_micros() as u64,
+        );
+    }
+}
+
+/// Applies a [`RetentionConfig`] to the filesystem
+#[derive(Debug)]
+pub struct RetentionManager {
+    config: RetentionConfig,
+    totals: Mutex<PurgeTotals>,
+}
+
+impl RetentionManager {
+    /// Create a manager for the given settings
+    pub fn new(config: RetentionConfig) -> Self {
+        Self {
+            config,
+            totals: Mutex::new(PurgeTotals::default()),
+        }
+    }
+
+    /// Retention settings in use
+    pub fn config(&self) -> &RetentionConfig {
+        &self.config
+    }
+
+    /// Totals accumulated over all sweeps so far
+    pub fn totals(&self) -> PurgeTotals {
+        self.totals.lock().unwrap().clone()
+    }
+
+    /// Sweep now using the configured dry-run setting
+    pub fn sweep(&self) -> PurgeReport {
+        self.sweep_at(SystemTime::now(), self.config.dry_run)
+    }
+
+    /// Sweep as of `now`, deleting files unless `dry_run` is set
+    pub fn sweep_at(&self, now: SystemTime, dry_run: bool) -> PurgeReport {
+        let mut report = PurgeReport {
+            dry_run,
+            ..PurgeReport::default()
+        };
+
+        for category in RetentionCategory::ALL {
+            let Some(retention) = self.config.retention(category) else {
+                continue;
+            };
+            for dir in self.config.dirs(category) {
+                sweep_dir(dir, category, retention, now, &mut report);
+            }
+        }
+
+        self.totals.lock().unwrap().record(&report, now);
+        log_report(&report);
+        report
+    }
+
+    /// Sweep immediately and then every `sweep_interval` on a background task
+    pub fn spawn(self: Arc<Self>) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(self.config.sweep_interval);
+            loop {
+                interval.tick().await;
+                let manager = Arc::clone(&self);
+                if let Err(e) = tokio::task::spawn_blocking(move || manager.sweep()).await {
+                    tracing::error!("Retention sweep panicked: {}", e);
+                }
+            }
+        })
+    }
+}
+
+/// Walk `dir` recursively and purge files older than `retention`
+fn sweep_dir(
+    dir: &Path,
+    category: RetentionCategory,
+    retention: Duration,
+    now: SystemTime,
+    report: &mut PurgeReport,
+) {
+    if !dir.exists() {
+        tracing::debug!("Retention directory {} does not exist", dir.display());
+        return;
+    }
+
+    let mut pending = vec![dir.to_path_buf()];
+    while let Some(current) = pending.pop() {
+        let entries = match fs::read_dir(&current) {
+            Ok(entries) => entries,
+            Err(e) => {
+                report.failures.push(PurgeFailure { path: current, category, error: e.to_string() });
+                continue;
+            }
+        };
+
+        for entry in entries {
+            let path = match entry {
+                Ok(entry) => entry.path(),
+                Err(e) => {
+                    report.failures.push(PurgeFailure {
+                        path: current.clone(),
+                        category,
+                        error: e.to_string(),
+                    });
+                    continue;
+                }
+            };
+
+            let metadata = match fs::symlink_metadata(&path) {
+                Ok(metadata) => metadata,
+                Err(e) => {
+                    report.failures.push(PurgeFailure { path, category, error: e.to_string() });
+                    continue;
+                }
+            };
+            if metadata.is_dir() {
+                pending.push(path);
+                continue;
+            }
+            if !metadata.is_file() {
+                continue;
+            }
+
+            report.scanned += 1;
+            let created = embedded_creation_time(&path)
+                .or_else(|| metadata.modified().ok())
+                .unwrap_or(now);
+            let age = now.duration_since(created).unwrap_or_default();
+            if age < retention {
+                continue;
+            }
+
+            if !report.dry_run {
+                if let Err(e) = fs::remove_file(&path) {
+                    report.failures.push(PurgeFailure { path, category, error: e.to_string() });
+                    continue;
+                }
+            }
+            report.purged.push(PurgedFile { path, category, age });
+        }
+    }
+}
+
+/// Read the creation time from a file's JSON header line, if it has one
+pub fn embedded_creation_time(path: &Path) -> Option<SystemTime> {
+    let file = fs::File::open(path).ok()?;
+    let mut line = String::new();
+    BufReader::new(file.take(MAX_HEADER_BYTES)).read_line(&mut line).ok()?;
+
+    let header: serde_json::Value = serde_json::from_str(line.trim()).ok()?;
+    let created_us = header.get(CREATED_US_FIELD)?.as_u64()?;
+    Some(UNIX_EPOCH + Duration::from_micros(created_us))
+}
+
+/// Header line recording `created` for files written by the sensor
+pub fn creation_header(created: SystemTime) -> String {
+    let created_us = created.duration_since(UNIX_EPOCH).unwrap_or_default().as_micros() as u64;
+    serde_json::json!({ CREATED_US_FIELD: created_us }).to_string()
+}
+
+/// Log a summary of a sweep, with each failure on its own line
+fn log_report(report: &PurgeReport) {
+    let action = if report.dry_run { "would purge" } else { "purged" };
+    tracing::info!(
+        "Retention sweep {} {} recordings, {} profiles, {} session logs ({} files scanned)",
+        action,
+        report.purged_in(RetentionCategory::Recordings),
+        report.purged_in(RetentionCategory::Profiles),
+        report.purged_in(RetentionCategory::SessionLogs),
+        report.scanned,
+    );
+    for failure in &report.failures {
+        tracing::warn!(
+            "Retention sweep failed on {} ({}): {}",
+            failure.path.display(),
+            failure.category.as_str(),
+            failure.error
+        );
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use std::io::Write;
+
+    const DAY: Duration = Duration::from_secs(SECS_PER_DAY);
+
+    /// Fresh scratch directory under the system temp dir
+    pub(crate) fn scratch_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("spectre_retention_{}_{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    /// Write a file whose modification time is `age` in the past
+    pub(crate) fn aged_file(path: &Path, age: Duration) {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        let file = fs::File::create(path).unwrap();
+        file.set_modified(SystemTime::now() - age).unwrap();
+    }
+
+    /// Retention config with 7/30/14 day periods over three subdirectories of `root`
+    pub(crate) fn config_for(root: &Path) -> RetentionConfig {
+        RetentionConfig {
+            recordings_days: Some(7),
+            profiles_days: Some(30),
+            session_logs_days: Some(14),
+            recordings_dirs: vec![root.join("recordings")],
+            profiles_dirs: vec![root.join("profiles")],
+            session_logs_dirs: vec![root.join("logs")],
+            ..RetentionConfig::default()
+        }
+    }
+
+    #[test]
+    fn test_selective_deletion_by_category() {
+        let root = scratch_dir("selective");
+        aged_file(&root.join("recordings/old.jsonl"), DAY * 10);
+        aged_file(&root.join("recordings/nested/old.jsonl"), DAY * 8);
+        aged_file(&root.join("recordings/new.jsonl"), DAY * 2);
+        aged_file(&root.join("profiles/baseline.json"), DAY * 10);
+        aged_file(&root.join("logs/old.log"), DAY * 20);
+
+        let manager = RetentionManager::new(config_for(&root));
+        let report = manager.sweep();
+
+        assert!(report.failures.is_empty());
+        assert_eq!(report.scanned, 5);
+        assert_eq!(report.purged_in(RetentionCategory::Recordings), 2);
+        assert_eq!(report.purged_in(RetentionCategory::Profiles), 0);
+        assert_eq!(report.purged_in(RetentionCategory::SessionLogs), 1);
+
+        assert!(!root.join("recordings/old.jsonl").exists());
+        assert!(!root.join("recordings/nested/old.jsonl").exists());
+        assert!(root.join("recordings/new.jsonl").exists());
+        assert!(root.join("profiles/baseline.json").exists());
+        assert!(!root.join("logs/old.log").exists());
+
+        let totals = manager.totals();
+        assert_eq!(totals.recordings_purged, 2);
+        assert_eq!(totals.session_logs_purged, 1);
+        assert!(totals.last_sweep_us.is_some());
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn test_dry_run_keeps_files() {
+        let root = scratch_dir("dry_run");
+        aged_file(&root.join("recordings/old.jsonl"), DAY * 10);
+
+        let manager = RetentionManager::new(RetentionConfig { dry_run: true, ..config_for(&root) });
+        let report = manager.sweep();
+
+        assert!(report.dry_run);
+        assert_eq!(report.purged.len(), 1);
+        assert!(root.join("recordings/old.jsonl").exists());
+        assert_eq!(manager.totals().recordings_purged, 0);
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn test_embedded_creation_time_overrides_mtime() {
+        let root = scratch_dir("header");
+        let path = root.join("recordings/copied.jsonl");
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        let mut file = fs::File::create(&path).unwrap();
+        writeln!(file, "{}", creation_header(SystemTime::now() - DAY * 9)).unwrap();
+        drop(file);
+
+        assert!(embedded_creation_time(&path).is_some());
+        let report = RetentionManager::new(config_for(&root)).sweep();
+        assert_eq!(report.purged_in(RetentionCategory::Recordings), 1);
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn test_unreadable_directory_is_reported() {
+        let root = scratch_dir("unreadable");
+        let not_a_dir = root.join("recordings");
+        fs::File::create(&not_a_dir).unwrap();
+
+        let report = RetentionManager::new(config_for(&root)).sweep();
+        assert_eq!(report.failures.len(), 1);
+        assert_eq!(report.failures[0].category, RetentionCategory::Recordings);
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn test_disabled_without_periods() {
+        let root = scratch_dir("disabled");
+        assert!(config_for(&root).is_enabled());
+        assert!(!RetentionConfig::default().is_enabled());
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
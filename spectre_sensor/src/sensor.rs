@@ -2,26 +2,66 @@
 
 use crate::{
     types::*,
-    yunet::{YuNetDetector, YuNetError},
-    calibrator::{AdaptiveCalibrator, CalibrationError},
+    yunet::YuNetError,
+    face_backend::{create_face_detector, FaceDetectorBackend},
+    face_tracker::{expand_rect, FaceTracker, CARRY_OVER_MARGIN},
+    capture::{Capture, CaptureStage, SharedSource},
+    camera_format::{negotiate_format, CameraFormat},
+    calibrator::{AdaptiveCalibrator, BaselineStats, CalibrationError},
     config::SensorConfig,
+    model_info::{sha256_hex, ModelInfo},
+    model_provider::{ModelProviderError, DEFAULT_EMOTION_MODEL_PATH},
+    normalization::{resolve_normalization, InputNormalization, Resolved, INPUT_SIZE, MODEL_METADATA_KEY},
+    emotion_input::{resolve_input, resolve_output, EmotionInputError, EmotionTensors},
+    clock_sync::SensorClockSync,
+    bug_report::{BufferedFrame, BugReport, FrameRecord, FrameRingBuffer, LogRingBuffer, PlatformInfo, StatusSnapshot},
+    recording::{FrameRecorder, RecordingHeader},
+    degradation::{EmotionBackend, EmotionBackendFactory, EmotionOutcome, EmotionPipeline},
+    inference_timeout::{timeout_notice, FACE_DETECTION},
+    startle::StartleDetector,
+    conditioning::LogitConditioner,
+    measure::{measure_source, FrameScorer, LiveFrames},
+    model_swap::{smoke_test, swap_channel, ModelSource, ModelSwapError, ModelSwapInbox, ModelSwapped, ModelSwapper, PreparedModel},
+    startup::{spawn_timed, InitBreakdown, ModelCacheStatus, OptimizedModelCache},
+    resume::{Clock, FrameSource, MetricsWindow, ResumeGuard, SessionSummary, SystemClock},
+    reconnect::{self, CaptureWatchdog, ReconnectOutcome},
+    mirror::{mirror_frame, MirrorInput},
+    phases::{publish_phase, CalibrationPhase, CalibrationPhases},
+    metrics_history::MetricsHistory,
+    heartbeat::{Heartbeat, HeartbeatTarget, Liveness},
+    power::{self, EmotionCadence, PowerControl, PowerMonitor, PowerProfileChanged, ProfileRates, RateOverride},
+    calibration_control::{control_channel, pipeline_phase, CalibrationAction, CalibrationControlError, CalibrationControlInbox, CalibrationController},
+    reconfigure::{reconfigure_channel, ConfigChange, EffectiveConfig, PipelineChange, ReconfigureError, ReconfigureInbox, Reconfigured, Reconfigurer},
+    mock_patterns::MockPattern,
+    quality::QualityController,
+    synthetic::{SyntheticCamera, SyntheticEmotion, SyntheticFaceDetector, SyntheticLatency, FRAME_HEIGHT, FRAME_WIDTH},
+    backpressure::{self, BackpressurePolicy, BackpressureSender, Delivery},
 };
+#[cfg(feature = "metrics")]
+use crate::metrics::SensorMetrics;
 use opencv::{
-    core::{Mat, Rect, Size},
+    core::{Mat, Rect, Size, Vector},
+    imgcodecs,
     imgproc,
     videoio::{VideoCapture, CAP_ANY},
     prelude::*,
 };
+use spectremesh_core::{math::compute_confidence, LogitsError};
 use ort::{
-    session::{Session, builder::GraphOptimizationLevel},
+    session::{Session, builder::{GraphOptimizationLevel, SessionBuilder}},
+    tensor::TensorElementType,
     value::Tensor,
 };
 
-use async_channel::{Sender, Receiver, bounded};
-use std::time::{Duration, Instant};
+use async_channel::Receiver;
+use std::path::PathBuf;
+use std::time::{Duration, Instant, UNIX_EPOCH};
 use std::sync::{Arc, Mutex};
-use tokio::time::sleep;
+use tokio::runtime::RuntimeFlavor;
+use tokio::sync::{broadcast, watch};
 use thiserror::Error;
+use tracing::field::Empty;
+use tracing::Instrument;
 
 /// Sensor errors
 #[derive(Debug, Error)]
@@ -34,6 +74,12 @@ pub enum SensorError {
     
     #[error("Emotion model loading failed: {0}")]
     ModelLoading(String),
+
+    #[error("Emotion model unavailable: {0}")]
+    ModelProvider(#[from] ModelProviderError),
+
+    #[error("Emotion model does not fit the pipeline: {0}")]
+    EmotionTensors(#[from] EmotionInputError),
     
     #[error("Face detection error: {0}")]
     FaceDetection(#[from] YuNetError),
@@ -49,16 +95,57 @@ pub enum SensorError {
     
     #[error("Sensor not initialized")]
     NotInitialized,
+
+    #[error("No face detected within {0:?}")]
+    MeasureTimeout(Duration),
+
+    #[error("{stage} took longer than {budget:?}, frame abandoned")]
+    InferenceTimeout { stage: &'static str, budget: Duration },
+
+    #[error("Emotion model swap failed: {0}")]
+    ModelSwap(#[from] ModelSwapError),
+
+    #[error("Calibration control failed: {0}")]
+    CalibrationControl(#[from] CalibrationControlError),
+
+    #[error("Reconfiguration failed: {0}")]
+    Reconfigure(#[from] ReconfigureError),
+
+    #[error("Invalid emotion logits: {0}")]
+    InvalidLogits(#[from] LogitsError),
+
+    #[error("Processing loop did not stop cleanly: {0}")]
+    StopFailed(String),
 }
 
+/// Longest [`EmotionSensor::stop`] waits for the processing loop to end
+const STOP_TIMEOUT: Duration = Duration::from_secs(2);
+/// How often a running sensor saves its baseline to the calibration cache
+const CALIBRATION_SAVE_INTERVAL: Duration = Duration::from_secs(30);
+
 /// Shared sensor state for thread communication
 #[derive(Debug, Clone)]
 pub struct SensorState {
     pub running: bool,
     pub calibration_progress: f32,
     pub calibrated: bool,
+    /// Whether the baseline is locked against further updates
+    pub calibration_frozen: bool,
     pub last_error: Option<String>,
     pub metrics: PerformanceMetrics,
+    pub session: SessionSummary,
+    /// Latest calibration baseline, published with the metrics
+    pub baseline: Option<BaselineStats>,
+    /// Rung of the emotion degradation ladder
+    pub capability: SensorCapability,
+    /// How long each initialization step took
+    pub init: Option<InitBreakdown>,
+    /// Emotion model input convention, once resolved
+    pub input_normalization: Option<InputNormalization>,
+    /// Why the sensor last stopped; cleared when it starts again
+    pub stopped_reason: Option<String>,
+    /// Resolution and frame rate the camera negotiated, once it is open
+    pub camera: Option<CameraFormat>,
 }
 
 impl Default for SensorState {
@@ -67,8 +154,16 @@ impl Default for SensorState {
             running: false,
             calibration_progress: 0.0,
             calibrated: false,
+            calibration_frozen: false,
             last_error: None,
             metrics: PerformanceMetrics::new(),
+            session: SessionSummary::default(),
+            baseline: None,
+            capability: SensorCapability::Full,
+            init: None,
+            input_normalization: None,
+            stopped_reason: None,
+            camera: None,
         }
     }
 }
@@ -76,125 +171,497 @@ impl Default for SensorState {
 /// High-performance emotion sensor with YuNet face detection
 pub struct EmotionSensor {
     /// YuNet face detector
-    face_detector: Option<YuNetDetector>,
+    face_detector: Option<Box<dyn FaceDetectorBackend>>,
     /// Emotion recognition session
     emotion_session: Option<Session>,
     /// Adaptive calibrator
     calibrator: Option<AdaptiveCalibrator>,
+    /// Camera opened during initialization; reopened on start if that failed
+    camera: Option<SensorSource>,
     /// Sensor configuration
     config: SensorConfig,
     /// Shared state for monitoring
     state: Arc<Mutex<SensorState>>,
-    /// Performance metrics tracking
-    #[allow(dead_code)]
-    latency_samples: Vec<Duration>,
+    /// Broadcast of markers inserted by the game
+    markers: broadcast::Sender<SensorMarker>,
+    /// Broadcast of faults raised by the processing loop
+    faults: broadcast::Sender<SensorFaultNotice>,
+    /// Broadcast of clock estimates reported by the game
+    clock_syncs: broadcast::Sender<SensorClockSync>,
+    /// Copies of running pipeline frames for single-shot measurements
+    live_frames: LiveFrames,
+    /// Broadcast of emotion model replacements
+    model_swaps: broadcast::Sender<ModelSwapped>,
+    /// Current calibration phase
+    calibration: watch::Sender<CalibrationPhase>,
+    /// Hands replacement models to the running processing loop
+    model_swapper: Option<ModelSwapper>,
+    /// Hands calibration actions to the running processing loop
+    calibration_controller: Option<CalibrationController>,
+    /// Hands camera and face detector changes to the running processing loop
+    reconfigurer: Option<Reconfigurer>,
+    /// Where a replacement emotion model was loaded from; the configured model if none
+    emotion_source: Option<ModelSource>,
+    /// Recent frames kept for bug reports
+    recent_frames: Arc<Mutex<FrameRingBuffer>>,
+    /// Periodic metrics snapshots for dashboards
+    metrics_history: Arc<Mutex<MetricsHistory>>,
+    /// Pipeline liveness behind `/health` and the heartbeat
+    liveness: Arc<Liveness>,
+    /// Prometheus metrics fed by the processing loop
+    loop_metrics: LoopMetrics,
+    /// Heartbeat target and the task beating to it
+    heartbeat: Option<(HeartbeatTarget, tokio::task::JoinHandle<()>)>,
+    /// Frame rate and emotion interval, from the power governor and overrides
+    power: Arc<PowerControl>,
+    /// Processing task of the current run, handing the models back when it ends
+    capture_task: Option<tokio::task::JoinHandle<RunParts>>,
+    /// Power readings for the governor; the system's if none was given
+    power_monitor: Option<Box<dyn PowerMonitor>>,
+    /// Task polling the power monitor
+    power_task: Option<tokio::task::JoinHandle<()>>,
+    /// Recent log lines kept for bug reports
+    logs: Option<LogRingBuffer>,
+    /// Provenance of the loaded models, face detector first
+    models: Vec<ModelInfo>,
+    /// Emotion model input convention resolved by initialization
+    input_normalization: InputNormalization,
+    /// Time the synthetic emotion model spends on each face
+    mock_latency: SyntheticLatency,
 }
 
 impl EmotionSensor {
     /// Create a new emotion sensor
     pub fn new(config: SensorConfig) -> Self {
+        let (markers, _) = broadcast::channel(16);
+        let (faults, _) = broadcast::channel(16);
+        let (clock_syncs, _) = broadcast::channel(16);
+        let (model_swaps, _) = broadcast::channel(16);
+        let (calibration, _) = watch::channel(CalibrationPhase::Idle);
+        let recent_frames = FrameRingBuffer::new(config.bug_report.window);
+        let metrics_history = MetricsHistory::new(&config.metrics_history);
+        let liveness = Liveness::new(config.heartbeat.stale_after);
+        let power = PowerControl::new(
+            config.power.clone(),
+            ProfileRates { target_fps: config.target_fps, emotion_interval: config.emotion_interval },
+        );
+        let mock_latency = SyntheticLatency::new(config.mock_inference_latency);
         Self {
             face_detector: None,
             emotion_session: None,
             calibrator: None,
+            camera: None,
             config,
             state: Arc::new(Mutex::new(SensorState::default())),
-            latency_samples: Vec::new(),
+            markers,
+            faults,
+            clock_syncs,
+            live_frames: LiveFrames::new(),
+            model_swaps,
+            calibration,
+            model_swapper: None,
+            calibration_controller: None,
+            reconfigurer: None,
+            emotion_source: None,
+            recent_frames: Arc::new(Mutex::new(recent_frames)),
+            metrics_history: Arc::new(Mutex::new(metrics_history)),
+            liveness: Arc::new(liveness),
+            loop_metrics: LoopMetrics::default(),
+            heartbeat: None,
+            power: Arc::new(power),
+            capture_task: None,
+            power_monitor: None,
+            power_task: None,
+            logs: None,
+            models: Vec::new(),
+            input_normalization: InputNormalization::default(),
+            mock_latency,
         }
     }
 
+    /// Include lines captured by a [`LogRingBuffer`] layer in bug reports
+    pub fn with_log_buffer(mut self, logs: LogRingBuffer) -> Self {
+        self.logs = Some(logs);
+        self
+    }
+
+    /// Feed frame counts, inference latencies and the FPS and calibration
+    /// gauges of the processing loop to `metrics`
+    #[cfg(feature = "metrics")]
+    pub fn with_metrics(mut self, metrics: Arc<SensorMetrics>) -> Self {
+        self.loop_metrics.prometheus = Some(metrics);
+        self
+    }
+
+    /// Read power and temperatures from `monitor` instead of this machine
+    pub fn with_power_monitor(mut self, monitor: Box<dyn PowerMonitor>) -> Self {
+        self.power_monitor = Some(monitor);
+        self
+    }
+
     /// Initialize the sensor with ONNX environment and models
+    ///
+    /// The face detector and emotion sessions are built, and the camera
+    /// opened, concurrently on the blocking pool. With
+    /// [`SensorConfig::mock`] set, synthetic ones stand in for all three.
     pub async fn initialize(&mut self) -> Result<(), SensorError> {
+        if let Some(pattern) = self.config.mock.clone() {
+            self.initialize_synthetic(&pattern);
+            return Ok(());
+        }
+        let start = Instant::now();
+
         // Initialize ONNX Runtime environment (global initialization)
         ort::init()
             .commit()
             .map_err(|e| SensorError::OnnxEnvironment(e.to_string()))?;
 
-        // Initialize YuNet face detector
-        let face_detector = if let Some(model_path) = &self.config.emotion_model_path {
-            YuNetDetector::from_file(model_path, self.config.onnx_threads)?
-        } else {
-            YuNetDetector::new(self.config.onnx_threads)?
-        };
+        // Initialize YuNet face detector on the configured backend
+        let config = self.config.clone();
+        let face_task = spawn_timed(move || create_face_detector(&config));
 
         // Load emotion recognition model
-        let emotion_session = self.load_emotion_model()?;
+        let config = self.config.clone();
+        let emotion_task = spawn_timed(move || Self::load_emotion_model(&config));
+
+        // Open the camera while the models load so its warm-up overlaps them
+        if let Err(e) = crate::permissions::check_camera_permissions().await {
+            tracing::warn!("Camera permission check failed: {}", e);
+            crate::permissions::provide_camera_troubleshooting_guidance();
+        }
+        let camera_config = self.config.clone();
+        let camera_task = spawn_timed(move || CameraSource::open(&camera_config));
 
         // Initialize adaptive calibrator
-        let calibrator = AdaptiveCalibrator::with_defaults(Duration::from_secs(30));
+        let calibrator_start = Instant::now();
+        let calibrator = self.new_calibrator();
+        let calibrator_time = calibrator_start.elapsed();
+
+        let (face_detector, face_detector_time) = face_task.await.map_err(init_task_error)?;
+        let (emotion, emotion_model_time) = emotion_task.await.map_err(init_task_error)?;
+        let (camera, camera_time) = camera_task.await.map_err(init_task_error)?;
+        let face_detector = face_detector?;
+        let emotion = emotion?;
+        let camera = match camera {
+            Ok(camera) => Some(camera),
+            Err(e) => {
+                tracing::warn!("Camera not ready during initialization, retrying on start: {}", e);
+                None
+            }
+        };
 
+        let init = InitBreakdown {
+            face_detector: face_detector_time,
+            emotion_model: emotion_model_time,
+            calibrator: calibrator_time,
+            camera: camera.as_ref().map(|_| camera_time),
+            total: start.elapsed(),
+            emotion_cache: emotion.cache,
+        };
+
+        let face_detector_name = face_detector.name();
+        self.models = vec![face_detector.model_info().clone(), emotion.info];
+        for model in &self.models {
+            tracing::info!("Model in use: {}", model);
+        }
+        tracing::info!("Emotion model input normalization: {}", emotion.normalization);
+        self.input_normalization = emotion.normalization.normalization;
         self.face_detector = Some(face_detector);
-        self.emotion_session = Some(emotion_session);
+        self.emotion_session = Some(emotion.session);
+        self.emotion_source = None;
         self.calibrator = Some(calibrator);
+        self.camera = camera.map(SensorSource::Camera);
+        {
+            let mut state = self.state.lock().unwrap();
+            state.init = Some(init);
+            state.input_normalization = Some(self.input_normalization);
+            state.camera = self.camera.as_ref().map(SensorSource::format);
+        }
 
-        tracing::info!("Sensor initialized with {} ONNX threads", self.config.onnx_threads);
+        tracing::info!(
+            "Sensor initialized in {} with {} ONNX threads, {} face detector",
+            init,
+            self.config.onnx_threads,
+            face_detector_name
+        );
         Ok(())
     }
 
+    /// Stand synthetic capture and models in for the camera and models
+    fn initialize_synthetic(&mut self, pattern: &MockPattern) {
+        let start = Instant::now();
+        let face_detector = SyntheticFaceDetector::new();
+        self.models = vec![face_detector.model_info().clone(), SyntheticEmotion::model_info(pattern)];
+        self.input_normalization = InputNormalization::default();
+        self.face_detector = Some(Box::new(face_detector));
+        self.emotion_session = None;
+        self.emotion_source = None;
+        self.calibrator = Some(self.new_calibrator());
+        let camera = SyntheticCamera::default().with_face_period(self.config.mock_face_period);
+        self.camera = Some(SensorSource::Synthetic(camera));
+
+        let init = InitBreakdown { camera: Some(Duration::ZERO), total: start.elapsed(), ..Default::default() };
+        {
+            let mut state = self.state.lock().unwrap();
+            state.init = Some(init);
+            state.input_normalization = Some(self.input_normalization);
+            state.camera = self.camera.as_ref().map(SensorSource::format);
+        }
+        tracing::info!("Sensor initialized with synthetic capture and models playing {:?}", pattern);
+    }
+
+    /// Calibrator for a fresh run, from the configuration
+    ///
+    /// Starts from the cached baseline when one is configured and recent
+    /// enough; a corrupt or stale cache is ignored with a warning.
+    fn new_calibrator(&self) -> AdaptiveCalibrator {
+        let mut calibrator = AdaptiveCalibrator::with_defaults(self.config.calibration_period)
+            .with_min_samples(self.config.calibration_min_samples)
+            .with_layout(self.config.emotion_layout)
+            .with_per_channel_calibration(self.config.per_channel_calibration)
+            .with_secondary_channel(self.config.secondary_emotion_channel)
+            .with_fear_index_weights(self.config.fear_index_weights.clone());
+        let Some(path) = &self.config.calibration_cache_path else {
+            return calibrator;
+        };
+        if !path.exists() {
+            return calibrator;
+        }
+        match calibrator.load_from_file(path, self.config.calibration_cache_max_age) {
+            Ok(()) => tracing::info!(
+                "Restored calibration from {}: mean={:.3}, std_dev={:.3}, samples={}",
+                path.display(),
+                calibrator.baseline_stats().mean,
+                calibrator.baseline_stats().std_dev,
+                calibrator.baseline_stats().sample_count
+            ),
+            Err(e) => tracing::warn!("Ignoring calibration cache {}: {}", path.display(), e),
+        }
+        calibrator
+    }
+
+    /// Lock a completed baseline when `freeze_calibration` is set, so frames
+    /// keep scoring against it without updating it
+    fn freeze_if_configured(config: &SensorConfig, calibrator: &mut AdaptiveCalibrator) {
+        if config.freeze_calibration && !calibrator.is_frozen() {
+            calibrator.freeze();
+        }
+    }
+
+    /// Write a completed calibration to the configured cache, if any
+    fn save_calibration(config: &SensorConfig, calibrator: &AdaptiveCalibrator) {
+        let Some(path) = &config.calibration_cache_path else {
+            return;
+        };
+        if !calibrator.is_calibrated() {
+            return;
+        }
+        if let Err(e) = calibrator.save_to_file(path) {
+            tracing::warn!("Could not save calibration to {}: {}", path.display(), e);
+        }
+    }
+
     /// Start the sensor and return a channel receiver for fear frames
+    ///
+    /// A stopped sensor starts again with the models and calibration of its
+    /// previous run.
     pub async fn start(&mut self) -> Result<Receiver<FearFrame>, SensorError> {
-        if self.face_detector.is_none() || self.emotion_session.is_none() {
+        let synthetic = self.config.mock.is_some();
+        if self.face_detector.is_none() || (self.emotion_session.is_none() && !synthetic) {
             return Err(SensorError::NotInitialized);
         }
+        let tensors = match &self.emotion_session {
+            Some(session) => Some(Self::emotion_tensors(session, &self.config)?),
+            None => None,
+        };
 
-        let (sender, receiver) = bounded(self.config.channel_buffer_size);
+        let (sender, receiver) = backpressure::channel(self.config.channel_buffer_size, self.config.backpressure);
         
         // Update state
         {
             let mut state = self.state.lock().unwrap();
             state.running = true;
             state.last_error = None;
+            state.stopped_reason = None;
         }
+        self.liveness.start();
+        self.start_heartbeat();
+        self.start_power_governor();
 
         // Spawn processing task
-        let face_detector = self.face_detector.take().unwrap();
-        let emotion_session = self.emotion_session.take().unwrap();
+        let mut face_detector = self.face_detector.take().unwrap();
+        let emotion_session = self.emotion_session.take();
         let mut calibrator = self.calibrator.take().unwrap();
+        let camera = self.camera.take();
+        let normalization = self.input_normalization;
         let config = self.config.clone();
         let state = Arc::clone(&self.state);
-
-        tokio::spawn(async move {
-            if let Err(e) = Self::processing_loop(
-                face_detector,
-                emotion_session,
+        let faults = self.faults.clone();
+        let calibration = self.calibration.clone();
+        let live_frames = self.live_frames.clone();
+        let recent_frames = Arc::clone(&self.recent_frames);
+        let metrics_history = Arc::clone(&self.metrics_history);
+        let liveness = Arc::clone(&self.liveness);
+        let loop_metrics = self.loop_metrics.clone();
+        let rates = self.power.subscribe_rates();
+
+        let emotion_sha256 = self.models.last().map(|info| info.sha256.to_string()).unwrap_or_default();
+        let layout = config.emotion_layout;
+        // A model swapped into a synthetic sensor runs on its synthetic faces
+        let (backend, rebuild): (Box<dyn EmotionBackend>, EmotionBackendFactory) = match (emotion_session.zip(tensors), &config.mock) {
+            (Some((session, tensors)), _) => (
+                Box::new(OrtEmotionBackend { session, tensors, normalization, layout }),
+                Self::rebuild_factory(config.clone(), self.emotion_source.clone(), emotion_sha256, normalization),
+            ),
+            (None, pattern) => {
+                let pattern = pattern.clone().unwrap_or_default();
+                let synthetic = SyntheticEmotion::new(&pattern, layout).with_shared_latency(self.mock_latency.clone());
+                (Box::new(synthetic), SyntheticEmotion::factory(pattern, layout))
+            }
+        };
+        let mut emotion = EmotionPipeline::new(backend, rebuild, config.degradation.clone(), faults.clone())
+            .with_inference_timeout(config.inference_timeout);
+        let (swapper, swaps) = swap_channel(self.model_swaps.clone());
+        self.model_swapper = Some(swapper);
+        let (controller, controls) = control_channel();
+        self.calibration_controller = Some(controller);
+        let (reconfigurer, changes) = reconfigure_channel();
+        self.reconfigurer = Some(reconfigurer);
+
+        self.capture_task = Some(tokio::spawn(async move {
+            // Keep the frame channel open until the stop is announced, so
+            // streams see the reason before the end of the frames
+            let frames_open = sender.clone();
+            let reason = match Self::processing_loop(
+                camera,
+                normalization,
+                face_detector.as_mut(),
+                &mut emotion,
                 &mut calibrator,
                 sender,
-                config,
-                state,
+                live_frames,
+                swaps,
+                controls,
+                changes,
+                config.clone(),
+                Arc::clone(&state),
+                faults.clone(),
+                calibration,
+                recent_frames,
+                metrics_history,
+                Arc::clone(&liveness),
+                loop_metrics,
+                rates,
             ).await {
-                tracing::error!("Sensor processing loop failed: {}", e);
+                Ok(()) => "Sensor stopped".to_string(),
+                Err(e) => {
+                    tracing::error!("Sensor processing loop failed: {}", e);
+                    format!("Sensor processing loop failed: {}", e)
+                }
+            };
+            Self::announce_stop(&state, &liveness, &faults, reason);
+            drop(frames_open);
+            Self::save_calibration(&config, &calibrator);
+
+            RunParts {
+                face_detector,
+                emotion_session: emotion.into_backend().into_session(),
+                calibrator,
             }
-        });
+        }));
 
         Ok(receiver)
     }
 
     /// Main processing loop
     async fn processing_loop(
-        mut face_detector: YuNetDetector,
-        mut emotion_session: Session,
+        camera: Option<SensorSource>,
+        mut normalization: InputNormalization,
+        face_detector: &mut dyn FaceDetectorBackend,
+        emotion: &mut EmotionPipeline,
         calibrator: &mut AdaptiveCalibrator,
-        sender: Sender<FearFrame>,
+        sender: BackpressureSender<FearFrame>,
+        live_frames: LiveFrames,
+        mut swaps: ModelSwapInbox,
+        mut controls: CalibrationControlInbox,
+        mut changes: ReconfigureInbox,
         config: SensorConfig,
         state: Arc<Mutex<SensorState>>,
+        faults: broadcast::Sender<SensorFaultNotice>,
+        calibration: watch::Sender<CalibrationPhase>,
+        recent_frames: Arc<Mutex<FrameRingBuffer>>,
+        metrics_history: Arc<Mutex<MetricsHistory>>,
+        liveness: Arc<Liveness>,
+        loop_metrics: LoopMetrics,
+        mut rates: watch::Receiver<ProfileRates>,
     ) -> Result<(), SensorError> {
-        // Check camera permissions first
-        if let Err(e) = crate::permissions::check_camera_permissions().await {
-            tracing::warn!("Camera permission check failed: {}", e);
-            crate::permissions::provide_camera_troubleshooting_guidance();
+        // Initialize camera with enhanced error reporting, unless initialization already did
+        let camera = match camera {
+            Some(camera) => camera,
+            None => SensorSource::open(&config)?,
+        };
+        let mut mirrored = camera.mirrored();
+        {
+            let mut state_guard = state.lock().unwrap();
+            state_guard.session.mirrored = Some(mirrored);
+            state_guard.camera = Some(camera.format());
         }
 
-        // Initialize camera with enhanced error reporting
-        let mut camera = Self::initialize_camera_with_backend_detection(config.camera_id)?;
+        // A recording that cannot be written must not stop the sensor
+        let mut recorder = config.record_path.as_ref().and_then(|path| {
+            match FrameRecorder::create(path, &RecordingHeader::new(&config, Some(camera.format()))) {
+                Ok(recorder) => {
+                    tracing::info!("Recording frames to {}", path.display());
+                    Some(recorder)
+                }
+                Err(e) => {
+                    tracing::warn!("{}, not recording", e);
+                    None
+                }
+            }
+        });
+
+        // Frames are read on their own thread, so slow inference never
+        // leaves them queueing in the camera; it paces itself to the rates
+        // the quality ladder leaves
+        let mut camera = SharedSource::new(camera);
+        let (pacing, paced) = watch::channel(*rates.borrow());
+        let capture = CaptureStage::spawn(camera.clone(), paced, {
+            let state = Arc::clone(&state);
+            let loop_metrics = loop_metrics.clone();
+            move || {
+                state.lock().unwrap().metrics.record_stale_frame();
+                loop_metrics.stale_frame();
+            }
+        })?;
+
+        let clock = SystemClock::new();
+        let mut resume_guard = ResumeGuard::new(config.resume.clone(), &clock, faults.clone());
+        let mut watchdog = CaptureWatchdog::new(config.reconnect.clone(), faults.clone());
+        let mut cadence = EmotionCadence::new();
+        let mut tracker = FaceTracker::new(config.face_tracking.clone()).with_detection_interval(config.detection_interval);
+        let mut window = MetricsWindow::new(clock.monotonic());
+        let mut last_cache_save = clock.monotonic();
+        let mut startle_detector = StartleDetector::new(config.startle.clone());
+        let conditioner = LogitConditioner::new(config.conditioning.clone());
+        let mut capability = emotion.capability();
+        // Fear repeated while no face is in frame; neutral until one is measured
+        let mut held_fear = calibrator.normalize_fear(calibrator.baseline_stats().mean);
+        // A cached baseline is complete before the first frame
+        if calibrator.is_calibrated() {
+            Self::freeze_if_configured(&config, calibrator);
+        }
+        publish_phase(&calibration, pipeline_phase(calibrator, capability));
 
-        let frame_duration = Duration::from_secs_f32(1.0 / config.target_fps);
-        let mut frame_count = 0u64;
-        let mut last_metrics_update = Instant::now();
-        let mut latency_samples = Vec::new();
+        // Every run starts at full quality, whatever the last one ended on
+        let mut quality = config.adaptive_quality.then(|| {
+            let performance = ProfileRates { target_fps: config.target_fps, emotion_interval: config.emotion_interval };
+            QualityController::new(config.quality.clone(), config.yunet_input_size, performance, clock.monotonic())
+        });
+        face_detector.set_input_size(config.yunet_params().input_size);
+        state.lock().unwrap().metrics.quality_level = 0;
 
         loop {
-            let frame_start = Instant::now();
-
             // Check if we should stop
             {
                 let state_guard = state.lock().unwrap();
@@ -203,33 +670,218 @@ impl EmotionSensor {
                 }
             }
 
-            // Capture frame
-            let mut frame = Mat::default();
-            if !camera.read(&mut frame).unwrap_or(false) || frame.empty() {
-                sleep(frame_duration).await;
+            // Follow the power governor and UpdateConfig between frames,
+            // within the quality ladder
+            let governed = *rates.borrow_and_update();
+            let current = quality.as_ref().map_or(governed, |quality| quality.apply(governed));
+            pacing.send_if_modified(|paced| std::mem::replace(paced, current) != current);
+            let ProfileRates { target_fps, emotion_interval } = current;
+            let frame_duration = Duration::from_secs_f32(1.0 / target_fps);
+
+            // Recover from system sleep before trusting the camera or the pacing deadline
+            if let Some(event) = resume_guard.poll(&clock) {
+                resume_guard.handle(event, &mut camera, calibrator, &mut window, &state, clock.monotonic());
+                capture.discard();
+                // A reopened camera may have settled on another format
+                state.lock().unwrap().camera = Some(camera.with(|source| source.format()));
+                startle_detector.reset();
+                tracker.reset();
+                publish_phase(&calibration, pipeline_phase(calibrator, capability));
                 continue;
             }
 
-            // Process frame
-            match Self::process_frame(
-                &frame,
-                &mut face_detector,
-                &mut emotion_session,
-                calibrator,
-            ).await {
-                Ok(fear_frame) => {
-                    latency_samples.push(fear_frame.inference_latency);
-                    
-                    // Try to send frame (non-blocking with back-pressure)
-                    match sender.try_send(fear_frame) {
-                        Ok(_) => {},
-                        Err(async_channel::TrySendError::Full(_)) => {
-                            // Channel full, drop oldest frame
+            // Install a replacement emotion model between two frames
+            if swaps.install_pending(emotion, calibrator, &mut normalization).is_some() {
+                // Fear on the new model's scale is not a jump, and the old
+                // model's logits must not be reused
+                startle_detector.reset();
+                cadence = EmotionCadence::new();
+                let mut state_guard = state.lock().unwrap();
+                state_guard.input_normalization = Some(normalization);
+                state_guard.calibration_progress = calibrator.progress();
+                state_guard.calibrated = calibrator.is_calibrated();
+                state_guard.calibration_frozen = calibrator.is_frozen();
+                publish_phase(&calibration, pipeline_phase(calibrator, capability));
+            }
+
+            // Apply calibration actions between frames, never during a sample
+            if controls.apply_pending(calibrator, capability, &calibration) > 0 {
+                let mut state_guard = state.lock().unwrap();
+                state_guard.calibration_progress = calibrator.progress();
+                state_guard.calibrated = calibrator.is_calibrated();
+                state_guard.calibration_frozen = calibrator.is_frozen();
+            }
+
+            // Switch cameras and thresholds between frames; a camera that
+            // fails to open leaves the current one capturing
+            let mut switched = false;
+            changes.apply_pending(|change| {
+                if let Some(camera_id) = change.camera_id {
+                    run_blocking(|| camera.with(|source| source.switch_camera(camera_id))).map_err(|e| {
+                        ReconfigureError::CameraUnavailable { camera_id, reason: e.to_string() }
+                    })?;
+                    switched = true;
+                }
+                if let Some(threshold) = change.face_confidence_threshold {
+                    face_detector.set_confidence_threshold(threshold);
+                }
+                Ok(())
+            });
+            if switched {
+                capture.discard();
+                mirrored = camera.with(|source| source.mirrored());
+                {
+                    let mut state_guard = state.lock().unwrap();
+                    state_guard.camera = Some(camera.with(|source| source.format()));
+                    state_guard.session.mirrored = Some(mirrored);
+                }
+                // Faces and motion from the other camera carry no meaning
+                // here, nor do its failed reads
+                startle_detector.reset();
+                tracker.reset();
+                watchdog.record_frame();
+            }
+
+            // Take the freshest frame; waiting past two frame times counts as a failed read
+            let waited = Instant::now();
+            let captured = match capture.next(frame_duration * 2).await {
+                Some(Capture::Frame(captured)) => Some(captured),
+                Some(Capture::Failed) | None => None,
+            };
+            let Some(captured) = captured else {
+                if watchdog.record_failure(clock.monotonic()) {
+                    {
+                        let mut state_guard = state.lock().unwrap();
+                        state_guard.metrics.current_fps = 0.0;
+                        state_guard.last_error = Some("Camera disconnected".to_string());
+                    }
+                    let is_running = || state.lock().unwrap().running;
+                    match watchdog.reconnect(&mut camera, is_running).await {
+                        ReconnectOutcome::Reconnected { .. } => {
+                            capture.discard();
+                            state.lock().unwrap().camera = Some(camera.with(|source| source.format()));
+                            if !watchdog.preserves_calibration() {
+                                calibrator.reset();
+                            }
+                            // Motion across the gap is not a startle, nor
+                            // are the faces before it the ones after
+                            startle_detector.reset();
+                            tracker.reset();
+                            window.reset(clock.monotonic());
+                            resume_guard.rearm(&clock);
+                            {
+                                let mut state_guard = state.lock().unwrap();
+                                state_guard.metrics.inference_latency.reset_window();
+                                state_guard.last_error = None;
+                            }
+                            publish_phase(&calibration, pipeline_phase(calibrator, capability));
+                        }
+                        ReconnectOutcome::Stopped => break,
+                        ReconnectOutcome::GaveUp { attempts } => {
+                            capture.stop().await;
+                            return Err(reconnect::gave_up_error(attempts));
+                        }
+                    }
+                }
+                continue;
+            };
+            watchdog.record_frame();
+            liveness.record_frame(Instant::now());
+            let queue_delay = captured.queue_delay();
+
+            // One span per frame, with a child per stage; the wait for the
+            // frame is over, so its span closes at once
+            let frame_start = Instant::now();
+            let frame_span = tracing::debug_span!("frame", sequence = captured.sequence, duration_us = Empty);
+            drop(tracing::debug_span!(
+                parent: &frame_span,
+                "capture",
+                duration_us = waited.elapsed().as_micros() as u64,
+                queue_delay_us = queue_delay.as_micros() as u64,
+            ));
+
+            // Score the frame; ONNX Runtime blocks, so step off the async
+            // worker when the runtime has others to run its tasks
+            let processed = run_blocking(|| {
+                frame_span.in_scope(|| {
+                    Self::process_frame(
+                        &captured.frame,
+                        face_detector,
+                        &mut tracker,
+                        emotion,
+                        &conditioner,
+                        calibrator,
+                        &mut cadence,
+                        emotion_interval,
+                        config.inference_timeout,
+                    )
+                })
+            });
+            // A frame abandoned over budget took at least the budget
+            let latency = match &processed {
+                Ok((fear_frame, _)) => Some(fear_frame.inference_latency),
+                Err(SensorError::InferenceTimeout { .. }) => Some(config.inference_timeout),
+                Err(_) => None,
+            };
+            match processed {
+                Ok((mut fear_frame, face)) => {
+                    let carried_over = tracker.carried_over();
+                    {
+                        let mut state_guard = state.lock().unwrap();
+                        state_guard.metrics.record_face_detection(carried_over);
+                        state_guard.metrics.record_inference_latency(fear_frame.inference_latency);
+                    }
+                    loop_metrics.face_detection(carried_over);
+                    // Without a face nothing was measured; the last fear stands
+                    if fear_frame.face_present {
+                        held_fear = fear_frame.fear_score;
+                    } else {
+                        fear_frame.fear_score = held_fear;
+                    }
+                    loop_metrics.frame_processed(fear_frame.inference_latency);
+                    let startle = startle_detector.update(
+                        clock.monotonic(),
+                        fear_frame.fear_score,
+                        fear_frame.calibrated && fear_frame.face_present && fear_frame.capability == SensorCapability::Full,
+                    );
+                    liveness.record_fear(fear_frame.fear_score);
+                    let face_bbox = fear_frame.face_bbox.filter(|_| config.share_face_position);
+                    let fear_frame = fear_frame
+                        .with_startle(startle)
+                        .with_input_normalization(normalization)
+                        .with_mirrored(mirrored)
+                        .with_face_bbox(face_bbox)
+                        .with_queue_delay(queue_delay)
+                        .with_capture_time(captured.wall_time)
+                        .with_sequence(captured.sequence);
+
+                    // Face imagery never leaves the loop in privacy mode
+                    let crop = if config.privacy_mode || !fear_frame.face_present { None } else { Self::encode_crop(&face) };
+                    let publish = tracing::debug_span!(parent: &frame_span, "publish", duration_us = Empty);
+                    let publish_start = Instant::now();
+                    publish.in_scope(|| {
+                        recent_frames.lock().unwrap().push(clock.monotonic(), BufferedFrame {
+                            record: FrameRecord::new(&fear_frame, fear_frame.timestamp_us()),
+                            crop,
+                        });
+                        if let Some(Err(e)) = recorder.as_mut().map(|recorder| recorder.record(&fear_frame)) {
+                            tracing::warn!("{}, recording stopped", e);
+                            recorder = None;
+                        }
+                    });
+
+                    // Hand the frame on; a full channel follows the back-pressure policy
+                    let published = live_frames.publish(fear_frame, &sender).instrument(publish.clone()).await;
+                    publish.record("duration_us", publish_start.elapsed().as_micros() as u64);
+                    match published {
+                        Ok(Delivery::Queued) => {},
+                        Ok(_) => {
                             let mut state_guard = state.lock().unwrap();
                             state_guard.metrics.record_dropped_frame();
-                            tracing::debug!("Dropped frame due to back-pressure");
+                            loop_metrics.frame_dropped(sender.policy());
+                            tracing::debug!("Dropped a frame under {} back-pressure", sender.policy());
                         },
-                        Err(async_channel::TrySendError::Closed(_)) => {
+                        Err(_) => {
                             tracing::info!("Receiver closed, stopping sensor");
                             break;
                         }
@@ -237,33 +889,84 @@ impl EmotionSensor {
                 },
                 Err(e) => {
                     tracing::warn!("Frame processing failed: {}", e);
+                    loop_metrics.inference_error();
+                    if matches!(e, SensorError::InferenceTimeout { .. }) {
+                        let _ = faults.send(timeout_notice(&e));
+                    }
                     let mut state_guard = state.lock().unwrap();
                     state_guard.last_error = Some(e.to_string());
                 }
             }
+            frame_span.record("duration_us", frame_start.elapsed().as_micros() as u64);
+            drop(frame_span);
+
+            // Step down the quality ladder under sustained latency pressure, and back up with headroom
+            let change = quality
+                .as_mut()
+                .zip(latency)
+                .and_then(|(quality, latency)| quality.observe(clock.monotonic(), latency, governed));
+            if let Some(change) = change {
+                tracing::info!("{}", change);
+                let size = change.rung.yunet_input_size.min(i32::MAX as u32) as i32;
+                face_detector.set_input_size(Size::new(size, size));
+                state.lock().unwrap().metrics.quality_level = change.to;
+                let _ = faults.send(change.notice());
+            }
+
+            if emotion.capability() != capability {
+                capability = emotion.capability();
+                state.lock().unwrap().capability = capability;
+                publish_phase(&calibration, pipeline_phase(calibrator, capability));
+            }
+
+            // Announce completion right away rather than with the next metrics update
+            let completed = calibrator.is_calibrated() && !calibration.borrow().is_calibrated();
+            if completed {
+                Self::freeze_if_configured(&config, calibrator);
+                state.lock().unwrap().calibration_frozen = calibrator.is_frozen();
+                publish_phase(&calibration, pipeline_phase(calibrator, capability));
+                Self::save_calibration(&config, calibrator);
+            }
 
-            frame_count += 1;
+            window.frame_count += 1;
 
             // Update metrics periodically
-            if last_metrics_update.elapsed() >= Duration::from_secs(1) {
+            let now = clock.monotonic();
+            let window_elapsed = window.elapsed(now);
+            if window_elapsed >= Duration::from_secs(1) {
                 let mut state_guard = state.lock().unwrap();
-                state_guard.metrics.update_fps(frame_count, last_metrics_update.elapsed());
-                state_guard.metrics.update_inference_latency(&latency_samples);
+                state_guard.metrics.update_fps(window.frame_count, window_elapsed);
+                liveness.record_fps(state_guard.metrics.current_fps);
+                state_guard.metrics.update_inference_latency();
                 state_guard.calibration_progress = calibrator.progress();
                 state_guard.calibrated = calibrator.is_calibrated();
-                state_guard.metrics.calibration_drift = calibrator.calculate_drift();
+                state_guard.calibration_frozen = calibrator.is_frozen();
+                state_guard.baseline = Some(calibrator.baseline_stats().clone());
+                state_guard.metrics.calibration_drift = resume_guard.take_drift(calibrator);
+                loop_metrics.update(&state_guard.metrics, state_guard.calibration_progress);
+                tracing::debug!(
+                    fps = state_guard.metrics.current_fps,
+                    p95_us = state_guard.metrics.p95_inference_latency.as_micros() as u64,
+                    dropped_frames = state_guard.metrics.dropped_frames,
+                    calibration_progress = state_guard.calibration_progress,
+                    calibrated = state_guard.calibrated,
+                    "Sensor metrics"
+                );
+                state_guard.session.processing += window_elapsed;
+                let wall_us = clock.wall().duration_since(UNIX_EPOCH).unwrap_or_default().as_micros() as u64;
+                metrics_history.lock().unwrap().record(wall_us, &state_guard.metrics);
+                publish_phase(&calibration, pipeline_phase(calibrator, capability));
+                drop(state_guard);
                 
-                last_metrics_update = Instant::now();
-                frame_count = 0;
-                latency_samples.clear();
-            }
-
-            // Maintain target FPS
-            let elapsed = frame_start.elapsed();
-            if elapsed < frame_duration {
-                sleep(frame_duration - elapsed).await;
+                if now.saturating_sub(last_cache_save) >= CALIBRATION_SAVE_INTERVAL {
+                    Self::save_calibration(&config, calibrator);
+                    last_cache_save = now;
+                }
+                window.reset(now);
             }
+            // The capture thread keeps the target rate
         }
+        capture.stop().await;
 
         // Update state on exit
         {
@@ -274,131 +977,620 @@ impl EmotionSensor {
         Ok(())
     }
 
-    /// Process a single frame to extract fear score
-    async fn process_frame(
+    /// Process a single frame to extract fear score, along with the face crop it used
+    ///
+    /// Only the tracker's primary subject is scored. A frame without one
+    /// yields a frame flagged `face_present: false` and an empty crop; the
+    /// calibrator does not see it and its fear score is left for the loop to
+    /// fill in. Between detections the tracker carries the primary face
+    /// over, cropped with a margin; when that crop scores far less
+    /// confidently than the detected face did, the frame is detected and
+    /// scored again. A frame whose detection or emotion inference runs past
+    /// `inference_timeout` is abandoned with [`SensorError::InferenceTimeout`].
+    fn process_frame(
         frame: &Mat,
-        face_detector: &mut YuNetDetector,
-        emotion_session: &mut Session,
+        face_detector: &mut dyn FaceDetectorBackend,
+        tracker: &mut FaceTracker,
+        emotion: &mut EmotionPipeline,
+        conditioner: &LogitConditioner,
         calibrator: &mut AdaptiveCalibrator,
-    ) -> Result<FearFrame, SensorError> {
+        cadence: &mut EmotionCadence,
+        emotion_interval: u32,
+        inference_timeout: Duration,
+    ) -> Result<(FearFrame, Mat), SensorError> {
         let inference_start = Instant::now();
 
-        // Detect largest face
-        let face_detection = face_detector.get_largest_face(frame)?;
-
-        // Crop face region
-        let face_roi = Self::crop_face_region(frame, &face_detection.bbox)?;
-
-        // Run emotion recognition
-        let emotion_logits = Self::run_emotion_inference(&face_roi, emotion_session).await?;
+        // Follow the primary subject among the detected faces, or carry it
+        // over from the last detection while the detection interval allows
+        let frame_size = Size::new(frame.cols(), frame.rows());
+        let carried = tracker.carry_over();
+        let carried_over = carried.is_some();
+        let primary = match carried {
+            Some(primary) => primary,
+            None => {
+                let detect = tracing::debug_span!("detect", duration_us = Empty);
+                let detections = match in_span(detect, || face_detector.detect_faces(frame)) {
+                    Err(YuNetError::NoFacesDetected) => Vec::new(),
+                    detections => detections?,
+                };
+                // Detection runs here, so it can only be abandoned once it is over
+                if inference_start.elapsed() > inference_timeout {
+                    return Err(SensorError::InferenceTimeout { stage: FACE_DETECTION, budget: inference_timeout });
+                }
+                let faces_seen = detections.len() as u32;
+                let Some(primary) = tracker.update(detections, frame_size) else {
+                    // Logits reused across the gap would belong to whoever was last in frame
+                    cadence.record(None);
+                    let fear_frame = FearFrame::new(
+                        0.0,
+                        EmotionLogits::zeros(calibrator.layout()),
+                        0.0,
+                        calibrator.is_calibrated(),
+                        inference_start.elapsed(),
+                    )
+                    .with_capability(emotion.capability())
+                    .with_face_present(false)
+                    .with_tracking(None, faces_seen);
+                    return Ok((fear_frame, Mat::default()));
+                };
+                primary
+            }
+        };
+        let tracking = (Some(primary.track_id), primary.faces_seen);
+        let face_detection = primary.detection;
+
+        // Against the capture resolution, not the detector's input size; a
+        // mirrored capture is already flipped, so the box is too
+        let bbox = face_detection.bbox;
+        let face_bbox = NormalizedRect::from_pixels(bbox.x, bbox.y, bbox.width, bbox.height, frame.cols(), frame.rows());
+
+        // Crop face region, with room for a carried-over face to have moved
+        let crop = if carried_over { expand_rect(&bbox, CARRY_OVER_MARGIN, frame_size) } else { bbox };
+        let face_roi = Self::crop_face_region(frame, &crop)?;
+
+        // Run emotion recognition through the degradation ladder, unless
+        // this frame reuses the last logits; reused logits are held values
+        let outcome = match cadence.reuse(emotion_interval) {
+            Some(logits) => EmotionOutcome::Held { logits, confidence_scale: 1.0 },
+            None => {
+                let infer = tracing::debug_span!("emotion_infer", duration_us = Empty);
+                let outcome = in_span(infer, || emotion.infer(&face_roi))?;
+                match &outcome {
+                    EmotionOutcome::Live(logits) => cadence.record(Some(logits)),
+                    _ => cadence.record(None),
+                }
+                outcome
+            }
+        };
 
         let inference_latency = inference_start.elapsed();
 
-        // Extract fear logit and update calibrator
-        let fear_logit = emotion_logits[2]; // Fear is at index 2
-        calibrator.add_sample(fear_logit)?;
+        let (emotion_logits, confidence, conditioning) = match outcome {
+            EmotionOutcome::Live(raw) => {
+                // Update the calibrator with all channels; it only sees
+                // conditioned samples, weighted by how sure the frame is
+                let conditioned = conditioner.apply(&raw, calibrator);
+                let confidence = compute_confidence(face_detection.confidence, &conditioned.logits);
+                if !tracker.scored(confidence) {
+                    // The face has left the carried-over crop; these logits
+                    // are not the subject's
+                    cadence.record(None);
+                    return Self::process_frame(
+                        frame,
+                        face_detector,
+                        tracker,
+                        emotion,
+                        conditioner,
+                        calibrator,
+                        cadence,
+                        emotion_interval,
+                        inference_timeout,
+                    );
+                }
+                // A frozen baseline keeps scoring without learning
+                if !calibrator.is_frozen() {
+                    let calibrate = tracing::debug_span!("calibrate", duration_us = Empty);
+                    in_span(calibrate, || calibrator.add_weighted_logits(&conditioned.sample, confidence))?;
+                }
+                (conditioned.logits, confidence, conditioned.applied)
+            }
+            // Held values must not feed the baseline
+            EmotionOutcome::Held { logits, confidence_scale } => {
+                let (logits, conditioning) = conditioner.condition(&logits);
+                let confidence = compute_confidence(face_detection.confidence, &logits) * confidence_scale;
+                (logits, confidence, conditioning)
+            }
+            EmotionOutcome::Offline => {
+                // Face presence only; fear is flagged unavailable
+                let fear_frame = FearFrame::new(
+                    0.0,
+                    EmotionLogits::zeros(calibrator.layout()),
+                    face_detection.confidence,
+                    calibrator.is_calibrated(),
+                    inference_latency,
+                )
+                .with_capability(SensorCapability::EmotionOffline)
+                .with_face_bbox(face_bbox)
+                .with_tracking(tracking.0, tracking.1);
+                return Ok((fear_frame, face_roi));
+            }
+        };
 
-        // Normalize fear score
-        let normalized_fear = calibrator.normalize_fear(fear_logit);
+        // Normalize fear, or combine the normalized channels into the fear index
+        let normalized_fear = calibrator.fear_score(&emotion_logits);
 
-        Ok(FearFrame::new(
+        let fear_frame = FearFrame::new(
             normalized_fear,
             emotion_logits,
-            face_detection.confidence,
+            confidence,
             calibrator.is_calibrated(),
             inference_latency,
-        ))
+        )
+        .with_capability(emotion.capability())
+        .with_conditioning(conditioning)
+        .with_face_bbox(face_bbox)
+        .with_tracking(tracking.0, tracking.1);
+        Ok((fear_frame, face_roi))
+    }
+
+    /// Load emotion recognition model, describe it and resolve its input convention
+    fn load_emotion_model(config: &SensorConfig) -> Result<EmotionModel, SensorError> {
+        let info = ModelInfo::for_file(Self::emotion_model_path(config)?, config.emotion_model_info.clone())
+            .map_err(|e| SensorError::ModelLoading(e.to_string()))?;
+        let (mut session, cache) = Self::load_emotion_session(config, &info.sha256)?;
+        let tensors = Self::emotion_tensors(&session, config)?;
+        tracing::info!("Emotion model tensors: {}", tensors);
+
+        let metadata = session
+            .metadata()
+            .ok()
+            .and_then(|metadata| metadata.custom(MODEL_METADATA_KEY).ok().flatten());
+        let normalization = resolve_normalization(config.input_normalization, metadata.as_deref(), |pixels| {
+            let pixels = tensors.input.shape.from_gray(pixels, INPUT_SIZE);
+            Self::run_emotion_model(&mut session, &tensors, pixels, config.emotion_layout)
+        })?;
+
+        Ok(EmotionModel { session, info, cache, normalization })
     }
 
-    /// Load emotion recognition model
-    fn load_emotion_model(&self) -> Result<Session, SensorError> {
-        // For this implementation, we'll assume the emotion model is also embedded
-        // In practice, you'd load from a file or embed it like YuNet
-        let model_path = self.config.emotion_model_path
-            .as_deref()
-            .unwrap_or("assets/models/face_emotion.onnx");
+    /// Build an emotion recognition session (also used to rebuild a failing one)
+    ///
+    /// With an optimized model cache configured, the Level3-optimized graph of
+    /// the model with hash `sha256` is loaded from or written to the cache.
+    fn load_emotion_session(config: &SensorConfig, sha256: &str) -> Result<(Session, ModelCacheStatus), SensorError> {
+        let model_path = Self::emotion_model_path(config)?;
+        let cache = config.optimized_model_cache.as_deref().map(OptimizedModelCache::new);
+
+        OptimizedModelCache::load(
+            cache.as_ref(),
+            sha256,
+            |target| {
+                let mut builder = Self::emotion_session_builder(config, GraphOptimizationLevel::Level3)?;
+                if let Some(target) = target {
+                    builder = builder
+                        .with_optimized_model_path(target.to_string_lossy().into_owned())
+                        .map_err(|e| SensorError::ModelLoading(e.to_string()))?;
+                }
+                builder
+                    .commit_from_file(&model_path)
+                    .map_err(|e| SensorError::ModelLoading(e.to_string()))
+            },
+            |cached| {
+                // Already optimized; running the optimizer again would only cost time
+                Self::emotion_session_builder(config, GraphOptimizationLevel::Disable)?
+                    .commit_from_file(cached)
+                    .map_err(|e| SensorError::ModelLoading(e.to_string()))
+            },
+        )
+    }
+
+    /// Rebuild step for the emotion model from `source`, or the configured one
+    fn rebuild_factory(
+        config: SensorConfig,
+        source: Option<ModelSource>,
+        sha256: String,
+        normalization: InputNormalization,
+    ) -> EmotionBackendFactory {
+        Box::new(move || {
+            // The rebuilt session runs the same model, so the resolved convention still holds
+            let session = match &source {
+                Some(source) => Self::load_replacement_session(&config, source)?,
+                None => Self::load_emotion_session(&config, &sha256)?.0,
+            };
+            let tensors = Self::emotion_tensors(&session, &config)?;
+            let layout = config.emotion_layout;
+            Ok(Box::new(OrtEmotionBackend { session, tensors, normalization, layout }) as Box<dyn EmotionBackend>)
+        })
+    }
 
+    /// Build an emotion session for a replacement model, bypassing the optimized model cache
+    fn load_replacement_session(config: &SensorConfig, source: &ModelSource) -> Result<Session, SensorError> {
+        let builder = Self::emotion_session_builder(config, GraphOptimizationLevel::Level3)?;
+        match source {
+            ModelSource::Path(path) => builder.commit_from_file(path),
+            ModelSource::Bytes(bytes) => builder.commit_from_memory(bytes),
+        }
+        .map_err(|e| SensorError::ModelLoading(e.to_string()))
+    }
+
+    fn emotion_session_builder(
+        config: &SensorConfig,
+        level: GraphOptimizationLevel,
+    ) -> Result<SessionBuilder, SensorError> {
         Session::builder()
             .map_err(|e| SensorError::ModelLoading(e.to_string()))?
-            .with_optimization_level(GraphOptimizationLevel::Level3)
-            .map_err(|e| SensorError::ModelLoading(e.to_string()))?
-            .with_intra_threads(self.config.onnx_threads)
+            .with_optimization_level(level)
             .map_err(|e| SensorError::ModelLoading(e.to_string()))?
-            .commit_from_file(model_path)
+            .with_intra_threads(config.onnx_threads)
             .map_err(|e| SensorError::ModelLoading(e.to_string()))
     }
 
-    /// Crop face region from frame
+    /// Where the emotion model takes faces and gives logits, from the
+    /// session's metadata and the configured input shape and output name
+    fn emotion_tensors(session: &Session, config: &SensorConfig) -> Result<EmotionTensors, EmotionInputError> {
+        let input = session.inputs.first().ok_or(EmotionInputError::NoInput)?;
+        let input_type = &input.input_type;
+        if input_type.tensor_type() != Some(TensorElementType::Float32) {
+            let found = match input_type.tensor_type() {
+                Some(ty) => format!("{:?} tensors", ty),
+                None => "no tensor".to_string(),
+            };
+            return Err(EmotionInputError::ElementType { name: input.name.clone(), found });
+        }
+        let dims = input_type.tensor_shape().map(|shape| shape.to_vec()).unwrap_or_default();
+        let input = resolve_input(&input.name, &dims, config.emotion_input_override)?;
+
+        let outputs = session.outputs.iter().map(|output| {
+            let dims = output.output_type.tensor_shape().map_or(&[][..], |shape| &shape[..]);
+            (output.name.as_str(), dims)
+        });
+        let output = resolve_output(outputs, config.emotion_output.as_deref())?;
+        Ok(EmotionTensors { input, output })
+    }
+
+    /// Emotion model file; the bundled asset unless a provider is configured,
+    /// which may download it first
+    fn emotion_model_path(config: &SensorConfig) -> Result<PathBuf, SensorError> {
+        match &config.emotion_model {
+            Some(provider) => Ok(provider.resolve()?),
+            None => Ok(PathBuf::from(DEFAULT_EMOTION_MODEL_PATH)),
+        }
+    }
+
+    /// Crop face region from frame, at capture resolution; the emotion
+    /// backend resizes it to whatever its model takes
     fn crop_face_region(frame: &Mat, bbox: &Rect) -> Result<Mat, SensorError> {
-        let roi = Mat::roi(frame, *bbox)
-            .map_err(|e| SensorError::FrameProcessing(e.to_string()))?;
-        
-        // Resize to emotion model input size (typically 48x48)
+        Mat::roi(frame, *bbox)
+            .and_then(|roi| roi.try_clone())
+            .map_err(|e| SensorError::FrameProcessing(e.to_string()))
+    }
+
+    /// PNG-encode a face crop for bug reports
+    fn encode_crop(face: &Mat) -> Option<Vec<u8>> {
+        let mut buffer = Vector::<u8>::new();
+        match imgcodecs::imencode(".png", face, &mut buffer, &Vector::new()) {
+            Ok(true) => Some(buffer.to_vec()),
+            Ok(false) | Err(_) => {
+                tracing::debug!("Failed to encode face crop for bug report");
+                None
+            }
+        }
+    }
+
+    /// Run emotion inference on face image
+    fn run_emotion_inference(
+        face_image: &Mat,
+        session: &mut Session,
+        tensors: &EmotionTensors,
+        normalization: InputNormalization,
+        layout: EmotionLayout,
+    ) -> Result<EmotionLogits, SensorError> {
+        let shape = tensors.input.shape;
+
+        // Resize to the model's input size
         let mut resized = Mat::default();
         imgproc::resize(
-            &roi,
+            face_image,
             &mut resized,
-            Size::new(48, 48),
+            Size::new(shape.width as i32, shape.height as i32),
             0.0,
             0.0,
             imgproc::INTER_LINEAR,
         )
         .map_err(|e| SensorError::FrameProcessing(e.to_string()))?;
 
-        Ok(resized)
-    }
-
-    /// Run emotion inference on face image
-    async fn run_emotion_inference(
-        face_image: &Mat,
-        session: &mut Session,
-    ) -> Result<[f32; 7], SensorError> {
-        // Convert to grayscale
-        let mut gray = Mat::default();
-        imgproc::cvt_color(face_image, &mut gray, imgproc::COLOR_BGR2GRAY, 0)
+        // Convert to grayscale or RGB, as the model takes it
+        let code = if shape.channels == 1 { imgproc::COLOR_BGR2GRAY } else { imgproc::COLOR_BGR2RGB };
+        let mut converted = Mat::default();
+        imgproc::cvt_color(&resized, &mut converted, code, 0)
             .map_err(|e| SensorError::FrameProcessing(e.to_string()))?;
 
         // Convert to float and normalize
         let mut float_img = Mat::default();
-        gray.convert_to(&mut float_img, opencv::core::CV_32F, 1.0 / 255.0, 0.0)
+        converted.convert_to(&mut float_img, opencv::core::CV_32F, 1.0 / 255.0, 0.0)
             .map_err(|e| SensorError::FrameProcessing(e.to_string()))?;
 
-        // Convert to ndarray format (NCHW)
-        let data = float_img.data_typed::<f32>()
-            .map_err(|e| SensorError::FrameProcessing(e.to_string()))?;
-        
-        let input_tensor = Tensor::from_array(([1, 1, 48, 48], data.to_vec()))
+        // Apply the model's input convention to the [0, 1] pixels, which run
+        // row by row with each pixel's channels together
+        let mut pixels: Vec<f32> = float_img.data_bytes()
+            .map_err(|e| SensorError::FrameProcessing(e.to_string()))?
+            .chunks_exact(std::mem::size_of::<f32>())
+            .map(|bytes| f32::from_ne_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+            .collect();
+        normalization.apply(&mut pixels);
+
+        Self::run_emotion_model(session, tensors, shape.arrange(pixels), layout)
+    }
+
+    /// Run the emotion model on a normalized buffer in its input tensor's order
+    fn run_emotion_model(
+        session: &mut Session,
+        tensors: &EmotionTensors,
+        pixels: Vec<f32>,
+        layout: EmotionLayout,
+    ) -> Result<EmotionLogits, SensorError> {
+        let input_tensor = Tensor::from_array((tensors.input.shape.tensor_shape(), pixels))
             .map_err(|e| SensorError::FrameProcessing(e.to_string()))?;
 
         // Run inference
         let outputs = session
-            .run(ort::inputs!["input" => input_tensor])
+            .run(ort::inputs![tensors.input.name.as_str() => input_tensor])
             .map_err(|e| SensorError::FrameProcessing(e.to_string()))?;
 
         // Extract emotion logits
-        let output = outputs.get("output")
+        let output = outputs.get(tensors.output.as_str())
             .ok_or_else(|| SensorError::FrameProcessing("Missing output tensor".to_string()))?;
         let (_, output_data) = output
             .try_extract_tensor::<f32>()
             .map_err(|_| SensorError::FrameProcessing("Invalid output format".to_string()))?;
 
-        if output_data.len() < 7 {
-            return Err(SensorError::FrameProcessing("Insufficient output dimensions".to_string()));
+        Self::model_logits(output_data, layout)
+    }
+
+    /// Emotion logits from the model's raw output
+    ///
+    /// The output must have exactly as many values as the layout has
+    /// channels; anything else comes from another model, so it is an
+    /// [`SensorError::InvalidLogits`] rather than something to cut down to size.
+    fn model_logits(output: &[f32], layout: EmotionLayout) -> Result<EmotionLogits, SensorError> {
+        Ok(EmotionLogits::new(output.to_vec(), layout)?)
+    }
+
+    /// Stop the sensor
+    ///
+    /// Subscribers to [`EmotionSensor::subscribe_faults`] receive a
+    /// `SENSOR_STOPPED` notice right away rather than when the processing
+    /// loop next checks the state. Returns once the processing loop has
+    /// ended: the camera is released, the frame channel closed, and the
+    /// models and calibration are back for the next [`EmotionSensor::start`].
+    pub async fn stop(&mut self) -> Result<(), SensorError> {
+        Self::announce_stop(&self.state, &self.liveness, &self.faults, "Stopped by request");
+        let Some(mut task) = self.capture_task.take() else {
+            return Ok(());
+        };
+
+        match tokio::time::timeout(STOP_TIMEOUT, &mut task).await {
+            Ok(Ok(parts)) => {
+                self.face_detector = Some(parts.face_detector);
+                self.emotion_session = parts.emotion_session;
+                self.calibrator = Some(parts.calibrator);
+                Ok(())
+            }
+            Ok(Err(e)) => Err(SensorError::StopFailed(e.to_string())),
+            Err(_) => {
+                task.abort();
+                Err(SensorError::StopFailed(format!("still running after {:?}", STOP_TIMEOUT)))
+            }
+        }
+    }
+
+    /// Mark the sensor stopped and send the `SENSOR_STOPPED` notice, once per run
+    fn announce_stop(
+        state: &Mutex<SensorState>,
+        liveness: &Liveness,
+        faults: &broadcast::Sender<SensorFaultNotice>,
+        reason: impl Into<String>,
+    ) {
+        let reason = reason.into();
+        liveness.stop();
+        {
+            let mut state = state.lock().unwrap();
+            state.running = false;
+            if state.stopped_reason.is_some() {
+                return;
+            }
+            state.stopped_reason = Some(reason.clone());
         }
+        tracing::info!("Sensor stopped: {}", reason);
+        // No subscribers just means nobody is streaming right now
+        let _ = faults.send(SensorFaultNotice::stopped(reason));
+    }
+
+    /// Capture and score a single frame
+    ///
+    /// While the sensor is running this waits for the pipeline's next frame,
+    /// which its subscribers still receive. Otherwise the camera is opened if
+    /// needed, and kept open for [`EmotionSensor::start`], and frames are
+    /// scored until one shows a face. The current calibration is applied but
+    /// not updated, and the face crop is discarded whatever the privacy mode.
+    pub async fn measure_once(&mut self, timeout: Duration) -> Result<FearFrame, SensorError> {
+        let running = self.state.lock().unwrap().running;
+        if running {
+            return self.live_frames.next(timeout).await;
+        }
+
+        let (Some(face_detector), Some(session), Some(calibrator)) = (
+            self.face_detector.as_deref_mut(),
+            self.emotion_session.as_mut(),
+            self.calibrator.as_ref(),
+        ) else {
+            return Err(SensorError::NotInitialized);
+        };
+        let tensors = Self::emotion_tensors(session, &self.config)?;
+        let mut camera = match self.camera.take() {
+            Some(camera) => camera,
+            None => SensorSource::open(&self.config)?,
+        };
+
+        let mut scorer = PipelineScorer {
+            face_detector,
+            session,
+            tensors,
+            normalization: self.input_normalization,
+            mirrored: camera.mirrored(),
+            share_face_position: self.config.share_face_position,
+            conditioner: LogitConditioner::new(self.config.conditioning.clone()),
+            calibrator,
+        };
+        let poll_interval = Duration::from_secs_f32(1.0 / self.config.target_fps);
+        let result = measure_source(&mut camera, &mut scorer, timeout, poll_interval).await;
+        self.camera = Some(camera);
+        result
+    }
+
+    /// Copies of the running pipeline's frames, for measuring without holding the sensor
+    pub fn live_frames(&self) -> LiveFrames {
+        self.live_frames.clone()
+    }
+
+    /// Load a replacement emotion model and check it fits the pipeline
+    ///
+    /// Blocks while the session is built, so call it off the async runtime.
+    /// The session's input and output are resolved as at startup and must
+    /// fit the pipeline, its input convention too, and a smoke
+    /// inference on a fixture face must give finite logits in the configured
+    /// emotion layout.
+    pub fn load_replacement_model(config: &SensorConfig, source: ModelSource) -> Result<ReplacementModel, SensorError> {
+        let info = match &source {
+            ModelSource::Path(path) => {
+                ModelInfo::for_file(path, None).map_err(|e| ModelSwapError::Load(e.to_string()))?
+            }
+            ModelSource::Bytes(bytes) => ModelInfo::hash_only("uploaded emotion model", sha256_hex(bytes)),
+        };
+        let mut session = Self::load_replacement_session(config, &source)
+            .map_err(|e| ModelSwapError::Load(e.to_string()))?;
+
+        let tensors = Self::emotion_tensors(&session, config).map_err(ModelSwapError::Tensors)?;
+
+        let metadata = session
+            .metadata()
+            .ok()
+            .and_then(|metadata| metadata.custom(MODEL_METADATA_KEY).ok().flatten());
+        let normalization = resolve_normalization(config.input_normalization, metadata.as_deref(), |pixels| {
+            let pixels = tensors.input.shape.from_gray(pixels, INPUT_SIZE);
+            Self::run_emotion_model(&mut session, &tensors, pixels, config.emotion_layout)
+        })
+        .map_err(|e| ModelSwapError::SmokeInference(e.to_string()))?;
+
+        let mut backend = OrtEmotionBackend {
+            session,
+            tensors,
+            normalization: normalization.normalization,
+            layout: config.emotion_layout,
+        };
+        let logits = smoke_test(&mut backend)?;
+        tracing::info!(
+            "Replacement emotion model {} passed validation (tensors {}, input normalization {}, fixture logits {:?})",
+            info,
+            backend.tensors,
+            normalization,
+            logits.as_slice()
+        );
+        Ok(ReplacementModel { backend, info, source })
+    }
+
+    /// Replace the emotion model with one from [`EmotionSensor::load_replacement_model`]
+    ///
+    /// A running sensor installs it between two frames, so streams carry on
+    /// without a gap; an idle one uses it from the next start. With
+    /// `reset_calibration` the calibration restarts and frames are flagged
+    /// uncalibrated until it completes. Subscribers to
+    /// [`EmotionSensor::subscribe_model_swaps`] are told of the swap.
+    pub async fn install_emotion_model(
+        &mut self,
+        model: ReplacementModel,
+        reset_calibration: bool,
+    ) -> Result<ModelSwapped, SensorError> {
+        let Some(old_sha256) = self.models.last().map(|info| info.sha256.to_string()) else {
+            return Err(SensorError::NotInitialized);
+        };
+        let ReplacementModel { backend, info, source } = model;
+        let normalization = backend.normalization;
+        let running = self.state.lock().unwrap().running;
+
+        let swapped = match self.model_swapper.as_ref().filter(|_| running) {
+            Some(swapper) => {
+                let prepared = PreparedModel {
+                    backend: Box::new(backend),
+                    rebuild: Self::rebuild_factory(
+                        self.config.clone(),
+                        Some(source.clone()),
+                        info.sha256.to_string(),
+                        normalization,
+                    ),
+                    info: info.clone(),
+                    normalization,
+                };
+                swapper.swap(prepared, old_sha256, reset_calibration).await?
+            }
+            None => {
+                let Some(session) = self.emotion_session.as_mut() else {
+                    return Err(SensorError::NotInitialized);
+                };
+                *session = backend.session;
+                if reset_calibration {
+                    if let Some(calibrator) = &mut self.calibrator {
+                        calibrator.reset();
+                        publish_phase(&self.calibration, CalibrationPhase::Idle);
+                    }
+                }
+                let swapped = ModelSwapped::new(old_sha256, info.sha256.to_string(), reset_calibration);
+                // No subscribers just means nobody is streaming right now
+                let _ = self.model_swaps.send(swapped.clone());
+                tracing::info!("Emotion model swapped to {}", info);
+                swapped
+            }
+        };
 
-        let mut emotion_logits = [0.0f32; 7];
-        for (i, &value) in output_data.iter().take(7).enumerate() {
-            emotion_logits[i] = value;
+        self.input_normalization = normalization;
+        self.emotion_source = Some(source);
+        if let Some(current) = self.models.last_mut() {
+            *current = info;
         }
+        self.state.lock().unwrap().input_normalization = Some(normalization);
+        Ok(swapped)
+    }
 
-        Ok(emotion_logits)
+    /// Load, validate and install a replacement emotion model
+    ///
+    /// The model is loaded on the blocking pool while frames keep flowing.
+    /// If it fails validation the current model keeps running and the error
+    /// says why.
+    pub async fn swap_emotion_model(
+        &mut self,
+        source: ModelSource,
+        reset_calibration: bool,
+    ) -> Result<ModelSwapped, SensorError> {
+        let config = self.config.clone();
+        let model = tokio::task::spawn_blocking(move || Self::load_replacement_model(&config, source))
+            .await
+            .map_err(|e| SensorError::ModelLoading(format!("Model validation task failed: {}", e)))??;
+        self.install_emotion_model(model, reset_calibration).await
     }
 
-    /// Stop the sensor
-    pub async fn stop(&mut self) -> Result<(), SensorError> {
-        let mut state = self.state.lock().unwrap();
-        state.running = false;
-        Ok(())
+    /// Subscribe to emotion model replacements
+    pub fn subscribe_model_swaps(&self) -> broadcast::Receiver<ModelSwapped> {
+        self.model_swaps.subscribe()
+    }
+
+    /// Configuration the sensor was created with
+    pub fn config(&self) -> &SensorConfig {
+        &self.config
+    }
+
+    /// Provenance of the models loaded by [`EmotionSensor::initialize`]
+    pub fn model_info(&self) -> &[ModelInfo] {
+        &self.models
     }
 
     /// Get current sensor state
@@ -406,29 +1598,269 @@ impl EmotionSensor {
         self.state.lock().unwrap().clone()
     }
 
-    /// Control calibration
-    pub fn control_calibration(&mut self, freeze: bool) -> Result<(), SensorError> {
-        if let Some(calibrator) = &mut self.calibrator {
-            if freeze {
-                calibrator.freeze();
-            } else {
-                calibrator.unfreeze();
+    /// Capture recent frames, logs and state for a bug report bundle
+    pub fn bug_report(&self) -> BugReport {
+        let state = self.get_state();
+        BugReport {
+            frames: self.recent_frames.lock().unwrap().snapshot(),
+            config: self.config.clone(),
+            baseline: state.baseline.clone(),
+            status: StatusSnapshot::from(&state),
+            logs: self.logs.as_ref().map(LogRingBuffer::lines).unwrap_or_default(),
+            metrics_history: self.metrics_history.lock().unwrap().samples(),
+            platform: PlatformInfo::current(),
+        }
+    }
+
+    /// Periodic performance metrics snapshots, shared with the processing loop
+    ///
+    /// Hand it to [`crate::metrics::start_metrics_server`] for the dashboard.
+    pub fn metrics_history(&self) -> Arc<Mutex<MetricsHistory>> {
+        Arc::clone(&self.metrics_history)
+    }
+
+    /// Pipeline liveness, shared with the processing loop
+    ///
+    /// Hand it to [`crate::metrics::start_metrics_server`] for `/health`.
+    pub fn liveness(&self) -> Arc<Liveness> {
+        Arc::clone(&self.liveness)
+    }
+
+    /// Heartbeat target being beaten to, if any
+    ///
+    /// `None` without a configured target, when it could not be opened and
+    /// once the heartbeat ended with the pipeline.
+    pub fn heartbeat_target(&self) -> Option<&HeartbeatTarget> {
+        self.heartbeat
+            .as_ref()
+            .filter(|(_, task)| !task.is_finished())
+            .map(|(target, _)| target)
+    }
+
+    /// Open the configured heartbeat target and beat while the pipeline is healthy
+    ///
+    /// A heartbeat left over from a previous run is replaced. A target that
+    /// cannot be opened is logged rather than failing the start: the
+    /// external monitor notices the missing beats either way.
+    fn start_heartbeat(&mut self) {
+        if let Some((_, task)) = self.heartbeat.take() {
+            task.abort();
+        }
+        match Heartbeat::open(&self.config.heartbeat) {
+            Ok(Some(heartbeat)) => {
+                tracing::info!("Heartbeat to {} every {:?}", heartbeat.target(), self.config.heartbeat.interval);
+                let target = heartbeat.target().clone();
+                let task = tokio::spawn(heartbeat.run(Arc::clone(&self.liveness)));
+                self.heartbeat = Some((target, task));
             }
+            Ok(None) => {}
+            Err(e) => tracing::error!("Heartbeat disabled: {}", e),
         }
-        Ok(())
     }
 
-    /// Reset calibration
-    pub fn reset_calibration(&mut self) -> Result<(), SensorError> {
-        if let Some(calibrator) = &mut self.calibrator {
-            calibrator.reset();
+    /// Poll the power monitor while the pipeline runs, if the governor is enabled
+    ///
+    /// A monitor given with [`Self::with_power_monitor`] serves the first
+    /// run; later runs, and runs without one, read this machine. Without a
+    /// readable battery or temperature sensor the configured rates stay.
+    fn start_power_governor(&mut self) {
+        if let Some(task) = self.power_task.take() {
+            task.abort();
+        }
+        if !self.config.power.enabled {
+            return;
+        }
+        let monitor = match self.power_monitor.take().map_or_else(power::system_monitor, Ok) {
+            Ok(monitor) => monitor,
+            Err(e) => {
+                tracing::warn!("Power governor disabled: {}", e);
+                return;
+            }
+        };
+        tracing::info!("Power governor reading power and temperatures every {:?}", self.config.power.poll_interval);
+        self.power_task = Some(tokio::spawn(Arc::clone(&self.power).run(monitor, Arc::clone(&self.liveness))));
+    }
+
+    /// Power governor behind the frame rate and emotion inference interval
+    pub fn power(&self) -> Arc<PowerControl> {
+        Arc::clone(&self.power)
+    }
+
+    /// Subscribe to power profile changes
+    pub fn subscribe_power(&self) -> broadcast::Receiver<PowerProfileChanged> {
+        self.power.subscribe()
+    }
+
+    /// Override the frame rate and emotion interval, winning over the power
+    /// governor; takes effect from the next frame
+    pub fn update_rates(&self, overrides: RateOverride) -> ProfileRates {
+        self.power.set_override(overrides)
+    }
+
+    /// Make the synthetic emotion model take `latency` per face from its
+    /// next face on, to exercise the pipeline as inference slows down and
+    /// recovers
+    pub fn set_mock_inference_latency(&self, latency: Duration) {
+        self.mock_latency.set(latency);
+    }
+
+    /// Stamp a named marker into the event stream
+    pub fn insert_marker(&self, label: impl Into<String>) -> SensorMarker {
+        let marker = SensorMarker::new(label);
+        // No subscribers just means nobody is streaming right now
+        let _ = self.markers.send(marker.clone());
+        tracing::debug!("Inserted marker '{}'", marker.label);
+        marker
+    }
+
+    /// Subscribe to markers inserted with [`EmotionSensor::insert_marker`]
+    pub fn subscribe_markers(&self) -> broadcast::Receiver<SensorMarker> {
+        self.markers.subscribe()
+    }
+
+    /// Stamp the game's clock estimate into the event stream so recordings carry it
+    pub fn record_clock_sync(&self, sync: SensorClockSync) {
+        // No subscribers just means nobody is recording right now
+        let _ = self.clock_syncs.send(sync);
+    }
+
+    /// Subscribe to estimates recorded with [`EmotionSensor::record_clock_sync`]
+    pub fn subscribe_clock_syncs(&self) -> broadcast::Receiver<SensorClockSync> {
+        self.clock_syncs.subscribe()
+    }
+
+    /// Start, freeze, unfreeze or reset calibration, returning the phase it is left in
+    ///
+    /// Actions illegal in the current phase are rejected with
+    /// [`CalibrationControlError::IllegalTransition`] and change nothing; see
+    /// [`crate::calibration_control`] for which are legal. A running sensor
+    /// applies the action between two frames; an idle one stays idle.
+    pub async fn control_calibration(&mut self, action: CalibrationAction) -> Result<CalibrationPhase, SensorError> {
+        let running = self.state.lock().unwrap().running;
+        if let Some(controller) = self.calibration_controller.clone().filter(|_| running) {
+            return Ok(controller.send(action).await?);
+        }
+
+        let Some(calibrator) = &mut self.calibrator else {
+            return Err(SensorError::NotInitialized);
+        };
+        action.apply(calibrator, &CalibrationPhase::Idle)?;
+        publish_phase(&self.calibration, CalibrationPhase::Idle);
+        Ok(CalibrationPhase::Idle)
+    }
+
+    /// Change settings, running or not, without restarting
+    ///
+    /// Values out of range and a freeze the calibration phase does not allow
+    /// are rejected before anything changes; see [`crate::reconfigure`] for
+    /// when each setting takes effect. A running sensor switches camera and
+    /// face confidence threshold between two frames; an idle one releases a
+    /// camera opened with another id and opens the new one on start.
+    pub async fn configure(&mut self, change: ConfigChange) -> Result<Reconfigured, SensorError> {
+        change.validate()?;
+        let running = self.state.lock().unwrap().running;
+        let freeze = change.freeze_calibration.map(|freeze| match freeze {
+            true => CalibrationAction::Freeze,
+            false => CalibrationAction::Unfreeze,
+        });
+        if let Some(action) = freeze {
+            // An idle sensor has no baseline to freeze, as in control_calibration
+            let phase = if running { self.calibration_phase() } else { CalibrationPhase::Idle };
+            action.check(&phase)?;
+        }
+
+        let pipeline = change.pipeline();
+        match self.reconfigurer.clone().filter(|_| running && !pipeline.is_empty()) {
+            Some(reconfigurer) => reconfigurer.send(pipeline).await?,
+            None => self.reconfigure_idle(pipeline),
+        }
+        if let Some(camera_id) = change.camera_id {
+            self.config.camera_id = camera_id;
+        }
+        if let Some(threshold) = change.face_confidence_threshold {
+            self.config.face_confidence_threshold = threshold;
+        }
+        if let Some(action) = freeze {
+            self.control_calibration(action).await?;
+        }
+        if let Some(target_fps) = change.target_fps {
+            let overrides = self.power.overrides();
+            self.update_rates(RateOverride { target_fps: Some(target_fps), ..overrides });
+        }
+
+        let config = self.effective_config();
+        let restart_required = change.restart_required(&config);
+        Ok(Reconfigured { config, restart_required })
+    }
+
+    /// Apply a pipeline change to the parts a stopped sensor holds
+    fn reconfigure_idle(&mut self, change: PipelineChange) {
+        if let (Some(threshold), Some(face_detector)) = (change.face_confidence_threshold, &mut self.face_detector) {
+            face_detector.set_confidence_threshold(threshold);
+        }
+        let Some(camera_id) = change.camera_id else {
+            return;
+        };
+        if matches!(&self.camera, Some(SensorSource::Camera(camera)) if camera.camera_id != camera_id) {
+            self.camera = None;
+            self.state.lock().unwrap().camera = None;
+        }
+    }
+
+    /// Settings in effect
+    pub fn effective_config(&self) -> EffectiveConfig {
+        EffectiveConfig {
+            camera_id: self.config.camera_id,
+            target_fps: self.power.rates().target_fps,
+            face_confidence_threshold: self.config.face_confidence_threshold,
+            calibration_frozen: matches!(self.calibration_phase(), CalibrationPhase::Frozen { .. }),
+            onnx_threads: self.config.onnx_threads,
         }
-        Ok(())
     }
 
-    /// Initialize camera with enhanced error reporting and backend detection
-    fn initialize_camera_with_backend_detection(camera_id: u32) -> Result<VideoCapture, SensorError> {
-        let camera = VideoCapture::new(camera_id as i32, CAP_ANY)
+    /// Pretend to run, with calibration actions going to the returned inbox
+    #[cfg(test)]
+    pub(crate) fn attach_calibration_inbox(&mut self) -> CalibrationControlInbox {
+        let (controller, inbox) = control_channel();
+        self.calibration_controller = Some(controller);
+        self.state.lock().unwrap().running = true;
+        inbox
+    }
+
+    /// Expose faults raised by the processing loop (e.g. `SYSTEM_RESUMED`)
+    pub fn subscribe_faults(&self) -> broadcast::Receiver<SensorFaultNotice> {
+        self.faults.subscribe()
+    }
+
+    /// Current calibration phase
+    pub fn calibration_phase(&self) -> CalibrationPhase {
+        self.calibration.borrow().clone()
+    }
+
+    /// Subscribe to calibration phase changes
+    pub fn subscribe_calibration(&self) -> watch::Receiver<CalibrationPhase> {
+        self.calibration.subscribe()
+    }
+
+    /// Calibration phases, starting with the current one
+    ///
+    /// The stream outlives the borrow of the sensor and ends when it stops;
+    /// a sensor that already stopped gives its last phase and ends.
+    pub fn phases(&self) -> CalibrationPhases {
+        // Subscribe before checking, so a stop in between is not missed
+        let faults = self.faults.subscribe();
+        let stopped = self.state.lock().unwrap().stopped_reason.is_some();
+        CalibrationPhases::watch(self.calibration.subscribe(), faults, stopped)
+    }
+
+    /// Initialize camera with enhanced error reporting and backend detection,
+    /// in the requested format where the driver grants it
+    fn initialize_camera_with_backend_detection(
+        camera_id: u32,
+        resolution: Option<(u32, u32)>,
+        fps: f32,
+    ) -> Result<(VideoCapture, CameraFormat), SensorError> {
+        let mut camera = VideoCapture::new(camera_id as i32, CAP_ANY)
             .map_err(|e| SensorError::CameraInit(format!("Failed to create camera {}: {}", camera_id, e)))?;
 
         if !camera.is_opened().unwrap_or(false) {
@@ -443,12 +1875,10 @@ impl EmotionSensor {
         let backend_name = Self::get_camera_backend_name(&camera);
         tracing::info!("Camera {} initialized with backend: {}", camera_id, backend_name);
 
-        // Validate camera properties
-        let width = camera.get(opencv::videoio::CAP_PROP_FRAME_WIDTH).unwrap_or(0.0);
-        let height = camera.get(opencv::videoio::CAP_PROP_FRAME_HEIGHT).unwrap_or(0.0);
-        tracing::info!("Camera resolution: {}x{}", width, height);
+        // Drivers often refuse a format silently; keep what they settled on
+        let negotiated = negotiate_format(camera_id, &mut camera, resolution, Some(fps));
 
-        Ok(camera)
+        Ok((camera, negotiated.actual))
     }
 
     /// Get camera backend name
@@ -469,6 +1899,15 @@ impl EmotionSensor {
         }
     }
 
+    /// Whether the camera backend reports delivering mirrored frames
+    ///
+    /// None of the OpenCV capture backends expose the driver's or OS's
+    /// mirroring setting, so there is no hint to give yet and
+    /// [`MirrorInput::Auto`] leaves frames unflipped.
+    fn camera_mirror_hint(_camera: &VideoCapture) -> Option<bool> {
+        None
+    }
+
     /// Get available camera backends for the platform
     fn get_available_backends() -> String {
         #[cfg(target_os = "windows")]
@@ -482,6 +1921,320 @@ impl EmotionSensor {
     }
 }
 
+/// An initialization task panicked or was cancelled
+fn init_task_error(error: tokio::task::JoinError) -> SensorError {
+    SensorError::ModelLoading(format!("Initialization task failed: {}", error))
+}
+
+/// A loaded emotion model and what is known about it
+struct EmotionModel {
+    session: Session,
+    info: ModelInfo,
+    cache: ModelCacheStatus,
+    normalization: Resolved,
+}
+
+/// A replacement emotion model that passed validation
+pub struct ReplacementModel {
+    backend: OrtEmotionBackend,
+    info: ModelInfo,
+    source: ModelSource,
+}
+
+impl ReplacementModel {
+    /// What is known about the model
+    pub fn info(&self) -> &ModelInfo {
+        &self.info
+    }
+}
+
+/// ONNX Runtime emotion session behind the degradation ladder
+struct OrtEmotionBackend {
+    session: Session,
+    tensors: EmotionTensors,
+    normalization: InputNormalization,
+    layout: EmotionLayout,
+}
+
+impl EmotionBackend for OrtEmotionBackend {
+    fn infer(&mut self, face: &Mat) -> Result<EmotionLogits, SensorError> {
+        EmotionSensor::run_emotion_inference(face, &mut self.session, &self.tensors, self.normalization, self.layout)
+    }
+
+    fn into_session(self: Box<Self>) -> Option<Session> {
+        Some(self.session)
+    }
+}
+
+/// Models and calibration a processing run hands back when it ends
+struct RunParts {
+    face_detector: Box<dyn FaceDetectorBackend>,
+    emotion_session: Option<Session>,
+    calibrator: AdaptiveCalibrator,
+}
+
+/// Scores frames for [`EmotionSensor::measure_once`] with the idle sensor's models
+struct PipelineScorer<'a> {
+    face_detector: &'a mut dyn FaceDetectorBackend,
+    session: &'a mut Session,
+    tensors: EmotionTensors,
+    normalization: InputNormalization,
+    /// Whether the camera flips the frames it hands over
+    mirrored: bool,
+    /// Whether frames carry the face box
+    share_face_position: bool,
+    conditioner: LogitConditioner,
+    calibrator: &'a AdaptiveCalibrator,
+}
+
+impl FrameScorer for PipelineScorer<'_> {
+    fn score(&mut self, frame: &Mat) -> Result<Option<FearFrame>, SensorError> {
+        let inference_start = Instant::now();
+
+        let face_detection = match self.face_detector.get_largest_face(frame) {
+            Ok(detection) => detection,
+            Err(YuNetError::NoFacesDetected) => return Ok(None),
+            Err(e) => return Err(e.into()),
+        };
+        let bbox = face_detection.bbox;
+        let face_bbox = NormalizedRect::from_pixels(bbox.x, bbox.y, bbox.width, bbox.height, frame.cols(), frame.rows())
+            .filter(|_| self.share_face_position);
+        let face_roi = EmotionSensor::crop_face_region(frame, &bbox)?;
+        let layout = self.calibrator.layout();
+        let raw = EmotionSensor::run_emotion_inference(&face_roi, self.session, &self.tensors, self.normalization, layout)?;
+        let (emotion_logits, conditioning) = self.conditioner.condition(&raw);
+        let confidence = compute_confidence(face_detection.confidence, &emotion_logits);
+
+        let fear_frame = FearFrame::new(
+            self.calibrator.fear_score(&emotion_logits),
+            emotion_logits,
+            confidence,
+            self.calibrator.is_calibrated(),
+            inference_start.elapsed(),
+        )
+        .with_input_normalization(self.normalization)
+        .with_conditioning(conditioning)
+        .with_mirrored(self.mirrored)
+        .with_face_bbox(face_bbox);
+        Ok(Some(fear_frame))
+    }
+}
+
+/// Camera capture that can be reopened after a resume
+///
+/// Frames are flipped back as they are read, so everything downstream sees
+/// the same orientation.
+struct CameraSource {
+    capture: VideoCapture,
+    camera_id: u32,
+    /// Resolved again for a camera switched to
+    mirror_input: MirrorInput,
+    mirrored: bool,
+    /// Format requested again on every reopen
+    resolution: Option<(u32, u32)>,
+    fps: f32,
+    /// Format the camera negotiated
+    format: CameraFormat,
+}
+
+impl CameraSource {
+    fn open(config: &SensorConfig) -> Result<Self, SensorError> {
+        let (camera_id, mirror_input) = (config.camera_id, config.mirror_input);
+        let (resolution, fps) = (config.camera_resolution, config.target_fps);
+        let (capture, format) = EmotionSensor::initialize_camera_with_backend_detection(camera_id, resolution, fps)?;
+        let mirrored = mirror_input.resolve(EmotionSensor::camera_mirror_hint(&capture));
+        tracing::info!("Camera {} mirroring: {} (flipping frames: {})", camera_id, mirror_input, mirrored);
+        Ok(Self {
+            capture,
+            camera_id,
+            mirror_input,
+            mirrored,
+            resolution,
+            fps,
+            format,
+        })
+    }
+
+    /// Capture from camera `camera_id` instead, opening it before the
+    /// current one is released so a failure leaves capture as it was
+    fn switch(&mut self, camera_id: u32) -> Result<(), SensorError> {
+        if camera_id == self.camera_id {
+            return Ok(());
+        }
+        let (capture, format) = EmotionSensor::initialize_camera_with_backend_detection(camera_id, self.resolution, self.fps)?;
+        let mirrored = self.mirror_input.resolve(EmotionSensor::camera_mirror_hint(&capture));
+        let mut previous = std::mem::replace(&mut self.capture, capture);
+        let _ = previous.release();
+        tracing::info!("Switched from camera {} to camera {} (flipping frames: {})", self.camera_id, camera_id, mirrored);
+        (self.camera_id, self.mirrored, self.format) = (camera_id, mirrored, format);
+        Ok(())
+    }
+}
+
+impl FrameSource for CameraSource {
+    fn read_frame(&mut self, frame: &mut Mat) -> bool {
+        if !self.capture.read(frame).unwrap_or(false) {
+            return false;
+        }
+        if self.mirrored && !frame.empty() {
+            if let Err(e) = mirror_frame(frame) {
+                tracing::warn!("Failed to mirror frame: {}", e);
+                return false;
+            }
+        }
+        true
+    }
+
+    fn reopen(&mut self) -> Result<(), SensorError> {
+        let _ = self.capture.release();
+        (self.capture, self.format) =
+            EmotionSensor::initialize_camera_with_backend_detection(self.camera_id, self.resolution, self.fps)?;
+        Ok(())
+    }
+}
+
+/// Run blocking work on the current task, moving the runtime's other tasks
+/// to another worker first when there is one to move them to
+fn run_blocking<T>(work: impl FnOnce() -> T) -> T {
+    match tokio::runtime::Handle::try_current() {
+        Ok(handle) if handle.runtime_flavor() == RuntimeFlavor::MultiThread => tokio::task::block_in_place(work),
+        _ => work(),
+    }
+}
+
+/// Run `work` inside `span`, recording how long it took as the span's `duration_us`
+fn in_span<T>(span: tracing::Span, work: impl FnOnce() -> T) -> T {
+    let start = Instant::now();
+    let result = span.in_scope(work);
+    span.record("duration_us", start.elapsed().as_micros() as u64);
+    result
+}
+
+/// Prometheus metrics the processing loop feeds, when the sensor was given any
+#[derive(Clone, Default)]
+struct LoopMetrics {
+    #[cfg(feature = "metrics")]
+    prometheus: Option<Arc<SensorMetrics>>,
+}
+
+#[cfg_attr(not(feature = "metrics"), allow(unused_variables))]
+impl LoopMetrics {
+    /// A frame was scored in `latency`
+    fn frame_processed(&self, latency: Duration) {
+        #[cfg(feature = "metrics")]
+        if let Some(metrics) = &self.prometheus {
+            metrics.record_frame_processed();
+            metrics.record_inference_latency(latency.as_secs_f64());
+        }
+    }
+
+    /// A frame's face was detected, or carried over from the last detection
+    fn face_detection(&self, carried_over: bool) {
+        #[cfg(feature = "metrics")]
+        if let Some(metrics) = &self.prometheus {
+            metrics.record_face_detection(carried_over);
+        }
+    }
+
+    /// A frame was dropped for a slow reader under `policy`
+    fn frame_dropped(&self, policy: BackpressurePolicy) {
+        #[cfg(feature = "metrics")]
+        if let Some(metrics) = &self.prometheus {
+            metrics.record_frame_dropped(policy);
+        }
+    }
+
+    /// A captured frame was replaced by a fresher one before it was scored
+    fn stale_frame(&self) {
+        #[cfg(feature = "metrics")]
+        if let Some(metrics) = &self.prometheus {
+            metrics.record_stale_frame();
+        }
+    }
+
+    /// A frame could not be scored
+    fn inference_error(&self) {
+        #[cfg(feature = "metrics")]
+        if let Some(metrics) = &self.prometheus {
+            metrics.record_inference_error();
+        }
+    }
+
+    /// Gauges from the periodic metrics update
+    fn update(&self, performance: &PerformanceMetrics, calibration_progress: f32) {
+        #[cfg(feature = "metrics")]
+        if let Some(metrics) = &self.prometheus {
+            metrics.update_fps(performance.current_fps);
+            metrics.update_calibration_drift(performance.calibration_drift);
+            metrics.update_calibration_progress(calibration_progress);
+            metrics.update_quality_level(performance.quality_level);
+            metrics.update_latency_percentiles(&performance.inference_latency);
+        }
+    }
+}
+
+/// Where the processing loop reads frames from
+enum SensorSource {
+    Camera(CameraSource),
+    /// Stand-in for headless runs, see [`SensorConfig::mock`]
+    Synthetic(SyntheticCamera),
+}
+
+impl SensorSource {
+    /// The configured source, opening the camera unless it is mocked
+    fn open(config: &SensorConfig) -> Result<Self, SensorError> {
+        match config.mock {
+            Some(_) => Ok(Self::Synthetic(SyntheticCamera::default().with_face_period(config.mock_face_period))),
+            None => CameraSource::open(config).map(Self::Camera),
+        }
+    }
+
+    /// What the source delivers; synthetic frames come at whatever rate they are read
+    fn format(&self) -> CameraFormat {
+        match self {
+            Self::Camera(camera) => camera.format,
+            Self::Synthetic(_) => CameraFormat {
+                width: FRAME_WIDTH as u32,
+                height: FRAME_HEIGHT as u32,
+                fps: 0.0,
+            },
+        }
+    }
+
+    /// Whether frames are flipped as they are read
+    fn mirrored(&self) -> bool {
+        match self {
+            Self::Camera(camera) => camera.mirrored,
+            Self::Synthetic(_) => false,
+        }
+    }
+
+    /// Read from camera `camera_id` instead; synthetic frames are the same
+    /// whichever camera is asked for
+    fn switch_camera(&mut self, camera_id: u32) -> Result<(), SensorError> {
+        match self {
+            Self::Camera(camera) => camera.switch(camera_id),
+            Self::Synthetic(_) => Ok(()),
+        }
+    }
+}
+
+impl FrameSource for SensorSource {
+    fn read_frame(&mut self, frame: &mut Mat) -> bool {
+        match self {
+            Self::Camera(camera) => camera.read_frame(frame),
+            Self::Synthetic(synthetic) => synthetic.read_frame(frame),
+        }
+    }
+
+    fn reopen(&mut self) -> Result<(), SensorError> {
+        match self {
+            Self::Camera(camera) => camera.reopen(),
+            Self::Synthetic(synthetic) => synthetic.reopen(),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -497,6 +2250,215 @@ mod tests {
         assert_eq!(state.calibration_progress, 0.0);
     }
 
+    #[test]
+    fn test_model_output_must_match_layout() {
+        let output: Vec<f32> = (0..10).map(|i| i as f32).collect();
+        let error = EmotionSensor::model_logits(&output, EmotionLayout::STANDARD).unwrap_err();
+        assert!(matches!(error, SensorError::InvalidLogits(LogitsError::Length { expected: 7, got: 10 })));
+        assert_eq!(error.to_string(), "Invalid emotion logits: expected 7 emotion channels, got 10");
+
+        let wide = EmotionLayout::new(10, 4).unwrap();
+        let logits = EmotionSensor::model_logits(&output, wide).unwrap();
+        assert_eq!(logits.as_slice(), &output[..]);
+        assert_eq!(logits.fear(), 4.0);
+    }
+
+    /// A model from `tests/fixtures/emotion_models`
+    fn fixture_model(file: &str) -> ModelSource {
+        ModelSource::Path(PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/emotion_models").join(file))
+    }
+
+    #[test]
+    fn test_emotion_input_is_read_from_the_model() {
+        let config = SensorConfig::default();
+        for (file, input, output, shape) in [
+            ("gray48_nchw.onnx", "input", "output", [1, 1, 48, 48]),
+            ("rgb64_nhwc.onnx", "pixels", "scores", [1, 64, 64, 3]),
+        ] {
+            let mut model = EmotionSensor::load_replacement_model(&config, fixture_model(file)).unwrap();
+            let tensors = &model.backend.tensors;
+            assert_eq!((tensors.input.name.as_str(), tensors.output.as_str()), (input, output), "{}", file);
+            assert_eq!(tensors.input.shape.tensor_shape(), shape, "{}", file);
+
+            let logits = smoke_test(&mut model.backend).unwrap();
+            assert_eq!(logits.as_slice().len(), 7, "{}", file);
+        }
+    }
+
+    #[test]
+    fn test_emotion_tensors_that_do_not_fit_are_explained() {
+        let gray = fixture_model("gray48_nchw.onnx");
+
+        let config = SensorConfig::default().with_emotion_input_override("64x64x1".parse().unwrap());
+        let error = EmotionSensor::load_replacement_model(&config, gray.clone()).err().unwrap();
+        assert!(error.to_string().contains("has shape [1, 1, 48, 48], but emotion_input_override"), "{}", error);
+        assert!(error.to_string().contains("expects [1, 1, 64, 64]"), "{}", error);
+
+        let config = SensorConfig::default().with_emotion_output("logits");
+        let error = EmotionSensor::load_replacement_model(&config, gray).err().unwrap();
+        assert!(matches!(error, SensorError::ModelSwap(ModelSwapError::Tensors(EmotionInputError::MissingOutput(_)))));
+    }
+
+    #[test]
+    fn test_insert_marker_reaches_subscribers() {
+        let sensor = EmotionSensor::new(SensorConfig::default());
+
+        // Inserting without subscribers must not fail
+        sensor.insert_marker("ignored");
+
+        let mut markers = sensor.subscribe_markers();
+        let sent = sensor.insert_marker("boss_start");
+        let received = markers.try_recv().unwrap();
+        assert_eq!(received, sent);
+        assert_eq!(received.label, "boss_start");
+    }
+
+    #[tokio::test]
+    async fn test_stop_notifies_subscribers_once() {
+        let mut sensor = EmotionSensor::new(SensorConfig::default());
+        let mut faults = sensor.subscribe_faults();
+
+        sensor.stop().await.unwrap();
+        sensor.stop().await.unwrap();
+
+        let notice = faults.try_recv().unwrap();
+        assert!(notice.is_stopped());
+        assert_eq!(notice.severity, FaultLevel::Info);
+        assert!(!notice.recoverable);
+        assert!(faults.try_recv().is_err());
+
+        let state = sensor.get_state();
+        assert!(!state.running);
+        assert_eq!(state.stopped_reason.as_deref(), Some("Stopped by request"));
+    }
+
+    #[tokio::test]
+    async fn test_phases_end_when_sensor_stops() {
+        use crate::phases::PhaseOutcome;
+
+        let mut sensor = EmotionSensor::new(SensorConfig::default());
+        assert_eq!(sensor.calibration_phase(), CalibrationPhase::Idle);
+
+        let mut phases = sensor.phases();
+        let outcome = phases.wait_for_phase(|phase| *phase == CalibrationPhase::Idle, Duration::from_millis(10)).await;
+        assert_eq!(outcome.unwrap(), PhaseOutcome::Reached(CalibrationPhase::Idle));
+
+        sensor.stop().await.unwrap();
+        let outcome = phases.wait_for_calibrated(Duration::from_secs(1)).await.unwrap();
+        assert_eq!(outcome, PhaseOutcome::SensorStopped);
+
+        // Subscribing after the stop still ends instead of waiting out the timeout
+        let outcome = sensor.phases().wait_for_calibrated(Duration::from_secs(60)).await.unwrap();
+        assert_eq!(outcome, PhaseOutcome::SensorStopped);
+    }
+
+    #[test]
+    fn test_calibration_phase_of_pipeline() {
+        let mut calibrator = AdaptiveCalibrator::new(Duration::ZERO, 0.05);
+        assert_eq!(
+            pipeline_phase(&calibrator, SensorCapability::Full),
+            CalibrationPhase::Collecting { progress: 0.0 }
+        );
+        assert!(pipeline_phase(&calibrator, SensorCapability::EmotionOffline).is_failed());
+
+        for _ in 0..30 {
+            calibrator.add_sample(0.5).unwrap();
+        }
+        assert_eq!(
+            pipeline_phase(&calibrator, SensorCapability::EmotionOffline),
+            CalibrationPhase::Calibrated { quality: calibrator.quality() }
+        );
+        calibrator.freeze();
+        assert_eq!(
+            pipeline_phase(&calibrator, SensorCapability::Full),
+            CalibrationPhase::Frozen { quality: calibrator.quality() }
+        );
+    }
+
+    /// The synthetic face, in the frames the script says
+    struct ScriptedFaces {
+        faces: SyntheticFaceDetector,
+        script: std::vec::IntoIter<bool>,
+    }
+
+    impl FaceDetectorBackend for ScriptedFaces {
+        fn name(&self) -> &'static str {
+            "scripted"
+        }
+
+        fn model_info(&self) -> &ModelInfo {
+            self.faces.model_info()
+        }
+
+        fn detect_faces(&mut self, image: &Mat) -> Result<Vec<crate::yunet::FaceDetection>, YuNetError> {
+            match self.script.next() {
+                Some(true) => self.faces.detect_faces(image),
+                _ => Ok(Vec::new()),
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_frames_without_a_face_skip_calibration() {
+        let pattern = MockPattern::Step;
+        let layout = EmotionLayout::STANDARD;
+        let (faults, _) = broadcast::channel(4);
+        let mut emotion = EmotionPipeline::new(
+            Box::new(SyntheticEmotion::new(&pattern, layout)),
+            SyntheticEmotion::factory(pattern, layout),
+            SensorConfig::default().degradation,
+            faults,
+        );
+        let conditioner = LogitConditioner::default();
+        let mut calibrator = AdaptiveCalibrator::with_defaults(Duration::from_secs(60));
+        let mut cadence = EmotionCadence::new();
+        let mut tracker = FaceTracker::new(Default::default());
+        let mut faces = ScriptedFaces {
+            faces: SyntheticFaceDetector::new(),
+            script: vec![true, false, false, false, true].into_iter(),
+        };
+        let mut frame = Mat::default();
+        assert!(SyntheticCamera::default().read_frame(&mut frame));
+
+        let mut seen = Vec::new();
+        for _ in 0..5 {
+            let (fear_frame, crop) = EmotionSensor::process_frame(
+                &frame,
+                &mut faces,
+                &mut tracker,
+                &mut emotion,
+                &conditioner,
+                &mut calibrator,
+                &mut cadence,
+                1,
+                Duration::from_secs(1),
+            )
+            .unwrap();
+            if !fear_frame.face_present {
+                assert!(crop.empty());
+                assert_eq!((fear_frame.confidence, fear_frame.face_bbox), (0.0, None));
+            }
+            seen.push((fear_frame.face_present, calibrator.baseline_stats().sample_count));
+        }
+
+        // The no-face streak leaves the sample count where it was
+        assert_eq!(seen, [(true, 1), (false, 1), (false, 1), (false, 1), (true, 2)]);
+    }
+
+    #[test]
+    fn test_bug_report_snapshot() {
+        let logs = LogRingBuffer::new(4);
+        logs.push("1 INFO spectre_sensor: started".to_string());
+        let sensor = EmotionSensor::new(SensorConfig::default()).with_log_buffer(logs);
+
+        let report = sensor.bug_report();
+        assert!(report.frames.is_empty());
+        assert!(report.config.privacy_mode);
+        assert!(report.baseline.is_none());
+        assert!(!report.status.running);
+        assert_eq!(report.logs, vec!["1 INFO spectre_sensor: started".to_string()]);
+    }
+
     #[tokio::test]
     async fn test_sensor_initialization() {
         let config = SensorConfig::default();
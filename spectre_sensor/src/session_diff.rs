@@ -0,0 +1,733 @@
+//! Session comparison for A/B content testing
+//!
+//! Loads two recorded fear traces, aligns them on a common timeline (either by
+//! normalized duration or by named markers the game stamped into the stream)
+//! and reports per-window fear statistics, time-in-bucket distributions and a
+//! Cohen's d effect size with a bootstrap confidence interval.
+//!
+//! Game event logs are timed in game time. When either the trace or the log
+//! carries a clock sync estimate, [`SessionTrace::merge_game_log`] maps the
+//! game's events onto the trace as markers, so they can be aligned on too.
+
+use crate::{
+    clock_sync::ClockSyncEstimate,
+    proto::{sensor_event, SensorEvent},
+    types::FearBucket,
+};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::fmt::Write as _;
+use std::path::Path;
+use std::time::Duration;
+use thiserror::Error;
+
+/// Session diff errors
+#[derive(Debug, Error)]
+pub enum SessionDiffError {
+    #[error("Failed to read trace '{path}': {message}")]
+    Io { path: String, message: String },
+
+    #[error("Invalid trace line {line}: {message}")]
+    Parse { line: usize, message: String },
+
+    #[error("Unsupported trace format: {0}")]
+    UnsupportedFormat(String),
+
+    #[error("Trace contains no score events")]
+    EmptyTrace,
+
+    #[error("Marker '{label}' not found in session {session}")]
+    MissingMarker { label: String, session: char },
+
+    #[error("Marker alignment needs at least two markers")]
+    NotEnoughMarkers,
+
+    #[error("Window count must be at least 1")]
+    InvalidWindowCount,
+
+    #[error("Neither the trace nor the game log carries a clock sync estimate")]
+    NoClockSync,
+}
+
+/// A single line of a recorded JSONL trace
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum TraceEvent {
+    /// A fear score measurement
+    Score { timestamp_us: u64, fear: f32 },
+    /// A named marker inserted by the game through `InsertMarker`
+    Marker { timestamp_us: u64, label: String },
+    /// The game's clock estimate, reported through `Ping`
+    ClockSync { timestamp_us: u64, clock_sync: ClockSyncEstimate },
+}
+
+impl TraceEvent {
+    /// Convert a streamed sensor event into a trace line, if it is recordable
+    pub fn from_sensor_event(event: &SensorEvent) -> Option<Self> {
+        match &event.event {
+            Some(sensor_event::Event::Score(score)) => Some(TraceEvent::Score {
+                timestamp_us: event.timestamp_us,
+                fear: score.normalized_fear,
+            }),
+            Some(sensor_event::Event::Marker(marker)) => Some(TraceEvent::Marker {
+                timestamp_us: event.timestamp_us,
+                label: marker.label.clone(),
+            }),
+            Some(sensor_event::Event::ClockSync(clock_sync)) => Some(TraceEvent::ClockSync {
+                timestamp_us: event.timestamp_us,
+                clock_sync: clock_sync.clone().into(),
+            }),
+            _ => None,
+        }
+    }
+
+    fn timestamp_us(&self) -> u64 {
+        match self {
+            TraceEvent::Score { timestamp_us, .. }
+            | TraceEvent::Marker { timestamp_us, .. }
+            | TraceEvent::ClockSync { timestamp_us, .. } => *timestamp_us,
+        }
+    }
+}
+
+/// A recorded session with timestamps relative to its first event (seconds)
+#[derive(Debug, Clone, Default)]
+pub struct SessionTrace {
+    /// (time, fear) samples in chronological order
+    pub scores: Vec<(f64, f32)>,
+    /// (time, label) markers in chronological order
+    pub markers: Vec<(f64, String)>,
+    /// Sensor wall time of the first event, in microseconds since Unix epoch
+    pub origin_us: u64,
+    /// Tightest clock sync estimate recorded during the session
+    pub clock_sync: Option<ClockSyncEstimate>,
+}
+
+impl SessionTrace {
+    /// Build a trace from events in any order
+    pub fn from_events(mut events: Vec<TraceEvent>) -> Self {
+        events.sort_by_key(|e| e.timestamp_us());
+        let origin = events.first().map(|e| e.timestamp_us()).unwrap_or(0);
+        let mut trace = Self { origin_us: origin, ..Self::default() };
+
+        for event in events {
+            let t = (event.timestamp_us() - origin) as f64 / 1_000_000.0;
+            match event {
+                TraceEvent::Score { fear, .. } if fear.is_finite() => trace.scores.push((t, fear)),
+                TraceEvent::Score { .. } => {}
+                TraceEvent::Marker { label, .. } => trace.markers.push((t, label)),
+                TraceEvent::ClockSync { clock_sync, .. } => {
+                    trace.clock_sync = Some(tighter(trace.clock_sync, clock_sync));
+                }
+            }
+        }
+
+        trace
+    }
+
+    /// Parse a JSONL trace from a string
+    pub fn from_jsonl(content: &str) -> Result<Self, SessionDiffError> {
+        Ok(Self::from_events(parse_jsonl(content)?))
+    }
+
+    /// Load a trace from disk
+    ///
+    /// Only JSONL traces are supported; Parquet exports must be converted first.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, SessionDiffError> {
+        Self::from_jsonl(&read_jsonl(path.as_ref())?)
+    }
+
+    /// Add a game log's events as markers on this trace's timeline
+    ///
+    /// Uses the tighter of the trace's and the log's clock sync estimates.
+    /// Returns the number of markers added.
+    pub fn merge_game_log(&mut self, log: &GameLog) -> Result<usize, SessionDiffError> {
+        let clock_sync = match (self.clock_sync, log.clock_sync) {
+            (Some(ours), Some(theirs)) => ours.tighter(theirs),
+            (Some(clock_sync), None) | (None, Some(clock_sync)) => clock_sync,
+            (None, None) => return Err(SessionDiffError::NoClockSync),
+        };
+
+        for (game_time_us, label) in &log.events {
+            let wall_us = clock_sync.game_to_sensor_wall_us(Duration::from_micros(*game_time_us));
+            let t = (wall_us - self.origin_us as i64) as f64 / 1_000_000.0;
+            self.markers.push((t, label.clone()));
+        }
+        self.markers.sort_by(|a, b| a.0.total_cmp(&b.0));
+        Ok(log.events.len())
+    }
+
+    /// Total duration covered by score samples
+    pub fn duration(&self) -> f64 {
+        self.scores.last().map(|(t, _)| *t).unwrap_or(0.0)
+    }
+
+    fn marker_time(&self, label: &str) -> Option<f64> {
+        self.markers.iter().find(|(_, l)| l == label).map(|(t, _)| *t)
+    }
+
+    fn scores_between(&self, start: f64, end: f64, inclusive_end: bool) -> Vec<f32> {
+        self.scores
+            .iter()
+            .filter(|(t, _)| *t >= start && (*t < end || (inclusive_end && *t <= end)))
+            .map(|(_, fear)| *fear)
+            .collect()
+    }
+}
+
+/// A single line of a game event log
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum GameLogEvent {
+    /// A named gameplay event, in microseconds since the game's first update
+    Event { game_time_us: u64, label: String },
+    /// The game's clock estimate at the time it was logged
+    ClockSync { game_time_us: u64, clock_sync: ClockSyncEstimate },
+}
+
+/// A game event log, timed in game time
+#[derive(Debug, Clone, Default)]
+pub struct GameLog {
+    /// (game time in microseconds, label) events in chronological order
+    pub events: Vec<(u64, String)>,
+    /// Tightest clock sync estimate in the log
+    pub clock_sync: Option<ClockSyncEstimate>,
+}
+
+impl GameLog {
+    /// Build a log from events in any order
+    pub fn from_events(events: Vec<GameLogEvent>) -> Self {
+        let mut log = Self::default();
+        for event in events {
+            match event {
+                GameLogEvent::Event { game_time_us, label } => log.events.push((game_time_us, label)),
+                GameLogEvent::ClockSync { clock_sync, .. } => {
+                    log.clock_sync = Some(tighter(log.clock_sync, clock_sync));
+                }
+            }
+        }
+        log.events.sort_by_key(|(game_time_us, _)| *game_time_us);
+        log
+    }
+
+    /// Parse a JSONL game log from a string
+    pub fn from_jsonl(content: &str) -> Result<Self, SessionDiffError> {
+        Ok(Self::from_events(parse_jsonl(content)?))
+    }
+
+    /// Load a JSONL game log from disk
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, SessionDiffError> {
+        Self::from_jsonl(&read_jsonl(path.as_ref())?)
+    }
+}
+
+fn tighter(current: Option<ClockSyncEstimate>, candidate: ClockSyncEstimate) -> ClockSyncEstimate {
+    current.map_or(candidate, |current| current.tighter(candidate))
+}
+
+fn parse_jsonl<T: DeserializeOwned>(content: &str) -> Result<Vec<T>, SessionDiffError> {
+    let mut events = Vec::new();
+    for (index, line) in content.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        let event = serde_json::from_str(line).map_err(|e| SessionDiffError::Parse {
+            line: index + 1,
+            message: e.to_string(),
+        })?;
+        events.push(event);
+    }
+    Ok(events)
+}
+
+fn read_jsonl(path: &Path) -> Result<String, SessionDiffError> {
+    if path.extension().is_some_and(|ext| ext == "parquet") {
+        return Err(SessionDiffError::UnsupportedFormat(
+            "parquet traces are not supported yet, export as JSONL".to_string(),
+        ));
+    }
+
+    std::fs::read_to_string(path).map_err(|e| SessionDiffError::Io {
+        path: path.display().to_string(),
+        message: e.to_string(),
+    })
+}
+
+/// How two sessions are put on a common timeline
+#[derive(Debug, Clone, PartialEq)]
+pub enum Alignment {
+    /// Split each session into `windows` equal slices of its own duration
+    Duration { windows: usize },
+    /// Split each session at the given markers, which both sessions must contain
+    Markers(Vec<String>),
+}
+
+/// Fear statistics for a set of samples
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct FearStats {
+    pub samples: usize,
+    pub mean: f32,
+    pub peak: f32,
+}
+
+impl FearStats {
+    /// Compute statistics for a slice of fear values
+    pub fn from_samples(samples: &[f32]) -> Self {
+        if samples.is_empty() {
+            return Self::default();
+        }
+        Self {
+            samples: samples.len(),
+            mean: mean(samples) as f32,
+            peak: samples.iter().copied().fold(f32::MIN, f32::max),
+        }
+    }
+}
+
+/// Fraction of samples spent in each fear bucket
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct BucketDistribution {
+    pub low: f32,
+    pub medium: f32,
+    pub high: f32,
+}
+
+impl BucketDistribution {
+    /// Compute the distribution for a slice of fear values
+    pub fn from_samples(samples: &[f32]) -> Self {
+        if samples.is_empty() {
+            return Self::default();
+        }
+        let mut counts = [0usize; 3];
+        for &fear in samples {
+            let index = match FearBucket::from_score(fear) {
+                FearBucket::Low => 0,
+                FearBucket::Medium => 1,
+                FearBucket::High => 2,
+            };
+            counts[index] += 1;
+        }
+        let total = samples.len() as f32;
+        Self {
+            low: counts[0] as f32 / total,
+            medium: counts[1] as f32 / total,
+            high: counts[2] as f32 / total,
+        }
+    }
+}
+
+/// Side-by-side statistics for one aligned window
+#[derive(Debug, Clone, Serialize)]
+pub struct WindowDiff {
+    pub label: String,
+    pub a: FearStats,
+    pub b: FearStats,
+    /// Difference in mean fear (B - A)
+    pub mean_delta: f32,
+}
+
+/// Standardized mean difference with a bootstrap confidence interval
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct EffectSize {
+    /// Cohen's d of B relative to A (positive means B was scarier)
+    pub cohens_d: f32,
+    /// Lower bound of the 95% bootstrap interval
+    pub ci_low: f32,
+    /// Upper bound of the 95% bootstrap interval
+    pub ci_high: f32,
+    /// Number of bootstrap resamples used
+    pub resamples: usize,
+}
+
+/// Options controlling the comparison
+#[derive(Debug, Clone)]
+pub struct DiffOptions {
+    pub alignment: Alignment,
+    /// Number of bootstrap resamples for the effect size interval
+    pub bootstrap_resamples: usize,
+    /// Seed for the bootstrap RNG so reports are reproducible
+    pub seed: u64,
+}
+
+impl Default for DiffOptions {
+    fn default() -> Self {
+        Self {
+            alignment: Alignment::Duration { windows: 10 },
+            bootstrap_resamples: 1000,
+            seed: 42,
+        }
+    }
+}
+
+/// Full comparison report between session A and session B
+#[derive(Debug, Clone, Serialize)]
+pub struct SessionDiffReport {
+    pub overall_a: FearStats,
+    pub overall_b: FearStats,
+    pub buckets_a: BucketDistribution,
+    pub buckets_b: BucketDistribution,
+    pub windows: Vec<WindowDiff>,
+    pub effect_size: EffectSize,
+}
+
+impl SessionDiffReport {
+    /// Render the report as JSON
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap_or_default()
+    }
+
+    /// Render the report as human-readable text
+    pub fn to_text(&self) -> String {
+        let mut out = String::new();
+        let _ = writeln!(out, "Session diff (B vs A)");
+        let _ = writeln!(
+            out,
+            "  overall: A mean={:.3} peak={:.3} n={} | B mean={:.3} peak={:.3} n={}",
+            self.overall_a.mean, self.overall_a.peak, self.overall_a.samples,
+            self.overall_b.mean, self.overall_b.peak, self.overall_b.samples,
+        );
+        let _ = writeln!(
+            out,
+            "  time in bucket: A low={:.0}% med={:.0}% high={:.0}% | B low={:.0}% med={:.0}% high={:.0}%",
+            self.buckets_a.low * 100.0, self.buckets_a.medium * 100.0, self.buckets_a.high * 100.0,
+            self.buckets_b.low * 100.0, self.buckets_b.medium * 100.0, self.buckets_b.high * 100.0,
+        );
+        let _ = writeln!(
+            out,
+            "  effect size: d={:.3} (95% CI {:.3}..{:.3}, {} resamples)",
+            self.effect_size.cohens_d, self.effect_size.ci_low, self.effect_size.ci_high,
+            self.effect_size.resamples,
+        );
+        let _ = writeln!(out, "  windows:");
+        for window in &self.windows {
+            let _ = writeln!(
+                out,
+                "    {:<24} A mean={:.3} peak={:.3} | B mean={:.3} peak={:.3} | delta={:+.3}",
+                window.label, window.a.mean, window.a.peak, window.b.mean, window.b.peak, window.mean_delta,
+            );
+        }
+        out
+    }
+}
+
+/// Split a session into labelled windows according to the alignment
+pub fn window_session(
+    trace: &SessionTrace,
+    alignment: &Alignment,
+    session: char,
+) -> Result<Vec<(String, Vec<f32>)>, SessionDiffError> {
+    if trace.scores.is_empty() {
+        return Err(SessionDiffError::EmptyTrace);
+    }
+
+    match alignment {
+        Alignment::Duration { windows } => {
+            if *windows == 0 {
+                return Err(SessionDiffError::InvalidWindowCount);
+            }
+            let duration = trace.duration();
+            let step = duration / *windows as f64;
+            Ok((0..*windows)
+                .map(|i| {
+                    let start = step * i as f64;
+                    let end = step * (i + 1) as f64;
+                    let last = i + 1 == *windows;
+                    let label = format!("{:.0}%-{:.0}%", i as f64 * 100.0 / *windows as f64,
+                        (i + 1) as f64 * 100.0 / *windows as f64);
+                    (label, trace.scores_between(start, end, last))
+                })
+                .collect())
+        }
+        Alignment::Markers(labels) => {
+            if labels.len() < 2 {
+                return Err(SessionDiffError::NotEnoughMarkers);
+            }
+            let times = labels
+                .iter()
+                .map(|label| {
+                    trace.marker_time(label).ok_or_else(|| SessionDiffError::MissingMarker {
+                        label: label.clone(),
+                        session,
+                    })
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+
+            Ok(labels
+                .windows(2)
+                .zip(times.windows(2))
+                .map(|(names, bounds)| {
+                    (format!("{}..{}", names[0], names[1]), trace.scores_between(bounds[0], bounds[1], false))
+                })
+                .collect())
+        }
+    }
+}
+
+/// Compare two sessions
+pub fn diff_sessions(
+    a: &SessionTrace,
+    b: &SessionTrace,
+    options: &DiffOptions,
+) -> Result<SessionDiffReport, SessionDiffError> {
+    let windows_a = window_session(a, &options.alignment, 'A')?;
+    let windows_b = window_session(b, &options.alignment, 'B')?;
+
+    let windows = windows_a
+        .iter()
+        .zip(&windows_b)
+        .map(|((label, samples_a), (_, samples_b))| {
+            let a = FearStats::from_samples(samples_a);
+            let b = FearStats::from_samples(samples_b);
+            let mean_delta = b.mean - a.mean;
+            WindowDiff { label: label.clone(), a, b, mean_delta }
+        })
+        .collect();
+
+    // Only samples inside aligned windows take part in the overall comparison
+    let all_a: Vec<f32> = windows_a.into_iter().flat_map(|(_, s)| s).collect();
+    let all_b: Vec<f32> = windows_b.into_iter().flat_map(|(_, s)| s).collect();
+
+    Ok(SessionDiffReport {
+        overall_a: FearStats::from_samples(&all_a),
+        overall_b: FearStats::from_samples(&all_b),
+        buckets_a: BucketDistribution::from_samples(&all_a),
+        buckets_b: BucketDistribution::from_samples(&all_b),
+        windows,
+        effect_size: bootstrap_effect_size(&all_a, &all_b, options.bootstrap_resamples, options.seed),
+    })
+}
+
+/// Cohen's d of `b` relative to `a` using the pooled standard deviation
+pub fn cohens_d(a: &[f32], b: &[f32]) -> f32 {
+    if a.len() < 2 || b.len() < 2 {
+        return 0.0;
+    }
+    let (mean_a, mean_b) = (mean(a), mean(b));
+    let (var_a, var_b) = (variance(a, mean_a), variance(b, mean_b));
+    let (n_a, n_b) = (a.len() as f64, b.len() as f64);
+    let pooled = (((n_a - 1.0) * var_a + (n_b - 1.0) * var_b) / (n_a + n_b - 2.0)).sqrt();
+
+    if pooled <= f64::EPSILON {
+        return 0.0;
+    }
+    ((mean_b - mean_a) / pooled) as f32
+}
+
+/// Cohen's d with a percentile bootstrap 95% confidence interval
+pub fn bootstrap_effect_size(a: &[f32], b: &[f32], resamples: usize, seed: u64) -> EffectSize {
+    let cohens_d = cohens_d(a, b);
+    if resamples == 0 || a.is_empty() || b.is_empty() {
+        return EffectSize { cohens_d, ci_low: cohens_d, ci_high: cohens_d, resamples: 0 };
+    }
+
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut resample_a = vec![0.0; a.len()];
+    let mut resample_b = vec![0.0; b.len()];
+    let mut estimates = Vec::with_capacity(resamples);
+
+    for _ in 0..resamples {
+        for slot in resample_a.iter_mut() {
+            *slot = a[rng.gen_range(0..a.len())];
+        }
+        for slot in resample_b.iter_mut() {
+            *slot = b[rng.gen_range(0..b.len())];
+        }
+        estimates.push(self::cohens_d(&resample_a, &resample_b));
+    }
+
+    estimates.sort_by(|x, y| x.total_cmp(y));
+    let percentile = |p: f64| estimates[((estimates.len() - 1) as f64 * p).round() as usize];
+
+    EffectSize {
+        cohens_d,
+        ci_low: percentile(0.025),
+        ci_high: percentile(0.975),
+        resamples,
+    }
+}
+
+fn mean(samples: &[f32]) -> f64 {
+    samples.iter().map(|&s| s as f64).sum::<f64>() / samples.len() as f64
+}
+
+fn variance(samples: &[f32], mean: f64) -> f64 {
+    samples.iter().map(|&s| (s as f64 - mean).powi(2)).sum::<f64>() / (samples.len() as f64 - 1.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Build a trace sampled at 10 Hz from a fear function of time
+    fn synthetic(duration_s: u64, fear: impl Fn(f64) -> f32, markers: &[(f64, &str)]) -> SessionTrace {
+        let mut events: Vec<TraceEvent> = (0..=duration_s * 10)
+            .map(|i| {
+                let t = i as f64 / 10.0;
+                TraceEvent::Score { timestamp_us: 1_000_000 + (t * 1_000_000.0) as u64, fear: fear(t) }
+            })
+            .collect();
+        for (t, label) in markers {
+            events.push(TraceEvent::Marker {
+                timestamp_us: 1_000_000 + (t * 1_000_000.0) as u64,
+                label: label.to_string(),
+            });
+        }
+        SessionTrace::from_events(events)
+    }
+
+    #[test]
+    fn test_jsonl_round_trip() {
+        let content = r#"{"type":"score","timestamp_us":2000000,"fear":0.4}
+
+{"type":"marker","timestamp_us":1500000,"label":"door"}
+{"type":"score","timestamp_us":1000000,"fear":0.2}"#;
+        let trace = SessionTrace::from_jsonl(content).unwrap();
+
+        assert_eq!(trace.scores, vec![(0.0, 0.2), (1.0, 0.4)]);
+        assert_eq!(trace.markers, vec![(0.5, "door".to_string())]);
+
+        let err = SessionTrace::from_jsonl("{\"type\":\"bogus\"}").unwrap_err();
+        assert!(matches!(err, SessionDiffError::Parse { line: 1, .. }));
+    }
+
+    #[test]
+    fn test_game_log_aligned_through_clock_sync() {
+        // The game started 5 s after the sensor's first score; two pings were
+        // recorded, the later one with a tighter bound
+        let game_start_us = 1_000_000 + 5_000_000;
+        let estimate = |error_us| ClockSyncEstimate {
+            wall_offset_us: game_start_us,
+            monotonic_offset_us: 0,
+            error_us,
+            confidence: 0.5,
+            samples: 4,
+        };
+        let mut trace = synthetic(30, |t| if (15.0..20.0).contains(&t) { 0.9 } else { 0.2 }, &[]);
+        let loose = ClockSyncEstimate { wall_offset_us: 0, ..estimate(9_000) };
+        let recorded = [
+            TraceEvent::ClockSync { timestamp_us: 7_000_000, clock_sync: loose },
+            TraceEvent::ClockSync { timestamp_us: 9_000_000, clock_sync: estimate(800) },
+        ];
+        let jsonl: String = recorded.iter().map(|e| serde_json::to_string(e).unwrap() + "\n").collect();
+        let synced = SessionTrace::from_jsonl(&jsonl).unwrap();
+        assert_eq!(synced.clock_sync, Some(estimate(800)));
+        trace.clock_sync = synced.clock_sync;
+
+        // The game logged the scare 10 s into its own clock
+        let log = GameLog::from_jsonl(
+            r#"{"type":"event","game_time_us":10000000,"label":"scare"}
+{"type":"event","game_time_us":15000000,"label":"calm"}
+{"type":"event","game_time_us":0,"label":"start"}"#,
+        )
+        .unwrap();
+        assert_eq!(trace.merge_game_log(&log).unwrap(), 3);
+        assert_eq!(
+            trace.markers,
+            vec![(5.0, "start".to_string()), (15.0, "scare".to_string()), (20.0, "calm".to_string())]
+        );
+
+        let labels = ["start", "scare", "calm"].map(String::from).to_vec();
+        let windows = window_session(&trace, &Alignment::Markers(labels), 'A').unwrap();
+        assert!(windows[1].1.iter().all(|&fear| fear == 0.9));
+
+        // A log's own estimate works when the trace has none
+        let mut unsynced = synthetic(30, |_| 0.2, &[]);
+        assert!(matches!(unsynced.merge_game_log(&log), Err(SessionDiffError::NoClockSync)));
+        let logged = GameLog::from_events(vec![GameLogEvent::ClockSync { game_time_us: 0, clock_sync: estimate(300) }]);
+        assert_eq!(logged.clock_sync, Some(estimate(300)));
+        unsynced.merge_game_log(&GameLog { events: log.events.clone(), ..logged }).unwrap();
+        assert_eq!(unsynced.marker_time("scare"), Some(15.0));
+    }
+
+    #[test]
+    fn test_duration_windowing() {
+        // Session A lasts 10 s, session B 20 s; both ramp 0 -> 1 so windows line up
+        let a = synthetic(10, |t| (t / 10.0) as f32, &[]);
+        let b = synthetic(20, |t| (t / 20.0) as f32, &[]);
+        let alignment = Alignment::Duration { windows: 4 };
+
+        let windows_a = window_session(&a, &alignment, 'A').unwrap();
+        let windows_b = window_session(&b, &alignment, 'B').unwrap();
+        assert_eq!(windows_a.len(), 4);
+        assert_eq!(windows_a[0].0, "0%-25%");
+
+        // Every sample lands in exactly one window
+        let total: usize = windows_a.iter().map(|(_, s)| s.len()).sum();
+        assert_eq!(total, a.scores.len());
+
+        for ((_, sa), (_, sb)) in windows_a.iter().zip(&windows_b) {
+            let (ma, mb) = (mean(sa), mean(sb));
+            assert!((ma - mb).abs() < 0.05, "normalized windows should match: {ma} vs {mb}");
+        }
+
+        assert!(matches!(
+            window_session(&a, &Alignment::Duration { windows: 0 }, 'A'),
+            Err(SessionDiffError::InvalidWindowCount)
+        ));
+    }
+
+    #[test]
+    fn test_marker_alignment() {
+        // The scare happens at different absolute times but between the same markers
+        let a = synthetic(30, |t| if (10.0..15.0).contains(&t) { 0.9 } else { 0.2 },
+            &[(0.0, "start"), (10.0, "scare"), (15.0, "calm"), (30.0, "end")]);
+        let b = synthetic(30, |t| if (20.0..25.0).contains(&t) { 0.9 } else { 0.2 },
+            &[(0.0, "start"), (20.0, "scare"), (25.0, "calm"), (30.0, "end")]);
+        let labels = ["start", "scare", "calm", "end"].map(String::from).to_vec();
+        let options = DiffOptions { alignment: Alignment::Markers(labels), ..Default::default() };
+
+        let report = diff_sessions(&a, &b, &options).unwrap();
+        assert_eq!(report.windows.len(), 3);
+        assert_eq!(report.windows[1].label, "scare..calm");
+        assert!((report.windows[1].a.mean - 0.9).abs() < 1e-6);
+        assert!((report.windows[1].b.mean - 0.9).abs() < 1e-6);
+        assert!(report.windows.iter().all(|w| w.mean_delta.abs() < 1e-6));
+
+        let missing = DiffOptions {
+            alignment: Alignment::Markers(vec!["start".into(), "boss".into()]),
+            ..Default::default()
+        };
+        assert!(matches!(
+            diff_sessions(&a, &b, &missing),
+            Err(SessionDiffError::MissingMarker { session: 'A', .. })
+        ));
+    }
+
+    #[test]
+    fn test_effect_size_on_known_difference() {
+        // B is shifted up by one pooled standard deviation
+        let a = synthetic(60, |t| 0.4 + 0.1 * (t * 1.3).sin() as f32, &[]);
+        let b = synthetic(60, |t| 0.4 + 0.1 * (t * 1.3).sin() as f32 + 0.0707, &[]);
+        let report = diff_sessions(&a, &b, &DiffOptions::default()).unwrap();
+
+        let d = report.effect_size.cohens_d;
+        assert!((d - 1.0).abs() < 0.1, "expected d close to 1.0, got {d}");
+        assert!(report.effect_size.ci_low <= d && d <= report.effect_size.ci_high);
+        assert!(report.effect_size.ci_low > 0.0, "interval should exclude zero");
+
+        // Identical sessions have no effect
+        let same = diff_sessions(&a, &a, &DiffOptions::default()).unwrap();
+        assert!(same.effect_size.cohens_d.abs() < 1e-6);
+        assert!(same.effect_size.ci_low <= 0.0 && same.effect_size.ci_high >= 0.0);
+
+        // Same seed gives the same interval
+        let again = diff_sessions(&a, &b, &DiffOptions::default()).unwrap();
+        assert_eq!(report.effect_size, again.effect_size);
+    }
+
+    #[test]
+    fn test_bucket_distribution_and_output() {
+        let a = synthetic(9, |t| if t < 3.0 { 0.1 } else if t < 6.0 { 0.5 } else { 0.9 }, &[]);
+        let report = diff_sessions(&a, &a, &DiffOptions::default()).unwrap();
+
+        let buckets = &report.buckets_a;
+        assert!((buckets.low + buckets.medium + buckets.high - 1.0).abs() < 1e-6);
+        assert!((buckets.low - 1.0 / 3.0).abs() < 0.02);
+        assert!((buckets.high - 1.0 / 3.0).abs() < 0.02);
+
+        assert!(report.to_text().contains("effect size"));
+        let json: serde_json::Value = serde_json::from_str(&report.to_json()).unwrap();
+        assert_eq!(json["windows"].as_array().unwrap().len(), 10);
+    }
+}
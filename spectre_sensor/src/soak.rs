@@ -0,0 +1,414 @@
+//! Invariants checked over a soak test's event stream
+//!
+//! [`SoakVerifier`] watches the events one client receives for a whole run
+//! and records every violation instead of stopping at the first: sequence
+//! numbers that repeat or jump too far, scores that fall silent or run
+//! backwards in time, fear outside [0, 1], calibration progress going back
+//! without a reset, a dropped-event count that disagrees with the daemon's,
+//! and resident memory that keeps growing. `sensor_fuzzer soak-test --verify`
+//! runs it against a mock daemon playing fuzzer output and exits non-zero
+//! when [`SoakReport::passed`] is false.
+
+use crate::{
+    phases::CalibrationPhase,
+    proto::{sensor_event, ClientInfo, SensorEvent},
+};
+use std::time::Duration;
+
+/// Bounds a soak run must stay within
+#[derive(Debug, Clone, PartialEq)]
+pub struct SoakLimits {
+    /// Most events that may be missing between two received ones
+    pub max_sequence_gap: u64,
+    /// Longest time between the capture of two consecutive scores
+    pub max_score_gap: Duration,
+    /// Share of events that may be dropped over the whole run
+    pub max_dropped_ratio: f64,
+    /// How far resident memory may grow past the first sample after warm-up
+    pub max_rss_growth: u64,
+}
+
+impl Default for SoakLimits {
+    fn default() -> Self {
+        Self {
+            max_sequence_gap: 30,
+            max_score_gap: Duration::from_secs(1),
+            max_dropped_ratio: 0.01,
+            max_rss_growth: 64 * 1024 * 1024,
+        }
+    }
+}
+
+/// Event counts and violations of a soak run
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SoakReport {
+    pub events: u64,
+    pub scores: u64,
+    pub calibration_updates: u64,
+    pub faults: u64,
+    pub markers: u64,
+    /// Events missing from the stream, from jumps in sequence numbers
+    pub dropped: u64,
+    /// Calibration resets requested during the run
+    pub recalibrations: u64,
+    /// Resident memory at the end of warm-up and at its highest since, in bytes
+    pub rss_baseline: Option<u64>,
+    pub rss_peak: Option<u64>,
+    pub violations: Vec<String>,
+}
+
+impl SoakReport {
+    /// Whether every invariant held
+    pub fn passed(&self) -> bool {
+        self.violations.is_empty()
+    }
+}
+
+/// Checks the events of one stream against [`SoakLimits`]
+#[derive(Debug, Clone)]
+pub struct SoakVerifier {
+    limits: SoakLimits,
+    report: SoakReport,
+    last_sequence: Option<u64>,
+    /// Sequence number of each event that followed a gap, and the gap
+    gaps: Vec<(u64, u64)>,
+    last_score_us: Option<u64>,
+    last_phase: Option<CalibrationPhase>,
+    /// A reset was requested and calibration has not started over since
+    reset_pending: bool,
+}
+
+impl SoakVerifier {
+    pub fn new(limits: SoakLimits) -> Self {
+        Self {
+            limits,
+            report: SoakReport::default(),
+            last_sequence: None,
+            gaps: Vec::new(),
+            last_score_us: None,
+            last_phase: None,
+            reset_pending: false,
+        }
+    }
+
+    /// Check one received event
+    pub fn observe(&mut self, event: &SensorEvent) {
+        self.report.events += 1;
+        self.check_sequence(event.sequence);
+        match &event.event {
+            Some(sensor_event::Event::Score(score)) => {
+                self.report.scores += 1;
+                let fear = score.normalized_fear;
+                if !(0.0..=1.0).contains(&fear) {
+                    self.violation(format!("Fear {} outside [0, 1] in event {}", fear, event.sequence));
+                }
+                self.check_score_time(event.timestamp_us, event.sequence);
+            }
+            Some(sensor_event::Event::CalibrationProgress(progress)) => {
+                self.report.calibration_updates += 1;
+                self.check_calibration(CalibrationPhase::from(progress), event.sequence);
+            }
+            Some(sensor_event::Event::SensorFault(_)) => self.report.faults += 1,
+            Some(sensor_event::Event::Marker(_)) => self.report.markers += 1,
+            _ => {}
+        }
+    }
+
+    /// Note a calibration reset, after which progress may start over
+    pub fn recalibration_requested(&mut self) {
+        self.report.recalibrations += 1;
+        self.reset_pending = true;
+    }
+
+    /// Compare the gaps seen so far with the daemon's count for this stream
+    ///
+    /// `client` is the daemon's listing of the stream. Call it once an event
+    /// numbered at least two past the listed `events_delivered +
+    /// events_dropped` has been observed, so every drop the listing counts
+    /// has shown up as a gap, whichever side of the listing it fell on.
+    pub fn reconcile_dropped(&mut self, client: &ClientInfo) {
+        let listed = client.events_delivered + client.events_dropped;
+        let seen_by_listing: u64 = self.gaps.iter().filter(|(sequence, _)| *sequence <= listed).map(|(_, gap)| gap).sum();
+        if client.events_dropped < seen_by_listing || client.events_dropped > self.report.dropped {
+            self.violation(format!(
+                "Daemon counted {} dropped events, the stream showed {} by event {} and {} in all",
+                client.events_dropped, seen_by_listing, listed, self.report.dropped
+            ));
+        }
+    }
+
+    /// Record a resident memory sample; `warm` once warm-up is over
+    ///
+    /// The first warm sample is the baseline later ones may grow past by at
+    /// most `max_rss_growth`.
+    pub fn record_memory(&mut self, rss: u64, warm: bool) {
+        if !warm {
+            return;
+        }
+        let baseline = *self.report.rss_baseline.get_or_insert(rss);
+        let peak = self.report.rss_peak.map_or(rss, |peak| peak.max(rss));
+        let was_within = self.report.rss_peak.is_none_or(|peak| peak - baseline <= self.limits.max_rss_growth);
+        self.report.rss_peak = Some(peak);
+        if was_within && peak - baseline > self.limits.max_rss_growth {
+            self.violation(format!(
+                "Resident memory grew by {} KiB after warm-up, from {} KiB to {} KiB",
+                (peak - baseline) / 1024,
+                baseline / 1024,
+                peak / 1024
+            ));
+        }
+    }
+
+    /// Record a violation found outside the events, e.g. the stream failing
+    pub fn record_violation(&mut self, message: impl Into<String>) {
+        self.violation(message.into());
+    }
+
+    /// Calibration phase of the last calibration update observed
+    pub fn calibration_phase(&self) -> Option<&CalibrationPhase> {
+        self.last_phase.as_ref()
+    }
+
+    /// Sequence number of the last event observed
+    pub fn last_sequence(&self) -> Option<u64> {
+        self.last_sequence
+    }
+
+    /// Finish the run, checking the share of dropped events
+    pub fn finish(mut self) -> SoakReport {
+        let numbered = self.report.events + self.report.dropped;
+        if numbered > 0 {
+            let ratio = self.report.dropped as f64 / numbered as f64;
+            if ratio > self.limits.max_dropped_ratio {
+                self.violation(format!(
+                    "Dropped {} of {} events ({:.2}%), more than the {:.2}% allowed",
+                    self.report.dropped,
+                    numbered,
+                    ratio * 100.0,
+                    self.limits.max_dropped_ratio * 100.0
+                ));
+            }
+        }
+        if self.report.scores == 0 {
+            self.violation("No scores received".to_string());
+        }
+        self.report
+    }
+
+    fn check_sequence(&mut self, sequence: u64) {
+        let expected = self.last_sequence.map_or(1, |last| last + 1);
+        if sequence < expected {
+            self.violation(format!("Event {} arrived after event {}", sequence, expected - 1));
+            return;
+        }
+        let gap = sequence - expected;
+        if gap > 0 {
+            self.report.dropped += gap;
+            self.gaps.push((sequence, gap));
+            if gap > self.limits.max_sequence_gap {
+                self.violation(format!(
+                    "{} events missing before event {}, more than the {} allowed",
+                    gap, sequence, self.limits.max_sequence_gap
+                ));
+            }
+        }
+        self.last_sequence = Some(sequence);
+    }
+
+    fn check_score_time(&mut self, timestamp_us: u64, sequence: u64) {
+        if let Some(last) = self.last_score_us {
+            if timestamp_us < last {
+                self.violation(format!(
+                    "Score in event {} captured {} us before the previous one",
+                    sequence,
+                    last - timestamp_us
+                ));
+            } else if Duration::from_micros(timestamp_us - last) > self.limits.max_score_gap {
+                self.violation(format!(
+                    "No score for {:?} before event {}, more than the {:?} allowed",
+                    Duration::from_micros(timestamp_us - last),
+                    sequence,
+                    self.limits.max_score_gap
+                ));
+            }
+        }
+        self.last_score_us = Some(self.last_score_us.map_or(timestamp_us, |last| last.max(timestamp_us)));
+    }
+
+    fn check_calibration(&mut self, phase: CalibrationPhase, sequence: u64) {
+        let progress = phase.progress();
+        if !(0.0..=1.0).contains(&progress) {
+            self.violation(format!("Calibration progress {} outside [0, 1] in event {}", progress, sequence));
+        }
+        // Progress only goes back, or calibration starts over, after a reset
+        let restarted = match (&self.last_phase, &phase) {
+            (Some(last), CalibrationPhase::Idle | CalibrationPhase::Collecting { .. }) => {
+                last.is_calibrated() || progress < last.progress()
+            }
+            _ => false,
+        };
+        if restarted {
+            if self.reset_pending {
+                self.reset_pending = false;
+            } else {
+                let last = self.last_phase.as_ref().map_or(0.0, CalibrationPhase::progress);
+                self.violation(format!(
+                    "Calibration progress went back from {:.3} to {:.3} in event {} without a reset",
+                    last, progress, sequence
+                ));
+            }
+        }
+        self.last_phase = Some(phase);
+    }
+
+    fn violation(&mut self, message: String) {
+        tracing::error!("Soak invariant violated: {}", message);
+        self.report.violations.push(message);
+    }
+}
+
+/// Resident memory of this process in bytes, where the platform reports it
+pub fn resident_memory() -> Option<u64> {
+    #[cfg(target_os = "linux")]
+    {
+        let status = std::fs::read_to_string("/proc/self/status").ok()?;
+        let line = status.lines().find(|line| line.starts_with("VmRSS:"))?;
+        let kib: u64 = line.split_whitespace().nth(1)?.parse().ok()?;
+        Some(kib * 1024)
+    }
+    #[cfg(not(target_os = "linux"))]
+    {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::proto::{CalibrationProgress, CalibrationState, Score};
+
+    fn score(sequence: u64, timestamp_us: u64, fear: f32) -> SensorEvent {
+        SensorEvent {
+            timestamp_us,
+            sequence,
+            event: Some(sensor_event::Event::Score(Score { normalized_fear: fear, ..Default::default() })),
+        }
+    }
+
+    fn collecting(sequence: u64, progress: f32) -> SensorEvent {
+        SensorEvent {
+            timestamp_us: 0,
+            sequence,
+            event: Some(sensor_event::Event::CalibrationProgress(CalibrationProgress {
+                progress,
+                state: CalibrationState::Collecting as i32,
+                ..Default::default()
+            })),
+        }
+    }
+
+    fn calibrated(sequence: u64) -> SensorEvent {
+        SensorEvent {
+            timestamp_us: 0,
+            sequence,
+            event: Some(sensor_event::Event::CalibrationProgress(CalibrationProgress {
+                progress: 1.0,
+                completed: true,
+                state: CalibrationState::Calibrated as i32,
+                ..Default::default()
+            })),
+        }
+    }
+
+    #[test]
+    fn test_clean_stream_passes() {
+        let mut verifier = SoakVerifier::new(SoakLimits::default());
+        for i in 1..=100 {
+            verifier.observe(&score(i, i * 33_000, (i % 10) as f32 / 10.0));
+        }
+        verifier.record_memory(50 << 20, false);
+        verifier.record_memory(40 << 20, true);
+        verifier.record_memory(60 << 20, true);
+        let report = verifier.finish();
+        assert!(report.passed(), "{:?}", report.violations);
+        assert_eq!((report.events, report.scores, report.dropped), (100, 100, 0));
+        assert_eq!((report.rss_baseline, report.rss_peak), (Some(40 << 20), Some(60 << 20)));
+    }
+
+    #[test]
+    fn test_violations_are_all_recorded() {
+        let limits = SoakLimits { max_sequence_gap: 5, max_dropped_ratio: 1.0, ..SoakLimits::default() };
+        let mut verifier = SoakVerifier::new(limits);
+        verifier.observe(&score(1, 1_000_000, 0.5));
+        verifier.observe(&score(2, 1_100_000, 1.5));
+        // Ten events lost, then a score 2 s after the last
+        verifier.observe(&score(13, 3_100_000, 0.5));
+        verifier.observe(&score(13, 3_200_000, 0.5));
+        verifier.observe(&score(14, 3_000_000, 0.5));
+        verifier.record_memory(10 << 20, true);
+        verifier.record_memory(100 << 20, true);
+        verifier.record_memory(200 << 20, true);
+
+        let report = verifier.finish();
+        assert_eq!(report.dropped, 10);
+        let violations = report.violations.join("\n");
+        assert_eq!(report.violations.len(), 6, "{}", violations);
+        assert!(violations.contains("Fear 1.5 outside [0, 1]"), "{}", violations);
+        assert!(violations.contains("10 events missing before event 13"), "{}", violations);
+        assert!(violations.contains("No score for 2s before event 13"), "{}", violations);
+        assert!(violations.contains("Event 13 arrived after event 13"), "{}", violations);
+        assert!(violations.contains("captured 200000 us before the previous one"), "{}", violations);
+        // Reported once, when the growth first exceeds the limit
+        assert!(violations.contains("Resident memory grew by 92160 KiB"), "{}", violations);
+    }
+
+    #[test]
+    fn test_calibration_may_only_restart_after_a_reset() {
+        let mut verifier = SoakVerifier::new(SoakLimits::default());
+        verifier.observe(&score(1, 0, 0.5));
+        verifier.observe(&collecting(2, 0.3));
+        verifier.observe(&collecting(3, 0.6));
+        verifier.recalibration_requested();
+        verifier.observe(&collecting(4, 0.0));
+        verifier.observe(&collecting(5, 0.4));
+        verifier.observe(&collecting(6, 0.2));
+        verifier.observe(&collecting(7, 1.0));
+        verifier.observe(&calibrated(8));
+        verifier.observe(&calibrated(9));
+        verifier.observe(&collecting(10, 0.1));
+
+        let report = verifier.finish();
+        assert_eq!(report.recalibrations, 1);
+        assert_eq!(report.violations.len(), 2, "{:?}", report.violations);
+        assert!(report.violations[0].contains("went back from 0.400 to 0.200 in event 6"));
+        assert!(report.violations[1].contains("went back from 1.000 to 0.100 in event 10"));
+    }
+
+    #[test]
+    fn test_dropped_events_reconcile_with_the_daemon() {
+        let mut verifier = SoakVerifier::new(SoakLimits { max_dropped_ratio: 1.0, ..SoakLimits::default() });
+        for sequence in [1, 2, 5, 6, 7, 9, 10] {
+            verifier.observe(&score(sequence, sequence * 1000, 0.5));
+        }
+        // Listed after event 6: two dropped; the one before event 9 came later
+        let listed = ClientInfo { events_delivered: 4, events_dropped: 2, ..Default::default() };
+        verifier.reconcile_dropped(&listed);
+        // Listed between counting the drop before event 9 and sending it
+        let listed = ClientInfo { events_delivered: 5, events_dropped: 3, ..Default::default() };
+        verifier.reconcile_dropped(&listed);
+        assert!(verifier.clone().finish().passed());
+
+        let listed = ClientInfo { events_delivered: 5, events_dropped: 1, ..Default::default() };
+        verifier.reconcile_dropped(&listed);
+        let listed = ClientInfo { events_delivered: 7, events_dropped: 4, ..Default::default() };
+        verifier.reconcile_dropped(&listed);
+        let report = verifier.finish();
+        assert_eq!(report.violations.len(), 2, "{:?}", report.violations);
+        assert!(report.violations[0].contains("Daemon counted 1 dropped events, the stream showed 2 by event 6"));
+    }
+
+    #[test]
+    fn test_resident_memory_on_linux() {
+        assert_eq!(resident_memory().is_some(), cfg!(target_os = "linux"));
+    }
+}
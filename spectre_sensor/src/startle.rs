@@ -0,0 +1,240 @@
+//! Startle (jump scare) detection
+//!
+//! Absolute fear misses the sudden spike. The startle signal is the positive
+//! rate of change of normalized fear over a short window, scaled by a gain
+//! and clamped to [0, 1]. It operates on the calibrated fear score, so it is
+//! relative to the player's baseline without a calibration of its own. A
+//! startle pulse lasts while the rate stays above the threshold; after it
+//! ends a refractory period suppresses new pulses so one noisy spike does not
+//! read as several.
+
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::time::Duration;
+
+/// Startle detection configuration
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StartleConfig {
+    /// Window over which the rate of change is measured
+    #[serde(with = "spectremesh_core::duration")]
+    pub window: Duration,
+    /// Startle per unit of fear-per-second
+    pub gain: f32,
+    /// Startle below this value is reported as zero
+    pub threshold: f32,
+    /// Quiet period after a pulse ends
+    #[serde(with = "spectremesh_core::duration")]
+    pub refractory: Duration,
+}
+
+impl Default for StartleConfig {
+    fn default() -> Self {
+        Self {
+            window: Duration::from_millis(250),
+            gain: 0.5, // A jump of 0.5 within the window saturates
+            threshold: 0.1,
+            refractory: Duration::from_millis(1500),
+        }
+    }
+}
+
+impl StartleConfig {
+    /// Validate the configuration; no refractory period is fine, no window is not
+    pub fn validate(&self) -> Result<(), String> {
+        if self.window.is_zero() {
+            return Err("Startle window must be positive".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// Computes the startle signal from a stream of normalized fear samples
+#[derive(Debug, Clone)]
+pub struct StartleDetector {
+    config: StartleConfig,
+    /// (monotonic time, normalized fear) within the window
+    samples: VecDeque<(Duration, f32)>,
+    /// Whether a pulse is in progress
+    in_pulse: bool,
+    /// End of the refractory period following the last pulse
+    refractory_until: Option<Duration>,
+}
+
+impl StartleDetector {
+    /// Create a detector
+    pub fn new(config: StartleConfig) -> Self {
+        Self {
+            config,
+            samples: VecDeque::new(),
+            in_pulse: false,
+            refractory_until: None,
+        }
+    }
+
+    /// Detection configuration
+    pub fn config(&self) -> &StartleConfig {
+        &self.config
+    }
+
+    /// Add a sample taken at monotonic time `now` and return the startle
+    ///
+    /// Uncalibrated samples clear the history: the jump from the neutral
+    /// warm-up score to the first calibrated score is not a startle.
+    pub fn update(&mut self, now: Duration, fear: f32, calibrated: bool) -> f32 {
+        if !calibrated {
+            self.reset();
+            return 0.0;
+        }
+
+        while self
+            .samples
+            .front()
+            .is_some_and(|&(t, _)| now.saturating_sub(t) > self.config.window)
+        {
+            self.samples.pop_front();
+        }
+
+        let rate = match self.samples.front() {
+            Some(&(t, oldest)) if now > t => (fear - oldest) / (now - t).as_secs_f32().max(f32::EPSILON),
+            _ => 0.0,
+        };
+        self.samples.push_back((now, fear));
+
+        let startle = (rate * self.config.gain).clamp(0.0, 1.0);
+        if startle < self.config.threshold {
+            if self.in_pulse {
+                self.in_pulse = false;
+                self.refractory_until = Some(now + self.config.refractory);
+            }
+            return 0.0;
+        }
+
+        if !self.in_pulse {
+            if self.refractory_until.is_some_and(|until| now < until) {
+                return 0.0;
+            }
+            self.in_pulse = true;
+        }
+        startle
+    }
+
+    /// Drop the sample history and any refractory period
+    pub fn reset(&mut self) {
+        self.samples.clear();
+        self.in_pulse = false;
+        self.refractory_until = None;
+    }
+}
+
+impl Default for StartleDetector {
+    fn default() -> Self {
+        Self::new(StartleConfig::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FRAME: Duration = Duration::from_millis(33);
+
+    /// Feed `sequence` at 30 FPS starting at `start` frames and return the startle per frame
+    fn feed(detector: &mut StartleDetector, start: u32, sequence: &[f32]) -> Vec<f32> {
+        sequence
+            .iter()
+            .enumerate()
+            .map(|(i, &fear)| detector.update(FRAME * (start + i as u32), fear, true))
+            .collect()
+    }
+
+    fn peak(values: &[f32]) -> f32 {
+        values.iter().copied().fold(0.0, f32::max)
+    }
+
+    /// Count separate runs of non-zero startle
+    fn pulses(values: &[f32]) -> usize {
+        values.windows(2).filter(|w| w[0] == 0.0 && w[1] > 0.0).count()
+            + usize::from(values.first().is_some_and(|&v| v > 0.0))
+    }
+
+    #[test]
+    fn test_step_saturates_once() {
+        let mut detector = StartleDetector::default();
+        let mut sequence = vec![0.3; 30];
+        sequence.extend([0.9; 30]);
+
+        let startle = feed(&mut detector, 0, &sequence);
+        assert!(startle[..30].iter().all(|&s| s == 0.0));
+        assert_eq!(startle[30], 1.0);
+        assert_eq!(pulses(&startle), 1);
+
+        // The pulse ends once the step leaves the window
+        assert!(startle[40..].iter().all(|&s| s == 0.0));
+    }
+
+    #[test]
+    fn test_small_step_magnitude() {
+        let mut detector = StartleDetector::default();
+        let mut sequence = vec![0.3; 30];
+        sequence.extend([0.4; 10]);
+
+        // 0.1 over the 231ms between the oldest in-window sample and the step
+        let startle = feed(&mut detector, 0, &sequence);
+        let expected = 0.1 / (FRAME * 7).as_secs_f32() * 0.5;
+        assert!((startle[30] - expected).abs() < 1e-3, "startle {}", startle[30]);
+    }
+
+    #[test]
+    fn test_ramp_magnitude() {
+        let mut detector = StartleDetector::default();
+
+        // About 0.6 per second: 0.3 startle at gain 0.5
+        let ramp: Vec<f32> = (0..30).map(|i| 0.2 + 0.02 * i as f32).collect();
+        let startle = feed(&mut detector, 0, &ramp);
+        let expected = 0.02 / FRAME.as_secs_f32() * 0.5;
+        for &s in &startle[10..] {
+            assert!((s - expected).abs() < 0.02, "startle {} expected {}", s, expected);
+        }
+        assert_eq!(pulses(&startle), 1);
+    }
+
+    #[test]
+    fn test_refractory_suppresses_repeat_spikes() {
+        let mut detector = StartleDetector::default();
+
+        // Two spikes 0.5s apart, then a third after the refractory period
+        let mut sequence = vec![0.2; 10];
+        sequence.extend([0.8; 15]);
+        sequence.extend([0.2; 5]);
+        sequence.extend([0.8; 15]);
+        let first = feed(&mut detector, 0, &sequence);
+        assert_eq!(pulses(&first), 1);
+        assert!(peak(&first[25..]) == 0.0, "second spike read as a new startle");
+
+        let mut later = vec![0.2; 60];
+        later.extend([0.8; 10]);
+        let second = feed(&mut detector, sequence.len() as u32, &later);
+        assert_eq!(pulses(&second), 1);
+        assert_eq!(peak(&second[60..]), 1.0);
+    }
+
+    #[test]
+    fn test_slow_drift_is_not_startle() {
+        // 0.3 → 0.8 over 20 seconds, measured without the threshold
+        let drift: Vec<f32> = (0..600).map(|i| 0.3 + 0.5 * i as f32 / 600.0).collect();
+        let mut detector = StartleDetector::new(StartleConfig {
+            threshold: 0.0,
+            ..StartleConfig::default()
+        });
+        let startle = feed(&mut detector, 0, &drift);
+        assert!(peak(&startle) < 0.05, "drift startle {}", peak(&startle));
+    }
+
+    #[test]
+    fn test_uncalibrated_samples_reset() {
+        let mut detector = StartleDetector::default();
+        assert_eq!(detector.update(Duration::ZERO, 0.3, false), 0.0);
+        assert_eq!(detector.update(FRAME, 0.9, true), 0.0);
+        assert_eq!(detector.update(FRAME * 2, 0.9, true), 0.0);
+    }
+}
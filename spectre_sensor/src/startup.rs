@@ -0,0 +1,260 @@
+//! Sensor cold start: parallel initialization and the optimized model cache
+//!
+//! Building the face detector and emotion sessions, and opening the camera,
+//! are independent blocking jobs. [`EmotionSensor::initialize`] runs them
+//! concurrently with [`spawn_timed`], so cold start takes about as long as
+//! the slowest job rather than their sum. ONNX Runtime's Level3 graph
+//! optimization dominates the emotion session build; with an
+//! [`OptimizedModelCache`] the optimized graph is written to disk on the first
+//! launch, keyed by the model's SHA-256, and later launches load it without
+//! optimizing again.
+//!
+//! [`EmotionSensor::initialize`]: crate::EmotionSensor::initialize
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+use tokio::task::JoinHandle;
+
+/// How the emotion session was built
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ModelCacheStatus {
+    /// No cache configured; the model was optimized in memory
+    #[default]
+    Disabled,
+    /// Optimized and written to the cache
+    Miss,
+    /// Loaded already optimized from the cache
+    Hit,
+}
+
+/// Time each initialization step took
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct InitBreakdown {
+    pub face_detector: Duration,
+    pub emotion_model: Duration,
+    pub calibrator: Duration,
+    /// Camera opening overlapped with model loading; `None` if it failed
+    pub camera: Option<Duration>,
+    /// Wall time of the whole initialization
+    pub total: Duration,
+    pub emotion_cache: ModelCacheStatus,
+}
+
+impl InitBreakdown {
+    /// Sum of the step durations, i.e. the cost of running them one after another
+    pub fn sequential(&self) -> Duration {
+        self.face_detector + self.emotion_model + self.calibrator + self.camera.unwrap_or_default()
+    }
+}
+
+impl fmt::Display for InitBreakdown {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let ms = |duration: Duration| duration.as_secs_f64() * 1000.0;
+        write!(
+            f,
+            "{:.0}ms total (face detector {:.0}ms, emotion model {:.0}ms [cache {:?}], calibrator {:.0}ms, camera ",
+            ms(self.total),
+            ms(self.face_detector),
+            ms(self.emotion_model),
+            self.emotion_cache,
+            ms(self.calibrator)
+        )?;
+        match self.camera {
+            Some(camera) => write!(f, "{:.0}ms)", ms(camera)),
+            None => write!(f, "failed)"),
+        }
+    }
+}
+
+/// Run a blocking initialization step on the blocking pool, timing it
+pub fn spawn_timed<T, F>(step: F) -> JoinHandle<(T, Duration)>
+where
+    F: FnOnce() -> T + Send + 'static,
+    T: Send + 'static,
+{
+    tokio::task::spawn_blocking(move || {
+        let start = Instant::now();
+        let output = step();
+        (output, start.elapsed())
+    })
+}
+
+/// Directory of ONNX Runtime optimized models, one file per model hash
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OptimizedModelCache {
+    dir: PathBuf,
+}
+
+impl OptimizedModelCache {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Cache file for the model with SHA-256 `sha256`
+    pub fn path_for(&self, sha256: &str) -> PathBuf {
+        self.dir.join(format!("{}.optimized.onnx", sha256.to_ascii_lowercase()))
+    }
+
+    /// Cached optimized model, if a non-empty one exists
+    pub fn lookup(&self, sha256: &str) -> Option<PathBuf> {
+        let path = self.path_for(sha256);
+        let present = std::fs::metadata(&path).is_ok_and(|meta| meta.is_file() && meta.len() > 0);
+        present.then_some(path)
+    }
+
+    /// Build a session through the cache
+    ///
+    /// On a hit `load_optimized` loads the cached file. Otherwise `optimize`
+    /// builds the session from the original model, writing the optimized
+    /// graph to the path it is given. A cached file that fails to load is
+    /// discarded and rebuilt.
+    pub fn load<T, E: fmt::Display>(
+        cache: Option<&Self>,
+        sha256: &str,
+        optimize: impl FnOnce(Option<&Path>) -> Result<T, E>,
+        load_optimized: impl FnOnce(&Path) -> Result<T, E>,
+    ) -> Result<(T, ModelCacheStatus), E> {
+        let Some(cache) = cache else {
+            return optimize(None).map(|session| (session, ModelCacheStatus::Disabled));
+        };
+
+        if let Some(path) = cache.lookup(sha256) {
+            match load_optimized(&path) {
+                Ok(session) => return Ok((session, ModelCacheStatus::Hit)),
+                Err(e) => {
+                    tracing::warn!("Discarding cached model {}: {}", path.display(), e);
+                    let _ = std::fs::remove_file(&path);
+                }
+            }
+        }
+
+        // A cache that cannot be written only costs the next launch its fast path
+        let target = match std::fs::create_dir_all(&cache.dir) {
+            Ok(()) => Some(cache.path_for(sha256)),
+            Err(e) => {
+                tracing::warn!("Optimized model cache {} unavailable: {}", cache.dir.display(), e);
+                None
+            }
+        };
+        optimize(target.as_deref()).map(|session| (session, ModelCacheStatus::Miss))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::thread::sleep;
+
+    const FACE_TIME: Duration = Duration::from_millis(150);
+    const OPTIMIZE_TIME: Duration = Duration::from_millis(300);
+    const CACHED_LOAD_TIME: Duration = Duration::from_millis(20);
+    const CAMERA_TIME: Duration = Duration::from_millis(200);
+
+    /// Stub emotion session: optimizing is slow, loading an optimized file fast
+    fn load_emotion(cache: Option<&OptimizedModelCache>) -> Result<(String, ModelCacheStatus), String> {
+        OptimizedModelCache::load(
+            cache,
+            "ABC123",
+            |target| {
+                sleep(OPTIMIZE_TIME);
+                if let Some(target) = target {
+                    std::fs::write(target, b"optimized graph").map_err(|e| e.to_string())?;
+                }
+                Ok("optimized".to_string())
+            },
+            |path| {
+                sleep(CACHED_LOAD_TIME);
+                let graph = std::fs::read(path).map_err(|e| e.to_string())?;
+                if graph != b"optimized graph" {
+                    return Err("corrupt graph".to_string());
+                }
+                Ok("cached".to_string())
+            },
+        )
+    }
+
+    /// Initialize the stub backends the way the sensor does
+    async fn initialize(cache: Option<OptimizedModelCache>) -> InitBreakdown {
+        let start = Instant::now();
+        let face = spawn_timed(|| sleep(FACE_TIME));
+        let emotion = spawn_timed(move || load_emotion(cache.as_ref()));
+        let camera = spawn_timed(|| sleep(CAMERA_TIME));
+
+        let ((), face_detector) = face.await.unwrap();
+        let (emotion, emotion_model) = emotion.await.unwrap();
+        let ((), camera) = camera.await.unwrap();
+        let (_, emotion_cache) = emotion.unwrap();
+
+        InitBreakdown {
+            face_detector,
+            emotion_model,
+            calibrator: Duration::ZERO,
+            camera: Some(camera),
+            total: start.elapsed(),
+            emotion_cache,
+        }
+    }
+
+    fn cache_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("spectre_model_cache_{}_{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        dir
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_initialization_takes_the_slowest_step_not_the_sum() {
+        let breakdown = initialize(None).await;
+        assert_eq!(breakdown.emotion_cache, ModelCacheStatus::Disabled);
+
+        let slowest = breakdown.face_detector.max(breakdown.emotion_model).max(breakdown.camera.unwrap());
+        assert!(breakdown.emotion_model >= OPTIMIZE_TIME);
+        assert!(breakdown.total >= slowest);
+        assert!(
+            breakdown.total < slowest + Duration::from_millis(100),
+            "{} against slowest step {:?}",
+            breakdown,
+            slowest
+        );
+        assert!(breakdown.total + Duration::from_millis(250) < breakdown.sequential());
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_second_initialization_hits_the_cache() {
+        let dir = cache_dir("hit");
+        let cache = OptimizedModelCache::new(&dir);
+        assert!(cache.lookup("abc123").is_none());
+
+        let first = initialize(Some(cache.clone())).await;
+        assert_eq!(first.emotion_cache, ModelCacheStatus::Miss);
+        assert_eq!(cache.lookup("abc123"), Some(dir.join("abc123.optimized.onnx")));
+
+        let second = initialize(Some(cache.clone())).await;
+        assert_eq!(second.emotion_cache, ModelCacheStatus::Hit);
+        assert!(second.emotion_model < OPTIMIZE_TIME / 2, "{}", second);
+        // The camera is now the slowest step
+        assert!(second.total < CAMERA_TIME + Duration::from_millis(100), "{}", second);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_corrupt_cache_entry_is_rebuilt() {
+        let dir = cache_dir("corrupt");
+        let cache = OptimizedModelCache::new(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(cache.path_for("abc123"), b"truncated").unwrap();
+
+        let (session, status) = load_emotion(Some(&cache)).unwrap();
+        assert_eq!((session.as_str(), status), ("optimized", ModelCacheStatus::Miss));
+        assert_eq!(load_emotion(Some(&cache)).unwrap().1, ModelCacheStatus::Hit);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,258 @@
+//! Synthetic capture and models for running the sensor headless
+//!
+//! With [`SensorConfig::mock`](crate::SensorConfig::mock) set,
+//! [`EmotionSensor`](crate::EmotionSensor) builds these in place of the
+//! camera, face detector and emotion model: grey frames with one lighter
+//! face in each, still in the middle or sweeping across the frame, a
+//! detector that finds it, and fear logits played from a [`MockPattern`]. Everything
+//! after them — conditioning, calibration, startle detection, metrics,
+//! back-pressure and events — is the real pipeline.
+
+use crate::{
+    degradation::{EmotionBackend, EmotionBackendFactory},
+    face_backend::FaceDetectorBackend,
+    mock_patterns::{self, MockPattern},
+    model_info::{ModelInfo, UNKNOWN},
+    resume::FrameSource,
+    sensor::SensorError,
+    yunet::{FaceDetection, YuNetError},
+};
+use opencv::{
+    core::{self, Mat, Rect, Scalar, Vec3b, CV_8UC3},
+    prelude::*,
+};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use spectremesh_core::{EmotionLayout, EmotionLogits};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Width of synthetic frames
+pub const FRAME_WIDTH: i32 = 320;
+/// Height of synthetic frames
+pub const FRAME_HEIGHT: i32 = 240;
+
+/// Largest random offset added to each fear logit, so the baseline has spread
+const NOISE: f32 = 0.05;
+
+/// Grey level of the frame around the face
+const BACKGROUND_LEVEL: f64 = 128.0;
+/// Grey level of the face
+const FACE_LEVEL: f64 = 200.0;
+
+/// The synthetic face at `time`: half the frame in each direction, in the
+/// middle, or sweeping across the frame and back once per `period`
+pub fn face_at(period: Duration, time: SystemTime) -> Rect {
+    let (width, height) = (FRAME_WIDTH / 2, FRAME_HEIGHT / 2);
+    let travel = (FRAME_WIDTH - width) as f64;
+    let x = if period.is_zero() {
+        travel / 2.0
+    } else {
+        let elapsed = time.duration_since(UNIX_EPOCH).unwrap_or_default().as_secs_f64();
+        let phase = elapsed % period.as_secs_f64() / period.as_secs_f64();
+        travel * (1.0 - (2.0 * phase - 1.0).abs())
+    };
+    Rect::new(x.round() as i32, (FRAME_HEIGHT - height) / 2, width, height)
+}
+
+/// Mid-grey frames with a lighter face at the target rate; there is no
+/// device to lose
+#[derive(Debug, Default)]
+pub struct SyntheticCamera {
+    /// See [`face_at`]
+    face_period: Duration,
+}
+
+impl SyntheticCamera {
+    /// Sweep the face across the frame and back once per `period`; zero
+    /// keeps it still
+    pub fn with_face_period(mut self, period: Duration) -> Self {
+        self.face_period = period;
+        self
+    }
+
+    fn draw(&self) -> opencv::Result<Mat> {
+        let mut frame = Mat::new_rows_cols_with_default(FRAME_HEIGHT, FRAME_WIDTH, CV_8UC3, Scalar::all(BACKGROUND_LEVEL))?;
+        let mut face = Mat::roi_mut(&mut frame, face_at(self.face_period, SystemTime::now()))?;
+        face.set_to(&Scalar::all(FACE_LEVEL), &core::no_array())?;
+        Ok(frame)
+    }
+}
+
+impl FrameSource for SyntheticCamera {
+    fn read_frame(&mut self, frame: &mut Mat) -> bool {
+        match self.draw() {
+            Ok(drawn) => {
+                *frame = drawn;
+                true
+            }
+            Err(e) => {
+                tracing::warn!("Failed to build synthetic frame: {}", e);
+                false
+            }
+        }
+    }
+
+    fn reopen(&mut self) -> Result<(), SensorError> {
+        Ok(())
+    }
+}
+
+/// Score of every synthetic face
+const SYNTHETIC_FACE_CONFIDENCE: f32 = 0.95;
+
+/// Finds the synthetic camera's face, with the same confidence every time
+pub struct SyntheticFaceDetector {
+    info: ModelInfo,
+    /// A threshold above the synthetic face's score hides it
+    confidence_threshold: f32,
+}
+
+impl SyntheticFaceDetector {
+    pub fn new() -> Self {
+        Self { info: ModelInfo::hash_only("synthetic face detector", UNKNOWN), confidence_threshold: 0.0 }
+    }
+}
+
+impl Default for SyntheticFaceDetector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FaceDetectorBackend for SyntheticFaceDetector {
+    fn name(&self) -> &'static str {
+        "synthetic"
+    }
+
+    fn model_info(&self) -> &ModelInfo {
+        &self.info
+    }
+
+    fn detect_faces(&mut self, image: &Mat) -> Result<Vec<FaceDetection>, YuNetError> {
+        if SYNTHETIC_FACE_CONFIDENCE < self.confidence_threshold {
+            return Ok(Vec::new());
+        }
+        Ok(find_face(image)
+            .map(|bbox| FaceDetection { bbox, confidence: SYNTHETIC_FACE_CONFIDENCE, landmarks: Vec::new() })
+            .into_iter()
+            .collect())
+    }
+
+    fn set_confidence_threshold(&mut self, threshold: f32) {
+        self.confidence_threshold = threshold;
+    }
+}
+
+/// The light box the synthetic camera drew, found by walking the middle row
+/// and then the middle of the face's columns
+fn find_face(image: &Mat) -> Option<Rect> {
+    let threshold = ((BACKGROUND_LEVEL + FACE_LEVEL) / 2.0) as u8;
+    let light = |x: i32, y: i32| image.at_2d::<Vec3b>(y, x).is_ok_and(|pixel| pixel[0] > threshold);
+    let row = image.rows() / 2;
+    let left = (0..image.cols()).find(|&x| light(x, row))?;
+    let right = (left..image.cols()).take_while(|&x| light(x, row)).last()?;
+    let column = (left + right) / 2;
+    let top = (0..=row).rev().take_while(|&y| light(column, y)).last()?;
+    let bottom = (row..image.rows()).take_while(|&y| light(column, y)).last()?;
+    Some(Rect::new(left, top, right - left + 1, bottom - top + 1))
+}
+
+/// Time the synthetic emotion model spends on each face, shared so it can
+/// change while the model runs
+#[derive(Debug, Clone, Default)]
+pub struct SyntheticLatency(Arc<AtomicU64>);
+
+impl SyntheticLatency {
+    pub fn new(latency: Duration) -> Self {
+        let shared = Self::default();
+        shared.set(latency);
+        shared
+    }
+
+    pub fn get(&self) -> Duration {
+        Duration::from_nanos(self.0.load(Ordering::Relaxed))
+    }
+
+    /// Spend `latency` on every face from the next one on
+    pub fn set(&self, latency: Duration) {
+        self.0.store(latency.as_nanos().min(u64::MAX as u128) as u64, Ordering::Relaxed);
+    }
+}
+
+/// Emotion logits with the pattern's next value, plus a little noise, as fear
+pub struct SyntheticEmotion {
+    samples: Vec<f32>,
+    next: usize,
+    layout: EmotionLayout,
+    rng: StdRng,
+    latency: SyntheticLatency,
+}
+
+impl SyntheticEmotion {
+    pub fn new(pattern: &MockPattern, layout: EmotionLayout) -> Self {
+        let mut samples = pattern.samples();
+        if samples.is_empty() {
+            samples.push(0.5);
+        }
+        Self { samples, next: 0, layout, rng: StdRng::seed_from_u64(0), latency: SyntheticLatency::default() }
+    }
+
+    /// Spend `latency` on every face, like a slow model would
+    pub fn with_latency(mut self, latency: Duration) -> Self {
+        self.latency = SyntheticLatency::new(latency);
+        self
+    }
+
+    /// Spend whatever `latency` holds on every face
+    pub fn with_shared_latency(mut self, latency: SyntheticLatency) -> Self {
+        self.latency = latency;
+        self
+    }
+
+    /// Rebuild step for the degradation ladder; the pattern starts over
+    pub fn factory(pattern: MockPattern, layout: EmotionLayout) -> EmotionBackendFactory {
+        Box::new(move || Ok(Box::new(Self::new(&pattern, layout)) as Box<dyn EmotionBackend>))
+    }
+
+    /// Provenance reported for the synthetic model
+    pub fn model_info(pattern: &MockPattern) -> ModelInfo {
+        ModelInfo::hash_only(format!("synthetic emotion model ({:?})", pattern), UNKNOWN)
+    }
+}
+
+impl EmotionBackend for SyntheticEmotion {
+    fn infer(&mut self, _face: &Mat) -> Result<EmotionLogits, SensorError> {
+        let latency = self.latency.get();
+        if !latency.is_zero() {
+            std::thread::sleep(latency);
+        }
+        let fear = self.samples[self.next] + self.rng.gen_range(-1.0..=1.0) * NOISE;
+        self.next = (self.next + 1) % self.samples.len();
+
+        Ok(mock_patterns::emotion_logits(fear, self.layout))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_synthetic_pipeline_inputs() {
+        let mut frame = Mat::default();
+        assert!(SyntheticCamera.read_frame(&mut frame));
+        assert_eq!((frame.cols(), frame.rows()), (FRAME_WIDTH, FRAME_HEIGHT));
+
+        let face = SyntheticFaceDetector::new().get_largest_face(&frame).unwrap();
+        assert_eq!(face.bbox, Rect::new(80, 60, 160, 120));
+
+        let layout = EmotionLayout::new(10, 4).unwrap();
+        let mut emotion = SyntheticEmotion::new(&MockPattern::Sequence { samples: vec![0.2, 0.8] }, layout);
+        let fears: Vec<f32> = (0..4).map(|_| emotion.infer(&frame).unwrap().fear()).collect();
+        for (fear, expected) in fears.iter().zip([0.2, 0.8, 0.2, 0.8]) {
+            assert!((fear - expected).abs() <= NOISE, "{} vs {}", fear, expected);
+        }
+        assert_eq!(emotion.infer(&frame).unwrap().as_slice().len(), 10);
+    }
+}
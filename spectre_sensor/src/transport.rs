@@ -0,0 +1,691 @@
+//! Transports for the sensor gRPC service
+//!
+//! The daemon and its clients talk over one of:
+//! - TCP on a socket address
+//! - a Unix domain socket (Linux and macOS)
+//! - a Windows named pipe
+//! - an in-memory loopback, for tests that need a real gRPC round trip on
+//!   every platform
+//!
+//! [`SensorTransport`] parses the same strings `SensorConfig::grpc_socket_path`
+//! has always held, so existing configuration keeps working. Local transports
+//! are restricted to the current user: Unix sockets are created with mode
+//! `0600` and named pipes with a DACL that grants access only to their owner
+//! and SYSTEM.
+
+use futures::stream::{self, Stream};
+use hyper_util::rt::TokioIo;
+use std::fmt;
+use std::io;
+use std::net::{SocketAddr, ToSocketAddrs};
+use std::path::PathBuf;
+use std::pin::Pin;
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+use thiserror::Error;
+use tokio::io::{AsyncRead, AsyncWrite, DuplexStream, ReadBuf};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::mpsc;
+use tonic::transport::{server::Connected, Channel, Endpoint, Uri};
+
+/// Prefix of every Windows named pipe path
+pub const PIPE_PREFIX: &str = r"\\.\pipe\";
+
+/// Per-direction buffer of a loopback connection
+const LOOPBACK_BUFFER_BYTES: usize = 64 * 1024;
+
+/// Authority used for transports that ignore the URI; HTTP/2 still needs one
+const LOCAL_ENDPOINT_URI: &str = "http://[::]:50051";
+
+/// Transport errors
+#[derive(Error, Debug)]
+pub enum TransportError {
+    #[error("Invalid transport address '{0}'")]
+    InvalidAddress(String),
+
+    #[error("{0} transport is not supported on this platform")]
+    Unsupported(&'static str),
+
+    #[error("Transport I/O error: {0}")]
+    Io(#[from] io::Error),
+
+    #[error("gRPC channel error: {0}")]
+    Channel(#[from] tonic::transport::Error),
+}
+
+/// Where the sensor gRPC service listens
+#[derive(Debug, Clone, PartialEq)]
+pub enum SensorTransport {
+    /// TCP socket address
+    Tcp(SocketAddr),
+    /// Unix domain socket path
+    Unix(PathBuf),
+    /// Full Windows named pipe path, `\\.\pipe\<name>`
+    NamedPipe(String),
+    /// In-memory connection between a server and clients in the same process
+    Loopback(LoopbackTransport),
+}
+
+impl SensorTransport {
+    /// Named pipe `\\.\pipe\<name>`
+    pub fn named_pipe(name: &str) -> Self {
+        Self::NamedPipe(format!("{}{}", PIPE_PREFIX, name))
+    }
+
+    /// Fresh in-memory transport
+    pub fn loopback() -> Self {
+        Self::Loopback(LoopbackTransport::new())
+    }
+
+    /// Transport named by a configured socket path
+    pub fn from_config(config: &crate::config::SensorConfig) -> Result<Self, TransportError> {
+        config.grpc_socket_path.parse()
+    }
+
+    /// Short transport name for logs and errors
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::Tcp(_) => "TCP",
+            Self::Unix(_) => "Unix socket",
+            Self::NamedPipe(_) => "Named pipe",
+            Self::Loopback(_) => "Loopback",
+        }
+    }
+
+    /// Start accepting connections
+    pub async fn listen(&self) -> Result<Incoming, TransportError> {
+        match self {
+            Self::Tcp(addr) => {
+                let listener = TcpListener::bind(addr).await?;
+                Ok(Box::pin(stream::unfold(listener, |listener| async move {
+                    let accepted = listener.accept().await.map(|(stream, _)| TransportStream::Tcp(stream));
+                    Some((accepted, listener))
+                })))
+            },
+            Self::Unix(path) => unix::listen(path),
+            Self::NamedPipe(name) => pipe::listen(name),
+            Self::Loopback(loopback) => {
+                let connections = loopback.listen()?;
+                Ok(Box::pin(stream::unfold(connections, |mut connections| async move {
+                    let stream = connections.recv().await?;
+                    Some((Ok(TransportStream::Loopback(stream)), connections))
+                })))
+            },
+        }
+    }
+
+    /// Open a gRPC channel to a service listening on this transport
+    pub async fn connect(&self) -> Result<Channel, TransportError> {
+        match self {
+            Self::Tcp(addr) => Ok(Endpoint::from_shared(format!("http://{}", addr))?.connect().await?),
+            Self::Unix(path) => unix::connect(path.clone()).await,
+            Self::NamedPipe(name) => pipe::connect(name.clone()).await,
+            Self::Loopback(loopback) => {
+                let loopback = loopback.clone();
+                let channel = Endpoint::from_static(LOCAL_ENDPOINT_URI)
+                    .connect_with_connector(tower::service_fn(move |_: Uri| {
+                        let stream = loopback.connect();
+                        async move { stream.map(TokioIo::new) }
+                    }))
+                    .await?;
+                Ok(channel)
+            },
+        }
+    }
+}
+
+impl FromStr for SensorTransport {
+    type Err = TransportError;
+
+    /// Accepts `\\.\pipe\<name>` or `pipe:<name>`, `unix:<path>` or any path,
+    /// and `tcp://host:port`, `http://host:port` or a bare `host:port`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let invalid = || TransportError::InvalidAddress(s.to_string());
+
+        let is_pipe_path = s.len() > PIPE_PREFIX.len()
+            && s.get(..PIPE_PREFIX.len()).is_some_and(|prefix| prefix.eq_ignore_ascii_case(PIPE_PREFIX));
+        if is_pipe_path {
+            return Ok(Self::NamedPipe(s.to_string()));
+        }
+        if let Some(name) = s.strip_prefix("pipe:") {
+            if name.is_empty() || name.contains('\\') {
+                return Err(invalid());
+            }
+            return Ok(Self::named_pipe(name));
+        }
+        if let Some(path) = s.strip_prefix("unix:") {
+            if path.is_empty() {
+                return Err(invalid());
+            }
+            return Ok(Self::Unix(PathBuf::from(path)));
+        }
+
+        let address = s
+            .strip_prefix("tcp://")
+            .or_else(|| s.strip_prefix("http://"))
+            .unwrap_or(s)
+            .trim_end_matches('/');
+        if address.contains(':') && !address.contains('/') && !address.contains('\\') {
+            if let Ok(addr) = address.parse() {
+                return Ok(Self::Tcp(addr));
+            }
+            return address
+                .to_socket_addrs()
+                .ok()
+                .and_then(|mut addrs| addrs.next())
+                .map(Self::Tcp)
+                .ok_or_else(invalid);
+        }
+
+        if address.contains('/') || address.contains('\\') || address.ends_with(".sock") {
+            return Ok(Self::Unix(PathBuf::from(s)));
+        }
+        Err(invalid())
+    }
+}
+
+impl fmt::Display for SensorTransport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Tcp(addr) => write!(f, "tcp://{}", addr),
+            Self::Unix(path) => write!(f, "unix:{}", path.display()),
+            Self::NamedPipe(name) => write!(f, "{}", name),
+            Self::Loopback(_) => write!(f, "loopback"),
+        }
+    }
+}
+
+/// In-memory transport: each client connection is a duplex pipe handed to the server
+///
+/// Clones share one listener. Connections made before the server starts
+/// listening wait in a backlog.
+#[derive(Debug, Clone)]
+pub struct LoopbackTransport {
+    connections: mpsc::UnboundedSender<DuplexStream>,
+    listener: Arc<Mutex<Option<mpsc::UnboundedReceiver<DuplexStream>>>>,
+}
+
+impl LoopbackTransport {
+    /// Create a transport nobody listens on yet
+    pub fn new() -> Self {
+        let (connections, listener) = mpsc::unbounded_channel();
+        Self {
+            connections,
+            listener: Arc::new(Mutex::new(Some(listener))),
+        }
+    }
+
+    /// Open a connection; the server end is queued for the listener
+    pub fn connect(&self) -> io::Result<DuplexStream> {
+        let (client, server) = tokio::io::duplex(LOOPBACK_BUFFER_BYTES);
+        self.connections
+            .send(server)
+            .map_err(|_| io::Error::new(io::ErrorKind::ConnectionRefused, "Loopback listener is gone"))?;
+        Ok(client)
+    }
+
+    /// Take the listening end; only one server may listen
+    fn listen(&self) -> io::Result<mpsc::UnboundedReceiver<DuplexStream>> {
+        self.listener
+            .lock()
+            .unwrap()
+            .take()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::AddrInUse, "Loopback transport already has a listener"))
+    }
+}
+
+impl Default for LoopbackTransport {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PartialEq for LoopbackTransport {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.listener, &other.listener)
+    }
+}
+
+/// Accepted connections, ready for `Server::serve_with_incoming`
+pub type Incoming = Pin<Box<dyn Stream<Item = io::Result<TransportStream>> + Send>>;
+
+/// Server side of an accepted connection
+#[derive(Debug)]
+pub enum TransportStream {
+    Tcp(TcpStream),
+    #[cfg(unix)]
+    Unix(tokio::net::UnixStream),
+    #[cfg(windows)]
+    NamedPipe(tokio::net::windows::named_pipe::NamedPipeServer),
+    Loopback(DuplexStream),
+}
+
+/// Forward an I/O call to whichever stream is inside
+macro_rules! delegate {
+    ($self:ident, $stream:ident => $call:expr) => {
+        match $self.get_mut() {
+            TransportStream::Tcp($stream) => $call,
+            #[cfg(unix)]
+            TransportStream::Unix($stream) => $call,
+            #[cfg(windows)]
+            TransportStream::NamedPipe($stream) => $call,
+            TransportStream::Loopback($stream) => $call,
+        }
+    };
+}
+
+impl AsyncRead for TransportStream {
+    fn poll_read(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &mut ReadBuf<'_>) -> Poll<io::Result<()>> {
+        delegate!(self, stream => Pin::new(stream).poll_read(cx, buf))
+    }
+}
+
+impl AsyncWrite for TransportStream {
+    fn poll_write(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &[u8]) -> Poll<io::Result<usize>> {
+        delegate!(self, stream => Pin::new(stream).poll_write(cx, buf))
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        delegate!(self, stream => Pin::new(stream).poll_flush(cx))
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        delegate!(self, stream => Pin::new(stream).poll_shutdown(cx))
+    }
+}
+
+/// Who is on the other end of an accepted connection
+///
+/// Available to request handlers as a request extension.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PeerInfo {
+    Tcp(Option<SocketAddr>),
+    /// Process and user of a Unix socket peer, where the OS reports them
+    Unix { pid: Option<i32>, uid: Option<u32> },
+    NamedPipe,
+    Loopback,
+}
+
+impl fmt::Display for PeerInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Tcp(Some(addr)) => write!(f, "tcp://{}", addr),
+            Self::Tcp(None) => write!(f, "tcp"),
+            Self::Unix { pid, uid } => {
+                write!(f, "unix")?;
+                match (pid, uid) {
+                    (Some(pid), Some(uid)) => write!(f, " (pid {}, uid {})", pid, uid),
+                    (None, Some(uid)) => write!(f, " (uid {})", uid),
+                    (Some(pid), None) => write!(f, " (pid {})", pid),
+                    (None, None) => Ok(()),
+                }
+            },
+            Self::NamedPipe => write!(f, "pipe"),
+            Self::Loopback => write!(f, "loopback"),
+        }
+    }
+}
+
+impl Connected for TransportStream {
+    type ConnectInfo = PeerInfo;
+
+    fn connect_info(&self) -> Self::ConnectInfo {
+        match self {
+            TransportStream::Tcp(stream) => PeerInfo::Tcp(stream.peer_addr().ok()),
+            #[cfg(unix)]
+            TransportStream::Unix(stream) => {
+                let credentials = stream.peer_cred().ok();
+                PeerInfo::Unix {
+                    pid: credentials.and_then(|credentials| credentials.pid()),
+                    uid: credentials.map(|credentials| credentials.uid()),
+                }
+            },
+            #[cfg(windows)]
+            TransportStream::NamedPipe(_) => PeerInfo::NamedPipe,
+            TransportStream::Loopback(_) => PeerInfo::Loopback,
+        }
+    }
+}
+
+#[cfg(unix)]
+mod unix {
+    use super::*;
+    use std::os::unix::fs::PermissionsExt;
+    use std::path::Path;
+    use tokio::net::{UnixListener, UnixStream};
+
+    /// Owner read/write only
+    const SOCKET_MODE: u32 = 0o600;
+
+    pub(super) fn listen(path: &Path) -> Result<Incoming, TransportError> {
+        if path.exists() {
+            // A socket nobody answers on is left over from a previous run
+            if std::os::unix::net::UnixStream::connect(path).is_ok() {
+                return Err(io::Error::new(
+                    io::ErrorKind::AddrInUse,
+                    format!("Another sensor is listening on {}", path.display()),
+                )
+                .into());
+            }
+            std::fs::remove_file(path)?;
+        }
+
+        let listener = UnixListener::bind(path)?;
+        std::fs::set_permissions(path, std::fs::Permissions::from_mode(SOCKET_MODE))?;
+
+        Ok(Box::pin(stream::unfold(listener, |listener| async move {
+            let accepted = listener.accept().await.map(|(stream, _)| TransportStream::Unix(stream));
+            Some((accepted, listener))
+        })))
+    }
+
+    pub(super) async fn connect(path: PathBuf) -> Result<Channel, TransportError> {
+        let channel = Endpoint::from_static(LOCAL_ENDPOINT_URI)
+            .connect_with_connector(tower::service_fn(move |_: Uri| {
+                let path = path.clone();
+                async move { UnixStream::connect(path).await.map(TokioIo::new) }
+            }))
+            .await?;
+        Ok(channel)
+    }
+}
+
+#[cfg(not(unix))]
+mod unix {
+    use super::*;
+    use std::path::Path;
+
+    pub(super) fn listen(_path: &Path) -> Result<Incoming, TransportError> {
+        Err(TransportError::Unsupported("Unix socket"))
+    }
+
+    pub(super) async fn connect(_path: PathBuf) -> Result<Channel, TransportError> {
+        Err(TransportError::Unsupported("Unix socket"))
+    }
+}
+
+#[cfg(windows)]
+mod pipe {
+    use super::*;
+    use std::ffi::c_void;
+    use std::time::Duration;
+    use tokio::net::windows::named_pipe::{ClientOptions, NamedPipeServer, ServerOptions};
+    use windows_sys::Win32::Foundation::{LocalFree, ERROR_PIPE_BUSY};
+    use windows_sys::Win32::Security::Authorization::{
+        ConvertStringSecurityDescriptorToSecurityDescriptorW, SDDL_REVISION_1,
+    };
+    use windows_sys::Win32::Security::{PSECURITY_DESCRIPTOR, SECURITY_ATTRIBUTES};
+
+    /// Protected DACL: full access for the pipe's owner and SYSTEM, nobody else
+    const CURRENT_USER_SDDL: &str = "D:P(A;;GA;;;OW)(A;;GA;;;SY)";
+
+    /// Wait between attempts while every pipe instance is busy
+    const BUSY_RETRY_DELAY: Duration = Duration::from_millis(50);
+
+    /// Security descriptor applied to every pipe instance
+    pub(super) struct PipeSecurity {
+        descriptor: PSECURITY_DESCRIPTOR,
+    }
+
+    // The descriptor is allocated once, never mutated and freed on drop
+    unsafe impl Send for PipeSecurity {}
+    unsafe impl Sync for PipeSecurity {}
+
+    impl PipeSecurity {
+        pub(super) fn current_user_only() -> io::Result<Self> {
+            let sddl: Vec<u16> = CURRENT_USER_SDDL.encode_utf16().chain(Some(0)).collect();
+            let mut descriptor: PSECURITY_DESCRIPTOR = std::ptr::null_mut();
+            let converted = unsafe {
+                ConvertStringSecurityDescriptorToSecurityDescriptorW(
+                    sddl.as_ptr(),
+                    SDDL_REVISION_1,
+                    &mut descriptor,
+                    std::ptr::null_mut(),
+                )
+            };
+            if converted == 0 {
+                return Err(io::Error::last_os_error());
+            }
+            Ok(Self { descriptor })
+        }
+
+        /// Create a pipe instance; the first one fails if the name is already taken
+        pub(super) fn create(&self, name: &str, first: bool) -> io::Result<NamedPipeServer> {
+            let mut attributes = SECURITY_ATTRIBUTES {
+                nLength: std::mem::size_of::<SECURITY_ATTRIBUTES>() as u32,
+                lpSecurityDescriptor: self.descriptor,
+                bInheritHandle: 0,
+            };
+            unsafe {
+                ServerOptions::new()
+                    .first_pipe_instance(first)
+                    .reject_remote_clients(true)
+                    .create_with_security_attributes_raw(name, &mut attributes as *mut _ as *mut c_void)
+            }
+        }
+    }
+
+    impl Drop for PipeSecurity {
+        fn drop(&mut self) {
+            unsafe {
+                LocalFree(self.descriptor as _);
+            }
+        }
+    }
+
+    pub(super) fn listen(name: &str) -> Result<Incoming, TransportError> {
+        let security = PipeSecurity::current_user_only()?;
+        let first = security.create(name, true)?;
+        let state = Some((first, name.to_string(), security));
+
+        Ok(Box::pin(stream::unfold(state, |state| async move {
+            let (server, name, security) = state?;
+            let connected = server.connect().await;
+            // Create the next instance before handing this one out, so clients never find the pipe missing
+            match security.create(&name, false) {
+                Ok(next) => Some((
+                    connected.map(|()| TransportStream::NamedPipe(server)),
+                    Some((next, name, security)),
+                )),
+                Err(e) => Some((Err(e), None)),
+            }
+        })))
+    }
+
+    pub(super) async fn connect(name: String) -> Result<Channel, TransportError> {
+        let channel = Endpoint::from_static(LOCAL_ENDPOINT_URI)
+            .connect_with_connector(tower::service_fn(move |_: Uri| {
+                let name = name.clone();
+                async move {
+                    loop {
+                        match ClientOptions::new().open(&name) {
+                            Ok(client) => return Ok(TokioIo::new(client)),
+                            Err(e) if e.raw_os_error() == Some(ERROR_PIPE_BUSY as i32) => {},
+                            Err(e) => return Err(e),
+                        }
+                        tokio::time::sleep(BUSY_RETRY_DELAY).await;
+                    }
+                }
+            }))
+            .await?;
+        Ok(channel)
+    }
+}
+
+#[cfg(not(windows))]
+mod pipe {
+    use super::*;
+
+    pub(super) fn listen(_name: &str) -> Result<Incoming, TransportError> {
+        Err(TransportError::Unsupported("Named pipe"))
+    }
+
+    pub(super) async fn connect(_name: String) -> Result<Channel, TransportError> {
+        Err(TransportError::Unsupported("Named pipe"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::StreamExt;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    #[test]
+    fn test_parse_named_pipe() {
+        let expected = SensorTransport::NamedPipe(r"\\.\pipe\spectre_sensor".to_string());
+        assert_eq!(r"\\.\pipe\spectre_sensor".parse::<SensorTransport>().unwrap(), expected);
+        assert_eq!("pipe:spectre_sensor".parse::<SensorTransport>().unwrap(), expected);
+        assert_eq!(SensorTransport::named_pipe("spectre_sensor"), expected);
+        assert!("pipe:".parse::<SensorTransport>().is_err());
+    }
+
+    #[test]
+    fn test_parse_unix_socket() {
+        let expected = SensorTransport::Unix(PathBuf::from("/tmp/spectre_sensor.sock"));
+        assert_eq!("/tmp/spectre_sensor.sock".parse::<SensorTransport>().unwrap(), expected);
+        assert_eq!("unix:/tmp/spectre_sensor.sock".parse::<SensorTransport>().unwrap(), expected);
+        assert_eq!(
+            "spectre.sock".parse::<SensorTransport>().unwrap(),
+            SensorTransport::Unix(PathBuf::from("spectre.sock"))
+        );
+    }
+
+    #[test]
+    fn test_parse_tcp() {
+        let addr: SocketAddr = "127.0.0.1:50051".parse().unwrap();
+        for input in ["127.0.0.1:50051", "tcp://127.0.0.1:50051", "http://127.0.0.1:50051/"] {
+            assert_eq!(input.parse::<SensorTransport>().unwrap(), SensorTransport::Tcp(addr));
+        }
+        assert!(matches!("[::1]:50051".parse(), Ok(SensorTransport::Tcp(addr)) if addr.is_ipv6()));
+        assert!(matches!(
+            "spectre".parse::<SensorTransport>(),
+            Err(TransportError::InvalidAddress(_))
+        ));
+    }
+
+    #[test]
+    fn test_display_round_trips() {
+        for transport in [
+            SensorTransport::Tcp("127.0.0.1:50051".parse().unwrap()),
+            SensorTransport::Unix(PathBuf::from("/tmp/spectre_sensor.sock")),
+            SensorTransport::named_pipe("spectre_sensor"),
+        ] {
+            assert_eq!(transport.to_string().parse::<SensorTransport>().unwrap(), transport);
+        }
+    }
+
+    #[test]
+    fn test_default_config_transport() {
+        let transport = SensorTransport::from_config(&crate::config::SensorConfig::default()).unwrap();
+        if cfg!(windows) {
+            assert!(matches!(transport, SensorTransport::NamedPipe(_)));
+        } else {
+            assert!(matches!(transport, SensorTransport::Unix(_)));
+        }
+    }
+
+    /// Write through a client stream and read it back from the accepted one
+    async fn assert_echo<C: AsyncRead + AsyncWrite + Unpin>(incoming: &mut Incoming, mut client: C) {
+        client.write_all(b"ping").await.unwrap();
+        let mut accepted = incoming.next().await.unwrap().unwrap();
+        let mut buf = [0u8; 4];
+        accepted.read_exact(&mut buf).await.unwrap();
+        assert_eq!(&buf, b"ping");
+
+        accepted.write_all(b"pong").await.unwrap();
+        client.read_exact(&mut buf).await.unwrap();
+        assert_eq!(&buf, b"pong");
+    }
+
+    #[tokio::test]
+    async fn test_loopback_connections() {
+        let transport = LoopbackTransport::new();
+        // Connections made before listening wait in the backlog
+        let early = transport.connect().unwrap();
+
+        let mut incoming = SensorTransport::Loopback(transport.clone()).listen().await.unwrap();
+        assert_echo(&mut incoming, early).await;
+        assert_echo(&mut incoming, transport.connect().unwrap()).await;
+
+        // Only one server per transport
+        assert!(SensorTransport::Loopback(transport.clone()).listen().await.is_err());
+
+        drop(incoming);
+        assert_eq!(
+            transport.connect().unwrap_err().kind(),
+            io::ErrorKind::ConnectionRefused
+        );
+    }
+
+    #[tokio::test]
+    async fn test_tcp_connections() {
+        let addr = std::net::TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap();
+        let mut incoming = SensorTransport::Tcp(addr).listen().await.unwrap();
+        assert_echo(&mut incoming, TcpStream::connect(addr).await.unwrap()).await;
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_unix_socket_is_private_and_replaces_stale_file() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = std::env::temp_dir().join(format!("spectre_transport_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("sensor.sock");
+        // Left over from a crashed daemon
+        std::fs::write(&path, b"").unwrap();
+
+        let transport = SensorTransport::Unix(path.clone());
+        let mut incoming = transport.listen().await.unwrap();
+        let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+
+        let client = tokio::net::UnixStream::connect(&path).await.unwrap();
+        assert_echo(&mut incoming, client).await;
+
+        // A live socket is never taken over
+        assert!(matches!(
+            transport.listen().await,
+            Err(TransportError::Io(e)) if e.kind() == io::ErrorKind::AddrInUse
+        ));
+
+        drop(incoming);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[cfg(windows)]
+    #[tokio::test]
+    async fn test_named_pipe_connections() {
+        use tokio::net::windows::named_pipe::ClientOptions;
+
+        let transport = SensorTransport::named_pipe(&format!("spectre_transport_{}", std::process::id()));
+        let SensorTransport::NamedPipe(name) = &transport else { unreachable!() };
+        let mut incoming = transport.listen().await.unwrap();
+
+        // Several clients in a row each get their own instance
+        for _ in 0..3 {
+            let client = ClientOptions::new().open(name).unwrap();
+            assert_echo(&mut incoming, client).await;
+        }
+
+        // Another server cannot squat on the name while we hold it
+        assert!(transport.listen().await.is_err());
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn test_pipe_security_descriptor() {
+        assert!(pipe::PipeSecurity::current_user_only().is_ok());
+    }
+
+    #[cfg(not(windows))]
+    #[tokio::test]
+    async fn test_named_pipe_unsupported() {
+        let transport = SensorTransport::named_pipe("spectre_sensor");
+        assert!(matches!(transport.listen().await, Err(TransportError::Unsupported(_))));
+        assert!(matches!(transport.connect().await, Err(TransportError::Unsupported(_))));
+    }
+}
@@ -1,87 +1,88 @@
 //! Core types for the spectre sensor
 
+use crate::latency::LatencyTracker;
 use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
-use serde::{Deserialize, Serialize};
 
-/// A single fear measurement frame with timing information
-#[derive(Debug, Clone, PartialEq)]
-pub struct FearFrame {
-    /// When this measurement was taken
-    pub timestamp: Instant,
-    /// The fear score measurement
-    pub fear_score: f32,
-    /// Raw emotion logits from the model
-    pub emotion_logits: [f32; 7],
-    /// Model confidence [0.0, 1.0]
-    pub confidence: f32,
-    /// Whether this score has been calibrated
-    pub calibrated: bool,
-    /// Inference latency for this frame
-    pub inference_latency: Duration,
+pub use spectremesh_core::types::{FearBucket, FearFrame, NormalizedRect, SensorCapability};
+pub use spectremesh_core::{EmotionLayout, EmotionLogits};
+
+/// A named marker stamped into the sensor stream by the game
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SensorMarker {
+    /// Marker label (e.g. "boss_start")
+    pub label: String,
+    /// When the marker was inserted, in microseconds since Unix epoch
+    pub timestamp_us: u64,
 }
 
-impl FearFrame {
-    /// Create a new fear frame
-    pub fn new(
-        fear_score: f32,
-        emotion_logits: [f32; 7],
-        confidence: f32,
-        calibrated: bool,
-        inference_latency: Duration,
-    ) -> Self {
+impl SensorMarker {
+    /// Create a marker stamped with the current time
+    pub fn new(label: impl Into<String>) -> Self {
         Self {
-            timestamp: Instant::now(),
-            fear_score,
-            emotion_logits,
-            confidence,
-            calibrated,
-            inference_latency,
+            label: label.into(),
+            timestamp_us: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_micros() as u64,
         }
     }
-
-    /// Get timestamp as microseconds since Unix epoch
-    pub fn timestamp_us(&self) -> u64 {
-        SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .unwrap_or_default()
-            .as_micros() as u64
-    }
-
-    /// Extract the fear logit from emotion logits
-    /// Assumes: [angry, disgust, fear, happy, sad, surprise, neutral]
-    pub fn extract_fear_logit(&self) -> f32 {
-        const FEAR_INDEX: usize = 2;
-        self.emotion_logits[FEAR_INDEX]
-    }
 }
 
-/// Fear bucket classification for terrain updates
+/// Severity of a sensor fault notice
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
-pub enum FearBucket {
-    Low,    // [0.0, 0.33)
-    Medium, // [0.33, 0.66)
-    High,   // [0.66, 1.0]
+pub enum FaultLevel {
+    Info,
+    Warning,
+    Error,
+    Critical,
 }
 
-impl FearBucket {
-    /// Classify a fear score into a bucket
-    pub fn from_score(score: f32) -> Self {
-        if score < 0.33 {
-            FearBucket::Low
-        } else if score < 0.66 {
-            FearBucket::Medium
-        } else {
-            FearBucket::High
+/// Error code of the last notice a stream carries before it ends
+pub const SENSOR_STOPPED: &str = "SENSOR_STOPPED";
+
+/// A fault or informational notice emitted by the sensor
+#[derive(Debug, Clone, PartialEq)]
+pub struct SensorFaultNotice {
+    /// Fault severity
+    pub severity: FaultLevel,
+    /// Human-readable message
+    pub message: String,
+    /// Error code for programmatic handling (e.g. "SYSTEM_RESUMED")
+    pub error_code: String,
+    /// Whether the sensor recovers without intervention
+    pub recoverable: bool,
+    /// When the fault occurred, in microseconds since Unix epoch
+    pub timestamp_us: u64,
+}
+
+impl SensorFaultNotice {
+    /// Create a fault notice stamped with the current time
+    pub fn new(
+        severity: FaultLevel,
+        message: impl Into<String>,
+        error_code: impl Into<String>,
+        recoverable: bool,
+    ) -> Self {
+        Self {
+            severity,
+            message: message.into(),
+            error_code: error_code.into(),
+            recoverable,
+            timestamp_us: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_micros() as u64,
         }
     }
 
-    /// Get the distortion intensity for shader uniforms
-    pub fn distortion_intensity(&self) -> f32 {
-        match self {
-            FearBucket::Low => 0.1,
-            FearBucket::Medium => 0.5,
-            FearBucket::High => 1.0,
-        }
+    /// Terminal notice sent to every subscriber when the sensor stops
+    pub fn stopped(reason: impl Into<String>) -> Self {
+        Self::new(FaultLevel::Info, reason, SENSOR_STOPPED, false)
+    }
+
+    /// Whether this notice announces that the sensor stopped
+    pub fn is_stopped(&self) -> bool {
+        self.error_code == SENSOR_STOPPED
     }
 }
 
@@ -90,12 +91,24 @@ impl FearBucket {
 pub struct PerformanceMetrics {
     /// Current frames per second
     pub current_fps: f32,
-    /// 95th percentile inference latency
+    /// 95th percentile inference latency over the last ten seconds
     pub p95_inference_latency: Duration,
+    /// 95th percentile inference latency since the sensor started
+    pub session_p95_inference_latency: Duration,
+    /// Inference latency histograms the percentiles are read from
+    pub inference_latency: LatencyTracker,
     /// Total number of dropped frames
     pub dropped_frames: u64,
+    /// Captured frames replaced by a fresher one before inference
+    pub stale_frames: u64,
+    /// Frames scored after running the face detector
+    pub face_detections: u64,
+    /// Frames scored on the face carried over from the last detection
+    pub carried_over_faces: u64,
     /// Calibration drift (change in baseline mean)
     pub calibration_drift: f32,
+    /// Rung of the adaptive quality ladder, 0 at full quality
+    pub quality_level: u32,
     /// Last update timestamp
     pub last_update: Instant,
 }
@@ -105,8 +118,14 @@ impl Default for PerformanceMetrics {
         Self {
             current_fps: 0.0,
             p95_inference_latency: Duration::ZERO,
+            session_p95_inference_latency: Duration::ZERO,
+            inference_latency: LatencyTracker::new(),
             dropped_frames: 0,
+            stale_frames: 0,
+            face_detections: 0,
+            carried_over_faces: 0,
             calibration_drift: 0.0,
+            quality_level: 0,
             last_update: Instant::now(),
         }
     }
@@ -131,50 +150,31 @@ impl PerformanceMetrics {
         self.dropped_frames += 1;
     }
 
-    /// Update inference latency percentile
-    pub fn update_inference_latency(&mut self, latencies: &[Duration]) {
-        if !latencies.is_empty() {
-            let mut sorted = latencies.to_vec();
-            sorted.sort();
-            let p95_index = (sorted.len() as f32 * 0.95) as usize;
-            self.p95_inference_latency = sorted.get(p95_index).copied().unwrap_or_default();
+    /// Record a captured frame that went stale before inference
+    pub fn record_stale_frame(&mut self) {
+        self.stale_frames += 1;
+    }
+
+    /// Record a scored frame whose face was detected, or carried over
+    pub fn record_face_detection(&mut self, carried_over: bool) {
+        if carried_over {
+            self.carried_over_faces += 1;
+        } else {
+            self.face_detections += 1;
         }
     }
-}
 
-/// Sensor configuration
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct SensorConfig {
-    /// Path to emotion model (can be overridden)
-    pub emotion_model_path: Option<String>,
-    /// Number of ONNX runtime threads
-    pub onnx_threads: usize,
-    /// Whether to freeze calibration after initial period
-    pub freeze_calibration: bool,
-    /// Camera device ID
-    pub camera_id: u32,
-    /// Target FPS
-    pub target_fps: f32,
-    /// Channel buffer size for back-pressure
-    pub channel_buffer_size: usize,
-    /// Metrics server port
-    pub metrics_port: u16,
-    /// gRPC server socket path
-    pub grpc_socket_path: String,
-}
+    /// Record the inference latency of a scored frame
+    pub fn record_inference_latency(&mut self, latency: Duration) {
+        self.inference_latency.record(latency);
+    }
 
-impl Default for SensorConfig {
-    fn default() -> Self {
-        Self {
-            emotion_model_path: None, // Use embedded model by default
-            onnx_threads: num_cpus::get(),
-            freeze_calibration: false,
-            camera_id: 0,
-            target_fps: 30.0,
-            channel_buffer_size: 2,
-            metrics_port: 9090,
-            grpc_socket_path: "/tmp/spectre_sensor.sock".to_string(),
-        }
+    /// Update the inference latency percentiles and start the next second
+    /// of the latency window
+    pub fn update_inference_latency(&mut self) {
+        self.p95_inference_latency = self.inference_latency.p95();
+        self.session_p95_inference_latency = self.inference_latency.session().p95();
+        self.inference_latency.advance();
     }
 }
 
@@ -182,40 +182,6 @@ impl Default for SensorConfig {
 mod tests {
     use super::*;
 
-    #[test]
-    fn test_fear_bucket_classification() {
-        assert_eq!(FearBucket::from_score(0.0), FearBucket::Low);
-        assert_eq!(FearBucket::from_score(0.32), FearBucket::Low);
-        assert_eq!(FearBucket::from_score(0.33), FearBucket::Medium);
-        assert_eq!(FearBucket::from_score(0.65), FearBucket::Medium);
-        assert_eq!(FearBucket::from_score(0.66), FearBucket::High);
-        assert_eq!(FearBucket::from_score(1.0), FearBucket::High);
-    }
-
-    #[test]
-    fn test_fear_bucket_distortion() {
-        assert_eq!(FearBucket::Low.distortion_intensity(), 0.1);
-        assert_eq!(FearBucket::Medium.distortion_intensity(), 0.5);
-        assert_eq!(FearBucket::High.distortion_intensity(), 1.0);
-    }
-
-    #[test]
-    fn test_fear_frame_creation() {
-        let emotion_logits = [0.1, 0.1, 0.8, 0.1, 0.1, 0.1, 0.1];
-        let frame = FearFrame::new(
-            0.75,
-            emotion_logits,
-            0.9,
-            true,
-            Duration::from_millis(5),
-        );
-
-        assert_eq!(frame.fear_score, 0.75);
-        assert_eq!(frame.extract_fear_logit(), 0.8);
-        assert!(frame.calibrated);
-        assert_eq!(frame.inference_latency, Duration::from_millis(5));
-    }
-
     #[test]
     fn test_performance_metrics() {
         let mut metrics = PerformanceMetrics::new();
@@ -229,14 +195,21 @@ mod tests {
         assert_eq!(metrics.dropped_frames, 1);
         
         // Test latency percentile calculation
-        let latencies = vec![
-            Duration::from_millis(1),
-            Duration::from_millis(2),
-            Duration::from_millis(3),
-            Duration::from_millis(4),
-            Duration::from_millis(10), // This should be the p95
-        ];
-        metrics.update_inference_latency(&latencies);
+        for latency in [1, 2, 3, 4, 10] {
+            metrics.record_inference_latency(Duration::from_millis(latency));
+        }
+        metrics.update_inference_latency();
         assert_eq!(metrics.p95_inference_latency, Duration::from_millis(10));
+        assert_eq!(metrics.session_p95_inference_latency, Duration::from_millis(10));
+
+        // A slow second later on leaves the window once ten more have passed
+        metrics.record_inference_latency(Duration::from_millis(40));
+        metrics.update_inference_latency();
+        for _ in 0..10 {
+            metrics.record_inference_latency(Duration::from_millis(2));
+            metrics.update_inference_latency();
+        }
+        assert!(metrics.p95_inference_latency < Duration::from_millis(3));
+        assert!(metrics.session_p95_inference_latency >= Duration::from_millis(10));
     }
 }
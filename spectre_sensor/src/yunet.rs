@@ -15,6 +15,7 @@ use ort::{
 use ndarray::Array4;
 use std::time::Instant;
 use thiserror::Error;
+use crate::model_info::{sha256_hex, ModelInfo, ModelInfoError};
 
 /// YuNet face detection errors
 #[derive(Debug, Error)]
@@ -33,6 +34,12 @@ pub enum YuNetError {
     
     #[error("Invalid model output format")]
     InvalidOutput,
+
+    #[error("Face detector backend unavailable: {0}")]
+    BackendUnavailable(String),
+
+    #[error("Face detection model metadata error: {0}")]
+    ModelInfo(#[from] ModelInfoError),
 }
 
 /// Face detection result
@@ -46,9 +53,79 @@ pub struct FaceDetection {
     pub landmarks: Vec<Point>,
 }
 
+impl FaceDetection {
+    /// The subject's right eye, which YuNet places on the image's left
+    pub fn right_eye(&self) -> Option<Point> {
+        self.landmarks.first().copied()
+    }
+
+    /// The subject's left eye
+    pub fn left_eye(&self) -> Option<Point> {
+        self.landmarks.get(1).copied()
+    }
+
+    /// Nose tip
+    pub fn nose(&self) -> Option<Point> {
+        self.landmarks.get(2).copied()
+    }
+
+    /// Rough head yaw: the nose tip's offset from between the eyes, in eye
+    /// spacings, positive when the head turns toward the subject's left
+    ///
+    /// Only the sign and rough size are meaningful; on a mirrored feed both
+    /// the eyes and the sign are swapped (see [`crate::mirror`]).
+    pub fn yaw(&self) -> Option<f32> {
+        let (right, left, nose) = (self.right_eye()?, self.left_eye()?, self.nose()?);
+        let spacing = (left.x - right.x) as f32;
+        if spacing == 0.0 {
+            return None;
+        }
+        Some((nose.x as f32 - (left.x + right.x) as f32 / 2.0) / spacing)
+    }
+}
+
+/// Thresholds and input size a YuNet detector runs with
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct YuNetParams {
+    /// Minimum confidence for a face to be reported
+    pub confidence_threshold: f32,
+    /// Overlap above which the weaker of two face boxes is suppressed
+    pub nms_threshold: f32,
+    /// Size frames are resized to before inference; a multiple of 32
+    pub input_size: Size,
+}
+
+impl Default for YuNetParams {
+    fn default() -> Self {
+        Self {
+            confidence_threshold: 0.6,
+            nms_threshold: 0.3,
+            input_size: Size::new(640, 640), // YuNet 2023mar model input size
+        }
+    }
+}
+
+impl YuNetParams {
+    /// Check the thresholds lie in (0, 1) and the input size suits the model's strides
+    pub fn validate(&self) -> Result<(), String> {
+        if !(self.confidence_threshold > 0.0 && self.confidence_threshold < 1.0) {
+            return Err(format!("Face confidence threshold must be in (0, 1), got {}", self.confidence_threshold));
+        }
+        if !(self.nms_threshold > 0.0 && self.nms_threshold < 1.0) {
+            return Err(format!("Face NMS threshold must be in (0, 1), got {}", self.nms_threshold));
+        }
+        let Size { width, height } = self.input_size;
+        if width < 32 || height < 32 || width % 32 != 0 || height % 32 != 0 {
+            return Err(format!("YuNet input size must be a positive multiple of 32, got {}x{}", width, height));
+        }
+        Ok(())
+    }
+}
+
 /// YuNet face detector using ONNX Runtime
 pub struct YuNetDetector {
     session: Session,
+    model_info: ModelInfo,
     input_size: Size,
     confidence_threshold: f32,
     nms_threshold: f32,
@@ -56,15 +133,18 @@ pub struct YuNetDetector {
 
 impl YuNetDetector {
     /// Create a new YuNet detector with embedded model
-    pub fn new(num_threads: usize) -> Result<Self, YuNetError> {
-        Self::from_bytes(crate::YUNET_MODEL_BYTES, num_threads)
+    #[cfg(feature = "embedded-models")]
+    pub fn new(num_threads: usize, params: YuNetParams) -> Result<Self, YuNetError> {
+        Self::from_bytes(crate::YUNET_MODEL_BYTES, num_threads, params)
     }
 
     /// Create a new YuNet detector from model bytes
     pub fn from_bytes(
         model_bytes: &[u8],
         num_threads: usize,
+        params: YuNetParams,
     ) -> Result<Self, YuNetError> {
+        let model_info = bytes_model_info(model_bytes);
         let session = Session::builder()
             .map_err(|e| YuNetError::SessionCreation(e.to_string()))?
             .with_optimization_level(GraphOptimizationLevel::Level3)
@@ -74,19 +154,20 @@ impl YuNetDetector {
             .commit_from_memory(model_bytes)
             .map_err(|e| YuNetError::SessionCreation(e.to_string()))?;
 
-        Ok(Self {
-            session,
-            input_size: Size::new(640, 640), // YuNet 2023mar model input size
-            confidence_threshold: 0.6,
-            nms_threshold: 0.3,
-        })
+        Ok(Self::with_session(session, model_info, params))
     }
 
     /// Create from external model file (for --model-path override)
+    ///
+    /// `model_info` describes the file for attribution; without it the
+    /// detector reports a hash-only record.
     pub fn from_file(
         model_path: &str,
         num_threads: usize,
+        model_info: Option<ModelInfo>,
+        params: YuNetParams,
     ) -> Result<Self, YuNetError> {
+        let model_info = ModelInfo::for_file(model_path, model_info)?;
         let session = Session::builder()
             .map_err(|e| YuNetError::SessionCreation(e.to_string()))?
             .with_optimization_level(GraphOptimizationLevel::Level3)
@@ -96,12 +177,32 @@ impl YuNetDetector {
             .commit_from_file(model_path)
             .map_err(|e| YuNetError::SessionCreation(e.to_string()))?;
 
-        Ok(Self {
+        Ok(Self::with_session(session, model_info, params))
+    }
+
+    fn with_session(session: Session, model_info: ModelInfo, params: YuNetParams) -> Self {
+        Self {
             session,
-            input_size: Size::new(640, 640), // YuNet 2023mar model input size
-            confidence_threshold: 0.6,
-            nms_threshold: 0.3,
-        })
+            model_info,
+            input_size: params.input_size,
+            confidence_threshold: params.confidence_threshold,
+            nms_threshold: params.nms_threshold,
+        }
+    }
+
+    /// Provenance of the loaded model
+    pub fn model_info(&self) -> &ModelInfo {
+        &self.model_info
+    }
+
+    /// Minimum score a detection needs, from the next image on
+    pub fn set_confidence_threshold(&mut self, threshold: f32) {
+        self.confidence_threshold = threshold;
+    }
+
+    /// Size frames are resized to, from the next image on; a multiple of 32
+    pub fn set_input_size(&mut self, size: Size) {
+        self.input_size = size;
     }
 
     /// Detect faces in the given image
@@ -301,7 +402,7 @@ impl YuNetDetector {
                     continue;
                 }
 
-                let iou = Self::calculate_iou_static(&detections[i].bbox, &detections[j].bbox);
+                let iou = calculate_iou(&detections[i].bbox, &detections[j].bbox);
                 if iou > nms_threshold {
                     keep[j] = false;
                 }
@@ -318,28 +419,38 @@ impl YuNetDetector {
 
         *detections = filtered;
     }
+}
 
-    /// Calculate Intersection over Union (IoU) for two bounding boxes (static version)
-    fn calculate_iou_static(bbox1: &Rect, bbox2: &Rect) -> f32 {
-        let x1 = bbox1.x.max(bbox2.x);
-        let y1 = bbox1.y.max(bbox2.y);
-        let x2 = (bbox1.x + bbox1.width).min(bbox2.x + bbox2.width);
-        let y2 = (bbox1.y + bbox1.height).min(bbox2.y + bbox2.height);
+/// Intersection over Union (IoU) of two bounding boxes
+pub fn calculate_iou(bbox1: &Rect, bbox2: &Rect) -> f32 {
+    let x1 = bbox1.x.max(bbox2.x);
+    let y1 = bbox1.y.max(bbox2.y);
+    let x2 = (bbox1.x + bbox1.width).min(bbox2.x + bbox2.width);
+    let y2 = (bbox1.y + bbox1.height).min(bbox2.y + bbox2.height);
 
-        if x2 <= x1 || y2 <= y1 {
-            return 0.0;
-        }
+    if x2 <= x1 || y2 <= y1 {
+        return 0.0;
+    }
 
-        let intersection = ((x2 - x1) * (y2 - y1)) as f32;
-        let area1 = (bbox1.width * bbox1.height) as f32;
-        let area2 = (bbox2.width * bbox2.height) as f32;
-        let union = area1 + area2 - intersection;
+    let intersection = ((x2 - x1) * (y2 - y1)) as f32;
+    let area1 = (bbox1.width * bbox1.height) as f32;
+    let area2 = (bbox2.width * bbox2.height) as f32;
+    let union = area1 + area2 - intersection;
 
-        if union > 0.0 {
-            intersection / union
-        } else {
-            0.0
-        }
+    if union > 0.0 {
+        intersection / union
+    } else {
+        0.0
+    }
+}
+
+/// Embedded YuNet metadata when the bytes are the embedded model, a hash-only record otherwise
+pub(crate) fn bytes_model_info(model_bytes: &[u8]) -> ModelInfo {
+    let sha256 = sha256_hex(model_bytes);
+    if crate::YUNET_MODEL_INFO.sha256 == sha256.as_str() {
+        crate::YUNET_MODEL_INFO
+    } else {
+        ModelInfo::hash_only("YuNet (in-memory)", sha256)
     }
 }
 
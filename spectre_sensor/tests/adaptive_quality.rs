@@ -0,0 +1,87 @@
+//! Adaptive quality on the synthetic pipeline: inference slowed past the
+//! frame budget steps the sensor down the ladder within a few seconds, with
+//! a notice for every step, and it climbs back to full quality once
+//! inference is fast again
+
+use spectre_sensor::{
+    mock_patterns::MockPattern,
+    quality::{QualityConfig, QUALITY_CHANGED},
+    types::{FaultLevel, SensorFaultNotice},
+    EmotionSensor, SensorConfig,
+};
+use std::time::{Duration, Instant};
+use tokio::sync::broadcast;
+
+fn config() -> SensorConfig {
+    SensorConfig::default()
+        .with_mock(MockPattern::Step)
+        .with_target_fps(30.0)
+        // Twice the 33 ms a frame may take at 30 FPS
+        .with_mock_inference_latency(Duration::from_millis(60))
+        .with_quality(QualityConfig {
+            window: Duration::from_millis(500),
+            recovery: Duration::from_secs(1),
+            ..QualityConfig::default()
+        })
+}
+
+/// Wait up to `timeout` for the sensor to reach a quality level `reached` accepts
+async fn wait_for_level(sensor: &EmotionSensor, timeout: Duration, reached: impl Fn(u32) -> bool) -> u32 {
+    let deadline = Instant::now() + timeout;
+    loop {
+        let level = sensor.get_state().metrics.quality_level;
+        if reached(level) || Instant::now() >= deadline {
+            return level;
+        }
+        tokio::time::sleep(Duration::from_millis(50)).await;
+    }
+}
+
+/// Quality notices received so far
+fn quality_notices(faults: &mut broadcast::Receiver<SensorFaultNotice>) -> Vec<SensorFaultNotice> {
+    std::iter::from_fn(|| faults.try_recv().ok())
+        .filter(|notice| notice.error_code == QUALITY_CHANGED)
+        .collect()
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_quality_follows_inference_latency() {
+    let mut sensor = EmotionSensor::new(config());
+    sensor.initialize().await.unwrap();
+    let mut faults = sensor.subscribe_faults();
+    let _frames = sensor.start().await.unwrap();
+
+    // Both smaller detector inputs leave the budget exceeded; 15 FPS does not
+    let level = wait_for_level(&sensor, Duration::from_secs(5), |level| level >= 3).await;
+    assert!(level >= 3, "still at quality level {} after 5 s", level);
+    let down = quality_notices(&mut faults);
+    assert!(down.len() >= 3, "{:?}", down);
+    assert!(down.iter().all(|notice| notice.severity == FaultLevel::Info && notice.recoverable));
+    assert!(down[0].message.starts_with("Quality level 0 -> 1"), "{}", down[0].message);
+    assert!(down[0].message.contains("face detector input 480"), "{}", down[0].message);
+    assert!(down[2].message.contains("15 FPS"), "{}", down[2].message);
+
+    // Fast inference climbs back a rung per recovery period
+    sensor.set_mock_inference_latency(Duration::ZERO);
+    let level = wait_for_level(&sensor, Duration::from_secs(15), |level| level == 0).await;
+    assert_eq!(level, 0);
+    let up = quality_notices(&mut faults);
+    assert!(up.len() >= 3, "{:?}", up);
+    assert!(up.last().unwrap().message.starts_with("Quality level 1 -> 0"), "{:?}", up);
+
+    sensor.stop().await.unwrap();
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_disabled_quality_holds_full_quality() {
+    let mut sensor = EmotionSensor::new(config().with_adaptive_quality(false));
+    sensor.initialize().await.unwrap();
+    let mut faults = sensor.subscribe_faults();
+    let _frames = sensor.start().await.unwrap();
+
+    let level = wait_for_level(&sensor, Duration::from_secs(2), |level| level > 0).await;
+    assert_eq!(level, 0);
+    assert!(quality_notices(&mut faults).is_empty());
+
+    sensor.stop().await.unwrap();
+}
@@ -0,0 +1,43 @@
+//! A reader slower than the sensor: by default a full frame channel drops
+//! its oldest frame, so whenever the reader catches up the next frame is a
+//! fresh one; under drop-newest it reads frames from before it fell behind
+
+use spectre_sensor::{mock_patterns::MockPattern, BackpressurePolicy, EmotionSensor, SensorConfig};
+use std::time::{Duration, SystemTime};
+
+/// How long the reader stops reading at a time
+const PAUSE: Duration = Duration::from_millis(300);
+
+/// Age of the frame a reader gets after each of a few pauses, under `policy`
+async fn ages_after_pauses(policy: BackpressurePolicy) -> Vec<Duration> {
+    let config = SensorConfig::default()
+        .with_mock(MockPattern::Step)
+        .with_target_fps(60.0)
+        .with_backpressure(policy);
+    let mut sensor = EmotionSensor::new(config);
+    sensor.initialize().await.unwrap();
+    let frames = sensor.start().await.unwrap();
+
+    let mut ages = Vec::new();
+    for _ in 0..3 {
+        tokio::time::sleep(PAUSE).await;
+        let frame = tokio::time::timeout(Duration::from_secs(1), frames.recv()).await.unwrap().unwrap();
+        ages.push(SystemTime::now().duration_since(frame.captured_at).unwrap_or_default());
+    }
+    sensor.stop().await.unwrap();
+    ages
+}
+
+#[tokio::test]
+async fn test_slow_reader_gets_fresh_frames_by_default() {
+    for age in ages_after_pauses(BackpressurePolicy::default()).await {
+        assert!(age < PAUSE / 2, "read a frame {:?} old", age);
+    }
+}
+
+#[tokio::test]
+async fn test_drop_newest_leaves_a_slow_reader_stale_frames() {
+    for age in ages_after_pauses(BackpressurePolicy::DropNewest).await {
+        assert!(age >= PAUSE / 2, "read a frame only {:?} old", age);
+    }
+}
@@ -0,0 +1,167 @@
+//! Calibration control against the phase state machine: every action in
+//! every phase, and concurrent clients racing a pipeline that keeps sampling
+
+use spectre_sensor::calibration_control::{
+    control_channel, pipeline_phase, CalibrationAction, CalibrationControlError,
+};
+use spectre_sensor::calibrator::AdaptiveCalibrator;
+use spectre_sensor::phases::CalibrationPhase;
+use spectre_sensor::types::SensorCapability;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::watch;
+
+const ACTIONS: [CalibrationAction; 4] = [
+    CalibrationAction::Start,
+    CalibrationAction::Freeze,
+    CalibrationAction::Unfreeze,
+    CalibrationAction::Reset,
+];
+
+/// Calibrator and capability forced into the named phase
+fn forced(phase: &str) -> (AdaptiveCalibrator, SensorCapability) {
+    let mut calibrator = AdaptiveCalibrator::new(Duration::ZERO, 0.05);
+    let mut capability = SensorCapability::Full;
+    match phase {
+        "idle" => {}
+        "collecting" => calibrator.add_sample(0.5).unwrap(),
+        "calibrated" | "frozen" => {
+            for _ in 0..30 {
+                calibrator.add_sample(0.5).unwrap();
+            }
+            if phase == "frozen" {
+                calibrator.freeze();
+            }
+        }
+        "failed" => capability = SensorCapability::EmotionOffline,
+        _ => unreachable!(),
+    }
+    (calibrator, capability)
+}
+
+/// Whether `action` is accepted in `phase`, and the phase it leaves
+fn expected(phase: &str, action: CalibrationAction) -> Option<&'static str> {
+    use CalibrationAction::*;
+    match (phase, action) {
+        ("idle", Start | Reset) => Some("idle"),
+        ("collecting", Start | Reset) => Some("collecting"),
+        ("calibrated", Freeze) | ("frozen", Freeze) => Some("frozen"),
+        ("calibrated", Unfreeze) | ("frozen", Unfreeze) => Some("calibrated"),
+        ("calibrated", Reset) => Some("collecting"),
+        ("failed", Start | Reset) => Some("failed"),
+        _ => None,
+    }
+}
+
+#[tokio::test]
+async fn test_every_action_in_every_phase() {
+    for phase_name in ["idle", "collecting", "calibrated", "frozen", "failed"] {
+        for action in ACTIONS {
+            let (mut calibrator, capability) = forced(phase_name);
+            let before = calibrator.baseline_stats().clone();
+            let frozen_before = calibrator.is_frozen();
+
+            let result = if phase_name == "idle" {
+                // An idle sensor applies actions itself; the loop never sees them
+                action
+                    .apply(&mut calibrator, &CalibrationPhase::Idle)
+                    .map(|()| CalibrationPhase::Idle)
+            } else {
+                let (controller, mut inbox) = control_channel();
+                let (published, _) = watch::channel(CalibrationPhase::Idle);
+                let sent = tokio::spawn(async move { controller.send(action).await });
+                while !sent.is_finished() {
+                    inbox.apply_pending(&mut calibrator, capability, &published);
+                    tokio::task::yield_now().await;
+                }
+                let result = sent.await.unwrap();
+                if let Ok(phase) = &result {
+                    assert_eq!(*published.borrow(), *phase);
+                }
+                result
+            };
+
+            match (expected(phase_name, action), result) {
+                (Some(left), Ok(phase)) => assert_eq!(phase.name(), left, "{action} while {phase_name}"),
+                (None, Err(CalibrationControlError::IllegalTransition { phase, action: rejected })) => {
+                    assert_eq!((phase.name(), rejected), (phase_name, action));
+                    // Rejected actions change nothing
+                    assert_eq!(calibrator.baseline_stats().sample_count, before.sample_count);
+                    assert_eq!(calibrator.is_frozen(), frozen_before);
+                }
+                (expected, result) => panic!("{action} while {phase_name}: expected {expected:?}, got {result:?}"),
+            }
+        }
+    }
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn test_concurrent_freeze_unfreeze_while_sampling() {
+    const ROUNDS: usize = 200;
+
+    let (mut calibrator, capability) = forced("calibrated");
+    let (controller, mut inbox) = control_channel();
+    let (published, mut phases) = watch::channel(pipeline_phase(&calibrator, capability));
+    let done = Arc::new(AtomicBool::new(false));
+
+    // Mock pipeline: a sample per frame, actions only between frames
+    let pipeline = tokio::spawn({
+        let done = Arc::clone(&done);
+        async move {
+            let mut sampled = 0;
+            while !done.load(Ordering::SeqCst) {
+                inbox.apply_pending(&mut calibrator, capability, &published);
+                if !calibrator.is_frozen() {
+                    calibrator.add_logits(&[0.1, 0.0, 0.5, 0.0, 0.0, 0.2, 0.0]).unwrap();
+                    sampled += 1;
+                    // Republished like the sensor loop's metrics tick, so the
+                    // quality a sample moves reaches subscribers
+                    published.send_replace(pipeline_phase(&calibrator, capability));
+                }
+                tokio::task::yield_now().await;
+            }
+            inbox.apply_pending(&mut calibrator, capability, &published);
+            (calibrator, sampled, published)
+        }
+    });
+
+    let clients: Vec<_> = [CalibrationAction::Freeze, CalibrationAction::Unfreeze]
+        .into_iter()
+        .map(|first| {
+            let controller = controller.clone();
+            tokio::spawn(async move {
+                for round in 0..ROUNDS {
+                    let action = match (first, round % 2) {
+                        (CalibrationAction::Freeze, 0) | (CalibrationAction::Unfreeze, 1) => CalibrationAction::Freeze,
+                        _ => CalibrationAction::Unfreeze,
+                    };
+                    // Both are legal, and idempotent, once calibrated
+                    let phase = controller.send(action).await.unwrap();
+                    match action {
+                        CalibrationAction::Freeze => assert!(matches!(phase, CalibrationPhase::Frozen { .. })),
+                        _ => assert!(matches!(phase, CalibrationPhase::Calibrated { .. })),
+                    }
+                }
+            })
+        })
+        .collect();
+    for client in clients {
+        client.await.unwrap();
+    }
+    done.store(true, Ordering::SeqCst);
+    let (calibrator, sampled, published) = pipeline.await.unwrap();
+
+    // The published phase is the calibrator's, and frozen means frozen
+    let phase = phases.borrow_and_update().clone();
+    assert_eq!(phase, pipeline_phase(&calibrator, capability));
+    assert_eq!(calibrator.is_frozen(), matches!(phase, CalibrationPhase::Frozen { .. }));
+    assert!(calibrator.is_calibrated());
+    assert_eq!(calibrator.baseline_stats().sample_count, 30 + sampled);
+    assert!(calibrator.baseline_stats().mean.is_finite());
+    assert!(calibrator.baseline_stats().std_dev.is_finite());
+    drop(published);
+
+    // Once the loop is gone actions are interrupted rather than lost
+    assert_eq!(controller.send(CalibrationAction::Freeze).await, Err(CalibrationControlError::Interrupted));
+}
@@ -0,0 +1,71 @@
+//! Face detection on every nth frame: with a synthetic face sweeping across
+//! the frame, the box carried over between detections stays on the face,
+//! and the metrics count detected and carried-over frames apart
+
+use spectre_sensor::{
+    mock_patterns::MockPattern,
+    synthetic::{face_at, FRAME_HEIGHT, FRAME_WIDTH},
+    EmotionSensor, SensorConfig,
+};
+use spectremesh_core::NormalizedRect;
+use std::time::Duration;
+
+/// One sweep across the frame and back, 80 pixels a second
+const FACE_PERIOD: Duration = Duration::from_secs(4);
+
+fn iou(a: &NormalizedRect, b: &NormalizedRect) -> f32 {
+    let width = (a.x + a.width).min(b.x + b.width) - a.x.max(b.x);
+    let height = (a.y + a.height).min(b.y + b.height) - a.y.max(b.y);
+    if width <= 0.0 || height <= 0.0 {
+        return 0.0;
+    }
+    let intersection = width * height;
+    intersection / (a.width * a.height + b.width * b.height - intersection)
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_carried_over_face_follows_moving_face() {
+    let config = SensorConfig::default()
+        .with_mock(MockPattern::Step)
+        .with_mock_face_period(FACE_PERIOD)
+        .with_target_fps(30.0)
+        .with_detection_interval(5);
+    let mut sensor = EmotionSensor::new(config);
+    sensor.initialize().await.unwrap();
+    let frames = sensor.start().await.unwrap();
+
+    let mut overlaps = Vec::new();
+    while overlaps.len() < 60 {
+        let frame = tokio::time::timeout(Duration::from_secs(1), frames.recv()).await.unwrap().unwrap();
+        let face = face_at(FACE_PERIOD, frame.captured_at);
+        let truth = NormalizedRect::from_pixels(face.x, face.y, face.width, face.height, FRAME_WIDTH, FRAME_HEIGHT);
+        overlaps.push(iou(&frame.face_bbox.expect("no face in frame"), &truth.unwrap()));
+    }
+    assert!(overlaps.iter().all(|&overlap| overlap >= 0.5), "{:?}", overlaps);
+    // The face really moved, so some frames scored a box it had left
+    assert!(overlaps.iter().any(|&overlap| overlap < 0.99), "{:?}", overlaps);
+
+    sensor.stop().await.unwrap();
+    let metrics = sensor.get_state().metrics;
+    assert!(metrics.face_detections > 0);
+    assert!(metrics.carried_over_faces >= 3 * metrics.face_detections, "{:?}", metrics);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_interval_of_one_detects_every_frame() {
+    let config = SensorConfig::default()
+        .with_mock(MockPattern::Step)
+        .with_target_fps(60.0)
+        .with_detection_interval(1);
+    let mut sensor = EmotionSensor::new(config);
+    sensor.initialize().await.unwrap();
+    let frames = sensor.start().await.unwrap();
+    for _ in 0..20 {
+        tokio::time::timeout(Duration::from_secs(1), frames.recv()).await.unwrap().unwrap();
+    }
+    sensor.stop().await.unwrap();
+
+    let metrics = sensor.get_state().metrics;
+    assert!(metrics.face_detections >= 20);
+    assert_eq!(metrics.carried_over_faces, 0);
+}
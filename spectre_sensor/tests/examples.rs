@@ -0,0 +1,74 @@
+//! Smoke tests: every example and the tutorial run to completion against
+//! the mock sensor
+
+#![cfg(all(feature = "mock", feature = "stream"))]
+
+use clap::Parser;
+use std::process::Command;
+
+#[allow(dead_code)]
+#[path = "../examples/in_process.rs"]
+mod in_process;
+#[allow(dead_code)]
+#[path = "../examples/stream_client.rs"]
+mod stream_client;
+#[allow(dead_code)]
+#[path = "../examples/calibration.rs"]
+mod calibration;
+#[allow(dead_code)]
+#[path = "../examples/record_replay.rs"]
+mod record_replay;
+#[allow(dead_code)]
+#[path = "../examples/measure.rs"]
+mod measure;
+
+#[tokio::test]
+async fn test_in_process_example() {
+    in_process::run(in_process::Args::parse_from(["in_process", "--mock", "--duration", "2"]))
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn test_stream_client_example() {
+    stream_client::run(stream_client::Args::parse_from(["stream_client", "--mock", "--duration", "3"]))
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn test_calibration_example() {
+    calibration::run(calibration::Args::parse_from(["calibration", "--mock", "--duration", "5"]))
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn test_record_replay_example() {
+    let output = std::env::temp_dir().join(format!("spectre_example_{}.jsonl", std::process::id()));
+    let args = ["record_replay", "--mock", "--duration", "2", "--speed", "20", "--output"];
+    let result = record_replay::run(record_replay::Args::parse_from(
+        args.iter().copied().chain([output.to_str().unwrap()]),
+    ))
+    .await;
+    let _ = std::fs::remove_file(&output);
+    result.unwrap();
+}
+
+#[tokio::test]
+async fn test_measure_example() {
+    measure::run(measure::Args::parse_from(["measure", "--mock", "--duration", "2"]))
+        .await
+        .unwrap();
+}
+
+#[test]
+fn test_tutorial_passes_every_stage() {
+    let output = Command::new(env!("CARGO_BIN_EXE_tutorial"))
+        .args(["--mock", "--yes", "--duration", "5"])
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(output.status.success(), "tutorial failed:\n{}\n{}", stdout, String::from_utf8_lossy(&output.stderr));
+    assert!(stdout.contains("All stages passed"));
+}
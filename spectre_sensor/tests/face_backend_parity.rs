@@ -0,0 +1,70 @@
+//! Parity between the ONNX Runtime and OpenCV face detection backends
+//!
+//! Runs both backends on the images in `tests/fixtures/faces` and checks that
+//! they agree on the most confident face.
+
+#![cfg(all(feature = "opencv-face-detector", feature = "embedded-models"))]
+
+use opencv::imgcodecs;
+use spectre_sensor::{
+    face_backend::{FaceDetectorBackend, OpenCvYuNetDetector},
+    yunet::{calculate_iou, YuNetDetector, YuNetParams},
+};
+use std::path::{Path, PathBuf};
+
+/// Minimum overlap between the two backends' face boxes
+const MIN_IOU: f32 = 0.8;
+/// Maximum difference between the two backends' confidences
+const MAX_CONFIDENCE_DELTA: f32 = 0.05;
+
+fn fixture_images() -> Vec<PathBuf> {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/faces");
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+
+    let mut images: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| {
+            path.extension()
+                .and_then(|ext| ext.to_str())
+                .is_some_and(|ext| matches!(ext.to_ascii_lowercase().as_str(), "jpg" | "jpeg" | "png"))
+        })
+        .collect();
+    images.sort();
+    images
+}
+
+#[test]
+fn test_backends_agree_on_fixture_faces() {
+    let images = fixture_images();
+    if images.is_empty() {
+        eprintln!("No face fixtures in tests/fixtures/faces, skipping parity test");
+        return;
+    }
+
+    let mut ort_detector = YuNetDetector::new(1, YuNetParams::default()).expect("ONNX Runtime detector");
+    let mut opencv_detector = OpenCvYuNetDetector::new(YuNetParams::default()).expect("OpenCV detector");
+
+    for path in images {
+        let image = imgcodecs::imread(&path.to_string_lossy(), imgcodecs::IMREAD_COLOR).unwrap();
+
+        let expected = ort_detector
+            .get_largest_face(&image)
+            .unwrap_or_else(|e| panic!("{}: ONNX Runtime backend failed: {}", path.display(), e));
+        let actual = opencv_detector
+            .get_largest_face(&image)
+            .unwrap_or_else(|e| panic!("{}: OpenCV backend failed: {}", path.display(), e));
+
+        let iou = calculate_iou(&expected.bbox, &actual.bbox);
+        assert!(iou > MIN_IOU, "{}: face boxes differ (IoU {:.3})", path.display(), iou);
+        assert!(
+            (expected.confidence - actual.confidence).abs() <= MAX_CONFIDENCE_DELTA,
+            "{}: confidence {:.3} vs {:.3}",
+            path.display(),
+            expected.confidence,
+            actual.confidence
+        );
+        assert_eq!(actual.landmarks.len(), expected.landmarks.len());
+    }
+}
@@ -0,0 +1,65 @@
+//! Feature matrix: the crate, its binaries and its tests build with each
+//! optional subsystem turned off
+//!
+//! Every configuration runs a full `cargo check`, so these are ignored by
+//! default. Run them after touching a `cfg(feature)` with
+//! `cargo test -p spectre-sensor --test feature_matrix -- --ignored`.
+
+use std::path::Path;
+use std::process::Command;
+
+/// `cargo check` every target of the crate with `args` selecting features
+fn check(name: &str, args: &[&str]) {
+    let manifest = Path::new(env!("CARGO_MANIFEST_DIR")).join("Cargo.toml");
+    // A target directory per configuration, so the checks neither wait on the
+    // outer build's lock nor invalidate each other's artifacts
+    let target_dir = Path::new(env!("CARGO_TARGET_TMPDIR")).join("feature-matrix").join(name);
+    let output = Command::new(env!("CARGO"))
+        .arg("check")
+        .arg("--manifest-path")
+        .arg(&manifest)
+        .arg("--target-dir")
+        .arg(&target_dir)
+        .args(["-p", "spectre-sensor", "--all-targets", "--quiet"])
+        .args(args)
+        .env("RUSTFLAGS", "-D warnings")
+        .output()
+        .expect("run cargo check");
+    assert!(
+        output.status.success(),
+        "`cargo check {}` failed for the {} configuration:\n{}",
+        args.join(" "),
+        name,
+        String::from_utf8_lossy(&output.stderr)
+    );
+}
+
+#[test]
+#[ignore = "runs cargo check"]
+fn test_minimal_in_process_build() {
+    check("minimal", &["--no-default-features"]);
+}
+
+#[test]
+#[ignore = "runs cargo check"]
+fn test_build_without_streaming() {
+    check("no-stream", &["--no-default-features", "--features", "metrics,embedded-models"]);
+}
+
+#[test]
+#[ignore = "runs cargo check"]
+fn test_build_without_metrics() {
+    check("no-metrics", &["--no-default-features", "--features", "stream,embedded-models"]);
+}
+
+#[test]
+#[ignore = "runs cargo check"]
+fn test_build_without_embedded_models() {
+    check("no-embedded-models", &["--no-default-features", "--features", "stream,metrics"]);
+}
+
+#[test]
+#[ignore = "runs cargo check"]
+fn test_build_with_everything() {
+    check("everything", &["--all-features"]);
+}
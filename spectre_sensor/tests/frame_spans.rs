@@ -0,0 +1,95 @@
+//! JSON logs trace the pipeline frame by frame: every stage closes a span
+//! recording its duration inside the span of the frame it worked on, which
+//! carries the frame's sequence number, and each metrics tick logs one
+//! summary record
+
+use serde_json::Value;
+use spectre_sensor::{
+    logging::{console_layer, parse_filter, LogFormat},
+    mock_patterns::MockPattern,
+    EmotionSensor, SensorConfig,
+};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tracing_subscriber::{fmt::MakeWriter, layer::SubscriberExt, Layer};
+
+/// Console output kept in memory
+#[derive(Clone, Default)]
+struct Captured(Arc<Mutex<Vec<u8>>>);
+
+impl Captured {
+    fn contains(&self, text: &str) -> bool {
+        String::from_utf8_lossy(&self.0.lock().unwrap()).contains(text)
+    }
+
+    fn records(&self) -> Vec<Value> {
+        let output = self.0.lock().unwrap();
+        output
+            .split(|&byte| byte == b'\n')
+            .filter(|line| !line.is_empty())
+            .map(|line| serde_json::from_slice(line).unwrap())
+            .collect()
+    }
+}
+
+impl std::io::Write for Captured {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.lock().unwrap().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+impl<'a> MakeWriter<'a> for Captured {
+    type Writer = Self;
+
+    fn make_writer(&'a self) -> Self::Writer {
+        self.clone()
+    }
+}
+
+// The loop runs on this thread, where the subscriber is the default
+#[tokio::test]
+async fn test_json_logs_carry_stage_latencies() {
+    let output = Captured::default();
+    let filter = parse_filter("spectre_sensor::sensor=debug").unwrap();
+    let subscriber = tracing_subscriber::registry().with(console_layer(LogFormat::Json, output.clone()).with_filter(filter));
+    let _guard = tracing::subscriber::set_default(subscriber);
+
+    let mut sensor = EmotionSensor::new(SensorConfig::default().with_mock(MockPattern::Step).with_target_fps(60.0));
+    sensor.initialize().await.unwrap();
+    let frames = sensor.start().await.unwrap();
+    // The metrics tick comes after a second of frames
+    let deadline = Instant::now() + Duration::from_secs(5);
+    while !output.contains("Sensor metrics") && Instant::now() < deadline {
+        tokio::time::timeout(Duration::from_secs(1), frames.recv()).await.unwrap().unwrap();
+    }
+    sensor.stop().await.unwrap();
+
+    let records = output.records();
+    let closed = |name: &str| {
+        records
+            .iter()
+            .find(|record| record["fields"]["message"] == "close" && record["span"]["name"] == name)
+            .unwrap_or_else(|| panic!("no {} span closed", name))
+    };
+    for stage in ["capture", "detect", "emotion_infer", "calibrate", "publish"] {
+        let record = closed(stage);
+        assert!(record["span"]["duration_us"].is_u64(), "{}", record);
+        assert_eq!(record["spans"][0]["name"], "frame", "{}", record);
+        assert!(record["spans"][0]["sequence"].is_u64(), "{}", record);
+    }
+    assert!(closed("capture")["span"]["queue_delay_us"].is_u64());
+    assert!(closed("frame")["span"]["duration_us"].is_u64());
+
+    let summary = records
+        .iter()
+        .find(|record| record["fields"]["message"] == "Sensor metrics")
+        .expect("no metrics summary");
+    for field in ["fps", "p95_us", "dropped_frames", "calibration_progress", "calibrated"] {
+        assert!(!summary["fields"][field].is_null(), "{} missing from {}", field, summary);
+    }
+}
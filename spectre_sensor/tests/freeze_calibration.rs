@@ -0,0 +1,66 @@
+//! With `freeze_calibration` the baseline locks the moment the initial
+//! calibration completes: frames keep flowing, scored against a baseline
+//! that no longer takes samples, while without it the baseline keeps
+//! following the subject
+
+use async_channel::Receiver;
+use spectre_sensor::{
+    mock_patterns::MockPattern,
+    phases::{CalibrationPhase, PhaseOutcome},
+    BaselineStats, EmotionSensor, FearFrame, SensorConfig,
+};
+use std::time::Duration;
+use tokio::time::Instant;
+
+/// Read frames for `period`; how many arrived
+async fn drain(frames: &Receiver<FearFrame>, period: Duration) -> usize {
+    let deadline = Instant::now() + period;
+    let mut count = 0;
+    while let Ok(frame) = tokio::time::timeout_at(deadline, frames.recv()).await {
+        frame.unwrap();
+        count += 1;
+    }
+    count
+}
+
+/// Baselines published a metrics tick apart after calibration, with the
+/// frames read meanwhile and the phase reached
+async fn baselines_after_calibration(freeze: bool) -> (BaselineStats, BaselineStats, usize, CalibrationPhase) {
+    let config = SensorConfig::default()
+        .with_mock(MockPattern::Sine { center: 0.5, amplitude: 0.3, period: 2.0 })
+        .with_calibration_period(Duration::from_millis(500))
+        .with_target_fps(60.0)
+        .with_freeze_calibration(freeze);
+    let mut sensor = EmotionSensor::new(config);
+    sensor.initialize().await.unwrap();
+    let mut phases = sensor.phases();
+    let frames = sensor.start().await.unwrap();
+    let phase = match phases.wait_for_calibrated(Duration::from_secs(5)).await.unwrap() {
+        PhaseOutcome::Reached(phase) => phase,
+        outcome => panic!("not calibrated: {:?}", outcome),
+    };
+
+    // The baseline is published once a second
+    drain(&frames, Duration::from_millis(1200)).await;
+    let held = sensor.get_state().baseline.unwrap();
+    let read = drain(&frames, Duration::from_millis(1200)).await;
+    let state = sensor.get_state();
+    assert_eq!(state.calibration_frozen, freeze);
+    sensor.stop().await.unwrap();
+    (held, state.baseline.unwrap(), read, phase)
+}
+
+#[tokio::test]
+async fn test_frozen_baseline_stops_moving_while_frames_flow() {
+    let (held, later, read, phase) = baselines_after_calibration(true).await;
+    assert!(matches!(phase, CalibrationPhase::Frozen { .. }), "{:?}", phase);
+    assert!(read > 30, "only {} frames after freezing", read);
+    assert_eq!(later.sample_count, held.sample_count);
+    assert_eq!(later.mean, held.mean);
+
+    // Left unfrozen, the same subject keeps moving it
+    let (held, later, _, phase) = baselines_after_calibration(false).await;
+    assert!(matches!(phase, CalibrationPhase::Calibrated { .. }), "{:?}", phase);
+    assert!(later.sample_count > held.sample_count);
+    assert_ne!(later.mean, held.mean);
+}
@@ -0,0 +1,83 @@
+//! `GrpcFearSensor` against a daemon serving a synthetic sensor over the
+//! in-memory transport: scores flow end to end through the legacy trait,
+//! calibration is reported, and the stream comes back after the daemon's
+//! sensor stops
+
+#![cfg(feature = "stream")]
+
+use spectre_sensor::{
+    compat::{FearSensor, GrpcFearSensor},
+    grpc_server::{serve_grpc_with_shutdown, SensorServiceImpl},
+    mock_patterns::MockPattern,
+    EmotionSensor, SensorConfig, SensorTransport,
+};
+use spectremesh_core::{FearConfig, FearScore};
+use std::time::Duration;
+
+const TIMEOUT: Duration = Duration::from_secs(5);
+
+async fn next_score(scores: &async_channel::Receiver<FearScore>) -> FearScore {
+    tokio::time::timeout(TIMEOUT, scores.recv()).await.expect("no score in time").unwrap()
+}
+
+#[tokio::test]
+async fn test_scores_flow_from_daemon_and_resume_after_sensor_stops() {
+    let config = SensorConfig::default()
+        .with_mock(MockPattern::Sine { center: 0.5, amplitude: 0.3, period: 2.0 })
+        .with_calibration_period(Duration::from_millis(300))
+        .with_target_fps(60.0);
+    let mut sensor = EmotionSensor::new(config);
+    sensor.initialize().await.unwrap();
+    let service = SensorServiceImpl::new(sensor);
+    let daemon_sensor = service.sensor();
+    let transport = SensorTransport::loopback();
+    tokio::spawn(serve_grpc_with_shutdown(transport.clone(), service, std::future::pending()));
+
+    let mut remote = GrpcFearSensor::new(transport)
+        .with_reconnect_backoff(Duration::from_millis(20), Duration::from_millis(100));
+    assert!(matches!(remote.start().await, Err(spectremesh_core::FearError::NotInitialized)));
+    remote.initialize(&FearConfig::default()).await.unwrap();
+    assert!(!remote.is_calibrated());
+
+    // Uncalibrated scores come first, then calibrated ones
+    let scores = remote.start().await.unwrap();
+    let score = loop {
+        let score = next_score(&scores).await;
+        assert!((0.0..=1.0).contains(&score.provisional_value()));
+        if score.calibrated {
+            break score;
+        }
+    };
+    assert_eq!(score.emotion_logits.len(), 7);
+    assert!(remote.is_calibrated());
+    assert_eq!(remote.calibration_progress(), 1.0);
+    assert!(remote.is_running());
+
+    // Stopping the daemon's sensor ends the stream; reconnecting starts it again
+    daemon_sensor.lock().await.stop().await.unwrap();
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    while scores.try_recv().is_ok() {}
+    next_score(&scores).await;
+    assert!(daemon_sensor.lock().await.get_state().running);
+
+    // Stopping cancels the streaming task, closing the channel
+    remote.stop().await.unwrap();
+    assert!(!remote.is_running());
+    tokio::time::timeout(Duration::from_millis(200), async { while scores.recv().await.is_ok() {} })
+        .await
+        .expect("score channel still open after stop");
+}
+
+#[tokio::test]
+async fn test_initialize_fails_without_daemon() {
+    // Nothing listens on a fresh loopback transport, so connecting hangs;
+    // a refused TCP connection fails straight away instead
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let address = listener.local_addr().unwrap();
+    drop(listener);
+
+    let mut remote = GrpcFearSensor::connect_to(&address.to_string()).unwrap();
+    let result = tokio::time::timeout(TIMEOUT, remote.initialize(&FearConfig::default())).await.unwrap();
+    assert!(result.is_err());
+    assert!(GrpcFearSensor::connect_to("not-an-address").is_err());
+}
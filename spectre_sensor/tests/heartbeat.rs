@@ -0,0 +1,87 @@
+//! File heartbeat against a synthetic pipeline: the watchdog file's mtime
+//! advances while frames flow, stops within the staleness threshold when the
+//! pipeline stalls and advances again once it recovers
+
+use spectre_sensor::heartbeat::{Heartbeat, HeartbeatConfig, HeartbeatTarget, Liveness};
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime};
+
+const INTERVAL: Duration = Duration::from_millis(20);
+const STALE_AFTER: Duration = Duration::from_millis(100);
+
+fn mtime(path: &Path) -> Option<SystemTime> {
+    std::fs::metadata(path).and_then(|metadata| metadata.modified()).ok()
+}
+
+/// Whether the file's mtime moves on within `within`
+async fn advances(path: &Path, within: Duration) -> bool {
+    let before = mtime(path);
+    let deadline = Instant::now() + within;
+    while Instant::now() < deadline {
+        tokio::time::sleep(INTERVAL / 2).await;
+        if mtime(path).is_some() && mtime(path) != before {
+            return true;
+        }
+    }
+    false
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_file_heartbeat_follows_pipeline() {
+    let dir = std::env::temp_dir().join(format!("spectre_heartbeat_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("alive");
+    let _ = std::fs::remove_file(&path);
+
+    let config = HeartbeatConfig {
+        target: Some(HeartbeatTarget::File { path: path.clone() }),
+        interval: INTERVAL,
+        stale_after: STALE_AFTER,
+    };
+    let liveness = Arc::new(Liveness::new(config.stale_after));
+    liveness.start();
+    let heartbeat = Heartbeat::open(&config).unwrap().unwrap();
+    assert_eq!(heartbeat.target().to_string(), format!("file:{}", path.display()));
+    let beating = tokio::spawn(heartbeat.run(Arc::clone(&liveness)));
+
+    // Synthetic pipeline: a frame every 10 ms unless stalled
+    let stalled = Arc::new(AtomicBool::new(false));
+    let pipeline = tokio::spawn({
+        let liveness = Arc::clone(&liveness);
+        let stalled = Arc::clone(&stalled);
+        async move {
+            liveness.record_fps(30.0);
+            liveness.record_fear(0.5);
+            loop {
+                if !stalled.load(Ordering::SeqCst) {
+                    liveness.record_frame(Instant::now());
+                }
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        }
+    });
+
+    // Beats flow while frames do
+    for _ in 0..3 {
+        assert!(advances(&path, STALE_AFTER).await, "heartbeat not advancing while running");
+    }
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), "OK 30.0 0.500\n");
+
+    // A stalled pipeline stops the beats within the staleness threshold
+    stalled.store(true, Ordering::SeqCst);
+    tokio::time::sleep(STALE_AFTER + 2 * INTERVAL).await;
+    assert!(!advances(&path, 5 * INTERVAL).await, "heartbeat kept beating for a stalled pipeline");
+    assert!(!beating.is_finished());
+
+    // Recovery brings them back
+    stalled.store(false, Ordering::SeqCst);
+    assert!(advances(&path, STALE_AFTER).await, "heartbeat did not resume");
+
+    // Stopping the pipeline ends the heartbeat
+    liveness.stop();
+    tokio::time::timeout(STALE_AFTER, beating).await.unwrap().unwrap();
+    pipeline.abort();
+    let _ = std::fs::remove_dir_all(&dir);
+}
@@ -32,7 +32,7 @@ async fn test_mock_sensor_compatibility() {
     
     // Validate score structure
     for score in &scores {
-        assert!(score.value >= 0.0 && score.value <= 1.0, "Fear value should be normalized");
+        assert!((0.0..=1.0).contains(&score.provisional_value()), "Fear value should be normalized");
         assert!(score.confidence >= 0.0 && score.confidence <= 1.0, "Confidence should be normalized");
         assert_eq!(score.emotion_logits.len(), 7, "Should have 7 emotion classes");
         assert_eq!(score.extract_fear_logit(), score.emotion_logits[2], "Fear should be at index 2");
@@ -108,10 +108,11 @@ async fn test_fear_score_compatibility() {
         .expect("Failed to receive score");
     
     // Test that FearScore has all expected methods and fields
-    let _value: f32 = score.value;
+    let _value: Option<f32> = score.value();
+    let _provisional_value: f32 = score.provisional_value();
     let _confidence: f32 = score.confidence;
     let _calibrated: bool = score.calibrated;
-    let _emotion_logits: [f32; 7] = score.emotion_logits;
+    let _emotion_logits: [f32; 7] = score.emotion_logits.to_standard().unwrap();
     let _timestamp = score.timestamp;
     
     // Test methods
@@ -0,0 +1,169 @@
+//! Power governor against scripted power and thermal readings: profile
+//! transitions and their events, no flapping at a threshold, and operator
+//! overrides winning over every profile
+
+use spectre_sensor::heartbeat::Liveness;
+use spectre_sensor::power::{
+    PowerConfig, PowerControl, PowerError, PowerMonitor, PowerProfile, PowerReading, ProfileRates, RateOverride,
+};
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::broadcast::error::TryRecvError;
+
+const PERFORMANCE: ProfileRates = ProfileRates { target_fps: 30.0, emotion_interval: 1 };
+
+/// Replays readings in order, then fails as if the sensors went away
+#[derive(Clone, Default)]
+struct ScriptedMonitor {
+    readings: Arc<Mutex<VecDeque<PowerReading>>>,
+}
+
+impl ScriptedMonitor {
+    fn push(&self, reading: PowerReading) {
+        self.readings.lock().unwrap().push_back(reading);
+    }
+}
+
+impl PowerMonitor for ScriptedMonitor {
+    fn read(&mut self) -> Result<PowerReading, PowerError> {
+        self.readings
+            .lock()
+            .unwrap()
+            .pop_front()
+            .ok_or_else(|| PowerError::Read("script ended".to_string()))
+    }
+}
+
+fn battery(charge: f32) -> PowerReading {
+    PowerReading { on_battery: true, battery_charge: Some(charge), temperature_c: Some(45.0) }
+}
+
+fn mains(temperature: f32) -> PowerReading {
+    PowerReading { on_battery: false, battery_charge: Some(1.0), temperature_c: Some(temperature) }
+}
+
+fn control() -> PowerControl {
+    PowerControl::new(PowerConfig { enabled: true, ..PowerConfig::default() }, PERFORMANCE)
+}
+
+/// Profile after each scripted reading
+fn profiles(control: &PowerControl, readings: &[PowerReading]) -> Vec<PowerProfile> {
+    let mut monitor = ScriptedMonitor::default();
+    readings
+        .iter()
+        .map(|&reading| {
+            monitor.push(reading);
+            control.poll(&mut monitor).unwrap();
+            control.profile()
+        })
+        .collect()
+}
+
+#[test]
+fn test_transitions_are_announced_with_their_rates() {
+    let control = control();
+    let mut changes = control.subscribe();
+    let mut rates = control.subscribe_rates();
+    let config = PowerConfig::default();
+
+    use PowerProfile::*;
+    let seen = profiles(&control, &[mains(50.0), battery(0.6), battery(0.15), mains(50.0), mains(91.0), mains(60.0)]);
+    assert_eq!(seen, [Performance, Balanced, Saver, Performance, Saver, Performance]);
+
+    let announced: Vec<(PowerProfile, PowerProfile, ProfileRates)> = std::iter::from_fn(|| changes.try_recv().ok())
+        .map(|change| (change.from, change.to, change.rates))
+        .collect();
+    assert_eq!(
+        announced,
+        [
+            (Performance, Balanced, config.balanced),
+            (Balanced, Saver, config.saver),
+            (Saver, Performance, PERFORMANCE),
+            (Performance, Saver, config.saver),
+            (Saver, Performance, PERFORMANCE),
+        ]
+    );
+    assert!(rates.has_changed().unwrap());
+    assert_eq!(*rates.borrow_and_update(), PERFORMANCE);
+}
+
+#[test]
+fn test_no_flapping_at_the_boundary() {
+    use PowerProfile::*;
+
+    // Charge wobbling around the 20% threshold drops to saver once and
+    // stays until it clears 25%
+    let control = control();
+    let mut changes = control.subscribe();
+    let seen = profiles(&control, &[battery(0.21), battery(0.19), battery(0.21), battery(0.2), battery(0.24), battery(0.26)]);
+    assert_eq!(seen, [Balanced, Saver, Saver, Saver, Saver, Balanced]);
+    assert_eq!(std::iter::from_fn(|| changes.try_recv().ok()).count(), 3);
+
+    // Same for a temperature hovering at the 80°C warm threshold
+    let control = self::control();
+    let mut changes = control.subscribe();
+    let seen = profiles(&control, &[mains(79.0), mains(80.0), mains(79.5), mains(80.2), mains(76.0), mains(74.0)]);
+    assert_eq!(seen, [Performance, Balanced, Balanced, Balanced, Balanced, Performance]);
+    assert_eq!(std::iter::from_fn(|| changes.try_recv().ok()).count(), 2);
+}
+
+#[test]
+fn test_override_wins_over_every_profile() {
+    let control = control();
+    let mut changes = control.subscribe();
+    let pinned = ProfileRates { target_fps: 24.0, emotion_interval: 1 };
+    assert_eq!(
+        control.set_override(RateOverride { target_fps: Some(24.0), emotion_interval: Some(1) }),
+        pinned
+    );
+
+    // The profile keeps following the readings, the rates do not
+    let mut monitor = ScriptedMonitor::default();
+    for reading in [battery(0.6), battery(0.1), mains(95.0)] {
+        monitor.push(reading);
+        control.poll(&mut monitor).unwrap();
+        assert_eq!(control.rates(), pinned);
+    }
+    let change = changes.try_recv().unwrap();
+    assert!(change.overridden);
+    assert_eq!((change.to, change.rates), (PowerProfile::Balanced, pinned));
+    assert_eq!(control.profile(), PowerProfile::Saver);
+
+    // Overriding one rate leaves the other to the profile
+    let rates = control.set_override(RateOverride { target_fps: Some(24.0), emotion_interval: None });
+    assert_eq!(rates, ProfileRates { target_fps: 24.0, emotion_interval: PowerConfig::default().saver.emotion_interval });
+
+    // Clearing hands the rates back to the current profile
+    assert_eq!(control.set_override(RateOverride::default()), PowerConfig::default().saver);
+    assert!(!control.overrides().is_set());
+}
+
+#[tokio::test]
+async fn test_polling_follows_readings_until_the_pipeline_stops() {
+    let control = Arc::new(PowerControl::new(
+        PowerConfig { enabled: true, poll_interval: Duration::from_millis(10), ..PowerConfig::default() },
+        PERFORMANCE,
+    ));
+    let mut changes = control.subscribe();
+    let monitor = ScriptedMonitor::default();
+    monitor.push(battery(0.6));
+    let liveness = Arc::new(Liveness::default());
+    liveness.start();
+    let task = tokio::spawn(Arc::clone(&control).run(Box::new(monitor.clone()), Arc::clone(&liveness)));
+
+    let change = tokio::time::timeout(Duration::from_secs(5), changes.recv()).await.unwrap().unwrap();
+    assert_eq!(change.to, PowerProfile::Balanced);
+    assert_eq!(change.reason, "on battery at 60%, 45°C");
+
+    // Failed readings keep the profile
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    assert_eq!(control.profile(), PowerProfile::Balanced);
+    monitor.push(mains(50.0));
+    let change = tokio::time::timeout(Duration::from_secs(5), changes.recv()).await.unwrap().unwrap();
+    assert_eq!(change.to, PowerProfile::Performance);
+
+    liveness.stop();
+    tokio::time::timeout(Duration::from_secs(5), task).await.unwrap().unwrap();
+    assert!(matches!(changes.try_recv(), Err(TryRecvError::Empty)));
+}
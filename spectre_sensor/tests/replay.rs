@@ -0,0 +1,137 @@
+//! A recorded session plays back through `FearSensor` with its recorded
+//! values and timing, the same way every time, at any speed and looped;
+//! a sensor with a record path writes every frame it produces
+
+use spectre_sensor::{
+    compat::FearSensor, mock_patterns::MockPattern, recording::Recording, EmotionSensor, ReplayFearSensor,
+    SensorConfig,
+};
+use spectremesh_core::FearConfig;
+use std::time::Duration;
+use tokio::time::Instant;
+
+/// A 15 FPS session with one dropped frame, a frame without a face and
+/// calibration completing on the fifth frame
+const FIXTURE: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/session.jsonl");
+
+/// Fear of each fixture frame; uncalibrated frames have none to report
+const FIXTURE_FEAR: [Option<f32>; 12] = [
+    None,
+    None,
+    None,
+    None,
+    Some(0.52),
+    Some(0.61),
+    Some(0.74),
+    Some(0.90),
+    Some(0.93),
+    Some(0.93),
+    Some(0.81),
+    Some(0.66),
+];
+
+const FIXTURE_OFFSETS_MS: [u64; 12] = [0, 66, 133, 200, 266, 333, 466, 533, 604, 666, 733, 800];
+
+/// A replayed score: fear, calibrated, frame number and arrival after start
+type Played = (Option<f32>, bool, u64, Duration);
+
+/// Scores until the stream ends or `limit` arrive
+async fn play(sensor: &mut ReplayFearSensor, limit: usize) -> Vec<Played> {
+    sensor.initialize(&FearConfig::default()).await.unwrap();
+    let scores = sensor.start().await.unwrap();
+    let start = Instant::now();
+    let mut played = Vec::new();
+    while played.len() < limit {
+        let Ok(score) = scores.recv().await else {
+            break;
+        };
+        played.push((score.value(), score.calibrated, score.sequence, start.elapsed()));
+    }
+    sensor.stop().await.unwrap();
+    played
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_fixture_replays_identically() {
+    let mut sensor = ReplayFearSensor::open(FIXTURE).unwrap();
+    assert_eq!(sensor.recording().header.config.target_fps, 15.0);
+    assert!(!sensor.is_calibrated());
+
+    let first = play(&mut sensor, usize::MAX).await;
+    let fear: Vec<Option<f32>> = first.iter().map(|played| played.0).collect();
+    assert_eq!(fear, FIXTURE_FEAR);
+    let arrivals: Vec<Duration> = first.iter().map(|played| played.3).collect();
+    assert_eq!(arrivals, FIXTURE_OFFSETS_MS.map(Duration::from_millis));
+    // Calibration completes where it did while recording, and the dropped
+    // frame still shows in the numbering
+    let calibrated: Vec<bool> = first.iter().map(|played| played.1).collect();
+    assert_eq!(calibrated, [[false; 4].as_slice(), &[true; 8]].concat());
+    assert_eq!(first[6].2 - first[5].2, 2);
+    assert!(sensor.is_calibrated());
+
+    // The same curve every time
+    assert_eq!(play(&mut sensor, usize::MAX).await, first);
+
+    let cameras = sensor.enumerate_cameras().await.unwrap();
+    assert_eq!(cameras[0].resolution, (640, 480));
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_speed_and_looping() {
+    let mut sensor = ReplayFearSensor::open(FIXTURE).unwrap().with_speed(2.0).with_looping(true);
+    let played = play(&mut sensor, 24).await;
+
+    // Twice as fast, and again after one typical frame interval
+    let interval = sensor.recording().frame_interval();
+    assert_eq!(interval, Duration::from_millis(67));
+    let second_pass = Duration::from_millis(800) + interval;
+    let expected: Vec<Duration> = FIXTURE_OFFSETS_MS
+        .iter()
+        .map(|&ms| Duration::from_millis(ms))
+        .chain(FIXTURE_OFFSETS_MS.iter().map(|&ms| second_pass + Duration::from_millis(ms)))
+        .map(|offset| offset / 2)
+        .collect();
+    assert_eq!(played.len(), expected.len());
+    for (played, expected) in played.iter().zip(expected) {
+        // The paused clock moves in whole milliseconds
+        assert!((played.3.as_secs_f64() - expected.as_secs_f64()).abs() <= 0.001, "{:?} != {:?}", played.3, expected);
+    }
+    assert_eq!(played[12].0, FIXTURE_FEAR[0]);
+    // Frame numbers carry on into the second pass
+    assert_eq!(played[11].2, 13);
+    assert_eq!(played[12].2, 14);
+
+    let mut stalled = ReplayFearSensor::open(FIXTURE).unwrap().with_speed(0.0);
+    assert!(stalled.initialize(&FearConfig::default()).await.is_err());
+}
+
+#[tokio::test]
+async fn test_sensor_records_every_frame() {
+    let path = std::env::temp_dir().join(format!("spectre_replay_{}.jsonl", std::process::id()));
+    let config = SensorConfig::default()
+        .with_mock(MockPattern::Sine { center: 0.5, amplitude: 0.3, period: 2.0 })
+        .with_target_fps(60.0)
+        .with_record_path(&path);
+    let mut sensor = EmotionSensor::new(config);
+    sensor.initialize().await.unwrap();
+
+    let frames = sensor.start().await.unwrap();
+    let mut received = Vec::new();
+    while received.len() < 20 {
+        received.push(tokio::time::timeout(Duration::from_secs(1), frames.recv()).await.unwrap().unwrap());
+    }
+    sensor.stop().await.unwrap();
+
+    let recording = Recording::load(&path).unwrap();
+    assert_eq!(recording.header.config.target_fps, 60.0);
+    assert_eq!(recording.header.camera.map(|camera| (camera.width, camera.height)), Some((320, 240)));
+    // Frames dropped on the way to a slow receiver are recorded all the same
+    let recorded = recording.to_frames().unwrap();
+    for frame in &received {
+        let replayed = recorded.iter().find(|recorded| recorded.sequence == frame.sequence).unwrap();
+        assert_eq!(replayed.fear_score, frame.fear_score);
+        assert_eq!(replayed.emotion_logits, frame.emotion_logits);
+        assert_eq!(replayed.calibrated, frame.calibrated);
+    }
+    let _ = std::fs::remove_file(&path);
+}
@@ -0,0 +1,126 @@
+//! Configure changes a streaming sensor without restarting it: a lower
+//! frame rate spaces the scores out from the next frames on, and the
+//! settings in effect show up in GetStatus
+
+#![cfg(feature = "stream")]
+
+use futures::StreamExt;
+use spectre_sensor::{
+    grpc_client::SensorClient,
+    grpc_server::{serve_grpc_with_shutdown, SensorServiceImpl},
+    mock_patterns::MockPattern,
+    proto::{sensor_event, ConfigureRequest, SensorEvent},
+    EmotionSensor, SensorConfig, SensorTransport,
+};
+use std::time::Duration;
+use tonic::{Code, Status};
+
+const TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Median time between the capture timestamps of the next `count` scores
+async fn median_interval<S>(scores: &mut S, count: usize) -> Duration
+where
+    S: StreamExt<Item = Result<SensorEvent, Status>> + Unpin,
+{
+    let mut timestamps = Vec::new();
+    while timestamps.len() < count {
+        let event = tokio::time::timeout(TIMEOUT, scores.next()).await.expect("no score in time").unwrap().unwrap();
+        if let Some(sensor_event::Event::Score(_)) = event.event {
+            timestamps.push(event.timestamp_us);
+        }
+    }
+    let mut intervals: Vec<u64> = timestamps.windows(2).map(|pair| pair[1] - pair[0]).collect();
+    intervals.sort_unstable();
+    Duration::from_micros(intervals[intervals.len() / 2])
+}
+
+/// Whether a score with `face_present` set to `present` arrives within 30 scores
+async fn reaches_face_present<S>(scores: &mut S, present: bool) -> bool
+where
+    S: StreamExt<Item = Result<SensorEvent, Status>> + Unpin,
+{
+    for _ in 0..30 {
+        let event = tokio::time::timeout(TIMEOUT, scores.next()).await.expect("no score in time").unwrap().unwrap();
+        if let Some(sensor_event::Event::Score(score)) = event.event {
+            if score.face_present == Some(present) {
+                return true;
+            }
+        }
+    }
+    false
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_target_fps_changes_mid_stream() {
+    let config = SensorConfig::default()
+        .with_mock(MockPattern::Sine { center: 0.5, amplitude: 0.3, period: 2.0 })
+        .with_target_fps(30.0);
+    let mut sensor = EmotionSensor::new(config);
+    sensor.initialize().await.unwrap();
+    let transport = SensorTransport::loopback();
+    tokio::spawn(serve_grpc_with_shutdown(transport.clone(), SensorServiceImpl::new(sensor), std::future::pending()));
+    let mut client = SensorClient::connect(&transport).await.unwrap();
+
+    let mut scores = client.stream_scores().await.unwrap();
+    let before = median_interval(&mut scores, 15).await;
+    assert!(before < Duration::from_millis(50), "{:?} between scores at 30 FPS", before);
+
+    let response = client
+        .configure(ConfigureRequest { target_fps: Some(10.0), onnx_threads: Some(64), ..Default::default() })
+        .await
+        .unwrap();
+    let applied = response.config.unwrap();
+    assert_eq!(applied.target_fps, 10.0);
+    // The thread count waits for a restart; the sessions keep theirs
+    assert_eq!(response.restart_required, ["onnx_threads"]);
+    assert_ne!(applied.onnx_threads, 64);
+
+    // A frame or two may already be on its way at the old rate
+    median_interval(&mut scores, 3).await;
+    let after = median_interval(&mut scores, 10).await;
+    assert!(after > Duration::from_millis(80), "{:?} between scores at 10 FPS", after);
+
+    let status = client.get_status().await.unwrap();
+    assert_eq!(status.target_fps, 10.0);
+    assert_eq!(status.config.unwrap(), applied);
+
+    // A rejected request changes nothing
+    let status = client
+        .configure(ConfigureRequest { target_fps: Some(20.0), face_confidence_threshold: Some(1.5), ..Default::default() })
+        .await
+        .unwrap_err();
+    assert_eq!(status.code(), Code::InvalidArgument);
+    assert_eq!(client.get_status().await.unwrap().target_fps, 10.0);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_threshold_applies_from_the_next_frame() {
+    let config = SensorConfig::default()
+        .with_mock(MockPattern::Sine { center: 0.5, amplitude: 0.3, period: 2.0 })
+        .with_target_fps(30.0);
+    let mut sensor = EmotionSensor::new(config);
+    sensor.initialize().await.unwrap();
+    let transport = SensorTransport::loopback();
+    tokio::spawn(serve_grpc_with_shutdown(transport.clone(), SensorServiceImpl::new(sensor), std::future::pending()));
+    let mut client = SensorClient::connect(&transport).await.unwrap();
+    let mut scores = client.stream_scores().await.unwrap();
+
+    assert!(reaches_face_present(&mut scores, true).await);
+
+    // Synthetic faces score 0.95, so a stricter threshold hides them once
+    // the tracker lets go of the last one
+    let response = client
+        .configure(ConfigureRequest { face_confidence_threshold: Some(0.99), ..Default::default() })
+        .await
+        .unwrap();
+    assert_eq!(response.config.unwrap().face_confidence_threshold, 0.99);
+    assert!(reaches_face_present(&mut scores, false).await);
+
+    // Nothing to freeze before calibration completes
+    let status = client
+        .configure(ConfigureRequest { freeze_calibration: Some(true), camera_id: Some(3), ..Default::default() })
+        .await
+        .unwrap_err();
+    assert_eq!(status.code(), Code::FailedPrecondition);
+    assert_eq!(client.get_status().await.unwrap().config.unwrap().camera_id, 0);
+}
@@ -0,0 +1,28 @@
+//! `sensor_fuzzer soak-test --verify` against its own mock daemon on a real
+//! socket: a short run with faults and recalibrations keeps every invariant
+//! and exits successfully
+
+#![cfg(all(feature = "mock", feature = "stream", unix))]
+
+use std::time::Duration;
+use tokio::process::Command;
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_short_verified_soak_passes() {
+    let socket = std::env::temp_dir().join(format!("sensor_fuzzer_{}.sock", std::process::id()));
+    let output = Command::new(env!("CARGO_BIN_EXE_sensor_fuzzer"))
+        .args(["soak-test", "--verify", "--duration", "6", "--socket", socket.to_str().unwrap()])
+        .args(["--calibration-secs", "1", "--recalibrate-every", "2", "--fault-every", "1", "--warmup", "2"])
+        .output();
+    let output = tokio::time::timeout(Duration::from_secs(60), output).await.expect("sensor_fuzzer hung").unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        output.status.success(),
+        "soak test failed:\n{}\n{}",
+        stdout,
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(stdout.contains("PASSED"), "{}", stdout);
+    assert!(!stdout.contains("Recalibrations: 0"), "{}", stdout);
+    assert!(!socket.exists(), "socket left behind");
+}
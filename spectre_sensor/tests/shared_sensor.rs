@@ -0,0 +1,105 @@
+//! Event streams share one running sensor: each has its own filters,
+//! a client that goes away leaves the others streaming, and GetStatus
+//! counts the streams open. With an idle shutdown the sensor stops once the
+//! last stream is gone and starts again with the next one.
+
+#![cfg(feature = "stream")]
+
+use futures::StreamExt;
+use spectre_sensor::{
+    grpc_client::SensorClient,
+    grpc_server::{serve_grpc_with_shutdown, SensorServiceImpl},
+    mock_patterns::MockPattern,
+    proto::{sensor_event, SensorEvent},
+    EmotionSensor, SensorConfig, SensorTransport,
+};
+use std::time::Duration;
+use tonic::Status;
+
+const TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Serve a mock sensor configured by `config` over a loopback transport
+async fn serve(config: SensorConfig) -> SensorTransport {
+    let mut sensor = EmotionSensor::new(config.with_mock(MockPattern::default()));
+    sensor.initialize().await.unwrap();
+    let transport = SensorTransport::loopback();
+    tokio::spawn(serve_grpc_with_shutdown(transport.clone(), SensorServiceImpl::new(sensor), std::future::pending()));
+    transport
+}
+
+/// Next `count` events, failing on a stream that ends or stalls
+async fn next_events<S>(stream: &mut S, count: usize) -> Vec<sensor_event::Event>
+where
+    S: StreamExt<Item = Result<SensorEvent, Status>> + Unpin,
+{
+    let mut events = Vec::with_capacity(count);
+    while events.len() < count {
+        let event = tokio::time::timeout(TIMEOUT, stream.next()).await.expect("no event in time");
+        events.extend(event.expect("stream ended").unwrap().event);
+    }
+    events
+}
+
+/// Poll GetStatus until `done` holds for it
+async fn wait_for_status(client: &mut SensorClient, done: impl Fn(u32, bool) -> bool) {
+    tokio::time::timeout(TIMEOUT, async {
+        loop {
+            let status = client.get_status().await.unwrap();
+            if done(status.subscribers, status.running) {
+                return;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+    })
+    .await
+    .expect("status never reached");
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_subscribers_with_different_filters_share_the_sensor() {
+    let transport = serve(SensorConfig::default()).await;
+    let mut admin = SensorClient::connect(&transport).await.unwrap();
+    let mut game = SensorClient::connect(&transport).await.unwrap();
+    let mut monitor = SensorClient::connect(&transport).await.unwrap();
+
+    let mut scores = game.stream_scores().await.unwrap().boxed();
+    let mut everything = monitor.stream_events().await.unwrap().boxed();
+    let status = admin.get_status().await.unwrap();
+    assert!(status.running);
+    assert_eq!(status.subscribers, 2);
+
+    // Both read the same run of the sensor, each through its own filter
+    let events = next_events(&mut scores, 10).await;
+    assert!(events.iter().all(|event| matches!(event, sensor_event::Event::Score(_))));
+    let events = next_events(&mut everything, 10).await;
+    assert!(events.iter().any(|event| matches!(event, sensor_event::Event::Score(_))));
+
+    // The game goes away; the sensor keeps running for the monitor
+    drop(scores);
+    wait_for_status(&mut admin, |subscribers, _| subscribers == 1).await;
+    let events = next_events(&mut everything, 20).await;
+    assert!(events.iter().any(|event| matches!(event, sensor_event::Event::Score(_))));
+    assert!(!events.iter().any(|event| matches!(event, sensor_event::Event::SensorFault(_))));
+
+    // Nobody streaming does not stop the sensor without an idle shutdown
+    drop(everything);
+    wait_for_status(&mut admin, |subscribers, _| subscribers == 0).await;
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    assert!(admin.get_status().await.unwrap().running);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_idle_shutdown_stops_and_next_stream_restarts() {
+    let transport = serve(SensorConfig::default().with_idle_shutdown(Duration::from_millis(100))).await;
+    let mut client = SensorClient::connect(&transport).await.unwrap();
+
+    let mut scores = client.stream_scores().await.unwrap().boxed();
+    next_events(&mut scores, 3).await;
+    drop(scores);
+    wait_for_status(&mut client, |subscribers, running| subscribers == 0 && !running).await;
+
+    let mut scores = client.stream_scores().await.unwrap().boxed();
+    let events = next_events(&mut scores, 3).await;
+    assert!(events.iter().all(|event| matches!(event, sensor_event::Event::Score(_))));
+    assert!(client.get_status().await.unwrap().running);
+}
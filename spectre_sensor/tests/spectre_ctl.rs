@@ -0,0 +1,88 @@
+//! `spectre_ctl` against a mock daemon on a real socket: scores stream out
+//! as JSON lines, a calibration reset shows in the status, and failures
+//! exit non-zero
+
+#![cfg(all(feature = "mock", feature = "stream", unix))]
+
+use serde_json::Value;
+use spectre_sensor::{
+    mock::{spawn_mock_daemon_on, MockSensorConfig},
+    SensorTransport,
+};
+use std::process::Output;
+use std::time::Duration;
+use tokio::process::Command;
+
+/// `spectre_ctl` with `args`, reaching the daemon through `connection`
+async fn spectre_ctl(connection: &[&str], args: &[&str]) -> Output {
+    let output = Command::new(env!("CARGO_BIN_EXE_spectre_ctl"))
+        .args(connection)
+        .args(args)
+        .output();
+    tokio::time::timeout(Duration::from_secs(30), output).await.expect("spectre_ctl hung").unwrap()
+}
+
+/// Standard output of a successful run
+fn stdout(output: Output) -> String {
+    assert!(output.status.success(), "spectre_ctl failed: {}", String::from_utf8_lossy(&output.stderr));
+    String::from_utf8(output.stdout).unwrap()
+}
+
+fn calibration_state(status: &str) -> String {
+    let status: Value = serde_json::from_str(status).unwrap();
+    status["calibration"]["state"].as_str().unwrap().to_string()
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_watch_and_reset_calibration() {
+    let socket = std::env::temp_dir().join(format!("spectre_ctl_{}.sock", std::process::id()));
+    let config = MockSensorConfig::default().with_target_fps(60.0).with_calibration_period(Duration::from_secs(2));
+    spawn_mock_daemon_on(&SensorTransport::Unix(socket.clone()), config).await.unwrap();
+    let connection = ["--socket", socket.to_str().unwrap()];
+
+    // One JSON object per line, as `jq` wants them
+    let scores = stdout(spectre_ctl(&connection, &["watch", "--json", "--count", "5"]).await);
+    let fear: Vec<f64> = scores
+        .lines()
+        .map(|line| serde_json::from_str::<Value>(line).unwrap()["normalized_fear"].as_f64().unwrap())
+        .collect();
+    assert_eq!(fear.len(), 5);
+    assert!(fear.iter().all(|fear| (0.0..=1.0).contains(fear)), "{:?}", fear);
+
+    let waited = stdout(spectre_ctl(&connection, &["wait-calibrated", "--timeout", "10"]).await);
+    assert_eq!(waited.trim(), "Calibrated");
+    let status = stdout(spectre_ctl(&connection, &["status", "--json"]).await);
+    assert_eq!(calibration_state(&status), "CALIBRATED");
+    let status: Value = serde_json::from_str(&status).unwrap();
+    assert!(status["calibration"]["baseline"]["sample_count"].as_u64().unwrap() > 0);
+    assert_eq!(status["running"], true);
+
+    let reset = stdout(spectre_ctl(&connection, &["--format", "json", "calibrate", "reset"]).await);
+    assert_eq!(serde_json::from_str::<Value>(&reset).unwrap()["state"], "COLLECTING");
+    let status = stdout(spectre_ctl(&connection, &["--format", "json", "status"]).await);
+    assert_eq!(calibration_state(&status), "COLLECTING");
+    let text = stdout(spectre_ctl(&connection, &["status"]).await);
+    assert!(text.contains("Calibration:  COLLECTING"), "{}", text);
+
+    // Nothing to freeze while collecting, and no calibration in 100 ms
+    assert!(!spectre_ctl(&connection, &["calibrate", "freeze"]).await.status.success());
+    let timed_out = spectre_ctl(&connection, &["wait-calibrated", "--timeout", "0.1"]).await;
+    assert!(!timed_out.status.success());
+    assert!(String::from_utf8_lossy(&timed_out.stderr).contains("Not calibrated"));
+
+    let _ = std::fs::remove_file(&socket);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_tcp_connection() {
+    let address = std::net::TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap();
+    spawn_mock_daemon_on(&SensorTransport::Tcp(address), MockSensorConfig::default()).await.unwrap();
+    let address = address.to_string();
+
+    let status = stdout(spectre_ctl(&["--tcp", &address], &["status", "--json"]).await);
+    assert_eq!(calibration_state(&status), "IDLE");
+
+    // Nothing listening there any more
+    let closed = std::net::TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap().to_string();
+    assert!(!spectre_ctl(&["--tcp", &closed], &["status"]).await.status.success());
+}
@@ -0,0 +1,52 @@
+//! GetStatus reports the calibrator's baseline once calibration completes,
+//! and holds it while calibration is frozen
+
+#![cfg(all(feature = "mock", feature = "stream"))]
+
+use spectre_sensor::{
+    grpc_client::SensorClient,
+    mock::{spawn_mock_daemon, MockSensorConfig},
+    mock_patterns,
+    phases::PhaseOutcome,
+    proto::CalibrationState,
+};
+use std::time::Duration;
+
+#[tokio::test]
+async fn test_status_reports_baseline_after_calibration() {
+    let config = MockSensorConfig::default()
+        .with_pattern(mock_patterns::constant(0.5, 1))
+        .with_calibration_period(Duration::from_millis(500));
+    let transport = spawn_mock_daemon(config).await.unwrap();
+    let mut client = SensorClient::connect(&transport).await.unwrap();
+
+    // Following the phases starts the sensor; no baseline before it calibrates
+    let mut phases = client.phases().await.unwrap();
+    let status = client.get_status().await.unwrap();
+    assert!(status.calibration.unwrap().baseline.is_none());
+
+    match phases.wait_for_calibrated(Duration::from_secs(5)).await.unwrap() {
+        PhaseOutcome::Reached(phase) => assert!(phase.is_calibrated(), "{:?}", phase),
+        outcome => panic!("not calibrated: {:?}", outcome),
+    }
+
+    let calibration = client.get_status().await.unwrap().calibration.unwrap();
+    assert!(calibration.completed);
+    let baseline = calibration.baseline.expect("baseline after calibration");
+    assert!(baseline.sample_count > 0);
+    // Logits of 0.5 with up to ±0.05 of noise; the calibrator's own spread, not a placeholder
+    assert!((baseline.mean - 0.5).abs() < 0.02, "mean {}", baseline.mean);
+    assert!(baseline.std_dev > 0.0 && baseline.std_dev < 1.0, "std_dev {}", baseline.std_dev);
+
+    // A frozen baseline is still reported, and stops taking samples; the
+    // baseline is published once a second, so let one publish pass first
+    client.freeze_calibration().await.unwrap();
+    tokio::time::sleep(Duration::from_millis(1200)).await;
+    let frozen = client.get_status().await.unwrap().calibration.unwrap();
+    assert_eq!(frozen.state, CalibrationState::Frozen as i32);
+    let held = frozen.baseline.unwrap();
+    tokio::time::sleep(Duration::from_millis(1200)).await;
+    let later = client.get_status().await.unwrap().calibration.unwrap().baseline.unwrap();
+    assert_eq!(later.sample_count, held.sample_count);
+    assert_eq!(later.mean, held.mean);
+}
@@ -0,0 +1,283 @@
+//! The full pipeline on synthetic capture and models: without a camera or
+//! model files the sensor calibrates, streams calibrated frames, restarts
+//! after a stop, restores a cached baseline, scores fresh frames behind a
+//! slow model, abandons inference over its time budget, scores the
+//! configured fear index and feeds its Prometheus metrics
+
+use spectre_sensor::{
+    calibrator::{CalibrationProfile, FearIndexWeights},
+    inference_timeout::MODEL_INFERENCE_TIMEOUT,
+    mock_patterns::MockPattern,
+    phases::PhaseOutcome,
+    types::FaultLevel,
+    AdaptiveCalibrator, EmotionSensor, SensorConfig,
+};
+use std::time::Duration;
+
+#[tokio::test]
+async fn test_synthetic_sensor_calibrates() {
+    let config = SensorConfig::default()
+        .with_mock(MockPattern::Sine { center: 0.5, amplitude: 0.3, period: 2.0 })
+        .with_calibration_period(Duration::from_millis(500))
+        .with_target_fps(60.0);
+    let mut sensor = EmotionSensor::new(config);
+    sensor.initialize().await.unwrap();
+
+    let init = sensor.get_state().init.unwrap();
+    assert!(init.camera.is_some());
+    // Synthetic frames report their own size
+    let camera = sensor.get_state().camera.unwrap();
+    assert_eq!((camera.width, camera.height), (320, 240));
+    assert_eq!(sensor.model_info()[0].name, "synthetic face detector");
+
+    let mut phases = sensor.phases();
+    let frames = sensor.start().await.unwrap();
+    match phases.wait_for_calibrated(Duration::from_secs(5)).await.unwrap() {
+        PhaseOutcome::Reached(phase) => assert!(phase.is_calibrated(), "{:?}", phase),
+        outcome => panic!("not calibrated: {:?}", outcome),
+    }
+
+    // Frames buffered before completion come first
+    let frame = loop {
+        let frame = tokio::time::timeout(Duration::from_secs(1), frames.recv()).await.unwrap().unwrap();
+        if frame.calibrated {
+            break frame;
+        }
+    };
+    assert!((0.0..=1.0).contains(&frame.fear_score));
+    assert!(frame.face_bbox.is_some());
+    // One face, followed on one track throughout
+    assert_eq!((frame.track_id, frame.faces_seen), (Some(1), 1));
+    assert!(!frame.mirrored);
+
+    sensor.stop().await.unwrap();
+    assert!(!sensor.get_state().running);
+}
+
+#[tokio::test]
+async fn test_synthetic_sensor_restarts_after_stop() {
+    let config = SensorConfig::default().with_mock(MockPattern::Step).with_target_fps(60.0);
+    let mut sensor = EmotionSensor::new(config);
+    sensor.initialize().await.unwrap();
+
+    for _ in 0..2 {
+        let frames = sensor.start().await.unwrap();
+        for _ in 0..3 {
+            tokio::time::timeout(Duration::from_secs(1), frames.recv()).await.unwrap().unwrap();
+        }
+
+        // The loop has ended by the time stop returns; only buffered frames are left
+        sensor.stop().await.unwrap();
+        tokio::time::timeout(Duration::from_millis(200), async {
+            while frames.recv().await.is_ok() {}
+        })
+        .await
+        .expect("frame channel still open after stop");
+        assert!(!sensor.get_state().running);
+    }
+}
+
+#[tokio::test]
+async fn test_restarted_sensor_restores_cached_calibration() {
+    let cache = std::env::temp_dir().join(format!("spectre_restart_cache_{}.json", std::process::id()));
+    let _ = std::fs::remove_file(&cache);
+    let pattern = MockPattern::Sine { center: 0.5, amplitude: 0.3, period: 2.0 };
+
+    let config = SensorConfig::default()
+        .with_mock(pattern.clone())
+        .with_calibration_period(Duration::from_millis(300))
+        .with_calibration_cache(&cache)
+        .with_target_fps(60.0);
+    let mut sensor = EmotionSensor::new(config);
+    sensor.initialize().await.unwrap();
+    let mut phases = sensor.phases();
+    let _frames = sensor.start().await.unwrap();
+    phases.wait_for_calibrated(Duration::from_secs(5)).await.unwrap();
+    sensor.stop().await.unwrap();
+    assert!(cache.exists());
+
+    // A fresh sensor with a calibration period it could never finish in
+    // time starts out calibrated from the cache
+    let config = SensorConfig::default()
+        .with_mock(pattern)
+        .with_calibration_period(Duration::from_secs(600))
+        .with_calibration_cache(&cache)
+        .with_target_fps(60.0);
+    let mut sensor = EmotionSensor::new(config);
+    sensor.initialize().await.unwrap();
+    let frames = sensor.start().await.unwrap();
+    let frame = tokio::time::timeout(Duration::from_secs(1), frames.recv()).await.unwrap().unwrap();
+    assert!(frame.calibrated);
+    sensor.stop().await.unwrap();
+
+    // A corrupt cache falls back to a fresh calibration
+    std::fs::write(&cache, "not a profile").unwrap();
+    let config = SensorConfig::default()
+        .with_mock(MockPattern::Step)
+        .with_calibration_period(Duration::from_secs(600))
+        .with_calibration_cache(&cache)
+        .with_target_fps(60.0);
+    let mut sensor = EmotionSensor::new(config);
+    sensor.initialize().await.unwrap();
+    let frames = sensor.start().await.unwrap();
+    let frame = tokio::time::timeout(Duration::from_secs(1), frames.recv()).await.unwrap().unwrap();
+    assert!(!frame.calibrated);
+    sensor.stop().await.unwrap();
+    std::fs::remove_file(&cache).unwrap();
+}
+
+#[tokio::test]
+async fn test_slow_inference_scores_fresh_frames() {
+    // Each face takes three capture intervals to score
+    let inference = Duration::from_millis(50);
+    let frame_interval = Duration::from_secs_f32(1.0 / 60.0);
+    let config = SensorConfig::default()
+        .with_mock(MockPattern::Step)
+        .with_mock_inference_latency(inference)
+        .with_target_fps(60.0);
+    let mut sensor = EmotionSensor::new(config);
+    sensor.initialize().await.unwrap();
+    let frames = sensor.start().await.unwrap();
+
+    let mut latencies = Vec::new();
+    let mut stamps = Vec::new();
+    for _ in 0..12 {
+        let frame = tokio::time::timeout(Duration::from_secs(1), frames.recv()).await.unwrap().unwrap();
+        assert!(frame.inference_latency >= inference);
+        latencies.push(frame.queue_delay + frame.inference_latency);
+        stamps.push((frame.sequence, frame.timestamp_us()));
+    }
+    sensor.stop().await.unwrap();
+
+    // Capture to score stays within about a frame of the inference time
+    // rather than growing with every frame captured meanwhile
+    let bound = inference + frame_interval + Duration::from_millis(25);
+    assert!(latencies.iter().all(|latency| *latency < bound), "{:?}", latencies);
+    assert!(sensor.get_state().metrics.stale_frames > 0);
+
+    // Frames carry their capture order and time: the stale ones show up as
+    // gaps, and each scored frame was captured at most a frame before the
+    // previous inference finished
+    let spacing = (inference - frame_interval).as_micros() as u64 - 5_000;
+    for pair in stamps.windows(2) {
+        let ((first, first_us), (second, second_us)) = (pair[0], pair[1]);
+        assert!(second > first + 1, "{:?}", stamps);
+        assert!(second_us >= first_us + spacing, "{:?}", stamps);
+    }
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_inference_over_budget_is_abandoned() {
+    // Each face takes five capture intervals, far past the budget
+    let config = SensorConfig::default()
+        .with_mock(MockPattern::Step)
+        .with_mock_inference_latency(Duration::from_millis(250))
+        .with_inference_timeout(Duration::from_millis(20))
+        .with_target_fps(20.0);
+    let mut sensor = EmotionSensor::new(config);
+    sensor.initialize().await.unwrap();
+    let mut faults = sensor.subscribe_faults();
+    let _frames = sensor.start().await.unwrap();
+    tokio::time::sleep(Duration::from_millis(1600)).await;
+    let state = sensor.get_state();
+    sensor.stop().await.unwrap();
+
+    // The loop kept the capture rate instead of waiting on the model
+    assert!(state.metrics.current_fps >= 15.0, "{}", state.metrics.current_fps);
+    // No abandoned result reached the baseline
+    assert_eq!(state.baseline.unwrap().sample_count, 0);
+
+    let timeouts: Vec<_> = std::iter::from_fn(|| faults.try_recv().ok())
+        .filter(|notice| notice.error_code == MODEL_INFERENCE_TIMEOUT)
+        .collect();
+    assert!(timeouts.len() >= 3, "{:?}", timeouts);
+    assert!(timeouts.iter().all(|notice| notice.severity == FaultLevel::Warning && notice.recoverable));
+}
+
+#[tokio::test]
+async fn test_synthetic_sensor_scores_the_configured_fear_index() {
+    // Fear and neutral move opposite ways in synthetic frames, so their
+    // composite holds near the middle while fear alone swings
+    let weights = FearIndexWeights(vec![0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0]);
+    let mut config = SensorConfig::default()
+        .with_mock(MockPattern::Sine { center: 0.5, amplitude: 0.3, period: 2.0 })
+        .with_calibration_period(Duration::from_millis(500))
+        .with_target_fps(60.0)
+        .with_freeze_calibration(true)
+        .with_fear_index_weights(Some(weights.clone()));
+    config.per_channel_calibration = true;
+    let mut sensor = EmotionSensor::new(config);
+    sensor.initialize().await.unwrap();
+    let mut phases = sensor.phases();
+    let frames = sensor.start().await.unwrap();
+    match phases.wait_for_calibrated(Duration::from_secs(5)).await.unwrap() {
+        PhaseOutcome::Reached(phase) => assert!(phase.is_calibrated(), "{:?}", phase),
+        outcome => panic!("not calibrated: {:?}", outcome),
+    }
+
+    // Wait out a metrics tick so the published baseline is the frozen one
+    let deadline = tokio::time::Instant::now() + Duration::from_millis(1200);
+    while let Ok(frame) = tokio::time::timeout_at(deadline, frames.recv()).await {
+        frame.unwrap();
+    }
+    let baseline = sensor.get_state().baseline.unwrap();
+    assert!(baseline.channels.is_some());
+    let mut reference = AdaptiveCalibrator::with_defaults(Duration::ZERO)
+        .with_per_channel_calibration(true)
+        .with_fear_index_weights(Some(weights));
+    reference.restore(&CalibrationProfile { created_us: 0, alpha: 0.05, baseline }).unwrap();
+    assert!(reference.is_calibrated());
+
+    let mut fear_alone_differs = false;
+    for _ in 0..30 {
+        let frame = tokio::time::timeout(Duration::from_secs(1), frames.recv()).await.unwrap().unwrap();
+        assert!(frame.calibrated);
+        let expected = reference.fear_score(&frame.emotion_logits);
+        assert!((frame.fear_score - expected).abs() < 1e-6, "{} != {}", frame.fear_score, expected);
+        fear_alone_differs |= (frame.fear_score - reference.normalize_fear(frame.emotion_logits.fear())).abs() > 0.05;
+    }
+    assert!(fear_alone_differs);
+
+    sensor.stop().await.unwrap();
+}
+
+/// Value of an unlabelled sample in Prometheus text
+#[cfg(feature = "metrics")]
+fn sample(text: &str, name: &str) -> f64 {
+    text.lines()
+        .find_map(|line| line.strip_prefix(name)?.strip_prefix(' '))
+        .unwrap_or_else(|| panic!("no {} in\n{}", name, text))
+        .parse()
+        .unwrap()
+}
+
+#[cfg(feature = "metrics")]
+#[tokio::test]
+async fn test_synthetic_sensor_feeds_prometheus_metrics() {
+    use spectre_sensor::metrics::SensorMetrics;
+    use std::sync::Arc;
+
+    let metrics = Arc::new(SensorMetrics::new().unwrap());
+    let config = SensorConfig::default().with_mock(MockPattern::Step).with_target_fps(60.0);
+    let mut sensor = EmotionSensor::new(config).with_metrics(Arc::clone(&metrics));
+    sensor.initialize().await.unwrap();
+
+    // Nobody reads the frames, so all but the buffered few are dropped
+    let _frames = sensor.start().await.unwrap();
+    tokio::time::sleep(Duration::from_millis(1300)).await;
+    // Let the loop finish its last frame so the counters agree
+    sensor.stop().await.unwrap();
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    let text = metrics.gather().unwrap();
+
+    assert!(sample(&text, "spectre_frames_processed_total") > 0.0);
+    assert!(sample(&text, "spectre_frames_dropped_total{policy=\"drop_oldest\"}") > 0.0);
+    assert_eq!(sample(&text, "spectre_inference_errors_total"), 0.0);
+    assert_eq!(
+        sample(&text, "spectre_inference_latency_seconds_count"),
+        sample(&text, "spectre_frames_processed_total")
+    );
+    // Set by the once-a-second update
+    assert!(sample(&text, "spectre_current_fps") > 0.0);
+    assert!(sample(&text, "spectre_calibration_progress") > 0.0);
+}
@@ -0,0 +1,58 @@
+//! YuNet detectors run with the thresholds they are given
+//!
+//! Uses the first image in `tests/fixtures/faces`, or a drawn test card when
+//! there is none: either way a near-zero confidence threshold lets through
+//! far more boxes than a near-one threshold.
+
+#![cfg(feature = "embedded-models")]
+
+use opencv::{
+    core::{Mat, Point, Rect, Scalar, CV_8UC3},
+    imgcodecs, imgproc,
+    prelude::*,
+};
+use spectre_sensor::yunet::{YuNetDetector, YuNetParams};
+use std::path::Path;
+
+fn fixture_image() -> Mat {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/faces");
+    let mut images: Vec<_> = std::fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| {
+            path.extension()
+                .and_then(|ext| ext.to_str())
+                .is_some_and(|ext| matches!(ext.to_ascii_lowercase().as_str(), "jpg" | "jpeg" | "png"))
+        })
+        .collect();
+    images.sort();
+    if let Some(path) = images.first() {
+        return imgcodecs::imread(&path.to_string_lossy(), imgcodecs::IMREAD_COLOR).unwrap();
+    }
+
+    // Light and dark blocks with a face-sized disc in the middle
+    let mut card = Mat::new_rows_cols_with_default(480, 640, CV_8UC3, Scalar::all(160.0)).unwrap();
+    for (i, x) in (0..640).step_by(80).enumerate() {
+        let shade = if i % 2 == 0 { 40.0 } else { 220.0 };
+        imgproc::rectangle(&mut card, Rect::new(x, 0, 80, 120), Scalar::all(shade), -1, imgproc::LINE_8, 0).unwrap();
+    }
+    imgproc::circle(&mut card, Point::new(320, 260), 90, Scalar::new(120.0, 150.0, 200.0, 0.0), -1, imgproc::LINE_8, 0)
+        .unwrap();
+    card
+}
+
+fn detections(params: YuNetParams, image: &Mat) -> usize {
+    let mut detector = YuNetDetector::new(1, params).expect("embedded YuNet detector");
+    detector.detect_faces(image).unwrap().len()
+}
+
+#[test]
+fn test_detector_uses_given_thresholds() {
+    let image = fixture_image();
+    let permissive = YuNetParams { confidence_threshold: 0.0, ..YuNetParams::default() };
+    let strict = YuNetParams { confidence_threshold: 0.99, ..YuNetParams::default() };
+
+    let (loose, tight) = (detections(permissive, &image), detections(strict, &image));
+    assert!(loose > tight, "{} boxes at 0.0 vs {} at 0.99", loose, tight);
+}